@@ -11,6 +11,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .out_dir(&out_dir)
         .compile_protos(&["proto/sbom.proto"], &["proto"])?;
 
+    // Standard grpc.health.v1 service, served unauthenticated alongside the
+    // SBOM services. Its own descriptor set so reflection can list it.
+    tonic_build::configure()
+        .build_server(true)
+        .build_client(false)
+        .file_descriptor_set_path(format!("{}/health_descriptor.bin", out_dir))
+        .out_dir(&out_dir)
+        .compile_protos(&["proto/health.proto"], &["proto"])?;
+
     // Hex registry resources (`/names`, `/versions`, `/packages/{name}`) are
     // plain protobuf messages with no gRPC service, so they are generated with
     // prost only. The schemas mirror hex_core's `mix_hex_pb_*` definitions.
@@ -26,6 +35,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             &["proto"],
         )?;
 
+    // Prometheus remote-write request, pushed by the optional metrics
+    // exporter. Plain messages, no service.
+    prost_build::Config::new()
+        .out_dir(&out_dir)
+        .compile_protos(&["proto/prometheus_remote.proto"], &["proto"])?;
+
     let git_sha = std::env::var("GIT_SHA")
         .ok()
         .filter(|s| !s.is_empty())
@@ -0,0 +1,13 @@
+-- Per-member include/exclude path patterns for virtual repositories.
+--
+-- Artifactory lets a virtual repository restrict which paths each member may
+-- serve (e.g. `com/mycorp/**` only from the internal member, everything else
+-- from the proxy member). Migrating users depend on that to keep internal
+-- coordinates from ever resolving against a public upstream.
+--
+-- Both arrays default to empty, which preserves the existing behaviour: a
+-- member with no include patterns accepts every path, and a member with no
+-- exclude patterns rejects none.
+ALTER TABLE virtual_repo_members
+    ADD COLUMN IF NOT EXISTS include_patterns TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS exclude_patterns TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,9 @@
+-- Lifecycle policies targeted by repository label selector.
+--
+-- A policy can now name a set of label selectors (`[{"key": "ephemeral",
+-- "value": "true"}]`) instead of a fixed repository_id. The selector is
+-- resolved against `repository_labels` on every run, so a repository that
+-- acquires the label later is covered without editing the policy. Empty
+-- (the default) keeps the existing repository_id / global semantics.
+ALTER TABLE lifecycle_policies
+    ADD COLUMN IF NOT EXISTS label_selector JSONB NOT NULL DEFAULT '[]'::jsonb;
@@ -0,0 +1,25 @@
+-- Label-based permission grants.
+--
+-- A grant with target_type = 'label_selector' applies to every repository
+-- whose labels satisfy the stored selector (AND across entries; an empty
+-- value matches any value for that key), instead of a single fixed
+-- repository. The selector is evaluated at check time, so a repository that
+-- acquires a matching label inherits the grant without any grant rewrite.
+-- For these rows target_id is an opaque server-assigned id that keeps the
+-- existing (principal, target_type, target_id) uniqueness constraint usable.
+
+ALTER TABLE permissions
+    ADD COLUMN IF NOT EXISTS label_selector JSONB NOT NULL DEFAULT '[]'::jsonb;
+
+CREATE INDEX IF NOT EXISTS idx_permissions_label_selector_grants
+    ON permissions(principal_type, principal_id)
+    WHERE target_type = 'label_selector';
+
+-- Repository label changes can grant or revoke effective access through
+-- label-selector grants, so they fan out the same coarse
+-- permissions_changed event as permission CRUD (see migration 142).
+DROP TRIGGER IF EXISTS ak_repository_labels_changed_notify ON repository_labels;
+CREATE TRIGGER ak_repository_labels_changed_notify
+    AFTER INSERT OR UPDATE OR DELETE ON repository_labels
+    FOR EACH ROW
+    EXECUTE FUNCTION ak_notify_permissions_changed();
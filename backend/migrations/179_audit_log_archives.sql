@@ -0,0 +1,21 @@
+-- Audit-log archival.
+--
+-- The retention sweep exports aged audit_log rows as gzip-compressed JSONL
+-- objects in primary storage before deleting them, so the table stays
+-- bounded without losing history. Each exported batch is recorded here with
+-- the time range it covers; the admin API lists these rows to find which
+-- archive holds a given period and reads the object back on demand.
+
+CREATE TABLE IF NOT EXISTS audit_log_archives (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    storage_key TEXT NOT NULL UNIQUE,
+    range_start TIMESTAMPTZ NOT NULL,
+    range_end TIMESTAMPTZ NOT NULL,
+    record_count BIGINT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    checksum_sha256 VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_archives_range
+    ON audit_log_archives(range_start, range_end);
@@ -0,0 +1,41 @@
+-- Per-user email notification preferences and delivery tracking.
+--
+-- Health alerts, promotion approval requests, quota warnings, and the weekly
+-- digest are addressed to users rather than to the per-repository
+-- email_subscriptions lists, so each user opts in or out per notification
+-- kind here. A missing row means "use the defaults" below.
+--
+-- Every attempted send is recorded in email_deliveries with its final
+-- status, so operators can see whether a notification went out and why it
+-- did not. The dedupe_key column lets periodic sweeps (quota warnings, the
+-- weekly digest) skip recipients they already notified recently.
+
+CREATE TABLE IF NOT EXISTS user_notification_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    health_alerts BOOLEAN NOT NULL DEFAULT false,
+    approval_requests BOOLEAN NOT NULL DEFAULT true,
+    quota_warnings BOOLEAN NOT NULL DEFAULT true,
+    weekly_digest BOOLEAN NOT NULL DEFAULT false,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS email_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    recipient TEXT NOT NULL,
+    notification_type VARCHAR(32) NOT NULL,
+    subject TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sent', 'failed', 'skipped')),
+    error TEXT,
+    dedupe_key TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    sent_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_deliveries_created
+    ON email_deliveries(created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_email_deliveries_dedupe
+    ON email_deliveries(dedupe_key, created_at DESC)
+    WHERE dedupe_key IS NOT NULL;
@@ -0,0 +1,69 @@
+-- Native Slack and Microsoft Teams integrations.
+--
+-- The generic webhook pipeline can already post Slack/Teams-shaped payloads,
+-- but it cannot route one integration to several channels, thread related
+-- messages, or accept button clicks. A chat integration is either:
+--
+--   * webhook mode: an incoming-webhook URL bound to a single channel, or
+--   * app mode: a Slack bot token or a Teams Bot Framework app, which can
+--     post to any channel the bot is in and reply in threads.
+--
+-- Credentials (webhook URLs embed a bearer secret, bot tokens, client
+-- secrets, Slack signing secrets) are stored AES-GCM encrypted with the
+-- same key as webhook signing secrets (AK_WEBHOOK_SECRET_KEY).
+--
+-- Routing rules pick which events reach which channel: an empty
+-- event_types array matches every event, a NULL repository_id matches every
+-- repository, and a NULL channel falls back to the integration default.
+--
+-- chat_message_threads remembers the first message posted for a related
+-- sequence of events (scan started/completed, approval requested/decided) so
+-- follow-ups are posted as thread replies in app mode.
+
+CREATE TABLE IF NOT EXISTS chat_integrations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    platform VARCHAR(32) NOT NULL
+        CHECK (platform IN ('slack', 'microsoft_teams')),
+    mode VARCHAR(16) NOT NULL
+        CHECK (mode IN ('webhook', 'app')),
+    webhook_url_encrypted BYTEA,
+    token_encrypted BYTEA,
+    signing_secret_encrypted BYTEA,
+    default_channel TEXT,
+    app_id TEXT,
+    app_tenant_id TEXT,
+    app_service_url TEXT,
+    is_enabled BOOLEAN NOT NULL DEFAULT true,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (
+        (mode = 'webhook' AND webhook_url_encrypted IS NOT NULL)
+        OR (mode = 'app' AND token_encrypted IS NOT NULL)
+    )
+);
+
+CREATE TABLE IF NOT EXISTS chat_routing_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    integration_id UUID NOT NULL REFERENCES chat_integrations(id) ON DELETE CASCADE,
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+    repository_id UUID REFERENCES repositories(id) ON DELETE CASCADE,
+    channel TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_routing_rules_integration
+    ON chat_routing_rules(integration_id);
+
+CREATE TABLE IF NOT EXISTS chat_message_threads (
+    integration_id UUID NOT NULL REFERENCES chat_integrations(id) ON DELETE CASCADE,
+    channel TEXT NOT NULL,
+    thread_key TEXT NOT NULL,
+    message_ref TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (integration_id, channel, thread_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_message_threads_created
+    ON chat_message_threads(created_at);
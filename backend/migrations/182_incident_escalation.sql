@@ -0,0 +1,57 @@
+-- PagerDuty / Opsgenie incident escalation for health monitor alerts.
+--
+-- Until now an alert-state transition only produced a log warning (and an
+-- opt-in email). An incident integration forwards those transitions to an
+-- on-call tool: ServiceDown opens (triggers) an incident and
+-- ServiceRecovered resolves it. The dedup key / alias is derived from the
+-- service name so repeat alerts after the cooldown fold into the same open
+-- incident instead of paging again.
+--
+-- credential_encrypted holds the PagerDuty Events v2 routing key or the
+-- Opsgenie API key, AES-GCM encrypted with AK_WEBHOOK_SECRET_KEY.
+--
+-- services limits which monitored services page through this integration;
+-- an empty array means all of them. Severities use the PagerDuty
+-- vocabulary and are mapped to Opsgenie priorities (critical=P1 ...
+-- info=P5).
+--
+-- incident_escalations tracks incidents we opened so recovery (including a
+-- recovery that happens during a suppression window) resolves exactly the
+-- incidents that are still open.
+
+CREATE TABLE IF NOT EXISTS incident_integrations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    provider VARCHAR(16) NOT NULL
+        CHECK (provider IN ('pagerduty', 'opsgenie')),
+    credential_encrypted BYTEA NOT NULL,
+    opsgenie_region VARCHAR(2) NOT NULL DEFAULT 'us'
+        CHECK (opsgenie_region IN ('us', 'eu')),
+    services TEXT[] NOT NULL DEFAULT '{}',
+    down_severity VARCHAR(16) NOT NULL DEFAULT 'critical'
+        CHECK (down_severity IN ('critical', 'error', 'warning', 'info')),
+    degraded_severity VARCHAR(16) NOT NULL DEFAULT 'warning'
+        CHECK (degraded_severity IN ('critical', 'error', 'warning', 'info')),
+    is_enabled BOOLEAN NOT NULL DEFAULT true,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS incident_escalations (
+    integration_id UUID NOT NULL REFERENCES incident_integrations(id) ON DELETE CASCADE,
+    service_name VARCHAR(255) NOT NULL,
+    dedup_key VARCHAR(255) NOT NULL,
+    severity VARCHAR(16) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'resolved')),
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_triggered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+    last_error TEXT,
+    PRIMARY KEY (integration_id, service_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_incident_escalations_open
+    ON incident_escalations (service_name)
+    WHERE status = 'open';
@@ -0,0 +1,77 @@
+-- Jira issue tracker integration for policy-violating scan findings.
+--
+-- An integration periodically syncs findings that violate an enabled scan
+-- policy (non-acknowledged, severity at or above the policy's
+-- max_severity, from the latest completed scan of each artifact) into Jira
+-- issues, grouped either per artifact or per (repository, component).
+--
+--   * A group with violating findings and no open issue gets a new issue in
+--     the project / issue type chosen by the first matching entry of
+--     project_mappings (falling back to project_key / issue_type), plus a
+--     remote link (and optional custom field) pointing back at Artifact
+--     Keeper.
+--   * When every finding in the group is fixed the issue is transitioned
+--     with close_transition; when the remaining findings are all
+--     acknowledged it is transitioned with acknowledge_transition (or
+--     close_transition when unset).
+--   * When a Jira issue is resolved with one of acknowledge_resolutions
+--     ("Won't Fix", "Risk Accepted", ...) the group's findings are
+--     acknowledged in Artifact Keeper.
+--
+-- The API token is AES-GCM encrypted with AK_WEBHOOK_SECRET_KEY. With
+-- auth_email set it is sent as Jira Cloud basic auth (email:token),
+-- otherwise as a Jira Data Center personal access token (Bearer).
+
+CREATE TABLE IF NOT EXISTS issue_tracker_integrations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    provider VARCHAR(16) NOT NULL DEFAULT 'jira'
+        CHECK (provider IN ('jira')),
+    base_url TEXT NOT NULL,
+    auth_email VARCHAR(255),
+    api_token_encrypted BYTEA NOT NULL,
+    project_key VARCHAR(64) NOT NULL,
+    issue_type VARCHAR(64) NOT NULL DEFAULT 'Bug',
+    grouping VARCHAR(16) NOT NULL DEFAULT 'artifact'
+        CHECK (grouping IN ('artifact', 'component')),
+    repository_id UUID REFERENCES repositories(id) ON DELETE CASCADE,
+    project_mappings JSONB NOT NULL DEFAULT '[]'::jsonb,
+    labels TEXT[] NOT NULL DEFAULT '{}',
+    backlink_base_url TEXT,
+    backlink_field_id VARCHAR(64),
+    close_transition VARCHAR(64) NOT NULL DEFAULT 'Done',
+    acknowledge_transition VARCHAR(64),
+    acknowledge_resolutions TEXT[] NOT NULL DEFAULT '{}',
+    is_enabled BOOLEAN NOT NULL DEFAULT true,
+    last_synced_at TIMESTAMPTZ,
+    last_sync_error TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One row per finding group per integration. fingerprints records the
+-- (cve, component) pairs the issue covers, so a group whose issue was
+-- closed in Jira is only re-filed when genuinely new findings appear.
+CREATE TABLE IF NOT EXISTS issue_tracker_links (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    integration_id UUID NOT NULL REFERENCES issue_tracker_integrations(id) ON DELETE CASCADE,
+    group_key TEXT NOT NULL,
+    repository_id UUID REFERENCES repositories(id) ON DELETE SET NULL,
+    artifact_id UUID REFERENCES artifacts(id) ON DELETE SET NULL,
+    component TEXT,
+    issue_key VARCHAR(64) NOT NULL,
+    issue_url TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'closed', 'acknowledged')),
+    fingerprints TEXT[] NOT NULL DEFAULT '{}',
+    highest_severity VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_at TIMESTAMPTZ,
+    UNIQUE (integration_id, group_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_issue_tracker_links_open
+    ON issue_tracker_links (integration_id)
+    WHERE status = 'open';
@@ -0,0 +1,29 @@
+-- Scan results ingested from external scanners (SARIF, Snyk JSON, custom).
+--
+-- Organizations with existing scanner contracts can POST reports to
+-- /api/v1/security/scans/ingest instead of running Trivy/Grype. Each report
+-- becomes a completed scan_results row plus its scan_findings, so the policy
+-- engine, security scores and promotion gates treat it like any built-in
+-- scan.
+--
+-- Ingested rows use scan_type 'external:<tool-slug>' (e.g. external:snyk,
+-- external:codeql). Everything that picks "the latest scan per
+-- (artifact_id, scan_type)" therefore lets a new report from a tool
+-- supersede only that tool's previous report, never another tool's or a
+-- built-in scanner's. The slug fits the existing VARCHAR(30) column.
+--
+-- source_tool / source_format / ingested_by record where the data came
+-- from; they are NULL for built-in scans.
+
+ALTER TABLE scan_results DROP CONSTRAINT IF EXISTS scan_results_scan_type_check;
+ALTER TABLE scan_results ADD CONSTRAINT scan_results_scan_type_check
+    CHECK (
+        scan_type IN ('dependency', 'image', 'license', 'malware', 'filesystem', 'grype', 'openscap', 'incus')
+        OR scan_type ~ '^external:[a-z0-9][a-z0-9-]{0,20}$'
+    );
+
+ALTER TABLE scan_results
+    ADD COLUMN IF NOT EXISTS source_tool VARCHAR(100),
+    ADD COLUMN IF NOT EXISTS source_format VARCHAR(16)
+        CHECK (source_format IN ('sarif', 'snyk', 'custom')),
+    ADD COLUMN IF NOT EXISTS ingested_by UUID REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,30 @@
+-- Yanked / deprecated package versions.
+--
+-- A row marks every artifact of (repository_id, package_name, version) as
+-- deprecated. The marker only changes how the version is advertised in the
+-- format's metadata:
+--
+--   * npm:   the version object in the packument gets `deprecated: <message>`
+--   * Cargo: the sparse index entry gets `yanked: true`
+--   * PyPI:  the simple index file gets PEP 592 `data-yanked` (HTML) /
+--            `yanked` (PEP 691 JSON)
+--
+-- The bytes stay downloadable so lockfiles that pin the version keep
+-- resolving; deleting the row un-yanks the version.
+--
+-- package_name is stored exactly as artifacts.name. message is NULL when
+-- none was given (Cargo yanks carry no reason).
+
+CREATE TABLE IF NOT EXISTS package_version_deprecations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    package_name VARCHAR(512) NOT NULL,
+    version VARCHAR(255) NOT NULL,
+    message TEXT,
+    deprecated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (repository_id, package_name, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_version_deprecations_package
+    ON package_version_deprecations (repository_id, package_name);
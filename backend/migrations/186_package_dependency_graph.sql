@@ -0,0 +1,46 @@
+-- Instance-wide package dependency graph.
+--
+-- Each stored artifact's declared dependencies (npm `dependencies`, Cargo
+-- `deps`, PyPI `Requires-Dist`, Maven POM `<dependencies>`, Helm
+-- `Chart.yaml` dependencies) are indexed as edges from the artifact to a
+-- dependency key. Keys are normalized per ecosystem so they match the
+-- package_key of stored artifacts:
+--
+--   npm / helm   name as published
+--   cargo        lowercased crate name
+--   pypi         PEP 503 normalized name
+--   maven        groupId:artifactId
+--
+-- Requirements are stored as declared (usually ranges) and are not
+-- resolved: the graph answers "which stored packages declare a dependency
+-- on X", which is what impact analysis before deleting or deprecating X
+-- needs.
+--
+-- Edges are (re)built by the dependency_graph_index scheduler task.
+-- package_dependency_index records which artifacts have been indexed and at
+-- which artifacts.updated_at, so metadata rewrites are picked up.
+
+CREATE TABLE IF NOT EXISTS package_dependency_edges (
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    ecosystem VARCHAR(16) NOT NULL,
+    package_key VARCHAR(512) NOT NULL,
+    dependency_key VARCHAR(512) NOT NULL,
+    requirement TEXT,
+    PRIMARY KEY (artifact_id, dependency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_dependency_edges_dependency
+    ON package_dependency_edges (ecosystem, dependency_key);
+CREATE INDEX IF NOT EXISTS idx_package_dependency_edges_package
+    ON package_dependency_edges (ecosystem, package_key);
+
+CREATE TABLE IF NOT EXISTS package_dependency_index (
+    artifact_id UUID PRIMARY KEY REFERENCES artifacts(id) ON DELETE CASCADE,
+    ecosystem VARCHAR(16) NOT NULL,
+    package_key VARCHAR(512) NOT NULL,
+    source_updated_at TIMESTAMPTZ NOT NULL,
+    indexed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_dependency_index_package
+    ON package_dependency_index (ecosystem, package_key);
@@ -0,0 +1,27 @@
+-- Artifact usage tracing.
+--
+-- Download clients or CI plugins report where an artifact was consumed
+-- (environment + host, optionally a deployment / service name). Repeated
+-- reports of the same (artifact, environment, host, deployment) bump
+-- last_seen_at and report_count, so the table is a deployment map: during a
+-- CVE response it answers where a bad version actually went.
+--
+-- deployment is '' rather than NULL when not given so the unique key also
+-- covers reports without one.
+
+CREATE TABLE IF NOT EXISTS artifact_consumers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    environment VARCHAR(128) NOT NULL,
+    host VARCHAR(255) NOT NULL,
+    deployment VARCHAR(255) NOT NULL DEFAULT '',
+    source VARCHAR(64),
+    reported_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    report_count BIGINT NOT NULL DEFAULT 1,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (artifact_id, environment, host, deployment)
+);
+
+CREATE INDEX IF NOT EXISTS idx_artifact_consumers_environment
+    ON artifact_consumers (environment, last_seen_at DESC);
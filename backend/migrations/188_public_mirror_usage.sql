@@ -0,0 +1,19 @@
+-- Public mirror bandwidth accounting.
+--
+-- In public mirror mode (PUBLIC_MIRROR_MODE=true) each replica counts the
+-- responses it serves on format endpoints in memory and periodically adds
+-- the totals here, one row per day and repository. `anonymous_*` columns
+-- cover unauthenticated traffic only (the traffic mirror mode throttles);
+-- `throttled_requests` counts anonymous requests rejected with 429.
+
+CREATE TABLE IF NOT EXISTS public_mirror_usage (
+    day DATE NOT NULL,
+    repository_key VARCHAR(255) NOT NULL,
+    requests BIGINT NOT NULL DEFAULT 0,
+    bytes_served BIGINT NOT NULL DEFAULT 0,
+    anonymous_requests BIGINT NOT NULL DEFAULT 0,
+    anonymous_bytes_served BIGINT NOT NULL DEFAULT 0,
+    throttled_requests BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (day, repository_key)
+);
@@ -0,0 +1,23 @@
+-- S3 gateway access keys.
+--
+-- S3 clients sign requests with AWS Signature V4, which needs the secret on
+-- the server side to recompute the HMAC. API tokens are only stored as
+-- bcrypt hashes, so an access key records the token itself, encrypted at
+-- rest: the client uses the access key ID plus its API token as the secret
+-- access key, and every S3 request is then authorized exactly as that token.
+--
+-- Deleting the token removes its access keys; a revoked or expired token is
+-- rejected at request time by the normal token validation.
+
+CREATE TABLE IF NOT EXISTS s3_access_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    access_key_id VARCHAR(32) NOT NULL UNIQUE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    api_token_id UUID NOT NULL REFERENCES api_tokens(id) ON DELETE CASCADE,
+    token_encrypted BYTEA NOT NULL,
+    description VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_s3_access_keys_user ON s3_access_keys (user_id);
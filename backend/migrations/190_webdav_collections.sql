@@ -0,0 +1,16 @@
+-- WebDAV collections for generic repositories.
+--
+-- Generic repositories have no directories of their own: a folder exists
+-- implicitly while some artifact path lies under it. WebDAV clients create
+-- a folder (MKCOL) before copying files into it and expect it to show up in
+-- listings while still empty, so explicitly created folders are recorded
+-- here. Paths carry no leading or trailing slash.
+
+CREATE TABLE IF NOT EXISTS webdav_collections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    path VARCHAR(2048) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (repository_id, path)
+);
@@ -0,0 +1,184 @@
+-- Per-repository change feed.
+--
+-- An ordered, persisted log of artifact changes that external indexers and
+-- replication consumers follow with a cursor (the event id) via
+-- GET /api/v1/repositories/:key/events?since=<cursor>, instead of diffing
+-- full listings.
+--
+-- Recorded by triggers rather than app-level publishes for the same reason
+-- as the cache-invalidation triggers (142): artifacts are written by dozens
+-- of format handlers, proxy caching, replication, import and GC paths, and a
+-- single missed publish would leave a silent gap in the feed. Rows are
+-- written in the same transaction as the change, so rolled-back changes
+-- never appear.
+--
+-- `xid` lets the reader hold back events behind a still-running
+-- transaction: ids come from a sequence, so a transaction that commits late
+-- can make a lower id visible after a consumer has already moved past it.
+--
+-- There is deliberately no foreign key to repositories: artifact rows
+-- removed by a repository delete cascade would otherwise insert events that
+-- reference the repository being deleted. Old events, including those of
+-- deleted repositories, are removed by the retention sweep
+-- (REPOSITORY_EVENT_RETENTION_DAYS).
+
+CREATE TABLE IF NOT EXISTS repository_events (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id UUID NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    artifact_id UUID,
+    path VARCHAR(2048),
+    details JSONB NOT NULL DEFAULT '{}',
+    xid XID8 NOT NULL DEFAULT pg_current_xact_id(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_repository_events_repo_id
+    ON repository_events (repository_id, id);
+CREATE INDEX IF NOT EXISTS idx_repository_events_created_at
+    ON repository_events (created_at);
+
+-- Highest event id removed by the retention sweep. A cursor below it may
+-- have missed events, so readers must resynchronise.
+CREATE TABLE IF NOT EXISTS repository_event_log_state (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    pruned_through BIGINT NOT NULL DEFAULT 0
+);
+
+INSERT INTO repository_event_log_state (id, pruned_through)
+VALUES (true, 0)
+ON CONFLICT (id) DO NOTHING;
+
+-- ---------------------------------------------------------------------------
+-- artifacts: created / updated / deleted.
+--
+-- Soft deletes (is_deleted false -> true) and hard deletes of live rows are
+-- both `artifact.deleted`; a re-upload that revives a soft-deleted row is
+-- `artifact.created`. Updates only count when content or coordinates change,
+-- not on bookkeeping writes such as updated_at bumps.
+-- ---------------------------------------------------------------------------
+
+CREATE OR REPLACE FUNCTION ak_record_artifact_event() RETURNS trigger AS $$
+DECLARE
+    kind TEXT;
+    rec artifacts%ROWTYPE;
+    event_details JSONB;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        IF NEW.is_deleted THEN
+            RETURN NEW;
+        END IF;
+        kind := 'artifact.created';
+        rec := NEW;
+    ELSIF TG_OP = 'DELETE' THEN
+        -- Already reported when soft-deleted; skipped entirely while the
+        -- owning repository itself is being deleted.
+        IF OLD.is_deleted
+           OR NOT EXISTS (SELECT 1 FROM repositories WHERE id = OLD.repository_id) THEN
+            RETURN OLD;
+        END IF;
+        kind := 'artifact.deleted';
+        rec := OLD;
+    ELSE
+        IF NOT OLD.is_deleted AND NEW.is_deleted THEN
+            kind := 'artifact.deleted';
+        ELSIF OLD.is_deleted AND NOT NEW.is_deleted THEN
+            kind := 'artifact.created';
+        ELSIF NOT NEW.is_deleted AND (
+            OLD.checksum_sha256 IS DISTINCT FROM NEW.checksum_sha256
+            OR OLD.size_bytes IS DISTINCT FROM NEW.size_bytes
+            OR OLD.path IS DISTINCT FROM NEW.path
+            OR OLD.version IS DISTINCT FROM NEW.version
+        ) THEN
+            kind := 'artifact.updated';
+        ELSE
+            RETURN NEW;
+        END IF;
+        rec := NEW;
+    END IF;
+
+    event_details := jsonb_build_object(
+        'name', rec.name,
+        'version', rec.version,
+        'size_bytes', rec.size_bytes,
+        'checksum_sha256', rec.checksum_sha256,
+        'content_type', rec.content_type
+    );
+    IF TG_OP = 'UPDATE' AND OLD.path IS DISTINCT FROM NEW.path THEN
+        event_details := event_details || jsonb_build_object('previous_path', OLD.path);
+    END IF;
+
+    INSERT INTO repository_events (repository_id, event_type, artifact_id, path, details)
+    VALUES (rec.repository_id, kind, rec.id, rec.path, event_details);
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS ak_artifact_event ON artifacts;
+CREATE TRIGGER ak_artifact_event
+    AFTER INSERT OR UPDATE OR DELETE ON artifacts
+    FOR EACH ROW
+    EXECUTE FUNCTION ak_record_artifact_event();
+
+-- ---------------------------------------------------------------------------
+-- Properties: artifact_metadata.properties and artifact labels.
+-- ---------------------------------------------------------------------------
+
+CREATE OR REPLACE FUNCTION ak_record_artifact_properties_event() RETURNS trigger AS $$
+DECLARE
+    target_id UUID;
+    event_details JSONB;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target_id := OLD.artifact_id;
+    ELSE
+        target_id := NEW.artifact_id;
+    END IF;
+
+    IF TG_TABLE_NAME = 'artifact_labels' THEN
+        IF TG_OP = 'DELETE' THEN
+            event_details := jsonb_build_object('source', 'labels', 'label_key', OLD.label_key);
+        ELSE
+            event_details := jsonb_build_object('source', 'labels', 'label_key', NEW.label_key);
+        END IF;
+    ELSE
+        event_details := jsonb_build_object('source', 'metadata');
+    END IF;
+
+    -- Label rows removed by an artifact hard delete find no live artifact
+    -- and record nothing.
+    INSERT INTO repository_events (repository_id, event_type, artifact_id, path, details)
+    SELECT a.repository_id, 'artifact.properties_changed', a.id, a.path, event_details
+    FROM artifacts a
+    WHERE a.id = target_id AND NOT a.is_deleted;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS ak_artifact_metadata_properties_event ON artifact_metadata;
+CREATE TRIGGER ak_artifact_metadata_properties_event
+    AFTER UPDATE OF properties ON artifact_metadata
+    FOR EACH ROW
+    WHEN (OLD.properties IS DISTINCT FROM NEW.properties)
+    EXECUTE FUNCTION ak_record_artifact_properties_event();
+
+DROP TRIGGER IF EXISTS ak_artifact_label_event ON artifact_labels;
+CREATE TRIGGER ak_artifact_label_event
+    AFTER INSERT OR DELETE ON artifact_labels
+    FOR EACH ROW
+    EXECUTE FUNCTION ak_record_artifact_properties_event();
+
+DROP TRIGGER IF EXISTS ak_artifact_label_update_event ON artifact_labels;
+CREATE TRIGGER ak_artifact_label_update_event
+    AFTER UPDATE ON artifact_labels
+    FOR EACH ROW
+    WHEN (OLD.label_value IS DISTINCT FROM NEW.label_value)
+    EXECUTE FUNCTION ak_record_artifact_properties_event();
@@ -0,0 +1,55 @@
+-- Instance-level block list of known-malicious content.
+--
+-- An entry blocks either one SHA-256 digest or a package identified by purl
+-- (type, optional namespace, name) with an optional version range; no range
+-- blocks every version. Uploads that match an entry are rejected, and
+-- artifacts already stored that match are moved to the `rejected`
+-- quarantine state by the block-list sweep.
+--
+-- `source` names the threat-intel feed an entry was imported from (or
+-- `manual`), so a feed can be re-imported in replace mode without touching
+-- entries from other feeds.
+
+CREATE TABLE IF NOT EXISTS blocklist_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('sha256', 'purl')),
+    sha256 CHAR(64),
+    purl_type VARCHAR(64),
+    purl_namespace VARCHAR(512),
+    purl_name VARCHAR(512),
+    version_range VARCHAR(1024),
+    reason TEXT,
+    source VARCHAR(255) NOT NULL DEFAULT 'manual',
+    external_id VARCHAR(255),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (
+        (kind = 'sha256' AND sha256 IS NOT NULL AND purl_name IS NULL)
+        OR (kind = 'purl' AND sha256 IS NULL AND purl_type IS NOT NULL AND purl_name IS NOT NULL)
+    )
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_blocklist_entries_sha256
+    ON blocklist_entries (sha256) WHERE kind = 'sha256';
+CREATE UNIQUE INDEX IF NOT EXISTS idx_blocklist_entries_purl
+    ON blocklist_entries (
+        purl_type, COALESCE(purl_namespace, ''), purl_name, COALESCE(version_range, '')
+    ) WHERE kind = 'purl';
+CREATE INDEX IF NOT EXISTS idx_blocklist_entries_source
+    ON blocklist_entries (source);
+
+-- Artifacts the sweep has rejected because of an entry. Remembers the
+-- quarantine status the artifact had before, so removing the entry can
+-- restore it, and keeps the sweep from re-rejecting an artifact an admin
+-- has since dealt with.
+CREATE TABLE IF NOT EXISTS blocklist_matches (
+    entry_id UUID NOT NULL REFERENCES blocklist_entries(id) ON DELETE CASCADE,
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    previous_status VARCHAR(20),
+    matched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (entry_id, artifact_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_blocklist_matches_artifact
+    ON blocklist_matches (artifact_id);
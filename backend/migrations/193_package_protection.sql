@@ -0,0 +1,53 @@
+-- Dependency-confusion and typosquatting protection for remote repositories.
+--
+-- A policy row opts one remote repository in. Before the proxy fetches (or
+-- serves from its cache) a package, the package name is compared with the
+-- names published in local and staging repositories of the same ecosystem
+-- plus the policy's `internal_patterns`:
+--   * an internal name requested from the public upstream is dependency
+--     confusion;
+--   * a name within `typosquat_max_distance` edits of a popular internal
+--     package is likely typosquatting.
+-- Each check is `off`, `alert` (record a finding, serve) or `block` (record a
+-- finding, refuse). Virtual repositories are covered through their remote
+-- members, whose fetches go through the same proxy path.
+
+CREATE TABLE IF NOT EXISTS package_protection_policies (
+    repository_id UUID PRIMARY KEY REFERENCES repositories(id) ON DELETE CASCADE,
+    dependency_confusion_action VARCHAR(16) NOT NULL DEFAULT 'block'
+        CHECK (dependency_confusion_action IN ('off', 'alert', 'block')),
+    typosquatting_action VARCHAR(16) NOT NULL DEFAULT 'alert'
+        CHECK (typosquatting_action IN ('off', 'alert', 'block')),
+    -- Glob patterns (`*`, `?`) naming internal packages that may not be
+    -- published locally yet, e.g. `@acme/*` or `com.acme:*`.
+    internal_patterns TEXT[] NOT NULL DEFAULT '{}',
+    -- Glob patterns exempt from both checks (legitimate public packages
+    -- that collide with, or sit close to, an internal name).
+    allowed_patterns TEXT[] NOT NULL DEFAULT '{}',
+    typosquat_max_distance INTEGER NOT NULL DEFAULT 1
+        CHECK (typosquat_max_distance BETWEEN 1 AND 3),
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One row per (repository, check, requested name); repeat hits bump the
+-- counter instead of adding rows.
+CREATE TABLE IF NOT EXISTS package_protection_findings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    kind VARCHAR(32) NOT NULL
+        CHECK (kind IN ('dependency_confusion', 'typosquatting')),
+    package_name VARCHAR(512) NOT NULL,
+    internal_name VARCHAR(512) NOT NULL,
+    -- Action applied on the most recent hit: `alert` or `block`.
+    action VARCHAR(16) NOT NULL,
+    last_path TEXT,
+    hit_count BIGINT NOT NULL DEFAULT 1,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (repository_id, kind, package_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_protection_findings_last_seen
+    ON package_protection_findings (last_seen_at DESC);
@@ -0,0 +1,60 @@
+-- Per-platform tracking for OCI image indexes / Docker manifest lists.
+--
+-- `oci_manifest_refs` (migration 092) records only the parent -> child edge
+-- of an image index. The index descriptor for each child also names the
+-- platform it was built for, its media type and its size; keeping those on
+-- the edge lets the UI list an index's platforms and lets platform-filtered
+-- pulls (`GET /v2/<name>/manifests/<ref>?platform=linux/arm64`) be
+-- reasoned about without reloading the index body.
+--
+-- `described_at` marks edges written from a parsed descriptor. Edges
+-- recorded before this migration keep it NULL until the startup backfill
+-- re-reads their (still tagged) parent index.
+
+ALTER TABLE oci_manifest_refs
+    ADD COLUMN IF NOT EXISTS media_type TEXT,
+    ADD COLUMN IF NOT EXISTS size_bytes BIGINT,
+    ADD COLUMN IF NOT EXISTS platform_os TEXT,
+    ADD COLUMN IF NOT EXISTS platform_architecture TEXT,
+    ADD COLUMN IF NOT EXISTS platform_variant TEXT,
+    ADD COLUMN IF NOT EXISTS platform_os_version TEXT,
+    ADD COLUMN IF NOT EXISTS described_at TIMESTAMPTZ;
+
+-- Ancestor walks (child -> parent within one repository) for the liveness
+-- check below.
+CREATE INDEX IF NOT EXISTS idx_oci_manifest_refs_repo_child
+    ON oci_manifest_refs (repository_id, child_digest);
+
+-- Whether `p_digest` is still referenced, directly or through nested
+-- indexes, by an image index that is tagged in `p_repository_id`.
+--
+-- The storage GC, blob-ref pruning and manifest deletion previously only
+-- treated a child as live when its immediate parent was tagged. An index of
+-- indexes (e.g. a multi-arch image whose per-platform entries are
+-- themselves indexes carrying attestations) left its grandchildren
+-- unprotected, so cleanup could break a still-tagged multi-arch image.
+-- `UNION` (not `UNION ALL`) makes the walk terminate on cyclic edges.
+CREATE OR REPLACE FUNCTION ak_oci_manifest_index_referenced(
+    p_repository_id UUID,
+    p_digest TEXT
+) RETURNS BOOLEAN
+LANGUAGE sql STABLE AS $$
+    WITH RECURSIVE ancestors(digest) AS (
+        SELECT omr.parent_digest
+        FROM oci_manifest_refs omr
+        WHERE omr.repository_id = p_repository_id
+          AND omr.child_digest = p_digest
+        UNION
+        SELECT omr.parent_digest
+        FROM oci_manifest_refs omr
+        JOIN ancestors anc ON omr.child_digest = anc.digest
+        WHERE omr.repository_id = p_repository_id
+    )
+    SELECT EXISTS (
+        SELECT 1
+        FROM ancestors anc
+        JOIN oci_tags ot
+          ON ot.repository_id = p_repository_id
+         AND ot.manifest_digest = anc.digest
+    )
+$$;
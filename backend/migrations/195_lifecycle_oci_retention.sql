@@ -0,0 +1,30 @@
+-- Container-specific lifecycle policy types.
+--
+-- The generic age/pattern policies match on `artifacts.name` and
+-- `created_at`, which for an image repository means they expire `latest`
+-- along with stale tags and soft-delete per-platform children of live
+-- indexes. Two OCI-aware types replace them for image repositories:
+--
+-- * `oci_untagged_days` - soft-delete manifests that no tag (and no tagged
+--   image index) references and that have not been pushed in N days.
+-- * `oci_tag_keep_last` - per image, keep the N most recently pushed tags
+--   matching a regex and remove the older matching tags.
+--
+-- Both skip any manifest recorded as an artifact of a build, so an image a
+-- release was built from is never cleaned up underneath it.
+ALTER TABLE lifecycle_policies DROP CONSTRAINT IF EXISTS lifecycle_policies_policy_type_check;
+ALTER TABLE lifecycle_policies ADD CONSTRAINT lifecycle_policies_policy_type_check
+    CHECK (policy_type IN (
+        'max_age_days',
+        'max_versions',
+        'no_downloads_days',
+        'tag_pattern_keep',
+        'tag_pattern_delete',
+        'size_quota_bytes',
+        'oci_untagged_days',
+        'oci_tag_keep_last'
+    ));
+
+-- Build pin lookups by manifest digest (`'sha256:' || checksum_sha256`).
+CREATE INDEX IF NOT EXISTS idx_build_artifacts_checksum
+    ON build_artifacts (checksum_sha256);
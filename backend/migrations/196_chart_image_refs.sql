@@ -0,0 +1,32 @@
+-- Helm chart -> container image relations.
+--
+-- A Helm upload scans the chart's values.yaml files and templates for the
+-- images it deploys and records one row per (chart artifact, image, place
+-- it was found). CVE impact analysis reads this backwards: "which charts
+-- deploy image X".
+--
+-- `image` is the normalized reference (`docker.io/library/nginx:1.25`);
+-- the registry / repository / tag / digest columns are its parts, so
+-- lookups can match a repository across every tag. `location` is the
+-- values key path (`mychart/values.yaml#controller.image`) or template file
+-- the reference came from.
+CREATE TABLE IF NOT EXISTS chart_image_refs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    image TEXT NOT NULL,
+    image_registry TEXT NOT NULL,
+    image_repository TEXT NOT NULL,
+    image_tag TEXT,
+    image_digest TEXT,
+    source TEXT NOT NULL CHECK (source IN ('values', 'template')),
+    location TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (artifact_id, image, location)
+);
+
+CREATE INDEX IF NOT EXISTS idx_chart_image_refs_image
+    ON chart_image_refs (image_registry, image_repository);
+CREATE INDEX IF NOT EXISTS idx_chart_image_refs_digest
+    ON chart_image_refs (image_digest)
+    WHERE image_digest IS NOT NULL;
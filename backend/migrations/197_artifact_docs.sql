@@ -0,0 +1,20 @@
+-- README / changelog text extracted from uploaded packages.
+--
+-- npm tarballs, crates and PyPI wheels carry their README (and often a
+-- changelog) inside the archive. Ingest extracts them into this table so
+-- search can match package documentation, not just names and paths, and
+-- return highlighted snippets from it. Text is capped at ingest; the
+-- generated `search_vector` is what search queries against.
+CREATE TABLE IF NOT EXISTS artifact_docs (
+    artifact_id UUID PRIMARY KEY REFERENCES artifacts(id) ON DELETE CASCADE,
+    readme TEXT,
+    changelog TEXT,
+    search_vector TSVECTOR GENERATED ALWAYS AS (
+        to_tsvector('english', COALESCE(readme, '') || ' ' || COALESCE(changelog, ''))
+    ) STORED,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_artifact_docs_search
+    ON artifact_docs USING GIN (search_vector);
@@ -0,0 +1,42 @@
+-- Saved searches and their match alerts.
+--
+-- A saved search stores a user's search criteria (JSONB, see
+-- SavedSearchCriteria in saved_search_service.rs). Searches with notify set
+-- are evaluated incrementally: the event bus subscriber checks each newly
+-- uploaded or newly scanned artifact against them, instead of re-running
+-- every search periodically, and records one alert per new match here.
+--
+-- The unique key keeps re-delivered events and repeated scans of the same
+-- artifact from raising duplicate alerts.
+
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    criteria JSONB NOT NULL,
+    notify BOOLEAN NOT NULL DEFAULT true,
+    last_matched_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_searches_notify
+    ON saved_searches(user_id) WHERE notify = true;
+
+CREATE TABLE IF NOT EXISTS saved_search_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    saved_search_id UUID NOT NULL REFERENCES saved_searches(id) ON DELETE CASCADE,
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    event_type VARCHAR(64) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}'::jsonb,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (saved_search_id, artifact_id, event_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_search_alerts_search
+    ON saved_search_alerts(saved_search_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_saved_search_alerts_unread
+    ON saved_search_alerts(saved_search_id) WHERE read_at IS NULL;
@@ -0,0 +1,22 @@
+-- One-time bootstrap tokens for the first-run setup wizard.
+--
+-- With SETUP_WIZARD=true, a server starting against a database with no
+-- admin does not generate an admin password. It issues a bootstrap token
+-- instead, prints it to the log, and stores only its SHA-256 here. The
+-- token authorizes exactly one call to POST /api/v1/setup/bootstrap, which
+-- creates the first admin and records the initial instance settings.
+--
+-- Each restart while setup is still pending replaces the outstanding token,
+-- so a lost log line is recovered by restarting the server.
+
+CREATE TABLE IF NOT EXISTS bootstrap_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    consumed_at TIMESTAMPTZ,
+    consumed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bootstrap_tokens_pending
+    ON bootstrap_tokens(created_at DESC) WHERE consumed_at IS NULL;
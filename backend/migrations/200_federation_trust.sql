@@ -0,0 +1,43 @@
+-- Instance-to-instance federation trust.
+--
+-- Every instance holds one Ed25519 identity key (generated on first start,
+-- private half encrypted at rest). Peers exchange self-signed identity
+-- documents through POST /api/v1/federation/handshake; an admin then approves
+-- the peer, which provisions a local, non-login service principal for it.
+-- From then on the peer can present short-lived tokens signed with its
+-- identity key instead of a pre-shared service-account API key.
+
+CREATE TABLE IF NOT EXISTS federation_identity (
+    -- Singleton row: the CHECK pins the only legal key to `true`.
+    singleton BOOLEAN PRIMARY KEY DEFAULT true CHECK (singleton),
+    instance_id UUID NOT NULL DEFAULT gen_random_uuid(),
+    public_key BYTEA NOT NULL,
+    private_key_enc BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS federation_trusted_peers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    instance_id UUID NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    endpoint_url TEXT NOT NULL,
+    public_key BYTEA NOT NULL,
+    key_fingerprint VARCHAR(80) NOT NULL,
+    -- 'pending' rows were recorded by an inbound handshake and grant nothing
+    -- until an admin approves them.
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'trusted')),
+    allowed_scopes TEXT[] NOT NULL DEFAULT '{}',
+    -- Empty means the principal is not token-restricted; private repositories
+    -- still require a role assignment for the service principal.
+    repository_ids UUID[] NOT NULL DEFAULT '{}',
+    service_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    approved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    approved_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_federation_trusted_peers_endpoint
+    ON federation_trusted_peers(endpoint_url);
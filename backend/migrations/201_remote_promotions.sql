@@ -0,0 +1,46 @@
+-- Promotion into a repository on a trusted federated instance.
+--
+-- Sending side: remote_promotions records each attempt to promote a local
+-- artifact into a remote repository (the local promotion_history table keys
+-- the target by a local repository id, so it cannot hold these).
+--
+-- Receiving side: inbound_promotions holds the manifest a peer announced
+-- (artifact facts, metadata, scan results, signatures) until the bytes
+-- arrive. The artifact is only registered once the content has been
+-- received and its checksum verified.
+
+CREATE TABLE IF NOT EXISTS remote_promotions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    source_repo_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    federated_peer_id UUID REFERENCES federation_trusted_peers(id) ON DELETE SET NULL,
+    target_instance_name VARCHAR(255) NOT NULL,
+    target_repository_key VARCHAR(255) NOT NULL,
+    status VARCHAR(16) NOT NULL CHECK (status IN ('completed', 'failed')),
+    remote_artifact_id UUID,
+    error_message TEXT,
+    policy_result JSONB,
+    notes TEXT,
+    promoted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_remote_promotions_source
+    ON remote_promotions(source_repo_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS inbound_promotions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    source_instance_id UUID NOT NULL,
+    source_promotion_id UUID NOT NULL,
+    federated_peer_id UUID REFERENCES federation_trusted_peers(id) ON DELETE CASCADE,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    manifest JSONB NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'completed', 'failed')),
+    artifact_id UUID REFERENCES artifacts(id) ON DELETE SET NULL,
+    error_message TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    UNIQUE (source_instance_id, source_promotion_id)
+);
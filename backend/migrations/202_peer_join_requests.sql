@@ -0,0 +1,48 @@
+-- Edge node auto-registration.
+--
+-- An admin issues a join token (only its SHA-256 is stored). A new node
+-- presents it to POST /api/v1/peers/join and lands in peer_join_requests as
+-- `pending` with the hostname and key fingerprint it reported. Nothing is
+-- created in peer_instances, so no syncing or heartbeats happen, until an
+-- admin approves the request.
+
+CREATE TABLE IF NOT EXISTS peer_join_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    description VARCHAR(255),
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    -- NULL means the token may be used until it expires or is revoked.
+    max_uses INTEGER CHECK (max_uses IS NULL OR max_uses > 0),
+    use_count INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS peer_join_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    join_token_id UUID REFERENCES peer_join_tokens(id) ON DELETE SET NULL,
+    name VARCHAR(255) NOT NULL,
+    hostname VARCHAR(255) NOT NULL,
+    endpoint_url VARCHAR(2048) NOT NULL,
+    region VARCHAR(100),
+    cache_size_bytes BIGINT NOT NULL,
+    fingerprint VARCHAR(255) NOT NULL,
+    -- Key this instance presents to the node once it is approved.
+    api_key VARCHAR(255) NOT NULL,
+    -- SHA-256 of the secret the node uses to poll its status and heartbeat.
+    node_secret_hash VARCHAR(64) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected')),
+    peer_instance_id UUID REFERENCES peer_instances(id) ON DELETE SET NULL,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    rejection_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_peer_join_requests_pending_name
+    ON peer_join_requests(name) WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_peer_join_requests_status
+    ON peer_join_requests(status, created_at DESC);
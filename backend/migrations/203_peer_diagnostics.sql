@@ -0,0 +1,12 @@
+-- Latest diagnostics forwarded by each edge node: its Prometheus metrics
+-- exposition and recent warning/error log lines. One row per peer, replaced
+-- on every upload.
+
+CREATE TABLE IF NOT EXISTS peer_diagnostics (
+    peer_instance_id UUID PRIMARY KEY REFERENCES peer_instances(id) ON DELETE CASCADE,
+    node_version VARCHAR(64),
+    metrics_text TEXT NOT NULL DEFAULT '',
+    logs JSONB NOT NULL DEFAULT '[]',
+    collected_at TIMESTAMPTZ NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,30 @@
+-- Edge cache integrity verification.
+--
+-- Edge side: cache_integrity_checks records when each stored object was last
+-- re-hashed, so the periodic verifier walks the cache oldest-checked first
+-- in bounded batches.
+--
+-- Primary side: peer_integrity_reports keeps each edge node's reported
+-- results (corrupted entries it purged and which of them were re-queued for
+-- sync) for alerting and review.
+
+CREATE TABLE IF NOT EXISTS cache_integrity_checks (
+    -- 'artifact' (artifacts.id) or 'proxy_cache' (proxy_cache_artifacts.id).
+    object_kind VARCHAR(16) NOT NULL,
+    object_id UUID NOT NULL,
+    checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (object_kind, object_id)
+);
+
+CREATE TABLE IF NOT EXISTS peer_integrity_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    peer_instance_id UUID NOT NULL REFERENCES peer_instances(id) ON DELETE CASCADE,
+    checked INTEGER NOT NULL,
+    corrupted INTEGER NOT NULL,
+    refetch_queued INTEGER NOT NULL DEFAULT 0,
+    entries JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_peer_integrity_reports_peer
+    ON peer_integrity_reports(peer_instance_id, created_at DESC);
@@ -0,0 +1,22 @@
+-- Audit trail of airlock (air-gap transfer) bundle imports.
+--
+-- A row is written in the same transaction that registers the bundle's
+-- artifacts, so it exists exactly when the import took effect.
+
+CREATE TABLE IF NOT EXISTS airlock_imports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    bundle_id UUID NOT NULL,
+    source_instance_id UUID NOT NULL,
+    source_instance_name VARCHAR(255) NOT NULL,
+    key_fingerprint VARCHAR(255) NOT NULL,
+    repository_id UUID REFERENCES repositories(id) ON DELETE SET NULL,
+    artifact_count INTEGER NOT NULL,
+    total_bytes BIGINT NOT NULL,
+    imported_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_airlock_imports_created_at
+    ON airlock_imports (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_airlock_imports_bundle
+    ON airlock_imports (bundle_id);
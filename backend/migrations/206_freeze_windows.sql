@@ -0,0 +1,27 @@
+-- Release freeze windows: periods during which uploads and/or promotions into
+-- a repository (or every repository matching a label selector) are blocked.
+--
+-- `schedule` is a tagged JSON object, either
+--   {"type": "once", "starts_at": ..., "ends_at": ...} or
+--   {"type": "weekly", "start": "fri 18:00", "end": "mon 06:00", "utc_offset_minutes": 0}.
+
+CREATE TABLE IF NOT EXISTS freeze_windows (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    repository_id UUID REFERENCES repositories(id) ON DELETE CASCADE,
+    label_selector JSONB,
+    schedule JSONB NOT NULL,
+    block_uploads BOOLEAN NOT NULL DEFAULT true,
+    block_promotions BOOLEAN NOT NULL DEFAULT true,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT freeze_windows_scope CHECK (
+        (repository_id IS NOT NULL) <> (label_selector IS NOT NULL)
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_freeze_windows_repository
+    ON freeze_windows (repository_id);
@@ -0,0 +1,11 @@
+-- Per-repository metadata schemas: JSON Schema documents describing the custom
+-- properties (`X-Artifact-Properties`) every upload to the repository must
+-- carry. Validated on write by `metadata_schema_service`.
+
+CREATE TABLE IF NOT EXISTS repository_metadata_schemas (
+    repository_id UUID PRIMARY KEY REFERENCES repositories(id) ON DELETE CASCADE,
+    schema JSONB NOT NULL,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
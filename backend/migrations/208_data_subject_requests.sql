@@ -0,0 +1,22 @@
+-- GDPR data-subject erasure requests.
+--
+-- Each executed erasure is recorded with its report: every table and column
+-- the pipeline touched and how many rows changed. The subject is kept only as
+-- the (pseudonymous) user id, with no foreign key, so the record survives a
+-- hard delete of the user it describes and holds no personal data itself.
+
+CREATE TABLE IF NOT EXISTS data_subject_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_user_id UUID NOT NULL,
+    mode VARCHAR(16) NOT NULL CHECK (mode IN ('anonymize', 'delete')),
+    reason TEXT,
+    report JSONB NOT NULL,
+    rows_affected BIGINT NOT NULL DEFAULT 0,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_data_subject_requests_subject
+    ON data_subject_requests (subject_user_id);
+CREATE INDEX IF NOT EXISTS idx_data_subject_requests_created
+    ON data_subject_requests (created_at DESC);
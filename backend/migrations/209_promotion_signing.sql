@@ -0,0 +1,33 @@
+-- Sign-on-promotion: release repositories that automatically sign artifacts
+-- promoted into them, and the stored signatures served alongside.
+--
+-- `signing_key_id` must be an instance key (signing_keys.repository_id IS
+-- NULL) or a key owned by the repository itself; the service enforces this.
+
+CREATE TABLE IF NOT EXISTS promotion_signing_config (
+    repository_id UUID PRIMARY KEY REFERENCES repositories(id) ON DELETE CASCADE,
+    signing_key_id UUID NOT NULL REFERENCES signing_keys(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One signature per artifact and format. `format` is `cosign` (container
+-- image manifests; the signature is also pushed to the registry under the
+-- `sha256-<digest>.sig` tag), `openpgp` (ASCII-armored detached `.asc`) or
+-- `rsa` (raw PKCS#1 v1.5 SHA-256 `.sig`). For cosign, `payload` holds the
+-- signed simple-signing JSON and `signature` the raw signature over it.
+CREATE TABLE IF NOT EXISTS artifact_signatures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    signing_key_id UUID REFERENCES signing_keys(id) ON DELETE SET NULL,
+    format VARCHAR(16) NOT NULL CHECK (format IN ('cosign', 'openpgp', 'rsa')),
+    algorithm VARCHAR(64) NOT NULL,
+    signature BYTEA NOT NULL,
+    payload BYTEA,
+    signature_sha256 VARCHAR(64) NOT NULL,
+    signed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (artifact_id, format)
+);
@@ -0,0 +1,51 @@
+-- Scan job queue. Every scan trigger (upload, manual artifact scan, repository
+-- rescan) enqueues a row; a bounded pool of workers claims the
+-- highest-priority available job with FOR UPDATE SKIP LOCKED, so replicas
+-- share one queue (the `RowClaimedQueue` pattern in cluster_work.rs). A
+-- running job holds a claim; a job whose claim expires (worker or process
+-- died mid-scan) is requeued until `max_attempts`.
+
+CREATE TABLE IF NOT EXISTS scan_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    trigger_type VARCHAR(16) NOT NULL CHECK (trigger_type IN ('manual', 'rescan', 'upload')),
+    priority INTEGER NOT NULL DEFAULT 0,
+    status VARCHAR(16) NOT NULL DEFAULT 'queued'
+        CHECK (status IN ('queued', 'running', 'completed', 'failed')),
+    force BOOLEAN NOT NULL DEFAULT false,
+    bypass_dedup BOOLEAN NOT NULL DEFAULT false,
+    -- Pre-allocated scan_results ids ({scan_type: id}) for manual triggers
+    -- that returned them to the caller.
+    prepared JSONB,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 3,
+    last_error TEXT,
+    claim_token UUID,
+    claimed_by VARCHAR(255),
+    enqueued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    available_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    claim_expires_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+-- At most one queued job per artifact; re-triggering merges into it.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_scan_jobs_queued_artifact
+    ON scan_jobs (artifact_id) WHERE status = 'queued';
+
+CREATE INDEX IF NOT EXISTS idx_scan_jobs_claim
+    ON scan_jobs (priority DESC, enqueued_at) WHERE status = 'queued';
+
+CREATE INDEX IF NOT EXISTS idx_scan_jobs_running_claim
+    ON scan_jobs (claim_expires_at) WHERE status = 'running';
+
+CREATE INDEX IF NOT EXISTS idx_scan_jobs_finished
+    ON scan_jobs (finished_at) WHERE finished_at IS NOT NULL;
+
+-- Per-repository priority added to every job's trigger priority.
+CREATE TABLE IF NOT EXISTS scan_queue_priorities (
+    repository_id UUID PRIMARY KEY REFERENCES repositories(id) ON DELETE CASCADE,
+    priority INTEGER NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,129 @@
+-- Deduplicated vulnerability storage.
+--
+-- `scan_findings` keeps one row per scan run and finding, so every version of
+-- a package (and every rescan of it) carried a full copy of each CVE's title
+-- and description. Findings now reference a shared `vulnerabilities` row,
+-- which holds the advisory text once, and `artifact_vulnerabilities` records
+-- the per-artifact state of each vulnerability across scans (first/last
+-- detected, open / acknowledged / resolved). A finding's `description` is
+-- kept only when it differs from the reference text; readers fall back to
+-- `vulnerabilities.description`.
+
+-- Identity of a vulnerability: the advisory id when the finding has one
+-- (CVE, GHSA, ...), otherwise the reporting source plus a title hash
+-- (policy rules, secrets, malware signatures).
+CREATE OR REPLACE FUNCTION vulnerability_key(cve_id TEXT, source TEXT, title TEXT)
+RETURNS TEXT
+LANGUAGE sql
+IMMUTABLE
+AS $$
+    SELECT CASE
+        WHEN NULLIF(btrim(cve_id), '') IS NOT NULL THEN upper(btrim(cve_id))
+        ELSE 'finding:' || COALESCE(lower(source), '') || ':' || md5(title)
+    END
+$$;
+
+CREATE TABLE IF NOT EXISTS vulnerabilities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    vuln_key TEXT NOT NULL UNIQUE,
+    cve_id VARCHAR(30),
+    severity VARCHAR(20) NOT NULL
+        CHECK (severity IN ('critical', 'high', 'medium', 'low', 'info')),
+    title VARCHAR(500) NOT NULL,
+    description TEXT,
+    source VARCHAR(100),
+    source_url VARCHAR(512),
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_vulnerabilities_cve ON vulnerabilities(cve_id)
+    WHERE cve_id IS NOT NULL;
+
+ALTER TABLE scan_findings
+    ADD COLUMN IF NOT EXISTS vulnerability_id UUID
+        REFERENCES vulnerabilities(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_scan_findings_vulnerability ON scan_findings(vulnerability_id)
+    WHERE vulnerability_id IS NOT NULL;
+
+-- One row per (artifact, vulnerability, affected component). `status` is
+-- `open` while the artifact's latest scans report it, `acknowledged` once a
+-- user accepted the risk, and `resolved` when no latest scan reports it any
+-- more (advisory withdrawn, scanner database corrected).
+CREATE TABLE IF NOT EXISTS artifact_vulnerabilities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    vulnerability_id UUID NOT NULL REFERENCES vulnerabilities(id) ON DELETE CASCADE,
+    affected_component VARCHAR(255) NOT NULL DEFAULT '',
+    affected_version VARCHAR(100),
+    fixed_version VARCHAR(100),
+    status VARCHAR(16) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'acknowledged', 'resolved')),
+    acknowledged_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    acknowledged_reason TEXT,
+    acknowledged_at TIMESTAMPTZ,
+    last_scan_result_id UUID REFERENCES scan_results(id) ON DELETE SET NULL,
+    first_detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+    UNIQUE (artifact_id, vulnerability_id, affected_component)
+);
+
+CREATE INDEX IF NOT EXISTS idx_artifact_vulnerabilities_vulnerability
+    ON artifact_vulnerabilities(vulnerability_id, status);
+
+-- Backfill: reference rows from existing findings, keeping the most recent
+-- report's text and the longest description seen.
+INSERT INTO vulnerabilities
+    (vuln_key, cve_id, severity, title, description, source, source_url,
+     first_seen_at, last_seen_at)
+SELECT DISTINCT ON (vuln_key)
+       vuln_key, NULLIF(upper(btrim(cve_id)), ''), severity, title,
+       FIRST_VALUE(description) OVER (
+           PARTITION BY vuln_key ORDER BY length(description) DESC NULLS LAST
+       ),
+       source, source_url,
+       MIN(created_at) OVER (PARTITION BY vuln_key),
+       MAX(created_at) OVER (PARTITION BY vuln_key)
+FROM (
+    SELECT vulnerability_key(cve_id, source, title) AS vuln_key, cve_id, severity, title,
+           description, source, source_url, created_at
+    FROM scan_findings
+) f
+ORDER BY vuln_key, created_at DESC
+ON CONFLICT (vuln_key) DO NOTHING;
+
+UPDATE scan_findings sf
+SET vulnerability_id = v.id,
+    description = CASE WHEN sf.description = v.description THEN NULL
+                       ELSE sf.description END
+FROM vulnerabilities v
+WHERE sf.vulnerability_id IS NULL
+  AND v.vuln_key = vulnerability_key(sf.cve_id, sf.source, sf.title);
+
+-- Per-artifact state from each artifact's latest completed scan per scan
+-- type; detection times span every scan that reported the vulnerability.
+INSERT INTO artifact_vulnerabilities
+    (artifact_id, vulnerability_id, affected_component, affected_version, fixed_version,
+     status, acknowledged_by, acknowledged_reason, acknowledged_at, last_scan_result_id,
+     first_detected_at, last_detected_at)
+SELECT DISTINCT ON (sf.artifact_id, sf.vulnerability_id, COALESCE(sf.affected_component, ''))
+       sf.artifact_id, sf.vulnerability_id, COALESCE(sf.affected_component, ''),
+       sf.affected_version, sf.fixed_version,
+       CASE WHEN sf.is_acknowledged THEN 'acknowledged' ELSE 'open' END,
+       sf.acknowledged_by, sf.acknowledged_reason, sf.acknowledged_at, sf.scan_result_id,
+       (SELECT MIN(h.created_at) FROM scan_findings h
+        WHERE h.artifact_id = sf.artifact_id AND h.vulnerability_id = sf.vulnerability_id),
+       sf.created_at
+FROM scan_findings sf
+JOIN (
+    SELECT DISTINCT ON (artifact_id, scan_type) id
+    FROM scan_results
+    WHERE status = 'completed'
+    ORDER BY artifact_id, scan_type, completed_at DESC NULLS LAST
+) latest ON latest.id = sf.scan_result_id
+WHERE sf.vulnerability_id IS NOT NULL
+ORDER BY sf.artifact_id, sf.vulnerability_id, COALESCE(sf.affected_component, ''),
+         sf.created_at DESC
+ON CONFLICT (artifact_id, vulnerability_id, affected_component) DO NOTHING;
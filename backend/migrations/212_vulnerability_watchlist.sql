@@ -0,0 +1,51 @@
+-- Vulnerability watchlist.
+--
+-- Security teams register advisories (CVE, GHSA, ...) or packages they want
+-- to hear about the moment they show up, independent of whether any policy
+-- blocks them. Uploaded and freshly scanned artifacts are matched against
+-- the enabled entries; each match is recorded once per entry, artifact and
+-- matched item in `vulnerability_watchlist_alerts` and published as a
+-- `watchlist.matched` event (and `watchlist_matched` webhook).
+
+CREATE TABLE IF NOT EXISTS vulnerability_watchlist (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('advisory', 'package')),
+    -- Advisory id (upper-cased) or package name (lower-cased). Package names
+    -- may end in `*` to watch every package with that prefix.
+    pattern VARCHAR(512) NOT NULL,
+    -- Package entries only: exact version to watch; NULL watches all.
+    version VARCHAR(100),
+    -- Restrict the entry to one repository; NULL watches all repositories.
+    repository_id UUID REFERENCES repositories(id) ON DELETE CASCADE,
+    note TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    last_matched_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vulnerability_watchlist_unique
+    ON vulnerability_watchlist (
+        kind, pattern, COALESCE(version, ''),
+        COALESCE(repository_id, '00000000-0000-0000-0000-000000000000'::uuid)
+    );
+
+CREATE TABLE IF NOT EXISTS vulnerability_watchlist_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    watch_id UUID NOT NULL REFERENCES vulnerability_watchlist(id) ON DELETE CASCADE,
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    -- What matched: the advisory id, or `name@version` of the component.
+    matched VARCHAR(640) NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    acknowledged_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    acknowledged_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (watch_id, artifact_id, matched)
+);
+
+CREATE INDEX IF NOT EXISTS idx_vulnerability_watchlist_alerts_created
+    ON vulnerability_watchlist_alerts (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_vulnerability_watchlist_alerts_open
+    ON vulnerability_watchlist_alerts (watch_id) WHERE acknowledged_at IS NULL;
@@ -0,0 +1,63 @@
+-- OpenSCAP / CIS benchmark compliance scans for container images.
+--
+-- The vulnerability scan path hands OpenSCAP only the artifact bytes, which
+-- for an image is the manifest JSON. Compliance scans instead assemble the
+-- image's root filesystem from its stored layers and evaluate a configured
+-- XCCDF profile against it, keeping every rule result so pass/fail summaries
+-- and the full report can be served per image.
+
+-- Named profile configurations. `schedule_interval_hours` NULL means the
+-- profile only runs on demand; otherwise the scheduler re-evaluates the
+-- latest image of every repository in scope at that interval.
+CREATE TABLE IF NOT EXISTS compliance_profiles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    -- XCCDF profile id, e.g. xccdf_org.ssgproject.content_profile_cis.
+    xccdf_profile VARCHAR(255) NOT NULL,
+    -- SCAP content label (e.g. rhel9, ubuntu2204); NULL auto-detects from
+    -- the image's /etc/os-release.
+    content VARCHAR(64),
+    description TEXT,
+    repository_id UUID REFERENCES repositories(id) ON DELETE CASCADE,
+    schedule_interval_hours INTEGER CHECK (schedule_interval_hours > 0),
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    last_scheduled_at TIMESTAMPTZ,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS compliance_scans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    artifact_id UUID NOT NULL REFERENCES artifacts(id) ON DELETE CASCADE,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    compliance_profile_id UUID REFERENCES compliance_profiles(id) ON DELETE SET NULL,
+    -- Snapshot of what was evaluated, so reports survive profile edits.
+    xccdf_profile VARCHAR(255) NOT NULL,
+    content VARCHAR(64),
+    trigger_type VARCHAR(16) NOT NULL CHECK (trigger_type IN ('manual', 'schedule')),
+    status VARCHAR(16) NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'completed', 'failed')),
+    passed INTEGER NOT NULL DEFAULT 0,
+    failed INTEGER NOT NULL DEFAULT 0,
+    -- error / unknown results: the rule could not be evaluated.
+    errored INTEGER NOT NULL DEFAULT 0,
+    -- notapplicable / notchecked / notselected / informational / fixed.
+    other INTEGER NOT NULL DEFAULT 0,
+    score DOUBLE PRECISION,
+    -- Every rule result: [{rule_id, result, severity, title, description, references}].
+    results JSONB NOT NULL DEFAULT '[]',
+    error_message TEXT,
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_scans_artifact
+    ON compliance_scans (artifact_id, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_compliance_scans_profile
+    ON compliance_scans (compliance_profile_id, started_at DESC);
+-- One in-flight scan per image and profile.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_compliance_scans_running
+    ON compliance_scans (artifact_id, xccdf_profile, COALESCE(content, ''))
+    WHERE status = 'running';
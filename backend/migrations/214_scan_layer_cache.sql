@@ -0,0 +1,23 @@
+-- Layer-digest scan cache for container images.
+--
+-- Hash-based scan dedup keys on the manifest checksum, so the same layers
+-- re-tagged, copied to another repository or wrapped in a manifest with
+-- different annotations/config were scanned again from scratch. Each
+-- completed image scan now records the ordered layer digests it covered;
+-- a later scan of an image with the identical layer stack reuses that
+-- result, and the layer list shows how much of a new image the scanner's
+-- own per-layer cache has already analyzed.
+
+CREATE TABLE IF NOT EXISTS scan_layer_stacks (
+    scan_result_id UUID PRIMARY KEY REFERENCES scan_results(id) ON DELETE CASCADE,
+    scan_type VARCHAR(50) NOT NULL,
+    -- sha256 over the ordered layer digests.
+    stack_key CHAR(64) NOT NULL,
+    layer_digests TEXT[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scan_layer_stacks_key
+    ON scan_layer_stacks (stack_key, scan_type);
+CREATE INDEX IF NOT EXISTS idx_scan_layer_stacks_layers
+    ON scan_layer_stacks USING GIN (layer_digests);
@@ -0,0 +1,62 @@
+-- Repository default properties: key/value pairs (e.g. `team`,
+-- `cost-center`) stamped onto every artifact stored in the repository.
+-- Properties sent with an upload override the defaults key by key.
+--
+-- Stamping happens in the database so every write path is covered, including
+-- format handlers that create their `artifact_metadata` row later or never:
+--
+-- * an artifact inserted into a repository with defaults gets a metadata row
+--   carrying them;
+-- * any `artifact_metadata` insert (including the INSERT half of an upsert,
+--   whose EXCLUDED row reflects BEFORE INSERT triggers) has the defaults
+--   merged beneath its own properties.
+
+CREATE TABLE IF NOT EXISTS repository_default_properties (
+    repository_id UUID PRIMARY KEY REFERENCES repositories(id) ON DELETE CASCADE,
+    properties JSONB NOT NULL,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE OR REPLACE FUNCTION ak_merge_default_properties() RETURNS trigger AS $$
+DECLARE
+    defaults JSONB;
+BEGIN
+    SELECT d.properties INTO defaults
+    FROM artifacts a
+    JOIN repository_default_properties d ON d.repository_id = a.repository_id
+    WHERE a.id = NEW.artifact_id;
+
+    IF defaults IS NOT NULL
+       AND jsonb_typeof(COALESCE(NEW.properties, '{}'::jsonb)) = 'object' THEN
+        NEW.properties := defaults || COALESCE(NEW.properties, '{}'::jsonb);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS ak_artifact_metadata_default_properties ON artifact_metadata;
+CREATE TRIGGER ak_artifact_metadata_default_properties
+    BEFORE INSERT ON artifact_metadata
+    FOR EACH ROW
+    EXECUTE FUNCTION ak_merge_default_properties();
+
+CREATE OR REPLACE FUNCTION ak_stamp_default_properties() RETURNS trigger AS $$
+BEGIN
+    -- The properties are filled in by ak_merge_default_properties.
+    INSERT INTO artifact_metadata (artifact_id, format, metadata, properties)
+    SELECT NEW.id, r.format::text, '{}'::jsonb, '{}'::jsonb
+    FROM repositories r
+    JOIN repository_default_properties d ON d.repository_id = r.id
+    WHERE r.id = NEW.repository_id
+    ON CONFLICT (artifact_id) DO NOTHING;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS ak_artifact_default_properties ON artifacts;
+CREATE TRIGGER ak_artifact_default_properties
+    AFTER INSERT ON artifacts
+    FOR EACH ROW
+    EXECUTE FUNCTION ak_stamp_default_properties();
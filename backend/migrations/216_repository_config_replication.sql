@@ -0,0 +1,67 @@
+-- Replication of repository configuration to peers.
+--
+-- A row enrols a (peer, repository) subscription in config replication: the
+-- repository's labels, repository-scoped lifecycle policies and scan
+-- policies are pushed to the peer so both sides enforce the same rules.
+--
+-- Every write to those tables bumps `changed_at` for the enrolled rows; the
+-- sync worker pushes any row whose `changed_at` is newer than its last push.
+-- `last_pushed_digest` lets it skip writes that did not change the pushed
+-- bundle (e.g. a lifecycle run stamping `last_run_at`).
+
+CREATE TABLE IF NOT EXISTS repository_config_replication (
+    peer_instance_id UUID NOT NULL REFERENCES peer_instances(id) ON DELETE CASCADE,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_attempt_at TIMESTAMPTZ,
+    last_pushed_at TIMESTAMPTZ,
+    -- sha256 of the bundle last accepted by the peer.
+    last_pushed_digest CHAR(64),
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (peer_instance_id, repository_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_repository_config_replication_repo
+    ON repository_config_replication (repository_id);
+
+CREATE OR REPLACE FUNCTION ak_mark_repository_config_changed() RETURNS trigger AS $$
+DECLARE
+    repo UUID;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        repo := OLD.repository_id;
+    ELSE
+        repo := NEW.repository_id;
+    END IF;
+
+    IF repo IS NOT NULL THEN
+        UPDATE repository_config_replication
+        SET changed_at = NOW()
+        WHERE repository_id = repo;
+    END IF;
+    -- A policy moved between repositories changes both.
+    IF TG_OP = 'UPDATE' AND OLD.repository_id IS DISTINCT FROM NEW.repository_id
+       AND OLD.repository_id IS NOT NULL THEN
+        UPDATE repository_config_replication
+        SET changed_at = NOW()
+        WHERE repository_id = OLD.repository_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS ak_repository_labels_config_changed ON repository_labels;
+CREATE TRIGGER ak_repository_labels_config_changed
+    AFTER INSERT OR UPDATE OR DELETE ON repository_labels
+    FOR EACH ROW EXECUTE FUNCTION ak_mark_repository_config_changed();
+
+DROP TRIGGER IF EXISTS ak_lifecycle_policies_config_changed ON lifecycle_policies;
+CREATE TRIGGER ak_lifecycle_policies_config_changed
+    AFTER INSERT OR UPDATE OR DELETE ON lifecycle_policies
+    FOR EACH ROW EXECUTE FUNCTION ak_mark_repository_config_changed();
+
+DROP TRIGGER IF EXISTS ak_scan_policies_config_changed ON scan_policies;
+CREATE TRIGGER ak_scan_policies_config_changed
+    AFTER INSERT OR UPDATE OR DELETE ON scan_policies
+    FOR EACH ROW EXECUTE FUNCTION ak_mark_repository_config_changed();
@@ -0,0 +1,17 @@
+-- Per-user preferences that follow the user across browsers and devices:
+-- default landing repository, API/UI defaults (page size, timezone) and a
+-- free-form object the web UI uses for its own settings. Email
+-- notification opt-ins stay in user_notification_preferences (migration
+-- 180); the preferences endpoint reads and writes both.
+--
+-- A missing row, or a NULL column, means "use the server default".
+
+CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    default_repository_id UUID REFERENCES repositories(id) ON DELETE SET NULL,
+    page_size INTEGER CHECK (page_size BETWEEN 1 AND 100),
+    -- IANA zone name, validated against pg_timezone_names on write.
+    timezone TEXT,
+    ui_settings JSONB NOT NULL DEFAULT '{}'::jsonb,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,21 @@
+-- Uploads whose content was already in storage under its content-addressed
+-- key, so the storage write was skipped. `body_skipped` marks the fast path
+-- where the client declared the SHA-256 of content already in the target
+-- repository and the body was never transferred.
+--
+-- Feeds the upload deduplication report (GET /api/v1/admin/analytics/uploads/dedup).
+
+CREATE TABLE IF NOT EXISTS upload_dedup_events (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    artifact_id UUID REFERENCES artifacts(id) ON DELETE SET NULL,
+    checksum_sha256 CHAR(64) NOT NULL,
+    bytes_saved BIGINT NOT NULL,
+    body_skipped BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_upload_dedup_events_created_at
+    ON upload_dedup_events (created_at);
+CREATE INDEX IF NOT EXISTS idx_upload_dedup_events_repo_created_at
+    ON upload_dedup_events (repository_id, created_at);
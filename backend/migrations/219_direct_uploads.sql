@@ -0,0 +1,26 @@
+-- Multipart uploads that clients send straight to object storage through
+-- presigned part URLs (POST /api/v1/artifacts/initiate-upload). The parts are
+-- assembled under `staging_key` and registered as an artifact by
+-- POST /api/v1/artifacts/complete-upload.
+
+CREATE TABLE IF NOT EXISTS direct_uploads (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    artifact_path TEXT NOT NULL,
+    content_type TEXT,
+    total_size BIGINT NOT NULL,
+    part_size BIGINT NOT NULL,
+    part_count INT NOT NULL,
+    checksum_sha256 CHAR(64) NOT NULL,
+    staging_key TEXT NOT NULL,
+    multipart_upload_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'completing', 'completed', 'failed')),
+    artifact_id UUID REFERENCES artifacts(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_direct_uploads_user ON direct_uploads (user_id);
+CREATE INDEX IF NOT EXISTS idx_direct_uploads_expires ON direct_uploads (expires_at);
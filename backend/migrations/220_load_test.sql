@@ -0,0 +1,40 @@
+-- Load/performance test mode: synthetic data runs and benchmark reports.
+--
+-- Synthetic repositories are ordinary repositories rows; this table records
+-- which run created them so they can be removed as a unit without touching
+-- real data.
+
+CREATE TABLE synthetic_data_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    seed BIGINT NOT NULL,
+    spec JSONB NOT NULL,
+    repositories_created INTEGER NOT NULL DEFAULT 0,
+    artifacts_created BIGINT NOT NULL DEFAULT 0,
+    downloads_created BIGINT NOT NULL DEFAULT 0,
+    total_bytes BIGINT NOT NULL DEFAULT 0,
+    duration_ms BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE synthetic_data_repositories (
+    run_id UUID NOT NULL REFERENCES synthetic_data_runs(id) ON DELETE CASCADE,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    PRIMARY KEY (run_id, repository_id)
+);
+
+CREATE INDEX idx_synthetic_data_repositories_repo
+    ON synthetic_data_repositories(repository_id);
+
+-- One row per benchmark harness run. `scenarios` holds the per-scenario
+-- timing summaries; the previous row is the baseline for regression checks.
+CREATE TABLE benchmark_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    artifact_count BIGINT NOT NULL,
+    scenarios JSONB NOT NULL,
+    regressions INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_benchmark_runs_created_at ON benchmark_runs(created_at DESC);
@@ -0,0 +1,39 @@
+-- Content-addressable storage dedup (STORAGE_DEDUP_ENABLED).
+--
+-- Generic uploads are written once per storage backend under the shared
+-- `cas/sha256/` namespace. `storage_cas_objects` records each physical blob;
+-- `storage_refs` records which artifact rows point at it. A blob is reclaimed
+-- by storage GC only once its reference count drops to zero.
+
+CREATE TABLE storage_cas_objects (
+    storage_backend TEXT NOT NULL,
+    checksum_sha256 TEXT NOT NULL,
+    storage_key TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Bumped by every upload that claims the blob; GC leaves recently claimed
+    -- blobs alone so an upload between its write and its ref insert is safe.
+    last_claimed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (storage_backend, checksum_sha256)
+);
+
+CREATE INDEX idx_storage_cas_objects_last_claimed
+    ON storage_cas_objects(last_claimed_at);
+
+-- One row per artifact stored in the CAS namespace. Rows go away with their
+-- artifact (GC hard-delete or repository delete); the RESTRICT foreign key
+-- makes it impossible to drop an object that is still referenced.
+CREATE TABLE storage_refs (
+    artifact_id UUID PRIMARY KEY REFERENCES artifacts(id) ON DELETE CASCADE,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    storage_backend TEXT NOT NULL,
+    checksum_sha256 TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (storage_backend, checksum_sha256)
+        REFERENCES storage_cas_objects(storage_backend, checksum_sha256)
+        ON DELETE RESTRICT
+);
+
+CREATE INDEX idx_storage_refs_object
+    ON storage_refs(storage_backend, checksum_sha256);
+CREATE INDEX idx_storage_refs_repository ON storage_refs(repository_id);
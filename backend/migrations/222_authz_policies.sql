@@ -0,0 +1,16 @@
+-- Inline Rego policies for the external authorization hook (AUTHZ_HOOK_URL).
+--
+-- The database is the source of truth; each enabled policy is pushed to the
+-- configured OPA instance as module `artifact-keeper/<name>` on write and
+-- re-synced at startup, so a restarted OPA picks the policies up again.
+
+CREATE TABLE IF NOT EXISTS authz_policies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(128) NOT NULL UNIQUE,
+    description TEXT,
+    rego TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,28 @@
+-- Storage encryption migration runs (STORAGE_ENCRYPTION).
+--
+-- A run walks every live artifact blob, encrypting objects written before
+-- encryption was enabled and rewrapping data keys still held under a retired
+-- key-encryption key. At most one run is active at a time; progress is
+-- flushed per batch so a run abandoned by a restart shows up as stale.
+
+CREATE TABLE storage_encryption_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    status TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'completed', 'failed', 'interrupted')),
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    active_key_id TEXT NOT NULL,
+    objects_scanned BIGINT NOT NULL DEFAULT 0,
+    objects_encrypted BIGINT NOT NULL DEFAULT 0,
+    objects_rewrapped BIGINT NOT NULL DEFAULT 0,
+    objects_failed BIGINT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX idx_storage_encryption_runs_one_running
+    ON storage_encryption_runs ((true)) WHERE status = 'running';
+
+CREATE INDEX idx_storage_encryption_runs_started_at
+    ON storage_encryption_runs(started_at DESC);
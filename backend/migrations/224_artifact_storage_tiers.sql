@@ -0,0 +1,49 @@
+-- Hot/cold storage tiering (STORAGE_COLD_BACKEND).
+--
+-- A `cold_tier_after_days` lifecycle policy queues old artifacts here; the
+-- tiering worker copies each body to the cold backend and frees the hot copy.
+-- The artifact row itself is untouched, so listings, search and metadata keep
+-- working. A download of a cold artifact flips it to `rehydrating` and answers
+-- 202 + Retry-After; the worker copies it back and keeps the hot copy until
+-- `rehydrated_until`, after which it is freed again.
+--
+-- `artifact_id` deliberately has no foreign key: storage GC hard-deletes
+-- artifact rows, and the cold copy must outlive that long enough for the
+-- worker to find the row and delete the cold object.
+ALTER TABLE lifecycle_policies DROP CONSTRAINT IF EXISTS lifecycle_policies_policy_type_check;
+ALTER TABLE lifecycle_policies ADD CONSTRAINT lifecycle_policies_policy_type_check
+    CHECK (policy_type IN (
+        'max_age_days',
+        'max_versions',
+        'no_downloads_days',
+        'tag_pattern_keep',
+        'tag_pattern_delete',
+        'size_quota_bytes',
+        'oci_untagged_days',
+        'oci_tag_keep_last',
+        'cold_tier_after_days'
+    ));
+
+CREATE TABLE artifact_storage_tiers (
+    artifact_id UUID PRIMARY KEY,
+    state TEXT NOT NULL DEFAULT 'transitioning'
+        CHECK (state IN ('transitioning', 'cold', 'rehydrating', 'rehydrated')),
+    policy_id UUID REFERENCES lifecycle_policies(id) ON DELETE SET NULL,
+    cold_backend TEXT,
+    cold_key TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    transitioned_at TIMESTAMPTZ,
+    rehydration_requested_at TIMESTAMPTZ,
+    rehydrated_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_artifact_storage_tiers_pending
+    ON artifact_storage_tiers(state, updated_at)
+    WHERE state IN ('transitioning', 'rehydrating');
+
+CREATE INDEX idx_artifact_storage_tiers_rehydrated_until
+    ON artifact_storage_tiers(rehydrated_until)
+    WHERE state = 'rehydrated';
@@ -0,0 +1,30 @@
+-- Storage shard rebalance runs (S3_SHARD_BUCKETS, AZURE_SHARD_CONTAINERS).
+--
+-- After a shard is added, a run walks every live artifact blob on a sharded
+-- backend and moves each object stored away from its owning shard. Reads
+-- fall back across shards meanwhile, so a run only restores locality. At
+-- most one run is active per backend; progress is flushed per batch so a run
+-- abandoned by a restart shows up as stale.
+
+CREATE TABLE storage_shard_rebalance_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    backend TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'completed', 'failed', 'interrupted')),
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    shards TEXT[] NOT NULL,
+    objects_scanned BIGINT NOT NULL DEFAULT 0,
+    objects_moved BIGINT NOT NULL DEFAULT 0,
+    objects_missing BIGINT NOT NULL DEFAULT 0,
+    objects_failed BIGINT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX idx_storage_shard_rebalance_runs_one_running
+    ON storage_shard_rebalance_runs (backend) WHERE status = 'running';
+
+CREATE INDEX idx_storage_shard_rebalance_runs_started_at
+    ON storage_shard_rebalance_runs(started_at DESC);
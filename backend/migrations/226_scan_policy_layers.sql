@@ -0,0 +1,18 @@
+-- Layered scan policies: instance default -> project -> repository.
+--
+-- A policy with neither repository_id nor project_id is an instance default.
+-- `inherit = true` (the default, and the pre-existing behaviour) merges the
+-- policy with the layers above it, strictest value winning per rule.
+-- `inherit = false` makes the policy override every less specific layer for
+-- the repositories it covers.
+
+ALTER TABLE scan_policies
+    ADD COLUMN IF NOT EXISTS project_id UUID REFERENCES projects(id) ON DELETE CASCADE,
+    ADD COLUMN IF NOT EXISTS inherit BOOLEAN NOT NULL DEFAULT true;
+
+ALTER TABLE scan_policies DROP CONSTRAINT IF EXISTS scan_policies_single_scope;
+ALTER TABLE scan_policies ADD CONSTRAINT scan_policies_single_scope
+    CHECK (repository_id IS NULL OR project_id IS NULL);
+
+CREATE INDEX IF NOT EXISTS idx_scan_policies_project ON scan_policies(project_id)
+    WHERE project_id IS NOT NULL;
@@ -0,0 +1,25 @@
+-- API token usage analytics and stale-token expiry.
+--
+-- Token uses are counted in memory and flushed periodically: `use_count` is
+-- the running total and `api_token_usage_ips` keeps per-source-IP counts.
+-- With `API_TOKEN_STALE_DAYS` set, a token unused for that long is revoked
+-- (`revoked_reason = 'stale'`), after its owner was warned
+-- (`stale_warned_at`) at least `API_TOKEN_STALE_WARNING_DAYS` earlier.
+
+ALTER TABLE api_tokens
+    ADD COLUMN IF NOT EXISTS use_count BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS stale_warned_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS revoked_reason TEXT;
+
+CREATE TABLE IF NOT EXISTS api_token_usage_ips (
+    token_id UUID NOT NULL REFERENCES api_tokens(id) ON DELETE CASCADE,
+    ip TEXT NOT NULL,
+    call_count BIGINT NOT NULL DEFAULT 0,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (token_id, ip)
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_last_used
+    ON api_tokens (COALESCE(last_used_at, created_at))
+    WHERE revoked_at IS NULL;
@@ -0,0 +1,42 @@
+-- Redirect aliases for renamed repositories.
+--
+-- Renaming a repository records its old key here so that format-endpoint
+-- URLs still using it redirect to the new key until `expires_at`
+-- (`REPOSITORY_KEY_ALIAS_TTL_DAYS`, or the TTL given with the rename).
+-- Aliases follow the repository id, so renaming twice keeps both old keys
+-- pointing at the current one.
+
+CREATE TABLE IF NOT EXISTS repository_key_aliases (
+    alias VARCHAR(255) PRIMARY KEY,
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_repository_key_aliases_repo
+    ON repository_key_aliases (repository_id);
+
+-- An active alias reserves its key: no other repository may take it until
+-- the alias expires or is deleted. Raised as a unique violation so every
+-- existing key-conflict path reports it as a 409.
+CREATE OR REPLACE FUNCTION ak_check_repository_key_alias() RETURNS trigger AS $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM repository_key_aliases
+        WHERE alias = NEW.key
+          AND repository_id <> NEW.id
+          AND expires_at > NOW()
+    ) THEN
+        RAISE EXCEPTION 'duplicate key: repository key "%" is reserved as a redirect alias', NEW.key
+            USING ERRCODE = 'unique_violation';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS ak_repository_key_alias_reserved ON repositories;
+CREATE TRIGGER ak_repository_key_alias_reserved
+    BEFORE INSERT OR UPDATE OF key ON repositories
+    FOR EACH ROW
+    EXECUTE FUNCTION ak_check_repository_key_alias();
@@ -0,0 +1,23 @@
+-- OCI referrers (Distribution Spec v1.1 `GET /v2/<name>/referrers/<digest>`).
+--
+-- A manifest pushed with a `subject` descriptor (a signature, SBOM or
+-- attestation attached by cosign / oras / notation) is a referrer of that
+-- subject. The edge is recorded at manifest-PUT time so the referrers API can
+-- answer from the database without re-reading manifests from storage. The
+-- descriptor columns are what the API returns for each referrer.
+--
+-- Rows are not removed when a referrer manifest is deleted; the listing joins
+-- `oci_tags` so only manifests still present in the repository are returned.
+
+CREATE TABLE IF NOT EXISTS oci_referrers (
+    repository_id UUID NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    subject_digest TEXT NOT NULL,
+    manifest_digest TEXT NOT NULL,
+    media_type TEXT NOT NULL,
+    artifact_type TEXT,
+    size_bytes BIGINT NOT NULL,
+    annotations JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (repository_id, name, subject_digest, manifest_digest)
+);
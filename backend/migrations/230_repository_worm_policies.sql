@@ -0,0 +1,33 @@
+-- WORM (write once, read many) retention for compliance repositories.
+--
+-- Every object written to a repository with a row here is locked at the
+-- storage provider (S3 Object Lock, Azure immutability policy) for
+-- `retention_days` from the time it is written. The lock itself lives at
+-- the provider; this table only says which repositories get one.
+
+CREATE TABLE IF NOT EXISTS repository_worm_policies (
+    repository_id UUID PRIMARY KEY REFERENCES repositories(id) ON DELETE CASCADE,
+    retention_days INTEGER NOT NULL CHECK (retention_days > 0),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- A policy can only be extended. Shortening it would leave later writes
+-- less protected than the compliance regime promised.
+CREATE OR REPLACE FUNCTION ak_check_worm_policy_extension() RETURNS trigger AS $$
+BEGIN
+    IF NEW.retention_days < OLD.retention_days THEN
+        RAISE EXCEPTION 'WORM retention for repository % cannot be shortened from % to % days',
+            OLD.repository_id, OLD.retention_days, NEW.retention_days
+            USING ERRCODE = 'check_violation';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS ak_repository_worm_policy_extend_only ON repository_worm_policies;
+CREATE TRIGGER ak_repository_worm_policy_extend_only
+    BEFORE UPDATE ON repository_worm_policies
+    FOR EACH ROW
+    EXECUTE FUNCTION ak_check_worm_policy_extension();
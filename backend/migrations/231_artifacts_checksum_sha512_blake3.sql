@@ -0,0 +1,26 @@
+-- SHA-512 and BLAKE3 digests alongside SHA-256 / SHA-1 / MD5.
+--
+-- New uploads through the artifact service record both at ingest. Rows
+-- written before this migration, or by format handlers that insert
+-- artifacts directly, start out NULL and are filled in by the checksum
+-- backfill scheduler job, which re-hashes the stored blob.
+--
+-- Both columns are lookup keys for checksum search and checksum deploy,
+-- hence the partial indexes (same reasoning as migration 107). The last
+-- index lets the backfill find its remaining work without a scan.
+
+ALTER TABLE artifacts
+    ADD COLUMN IF NOT EXISTS checksum_sha512 CHAR(128),
+    ADD COLUMN IF NOT EXISTS checksum_blake3 CHAR(64);
+
+CREATE INDEX IF NOT EXISTS idx_artifacts_checksum_sha512
+  ON artifacts (checksum_sha512)
+  WHERE checksum_sha512 IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_artifacts_checksum_blake3
+  ON artifacts (checksum_blake3)
+  WHERE checksum_blake3 IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_artifacts_checksum_backfill
+  ON artifacts (created_at)
+  WHERE is_deleted = false AND (checksum_sha512 IS NULL OR checksum_blake3 IS NULL);
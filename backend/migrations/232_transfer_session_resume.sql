@@ -0,0 +1,21 @@
+-- Resumable peer transfer sessions.
+--
+-- Chunks a requesting peer uploads through the transfer API are staged in
+-- primary storage; `storage_key` records where, so a resume can check that a
+-- chunk marked completed still has its bytes and the expiry job can delete
+-- the staged chunks of an abandoned session.
+--
+-- `updated_at` is the session's last activity (chunk completed, failed or
+-- staged, session resumed). The scheduler expires pending and in-progress
+-- sessions that have seen none for a day; the partial index keeps that scan
+-- off the completed history.
+
+ALTER TABLE transfer_chunks
+    ADD COLUMN IF NOT EXISTS storage_key TEXT;
+
+ALTER TABLE transfer_sessions
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS idx_transfer_sessions_activity
+  ON transfer_sessions (updated_at)
+  WHERE status IN ('pending', 'in_progress', 'failed');
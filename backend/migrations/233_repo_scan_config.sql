@@ -0,0 +1,18 @@
+-- Per-repository scan engine configuration.
+--
+-- Without a row here every registered scanner runs on a scan-enabled
+-- repository. `engines` lists the engines the dispatcher runs instead (an
+-- empty array disables scanning, e.g. for raw binary repositories),
+-- `ignore_file` holds a .trivyignore-style list of suppressed vulnerability
+-- ids, and `scanner_args` maps an engine to its extra, allowlisted
+-- arguments.
+
+CREATE TABLE IF NOT EXISTS repo_scan_config (
+    repository_id UUID PRIMARY KEY REFERENCES repositories(id) ON DELETE CASCADE,
+    engines TEXT[] NOT NULL DEFAULT '{}'
+        CHECK (engines <@ ARRAY['trivy', 'grype', 'osv', 'openscap']::TEXT[]),
+    ignore_file TEXT,
+    scanner_args JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
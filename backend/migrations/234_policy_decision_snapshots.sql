@@ -0,0 +1,40 @@
+-- Point-in-time snapshots of policy decisions for gated actions.
+--
+-- Each row records what a policy decision was based on when an artifact
+-- passed a promotion or a download from a policy-gated repository: the
+-- policies in force, the scans and open findings considered and the
+-- exceptions applied. Policies, findings and acknowledgements all change
+-- later, so this is the only way to answer "why was this allowed at the
+-- time?" during incident forensics.
+--
+-- There is deliberately no foreign key to artifacts or repositories: the
+-- record must outlive a purge of the artifact it describes. The snapshot
+-- carries the artifact's path and repository key for that case.
+--
+-- Promotion decisions are one row per promotion (`reference_id` is the
+-- promotion id). Download decisions are deduplicated on the snapshot
+-- digest; a repeat with identical inputs only moves `last_seen_at`.
+
+CREATE TABLE IF NOT EXISTS policy_decision_snapshots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    artifact_id UUID NOT NULL,
+    repository_id UUID NOT NULL,
+    action VARCHAR(32) NOT NULL CHECK (action IN ('download', 'promotion')),
+    reference_id UUID,
+    actor_id UUID,
+    allowed BOOLEAN NOT NULL,
+    snapshot JSONB NOT NULL,
+    snapshot_digest CHAR(64) NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_policy_decision_snapshots_dedup
+  ON policy_decision_snapshots (artifact_id, action, snapshot_digest)
+  WHERE reference_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_policy_decision_snapshots_artifact
+  ON policy_decision_snapshots (artifact_id, first_seen_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_policy_decision_snapshots_repository
+  ON policy_decision_snapshots (repository_id, first_seen_at DESC);
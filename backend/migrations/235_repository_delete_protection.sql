@@ -0,0 +1,18 @@
+-- Download-activity based deletion protection.
+--
+-- A repository with a row here refuses to delete artifacts that production
+-- may still depend on: artifacts downloaded within the last
+-- `recent_download_days` days, or by more than `min_distinct_consumers`
+-- distinct consumers. `action` is 'block' (the delete is refused) or
+-- 'confirm' (the delete needs an explicit confirmation).
+
+CREATE TABLE IF NOT EXISTS repository_delete_protection (
+    repository_id UUID PRIMARY KEY REFERENCES repositories(id) ON DELETE CASCADE,
+    recent_download_days INTEGER CHECK (recent_download_days > 0),
+    min_distinct_consumers INTEGER CHECK (min_distinct_consumers >= 0),
+    action TEXT NOT NULL DEFAULT 'block' CHECK (action IN ('block', 'confirm')),
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (recent_download_days IS NOT NULL OR min_distinct_consumers IS NOT NULL)
+);
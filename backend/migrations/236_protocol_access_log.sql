@@ -0,0 +1,32 @@
+-- Protocol access log: one row per request to a native format endpoint
+-- (`/npm/...`, `/pypi/...`, `/v2/...`, ...), with the client tool parsed from
+-- the User-Agent. Rows are written in batches and pruned after
+-- ACCESS_LOG_RETENTION_DAYS.
+
+CREATE TABLE IF NOT EXISTS protocol_access_log (
+    id BIGSERIAL PRIMARY KEY,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    format TEXT NOT NULL,
+    repository_key TEXT,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    package TEXT,
+    version TEXT,
+    artifact_id UUID,
+    client_tool TEXT,
+    client_version TEXT,
+    user_agent TEXT,
+    user_id UUID,
+    client_ip TEXT,
+    status INTEGER NOT NULL,
+    outcome TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    correlation_id TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_protocol_access_log_occurred_at
+    ON protocol_access_log (occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_protocol_access_log_repo
+    ON protocol_access_log (repository_key, occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_protocol_access_log_client
+    ON protocol_access_log (client_tool, occurred_at DESC);
@@ -3,6 +3,11 @@
 //! Provides utilities for handlers to return either:
 //! - 302 redirect to presigned URL (S3/CloudFront/Azure/GCS)
 //! - Streamed content (filesystem or when redirect is disabled)
+//!
+//! It also owns the download headers: [`download_content_type`] maps a
+//! format and path to the media type package clients expect when the stored
+//! type is the generic `application/octet-stream`, and
+//! [`download_content_disposition`] picks `inline` or `attachment` for it.
 
 use axum::body::Body;
 use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE, LOCATION};
@@ -11,6 +16,7 @@ use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
 use std::time::Duration;
 
+use crate::models::repository::RepositoryFormat;
 use crate::storage::{PresignedUrl, PresignedUrlSource, StorageBackend};
 
 /// Header to indicate how the artifact was served
@@ -33,6 +39,16 @@ pub const X_ARTIFACT_STORAGE: &str = "x-artifact-storage";
 ///
 /// The returned string is always a valid `HeaderValue`.
 pub(crate) fn content_disposition_attachment(filename: &str) -> String {
+    content_disposition("attachment", filename)
+}
+
+/// `Content-Disposition: inline` counterpart of
+/// [`content_disposition_attachment`], with the same sanitising.
+pub(crate) fn content_disposition_inline(filename: &str) -> String {
+    content_disposition("inline", filename)
+}
+
+fn content_disposition(kind: &str, filename: &str) -> String {
     // Drop control characters (incl. CR, LF, NUL, tab) up front so nothing that
     // could break the header survives into either rendered form.
     let cleaned: String = filename.chars().filter(|c| !c.is_control()).collect();
@@ -51,12 +67,131 @@ pub(crate) fn content_disposition_attachment(filename: &str) -> String {
     }
 
     if cleaned.is_ascii() {
-        format!("attachment; filename=\"{ascii}\"")
+        format!("{kind}; filename=\"{ascii}\"")
     } else {
         // RFC 5987 extended value carries the exact UTF-8 name for clients that
         // support it; the ASCII `filename` above remains for those that don't.
         let encoded = urlencoding::encode(&cleaned);
-        format!("attachment; filename=\"{ascii}\"; filename*=UTF-8''{encoded}")
+        format!("{kind}; filename=\"{ascii}\"; filename*=UTF-8''{encoded}")
+    }
+}
+
+/// Content type used when nothing more specific is known.
+pub const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Stored or upstream content types that say nothing about the payload.
+fn is_generic_content_type(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    matches!(
+        essence.as_str(),
+        "" | "application/octet-stream" | "binary/octet-stream" | "application/unknown"
+    )
+}
+
+fn is_oci_format(format: &RepositoryFormat) -> bool {
+    matches!(
+        format,
+        RepositoryFormat::Docker
+            | RepositoryFormat::Podman
+            | RepositoryFormat::Buildx
+            | RepositoryFormat::Oras
+            | RepositoryFormat::WasmOci
+            | RepositoryFormat::HelmOci
+    )
+}
+
+/// Media type package clients expect for `path` in a `format` repository,
+/// or `None` when the format has no convention for it.
+fn format_content_type(format: &RepositoryFormat, path: &str) -> Option<&'static str> {
+    let lower = path.to_ascii_lowercase();
+    if lower.is_empty() || lower.ends_with('/') {
+        // Directory listings and simple-index pages.
+        return Some("text/html; charset=utf-8");
+    }
+    if is_oci_format(format) && lower.contains("/manifests/") {
+        return Some("application/vnd.oci.image.manifest.v1+json");
+    }
+    let file = lower.rsplit('/').next().unwrap_or(&lower);
+    if file.ends_with(".tar.gz") {
+        return Some("application/gzip");
+    }
+    let ext = file.rsplit_once('.').map(|(_, ext)| ext)?;
+    Some(match ext {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "rpm" => "application/x-rpm",
+        "deb" | "udeb" | "ddeb" => "application/vnd.debian.binary-package",
+        "jar" | "war" | "ear" => "application/java-archive",
+        "pom" | "xml" => "application/xml",
+        "whl" | "egg" | "zip" | "nupkg" | "snupkg" | "vsix" => "application/zip",
+        "tgz" | "gz" | "crate" => "application/gzip",
+        "tar" => "application/x-tar",
+        "bz2" => "application/x-bzip2",
+        "xz" => "application/x-xz",
+        "zst" => "application/zstd",
+        "json" => "application/json",
+        "yaml" | "yml" => "application/x-yaml",
+        "asc" | "sig" => "application/pgp-signature",
+        "md5" | "sha1" | "sha256" | "sha512" => "text/plain; charset=utf-8",
+        _ => return None,
+    })
+}
+
+/// Content type to serve a download of `path` from a `format` repository
+/// with.
+///
+/// A specific `stored` type (recorded at upload, or sent by the upstream)
+/// wins. When it is missing or generic, the format's convention for the
+/// path applies, e.g. `application/x-rpm` for RPMs or `text/html` for index
+/// pages; generic repositories also fall back to the extension's registered
+/// type. Anything else stays `application/octet-stream`.
+pub(crate) fn download_content_type(
+    format: &RepositoryFormat,
+    path: &str,
+    stored: Option<&str>,
+) -> String {
+    if let Some(stored) = stored.map(str::trim) {
+        if !is_generic_content_type(stored) && axum::http::HeaderValue::from_str(stored).is_ok() {
+            return stored.to_string();
+        }
+    }
+    if let Some(content_type) = format_content_type(format, path) {
+        return content_type.to_string();
+    }
+    if *format == RepositoryFormat::Generic {
+        if let Some(guess) = mime_guess::from_path(path).first_raw() {
+            return guess.to_string();
+        }
+    }
+    OCTET_STREAM.to_string()
+}
+
+/// `Content-Disposition` for a download served as `content_type`.
+///
+/// Documents a client or browser reads in place (HTML indexes, JSON, XML,
+/// YAML and plain text) are `inline`; packages and other binaries are
+/// `attachment`, so `curl -OJ` and browsers save them under `filename`.
+pub(crate) fn download_content_disposition(content_type: &str, filename: &str) -> String {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let inline = essence.starts_with("text/")
+        || essence == "application/json"
+        || essence == "application/xml"
+        || essence == "application/x-yaml"
+        || essence.ends_with("+json")
+        || essence.ends_with("+xml");
+    if inline {
+        content_disposition_inline(filename)
+    } else {
+        content_disposition_attachment(filename)
     }
 }
 
@@ -298,6 +433,120 @@ mod tests {
         assert!(v.contains("%C3%AF") && v.contains("%C3%A9"), "v = {v:?}");
     }
 
+    #[test]
+    fn test_download_content_type_prefers_specific_stored_type() {
+        assert_eq!(
+            download_content_type(
+                &RepositoryFormat::Docker,
+                "v2/app/manifests/latest",
+                Some("application/vnd.docker.distribution.manifest.v2+json"),
+            ),
+            "application/vnd.docker.distribution.manifest.v2+json"
+        );
+        // A header-unsafe stored value is never echoed.
+        assert_eq!(
+            download_content_type(&RepositoryFormat::Rpm, "x.rpm", Some("text/plain\r\nX: y")),
+            "application/x-rpm"
+        );
+    }
+
+    #[test]
+    fn test_download_content_type_maps_generic_types_per_format() {
+        let cases = [
+            (
+                RepositoryFormat::Rpm,
+                "el9/x86_64/curl-8.0-1.x86_64.rpm",
+                "application/x-rpm",
+            ),
+            (
+                RepositoryFormat::Debian,
+                "pool/main/c/curl/curl_8.0_amd64.deb",
+                "application/vnd.debian.binary-package",
+            ),
+            (
+                RepositoryFormat::Maven,
+                "com/acme/app/1.0/app-1.0.jar",
+                "application/java-archive",
+            ),
+            (
+                RepositoryFormat::Maven,
+                "com/acme/app/1.0/app-1.0.pom",
+                "application/xml",
+            ),
+            (
+                RepositoryFormat::Pypi,
+                "simple/requests/",
+                "text/html; charset=utf-8",
+            ),
+            (
+                RepositoryFormat::Pypi,
+                "requests-2.0-py3-none-any.whl",
+                "application/zip",
+            ),
+            (
+                RepositoryFormat::Npm,
+                "lodash/-/lodash-4.17.21.tgz",
+                "application/gzip",
+            ),
+            (RepositoryFormat::Helm, "index.yaml", "application/x-yaml"),
+            (
+                RepositoryFormat::Oras,
+                "v2/app/manifests/sha256:abc",
+                "application/vnd.oci.image.manifest.v1+json",
+            ),
+            (
+                RepositoryFormat::Maven,
+                "com/acme/app/1.0/app-1.0.jar.sha1",
+                "text/plain; charset=utf-8",
+            ),
+            (
+                RepositoryFormat::Generic,
+                "docs/guide.pdf",
+                "application/pdf",
+            ),
+            // No convention for the path: stays generic.
+            (
+                RepositoryFormat::Rubygems,
+                "gems/rails-7.0.gem",
+                OCTET_STREAM,
+            ),
+            (
+                RepositoryFormat::Alpine,
+                "v3.19/main/x86_64/curl-8.5.apk",
+                OCTET_STREAM,
+            ),
+        ];
+        for (format, path, expected) in cases {
+            for stored in [None, Some(""), Some("application/octet-stream")] {
+                assert_eq!(
+                    download_content_type(&format, path, stored),
+                    expected,
+                    "{format:?} {path} (stored {stored:?})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_download_content_disposition() {
+        assert_eq!(
+            download_content_disposition("application/x-rpm", "curl.rpm"),
+            "attachment; filename=\"curl.rpm\""
+        );
+        assert_eq!(
+            download_content_disposition("text/html; charset=utf-8", "index.html"),
+            "inline; filename=\"index.html\""
+        );
+        assert_eq!(
+            download_content_disposition("application/vnd.oci.image.manifest.v1+json", "latest"),
+            "inline; filename=\"latest\""
+        );
+        assert!(
+            download_content_disposition("application/json", "a\r\nb.json")
+                .starts_with("inline; filename=\"ab.json\"")
+        );
+    }
+
     #[test]
     fn test_redirect_constructor() {
         let presigned = PresignedUrl {
@@ -126,11 +126,13 @@ fn parse_external_url(raw: &str) -> Option<String> {
 }
 
 /// Operator-configured external base URL, read once from `AK_EXTERNAL_URL`
-/// and cached for the process lifetime. When set, this overrides whatever
-/// [`request_base_url_from_request`] derives from request metadata.
+/// and cached for the process lifetime, falling back to the base URL the
+/// setup wizard recorded (see [`set_stored_external_url`]). When set, this
+/// overrides whatever [`request_base_url_from_request`] derives from request
+/// metadata.
 ///
 /// This is the ONLY *trusted* source of the SP base URL — the value comes
-/// from a process env var set by the operator, never from
+/// from a process env var or the admin-run setup wizard, never from
 /// attacker-influenceable request headers (`Host`, `X-Forwarded-Host`, etc.).
 /// Use [`trusted_external_url`] from outside this module when the caller
 /// needs a base URL that must not be spoofable — e.g. when embedding it in
@@ -144,6 +146,20 @@ fn configured_external_url() -> Option<&'static str> {
             parse_external_url(&raw)
         })
         .as_deref()
+        .or_else(|| STORED_EXTERNAL_URL.get().map(String::as_str))
+}
+
+/// Base URL recorded by the setup wizard (`server.base_url`).
+static STORED_EXTERNAL_URL: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+/// Install the base URL recorded by the setup wizard, used when
+/// `AK_EXTERNAL_URL` is unset. Called at startup with the stored setting and
+/// when the wizard completes; the first valid value wins for the process
+/// lifetime, and other replicas pick it up on their next restart.
+pub fn set_stored_external_url(raw: &str) {
+    if let Some(url) = parse_external_url(raw) {
+        let _ = STORED_EXTERNAL_URL.set(url);
+    }
 }
 
 /// Public accessor for the *trusted* external base URL — the value of
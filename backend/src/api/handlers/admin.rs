@@ -1,4 +1,4 @@
-//! Admin handlers (backups, system settings).
+//! Admin handlers (backups, system settings, configuration reload).
 
 use axum::{
     extract::{Extension, Path, Query, State},
@@ -6,18 +6,30 @@ use axum::{
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, OnceLock};
-use tokio::sync::Semaphore;
+use std::sync::Arc;
 use utoipa::{IntoParams, OpenApi, ToSchema};
 use uuid::Uuid;
 
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
+use crate::config_reload::ReloadReport;
 use crate::error::{AppError, Result};
+use crate::services::access_log_service::{
+    AccessLogEntry, AccessLogFilter, AccessLogService, AccessOutcome,
+};
+use crate::services::audit_archive_service::{
+    ArchivedAuditRecord, AuditArchive, AuditArchiveService,
+};
+use crate::services::audit_service::audit_fire_and_forget;
 use crate::services::backup_service::{
     BackupService, BackupStatus, BackupType, CreateBackupRequest as ServiceCreateBackup,
     RestoreOptions,
 };
+use crate::services::bootstrap_service::{self, InstalledLicense};
+use crate::services::notification_email_service::{
+    EmailDelivery, NotificationEmailService, NotificationKind,
+};
+use crate::services::scan_queue_service::{self, ScanJobOptions, ScanTrigger};
 use crate::services::storage_service::StorageService;
 
 /// Create admin routes
@@ -35,9 +47,14 @@ pub fn router() -> Router<SharedState> {
         .route("/downloads/by-user/:user_id", get(list_downloads_by_user))
         .route("/cleanup", post(run_cleanup))
         .route("/reindex", post(trigger_reindex))
+        .route("/config/reload", post(reload_config))
         .route("/rescan-for-inventory", post(rescan_for_inventory))
         .route("/storage-backends", get(list_storage_backends))
         .route("/audit", get(list_audit_logs))
+        .route("/audit/archives", get(list_audit_archives))
+        .route("/audit/archives/:id", get(get_audit_archive))
+        .route("/notifications/deliveries", get(list_email_deliveries))
+        .route("/access-log", get(list_access_log))
 }
 
 // ---------------------------------------------------------------------------
@@ -191,6 +208,298 @@ pub async fn list_audit_logs(
     }))
 }
 
+/// Filters for `GET /api/v1/admin/access-log`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AccessLogQuery {
+    /// Filter by package format (e.g. `npm`, `pypi`, `oci`).
+    pub format: Option<String>,
+    /// Filter by repository key.
+    pub repository: Option<String>,
+    /// Filter by package name (case-insensitive).
+    pub package: Option<String>,
+    /// Filter by client tool parsed from the User-Agent (e.g. `pip`, `docker`).
+    pub client_tool: Option<String>,
+    /// Filter by request outcome.
+    pub outcome: Option<AccessOutcome>,
+    /// Filter by exact HTTP status code.
+    pub status: Option<i32>,
+    /// Filter by authenticated user id.
+    pub user_id: Option<Uuid>,
+    /// Filter by client IP address.
+    pub client_ip: Option<String>,
+    /// Inclusive lower time bound (RFC 3339).
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Inclusive upper time bound (RFC 3339).
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    /// 1-based page index (default 1).
+    pub page: Option<u32>,
+    /// Page size (default 50, max 200).
+    pub per_page: Option<u32>,
+}
+
+/// Paginated protocol access-log query response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccessLogListResponse {
+    pub items: Vec<AccessLogEntry>,
+    pub total: i64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+/// Query the protocol access log (admin only).
+///
+/// Lists requests to the native format endpoints (`/npm/...`, `/pypi/...`,
+/// `/v2/...`, ...) newest first, with the package, client tool and outcome of
+/// each. Entries are kept for `ACCESS_LOG_RETENTION_DAYS`.
+#[utoipa::path(
+    get,
+    path = "/access-log",
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(AccessLogQuery),
+    responses(
+        (status = 200, description = "Protocol requests", body = AccessLogListResponse),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_access_log(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<AccessLogQuery>,
+) -> Result<Json<AccessLogListResponse>> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+
+    let (offset, limit, page, per_page) = audit_page_bounds(query.page, query.per_page);
+    let filter = AccessLogFilter {
+        format: query.format.as_deref(),
+        repository_key: query.repository.as_deref(),
+        package: query.package.as_deref(),
+        client_tool: query.client_tool.as_deref(),
+        outcome: query.outcome,
+        status: query.status,
+        user_id: query.user_id,
+        client_ip: query.client_ip.as_deref(),
+        from: query.from,
+        to: query.to,
+    };
+    let (items, total) = AccessLogService::new(state.db.clone())
+        .query(&filter, offset, limit)
+        .await?;
+
+    Ok(Json(AccessLogListResponse {
+        items,
+        total,
+        page,
+        per_page,
+    }))
+}
+
+/// Filters for `GET /api/v1/admin/audit/archives`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditArchiveQuery {
+    /// Only archives containing records at or after this time (RFC 3339).
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only archives containing records at or before this time (RFC 3339).
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    /// 1-based page index (default 1).
+    pub page: Option<u32>,
+    /// Page size (default 50, max 200).
+    pub per_page: Option<u32>,
+}
+
+/// Paginated list of audit archives.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditArchiveListResponse {
+    pub items: Vec<AuditArchive>,
+    pub total: i64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+/// One audit archive together with its records.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditArchiveDetailResponse {
+    pub archive: AuditArchive,
+    pub records: Vec<ArchivedAuditRecord>,
+}
+
+/// List audit-log archives (admin only).
+///
+/// Audit rows removed by the retention sweep are exported to storage first;
+/// this lists those exports, oldest first, optionally restricted to archives
+/// whose time range overlaps `from`..`to`.
+#[utoipa::path(
+    get,
+    path = "/audit/archives",
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(AuditArchiveQuery),
+    responses(
+        (status = 200, description = "Audit archives", body = AuditArchiveListResponse),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_audit_archives(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<AuditArchiveQuery>,
+) -> Result<Json<AuditArchiveListResponse>> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+
+    let (offset, limit, page, per_page) = audit_page_bounds(query.page, query.per_page);
+    let service = AuditArchiveService::new(state.db.clone(), state.storage.clone());
+    let (items, total) = service
+        .list_archives(query.from, query.to, offset, limit)
+        .await?;
+
+    Ok(Json(AuditArchiveListResponse {
+        items,
+        total,
+        page,
+        per_page,
+    }))
+}
+
+/// Read an audit-log archive back from storage (admin only).
+///
+/// Returns the archive metadata and every record it holds. The object's
+/// checksum is verified before the records are returned.
+#[utoipa::path(
+    get,
+    path = "/audit/archives/{id}",
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Archive ID")
+    ),
+    responses(
+        (status = 200, description = "Archive records", body = AuditArchiveDetailResponse),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Archive not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_audit_archive(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AuditArchiveDetailResponse>> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+
+    let service = AuditArchiveService::new(state.db.clone(), state.storage.clone());
+    let archive = service.get_archive(id).await?;
+    let records = service.read_archive(&archive).await?;
+
+    Ok(Json(AuditArchiveDetailResponse { archive, records }))
+}
+
+/// Filters for `GET /api/v1/admin/notifications/deliveries`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EmailDeliveryQuery {
+    /// Delivery status: `pending`, `sent`, `failed`, or `skipped`.
+    pub status: Option<String>,
+    /// Notification kind: `health_alert`, `approval_request`,
+    /// `quota_warning`, or `weekly_digest`.
+    pub notification_type: Option<String>,
+    /// 1-based page index (default 1).
+    pub page: Option<u32>,
+    /// Page size (default 50, max 200).
+    pub per_page: Option<u32>,
+}
+
+/// Paginated list of notification email deliveries.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailDeliveryListResponse {
+    pub items: Vec<EmailDelivery>,
+    pub total: i64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+/// Reject unknown delivery filters up front instead of silently returning an
+/// empty page.
+fn validate_delivery_filters(query: &EmailDeliveryQuery) -> Result<()> {
+    if let Some(status) = query.status.as_deref() {
+        if !matches!(status, "pending" | "sent" | "failed" | "skipped") {
+            return Err(AppError::Validation(format!(
+                "Unknown delivery status '{}'",
+                status
+            )));
+        }
+    }
+    if let Some(kind) = query.notification_type.as_deref() {
+        if NotificationKind::parse(kind).is_none() {
+            return Err(AppError::Validation(format!(
+                "Unknown notification type '{}'",
+                kind
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// List notification email deliveries (admin only).
+///
+/// Every health alert, approval request, quota warning, and weekly digest
+/// email is recorded with its delivery status, newest first.
+#[utoipa::path(
+    get,
+    path = "/notifications/deliveries",
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    params(EmailDeliveryQuery),
+    responses(
+        (status = 200, description = "Email deliveries", body = EmailDeliveryListResponse),
+        (status = 400, description = "Unknown status or notification type"),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_email_deliveries(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<EmailDeliveryQuery>,
+) -> Result<Json<EmailDeliveryListResponse>> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+    validate_delivery_filters(&query)?;
+
+    let (offset, limit, page, per_page) = audit_page_bounds(query.page, query.per_page);
+    let service = NotificationEmailService::new(state.db.clone(), state.smtp_service.clone());
+    let (items, total) = service
+        .list_deliveries(
+            query.status.as_deref(),
+            query.notification_type.as_deref(),
+            limit,
+            offset,
+        )
+        .await?;
+
+    Ok(Json(EmailDeliveryListResponse {
+        items,
+        total,
+        page,
+        per_page,
+    }))
+}
+
 /// List available storage backends.
 ///
 /// Returns the names of all configured and available storage backends.
@@ -602,6 +911,10 @@ pub struct SystemSettings {
     pub audit_retention_days: i32,
     pub backup_retention_count: i32,
     pub edge_stale_threshold_minutes: i32,
+    /// Read-only: the license imported by the setup wizard, identified by
+    /// its fingerprint. Ignored in the update-settings request body.
+    #[serde(default)]
+    pub license: Option<InstalledLicense>,
 }
 
 /// Get system settings
@@ -646,6 +959,7 @@ pub async fn get_settings(State(state): State<SharedState>) -> Result<Json<Syste
         audit_retention_days: 90,
         backup_retention_count: 10,
         edge_stale_threshold_minutes: 5,
+        license: bootstrap_service::stored_license(&state.db).await?,
     };
 
     for row in settings {
@@ -708,7 +1022,7 @@ struct SystemSettingsRow {
 pub async fn update_settings(
     State(state): State<SharedState>,
     Extension(_auth): Extension<AuthExtension>,
-    Json(settings): Json<SystemSettings>,
+    Json(mut settings): Json<SystemSettings>,
 ) -> Result<Json<SystemSettings>> {
     // Update each setting
     let settings_to_update = vec![
@@ -750,6 +1064,7 @@ pub async fn update_settings(
         .map_err(|e| AppError::Database(e.to_string()))?;
     }
 
+    settings.license = bootstrap_service::stored_license(&state.db).await?;
     Ok(Json(settings))
 }
 
@@ -1104,10 +1419,16 @@ pub async fn run_cleanup(
     let settings = get_settings(State(state.clone())).await?.0;
 
     if request.cleanup_audit_logs.unwrap_or(false) {
-        use crate::services::audit_service::AuditService;
-        let audit_service = AuditService::new(state.db.clone());
-        result.audit_logs_deleted =
-            audit_service.cleanup(settings.audit_retention_days).await? as i64;
+        // Aged rows are exported to storage before deletion unless the
+        // operator turned archival off (AUDIT_ARCHIVE_ENABLED=false).
+        let archive_service = AuditArchiveService::new(state.db.clone(), state.storage.clone());
+        result.audit_logs_deleted = archive_service
+            .apply_retention(
+                settings.audit_retention_days,
+                state.config.audit_archive_enabled,
+            )
+            .await?
+            .records_deleted as i64;
     }
 
     if request.cleanup_old_backups.unwrap_or(false) {
@@ -1186,6 +1507,39 @@ pub async fn trigger_reindex(
     }))
 }
 
+/// Reload the runtime-tunable configuration.
+///
+/// Re-reads the log filter, rate limits, scanner endpoints, demo mode and
+/// quota warning threshold from the environment and env file and applies
+/// the ones that changed, without a restart. Same as sending `SIGHUP`.
+#[utoipa::path(
+    post,
+    path = "/config/reload",
+    context_path = "/api/v1/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Configuration reloaded", body = ReloadReport),
+        (status = 400, description = "Invalid log filter"),
+        (status = 401, description = "Admin privileges required"),
+        (status = 500, description = "Configuration could not be parsed")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn reload_config(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<ReloadReport>> {
+    if !auth.is_admin {
+        return Err(AppError::Unauthorized(
+            "Admin privileges required".to_string(),
+        ));
+    }
+
+    let report = crate::config_reload::reload(state.scanner_service.as_deref())?;
+    audit_fire_and_forget(state.db.clone(), report.audit_entry().user(auth.user_id)).await;
+    Ok(Json(report))
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct RescanForInventoryRequest {
     /// Maximum number of artifacts to enqueue in this call. Operators
@@ -1220,32 +1574,13 @@ pub struct RescanForInventoryResponse {
 /// 503 if the scanner is not configured (operationally normal on
 /// minimal stacks, not a server bug).
 ///
-/// The handler is dispatch-and-return: scans run on background tokio
-/// tasks. Callers poll the response.artifacts_enqueued value across
-/// repeated calls to drive a full backfill, stopping when a call
-/// returns zero.
-/// Bound on simultaneously in-flight rescan tasks across all calls of
-/// the rescan-for-inventory endpoint. Each permit corresponds to one
-/// background scan; the cap exists so a polling admin (or a stolen
-/// admin token) cannot pin every Tokio worker on scanner I/O and
-/// starve normal upload traffic. 16 leaves headroom for parallel
-/// rescans on a default 8-vCPU runtime without monopolising the pool.
-///
-/// Back-pressure model: dispatch is unbounded (`tokio::spawn` per
-/// artifact), but each task awaits a permit before doing scanner work.
-/// A tight polling loop will accumulate parked tasks proportional to
-/// the dispatch rate; the resulting memory is bounded by the operator
-/// pacing because each call returns immediately and exposes
-/// `artifacts_enqueued`. We do NOT acquire pre-spawn / 503-on-contention
-/// here: the dispatch-and-return contract is the point of the endpoint
-/// (callers poll until `artifacts_enqueued == 0`).
-const RESCAN_INFLIGHT_CAP: usize = 16;
-
-fn rescan_inflight_semaphore() -> &'static Arc<Semaphore> {
-    static SEM: OnceLock<Arc<Semaphore>> = OnceLock::new();
-    SEM.get_or_init(|| Arc::new(Semaphore::new(RESCAN_INFLIGHT_CAP)))
-}
-
+/// The handler is enqueue-and-return: rescans are queued as `rescan` jobs
+/// and run by the scan queue workers, behind manual scans and ahead of
+/// scan-on-upload, so a backfill neither starves interactive scans nor is
+/// starved by upload traffic. Callers poll the response.artifacts_enqueued
+/// value across repeated calls to drive a full backfill, stopping when a
+/// call returns zero. Re-enqueueing an artifact that is still queued merges
+/// into its existing job.
 #[utoipa::path(
     post,
     path = "/rescan-for-inventory",
@@ -1270,11 +1605,11 @@ pub async fn rescan_for_inventory(
         ));
     }
 
-    let scanner = state
-        .scanner_service
-        .as_ref()
-        .ok_or_else(|| AppError::ServiceUnavailable("Scanner service not configured".to_string()))?
-        .clone();
+    if state.scanner_service.is_none() {
+        return Err(AppError::ServiceUnavailable(
+            "Scanner service not configured".to_string(),
+        ));
+    }
 
     // Cap the limit at 1000 to bound work per call regardless of what the
     // caller asks for. The default of 100 matches the typical operator
@@ -1295,50 +1630,30 @@ pub async fn rescan_for_inventory(
         actor_username = %auth.username,
         limit,
         enqueued,
-        "admin.rescan_for_inventory: dispatching background rescans"
+        "admin.rescan_for_inventory: queueing rescans"
     );
 
-    let semaphore = rescan_inflight_semaphore().clone();
-    for artifact_id in artifact_ids {
-        let scanner_for_spawn = scanner.clone();
-        let permit_sem = semaphore.clone();
-        tokio::spawn(async move {
-            // Acquire a permit before doing scanner work. Bounds total
-            // in-flight rescans across all callers of this endpoint so
-            // a tight polling loop can't pin every Tokio worker. The
-            // permit is held for the duration of the scan and released
-            // on task completion (success or failure).
-            // Nothing in this binary calls `close()` on the semaphore, so
-            // the only way `acquire_owned` errors is if a future change
-            // adds an explicit shutdown path. Bail quietly if that happens.
-            let _permit = match permit_sem.acquire_owned().await {
-                Ok(p) => p,
-                Err(_) => return,
-            };
-            // Use `force = true` (via scan_artifact_with_options) so the
-            // repo's scan-enabled config doesn't block the backfill. The
-            // operator already chose to rescan; respecting per-repo config
-            // here would silently skip exactly the repos the inventory
-            // gap most likely affects.
-            //
-            // `bypass_dedup = true` (#1469) is required here too: the
-            // inventory backfill is the user-visible "rescan to populate
-            // SBOM rows" admin path. If a prior scan completed with zero
-            // findings due to a silent extraction failure, the cached row
-            // would short-circuit this rescan too and the inventory would
-            // stay empty.
-            if let Err(e) = scanner_for_spawn
-                .scan_artifact_with_options(artifact_id, true, true)
-                .await
-            {
-                tracing::error!(
-                    artifact_id = %artifact_id,
-                    error = %e,
-                    "rescan-for-inventory: scan failed"
-                );
-            }
-        });
-    }
+    // `force = true` so the repo's scan-enabled config doesn't block the
+    // backfill. The operator already chose to rescan; respecting per-repo
+    // config here would silently skip exactly the repos the inventory gap
+    // most likely affects.
+    //
+    // `bypass_dedup = true` (#1469) is required here too: the inventory
+    // backfill is the user-visible "rescan to populate SBOM rows" admin
+    // path. If a prior scan completed with zero findings due to a silent
+    // extraction failure, the cached row would short-circuit this rescan
+    // too and the inventory would stay empty.
+    scan_queue_service::enqueue_many(
+        &state.db,
+        &artifact_ids,
+        ScanTrigger::Rescan,
+        ScanJobOptions {
+            force: true,
+            bypass_dedup: true,
+            prepared: None,
+        },
+    )
+    .await?;
 
     Ok(Json(RescanForInventoryResponse {
         artifacts_enqueued: enqueued,
@@ -1364,9 +1679,14 @@ pub async fn rescan_for_inventory(
         list_downloads_by_user,
         run_cleanup,
         trigger_reindex,
+        reload_config,
         rescan_for_inventory,
         list_storage_backends,
         list_audit_logs,
+        list_audit_archives,
+        get_audit_archive,
+        list_email_deliveries,
+        list_access_log,
     ),
     components(schemas(
         ListBackupsQuery,
@@ -1376,6 +1696,7 @@ pub async fn rescan_for_inventory(
         RestoreRequest,
         RestoreResponse,
         SystemSettings,
+        InstalledLicense,
         SystemStats,
         ListDownloadsQuery,
         DownloadRecord,
@@ -1383,10 +1704,20 @@ pub async fn rescan_for_inventory(
         CleanupRequest,
         CleanupResponse,
         ReindexResponse,
+        ReloadReport,
         RescanForInventoryRequest,
         RescanForInventoryResponse,
         AuditLogItem,
         AuditLogListResponse,
+        AuditArchive,
+        ArchivedAuditRecord,
+        AuditArchiveListResponse,
+        AuditArchiveDetailResponse,
+        EmailDelivery,
+        EmailDeliveryListResponse,
+        AccessLogEntry,
+        AccessOutcome,
+        AccessLogListResponse,
     ))
 )]
 pub struct AdminApiDoc;
@@ -1400,6 +1731,32 @@ mod tests {
     // the coverage gate exercises it even without Postgres.
     // -----------------------------------------------------------------------
 
+    fn delivery_query(status: Option<&str>, kind: Option<&str>) -> EmailDeliveryQuery {
+        EmailDeliveryQuery {
+            status: status.map(str::to_string),
+            notification_type: kind.map(str::to_string),
+            page: None,
+            per_page: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_delivery_filters() {
+        assert!(validate_delivery_filters(&delivery_query(None, None)).is_ok());
+        assert!(
+            validate_delivery_filters(&delivery_query(Some("failed"), Some("weekly_digest")))
+                .is_ok()
+        );
+        assert!(matches!(
+            validate_delivery_filters(&delivery_query(Some("bounced"), None)),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            validate_delivery_filters(&delivery_query(None, Some("sms"))),
+            Err(AppError::Validation(_))
+        ));
+    }
+
     #[test]
     fn test_audit_page_bounds_defaults() {
         // No page/per_page -> page 1, default page size, offset 0.
@@ -1558,6 +1915,7 @@ mod tests {
             audit_retention_days: 90,
             backup_retention_count: 10,
             edge_stale_threshold_minutes: 5,
+            license: None,
         };
         assert!(!settings.allow_anonymous_download);
         assert_eq!(settings.max_upload_size_bytes, 104_857_600);
@@ -1581,6 +1939,7 @@ mod tests {
             audit_retention_days: 7,
             backup_retention_count: 5,
             edge_stale_threshold_minutes: 10,
+            license: None,
         };
         let json = serde_json::to_string(&settings).unwrap();
         let parsed: SystemSettings = serde_json::from_str(&json).unwrap();
@@ -1608,6 +1967,7 @@ mod tests {
             audit_retention_days: 90,
             backup_retention_count: 10,
             edge_stale_threshold_minutes: 5,
+            license: None,
         };
         let json = serde_json::to_string(&settings).unwrap();
         assert!(
@@ -1652,6 +2012,44 @@ mod tests {
         assert_eq!(settings.environment, "development");
     }
 
+    /// The wizard's imported license is reported by fingerprint only.
+    #[tokio::test]
+    async fn test_get_settings_reports_imported_license() {
+        use crate::api::handlers::test_db_helpers as tdh;
+
+        let Some(pool) = tdh::try_pool().await else {
+            return;
+        };
+        // Leave a real installation's license alone.
+        let inserted = sqlx::query(
+            "INSERT INTO system_settings (key, value, is_sensitive) VALUES ($1, $2, true) \
+             ON CONFLICT (key) DO NOTHING",
+        )
+        .bind(bootstrap_service::LICENSE_SETTING)
+        .bind(serde_json::json!("LICENSE-123"))
+        .execute(&pool)
+        .await
+        .unwrap()
+        .rows_affected();
+        if inserted == 0 {
+            return;
+        }
+        let state = tdh::build_state(pool.clone(), "/tmp/admin-settings-license");
+
+        let result = get_settings(State(state)).await;
+        let _ = sqlx::query("DELETE FROM system_settings WHERE key = $1")
+            .bind(bootstrap_service::LICENSE_SETTING)
+            .execute(&pool)
+            .await;
+
+        let Json(settings) = result.unwrap();
+        let license = settings.license.expect("license reported");
+        assert_eq!(license.size_bytes, 11);
+        assert!(!serde_json::to_string(&license)
+            .unwrap()
+            .contains("LICENSE-123"));
+    }
+
     // -----------------------------------------------------------------------
     // BackupResponse serialization
     // -----------------------------------------------------------------------
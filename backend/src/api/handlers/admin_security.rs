@@ -18,6 +18,10 @@
 //! Public / global repos are never enumerated (`exposure: everyone` /
 //! `effectively-everyone`).
 //!
+//! `.../image/charts` answers the chart side of image CVE impact: which Helm
+//! charts deploy a given image, from the references recorded at chart upload
+//! (`chart_image_service`).
+//!
 //! Everything here is mounted under the `/admin` nest (admin_middleware),
 //! and each handler re-checks `is_admin` as defense in depth: download
 //! attribution is sensitive telemetry.
@@ -35,6 +39,8 @@ use crate::api::handlers::admin::parse_rfc3339_bound;
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
+use crate::formats::oci::ImageReference;
+use crate::services::chart_image_service::{self, ChartUsingImage};
 use crate::services::repository_service::permissions_grant_exists_for;
 
 /// Create admin security-analytics routes (nested at `/admin/security`).
@@ -52,6 +58,7 @@ pub fn router() -> Router<SharedState> {
             "/artifact/:artifact_id/accessible-users",
             get(artifact_accessible_users),
         )
+        .route("/image/charts", get(charts_using_image))
 }
 
 /// Default page size for the downloaders listing.
@@ -895,13 +902,74 @@ pub async fn artifact_accessible_users(
     ))
 }
 
+/// Default / maximum number of charts returned by the charts-by-image lookup.
+const CHARTS_BY_IMAGE_DEFAULT_LIMIT: i64 = 100;
+const CHARTS_BY_IMAGE_MAX_LIMIT: i64 = 1000;
+
+/// Query parameters for the charts-by-image lookup.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct ChartsUsingImageQuery {
+    /// Image reference, e.g. `nginx`, `bitnami/redis:7.2` or
+    /// `ghcr.io/acme/app@sha256:...`. Without a tag or digest every tag of
+    /// the image matches.
+    pub image: String,
+    /// Maximum charts returned (1..=1000, default 100).
+    pub limit: Option<i64>,
+}
+
+/// Charts deploying an image.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChartsUsingImageResponse {
+    /// The looked-up image, normalized (`docker.io/library/nginx`).
+    pub image: String,
+    pub charts: Vec<ChartUsingImage>,
+}
+
+/// Parse the `image` query parameter into a normalized reference.
+pub(crate) fn parse_image_query(image: &str) -> Result<ImageReference> {
+    ImageReference::parse(image)
+        .ok_or_else(|| AppError::Validation(format!("Invalid image reference: {:?}", image)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/image/charts",
+    context_path = "/api/v1/admin/security",
+    tag = "admin",
+    params(ChartsUsingImageQuery),
+    responses(
+        (status = 200, description = "Charts deploying the image", body = ChartsUsingImageResponse),
+        (status = 400, description = "Invalid image reference"),
+        (status = 403, description = "Admin privileges required")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn charts_using_image(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ChartsUsingImageQuery>,
+) -> Result<Json<ChartsUsingImageResponse>> {
+    require_admin(&auth)?;
+    let image = parse_image_query(&query.image)?;
+    let limit = query
+        .limit
+        .unwrap_or(CHARTS_BY_IMAGE_DEFAULT_LIMIT)
+        .clamp(1, CHARTS_BY_IMAGE_MAX_LIMIT);
+    let charts = chart_image_service::find_charts_using_image(&state.db, &image, limit).await?;
+    Ok(Json(ChartsUsingImageResponse {
+        image: image.to_string(),
+        charts,
+    }))
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
         cve_blast_radius,
         artifact_blast_radius,
         cve_accessible_users,
-        artifact_accessible_users
+        artifact_accessible_users,
+        charts_using_image
     ),
     components(schemas(
         BlastRadiusResponse,
@@ -912,6 +980,8 @@ pub async fn artifact_accessible_users(
         AccessibleUsersResponse,
         RepoExposure,
         AccessibleUser,
+        ChartsUsingImageResponse,
+        ChartUsingImage,
     ))
 )]
 pub struct AdminSecurityApiDoc;
@@ -924,6 +994,25 @@ mod tests {
     // Pure helpers — no DB required.
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn test_parse_image_query_normalizes() {
+        let image = parse_image_query("nginx:1.25").unwrap();
+        assert_eq!(image.to_string(), "docker.io/library/nginx:1.25");
+        let image = parse_image_query("ghcr.io/acme/app").unwrap();
+        assert_eq!(image.registry, "ghcr.io");
+        assert_eq!(image.tag, None);
+    }
+
+    #[test]
+    fn test_parse_image_query_rejects_invalid() {
+        for bad in ["", "  ", "nginx:", "{{ .Values.image }}"] {
+            assert!(
+                matches!(parse_image_query(bad), Err(AppError::Validation(_))),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+
     #[test]
     fn test_blast_page_bounds_defaults() {
         let (offset, limit, page, per_page) = blast_page_bounds(None, None);
@@ -0,0 +1,156 @@
+//! Kubernetes validating admission webhook.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admission (/api/v1/admission)
+//! POST /validate                    → validate
+//! ```
+//!
+//! Register the endpoint in a `ValidatingWebhookConfiguration` for pods (and
+//! optionally workload kinds) on CREATE and UPDATE. The API server must
+//! authenticate with an API token, supplied through the admission
+//! configuration's kubeconfig; the token's repository access bounds which
+//! images can be admitted. See [`crate::services::admission_service`] for
+//! the rules and `ADMISSION_*` settings.
+
+use axum::{
+    extract::{Extension, State},
+    routing::post,
+    Json, Router,
+};
+
+use crate::api::handlers::artifacts::check_artifact_visibility;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::admission_service::{
+    self, AdmissionPolicy, AdmissionResponse, AdmissionReview, ImageReference,
+};
+use crate::services::artifact_verification_service::ArtifactVerificationService;
+use crate::services::audit_export::Outcome;
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::deploy_gate_service::{self, DeployGateRequest, GateTarget};
+use crate::services::signing_service::SigningService;
+
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/validate", post(validate))
+}
+
+/// Why one image is not admitted, or `None` when it is.
+async fn check_image(
+    state: &SharedState,
+    auth: &AuthExtension,
+    policy: &AdmissionPolicy,
+    verification: &ArtifactVerificationService,
+    image: &str,
+) -> Result<Option<String>> {
+    let target = match ImageReference::parse(image).and_then(|r| policy.target_for(&r)) {
+        Ok(target) => target,
+        Err(reason) => return Ok(Some(reason)),
+    };
+    let repository = match &target {
+        GateTarget::Digest { repository, .. } => repository.clone().unwrap_or_default(),
+        GateTarget::Path { repository, .. } | GateTarget::Coordinates { repository, .. } => {
+            repository.clone()
+        }
+    };
+
+    let caller = Some(auth.clone());
+    let gate = DeployGateRequest::default();
+    let mut artifacts = Vec::new();
+    for artifact_id in deploy_gate_service::resolve_artifacts(&state.db, &target).await? {
+        if check_artifact_visibility(&caller, artifact_id, &state.db)
+            .await
+            .is_ok()
+        {
+            artifacts.push(
+                deploy_gate_service::evaluate_artifact(&state.db, verification, artifact_id, &gate)
+                    .await?,
+            );
+        }
+    }
+    if artifacts.is_empty() {
+        return Ok(Some(format!(
+            "{} was not found in repository {}",
+            image, repository
+        )));
+    }
+
+    let decision = deploy_gate_service::decide(artifacts, None, chrono::Utc::now());
+    Ok((!decision.allowed).then(|| format!("{}: {}", image, decision.reasons.join(", "))))
+}
+
+/// Review a pod or workload admission request
+///
+/// Accepts and returns `admission.k8s.io/v1` `AdmissionReview` objects.
+pub async fn validate(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(review): Json<AdmissionReview>,
+) -> Result<Json<AdmissionReview>> {
+    let api_version = if review.api_version.is_empty() {
+        admission_service::ADMISSION_API_VERSION.to_string()
+    } else {
+        review.api_version
+    };
+    let request = review
+        .request
+        .ok_or_else(|| AppError::Validation("AdmissionReview has no request".to_string()))?;
+    let respond = |response: AdmissionResponse| -> Result<Json<AdmissionReview>> {
+        Ok(Json(AdmissionReview::respond(&api_version, response)))
+    };
+
+    let policy = AdmissionPolicy::from_config(&state.config);
+    if !matches!(request.operation.as_str(), "CREATE" | "UPDATE")
+        || policy.is_exempt(request.namespace.as_deref())
+    {
+        return respond(AdmissionResponse::allow(&request.uid));
+    }
+    let images = request
+        .object
+        .as_ref()
+        .map(|object| admission_service::extract_images(&request.kind.kind, object))
+        .unwrap_or_default();
+    if images.is_empty() {
+        return respond(AdmissionResponse::allow(&request.uid));
+    }
+
+    let signing = SigningService::new(state.db.clone(), &state.config.jwt_secret);
+    let verification = ArtifactVerificationService::new(state.db.clone(), signing);
+    let mut reasons = Vec::new();
+    for image in &images {
+        if let Some(reason) = check_image(&state, &auth, &policy, &verification, image).await? {
+            reasons.push(reason);
+        }
+    }
+    if reasons.is_empty() {
+        return respond(AdmissionResponse::allow(&request.uid));
+    }
+
+    tracing::info!(
+        namespace = request.namespace.as_deref().unwrap_or(""),
+        name = request.name.as_deref().unwrap_or(""),
+        kind = %request.kind.kind,
+        denied = reasons.len(),
+        "Admission denied"
+    );
+    let _ = AuditService::new(state.db.clone())
+        .log(
+            AuditEntry::new(AuditAction::DeployGateEvaluated, ResourceType::Artifact)
+                .user(auth.user_id)
+                .actor_name(auth.username.clone())
+                .outcome(Outcome::Denied)
+                .details(serde_json::json!({
+                    "source": "admission",
+                    "decision": "deny",
+                    "namespace": request.namespace,
+                    "name": request.name,
+                    "kind": request.kind.kind,
+                    "images": images,
+                    "reasons": reasons,
+                })),
+        )
+        .await;
+    respond(AdmissionResponse::deny(&request.uid, &reasons))
+}
@@ -371,6 +371,7 @@ pub async fn update_repo_age_gate(
                     visibility: Some(if repo.is_public { "public" } else { "private" }.to_owned()),
                     age_gate_enabled: Some(body.enabled),
                     age_gate_min_age_days: Some(body.min_age_days),
+                    previous_key: None,
                 }),
         )
         .await;
@@ -0,0 +1,366 @@
+//! Air-gap transfer bundles (admin).
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/airlock)
+//! POST   /export                    → export_bundle
+//! POST   /import                    → import_bundle
+//! GET    /imports                   → list_imports
+//! ```
+//!
+//! See [`crate::services::airlock_service`] for the bundle layout and what an
+//! import verifies.
+
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    extract::{Extension, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::handlers::proxy_helpers;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::airlock_service::{
+    self, AirlockImport, AirlockImportReport, AirlockManifest, ScanPolicy,
+};
+use crate::services::artifact_service::ArtifactService;
+use crate::services::federation_service;
+use crate::services::repository_service::RepositoryService;
+use crate::services::scan_result_service::ScanResultService;
+
+/// Airlock routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/export", post(export_bundle))
+        .route("/import", post(import_bundle))
+        .route("/imports", get(list_imports))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AirlockExportRequest {
+    pub repository_key: String,
+    /// Only export artifacts whose path starts with this prefix.
+    pub path_prefix: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct AirlockImportQuery {
+    /// Repository to import into. Defaults to the bundle's source repository
+    /// key.
+    pub target_repository: Option<String>,
+    /// Fingerprint of the exporting instance's key, obtained out of band.
+    /// Required unless the exporter is a trusted federation peer.
+    pub expected_fingerprint: Option<String>,
+    /// Reject the bundle unless every artifact carries a completed scan.
+    #[serde(default)]
+    pub require_scan: bool,
+    /// Reject the bundle if any finding is at or above this severity
+    /// (`critical`, `high`, `medium`, `low`, `info`).
+    pub block_severity: Option<String>,
+    /// Verify the bundle without importing it.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AirlockImportsQuery {
+    /// Number of imports to return (default 50, max 500).
+    pub limit: Option<i64>,
+}
+
+/// POST /api/v1/admin/airlock/export
+#[utoipa::path(
+    post,
+    path = "/export",
+    context_path = "/api/v1/admin/airlock",
+    tag = "airlock",
+    request_body = AirlockExportRequest,
+    responses(
+        (status = 200, description = "Signed bundle (tar)", content_type = "application/x-tar"),
+        (status = 404, description = "Repository not found or no artifacts match", body = crate::api::openapi::ErrorResponse),
+        (status = 422, description = "Too many artifacts selected", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn export_bundle(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<AirlockExportRequest>,
+) -> Result<Response> {
+    auth.require_admin()?;
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&payload.repository_key)
+        .await?;
+    let artifacts =
+        airlock_service::export_candidates(&state.db, repo.id, payload.path_prefix.as_deref())
+            .await?;
+
+    let identity = federation_service::local_identity(
+        &state.db,
+        &state.config.jwt_secret,
+        &state.config.peer_instance_name,
+        &state.config.peer_public_endpoint,
+    )
+    .await?;
+    let format = format!("{:?}", repo.format).to_lowercase();
+    let manifest = airlock_service::build_manifest(
+        &state.db,
+        &identity,
+        &repo.key,
+        &format,
+        &artifacts,
+        &auth.username,
+    )
+    .await?;
+    let (manifest_bytes, signature) = airlock_service::sign_manifest(&identity, &manifest)?;
+    let storage = state.storage_for_repo(&repo.storage_location())?;
+
+    tracing::info!(
+        repository = %repo.key,
+        bundle_id = %manifest.bundle_id,
+        artifacts = manifest.artifacts.len(),
+        exported_by = %auth.username,
+        "Airlock bundle exported"
+    );
+    let stream = airlock_service::bundle_stream(
+        manifest_bytes,
+        signature,
+        airlock_service::export_blobs(&artifacts),
+        storage,
+    );
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/x-tar".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"airlock-{}-{}.tar\"",
+                    repo.key, manifest.bundle_id
+                ),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// POST /api/v1/admin/airlock/import
+///
+/// Verifies the whole bundle first; nothing is stored unless every check
+/// passes, and all artifacts are registered in one transaction.
+#[utoipa::path(
+    post,
+    path = "/import",
+    context_path = "/api/v1/admin/airlock",
+    tag = "airlock",
+    params(AirlockImportQuery),
+    request_body(content = Vec<u8>, content_type = "application/x-tar"),
+    responses(
+        (status = 200, description = "Bundle verified (and imported unless dry_run)", body = AirlockImportReport),
+        (status = 403, description = "Bundle signer is not trusted", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Target repository not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "An artifact already exists in the target repository", body = crate::api::openapi::ErrorResponse),
+        (status = 413, description = "Bundle exceeds the upload size limit"),
+        (status = 422, description = "Bundle failed verification or the scan policy", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn import_bundle(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<AirlockImportQuery>,
+    body: Body,
+) -> std::result::Result<Json<AirlockImportReport>, Response> {
+    auth.require_admin().map_err(IntoResponse::into_response)?;
+    let (staged, _) =
+        proxy_helpers::stage_stream_content_addressed(&state, body.into_data_stream()).await?;
+    let report = import_staged(&state, &auth, &query, &staged)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    drop(staged);
+    Ok(Json(report))
+}
+
+async fn import_staged(
+    state: &SharedState,
+    auth: &AuthExtension,
+    query: &AirlockImportQuery,
+    staged: &proxy_helpers::StagedUpload,
+) -> Result<AirlockImportReport> {
+    let path = staged.path().to_path_buf();
+    let bundle = tokio::task::spawn_blocking(move || airlock_service::read_bundle(&path))
+        .await
+        .map_err(|e| AppError::Internal(format!("Bundle verification task failed: {}", e)))??;
+    let (manifest, key) = airlock_service::verify_bundle(&bundle)?;
+    let fingerprint = airlock_service::check_signer(
+        &state.db,
+        &manifest,
+        &key,
+        query.expected_fingerprint.as_deref(),
+    )
+    .await?;
+
+    let policy = ScanPolicy {
+        require_scan: query.require_scan,
+        block_severity: query.block_severity.clone(),
+    };
+    let violations = policy.violations(&manifest)?;
+    if !violations.is_empty() {
+        return Err(AppError::Validation(format!(
+            "Bundle violates the scan policy ({} artifact(s)): {}",
+            violations.len(),
+            violations
+                .iter()
+                .take(10)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ")
+        )));
+    }
+
+    let target_key = query
+        .target_repository
+        .as_deref()
+        .unwrap_or(&manifest.repository_key);
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(target_key)
+        .await?;
+    for entry in &manifest.artifacts {
+        super::federation::validate_promotion_target(
+            &repo,
+            entry,
+            state.config.max_upload_size_bytes,
+        )?;
+    }
+    let paths: Vec<&str> = manifest.artifacts.iter().map(|a| a.path.as_str()).collect();
+    let existing: Vec<String> = sqlx::query_scalar(
+        "SELECT path FROM artifacts \
+         WHERE repository_id = $1 AND path = ANY($2) AND is_deleted = false \
+         ORDER BY path LIMIT 10",
+    )
+    .bind(repo.id)
+    .bind(&paths)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    if !existing.is_empty() {
+        return Err(AppError::Conflict(format!(
+            "Artifacts already exist in repository {}: {}",
+            repo.key,
+            existing.join(", ")
+        )));
+    }
+
+    let mut report = AirlockImportReport {
+        bundle_id: manifest.bundle_id,
+        source_instance_name: manifest.source.name.clone(),
+        key_fingerprint: fingerprint,
+        repository_key: repo.key.clone(),
+        artifact_count: manifest.artifacts.len(),
+        total_bytes: manifest.artifacts.iter().map(|a| a.size_bytes).sum(),
+        dry_run: query.dry_run,
+        import_id: None,
+        artifact_ids: Vec::new(),
+    };
+    if query.dry_run {
+        return Ok(report);
+    }
+
+    let storage = state.storage_for_repo(&repo.storage_location())?;
+    let mut storage_keys = HashMap::with_capacity(bundle.blobs.len());
+    for (sha256, blob) in &bundle.blobs {
+        let storage_key = ArtifactService::storage_key_from_checksum(sha256);
+        crate::services::artifact_service::guard_foreign_storage_key_for_backend(
+            &state.db,
+            repo.id,
+            &repo.storage_backend,
+            &storage_key,
+        )
+        .await?;
+        if !storage.exists(&storage_key).await? {
+            let content = airlock_service::open_blob(staged.path(), blob).await?;
+            storage.put_stream(&storage_key, content).await?;
+        }
+        storage_keys.insert(sha256.clone(), storage_key);
+    }
+
+    for entry in &manifest.artifacts {
+        super::cleanup_soft_deleted_artifact(&state.db, repo.id, &entry.path).await;
+    }
+    let (import_id, artifact_ids) = airlock_service::register_bundle(
+        &state.db,
+        repo.id,
+        &manifest,
+        &report.key_fingerprint,
+        &bundle.blobs,
+        &storage_keys,
+        auth.user_id,
+    )
+    .await?;
+
+    if manifest.artifacts.iter().any(|a| !a.scans.is_empty()) {
+        if let Err(e) = ScanResultService::new(state.db.clone())
+            .recalculate_score(repo.id)
+            .await
+        {
+            tracing::warn!(
+                "Security score recalculation failed for {}: {}",
+                repo.key,
+                e
+            );
+        }
+    }
+    tracing::info!(
+        repository = %repo.key,
+        bundle_id = %manifest.bundle_id,
+        source = %manifest.source.name,
+        artifacts = artifact_ids.len(),
+        imported_by = %auth.username,
+        "Airlock bundle imported"
+    );
+    report.import_id = Some(import_id);
+    report.artifact_ids = artifact_ids;
+    Ok(report)
+}
+
+/// GET /api/v1/admin/airlock/imports
+#[utoipa::path(
+    get,
+    path = "/imports",
+    context_path = "/api/v1/admin/airlock",
+    tag = "airlock",
+    params(AirlockImportsQuery),
+    responses(
+        (status = 200, description = "Completed imports, newest first", body = Vec<AirlockImport>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_imports(
+    State(state): State<SharedState>,
+    Query(query): Query<AirlockImportsQuery>,
+) -> Result<Json<Vec<AirlockImport>>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    Ok(Json(airlock_service::list_imports(&state.db, limit).await?))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(export_bundle, import_bundle, list_imports),
+    components(schemas(
+        AirlockExportRequest,
+        AirlockManifest,
+        AirlockImportReport,
+        AirlockImport,
+    ))
+)]
+pub struct AirlockApiDoc;
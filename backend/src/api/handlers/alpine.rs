@@ -7,6 +7,11 @@
 //!   GET  /alpine/{repo_key}/{branch}/{repository}/{arch}/{filename}.apk   - Download package
 //!   PUT  /alpine/{repo_key}/{branch}/{repository}/{arch}/{filename}.apk   - Upload package
 //!   POST /alpine/{repo_key}/upload                                        - Upload package (alternative)
+//!   GET  /alpine/{repo_key}/{branch}/keys/artifact-keeper.rsa.pub          - Index signing key
+//!
+//! Hosted indexes are signed with the repository's RSA key from the signing
+//! service when one is configured; install the key into `/etc/apk/keys/`
+//! under its served name.
 
 use axum::body::Body;
 use axum::extract::{Path, State};
@@ -22,7 +27,7 @@ use flate2::write::GzEncoder;
 use flate2::Compression;
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
-use std::io::Read;
+use std::io::{Read, Write};
 use tracing::info;
 
 use crate::api::handlers::proxy_helpers::{self, RepoInfo};
@@ -582,94 +587,90 @@ fn push_optional_field(
     }
 }
 
-/// Create an APKINDEX.tar.gz from the text content with an optional RSA signature.
-///
-/// When `signature` is `Some`, the archive contains:
-///   1. `.SIGN.RSA.artifact-keeper.rsa.pub` — raw RSA signature bytes
-///   2. `APKINDEX` — the package index
-///
-/// When `signature` is `None`, only the `APKINDEX` entry is included.
-#[allow(clippy::result_large_err)]
-fn create_apkindex_tar_gz(
-    apkindex_text: &str,
-    signature: Option<&[u8]>,
-) -> Result<Vec<u8>, Response> {
-    let gz_buf = Vec::new();
-    let gz_encoder = GzEncoder::new(gz_buf, Compression::default());
-    let mut tar_builder = tar::Builder::new(gz_encoder);
-
-    let mtime = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
+/// File name of the repository signing key in apk's key directory
+/// (`/etc/apk/keys/`). apk looks the key up by the name embedded in the
+/// index's `.SIGN.*` entry, and [`public_key`] serves it under this name.
+const APK_KEY_NAME: &str = "artifact-keeper.rsa.pub";
 
-    // If a signature is available, add .SIGN file FIRST (apk verifies order)
-    if let Some(sig_bytes) = signature {
-        let mut sig_header = tar::Header::new_gnu();
-        sig_header
-            .set_path(".SIGN.RSA.artifact-keeper.rsa.pub")
-            .map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to set tar path for signature: {}", e),
-                )
-                    .into_response()
-            })?;
-        sig_header.set_size(sig_bytes.len() as u64);
-        sig_header.set_mode(0o644);
-        sig_header.set_mtime(mtime);
-        // A bare GNU header defaults to a NUL typeflag; apk-tools only accepts
-        // index entries that are marked as regular files.
-        sig_header.set_entry_type(tar::EntryType::Regular);
-        sig_header.set_cksum();
-
-        tar_builder.append(&sig_header, sig_bytes).map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to append signature to tar: {}", e),
-            )
-                .into_response()
-        })?;
-    }
+/// Map a tar/gzip build failure to a 500.
+fn apkindex_build_error(what: &str, e: impl std::fmt::Display) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("Failed to {}: {}", what, e),
+    )
+        .into_response()
+}
 
-    let content_bytes = apkindex_text.as_bytes();
+/// A regular-file tar header for an APKINDEX.tar.gz entry.
+#[allow(clippy::result_large_err)]
+fn apkindex_tar_header(path: &str, size: usize, mtime: u64) -> Result<tar::Header, Response> {
     let mut header = tar::Header::new_gnu();
-    header.set_path("APKINDEX").map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to set tar path: {}", e),
-        )
-            .into_response()
-    })?;
-    header.set_size(content_bytes.len() as u64);
+    header
+        .set_path(path)
+        .map_err(|e| apkindex_build_error("set tar path", e))?;
+    header.set_size(size as u64);
     header.set_mode(0o644);
     header.set_mtime(mtime);
+    // A bare GNU header defaults to a NUL typeflag; apk-tools only accepts
+    // index entries that are marked as regular files.
     header.set_entry_type(tar::EntryType::Regular);
     header.set_cksum();
+    Ok(header)
+}
 
-    tar_builder.append(&header, content_bytes).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to append to tar: {}", e),
-        )
-            .into_response()
-    })?;
+/// Build the gzip stream holding the `APKINDEX` tar archive. This is the
+/// stream the index signature covers, byte for byte.
+#[allow(clippy::result_large_err)]
+fn create_apkindex_stream(apkindex_text: &str, mtime: u64) -> Result<Vec<u8>, Response> {
+    let content_bytes = apkindex_text.as_bytes();
+    let header = apkindex_tar_header("APKINDEX", content_bytes.len(), mtime)?;
+    let mut tar_builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    tar_builder
+        .append(&header, content_bytes)
+        .map_err(|e| apkindex_build_error("append to tar", e))?;
+    tar_builder
+        .into_inner()
+        .map_err(|e| apkindex_build_error("finalize tar", e))?
+        .finish()
+        .map_err(|e| apkindex_build_error("finalize gzip", e))
+}
 
-    let gz_encoder = tar_builder.into_inner().map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to finalize tar: {}", e),
-        )
-            .into_response()
-    })?;
+/// Assemble APKINDEX.tar.gz from the index stream and an optional signature
+/// over it.
+///
+/// A signed index is laid out the way `abuild-sign` writes it: a gzip stream
+/// holding only the `.SIGN.RSA256.<key>` tar entry (RSA PKCS#1 v1.5 over
+/// SHA-256, which is what [`SigningService`] produces) and *no* end-of-archive
+/// blocks, followed by the unchanged index stream. apk verifies the signature
+/// against the raw bytes of that second stream, so it must not be
+/// re-compressed after signing. Without a signature the index stream is
+/// served as is.
+#[allow(clippy::result_large_err)]
+fn create_apkindex_tar_gz(
+    index_stream: Vec<u8>,
+    signature: Option<&[u8]>,
+    mtime: u64,
+) -> Result<Vec<u8>, Response> {
+    let Some(sig_bytes) = signature else {
+        return Ok(index_stream);
+    };
 
-    gz_encoder.finish().map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to finalize gzip: {}", e),
-        )
-            .into_response()
-    })
+    let sig_path = format!(".SIGN.RSA256.{}", APK_KEY_NAME);
+    let header = apkindex_tar_header(&sig_path, sig_bytes.len(), mtime)?;
+    let mut segment = Vec::with_capacity(512 + sig_bytes.len().div_ceil(512) * 512);
+    segment.extend_from_slice(header.as_bytes());
+    segment.extend_from_slice(sig_bytes);
+    segment.resize(512 + sig_bytes.len().div_ceil(512) * 512, 0);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&segment)
+        .map_err(|e| apkindex_build_error("compress signature", e))?;
+    let mut out = encoder
+        .finish()
+        .map_err(|e| apkindex_build_error("finalize gzip", e))?;
+    out.extend_from_slice(&index_stream);
+    Ok(out)
 }
 
 // ---------------------------------------------------------------------------
@@ -739,7 +740,9 @@ async fn apk_index(
     // upstream-signed indexes are returned when available.
     if repo.repo_type == RepositoryType::Virtual {
         let upstream_path = build_apk_index_upstream_path(&branch, &repository, &arch);
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &upstream_path)
+                .await?;
 
         for member in &members {
             if member.repo_type != RepositoryType::Remote {
@@ -796,15 +799,19 @@ async fn apk_index(
 
     let apkindex_text = generate_apkindex_text(&artifacts, &arch);
 
-    // Sign the APKINDEX content if signing is configured for this repository.
+    let mtime = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let index_stream = create_apkindex_stream(&apkindex_text, mtime)?;
+
+    // Sign the compressed index stream if signing is configured for this
+    // repository.
     let signing_svc = SigningService::new(state.db.clone(), &state.config.jwt_secret);
-    let signature = resolve_apkindex_signature(
-        signing_svc
-            .sign_data(repo.id, apkindex_text.as_bytes())
-            .await,
-    )?;
+    let signature =
+        resolve_apkindex_signature(signing_svc.sign_data(repo.id, &index_stream).await)?;
 
-    let tar_gz = create_apkindex_tar_gz(&apkindex_text, signature.as_deref())?;
+    let tar_gz = create_apkindex_tar_gz(index_stream, signature.as_deref(), mtime)?;
 
     Ok(Response::builder()
         .status(StatusCode::OK)
@@ -1086,7 +1093,7 @@ async fn public_key(
         .header(CONTENT_TYPE, "application/x-pem-file")
         .header(
             "Content-Disposition",
-            "attachment; filename=\"artifact-keeper.rsa.pub\"",
+            format!("attachment; filename=\"{}\"", APK_KEY_NAME),
         )
         .header(CONTENT_LENGTH, public_pem.len().to_string())
         .body(Body::from(public_pem))
@@ -2459,22 +2466,23 @@ mod tests {
         assert!(text.is_empty());
     }
 
+    fn apkindex(content: &str, signature: Option<&[u8]>) -> Vec<u8> {
+        let index_stream = create_apkindex_stream(content, 1_700_000_000).unwrap();
+        create_apkindex_tar_gz(index_stream, signature, 1_700_000_000).unwrap()
+    }
+
     #[test]
     fn test_create_apkindex_tar_gz_empty() {
-        let result = create_apkindex_tar_gz("", None);
-        assert!(result.is_ok());
-        let tar_gz = result.unwrap();
+        let tar_gz = apkindex("", None);
         assert!(!tar_gz.is_empty());
     }
 
     #[test]
     fn test_create_apkindex_tar_gz_with_content() {
         let content = "C:abc123\nP:curl\nV:8.5.0-r0\nA:x86_64\nS:1234\nI:5678\nT:URL retrieval utility\nU:https://curl.se\nL:MIT\n\n";
-        let result = create_apkindex_tar_gz(content, None);
-        assert!(result.is_ok());
+        let tar_gz = apkindex(content, None);
 
         // Verify it's a valid tar.gz by decompressing
-        let tar_gz = result.unwrap();
         let gz = flate2::read::GzDecoder::new(&tar_gz[..]);
         let mut archive = tar::Archive::new(gz);
         let entries: Vec<_> = archive.entries().unwrap().collect();
@@ -2485,12 +2493,10 @@ mod tests {
     fn test_create_apkindex_tar_gz_with_signature() {
         let content = "C:abc123\nP:curl\nV:8.5.0-r0\nA:x86_64\nS:1234\nI:5678\nT:URL retrieval utility\nU:https://curl.se\nL:MIT\n\n";
         let fake_signature = b"fake-rsa-signature-bytes";
-        let result = create_apkindex_tar_gz(content, Some(fake_signature));
-        assert!(result.is_ok());
+        let tar_gz = apkindex(content, Some(fake_signature));
 
-        // Verify both entries exist in the correct order
-        let tar_gz = result.unwrap();
-        let gz = flate2::read::GzDecoder::new(&tar_gz[..]);
+        // Both gzip members read as one tar stream, signature first.
+        let gz = flate2::read::MultiGzDecoder::new(&tar_gz[..]);
         let mut archive = tar::Archive::new(gz);
         let entry_names: Vec<String> = archive
             .entries()
@@ -2501,14 +2507,37 @@ mod tests {
             })
             .collect();
         assert_eq!(entry_names.len(), 2);
-        assert_eq!(entry_names[0], ".SIGN.RSA.artifact-keeper.rsa.pub");
+        assert_eq!(entry_names[0], ".SIGN.RSA256.artifact-keeper.rsa.pub");
         assert_eq!(entry_names[1], "APKINDEX");
     }
 
+    /// apk verifies the signature against the raw bytes of the index gzip
+    /// stream, so that stream must follow the signature segment unchanged,
+    /// and the signature segment must not end the tar archive.
+    #[test]
+    fn test_signed_apkindex_keeps_signed_stream_verbatim() {
+        let index_stream = create_apkindex_stream("C:Q1abc\nP:curl\n\n", 1).unwrap();
+        let tar_gz = create_apkindex_tar_gz(index_stream.clone(), Some(b"sig"), 1).unwrap();
+        assert!(tar_gz.ends_with(&index_stream));
+
+        let sig_member = &tar_gz[..tar_gz.len() - index_stream.len()];
+        let mut sig_tar = Vec::new();
+        flate2::read::GzDecoder::new(sig_member)
+            .read_to_end(&mut sig_tar)
+            .unwrap();
+        assert_eq!(sig_tar.len(), 1024, "header plus one padded data block");
+        assert!(sig_tar[512..].starts_with(b"sig"));
+
+        assert_eq!(
+            create_apkindex_tar_gz(index_stream.clone(), None, 1).unwrap(),
+            index_stream
+        );
+    }
+
     /// Typeflag byte of every tar header in an uncompressed tar stream.
     fn tar_typeflags(tar_gz: &[u8]) -> Vec<u8> {
         let mut tar_bytes = Vec::new();
-        flate2::read::GzDecoder::new(tar_gz)
+        flate2::read::MultiGzDecoder::new(tar_gz)
             .read_to_end(&mut tar_bytes)
             .unwrap();
         let mut flags = Vec::new();
@@ -2533,13 +2562,13 @@ mod tests {
     fn test_apkindex_tar_entry_is_a_regular_file() {
         // apk-tools rejects the index unless the entry is typeflag '0'; a bare GNU
         // header would leave a NUL here.
-        let tar_gz = create_apkindex_tar_gz("C:Q1abc\nP:curl\n\n", None).unwrap();
+        let tar_gz = apkindex("C:Q1abc\nP:curl\n\n", None);
         assert_eq!(tar_typeflags(&tar_gz), vec![b'0']);
     }
 
     #[test]
     fn test_apkindex_signature_tar_entry_is_a_regular_file() {
-        let tar_gz = create_apkindex_tar_gz("C:Q1abc\nP:curl\n\n", Some(b"sig-bytes")).unwrap();
+        let tar_gz = apkindex("C:Q1abc\nP:curl\n\n", Some(b"sig-bytes"));
         assert_eq!(tar_typeflags(&tar_gz), vec![b'0', b'0']);
     }
 
@@ -3172,6 +3201,141 @@ mod db_cov_tests {
         fx.teardown().await;
     }
 
+    // -----------------------------------------------------------------------
+    // Signed APKINDEX end to end: what apk-tools checks when it fetches the
+    // index with the served key installed in /etc/apk/keys/.
+    // -----------------------------------------------------------------------
+
+    /// GET the raw APKINDEX.tar.gz bytes.
+    async fn fetch_index_bytes(fx: &tdh::Fixture) -> bytes::Bytes {
+        let k = fx.repo_key.clone();
+        let app = fx.router_with_auth(super::router());
+        let (status, body) = tdh::send(
+            app,
+            tdh::get(format!("/{k}/v3.21/main/aarch64/APKINDEX.tar.gz")),
+        )
+        .await;
+        assert_eq!(status, axum::http::StatusCode::OK);
+        body
+    }
+
+    /// Split a signed index into the name and bytes of its `.SIGN.*` entry and
+    /// the index stream that follows the signature's gzip member.
+    fn split_signed_index(body: &[u8]) -> (String, Vec<u8>, &[u8]) {
+        let mut rest = body;
+        let mut sig_tar = Vec::new();
+        std::io::Read::read_to_end(
+            &mut flate2::bufread::GzDecoder::new(&mut rest),
+            &mut sig_tar,
+        )
+        .expect("gunzip signature member");
+        let header = tar::Header::from_byte_slice(&sig_tar[..512]);
+        let name = header.path().expect("path").to_string_lossy().into_owned();
+        let size = header.size().expect("size") as usize;
+        (name, sig_tar[512..512 + size].to_vec(), rest)
+    }
+
+    /// Without an active signing key the index is served unsigned: a single
+    /// gzip member holding only `APKINDEX`, and no key to download.
+    #[tokio::test]
+    async fn test_index_without_signing_key_is_served_unsigned() {
+        let Some(fx) = tdh::Fixture::setup("local", "alpine").await else {
+            return;
+        };
+        publish_marker_and_fetch_index(&fx).await;
+
+        let body = fetch_index_bytes(&fx).await;
+        let mut archive = tar::Archive::new(flate2::read::MultiGzDecoder::new(&body[..]));
+        let names: Vec<String> = archive
+            .entries()
+            .expect("tar entries")
+            .map(|e| {
+                e.expect("tar entry")
+                    .path()
+                    .expect("path")
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(names, vec!["APKINDEX".to_string()]);
+
+        let k = fx.repo_key.clone();
+        let app = fx.router_with_auth(super::router());
+        let (status, _) = tdh::send(
+            app,
+            tdh::get(format!("/{k}/v3.21/keys/artifact-keeper.rsa.pub")),
+        )
+        .await;
+        assert_eq!(status, axum::http::StatusCode::NOT_FOUND);
+        fx.teardown().await;
+    }
+
+    /// With an active RSA key the index leads with the
+    /// `.SIGN.RSA256.artifact-keeper.rsa.pub` entry, and its signature verifies
+    /// against the served public key over the raw bytes of the index stream,
+    /// exactly as apk-tools checks it.
+    #[tokio::test]
+    async fn test_signed_index_verifies_against_served_public_key() {
+        use crate::services::signing_service::{CreateKeyRequest, SigningService};
+        use rsa::pkcs8::DecodePublicKey;
+        use rsa::signature::Verifier;
+
+        let Some(fx) = tdh::Fixture::setup("local", "alpine").await else {
+            return;
+        };
+        let signing = SigningService::new(fx.pool.clone(), &fx.state.config.jwt_secret);
+        let key = signing
+            .create_key(CreateKeyRequest {
+                repository_id: Some(fx.repo_id),
+                name: "apk-index-key".to_string(),
+                key_type: "rsa".to_string(),
+                algorithm: "rsa2048".to_string(),
+                uid_name: None,
+                uid_email: None,
+                created_by: None,
+            })
+            .await
+            .expect("create_key");
+        signing
+            .update_signing_config(fx.repo_id, Some(key.id), true, false, false)
+            .await
+            .expect("update_signing_config");
+        publish_marker_and_fetch_index(&fx).await;
+
+        let body = fetch_index_bytes(&fx).await;
+        let (name, signature, index_stream) = split_signed_index(&body);
+        assert_eq!(name, format!(".SIGN.RSA256.{}", super::APK_KEY_NAME));
+
+        let k = fx.repo_key.clone();
+        let app = fx.router_with_auth(super::router());
+        let (status, pem) = tdh::send(
+            app,
+            tdh::get(format!("/{k}/v3.21/keys/{}", super::APK_KEY_NAME)),
+        )
+        .await;
+        assert_eq!(status, axum::http::StatusCode::OK);
+        let public_key = rsa::RsaPublicKey::from_public_key_pem(
+            std::str::from_utf8(&pem).expect("PEM is UTF-8"),
+        )
+        .expect("served key is an RSA public key");
+        let signature =
+            rsa::pkcs1v15::Signature::try_from(signature.as_slice()).expect("RSA signature");
+        rsa::pkcs1v15::VerifyingKey::<sha2::Sha256>::new(public_key)
+            .verify(index_stream, &signature)
+            .expect("signature must verify over the index stream");
+
+        // The signed stream is the index apk reads after the signature.
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(index_stream));
+        let entry = archive
+            .entries()
+            .expect("tar entries")
+            .next()
+            .expect("APKINDEX entry")
+            .expect("tar entry");
+        assert_eq!(entry.path().expect("path").to_string_lossy(), "APKINDEX");
+        fx.teardown().await;
+    }
+
     /// A package stored before the apk checksum was recorded is backfilled from
     /// the stored bytes on the next index request, instead of dropping out of it.
     #[tokio::test]
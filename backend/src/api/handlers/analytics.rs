@@ -5,13 +5,14 @@ use axum::{
     routing::get,
     Json, Router,
 };
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
 use serde::Deserialize;
 use utoipa::{IntoParams, OpenApi, ToSchema};
 use uuid::Uuid;
 
 use crate::api::SharedState;
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::services::access_log_service::AccessLogService;
 use crate::services::analytics_service::AnalyticsService;
 
 pub fn router() -> Router<SharedState> {
@@ -19,9 +20,14 @@ pub fn router() -> Router<SharedState> {
         .route("/storage/trend", get(get_storage_trend))
         .route("/storage/breakdown", get(get_storage_breakdown))
         .route("/storage/growth", get(get_growth_summary))
+        .route("/storage/forecast", get(get_storage_forecast))
         .route("/artifacts/stale", get(get_stale_artifacts))
         .route("/downloads/trend", get(get_download_trends))
         .route("/repositories/:id/trend", get(get_repository_trend))
+        .route("/containers/layer-dedup", get(get_layer_dedup_report))
+        .route("/uploads/dedup", get(get_upload_dedup_report))
+        .route("/properties/:key/breakdown", get(get_property_breakdown))
+        .route("/clients", get(get_client_tool_usage))
         .route("/snapshot", axum::routing::post(capture_snapshot))
 }
 
@@ -53,6 +59,12 @@ pub struct StaleQuery {
     pub limit: Option<i64>,
 }
 
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct LayerDedupQuery {
+    /// Entries in each layer ranking (default 20, max 500).
+    pub limit: Option<i64>,
+}
+
 /// GET /api/v1/admin/analytics/storage/trend
 #[utoipa::path(
     get,
@@ -112,10 +124,50 @@ pub async fn get_growth_summary(
 ) -> Result<Json<crate::services::analytics_service::GrowthSummary>> {
     let (from, to) = query.parse_dates();
     let service = AnalyticsService::new(state.db.clone());
-    let summary = service.get_growth_summary(from, to).await?;
+    let summary = service
+        .get_growth_summary(from, to, state.config.storage_capacity_bytes)
+        .await?;
     Ok(Json(summary))
 }
 
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ForecastQuery {
+    /// Maximum repositories to return, soonest to fill first (default 50,
+    /// max 500).
+    pub limit: Option<usize>,
+}
+
+/// GET /api/v1/admin/analytics/storage/forecast
+#[utoipa::path(
+    get,
+    path = "/storage/forecast",
+    context_path = "/api/v1/admin/analytics",
+    tag = "analytics",
+    params(ForecastQuery),
+    responses(
+        (status = 200, description = "Projected storage at 30/90/180 days and time until quota or capacity is reached", body = crate::services::analytics_service::StorageForecastReport),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_storage_forecast(
+    State(state): State<SharedState>,
+    Query(query): Query<ForecastQuery>,
+) -> Result<Json<crate::services::analytics_service::StorageForecastReport>> {
+    let today = chrono::Utc::now().date_naive();
+    let service = AnalyticsService::new(state.db.clone());
+    let global = service
+        .get_storage_forecast(today, state.config.storage_capacity_bytes)
+        .await?;
+    let mut repositories = service.get_repository_forecasts(today).await?;
+    repositories.truncate(query.limit.unwrap_or(50).clamp(1, 500));
+    Ok(Json(
+        crate::services::analytics_service::StorageForecastReport {
+            global,
+            repositories,
+        },
+    ))
+}
+
 /// GET /api/v1/admin/analytics/artifacts/stale
 #[utoipa::path(
     get,
@@ -187,6 +239,123 @@ pub async fn get_repository_trend(
     Ok(Json(trend))
 }
 
+/// GET /api/v1/admin/analytics/containers/layer-dedup
+#[utoipa::path(
+    get,
+    path = "/containers/layer-dedup",
+    context_path = "/api/v1/admin/analytics",
+    tag = "analytics",
+    params(LayerDedupQuery),
+    responses(
+        (status = 200, description = "Shared-layer savings across container repositories", body = crate::services::analytics_service::LayerDedupReport),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_layer_dedup_report(
+    State(state): State<SharedState>,
+    Query(query): Query<LayerDedupQuery>,
+) -> Result<Json<crate::services::analytics_service::LayerDedupReport>> {
+    let limit = query.limit.unwrap_or(20).clamp(1, 500);
+    let service = AnalyticsService::new(state.db.clone());
+    let report = service.get_layer_dedup_report(limit).await?;
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct UploadDedupQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Repositories in the ranking (default 20, max 500).
+    pub limit: Option<i64>,
+}
+
+/// GET /api/v1/admin/analytics/uploads/dedup
+#[utoipa::path(
+    get,
+    path = "/uploads/dedup",
+    context_path = "/api/v1/admin/analytics",
+    tag = "analytics",
+    params(UploadDedupQuery),
+    responses(
+        (status = 200, description = "Storage writes and transfers avoided by upload deduplication", body = crate::services::analytics_service::UploadDedupReport),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_upload_dedup_report(
+    State(state): State<SharedState>,
+    Query(query): Query<UploadDedupQuery>,
+) -> Result<Json<crate::services::analytics_service::UploadDedupReport>> {
+    let (from, to) = DateRangeQuery {
+        from: query.from,
+        to: query.to,
+    }
+    .parse_dates();
+    let limit = query.limit.unwrap_or(20).clamp(1, 500);
+    let service = AnalyticsService::new(state.db.clone());
+    let report = service.get_upload_dedup_report(from, to, limit).await?;
+    Ok(Json(report))
+}
+
+/// GET /api/v1/admin/analytics/properties/:key/breakdown
+#[utoipa::path(
+    get,
+    path = "/properties/{key}/breakdown",
+    context_path = "/api/v1/admin/analytics",
+    tag = "analytics",
+    params(
+        ("key" = String, Path, description = "Artifact property key, e.g. team or cost-center"),
+        DateRangeQuery,
+    ),
+    responses(
+        (status = 200, description = "Storage and downloads grouped by property value", body = Vec<crate::services::analytics_service::PropertyBreakdown>),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_property_breakdown(
+    State(state): State<SharedState>,
+    Path(key): Path<String>,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<Json<Vec<crate::services::analytics_service::PropertyBreakdown>>> {
+    if key.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Property key must not be empty".to_string(),
+        ));
+    }
+    let (from, to) = query.parse_dates();
+    let service = AnalyticsService::new(state.db.clone());
+    let breakdown = service.get_property_breakdown(&key, from, to).await?;
+    Ok(Json(breakdown))
+}
+
+/// GET /api/v1/admin/analytics/clients - requests per format and client tool
+///
+/// Aggregated from the protocol access log, so the range is bounded by
+/// `ACCESS_LOG_RETENTION_DAYS`.
+#[utoipa::path(
+    get,
+    path = "/clients",
+    context_path = "/api/v1/admin/analytics",
+    tag = "analytics",
+    params(DateRangeQuery),
+    responses(
+        (status = 200, description = "Protocol requests grouped by format and client tool", body = Vec<crate::services::access_log_service::ClientToolUsage>),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_client_tool_usage(
+    State(state): State<SharedState>,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<Json<Vec<crate::services::access_log_service::ClientToolUsage>>> {
+    let (from, to) = query.parse_dates();
+    let from = from.and_time(NaiveTime::MIN).and_utc();
+    let to = (to + chrono::Duration::days(1))
+        .and_time(NaiveTime::MIN)
+        .and_utc();
+    let service = AccessLogService::new(state.db.clone());
+    let usage = service.client_tool_usage(from, to).await?;
+    Ok(Json(usage))
+}
+
 /// POST /api/v1/admin/analytics/snapshot - manually trigger a snapshot
 #[utoipa::path(
     post,
@@ -213,20 +382,38 @@ pub async fn capture_snapshot(
         get_storage_trend,
         get_storage_breakdown,
         get_growth_summary,
+        get_storage_forecast,
         get_stale_artifacts,
         get_download_trends,
         get_repository_trend,
+        get_layer_dedup_report,
+        get_upload_dedup_report,
+        get_property_breakdown,
+        get_client_tool_usage,
         capture_snapshot,
     ),
     components(schemas(
         DateRangeQuery,
         StaleQuery,
+        LayerDedupQuery,
+        UploadDedupQuery,
+        ForecastQuery,
         crate::services::analytics_service::StorageSnapshot,
         crate::services::analytics_service::RepositorySnapshot,
         crate::services::analytics_service::RepositoryStorageBreakdown,
         crate::services::analytics_service::GrowthSummary,
+        crate::services::analytics_service::StorageForecast,
+        crate::services::analytics_service::StorageForecastReport,
+        crate::services::analytics_service::RepositoryForecast,
+        crate::services::analytics_service::CapacityLimit,
         crate::services::analytics_service::StaleArtifact,
         crate::services::analytics_service::DownloadTrend,
+        crate::services::analytics_service::PropertyBreakdown,
+        crate::services::analytics_service::LayerDedupReport,
+        crate::services::analytics_service::LayerUsage,
+        crate::services::analytics_service::UploadDedupReport,
+        crate::services::analytics_service::RepositoryUploadDedup,
+        crate::services::access_log_service::ClientToolUsage,
     ))
 )]
 pub struct AnalyticsApiDoc;
@@ -393,6 +580,23 @@ mod tests {
         assert_eq!(limit, 100);
     }
 
+    #[test]
+    fn test_upload_dedup_query_deserialize() {
+        let q: UploadDedupQuery =
+            serde_json::from_str(r#"{"from": "2026-01-01", "limit": 5}"#).unwrap();
+        assert_eq!(q.from.as_deref(), Some("2026-01-01"));
+        assert_eq!(q.limit, Some(5));
+        assert!(q.to.is_none());
+    }
+
+    #[test]
+    fn test_layer_dedup_query_deserialize() {
+        let q: LayerDedupQuery = serde_json::from_str(r#"{"limit": 5}"#).unwrap();
+        assert_eq!(q.limit, Some(5));
+        let q: LayerDedupQuery = serde_json::from_str("{}").unwrap();
+        assert!(q.limit.is_none());
+    }
+
     #[test]
     fn test_stale_query_custom_days() {
         let q = StaleQuery {
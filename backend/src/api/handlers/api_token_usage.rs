@@ -0,0 +1,85 @@
+//! Instance-wide API token usage: the stale-token report.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/api-tokens)
+//! GET    /stale                     → list_stale_api_tokens
+//! ```
+//!
+//! Per-token usage (call count, last use, source IPs) is served to the token
+//! owner at `GET /api/v1/users/{id}/tokens/{token_id}/usage`. Automatic
+//! revocation of stale tokens is configured with `API_TOKEN_STALE_DAYS`.
+
+use axum::{
+    extract::{Extension, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::token_usage_service::{StaleToken, TokenUsageService};
+
+/// Report threshold when neither the query nor `API_TOKEN_STALE_DAYS` sets one.
+const DEFAULT_STALE_DAYS: u32 = 90;
+
+/// API token admin routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/stale", get(list_stale_api_tokens))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StaleTokensQuery {
+    /// Minimum days without use (default `API_TOKEN_STALE_DAYS`, or 90 when
+    /// automatic revocation is off).
+    pub days: Option<u32>,
+    /// Maximum tokens to return (default 100, max 1000).
+    pub limit: Option<i64>,
+}
+
+/// GET /api/v1/admin/api-tokens/stale
+#[utoipa::path(
+    get,
+    path = "/stale",
+    context_path = "/api/v1/admin/api-tokens",
+    tag = "api_tokens",
+    params(StaleTokensQuery),
+    responses(
+        (status = 200, description = "Active tokens unused for at least `days`, longest idle first; `unnotifiable` marks tokens the stale sweep cannot warn about and so never revokes", body = Vec<StaleToken>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_stale_api_tokens(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<StaleTokensQuery>,
+) -> Result<Json<Vec<StaleToken>>> {
+    auth.require_admin()?;
+    let days = query
+        .days
+        .unwrap_or(match state.config.api_token_stale_days {
+            0 => DEFAULT_STALE_DAYS,
+            days => days,
+        });
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    Ok(Json(
+        TokenUsageService::new(state.db.clone())
+            .stale_tokens(
+                days,
+                limit,
+                state
+                    .smtp_service
+                    .as_ref()
+                    .is_some_and(|smtp| smtp.is_configured()),
+            )
+            .await?,
+    ))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_stale_api_tokens), components(schemas(StaleToken)))]
+pub struct ApiTokenUsageApiDoc;
@@ -16,11 +16,12 @@ use utoipa::{OpenApi, ToSchema};
 use uuid::Uuid;
 
 use crate::api::dto::Pagination;
-use crate::api::handlers::promotion::validate_promotion_repos;
+use crate::api::handlers::promotion::{sign_on_promotion, validate_promotion_repos};
 use crate::api::handlers::repositories::{require_repo_id_visible, require_visible};
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
+use crate::services::notification_email_service::{ApprovalNotice, NotificationEmailService};
 use crate::services::promotion_policy_service::PromotionPolicyService;
 use crate::services::repository_service::RepositoryService;
 
@@ -89,6 +90,9 @@ pub struct ReviewRequest {
     /// the approval-execute path has the same documented escape hatch.
     #[serde(default)]
     pub skip_policy_check: bool,
+    /// Reason for executing the promotion through an active freeze window on
+    /// the target. Requires admin or the `freeze_override` permission.
+    pub freeze_override_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -494,6 +498,30 @@ pub async fn request_approval(
         requested_by = %auth.user_id,
         "Promotion approval requested"
     );
+    state.event_bus.emit_for_repo(
+        "approval.requested",
+        id,
+        source_repo.id,
+        Some(auth.username.clone()),
+    );
+
+    // Email opted-in approvers in the background so a slow or unreachable
+    // SMTP server never delays the request itself.
+    let notifier = NotificationEmailService::new(state.db.clone(), state.smtp_service.clone());
+    let notice = ApprovalNotice {
+        approval_id: id,
+        artifact_id: req.artifact_id,
+        source_repository: req.source_repository.clone(),
+        target_repository: req.target_repository.clone(),
+        requested_by: auth.username.clone(),
+        notes: req.notes.clone(),
+    };
+    let requester = auth.user_id;
+    tokio::spawn(async move {
+        if let Err(e) = notifier.notify_approval_requested(&notice, requester).await {
+            tracing::warn!(approval_id = %notice.approval_id, "Failed to send approval request emails: {}", e);
+        }
+    });
 
     Ok((
         axum::http::StatusCode::CREATED,
@@ -761,6 +789,16 @@ pub async fn approve_promotion(
     )
     .await?;
 
+    // Release freeze windows on the target.
+    crate::services::freeze_window_service::check_promotion(
+        &state.db,
+        target_repo.id,
+        auth.user_id,
+        auth.is_admin,
+        req.freeze_override_reason.as_deref(),
+    )
+    .await?;
+
     #[derive(sqlx::FromRow)]
     #[allow(dead_code)]
     struct ArtifactRow {
@@ -946,6 +984,25 @@ pub async fn approve_promotion(
         approved_by = %auth.user_id,
         "Promotion approved and executed"
     );
+    crate::services::policy_decision_service::record_promotion(
+        &state.db,
+        approval.artifact_id,
+        promotion_id,
+        auth.user_id,
+        crate::services::policy_decision_service::SnapshotDecision {
+            allowed: true,
+            policy_check_skipped: req.skip_policy_check,
+            violations: vec![],
+        },
+    )
+    .await;
+    sign_on_promotion(&state, &target_repo, new_artifact_id, &auth).await;
+    state.event_bus.emit_for_repo(
+        "approval.approved",
+        approval_id,
+        approval.source_repo_id,
+        Some(auth.username.clone()),
+    );
 
     // Return the updated approval
     let row: ApprovalRow = sqlx::query_as(&format!("{} WHERE pa.id = $1", SELECT_APPROVAL))
@@ -987,23 +1044,23 @@ pub async fn reject_promotion(
         ));
     }
 
-    let current_status: Option<(String,)> =
-        sqlx::query_as("SELECT status FROM promotion_approvals WHERE id = $1")
+    let current_status: Option<(String, Uuid)> =
+        sqlx::query_as("SELECT status, source_repo_id FROM promotion_approvals WHERE id = $1")
             .bind(approval_id)
             .fetch_optional(&state.db)
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
 
-    match current_status {
+    let source_repo_id = match current_status {
         None => return Err(AppError::NotFound("Approval request not found".to_string())),
-        Some((status,)) if status != "pending" => {
+        Some((status, _)) if status != "pending" => {
             return Err(AppError::Conflict(format!(
                 "Approval request has already been {}",
                 status
             )))
         }
-        _ => {}
-    }
+        Some((_, source_repo_id)) => source_repo_id,
+    };
 
     let now = Utc::now();
     sqlx::query(
@@ -1026,6 +1083,12 @@ pub async fn reject_promotion(
         rejected_by = %auth.user_id,
         "Promotion request rejected"
     );
+    state.event_bus.emit_for_repo(
+        "approval.rejected",
+        approval_id,
+        source_repo_id,
+        Some(auth.username.clone()),
+    );
 
     let row: ApprovalRow = sqlx::query_as(&format!("{} WHERE pa.id = $1", SELECT_APPROVAL))
         .bind(approval_id)
@@ -2544,6 +2607,7 @@ mod tests {
                 Json(ReviewRequest {
                     notes: None,
                     skip_policy_check: false,
+                    freeze_override_reason: None,
                 }),
             )
             .await;
@@ -2599,6 +2663,7 @@ mod tests {
                 Json(ReviewRequest {
                     notes: Some("ok".to_string()),
                     skip_policy_check: false,
+                    freeze_override_reason: None,
                 }),
             )
             .await;
@@ -2641,6 +2706,7 @@ mod tests {
                 Json(ReviewRequest {
                     notes: None,
                     skip_policy_check: true,
+                    freeze_override_reason: None,
                 }),
             )
             .await;
@@ -2685,6 +2751,7 @@ mod tests {
                 Json(ReviewRequest {
                     notes: None,
                     skip_policy_check: false,
+                    freeze_override_reason: None,
                 }),
             )
             .await;
@@ -2738,6 +2805,7 @@ mod tests {
                 Json(ReviewRequest {
                     notes: Some("second pair of eyes".to_string()),
                     skip_policy_check: false,
+                    freeze_override_reason: None,
                 }),
             )
             .await;
@@ -2788,6 +2856,7 @@ mod tests {
                 Json(ReviewRequest {
                     notes: None,
                     skip_policy_check: false,
+                    freeze_override_reason: None,
                 }),
             )
             .await;
@@ -0,0 +1,299 @@
+//! Artifact usage tracing handlers.
+//!
+//! Download clients or CI plugins report the environment and host that
+//! consumed an artifact; the resulting deployment map is queryable per
+//! artifact, so a CVE response can find where a bad version went.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    routing::{delete, get},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::handlers::artifacts::check_artifact_visibility;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::artifact_consumer_service::{
+    environments, ArtifactConsumer, ArtifactConsumerService, ConsumerReport,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_consumers, report_consumer, delete_consumer),
+    components(schemas(
+        ArtifactConsumer,
+        ReportConsumerRequest,
+        ArtifactConsumerListResponse,
+    )),
+    tags((name = "artifact-consumers", description = "Artifact usage tracing"))
+)]
+pub struct ArtifactConsumersApiDoc;
+
+/// Create artifact consumer routes (nested under /api/v1/artifacts/:id/consumers).
+pub fn artifact_consumers_router() -> Router<SharedState> {
+    Router::new()
+        .route("/:id/consumers", get(list_consumers).post(report_consumer))
+        .route("/:id/consumers/:consumer_id", delete(delete_consumer))
+}
+
+// ---------------------------------------------------------------------------
+// Request / Response types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReportConsumerRequest {
+    /// Environment the artifact was deployed to (e.g. `production`).
+    pub environment: String,
+    /// Host, node or cluster that pulled the artifact.
+    pub host: String,
+    /// Deployment or service name.
+    pub deployment: Option<String>,
+    /// Reporting client (e.g. `github-actions`, `argocd`).
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListConsumersQuery {
+    /// Only list consumers in this environment.
+    pub environment: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArtifactConsumerListResponse {
+    pub items: Vec<ArtifactConsumer>,
+    pub total: usize,
+    /// Distinct environments the artifact was reported in.
+    pub environments: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Removing a consumer record needs the `write` scope. Reporting only needs
+/// an authenticated caller that can see the artifact, so read-only CI tokens
+/// can report what they pulled.
+fn authorize_consumer_delete(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    Ok(auth)
+}
+
+/// Verify an artifact exists (not deleted).
+async fn verify_artifact_exists(db: &sqlx::PgPool, artifact_id: Uuid) -> Result<()> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM artifacts WHERE id = $1 AND is_deleted = false)",
+    )
+    .bind(artifact_id)
+    .fetch_one(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    if !exists {
+        return Err(AppError::NotFound(format!(
+            "Artifact {artifact_id} not found"
+        )));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// List where an artifact was consumed
+#[utoipa::path(
+    get,
+    operation_id = "list_artifact_consumers",
+    path = "/{id}/consumers",
+    context_path = "/api/v1/artifacts",
+    tag = "artifact-consumers",
+    params(
+        ("id" = Uuid, Path, description = "Artifact ID"),
+        ListConsumersQuery
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Deployment map", body = ArtifactConsumerListResponse),
+        (status = 404, description = "Artifact not found")
+    )
+)]
+async fn list_consumers(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ListConsumersQuery>,
+) -> Result<Json<ArtifactConsumerListResponse>> {
+    let auth = require_auth(auth)?;
+
+    check_artifact_visibility(&Some(auth), id, &state.db).await?;
+    verify_artifact_exists(&state.db, id).await?;
+
+    let environment = query
+        .environment
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty());
+    let items = ArtifactConsumerService::new(state.db.clone())
+        .list(id, environment)
+        .await?;
+    let environments = environments(&items);
+    let total = items.len();
+
+    Ok(Json(ArtifactConsumerListResponse {
+        items,
+        total,
+        environments,
+    }))
+}
+
+/// Report that an artifact was consumed
+///
+/// Re-reporting the same environment, host and deployment refreshes
+/// `last_seen_at` and increments `report_count`.
+#[utoipa::path(
+    post,
+    operation_id = "report_artifact_consumer",
+    path = "/{id}/consumers",
+    context_path = "/api/v1/artifacts",
+    tag = "artifact-consumers",
+    params(
+        ("id" = Uuid, Path, description = "Artifact ID")
+    ),
+    request_body = ReportConsumerRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Consumption recorded", body = ArtifactConsumer),
+        (status = 400, description = "Invalid report"),
+        (status = 404, description = "Artifact not found")
+    )
+)]
+async fn report_consumer(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReportConsumerRequest>,
+) -> Result<Json<ArtifactConsumer>> {
+    let auth = require_auth(auth)?;
+    let user_id = auth.user_id;
+
+    check_artifact_visibility(&Some(auth), id, &state.db).await?;
+    verify_artifact_exists(&state.db, id).await?;
+
+    let report = ConsumerReport::new(
+        &payload.environment,
+        &payload.host,
+        payload.deployment.as_deref(),
+        payload.source.as_deref(),
+    )?;
+    let consumer = ArtifactConsumerService::new(state.db.clone())
+        .record(id, &report, Some(user_id))
+        .await?;
+
+    Ok(Json(consumer))
+}
+
+/// Remove a consumer record
+#[utoipa::path(
+    delete,
+    operation_id = "delete_artifact_consumer",
+    path = "/{id}/consumers/{consumer_id}",
+    context_path = "/api/v1/artifacts",
+    tag = "artifact-consumers",
+    params(
+        ("id" = Uuid, Path, description = "Artifact ID"),
+        ("consumer_id" = Uuid, Path, description = "Consumer record ID")
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Consumer removed"),
+        (status = 404, description = "Artifact or consumer not found")
+    )
+)]
+async fn delete_consumer(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path((id, consumer_id)): Path<(Uuid, Uuid)>,
+) -> Result<axum::http::StatusCode> {
+    let auth = authorize_consumer_delete(auth)?;
+
+    check_artifact_visibility(&Some(auth), id, &state.db).await?;
+
+    let removed = ArtifactConsumerService::new(state.db.clone())
+        .remove(id, consumer_id)
+        .await?;
+    if !removed {
+        return Err(AppError::NotFound(format!(
+            "Consumer {consumer_id} not found"
+        )));
+    }
+
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt_auth() -> AuthExtension {
+        AuthExtension {
+            user_id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            is_admin: false,
+            is_api_token: false,
+            is_service_account: false,
+            scopes: None,
+            allowed_repo_ids: crate::models::access_scope::AccessScope::Admin,
+            iat_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_authorize_consumer_delete_requires_auth() {
+        assert!(authorize_consumer_delete(None).is_err());
+        assert!(authorize_consumer_delete(Some(jwt_auth())).is_ok());
+    }
+
+    #[test]
+    fn test_authorize_consumer_delete_read_only_token_rejected() {
+        let mut auth = jwt_auth();
+        auth.is_api_token = true;
+        auth.scopes = Some(vec!["read".to_string()]);
+        assert!(authorize_consumer_delete(Some(auth)).is_err());
+    }
+
+    #[test]
+    fn test_report_request_optional_fields() {
+        let req: ReportConsumerRequest =
+            serde_json::from_str(r#"{"environment": "prod", "host": "web-01"}"#).unwrap();
+        assert_eq!(req.environment, "prod");
+        assert!(req.deployment.is_none());
+        assert!(req.source.is_none());
+    }
+
+    /// Every handler must apply the artifact visibility / token-scope check.
+    #[test]
+    fn test_consumer_handlers_check_visibility() {
+        let source = include_str!("artifact_consumers.rs");
+        for handler in ["list_consumers", "report_consumer", "delete_consumer"] {
+            let marker = format!("async fn {}(", handler);
+            let start = source.find(&marker).unwrap();
+            let rest = &source[start + marker.len()..];
+            let end = rest.find("\n}\n").unwrap();
+            assert!(
+                rest[..end].contains("check_artifact_visibility("),
+                "handler `{}` must call check_artifact_visibility",
+                handler
+            );
+        }
+    }
+}
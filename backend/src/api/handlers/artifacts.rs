@@ -4,18 +4,29 @@
 //! the repository-nested artifact routes in repositories.rs.
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     routing::get,
     Extension, Json, Router,
 };
-use serde::Serialize;
-use utoipa::{OpenApi, ToSchema};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
 use uuid::Uuid;
 
 use crate::api::handlers::repositories::ArtifactResponse;
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
+use crate::services::artifact_compare_service::{
+    self as compare_service, ArchiveDiff, ArtifactComparison, CompareSide, ComparedArtifact,
+    DependencyChange, DependencyDiff, FieldChange, MemberChange,
+};
+use crate::services::artifact_verification_service::{
+    ArtifactVerificationService, BuildReference, EnvelopeSignature, PromotionReference,
+    SbomReference, VerificationBundle, VerificationEnvelope, VerificationFindingCounts,
+    VerificationPolicy, VerificationProvenance, VerificationScan, VerificationScanSummary,
+    VerificationSignatureStatus, VerifiedArtifact,
+};
+use crate::services::signing_service::SigningService;
 
 /// Check that the caller is allowed to see this artifact.
 ///
@@ -79,10 +90,15 @@ pub(crate) async fn check_artifact_visibility(
 /// Create artifact routes
 pub fn router() -> Router<SharedState> {
     Router::new()
+        .route("/compare", get(compare_artifacts))
         .route("/:id", get(get_artifact))
         .route("/:id/metadata", get(get_artifact_metadata))
         .route("/:id/stats", get(get_artifact_stats))
+        .route("/:id/checksums", get(get_artifact_checksums))
+        .route("/:id/verification", get(get_artifact_verification))
         .merge(super::artifact_labels::artifact_labels_router())
+        .merge(super::artifact_consumers::artifact_consumers_router())
+        .merge(super::direct_uploads::direct_uploads_router())
 }
 
 /// Row shape for the by-id artifact lookup.
@@ -111,6 +127,14 @@ pub struct ArtifactMetadataResponse {
     pub properties: serde_json::Value,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CompareQuery {
+    /// Artifact ID of the base version
+    pub a: Uuid,
+    /// Artifact ID of the version compared against the base
+    pub b: Uuid,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ArtifactStatsResponse {
     pub artifact_id: Uuid,
@@ -119,6 +143,18 @@ pub struct ArtifactStatsResponse {
     pub last_downloaded: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Every digest recorded for an artifact. SHA-512 and BLAKE3 are absent
+/// until computed for artifacts stored before they were recorded.
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ArtifactChecksumsResponse {
+    pub artifact_id: Uuid,
+    pub sha256: String,
+    pub sha1: Option<String>,
+    pub md5: Option<String>,
+    pub sha512: Option<String>,
+    pub blake3: Option<String>,
+}
+
 /// Get artifact by ID
 #[utoipa::path(
     get,
@@ -248,6 +284,48 @@ pub async fn get_artifact_metadata(
     }))
 }
 
+/// Get all checksums of an artifact
+#[utoipa::path(
+    get,
+    path = "/{id}/checksums",
+    context_path = "/api/v1/artifacts",
+    tag = "artifacts",
+    params(
+        ("id" = Uuid, Path, description = "Artifact ID")
+    ),
+    responses(
+        (status = 200, description = "Artifact checksums", body = ArtifactChecksumsResponse),
+        (status = 404, description = "Artifact not found", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn get_artifact_checksums(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ArtifactChecksumsResponse>> {
+    check_artifact_visibility(&auth, id, &state.db).await?;
+
+    let checksums = sqlx::query_as::<_, ArtifactChecksumsResponse>(
+        r#"
+        SELECT id AS artifact_id,
+               TRIM(checksum_sha256) AS sha256,
+               TRIM(checksum_sha1) AS sha1,
+               TRIM(checksum_md5) AS md5,
+               TRIM(checksum_sha512) AS sha512,
+               TRIM(checksum_blake3) AS blake3
+        FROM artifacts
+        WHERE id = $1 AND is_deleted = false
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Artifact not found".to_string()))?;
+
+    Ok(Json(checksums))
+}
+
 /// Get artifact download statistics
 #[utoipa::path(
     get,
@@ -304,16 +382,119 @@ pub async fn get_artifact_stats(
     }))
 }
 
+/// Compare two versions of the same package
+///
+/// Reports archive members added, removed or changed between `a` and `b`,
+/// the size delta, metadata and manifest differences, and dependency changes.
+/// Both artifacts must be the same package (same format and name) and
+/// visible to the caller.
+#[utoipa::path(
+    get,
+    path = "/compare",
+    context_path = "/api/v1/artifacts",
+    tag = "artifacts",
+    params(CompareQuery),
+    responses(
+        (status = 200, description = "Differences between the two artifacts", body = ArtifactComparison),
+        (status = 400, description = "Artifacts are not versions of the same package", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Artifact not found", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn compare_artifacts(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Query(query): Query<CompareQuery>,
+) -> Result<Json<ArtifactComparison>> {
+    let a = CompareSide::load(&state.db, query.a).await?;
+    let b = CompareSide::load(&state.db, query.b).await?;
+    for side in [&a, &b] {
+        check_artifact_visibility(&auth, side.id, &state.db).await?;
+    }
+    compare_service::check_comparable(&a, &b)?;
+    // Listing members reads content, so quarantine holds apply as they do
+    // to previews and downloads.
+    for side in [&a, &b] {
+        crate::services::quarantine_service::check_artifact_download(&state.db, side.id).await?;
+    }
+
+    let a_storage = state.storage_for_repo(&a.storage_location())?;
+    let b_storage = state.storage_for_repo(&b.storage_location())?;
+    let comparison =
+        compare_service::compare(&a, a_storage.as_ref(), &b, b_storage.as_ref()).await?;
+    Ok(Json(comparison))
+}
+
+/// Get a signed verification bundle for an artifact
+///
+/// Returns the artifact's checksum, latest scan summary, policy decision,
+/// signature status and provenance references as a DSSE envelope signed with
+/// the repository's active signing key. Deployment tooling can verify the
+/// envelope against the key published at `/api/v1/signing/keys/{key_id}/public`
+/// before rollout. The `signatures` list is empty when the repository has no
+/// active signing key.
+#[utoipa::path(
+    get,
+    path = "/{id}/verification",
+    context_path = "/api/v1/artifacts",
+    tag = "artifacts",
+    params(
+        ("id" = Uuid, Path, description = "Artifact ID")
+    ),
+    responses(
+        (status = 200, description = "Signed verification bundle", body = VerificationEnvelope),
+        (status = 404, description = "Artifact not found", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn get_artifact_verification(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VerificationEnvelope>> {
+    check_artifact_visibility(&auth, id, &state.db).await?;
+
+    let signing = SigningService::new(state.db.clone(), &state.config.jwt_secret);
+    let envelope = ArtifactVerificationService::new(state.db.clone(), signing)
+        .signed_bundle(id)
+        .await?;
+    Ok(Json(envelope))
+}
+
 #[derive(OpenApi)]
 #[openapi(
-    paths(get_artifact, get_artifact_metadata, get_artifact_stats,),
+    paths(get_artifact, get_artifact_metadata, get_artifact_stats,
+        get_artifact_checksums, get_artifact_verification, compare_artifacts,
+    ),
     // `ArtifactResponse` is intentionally NOT registered here: the canonical
     // schema lives in repositories.rs (RepositoriesApiDoc). Registering a
     // second struct under the same name used to shadow it (utoipa merge is
     // first-wins on schema names) and published a stale shape for
     // GET /api/v1/artifacts/{id}. See the schema-name-uniqueness regression
     // test in openapi.rs.
-    components(schemas(ArtifactMetadataResponse, ArtifactStatsResponse,))
+    components(schemas(
+        ArtifactMetadataResponse,
+        ArtifactStatsResponse,
+        ArtifactChecksumsResponse,
+        VerificationEnvelope,
+        EnvelopeSignature,
+        VerificationBundle,
+        VerifiedArtifact,
+        VerificationScan,
+        VerificationFindingCounts,
+        VerificationScanSummary,
+        VerificationPolicy,
+        VerificationSignatureStatus,
+        VerificationProvenance,
+        SbomReference,
+        BuildReference,
+        PromotionReference,
+        ArtifactComparison,
+        ComparedArtifact,
+        ArchiveDiff,
+        MemberChange,
+        FieldChange,
+        DependencyDiff,
+        DependencyChange,
+    ))
 )]
 pub struct ArtifactsApiDoc;
 
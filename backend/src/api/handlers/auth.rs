@@ -28,6 +28,8 @@ use crate::services::audit_service::{
 };
 use crate::services::auth_config_service::AuthConfigService;
 use crate::services::auth_service::AuthService;
+use crate::services::bootstrap_service::{self, BootstrapRequest, BootstrapResponse};
+use crate::services::password_policy::PasswordPolicyConfig;
 
 /// Fire-and-forget auth audit log. Failures are silently ignored so audit
 /// issues never break the auth flow.
@@ -100,7 +102,9 @@ pub fn public_router() -> Router<SharedState> {
 
 /// Setup status endpoint (public, no auth required)
 pub fn setup_router() -> Router<SharedState> {
-    Router::new().route("/status", get(setup_status))
+    Router::new()
+        .route("/status", get(setup_status))
+        .route("/bootstrap", post(bootstrap))
 }
 
 /// Response body for the setup status endpoint.
@@ -114,6 +118,11 @@ pub struct SetupStatusResponse {
     /// falls back to its built-in Docker Compose instruction (#2802).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub setup_password_hint: Option<String>,
+    /// Whether setup must be completed through the first-run wizard
+    /// (`POST /api/v1/setup/bootstrap`) rather than the password change.
+    /// Omitted when false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bootstrap_required: bool,
 }
 
 /// Returns whether initial setup (password change) is required.
@@ -132,9 +141,17 @@ pub async fn setup_status(State(state): State<SharedState>) -> Json<serde_json::
     // endpoint is what the web UI uses to decide whether to show the
     // first-time-setup flow (#2492). `setup_still_required` latches the
     // process-local flag to false once the DB confirms completion.
-    let mut body = serde_json::json!({
-        "setup_required": state.setup_still_required().await
-    });
+    let setup_required = state.setup_still_required().await;
+    let mut body = serde_json::json!({ "setup_required": setup_required });
+    // The web UI shows the wizard instead of the change-password screen while
+    // a bootstrap token is outstanding.
+    if setup_required
+        && bootstrap_service::is_pending(&state.db)
+            .await
+            .unwrap_or(false)
+    {
+        body["bootstrap_required"] = serde_json::Value::Bool(true);
+    }
     // Surface the operator-configured retrieval hint only when set, so the
     // absent case leaves the web UI on its built-in default text (#2802).
     if let Some(hint) = &state.config.setup_password_hint {
@@ -143,6 +160,60 @@ pub async fn setup_status(State(state): State<SharedState>) -> Json<serde_json::
     Json(body)
 }
 
+/// Complete the first-run setup wizard: create the first admin and record
+/// the initial instance settings. Requires the one-time bootstrap token
+/// printed in the server log.
+#[utoipa::path(
+    post,
+    path = "/bootstrap",
+    context_path = "/api/v1/setup",
+    tag = "auth",
+    request_body = BootstrapRequest,
+    responses(
+        (status = 200, description = "Setup completed", body = BootstrapResponse),
+        (status = 400, description = "Invalid admin account or settings"),
+        (status = 401, description = "Invalid or expired bootstrap token"),
+        (status = 409, description = "Setup has already been completed"),
+    )
+)]
+pub async fn bootstrap(
+    State(state): State<SharedState>,
+    Json(payload): Json<BootstrapRequest>,
+) -> Result<Json<BootstrapResponse>> {
+    let policy = PasswordPolicyConfig::from_config(&state.config);
+    let completed = bootstrap_service::complete(&state.db, &payload, &policy, |backend| {
+        state.storage_registry.is_available(backend)
+    })
+    .await?;
+    if let Some(base_url) = bootstrap_service::stored_base_url(&state.db).await? {
+        crate::api::extractors::set_stored_external_url(&base_url);
+    }
+
+    // Other replicas unlock through `setup_still_required` once they see the
+    // consumed token.
+    state
+        .setup_required
+        .store(false, std::sync::atomic::Ordering::Relaxed);
+    tracing::info!(
+        user_id = %completed.user_id,
+        settings = ?completed.settings_applied,
+        "First-run setup completed; API unlocked"
+    );
+    audit_auth(
+        &state,
+        AuditAction::UserCreated,
+        Some(completed.user_id),
+        Some(&completed.username),
+        serde_json::json!({
+            "source": "setup_wizard",
+            "settings_applied": completed.settings_applied,
+        }),
+    )
+    .await;
+
+    Ok(Json(completed))
+}
+
 /// Create protected auth routes (auth required)
 pub fn protected_router() -> Router<SharedState> {
     Router::new()
@@ -923,6 +994,7 @@ pub async fn create_download_ticket(
 #[openapi(
     paths(
         setup_status,
+        bootstrap,
         login,
         logout,
         refresh_token,
@@ -933,6 +1005,9 @@ pub async fn create_download_ticket(
     ),
     components(schemas(
         SetupStatusResponse,
+        BootstrapRequest,
+        crate::services::bootstrap_service::BootstrapAdmin,
+        BootstrapResponse,
         LoginRequest,
         LoginResponse,
         RefreshTokenRequest,
@@ -1320,6 +1395,7 @@ mod tests {
         let resp = SetupStatusResponse {
             setup_required: true,
             setup_password_hint: None,
+            bootstrap_required: false,
         };
         let json = serde_json::to_value(&resp).unwrap();
         assert_eq!(json["setup_required"], true);
@@ -1333,6 +1409,7 @@ mod tests {
         let resp = SetupStatusResponse {
             setup_required: false,
             setup_password_hint: None,
+            bootstrap_required: false,
         };
         let json = serde_json::to_value(&resp).unwrap();
         assert_eq!(json["setup_required"], false);
@@ -1347,6 +1424,7 @@ mod tests {
             setup_password_hint: Some(
                 "kubectl exec deploy/artifact-keeper -- cat /data/storage/admin.password".into(),
             ),
+            bootstrap_required: false,
         };
         let json = serde_json::to_value(&resp).unwrap();
         assert_eq!(json["setup_required"], true);
@@ -1354,6 +1432,32 @@ mod tests {
             json["setup_password_hint"],
             "kubectl exec deploy/artifact-keeper -- cat /data/storage/admin.password"
         );
+        assert!(json.get("bootstrap_required").is_none());
+    }
+
+    #[test]
+    fn test_setup_status_response_serialize_bootstrap_required() {
+        let resp = SetupStatusResponse {
+            setup_required: true,
+            setup_password_hint: None,
+            bootstrap_required: true,
+        };
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["bootstrap_required"], true);
+    }
+
+    #[test]
+    fn test_bootstrap_request_deserialize() {
+        let req: BootstrapRequest = serde_json::from_value(serde_json::json!({
+            "token": "akbt_abc",
+            "admin": {"username": "ops", "email": "ops@example.com", "password": "s3cret-pass"},
+            "base_url": "https://ak.example.com"
+        }))
+        .unwrap();
+        assert_eq!(req.admin.username, "ops");
+        assert!(req.admin.display_name.is_none());
+        assert!(req.storage_backend.is_none());
+        assert!(req.license_key.is_none());
     }
 
     // -----------------------------------------------------------------------
@@ -0,0 +1,367 @@
+//! External authorization hook: status, inline Rego policies and a dry-run
+//! evaluator for writing them.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/authz)
+//! GET    /status                    → get_authz_status
+//! GET    /policies                  → list_authz_policies
+//! POST   /policies                  → create_authz_policy
+//! GET    /policies/:id              → get_authz_policy
+//! PUT    /policies/:id              → update_authz_policy
+//! DELETE /policies/:id              → delete_authz_policy
+//! POST   /evaluate                  → evaluate_authz
+//! ```
+//!
+//! Policy writes are pushed to OPA and need `AUTHZ_HOOK_KIND=opa`; the
+//! evaluator sends one input straight to the hook, bypassing the decision
+//! cache and `AUTHZ_HOOK_FAIL_OPEN`.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::external_authz_service::{
+    self, AuthzDecision, AuthzHookKind, AuthzInput, AuthzPolicy, AuthzPolicyInput, AuthzRepository,
+    AuthzUser, ExternalAuthzService,
+};
+
+/// Authorization hook routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/status", get(get_authz_status))
+        .route(
+            "/policies",
+            get(list_authz_policies).post(create_authz_policy),
+        )
+        .route(
+            "/policies/:id",
+            get(get_authz_policy)
+                .put(update_authz_policy)
+                .delete(delete_authz_policy),
+        )
+        .route("/evaluate", post(evaluate_authz))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthzStatus {
+    pub enabled: bool,
+    pub kind: Option<AuthzHookKind>,
+    pub url: Option<String>,
+    pub timeout_ms: Option<u64>,
+    pub fail_open: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EvaluateAuthzRequest {
+    /// User to evaluate as; omit for an anonymous request.
+    pub user_id: Option<Uuid>,
+    /// Repository key.
+    pub repository: String,
+    /// `read`, `write` or `delete`.
+    pub action: String,
+    /// Repository-relative path, as native-protocol requests send it.
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EvaluateAuthzResponse {
+    pub input: AuthzInput,
+    pub decision: AuthzDecision,
+}
+
+fn configured_hook(state: &SharedState) -> Result<Arc<ExternalAuthzService>> {
+    state
+        .permission_service
+        .external_authz()
+        .cloned()
+        .ok_or_else(|| {
+            AppError::Validation(
+                "External authorization hook is not configured; set AUTHZ_HOOK_URL".to_string(),
+            )
+        })
+}
+
+async fn audit_policy_change(
+    state: &SharedState,
+    auth: &AuthExtension,
+    policy_id: Uuid,
+    name: &str,
+    operation: &str,
+) {
+    let _ = AuditService::new(state.db.clone())
+        .log(
+            AuditEntry::new(AuditAction::AuthzPolicyChanged, ResourceType::Setting)
+                .user(auth.user_id)
+                .resource(policy_id)
+                .actor_name(auth.username.clone())
+                .details(serde_json::json!({
+                    "policy": name,
+                    "operation": operation,
+                })),
+        )
+        .await;
+}
+
+/// GET /api/v1/admin/authz/status
+#[utoipa::path(
+    get,
+    path = "/status",
+    context_path = "/api/v1/admin/authz",
+    tag = "authz",
+    responses(
+        (status = 200, description = "Authorization hook configuration", body = AuthzStatus),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_authz_status(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<AuthzStatus>> {
+    auth.require_admin()?;
+    let config = state
+        .permission_service
+        .external_authz()
+        .map(|hook| hook.config().clone());
+    Ok(Json(AuthzStatus {
+        enabled: config.is_some(),
+        kind: config.as_ref().map(|c| c.kind),
+        url: config.as_ref().map(|c| c.url.clone()),
+        timeout_ms: config.as_ref().map(|c| c.timeout.as_millis() as u64),
+        fail_open: config.as_ref().map(|c| c.fail_open),
+    }))
+}
+
+/// GET /api/v1/admin/authz/policies
+#[utoipa::path(
+    get,
+    path = "/policies",
+    context_path = "/api/v1/admin/authz",
+    tag = "authz",
+    responses(
+        (status = 200, description = "Inline Rego policies, by name", body = Vec<AuthzPolicy>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_authz_policies(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<Vec<AuthzPolicy>>> {
+    auth.require_admin()?;
+    Ok(Json(
+        external_authz_service::list_policies(&state.db).await?,
+    ))
+}
+
+/// POST /api/v1/admin/authz/policies
+#[utoipa::path(
+    post,
+    path = "/policies",
+    context_path = "/api/v1/admin/authz",
+    tag = "authz",
+    request_body = AuthzPolicyInput,
+    responses(
+        (status = 201, description = "Policy stored and loaded into OPA", body = AuthzPolicy),
+        (status = 400, description = "Invalid policy, rejected by OPA, or no OPA hook configured", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "A policy with this name exists", body = crate::api::openapi::ErrorResponse),
+        (status = 503, description = "OPA unreachable", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn create_authz_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(input): Json<AuthzPolicyInput>,
+) -> Result<(StatusCode, Json<AuthzPolicy>)> {
+    auth.require_admin()?;
+    let hook = configured_hook(&state)?;
+    let policy = hook
+        .create_policy(&state.db, &input, Some(auth.user_id))
+        .await?;
+    audit_policy_change(&state, &auth, policy.id, &policy.name, "create").await;
+    Ok((StatusCode::CREATED, Json(policy)))
+}
+
+/// GET /api/v1/admin/authz/policies/{id}
+#[utoipa::path(
+    get,
+    path = "/policies/{id}",
+    context_path = "/api/v1/admin/authz",
+    tag = "authz",
+    params(("id" = Uuid, Path, description = "Policy ID")),
+    responses(
+        (status = 200, description = "Policy", body = AuthzPolicy),
+        (status = 404, description = "Policy not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_authz_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AuthzPolicy>> {
+    auth.require_admin()?;
+    Ok(Json(
+        external_authz_service::get_policy(&state.db, id).await?,
+    ))
+}
+
+/// PUT /api/v1/admin/authz/policies/{id}
+#[utoipa::path(
+    put,
+    path = "/policies/{id}",
+    context_path = "/api/v1/admin/authz",
+    tag = "authz",
+    params(("id" = Uuid, Path, description = "Policy ID")),
+    request_body = AuthzPolicyInput,
+    responses(
+        (status = 200, description = "Policy replaced and reloaded into OPA", body = AuthzPolicy),
+        (status = 400, description = "Invalid policy, rejected by OPA, or no OPA hook configured", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Policy not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "A policy with this name exists", body = crate::api::openapi::ErrorResponse),
+        (status = 503, description = "OPA unreachable", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn update_authz_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<AuthzPolicyInput>,
+) -> Result<Json<AuthzPolicy>> {
+    auth.require_admin()?;
+    let hook = configured_hook(&state)?;
+    let policy = hook.update_policy(&state.db, id, &input).await?;
+    audit_policy_change(&state, &auth, policy.id, &policy.name, "update").await;
+    Ok(Json(policy))
+}
+
+/// DELETE /api/v1/admin/authz/policies/{id}
+#[utoipa::path(
+    delete,
+    path = "/policies/{id}",
+    context_path = "/api/v1/admin/authz",
+    tag = "authz",
+    params(("id" = Uuid, Path, description = "Policy ID")),
+    responses(
+        (status = 204, description = "Policy deleted and unloaded from OPA"),
+        (status = 404, description = "Policy not found", body = crate::api::openapi::ErrorResponse),
+        (status = 503, description = "OPA unreachable", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_authz_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    auth.require_admin()?;
+    let hook = configured_hook(&state)?;
+    let policy = external_authz_service::get_policy(&state.db, id).await?;
+    hook.remove_policy(&state.db, id).await?;
+    audit_policy_change(&state, &auth, policy.id, &policy.name, "delete").await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/admin/authz/evaluate
+#[utoipa::path(
+    post,
+    path = "/evaluate",
+    context_path = "/api/v1/admin/authz",
+    tag = "authz",
+    request_body = EvaluateAuthzRequest,
+    responses(
+        (status = 200, description = "The input sent to the hook and its decision", body = EvaluateAuthzResponse),
+        (status = 400, description = "Invalid request or no hook configured", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "User or repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn evaluate_authz(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(req): Json<EvaluateAuthzRequest>,
+) -> Result<Json<EvaluateAuthzResponse>> {
+    auth.require_admin()?;
+    let hook = configured_hook(&state)?;
+    if !matches!(req.action.as_str(), "read" | "write" | "delete") {
+        return Err(AppError::Validation(
+            "action must be one of read, write, delete".to_string(),
+        ));
+    }
+
+    let user = match req.user_id {
+        None => None,
+        Some(user_id) => Some(
+            sqlx::query_as::<_, (Uuid, String, bool, bool)>(
+                "SELECT id, username, is_admin, is_service_account FROM users WHERE id = $1",
+            )
+            .bind(user_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .map(|(id, username, is_admin, is_service_account)| AuthzUser {
+                id,
+                username,
+                is_admin,
+                is_service_account,
+            })
+            .ok_or_else(|| AppError::NotFound(format!("User {user_id} not found")))?,
+        ),
+    };
+    let repository_id: Uuid = sqlx::query_scalar("SELECT id FROM repositories WHERE key = $1")
+        .bind(&req.repository)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Repository '{}' not found", req.repository)))?;
+
+    let input = ExternalAuthzService::build_input(
+        &state.db,
+        user,
+        &req.action,
+        repository_id,
+        req.path.as_deref(),
+    )
+    .await?;
+    let decision = hook.evaluate(&input).await?;
+    Ok(Json(EvaluateAuthzResponse { input, decision }))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_authz_status,
+        list_authz_policies,
+        create_authz_policy,
+        get_authz_policy,
+        update_authz_policy,
+        delete_authz_policy,
+        evaluate_authz,
+    ),
+    components(schemas(
+        AuthzStatus,
+        AuthzHookKind,
+        AuthzPolicy,
+        AuthzPolicyInput,
+        EvaluateAuthzRequest,
+        EvaluateAuthzResponse,
+        AuthzInput,
+        AuthzUser,
+        AuthzRepository,
+        AuthzDecision,
+    ))
+)]
+pub struct AuthzHookApiDoc;
@@ -0,0 +1,160 @@
+//! Embeddable package status badges.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Badges (/api/v1/badges)
+//! GET /:repo_key/<package>/version.svg  → package_badge
+//! GET /:repo_key/<package>/scan.svg     → package_badge
+//! GET /:repo_key/<package>/license.svg  → package_badge
+//! ```
+//!
+//! Package names may contain slashes (npm scopes, Go modules), so everything
+//! between the repository key and the badge file name is the package name.
+//! Badges are served without authentication for public repositories; private
+//! repositories render the same "not found" badge as an unknown package
+//! unless the caller is authenticated and allowed to see them.
+
+use axum::{
+    body::Body,
+    extract::{Extension, Path, Query, State},
+    http::{header, StatusCode},
+    response::Response,
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::api::handlers::repositories::require_visible;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::badge_service::{self, BadgeKind, COLOR_BLUE, COLOR_GREY};
+use crate::services::repository_service::RepositoryService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(package_badge),
+    tags((name = "badges", description = "Embeddable SVG status badges for packages"))
+)]
+pub struct BadgesApiDoc;
+
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/:repo_key/*path", get(package_badge))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BadgeQuery {
+    /// Replace the left-hand label text.
+    pub label: Option<String>,
+}
+
+/// Badge text for one request, before rendering.
+struct Badge {
+    status: StatusCode,
+    message: String,
+    color: &'static str,
+    public: bool,
+}
+
+impl Badge {
+    fn not_found() -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: "not found".to_string(),
+            color: COLOR_GREY,
+            public: false,
+        }
+    }
+}
+
+/// Render a package status badge
+#[utoipa::path(
+    get,
+    path = "/{repo_key}/{package}/{badge}",
+    context_path = "/api/v1/badges",
+    tag = "badges",
+    params(
+        ("repo_key" = String, Path, description = "Repository key"),
+        ("package" = String, Path, description = "Package name (may contain slashes)"),
+        ("badge" = String, Path, description = "version.svg, scan.svg or license.svg"),
+        BadgeQuery,
+    ),
+    responses(
+        (status = 200, description = "SVG badge", content_type = "image/svg+xml", body = String),
+        (status = 404, description = "SVG \"not found\" badge for unknown or invisible packages", content_type = "image/svg+xml", body = String),
+    )
+)]
+pub async fn package_badge(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path((repo_key, path)): Path<(String, String)>,
+    Query(query): Query<BadgeQuery>,
+) -> Result<Response> {
+    let Some((package, file_name)) = path.trim_matches('/').rsplit_once('/') else {
+        return Err(AppError::NotFound("Unknown badge".to_string()));
+    };
+    let kind = BadgeKind::from_file_name(file_name)
+        .ok_or_else(|| AppError::NotFound(format!("Unknown badge '{}'", file_name)))?;
+
+    let badge = match resolve(&state, &auth, &repo_key, package, kind).await {
+        Ok(badge) => badge,
+        Err(AppError::NotFound(_)) => Badge::not_found(),
+        Err(e) => return Err(e),
+    };
+
+    let label = query
+        .label
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .unwrap_or(kind.default_label());
+    let svg = badge_service::render(label, &badge.message, badge.color);
+
+    // Short cache so embedded badges stay live without hammering the server;
+    // private badges must not be stored by shared caches.
+    let cache_control = if badge.public {
+        "public, max-age=300"
+    } else {
+        "private, max-age=300"
+    };
+    Response::builder()
+        .status(badge.status)
+        .header(header::CONTENT_TYPE, "image/svg+xml;charset=utf-8")
+        .header(header::CACHE_CONTROL, cache_control)
+        .body(Body::from(svg))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
+
+async fn resolve(
+    state: &SharedState,
+    auth: &Option<AuthExtension>,
+    repo_key: &str,
+    package: &str,
+    kind: BadgeKind,
+) -> Result<Badge> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(repo_key).await?;
+    require_visible(&repo, auth, &repo_service).await?;
+
+    let data = badge_service::package_badge_data(&state.db, repo.id, package)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Package '{}' not found", package)))?;
+
+    let (message, color) = match kind {
+        BadgeKind::Version if data.version.starts_with(['v', 'V']) => (data.version, COLOR_BLUE),
+        BadgeKind::Version => (format!("v{}", data.version), COLOR_BLUE),
+        BadgeKind::Scan => data.scan.message_and_color(),
+        BadgeKind::License => match data.license {
+            Some(license) => (license, COLOR_BLUE),
+            None => ("unknown".to_string(), COLOR_GREY),
+        },
+    };
+    Ok(Badge {
+        status: StatusCode::OK,
+        message,
+        color,
+        public: repo.is_public,
+    })
+}
@@ -0,0 +1,279 @@
+//! Instance block list administration.
+//!
+//! Admin-only, nested under `/api/v1/admin/blocklist`. Entries block a
+//! SHA-256 digest or a package purl (optionally limited to a version range);
+//! see `services::blocklist_service` for matching and the feed format.
+
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Extension, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::blocklist_service::{
+    parse_text_feed, BlocklistEntry, BlocklistFeed, BlocklistService, FeedEntry, ImportSummary,
+    NewEntry,
+};
+
+/// Largest feed accepted by `POST /import`.
+const MAX_FEED_BYTES: usize = 16 * 1024 * 1024;
+
+/// Admin routes, nested at `/api/v1/admin/blocklist`.
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_entries).post(create_entry))
+        .route("/:id", delete(delete_entry))
+        .route(
+            "/import",
+            post(import_feed).layer(DefaultBodyLimit::max(MAX_FEED_BYTES)),
+        )
+}
+
+fn require_admin(auth: &AuthExtension) -> Result<()> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BlocklistQuery {
+    /// Only entries imported from this feed (`manual` for API-created ones).
+    pub source: Option<String>,
+    /// Page size (default 100, max 1000).
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BlocklistListResponse {
+    pub items: Vec<BlocklistEntry>,
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BlocklistCreateResponse {
+    pub entry: BlocklistEntry,
+    /// Stored artifacts rejected because they match the entry.
+    pub rejected_artifacts: u64,
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/admin/blocklist",
+    tag = "blocklist",
+    params(BlocklistQuery),
+    responses(
+        (status = 200, description = "Block-list entries", body = BlocklistListResponse),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_entries(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<BlocklistQuery>,
+) -> Result<Json<BlocklistListResponse>> {
+    require_admin(&auth)?;
+
+    let source = query
+        .source
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let (items, total) = BlocklistService::new(state.db.clone())
+        .list(source, limit, offset)
+        .await?;
+    Ok(Json(BlocklistListResponse { items, total }))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    context_path = "/api/v1/admin/blocklist",
+    tag = "blocklist",
+    request_body = FeedEntry,
+    responses(
+        (status = 201, description = "Entry created and stored matches rejected", body = BlocklistCreateResponse),
+        (status = 400, description = "Invalid digest, purl or version range"),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_entry(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(body): Json<FeedEntry>,
+) -> Result<(StatusCode, Json<BlocklistCreateResponse>)> {
+    require_admin(&auth)?;
+
+    let entry = NewEntry::from_feed(&body)?;
+    let (entry, rejected_artifacts) = BlocklistService::new(state.db.clone())
+        .create(&entry, Some(auth.user_id))
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(BlocklistCreateResponse {
+            entry,
+            rejected_artifacts,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    context_path = "/api/v1/admin/blocklist",
+    tag = "blocklist",
+    params(("id" = Uuid, Path, description = "Entry ID")),
+    responses(
+        (status = 204, description = "Entry removed; artifacts it alone rejected are restored"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Entry not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_entry(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_admin(&auth)?;
+
+    BlocklistService::new(state.db.clone()).delete(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImportQuery {
+    /// Feed name for a `text/plain` body (JSON bodies carry their own).
+    pub source: Option<String>,
+    /// Replace the feed's previous entries (`text/plain` only).
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// Read a feed body: a JSON [`BlocklistFeed`], or plain text with the
+/// source and replace flag taken from the query string.
+fn parse_feed(content_type: &str, query: &ImportQuery, body: &[u8]) -> Result<BlocklistFeed> {
+    if content_type.starts_with("text/plain") {
+        let text = std::str::from_utf8(body)
+            .map_err(|_| AppError::Validation("Feed is not valid UTF-8".to_string()))?;
+        let source = query.source.clone().ok_or_else(|| {
+            AppError::Validation("A text feed needs a `source` query parameter".to_string())
+        })?;
+        return Ok(BlocklistFeed {
+            source,
+            replace: query.replace,
+            entries: parse_text_feed(text),
+        });
+    }
+    serde_json::from_slice(body)
+        .map_err(|e| AppError::Validation(format!("Invalid block-list feed: {}", e)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/import",
+    context_path = "/api/v1/admin/blocklist",
+    tag = "blocklist",
+    params(ImportQuery),
+    request_body(content = BlocklistFeed, description = "JSON feed, or `text/plain` with one digest or purl per line"),
+    responses(
+        (status = 200, description = "Feed imported and stored matches rejected", body = ImportSummary),
+        (status = 400, description = "Invalid feed"),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_feed(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ImportQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<ImportSummary>> {
+    require_admin(&auth)?;
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/json")
+        .to_ascii_lowercase();
+    let feed = parse_feed(&content_type, &query, &body)?;
+    let summary = BlocklistService::new(state.db.clone())
+        .import(&feed, Some(auth.user_id))
+        .await?;
+    tracing::info!(
+        "Imported block-list feed '{}': {} upserted, {} removed, {} artifact(s) rejected",
+        feed.source,
+        summary.upserted,
+        summary.removed,
+        summary.rejected_artifacts
+    );
+    Ok(Json(summary))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_entries, create_entry, delete_entry, import_feed),
+    components(schemas(
+        BlocklistEntry,
+        BlocklistListResponse,
+        BlocklistCreateResponse,
+        BlocklistFeed,
+        FeedEntry,
+        ImportSummary,
+    ))
+)]
+pub struct BlocklistApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feed_json() {
+        let query = ImportQuery {
+            source: None,
+            replace: false,
+        };
+        let feed = parse_feed(
+            "application/json",
+            &query,
+            br#"{"source":"osv","replace":true,"entries":[{"purl":"pkg:npm/evil","versions":"<2"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(feed.source, "osv");
+        assert!(feed.replace);
+        assert_eq!(feed.entries[0].versions.as_deref(), Some("<2"));
+    }
+
+    #[test]
+    fn test_parse_feed_text_needs_source() {
+        let mut query = ImportQuery {
+            source: None,
+            replace: true,
+        };
+        assert!(parse_feed("text/plain", &query, b"pkg:npm/evil").is_err());
+
+        query.source = Some("intel".to_string());
+        let feed = parse_feed("text/plain; charset=utf-8", &query, b"pkg:npm/evil\n").unwrap();
+        assert_eq!(feed.source, "intel");
+        assert!(feed.replace);
+        assert_eq!(feed.entries.len(), 1);
+    }
+}
@@ -0,0 +1,223 @@
+//! Lockfile-driven cache warming.
+//!
+//! `POST /repositories/:key/warm-cache` takes a lockfile as the request body
+//! and pulls every package it pins through the repository (and any configured
+//! edge caches), so the first CI run in a new region starts from a warm
+//! cache. See `services::cache_warming_service` for how requests are planned.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::{header, HeaderMap, HeaderName, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::require_visible;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::models::repository::RepositoryType;
+use crate::services::cache_warming_service::{
+    loopback_base_url, parse_lockfile, plan_requests, CacheWarmer, LockfileKind, WarmFailure,
+    WarmTargetResult, MAX_WARM_PACKAGES,
+};
+use crate::services::repository_service::RepositoryService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(warm_cache),
+    components(schemas(LockfileKind, WarmCacheResponse, WarmTargetResult, WarmFailure)),
+    tags((name = "cache-warming", description = "Lockfile-driven proxy cache warming"))
+)]
+pub struct CacheWarmingApiDoc;
+
+/// Create cache warming routes (nested under /api/v1/repositories/:key/warm-cache).
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/:key/warm-cache", post(warm_cache))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WarmCacheQuery {
+    /// `npm`, `poetry`, `go` or `cargo` (also accepts the lockfile file name).
+    /// Detected from the content when omitted.
+    pub lockfile: Option<String>,
+    /// Wait for warming to finish and report per-target results instead of
+    /// returning 202 straight away.
+    #[serde(default)]
+    pub wait: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WarmCacheResponse {
+    pub repository_key: String,
+    pub lockfile: LockfileKind,
+    /// Registry packages pinned by the lockfile.
+    pub packages: usize,
+    /// Requests issued per target.
+    pub requests: usize,
+    /// Edge caches warmed after the local cache.
+    pub edges: Vec<String>,
+    /// `queued` or `completed`.
+    pub status: String,
+    /// Per-target outcome; empty while queued.
+    pub results: Vec<WarmTargetResult>,
+}
+
+/// Headers that carry the caller's identity to the replayed requests.
+fn forwarded_credentials(headers: &HeaderMap) -> HeaderMap {
+    let mut out = HeaderMap::new();
+    for name in [
+        header::AUTHORIZATION,
+        HeaderName::from_static("x-api-key"),
+        header::COOKIE,
+    ] {
+        if let Some(value) = headers.get(&name) {
+            out.insert(name, value.clone());
+        }
+    }
+    out
+}
+
+/// Warm the proxy cache from a lockfile
+///
+/// Accepts `package-lock.json` (npm, Yarn and pnpm repositories),
+/// `poetry.lock` (PyPI), `go.sum` (Go) or `Cargo.lock` (Cargo) as the raw
+/// request body. Every registry package it pins is requested through the
+/// repository's native endpoint with the caller's credentials, then through
+/// each edge cache in `CACHE_WARM_EDGE_URLS`. Git, path and link
+/// dependencies are skipped.
+#[utoipa::path(
+    post,
+    path = "/{key}/warm-cache",
+    context_path = "/api/v1/repositories",
+    tag = "cache-warming",
+    params(
+        ("key" = String, Path, description = "Remote or virtual repository key"),
+        WarmCacheQuery
+    ),
+    request_body(content = String, description = "Lockfile content", content_type = "text/plain"),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Warming finished (`wait=true`)", body = WarmCacheResponse),
+        (status = 202, description = "Warming started in the background", body = WarmCacheResponse),
+        (status = 400, description = "Unrecognised or invalid lockfile, or lockfile does not match the repository format"),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Repository not found")
+    )
+)]
+async fn warm_cache(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Query(query): Query<WarmCacheQuery>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<(StatusCode, Json<WarmCacheResponse>)> {
+    let Some(caller) = auth.as_ref() else {
+        return Err(AppError::Unauthorized(
+            "Authentication required".to_string(),
+        ));
+    };
+    caller.require_scope("read")?;
+
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &auth, &repo_service).await?;
+    if !matches!(
+        repo.repo_type,
+        RepositoryType::Remote | RepositoryType::Virtual
+    ) {
+        return Err(AppError::Validation(
+            "Cache warming applies to remote and virtual repositories".to_string(),
+        ));
+    }
+
+    let kind = match query.lockfile.as_deref().filter(|l| !l.trim().is_empty()) {
+        Some(name) => LockfileKind::parse(name)
+            .ok_or_else(|| AppError::Validation(format!("Unknown lockfile type '{}'", name)))?,
+        None => LockfileKind::detect(&body).ok_or_else(|| {
+            AppError::Validation(
+                "Could not detect the lockfile type; pass ?lockfile=npm|poetry|go|cargo"
+                    .to_string(),
+            )
+        })?,
+    };
+    let prefix = kind.route_prefix(&repo.format).ok_or_else(|| {
+        AppError::Validation(format!(
+            "A {:?} lockfile cannot be warmed into a {:?} repository",
+            kind, repo.format
+        ))
+    })?;
+
+    let packages = parse_lockfile(kind, &body)?;
+    if packages.len() > MAX_WARM_PACKAGES {
+        return Err(AppError::Validation(format!(
+            "Lockfile pins {} packages; at most {} can be warmed per request",
+            packages.len(),
+            MAX_WARM_PACKAGES
+        )));
+    }
+    let plan = plan_requests(kind, prefix, &repo.key, &packages);
+
+    let local_base = loopback_base_url(&state.config.bind_address).ok_or_else(|| {
+        AppError::Internal(format!(
+            "BIND_ADDRESS '{}' is not a socket address; cannot reach the local listener",
+            state.config.bind_address
+        ))
+    })?;
+    let edges = state.config.cache_warm_edge_urls.clone();
+    let warmer = CacheWarmer::new(local_base, edges.clone(), forwarded_credentials(&headers))?;
+
+    let mut response = WarmCacheResponse {
+        repository_key: repo.key.clone(),
+        lockfile: kind,
+        packages: packages.len(),
+        requests: plan.len(),
+        edges,
+        status: "queued".to_string(),
+        results: Vec::new(),
+    };
+
+    tracing::info!(
+        repository = %repo.key,
+        lockfile = ?kind,
+        packages = packages.len(),
+        requests = plan.len(),
+        user = %caller.username,
+        "Cache warming requested"
+    );
+
+    if query.wait {
+        response.results = warmer.run(&plan).await;
+        response.status = "completed".to_string();
+        return Ok((StatusCode::OK, Json(response)));
+    }
+
+    tokio::spawn(async move {
+        warmer.run(&plan).await;
+    });
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwards_only_credential_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer abc".parse().unwrap());
+        headers.insert("x-api-key", "key".parse().unwrap());
+        headers.insert(header::COOKIE, "ak_access_token=t".parse().unwrap());
+        headers.insert(header::CONTENT_TYPE, "text/plain".parse().unwrap());
+        headers.insert(header::HOST, "example.com".parse().unwrap());
+
+        let forwarded = forwarded_credentials(&headers);
+        assert_eq!(forwarded.len(), 3);
+        assert_eq!(forwarded.get("authorization").unwrap(), "Bearer abc");
+        assert!(forwarded.get("host").is_none());
+        assert!(forwarded.get("content-type").is_none());
+    }
+}
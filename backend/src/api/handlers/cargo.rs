@@ -18,7 +18,7 @@ use axum::extract::{Path, State};
 use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{get, put};
+use axum::routing::{delete, get, put};
 use axum::Extension;
 use axum::Router;
 use bytes::Bytes;
@@ -35,6 +35,8 @@ use crate::api::SharedState;
 use crate::api::{CachedRepo, IndexCache, RepoCache, REPO_CACHE_TTL_SECS};
 use crate::error::AppError;
 use crate::models::repository::RepositoryType;
+use crate::services::package_docs_service;
+use crate::services::version_deprecation_service::VersionDeprecationService;
 
 // ---------------------------------------------------------------------------
 // In-process caches
@@ -196,6 +198,12 @@ pub fn router() -> Router<SharedState> {
             "/:repo_key/api/v1/crates/:name/:version/download",
             get(download),
         )
+        // Yank / unyank (`cargo yank [--undo]`)
+        .route("/:repo_key/api/v1/crates/:name/:version/yank", delete(yank))
+        .route(
+            "/:repo_key/api/v1/crates/:name/:version/unyank",
+            put(unyank),
+        )
         // Sparse index — index/ prefixed paths (legacy / internal)
         .route("/:repo_key/index/1/:name", get(sparse_index_1))
         .route("/:repo_key/index/2/:name", get(sparse_index_2))
@@ -692,6 +700,14 @@ async fn store_crate_artifact(
     crate::services::quarantine_service::apply_upload_hold_hosted(&state.db, repo.id, artifact_id)
         .await;
 
+    // README / changelog for search (best-effort; a saturated extraction
+    // budget skips them rather than failing the publish).
+    let docs = crate::util::bounded_archive::with_ingest_extraction(|| {
+        package_docs_service::extract_from_tar_gz(&crate_bytes)
+    })
+    .unwrap_or_default();
+    package_docs_service::record_package_docs(&state.db, artifact_id, &docs).await;
+
     let _ = sqlx::query!(
         r#"
         INSERT INTO artifact_metadata (artifact_id, format, metadata)
@@ -777,15 +793,45 @@ async fn publish(
     .await?;
 
     // Invalidate the index cache for this crate so the next fetch sees the new version.
+    invalidate_crate_index(&state, repo.id, &repo_key, &name_lower).await;
+
+    info!(
+        "Cargo publish: {} {} ({} bytes) to repo {}",
+        name_lower, parsed.crate_version, size_bytes, repo_key
+    );
+
+    // Cargo expects a JSON response with warnings
+    let response = serde_json::json!({
+        "warnings": {
+            "invalid_categories": [],
+            "invalid_badges": [],
+            "other": []
+        }
+    });
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_string(&response).unwrap()))
+        .unwrap())
+}
+
+/// Drop the cached index file for a crate in `repo_key` and in every virtual
+/// repository that includes it, so the next fetch sees new or yanked versions.
+pub(crate) async fn invalidate_crate_index(
+    state: &SharedState,
+    repo_id: uuid::Uuid,
+    repo_key: &str,
+    name_lower: &str,
+) {
     index_cache_invalidate(&state.index_cache, &format!("{}:{}", repo_key, name_lower)).await;
 
-    // Also invalidate any virtual repos that include this hosted repo.
     let virtual_keys: Vec<String> = sqlx::query_scalar(
         "SELECT r.key FROM repositories r \
          INNER JOIN virtual_repo_members vrm ON r.id = vrm.virtual_repo_id \
          WHERE vrm.member_repo_id = $1",
     )
-    .bind(repo.id)
+    .bind(repo_id)
     .fetch_all(&state.db)
     .await
     .unwrap_or_default();
@@ -793,25 +839,76 @@ async fn publish(
     for vkey in &virtual_keys {
         index_cache_invalidate(&state.index_cache, &format!("{}:{}", vkey, name_lower)).await;
     }
+}
+
+// ---------------------------------------------------------------------------
+// DELETE /cargo/{repo_key}/api/v1/crates/{name}/{version}/yank
+// PUT    /cargo/{repo_key}/api/v1/crates/{name}/{version}/unyank
+// ---------------------------------------------------------------------------
+
+async fn yank(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path((repo_key, name, version)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> Result<Response, Response> {
+    set_yanked(&state, auth, &headers, &repo_key, &name, &version, true).await
+}
+
+async fn unyank(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path((repo_key, name, version)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> Result<Response, Response> {
+    set_yanked(&state, auth, &headers, &repo_key, &name, &version, false).await
+}
+
+/// Yanking only flips `yanked` in the index: the `.crate` stays downloadable
+/// so existing `Cargo.lock` files keep building.
+async fn set_yanked(
+    state: &SharedState,
+    auth: Option<AuthExtension>,
+    headers: &HeaderMap,
+    repo_key: &str,
+    name: &str,
+    version: &str,
+    yanked: bool,
+) -> Result<Response, Response> {
+    crate::api::middleware::auth::require_scope_response(auth.as_ref(), "write")?;
+    let user_id =
+        require_auth_with_bearer_fallback(auth, headers, &state.db, &state.config, "cargo").await?;
+    let repo = resolve_cargo_repo(&state.db, repo_key, &state.repo_cache).await?;
+    proxy_helpers::reject_write_if_not_hosted(&repo.repo_type)?;
+
+    let name_lower = name.to_lowercase();
+    let service = VersionDeprecationService::new(state.db.clone());
+    if yanked {
+        service
+            .deprecate(repo.id, &name_lower, version, None, Some(user_id))
+            .await
+            .map_err(|e| e.into_response())?;
+    } else {
+        service
+            .undeprecate(repo.id, &name_lower, version)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+
+    invalidate_crate_index(state, repo.id, repo_key, &name_lower).await;
 
     info!(
-        "Cargo publish: {} {} ({} bytes) to repo {}",
-        name_lower, parsed.crate_version, size_bytes, repo_key
+        "Cargo {}: {} {} in repo {}",
+        if yanked { "yank" } else { "unyank" },
+        name_lower,
+        version,
+        repo_key
     );
 
-    // Cargo expects a JSON response with warnings
-    let response = serde_json::json!({
-        "warnings": {
-            "invalid_categories": [],
-            "invalid_badges": [],
-            "other": []
-        }
-    });
-
     Ok(Response::builder()
         .status(StatusCode::OK)
         .header(CONTENT_TYPE, "application/json")
-        .body(Body::from(serde_json::to_string(&response).unwrap()))
+        .body(Body::from(r#"{"ok":true}"#))
         .unwrap())
 }
 
@@ -933,8 +1030,13 @@ async fn download(
                 // anyway, and skipping it spares the DB an existence
                 // check on every malformed request.
                 let local_owns = if crate::formats::cargo::is_valid_cargo_name(&name_lower) {
-                    proxy_helpers::virtual_non_remote_owns_name(&state.db, repo.id, &name_lower)
-                        .await?
+                    proxy_helpers::virtual_non_remote_owns_name(
+                        &state.db,
+                        repo.id,
+                        &name_lower,
+                        &upstream_path,
+                    )
+                    .await?
                 } else {
                     false
                 };
@@ -1063,6 +1165,7 @@ fn build_index_entry(
     version: &str,
     checksum: &str,
     metadata: Option<&serde_json::Value>,
+    yanked: bool,
 ) -> String {
     let (deps, features, links, rust_version) = extract_index_fields(metadata);
 
@@ -1072,7 +1175,7 @@ fn build_index_entry(
         "deps": deps,
         "cksum": checksum,
         "features": features,
-        "yanked": false,
+        "yanked": yanked,
     });
 
     if !links.is_null() {
@@ -1234,7 +1337,16 @@ async fn try_virtual_index(
         return None;
     }
 
-    let members = match proxy_helpers::fetch_virtual_members(&state.db, repo.id).await {
+    // Route by the crate's download directory so the index only merges
+    // members the `.crate` downloads themselves would be routed to.
+    let route_path = format!("api/v1/crates/{}/", name_lower);
+    let members = match proxy_helpers::fetch_virtual_members_for_path(
+        &state.db,
+        repo.id,
+        &route_path,
+    )
+    .await
+    {
         Ok(m) => m,
         Err(e) => return Some(Err(e)),
     };
@@ -1345,6 +1457,18 @@ async fn try_virtual_index(
                     Vec::new()
                 });
 
+                let yanked = VersionDeprecationService::new(state.db.clone())
+                    .deprecated_versions(member.id, name_lower)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::warn!(
+                            "Failed to query yanked versions for member {}: {}",
+                            member.id,
+                            e
+                        );
+                        Default::default()
+                    });
+
                 for row in &rows {
                     let vers: Option<String> = row.get("version");
                     let Some(vers) = vers else { continue };
@@ -1353,7 +1477,13 @@ async fn try_virtual_index(
                     }
                     let cksum: String = row.get("checksum_sha256");
                     let meta: Option<serde_json::Value> = row.get("metadata");
-                    aggregated.push(build_index_entry(name_lower, &vers, &cksum, meta.as_ref()));
+                    aggregated.push(build_index_entry(
+                        name_lower,
+                        &vers,
+                        &cksum,
+                        meta.as_ref(),
+                        yanked.contains_key(&vers),
+                    ));
                 }
             }
             RepositoryType::Virtual => {
@@ -1561,12 +1691,23 @@ async fn serve_index(
         return Err(AppError::NotFound("Crate not found in index".to_string()).into_response());
     }
 
+    let yanked = VersionDeprecationService::new(state.db.clone())
+        .deprecated_versions(repo.id, &name_lower)
+        .await
+        .map_err(|e| e.into_response())?;
+
     // Build index file: one JSON object per line
     let lines: Vec<String> = versions
         .iter()
         .map(|v| {
             let vers = v.version.as_deref().unwrap_or("0.0.0");
-            build_index_entry(&name_lower, vers, &v.checksum_sha256, v.metadata.as_ref())
+            build_index_entry(
+                &name_lower,
+                vers,
+                &v.checksum_sha256,
+                v.metadata.as_ref(),
+                yanked.contains_key(vers),
+            )
         })
         .collect();
 
@@ -2297,7 +2438,7 @@ mod tests {
 
     #[test]
     fn test_build_index_entry_no_metadata() {
-        let entry_str = build_index_entry("my-crate", "1.0.0", "abcdef1234", None);
+        let entry_str = build_index_entry("my-crate", "1.0.0", "abcdef1234", None, false);
         let entry: serde_json::Value = serde_json::from_str(&entry_str).unwrap();
 
         assert_eq!(entry["name"], "my-crate");
@@ -2318,7 +2459,7 @@ mod tests {
             "links": "openssl",
             "rust_version": "1.75.0"
         });
-        let entry_str = build_index_entry("openssl-sys", "0.9.102", "deadbeef", Some(&meta));
+        let entry_str = build_index_entry("openssl-sys", "0.9.102", "deadbeef", Some(&meta), false);
         let entry: serde_json::Value = serde_json::from_str(&entry_str).unwrap();
 
         assert_eq!(entry["name"], "openssl-sys");
@@ -2337,7 +2478,7 @@ mod tests {
             "deps": [],
             "features": {}
         });
-        let entry_str = build_index_entry("simple", "0.1.0", "aaa", Some(&meta));
+        let entry_str = build_index_entry("simple", "0.1.0", "aaa", Some(&meta), false);
         let entry: serde_json::Value = serde_json::from_str(&entry_str).unwrap();
 
         assert!(entry.get("links").is_none());
@@ -2346,19 +2487,27 @@ mod tests {
 
     #[test]
     fn test_build_index_entry_is_valid_json() {
-        let entry_str = build_index_entry("test", "0.0.1", "checksum", None);
+        let entry_str = build_index_entry("test", "0.0.1", "checksum", None, false);
         let parsed: Result<serde_json::Value, _> = serde_json::from_str(&entry_str);
         assert!(parsed.is_ok());
     }
 
     #[test]
-    fn test_build_index_entry_yanked_is_always_false() {
+    fn test_build_index_entry_yanked_defaults_to_false() {
         let meta = serde_json::json!({"deps": [], "features": {}});
-        let entry_str = build_index_entry("crate", "1.0.0", "cksum", Some(&meta));
+        let entry_str = build_index_entry("crate", "1.0.0", "cksum", Some(&meta), false);
         let entry: serde_json::Value = serde_json::from_str(&entry_str).unwrap();
         assert_eq!(entry["yanked"], false);
     }
 
+    #[test]
+    fn test_build_index_entry_yanked_version_keeps_checksum() {
+        let entry_str = build_index_entry("crate", "1.0.0", "cksum", None, true);
+        let entry: serde_json::Value = serde_json::from_str(&entry_str).unwrap();
+        assert_eq!(entry["yanked"], true);
+        assert_eq!(entry["cksum"], "cksum");
+    }
+
     #[test]
     fn test_build_index_entry_normalises_dep_version_req_field() {
         // Cargo publish sends "version_req" but the sparse index requires "req".
@@ -2370,7 +2519,7 @@ mod tests {
                 "deps": [{ "name": "dep", field: ver, "kind": "normal" }],
                 "features": {}
             });
-            let entry_str = build_index_entry("test-crate", "0.1.0", "aaa", Some(&meta));
+            let entry_str = build_index_entry("test-crate", "0.1.0", "aaa", Some(&meta), false);
             let entry: serde_json::Value = serde_json::from_str(&entry_str).unwrap();
             let dep = &entry["deps"][0];
             assert_eq!(dep["req"], ver, "field '{field}' should produce req={ver}");
@@ -2818,9 +2967,9 @@ mod tests {
     #[test]
     fn test_index_multiline_output() {
         let lines: Vec<String> = vec![
-            build_index_entry("mycrate", "0.1.0", "aaa", None),
-            build_index_entry("mycrate", "0.2.0", "bbb", None),
-            build_index_entry("mycrate", "1.0.0", "ccc", None),
+            build_index_entry("mycrate", "0.1.0", "aaa", None, false),
+            build_index_entry("mycrate", "0.2.0", "bbb", None, false),
+            build_index_entry("mycrate", "1.0.0", "ccc", None, false),
         ];
         let body = lines.join("\n");
 
@@ -2844,7 +2993,9 @@ mod tests {
 
     #[test]
     fn test_index_single_version() {
-        let lines: Vec<String> = vec![build_index_entry("single", "1.0.0", "checksum", None)];
+        let lines: Vec<String> = vec![build_index_entry(
+            "single", "1.0.0", "checksum", None, false,
+        )];
         let body = lines.join("\n");
         assert!(!body.contains('\n'));
 
@@ -0,0 +1,499 @@
+//! Slack / Microsoft Teams chat integration handlers.
+//!
+//! Management endpoints are admin-only. The Slack interactivity callback is
+//! public (Slack cannot authenticate to us) and is authenticated instead by
+//! the integration's signing secret; the clicking Slack user is mapped to an
+//! Artifact Keeper admin by email before the approval is reviewed.
+
+use axum::{
+    body::Bytes,
+    extract::{Extension, Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::handlers::approval::{self, ReviewRequest};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::validation::validate_outbound_webhook_url;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::models::user::User;
+use crate::services::chat_integration_service::{
+    self, ApprovalDecision, ChatIntegration, ChatIntegrationService, ChatMode, ChatPlatform,
+    ChatRoutingRule, ChatRoutingRuleInput, CreateChatIntegration, UpdateChatIntegration,
+};
+
+/// Admin management routes, nested at `/api/v1/chat-integrations`.
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_integrations).post(create_integration))
+        .route(
+            "/:id",
+            get(get_integration)
+                .put(update_integration)
+                .delete(delete_integration),
+        )
+        .route("/:id/rules", get(list_rules).put(replace_rules))
+        .route("/:id/test", post(test_integration))
+}
+
+/// Public platform callbacks, nested at `/api/v1/chat-callbacks`.
+pub fn public_router() -> Router<SharedState> {
+    Router::new().route("/slack/:id/interactions", post(slack_interaction))
+}
+
+fn require_admin(auth: &AuthExtension) -> Result<()> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn service(state: &SharedState) -> ChatIntegrationService {
+    ChatIntegrationService::new(state.db.clone())
+}
+
+fn ensure_crypto_configured() -> Result<()> {
+    crate::services::webhook_secret_crypto::ensure_configured()
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+fn validate_urls(webhook_url: Option<&str>, app_service_url: Option<&str>) -> Result<()> {
+    if let Some(url) = webhook_url.filter(|u| !u.is_empty()) {
+        validate_outbound_webhook_url(url, "Chat webhook URL")?;
+    }
+    if let Some(url) = app_service_url.filter(|u| !u.is_empty()) {
+        validate_outbound_webhook_url(url, "Teams service URL")?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChatIntegrationListResponse {
+    pub items: Vec<ChatIntegration>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChatIntegrationDetailResponse {
+    #[serde(flatten)]
+    pub integration: ChatIntegration,
+    pub rules: Vec<ChatRoutingRule>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReplaceChatRulesRequest {
+    pub rules: Vec<ChatRoutingRuleInput>,
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/chat-integrations",
+    tag = "chat_integrations",
+    responses(
+        (status = 200, description = "Configured chat integrations", body = ChatIntegrationListResponse),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_integrations(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<ChatIntegrationListResponse>> {
+    require_admin(&auth)?;
+    let items = service(&state).list().await?;
+    Ok(Json(ChatIntegrationListResponse { items }))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    context_path = "/api/v1/chat-integrations",
+    tag = "chat_integrations",
+    request_body = CreateChatIntegration,
+    responses(
+        (status = 201, description = "Integration created", body = ChatIntegrationDetailResponse),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 409, description = "Name already in use"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(req): Json<CreateChatIntegration>,
+) -> Result<(StatusCode, Json<ChatIntegrationDetailResponse>)> {
+    require_admin(&auth)?;
+    chat_integration_service::validate_new_integration(&req)?;
+    validate_urls(req.webhook_url.as_deref(), req.app_service_url.as_deref())?;
+    ensure_crypto_configured()?;
+
+    let svc = service(&state);
+    let integration = svc.create(&req, auth.user_id).await?;
+    let rules = svc.list_rules(integration.id).await?;
+    tracing::info!(
+        integration_id = %integration.id,
+        platform = %integration.platform,
+        created_by = %auth.user_id,
+        "Chat integration created"
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(ChatIntegrationDetailResponse { integration, rules }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    context_path = "/api/v1/chat-integrations",
+    tag = "chat_integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 200, description = "Integration and its routing rules", body = ChatIntegrationDetailResponse),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ChatIntegrationDetailResponse>> {
+    require_admin(&auth)?;
+    let svc = service(&state);
+    let integration = svc.get(id).await?;
+    let rules = svc.list_rules(id).await?;
+    Ok(Json(ChatIntegrationDetailResponse { integration, rules }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    context_path = "/api/v1/chat-integrations",
+    tag = "chat_integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    request_body = UpdateChatIntegration,
+    responses(
+        (status = 200, description = "Integration updated", body = ChatIntegration),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateChatIntegration>,
+) -> Result<Json<ChatIntegration>> {
+    require_admin(&auth)?;
+    validate_urls(req.webhook_url.as_deref(), req.app_service_url.as_deref())?;
+    if req.webhook_url.is_some() || req.token.is_some() || req.signing_secret.is_some() {
+        ensure_crypto_configured()?;
+    }
+    Ok(Json(service(&state).update(id, &req).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    context_path = "/api/v1/chat-integrations",
+    tag = "chat_integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 204, description = "Integration deleted"),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_admin(&auth)?;
+    service(&state).delete(id).await?;
+    tracing::info!(integration_id = %id, deleted_by = %auth.user_id, "Chat integration deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/rules",
+    context_path = "/api/v1/chat-integrations",
+    tag = "chat_integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 200, description = "Routing rules", body = Vec<ChatRoutingRule>),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_rules(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ChatRoutingRule>>> {
+    require_admin(&auth)?;
+    let svc = service(&state);
+    svc.get(id).await?;
+    Ok(Json(svc.list_rules(id).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}/rules",
+    context_path = "/api/v1/chat-integrations",
+    tag = "chat_integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    request_body = ReplaceChatRulesRequest,
+    responses(
+        (status = 200, description = "Routing rules replaced", body = Vec<ChatRoutingRule>),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn replace_rules(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<ReplaceChatRulesRequest>,
+) -> Result<Json<Vec<ChatRoutingRule>>> {
+    require_admin(&auth)?;
+    Ok(Json(service(&state).replace_rules(id, &req.rules).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/test",
+    context_path = "/api/v1/chat-integrations",
+    tag = "chat_integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 204, description = "Test message delivered"),
+        (status = 400, description = "Integration has no default channel"),
+        (status = 404, description = "Integration not found"),
+        (status = 500, description = "Delivery failed"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn test_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_admin(&auth)?;
+    service(&state).send_test(id, &auth.username).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct SlackInteractionForm {
+    payload: String,
+}
+
+/// Resolve the Slack user behind an interaction to an active, human
+/// Artifact Keeper admin with the same email address.
+async fn resolve_slack_reviewer(
+    state: &SharedState,
+    svc: &ChatIntegrationService,
+    integration_id: Uuid,
+    slack_user_id: &str,
+) -> Result<Option<User>> {
+    let Some(email) = svc.slack_user_email(integration_id, slack_user_id).await? else {
+        return Ok(None);
+    };
+    sqlx::query_as::<_, User>(
+        r#"
+        SELECT * FROM users
+        WHERE LOWER(email) = LOWER($1)
+          AND is_active = true
+          AND is_admin = true
+          AND is_service_account = false
+        "#,
+    )
+    .bind(email)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Slack interactivity endpoint for the Approve / Reject buttons.
+///
+/// Always answers 200 once the signature checks out: Slack shows an error
+/// to the clicking user for any other status, so outcomes are reported back
+/// through the interaction's `response_url` instead.
+#[utoipa::path(
+    post,
+    path = "/slack/{id}/interactions",
+    context_path = "/api/v1/chat-callbacks",
+    tag = "chat_integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    request_body(content = String, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Interaction accepted"),
+        (status = 401, description = "Missing or invalid Slack signature"),
+    )
+)]
+pub async fn slack_interaction(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode> {
+    let svc = service(&state);
+    let secret = svc
+        .slack_signing_secret(id)
+        .await?
+        .ok_or_else(|| AppError::Authentication("Invalid Slack signature".to_string()))?;
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+    };
+    if !chat_integration_service::verify_slack_signature(
+        &secret,
+        header("x-slack-request-timestamp"),
+        &body,
+        header("x-slack-signature"),
+        chrono::Utc::now().timestamp(),
+    ) {
+        return Err(AppError::Authentication(
+            "Invalid Slack signature".to_string(),
+        ));
+    }
+
+    let form: SlackInteractionForm = serde_urlencoded::from_bytes(&body)
+        .map_err(|_| AppError::Validation("Missing interaction payload".to_string()))?;
+    let payload: serde_json::Value = serde_json::from_str(&form.payload)
+        .map_err(|_| AppError::Validation("Malformed interaction payload".to_string()))?;
+    let Some(action) = chat_integration_service::parse_slack_interaction(&payload) else {
+        // Not one of our buttons; acknowledge and ignore.
+        return Ok(StatusCode::OK);
+    };
+
+    let reply = match resolve_slack_reviewer(&state, &svc, id, &action.slack_user_id).await? {
+        None => "Only Artifact Keeper administrators whose Slack email matches their \
+                 account can review promotions from Slack."
+            .to_string(),
+        Some(user) => {
+            let reviewer = user.username.clone();
+            let auth = AuthExtension::from(user);
+            let review = ReviewRequest {
+                notes: Some("Reviewed from Slack".to_string()),
+                skip_policy_check: false,
+                freeze_override_reason: None,
+            };
+            let outcome = match action.decision {
+                ApprovalDecision::Approve => {
+                    approval::approve_promotion(
+                        State(state.clone()),
+                        Extension(auth),
+                        Path(action.approval_id),
+                        Json(review),
+                    )
+                    .await
+                }
+                ApprovalDecision::Reject => {
+                    approval::reject_promotion(
+                        State(state.clone()),
+                        Extension(auth),
+                        Path(action.approval_id),
+                        Json(review),
+                    )
+                    .await
+                }
+            };
+            match outcome {
+                Ok(Json(resp)) => format!("Promotion {} by {}", resp.status, reviewer),
+                Err(e) => format!("Could not review promotion: {}", e),
+            }
+        }
+    };
+
+    if let Some(url) = &action.response_url {
+        // The response_url comes from the signed payload, but still must
+        // not be able to point us at internal hosts.
+        if validate_outbound_webhook_url(url, "Slack response URL").is_ok() {
+            svc.respond_to_slack_interaction(url, &reply).await;
+        }
+    }
+    Ok(StatusCode::OK)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_integrations,
+        create_integration,
+        get_integration,
+        update_integration,
+        delete_integration,
+        list_rules,
+        replace_rules,
+        test_integration,
+        slack_interaction,
+    ),
+    components(schemas(
+        ChatPlatform,
+        ChatMode,
+        ChatIntegration,
+        ChatRoutingRule,
+        ChatRoutingRuleInput,
+        CreateChatIntegration,
+        UpdateChatIntegration,
+        ChatIntegrationListResponse,
+        ChatIntegrationDetailResponse,
+        ReplaceChatRulesRequest,
+    )),
+    tags(
+        (name = "chat_integrations", description = "Slack and Microsoft Teams notification integrations"),
+    )
+)]
+pub struct ChatIntegrationsApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_require_admin() {
+        let user = AuthExtension::default();
+        assert!(matches!(
+            require_admin(&user),
+            Err(AppError::Authorization(_))
+        ));
+        let admin = AuthExtension {
+            is_admin: true,
+            ..Default::default()
+        };
+        assert!(require_admin(&admin).is_ok());
+    }
+
+    #[test]
+    fn test_validate_urls_rejects_internal_targets() {
+        assert!(validate_urls(Some("http://127.0.0.1/hook"), None).is_err());
+        assert!(validate_urls(None, Some("http://169.254.169.254/")).is_err());
+        assert!(validate_urls(None, None).is_ok());
+        assert!(validate_urls(Some(""), Some("")).is_ok());
+    }
+
+    #[test]
+    fn test_interaction_form_parses_payload_field() {
+        let form: SlackInteractionForm =
+            serde_urlencoded::from_bytes(b"payload=%7B%22type%22%3A%22block_actions%22%7D")
+                .unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&form.payload).unwrap();
+        assert_eq!(payload["type"], "block_actions");
+    }
+}
@@ -379,7 +379,10 @@ async fn resolve_virtual_composer_metadata<R>(
 where
     R: Fn(&str, &str, &[ComposerArtifactRow]) -> Response,
 {
-    let members = proxy_helpers::fetch_virtual_members(&state.db, virtual_repo_id).await?;
+    let route_path = format!("dist/{}/", full_name);
+    let members =
+        proxy_helpers::fetch_virtual_members_for_path(&state.db, virtual_repo_id, &route_path)
+            .await?;
 
     if members.is_empty() {
         return Err((StatusCode::NOT_FOUND, "Virtual repository has no members").into_response());
@@ -1047,12 +1050,20 @@ async fn packages_json(
     // resolves those per-package via the metadata-url).
     let rows = if repo.repo_type == RepositoryType::Virtual {
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
         let mut aggregated: Vec<PackageIndexRow> = Vec::new();
         for member in &members {
             if member.repo_type == RepositoryType::Local
                 || member.repo_type == RepositoryType::Staging
             {
-                aggregated.extend(fetch_package_index_rows(&state.db, member.id).await?);
+                // Skip packages the member's routing patterns keep it from
+                // serving, checked against the package's dist directory.
+                aggregated.extend(
+                    fetch_package_index_rows(&state.db, member.id)
+                        .await?
+                        .into_iter()
+                        .filter(|row| routes.allows(member.id, &format!("dist/{}/", row.name))),
+                );
             }
         }
         aggregated
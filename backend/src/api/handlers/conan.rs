@@ -463,6 +463,14 @@ async fn search_recipes_for_repo(
         .collect())
 }
 
+/// The `v2/conans/{name}/{version}/{user}/{channel}/` directory a search
+/// result reference (`name/version[@user/channel]`) downloads from, used to
+/// check the reference against virtual member routing patterns.
+fn conan_reference_route_path(reference: &str) -> String {
+    let (name_version, user_channel) = reference.split_once('@').unwrap_or((reference, "_/_"));
+    format!("v2/conans/{}/{}/", name_version, user_channel)
+}
+
 /// Forward a Conan v2 search query to a remote upstream and parse the JSON
 /// `results: [...]` array. Returns `Ok(Vec::new())` on any non-200 response
 /// or parse error — search is a best-effort, additive operation, so a remote
@@ -965,13 +973,21 @@ async fn search(
         // Walk virtual members in priority order. Hosted members are queried
         // directly; remote members are forwarded to their upstream. Each
         // member's results are merged and deduped.
+        // Each member only contributes the references its routing patterns
+        // let it serve.
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
+        let routed = |member_id: uuid::Uuid, refs: Vec<String>| -> Vec<String> {
+            refs.into_iter()
+                .filter(|r| routes.allows(member_id, &conan_reference_route_path(r)))
+                .collect()
+        };
         for member in &members {
             if member.repo_type.is_hosted() {
                 let local = search_recipes_for_repo(&state.db, member.id, &like_pattern)
                     .await
                     .map_err(map_db_err)?;
-                push(local, &mut seen, &mut results);
+                push(routed(member.id, local), &mut seen, &mut results);
             } else if member.repo_type == RepositoryType::Remote {
                 if let (Some(upstream_url), Some(proxy)) = (
                     member.upstream_url.as_deref(),
@@ -985,7 +1001,7 @@ async fn search(
                         &pattern,
                     )
                     .await;
-                    push(remote, &mut seen, &mut results);
+                    push(routed(member.id, remote), &mut seen, &mut results);
                 }
             }
         }
@@ -1080,7 +1096,9 @@ async fn recipe_latest(
     // used by `recipe_file_download`. Remote member aggregation is deferred to
     // a follow-up; only hosted (Local/Staging) members are consulted here.
     let revision = if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!("v2/conans/{}/{}/{}/{}/latest", name, version, user, channel);
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         let mut found: Option<String> = None;
         for member in &members {
             if !member.repo_type.is_hosted() {
@@ -1219,7 +1237,12 @@ async fn recipe_revisions(
     // union of revisions, deduped by revision id, ordered by newest first.
     // Remote-member aggregation is deferred (matches recipe_latest semantics).
     let rows = if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!(
+            "v2/conans/{}/{}/{}/{}/revisions",
+            name, version, user, channel
+        );
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         let mut seen = std::collections::HashSet::<String>::new();
         let mut merged: Vec<RecipeRevisionRow> = Vec::new();
         for member in &members {
@@ -1364,7 +1387,12 @@ async fn recipe_package_search(
     };
 
     if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!(
+            "v2/conans/{}/{}/{}/{}/revisions/{}/search",
+            name, version, user, channel, revision
+        );
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         for member in &members {
             if member.repo_type.is_hosted() {
                 let ids = package_ids_for_recipe_revision(
@@ -1492,7 +1520,12 @@ async fn recipe_files_list(
     // For virtual repos, walk hosted members in priority order and merge the
     // union of file names, deduped. Order matches recipe_revisions semantics.
     let filenames: Vec<String> = if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!(
+            "v2/conans/{}/{}/{}/{}/revisions/{}/files",
+            name, version, user, channel, revision
+        );
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         let mut seen = std::collections::HashSet::<String>::new();
         let mut merged: Vec<String> = Vec::new();
         for member in &members {
@@ -1932,7 +1965,12 @@ async fn package_latest(
     // return the first member that has a matching package revision. Matches
     // recipe_latest semantics. Remote-member aggregation is deferred.
     let pkg_revision = if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!(
+            "v2/conans/{}/{}/{}/{}/revisions/{}/packages/{}/latest",
+            name, version, user, channel, revision, package_id
+        );
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         let mut found: Option<String> = None;
         for member in &members {
             if !member.repo_type.is_hosted() {
@@ -2096,7 +2134,12 @@ async fn package_revisions(
     // Virtual fan-out: union of package revisions across hosted members,
     // deduped by revision id and re-sorted by newest first.
     let rows = if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!(
+            "v2/conans/{}/{}/{}/{}/revisions/{}/packages/{}/revisions",
+            name, version, user, channel, revision, package_id
+        );
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         let mut seen = std::collections::HashSet::<String>::new();
         let mut merged: Vec<PackageRevisionRow> = Vec::new();
         for member in &members {
@@ -2241,7 +2284,12 @@ async fn package_files_list(
     // Virtual fan-out: union of package file names across hosted members,
     // deduped by file name.
     let filenames: Vec<String> = if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!(
+            "v2/conans/{}/{}/{}/{}/revisions/{}/packages/{}/revisions/{}/files",
+            name, version, user, channel, revision, package_id, pkg_revision
+        );
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         let mut seen = std::collections::HashSet::<String>::new();
         let mut merged: Vec<String> = Vec::new();
         for member in &members {
@@ -3534,6 +3582,24 @@ mod tests {
         assert!(path.contains("/myuser/stable/"));
     }
 
+    #[test]
+    fn test_conan_reference_route_path_is_prefix_of_file_paths() {
+        let route = conan_reference_route_path("zlib/1.2.13@_/_");
+        assert_eq!(route, "v2/conans/zlib/1.2.13/_/_/");
+        assert!(
+            build_recipe_upstream_path("zlib", "1.2.13", "_", "_", "r", "conanfile.py")
+                .starts_with(&route)
+        );
+        assert_eq!(
+            conan_reference_route_path("boost/1.80"),
+            "v2/conans/boost/1.80/_/_/"
+        );
+        assert_eq!(
+            conan_reference_route_path("boost/1.80@myuser/stable"),
+            "v2/conans/boost/1.80/myuser/stable/"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // test_helpers — shared scaffolding for DB-backed handler tests.
     //
@@ -3626,7 +3692,20 @@ mod tests {
                 guest_access_enabled: true,
                 expose_detailed_health: false,
                 setup_password_hint: None,
+                setup_wizard: false,
                 grpc_reflection_enabled: false,
+                grpc_keepalive_interval_secs: 30,
+                grpc_keepalive_timeout_secs: 20,
+                grpc_tcp_keepalive_secs: 60,
+                grpc_health_probe_interval_secs: 10,
+                s3_gateway_enabled: false,
+                s3_gateway_region: "us-east-1".to_string(),
+                repository_event_retention_days: 30,
+                pii_retention_days: 0,
+                cache_warm_edge_urls: Vec::new(),
+                admission_registry_hosts: Vec::new(),
+                admission_allowed_repositories: Vec::new(),
+                admission_exempt_namespaces: vec!["kube-system".to_string()],
                 plugins_require_signed: true,
                 plugins_trusted_pubkey: None,
                 peer_instance_name: "test".into(),
@@ -3644,6 +3723,8 @@ mod tests {
                 stuck_scan_threshold_secs: 1800,
                 stuck_scan_check_interval_secs: 600,
                 stuck_scan_reap_limit: 1000,
+                audit_retention_enabled: false,
+                audit_archive_enabled: true,
                 allow_local_admin_login: false,
                 sso_disable_admin_break_glass: false,
                 max_upload_size_bytes: 10_737_418_240,
@@ -3689,9 +3770,17 @@ mod tests {
                 password_min_strength: 0,
                 presigned_downloads_enabled: false,
                 presigned_download_expiry_secs: 300,
+                presigned_canary_interval_secs: 600,
                 proxy_singleflight_advisory_locks_enabled: false,
                 proxy_singleflight_lock_poll_interval_ms: 200,
                 proxy_singleflight_lock_wait_timeout_secs: 65,
+                public_mirror_mode: false,
+                public_mirror_anon_requests_per_window: 1200,
+                public_mirror_window_secs: 60,
+                public_mirror_anon_bytes_per_window: 53_687_091_200,
+                public_mirror_bandwidth_window_secs: 3600,
+                public_mirror_metadata_max_age_secs: 300,
+                public_mirror_artifact_max_age_secs: 31_536_000,
                 smtp_host: None,
                 smtp_port: 587,
                 smtp_username: None,
@@ -3706,6 +3795,18 @@ mod tests {
                 npm_upstream_feed_url:
                     crate::services::upstream_feed::NPM_REPLICATION_FEED_DEFAULT_URL.into(),
                 scan_token_ttl_seconds: 300,
+                load_test_mode: false,
+                storage_dedup_enabled: false,
+                storage_capacity_bytes: None,
+                storage_forecast_alert_days: 30,
+                quota_warning_threshold_percent: 80,
+                storage_cold_backend: None,
+                storage_cold_path: None,
+                storage_cold_rehydrated_days: 7,
+                api_token_stale_days: 0,
+                api_token_stale_warning_days: 7,
+                repository_key_alias_ttl_days: 90,
+                access_log_retention_days: 14,
             }
         }
 
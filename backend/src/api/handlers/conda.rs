@@ -2217,13 +2217,17 @@ async fn build_repodata(
 /// Merge package maps from a source into an accumulator using first-writer-wins.
 ///
 /// Entries already present in the accumulator are not overwritten, so higher-priority
-/// members (inserted first) win on conflicts.
+/// members (inserted first) win on conflicts. Only keys `keep` accepts are merged,
+/// which is how a member's routing patterns hide entries it may not serve.
 fn merge_package_maps(
     target: &mut serde_json::Map<String, serde_json::Value>,
     source: &serde_json::Map<String, serde_json::Value>,
+    keep: impl Fn(&str) -> bool,
 ) {
     for (k, v) in source {
-        target.entry(k.clone()).or_insert(v.clone());
+        if keep(k) {
+            target.entry(k.clone()).or_insert(v.clone());
+        }
     }
 }
 
@@ -2279,13 +2283,31 @@ fn build_channeldata_entry(
     })
 }
 
+/// The `{subdir}/{name}-` prefix shared by the download paths of every build
+/// of package `name` in `subdir`. Channeldata entries describe a package
+/// rather than one file, so virtual member routing is checked against it.
+fn package_route_prefix(subdir: &str, name: &str) -> String {
+    format!("{}/{}-", subdir, name)
+}
+
+/// Subdir of a stored conda artifact: the recorded `subdir` metadata, else the
+/// first segment of its path (see [`artifacts_for_subdir`]).
+fn artifact_subdir(artifact: &CondaArtifact) -> &str {
+    artifact
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("subdir").and_then(|v| v.as_str()))
+        .unwrap_or_else(|| artifact.path.split('/').next().unwrap_or("noarch"))
+}
+
 /// Build merged repodata.json for a virtual repository by combining member repos.
 ///
 /// Members are iterated in priority order (from `virtual_repo_members` table).
 /// For hosted/local members, we query their artifacts directly. For remote members,
 /// we proxy their upstream repodata and parse it. The merge uses first-writer-wins
 /// semantics: if two members provide the same filename, the higher-priority member
-/// (lower priority number) wins.
+/// (lower priority number) wins. A member never contributes a file its routing
+/// patterns keep it from serving at `{subdir}/{filename}`.
 async fn build_virtual_repodata(
     db: &sqlx::PgPool,
     proxy_service: Option<&crate::services::proxy_service::ProxyService>,
@@ -2297,6 +2319,10 @@ async fn build_virtual_repodata(
         .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid subdir: {}", e)).into_response())?;
 
     let members = proxy_helpers::fetch_virtual_members(db, virtual_repo_id).await?;
+    let routes = proxy_helpers::fetch_virtual_member_routes(db, virtual_repo_id).await?;
+    let allowed = |member_id: uuid::Uuid, filename: &str| {
+        routes.allows(member_id, &format!("{}/{}", subdir, filename))
+    };
 
     let mut merged_packages = serde_json::Map::new();
     let mut merged_packages_conda = serde_json::Map::new();
@@ -2316,9 +2342,10 @@ async fn build_virtual_repodata(
     )
     .await?;
 
-    for (_member_key, (pkgs, pkgs_conda)) in &remote_data {
-        merge_package_maps(&mut merged_packages, pkgs);
-        merge_package_maps(&mut merged_packages_conda, pkgs_conda);
+    for (member_id, (pkgs, pkgs_conda)) in &remote_data {
+        let keep = |filename: &str| allowed(*member_id, filename);
+        merge_package_maps(&mut merged_packages, pkgs, keep);
+        merge_package_maps(&mut merged_packages_conda, pkgs_conda, keep);
     }
 
     // Handle hosted/local members
@@ -2329,7 +2356,7 @@ async fn build_virtual_repodata(
 
             for artifact in &subdir_artifacts {
                 let filename = artifact.path.rsplit('/').next().unwrap_or(&artifact.path);
-                if !is_conda_package(filename) {
+                if !is_conda_package(filename) || !allowed(member.id, filename) {
                     continue;
                 }
                 let entry = build_artifact_entry(artifact, filename, subdir);
@@ -2359,12 +2386,16 @@ async fn build_virtual_repodata(
 }
 
 /// Build merged channeldata.json for a virtual repository.
+///
+/// A member only lists a package when its routing patterns allow the
+/// package's [`package_route_prefix`] in at least one of its subdirs.
 async fn build_virtual_channeldata(
     db: &sqlx::PgPool,
     proxy_service: Option<&crate::services::proxy_service::ProxyService>,
     virtual_repo_id: uuid::Uuid,
 ) -> Result<serde_json::Value, Response> {
     let members = proxy_helpers::fetch_virtual_members(db, virtual_repo_id).await?;
+    let routes = proxy_helpers::fetch_virtual_member_routes(db, virtual_repo_id).await?;
 
     let mut merged_packages = serde_json::Map::new();
 
@@ -2386,8 +2417,20 @@ async fn build_virtual_channeldata(
     )
     .await?;
 
-    for (_member_key, pkgs) in &remote_data {
-        merge_package_maps(&mut merged_packages, pkgs);
+    for (member_id, pkgs) in &remote_data {
+        merge_package_maps(&mut merged_packages, pkgs, |name| {
+            let subdirs = pkgs
+                .get(name)
+                .and_then(|entry| entry.get("subdirs"))
+                .and_then(|v| v.as_array());
+            match subdirs {
+                Some(subdirs) => subdirs
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .any(|subdir| routes.allows(*member_id, &package_route_prefix(subdir, name))),
+                None => routes.allows(*member_id, &package_route_prefix("noarch", name)),
+            }
+        });
     }
 
     // Handle hosted/local members
@@ -2405,6 +2448,12 @@ async fn build_virtual_channeldata(
                     .and_then(|m| m.get("name").and_then(|v| v.as_str()))
                     .map(|s| s.to_string())
                     .unwrap_or_else(|| artifact.name.clone());
+                if !routes.allows(
+                    member.id,
+                    &package_route_prefix(artifact_subdir(artifact), &pkg_name),
+                ) {
+                    continue;
+                }
 
                 merged_packages.entry(pkg_name).or_insert_with(|| {
                     build_channeldata_entry(artifact.version.as_deref(), artifact.metadata.as_ref())
@@ -6861,7 +6910,7 @@ mod tests {
         let mut source = serde_json::Map::new();
         source.insert("b".into(), serde_json::json!(2));
 
-        merge_package_maps(&mut target, &source);
+        merge_package_maps(&mut target, &source, |_| true);
         assert_eq!(target.len(), 2);
         assert_eq!(target["a"], 1);
         assert_eq!(target["b"], 2);
@@ -6875,7 +6924,7 @@ mod tests {
         let mut source = serde_json::Map::new();
         source.insert("pkg".into(), serde_json::json!({"version": "2.0"}));
 
-        merge_package_maps(&mut target, &source);
+        merge_package_maps(&mut target, &source, |_| true);
         assert_eq!(target.len(), 1);
         assert_eq!(target["pkg"]["version"], "1.0");
     }
@@ -6886,7 +6935,7 @@ mod tests {
         target.insert("a".into(), serde_json::json!(1));
 
         let source = serde_json::Map::new();
-        merge_package_maps(&mut target, &source);
+        merge_package_maps(&mut target, &source, |_| true);
         assert_eq!(target.len(), 1);
     }
 
@@ -6898,7 +6947,7 @@ mod tests {
         source.insert("a".into(), serde_json::json!(1));
         source.insert("b".into(), serde_json::json!(2));
 
-        merge_package_maps(&mut target, &source);
+        merge_package_maps(&mut target, &source, |_| true);
         assert_eq!(target.len(), 2);
     }
 
@@ -6912,13 +6961,32 @@ mod tests {
         source.insert("b".into(), serde_json::json!("source_b"));
         source.insert("c".into(), serde_json::json!("source_c"));
 
-        merge_package_maps(&mut target, &source);
+        merge_package_maps(&mut target, &source, |_| true);
         assert_eq!(target.len(), 3);
         assert_eq!(target["a"], "target_a");
         assert_eq!(target["b"], "target_b"); // target wins
         assert_eq!(target["c"], "source_c");
     }
 
+    #[test]
+    fn test_merge_package_maps_skips_rejected_keys() {
+        let mut target = serde_json::Map::new();
+        let mut source = serde_json::Map::new();
+        source.insert("mycorp-1.0-0.conda".into(), serde_json::json!("internal"));
+        source.insert("numpy-1.0-0.conda".into(), serde_json::json!("public"));
+
+        merge_package_maps(&mut target, &source, |k| !k.starts_with("mycorp-"));
+        assert_eq!(target.len(), 1);
+        assert_eq!(target["numpy-1.0-0.conda"], "public");
+    }
+
+    #[test]
+    fn test_package_route_prefix_is_prefix_of_download_paths() {
+        let prefix = package_route_prefix("linux-64", "mycorp-lib");
+        assert_eq!(prefix, "linux-64/mycorp-lib-");
+        assert!("linux-64/mycorp-lib-1.0-0.conda".starts_with(&prefix));
+    }
+
     #[test]
     fn test_parse_upstream_repodata_both_sections() {
         let content = serde_json::to_vec(&serde_json::json!({
@@ -7051,19 +7119,19 @@ mod tests {
         let mut m1 = serde_json::Map::new();
         m1.insert("shared".into(), serde_json::json!({"from": "m1"}));
         m1.insert("only_m1".into(), serde_json::json!({"from": "m1"}));
-        merge_package_maps(&mut merged, &m1);
+        merge_package_maps(&mut merged, &m1, |_| true);
 
         // Member 2
         let mut m2 = serde_json::Map::new();
         m2.insert("shared".into(), serde_json::json!({"from": "m2"}));
         m2.insert("only_m2".into(), serde_json::json!({"from": "m2"}));
-        merge_package_maps(&mut merged, &m2);
+        merge_package_maps(&mut merged, &m2, |_| true);
 
         // Member 3 (lowest priority)
         let mut m3 = serde_json::Map::new();
         m3.insert("shared".into(), serde_json::json!({"from": "m3"}));
         m3.insert("only_m3".into(), serde_json::json!({"from": "m3"}));
-        merge_package_maps(&mut merged, &m3);
+        merge_package_maps(&mut merged, &m3, |_| true);
 
         assert_eq!(merged.len(), 4);
         assert_eq!(merged["shared"]["from"], "m1"); // highest priority wins
@@ -66,19 +66,49 @@ async fn resolve_cran_repo(db: &PgPool, repo_key: &str) -> Result<RepoInfo, Resp
     proxy_helpers::resolve_repo_by_key(db, repo_key, &["cran"], "a CRAN").await
 }
 
+/// Keep only the stanzas of a PACKAGES index whose source tarball path
+/// (`src/contrib/{Package}_{Version}.tar.gz`) `keep` accepts. Stanzas missing
+/// either field cannot be downloaded and are passed through unchanged.
+fn filter_packages_stanzas(index: &str, keep: impl Fn(&str) -> bool) -> String {
+    index
+        .split("\n\n")
+        .filter(|stanza| {
+            let field = |name: &str| {
+                stanza.lines().find_map(|line| {
+                    line.strip_prefix(name)
+                        .and_then(|rest| rest.strip_prefix(':'))
+                        .map(str::trim)
+                })
+            };
+            match (field("Package"), field("Version")) {
+                (Some(package), Some(version)) => {
+                    keep(&format!("src/contrib/{}_{}.tar.gz", package, version))
+                }
+                _ => true,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 /// Build a combined PACKAGES index from all virtual repository members.
 /// Collects local member indexes via `build_source_index` and remote
 /// member indexes via proxy, concatenating them with newline separators.
+/// Each member's stanzas are limited to what its routing patterns let it serve.
 async fn build_virtual_combined_index(
     state: &SharedState,
     virtual_repo_id: uuid::Uuid,
 ) -> Result<String, Response> {
     let members = proxy_helpers::fetch_virtual_members(&state.db, virtual_repo_id).await?;
+    let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, virtual_repo_id).await?;
     let mut combined = String::new();
 
     for member in &members {
         if member.repo_type != RepositoryType::Remote {
-            let local_index = build_source_index(&state.db, member.id).await?;
+            let local_index =
+                filter_packages_stanzas(&build_source_index(&state.db, member.id).await?, |path| {
+                    routes.allows(member.id, path)
+                });
             if !local_index.is_empty() {
                 if !combined.is_empty() {
                     combined.push('\n');
@@ -101,7 +131,9 @@ async fn build_virtual_combined_index(
     )
     .await?;
 
-    for (_key, remote_index) in remote_indexes {
+    for (member_id, remote_index) in remote_indexes {
+        let remote_index =
+            filter_packages_stanzas(&remote_index, |path| routes.allows(member_id, path));
         if !remote_index.is_empty() {
             if !combined.is_empty() {
                 combined.push('\n');
@@ -472,6 +504,19 @@ mod tests {
     use flate2::read::GzDecoder;
     use std::io::Read;
 
+    // -----------------------------------------------------------------------
+    // filter_packages_stanzas
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_filter_packages_stanzas_by_tarball_path() {
+        let index = "Package: mycorpr\nVersion: 1.0.0\n\nPackage: ggplot2\nVersion: 3.4.0\n";
+        let filtered =
+            filter_packages_stanzas(index, |path| path != "src/contrib/mycorpr_1.0.0.tar.gz");
+        assert_eq!(filtered, "Package: ggplot2\nVersion: 3.4.0\n");
+        assert_eq!(filter_packages_stanzas(index, |_| true), index);
+    }
+
     // -----------------------------------------------------------------------
     // extract_credentials — Bearer token
     // -----------------------------------------------------------------------
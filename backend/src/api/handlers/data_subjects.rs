@@ -0,0 +1,192 @@
+//! GDPR data-subject erasure administration.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/data-subjects)
+//! POST   /erasures                  → erase_subject
+//! GET    /erasures                  → list_erasures
+//! GET    /erasures/:id              → get_erasure
+//! ```
+//!
+//! See [`crate::services::data_subject_service`] for what each mode touches.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::data_subject_service::{
+    DataSubjectRequest, DataSubjectService, ErasureMode, ErasureReport, TableAction, TableReport,
+};
+
+/// Data-subject routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/erasures", get(list_erasures).post(erase_subject))
+        .route("/erasures/:id", get(get_erasure))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EraseSubjectRequest {
+    /// User whose personal data is erased.
+    pub user_id: Uuid,
+    pub mode: ErasureMode,
+    /// Report what would be touched without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Why the erasure was performed, e.g. a ticket reference. Stored with
+    /// the request record; must not itself contain the subject's details.
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListErasuresQuery {
+    /// Maximum records to return (default 100, max 1000).
+    pub limit: Option<i64>,
+}
+
+/// POST /api/v1/admin/data-subjects/erasures
+#[utoipa::path(
+    post,
+    path = "/erasures",
+    context_path = "/api/v1/admin/data-subjects",
+    tag = "data_subjects",
+    request_body = EraseSubjectRequest,
+    responses(
+        (status = 200, description = "Dry-run report", body = ErasureReport),
+        (status = 201, description = "Erasure performed", body = ErasureReport),
+        (status = 400, description = "Cannot erase this user", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "User not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn erase_subject(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<EraseSubjectRequest>,
+) -> Result<(StatusCode, Json<ErasureReport>)> {
+    auth.require_admin()?;
+    if payload.user_id == auth.user_id {
+        return Err(AppError::Validation("Cannot erase yourself".to_string()));
+    }
+    let reason = payload
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    let report = DataSubjectService::new(state.db.clone())
+        .erase(
+            payload.user_id,
+            payload.mode,
+            payload.dry_run,
+            reason,
+            Some(auth.user_id),
+        )
+        .await?;
+    if report.dry_run {
+        return Ok((StatusCode::OK, Json(report)));
+    }
+
+    if report.mode == ErasureMode::Delete {
+        state.event_bus.emit(
+            "user.deleted",
+            report.subject_user_id,
+            Some(auth.username.clone()),
+        );
+    }
+    tracing::info!(
+        request_id = ?report.request_id,
+        mode = report.mode.as_str(),
+        rows = report.rows_affected,
+        requested_by = %auth.username,
+        "Data subject erased"
+    );
+    let _ = AuditService::new(state.db.clone())
+        .log(
+            AuditEntry::new(AuditAction::DataSubjectErased, ResourceType::User)
+                .user(auth.user_id)
+                .resource(report.subject_user_id)
+                .actor_name(auth.username.clone())
+                .details(serde_json::json!({
+                    "request_id": report.request_id,
+                    "mode": report.mode.as_str(),
+                    "tables": report.tables.len(),
+                    "rows_affected": report.rows_affected,
+                })),
+        )
+        .await;
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// GET /api/v1/admin/data-subjects/erasures
+#[utoipa::path(
+    get,
+    path = "/erasures",
+    context_path = "/api/v1/admin/data-subjects",
+    tag = "data_subjects",
+    params(ListErasuresQuery),
+    responses(
+        (status = 200, description = "Recorded erasures, newest first", body = Vec<DataSubjectRequest>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_erasures(
+    State(state): State<SharedState>,
+    Query(query): Query<ListErasuresQuery>,
+) -> Result<Json<Vec<DataSubjectRequest>>> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    Ok(Json(
+        DataSubjectService::new(state.db.clone())
+            .list_requests(limit)
+            .await?,
+    ))
+}
+
+/// GET /api/v1/admin/data-subjects/erasures/{id}
+#[utoipa::path(
+    get,
+    path = "/erasures/{id}",
+    context_path = "/api/v1/admin/data-subjects",
+    tag = "data_subjects",
+    params(("id" = Uuid, Path, description = "Erasure request ID")),
+    responses(
+        (status = 200, description = "Erasure record with its table report", body = DataSubjectRequest),
+        (status = 404, description = "Erasure request not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_erasure(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DataSubjectRequest>> {
+    Ok(Json(
+        DataSubjectService::new(state.db.clone())
+            .get_request(id)
+            .await?,
+    ))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(erase_subject, list_erasures, get_erasure),
+    components(schemas(
+        EraseSubjectRequest,
+        ErasureReport,
+        ErasureMode,
+        TableReport,
+        TableAction,
+        DataSubjectRequest,
+    ))
+)]
+pub struct DataSubjectsApiDoc;
@@ -16,13 +16,18 @@
 //!   GET  /debian/{repo_key}/pool/{component}/*path                                  - Download .deb
 //!   PUT  /debian/{repo_key}/pool/{component}/*path                                  - Upload .deb
 //!   POST /debian/{repo_key}/upload                                                  - Upload .deb (raw body)
+//!
+//! Uploads take an optional `?distribution=bookworm[,trixie]` query parameter
+//! (the raw upload also `?component=`). A package uploaded with distributions
+//! is indexed only in those distributions' `Release`/`Packages`; one uploaded
+//! without is indexed in every distribution.
 
 use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::io::{self, Read, Write};
 
 use axum::body::Body;
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
@@ -713,10 +718,60 @@ fn package_matches_requested_arch(package_arch: &str, requested_arch: &str) -> b
     }
 }
 
-/// Fetch all package entries for a given repo, component, and architecture.
+/// Whether a package belongs to `distribution`: packages uploaded without a
+/// distribution belong to all of them.
+fn package_in_distribution(metadata: Option<&serde_json::Value>, distribution: &str) -> bool {
+    match metadata
+        .and_then(|m| m.get("distributions"))
+        .and_then(|d| d.as_array())
+    {
+        Some(distributions) if !distributions.is_empty() => distributions
+            .iter()
+            .any(|d| d.as_str() == Some(distribution)),
+        _ => true,
+    }
+}
+
+/// Parse the `distribution` upload parameter: a comma-separated list of
+/// suite/codename names.
+#[allow(clippy::result_large_err)]
+fn parse_upload_distributions(raw: Option<&str>) -> Result<Vec<String>, Response> {
+    let mut distributions: Vec<String> = Vec::new();
+    for name in raw.unwrap_or_default().split(',').map(str::trim) {
+        if name.is_empty() {
+            continue;
+        }
+        if !is_valid_archive_name(name) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Invalid Debian distribution name '{}'", name),
+            )
+                .into_response());
+        }
+        if !distributions.iter().any(|d| d == name) {
+            distributions.push(name.to_string());
+        }
+    }
+    Ok(distributions)
+}
+
+/// Distribution and component names are single path segments under
+/// `dists/`: letters, digits, `.`, `_`, `+` and `-`.
+fn is_valid_archive_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '+' | '-'))
+}
+
+/// Fetch the package entries of a distribution's component and
+/// architecture.
 async fn fetch_package_entries(
     db: &PgPool,
     repo_id: uuid::Uuid,
+    distribution: &str,
     component: &str,
     arch: &str,
 ) -> Result<Vec<PackageEntry>, Response> {
@@ -741,6 +796,9 @@ async fn fetch_package_entries(
     let mut entries = Vec::new();
     for a in &artifacts {
         let (path, size_bytes, checksum_sha256, checksum_sha1, checksum_md5, metadata) = a;
+        if !package_in_distribution(metadata.as_ref(), distribution) {
+            continue;
+        }
         let filename = path.rsplit('/').next().unwrap_or(path);
         let deb_info = match parse_deb_filename(filename) {
             Some(info) => info,
@@ -1096,14 +1154,16 @@ async fn generate_release_content(
     repo_id: uuid::Uuid,
     distribution: &str,
 ) -> Result<String, Response> {
-    let (components, architectures) = discover_release_layout(&state.db, repo_id).await?;
+    let (components, architectures) =
+        discover_release_layout(&state.db, repo_id, distribution).await?;
     let component_str = components.iter().cloned().collect::<Vec<_>>().join(" ");
     let arch_str = architectures.iter().cloned().collect::<Vec<_>>().join(" ");
 
     let mut release_files = Vec::new();
     for component in &components {
         for arch in &architectures {
-            let entries = fetch_package_entries(&state.db, repo_id, component, arch).await?;
+            let entries =
+                fetch_package_entries(&state.db, repo_id, distribution, component, arch).await?;
             let packages_text = build_packages_text(&entries);
             let packages_bytes = packages_text.into_bytes();
             let packages_path = format!("{}/binary-{}/Packages", component, arch);
@@ -1160,6 +1220,7 @@ async fn generate_release_content(
 async fn discover_release_layout(
     db: &PgPool,
     repo_id: uuid::Uuid,
+    distribution: &str,
 ) -> Result<(BTreeSet<String>, BTreeSet<String>), Response> {
     let artifacts: Vec<(String, Option<serde_json::Value>)> = sqlx::query_as(
         r#"
@@ -1181,6 +1242,9 @@ async fn discover_release_layout(
 
     for artifact in &artifacts {
         let (path, metadata) = artifact;
+        if !package_in_distribution(metadata.as_ref(), distribution) {
+            continue;
+        }
         if let Some(component) = metadata
             .as_ref()
             .and_then(|m| json_string(m, "component"))
@@ -1909,7 +1973,12 @@ async fn try_virtual_dists(
     default_content_type: &'static str,
 ) -> Result<Option<Response>, Response> {
     let _ = virtual_repo_key;
-    let members = proxy_helpers::fetch_virtual_members(&state.db, virtual_repo_id).await?;
+    // Signed `dists/` files are passed through byte-for-byte (their signature
+    // covers the whole file), so they cannot be filtered per entry; route the
+    // file itself instead.
+    let members =
+        proxy_helpers::fetch_virtual_members_for_path(&state.db, virtual_repo_id, upstream_path)
+            .await?;
     let Some(proxy) = state.proxy_service.as_deref() else {
         return Ok(None);
     };
@@ -2037,7 +2106,9 @@ async fn try_virtual_dists_detecting_change(
     default_content_type: &'static str,
 ) -> Result<Option<Response>, Response> {
     let _ = virtual_repo_key;
-    let members = proxy_helpers::fetch_virtual_members(&state.db, virtual_repo_id).await?;
+    let members =
+        proxy_helpers::fetch_virtual_members_for_path(&state.db, virtual_repo_id, upstream_path)
+            .await?;
     let Some(proxy) = state.proxy_service.as_deref() else {
         return Ok(None);
     };
@@ -2366,7 +2437,8 @@ async fn packages_index(
 
     let arch = strip_binary_arch_prefix(&binary_arch);
 
-    let entries = fetch_package_entries(&state.db, repo.id, &component, arch).await?;
+    let entries =
+        fetch_package_entries(&state.db, repo.id, &distribution, &component, arch).await?;
     let text = build_packages_text(&entries);
 
     Ok(Response::builder()
@@ -2393,7 +2465,8 @@ async fn packages_index_gz(
 
     let arch = strip_binary_arch_prefix(&binary_arch);
 
-    let entries = fetch_package_entries(&state.db, repo.id, &component, arch).await?;
+    let entries =
+        fetch_package_entries(&state.db, repo.id, &distribution, &component, arch).await?;
     let text = build_packages_text(&entries);
 
     let compressed = gzip_compress(text.as_bytes()).map_err(|e| {
@@ -2428,7 +2501,8 @@ async fn packages_index_xz(
 
     let arch = strip_binary_arch_prefix(&binary_arch);
 
-    let entries = fetch_package_entries(&state.db, repo.id, &component, arch).await?;
+    let entries =
+        fetch_package_entries(&state.db, repo.id, &distribution, &component, arch).await?;
 
     let compressed = build_packages_xz(&entries).map_err(|e| {
         (
@@ -2753,7 +2827,13 @@ async fn pool_download(
                 // fetch would 404, while members that DO allow the path still
                 // aggregate. Fail-closed per member: a filter-load error skips
                 // only that member, it does not fail the whole request open.
-                let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+                let upstream_path = format!("pool/{}/{}", component, path);
+                let members = proxy_helpers::fetch_virtual_members_for_path(
+                    &state.db,
+                    repo.id,
+                    &upstream_path,
+                )
+                .await?;
                 let mut allowed_members = Vec::with_capacity(members.len());
                 for member in members {
                     match remote_member_upstream(&member) {
@@ -2775,7 +2855,6 @@ async fn pool_download(
                 }
 
                 let db = state.db.clone();
-                let upstream_path = format!("pool/{}/{}", component, path);
                 let artifact_path_clone = artifact_path.clone();
                 let result = proxy_helpers::resolve_virtual_download_from_members(
                     allowed_members,
@@ -2857,6 +2936,7 @@ fn prepare_debian_upload(
     component: &str,
     path: &str,
     body: &[u8],
+    distributions: &[String],
 ) -> Result<DebianPackageUpload, Response> {
     let filename = path.rsplit('/').next().unwrap_or(path);
     let deb_info = parse_deb_filename(filename).ok_or_else(|| {
@@ -2876,13 +2956,16 @@ fn prepare_debian_upload(
     validate_debian_control_matches_filename(&deb_info, &control)?;
 
     let artifact_path = format!("pool/{}/{}", component, path);
-    let metadata = build_debian_artifact_metadata(
+    let mut metadata = build_debian_artifact_metadata(
         component,
         &artifact_path,
         filename,
         &deb_info.package_type,
         &control,
     );
+    if !distributions.is_empty() {
+        metadata["distributions"] = serde_json::json!(distributions);
+    }
 
     Ok(DebianPackageUpload {
         artifact_path,
@@ -3037,7 +3120,7 @@ async fn persist_debian_upload(
             artifact.id,
             "debian",
             upload.metadata.clone(),
-            serde_json::json!({}),
+            crate::services::metadata_schema_service::upload_properties(),
         )
         .await
         .map_err(|e| e.into_response())?;
@@ -3068,10 +3151,20 @@ async fn persist_debian_upload(
 // PUT /debian/{repo_key}/pool/{component}/*path — Upload .deb
 // ---------------------------------------------------------------------------
 
+/// Query parameters of the upload endpoints.
+#[derive(Debug, Default, serde::Deserialize)]
+struct DebianUploadQuery {
+    /// Comma-separated distributions to publish the package in.
+    distribution: Option<String>,
+    /// Component for the raw upload endpoint (default `main`).
+    component: Option<String>,
+}
+
 async fn pool_upload(
     State(state): State<SharedState>,
     Extension(auth): Extension<Option<AuthExtension>>,
     Path((repo_key, component, path)): Path<(String, String, String)>,
+    Query(query): Query<DebianUploadQuery>,
     headers: HeaderMap,
     body: Bytes,
 ) -> Result<Response, Response> {
@@ -3081,7 +3174,8 @@ async fn pool_upload(
     proxy_helpers::reject_write_if_not_hosted(&repo.repo_type)?;
     repo.reject_if_promotion_only(false)?;
 
-    let upload = prepare_debian_upload(&component, &path, &body)?;
+    let distributions = parse_upload_distributions(query.distribution.as_deref())?;
+    let upload = prepare_debian_upload(&component, &path, &body, &distributions)?;
     persist_debian_upload(
         &state,
         &repo,
@@ -3115,6 +3209,7 @@ async fn upload_raw(
     State(state): State<SharedState>,
     Extension(auth): Extension<Option<AuthExtension>>,
     Path(repo_key): Path<String>,
+    Query(query): Query<DebianUploadQuery>,
     headers: HeaderMap,
     body: Bytes,
 ) -> Result<Response, Response> {
@@ -3155,13 +3250,21 @@ async fn upload_raw(
             .into_response()
     })?;
 
-    let component = "main";
+    let component = query.component.as_deref().unwrap_or("main");
+    if !is_valid_archive_name(component) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid Debian component name '{}'", component),
+        )
+            .into_response());
+    }
+    let distributions = parse_upload_distributions(query.distribution.as_deref())?;
     let artifact_path = DebianHandler::get_pool_path(component, &deb_info.name, &filename);
     let path = artifact_path
-        .strip_prefix("pool/main/")
+        .strip_prefix(&format!("pool/{}/", component))
         .unwrap_or(&artifact_path)
         .to_string();
-    let upload = prepare_debian_upload(component, &path, &body)?;
+    let upload = prepare_debian_upload(component, &path, &body, &distributions)?;
     let artifact = persist_debian_upload(
         &state,
         &repo,
@@ -4797,6 +4900,61 @@ mod upload_db_tests {
 
         f.teardown().await;
     }
+
+    #[test]
+    fn package_without_distributions_is_in_every_distribution() {
+        assert!(package_in_distribution(None, "bookworm"));
+        let meta = serde_json::json!({"component": "main"});
+        assert!(package_in_distribution(Some(&meta), "bookworm"));
+        let meta = serde_json::json!({"distributions": []});
+        assert!(package_in_distribution(Some(&meta), "trixie"));
+    }
+
+    #[test]
+    fn package_with_distributions_is_scoped_to_them() {
+        let meta = serde_json::json!({"distributions": ["bookworm", "trixie"]});
+        assert!(package_in_distribution(Some(&meta), "bookworm"));
+        assert!(package_in_distribution(Some(&meta), "trixie"));
+        assert!(!package_in_distribution(Some(&meta), "bullseye"));
+    }
+
+    #[test]
+    fn parse_upload_distributions_splits_trims_and_dedups() {
+        assert!(parse_upload_distributions(None).unwrap().is_empty());
+        assert!(parse_upload_distributions(Some("")).unwrap().is_empty());
+        assert_eq!(
+            parse_upload_distributions(Some("bookworm, trixie,,bookworm")).unwrap(),
+            vec!["bookworm".to_string(), "trixie".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_upload_distributions_rejects_path_like_names() {
+        for bad in ["../etc", "a/b", ".hidden", "sid stable"] {
+            let err = parse_upload_distributions(Some(bad)).unwrap_err();
+            assert_eq!(err.status(), StatusCode::BAD_REQUEST, "{bad}");
+        }
+    }
+
+    #[test]
+    fn prepare_debian_upload_records_distributions() {
+        let deb = minimal_deb("hello", "1.0", "amd64", "Hello");
+        let upload = prepare_debian_upload(
+            "main",
+            "h/hello/hello_1.0_amd64.deb",
+            &deb,
+            &["bookworm".to_string()],
+        )
+        .expect("valid deb");
+        assert_eq!(
+            upload.metadata["distributions"],
+            serde_json::json!(["bookworm"])
+        );
+
+        let upload = prepare_debian_upload("main", "h/hello/hello_1.0_amd64.deb", &deb, &[])
+            .expect("valid deb");
+        assert!(upload.metadata.get("distributions").is_none());
+    }
 }
 
 // ---------------------------------------------------------------------------
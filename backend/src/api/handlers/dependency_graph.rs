@@ -0,0 +1,177 @@
+//! Dependency graph query handlers.
+//!
+//! Answers "what depends on package X within this instance" (with transitive
+//! closure up to a depth limit) so the impact of deleting or deprecating X
+//! can be checked first. Results are limited to repositories the caller can
+//! see.
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::api::handlers::packages::repo_visibility_for;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::dependency_graph_service::{
+    DependencyGraphService, GraphNode, GraphQueryResult, MAX_GRAPH_DEPTH,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_dependents, get_dependencies),
+    components(schemas(GraphNode, GraphQueryResult)),
+    tags((name = "dependency-graph", description = "Reverse and transitive package dependency queries"))
+)]
+pub struct DependencyGraphApiDoc;
+
+/// Create dependency graph routes (nested under /api/v1/dependency-graph).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/dependents", get(get_dependents))
+        .route("/dependencies", get(get_dependencies))
+}
+
+/// Ecosystems accepted by the `ecosystem` parameter.
+const ECOSYSTEMS: &[&str] = &["npm", "cargo", "pypi", "maven", "helm"];
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GraphQuery {
+    /// One of `npm`, `cargo`, `pypi`, `maven`, `helm`.
+    pub ecosystem: String,
+    /// Package name. Maven packages are `groupId:artifactId`.
+    pub name: String,
+    /// Version to start from (`/dependencies` only; default newest stored).
+    pub version: Option<String>,
+    /// Traversal depth, 1 (direct only) to 10. Default 1.
+    pub depth: Option<u32>,
+}
+
+fn validate_query(query: &GraphQuery) -> Result<u32> {
+    if !ECOSYSTEMS.contains(&query.ecosystem.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Unsupported ecosystem '{}'; expected one of {}",
+            query.ecosystem,
+            ECOSYSTEMS.join(", ")
+        )));
+    }
+    if query.name.trim().is_empty() {
+        return Err(AppError::Validation("Package name is required".to_string()));
+    }
+    let depth = query.depth.unwrap_or(1);
+    if depth == 0 || depth > MAX_GRAPH_DEPTH {
+        return Err(AppError::Validation(format!(
+            "depth must be between 1 and {}",
+            MAX_GRAPH_DEPTH
+        )));
+    }
+    Ok(depth)
+}
+
+/// List packages that depend on a package
+///
+/// Walks reverse dependency edges: depth 1 returns packages declaring a
+/// direct dependency, deeper levels add packages depending on those.
+#[utoipa::path(
+    get,
+    path = "/dependents",
+    context_path = "/api/v1/dependency-graph",
+    tag = "dependency-graph",
+    params(GraphQuery),
+    responses(
+        (status = 200, description = "Dependent packages", body = GraphQueryResult),
+        (status = 400, description = "Invalid ecosystem, name or depth")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_dependents(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Query(query): Query<GraphQuery>,
+) -> Result<Json<GraphQueryResult>> {
+    let depth = validate_query(&query)?;
+    let visibility = repo_visibility_for(auth.as_ref());
+    let result = DependencyGraphService::new(state.db.clone())
+        .dependents(&query.ecosystem, query.name.trim(), depth, &visibility)
+        .await?;
+    Ok(Json(result))
+}
+
+/// List the dependencies of a package
+///
+/// Walks declared dependencies forward, resolving each dependency to its
+/// newest stored version. Dependencies not stored in this instance are
+/// returned as leaves with a nil `artifact_id`.
+#[utoipa::path(
+    get,
+    path = "/dependencies",
+    context_path = "/api/v1/dependency-graph",
+    tag = "dependency-graph",
+    params(GraphQuery),
+    responses(
+        (status = 200, description = "Dependencies", body = GraphQueryResult),
+        (status = 400, description = "Invalid ecosystem, name or depth")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_dependencies(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Query(query): Query<GraphQuery>,
+) -> Result<Json<GraphQueryResult>> {
+    let depth = validate_query(&query)?;
+    let visibility = repo_visibility_for(auth.as_ref());
+    let result = DependencyGraphService::new(state.db.clone())
+        .dependencies(
+            &query.ecosystem,
+            query.name.trim(),
+            query.version.as_deref(),
+            depth,
+            &visibility,
+        )
+        .await?;
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(ecosystem: &str, name: &str, depth: Option<u32>) -> GraphQuery {
+        GraphQuery {
+            ecosystem: ecosystem.to_string(),
+            name: name.to_string(),
+            version: None,
+            depth,
+        }
+    }
+
+    #[test]
+    fn test_validate_query_defaults_depth() {
+        assert_eq!(validate_query(&query("npm", "lodash", None)).unwrap(), 1);
+        assert_eq!(
+            validate_query(&query("maven", "org.slf4j:slf4j-api", Some(10))).unwrap(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_validate_query_rejects_bad_input() {
+        assert!(validate_query(&query("docker", "nginx", None)).is_err());
+        assert!(validate_query(&query("npm", "  ", None)).is_err());
+        assert!(validate_query(&query("npm", "lodash", Some(0))).is_err());
+        assert!(validate_query(&query("npm", "lodash", Some(MAX_GRAPH_DEPTH + 1))).is_err());
+    }
+
+    #[test]
+    fn test_ecosystems_match_service_mapping() {
+        use crate::services::dependency_graph_service::ecosystem_for_format;
+        for ecosystem in ECOSYSTEMS {
+            assert_eq!(ecosystem_for_format(ecosystem), Some(*ecosystem));
+        }
+    }
+}
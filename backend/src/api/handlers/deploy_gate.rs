@@ -0,0 +1,169 @@
+//! Pre-deploy verification gate.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Deploy gate (/api/v1/deploy-gate)
+//! POST /verify                      → verify
+//! POST /webhook                     → webhook
+//! ```
+//!
+//! Both take the same [`DeployGateRequest`] and return the same
+//! [`DeployGateResponse`]. `verify` always answers 200 and callers read
+//! `decision`; `webhook` answers 200 on `allow` and 412 on `deny`, so tools
+//! that only look at the status code (a Spinnaker webhook stage, `curl
+//! --fail` in an Argo CD PreSync job) gate on it directly.
+//!
+//! The caller only sees artifacts in repositories it can read; copies in
+//! other repositories are ignored rather than reported, and a target with no
+//! visible copy is denied.
+
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use chrono::Utc;
+use utoipa::OpenApi;
+
+use crate::api::handlers::artifacts::check_artifact_visibility;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::artifact_verification_service::ArtifactVerificationService;
+use crate::services::audit_export::Outcome;
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::deploy_gate_service::{
+    self, ArtifactGateResult, DeployGateRequest, DeployGateResponse, GateCheck, GateDecision,
+};
+use crate::services::signing_service::SigningService;
+
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/verify", post(verify))
+        .route("/webhook", post(webhook))
+}
+
+async fn evaluate(
+    state: &SharedState,
+    auth: &AuthExtension,
+    request: &DeployGateRequest,
+) -> Result<DeployGateResponse> {
+    let target = request.target()?;
+    let candidates = deploy_gate_service::resolve_artifacts(&state.db, &target).await?;
+
+    let signing = SigningService::new(state.db.clone(), &state.config.jwt_secret);
+    let verification = ArtifactVerificationService::new(state.db.clone(), signing);
+    let caller = Some(auth.clone());
+    let mut artifacts = Vec::with_capacity(candidates.len());
+    for artifact_id in candidates {
+        if check_artifact_visibility(&caller, artifact_id, &state.db)
+            .await
+            .is_err()
+        {
+            continue;
+        }
+        artifacts.push(
+            deploy_gate_service::evaluate_artifact(&state.db, &verification, artifact_id, request)
+                .await?,
+        );
+    }
+
+    let environment = request
+        .environment
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(str::to_string);
+    let response = deploy_gate_service::decide(artifacts, environment, Utc::now());
+
+    tracing::info!(
+        decision = ?response.decision,
+        artifacts = response.artifacts.len(),
+        environment = response.environment.as_deref().unwrap_or(""),
+        requested_by = %auth.username,
+        "Deploy gate evaluated"
+    );
+    let mut entry = AuditEntry::new(AuditAction::DeployGateEvaluated, ResourceType::Artifact)
+        .user(auth.user_id)
+        .actor_name(auth.username.clone())
+        .details(serde_json::json!({
+            "decision": response.decision,
+            "environment": response.environment,
+            "digest": request.digest,
+            "repository": request.repository,
+            "artifact_ids": response.artifacts.iter().map(|a| a.artifact_id).collect::<Vec<_>>(),
+            "reasons": response.reasons,
+        }));
+    if let Some(first) = response.artifacts.first() {
+        entry = entry.resource(first.artifact_id);
+    }
+    if !response.allowed {
+        entry = entry.outcome(Outcome::Denied);
+    }
+    let _ = AuditService::new(state.db.clone()).log(entry).await;
+
+    Ok(response)
+}
+
+/// Decide whether an artifact may be deployed
+#[utoipa::path(
+    post,
+    path = "/verify",
+    context_path = "/api/v1/deploy-gate",
+    tag = "deploy_gate",
+    request_body = DeployGateRequest,
+    responses(
+        (status = 200, description = "Decision (allow or deny) with per-artifact checks", body = DeployGateResponse),
+        (status = 400, description = "Invalid digest or coordinates", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn verify(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(request): Json<DeployGateRequest>,
+) -> Result<Json<DeployGateResponse>> {
+    Ok(Json(evaluate(&state, &auth, &request).await?))
+}
+
+/// Deployment webhook: 200 when the artifact may be deployed, 412 when not
+#[utoipa::path(
+    post,
+    path = "/webhook",
+    context_path = "/api/v1/deploy-gate",
+    tag = "deploy_gate",
+    request_body = DeployGateRequest,
+    responses(
+        (status = 200, description = "Deployment allowed", body = DeployGateResponse),
+        (status = 412, description = "Deployment denied", body = DeployGateResponse),
+        (status = 400, description = "Invalid digest or coordinates", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn webhook(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(request): Json<DeployGateRequest>,
+) -> Result<(StatusCode, Json<DeployGateResponse>)> {
+    let response = evaluate(&state, &auth, &request).await?;
+    let status = match response.decision {
+        GateDecision::Allow => StatusCode::OK,
+        GateDecision::Deny => StatusCode::PRECONDITION_FAILED,
+    };
+    Ok((status, Json(response)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(verify, webhook),
+    components(schemas(
+        DeployGateRequest,
+        DeployGateResponse,
+        ArtifactGateResult,
+        GateCheck,
+        GateDecision,
+    ))
+)]
+pub struct DeployGateApiDoc;
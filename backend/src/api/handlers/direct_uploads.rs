@@ -0,0 +1,538 @@
+//! Direct-to-storage artifact uploads.
+//!
+//! For storage backends that support it (S3 and S3-compatible services with
+//! `S3_DIRECT_UPLOADS=true`), large artifacts skip the API server on the way
+//! in:
+//!   POST /api/v1/artifacts/initiate-upload - Start a multipart upload and
+//!                                            get presigned part URLs
+//!   POST /api/v1/artifacts/complete-upload - Assemble the parts and register
+//!                                            the artifact
+//!   POST /api/v1/artifacts/abort-upload    - Discard an unfinished upload
+//!
+//! Clients `PUT` each part to its URL and report the returned `ETag`s on
+//! completion. The backend then reads the assembled object once to verify its
+//! size and SHA-256 and runs the same checks as a regular generic upload.
+
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Extension, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::handlers::proxy_helpers;
+use crate::api::handlers::repositories::{
+    authorize_generic_upload, persist_generic_staged_upload, ArtifactResponse,
+};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::direct_upload_service::{
+    self, DirectUpload, DirectUploadService, NewDirectUpload,
+};
+use crate::services::repository_service::RepositoryService;
+use crate::services::s3_gateway_service::is_sha256_hex;
+use crate::services::upload_service;
+use crate::storage::{StorageBackend, UploadedPart};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(initiate_upload, complete_upload, abort_upload),
+    components(schemas(
+        InitiateUploadRequest,
+        InitiateUploadResponse,
+        PresignedPartResponse,
+        CompleteUploadRequest,
+        CompletedPartRequest,
+        AbortUploadRequest,
+    )),
+    tags((name = "direct-uploads", description = "Direct-to-storage artifact uploads"))
+)]
+pub struct DirectUploadsApiDoc;
+
+/// Direct upload routes (nested under /api/v1/artifacts).
+pub fn direct_uploads_router() -> Router<SharedState> {
+    Router::new()
+        .route("/initiate-upload", post(initiate_upload))
+        .route("/complete-upload", post(complete_upload))
+        .route("/abort-upload", post(abort_upload))
+}
+
+// ---------------------------------------------------------------------------
+// Request / Response types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InitiateUploadRequest {
+    /// Repository key (e.g. "releases")
+    pub repository_key: String,
+    /// Artifact path within the repository (e.g. "tool/1.0/tool.tar.gz")
+    pub path: String,
+    /// Total size of the artifact in bytes
+    pub size: i64,
+    /// Expected SHA-256 of the complete artifact
+    pub checksum_sha256: String,
+    /// MIME content type (guessed from the path when omitted)
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InitiateUploadResponse {
+    pub upload_id: Uuid,
+    /// Size of every part except the last
+    pub part_size: i64,
+    /// One URL per part, in order. `PUT` the bytes of each part to its URL
+    /// and keep the `ETag` response header.
+    pub parts: Vec<PresignedPartResponse>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PresignedPartResponse {
+    pub part_number: u32,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CompleteUploadRequest {
+    pub upload_id: Uuid,
+    /// Every part, numbered from 1, with the `ETag` storage returned for it
+    pub parts: Vec<CompletedPartRequest>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CompletedPartRequest {
+    pub part_number: u32,
+    pub etag: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AbortUploadRequest {
+    pub upload_id: Uuid,
+}
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Parts sorted by number, rejecting a list that does not cover exactly
+/// `1..=part_count`.
+fn ordered_parts(
+    mut parts: Vec<CompletedPartRequest>,
+    part_count: i32,
+) -> Result<Vec<UploadedPart>> {
+    parts.sort_by_key(|p| p.part_number);
+    let complete = parts.len() == part_count as usize
+        && parts
+            .iter()
+            .enumerate()
+            .all(|(i, p)| p.part_number as usize == i + 1);
+    if !complete {
+        return Err(AppError::Validation(format!(
+            "Expected parts 1 to {}, each exactly once",
+            part_count
+        )));
+    }
+    if parts.iter().any(|p| p.etag.trim().is_empty()) {
+        return Err(AppError::Validation("Every part needs an etag".to_string()));
+    }
+    Ok(parts
+        .into_iter()
+        .map(|p| UploadedPart {
+            part_number: p.part_number,
+            etag: p.etag.trim().to_string(),
+        })
+        .collect())
+}
+
+/// Headers the generic upload tail reads the declared checksum and content
+/// type from.
+fn declared_upload_headers(upload: &DirectUpload) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&upload.checksum_sha256) {
+        headers.insert("x-checksum-sha256", value);
+    }
+    if let Some(value) = upload
+        .content_type
+        .as_deref()
+        .and_then(|ct| HeaderValue::from_str(ct).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    headers
+}
+
+fn unsupported_storage() -> AppError {
+    AppError::Validation(
+        "This repository's storage does not support direct uploads; \
+         upload through /api/v1/repositories/{key}/artifacts instead"
+            .to_string(),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// Start a direct upload and return presigned part URLs
+#[utoipa::path(
+    post,
+    path = "/initiate-upload",
+    context_path = "/api/v1/artifacts",
+    tag = "direct-uploads",
+    request_body = InitiateUploadRequest,
+    responses(
+        (status = 201, description = "Upload started", body = InitiateUploadResponse),
+        (status = 400, description = "Invalid request or storage without direct upload support", body = crate::api::openapi::ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "Forbidden", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn initiate_upload(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Json(req): Json<InitiateUploadRequest>,
+) -> std::result::Result<Response, Response> {
+    let auth = require_auth(auth).map_err(|e| e.into_response())?;
+    auth.require_scope("write").map_err(|e| e.into_response())?;
+    upload_service::validate_artifact_path(&req.path)
+        .map_err(|e| AppError::Validation(e.to_string()).into_response())?;
+    upload_service::enforce_max_total_size(req.size, state.config.max_upload_size_bytes)
+        .map_err(|e| AppError::Validation(e.to_string()).into_response())?;
+    let checksum = req.checksum_sha256.trim().to_ascii_lowercase();
+    if !is_sha256_hex(&checksum) {
+        return Err(
+            AppError::Validation("checksum_sha256 must be 64 hex characters".to_string())
+                .into_response(),
+        );
+    }
+    // Validates the size before anything is created in storage.
+    direct_upload_service::plan_parts(req.size).map_err(|e| e.into_response())?;
+
+    let (_, repo) = authorize_generic_upload(&state, &auth, &req.repository_key).await?;
+    let storage = state
+        .storage_for_repo(&repo.storage_location())
+        .map_err(|e| e.into_response())?;
+    let direct = storage
+        .direct_uploads()
+        .ok_or_else(|| unsupported_storage().into_response())?;
+
+    let id = Uuid::new_v4();
+    let staging_key = direct_upload_service::staging_key(id);
+    let multipart_upload_id = direct
+        .create_multipart_upload(&staging_key)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let now = Utc::now();
+    let expires_at = now + chrono::Duration::hours(direct_upload_service::SESSION_TTL_HOURS);
+    let service = DirectUploadService::new(state.db.clone());
+    let upload = match service
+        .create(NewDirectUpload {
+            id,
+            user_id: auth.user_id,
+            repository_id: repo.id,
+            artifact_path: &req.path,
+            content_type: req
+                .content_type
+                .as_deref()
+                .filter(|ct| !ct.trim().is_empty()),
+            total_size: req.size,
+            checksum_sha256: &checksum,
+            staging_key: &staging_key,
+            multipart_upload_id: &multipart_upload_id,
+            expires_at,
+        })
+        .await
+    {
+        Ok(upload) => upload,
+        Err(e) => {
+            let _ = direct
+                .abort_multipart_upload(&staging_key, &multipart_upload_id)
+                .await;
+            return Err(e.into_response());
+        }
+    };
+
+    let url_ttl = (expires_at - now).to_std().unwrap_or_default();
+    let mut parts = Vec::with_capacity(upload.part_count as usize);
+    for part_number in 1..=upload.part_count as u32 {
+        let url = direct
+            .presign_upload_part(&staging_key, &multipart_upload_id, part_number, url_ttl)
+            .await
+            .map_err(|e| e.into_response())?;
+        parts.push(PresignedPartResponse { part_number, url });
+    }
+
+    tracing::info!(
+        upload_id = %id,
+        repository = %repo.key,
+        path = %upload.artifact_path,
+        size = upload.total_size,
+        parts = upload.part_count,
+        "Direct upload started"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(InitiateUploadResponse {
+            upload_id: id,
+            part_size: upload.part_size,
+            parts,
+            expires_at: upload.expires_at,
+        }),
+    )
+        .into_response())
+}
+
+/// Assemble a direct upload and register it as an artifact
+#[utoipa::path(
+    post,
+    path = "/complete-upload",
+    context_path = "/api/v1/artifacts",
+    tag = "direct-uploads",
+    request_body = CompleteUploadRequest,
+    responses(
+        (status = 201, description = "Artifact registered", body = ArtifactResponse),
+        (status = 400, description = "Missing parts, expired upload or content mismatch", body = crate::api::openapi::ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Upload not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Upload already completed or aborted", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn complete_upload(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Json(req): Json<CompleteUploadRequest>,
+) -> std::result::Result<Response, Response> {
+    let auth = require_auth(auth).map_err(|e| e.into_response())?;
+    auth.require_scope("write").map_err(|e| e.into_response())?;
+
+    let service = DirectUploadService::new(state.db.clone());
+    let upload = service
+        .claim(req.upload_id, auth.user_id)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let result = finish_upload(&state, &auth, &upload, req.parts).await;
+    let recorded = if result.is_ok() {
+        service.mark_completed(upload.id).await
+    } else {
+        service.mark_failed(upload.id).await
+    };
+    if let Err(e) = recorded {
+        tracing::warn!(upload_id = %upload.id, "Failed to record direct upload outcome: {}", e);
+    }
+    result
+}
+
+/// Assemble, verify and register a claimed upload. The staged object is
+/// removed whatever the outcome; on failure the multipart upload is aborted.
+async fn finish_upload(
+    state: &SharedState,
+    auth: &AuthExtension,
+    upload: &DirectUpload,
+    parts: Vec<CompletedPartRequest>,
+) -> std::result::Result<Response, Response> {
+    // Re-check write access: it may have been revoked since the upload began.
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_id(upload.repository_id)
+        .await
+        .map_err(|e| e.into_response())?;
+    let (repo_service, repo) = authorize_generic_upload(state, auth, &repo.key).await?;
+    let storage = state
+        .storage_for_repo(&repo.storage_location())
+        .map_err(|e| e.into_response())?;
+    let direct = storage
+        .direct_uploads()
+        .ok_or_else(|| unsupported_storage().into_response())?;
+
+    let assembled = match ordered_parts(parts, upload.part_count) {
+        Ok(parts) => {
+            direct
+                .complete_multipart_upload(&upload.staging_key, &upload.multipart_upload_id, &parts)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    if let Err(e) = assembled {
+        let _ = direct
+            .abort_multipart_upload(&upload.staging_key, &upload.multipart_upload_id)
+            .await;
+        return Err(e.into_response());
+    }
+
+    let result = register_staged_object(state, auth, &repo_service, &repo, &*storage, upload).await;
+    if let Err(e) = storage.delete(&upload.staging_key).await {
+        tracing::warn!(upload_id = %upload.id, "Failed to remove staged direct upload: {}", e);
+    }
+    result
+}
+
+/// Read the assembled object back through the generic upload path, which
+/// verifies the declared checksum, applies the archive policy and stores the
+/// content under its content-addressed key.
+async fn register_staged_object(
+    state: &SharedState,
+    auth: &AuthExtension,
+    repo_service: &RepositoryService,
+    repo: &crate::models::repository::Repository,
+    storage: &dyn StorageBackend,
+    upload: &DirectUpload,
+) -> std::result::Result<Response, Response> {
+    let stream = storage
+        .get_stream(&upload.staging_key)
+        .await
+        .map_err(|e| e.into_response())?;
+    let (staged, digests) = proxy_helpers::stage_stream_content_addressed(state, stream).await?;
+    if staged.size_bytes() != upload.total_size {
+        return Err(AppError::Validation(format!(
+            "Uploaded size {} does not match declared size {}",
+            staged.size_bytes(),
+            upload.total_size
+        ))
+        .into_response());
+    }
+
+    persist_generic_staged_upload(
+        state,
+        auth,
+        repo_service,
+        repo,
+        repo.key.clone(),
+        upload.artifact_path.clone(),
+        &declared_upload_headers(upload),
+        staged,
+        digests,
+    )
+    .await
+}
+
+/// Abort an unfinished direct upload
+#[utoipa::path(
+    post,
+    path = "/abort-upload",
+    context_path = "/api/v1/artifacts",
+    tag = "direct-uploads",
+    request_body = AbortUploadRequest,
+    responses(
+        (status = 204, description = "Upload aborted"),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Upload not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Upload already completed or aborted", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn abort_upload(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Json(req): Json<AbortUploadRequest>,
+) -> Result<StatusCode> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+
+    let upload = DirectUploadService::new(state.db.clone())
+        .cancel(req.upload_id, auth.user_id)
+        .await?;
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_id(upload.repository_id)
+        .await?;
+    let storage = state.storage_for_repo(&repo.storage_location())?;
+    if let Some(direct) = storage.direct_uploads() {
+        direct
+            .abort_multipart_upload(&upload.staging_key, &upload.multipart_upload_id)
+            .await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(part_number: u32, etag: &str) -> CompletedPartRequest {
+        CompletedPartRequest {
+            part_number,
+            etag: etag.to_string(),
+        }
+    }
+
+    fn upload(content_type: Option<&str>) -> DirectUpload {
+        DirectUpload {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            repository_id: Uuid::new_v4(),
+            artifact_path: "tool/1.0/tool.tar.gz".to_string(),
+            content_type: content_type.map(str::to_string),
+            total_size: 10,
+            part_size: 16 * 1024 * 1024,
+            part_count: 1,
+            checksum_sha256: "ab".repeat(32),
+            staging_key: "direct-uploads/x".to_string(),
+            multipart_upload_id: "mp".to_string(),
+            status: "completing".to_string(),
+            artifact_id: None,
+            created_at: Utc::now(),
+            expires_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_ordered_parts_sorts_and_trims() {
+        let parts = ordered_parts(vec![part(2, " \"b\" "), part(1, "\"a\"")], 2).unwrap();
+        assert_eq!(
+            parts,
+            vec![
+                UploadedPart {
+                    part_number: 1,
+                    etag: "\"a\"".to_string()
+                },
+                UploadedPart {
+                    part_number: 2,
+                    etag: "\"b\"".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ordered_parts_rejects_missing_duplicate_and_extra_parts() {
+        assert!(ordered_parts(vec![part(1, "a")], 2).is_err());
+        assert!(ordered_parts(vec![part(1, "a"), part(1, "b")], 2).is_err());
+        assert!(ordered_parts(vec![part(1, "a"), part(3, "c")], 2).is_err());
+        assert!(ordered_parts(vec![part(1, "a"), part(2, "b"), part(3, "c")], 2).is_err());
+        assert!(ordered_parts(vec![part(0, "a")], 1).is_err());
+    }
+
+    #[test]
+    fn test_ordered_parts_rejects_empty_etag() {
+        assert!(ordered_parts(vec![part(1, "  ")], 1).is_err());
+    }
+
+    #[test]
+    fn test_declared_upload_headers() {
+        let headers = declared_upload_headers(&upload(Some("application/gzip")));
+        assert_eq!(headers["x-checksum-sha256"], "ab".repeat(32).as_str());
+        assert_eq!(headers[header::CONTENT_TYPE], "application/gzip");
+
+        let headers = declared_upload_headers(&upload(None));
+        assert!(headers.get(header::CONTENT_TYPE).is_none());
+    }
+
+    #[test]
+    fn test_complete_request_deserializes() {
+        let req: CompleteUploadRequest = serde_json::from_value(serde_json::json!({
+            "upload_id": "00000000-0000-0000-0000-000000000001",
+            "parts": [{"part_number": 1, "etag": "\"abc\""}]
+        }))
+        .unwrap();
+        assert_eq!(req.parts.len(), 1);
+        assert_eq!(req.parts[0].etag, "\"abc\"");
+    }
+}
@@ -0,0 +1,114 @@
+//! Edge node diagnostics handlers (admin).
+//!
+//! Serves the latest metrics and log excerpts an edge node forwarded through
+//! `POST /api/v1/peers/join/:id/diagnostics`, and the cache integrity reports
+//! it sent through `POST /api/v1/peers/join/:id/integrity`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+use uuid::Uuid;
+
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::cache_integrity_service::{self, CorruptEntry, PeerIntegrityReport};
+use crate::services::edge_diagnostics_service::{self, EdgeDiagnostics, LogExcerpt};
+
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/:id/diagnostics", get(get_diagnostics))
+        .route("/:id/diagnostics/metrics", get(get_diagnostics_metrics))
+        .route("/:id/integrity-reports", get(list_integrity_reports))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct IntegrityReportsQuery {
+    /// Number of reports to return (default 20, max 200).
+    pub limit: Option<i64>,
+}
+
+/// GET /api/v1/admin/edge-nodes/{id}/diagnostics
+#[utoipa::path(
+    get,
+    path = "/{id}/diagnostics",
+    context_path = "/api/v1/admin/edge-nodes",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Peer instance ID")),
+    responses(
+        (status = 200, description = "Latest diagnostics from the node", body = EdgeDiagnostics),
+        (status = 404, description = "No diagnostics received from this node"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_diagnostics(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<EdgeDiagnostics>> {
+    Ok(Json(edge_diagnostics_service::get(&state.db, id).await?))
+}
+
+/// GET /api/v1/admin/edge-nodes/{id}/diagnostics/metrics
+///
+/// The node's metrics as Prometheus text, e.g. for `promtool` or a one-off
+/// scrape.
+#[utoipa::path(
+    get,
+    path = "/{id}/diagnostics/metrics",
+    context_path = "/api/v1/admin/edge-nodes",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Peer instance ID")),
+    responses(
+        (status = 200, description = "Node metrics in Prometheus text format", content_type = "text/plain"),
+        (status = 404, description = "No diagnostics received from this node"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_diagnostics_metrics(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let diagnostics = edge_diagnostics_service::get(&state.db, id).await?;
+    Ok((
+        StatusCode::OK,
+        [("content-type", "text/plain; charset=utf-8")],
+        diagnostics.metrics,
+    ))
+}
+
+/// GET /api/v1/admin/edge-nodes/{id}/integrity-reports
+#[utoipa::path(
+    get,
+    path = "/{id}/integrity-reports",
+    context_path = "/api/v1/admin/edge-nodes",
+    tag = "peers",
+    params(
+        ("id" = Uuid, Path, description = "Peer instance ID"),
+        IntegrityReportsQuery,
+    ),
+    responses(
+        (status = 200, description = "Cache integrity reports, newest first", body = Vec<PeerIntegrityReport>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_integrity_reports(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<IntegrityReportsQuery>,
+) -> Result<Json<Vec<PeerIntegrityReport>>> {
+    let limit = query.limit.unwrap_or(20).clamp(1, 200);
+    let reports = cache_integrity_service::list_reports(&state.db, id, limit).await?;
+    Ok(Json(reports))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_diagnostics, get_diagnostics_metrics, list_integrity_reports),
+    components(schemas(EdgeDiagnostics, LogExcerpt, PeerIntegrityReport, CorruptEntry))
+)]
+pub struct EdgeDiagnosticsApiDoc;
@@ -0,0 +1,262 @@
+//! Compliance evidence exports.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/exports)
+//! GET    /datasets                  → list_datasets
+//! GET    /findings                  → export_findings
+//! GET    /policy-violations         → export_policy_violations
+//! GET    /audit-log                 → export_audit_log
+//! GET    /artifacts                 → export_artifacts
+//! ```
+//!
+//! Exports stream as CSV or JSON Lines; see
+//! [`crate::services::data_export_service`] for the columns of each dataset.
+
+use axum::{
+    body::Body,
+    extract::{Extension, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::data_export_service::{ExportDataset, ExportFilter, ExportFormat, ExportPlan};
+use crate::services::repository_service::RepositoryService;
+
+/// Export routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/datasets", get(list_datasets))
+        .route("/findings", get(export_findings))
+        .route("/policy-violations", get(export_policy_violations))
+        .route("/audit-log", get(export_audit_log))
+        .route("/artifacts", get(export_artifacts))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// `csv` (default) or `jsonl`.
+    pub format: Option<ExportFormat>,
+    /// Comma-separated column names, in output order. All columns when
+    /// omitted; see `GET /datasets`.
+    pub columns: Option<String>,
+    /// Only rows of this repository (key). Not supported for the audit log.
+    pub repository: Option<String>,
+    /// Only rows at or after this time (RFC 3339).
+    pub since: Option<DateTime<Utc>>,
+    /// Only rows before this time (RFC 3339).
+    pub until: Option<DateTime<Utc>>,
+    /// Maximum rows to export.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportDatasetInfo {
+    pub dataset: ExportDataset,
+    /// Exportable columns in default order.
+    pub columns: Vec<String>,
+}
+
+/// GET /api/v1/admin/exports/datasets
+#[utoipa::path(
+    get,
+    path = "/datasets",
+    context_path = "/api/v1/admin/exports",
+    tag = "exports",
+    responses(
+        (status = 200, description = "Exportable datasets and their columns", body = Vec<ExportDatasetInfo>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_datasets(
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<Vec<ExportDatasetInfo>>> {
+    auth.require_admin()?;
+    Ok(Json(
+        ExportDataset::ALL
+            .iter()
+            .map(|dataset| ExportDatasetInfo {
+                dataset: *dataset,
+                columns: dataset
+                    .column_names()
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            })
+            .collect(),
+    ))
+}
+
+/// GET /api/v1/admin/exports/findings
+#[utoipa::path(
+    get,
+    path = "/findings",
+    context_path = "/api/v1/admin/exports",
+    tag = "exports",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Scan findings (CSV or JSON Lines stream)", content_type = "text/csv", body = String),
+        (status = 400, description = "Unknown column or invalid filter", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn export_findings(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response> {
+    export(state, auth, ExportDataset::Findings, query).await
+}
+
+/// GET /api/v1/admin/exports/policy-violations
+#[utoipa::path(
+    get,
+    path = "/policy-violations",
+    context_path = "/api/v1/admin/exports",
+    tag = "exports",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Failed quality-gate rules (CSV or JSON Lines stream)", content_type = "text/csv", body = String),
+        (status = 400, description = "Unknown column or invalid filter", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn export_policy_violations(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response> {
+    export(state, auth, ExportDataset::PolicyViolations, query).await
+}
+
+/// GET /api/v1/admin/exports/audit-log
+#[utoipa::path(
+    get,
+    path = "/audit-log",
+    context_path = "/api/v1/admin/exports",
+    tag = "exports",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Live audit-log entries (CSV or JSON Lines stream)", content_type = "text/csv", body = String),
+        (status = 400, description = "Unknown column or invalid filter", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn export_audit_log(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response> {
+    export(state, auth, ExportDataset::AuditLog, query).await
+}
+
+/// GET /api/v1/admin/exports/artifacts
+#[utoipa::path(
+    get,
+    path = "/artifacts",
+    context_path = "/api/v1/admin/exports",
+    tag = "exports",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Artifact inventory (CSV or JSON Lines stream)", content_type = "text/csv", body = String),
+        (status = 400, description = "Unknown column or invalid filter", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn export_artifacts(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response> {
+    export(state, auth, ExportDataset::Artifacts, query).await
+}
+
+async fn export(
+    state: SharedState,
+    auth: AuthExtension,
+    dataset: ExportDataset,
+    query: ExportQuery,
+) -> Result<Response> {
+    auth.require_admin()?;
+    let repository_id = match query.repository.as_deref() {
+        Some(key) => Some(
+            RepositoryService::new(state.db.clone())
+                .get_by_key(key)
+                .await?
+                .id,
+        ),
+        None => None,
+    };
+    let filter = ExportFilter {
+        since: query.since,
+        until: query.until,
+        repository_id,
+        limit: query.limit,
+    };
+    let format = query.format.unwrap_or_default();
+    let plan = ExportPlan::new(dataset, format, query.columns.as_deref(), filter)?;
+
+    // Exports can carry the whole audit trail, so reading one is itself
+    // audited.
+    let _ = AuditService::new(state.db.clone())
+        .log(
+            AuditEntry::new(AuditAction::DataExported, ResourceType::Export)
+                .user(auth.user_id)
+                .actor_name(auth.username.clone())
+                .details(serde_json::json!({
+                    "dataset": dataset.as_str(),
+                    "format": format.extension(),
+                    "columns": plan.column_names(),
+                    "repository": query.repository,
+                    "since": query.since,
+                    "until": query.until,
+                    "limit": query.limit,
+                })),
+        )
+        .await;
+
+    let filename = format!(
+        "{}-{}.{}",
+        dataset.as_str(),
+        Utc::now().format("%Y%m%dT%H%M%SZ"),
+        format.extension()
+    );
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(plan.stream(state.db.clone())),
+    )
+        .into_response())
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_datasets,
+        export_findings,
+        export_policy_violations,
+        export_audit_log,
+        export_artifacts,
+    ),
+    components(schemas(ExportDatasetInfo, ExportDataset, ExportFormat))
+)]
+pub struct ExportsApiDoc;
@@ -0,0 +1,640 @@
+//! Instance federation: identity exchange and trusted-peer administration.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Public (/api/v1/federation)
+//! GET    /identity                  → get_identity
+//! POST   /handshake                 → handshake
+//!
+//! Admin (/api/v1/admin/federation)
+//! GET    /peers                     → list_trusted_peers
+//! POST   /peers                     → add_trusted_peer
+//! GET    /peers/:id                 → get_trusted_peer
+//! POST   /peers/:id/approve         → approve_trusted_peer
+//! DELETE /peers/:id                 → remove_trusted_peer
+//!
+//! Peer (/api/v1/federation, federation token)
+//! POST   /promotions                → announce_promotion
+//! PUT    /promotions/:id/content    → receive_promotion_content
+//! ```
+
+use axum::{
+    body::Body,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::handlers::proxy_helpers;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::validation::validate_outbound_url;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::models::repository::{Repository, RepositoryType};
+use crate::services::artifact_service::{ArtifactService, ContentDigests};
+use crate::services::federation_service::{
+    self, FederationScope, InstanceIdentity, LocalIdentity, TrustGrant, TrustedPeer,
+};
+use crate::services::remote_promotion_service::{
+    self, InboundPromotion, InboundPromotionResponse, ManifestFinding, ManifestMetadata,
+    ManifestScan, ManifestSignature, PromotionManifest,
+};
+use crate::services::repository_service::RepositoryService;
+use crate::services::scan_result_service::ScanResultService;
+
+/// Unauthenticated identity exchange routes.
+pub fn public_router() -> Router<SharedState> {
+    Router::new()
+        .route("/identity", get(get_identity))
+        .route("/handshake", post(handshake))
+}
+
+/// Trusted-peer administration (auth enforced by the outer admin_middleware).
+pub fn admin_router() -> Router<SharedState> {
+    Router::new()
+        .route("/peers", get(list_trusted_peers).post(add_trusted_peer))
+        .route(
+            "/peers/:id",
+            get(get_trusted_peer).delete(remove_trusted_peer),
+        )
+        .route("/peers/:id/approve", post(approve_trusted_peer))
+}
+
+/// Routes called by trusted peers (auth enforced by the outer auth_middleware;
+/// the caller must be a federated principal).
+pub fn peer_router() -> Router<SharedState> {
+    Router::new()
+        .route("/promotions", post(announce_promotion))
+        .route("/promotions/:id/content", put(receive_promotion_content))
+}
+
+async fn local_identity(state: &SharedState) -> Result<std::sync::Arc<LocalIdentity>> {
+    federation_service::local_identity(
+        &state.db,
+        &state.config.jwt_secret,
+        &state.config.peer_instance_name,
+        &state.config.peer_public_endpoint,
+    )
+    .await
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddTrustedPeerRequest {
+    /// Base URL of the remote instance.
+    pub endpoint_url: String,
+    pub scopes: Vec<FederationScope>,
+    #[serde(default)]
+    pub repository_ids: Vec<Uuid>,
+    /// Fingerprint of the remote key, obtained out of band
+    /// (`GET /api/v1/federation/identity` on the remote).
+    pub expected_fingerprint: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/identity",
+    context_path = "/api/v1/federation",
+    tag = "federation",
+    responses(
+        (status = 200, description = "This instance's signed identity", body = InstanceIdentity),
+    )
+)]
+pub async fn get_identity(State(state): State<SharedState>) -> Result<Json<InstanceIdentity>> {
+    Ok(Json(local_identity(&state).await?.document()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/handshake",
+    context_path = "/api/v1/federation",
+    tag = "federation",
+    request_body = InstanceIdentity,
+    responses(
+        (status = 200, description = "Caller's identity recorded; this instance's identity returned", body = InstanceIdentity),
+        (status = 400, description = "Identity document does not verify", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Key changed for a trusted peer, or too many pending handshakes", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn handshake(
+    State(state): State<SharedState>,
+    Json(remote): Json<InstanceIdentity>,
+) -> Result<Json<InstanceIdentity>> {
+    let local = local_identity(&state).await?;
+    let peer = federation_service::record_handshake(&state.db, &remote, local.instance_id).await?;
+    tracing::info!(
+        "Federation handshake from '{}' ({}), status {}",
+        peer.name,
+        peer.key_fingerprint,
+        peer.status
+    );
+    Ok(Json(local.document()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/peers",
+    context_path = "/api/v1/admin/federation",
+    tag = "federation",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Known federated peers", body = Vec<TrustedPeer>),
+        (status = 401, description = "Unauthorized", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin required", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn list_trusted_peers(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<Vec<TrustedPeer>>> {
+    auth.require_admin()?;
+    Ok(Json(federation_service::list_peers(&state.db).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/peers",
+    context_path = "/api/v1/admin/federation",
+    tag = "federation",
+    security(("bearer_auth" = [])),
+    request_body = AddTrustedPeerRequest,
+    responses(
+        (status = 200, description = "Handshake completed and peer trusted", body = TrustedPeer),
+        (status = 400, description = "Invalid request or fingerprint mismatch", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin required", body = crate::api::openapi::ErrorResponse),
+        (status = 502, description = "Remote handshake failed", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn add_trusted_peer(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<AddTrustedPeerRequest>,
+) -> Result<Json<TrustedPeer>> {
+    auth.require_admin()?;
+    validate_outbound_url(&payload.endpoint_url, "Federation endpoint URL")?;
+    let grant = TrustGrant {
+        scopes: payload.scopes,
+        repository_ids: payload.repository_ids,
+        expected_fingerprint: payload.expected_fingerprint,
+    };
+    grant.validate()?;
+
+    let local = local_identity(&state).await?;
+    let remote =
+        federation_service::perform_handshake(&payload.endpoint_url, &local.document()).await?;
+    let peer = federation_service::record_handshake(&state.db, &remote, local.instance_id).await?;
+    let peer = federation_service::approve_peer(&state.db, peer.id, &grant, auth.user_id).await?;
+    tracing::info!(
+        "Federated peer '{}' ({}) trusted by {}",
+        peer.name,
+        peer.key_fingerprint,
+        auth.username
+    );
+    Ok(Json(peer))
+}
+
+#[utoipa::path(
+    get,
+    path = "/peers/{id}",
+    context_path = "/api/v1/admin/federation",
+    tag = "federation",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Federated peer ID")),
+    responses(
+        (status = 200, description = "Federated peer", body = TrustedPeer),
+        (status = 403, description = "Admin required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Peer not found", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn get_trusted_peer(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TrustedPeer>> {
+    auth.require_admin()?;
+    Ok(Json(federation_service::get_peer(&state.db, id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/peers/{id}/approve",
+    context_path = "/api/v1/admin/federation",
+    tag = "federation",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Federated peer ID")),
+    request_body = TrustGrant,
+    responses(
+        (status = 200, description = "Peer trusted with the given grant", body = TrustedPeer),
+        (status = 400, description = "Invalid grant or fingerprint mismatch", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Peer not found", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn approve_trusted_peer(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(grant): Json<TrustGrant>,
+) -> Result<Json<TrustedPeer>> {
+    auth.require_admin()?;
+    let peer = federation_service::approve_peer(&state.db, id, &grant, auth.user_id).await?;
+    tracing::info!(
+        "Federated peer '{}' ({}) approved by {}",
+        peer.name,
+        peer.key_fingerprint,
+        auth.username
+    );
+    Ok(Json(peer))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/peers/{id}",
+    context_path = "/api/v1/admin/federation",
+    tag = "federation",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Federated peer ID")),
+    responses(
+        (status = 204, description = "Peer removed and its principal deactivated"),
+        (status = 403, description = "Admin required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Peer not found", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn remove_trusted_peer(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    auth.require_admin()?;
+    federation_service::remove_peer(&state.db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The trusted peer behind a federated principal holding the replication
+/// scope.
+async fn calling_peer(state: &SharedState, auth: &AuthExtension) -> Result<TrustedPeer> {
+    auth.require_scope("write:artifacts")?;
+    federation_service::peer_for_principal(&state.db, auth.user_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::Authorization("Only trusted federated peers may push promotions".to_string())
+        })
+}
+
+/// Target-shape checks for an announced promotion: the repository must be a
+/// local repository of the manifest's format with room for the content.
+pub(crate) fn validate_promotion_target(
+    repo: &Repository,
+    manifest: &PromotionManifest,
+    max_upload_size_bytes: u64,
+) -> Result<()> {
+    if repo.repo_type != RepositoryType::Local {
+        return Err(AppError::Validation(
+            "Target repository must be a local (release) repository".to_string(),
+        ));
+    }
+    let repo_format = format!("{:?}", repo.format).to_lowercase();
+    if repo_format != manifest.format {
+        return Err(AppError::Validation(format!(
+            "Repository format mismatch: source is {}, target is {}",
+            manifest.format, repo_format
+        )));
+    }
+    if max_upload_size_bytes != 0 && manifest.size_bytes as u64 > max_upload_size_bytes {
+        return Err(AppError::Validation(format!(
+            "Artifact exceeds the maximum allowed size of {} bytes",
+            max_upload_size_bytes
+        )));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/promotions",
+    context_path = "/api/v1/federation",
+    tag = "federation",
+    security(("bearer_auth" = [])),
+    request_body = PromotionManifest,
+    responses(
+        (status = 200, description = "Promotion recorded; awaiting content", body = InboundPromotionResponse),
+        (status = 403, description = "Caller is not a trusted peer with write access", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Target repository not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Artifact already exists in target, or the target is frozen", body = crate::api::openapi::ErrorResponse),
+        (status = 422, description = "Invalid manifest or target repository", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn announce_promotion(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(manifest): Json<PromotionManifest>,
+) -> Result<Json<InboundPromotionResponse>> {
+    let peer = calling_peer(&state, &auth).await?;
+    if manifest.source_instance_id != peer.instance_id {
+        return Err(AppError::Authorization(
+            "Manifest source instance does not match the calling peer".to_string(),
+        ));
+    }
+    manifest.validate()?;
+
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&manifest.target_repository).await?;
+    super::repositories::require_repo_write_access(&auth, &repo, &repo_service).await?;
+    validate_promotion_target(&repo, &manifest, state.config.max_upload_size_bytes)?;
+    // Peers cannot override a freeze; the sender retries after the window.
+    crate::services::freeze_window_service::check_promotion(
+        &state.db,
+        repo.id,
+        auth.user_id,
+        false,
+        None,
+    )
+    .await?;
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM artifacts \
+         WHERE repository_id = $1 AND path = $2 AND is_deleted = false)",
+    )
+    .bind(repo.id)
+    .bind(&manifest.path)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let inbound = remote_promotion_service::announce(&state.db, &peer, repo.id, &manifest).await?;
+    if exists && inbound.status != "completed" {
+        let message = format!(
+            "Artifact already exists in target repository: {}",
+            manifest.path
+        );
+        remote_promotion_service::mark_failed(&state.db, inbound.id, &message).await;
+        return Err(AppError::Conflict(message));
+    }
+    tracing::info!(
+        peer = %peer.name,
+        target_repo = %repo.key,
+        artifact = %manifest.path,
+        "Inbound promotion announced"
+    );
+    Ok(Json(inbound.response()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/promotions/{id}/content",
+    context_path = "/api/v1/federation",
+    tag = "federation",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Inbound promotion ID")),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Content verified and artifact registered", body = InboundPromotionResponse),
+        (status = 404, description = "Inbound promotion not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Promotion expired, failed, or artifact already exists", body = crate::api::openapi::ErrorResponse),
+        (status = 413, description = "Content exceeds the upload size limit"),
+        (status = 422, description = "Content does not match the manifest", body = crate::api::openapi::ErrorResponse),
+    )
+)]
+pub async fn receive_promotion_content(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    body: Body,
+) -> std::result::Result<Json<InboundPromotionResponse>, Response> {
+    let peer = calling_peer(&state, &auth)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    let inbound = remote_promotion_service::get_inbound(&state.db, id, peer.id)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    if inbound.status == "completed" {
+        return Ok(Json(inbound.response()));
+    }
+    if inbound.status != "pending" || inbound.expires_at < chrono::Utc::now() {
+        return Err(AppError::Conflict(
+            "Inbound promotion is no longer accepting content; announce it again".to_string(),
+        )
+        .into_response());
+    }
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_id(inbound.repository_id)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    let (staged, digests) =
+        proxy_helpers::stage_stream_content_addressed(&state, body.into_data_stream()).await?;
+
+    match store_promoted_content(&state, &repo, &inbound, staged, &digests, auth.user_id).await {
+        Ok(artifact_id) => {
+            tracing::info!(
+                peer = %peer.name,
+                target_repo = %repo.key,
+                artifact = %inbound.manifest.path,
+                "Inbound promotion completed"
+            );
+            Ok(Json(InboundPromotionResponse {
+                id: inbound.id,
+                status: "completed".to_string(),
+                artifact_id: Some(artifact_id),
+            }))
+        }
+        Err(e) => {
+            remote_promotion_service::mark_failed(&state.db, inbound.id, &e.to_string()).await;
+            Err(e.into_response())
+        }
+    }
+}
+
+/// Verify staged content against the manifest, move it into the target
+/// repository's storage and register the artifact.
+async fn store_promoted_content(
+    state: &SharedState,
+    repo: &Repository,
+    inbound: &InboundPromotion,
+    staged: proxy_helpers::StagedUpload,
+    digests: &ContentDigests,
+    uploaded_by: Uuid,
+) -> Result<Uuid> {
+    remote_promotion_service::verify_content(&inbound.manifest, digests, staged.size_bytes())?;
+
+    let storage_key = ArtifactService::storage_key_from_checksum(&digests.sha256);
+    crate::services::artifact_service::guard_foreign_storage_key_for_backend(
+        &state.db,
+        repo.id,
+        &repo.storage_backend,
+        &storage_key,
+    )
+    .await?;
+    let storage = state.storage_for_repo(&repo.storage_location())?;
+    if !storage.exists(&storage_key).await? {
+        let stream = proxy_helpers::open_staged_upload_stream(&staged)
+            .await
+            .map_err(|_| AppError::Internal("Failed to reopen staged content".to_string()))?;
+        storage.put_stream(&storage_key, stream).await?;
+    }
+    drop(staged);
+
+    super::cleanup_soft_deleted_artifact(&state.db, repo.id, &inbound.manifest.path).await;
+    let artifact_id =
+        remote_promotion_service::register(&state.db, inbound, &storage_key, digests, uploaded_by)
+            .await?;
+
+    if !inbound.manifest.scans.is_empty() {
+        if let Err(e) = ScanResultService::new(state.db.clone())
+            .recalculate_score(repo.id)
+            .await
+        {
+            tracing::warn!(
+                "Security score recalculation failed for {}: {}",
+                repo.key,
+                e
+            );
+        }
+    }
+    Ok(artifact_id)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_identity,
+        handshake,
+        list_trusted_peers,
+        add_trusted_peer,
+        get_trusted_peer,
+        approve_trusted_peer,
+        remove_trusted_peer,
+        announce_promotion,
+        receive_promotion_content,
+    ),
+    components(schemas(
+        InstanceIdentity,
+        TrustedPeer,
+        TrustGrant,
+        FederationScope,
+        AddTrustedPeerRequest,
+        PromotionManifest,
+        ManifestMetadata,
+        ManifestScan,
+        ManifestFinding,
+        ManifestSignature,
+        InboundPromotionResponse,
+    ))
+)]
+pub struct FederationApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::handlers::test_db_helpers as tdh;
+
+    #[test]
+    fn add_request_defaults_repositories() {
+        let req: AddTrustedPeerRequest = serde_json::from_value(serde_json::json!({
+            "endpoint_url": "https://ak-west.example.com",
+            "scopes": ["replication"]
+        }))
+        .unwrap();
+        assert_eq!(req.scopes, vec![FederationScope::Replication]);
+        assert!(req.repository_ids.is_empty());
+        assert!(req.expected_fingerprint.is_none());
+    }
+
+    #[test]
+    fn unknown_scope_is_rejected() {
+        let parsed: std::result::Result<AddTrustedPeerRequest, _> =
+            serde_json::from_value(serde_json::json!({
+                "endpoint_url": "https://ak-west.example.com",
+                "scopes": ["admin"]
+            }));
+        assert!(parsed.is_err());
+    }
+
+    #[tokio::test]
+    async fn handshake_then_approve_issues_usable_token() {
+        let Some(pool) = tdh::try_pool().await else {
+            return;
+        };
+        let storage_path = std::env::temp_dir()
+            .join(format!("federation-tests-{}", Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string();
+        let state = tdh::build_state(pool.clone(), &storage_path);
+        let (admin_id, admin_name) = tdh::create_user(&pool).await;
+        let auth = tdh::admin_auth(admin_id, &admin_name);
+
+        let local = local_identity(&state).await.expect("local identity");
+        let remote = federation_service::tests_support::identity_with_seed(42);
+        let answer = handshake(State(state.clone()), Json(remote.document()))
+            .await
+            .expect("handshake accepted")
+            .0;
+        assert_eq!(answer.instance_id, local.instance_id);
+
+        let token = remote.mint_token(
+            local.instance_id,
+            FederationScope::Download,
+            chrono::Utc::now().timestamp(),
+        );
+        assert!(
+            federation_service::authenticate(&pool, &token)
+                .await
+                .is_none(),
+            "pending peers must not authenticate"
+        );
+
+        let pending = list_trusted_peers(State(state.clone()), Extension(auth.clone()))
+            .await
+            .unwrap()
+            .0
+            .into_iter()
+            .find(|p| p.instance_id == remote.instance_id)
+            .expect("pending peer recorded");
+        assert_eq!(pending.status, "pending");
+
+        let approved = approve_trusted_peer(
+            State(state.clone()),
+            Extension(auth.clone()),
+            Path(pending.id),
+            Json(TrustGrant {
+                scopes: vec![FederationScope::Download],
+                repository_ids: vec![],
+                expected_fingerprint: Some(remote.fingerprint()),
+            }),
+        )
+        .await
+        .expect("approve")
+        .0;
+        assert_eq!(approved.status, "trusted");
+
+        let principal = federation_service::authenticate(&pool, &token)
+            .await
+            .expect("trusted peer authenticates");
+        assert_eq!(Some(principal.user_id), approved.service_user_id);
+
+        let replication = remote.mint_token(
+            local.instance_id,
+            FederationScope::Replication,
+            chrono::Utc::now().timestamp(),
+        );
+        assert!(
+            federation_service::authenticate(&pool, &replication)
+                .await
+                .is_none(),
+            "scopes beyond the grant are refused"
+        );
+
+        remove_trusted_peer(State(state.clone()), Extension(auth), Path(pending.id))
+            .await
+            .unwrap();
+        assert!(federation_service::authenticate(&pool, &token)
+            .await
+            .is_none());
+        tdh::cleanup_user(&pool, admin_id).await;
+    }
+}
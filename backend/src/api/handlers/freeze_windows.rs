@@ -0,0 +1,168 @@
+//! Release freeze window administration.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/freeze-windows)
+//! GET    /                          → list_windows
+//! POST   /                          → create_window
+//! GET    /:id                       → get_window
+//! PUT    /:id                       → update_window
+//! DELETE /:id                       → delete_window
+//! ```
+//!
+//! See [`crate::services::freeze_window_service`] for schedules and how
+//! uploads and promotions are checked.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::freeze_window_service::{
+    self, FreezeSchedule, FreezeWindow, FreezeWindowRequest,
+};
+use crate::services::repository_label_service::LabelEntry;
+
+/// Freeze window routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_windows).post(create_window))
+        .route(
+            "/:id",
+            get(get_window).put(update_window).delete(delete_window),
+        )
+}
+
+/// GET /api/v1/admin/freeze-windows
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/admin/freeze-windows",
+    tag = "freeze_windows",
+    responses(
+        (status = 200, description = "All freeze windows", body = Vec<FreezeWindow>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_windows(State(state): State<SharedState>) -> Result<Json<Vec<FreezeWindow>>> {
+    Ok(Json(freeze_window_service::list(&state.db).await?))
+}
+
+/// POST /api/v1/admin/freeze-windows
+#[utoipa::path(
+    post,
+    path = "",
+    context_path = "/api/v1/admin/freeze-windows",
+    tag = "freeze_windows",
+    request_body = FreezeWindowRequest,
+    responses(
+        (status = 201, description = "Freeze window created", body = FreezeWindow),
+        (status = 400, description = "Invalid scope or schedule", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn create_window(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<FreezeWindowRequest>,
+) -> Result<(StatusCode, Json<FreezeWindow>)> {
+    auth.require_admin()?;
+    let window = freeze_window_service::create(&state.db, &payload, auth.user_id).await?;
+    tracing::info!(
+        window = %window.name,
+        created_by = %auth.username,
+        "Freeze window created"
+    );
+    Ok((StatusCode::CREATED, Json(window)))
+}
+
+/// GET /api/v1/admin/freeze-windows/{id}
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    context_path = "/api/v1/admin/freeze-windows",
+    tag = "freeze_windows",
+    params(("id" = Uuid, Path, description = "Freeze window ID")),
+    responses(
+        (status = 200, description = "Freeze window", body = FreezeWindow),
+        (status = 404, description = "Freeze window not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_window(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FreezeWindow>> {
+    Ok(Json(freeze_window_service::get(&state.db, id).await?))
+}
+
+/// PUT /api/v1/admin/freeze-windows/{id}
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    context_path = "/api/v1/admin/freeze-windows",
+    tag = "freeze_windows",
+    params(("id" = Uuid, Path, description = "Freeze window ID")),
+    request_body = FreezeWindowRequest,
+    responses(
+        (status = 200, description = "Freeze window replaced", body = FreezeWindow),
+        (status = 400, description = "Invalid scope or schedule", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Freeze window or repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn update_window(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<FreezeWindowRequest>,
+) -> Result<Json<FreezeWindow>> {
+    auth.require_admin()?;
+    let window = freeze_window_service::update(&state.db, id, &payload).await?;
+    tracing::info!(
+        window = %window.name,
+        updated_by = %auth.username,
+        "Freeze window updated"
+    );
+    Ok(Json(window))
+}
+
+/// DELETE /api/v1/admin/freeze-windows/{id}
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    context_path = "/api/v1/admin/freeze-windows",
+    tag = "freeze_windows",
+    params(("id" = Uuid, Path, description = "Freeze window ID")),
+    responses(
+        (status = 204, description = "Freeze window deleted"),
+        (status = 404, description = "Freeze window not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_window(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    auth.require_admin()?;
+    freeze_window_service::delete(&state.db, id).await?;
+    tracing::info!(window_id = %id, deleted_by = %auth.username, "Freeze window deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_windows, create_window, get_window, update_window, delete_window),
+    components(schemas(FreezeWindow, FreezeWindowRequest, FreezeSchedule, LabelEntry))
+)]
+pub struct FreezeWindowsApiDoc;
@@ -402,6 +402,7 @@ async fn try_proxy_go_metadata(
             state.proxy_service.as_deref(),
             repo.id,
             upstream_path,
+            upstream_path,
             |bytes, _key| {
                 let ct = ct.clone();
                 async move {
@@ -455,21 +456,28 @@ async fn list_versions(
         // local (non-Remote) member repos, which `try_proxy_go_metadata` does
         // not consult. Aggregate distinct versions across all members first so
         // a module stored only in a Local member is listed (#1782).
+        let encoded = encode_module_path(module);
+        let upstream_path = format!("{}/@v/list", encoded);
         if repo.repo_type == RepositoryType::Virtual {
-            let member_versions: Vec<Option<String>> = sqlx::query_scalar!(
+            let member_ids: Vec<uuid::Uuid> =
+                proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &upstream_path)
+                    .await?
+                    .iter()
+                    .map(|m| m.id)
+                    .collect();
+            let member_versions: Vec<Option<String>> = sqlx::query_scalar(
                 r#"
                 SELECT DISTINCT a.version
                 FROM artifacts a
-                INNER JOIN virtual_repo_members vrm ON a.repository_id = vrm.member_repo_id
-                WHERE vrm.virtual_repo_id = $1
+                WHERE a.repository_id = ANY($1::uuid[])
                   AND a.name = $2
                   AND a.is_deleted = false
                   AND a.version IS NOT NULL
                 ORDER BY a.version
                 "#,
-                repo.id,
-                module
             )
+            .bind(&member_ids)
+            .bind(module)
             .fetch_all(&state.db)
             .await
             .map_err(crate::api::handlers::db_err)?;
@@ -489,8 +497,6 @@ async fn list_versions(
             }
         }
 
-        let encoded = encode_module_path(module);
-        let upstream_path = format!("{}/@v/list", encoded);
         if let Ok(resp) =
             try_proxy_go_metadata(state, repo, &upstream_path, "text/plain; charset=utf-8").await
         {
@@ -550,31 +556,38 @@ async fn version_info(
             // member repo whose artifact rows `try_proxy_go_metadata` never
             // queries. Look across member repos for the earliest matching
             // artifact before falling through to the upstream proxy (#1782).
+            let encoded = encode_module_path(module);
+            let upstream_path = format!("{}/@v/{}.info", encoded, version);
             if repo.repo_type == RepositoryType::Virtual {
-                if let Some(member_row) = sqlx::query!(
+                let member_ids: Vec<uuid::Uuid> = proxy_helpers::fetch_virtual_members_for_path(
+                    &state.db,
+                    repo.id,
+                    &upstream_path,
+                )
+                .await?
+                .iter()
+                .map(|m| m.id)
+                .collect();
+                if let Some(created_at) = sqlx::query_scalar::<_, chrono::DateTime<chrono::Utc>>(
                     r#"
                     SELECT a.created_at
                     FROM artifacts a
-                    INNER JOIN virtual_repo_members vrm ON a.repository_id = vrm.member_repo_id
-                    WHERE vrm.virtual_repo_id = $1
+                    WHERE a.repository_id = ANY($1::uuid[])
                       AND a.name = $2
                       AND a.version = $3
                       AND a.is_deleted = false
                     ORDER BY a.created_at ASC
                     LIMIT 1
                     "#,
-                    repo.id,
-                    module,
-                    version
                 )
+                .bind(&member_ids)
+                .bind(module)
+                .bind(version)
                 .fetch_optional(&state.db)
                 .await
                 .map_err(crate::api::handlers::db_err)?
                 {
-                    let time_str = member_row
-                        .created_at
-                        .format("%Y-%m-%dT%H:%M:%SZ")
-                        .to_string();
+                    let time_str = created_at.format("%Y-%m-%dT%H:%M:%SZ").to_string();
                     let info = serde_json::json!({
                         "Version": version,
                         "Time": time_str,
@@ -587,8 +600,6 @@ async fn version_info(
                 }
             }
 
-            let encoded = encode_module_path(module);
-            let upstream_path = format!("{}/@v/{}.info", encoded, version);
             if let Ok(resp) =
                 try_proxy_go_metadata(state, repo, &upstream_path, "application/json").await
             {
@@ -204,7 +204,8 @@ pub async fn health_check(State(state): State<SharedState>) -> impl IntoResponse
 
     let storage_check = check_storage_health(&state.config, &state.storage).await;
 
-    let scanner_check = match &state.config.trivy_url {
+    let scanner_endpoints = crate::config_reload::scanner_endpoints(&state.config);
+    let scanner_check = match &scanner_endpoints.trivy_url {
         Some(url) => Some(check_service_health(url, "/healthz", "Trivy").await),
         None => None,
     };
@@ -298,7 +299,7 @@ pub async fn health_check(State(state): State<SharedState>) -> impl IntoResponse
     let response = HealthResponse {
         status: overall_status.to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
-        demo_mode: state.config.demo_mode,
+        demo_mode: crate::config_reload::demo_mode(&state.config),
         checks: HealthChecks {
             database: db_check,
             storage: storage_check,
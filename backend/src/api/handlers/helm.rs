@@ -10,6 +10,10 @@
 //!   POST   /helm/{repo_key}/api/charts                        - Upload chart (multipart)
 //!   DELETE /helm/{repo_key}/api/charts/{name}/{version}        - Delete chart
 //!
+//! `index.yaml` is assembled from the `Chart.yaml` metadata extracted at
+//! upload, kept per repository by `services::helm_index_service` and updated
+//! incrementally as charts are pushed and deleted.
+//!
 //! ## Provenance (#2635)
 //!
 //! `helm package --sign` emits a clearsigned `<chart>.tgz.prov` next to the
@@ -22,7 +26,7 @@
 //! suffix lookup the chart itself uses.
 
 use axum::body::Body;
-use axum::extract::{Multipart, Path, State};
+use axum::extract::{Multipart, Path, Query, State};
 use axum::http::header::CONTENT_TYPE;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
@@ -35,8 +39,10 @@ use tracing::info;
 use crate::api::handlers::proxy_helpers::{self, RepoInfo};
 use crate::api::middleware::auth::{require_auth_basic_scope, AuthExtension};
 use crate::api::SharedState;
-use crate::formats::helm::{generate_index_yaml, ChartYaml, HelmHandler, HelmIndex};
+use crate::formats::helm::{generate_index_yaml, ChartImageRef, ChartYaml, HelmHandler, HelmIndex};
 use crate::models::repository::{RepositoryFormat, RepositoryType};
+use crate::services::chart_image_service;
+use crate::services::helm_index_service::HelmIndexService;
 use crate::services::proxy_service::ProxyService;
 use crate::services::quarantine_service;
 
@@ -161,86 +167,38 @@ fn chart_download_url(repo_key: &str, path: Option<&str>, name: &str, version: &
     format!("/helm/{}/charts/{}", repo_key, filename)
 }
 
-/// Query Helm chart artifacts from a repository and append chart entries to `out`.
+/// The `charts/{filename}` path a chart downloads from, given its advertised
+/// download URL. Virtual member routing patterns are checked against it.
+fn chart_route_path(download_url: &str) -> String {
+    format!(
+        "charts/{}",
+        download_url.rsplit('/').next().unwrap_or_default()
+    )
+}
+
+/// Append the chart entries of a hosted repository to `out`, with download
+/// URLs under `repo_key` (the virtual repository's key for a member).
 ///
-/// Provenance rows are excluded: a `.prov` is stored as its own artifact under
-/// the same `name`/`version` as its chart (#2635), so without the filter every
-/// signed chart would render a duplicate `index.yaml` entry.
+/// Entries come from the incrementally maintained index in
+/// `services::helm_index_service`, so only charts added or changed since the
+/// last request are loaded.
 async fn query_charts_from_repo(
     db: &PgPool,
     repo_id: uuid::Uuid,
     repo_key: &str,
     out: &mut Vec<(ChartYaml, String, String, String)>,
 ) -> Result<(), Response> {
-    let rows = sqlx::query(
-        r#"
-        SELECT a.id, a.name, a.version, a.path, a.size_bytes, a.checksum_sha256,
-               a.created_at,
-               am.metadata
-        FROM artifacts a
-        LEFT JOIN artifact_metadata am ON am.artifact_id = a.id
-        WHERE a.repository_id = $1
-          AND a.is_deleted = false
-          AND a.path NOT LIKE '%.prov'
-        ORDER BY a.name ASC, a.created_at DESC
-        "#,
-    )
-    .bind(repo_id)
-    .fetch_all(db)
-    .await
-    .map_err(super::db_err)?;
-
-    for row in &rows {
-        let name: String = row.get("name");
-        let version: Option<String> = row.get("version");
-        let path: String = row.get("path");
-        let checksum_sha256: String = row.get("checksum_sha256");
-        let created_at: chrono::DateTime<chrono::Utc> = row.get("created_at");
-        let metadata: Option<serde_json::Value> = row.get("metadata");
-
-        let version = match version {
-            Some(v) => v,
-            None => continue,
-        };
-
-        let chart_yaml = metadata
-            .as_ref()
-            .and_then(|m| m.get("chart"))
-            .and_then(|chart_value| serde_json::from_value::<ChartYaml>(chart_value.clone()).ok());
-
-        let chart_yaml = chart_yaml.unwrap_or_else(|| ChartYaml {
-            api_version: "v2".to_string(),
-            name: name.clone(),
-            version: version.clone(),
-            kube_version: None,
-            description: metadata
-                .as_ref()
-                .and_then(|m| m.get("description"))
-                .and_then(|v| v.as_str())
-                .map(String::from),
-            chart_type: None,
-            keywords: None,
-            home: None,
-            sources: None,
-            dependencies: None,
-            maintainers: None,
-            icon: None,
-            app_version: metadata
-                .as_ref()
-                .and_then(|m| m.get("appVersion"))
-                .and_then(|v| v.as_str())
-                .map(String::from),
-            deprecated: None,
-            annotations: None,
-        });
-
-        let url = chart_download_url(repo_key, Some(&path), &name, &version);
-        let created = created_at.to_rfc3339();
-        let digest = checksum_sha256;
-
-        out.push((chart_yaml, url, created, digest));
-    }
-
+    let charts = HelmIndexService::charts(db, repo_id)
+        .await
+        .map_err(|e| e.into_response())?;
+    out.extend(charts.iter().map(|c| {
+        (
+            c.chart.clone(),
+            chart_download_url(repo_key, Some(&c.path), &c.chart.name, &c.chart.version),
+            c.created_at.to_rfc3339(),
+            c.digest.clone(),
+        )
+    }));
     Ok(())
 }
 
@@ -277,6 +235,9 @@ async fn index_yaml(
     // Virtual repository: merge index.yaml from all member repositories
     if repo.repo_type == RepositoryType::Virtual {
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        // Each member only advertises the charts its routing patterns let it
+        // serve, so the index never lists a chart the download would refuse.
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
         let mut all_charts: Vec<(ChartYaml, String, String, String)> = Vec::new();
 
         // Collect index.yaml from remote members and parse chart entries
@@ -297,7 +258,7 @@ async fn index_yaml(
         )
         .await?;
 
-        for (_member_key, index) in remote_indexes {
+        for (member_id, index) in remote_indexes {
             for (_chart_name, entries) in index.entries {
                 for entry in entries {
                     // Remote upstream entries have no local stored path; rebuild
@@ -309,6 +270,9 @@ async fn index_yaml(
                         &entry.chart.name,
                         &entry.chart.version,
                     );
+                    if !routes.allows(member_id, &chart_route_path(&url)) {
+                        continue;
+                    }
                     all_charts.push((entry.chart, url, entry.created, entry.digest));
                 }
             }
@@ -317,7 +281,13 @@ async fn index_yaml(
         // Query artifacts from local/hosted members
         for member in &members {
             if member.repo_type != RepositoryType::Remote {
-                query_charts_from_repo(&state.db, member.id, &repo_key, &mut all_charts).await?;
+                let mut member_charts = Vec::new();
+                query_charts_from_repo(&state.db, member.id, &repo_key, &mut member_charts).await?;
+                all_charts.extend(
+                    member_charts
+                        .into_iter()
+                        .filter(|(_, url, _, _)| routes.allows(member.id, &chart_route_path(url))),
+                );
             }
         }
 
@@ -483,7 +453,9 @@ async fn download_chart_via_index(
     }
 
     if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!("charts/{}", filename);
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         for member in &members {
             if member.repo_type != RepositoryType::Remote {
                 // Hosted / staging member: check local storage.
@@ -666,20 +638,26 @@ async fn upload_chart(
     }
 
     // Extract and validate Chart.yaml from the staged archive on disk, reading
-    // only the Chart.yaml entry (bounded memory) rather than the whole package.
+    // only the Chart.yaml entry (bounded memory) rather than the whole package,
+    // then collect the images the chart deploys under the same permit.
     // #2561: permit held across the blocking decode, fast-fail 503 on saturation.
-    let chart_yaml = crate::util::bounded_archive::with_ingest_extraction_async(|| {
-        extract_chart_yaml_from_staged(staged.path())
-    })
-    .await
-    .map_err(|e| e.into_response())?
-    .map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid chart package: {}", e),
-        )
-            .into_response()
-    })?;
+    let staged_path = staged.path();
+    let (chart_yaml, chart_images) =
+        crate::util::bounded_archive::with_ingest_extraction_async(|| async move {
+            let chart_yaml = extract_chart_yaml_from_staged(staged_path).await?;
+            let chart_images =
+                extract_chart_images_from_staged(staged_path, chart_yaml.app_version.clone()).await;
+            Ok::<_, String>((chart_yaml, chart_images))
+        })
+        .await
+        .map_err(|e| e.into_response())?
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid chart package: {}", e),
+            )
+                .into_response()
+        })?;
 
     let chart_name = &chart_yaml.name;
     let chart_version = &chart_yaml.version;
@@ -816,6 +794,7 @@ async fn upload_chart(
         &helm_metadata,
     )
     .await;
+    chart_image_service::record_chart_images(&state.db, artifact_id, repo.id, &chart_images).await;
 
     if let Some(prov_artifact_id) = prov_artifact_id {
         quarantine_service::apply_upload_hold_hosted(&state.db, repo.id, prov_artifact_id).await;
@@ -894,6 +873,33 @@ async fn extract_chart_yaml_from_staged(path: &std::path::Path) -> Result<ChartY
     .map_err(|e| format!("chart extraction task failed: {}", e))?
 }
 
+/// Collect the container images a staged chart deploys. Best-effort: the
+/// chart is already known to be valid, so a failure here only loses the
+/// chart→image relation and is logged rather than failing the upload.
+async fn extract_chart_images_from_staged(
+    path: &std::path::Path,
+    app_version: Option<String>,
+) -> Vec<ChartImageRef> {
+    let path = path.to_path_buf();
+    let result = tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(&path)
+            .map_err(|e| format!("Failed to open staged archive: {}", e))?;
+        HelmHandler::extract_image_refs_from_reader(
+            std::io::BufReader::new(file),
+            app_version.as_deref(),
+        )
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("chart image extraction task failed: {}", e))
+    .and_then(|r| r);
+
+    result.unwrap_or_else(|e| {
+        tracing::warn!("Failed to extract image references from chart: {}", e);
+        Vec::new()
+    })
+}
+
 // ---------------------------------------------------------------------------
 // DELETE /helm/{repo_key}/api/charts/{name}/{version} -- Delete chart
 // ---------------------------------------------------------------------------
@@ -902,6 +908,7 @@ async fn delete_chart(
     State(state): State<SharedState>,
     Extension(auth): Extension<Option<AuthExtension>>,
     Path((repo_key, name, version)): Path<(String, String, String)>,
+    Query(query): Query<crate::api::handlers::repositories::DeleteArtifactQuery>,
 ) -> Result<Response, Response> {
     // Authenticate
     // GHSA-vvc3-h39c-mrq5: enforce token scope before processing.
@@ -943,6 +950,12 @@ async fn delete_chart(
         .filter(|r| is_prov_filename(&r.get::<String, _>("path")))
         .count();
 
+    // Download-activity protection, as on the REST artifact delete.
+    crate::services::delete_protection_service::DeleteProtectionService::new(state.db.clone())
+        .check_delete_all(repo.id, &artifact_ids, query.confirm.unwrap_or(false))
+        .await
+        .map_err(IntoResponse::into_response)?;
+
     // Soft-delete the chart together with its provenance: leaving an orphaned
     // .prov behind would let a later re-upload serve provenance for a chart it
     // does not describe.
@@ -1179,6 +1192,19 @@ wsDcBAEBCgAQBQJqWW7VCRA8wAoTVPCkgwAAVAoMACmQbvnhlkWncOkVJXfissGD\n\
         );
     }
 
+    #[test]
+    fn test_chart_route_path_matches_download_route() {
+        assert_eq!(
+            chart_route_path(&chart_download_url(
+                "myrepo",
+                Some("nginx/1.24.0/nginx-1.24.0.tgz"),
+                "nginx",
+                "1.24.0"
+            )),
+            "charts/nginx-1.24.0.tgz"
+        );
+    }
+
     #[test]
     fn test_chart_download_url_no_path_falls_back_to_reconstruction() {
         // Remote upstream entries have no local path; keep the ChartMuseum
@@ -34,6 +34,7 @@ use crate::formats::hex_registry;
 use crate::models::repository::{Repository, RepositoryType};
 use crate::services::curation_service::version_compare;
 use crate::services::signing_service::SigningService;
+use crate::services::virtual_member_routing::MemberRoutes;
 
 // ---------------------------------------------------------------------------
 // Router
@@ -392,7 +393,10 @@ async fn package_info(
         // published name. Local-first lookup also avoids an unnecessary
         // network round-trip when the package is already known to a member.
         if repo.repo_type == RepositoryType::Virtual {
-            let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+            let route_path = tarball_route_prefix(&name);
+            let members =
+                proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path)
+                    .await?;
 
             // Pass 1+2: any member that already has artifact rows for this name.
             // Non-Remote members run first so they shadow Remote upstreams; this
@@ -414,6 +418,7 @@ async fn package_info(
                 state.proxy_service.as_deref(),
                 repo.id,
                 &upstream_path,
+                &route_path,
                 |content, _member_key| async move {
                     Ok(Response::builder()
                         .status(StatusCode::OK)
@@ -601,7 +606,8 @@ async fn download_tarball(
             // in `package_info`; this is the matching guard on the bytes
             // side. Forward-ported from PR #974 (#973).
             if repo.repo_type == RepositoryType::Virtual
-                && virtual_local_owns_tarball_name(&state.db, repo.id, filename).await?
+                && virtual_local_owns_tarball_name(&state.db, repo.id, filename, &upstream_path)
+                    .await?
             {
                 return serve_virtual_tarball_local_only(&state, repo.id, &upstream_path, filename)
                     .await;
@@ -718,16 +724,24 @@ async fn find_hosted_tarball_case_insensitive(
     }))
 }
 
+/// The `tarballs/{name}-` prefix shared by every tarball of `name`, used to
+/// route name-level lookups (package info, `/names`) through member patterns.
+fn tarball_route_prefix(name: &str) -> String {
+    format!("tarballs/{}-", name)
+}
+
 /// Returns true if any non-Remote member of a virtual repo has an artifact
 /// row matching the package name parsed from a tarball filename. When true,
 /// the caller must block an upstream Remote member from satisfying the
 /// download (supply-chain name-shadowing guard, #973 / PR #974).
 ///
 /// Falls back to `false` if the filename does not parse as a hex tarball.
+/// `upstream_path` is the tarball download path, used for member routing.
 async fn virtual_local_owns_tarball_name(
     db: &PgPool,
     virtual_repo_id: uuid::Uuid,
     filename: &str,
+    upstream_path: &str,
 ) -> Result<bool, Response> {
     let Some(pkg_name) = package_name_from_tarball_filename(filename) else {
         return Ok(false);
@@ -737,7 +751,7 @@ async fn virtual_local_owns_tarball_name(
     // The hex-specific work is parsing the tarball filename into a
     // package name; the DB lookup is shared with cargo / npm / pypi /
     // maven / rubygems.
-    proxy_helpers::virtual_non_remote_owns_name(db, virtual_repo_id, &pkg_name).await
+    proxy_helpers::virtual_non_remote_owns_name(db, virtual_repo_id, &pkg_name, upstream_path).await
 }
 
 /// Serve a tarball download restricted to the virtual repo's non-Remote
@@ -1129,7 +1143,8 @@ async fn list_names(
     // Virtual: merge package names from all member repositories (local DB + remote proxy).
     if repo.repo_type == RepositoryType::Virtual {
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
-        let mut merged = query_local_member_names(&state.db, &members).await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
+        let mut merged = query_local_member_names(&state.db, &members, &routes).await?;
 
         let remote_results = proxy_helpers::collect_virtual_metadata(
             &state.db,
@@ -1139,8 +1154,12 @@ async fn list_names(
             |bytes, _member_key| async move { parse_upstream_names(&bytes) },
         )
         .await?;
-        for (_key, remote_names) in remote_results {
-            merged.extend(remote_names);
+        for (member_id, remote_names) in remote_results {
+            merged.extend(
+                remote_names
+                    .into_iter()
+                    .filter(|n| routes.allows(member_id, &tarball_route_prefix(n))),
+            );
         }
 
         let deduped = merge_and_sort_names(merged);
@@ -1284,7 +1303,8 @@ async fn list_versions(
     // Virtual: merge versions from all member repositories (local DB + remote proxy).
     if repo.repo_type == RepositoryType::Virtual {
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
-        let mut merged = query_local_member_versions(&state.db, &members).await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
+        let mut merged = query_local_member_versions(&state.db, &members, &routes).await?;
 
         let remote_results = proxy_helpers::collect_virtual_metadata(
             &state.db,
@@ -1294,9 +1314,15 @@ async fn list_versions(
             |bytes, _member_key| async move { parse_upstream_versions(&bytes) },
         )
         .await?;
-        for (_key, remote_versions) in remote_results {
+        for (member_id, remote_versions) in remote_results {
             for (name, versions) in remote_versions {
-                merged.entry(name).or_default().extend(versions);
+                let versions: Vec<String> = versions
+                    .into_iter()
+                    .filter(|v| routes.allows(member_id, &format!("tarballs/{}-{}.tar", name, v)))
+                    .collect();
+                if !versions.is_empty() {
+                    merged.entry(name).or_default().extend(versions);
+                }
             }
         }
 
@@ -1459,10 +1485,12 @@ fn build_package_info_json(
 ///
 /// Includes Remote members because cached pull-through packages are recorded
 /// as `artifacts` rows by `ProxyService`, and a virtual repo's `/names`
-/// index must surface those alongside locally hosted ones (#973).
+/// index must surface those alongside locally hosted ones (#973). Names a
+/// member's routing patterns exclude are left out.
 async fn query_local_member_names(
     db: &PgPool,
     members: &[Repository],
+    routes: &MemberRoutes,
 ) -> Result<Vec<String>, Response> {
     let mut all_names = Vec::new();
     for member in members {
@@ -1479,7 +1507,11 @@ async fn query_local_member_names(
         .fetch_all(db)
         .await
         .map_err(crate::api::handlers::db_err)?;
-        all_names.extend(names);
+        all_names.extend(
+            names
+                .into_iter()
+                .filter(|n| routes.allows(member.id, &tarball_route_prefix(n))),
+        );
     }
     Ok(all_names)
 }
@@ -1488,10 +1520,12 @@ async fn query_local_member_names(
 /// grouped by package name.
 ///
 /// Includes Remote members because their proxy cache populates `artifacts`
-/// rows on pull-through (#973).
+/// rows on pull-through (#973). Tarballs a member's routing patterns exclude
+/// are left out.
 async fn query_local_member_versions(
     db: &PgPool,
     members: &[Repository],
+    routes: &MemberRoutes,
 ) -> Result<std::collections::BTreeMap<String, Vec<String>>, Response> {
     let mut packages: std::collections::BTreeMap<String, Vec<String>> =
         std::collections::BTreeMap::new();
@@ -1512,6 +1546,9 @@ async fn query_local_member_versions(
         for a in &artifacts {
             let name = a.name.clone();
             let version = a.version.clone().unwrap_or_default();
+            if !routes.allows(member.id, &format!("tarballs/{}-{}.tar", name, version)) {
+                continue;
+            }
             packages.entry(name).or_default().push(version);
         }
     }
@@ -42,6 +42,7 @@ use crate::api::handlers::proxy_helpers::{self, RepoInfo};
 use crate::api::middleware::auth::{require_auth_basic_scope, AuthExtension};
 use crate::api::SharedState;
 use crate::formats::incus::IncusHandler;
+use crate::services::scan_queue_service::{self, ScanJobOptions, ScanTrigger};
 // `StorageBackend` is referenced implicitly via `state.storage_for_repo(...)`
 // returning `Arc<dyn StorageBackend>`; no direct trait import needed.
 
@@ -1580,7 +1581,7 @@ async fn run_finalize(
     // scan_on_upload trigger — format-native upload paths bypass
     // `ArtifactService::upload`'s auto-scan gate, so mirror it here. No-op when
     // the scanner_service is None or `scan_on_upload`/`scan_enabled` is false.
-    if let Some(scanner) = state.scanner_service.clone() {
+    if state.scanner_service.is_some() {
         let should_scan = sqlx::query_scalar!(
             "SELECT scan_on_upload FROM scan_configs WHERE repository_id = $1 AND scan_enabled = true",
             p.repo_id
@@ -1590,11 +1591,19 @@ async fn run_finalize(
         .ok()
         .flatten()
         .unwrap_or(false);
+        let db = state.db.clone();
         crate::services::scanner_service::spawn_scan_on_upload(
             should_scan,
             artifact_id,
             move |aid| async move {
-                if let Err(e) = scanner.scan_artifact(aid).await {
+                if let Err(e) = scan_queue_service::enqueue(
+                    &db,
+                    aid,
+                    ScanTrigger::Upload,
+                    ScanJobOptions::default(),
+                )
+                .await
+                {
                     tracing::warn!(
                         artifact_id = %aid,
                         error = %e,
@@ -0,0 +1,365 @@
+//! Jira issue tracker integration handlers.
+//!
+//! All endpoints are admin-only and nested under `/api/v1/admin`. Syncing
+//! normally runs from the scheduler; `POST /{id}/sync` runs it on demand.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::validation::validate_outbound_webhook_url;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::issue_tracker_service::{
+    CreateIssueTrackerIntegration, IssueGrouping, IssueSyncSummary, IssueTrackerIntegration,
+    IssueTrackerLink, IssueTrackerService, ProjectMapping, UpdateIssueTrackerIntegration,
+};
+
+/// Admin routes, nested at `/api/v1/admin/issue-trackers`.
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_integrations).post(create_integration))
+        .route(
+            "/:id",
+            get(get_integration)
+                .put(update_integration)
+                .delete(delete_integration),
+        )
+        .route("/:id/links", get(list_links))
+        .route("/:id/sync", post(sync_integration))
+        .route("/:id/test", post(test_integration))
+}
+
+fn require_admin(auth: &AuthExtension) -> Result<()> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn service(state: &SharedState) -> IssueTrackerService {
+    IssueTrackerService::new(state.db.clone())
+}
+
+fn ensure_crypto_configured() -> Result<()> {
+    crate::services::webhook_secret_crypto::ensure_configured()
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+fn validate_urls(base_url: Option<&str>, backlink_base_url: Option<&str>) -> Result<()> {
+    if let Some(url) = base_url {
+        validate_outbound_webhook_url(url, "Jira base URL")?;
+    }
+    // The backlink is only rendered into Jira, never fetched, so it just
+    // needs to be a well-formed http(s) URL.
+    if let Some(url) = backlink_base_url.filter(|u| !u.is_empty()) {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|_| AppError::Validation("Invalid backlink_base_url".to_string()))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(AppError::Validation(
+                "backlink_base_url must use http or https".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssueTrackerListResponse {
+    pub items: Vec<IssueTrackerIntegration>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssueTrackerLinkListResponse {
+    pub items: Vec<IssueTrackerLink>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LinkQuery {
+    /// Filter by link status: open, closed or acknowledged.
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssueTrackerTestResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/admin/issue-trackers",
+    tag = "issue_trackers",
+    responses(
+        (status = 200, description = "Configured issue tracker integrations", body = IssueTrackerListResponse),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_integrations(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<IssueTrackerListResponse>> {
+    require_admin(&auth)?;
+    let items = service(&state).list().await?;
+    Ok(Json(IssueTrackerListResponse { items }))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    context_path = "/api/v1/admin/issue-trackers",
+    tag = "issue_trackers",
+    request_body = CreateIssueTrackerIntegration,
+    responses(
+        (status = 201, description = "Integration created", body = IssueTrackerIntegration),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 409, description = "Name already in use"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(req): Json<CreateIssueTrackerIntegration>,
+) -> Result<(StatusCode, Json<IssueTrackerIntegration>)> {
+    require_admin(&auth)?;
+    validate_urls(Some(&req.base_url), req.backlink_base_url.as_deref())?;
+    ensure_crypto_configured()?;
+
+    let integration = service(&state).create(&req, auth.user_id).await?;
+    tracing::info!(
+        integration_id = %integration.id,
+        project = %integration.project_key,
+        created_by = %auth.user_id,
+        "Issue tracker integration created"
+    );
+    Ok((StatusCode::CREATED, Json(integration)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    context_path = "/api/v1/admin/issue-trackers",
+    tag = "issue_trackers",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 200, description = "Integration", body = IssueTrackerIntegration),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<IssueTrackerIntegration>> {
+    require_admin(&auth)?;
+    Ok(Json(service(&state).get(id).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    context_path = "/api/v1/admin/issue-trackers",
+    tag = "issue_trackers",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    request_body = UpdateIssueTrackerIntegration,
+    responses(
+        (status = 200, description = "Integration updated", body = IssueTrackerIntegration),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateIssueTrackerIntegration>,
+) -> Result<Json<IssueTrackerIntegration>> {
+    require_admin(&auth)?;
+    validate_urls(req.base_url.as_deref(), req.backlink_base_url.as_deref())?;
+    if req.api_token.is_some() {
+        ensure_crypto_configured()?;
+    }
+    Ok(Json(service(&state).update(id, &req).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    context_path = "/api/v1/admin/issue-trackers",
+    tag = "issue_trackers",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 204, description = "Integration deleted"),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_admin(&auth)?;
+    service(&state).delete(id).await?;
+    tracing::info!(integration_id = %id, deleted_by = %auth.user_id, "Issue tracker integration deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/links",
+    context_path = "/api/v1/admin/issue-trackers",
+    tag = "issue_trackers",
+    params(("id" = Uuid, Path, description = "Integration ID"), LinkQuery),
+    responses(
+        (status = 200, description = "Jira issues filed by this integration", body = IssueTrackerLinkListResponse),
+        (status = 400, description = "Invalid status filter"),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_links(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<LinkQuery>,
+) -> Result<Json<IssueTrackerLinkListResponse>> {
+    require_admin(&auth)?;
+    if let Some(status) = query.status.as_deref() {
+        if !matches!(status, "open" | "closed" | "acknowledged") {
+            return Err(AppError::Validation(format!(
+                "Invalid status '{}': must be open, closed or acknowledged",
+                status
+            )));
+        }
+    }
+    let svc = service(&state);
+    svc.get(id).await?;
+    let items = svc.list_links(id, query.status.as_deref()).await?;
+    Ok(Json(IssueTrackerLinkListResponse { items }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/sync",
+    context_path = "/api/v1/admin/issue-trackers",
+    tag = "issue_trackers",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 200, description = "Sync completed", body = IssueSyncSummary),
+        (status = 404, description = "Integration not found"),
+        (status = 500, description = "Sync failed"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn sync_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<IssueSyncSummary>> {
+    require_admin(&auth)?;
+    Ok(Json(service(&state).sync_integration(id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/test",
+    context_path = "/api/v1/admin/issue-trackers",
+    tag = "issue_trackers",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 200, description = "Connection test result", body = IssueTrackerTestResponse),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn test_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<IssueTrackerTestResponse>> {
+    require_admin(&auth)?;
+    let response = match service(&state).test_connection(id).await {
+        Ok(()) => IssueTrackerTestResponse {
+            success: true,
+            message: "Connected to Jira and found the default project".to_string(),
+        },
+        Err(AppError::NotFound(msg)) => return Err(AppError::NotFound(msg)),
+        Err(e) => IssueTrackerTestResponse {
+            success: false,
+            message: e.to_string(),
+        },
+    };
+    Ok(Json(response))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_integrations,
+        create_integration,
+        get_integration,
+        update_integration,
+        delete_integration,
+        list_links,
+        sync_integration,
+        test_integration,
+    ),
+    components(schemas(
+        IssueTrackerIntegration,
+        CreateIssueTrackerIntegration,
+        UpdateIssueTrackerIntegration,
+        IssueGrouping,
+        ProjectMapping,
+        IssueTrackerLink,
+        IssueSyncSummary,
+        IssueTrackerListResponse,
+        IssueTrackerLinkListResponse,
+        IssueTrackerTestResponse,
+    ))
+)]
+pub struct IssueTrackersApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_require_admin() {
+        assert!(matches!(
+            require_admin(&AuthExtension::default()),
+            Err(AppError::Authorization(_))
+        ));
+        let admin = AuthExtension {
+            is_admin: true,
+            ..Default::default()
+        };
+        assert!(require_admin(&admin).is_ok());
+    }
+
+    #[test]
+    fn test_validate_urls() {
+        assert!(validate_urls(Some("http://127.0.0.1/"), None).is_err());
+        assert!(validate_urls(Some("https://example.atlassian.net"), None).is_ok());
+        // Backlinks may point at internal hosts; they are never fetched.
+        assert!(validate_urls(None, Some("http://artifact-keeper.internal:8080")).is_ok());
+        assert!(validate_urls(None, Some("ftp://example.com")).is_err());
+        assert!(validate_urls(None, Some("not a url")).is_err());
+        assert!(validate_urls(None, Some("")).is_ok());
+    }
+}
@@ -16,6 +16,7 @@ use crate::services::lifecycle_service::{
     CreateLifecyclePolicyRequest, LifecyclePolicy, LifecycleService, PolicyExecutionResult,
     UpdateLifecyclePolicyRequest,
 };
+use crate::services::repository_label_service::LabelEntry;
 
 #[derive(OpenApi)]
 #[openapi(
@@ -34,6 +35,7 @@ use crate::services::lifecycle_service::{
         CreateLifecyclePolicyRequest,
         UpdateLifecyclePolicyRequest,
         PolicyExecutionResult,
+        LabelEntry,
     ))
 )]
 pub struct LifecycleApiDoc;
@@ -436,6 +438,7 @@ mod tests {
             last_run_at: None,
             last_run_items_removed: None,
             cron_schedule: None,
+            label_selector: vec![],
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -460,6 +463,7 @@ mod tests {
             last_run_at: None,
             last_run_items_removed: None,
             cron_schedule: None,
+            label_selector: vec![],
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
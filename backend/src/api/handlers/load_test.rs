@@ -0,0 +1,240 @@
+//! Load-test mode: synthetic data and the benchmark harness.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/load-test)
+//! POST   /synthetic-data            → generate_synthetic_data
+//! GET    /synthetic-data            → list_synthetic_data_runs
+//! DELETE /synthetic-data/:id        → delete_synthetic_data_run
+//! POST   /benchmarks                → run_benchmark
+//! GET    /benchmarks                → list_benchmarks
+//! ```
+//!
+//! Generating data requires `LOAD_TEST_MODE=true`; listing, deleting and
+//! benchmarking work regardless so leftover runs can always be cleaned up.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::benchmark_service::{
+    BenchmarkOptions, BenchmarkReport, BenchmarkRun, BenchmarkService, ScenarioTiming,
+};
+use crate::services::synthetic_data_service::{
+    SyntheticDataRun, SyntheticDataService, SyntheticDataSpec,
+};
+
+/// Load-test routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route(
+            "/synthetic-data",
+            get(list_synthetic_data_runs).post(generate_synthetic_data),
+        )
+        .route("/synthetic-data/:id", delete(delete_synthetic_data_run))
+        .route("/benchmarks", get(list_benchmarks).post(run_benchmark))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListRunsQuery {
+    /// Maximum records to return (default 50, max 500).
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteSyntheticDataResponse {
+    pub repositories_removed: u64,
+}
+
+/// POST /api/v1/admin/load-test/synthetic-data
+#[utoipa::path(
+    post,
+    path = "/synthetic-data",
+    context_path = "/api/v1/admin/load-test",
+    tag = "load_test",
+    request_body = SyntheticDataSpec,
+    responses(
+        (status = 201, description = "Synthetic data generated", body = SyntheticDataRun),
+        (status = 400, description = "Invalid spec", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Load test mode is not enabled", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn generate_synthetic_data(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(spec): Json<SyntheticDataSpec>,
+) -> Result<(StatusCode, Json<SyntheticDataRun>)> {
+    auth.require_admin()?;
+    if !state.config.load_test_mode {
+        return Err(AppError::Authorization(
+            "Load test mode is not enabled; set LOAD_TEST_MODE=true to generate synthetic data"
+                .to_string(),
+        ));
+    }
+
+    let run = SyntheticDataService::new(state.db.clone())
+        .generate(
+            spec,
+            Some(auth.user_id),
+            &state.config.storage_backend,
+            &state.config.storage_path,
+        )
+        .await?;
+    tracing::info!(
+        run_id = %run.id,
+        seed = run.seed,
+        repositories = run.repositories_created,
+        artifacts = run.artifacts_created,
+        downloads = run.downloads_created,
+        duration_ms = run.duration_ms,
+        requested_by = %auth.username,
+        "Synthetic data generated"
+    );
+    Ok((StatusCode::CREATED, Json(run)))
+}
+
+/// GET /api/v1/admin/load-test/synthetic-data
+#[utoipa::path(
+    get,
+    path = "/synthetic-data",
+    context_path = "/api/v1/admin/load-test",
+    tag = "load_test",
+    params(ListRunsQuery),
+    responses(
+        (status = 200, description = "Synthetic data runs, newest first", body = Vec<SyntheticDataRun>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_synthetic_data_runs(
+    State(state): State<SharedState>,
+    Query(query): Query<ListRunsQuery>,
+) -> Result<Json<Vec<SyntheticDataRun>>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    Ok(Json(
+        SyntheticDataService::new(state.db.clone())
+            .list_runs(limit)
+            .await?,
+    ))
+}
+
+/// DELETE /api/v1/admin/load-test/synthetic-data/{id}
+#[utoipa::path(
+    delete,
+    path = "/synthetic-data/{id}",
+    context_path = "/api/v1/admin/load-test",
+    tag = "load_test",
+    params(("id" = Uuid, Path, description = "Synthetic data run ID")),
+    responses(
+        (status = 200, description = "Run and its repositories removed", body = DeleteSyntheticDataResponse),
+        (status = 404, description = "Run not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_synthetic_data_run(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DeleteSyntheticDataResponse>> {
+    auth.require_admin()?;
+    let repositories_removed = SyntheticDataService::new(state.db.clone())
+        .delete_run(id)
+        .await?;
+    tracing::info!(
+        run_id = %id,
+        repositories = repositories_removed,
+        requested_by = %auth.username,
+        "Synthetic data removed"
+    );
+    Ok(Json(DeleteSyntheticDataResponse {
+        repositories_removed,
+    }))
+}
+
+/// POST /api/v1/admin/load-test/benchmarks
+#[utoipa::path(
+    post,
+    path = "/benchmarks",
+    context_path = "/api/v1/admin/load-test",
+    tag = "load_test",
+    request_body = BenchmarkOptions,
+    responses(
+        (status = 201, description = "Benchmark report, compared with the latest comparable run", body = BenchmarkReport),
+        (status = 400, description = "Invalid options", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn run_benchmark(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(options): Json<BenchmarkOptions>,
+) -> Result<(StatusCode, Json<BenchmarkReport>)> {
+    auth.require_admin()?;
+    let report = BenchmarkService::new(state.db.clone(), state.storage_registry.clone())
+        .run(&options, Some(auth.user_id))
+        .await?;
+    if report.regressions > 0 {
+        tracing::warn!(
+            run_id = %report.id,
+            baseline = ?report.baseline_run_id,
+            regressions = report.regressions,
+            "Benchmark regressions detected"
+        );
+    }
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// GET /api/v1/admin/load-test/benchmarks
+#[utoipa::path(
+    get,
+    path = "/benchmarks",
+    context_path = "/api/v1/admin/load-test",
+    tag = "load_test",
+    params(ListRunsQuery),
+    responses(
+        (status = 200, description = "Stored benchmark reports, newest first", body = Vec<BenchmarkRun>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_benchmarks(
+    State(state): State<SharedState>,
+    Query(query): Query<ListRunsQuery>,
+) -> Result<Json<Vec<BenchmarkRun>>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    Ok(Json(
+        BenchmarkService::new(state.db.clone(), state.storage_registry.clone())
+            .list_runs(limit)
+            .await?,
+    ))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        generate_synthetic_data,
+        list_synthetic_data_runs,
+        delete_synthetic_data_run,
+        run_benchmark,
+        list_benchmarks,
+    ),
+    components(schemas(
+        SyntheticDataSpec,
+        SyntheticDataRun,
+        DeleteSyntheticDataResponse,
+        BenchmarkOptions,
+        BenchmarkReport,
+        BenchmarkRun,
+        ScenarioTiming,
+    ))
+)]
+pub struct LoadTestApiDoc;
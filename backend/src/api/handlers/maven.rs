@@ -758,6 +758,105 @@ fn generate_snapshot_metadata_xml(
     ))
 }
 
+/// Files of one `mvn deploy` arrive as separate PUTs; a non-unique SNAPSHOT
+/// file joins the latest build when that build is younger than this and does
+/// not hold the same (classifier, extension) yet.
+const SNAPSHOT_BUILD_WINDOW_SECS: i64 = 300;
+
+/// Rewrite a non-unique SNAPSHOT file path
+/// (`g/a/1.0-SNAPSHOT/a-1.0-SNAPSHOT[-classifier].ext`, sidecars included) to
+/// its unique form `a-1.0-{timestamp}-{buildNumber}[-classifier].ext`.
+///
+/// Returns `None` for release paths, already timestamped SNAPSHOT files and
+/// `maven-metadata.xml`.
+fn unique_snapshot_path(path: &str, timestamp: &str, build_number: u32) -> Option<String> {
+    let (dir, filename) = path.rsplit_once('/')?;
+    let (artifact_dir, version) = dir.rsplit_once('/')?;
+    let artifact_id = artifact_dir.rsplit('/').next()?;
+    let base_version = version.strip_suffix("-SNAPSHOT")?;
+    let rest = filename.strip_prefix(&format!("{}-{}", artifact_id, version))?;
+    if !rest.starts_with(['.', '-']) {
+        return None;
+    }
+    Some(format!(
+        "{}/{}-{}-{}-{}{}",
+        dir, artifact_id, base_version, timestamp, build_number, rest
+    ))
+}
+
+/// Pick the `(timestamp, buildNumber)` for a non-unique SNAPSHOT upload of
+/// `(classifier, extension)` given the builds already stored for the version.
+///
+/// The file joins the latest build while that build is recent and lacks this
+/// file; otherwise a new build is started at `now` with the next build number.
+fn next_snapshot_build(
+    entries: &[SnapshotEntry],
+    classifier: Option<&str>,
+    extension: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> (String, u32) {
+    let now_ts = now.format("%Y%m%d.%H%M%S").to_string();
+    let Some(latest) = entries.iter().max_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then(a.build_number.cmp(&b.build_number))
+    }) else {
+        return (now_ts, 1);
+    };
+
+    let recent = chrono::NaiveDateTime::parse_from_str(&latest.timestamp, "%Y%m%d.%H%M%S")
+        .is_ok_and(|ts| (now.naive_utc() - ts).num_seconds() <= SNAPSHOT_BUILD_WINDOW_SECS);
+    let holds_file = entries.iter().any(|e| {
+        e.timestamp == latest.timestamp
+            && e.build_number == latest.build_number
+            && e.classifier.as_deref() == classifier
+            && e.extension == extension
+    });
+    if recent && !holds_file {
+        return (latest.timestamp.clone(), latest.build_number);
+    }
+    // A build number identifies a build on its own, so it keeps growing even
+    // when two builds land within the same second.
+    let timestamp = now_ts.max(latest.timestamp.clone());
+    (timestamp, latest.build_number + 1)
+}
+
+/// Whether a stored SNAPSHOT `maven-metadata.xml` misses builds recorded in
+/// the repository, e.g. ones the server timestamped itself, so that it has to
+/// be regenerated instead of served verbatim.
+fn snapshot_metadata_is_stale(document: &[SnapshotEntry], stored: &[SnapshotEntry]) -> bool {
+    let latest = |entries: &[SnapshotEntry]| {
+        entries
+            .iter()
+            .map(|e| (e.timestamp.clone(), e.build_number))
+            .max()
+    };
+    match (latest(document), latest(stored)) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(doc), Some(rows)) => rows > doc,
+    }
+}
+
+/// Parse an uploaded checksum sidecar. Accepts the bare digest as well as the
+/// `<digest>  <filename>` form some tools write; the digest must have the
+/// algorithm's length.
+fn parse_checksum_sidecar(content: &[u8], checksum_type: ChecksumType) -> Option<String> {
+    let digest = std::str::from_utf8(content)
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .to_ascii_lowercase();
+    let expected_len = match checksum_type {
+        ChecksumType::Md5 => 32,
+        ChecksumType::Sha1 => 40,
+        ChecksumType::Sha256 => 64,
+        ChecksumType::Sha512 => 128,
+    };
+    (digest.len() == expected_len && digest.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then_some(digest)
+}
+
 /// Check if a path is a checksum request. Returns the base path and checksum type.
 fn parse_checksum_path(path: &str) -> Option<(&str, ChecksumType)> {
     if let Some(base) = path.strip_suffix(".sha512") {
@@ -851,7 +950,7 @@ async fn download_root(
     }
 
     if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let members = proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, "").await?;
         for member in &members {
             if member.repo_type == RepositoryType::Remote {
                 if let (Some(ref upstream_url), Some(ref proxy)) =
@@ -1071,7 +1170,8 @@ async fn download(
             // checksum. A private member's checksum reveals the existence and
             // exact content hash of its artifact, so it must be gated the same
             // way the artifact bytes are.
-            let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+            let members =
+                proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &path).await?;
             let members = proxy_helpers::authorize_virtual_members(
                 &state.permission_service,
                 auth.as_ref(),
@@ -1232,7 +1332,8 @@ async fn fetch_maven_metadata_bytes(
 
     // Virtual repos: merge metadata from all members.
     if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, path).await?;
         let members =
             proxy_helpers::authorize_virtual_members(&state.permission_service, auth, members)
                 .await;
@@ -1437,28 +1538,46 @@ async fn fetch_maven_metadata_bytes(
     // generation below, while foreign/unattributed keys fall through.
     // Repo-scoped candidate first (#2624): the key embeds this repository's
     // id, so no attribution gate is needed for it.
+    let mut stored = None;
     if let Some(scoped_key) = crate::storage::StorageKeyScheme::from_env().scoped_read_key(
         &repo.storage_backend,
         "maven",
         repo.id,
         path,
     ) {
-        if let Ok(content) = storage.get(&scoped_key).await {
-            return Ok(content);
-        }
+        stored = storage.get(&scoped_key).await.ok();
     }
     let meta_storage_key = format!("maven/{}", path);
-    if crate::services::maven_flat_attribution::flat_key_readable(
-        &state.db,
-        repo.id,
-        &repo.storage_backend,
-        &meta_storage_key,
-    )
-    .await
+    if stored.is_none()
+        && crate::services::maven_flat_attribution::flat_key_readable(
+            &state.db,
+            repo.id,
+            &repo.storage_backend,
+            &meta_storage_key,
+        )
+        .await
     {
-        if let Ok(content) = storage.get(&meta_storage_key).await {
-            return Ok(content);
+        stored = storage.get(&meta_storage_key).await.ok();
+    }
+    if let Some(content) = stored {
+        // A stored SNAPSHOT document stays authoritative until a newer build
+        // lands that it does not list (e.g. one the server timestamped for a
+        // non-unique deploy); it is then regenerated from both sources.
+        if let Some((group_id, artifact_id, version)) = parse_snapshot_metadata_path(path) {
+            let mut entries = parse_snapshot_versions_xml(&String::from_utf8_lossy(&content));
+            let rows =
+                collect_snapshot_entries(&state.db, repo.id, &group_id, &artifact_id, &version)
+                    .await;
+            if snapshot_metadata_is_stale(&entries, &rows) {
+                entries.extend(rows);
+                if let Some(xml) =
+                    generate_snapshot_metadata_xml(&group_id, &artifact_id, &version, &entries)
+                {
+                    return Ok(Bytes::from(xml));
+                }
+            }
         }
+        return Ok(content);
     }
 
     if let Some((group_id, artifact_id)) = parse_metadata_path(path) {
@@ -1752,7 +1871,8 @@ async fn serve_artifact(
                 // caller could not read directly are dropped, so a denied
                 // member behaves exactly as if it did not contain the artifact
                 // (404), never leaking its existence.
-                let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+                let members =
+                    proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, path).await?;
                 let members = proxy_helpers::authorize_virtual_members(
                     &state.permission_service,
                     auth,
@@ -2024,10 +2144,15 @@ async fn serve_computed_checksum(
         }
     };
 
-    // For SHA-256 we already have it stored
-    let checksum = match checksum_type {
-        ChecksumType::Sha256 => resolved_sha256,
-        _ => {
+    // SHA-256 is always stored; the others usually are. Hash the content
+    // only for rows that predate them.
+    let stored = match checksum_type {
+        ChecksumType::Sha256 => Some(resolved_sha256),
+        _ => stored_checksum(&state.db, repo_id, &resolved_storage_key, checksum_type).await,
+    };
+    let checksum = match stored {
+        Some(checksum) => checksum,
+        None => {
             let storage = state.storage_for_repo_or_500(location)?;
             let content = storage
                 .get(&resolved_storage_key)
@@ -2044,6 +2169,34 @@ async fn serve_computed_checksum(
         .unwrap())
 }
 
+/// A digest recorded at ingest for the content at `storage_key`.
+async fn stored_checksum(
+    db: &sqlx::PgPool,
+    repo_id: uuid::Uuid,
+    storage_key: &str,
+    checksum_type: ChecksumType,
+) -> Option<String> {
+    let column = match checksum_type {
+        ChecksumType::Md5 => "checksum_md5",
+        ChecksumType::Sha1 => "checksum_sha1",
+        ChecksumType::Sha256 => "checksum_sha256",
+        ChecksumType::Sha512 => "checksum_sha512",
+    };
+    let value: Option<String> = sqlx::query_scalar(&format!(
+        "SELECT {column} FROM artifacts \
+         WHERE repository_id = $1 AND storage_key = $2 AND is_deleted = false \
+           AND {column} IS NOT NULL \
+         LIMIT 1"
+    ))
+    .bind(repo_id)
+    .bind(storage_key)
+    .fetch_optional(db)
+    .await
+    .ok()
+    .flatten();
+    value.map(|v| v.trim().to_string())
+}
+
 fn compute_checksum(data: &[u8], checksum_type: ChecksumType) -> String {
     match checksum_type {
         ChecksumType::Md5 => {
@@ -2180,6 +2333,110 @@ async fn queue_maven_sync_tasks(
 // PUT /maven/{repo_key}/*path — Upload artifact
 // ---------------------------------------------------------------------------
 
+/// Largest checksum sidecar accepted: a SHA-512 digest plus a filename.
+const MAX_CHECKSUM_SIDECAR_BYTES: i64 = 1024;
+
+/// The path a Maven upload is stored under. Non-unique SNAPSHOT files
+/// (`lib-1.0-SNAPSHOT.jar`, as deployed by clients with `uniqueVersion=false`
+/// or plain HTTP PUTs) get a server-assigned timestamp and build number so
+/// every deploy stays addressable and `maven-metadata.xml` can list it. Their
+/// checksum sidecars follow the file they belong to.
+async fn maven_upload_path(db: &PgPool, repo_id: Uuid, path: &str) -> String {
+    let file_path = parse_checksum_path(path).map_or(path, |(base_path, _)| base_path);
+    // Only non-unique SNAPSHOT files have a unique form to rewrite to.
+    if unique_snapshot_path(file_path, "", 0).is_none() {
+        return path.to_string();
+    }
+    let Ok(coords) = MavenHandler::parse_coordinates(file_path) else {
+        return path.to_string();
+    };
+    let entries = collect_snapshot_entries(
+        db,
+        repo_id,
+        &coords.group_id,
+        &coords.artifact_id,
+        &coords.version,
+    )
+    .await;
+    let build = if file_path == path {
+        Some(next_snapshot_build(
+            &entries,
+            coords.classifier.as_deref(),
+            &coords.extension,
+            chrono::Utc::now(),
+        ))
+    } else {
+        // A sidecar belongs to the latest build of its file.
+        entries
+            .iter()
+            .filter(|e| {
+                e.classifier.as_deref() == coords.classifier.as_deref()
+                    && e.extension == coords.extension
+            })
+            .map(|e| (e.timestamp.clone(), e.build_number))
+            .max()
+    };
+    build
+        .and_then(|(timestamp, build_number)| unique_snapshot_path(path, &timestamp, build_number))
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Reject a checksum sidecar that is malformed or disagrees with the digest
+/// recorded for the artifact it belongs to. Sidecars uploaded before their
+/// artifact, or whose digest is not recorded yet (SHA-512 is filled in by the
+/// checksum backfill), are only checked for shape.
+async fn verify_checksum_sidecar(
+    db: &PgPool,
+    repo_id: Uuid,
+    base_path: &str,
+    checksum_type: ChecksumType,
+    staged: &proxy_helpers::StagedUpload,
+) -> Result<(), Response> {
+    let suffix = checksum_suffix(checksum_type);
+    if staged.size_bytes() > MAX_CHECKSUM_SIDECAR_BYTES {
+        return Err(
+            AppError::Validation(format!("Checksum file .{} is too large", suffix)).into_response(),
+        );
+    }
+    let content = tokio::fs::read(staged.path())
+        .await
+        .map_err(|e| proxy_helpers::internal_error("Reading staged checksum", e))?;
+    let digest = parse_checksum_sidecar(&content, checksum_type).ok_or_else(|| {
+        AppError::Validation(format!("Malformed .{} checksum file", suffix)).into_response()
+    })?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT checksum_md5, checksum_sha1, checksum_sha256, checksum_sha512
+        FROM artifacts
+        WHERE repository_id = $1 AND path = $2 AND is_deleted = false
+        "#,
+    )
+    .bind(repo_id)
+    .bind(base_path)
+    .fetch_optional(db)
+    .await
+    .map_err(map_db_err)?;
+
+    use sqlx::Row;
+    let expected: Option<String> = row.and_then(|row| match checksum_type {
+        ChecksumType::Md5 => row.get("checksum_md5"),
+        ChecksumType::Sha1 => row.get("checksum_sha1"),
+        ChecksumType::Sha256 => row.get("checksum_sha256"),
+        ChecksumType::Sha512 => row.get("checksum_sha512"),
+    });
+    if let Some(expected) = expected {
+        if !expected.eq_ignore_ascii_case(&digest) {
+            return Err(AppError::Validation(format!(
+                "Checksum mismatch for {}: .{} is {} but the stored artifact has {}",
+                base_path, suffix, digest, expected
+            ))
+            .into_response());
+        }
+    }
+    Ok(())
+}
+
 async fn upload(
     State(state): State<SharedState>,
     Extension(auth): Extension<Option<AuthExtension>>,
@@ -2208,6 +2465,8 @@ async fn upload(
     .unwrap_or(false);
     proxy_helpers::reject_direct_upload_if_promotion_only(promotion_only, auth.is_admin)?;
 
+    let path = maven_upload_path(&state.db, repo.id, &path).await;
+
     // #2624: on shared cloud namespaces new objects are written under a
     // repository-scoped key (`maven/{repository_id}/{path}`) so the physical
     // key can never collide with — or be claimed by — another repository.
@@ -2251,10 +2510,15 @@ async fn upload(
     let (staged, digests) =
         proxy_helpers::stage_stream_content_addressed(&state, body.into_data_stream()).await?;
 
-    // If this is a checksum file (.sha1, .md5, .sha256), just store it and return.
+    // If this is a checksum file (.sha1, .md5, .sha256), verify it against the
+    // artifact it belongs to, store it and return. Metadata sidecars are not
+    // verified: their checksums are always computed from the served document.
     // These row-less puts create no artifact row, so attribution is committed
     // here -- only after the object bytes are durably written (#2574, V3b).
-    if parse_checksum_path(&path).is_some() {
+    if let Some((base_path, checksum_type)) = parse_checksum_path(&path) {
+        if !MavenHandler::is_metadata(base_path) {
+            verify_checksum_sidecar(&state.db, repo.id, base_path, checksum_type, &staged).await?;
+        }
         // Atomically claim the flat key BEFORE writing its bytes: exactly one
         // concurrent first-publisher wins, the rest are refused, so the stored
         // bytes and the attributed owner can never disagree (#2586). Release the
@@ -3497,6 +3761,123 @@ mod tests {
         assert_eq!(occurrences, 2);
     }
 
+    // -----------------------------------------------------------------------
+    // Server-assigned SNAPSHOT timestamps and checksum sidecars
+    // -----------------------------------------------------------------------
+
+    fn snapshot_entry(classifier: Option<&str>, ext: &str, ts: &str, n: u32) -> SnapshotEntry {
+        SnapshotEntry {
+            classifier: classifier.map(String::from),
+            extension: ext.into(),
+            timestamp: ts.into(),
+            build_number: n,
+        }
+    }
+
+    #[test]
+    fn test_unique_snapshot_path() {
+        assert_eq!(
+            unique_snapshot_path(
+                "com/example/lib/1.0-SNAPSHOT/lib-1.0-SNAPSHOT.jar",
+                "20261016.120000",
+                3
+            )
+            .as_deref(),
+            Some("com/example/lib/1.0-SNAPSHOT/lib-1.0-20261016.120000-3.jar")
+        );
+        assert_eq!(
+            unique_snapshot_path(
+                "com/example/lib/1.0-SNAPSHOT/lib-1.0-SNAPSHOT-sources.jar.sha1",
+                "20261016.120000",
+                1
+            )
+            .as_deref(),
+            Some("com/example/lib/1.0-SNAPSHOT/lib-1.0-20261016.120000-1-sources.jar.sha1")
+        );
+        // Already unique, release, metadata and foreign filenames are kept.
+        for path in [
+            "com/example/lib/1.0-SNAPSHOT/lib-1.0-20260101.120000-1.jar",
+            "com/example/lib/1.0/lib-1.0.jar",
+            "com/example/lib/1.0-SNAPSHOT/maven-metadata.xml",
+            "com/example/lib/1.0-SNAPSHOT/other-1.0-SNAPSHOT.jar",
+            "com/example/lib/1.0-SNAPSHOT/lib-1.0-SNAPSHOTx.jar",
+        ] {
+            assert_eq!(unique_snapshot_path(path, "20261016.120000", 1), None);
+        }
+    }
+
+    #[test]
+    fn test_next_snapshot_build_joins_recent_build() {
+        use chrono::TimeZone;
+        let now = chrono::Utc
+            .with_ymd_and_hms(2026, 10, 16, 12, 1, 0)
+            .unwrap();
+        assert_eq!(
+            next_snapshot_build(&[], None, "jar", now),
+            ("20261016.120100".to_string(), 1)
+        );
+
+        let entries = vec![
+            snapshot_entry(None, "jar", "20261015.080000", 1),
+            snapshot_entry(None, "pom", "20261016.120000", 2),
+        ];
+        // The jar of the deploy that just uploaded its POM joins build 2.
+        assert_eq!(
+            next_snapshot_build(&entries, None, "jar", now),
+            ("20261016.120000".to_string(), 2)
+        );
+        // A second POM means a new deploy.
+        assert_eq!(
+            next_snapshot_build(&entries, None, "pom", now),
+            ("20261016.120100".to_string(), 3)
+        );
+        // An old build is never joined.
+        let later = now + chrono::Duration::hours(1);
+        assert_eq!(
+            next_snapshot_build(&entries, Some("sources"), "jar", later),
+            ("20261016.130100".to_string(), 3)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_metadata_is_stale() {
+        let doc = vec![snapshot_entry(None, "jar", "20261016.120000", 1)];
+        assert!(!snapshot_metadata_is_stale(&doc, &[]));
+        assert!(!snapshot_metadata_is_stale(&doc, &doc));
+        assert!(snapshot_metadata_is_stale(
+            &doc,
+            &[snapshot_entry(None, "jar", "20261016.120000", 2)]
+        ));
+        assert!(snapshot_metadata_is_stale(&[], &doc));
+    }
+
+    #[test]
+    fn test_parse_checksum_sidecar() {
+        let sha1 = "4ebaf1c9ee04a730b07c9301577c4681b5c15fb6";
+        assert_eq!(
+            parse_checksum_sidecar(sha1.as_bytes(), ChecksumType::Sha1).as_deref(),
+            Some(sha1)
+        );
+        let with_name = format!("{}  lib-1.0.jar\n", sha1.to_uppercase());
+        assert_eq!(
+            parse_checksum_sidecar(with_name.as_bytes(), ChecksumType::Sha1).as_deref(),
+            Some(sha1)
+        );
+        assert_eq!(
+            parse_checksum_sidecar(sha1.as_bytes(), ChecksumType::Md5),
+            None
+        );
+        assert_eq!(
+            parse_checksum_sidecar(b"zz112233445566778899aabbccddeeff", ChecksumType::Md5),
+            None
+        );
+        assert_eq!(parse_checksum_sidecar(b"  \n", ChecksumType::Sha256), None);
+        assert_eq!(
+            parse_checksum_sidecar(&[0xff; 40], ChecksumType::Sha1),
+            None
+        );
+    }
+
     #[test]
     fn test_generate_snapshot_metadata_xml_dedupes_by_key() {
         // Two entries for the same (classifier=None, extension=jar) key; the
@@ -4033,7 +4414,7 @@ mod tests {
         // -- Row-less checksum sidecar: stored scoped, served via the scoped
         //    read candidate (no attribution row needed).
         let sha1_path = format!("{path}.sha1");
-        let sha1 = bytes::Bytes::from_static(b"da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        let sha1 = bytes::Bytes::from_static(b"4ebaf1c9ee04a730b07c9301577c4681b5c15fb6");
         let (status, _) = tdh::send(
             router.clone(),
             tdh::put(format!("/{repo_key}/{sha1_path}"), sha1.clone()),
@@ -5230,6 +5611,130 @@ mod tests {
         );
     }
 
+    /// VIRTUAL maven repo whose LOCAL member includes `com/example/routed/*`
+    /// and whose REMOTE member excludes it: the merged `maven-metadata.xml`
+    /// must come from the local member alone, and the remote upstream must
+    /// never be asked (wiremock `expect(0)`), so a public upstream cannot
+    /// inject versions into an internal coordinate. DB-gated.
+    #[tokio::test]
+    async fn test_virtual_metadata_honours_member_routing_patterns() {
+        use crate::api::handlers::test_db_helpers as tdh;
+        use axum::extract::{Path, State};
+        use axum::Extension;
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let Some(pool) = tdh::try_pool().await else {
+            return;
+        };
+
+        let group_id = "com.example.routed";
+        let artifact_id = "lib";
+        let meta_path = format!(
+            "{}/{}/maven-metadata.xml",
+            group_id.replace('.', "/"),
+            artifact_id
+        );
+        let upstream_meta = generate_metadata_xml(
+            group_id,
+            artifact_id,
+            &["3.0.0".to_string()],
+            "3.0.0",
+            Some("3.0.0"),
+            "20240101000000",
+        );
+        let mock = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r".*maven-metadata\.xml$"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(upstream_meta))
+            .expect(0)
+            .mount(&mock)
+            .await;
+
+        let (local_id, _lk, dir_l) = tdh::create_repo(&pool, "local", "maven").await;
+        let (remote_id, _rk, dir_r) = tdh::create_repo(&pool, "remote", "maven").await;
+        sqlx::query("UPDATE repositories SET upstream_url = $1 WHERE id = $2")
+            .bind(mock.uri())
+            .bind(remote_id)
+            .execute(&pool)
+            .await
+            .expect("point remote upstream at mock");
+        let (user_id, username) = tdh::create_user(&pool).await;
+        seed_maven_version(&pool, local_id, user_id, group_id, artifact_id, "1.0.0").await;
+
+        let virtual_id = uuid::Uuid::new_v4();
+        let virtual_key = format!("v-routed-{}", virtual_id.simple());
+        let virtual_dir = std::env::temp_dir().join(format!("routed-{}", virtual_id));
+        std::fs::create_dir_all(&virtual_dir).expect("create virtual dir");
+        sqlx::query(
+            "INSERT INTO repositories (id, key, name, storage_path, repo_type, format) \
+             VALUES ($1, $2, $3, $4, 'virtual'::repository_type, 'maven'::repository_format)",
+        )
+        .bind(virtual_id)
+        .bind(&virtual_key)
+        .bind(&virtual_key)
+        .bind(virtual_dir.to_string_lossy().as_ref())
+        .execute(&pool)
+        .await
+        .expect("insert virtual repo");
+        let pinned = vec!["com/example/routed/*".to_string()];
+        for (i, (m, include, exclude)) in [
+            (local_id, pinned.clone(), Vec::new()),
+            (remote_id, Vec::new(), pinned.clone()),
+        ]
+        .iter()
+        .enumerate()
+        {
+            sqlx::query(
+                "INSERT INTO virtual_repo_members \
+                 (virtual_repo_id, member_repo_id, priority, include_patterns, exclude_patterns) \
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(virtual_id)
+            .bind(m)
+            .bind(i as i32)
+            .bind(include)
+            .bind(exclude)
+            .execute(&pool)
+            .await
+            .expect("link virtual member");
+        }
+
+        let proxy = tdh::build_proxy_service_with_fs(pool.clone(), dir_r.to_str().unwrap());
+        let state = tdh::build_state_with_proxy(pool.clone(), dir_r.to_str().unwrap(), proxy);
+        let auth = tdh::make_auth(user_id, &username);
+
+        let resp = download(
+            State(state.clone()),
+            Extension(Some(auth.clone())),
+            Path((virtual_key.clone(), meta_path.clone())),
+            HeaderMap::new(),
+            Default::default(),
+        )
+        .await
+        .expect("virtual metadata download must succeed");
+        let body = axum::body::to_bytes(resp.into_body(), 1 << 20)
+            .await
+            .expect("read merged metadata body");
+        let body_str = String::from_utf8(body.to_vec()).expect("merged metadata is utf-8");
+
+        let _ = sqlx::query("DELETE FROM repositories WHERE id = $1")
+            .bind(virtual_id)
+            .execute(&pool)
+            .await;
+        tdh::cleanup(&pool, local_id, user_id).await;
+        tdh::cleanup(&pool, remote_id, user_id).await;
+        let _ = std::fs::remove_dir_all(&dir_l);
+        let _ = std::fs::remove_dir_all(&dir_r);
+        let _ = std::fs::remove_dir_all(&virtual_dir);
+
+        assert!(
+            body_str.contains("1.0.0") && !body_str.contains("3.0.0"),
+            "metadata for a coordinate pinned to the local member must not \
+             merge the excluded remote member's versions; got: {body_str}"
+        );
+    }
+
     /// #1562: a virtual repo must serve an artifact that one of its REMOTE
     /// members can proxy-fetch on first request, even when no local member
     /// holds it (e.g. a remote-only parent POM like `io.confluent:common`).
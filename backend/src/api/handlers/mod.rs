@@ -209,17 +209,26 @@ pub(crate) mod test_db_helpers;
 
 pub mod admin;
 pub mod admin_security;
+pub mod admission;
 pub mod age_gate;
+pub mod airlock;
 pub mod alpine;
 pub mod analytics;
 pub mod ansible;
+pub mod api_token_usage;
 pub mod approval;
+pub mod artifact_consumers;
 pub mod artifact_labels;
 pub mod artifacts;
 pub mod auth;
+pub mod authz_hook;
+pub mod badges;
+pub mod blocklist;
 pub mod builds;
 pub mod cache_headers;
+pub mod cache_warming;
 pub mod cargo;
+pub mod chat_integrations;
 pub mod chef;
 pub mod ci_auth;
 pub mod ci_auth_admin;
@@ -229,10 +238,18 @@ pub mod conan;
 pub mod conda;
 pub mod cran;
 pub mod curation;
+pub mod data_subjects;
 pub mod debian;
+pub mod dependency_graph;
 pub mod dependency_track;
+pub mod deploy_gate;
+pub mod direct_uploads;
+pub mod edge_diagnostics;
 pub mod email_subscriptions;
 pub mod events;
+pub mod exports;
+pub mod federation;
+pub mod freeze_windows;
 pub mod general;
 pub mod gitlfs;
 pub mod goproxy;
@@ -242,8 +259,10 @@ pub mod helm;
 pub mod hex;
 pub mod huggingface;
 pub mod incus;
+pub mod issue_trackers;
 pub mod jetbrains;
 pub mod lifecycle;
+pub mod load_test;
 pub mod maven;
 pub mod maven_proxy;
 pub mod migration;
@@ -251,9 +270,11 @@ pub mod monitoring;
 pub mod npm;
 pub mod nuget;
 pub mod oci_v2;
+pub mod package_protection;
 pub mod packages;
 pub mod peer;
 pub mod peer_instance_labels;
+pub mod peer_join;
 pub mod peers;
 pub mod permissions;
 pub mod plugins;
@@ -264,16 +285,27 @@ pub mod promotion_rules;
 pub mod protobuf;
 pub mod proxy_helpers;
 pub mod pub_registry;
+pub mod public_mirror;
 pub mod puppet;
 pub mod pypi;
 pub mod quality_gates;
 pub mod quarantine;
 pub mod remote_instances;
+pub mod remote_repositories;
 pub mod repo_tokens;
 pub mod repositories;
+pub mod repository_aliases;
+pub mod repository_delete_protection;
+pub mod repository_events;
 pub mod repository_labels;
+pub mod repository_metadata_schemas;
+pub mod repository_replicated_config;
+pub mod repository_stats;
+pub mod repository_version_rules;
+pub mod repository_worm;
 pub mod rpm;
 pub mod rubygems;
+pub mod s3_gateway;
 pub mod sbom;
 pub mod sbt;
 pub mod search;
@@ -283,7 +315,10 @@ pub mod signing;
 pub mod smtp;
 pub mod sso;
 pub mod sso_admin;
+pub mod storage_encryption;
 pub mod storage_gc;
+pub mod storage_recovery;
+pub mod storage_shards;
 pub mod swift;
 pub mod sync_policies;
 pub mod system_config;
@@ -294,8 +329,11 @@ pub mod transfer;
 pub mod tree;
 pub mod upload;
 pub mod users;
+pub mod version_deprecations;
 pub mod vscode;
+pub mod vulnerability_watchlist;
 pub mod wasm_proxy;
+pub mod webdav;
 pub mod webhooks;
 
 #[allow(clippy::disallowed_methods)]
@@ -1,13 +1,15 @@
 //! Health monitoring API handlers.
 
 use axum::{
-    extract::{Extension, Query, State},
-    routing::{get, post},
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
     Router,
 };
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
 
 // Use the project's custom `Json` extractor so a malformed/incomplete request
 // body (e.g. missing `service_name`) surfaces as the standard 400 +
@@ -20,11 +22,36 @@ use crate::error::{AppError, Result};
 use crate::services::health_monitor_service::{
     AlertState, HealthMonitorService, MonitorConfig, ServiceHealthEntry,
 };
+use crate::services::incident_escalation_service::{
+    CreateIncidentIntegration, IncidentEscalation, IncidentEscalationService, IncidentIntegration,
+    IncidentProvider, IncidentSeverity, UpdateIncidentIntegration,
+};
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(get_health_log, get_alert_states, suppress_alert, run_health_check,),
-    components(schemas(SuppressRequest, ServiceHealthEntry, AlertState,))
+    paths(
+        get_health_log,
+        get_alert_states,
+        suppress_alert,
+        run_health_check,
+        list_incident_integrations,
+        create_incident_integration,
+        update_incident_integration,
+        delete_incident_integration,
+        test_incident_integration,
+        list_incidents,
+    ),
+    components(schemas(
+        SuppressRequest,
+        ServiceHealthEntry,
+        AlertState,
+        IncidentProvider,
+        IncidentSeverity,
+        IncidentIntegration,
+        IncidentEscalation,
+        CreateIncidentIntegration,
+        UpdateIncidentIntegration,
+    ))
 )]
 pub struct MonitoringApiDoc;
 
@@ -34,6 +61,25 @@ pub fn router() -> Router<SharedState> {
         .route("/alerts", get(get_alert_states))
         .route("/alerts/suppress", post(suppress_alert))
         .route("/check", post(run_health_check))
+        .route(
+            "/escalations",
+            get(list_incident_integrations).post(create_incident_integration),
+        )
+        .route(
+            "/escalations/:id",
+            put(update_incident_integration).delete(delete_incident_integration),
+        )
+        .route("/escalations/:id/test", post(test_incident_integration))
+        .route("/incidents", get(list_incidents))
+}
+
+fn require_admin(auth: &AuthExtension) -> Result<()> {
+    if !auth.is_admin {
+        return Err(AppError::Unauthorized(
+            "Admin privileges required".to_string(),
+        ));
+    }
+    Ok(())
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
@@ -151,11 +197,170 @@ pub async fn run_health_check(
             "Admin privileges required".to_string(),
         ));
     }
-    let monitor = HealthMonitorService::new(state.db.clone(), MonitorConfig::default());
+    let monitor = HealthMonitorService::new(state.db.clone(), MonitorConfig::default())
+        .with_incident_escalation(IncidentEscalationService::new(state.db.clone()));
     let results = monitor.check_all_services(&state.config).await?;
     Ok(Json(results))
 }
 
+/// GET /api/v1/admin/monitoring/escalations
+#[utoipa::path(
+    get,
+    path = "/escalations",
+    context_path = "/api/v1/admin/monitoring",
+    tag = "monitoring",
+    responses(
+        (status = 200, description = "PagerDuty / Opsgenie integrations", body = Vec<IncidentIntegration>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_incident_integrations(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<Vec<IncidentIntegration>>> {
+    require_admin(&auth)?;
+    let svc = IncidentEscalationService::new(state.db.clone());
+    Ok(Json(svc.list().await?))
+}
+
+/// POST /api/v1/admin/monitoring/escalations
+#[utoipa::path(
+    post,
+    path = "/escalations",
+    context_path = "/api/v1/admin/monitoring",
+    tag = "monitoring",
+    request_body = CreateIncidentIntegration,
+    responses(
+        (status = 201, description = "Integration created", body = IncidentIntegration),
+        (status = 400, description = "Validation error"),
+        (status = 409, description = "Name already in use"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn create_incident_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<CreateIncidentIntegration>,
+) -> Result<(StatusCode, Json<IncidentIntegration>)> {
+    require_admin(&auth)?;
+    crate::services::webhook_secret_crypto::ensure_configured()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let svc = IncidentEscalationService::new(state.db.clone());
+    let integration = svc.create(&payload, auth.user_id).await?;
+    Ok((StatusCode::CREATED, Json(integration)))
+}
+
+/// PUT /api/v1/admin/monitoring/escalations/{id}
+#[utoipa::path(
+    put,
+    path = "/escalations/{id}",
+    context_path = "/api/v1/admin/monitoring",
+    tag = "monitoring",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    request_body = UpdateIncidentIntegration,
+    responses(
+        (status = 200, description = "Integration updated", body = IncidentIntegration),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn update_incident_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateIncidentIntegration>,
+) -> Result<Json<IncidentIntegration>> {
+    require_admin(&auth)?;
+    if payload.credential.is_some() {
+        crate::services::webhook_secret_crypto::ensure_configured()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+    }
+    let svc = IncidentEscalationService::new(state.db.clone());
+    Ok(Json(svc.update(id, &payload).await?))
+}
+
+/// DELETE /api/v1/admin/monitoring/escalations/{id}
+#[utoipa::path(
+    delete,
+    path = "/escalations/{id}",
+    context_path = "/api/v1/admin/monitoring",
+    tag = "monitoring",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 204, description = "Integration deleted"),
+        (status = 404, description = "Integration not found"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_incident_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_admin(&auth)?;
+    let svc = IncidentEscalationService::new(state.db.clone());
+    svc.delete(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/admin/monitoring/escalations/{id}/test - trigger and resolve
+/// a test incident
+#[utoipa::path(
+    post,
+    path = "/escalations/{id}/test",
+    context_path = "/api/v1/admin/monitoring",
+    tag = "monitoring",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 204, description = "Test incident delivered"),
+        (status = 404, description = "Integration not found"),
+        (status = 500, description = "Provider rejected the test incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn test_incident_integration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_admin(&auth)?;
+    let svc = IncidentEscalationService::new(state.db.clone());
+    svc.send_test(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct IncidentQuery {
+    /// Only return incidents that are still open.
+    #[serde(default)]
+    pub open: bool,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/v1/admin/monitoring/incidents
+#[utoipa::path(
+    get,
+    path = "/incidents",
+    context_path = "/api/v1/admin/monitoring",
+    tag = "monitoring",
+    params(IncidentQuery),
+    responses(
+        (status = 200, description = "Incidents opened by health alerts", body = Vec<IncidentEscalation>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_incidents(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<IncidentQuery>,
+) -> Result<Json<Vec<IncidentEscalation>>> {
+    require_admin(&auth)?;
+    let svc = IncidentEscalationService::new(state.db.clone());
+    let limit = clamp_health_log_limit(query.limit);
+    Ok(Json(svc.list_incidents(query.open, limit).await?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -322,4 +527,23 @@ mod tests {
         assert_eq!(json["consecutive_failures"], 5);
         assert!(json["suppressed_until"].is_null());
     }
+
+    // ── Incident escalation ─────────────────────────────────────────
+
+    #[test]
+    fn test_incident_query_defaults() {
+        let q: IncidentQuery = serde_json::from_str("{}").unwrap();
+        assert!(!q.open);
+        assert!(q.limit.is_none());
+    }
+
+    #[test]
+    fn test_require_admin_rejects_non_admin() {
+        assert!(require_admin(&AuthExtension::default()).is_err());
+        let admin = AuthExtension {
+            is_admin: true,
+            ..Default::default()
+        };
+        assert!(require_admin(&admin).is_ok());
+    }
 }
@@ -11,6 +11,8 @@
 //!   GET  /npm/{repo_key}/{@scope}/{package}/-/{filename} - Download scoped tarball
 //!   PUT  /npm/{repo_key}/{package}                    - Publish package
 //!   PUT  /npm/{repo_key}/{@scope}/{package}           - Publish scoped package
+//!   PUT  /npm/{repo_key}/-/user/org.couchdb.user:{name} - `npm login` (issues an API token)
+//!   DELETE /npm/{repo_key}/-/user/token/{token}        - `npm logout` (revokes it)
 
 use axum::body::Body;
 use axum::extract::{Path, State};
@@ -19,7 +21,7 @@ use axum::http::header::{
 };
 use axum::http::{HeaderMap, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{get, post, put};
+use axum::routing::{delete, get, post, put};
 use axum::Extension;
 use axum::Router;
 use base64::Engine;
@@ -38,10 +40,16 @@ use crate::api::SharedState;
 use crate::error::AppError;
 use crate::models::repository::{RepositoryFormat, RepositoryType};
 use crate::services::age_gate_service::{AgeGateDecision, AgeGateService};
+use crate::services::audit_service::{api_token_audit_entry, audit_fire_and_forget, AuditAction};
+use crate::services::auth_service::AuthService;
 use crate::services::npm_packument_cache::{
     self as packument_cache, CachedPackument, NpmPackumentCache,
 };
+use crate::services::package_docs_service;
 use crate::services::upstream_metadata::UpstreamMetadataCache;
+use crate::services::version_deprecation_service::{
+    npm_deprecation_message, VersionDeprecationService,
+};
 use chrono::Utc;
 
 // ---------------------------------------------------------------------------
@@ -87,6 +95,11 @@ pub fn router() -> Router<SharedState> {
         // wildcard because axum prefers literal segments over `*` wildcards.
         // See the filed issue for the full reproducer and impact analysis.
         .route("/:repo_key/-/*rest", get(npm_meta_get))
+        // npm login / logout (legacy CouchDB user API). The visibility
+        // middleware lets these through unauthenticated: the handlers check
+        // the credentials in the request themselves.
+        .route("/:repo_key/-/user/:user", put(npm_login))
+        .route("/:repo_key/-/user/token/:token", delete(npm_logout))
         // Scoped package tarball: GET /npm/{repo_key}/@{scope}/{package}/-/{filename}
         .route(
             "/:repo_key/@:scope/:package/-/:filename",
@@ -1271,7 +1284,12 @@ async fn npm_meta_get(
     // Virtual: try the first Remote member whose upstream is reachable, applying
     // that member's scope policy (parity with the metadata/packument loops).
     if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let members = proxy_helpers::fetch_virtual_members_for_path(
+            &state.db,
+            repo.id,
+            &format!("-/{}", rest.trim_start_matches('/')),
+        )
+        .await?;
         for member in &members {
             if member.repo_type != RepositoryType::Remote {
                 continue;
@@ -1322,6 +1340,161 @@ async fn npm_meta_get(
         .into_response())
 }
 
+// ---------------------------------------------------------------------------
+// npm login / logout
+// ---------------------------------------------------------------------------
+
+/// Path prefix of the CouchDB user document `npm login` writes.
+const NPM_USER_DOC_PREFIX: &str = "org.couchdb.user:";
+
+/// Scopes of the API token `npm login` issues: install and publish.
+const NPM_LOGIN_TOKEN_SCOPES: &[&str] = &["read", "write"];
+
+/// Body of the CouchDB-style user document `npm login` (`--auth-type=legacy`,
+/// and the fallback of web login) PUTs.
+#[derive(serde::Deserialize)]
+struct NpmLoginRequest {
+    name: String,
+    password: String,
+}
+
+fn npm_login_error(status: StatusCode, message: &str) -> Response {
+    (status, axum::Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+/// Username from a `/-/user/org.couchdb.user:{name}` path segment.
+fn npm_login_username(user_doc: &str) -> Option<&str> {
+    user_doc
+        .strip_prefix(NPM_USER_DOC_PREFIX)
+        .filter(|name| !name.is_empty())
+}
+
+/// Handler for `PUT /npm/{repo_key}/-/user/org.couchdb.user:{name}`.
+///
+/// Authenticates the username and password in the body and answers with a
+/// new API token, which npm stores as the registry's `_authToken`. Account
+/// creation is not supported: unknown users get the same 401 as a wrong
+/// password. As on `docker login`, accounts with TOTP enabled must use a
+/// personal access token instead, since npm cannot answer the challenge.
+async fn npm_login(
+    State(state): State<SharedState>,
+    Path((repo_key, user_doc)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Response, Response> {
+    let username = npm_login_username(&user_doc).ok_or_else(|| {
+        npm_login_error(
+            StatusCode::BAD_REQUEST,
+            "expected /-/user/org.couchdb.user:<name>",
+        )
+    })?;
+    let login: NpmLoginRequest = serde_json::from_slice(&body)
+        .map_err(|_| npm_login_error(StatusCode::BAD_REQUEST, "invalid login request body"))?;
+    if login.name != username {
+        return Err(npm_login_error(
+            StatusCode::BAD_REQUEST,
+            "username in the body does not match the URL",
+        ));
+    }
+
+    let auth_service =
+        AuthService::new(state.db.clone(), std::sync::Arc::new(state.config.clone()));
+    let (user, _) = auth_service
+        .authenticate(&login.name, &login.password)
+        .await
+        .map_err(|_| npm_login_error(StatusCode::UNAUTHORIZED, "invalid username or password"))?;
+    if user.totp_enabled {
+        return Err(npm_login_error(
+            StatusCode::UNAUTHORIZED,
+            "TOTP 2FA is enabled on this account. Create a personal access token \
+             and set it as the registry's _authToken instead.",
+        ));
+    }
+    // Resolved only after authenticating, so an anonymous caller cannot
+    // tell existing repository keys from unknown ones.
+    resolve_npm_repo(&state.db, &repo_key).await?;
+
+    let scopes: Vec<String> = NPM_LOGIN_TOKEN_SCOPES
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    crate::services::token_service::enforce_admin_only_scopes(&scopes, user.is_admin)
+        .map_err(|e| npm_login_error(StatusCode::FORBIDDEN, &e))?;
+    let token_name = format!("npm login ({})", repo_key);
+    let (token, token_id) = auth_service
+        .generate_api_token(user.id, &token_name, scopes, None)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    audit_fire_and_forget(
+        state.db.clone(),
+        api_token_audit_entry(
+            AuditAction::ApiTokenCreated,
+            user.id,
+            token_id,
+            Some(&token_name),
+            "npm-login",
+        ),
+    )
+    .await;
+    info!(user = %user.username, repository = %repo_key, "npm login issued an API token");
+
+    Ok((
+        StatusCode::CREATED,
+        axum::Json(serde_json::json!({
+            "ok": true,
+            "id": user_doc,
+            "token": token,
+        })),
+    )
+        .into_response())
+}
+
+/// Handler for `DELETE /npm/{repo_key}/-/user/token/{token}` (`npm logout`).
+///
+/// Presenting the token proves possession; it is revoked whoever sends it.
+async fn npm_logout(
+    State(state): State<SharedState>,
+    Path((repo_key, token)): Path<(String, String)>,
+) -> Result<Response, Response> {
+    let auth_service =
+        AuthService::new(state.db.clone(), std::sync::Arc::new(state.config.clone()));
+    let validation = auth_service
+        .validate_api_token(&token)
+        .await
+        .map_err(|_| npm_login_error(StatusCode::UNAUTHORIZED, "invalid token"))?;
+    resolve_npm_repo(&state.db, &repo_key).await?;
+    let token_id: uuid::Uuid =
+        sqlx::query_scalar("SELECT id FROM api_tokens WHERE token_prefix = $1 AND user_id = $2")
+            .bind(token.get(..8).unwrap_or_default())
+            .bind(validation.user.id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(map_db_err)?
+            .ok_or_else(|| npm_login_error(StatusCode::UNAUTHORIZED, "invalid token"))?;
+    auth_service
+        .revoke_api_token(token_id, validation.user.id)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    audit_fire_and_forget(
+        state.db.clone(),
+        api_token_audit_entry(
+            AuditAction::ApiTokenRevoked,
+            validation.user.id,
+            token_id,
+            None,
+            "npm-logout",
+        ),
+    )
+    .await;
+
+    Ok((
+        StatusCode::OK,
+        axum::Json(serde_json::json!({ "ok": true })),
+    )
+        .into_response())
+}
+
 /// Handler for `POST /npm/{repo_key}/-/npm/v1/security/advisories/bulk`.
 ///
 /// This endpoint is used by `npm audit` (npm >= 7) to look up known security
@@ -1483,6 +1656,9 @@ struct NpmMetadataArtifact {
     version: Option<String>,
     checksum_sha256: String,
     metadata: Option<serde_json::Value>,
+    /// `deprecated` message for a version marked deprecated through the
+    /// deprecation API. Overrides any message in the published metadata.
+    deprecated: Option<String>,
 }
 
 /// Build an npm package metadata JSON response from a set of artifacts.
@@ -1549,6 +1725,13 @@ fn build_npm_metadata_response(
             }),
         );
 
+        if let Some(message) = &artifact.deprecated {
+            obj.insert(
+                "deprecated".to_string(),
+                serde_json::Value::String(message.clone()),
+            );
+        }
+
         versions.insert(version.clone(), version_obj);
         version_list.push(version);
     }
@@ -1677,9 +1860,19 @@ async fn fetch_npm_artifacts(
     .await
     .map_err(map_db_err)?;
 
+    let deprecated = VersionDeprecationService::new(db.clone())
+        .deprecated_versions(repository_id, package_name)
+        .await
+        .map_err(|e| e.into_response())?;
+
     Ok(rows
         .into_iter()
         .map(|a| NpmMetadataArtifact {
+            deprecated: a
+                .version
+                .as_ref()
+                .and_then(|v| deprecated.get(v))
+                .map(|m| npm_deprecation_message(m.as_deref())),
             path: a.path,
             version: a.version,
             checksum_sha256: a.checksum_sha256,
@@ -2009,7 +2202,10 @@ async fn get_package_metadata(
     // Local/Staging members are checked first (query DB for artifacts),
     // then Remote members are proxied from upstream. First match wins.
     if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        // Route by package name: `@mycorp/*` then covers both the packument
+        // and the `@mycorp/pkg/-/pkg-1.0.0.tgz` tarball paths.
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, package_name).await?;
 
         if members.is_empty() {
             return Err(
@@ -2244,7 +2440,8 @@ async fn fetch_virtual_packument(
     package_name: &str,
     base_url: &str,
 ) -> Result<serde_json::Value, Response> {
-    let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+    let members =
+        proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, package_name).await?;
     if members.is_empty() {
         return Err(
             AppError::NotFound("Virtual repository has no members".to_string()).into_response(),
@@ -2819,7 +3016,13 @@ async fn serve_tarball(
         // always return false; skipping it spares the DB an existence
         // check on every malformed request.
         let local_owns = if crate::formats::npm::is_valid_npm_name(package_name) {
-            proxy_helpers::virtual_non_remote_owns_name(&state.db, repo.id, package_name).await?
+            proxy_helpers::virtual_non_remote_owns_name(
+                &state.db,
+                repo.id,
+                package_name,
+                &upstream_path,
+            )
+            .await?
         } else {
             false
         };
@@ -2841,7 +3044,9 @@ async fn serve_tarball(
         // no other format (maven/hex/...) is affected. Non-Remote members are
         // always eligible, preserving the `virtual_non_remote_owns_name`
         // shadowing guard and the local-only primitive above.
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &upstream_path)
+                .await?;
         let member_ids: Vec<uuid::Uuid> = members.iter().map(|m| m.id).collect();
         let scope_policies = fetch_npm_scope_policies(&state.db, &member_ids)
             .await
@@ -3069,6 +3274,51 @@ struct NpmVersionToPublish {
     sha256: String,
 }
 
+/// Recognise the packument `npm deprecate` PUTs back: no tarballs in
+/// `_attachments`, and a `deprecated` string on each version it changed (an
+/// empty string un-deprecates). Returns `(version, message)` pairs, with
+/// `None` meaning un-deprecate, or `None` for a regular publish.
+#[allow(clippy::result_large_err)]
+fn parse_npm_deprecate_payload(
+    body: &Bytes,
+    package_name: &str,
+) -> Result<Option<Vec<(String, Option<String>)>>, Response> {
+    let payload: serde_json::Value = serde_json::from_slice(body).map_err(|e| {
+        AppError::Validation(format!("Invalid JSON payload: {}", e)).into_response()
+    })?;
+
+    let has_attachments = payload
+        .get("_attachments")
+        .and_then(|v| v.as_object())
+        .is_some_and(|a| !a.is_empty());
+    if has_attachments {
+        return Ok(None);
+    }
+    if payload
+        .get("name")
+        .and_then(|v| v.as_str())
+        .is_some_and(|name| name != package_name)
+    {
+        return Ok(None);
+    }
+    let Some(versions) = payload.get("versions").and_then(|v| v.as_object()) else {
+        return Ok(None);
+    };
+
+    let changes: Vec<(String, Option<String>)> = versions
+        .iter()
+        .filter_map(|(version, data)| {
+            let message = data.get("deprecated")?.as_str()?;
+            let message = (!message.trim().is_empty()).then(|| message.to_string());
+            Some((version.clone(), message))
+        })
+        .collect();
+    if changes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(changes))
+}
+
 /// Parse and validate the raw npm publish JSON body into structured data.
 /// Returns an error response if the payload is malformed.
 #[allow(clippy::result_large_err)]
@@ -3258,6 +3508,14 @@ async fn store_npm_version(
     crate::services::quarantine_service::apply_upload_hold_hosted(&state.db, repo_id, artifact_id)
         .await;
 
+    // README / changelog for search (best-effort; a saturated extraction
+    // budget skips them rather than failing the publish).
+    let docs = crate::util::bounded_archive::with_ingest_extraction(|| {
+        package_docs_service::extract_from_tar_gz(&ver.tarball_bytes)
+    })
+    .unwrap_or_default();
+    package_docs_service::record_package_docs(&state.db, artifact_id, &docs).await;
+
     // Store metadata
     let npm_metadata = serde_json::json!({
         "name": package_name,
@@ -3321,6 +3579,30 @@ async fn publish_package(
     proxy_helpers::reject_write_if_not_hosted(&repo.repo_type)?;
     repo.reject_if_promotion_only(false)?;
 
+    if let Some(changes) = parse_npm_deprecate_payload(&body, package_name)? {
+        let service = VersionDeprecationService::new(state.db.clone());
+        for (version, message) in &changes {
+            match message {
+                Some(message) => {
+                    service
+                        .deprecate(repo.id, package_name, version, Some(message), Some(user_id))
+                        .await
+                        .map_err(|e| e.into_response())?;
+                }
+                None => {
+                    service
+                        .undeprecate(repo.id, package_name, version)
+                        .await
+                        .map_err(|e| e.into_response())?;
+                }
+            }
+        }
+        invalidate_packument_caches(state, repo.id, repo_key, package_name).await;
+        return Ok(build_json_metadata_response(
+            serde_json::to_string(&serde_json::json!({"ok": true})).unwrap(),
+        ));
+    }
+
     let parsed = parse_npm_publish_payload(&body, package_name)?;
 
     for ver in &parsed.versions {
@@ -4772,6 +5054,137 @@ mod tests {
         fx.teardown().await;
     }
 
+    /// DB-backed: a virtual repo whose first Remote member excludes the
+    /// requested name through its routing patterns resolves both the
+    /// metadata and the packument through the second member, and the
+    /// excluded member's upstream is NEVER contacted (wiremock `expect(0)`).
+    /// Skips when no `DATABASE_URL` is configured.
+    #[tokio::test]
+    async fn test_virtual_member_routing_patterns_filter_packument_db() {
+        use crate::api::handlers::test_db_helpers as tdh;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let Some(fx) = tdh::Fixture::setup("virtual", "npm").await else {
+            return;
+        };
+        let package = "mycorp-routed-pkg";
+
+        // Member A upstream: would serve the package, but its exclude pattern
+        // pins `mycorp-*` away from it, so it must never be asked.
+        let excluded_upstream = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/{package}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": package, "versions": {}
+            })))
+            .expect(0)
+            .mount(&excluded_upstream)
+            .await;
+
+        // Member B upstream: includes `mycorp-*`, serves the packument.
+        let included_upstream = MockServer::start().await;
+        let packument = serde_json::json!({
+            "name": package,
+            "dist-tags": {"latest": "1.0.0"},
+            "versions": {
+                "1.0.0": {"name": package, "version": "1.0.0",
+                          "dist": {"tarball": format!(
+                              "{}/{}/-/{}-1.0.0.tgz", included_upstream.uri(), package, package)}},
+            }
+        });
+        Mock::given(method("GET"))
+            .and(path(format!("/{package}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&packument))
+            .mount(&included_upstream)
+            .await;
+
+        let mut member_ids = Vec::new();
+        for (upstream, priority, include, exclude) in [
+            (
+                &excluded_upstream,
+                1,
+                Vec::<String>::new(),
+                vec!["mycorp-*".to_string()],
+            ),
+            (
+                &included_upstream,
+                2,
+                vec!["mycorp-*".to_string()],
+                Vec::new(),
+            ),
+        ] {
+            let (member_id, _mkey, _mdir) = tdh::create_repo(&fx.pool, "remote", "npm").await;
+            sqlx::query(
+                "UPDATE repositories SET upstream_url = $1, is_public = true WHERE id = $2",
+            )
+            .bind(upstream.uri())
+            .bind(member_id)
+            .execute(&fx.pool)
+            .await
+            .expect("configure member");
+            sqlx::query(
+                "INSERT INTO virtual_repo_members \
+                 (virtual_repo_id, member_repo_id, priority, include_patterns, exclude_patterns) \
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(fx.repo_id)
+            .bind(member_id)
+            .bind(priority)
+            .bind(&include)
+            .bind(&exclude)
+            .execute(&fx.pool)
+            .await
+            .expect("attach member");
+            member_ids.push(member_id);
+        }
+
+        let storage_path = fx.storage_dir.to_str().unwrap().to_string();
+        let proxy = tdh::build_proxy_service_with_fs(fx.pool.clone(), storage_path.as_str());
+        let state = tdh::build_state_with_proxy(fx.pool.clone(), storage_path.as_str(), proxy);
+
+        let meta =
+            super::get_package_metadata(&state, &fx.repo_key, package, "http://localhost", false)
+                .await;
+        match meta {
+            Ok(resp) => assert_eq!(resp.status(), StatusCode::OK),
+            Err(r) => panic!(
+                "virtual metadata must resolve via member B: HTTP {}",
+                r.status()
+            ),
+        }
+
+        let repo = fx.repo_info("virtual", None);
+        let packument_json = super::fetch_virtual_packument(
+            &state,
+            &repo,
+            &fx.repo_key,
+            package,
+            "http://localhost",
+        )
+        .await;
+        match packument_json {
+            Ok(json) => assert_eq!(json["name"], package),
+            Err(r) => panic!(
+                "virtual packument must resolve via member B: HTTP {}",
+                r.status()
+            ),
+        }
+
+        // Cleanup (wiremock verifies `expect(0)` for member A on drop).
+        for member_id in member_ids {
+            let _ = sqlx::query("DELETE FROM virtual_repo_members WHERE member_repo_id = $1")
+                .bind(member_id)
+                .execute(&fx.pool)
+                .await;
+            let _ = sqlx::query("DELETE FROM repositories WHERE id = $1")
+                .bind(member_id)
+                .execute(&fx.pool)
+                .await;
+        }
+        fx.teardown().await;
+    }
+
     // -----------------------------------------------------------------------
     // Extracted pure functions (test-only)
     // -----------------------------------------------------------------------
@@ -4885,6 +5298,21 @@ mod tests {
         version_obj
     }
 
+    // -----------------------------------------------------------------------
+    // npm login
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_npm_login_username() {
+        assert_eq!(npm_login_username("org.couchdb.user:alice"), Some("alice"));
+        assert_eq!(
+            npm_login_username("org.couchdb.user:ci-bot@example.com"),
+            Some("ci-bot@example.com")
+        );
+        assert_eq!(npm_login_username("org.couchdb.user:"), None);
+        assert_eq!(npm_login_username("alice"), None);
+    }
+
     // -----------------------------------------------------------------------
     // rewrite_npm_tarball_urls
     // -----------------------------------------------------------------------
@@ -5730,6 +6158,55 @@ mod tests {
     // -----------------------------------------------------------------------
 
     /// Build an attachments map with a single entry containing base64-encoded data.
+    #[test]
+    fn test_parse_npm_deprecate_payload_collects_messages() {
+        let body = Bytes::from(
+            serde_json::to_vec(&serde_json::json!({
+                "name": "pkg",
+                "versions": {
+                    "1.0.0": { "version": "1.0.0", "deprecated": "use 2.x" },
+                    "1.1.0": { "version": "1.1.0", "deprecated": "" },
+                    "2.0.0": { "version": "2.0.0" }
+                }
+            }))
+            .unwrap(),
+        );
+        let mut changes = parse_npm_deprecate_payload(&body, "pkg").unwrap().unwrap();
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![
+                ("1.0.0".to_string(), Some("use 2.x".to_string())),
+                ("1.1.0".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_npm_deprecate_payload_ignores_publish() {
+        let body = Bytes::from(
+            serde_json::to_vec(&serde_json::json!({
+                "name": "pkg",
+                "versions": { "1.0.0": { "deprecated": "x" } },
+                "_attachments": { "pkg-1.0.0.tgz": { "data": "dGVzdA==" } }
+            }))
+            .unwrap(),
+        );
+        assert!(parse_npm_deprecate_payload(&body, "pkg").unwrap().is_none());
+
+        let no_markers = Bytes::from(
+            serde_json::to_vec(&serde_json::json!({
+                "name": "pkg",
+                "versions": { "1.0.0": {} },
+                "_attachments": {}
+            }))
+            .unwrap(),
+        );
+        assert!(parse_npm_deprecate_payload(&no_markers, "pkg")
+            .unwrap()
+            .is_none());
+    }
+
     fn make_attachments(filename: &str, data: &[u8]) -> serde_json::Map<String, serde_json::Value> {
         let b64 = base64::engine::general_purpose::STANDARD.encode(data);
         let mut m = serde_json::Map::new();
@@ -6017,6 +6494,7 @@ mod tests {
             version: Some(version.to_string()),
             checksum_sha256: sha256.to_string(),
             metadata: None,
+            deprecated: None,
         }
     }
 
@@ -6043,6 +6521,27 @@ mod tests {
         serde_json::from_slice(&body_bytes).unwrap()
     }
 
+    #[tokio::test]
+    async fn test_build_npm_metadata_response_marks_deprecated_version() {
+        let mut deprecated = make_artifact("pkg/1.0.0/pkg-1.0.0.tgz", "1.0.0", SHA256_ZEROS);
+        deprecated.deprecated = Some("use 2.0.0".to_string());
+        deprecated.metadata = Some(serde_json::json!({
+            "version_data": { "deprecated": "stale publish-time message" }
+        }));
+        let artifacts = vec![
+            deprecated,
+            make_artifact("pkg/2.0.0/pkg-2.0.0.tgz", "2.0.0", SHA256_ABCD),
+        ];
+
+        let body =
+            metadata_response_json(&artifacts, "pkg", "http://localhost:8080", "npm-hosted").await;
+
+        assert_eq!(body["versions"]["1.0.0"]["deprecated"], "use 2.0.0");
+        assert!(body["versions"]["2.0.0"].get("deprecated").is_none());
+        // Deprecation never hides the tarball.
+        assert!(body["versions"]["1.0.0"]["dist"]["tarball"].is_string());
+    }
+
     const SHA256_ZEROS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
     const SHA256_EMPTY: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
     const SHA256_ABCD: &str = "abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789";
@@ -6146,6 +6645,7 @@ mod tests {
                     "main": "index.js"
                 }
             })),
+            deprecated: None,
         }];
 
         let body =
@@ -6169,6 +6669,7 @@ mod tests {
                 version: None,
                 checksum_sha256: SHA256_ABCD.to_string(),
                 metadata: None,
+                deprecated: None,
             },
         ];
 
@@ -6392,6 +6893,7 @@ mod tests {
             checksum_sha256: "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
                 .to_string(),
             metadata: None,
+            deprecated: None,
         }];
         let scoped = vec![NpmMetadataArtifact {
             path: "@types/mdurl/2.0.0/mdurl-2.0.0.tgz".to_string(),
@@ -6399,6 +6901,7 @@ mod tests {
             checksum_sha256: "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"
                 .to_string(),
             metadata: None,
+            deprecated: None,
         }];
 
         let resp_unscoped = build_npm_metadata_response(
@@ -8477,6 +8980,9 @@ mod db_cov_tests {
                 member_key.clone(),
                 "delpkg/1.0.0/delpkg-1.0.0.tgz".to_string(),
             )),
+            axum::extract::Query(crate::api::handlers::repositories::DeleteArtifactQuery {
+                confirm: None,
+            }),
             HeaderMap::new(),
         )
         .await;
@@ -28,6 +28,7 @@ use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::models::repository::{RepositoryFormat, RepositoryType};
 use crate::services::curation_service::version_compare;
+use crate::services::virtual_member_routing::MemberRoutes;
 
 // ---------------------------------------------------------------------------
 // Router
@@ -96,15 +97,26 @@ async fn resolve_nuget_repo(db: &PgPool, repo_key: &str) -> Result<RepoInfo, Res
 ///
 /// Returns the resolved IDs alongside the list of virtual members (empty for
 /// non-virtual repos) so callers can additionally proxy remote members.
+///
+/// `package_id_lower` narrows the members to those whose routing patterns
+/// allow that package; queries spanning many packages pass `None` and drop
+/// excluded rows themselves (see [`package_route_path`]).
 async fn effective_local_repo_ids(
     db: &PgPool,
     repo: &RepoInfo,
+    package_id_lower: Option<&str>,
 ) -> Result<(Vec<uuid::Uuid>, Vec<crate::models::repository::Repository>), Response> {
     if repo.repo_type != RepositoryType::Virtual {
         return Ok((vec![repo.id], Vec::new()));
     }
 
-    let members = proxy_helpers::fetch_virtual_members(db, repo.id).await?;
+    let members = match package_id_lower {
+        Some(id) => {
+            proxy_helpers::fetch_virtual_members_for_path(db, repo.id, &package_route_path(id))
+                .await?
+        }
+        None => proxy_helpers::fetch_virtual_members(db, repo.id).await?,
+    };
     let local_ids: Vec<uuid::Uuid> = members
         .iter()
         .filter(|m| m.repo_type != RepositoryType::Remote)
@@ -113,6 +125,12 @@ async fn effective_local_repo_ids(
     Ok((local_ids, members))
 }
 
+/// The `v3/flatcontainer/{id}/` directory every `.nupkg` of a package is
+/// downloaded from, matched against virtual member routing patterns.
+fn package_route_path(package_id_lower: &str) -> String {
+    format!("v3/flatcontainer/{}/", package_id_lower)
+}
+
 /// Detect a NuGet pre-release version. Per the SemVer rules NuGet follows, a
 /// pre-release version carries a `-` separated suffix after the version core
 /// (e.g. `2.0.0-beta.1`). Stable versions have no such suffix.
@@ -499,6 +517,7 @@ struct SearchPackageRow {
     name: String,
     versions: Vec<String>,
     description: Option<String>,
+    repository_ids: Vec<uuid::Uuid>,
 }
 
 async fn search_packages(
@@ -522,12 +541,12 @@ async fn search_packages(
 
     // Federate over virtual members (local/staging) when the repo is virtual;
     // otherwise query the repo itself.
-    let (repo_ids, _members) = effective_local_repo_ids(&state.db, &repo).await?;
+    let (repo_ids, _members) = effective_local_repo_ids(&state.db, &repo, None).await?;
 
     // Pull the latest-by-created_at description per package via a LATERAL
     // join so the search payload carries the package summary instead of a
     // hardcoded empty string.
-    let packages: Vec<SearchPackageRow> = sqlx::query_as(
+    let mut packages: Vec<SearchPackageRow> = sqlx::query_as(
         r#"
         SELECT a.name AS name,
                ARRAY_AGG(DISTINCT a.version) FILTER (WHERE a.version IS NOT NULL) AS versions,
@@ -540,7 +559,8 @@ async fn search_packages(
                      AND LOWER(a2.name) = LOWER(a.name)
                    ORDER BY a2.created_at DESC
                    LIMIT 1
-               ) AS description
+               ) AS description,
+               ARRAY_AGG(DISTINCT a.repository_id) AS repository_ids
         FROM artifacts a
         WHERE a.repository_id = ANY($1::uuid[])
           AND a.is_deleted = false
@@ -558,6 +578,16 @@ async fn search_packages(
     .await
     .map_err(crate::api::handlers::db_err)?;
 
+    // Drop packages no holding member's routing patterns allow. Filtering the
+    // fetched page keeps the query paged, so a page may come back short.
+    if repo.repo_type == RepositoryType::Virtual {
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
+        packages.retain(|p| {
+            let path = package_route_path(&p.name.to_lowercase());
+            p.repository_ids.iter().any(|id| routes.allows(*id, &path))
+        });
+    }
+
     // Get total count for pagination.
     let total_count: i64 = sqlx::query_scalar(
         r#"
@@ -629,7 +659,8 @@ async fn registration_index(
 
     // Resolve the set of local repo IDs to query: the repo itself, or all
     // local/staging members for a virtual repo.
-    let (repo_ids, members) = effective_local_repo_ids(&state.db, &repo).await?;
+    let (repo_ids, members) =
+        effective_local_repo_ids(&state.db, &repo, Some(&package_id_lower)).await?;
 
     // Fetch all versions of this package across the effective repo IDs.
     let artifacts = sqlx::query!(
@@ -794,7 +825,8 @@ async fn flatcontainer_versions(
 
     // Resolve the set of local repo IDs to query: the repo itself, or all
     // local/staging members for a virtual repo.
-    let (repo_ids, members) = effective_local_repo_ids(&state.db, &repo).await?;
+    let (repo_ids, members) =
+        effective_local_repo_ids(&state.db, &repo, Some(&package_id_lower)).await?;
 
     let mut versions: Vec<String> = sqlx::query_scalar(
         r#"
@@ -939,8 +971,13 @@ async fn flatcontainer_download(
                 // Remote members need V3 service-index discovery to resolve the
                 // real `PackageBaseAddress`, so try them explicitly first (#2775).
                 if let Some(proxy) = state.proxy_service.as_deref() {
-                    let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
                     let sub_path = format!("{}/{}/{}", package_id_lower, version, filename);
+                    let members = proxy_helpers::fetch_virtual_members_for_path(
+                        &state.db,
+                        repo.id,
+                        &format!("v3/flatcontainer/{}", sub_path),
+                    )
+                    .await?;
                     for member in &members {
                         if member.repo_type != RepositoryType::Remote {
                             continue;
@@ -1377,6 +1414,17 @@ fn sanitize_cache_segment(s: &str) -> String {
         .collect()
 }
 
+/// One artifact row of a hosted V2 feed.
+#[derive(sqlx::FromRow)]
+struct V2EntryRow {
+    repository_id: uuid::Uuid,
+    name: String,
+    version: Option<String>,
+    size_bytes: i64,
+    checksum_sha256: Option<String>,
+    metadata: Option<serde_json::Value>,
+}
+
 /// Load hosted V2 feed entries for a repo, optionally filtered by package
 /// id/version. Federates over virtual local members like the V3 handlers.
 async fn load_hosted_v2_entries(
@@ -1385,13 +1433,20 @@ async fn load_hosted_v2_entries(
     id_filter: Option<&str>,
     version_filter: Option<&str>,
 ) -> Result<Vec<V2Entry>, Response> {
-    let (repo_ids, _members) = effective_local_repo_ids(&state.db, repo).await?;
     let id_lower = id_filter.map(|s| s.to_lowercase());
-    let rows = sqlx::query!(
+    let (repo_ids, _members) = effective_local_repo_ids(&state.db, repo, None).await?;
+    let routes = if repo.repo_type == RepositoryType::Virtual {
+        proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?
+    } else {
+        MemberRoutes::default()
+    };
+    // Each row carries its member so it can be checked against that member's
+    // routing patterns. Filtering the fetched rows keeps the single bounded
+    // query, so a feed may come back short of the limit.
+    let rows: Vec<V2EntryRow> = sqlx::query_as(
         r#"
-        SELECT a.name AS name, a.version AS "version?", a.size_bytes AS size_bytes,
-               a.checksum_sha256 AS "checksum_sha256?",
-               am.metadata AS "metadata?"
+        SELECT a.repository_id, a.name, a.version, a.size_bytes, a.checksum_sha256,
+               am.metadata
         FROM artifacts a
         LEFT JOIN artifact_metadata am ON am.artifact_id = a.id
         WHERE a.repository_id = ANY($1::uuid[])
@@ -1402,16 +1457,17 @@ async fn load_hosted_v2_entries(
         ORDER BY a.name ASC, a.created_at ASC
         LIMIT 500
         "#,
-        &repo_ids,
-        id_lower.as_deref(),
-        version_filter,
     )
+    .bind(&repo_ids)
+    .bind(id_lower.as_deref())
+    .bind(version_filter)
     .fetch_all(&state.db)
     .await
     .map_err(crate::api::handlers::db_err)?;
 
     Ok(rows
         .into_iter()
+        .filter(|r| routes.allows(r.repository_id, &package_route_path(&r.name.to_lowercase())))
         .map(|r| {
             let meta = r.metadata;
             let authors = meta
@@ -1477,7 +1533,7 @@ async fn v2_download(
 
     // Hosted / local: look the artifact up and stream from storage.
     let id_lower = id.to_lowercase();
-    let (repo_ids, _members) = effective_local_repo_ids(&state.db, repo).await?;
+    let (repo_ids, _members) = effective_local_repo_ids(&state.db, repo, Some(&id_lower)).await?;
     let artifact = sqlx::query!(
         r#"
         SELECT id, storage_key, size_bytes
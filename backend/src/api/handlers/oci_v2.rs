@@ -14,7 +14,7 @@ use std::sync::{
 use std::time::Duration;
 
 use axum::body::{to_bytes, Body, HttpBody};
-use axum::extract::{DefaultBodyLimit, Query, State};
+use axum::extract::{DefaultBodyLimit, Query, RawQuery, State};
 use axum::http::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, LOCATION};
 use axum::http::{HeaderMap, Method, StatusCode};
 use axum::response::Response;
@@ -36,7 +36,8 @@ use crate::api::SharedState;
 use crate::error::AppError;
 use crate::models::repository::{RepositoryFormat, RepositoryType};
 use crate::models::user::User;
-use crate::services::auth_service::AuthService;
+use crate::services::auth_service::{AuthService, TokenPair};
+use crate::services::registry_scope::{requested_scopes, RegistryScope};
 use crate::storage::keys::OCI_MANIFEST_STORAGE_PREFIX;
 
 // ---------------------------------------------------------------------------
@@ -191,9 +192,9 @@ fn extract_basic_credentials(headers: &HeaderMap) -> Option<(String, String)> {
 /// Form body sent by Docker for the OAuth2 password-grant flow against the
 /// distribution token endpoint (`POST /v2/token`). Only `grant_type`,
 /// `username`, and `password` are used here. The `service` field is read from
-/// the URL query string (`Query<TokenQuery>`) for validation; `scope` and
-/// `client_id` are not used for authentication (the issued token is derived
-/// from the identity's permissions, not the requested scope — see `TokenQuery`).
+/// the URL query string (`Query<TokenQuery>`) for validation. `scope` is
+/// read from the raw body by `requested_scopes` (it may repeat, which
+/// serde_urlencoded rejects); `client_id` is unused.
 #[derive(Deserialize, Default)]
 struct TokenForm {
     grant_type: Option<String>,
@@ -545,11 +546,31 @@ fn enforce_token_repo_scope(
 /// are unaffected: remote/virtual repos reject pushes earlier, and replication
 /// identities hold a repo-scoped or global grant. Fails closed (503) if the
 /// membership lookup errors, mirroring the REST middleware.
+///
+/// The external authorization hook then gets the last word on the `write`,
+/// as it does for native format uploads.
 async fn require_oci_repo_write_access(
     state: &SharedState,
     claims: &crate::services::auth_service::Claims,
     repo_id: Uuid,
     repo_is_public: bool,
+) -> Result<(), Response> {
+    require_oci_repo_membership(state, claims, repo_id, repo_is_public).await?;
+    if oci_external_authz(state, Some(claims), repo_id, "write", None).await? {
+        Ok(())
+    } else {
+        Err(oci_denied_repo_access())
+    }
+}
+
+/// The membership half of [`require_oci_repo_write_access`]: token allow-list,
+/// admin and public bypass, then a role assignment on the repository. Token
+/// issuance uses it on its own to decide which repositories a token opens.
+async fn require_oci_repo_membership(
+    state: &SharedState,
+    claims: &crate::services::auth_service::Claims,
+    repo_id: Uuid,
+    repo_is_public: bool,
 ) -> Result<(), Response> {
     // An API-token-scoped bearer may only touch repositories in its declared
     // allow-list — a ceiling that applies even to admins (#2290). This mirrors
@@ -577,6 +598,59 @@ async fn require_oci_repo_write_access(
     }
 }
 
+/// Ask the external authorization hook (`AUTHZ_HOOK_URL`) about an OCI
+/// request the built-in gates already allowed, with the same
+/// restrictive-only semantics as the native format middleware. `Ok(true)`
+/// when no hook is configured; a hook outage fails closed (503) unless the
+/// hook is set to fail open.
+#[allow(clippy::result_large_err)] // Response-as-error is used throughout this module
+async fn oci_external_authz(
+    state: &SharedState,
+    claims: Option<&crate::services::auth_service::Claims>,
+    repo_id: Uuid,
+    action: &str,
+    path: Option<&str>,
+) -> Result<bool, Response> {
+    let user =
+        claims.map(|c| crate::api::middleware::auth::AuthExtension::from(c.clone()).authz_user());
+    match state
+        .permission_service
+        .authorize_external(user, action, repo_id, path)
+        .await
+    {
+        Ok(allow) => Ok(allow),
+        Err(e) => {
+            tracing::error!("OCI authorization hook failed: {}", e);
+            Err(oci_error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "DENIED",
+                "authorization hook temporarily unavailable",
+            ))
+        }
+    }
+}
+
+/// The external authorization hook on an OCI pull (blob, manifest, tag list
+/// or referrers read). A denial challenges anonymous callers so clients retry
+/// with credentials, and is `DENIED` for authenticated ones.
+#[allow(clippy::result_large_err)] // Response-as-error is used throughout this module
+async fn require_oci_pull_authz(
+    state: &SharedState,
+    claims: Option<&crate::services::auth_service::Claims>,
+    repo_id: Uuid,
+    path: &str,
+    base_url: &str,
+    scope: &str,
+) -> Result<(), Response> {
+    if oci_external_authz(state, claims, repo_id, "read", Some(path)).await? {
+        Ok(())
+    } else if claims.is_none() {
+        Err(unauthorized_challenge_with_scope(base_url, Some(scope)))
+    } else {
+        Err(oci_denied_repo_access())
+    }
+}
+
 /// Fine-grained per-action OCI gate, applied AFTER `require_oci_repo_write_access`
 /// (the tenant-membership gate) on the destructive manifest-delete path.
 ///
@@ -1864,15 +1938,145 @@ pub(crate) fn stored_media_type_for(class: &ManifestClass, header: &str) -> Stri
 }
 
 /// Parse an OCI image index manifest body and return the list of child
-/// manifest digests. Used by both the push handler (to populate
-/// `oci_manifest_refs` synchronously) and the startup backfill (to fill
-/// in any rows that pre-date this code).
+/// manifest digests. Thin wrapper over [`extract_child_manifests`] for
+/// callers that only need the edges.
 ///
 /// Returns an empty vec when the body is not parseable as JSON or has no
 /// `manifests` array. Callers should treat that as a no-op rather than
 /// an error, since a stray non-conformant manifest should not block the
 /// rest of GC protection.
 pub(crate) fn extract_child_digests(body: &[u8]) -> Vec<String> {
+    extract_child_manifests(body)
+        .into_iter()
+        .map(|c| c.digest)
+        .collect()
+}
+
+/// Target platform of an image, as carried by an index descriptor's
+/// `platform` object or requested as `os/arch[/variant]`.
+///
+/// Values are normalized the way container runtimes compare them
+/// (`x86_64` → `amd64`, `aarch64` → `arm64`, `arm64` defaults to variant
+/// `v8`, `arm` to `v7`), so `linux/aarch64` selects a `linux/arm64/v8`
+/// entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OciPlatform {
+    pub os: String,
+    pub architecture: String,
+    pub variant: Option<String>,
+    pub os_version: Option<String>,
+}
+
+impl OciPlatform {
+    fn normalized(
+        os: &str,
+        architecture: &str,
+        variant: Option<&str>,
+        os_version: Option<&str>,
+    ) -> Self {
+        let os = match os.trim().to_ascii_lowercase().as_str() {
+            "macos" => "darwin".to_string(),
+            other => other.to_string(),
+        };
+        let variant = variant
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty());
+        let (architecture, variant) = match architecture.trim().to_ascii_lowercase().as_str() {
+            "x86_64" | "x86-64" | "amd64" => ("amd64".to_string(), variant),
+            "i386" | "i686" | "386" => ("386".to_string(), variant),
+            "aarch64" | "arm64" => (
+                "arm64".to_string(),
+                match variant.as_deref() {
+                    None | Some("8") | Some("v8") => Some("v8".to_string()),
+                    _ => variant,
+                },
+            ),
+            "armhf" => ("arm".to_string(), Some("v7".to_string())),
+            "armel" => ("arm".to_string(), Some("v6".to_string())),
+            "arm" => (
+                "arm".to_string(),
+                match variant.as_deref() {
+                    None | Some("7") | Some("v7") => Some("v7".to_string()),
+                    Some(v @ ("5" | "6" | "8")) => Some(format!("v{}", v)),
+                    _ => variant,
+                },
+            ),
+            other => (other.to_string(), variant),
+        };
+        Self {
+            os,
+            architecture,
+            variant,
+            os_version: os_version
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string),
+        }
+    }
+
+    /// Parse a `platform` query value: `os/arch`, `os/arch/variant`, or
+    /// either with a `:os.version` suffix on the OS (`windows:10.0.17763/amd64`).
+    pub(crate) fn parse(spec: &str) -> Option<Self> {
+        let mut parts = spec.trim().split('/');
+        let os_part = parts.next().filter(|p| !p.is_empty())?;
+        let architecture = parts.next().filter(|p| !p.is_empty())?;
+        let variant = parts.next();
+        if parts.next().is_some() {
+            return None;
+        }
+        let (os, os_version) = match os_part.split_once(':') {
+            Some((os, version)) => (os, Some(version)),
+            None => (os_part, None),
+        };
+        Some(Self::normalized(os, architecture, variant, os_version))
+    }
+
+    /// Platform of an index descriptor, when it declares one.
+    fn from_descriptor(descriptor: &serde_json::Value) -> Option<Self> {
+        let platform = descriptor.get("platform")?;
+        let field = |name: &str| platform.get(name).and_then(|v| v.as_str());
+        Some(Self::normalized(
+            field("os")?,
+            field("architecture")?,
+            field("variant"),
+            field("os.version"),
+        ))
+    }
+
+    /// Whether a descriptor built for `self` satisfies a request for
+    /// `wanted`. A request without an OS version accepts any.
+    pub(crate) fn satisfies(&self, wanted: &OciPlatform) -> bool {
+        self.os == wanted.os
+            && self.architecture == wanted.architecture
+            && (wanted.variant.is_none() || self.variant == wanted.variant)
+            && (wanted.os_version.is_none() || self.os_version == wanted.os_version)
+    }
+}
+
+impl std::fmt::Display for OciPlatform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.os, self.architecture)?;
+        if let Some(variant) = &self.variant {
+            write!(f, "/{}", variant)?;
+        }
+        Ok(())
+    }
+}
+
+/// One child descriptor of an image index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ChildManifest {
+    pub digest: String,
+    pub media_type: Option<String>,
+    pub size: Option<i64>,
+    pub platform: Option<OciPlatform>,
+}
+
+/// Parse an OCI image index manifest body and return its child
+/// descriptors. Used by both the push handler (to populate
+/// `oci_manifest_refs` synchronously) and the startup backfill (to fill
+/// in any rows that pre-date this code).
+pub(crate) fn extract_child_manifests(body: &[u8]) -> Vec<ChildManifest> {
     let json: serde_json::Value = match serde_json::from_slice(body) {
         Ok(v) => v,
         Err(_) => return Vec::new(),
@@ -1881,16 +2085,91 @@ pub(crate) fn extract_child_digests(body: &[u8]) -> Vec<String> {
         .and_then(|m| m.as_array())
         .map(|arr| {
             arr.iter()
-                .filter_map(|m| m.get("digest").and_then(|d| d.as_str()))
-                .map(|s| s.to_string())
+                .filter_map(|m| {
+                    Some(ChildManifest {
+                        digest: m.get("digest")?.as_str()?.to_string(),
+                        media_type: m
+                            .get("mediaType")
+                            .and_then(|t| t.as_str())
+                            .map(str::to_string),
+                        size: m.get("size").and_then(|s| s.as_i64()),
+                        platform: OciPlatform::from_descriptor(m),
+                    })
+                })
                 .collect()
         })
         .unwrap_or_default()
 }
 
+/// Pick the child of an image index built for `wanted`. When several
+/// entries match (a request without a variant), the first one listed wins,
+/// which is the order builders write the preferred variant in.
+pub(crate) fn select_platform_manifest(
+    index_body: &[u8],
+    wanted: &OciPlatform,
+) -> Option<ChildManifest> {
+    extract_child_manifests(index_body).into_iter().find(|c| {
+        c.platform
+            .as_ref()
+            .is_some_and(|platform| platform.satisfies(wanted))
+    })
+}
+
+/// Batched `oci_manifest_refs` insert shared by [`record_oci_manifest_refs`]
+/// and [`persist_tag_and_refs_in_tx`]. An existing edge keeps its row but
+/// picks up descriptor metadata if it was recorded before migration 194.
+const INSERT_OCI_MANIFEST_REFS_SQL: &str = r#"
+    INSERT INTO oci_manifest_refs (
+        parent_digest, child_digest, repository_id, media_type, size_bytes,
+        platform_os, platform_architecture, platform_variant, platform_os_version,
+        described_at
+    )
+    SELECT $1, child, $3, media, size, os, arch, variant, os_version, NOW()
+    FROM UNNEST(
+        $2::text[], $4::text[], $5::bigint[], $6::text[], $7::text[], $8::text[], $9::text[]
+    ) AS t(child, media, size, os, arch, variant, os_version)
+    ON CONFLICT (parent_digest, child_digest, repository_id) DO UPDATE SET
+        media_type = EXCLUDED.media_type,
+        size_bytes = EXCLUDED.size_bytes,
+        platform_os = EXCLUDED.platform_os,
+        platform_architecture = EXCLUDED.platform_architecture,
+        platform_variant = EXCLUDED.platform_variant,
+        platform_os_version = EXCLUDED.platform_os_version,
+        described_at = EXCLUDED.described_at
+    WHERE oci_manifest_refs.described_at IS NULL
+"#;
+
+/// Column arrays for [`INSERT_OCI_MANIFEST_REFS_SQL`], in bind order
+/// `$2` and `$4`..`$9`.
+type ChildRefColumns = (
+    Vec<String>,
+    Vec<Option<String>>,
+    Vec<Option<i64>>,
+    Vec<Option<String>>,
+    Vec<Option<String>>,
+    Vec<Option<String>>,
+    Vec<Option<String>>,
+);
+
+fn child_ref_columns(children: &[ChildManifest]) -> ChildRefColumns {
+    let mut cols: ChildRefColumns = Default::default();
+    for child in children {
+        let platform = child.platform.as_ref();
+        cols.0.push(child.digest.clone());
+        cols.1.push(child.media_type.clone());
+        cols.2.push(child.size);
+        cols.3.push(platform.map(|p| p.os.clone()));
+        cols.4.push(platform.map(|p| p.architecture.clone()));
+        cols.5.push(platform.and_then(|p| p.variant.clone()));
+        cols.6.push(platform.and_then(|p| p.os_version.clone()));
+    }
+    cols
+}
+
 /// Insert (parent_digest, child_digest, repository_id) rows into
-/// `oci_manifest_refs` for every child of an image index. Idempotent: on
-/// conflict the existing row is kept.
+/// `oci_manifest_refs` for every child of an image index, with each
+/// child's descriptor metadata. Idempotent: on conflict the existing row is
+/// kept, only filling in metadata it lacks.
 ///
 /// Called inline from `handle_put_manifest` and from the startup
 /// backfill. The caller is responsible for verifying that `parent_body`
@@ -1907,28 +2186,23 @@ pub(crate) async fn record_oci_manifest_refs(
     parent_digest: &str,
     parent_body: &[u8],
 ) -> Result<usize, sqlx::Error> {
-    let children = extract_child_digests(parent_body);
+    let children = extract_child_manifests(parent_body);
     if children.is_empty() {
         return Ok(0);
     }
-    // Batched insert: expand the child digest array via UNNEST and
-    // pair every row with the constant parent_digest / repo_id. One
-    // round-trip total. `query_as` over `query!` because sqlx's macro
-    // form does not currently type-check `UNNEST` array bindings
-    // against the offline metadata cache without explicit casts.
-    let res = sqlx::query(
-        r#"
-        INSERT INTO oci_manifest_refs (parent_digest, child_digest, repository_id)
-        SELECT $1, child, $3
-        FROM UNNEST($2::text[]) AS t(child)
-        ON CONFLICT (parent_digest, child_digest, repository_id) DO NOTHING
-        "#,
-    )
-    .bind(parent_digest)
-    .bind(&children)
-    .bind(repo_id)
-    .execute(db)
-    .await?;
+    let (digests, media, sizes, os, arch, variant, os_version) = child_ref_columns(&children);
+    let res = sqlx::query(INSERT_OCI_MANIFEST_REFS_SQL)
+        .bind(parent_digest)
+        .bind(&digests)
+        .bind(repo_id)
+        .bind(&media)
+        .bind(&sizes)
+        .bind(&os)
+        .bind(&arch)
+        .bind(&variant)
+        .bind(&os_version)
+        .execute(db)
+        .await?;
     Ok(res.rows_affected() as usize)
 }
 
@@ -2078,7 +2352,7 @@ pub async fn record_manifest_blob_refs(
 ///
 /// On the success path the resulting database state is byte-identical to
 /// the previous two-statement form (same upsert SQL, same `UNNEST` ref
-/// insert with the same `ON CONFLICT DO NOTHING`). The only behavioural
+/// insert as [`record_oci_manifest_refs`]). The only behavioural
 /// change is in the failure direction: a ref-write error now rolls the tag
 /// back and propagates instead of being swallowed by a `warn!`.
 ///
@@ -2166,21 +2440,22 @@ pub(crate) async fn persist_tag_and_refs_in_tx(
     //    the tag back when `tx` is dropped without a commit.
     match class {
         ManifestClass::Index => {
-            let children = extract_child_digests(manifest_body);
+            let children = extract_child_manifests(manifest_body);
             if !children.is_empty() {
-                sqlx::query(
-                    r#"
-                    INSERT INTO oci_manifest_refs (parent_digest, child_digest, repository_id)
-                    SELECT $1, child, $3
-                    FROM UNNEST($2::text[]) AS t(child)
-                    ON CONFLICT (parent_digest, child_digest, repository_id) DO NOTHING
-                    "#,
-                )
-                .bind(manifest_digest)
-                .bind(&children)
-                .bind(repo_id)
-                .execute(&mut **tx)
-                .await?;
+                let (digests, media, sizes, os, arch, variant, os_version) =
+                    child_ref_columns(&children);
+                sqlx::query(INSERT_OCI_MANIFEST_REFS_SQL)
+                    .bind(manifest_digest)
+                    .bind(&digests)
+                    .bind(repo_id)
+                    .bind(&media)
+                    .bind(&sizes)
+                    .bind(&os)
+                    .bind(&arch)
+                    .bind(&variant)
+                    .bind(&os_version)
+                    .execute(&mut **tx)
+                    .await?;
             }
         }
         ManifestClass::Image => {
@@ -2424,6 +2699,26 @@ async fn resolve_repo(db: &PgPool, image_name: &str) -> Result<OciRepoInfo, Resp
         .map_err(map_db_err)?;
     let mut effective_image = image.to_string();
 
+    // 1b. A renamed repository's old key resolves to the repository itself,
+    //     so image references under the old key keep pulling and pushing
+    //     until its alias expires.
+    if repo.is_none() {
+        let alias =
+            crate::services::repository_alias_service::RepositoryAliasService::new(db.clone())
+                .resolve(repo_key)
+                .await
+                .map_err(|e| {
+                    oci_error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "INTERNAL_ERROR",
+                        &e.to_string(),
+                    )
+                })?;
+        if let Some(target) = alias {
+            repo = select_repo_by_key(target.key).await.map_err(map_db_err)?;
+        }
+    }
+
     // 2. Mirror-mode fallback: if the literal lookup missed AND a default
     //    Docker mirror repo is configured, re-resolve through it with the
     //    full original image_name as the image path. This makes dockerd's
@@ -2828,7 +3123,8 @@ pub async fn resolve_virtual_blob(
         return None;
     }
 
-    let members = proxy_helpers::fetch_virtual_members(&state.db, repo_id)
+    let route_path = format!("{}/blobs/{}", image_name, digest);
+    let members = proxy_helpers::fetch_virtual_members_for_path(&state.db, repo_id, &route_path)
         .await
         .ok()?;
 
@@ -2930,7 +3226,8 @@ pub async fn resolve_virtual_manifest(
         return None;
     }
 
-    let members = proxy_helpers::fetch_virtual_members(&state.db, repo_id)
+    let route_path = format!("{}/manifests/{}", image_name, reference);
+    let members = proxy_helpers::fetch_virtual_members_for_path(&state.db, repo_id, &route_path)
         .await
         .ok()?;
 
@@ -3568,18 +3865,12 @@ struct TokenQuery {
     /// `"artifact-keeper"` at the challenge site). Missing is allowed for
     /// backward compatibility with clients that pre-date the validation.
     service: Option<String>,
-    // NOTE: `scope` is intentionally NOT a field here. The token this endpoint
-    // issues is derived from the authenticated identity's permissions, not from
-    // the requested scope, so scope is unused for authorization. Declaring it as
-    // a single `Option<String>` made `Query<TokenQuery>` (serde_urlencoded)
-    // reject spec-compliant requests that repeat `?scope=...`: the OCI/Docker
-    // token spec permits multiple `scope` parameters, and kaniko/BuildKit send
-    // one per resource (e.g. the push target plus a base-image repo for a
-    // cross-repo blob mount). serde_urlencoded errors on the repeated known
-    // field with "400 duplicate field `scope`", breaking those pushes. Omitting
-    // the field lets serde ignore the (now unknown) repeated `scope` params
-    // instead of erroring. If scope-based authz is added later, parse it with an
-    // extractor that supports repeated keys (e.g. axum-extra's serde_html_form).
+    // NOTE: `scope` is intentionally NOT a field here. The OCI/Docker token
+    // spec permits multiple `scope` parameters, and kaniko/BuildKit send one
+    // per resource (e.g. the push target plus a base-image repo for a
+    // cross-repo blob mount); serde_urlencoded rejects a repeated known field
+    // with "400 duplicate field `scope`". Scopes are instead parsed from the
+    // raw query string by `registry_scope::requested_scopes`.
     #[allow(dead_code)]
     account: Option<String>,
     /// GET-flow equivalent of OAuth2 `access_type=offline`, per the
@@ -3692,6 +3983,97 @@ fn offline_access_type_from_query(offline_token: Option<&str>) -> Option<String>
     }
 }
 
+/// Map the requested repository scopes onto the caller's RBAC grants.
+///
+/// `claims` are those of the token about to be issued, so its action-scope
+/// ceiling and repository allow-list bound the result. For each requested
+/// repository the caller must pass the same gates the resource handlers
+/// apply: membership (or a public repository, or admin) for `pull`, plus the
+/// fine-grained `write`/`delete` permission when the repository has rules.
+/// Returns the granted action scopes and repositories, or `None` when no
+/// repository scope was requested (`docker login`, `registry:catalog:*`).
+///
+/// The granted actions form one ceiling across all granted repositories; the
+/// handlers still check each repository's permissions per request.
+async fn grant_requested_scopes(
+    state: &SharedState,
+    claims: &crate::services::auth_service::Claims,
+    requested: &[RegistryScope],
+) -> Option<(Vec<String>, Vec<Uuid>)> {
+    let mut repo_scopes = requested.iter().filter(|s| s.is_repository()).peekable();
+    repo_scopes.peek()?;
+
+    let mut actions = std::collections::BTreeSet::new();
+    let mut repo_ids = Vec::new();
+    for scope in repo_scopes {
+        let Ok(repo) = resolve_repo(&state.db, &scope.name).await else {
+            continue;
+        };
+        // Covers the token's repository allow-list, admin and public repos.
+        if require_oci_repo_membership(state, claims, repo.id, repo.is_public)
+            .await
+            .is_err()
+        {
+            continue;
+        }
+        let mut granted = false;
+        for action in scope.token_scopes() {
+            // Pulls are gated by membership alone, as on the read handlers.
+            if action != "read"
+                && (!oci_scopes_grant(&claims.scopes, action)
+                    || require_oci_repo_fine_grained_action(state, claims, repo.id, action)
+                        .await
+                        .is_err())
+            {
+                continue;
+            }
+            actions.insert(action.to_string());
+            granted = true;
+        }
+        if granted && !repo_ids.contains(&repo.id) {
+            repo_ids.push(repo.id);
+        }
+    }
+    Some((actions.into_iter().collect(), repo_ids))
+}
+
+/// Re-mint `access_token` narrowed to the requested repository scopes. An
+/// empty grant still yields a token, one that opens no repository, which
+/// the registry then answers with `DENIED` as the token spec expects.
+/// Returns `Ok(None)` when no repository scope was requested.
+#[allow(clippy::result_large_err)] // Response-as-error is used throughout this module
+async fn narrow_to_requested_scopes(
+    state: &SharedState,
+    auth_service: &AuthService,
+    user: &User,
+    access_token: &str,
+    requested: &[RegistryScope],
+) -> Result<Option<TokenPair>, Response> {
+    let token_generation_failed = || {
+        oci_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "INTERNAL_ERROR",
+            "token generation failed",
+        )
+    };
+    let claims = auth_service
+        .validate_access_token(access_token)
+        .map_err(|_| token_generation_failed())?;
+    let Some((scopes, repo_ids)) = grant_requested_scopes(state, &claims, requested).await else {
+        return Ok(None);
+    };
+    tracing::debug!(
+        user = %user.username,
+        scopes = ?scopes,
+        repositories = repo_ids.len(),
+        "Issuing registry token narrowed to requested scopes"
+    );
+    auth_service
+        .generate_tokens_with_scope(user, Some(scopes), Some(repo_ids))
+        .map(Some)
+        .map_err(|_| token_generation_failed())
+}
+
 /// Exchange a (reusable) offline refresh token for a fresh access token.
 /// Per [the Docker Distribution OAuth2 spec](https://distribution.github.io/distribution/spec/auth/oauth/),
 /// the refresh-grant request body is just `grant_type=refresh_token` +
@@ -3709,7 +4091,13 @@ fn offline_access_type_from_query(offline_token: Option<&str>) -> Option<String>
 /// `refresh_token_jti.revoked_at`, so any of (a) deactivated user,
 /// (b) password change since issue, (c) TOTP enable/disable since issue,
 /// (d) logout/admin family revocation, will surface here as a 401.
-async fn handle_refresh_grant(state: &SharedState, form: &TokenForm) -> Response {
+///
+/// The access token is narrowed to the `requested` scopes like any other.
+async fn handle_refresh_grant(
+    state: &SharedState,
+    form: &TokenForm,
+    requested: &[RegistryScope],
+) -> Response {
     let refresh = match form.refresh_token.as_deref() {
         Some(s) if !s.is_empty() => s,
         _ => {
@@ -3721,7 +4109,7 @@ async fn handle_refresh_grant(state: &SharedState, form: &TokenForm) -> Response
         }
     };
     let auth_service = AuthService::new(state.db.clone(), Arc::new(state.config.clone()));
-    let (_user, tokens) = match auth_service
+    let (user, mut tokens) = match auth_service
         .mint_access_from_registry_refresh(refresh)
         .await
     {
@@ -3744,6 +4132,16 @@ async fn handle_refresh_grant(state: &SharedState, form: &TokenForm) -> Response
     // (`mint_access_from_registry_refresh` does not rotate, #2477). Docker
     // clients replace their stored offline token with whatever this field
     // carries, so echoing the same token keeps their credential live.
+    match narrow_to_requested_scopes(state, &auth_service, &user, &tokens.access_token, requested)
+        .await
+    {
+        Ok(Some(narrowed)) => {
+            tokens.access_token = narrowed.access_token;
+            tokens.expires_in = narrowed.expires_in;
+        }
+        Ok(None) => {}
+        Err(resp) => return resp,
+    }
     let resp = TokenResponse {
         token: tokens.access_token.clone(),
         access_token: tokens.access_token.clone(),
@@ -3762,6 +4160,7 @@ async fn token(
     State(state): State<SharedState>,
     headers: HeaderMap,
     Query(query): Query<TokenQuery>,
+    RawQuery(raw_query): RawQuery,
     body: Bytes,
 ) -> Response {
     // Per the OCI Distribution token spec, clients must request a token
@@ -3780,6 +4179,11 @@ async fn token(
     // `access_type` through to response construction.
     let form = parse_oauth2_form(&headers, &body);
 
+    // Requested registry scopes (`repository:<name>:pull,push`). When any
+    // repository scope is present the issued access token is narrowed to
+    // what the caller may actually do there; see `grant_requested_scopes`.
+    let requested = requested_scopes(raw_query.as_deref(), form.as_ref().map(|_| body.as_ref()));
+
     // grant_type=refresh_token short-circuit. Per the Docker Distribution
     // OAuth2 spec[1], the refresh-grant flow doesn't carry credentials —
     // the refresh_token itself authenticates. So this branch must be
@@ -3788,7 +4192,7 @@ async fn token(
     // [1]: https://distribution.github.io/distribution/spec/auth/oauth/
     if let Some(ref f) = form {
         if f.grant_type.as_deref() == Some("refresh_token") {
-            return handle_refresh_grant(&state, f).await;
+            return handle_refresh_grant(&state, f, &requested).await;
         }
     }
 
@@ -3888,12 +4292,12 @@ async fn token(
                         // repository allow-list across the swap (#2430/#2290): a
                         // JWT exchanged from a scoped API token must not be
                         // re-widened to full access here.
-                        match auth_service.generate_tokens_with_scope(
+                        let t = match auth_service.generate_tokens_with_scope(
                             &user,
                             claims.scopes.clone(),
                             claims.allowed_repo_ids.clone(),
                         ) {
-                            Ok(t) => (t.access_token, t.expires_in),
+                            Ok(t) => t,
                             Err(_) => {
                                 return oci_error(
                                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -3901,6 +4305,19 @@ async fn token(
                                     "token generation failed",
                                 )
                             }
+                        };
+                        match narrow_to_requested_scopes(
+                            &state,
+                            &auth_service,
+                            &user,
+                            &t.access_token,
+                            &requested,
+                        )
+                        .await
+                        {
+                            Ok(Some(narrowed)) => (narrowed.access_token, narrowed.expires_in),
+                            Ok(None) => (t.access_token, t.expires_in),
+                            Err(resp) => return resp,
                         }
                     };
 
@@ -3968,7 +4385,7 @@ async fn token(
     // locks itself out. `validate_api_token` has no failure-counter side
     // effect, so trying it first keeps the lockout counter accurate while
     // still falling through to bcrypt for actual passwords.
-    let (user, tokens, authenticated_via_api_token) = match auth_service
+    let (user, mut tokens, authenticated_via_api_token) = match auth_service
         .validate_api_token(&credentials.1)
         .await
     {
@@ -4079,6 +4496,23 @@ async fn token(
         );
     }
 
+    match narrow_to_requested_scopes(
+        &state,
+        &auth_service,
+        &user,
+        &tokens.access_token,
+        &requested,
+    )
+    .await
+    {
+        Ok(Some(narrowed)) => {
+            tokens.access_token = narrowed.access_token;
+            tokens.expires_in = narrowed.expires_in;
+        }
+        Ok(None) => {}
+        Err(resp) => return resp,
+    }
+
     // Emit a REUSABLE, registry-marked offline refresh token (#2477/#2487)
     // only when the client asked for one AND did not authenticate via an API
     // token (see `offline_refresh_token` for the suppression rationale, which
@@ -4210,7 +4644,7 @@ fn parse_oci_path(path: &str) -> Option<(String, String, Option<String>)> {
     // Find terminal content operations in the remaining path.
     let op_idx = parts
         .iter()
-        .position(|&p| p == "manifests" || p == "blobs")?;
+        .position(|&p| p == "manifests" || p == "blobs" || p == "referrers")?;
     let name = parts[..op_idx].join("/");
     let operation = parts[op_idx];
 
@@ -4285,6 +4719,19 @@ async fn handle_head_blob(
             return resp;
         }
     }
+    let hook_path = format!("{}/blobs/{}", image_name, digest);
+    if let Err(resp) = require_oci_pull_authz(
+        state,
+        claims.as_ref(),
+        repo.id,
+        &hook_path,
+        base_url,
+        &scope,
+    )
+    .await
+    {
+        return resp;
+    }
 
     // Check oci_blobs table. Look up by the canonical digest so an upper-case
     // pull still resolves a blob stored under its canonical lowercase digest.
@@ -4459,6 +4906,19 @@ async fn handle_get_blob(
             return resp;
         }
     }
+    let hook_path = format!("{}/blobs/{}", image_name, digest);
+    if let Err(resp) = require_oci_pull_authz(
+        state,
+        claims.as_ref(),
+        repo.id,
+        &hook_path,
+        base_url,
+        &scope,
+    )
+    .await
+    {
+        return resp;
+    }
 
     // Look up by the canonical digest so an upper-case pull still resolves a
     // blob stored under its canonical lowercase digest.
@@ -6519,10 +6979,10 @@ async fn resolve_manifest_delete_target(
 }
 
 /// Remove this repository's index relationship metadata for a deleted manifest
-/// digest when that relationship is no longer live. Edges from still-tagged
-/// parent indexes to this digest are intentionally preserved: those rows prove
-/// the tagged parent still depends on this child and keep its blobs protected
-/// (see [`delete_manifest_blob_refs`]).
+/// digest when that relationship is no longer live. Edges from parent indexes
+/// that are still tagged, or still referenced by a tagged index themselves,
+/// are intentionally preserved: those rows prove a live image still depends on
+/// this child and keep its blobs protected (see [`delete_manifest_blob_refs`]).
 async fn clear_repo_manifest_refs<'e, E>(
     executor: E,
     repository_id: Uuid,
@@ -6545,6 +7005,7 @@ where
                 WHERE ot.repository_id = omr.repository_id
                   AND ot.manifest_digest = omr.parent_digest
               )
+              AND NOT ak_oci_manifest_index_referenced(omr.repository_id, omr.parent_digest)
             )
           )
         "#,
@@ -6599,6 +7060,19 @@ async fn handle_head_manifest(
             return resp;
         }
     }
+    let hook_path = format!("{}/manifests/{}", image_name, reference);
+    if let Err(resp) = require_oci_pull_authz(
+        state,
+        claims.as_ref(),
+        repo.id,
+        &hook_path,
+        base_url,
+        &scope,
+    )
+    .await
+    {
+        return resp;
+    }
 
     // Reference can be a tag or a digest. Resolve locally first: a surviving
     // tag row, or — for a digest this hosted repo proves it owns via committed
@@ -6794,6 +7268,19 @@ async fn handle_get_manifest(
             return resp;
         }
     }
+    let hook_path = format!("{}/manifests/{}", image_name, reference);
+    if let Err(resp) = require_oci_pull_authz(
+        state,
+        claims.as_ref(),
+        repo.id,
+        &hook_path,
+        base_url,
+        &scope,
+    )
+    .await
+    {
+        return resp;
+    }
 
     // Resolve locally first: a surviving tag row, or — for a digest this hosted
     // repo proves it owns via committed metadata — the content-addressable
@@ -6924,6 +7411,84 @@ async fn handle_get_manifest(
     )
 }
 
+/// Platform-filtered manifest pull: `GET|HEAD
+/// /v2/<name>/manifests/<reference>?platform=os/arch[/variant]`.
+///
+/// Resolves `reference` through the normal pull path; when it is an image
+/// index (or Docker manifest list) the child built for the requested
+/// platform is served in its place, by digest, so a client that cannot
+/// select from an index itself (plain HTTP tooling, some scanners and CI
+/// caches) still gets a runnable single-platform manifest. Non-index
+/// manifests are returned unchanged. The index lookup is not counted as a
+/// download; only the served child is.
+#[allow(clippy::too_many_arguments)]
+async fn handle_platform_manifest(
+    state: &SharedState,
+    headers: &HeaderMap,
+    base_url: &str,
+    image_name: &str,
+    reference: &str,
+    platform_spec: &str,
+    ctx: &crate::api::middleware::download_telemetry::DownloadContext,
+    include_body: bool,
+) -> Response {
+    let Some(wanted) = OciPlatform::parse(platform_spec) else {
+        return oci_error(
+            StatusCode::BAD_REQUEST,
+            "UNSUPPORTED",
+            &format!(
+                "invalid platform '{}': expected os/arch[/variant]",
+                platform_spec
+            ),
+        );
+    };
+
+    let probe_ctx = crate::api::middleware::download_telemetry::DownloadContext {
+        is_head: true,
+        ..ctx.clone()
+    };
+    let resolved =
+        handle_get_manifest(state, headers, base_url, image_name, reference, &probe_ctx).await;
+    let is_index = resolved
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(is_index_content_type);
+    if !resolved.status().is_success() || !is_index {
+        if include_body {
+            return resolved;
+        }
+        return handle_head_manifest(state, headers, base_url, image_name, reference).await;
+    }
+
+    let index_body = match to_bytes(
+        resolved.into_body(),
+        crate::services::oci_manifest_refs_backfill::MAX_INDEX_MANIFEST_BYTES,
+    )
+    .await
+    {
+        Ok(body) => body,
+        Err(e) => return oci_internal_error(&format!("failed to read image index: {}", e)),
+    };
+    let Some(child) = select_platform_manifest(&index_body, &wanted) else {
+        return oci_error(
+            StatusCode::NOT_FOUND,
+            "MANIFEST_UNKNOWN",
+            &format!(
+                "image index {} has no manifest for platform {}",
+                reference, wanted
+            ),
+        );
+    };
+
+    tracing::debug!(image = %image_name, reference = %reference, platform = %wanted, digest = %child.digest, "GET manifest: serving platform child of image index");
+    if include_body {
+        handle_get_manifest(state, headers, base_url, image_name, &child.digest, ctx).await
+    } else {
+        handle_head_manifest(state, headers, base_url, image_name, &child.digest).await
+    }
+}
+
 /// True when a manifest reference is a tag rather than a digest
 /// (`sha256:...`). Same filter as the tags/list handler and the
 /// `docker_tag` grouping query (`POSITION(':' IN tag) = 0`): OCI tag
@@ -7202,6 +7767,20 @@ async fn handle_put_manifest(
         );
     }
 
+    // Referrers API: a manifest carrying a `subject` (signature, SBOM,
+    // attestation) is listed under that subject. Best-effort like the
+    // artifact record below — the manifest and its tag are already committed.
+    let referrer = crate::services::oci_referrers::parse_referrer(&body, &digest, &content_type);
+    if let Some((subject, descriptor)) = &referrer {
+        if let Err(e) = crate::services::oci_referrers::record_referrer(
+            &state.db, repo_id, &image, subject, descriptor,
+        )
+        .await
+        {
+            tracing::error!("Failed to record referrer {} of {}: {}", digest, subject, e);
+        }
+    }
+
     // Calculate total image size from manifest (config + layers)
     let total_size: i64 = manifest_total_size(&body);
 
@@ -7275,13 +7854,113 @@ async fn handle_put_manifest(
 
     info!("Manifest pushed: {}:{} ({})", image_name, reference, digest);
 
-    Response::builder()
+    let mut response = Response::builder()
         .status(StatusCode::CREATED)
         .header(LOCATION, format!("/v2/{}/manifests/{}", image_name, digest))
         .header("Docker-Content-Digest", &digest)
-        .header(CONTENT_LENGTH, "0")
-        .body(Body::empty())
-        .unwrap()
+        .header(CONTENT_LENGTH, "0");
+    // Tells clients the registry supports the referrers API, so they skip
+    // the tag-schema fallback (`sha256-<hex>` tags).
+    if let Some((subject, _)) = &referrer {
+        response = response.header("OCI-Subject", subject);
+    }
+    response.body(Body::empty()).unwrap()
+}
+
+// ---------------------------------------------------------------------------
+// Referrers handler
+// ---------------------------------------------------------------------------
+
+/// `GET /v2/<name>/referrers/<digest>[?artifactType=...]`.
+///
+/// Lists the manifests pushed to this repository with `subject` = `digest`
+/// as an OCI image index. An unknown subject yields an empty index, never a
+/// 404, per the Distribution Spec. Referrers are recorded at push time, so
+/// Remote and Virtual repositories only list what was pushed locally (none).
+async fn handle_referrers(
+    state: &SharedState,
+    headers: &HeaderMap,
+    base_url: &str,
+    image_name: &str,
+    digest: &str,
+    query: &std::collections::HashMap<String, String>,
+) -> Response {
+    let scope = pull_scope(image_name);
+    // Same gate as tags/list: anonymous tokens may read public repositories.
+    let is_anon = is_anonymous_token(headers);
+    let claims = if is_anon {
+        None
+    } else {
+        match authenticate_oci(&state.db, &state.config, headers).await {
+            Ok(c) => Some(c),
+            Err(()) => return unauthorized_challenge_with_scope(base_url, Some(&scope)),
+        }
+    };
+
+    let repo = match resolve_repo(&state.db, image_name).await {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+    if is_anon && !repo.is_public {
+        return unauthorized_challenge_with_scope(base_url, Some(&scope));
+    }
+    let hook_path = format!("{}/referrers/{}", image_name, digest);
+    if let Err(resp) = require_oci_pull_authz(
+        state,
+        claims.as_ref(),
+        repo.id,
+        &hook_path,
+        base_url,
+        &scope,
+    )
+    .await
+    {
+        return resp;
+    }
+
+    if !is_digest_reference(digest) {
+        return oci_error(
+            StatusCode::BAD_REQUEST,
+            "DIGEST_INVALID",
+            "referrers require a digest reference",
+        );
+    }
+
+    let artifact_type = query
+        .get("artifactType")
+        .map(String::as_str)
+        .filter(|t| !t.is_empty());
+    let referrers = match crate::services::oci_referrers::list_referrers(
+        &state.db,
+        repo.id,
+        &repo.image,
+        digest,
+        artifact_type,
+    )
+    .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            return oci_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "INTERNAL_ERROR",
+                &e.to_string(),
+            )
+        }
+    };
+
+    let body = crate::services::oci_referrers::referrers_index(&referrers).to_string();
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            CONTENT_TYPE,
+            crate::services::oci_referrers::REFERRERS_INDEX_MEDIA_TYPE,
+        )
+        .header(CONTENT_LENGTH, body.len().to_string());
+    if artifact_type.is_some() {
+        response = response.header("OCI-Filters-Applied", "artifactType");
+    }
+    response.body(Body::from(body)).unwrap()
 }
 
 // ---------------------------------------------------------------------------
@@ -7299,13 +7978,14 @@ async fn handle_tags_list(
     // #1776: mirror handle_head_manifest — anonymous tokens are allowed past the
     // auth gate so a public repository's tags can be listed without credentials.
     let is_anon = is_anonymous_token(headers);
-    if !is_anon
-        && authenticate_oci(&state.db, &state.config, headers)
-            .await
-            .is_err()
-    {
-        return unauthorized_challenge_with_scope(base_url, Some(&scope));
-    }
+    let claims = if is_anon {
+        None
+    } else {
+        match authenticate_oci(&state.db, &state.config, headers).await {
+            Ok(c) => Some(c),
+            Err(()) => return unauthorized_challenge_with_scope(base_url, Some(&scope)),
+        }
+    };
 
     let repo = match resolve_repo(&state.db, image_name).await {
         Ok(r) => r,
@@ -7316,6 +7996,19 @@ async fn handle_tags_list(
     if is_anon && !repo.is_public {
         return unauthorized_challenge_with_scope(base_url, Some(&scope));
     }
+    let hook_path = format!("{}/tags/list", image_name);
+    if let Err(resp) = require_oci_pull_authz(
+        state,
+        claims.as_ref(),
+        repo.id,
+        &hook_path,
+        base_url,
+        &scope,
+    )
+    .await
+    {
+        return resp;
+    }
 
     let (n, last) = match parse_pagination_params(query) {
         Ok(v) => v,
@@ -7819,7 +8512,9 @@ async fn tags_list_virtual(
     n_limit: usize,
     last: Option<&str>,
 ) -> Result<Vec<String>, Response> {
-    let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+    let route_path = format!("{}/tags/list", repo.image);
+    let members =
+        proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
     let member_limit = n_limit.saturating_add(1);
     let member_cursor = last;
     let image = repo.image.clone();
@@ -8046,8 +8741,9 @@ async fn catalog_local_entries(
 /// references them (#1409). Without this, refs live forever and a blob stays
 /// pinned even after every referencing manifest is gone.
 ///
-/// Scoped to NOT delete refs for a digest that is still a live
-/// per-architecture child of a tagged image index: such a child's blobs are
+/// Scoped to NOT delete refs for a digest that is still a live child of a
+/// tagged image index, directly or through nested indexes
+/// (`ak_oci_manifest_index_referenced`): such a child's blobs are
 /// protected ONLY by these rows (the blob-orphan predicate has no
 /// `oci_manifest_refs` join), so deleting them while the index still serves
 /// the child would strip a live image's protection. The caller deletes the
@@ -8062,15 +8758,7 @@ async fn delete_manifest_blob_refs(
         DELETE FROM manifest_blob_refs
         WHERE repository_id = $1
           AND manifest_digest = $2
-          AND NOT EXISTS (
-            SELECT 1
-            FROM oci_manifest_refs omr
-            JOIN oci_tags ot
-              ON ot.repository_id = omr.repository_id
-             AND ot.manifest_digest = omr.parent_digest
-            WHERE omr.repository_id = $1
-              AND omr.child_digest = $2
-          )
+          AND NOT ak_oci_manifest_index_referenced($1, $2)
         "#,
     )
     .bind(repo_id)
@@ -8086,6 +8774,7 @@ async fn handle_delete_manifest(
     base_url: &str,
     image_name: &str,
     reference: &str,
+    confirmed: bool,
 ) -> Response {
     let scope = push_scope(image_name);
     let (claims, token_scopes) =
@@ -8104,8 +8793,7 @@ async fn handle_delete_manifest(
         Err(e) => return e,
     };
     // Repository write/delete authorization (private-repo members-only gate).
-    if let Err(resp) = require_oci_repo_write_access(state, &claims, repo.id, repo.is_public).await
-    {
+    if let Err(resp) = require_oci_repo_membership(state, &claims, repo.id, repo.is_public).await {
         return resp;
     }
     // Fine-grained delete gate (#2321 G2): the tenant gate above admits any
@@ -8116,6 +8804,12 @@ async fn handle_delete_manifest(
     {
         return resp;
     }
+    let hook_path = format!("{}/manifests/{}", image_name, reference);
+    match oci_external_authz(state, Some(&claims), repo.id, "delete", Some(&hook_path)).await {
+        Ok(true) => {}
+        Ok(false) => return oci_denied_repo_access(),
+        Err(resp) => return resp,
+    }
 
     // Promotion-only release repositories: deleting a manifest is the symmetric
     // mutation to the (already-gated) direct push and would let a plain
@@ -8202,6 +8896,42 @@ async fn handle_delete_manifest(
         }
     };
 
+    // Download-activity protection, as on the REST artifact delete; a
+    // `?confirm=true` query confirms the delete where the rule allows it.
+    // Pulls are recorded against the manifest's content-addressed artifact,
+    // so every live path of the digest is checked.
+    let manifest_artifacts = match sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM artifacts WHERE repository_id = $1 AND storage_key = $2 AND is_deleted = false",
+    )
+    .bind(repo.id)
+    .bind(manifest_storage_key(&digest))
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            return oci_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "INTERNAL_ERROR",
+                &e.to_string(),
+            )
+        }
+    };
+    match crate::services::delete_protection_service::DeleteProtectionService::new(state.db.clone())
+        .check_delete_all(repo.id, &manifest_artifacts, confirmed)
+        .await
+    {
+        Ok(()) => {}
+        Err(AppError::Conflict(msg)) => return oci_error(StatusCode::CONFLICT, "DENIED", &msg),
+        Err(e) => {
+            return oci_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "INTERNAL_ERROR",
+                &e.to_string(),
+            )
+        }
+    }
+
     let mut tx = match state.db.begin().await {
         Ok(tx) => tx,
         Err(e) => {
@@ -8407,6 +9137,23 @@ async fn catch_all(
             };
             handle_cancel_upload(&state, &headers, base_url, &image_name, &u).await
         }
+        ("HEAD" | "GET", "manifests") if query.contains_key("platform") => {
+            let r = require_ref!(reference, "NAME_INVALID", "reference required");
+            handle_platform_manifest(
+                &state,
+                &headers,
+                base_url,
+                &image_name,
+                &r,
+                query
+                    .get("platform")
+                    .map(String::as_str)
+                    .unwrap_or_default(),
+                &ctx,
+                method == Method::GET,
+            )
+            .await
+        }
         ("HEAD", "manifests") => {
             let r = require_ref!(reference, "NAME_INVALID", "reference required");
             handle_head_manifest(&state, &headers, base_url, &image_name, &r).await
@@ -8427,9 +9174,14 @@ async fn catch_all(
         }
         ("DELETE", "manifests") => {
             let r = require_ref!(reference, "NAME_INVALID", "reference required");
-            handle_delete_manifest(&state, &headers, base_url, &image_name, &r).await
+            let confirmed = query.get("confirm").is_some_and(|v| v == "true");
+            handle_delete_manifest(&state, &headers, base_url, &image_name, &r, confirmed).await
         }
         ("GET", "tags") => handle_tags_list(&state, &headers, base_url, &image_name, &query).await,
+        ("GET", "referrers") => {
+            let d = require_ref!(reference, "DIGEST_INVALID", "digest required");
+            handle_referrers(&state, &headers, base_url, &image_name, &d, &query).await
+        }
         _ => oci_error(
             StatusCode::METHOD_NOT_ALLOWED,
             "UNSUPPORTED",
@@ -9868,6 +10620,15 @@ mod tests {
         assert_eq!(reference, Some("latest".to_string()));
     }
 
+    #[test]
+    fn test_parse_oci_path_referrers() {
+        let result = parse_oci_path("/test/python/referrers/sha256:abc");
+        let (name, op, reference) = result.unwrap();
+        assert_eq!(name, "test/python");
+        assert_eq!(op, "referrers");
+        assert_eq!(reference, Some("sha256:abc".to_string()));
+    }
+
     #[test]
     fn test_parse_oci_path_uploads_no_uuid() {
         let result = parse_oci_path("/test/python/blobs/uploads/");
@@ -13568,6 +14329,111 @@ mod oci_manifest_refs_tests {
         assert_eq!(children, vec!["sha256:aaaa", "sha256:bbbb"]);
     }
 
+    // -- per-platform index children ----------------------------------------
+
+    const MULTI_ARCH_INDEX: &[u8] = br#"{
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.index.v1+json",
+        "manifests": [
+            {
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": "sha256:amd64",
+                "size": 1200,
+                "platform": {"architecture": "amd64", "os": "linux"}
+            },
+            {
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": "sha256:armv6",
+                "size": 1100,
+                "platform": {"architecture": "arm", "os": "linux", "variant": "v6"}
+            },
+            {
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": "sha256:arm64",
+                "size": 1150,
+                "platform": {"architecture": "arm64", "os": "linux", "variant": "v8"}
+            },
+            {
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": "sha256:win",
+                "size": 900,
+                "platform": {"architecture": "amd64", "os": "windows", "os.version": "10.0.17763.1"}
+            },
+            {
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": "sha256:attest",
+                "size": 500,
+                "platform": {"architecture": "unknown", "os": "unknown"}
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn extract_child_manifests_reads_descriptors() {
+        let children = extract_child_manifests(MULTI_ARCH_INDEX);
+        assert_eq!(children.len(), 5);
+        let arm = &children[1];
+        assert_eq!(arm.size, Some(1100));
+        assert_eq!(
+            arm.media_type.as_deref(),
+            Some("application/vnd.oci.image.manifest.v1+json")
+        );
+        let platform = arm.platform.as_ref().unwrap();
+        assert_eq!(platform.to_string(), "linux/arm/v6");
+        assert_eq!(
+            children[3].platform.as_ref().unwrap().os_version.as_deref(),
+            Some("10.0.17763.1")
+        );
+
+        // A descriptor without a platform still yields its edge.
+        let bare = extract_child_manifests(br#"{"manifests": [{"digest": "sha256:x"}]}"#);
+        assert_eq!(bare[0].platform, None);
+        assert_eq!(bare[0].size, None);
+    }
+
+    #[test]
+    fn oci_platform_parse_normalizes_aliases() {
+        let p = OciPlatform::parse("linux/aarch64").unwrap();
+        assert_eq!(p.to_string(), "linux/arm64/v8");
+        assert_eq!(
+            OciPlatform::parse("Linux/x86_64").unwrap().to_string(),
+            "linux/amd64"
+        );
+        assert_eq!(
+            OciPlatform::parse("linux/arm").unwrap().to_string(),
+            "linux/arm/v7"
+        );
+        assert_eq!(
+            OciPlatform::parse("linux/arm/6").unwrap().to_string(),
+            "linux/arm/v6"
+        );
+        let win = OciPlatform::parse("windows:10.0.17763.1/amd64").unwrap();
+        assert_eq!(win.os, "windows");
+        assert_eq!(win.os_version.as_deref(), Some("10.0.17763.1"));
+
+        assert!(OciPlatform::parse("").is_none());
+        assert!(OciPlatform::parse("linux").is_none());
+        assert!(OciPlatform::parse("linux/").is_none());
+        assert!(OciPlatform::parse("linux/arm/v7/extra").is_none());
+    }
+
+    #[test]
+    fn select_platform_manifest_matches_normalized_platform() {
+        let pick = |spec: &str| {
+            select_platform_manifest(MULTI_ARCH_INDEX, &OciPlatform::parse(spec).unwrap())
+                .map(|c| c.digest)
+        };
+        assert_eq!(pick("linux/amd64").as_deref(), Some("sha256:amd64"));
+        assert_eq!(pick("linux/arm64").as_deref(), Some("sha256:arm64"));
+        assert_eq!(pick("linux/aarch64/8").as_deref(), Some("sha256:arm64"));
+        assert_eq!(pick("linux/arm/v6").as_deref(), Some("sha256:armv6"));
+        // arm defaults to v7, which this index does not carry.
+        assert_eq!(pick("linux/arm"), None);
+        assert_eq!(pick("windows/amd64").as_deref(), Some("sha256:win"));
+        assert_eq!(pick("windows:10.0.20348.1/amd64"), None);
+        assert_eq!(pick("linux/s390x"), None);
+    }
+
     // -- manifest_blob_refs (#1635) blob-edge extraction --------------------
 
     #[test]
@@ -15103,6 +15969,189 @@ mod oci_blob_upload_streaming_tests {
         );
     }
 
+    /// The external authorization hook gets the last word on `/v2` pulls: a
+    /// manifest the member can pull is refused once the hook denies the read.
+    #[tokio::test]
+    async fn pull_denied_by_external_authz_hook() {
+        use crate::services::external_authz_service::{
+            AuthzHookKind, ExternalAuthzConfig, ExternalAuthzService,
+        };
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let Some(f) = OciUploadFixture::setup().await else {
+            return;
+        };
+        let ct = "application/vnd.oci.image.manifest.v1+json";
+        let body = Bytes::from_static(
+            br#"{"schemaVersion":2,"mediaType":"application/vnd.oci.image.manifest.v1+json","config":{"mediaType":"application/vnd.oci.image.config.v1+json","digest":"sha256:cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd","size":1},"layers":[]}"#,
+        );
+        let mut put = request(
+            Method::PUT,
+            format!("/{}/app/manifests/v1", f.inner.repo_key),
+            &f.authorization,
+            body,
+        );
+        put.headers_mut()
+            .insert(CONTENT_TYPE, axum::http::HeaderValue::from_static(ct));
+        let (put_status, _h, _b) = send(f.app(), put).await;
+        let pull = || {
+            request(
+                Method::GET,
+                format!("/{}/app/manifests/v1", f.inner.repo_key),
+                &f.authorization,
+                Bytes::new(),
+            )
+        };
+        let (open_status, _h, _b) = send(f.app(), pull()).await;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/authz"))
+            .and(body_partial_json(serde_json::json!({"action": "read"})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"allow": false, "reason": "frozen"})),
+            )
+            .expect(1..)
+            .mount(&server)
+            .await;
+        let hook = ExternalAuthzService::new(ExternalAuthzConfig {
+            url: format!("{}/authz", server.uri()),
+            kind: AuthzHookKind::Webhook,
+            timeout: std::time::Duration::from_secs(5),
+            fail_open: false,
+            token: None,
+        })
+        .expect("build authz hook");
+        let mut state = (*f.inner.state).clone();
+        state.set_external_authz(Arc::new(hook));
+        let (denied_status, _h, denied_body) =
+            send(router().with_state(Arc::new(state)), pull()).await;
+
+        f.teardown().await;
+
+        assert_eq!(put_status, StatusCode::CREATED, "seed manifest push");
+        assert_eq!(open_status, StatusCode::OK, "pull without a hook");
+        assert_eq!(
+            denied_status,
+            StatusCode::FORBIDDEN,
+            "a hook denial must block the pull"
+        );
+        assert!(
+            String::from_utf8_lossy(&denied_body).contains("DENIED"),
+            "403 body must carry the OCI DENIED code; got: {}",
+            String::from_utf8_lossy(&denied_body)
+        );
+    }
+
+    /// Deletion protection covers registry deletes: a recently pulled manifest
+    /// is refused (409) under a `block` rule, and a `confirm` rule lets the
+    /// delete through with `?confirm=true`.
+    #[tokio::test]
+    async fn delete_manifest_honours_delete_protection() {
+        use crate::services::delete_protection_service::{
+            DeleteProtectionService, ProtectionAction,
+        };
+
+        let Some(f) = OciUploadFixture::setup().await else {
+            return;
+        };
+        let body = Bytes::from_static(
+            br#"{"schemaVersion":2,"mediaType":"application/vnd.oci.image.manifest.v1+json","config":{"mediaType":"application/vnd.oci.image.config.v1+json","digest":"sha256:efefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefef","size":1},"layers":[]}"#,
+        );
+        let mut put = request(
+            Method::PUT,
+            format!("/{}/app/manifests/v1", f.inner.repo_key),
+            &f.authorization,
+            body,
+        );
+        put.headers_mut().insert(
+            CONTENT_TYPE,
+            axum::http::HeaderValue::from_static("application/vnd.oci.image.manifest.v1+json"),
+        );
+        let (put_status, _h, _b) = send(f.app(), put).await;
+        sqlx::query(
+            "INSERT INTO download_statistics (artifact_id, user_id, ip_address, user_agent) \
+             SELECT id, $2, '10.0.0.1', 'docker' FROM artifacts WHERE repository_id = $1",
+        )
+        .bind(f.inner.repo_id)
+        .bind(f.inner.user_id)
+        .execute(&f.inner.pool)
+        .await
+        .expect("record a pull");
+        let protection = DeleteProtectionService::new(f.inner.pool.clone());
+        let delete = |query: &str| {
+            request(
+                Method::DELETE,
+                format!("/{}/app/manifests/v1{}", f.inner.repo_key, query),
+                &f.authorization,
+                Bytes::new(),
+            )
+        };
+
+        protection
+            .set(
+                f.inner.repo_id,
+                Some(30),
+                None,
+                ProtectionAction::Block,
+                None,
+            )
+            .await
+            .expect("set block rule");
+        let (blocked_status, _h, blocked_body) = send(f.app(), delete("?confirm=true")).await;
+        let surviving: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM oci_tags WHERE repository_id = $1 AND tag = 'v1'",
+        )
+        .bind(f.inner.repo_id)
+        .fetch_one(&f.inner.pool)
+        .await
+        .expect("count v1 tag");
+
+        protection
+            .set(
+                f.inner.repo_id,
+                Some(30),
+                None,
+                ProtectionAction::Confirm,
+                None,
+            )
+            .await
+            .expect("set confirm rule");
+        let (unconfirmed_status, _h, _b) = send(f.app(), delete("")).await;
+        let (confirmed_status, _h, _b) = send(f.app(), delete("?confirm=true")).await;
+
+        let _ = protection.clear(f.inner.repo_id).await;
+        let _ = sqlx::query(
+            "DELETE FROM download_statistics WHERE artifact_id IN \
+             (SELECT id FROM artifacts WHERE repository_id = $1)",
+        )
+        .bind(f.inner.repo_id)
+        .execute(&f.inner.pool)
+        .await;
+        f.teardown().await;
+
+        assert_eq!(put_status, StatusCode::CREATED, "seed manifest push");
+        assert_eq!(
+            blocked_status,
+            StatusCode::CONFLICT,
+            "a block rule refuses the delete even when confirmed"
+        );
+        assert!(
+            String::from_utf8_lossy(&blocked_body).contains("protected from deletion"),
+            "409 body must explain the protection; got: {}",
+            String::from_utf8_lossy(&blocked_body)
+        );
+        assert_eq!(surviving, 1, "a refused delete must leave the tag intact");
+        assert_eq!(unconfirmed_status, StatusCode::CONFLICT);
+        assert_eq!(
+            confirmed_status,
+            StatusCode::ACCEPTED,
+            "a confirm rule lets a confirmed delete through"
+        );
+    }
+
     /// #1409: a manifest DELETE removes its blob refs (so the blobs become
     /// reclaimable) end-to-end through the router.
     #[tokio::test]
@@ -0,0 +1,303 @@
+//! Dependency-confusion and typosquatting protection administration.
+//!
+//! Admin-only, nested under `/api/v1/admin/package-protection`. Policies are
+//! set per remote repository; findings record every detection in alert or
+//! block mode. See `services::package_protection_service` for the checks.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::models::repository::RepositoryType;
+use crate::services::package_protection_service::{
+    ecosystem, PackageProtectionService, ProtectionAction, ProtectionFinding, ProtectionPolicy,
+    UpdateProtectionPolicy, KIND_DEPENDENCY_CONFUSION, KIND_TYPOSQUATTING,
+};
+use crate::services::repository_service::RepositoryService;
+
+/// Admin routes, nested at `/api/v1/admin/package-protection`.
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/policies", get(list_policies))
+        .route(
+            "/policies/:key",
+            get(get_policy).put(update_policy).delete(delete_policy),
+        )
+        .route("/findings", get(list_findings))
+        .route("/findings/:id", delete(delete_finding))
+}
+
+fn require_admin(auth: &AuthExtension) -> Result<()> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Policies only make sense where the proxy fetches from an upstream, and
+/// only for formats whose paths name a package.
+fn validate_target(repo: &crate::models::repository::Repository) -> Result<()> {
+    if repo.repo_type != RepositoryType::Remote {
+        return Err(AppError::Validation(
+            "Package protection applies to remote repositories; virtual repositories \
+             are covered through their remote members"
+                .to_string(),
+        ));
+    }
+    if ecosystem(&repo.format).is_none() {
+        return Err(AppError::Validation(format!(
+            "Package protection is not supported for {:?} repositories",
+            repo.format
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProtectionPolicyListResponse {
+    pub items: Vec<ProtectionPolicy>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/policies",
+    context_path = "/api/v1/admin/package-protection",
+    tag = "package_protection",
+    responses(
+        (status = 200, description = "Policies of all protected repositories", body = ProtectionPolicyListResponse),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_policies(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<ProtectionPolicyListResponse>> {
+    require_admin(&auth)?;
+
+    let items = PackageProtectionService::new(state.db.clone())
+        .list_policies()
+        .await?;
+    Ok(Json(ProtectionPolicyListResponse { items }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/policies/{key}",
+    context_path = "/api/v1/admin/package-protection",
+    tag = "package_protection",
+    params(("key" = String, Path, description = "Remote repository key")),
+    responses(
+        (status = 200, description = "Repository policy", body = ProtectionPolicy),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Repository not found or not protected"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(key): Path<String>,
+) -> Result<Json<ProtectionPolicy>> {
+    require_admin(&auth)?;
+
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+    PackageProtectionService::new(state.db.clone())
+        .get_policy(repo.id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("Repository '{}' has no protection policy", key)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/policies/{key}",
+    context_path = "/api/v1/admin/package-protection",
+    tag = "package_protection",
+    params(("key" = String, Path, description = "Remote repository key")),
+    request_body = UpdateProtectionPolicy,
+    responses(
+        (status = 200, description = "Policy created or replaced", body = ProtectionPolicy),
+        (status = 400, description = "Not a supported remote repository, or invalid settings"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(key): Path<String>,
+    Json(body): Json<UpdateProtectionPolicy>,
+) -> Result<Json<ProtectionPolicy>> {
+    require_admin(&auth)?;
+
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+    validate_target(&repo)?;
+    let update = body.normalized()?;
+    let policy = PackageProtectionService::new(state.db.clone())
+        .upsert_policy(repo.id, &update, auth.user_id)
+        .await?;
+    Ok(Json(policy))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/policies/{key}",
+    context_path = "/api/v1/admin/package-protection",
+    tag = "package_protection",
+    params(("key" = String, Path, description = "Remote repository key")),
+    responses(
+        (status = 204, description = "Protection disabled for the repository"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Repository not found or not protected"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(key): Path<String>,
+) -> Result<StatusCode> {
+    require_admin(&auth)?;
+
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+    if !PackageProtectionService::new(state.db.clone())
+        .delete_policy(repo.id)
+        .await?
+    {
+        return Err(AppError::NotFound(format!(
+            "Repository '{}' has no protection policy",
+            key
+        )));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct FindingsQuery {
+    /// Only findings of this repository key.
+    pub repository: Option<String>,
+    /// `dependency_confusion` or `typosquatting`.
+    pub kind: Option<String>,
+    /// Page size (default 100, max 1000).
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProtectionFindingListResponse {
+    pub items: Vec<ProtectionFinding>,
+    pub total: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/findings",
+    context_path = "/api/v1/admin/package-protection",
+    tag = "package_protection",
+    params(FindingsQuery),
+    responses(
+        (status = 200, description = "Detections, most recent first", body = ProtectionFindingListResponse),
+        (status = 400, description = "Unknown finding kind"),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_findings(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<FindingsQuery>,
+) -> Result<Json<ProtectionFindingListResponse>> {
+    require_admin(&auth)?;
+
+    let kind = query.kind.as_deref().filter(|k| !k.is_empty());
+    if let Some(kind) = kind {
+        if kind != KIND_DEPENDENCY_CONFUSION && kind != KIND_TYPOSQUATTING {
+            return Err(AppError::Validation(format!(
+                "Unknown finding kind '{}'",
+                kind
+            )));
+        }
+    }
+    let repository_id: Option<Uuid> = match query.repository.as_deref() {
+        Some(key) if !key.is_empty() => Some(
+            RepositoryService::new(state.db.clone())
+                .get_by_key(key)
+                .await?
+                .id,
+        ),
+        _ => None,
+    };
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let (items, total) = PackageProtectionService::new(state.db.clone())
+        .list_findings(repository_id, kind, limit, offset)
+        .await?;
+    Ok(Json(ProtectionFindingListResponse { items, total }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/findings/{id}",
+    context_path = "/api/v1/admin/package-protection",
+    tag = "package_protection",
+    params(("id" = Uuid, Path, description = "Finding ID")),
+    responses(
+        (status = 204, description = "Finding dismissed"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Finding not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_finding(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_admin(&auth)?;
+
+    PackageProtectionService::new(state.db.clone())
+        .delete_finding(id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_policies,
+        get_policy,
+        update_policy,
+        delete_policy,
+        list_findings,
+        delete_finding,
+    ),
+    components(schemas(
+        ProtectionAction,
+        ProtectionPolicy,
+        ProtectionPolicyListResponse,
+        UpdateProtectionPolicy,
+        ProtectionFinding,
+        ProtectionFindingListResponse,
+    ))
+)]
+pub struct PackageProtectionApiDoc;
@@ -25,7 +25,7 @@ use crate::services::repository_service::{
 /// packages endpoints enforce the same per-user authorization model
 /// (public repos plus any repo the user holds a role assignment for) instead
 /// of treating every authenticated caller as entitled to all packages.
-fn repo_visibility_for(auth: Option<&AuthExtension>) -> RepoVisibility {
+pub(crate) fn repo_visibility_for(auth: Option<&AuthExtension>) -> RepoVisibility {
     match auth {
         None => RepoVisibility::PublicOnly,
         Some(a) if a.is_admin => RepoVisibility::All,
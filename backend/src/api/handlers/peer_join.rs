@@ -0,0 +1,488 @@
+//! Edge node auto-registration handlers.
+//!
+//! Public (node-facing, mounted at `/api/v1/peers/join`):
+//! - `POST /`               redeem a join token and enter the approval queue
+//! - `GET  /:id`            poll the request status (node secret as Bearer)
+//! - `POST /:id/heartbeat`  report a heartbeat once approved (node secret)
+//! - `POST /:id/diagnostics` forward metrics and recent logs (node secret)
+//! - `POST /:id/integrity`  report cache integrity results (node secret)
+//!
+//! Admin (mounted under the authenticated `/api/v1/peers` router):
+//! - `GET/POST /join-tokens`, `DELETE /join-tokens/:id`
+//! - `GET /join-requests`, `POST /join-requests/:id/approve`,
+//!   `POST /join-requests/:id/reject`
+
+use axum::{
+    extract::{DefaultBodyLimit, Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::handlers::peers::{parse_status, HeartbeatRequest};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::validation::validate_outbound_url;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::cache_integrity_service::{
+    self, CorruptEntry, IntegrityReport, IntegrityReportResponse,
+};
+use crate::services::edge_diagnostics_service::{self, DiagnosticsBatch, LogExcerpt};
+use crate::services::peer_instance_service::PeerInstanceService;
+use crate::services::peer_join_service::{
+    self, CreateJoinToken, JoinRequest, JoinRequestRecord, JoinToken,
+};
+use crate::services::sync_policy_service::SyncPolicyService;
+
+/// Node-facing routes; authenticated by join token or node secret, not by
+/// the regular auth middleware.
+pub fn public_router() -> Router<SharedState> {
+    Router::new()
+        .route("/", post(join))
+        .route("/:id", get(join_status))
+        .route("/:id/heartbeat", post(node_heartbeat))
+        .route(
+            "/:id/diagnostics",
+            post(node_diagnostics).layer(DefaultBodyLimit::max(4 * 1024 * 1024)),
+        )
+        .route(
+            "/:id/integrity",
+            post(node_integrity_report).layer(DefaultBodyLimit::max(1024 * 1024)),
+        )
+}
+
+/// Admin routes for join tokens and the approval queue.
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route(
+            "/join-tokens",
+            get(list_join_tokens).post(create_join_token),
+        )
+        .route("/join-tokens/:id", delete(revoke_join_token))
+        .route("/join-requests", get(list_join_requests))
+        .route("/join-requests/:id/approve", post(approve_join_request))
+        .route("/join-requests/:id/reject", post(reject_join_request))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JoinResponse {
+    pub request: JoinRequestRecord,
+    /// Secret for polling status and sending heartbeats. Shown only once.
+    pub node_secret: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateJoinTokenResponse {
+    pub token: JoinToken,
+    /// The join token to hand to new nodes. Shown only once.
+    pub join_token: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListJoinRequestsQuery {
+    /// Filter by status: `pending`, `approved` or `rejected`.
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RejectJoinRequest {
+    pub reason: Option<String>,
+}
+
+/// Extract the node secret from `Authorization: Bearer <secret>`.
+fn node_secret(headers: &HeaderMap) -> Result<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| {
+            AppError::Authentication(
+                "Supply the node secret as: Authorization: Bearer <secret>".into(),
+            )
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Node-facing handlers
+// ---------------------------------------------------------------------------
+
+/// Redeem a join token and queue this node for admin approval
+#[utoipa::path(
+    post,
+    path = "",
+    context_path = "/api/v1/peers/join",
+    tag = "peers",
+    request_body = JoinRequest,
+    responses(
+        (status = 201, description = "Join request queued", body = JoinResponse),
+        (status = 401, description = "Invalid, expired or exhausted join token"),
+        (status = 409, description = "A request with this name is already pending"),
+    )
+)]
+pub async fn join(
+    State(state): State<SharedState>,
+    Json(payload): Json<JoinRequest>,
+) -> Result<(StatusCode, Json<JoinResponse>)> {
+    validate_outbound_url(&payload.endpoint_url, "Peer endpoint URL")?;
+    let (request, node_secret) = peer_join_service::submit(&state.db, &payload).await?;
+    tracing::info!(
+        "Edge node '{}' ({}) requested to join, fingerprint {}",
+        request.name,
+        request.hostname,
+        request.fingerprint
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(JoinResponse {
+            request,
+            node_secret,
+        }),
+    ))
+}
+
+/// Poll the status of this node's join request
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    context_path = "/api/v1/peers/join",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Join request ID")),
+    responses(
+        (status = 200, description = "Join request", body = JoinRequestRecord),
+        (status = 401, description = "Missing node secret"),
+        (status = 404, description = "Unknown request or wrong secret"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn join_status(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<JoinRequestRecord>> {
+    let secret = node_secret(&headers)?;
+    let request = peer_join_service::authenticate_node(&state.db, id, secret).await?;
+    Ok(Json(request))
+}
+
+/// Report a heartbeat for an approved node
+#[utoipa::path(
+    post,
+    path = "/{id}/heartbeat",
+    context_path = "/api/v1/peers/join",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Join request ID")),
+    request_body = HeartbeatRequest,
+    responses(
+        (status = 200, description = "Heartbeat recorded"),
+        (status = 403, description = "Join request was rejected"),
+        (status = 404, description = "Unknown request or wrong secret"),
+        (status = 409, description = "Join request is awaiting approval"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn node_heartbeat(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<HeartbeatRequest>,
+) -> Result<()> {
+    let secret = node_secret(&headers)?;
+    let request = peer_join_service::authenticate_node(&state.db, id, secret).await?;
+    let peer_id = peer_join_service::approved_peer(&request)?;
+
+    let status = payload.status.as_ref().and_then(|s| parse_status(s));
+    PeerInstanceService::new(state.db.clone())
+        .heartbeat(peer_id, payload.cache_used_bytes, status)
+        .await?;
+    Ok(())
+}
+
+/// Forward an approved node's metrics and recent log lines
+#[utoipa::path(
+    post,
+    path = "/{id}/diagnostics",
+    context_path = "/api/v1/peers/join",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Join request ID")),
+    request_body = DiagnosticsBatch,
+    responses(
+        (status = 204, description = "Diagnostics stored"),
+        (status = 400, description = "Batch exceeds size limits"),
+        (status = 403, description = "Join request was rejected"),
+        (status = 404, description = "Unknown request or wrong secret"),
+        (status = 409, description = "Join request is awaiting approval"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn node_diagnostics(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(batch): Json<DiagnosticsBatch>,
+) -> Result<StatusCode> {
+    let secret = node_secret(&headers)?;
+    let request = peer_join_service::authenticate_node(&state.db, id, secret).await?;
+    let peer_id = peer_join_service::approved_peer(&request)?;
+    edge_diagnostics_service::record(&state.db, peer_id, &batch).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Report an approved node's cache integrity results; purged artifacts the
+/// node is subscribed to are re-queued for sync
+#[utoipa::path(
+    post,
+    path = "/{id}/integrity",
+    context_path = "/api/v1/peers/join",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Join request ID")),
+    request_body = IntegrityReport,
+    responses(
+        (status = 200, description = "Report stored", body = IntegrityReportResponse),
+        (status = 400, description = "Invalid report"),
+        (status = 403, description = "Join request was rejected"),
+        (status = 404, description = "Unknown request or wrong secret"),
+        (status = 409, description = "Join request is awaiting approval"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn node_integrity_report(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(report): Json<IntegrityReport>,
+) -> Result<Json<IntegrityReportResponse>> {
+    let secret = node_secret(&headers)?;
+    let request = peer_join_service::authenticate_node(&state.db, id, secret).await?;
+    let peer_id = peer_join_service::approved_peer(&request)?;
+    let refetch_queued =
+        cache_integrity_service::record_report(&state.db, peer_id, &request.name, &report).await?;
+    Ok(Json(IntegrityReportResponse { refetch_queued }))
+}
+
+// ---------------------------------------------------------------------------
+// Admin handlers
+// ---------------------------------------------------------------------------
+
+/// List join tokens
+#[utoipa::path(
+    get,
+    path = "/join-tokens",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    responses(
+        (status = 200, description = "Join tokens", body = Vec<JoinToken>),
+        (status = 403, description = "Admin access required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_join_tokens(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<Vec<JoinToken>>> {
+    auth.require_admin()?;
+    Ok(Json(peer_join_service::list_tokens(&state.db).await?))
+}
+
+/// Issue a join token for new edge nodes
+#[utoipa::path(
+    post,
+    path = "/join-tokens",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    request_body = CreateJoinToken,
+    responses(
+        (status = 201, description = "Join token created", body = CreateJoinTokenResponse),
+        (status = 400, description = "Invalid parameters"),
+        (status = 403, description = "Admin access required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_join_token(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<CreateJoinToken>,
+) -> Result<(StatusCode, Json<CreateJoinTokenResponse>)> {
+    auth.require_admin()?;
+    let (token, join_token) =
+        peer_join_service::create_token(&state.db, &payload, auth.user_id).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateJoinTokenResponse { token, join_token }),
+    ))
+}
+
+/// Revoke a join token
+#[utoipa::path(
+    delete,
+    path = "/join-tokens/{id}",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Join token ID")),
+    responses(
+        (status = 204, description = "Join token revoked"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Join token not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_join_token(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    auth.require_admin()?;
+    peer_join_service::revoke_token(&state.db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List edge node join requests
+#[utoipa::path(
+    get,
+    path = "/join-requests",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(ListJoinRequestsQuery),
+    responses(
+        (status = 200, description = "Join requests", body = Vec<JoinRequestRecord>),
+        (status = 403, description = "Admin access required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_join_requests(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ListJoinRequestsQuery>,
+) -> Result<Json<Vec<JoinRequestRecord>>> {
+    auth.require_admin()?;
+    let requests = peer_join_service::list_requests(&state.db, query.status.as_deref()).await?;
+    Ok(Json(requests))
+}
+
+/// Approve a pending join request and activate syncing for the node
+#[utoipa::path(
+    post,
+    path = "/join-requests/{id}/approve",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Join request ID")),
+    responses(
+        (status = 200, description = "Node registered", body = JoinRequestRecord),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Join request not found"),
+        (status = 409, description = "Already reviewed or peer name taken"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn approve_join_request(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<JoinRequestRecord>> {
+    auth.require_admin()?;
+    let request = peer_join_service::approve(&state.db, id, auth.user_id).await?;
+
+    if let Some(peer_id) = request.peer_instance_id {
+        let sync_svc = SyncPolicyService::new(state.db.clone());
+        if let Err(e) = sync_svc.evaluate_for_peer(peer_id).await {
+            tracing::warn!(
+                "Sync policy evaluation failed for approved peer {}: {}",
+                peer_id,
+                e
+            );
+        }
+    }
+    tracing::info!(
+        "Edge node '{}' approved by {} as peer {:?}",
+        request.name,
+        auth.username,
+        request.peer_instance_id
+    );
+    Ok(Json(request))
+}
+
+/// Reject a pending join request
+#[utoipa::path(
+    post,
+    path = "/join-requests/{id}/reject",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(("id" = Uuid, Path, description = "Join request ID")),
+    request_body = RejectJoinRequest,
+    responses(
+        (status = 200, description = "Join request rejected", body = JoinRequestRecord),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Join request not found"),
+        (status = 409, description = "Already reviewed"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn reject_join_request(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RejectJoinRequest>,
+) -> Result<Json<JoinRequestRecord>> {
+    auth.require_admin()?;
+    let request =
+        peer_join_service::reject(&state.db, id, auth.user_id, payload.reason.as_deref()).await?;
+    Ok(Json(request))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        join,
+        join_status,
+        node_heartbeat,
+        node_diagnostics,
+        node_integrity_report,
+        list_join_tokens,
+        create_join_token,
+        revoke_join_token,
+        list_join_requests,
+        approve_join_request,
+        reject_join_request,
+    ),
+    components(schemas(
+        DiagnosticsBatch,
+        LogExcerpt,
+        IntegrityReport,
+        IntegrityReportResponse,
+        CorruptEntry,
+        JoinRequest,
+        JoinResponse,
+        JoinRequestRecord,
+        JoinToken,
+        CreateJoinToken,
+        CreateJoinTokenResponse,
+        RejectJoinRequest,
+    ))
+)]
+pub struct PeerJoinApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_secret_requires_bearer() {
+        let mut headers = HeaderMap::new();
+        assert!(node_secret(&headers).is_err());
+
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            "Basic abc".parse().unwrap(),
+        );
+        assert!(node_secret(&headers).is_err());
+
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            "Bearer akns_123".parse().unwrap(),
+        );
+        assert_eq!(node_secret(&headers).unwrap(), "akns_123");
+    }
+}
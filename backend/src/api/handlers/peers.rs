@@ -18,6 +18,10 @@ use crate::services::peer_instance_service::{
     ReplicationMode, SyncStatus,
 };
 use crate::services::peer_service::{PeerAnnouncement, PeerService};
+use crate::services::repository_config_replication_service::{
+    ConfigDrift, ConfigDriftReport, ConfigReplicationStatus, ConfigSection, DriftKind,
+    RepositoryConfigReplicationService,
+};
 use crate::services::sync_policy_service::SyncPolicyService;
 
 /// Create peer instance routes
@@ -42,6 +46,16 @@ pub fn router() -> Router<SharedState> {
             "/:id/repositories/:repo_id/sync",
             post(run_subscription_now),
         )
+        .route(
+            "/:id/repositories/:repo_id/config-replication",
+            get(get_config_replication)
+                .put(enable_config_replication)
+                .delete(disable_config_replication),
+        )
+        .route(
+            "/:id/repositories/:repo_id/config-drift",
+            get(get_config_drift),
+        )
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
@@ -60,6 +74,10 @@ pub struct RegisterPeerRequest {
     pub cache_size_bytes: Option<i64>,
     #[schema(value_type = Object)]
     pub sync_filter: Option<serde_json::Value>,
+    /// Key this instance presents to the peer. Leave empty when the peer
+    /// trusts this instance through federation; a signed token is sent
+    /// instead.
+    #[serde(default)]
     pub api_key: String,
 }
 
@@ -217,7 +235,7 @@ fn sync_status_label(status: SyncStatus) -> &'static str {
     }
 }
 
-fn parse_status(s: &str) -> Option<InstanceStatus> {
+pub(crate) fn parse_status(s: &str) -> Option<InstanceStatus> {
     match s.to_lowercase().as_str() {
         "online" => Some(InstanceStatus::Online),
         "offline" => Some(InstanceStatus::Offline),
@@ -719,6 +737,132 @@ pub async fn unassign_repo(
     Ok(())
 }
 
+/// Get the config replication state of a (peer, repo) subscription.
+#[utoipa::path(
+    get,
+    path = "/{id}/repositories/{repo_id}/config-replication",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(
+        ("id" = Uuid, Path, description = "Peer instance ID"),
+        ("repo_id" = Uuid, Path, description = "Repository ID"),
+    ),
+    responses(
+        (status = 200, description = "Config replication state", body = ConfigReplicationStatus),
+        (status = 404, description = "Config replication not enabled for the subscription"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_config_replication(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path((id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ConfigReplicationStatus>> {
+    auth.require_admin()?;
+    let service = RepositoryConfigReplicationService::new(state.db.clone());
+    Ok(Json(service.status(id, repo_id).await?))
+}
+
+/// Replicate the repository's labels, lifecycle policies and scan policies
+/// to the peer.
+///
+/// The subscription must exist. The current configuration is pushed on the
+/// next sync worker tick and again after every change. Calling this on an
+/// enrolled subscription forces a fresh push.
+#[utoipa::path(
+    put,
+    path = "/{id}/repositories/{repo_id}/config-replication",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(
+        ("id" = Uuid, Path, description = "Peer instance ID"),
+        ("repo_id" = Uuid, Path, description = "Repository ID"),
+    ),
+    responses(
+        (status = 200, description = "Config replication enabled", body = ConfigReplicationStatus),
+        (status = 404, description = "Subscription not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn enable_config_replication(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path((id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ConfigReplicationStatus>> {
+    auth.require_admin()?;
+    let service = RepositoryConfigReplicationService::new(state.db.clone());
+    Ok(Json(service.enable(id, repo_id).await?))
+}
+
+/// Stop replicating the repository's configuration to the peer. The peer
+/// keeps the configuration it last received.
+#[utoipa::path(
+    delete,
+    path = "/{id}/repositories/{repo_id}/config-replication",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(
+        ("id" = Uuid, Path, description = "Peer instance ID"),
+        ("repo_id" = Uuid, Path, description = "Repository ID"),
+    ),
+    responses(
+        (status = 204, description = "Config replication disabled"),
+        (status = 404, description = "Config replication not enabled for the subscription"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn disable_config_replication(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path((id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<axum::http::StatusCode> {
+    auth.require_admin()?;
+    let service = RepositoryConfigReplicationService::new(state.db.clone());
+    service.disable(id, repo_id).await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+/// Compare the repository's labels and policies with the peer's copy.
+///
+/// Fetches the peer's current configuration and lists every label and
+/// policy that is missing on the peer, only present there, or set
+/// differently. Works whether or not config replication is enabled.
+#[utoipa::path(
+    get,
+    path = "/{id}/repositories/{repo_id}/config-drift",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(
+        ("id" = Uuid, Path, description = "Peer instance ID"),
+        ("repo_id" = Uuid, Path, description = "Repository ID"),
+    ),
+    responses(
+        (status = 200, description = "Drift report", body = ConfigDriftReport),
+        (status = 404, description = "Peer instance or repository not found"),
+        (status = 502, description = "Peer could not be queried"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_config_drift(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path((id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ConfigDriftReport>> {
+    auth.require_admin()?;
+    let client = crate::services::http_client::base_client_builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| {
+            crate::error::AppError::Internal(format!("Failed to build HTTP client: {e}"))
+        })?;
+    let service = RepositoryConfigReplicationService::new(state.db.clone());
+    Ok(Json(service.drift_report(&client, id, repo_id).await?))
+}
+
 /// POST /api/v1/peers/announce
 #[utoipa::path(
     post,
@@ -802,6 +946,10 @@ async fn get_identity(
         unassign_repo,
         get_subscription,
         run_subscription_now,
+        get_config_replication,
+        enable_config_replication,
+        disable_config_replication,
+        get_config_drift,
         announce_peer,
         get_identity,
     ),
@@ -816,6 +964,11 @@ async fn get_identity(
         SyncTaskResponse,
         AnnouncePeerRequest,
         IdentityResponse,
+        ConfigReplicationStatus,
+        ConfigDriftReport,
+        ConfigDrift,
+        ConfigSection,
+        DriftKind,
     ))
 )]
 pub struct PeersApiDoc;
@@ -15,6 +15,8 @@ use crate::api::dto::Pagination;
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
+use crate::services::permission_service::validate_grant_target;
+use crate::services::repository_label_service::LabelEntry;
 
 /// Require that the request is authenticated, returning an error if not.
 fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
@@ -53,6 +55,8 @@ pub struct PermissionRow {
     pub target_id: Uuid,
     pub target_name: Option<String>,
     pub actions: Vec<String>,
+    #[sqlx(json)]
+    pub label_selector: Vec<LabelEntry>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -67,6 +71,9 @@ pub struct PermissionResponse {
     pub target_id: Uuid,
     pub target_name: Option<String>,
     pub actions: Vec<String>,
+    /// Labels a repository must carry for a `label_selector` grant to apply.
+    /// Empty for every other target type.
+    pub label_selector: Vec<LabelEntry>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -82,6 +89,7 @@ impl From<PermissionRow> for PermissionResponse {
             target_id: row.target_id,
             target_name: row.target_name,
             actions: row.actions,
+            label_selector: row.label_selector,
             created_at: row.created_at,
             updated_at: row.updated_at,
         }
@@ -138,7 +146,7 @@ pub async fn list_permissions(
     let permissions: Vec<PermissionRow> = sqlx::query_as(
         r#"
         SELECT p.id, p.principal_type, p.principal_id, p.target_type, p.target_id,
-               p.actions, p.created_at, p.updated_at,
+               p.actions, p.label_selector, p.created_at, p.updated_at,
                CASE
                    WHEN p.principal_type = 'user' THEN u.username
                    WHEN p.principal_type = 'group' THEN g.name
@@ -208,8 +216,16 @@ pub struct CreatePermissionRequest {
     pub principal_type: String,
     pub principal_id: Uuid,
     pub target_type: String,
-    pub target_id: Uuid,
+    /// Required for fixed targets. May be omitted for `label_selector`
+    /// grants, in which case the server assigns an opaque id.
+    #[serde(default)]
+    pub target_id: Option<Uuid>,
     pub actions: Vec<String>,
+    /// Labels a repository must carry for the grant to apply (AND semantics;
+    /// an empty value matches any value). Only valid with
+    /// `target_type = "label_selector"`.
+    #[serde(default)]
+    pub label_selector: Vec<LabelEntry>,
 }
 
 #[derive(Debug, FromRow, ToSchema)]
@@ -220,6 +236,8 @@ pub struct CreatedPermissionRow {
     pub target_type: String,
     pub target_id: Uuid,
     pub actions: Vec<String>,
+    #[sqlx(json)]
+    pub label_selector: Vec<LabelEntry>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -281,19 +299,26 @@ pub async fn create_permission(
         .permission_service
         .validate_principal(&payload.principal_type, payload.principal_id)
         .await?;
+    validate_grant_target(
+        &payload.target_type,
+        payload.target_id,
+        &payload.label_selector,
+    )?;
+    let target_id = payload.target_id.unwrap_or_else(Uuid::new_v4);
 
     let permission: CreatedPermissionRow = sqlx::query_as(
         r#"
-        INSERT INTO permissions (principal_type, principal_id, target_type, target_id, actions)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING id, principal_type, principal_id, target_type, target_id, actions, created_at, updated_at
+        INSERT INTO permissions (principal_type, principal_id, target_type, target_id, actions, label_selector)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, principal_type, principal_id, target_type, target_id, actions, label_selector, created_at, updated_at
         "#
     )
     .bind(&payload.principal_type)
     .bind(payload.principal_id)
     .bind(&payload.target_type)
-    .bind(payload.target_id)
+    .bind(target_id)
     .bind(&payload.actions)
+    .bind(sqlx::types::Json(&payload.label_selector))
     .fetch_one(&state.db)
     .await
     .map_err(|e| {
@@ -319,6 +344,7 @@ pub async fn create_permission(
         target_id: permission.target_id,
         target_name: None,
         actions: permission.actions,
+        label_selector: permission.label_selector,
         created_at: permission.created_at,
         updated_at: permission.updated_at,
     }))
@@ -359,7 +385,7 @@ pub async fn get_permission(
     let permission: PermissionRow = sqlx::query_as(
         r#"
         SELECT p.id, p.principal_type, p.principal_id, p.target_type, p.target_id,
-               p.actions, p.created_at, p.updated_at,
+               p.actions, p.label_selector, p.created_at, p.updated_at,
                CASE
                    WHEN p.principal_type = 'user' THEN u.username
                    WHEN p.principal_type = 'group' THEN g.name
@@ -424,14 +450,25 @@ pub async fn update_permission(
         .permission_service
         .validate_principal(&payload.principal_type, payload.principal_id)
         .await?;
-
+    validate_grant_target(
+        &payload.target_type,
+        payload.target_id,
+        &payload.label_selector,
+    )?;
+
+    // A label-selector grant updated without a target_id keeps the opaque id
+    // it was created with rather than being re-keyed.
     let permission: CreatedPermissionRow = sqlx::query_as(
         r#"
         UPDATE permissions
-        SET principal_type = $2, principal_id = $3, target_type = $4, target_id = $5,
-            actions = $6, updated_at = NOW()
+        SET principal_type = $2, principal_id = $3, target_type = $4,
+            target_id = COALESCE(
+                $5,
+                CASE WHEN target_type = 'label_selector' THEN target_id ELSE gen_random_uuid() END
+            ),
+            actions = $6, label_selector = $7, updated_at = NOW()
         WHERE id = $1
-        RETURNING id, principal_type, principal_id, target_type, target_id, actions, created_at, updated_at
+        RETURNING id, principal_type, principal_id, target_type, target_id, actions, label_selector, created_at, updated_at
         "#
     )
     .bind(id)
@@ -440,6 +477,7 @@ pub async fn update_permission(
     .bind(&payload.target_type)
     .bind(payload.target_id)
     .bind(&payload.actions)
+    .bind(sqlx::types::Json(&payload.label_selector))
     .fetch_optional(&state.db)
     .await
     .map_err(|e| AppError::Database(e.to_string()))?
@@ -459,6 +497,7 @@ pub async fn update_permission(
         target_id: permission.target_id,
         target_name: None,
         actions: permission.actions,
+        label_selector: permission.label_selector,
         created_at: permission.created_at,
         updated_at: permission.updated_at,
     }))
@@ -523,6 +562,7 @@ pub async fn delete_permission(
         PermissionListResponse,
         CreatePermissionRequest,
         CreatedPermissionRow,
+        LabelEntry,
     ))
 )]
 pub struct PermissionsApiDoc;
@@ -641,6 +681,7 @@ mod tests {
             target_id: tid,
             target_name: Some("my-repo".to_string()),
             actions: vec!["read".to_string(), "write".to_string()],
+            label_selector: vec![],
             created_at: now,
             updated_at: now,
         };
@@ -667,6 +708,7 @@ mod tests {
             target_id: Uuid::new_v4(),
             target_name: None,
             actions: vec![],
+            label_selector: vec![],
             created_at: now,
             updated_at: now,
         };
@@ -692,6 +734,7 @@ mod tests {
             target_id: Uuid::new_v4(),
             target_name: Some("repo1".to_string()),
             actions: vec!["read".to_string(), "deploy".to_string()],
+            label_selector: vec![],
             created_at: now,
             updated_at: now,
         };
@@ -718,6 +761,7 @@ mod tests {
             target_id: Uuid::new_v4(),
             target_name: None,
             actions: vec!["admin".to_string()],
+            label_selector: vec![],
             created_at: now,
             updated_at: now,
         };
@@ -742,8 +786,28 @@ mod tests {
         assert_eq!(req.principal_type, "user");
         assert_eq!(req.principal_id, pid);
         assert_eq!(req.target_type, "repository");
-        assert_eq!(req.target_id, tid);
+        assert_eq!(req.target_id, Some(tid));
         assert_eq!(req.actions, vec!["read", "write"]);
+        assert!(req.label_selector.is_empty());
+    }
+
+    #[test]
+    fn test_create_permission_request_label_selector() {
+        let pid = Uuid::new_v4();
+        let json = format!(
+            r#"{{"principal_type": "group", "principal_id": "{}", "target_type": "label_selector", "label_selector": [{{"key": "team", "value": "payments"}}], "actions": ["read"]}}"#,
+            pid
+        );
+        let req: CreatePermissionRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(req.target_type, "label_selector");
+        assert!(req.target_id.is_none());
+        assert_eq!(
+            req.label_selector,
+            vec![LabelEntry {
+                key: "team".to_string(),
+                value: "payments".to_string(),
+            }]
+        );
     }
 
     #[test]
@@ -782,6 +846,7 @@ mod tests {
                 target_id: Uuid::new_v4(),
                 target_name: Some("r1".to_string()),
                 actions: vec!["read".to_string()],
+                label_selector: vec![],
                 created_at: now,
                 updated_at: now,
             }],
@@ -829,6 +894,7 @@ mod tests {
             target_id: Uuid::new_v4(),
             target_name: Some("repo-a".to_string()),
             actions: vec!["read".to_string(), "write".to_string(), "admin".to_string()],
+            label_selector: vec![],
             created_at: now,
             updated_at: now,
         };
@@ -1,11 +1,11 @@
 //! Profile handlers — endpoints scoped to the authenticated user.
 
 use axum::{
-    extract::{Extension, Path, State},
-    routing::{delete, get},
+    extract::{Extension, Path, Query, State},
+    routing::{delete, get, post},
     Json, Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -14,6 +14,12 @@ use crate::api::SharedState;
 use crate::error::Result;
 use crate::services::audit_service::{api_token_audit_entry, audit_fire_and_forget, AuditAction};
 use crate::services::auth_service::AuthService;
+use crate::services::notification_email_service::{
+    NotificationEmailService, NotificationPreferences, UpdateNotificationPreferences,
+};
+use crate::services::saved_search_service::{
+    self, SavedSearch, SavedSearchAlert, SavedSearchCriteria,
+};
 
 use super::users::{ApiTokenCreatedResponse, ApiTokenListResponse, ApiTokenResponse};
 
@@ -25,6 +31,25 @@ pub fn router() -> Router<SharedState> {
             get(list_access_tokens).post(create_access_token),
         )
         .route("/access-tokens/:token_id", delete(revoke_access_token))
+        .route(
+            "/notification-preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route(
+            "/saved-searches",
+            get(list_saved_searches).post(create_saved_search),
+        )
+        .route("/saved-searches/alerts", get(list_saved_search_alerts))
+        .route(
+            "/saved-searches/alerts/read",
+            post(mark_saved_search_alerts_read),
+        )
+        .route(
+            "/saved-searches/:search_id",
+            get(get_saved_search)
+                .patch(update_saved_search)
+                .delete(delete_saved_search),
+        )
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,6 +161,174 @@ async fn revoke_access_token(
     Ok(())
 }
 
+/// Get the authenticated user's email notification preferences.
+async fn get_notification_preferences(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<NotificationPreferences>> {
+    let service = NotificationEmailService::new(state.db.clone(), state.smtp_service.clone());
+    Ok(Json(service.get_preferences(auth.user_id).await?))
+}
+
+/// Update the authenticated user's email notification preferences.
+/// Fields omitted from the body keep their current value.
+async fn update_notification_preferences(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<UpdateNotificationPreferences>,
+) -> Result<Json<NotificationPreferences>> {
+    let service = NotificationEmailService::new(state.db.clone(), state.smtp_service.clone());
+    Ok(Json(
+        service.update_preferences(auth.user_id, &payload).await?,
+    ))
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavedSearchListResponse {
+    pub items: Vec<SavedSearch>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSavedSearchRequest {
+    pub name: String,
+    pub criteria: SavedSearchCriteria,
+    #[serde(default = "default_notify")]
+    pub notify: bool,
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSavedSearchRequest {
+    pub name: Option<String>,
+    pub criteria: Option<SavedSearchCriteria>,
+    pub notify: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavedSearchAlertsQuery {
+    pub saved_search_id: Option<Uuid>,
+    #[serde(default)]
+    pub unread: bool,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavedSearchAlertListResponse {
+    pub items: Vec<SavedSearchAlert>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MarkAlertsReadRequest {
+    /// Alerts to mark read. Omit to mark all of the user's alerts read.
+    pub ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarkAlertsReadResponse {
+    pub updated: u64,
+}
+
+/// List the authenticated user's saved searches.
+async fn list_saved_searches(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<SavedSearchListResponse>> {
+    let items = saved_search_service::list_saved_searches(&state.db, auth.user_id).await?;
+    Ok(Json(SavedSearchListResponse { items }))
+}
+
+/// Save a search. With `notify` (the default), new matching artifacts or
+/// findings raise alerts as they appear.
+async fn create_saved_search(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<CreateSavedSearchRequest>,
+) -> Result<Json<SavedSearch>> {
+    Ok(Json(
+        saved_search_service::create_saved_search(
+            &state.db,
+            auth.user_id,
+            &payload.name,
+            &payload.criteria,
+            payload.notify,
+        )
+        .await?,
+    ))
+}
+
+/// Get one of the authenticated user's saved searches.
+async fn get_saved_search(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(search_id): Path<Uuid>,
+) -> Result<Json<SavedSearch>> {
+    Ok(Json(
+        saved_search_service::get_saved_search(&state.db, auth.user_id, search_id).await?,
+    ))
+}
+
+/// Update a saved search. Fields omitted from the body keep their value.
+async fn update_saved_search(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(search_id): Path<Uuid>,
+    Json(payload): Json<UpdateSavedSearchRequest>,
+) -> Result<Json<SavedSearch>> {
+    Ok(Json(
+        saved_search_service::update_saved_search(
+            &state.db,
+            auth.user_id,
+            search_id,
+            payload.name.as_deref(),
+            payload.criteria.as_ref(),
+            payload.notify,
+        )
+        .await?,
+    ))
+}
+
+/// Delete a saved search and its alerts.
+async fn delete_saved_search(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(search_id): Path<Uuid>,
+) -> Result<()> {
+    saved_search_service::delete_saved_search(&state.db, auth.user_id, search_id).await
+}
+
+/// List alerts raised by the authenticated user's saved searches.
+async fn list_saved_search_alerts(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<SavedSearchAlertsQuery>,
+) -> Result<Json<SavedSearchAlertListResponse>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let items = saved_search_service::list_alerts(
+        &state.db,
+        auth.user_id,
+        query.saved_search_id,
+        query.unread,
+        limit,
+    )
+    .await?;
+    Ok(Json(SavedSearchAlertListResponse { items }))
+}
+
+/// Mark saved-search alerts read.
+async fn mark_saved_search_alerts_read(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<MarkAlertsReadRequest>,
+) -> Result<Json<MarkAlertsReadResponse>> {
+    let updated =
+        saved_search_service::mark_alerts_read(&state.db, auth.user_id, payload.ids.as_deref())
+            .await?;
+    Ok(Json(MarkAlertsReadResponse { updated }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,6 +400,45 @@ mod tests {
         assert_eq!(req.expires_in_days, Some(365));
     }
 
+    // ── Notification preference payload tests ───────────────────────
+
+    #[test]
+    fn test_update_notification_preferences_partial_body() {
+        let json = r#"{"weekly_digest": true}"#;
+        let req: UpdateNotificationPreferences = serde_json::from_str(json).unwrap();
+        assert_eq!(req.weekly_digest, Some(true));
+        assert!(req.health_alerts.is_none());
+        assert!(req.approval_requests.is_none());
+        assert!(req.quota_warnings.is_none());
+    }
+
+    #[test]
+    fn test_create_saved_search_request_defaults_notify() {
+        let json = r#"{"name": "payments criticals", "criteria": {"target": "finding", "min_severity": "critical", "repository_labels": [{"key": "team", "value": "payments"}]}}"#;
+        let req: CreateSavedSearchRequest = serde_json::from_str(json).unwrap();
+        assert!(req.notify);
+        assert_eq!(
+            req.criteria.target,
+            crate::services::saved_search_service::SearchTarget::Finding
+        );
+        assert_eq!(req.criteria.repository_labels.len(), 1);
+    }
+
+    #[test]
+    fn test_mark_alerts_read_request_all() {
+        let req: MarkAlertsReadRequest = serde_json::from_str("{}").unwrap();
+        assert!(req.ids.is_none());
+    }
+
+    #[test]
+    fn test_notification_preferences_serialization() {
+        let v = serde_json::to_value(NotificationPreferences::default()).unwrap();
+        assert_eq!(v["health_alerts"], false);
+        assert_eq!(v["approval_requests"], true);
+        assert_eq!(v["quota_warnings"], true);
+        assert_eq!(v["weekly_digest"], false);
+    }
+
     // ── Default scopes logic tests ──────────────────────────────────
 
     #[test]
@@ -16,11 +16,19 @@ use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
 use crate::models::quality::{QualityGateEvaluation, QualityGateViolation};
-use crate::models::repository::RepositoryType;
+use crate::models::repository::{Repository, RepositoryType};
 use crate::models::sbom::PolicyAction;
+use crate::services::audit_export::Outcome;
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::federation_service;
+use crate::services::freeze_window_service;
+use crate::services::policy_decision_service;
 use crate::services::promotion_policy_service::PromotionPolicyService;
+use crate::services::promotion_signing_service::PromotionSigningService;
 use crate::services::quality_check_service::QualityCheckService;
+use crate::services::remote_promotion_service;
 use crate::services::repository_service::RepositoryService;
+use crate::services::signing_service::SigningService;
 
 pub fn router() -> Router<SharedState> {
     Router::new()
@@ -48,9 +56,15 @@ pub struct PromoteArtifactRequest {
     /// Target release repository key. When omitted, the staging repository's
     /// linked release target (from `repository_config`) is used instead.
     pub target_repository: Option<String>,
+    /// Trusted federated peer to promote into. When set, `target_repository`
+    /// names a repository on that instance and is required.
+    pub target_instance: Option<Uuid>,
     #[serde(default)]
     pub skip_policy_check: bool,
     pub notes: Option<String>,
+    /// Reason for promoting through an active freeze window on the target.
+    /// Requires admin or the `freeze_override` permission.
+    pub freeze_override_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -62,6 +76,9 @@ pub struct BulkPromoteRequest {
     #[serde(default)]
     pub skip_policy_check: bool,
     pub notes: Option<String>,
+    /// Reason for promoting through an active freeze window on the target.
+    /// Requires admin or the `freeze_override` permission.
+    pub freeze_override_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -207,16 +224,38 @@ pub fn validate_promotion_target_and_format(
     Ok(())
 }
 
+/// Copy an artifact body from `source` storage to `target` storage, natively
+/// when the target can reach the source (same bucket or container, or
+/// filesystem roots on one volume) and otherwise by streaming.
+///
+/// Promotion resolves the source and target backends independently, so a
+/// single-backend [`StorageBackend::copy`] does not apply; the target's
+/// [`StorageBackend::copy_from`] decides whether a server-side copy, hard link
+/// or reflink is possible. When it is not, the bytes go through
+/// [`stream_copy_artifact`].
+pub async fn copy_artifact_content(
+    source: &dyn crate::storage::StorageBackend,
+    target: &dyn crate::storage::StorageBackend,
+    storage_key: &str,
+) -> Result<()> {
+    if target.copy_from(source, storage_key, storage_key).await? {
+        tracing::debug!(storage_key, "Promotion copied artifact without streaming");
+        return Ok(());
+    }
+    stream_copy_artifact(source, target, storage_key)
+        .await
+        .map(|_| ())
+}
+
 /// Copy an artifact body from `source` storage to `target` storage by streaming
 /// rather than buffering the whole object in memory (#1608, Core Invariant ①).
 ///
-/// Promotion resolves the source and target backends independently, so the two
-/// `StorageBackend` handles can be different backends (e.g. a filesystem staging
-/// repo promoted to an S3 release repo). [`StorageBackend::copy`] only operates
-/// within a single backend, so it cannot be used here; instead we tee the
-/// `get_stream` from the source directly into `put_stream` on the target. Peak
-/// memory therefore stays O(chunk) regardless of artifact size, which is what
-/// prevents an OOM on multi-GB cross-backend promotions.
+/// This is the fallback of [`copy_artifact_content`] for backends with no
+/// native route between them (e.g. a filesystem staging repo promoted to an
+/// S3 release repo). The `get_stream` from the source is teed directly into
+/// `put_stream` on the target. Peak memory therefore stays O(chunk) regardless
+/// of artifact size, which is what prevents an OOM on multi-GB cross-backend
+/// promotions.
 ///
 /// A missing source key surfaces as [`AppError::NotFound`] from `get_stream`,
 /// matching the storage NotFound contract (#1016). Returns the
@@ -417,6 +456,66 @@ async fn enforce_release_target_link(
     Ok(())
 }
 
+/// Sign a freshly promoted artifact when the target repository signs on
+/// promotion (see [`crate::services::promotion_signing_service`]).
+///
+/// Runs after the promotion has committed, so it never fails the request: a
+/// signing error is logged and audited as a failed `ARTIFACT_SIGNED` and the
+/// artifact stays promoted, unsigned, for an operator to re-sign.
+pub(crate) async fn sign_on_promotion(
+    state: &SharedState,
+    target_repo: &Repository,
+    artifact_id: Uuid,
+    auth: &AuthExtension,
+) {
+    let storage = match state.storage_for_repo(&target_repo.storage_location()) {
+        Ok(storage) => storage,
+        Err(e) => {
+            tracing::warn!(repo = %target_repo.key, "Sign-on-promotion skipped: {}", e);
+            return;
+        }
+    };
+    let signing = SigningService::new(state.db.clone(), &state.config.jwt_secret);
+    let result = PromotionSigningService::new(state.db.clone(), signing)
+        .sign_promoted(
+            &storage,
+            target_repo.id,
+            &target_repo.key,
+            artifact_id,
+            Some(auth.user_id),
+        )
+        .await;
+
+    let entry = AuditEntry::new(AuditAction::ArtifactSigned, ResourceType::Artifact)
+        .user(auth.user_id)
+        .resource(artifact_id)
+        .actor_name(auth.username.clone());
+    let entry = match result {
+        Ok(None) => return,
+        Ok(Some(signature)) => entry.details(serde_json::json!({
+            "trigger": "promotion",
+            "repository": target_repo.key,
+            "format": signature.format,
+            "signing_key_id": signature.signing_key_id,
+            "signature_sha256": signature.signature_sha256,
+        })),
+        Err(e) => {
+            tracing::warn!(
+                repo = %target_repo.key,
+                artifact_id = %artifact_id,
+                "Sign-on-promotion failed: {}",
+                e
+            );
+            entry.outcome(Outcome::Failure).details(serde_json::json!({
+                "trigger": "promotion",
+                "repository": target_repo.key,
+                "error": e.to_string(),
+            }))
+        }
+    };
+    let _ = AuditService::new(state.db.clone()).log(entry).await;
+}
+
 /// Authorize a direct promotion request.
 ///
 /// Promoting an artifact into a release repository is an admin-only action,
@@ -533,6 +632,221 @@ fn rule_violations_to_policy_violations(
         .collect()
 }
 
+/// The decision behind a promotion that went ahead, for the policy decision
+/// record. `violations` are the non-blocking ones reported with it.
+fn promotion_decision(
+    skip_policy_check: bool,
+    violations: &[PolicyViolation],
+) -> policy_decision_service::SnapshotDecision {
+    policy_decision_service::SnapshotDecision {
+        allowed: true,
+        policy_check_skipped: skip_policy_check,
+        violations: violations
+            .iter()
+            .map(|v| format!("{} ({}): {}", v.rule, v.severity, v.message))
+            .collect(),
+    }
+}
+
+/// Promote into a repository on a trusted federated instance.
+///
+/// Runs the source-side gates of a local promotion (tenant access, quality
+/// gate, promotion policy), then pushes the artifact with its metadata, scan
+/// results and signatures to the peer, which re-checks write access, format
+/// and path collisions before registering it. Per-pair promotion rules and
+/// approval rows are keyed by a local target repository and cannot apply, so
+/// a source that requires approval refuses remote promotion rather than
+/// silently skipping its approval.
+async fn promote_to_remote_instance(
+    state: &SharedState,
+    auth: &AuthExtension,
+    repo_key: &str,
+    artifact_id: Uuid,
+    peer_id: Uuid,
+    req: &PromoteArtifactRequest,
+) -> Result<Json<PromotionResponse>> {
+    let target_key = req.target_repository.as_deref().ok_or_else(|| {
+        AppError::Validation(
+            "target_repository is required when promoting to a remote instance".to_string(),
+        )
+    })?;
+    let peer = federation_service::get_peer(&state.db, peer_id).await?;
+    if peer.status != "trusted" {
+        return Err(AppError::Validation(format!(
+            "Federated peer '{}' is not trusted",
+            peer.name
+        )));
+    }
+
+    let repo_service = RepositoryService::new(state.db.clone());
+    let source_repo = repo_service.get_by_key(repo_key).await?;
+    let has_grant = repo_service
+        .user_can_access_repo(source_repo.id, auth.user_id)
+        .await?;
+    if !promotion_tenant_access_allowed(source_repo.is_public, has_grant) {
+        return Err(AppError::Authorization(format!(
+            "You are not authorized to promote from the '{}' repository's tenant",
+            source_repo.key
+        )));
+    }
+
+    let artifact: crate::models::artifact::Artifact = sqlx::query_as(
+        "SELECT id, repository_id, path, name, version, size_bytes, \
+                checksum_sha256, checksum_md5, checksum_sha1, \
+                content_type, storage_key, is_deleted, uploaded_by, \
+                quarantine_status, quarantine_until, created_at, updated_at \
+         FROM artifacts \
+         WHERE id = $1 AND repository_id = $2 AND is_deleted = false",
+    )
+    .bind(artifact_id)
+    .bind(source_repo.id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Artifact not found in source repository".to_string()))?;
+
+    let source = format!("{}/{}", repo_key, artifact.path);
+    let target = format!("{}:{}/{}", peer.name, target_key, artifact.path);
+
+    let gate_outcome = evaluate_gate_once(
+        state.quality_check_service.as_deref(),
+        artifact_id,
+        source_repo.id,
+        req.skip_policy_check,
+    )
+    .await;
+    if let GateOutcome::Block(ref eval) = gate_outcome {
+        return Err(gate_block_error(eval));
+    }
+    validate_promotion_source_is_staging(&source_repo)?;
+
+    if super::approval::check_approval_required(&state.db, source_repo.id).await? {
+        return Err(AppError::Validation(format!(
+            "Repository '{}' requires approval for promotion; promote to a local repository \
+             through the approval workflow instead",
+            repo_key
+        )));
+    }
+
+    let mut policy_violations: Vec<PolicyViolation> = vec![];
+    let mut policy_result_json = serde_json::json!({"passed": true, "violations": []});
+    if !req.skip_policy_check {
+        let eval_result = PromotionPolicyService::new(state.db.clone())
+            .evaluate_artifact(artifact_id, source_repo.id)
+            .await?;
+        policy_violations = eval_result
+            .violations
+            .iter()
+            .map(|v| PolicyViolation {
+                rule: v.rule.clone(),
+                severity: v.severity.clone(),
+                message: v.message.clone(),
+            })
+            .collect();
+        policy_result_json = serde_json::json!({
+            "passed": eval_result.passed,
+            "action": format!("{:?}", eval_result.action).to_lowercase(),
+            "violations": eval_result.violations,
+            "cve_summary": eval_result.cve_summary,
+            "license_summary": eval_result.license_summary,
+        });
+        if !eval_result.passed && eval_result.action == PolicyAction::Block {
+            return Ok(Json(PromotionResponse {
+                promoted: false,
+                source,
+                target,
+                promotion_id: None,
+                policy_violations,
+                message: Some("Promotion blocked by policy violations".to_string()),
+            }));
+        }
+    }
+    if let GateOutcome::Warn(violations) = gate_outcome {
+        for v in violations {
+            policy_violations.push(PolicyViolation {
+                rule: v.rule,
+                severity: "medium".to_string(),
+                message: v.message,
+            });
+        }
+    }
+
+    let identity = federation_service::local_identity(
+        &state.db,
+        &state.config.jwt_secret,
+        &state.config.peer_instance_name,
+        &state.config.peer_public_endpoint,
+    )
+    .await?;
+    let promotion_id = Uuid::new_v4();
+    let source_format = format!("{:?}", source_repo.format).to_lowercase();
+    let manifest = remote_promotion_service::build_manifest(
+        &state.db,
+        &identity,
+        &artifact,
+        remote_promotion_service::ManifestTarget {
+            promotion_id,
+            source_repository: repo_key,
+            format: &source_format,
+            target_repository: target_key,
+            promoted_by: &auth.username,
+            notes: req.notes.as_deref(),
+        },
+    )
+    .await?;
+
+    let source_storage = state.storage_for_repo(&source_repo.storage_location())?;
+    let pushed = match source_storage.get_stream(&artifact.storage_key).await {
+        Ok(content) => remote_promotion_service::push(&identity, &peer, &manifest, content).await,
+        Err(e) => Err(e),
+    };
+
+    let error = pushed.as_ref().err().map(|e| e.to_string());
+    remote_promotion_service::record_remote_promotion(
+        &state.db,
+        remote_promotion_service::RemotePromotionRecord {
+            id: promotion_id,
+            artifact_id,
+            source_repo_id: source_repo.id,
+            peer: &peer,
+            target_repository: target_key,
+            remote_artifact_id: pushed.as_ref().ok().copied(),
+            error: error.as_deref(),
+            policy_result: &policy_result_json,
+            notes: req.notes.as_deref(),
+            promoted_by: auth.user_id,
+        },
+    )
+    .await?;
+    pushed?;
+    policy_decision_service::record_promotion(
+        &state.db,
+        artifact_id,
+        promotion_id,
+        auth.user_id,
+        promotion_decision(req.skip_policy_check, &policy_violations),
+    )
+    .await;
+
+    tracing::info!(
+        source_repo = %repo_key,
+        target_instance = %peer.name,
+        target_repo = %target_key,
+        artifact = %artifact.path,
+        promoted_by = %auth.user_id,
+        "Artifact promoted to remote instance"
+    );
+
+    Ok(Json(PromotionResponse {
+        promoted: true,
+        source,
+        target,
+        promotion_id: Some(promotion_id),
+        policy_violations,
+        message: Some("Artifact promoted to remote instance".to_string()),
+    }))
+}
+
 #[utoipa::path(
     post,
     path = "/repositories/{key}/artifacts/{artifact_id}/promote",
@@ -564,6 +878,11 @@ pub async fn promote_artifact(
     let has_promote_scope = auth.is_api_token && auth.has_scope("promote:artifacts");
     ensure_promotion_authorized(auth.is_admin, has_promote_scope)?;
 
+    if let Some(peer_id) = req.target_instance {
+        return promote_to_remote_instance(&state, &auth, &repo_key, artifact_id, peer_id, &req)
+            .await;
+    }
+
     let repo_service = RepositoryService::new(state.db.clone());
 
     let source_repo = repo_service.get_by_key(&repo_key).await?;
@@ -636,6 +955,16 @@ pub async fn promote_artifact(
     // a violating artifact cannot be masked by a 400 staging-source error.
     validate_promotion_repos(&source_repo, &target_repo)?;
 
+    // Release freeze windows on the target.
+    freeze_window_service::check_promotion(
+        &state.db,
+        target_repo.id,
+        auth.user_id,
+        auth.is_admin,
+        req.freeze_override_reason.as_deref(),
+    )
+    .await?;
+
     // Ordering note (#1382 review): quality-gate block precedes
     // approval-required. A gate-violating artifact in an approval-required
     // repository returns 409 (gate block) rather than the approval-required
@@ -771,10 +1100,10 @@ pub async fn promote_artifact(
     let source_storage = state.storage_for_repo(&source_repo.storage_location())?;
     let target_storage = state.storage_for_repo(&target_repo.storage_location())?;
 
-    // Stream the artifact body across (possibly distinct) storage backends
+    // Copy natively where the backends allow it, otherwise stream the body
     // instead of buffering it in memory (#1608, Core Invariant ①). Shares the
-    // same tee helper as the bulk path.
-    stream_copy_artifact(&*source_storage, &*target_storage, &artifact.storage_key)
+    // same helper as the bulk path.
+    copy_artifact_content(&*source_storage, &*target_storage, &artifact.storage_key)
         .await
         .map_err(|e| AppError::Internal(format!("Failed to copy artifact: {}", e)))?;
 
@@ -843,6 +1172,15 @@ pub async fn promote_artifact(
         promoted_by = %auth.user_id,
         "Artifact promoted successfully"
     );
+    policy_decision_service::record_promotion(
+        &state.db,
+        artifact_id,
+        promotion_id,
+        auth.user_id,
+        promotion_decision(req.skip_policy_check, &policy_violations),
+    )
+    .await;
+    sign_on_promotion(&state, &target_repo, new_artifact_id, &auth).await;
 
     Ok(Json(PromotionResponse {
         promoted: true,
@@ -904,6 +1242,16 @@ pub async fn promote_artifacts_bulk(
     require_promotion_tenant_access(&repo_service, auth.user_id, &source_repo, &target_repo)
         .await?;
 
+    // Release freeze windows on the target, checked once for the batch.
+    freeze_window_service::check_promotion(
+        &state.db,
+        target_repo.id,
+        auth.user_id,
+        auth.is_admin,
+        req.freeze_override_reason.as_deref(),
+    )
+    .await?;
+
     // Approval gate (promotion-approval-gate-bypass). When the source requires
     // approval, each artifact must independently consume its own APPROVED +
     // unconsumed approval row for this (artifact, source, target) pair before its
@@ -1044,12 +1392,11 @@ pub async fn promote_artifacts_bulk(
         let source_storage = state.storage_for_repo(&source_repo.storage_location())?;
         let target_storage = state.storage_for_repo(&target_repo.storage_location())?;
 
-        // Stream the artifact body from source to target instead of buffering
-        // the whole object in memory (#1608, Core Invariant ①). See
-        // `stream_copy_artifact` for why `StorageBackend::copy` cannot be used
-        // when source and target are distinct backends.
+        // Copy natively where the backends allow it, otherwise stream the body
+        // instead of buffering the whole object in memory (#1608, Core
+        // Invariant ①). See `copy_artifact_content`.
         if let Err(e) =
-            stream_copy_artifact(&*source_storage, &*target_storage, &artifact.storage_key).await
+            copy_artifact_content(&*source_storage, &*target_storage, &artifact.storage_key).await
         {
             failed += 1;
             results.push(failed_response(
@@ -1120,6 +1467,15 @@ pub async fn promote_artifacts_bulk(
         .execute(&state.db)
         .await;
 
+        policy_decision_service::record_promotion(
+            &state.db,
+            *artifact_id,
+            promotion_id,
+            auth.user_id,
+            promotion_decision(req.skip_policy_check, &[]),
+        )
+        .await;
+        sign_on_promotion(&state, &target_repo, new_artifact_id, &auth).await;
         promoted += 1;
         results.push(PromotionResponse {
             promoted: true,
@@ -3106,6 +3462,83 @@ mod tests {
         assert_eq!(result.checksum_sha256, expected_digest);
     }
 
+    /// A target that reaches the source natively: `copy_from` succeeds and
+    /// any attempt to stream into it fails the test.
+    #[derive(Default)]
+    struct NativeCopyTarget {
+        native_copies: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl StorageBackend for NativeCopyTarget {
+        async fn put(&self, _key: &str, _content: Bytes) -> crate::error::Result<()> {
+            panic!("native copy must not write through put");
+        }
+        async fn get(&self, _key: &str) -> crate::error::Result<Bytes> {
+            Ok(Bytes::new())
+        }
+        async fn exists(&self, _key: &str) -> crate::error::Result<bool> {
+            Ok(true)
+        }
+        async fn delete(&self, _key: &str) -> crate::error::Result<()> {
+            Ok(())
+        }
+        async fn copy_from(
+            &self,
+            _source: &dyn StorageBackend,
+            source_key: &str,
+            dest_key: &str,
+        ) -> crate::error::Result<bool> {
+            assert_eq!(source_key, dest_key);
+            self.native_copies
+                .lock()
+                .unwrap()
+                .push(dest_key.to_string());
+            Ok(true)
+        }
+        async fn put_stream(
+            &self,
+            _key: &str,
+            _stream: BoxStream<'static, crate::error::Result<Bytes>>,
+        ) -> crate::error::Result<PutStreamResult> {
+            panic!("native copy must not stream");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_copy_artifact_content_prefers_native_copy() {
+        let source = ChunkedSource {
+            payload: Bytes::from_static(b"never read"),
+            missing: false,
+        };
+        let target = NativeCopyTarget::default();
+
+        copy_artifact_content(&source, &target, "generic/tool.tar.gz")
+            .await
+            .expect("native copy should succeed");
+
+        assert_eq!(
+            *target.native_copies.lock().unwrap(),
+            vec!["generic/tool.tar.gz".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_artifact_content_streams_without_native_route() {
+        let payload = Bytes::from_static(b"cross-backend payload");
+        let source = ChunkedSource {
+            payload: payload.clone(),
+            missing: false,
+        };
+        let target = CapturingTarget::default();
+
+        copy_artifact_content(&source, &target, "generic/tool.tar.gz")
+            .await
+            .expect("streamed copy should succeed");
+
+        assert_eq!(&target.received.lock().unwrap()[..], &payload[..]);
+    }
+
     #[tokio::test]
     async fn test_stream_copy_artifact_propagates_source_not_found() {
         // A missing source object must surface as AppError::NotFound from
@@ -3433,8 +3866,10 @@ mod tests {
                 Path((src_key.clone(), artifact)),
                 Json(PromoteArtifactRequest {
                     target_repository: Some(tgt_key.clone()),
+                    target_instance: None,
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3478,8 +3913,10 @@ mod tests {
                 Path((src_key.clone(), artifact)),
                 Json(PromoteArtifactRequest {
                     target_repository: Some(tgt_key.clone()),
+                    target_instance: None,
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3519,8 +3956,10 @@ mod tests {
                 Path((src_key.clone(), artifact)),
                 Json(PromoteArtifactRequest {
                     target_repository: Some(tgt_key.clone()),
+                    target_instance: None,
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3558,8 +3997,10 @@ mod tests {
                 Path((src_key.clone(), artifact)),
                 Json(PromoteArtifactRequest {
                     target_repository: Some(tgt_key.clone()),
+                    target_instance: None,
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3604,6 +4045,7 @@ mod tests {
                     artifact_ids: vec![artifact],
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3647,8 +4089,10 @@ mod tests {
                 Path((src_key.clone(), artifact)),
                 Json(PromoteArtifactRequest {
                     target_repository: Some(tgt_key.clone()),
+                    target_instance: None,
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3688,8 +4132,10 @@ mod tests {
                 Path((src_key.clone(), artifact)),
                 Json(PromoteArtifactRequest {
                     target_repository: Some(tgt_key.clone()),
+                    target_instance: None,
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3744,8 +4190,10 @@ mod tests {
                 Path((src_key.clone(), artifact)),
                 Json(PromoteArtifactRequest {
                     target_repository: Some(tgt_key.clone()),
+                    target_instance: None,
                     skip_policy_check: true,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3785,6 +4233,7 @@ mod tests {
                     artifact_ids: vec![met_art],
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
@@ -3813,6 +4262,7 @@ mod tests {
                     artifact_ids: vec![no_art],
                     skip_policy_check: false,
                     notes: None,
+                    freeze_override_reason: None,
                 }),
             )
             .await
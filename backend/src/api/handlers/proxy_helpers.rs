@@ -1180,6 +1180,9 @@ where
 /// writer should have written it — a client retry is warranted); or propagates
 /// non-`NotFound` storage errors as 500.
 ///
+/// A miss on an artifact held by the cold storage tier skips the retry and
+/// returns 202 + `Retry-After` while the artifact is rehydrated.
+///
 /// This is the local missing-file repair path: when multiple concurrent
 /// requests arrive for the same artifact and the file is transiently absent,
 /// they queue behind the in-process coordinator rather than all failing
@@ -1190,6 +1193,19 @@ pub(crate) async fn coordinated_retry_get(
     storage_key: &str,
     storage: &dyn crate::storage::StorageBackend,
 ) -> Result<Bytes, Response> {
+    // An artifact moved to the cold storage tier is not transiently absent:
+    // queue its rehydration and answer 202 + Retry-After straight away.
+    match crate::services::storage_tiering_service::request_rehydration(db, artifact_id).await {
+        Ok(Some(retry_after_secs)) => {
+            return Err(crate::error::AppError::Rehydrating { retry_after_secs }.into_response());
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!(
+            artifact_id = %artifact_id,
+            "cold tier lookup failed; treating the miss as transient: {}",
+            e
+        ),
+    }
     let hydration_lease_key = format!("artifact-read-retry:{}", storage_key);
     tracing::warn!(
         artifact_id = %artifact_id,
@@ -1931,7 +1947,7 @@ where
     F: Fn(Uuid, StorageLocation) -> Fut,
     Fut: std::future::Future<Output = Result<StreamingFetchResult, Response>>,
 {
-    let members = fetch_virtual_members(db, virtual_repo_id).await?;
+    let members = fetch_virtual_members_for_path(db, virtual_repo_id, path).await?;
     resolve_virtual_download_from_members(members, proxy_service, path, local_fetch).await
 }
 
@@ -2098,7 +2114,7 @@ where
     F: Fn(Uuid, StorageLocation) -> Fut,
     Fut: std::future::Future<Output = Result<StreamingFetchResult, Response>>,
 {
-    let members = fetch_virtual_members(&state.db, virtual_repo_id).await?;
+    let members = fetch_virtual_members_for_path(&state.db, virtual_repo_id, path).await?;
 
     if members.is_empty() {
         return Err((StatusCode::NOT_FOUND, "Virtual repository has no members").into_response());
@@ -2221,18 +2237,24 @@ where
 ///
 /// Suitable for metadata endpoints where only one upstream response is
 /// needed (npm package info, pypi simple index, hex package, rubygems gem info).
+///
+/// `path` is fetched from each upstream; `route_path` is what the members'
+/// routing patterns are matched against. They differ when the metadata
+/// describes a package whose downloads live elsewhere (e.g. rubygems gem info
+/// routes by the `gems/{name}-` prefix of its `.gem` files).
 pub async fn resolve_virtual_metadata<F, Fut>(
     db: &PgPool,
     proxy_service: Option<&ProxyService>,
     virtual_repo_id: Uuid,
     path: &str,
+    route_path: &str,
     transform: F,
 ) -> Result<Response, Response>
 where
     F: Fn(Bytes, String) -> Fut,
     Fut: std::future::Future<Output = Result<Response, Response>>,
 {
-    let members = fetch_virtual_members(db, virtual_repo_id).await?;
+    let members = fetch_virtual_members_for_path(db, virtual_repo_id, route_path).await?;
 
     if members.is_empty() {
         return Err((StatusCode::NOT_FOUND, "Virtual repository has no members").into_response());
@@ -2339,18 +2361,22 @@ where
 
 /// Collect metadata from ALL remote members of a virtual repository.
 /// Each member's response is extracted via the `extract` closure and
-/// gathered into a `Vec<(repo_key, T)>`. The caller is responsible for
+/// gathered into a `Vec<(member_id, T)>`. The caller is responsible for
 /// merging the collected results.
 ///
 /// Suitable for metadata endpoints where responses from every upstream
 /// must be combined (conda repodata, cran PACKAGES, helm index, rubygems specs).
+///
+/// `path` names the index, not a package, so it is not run through member
+/// routing. Callers must instead drop the entries a member's routing patterns
+/// exclude, using [`fetch_virtual_member_routes`] and the returned member id.
 pub async fn collect_virtual_metadata<T, F, Fut>(
     db: &PgPool,
     proxy_service: Option<&ProxyService>,
     virtual_repo_id: Uuid,
     path: &str,
     extract: F,
-) -> Result<Vec<(String, T)>, Response>
+) -> Result<Vec<(Uuid, T)>, Response>
 where
     F: Fn(Bytes, String) -> Fut,
     Fut: std::future::Future<Output = Result<T, Response>>,
@@ -2368,7 +2394,7 @@ where
         .iter()
         .filter(|m| m.repo_type == RepositoryType::Remote)
         .collect();
-    let mut results: Vec<(String, T)> = Vec::new();
+    let mut results: Vec<(Uuid, T)> = Vec::new();
     for chunk in remote_members.chunks(MAX_VIRTUAL_FANOUT) {
         let batch = futures::future::join_all(chunk.iter().copied().map(|member| async move {
             let (Some(proxy), Some(upstream_url)) = (proxy_service, member.upstream_url.as_deref())
@@ -2377,7 +2403,7 @@ where
             };
             match proxy_fetch(proxy, member.id, &member.key, upstream_url, path).await {
                 Ok((bytes, _ct)) => match extract(bytes, member.key.clone()).await {
-                    Ok(data) => Some((member.key.clone(), data)),
+                    Ok(data) => Some((member.id, data)),
                     Err(_) => {
                         tracing::warn!(
                             member = %member.key,
@@ -2467,6 +2493,53 @@ pub async fn fetch_virtual_members(
     .map_err(map_db_err)
 }
 
+/// Drop the members whose include/exclude routing patterns do not allow
+/// `path`, preserving priority order. See
+/// [`crate::services::virtual_member_routing`] for the pattern semantics.
+///
+/// Must run before any member is probed so an excluded member is never asked
+/// for the path at all — an internal coordinate pinned to the internal member
+/// can then never be satisfied by a public upstream, even on a local miss.
+pub async fn route_virtual_members(
+    db: &PgPool,
+    virtual_repo_id: Uuid,
+    path: &str,
+    members: Vec<Repository>,
+) -> Result<Vec<Repository>, Response> {
+    let routes = fetch_virtual_member_routes(db, virtual_repo_id).await?;
+    if routes.is_empty() {
+        return Ok(members);
+    }
+    Ok(crate::services::virtual_member_routing::filter_members_for_path(members, &routes, path))
+}
+
+/// The include/exclude routing patterns of a virtual repository's members.
+///
+/// For fan-outs that merge entries from every member into one response
+/// (indexes, listings, search): a member cannot be dropped up front there,
+/// so each entry it contributes is checked with
+/// [`MemberRoutes::allows`](crate::services::virtual_member_routing::MemberRoutes::allows)
+/// against the path the entry is downloaded from.
+pub async fn fetch_virtual_member_routes(
+    db: &PgPool,
+    virtual_repo_id: Uuid,
+) -> Result<crate::services::virtual_member_routing::MemberRoutes, Response> {
+    crate::services::virtual_member_routing::load_member_routes(db, virtual_repo_id)
+        .await
+        .map_err(|e| e.into_response())
+}
+
+/// [`fetch_virtual_members`] followed by [`route_virtual_members`]: the
+/// members that may serve `path`, in priority order.
+pub async fn fetch_virtual_members_for_path(
+    db: &PgPool,
+    virtual_repo_id: Uuid,
+    path: &str,
+) -> Result<Vec<Repository>, Response> {
+    let members = fetch_virtual_members(db, virtual_repo_id).await?;
+    route_virtual_members(db, virtual_repo_id, path, members).await
+}
+
 /// Decide whether `auth` is allowed to read `member` directly, mirroring the
 /// read-access model that [`crate::api::middleware::auth::repo_visibility_middleware`]
 /// applies to the URL-named repository.
@@ -3184,6 +3257,10 @@ pub(crate) fn classify_remote_or_virtual(repo_type: &str) -> RemoteOrVirtualActi
 /// false` predicate matches the partial-index WHERE clause exactly so
 /// the planner uses the index.
 ///
+/// Only members whose routing patterns allow `path` (the download path the
+/// caller is about to resolve) are considered, so a local member excluded
+/// from that path cannot block the member the path is routed to.
+///
 /// Fails closed: a database error returns 500 rather than allowing the
 /// caller to proceed without the guard. Returns false (allow proxy
 /// fan-out) on the benign "no non-Remote members" case so virtual repos
@@ -3194,8 +3271,9 @@ pub async fn virtual_non_remote_owns_name(
     db: &PgPool,
     virtual_repo_id: Uuid,
     package_name: &str,
+    path: &str,
 ) -> Result<bool, Response> {
-    virtual_non_remote_owns_name_version(db, virtual_repo_id, package_name, None).await
+    virtual_non_remote_owns_name_version(db, virtual_repo_id, package_name, None, path).await
 }
 
 /// Version-aware variant of [`virtual_non_remote_owns_name`]. When `version`
@@ -3209,8 +3287,9 @@ pub async fn virtual_non_remote_owns_name_version(
     virtual_repo_id: Uuid,
     package_name: &str,
     version: Option<&str>,
+    path: &str,
 ) -> Result<bool, Response> {
-    let members = fetch_virtual_members(db, virtual_repo_id).await?;
+    let members = fetch_virtual_members_for_path(db, virtual_repo_id, path).await?;
     let non_remote_ids: Vec<Uuid> = members
         .iter()
         .filter(|m| m.repo_type != RepositoryType::Remote)
@@ -3327,15 +3406,17 @@ fn shadowing_guard_db_err(virtual_repo_id: Uuid, format: &str, e: sqlx::Error) -
 /// members (the #1267 union / #1584 version fallthrough then apply).
 ///
 /// `normalized_name` must already be PEP 503 normalized; the ownership query
-/// uses the same normalization the simple index uses so the two agree.
+/// uses the same normalization the simple index uses so the two agree. Only
+/// local members whose routing patterns allow `path` can own the name.
 /// Fails closed (Err 500) on DB error.
 #[allow(clippy::result_large_err)]
 pub async fn pypi_virtual_isolates_name(
     db: &PgPool,
     virtual_repo_id: Uuid,
     normalized_name: &str,
+    path: &str,
 ) -> Result<Option<i32>, Response> {
-    let members = fetch_virtual_members(db, virtual_repo_id).await?;
+    let members = fetch_virtual_members_for_path(db, virtual_repo_id, path).await?;
     let local_ids: Vec<Uuid> = members
         .iter()
         .filter(|m| m.repo_type == RepositoryType::Local || m.repo_type == RepositoryType::Staging)
@@ -3430,6 +3511,8 @@ pub async fn fetch_virtual_member_priorities(
 /// Remote member earlier in priority order would win with the wrong (or
 /// empty) bytes (B9).
 ///
+/// Members whose routing patterns exclude `path` are not considered.
+///
 /// Fails closed on DB error (matches [`virtual_non_remote_owns_name`]).
 /// Returns false on the benign "no non-Remote members" case so virtual repos
 /// that contain only upstream proxies behave exactly as before.
@@ -3439,7 +3522,7 @@ pub async fn virtual_non_remote_owns_path(
     virtual_repo_id: Uuid,
     path: &str,
 ) -> Result<bool, Response> {
-    let members = fetch_virtual_members(db, virtual_repo_id).await?;
+    let members = fetch_virtual_members_for_path(db, virtual_repo_id, path).await?;
     let non_remote_ids: Vec<Uuid> = members
         .iter()
         .filter(|m| m.repo_type != RepositoryType::Remote)
@@ -3567,6 +3650,9 @@ pub(crate) fn maven_gav_like_pattern(group_id: &str, artifact_id: &str, version:
 /// widen the match. Uses the `(repository_id, path)` btree
 /// (`idx_artifacts_repo_path`).
 ///
+/// Only members whose routing patterns allow the version directory
+/// (`<group-path>/<artifact_id>/<version>/`) are considered.
+///
 /// Fails closed on DB error (matches `virtual_non_remote_owns_name`).
 #[allow(clippy::result_large_err)]
 pub async fn virtual_non_remote_owns_maven_gav(
@@ -3576,7 +3662,13 @@ pub async fn virtual_non_remote_owns_maven_gav(
     artifact_id: &str,
     version: &str,
 ) -> Result<bool, Response> {
-    let members = fetch_virtual_members(db, virtual_repo_id).await?;
+    let gav_dir = format!(
+        "{}/{}/{}/",
+        group_id.replace('.', "/"),
+        artifact_id,
+        version
+    );
+    let members = fetch_virtual_members_for_path(db, virtual_repo_id, &gav_dir).await?;
     let non_remote_ids: Vec<Uuid> = members
         .iter()
         .filter(|m| m.repo_type != RepositoryType::Remote)
@@ -4394,6 +4486,25 @@ pub struct NewArtifact<'a> {
 #[allow(clippy::result_large_err)]
 pub async fn insert_artifact(db: &PgPool, art: NewArtifact<'_>) -> Result<Uuid, Response> {
     let repository_id = art.repository_id;
+    crate::api::middleware::access_log::note_package(art.name, Some(art.version));
+
+    // Instance block list; the same check `ArtifactService` runs on uploads.
+    crate::services::blocklist_service::check_upload(
+        db,
+        repository_id,
+        art.name,
+        Some(art.version),
+        art.path,
+        art.checksum_sha256,
+    )
+    .await
+    .map_err(|e| e.into_response())?;
+    crate::services::freeze_window_service::check_upload(db, repository_id, Some(art.uploaded_by))
+        .await
+        .map_err(|e| e.into_response())?;
+    crate::services::metadata_schema_service::check_upload(db, repository_id)
+        .await
+        .map_err(|e| e.into_response())?;
 
     let mut conn = db
         .acquire()
@@ -4408,6 +4519,7 @@ pub async fn insert_artifact(db: &PgPool, art: NewArtifact<'_>) -> Result<Uuid,
     // inserts — which carry their own sidecar quarantine state — are not
     // double-held. Best-effort: never fails the insert.
     crate::services::quarantine_service::apply_upload_hold_hosted(db, repository_id, id).await;
+    crate::services::metadata_schema_service::record_upload_properties(db, id).await;
 
     Ok(id)
 }
@@ -7476,7 +7588,20 @@ mod tests {
                 guest_access_enabled: true,
                 expose_detailed_health: false,
                 setup_password_hint: None,
+                setup_wizard: false,
                 grpc_reflection_enabled: false,
+                grpc_keepalive_interval_secs: 30,
+                grpc_keepalive_timeout_secs: 20,
+                grpc_tcp_keepalive_secs: 60,
+                grpc_health_probe_interval_secs: 10,
+                s3_gateway_enabled: false,
+                s3_gateway_region: "us-east-1".to_string(),
+                repository_event_retention_days: 30,
+                pii_retention_days: 0,
+                cache_warm_edge_urls: Vec::new(),
+                admission_registry_hosts: Vec::new(),
+                admission_allowed_repositories: Vec::new(),
+                admission_exempt_namespaces: vec!["kube-system".to_string()],
                 plugins_require_signed: true,
                 plugins_trusted_pubkey: None,
                 peer_instance_name: "test".into(),
@@ -7494,6 +7619,8 @@ mod tests {
                 stuck_scan_threshold_secs: 1800,
                 stuck_scan_check_interval_secs: 600,
                 stuck_scan_reap_limit: 1000,
+                audit_retention_enabled: false,
+                audit_archive_enabled: true,
                 allow_local_admin_login: false,
                 sso_disable_admin_break_glass: false,
                 max_upload_size_bytes: 10_737_418_240,
@@ -7539,9 +7666,17 @@ mod tests {
                 password_min_strength: 0,
                 presigned_downloads_enabled: false,
                 presigned_download_expiry_secs: 300,
+                presigned_canary_interval_secs: 600,
                 proxy_singleflight_advisory_locks_enabled: false,
                 proxy_singleflight_lock_poll_interval_ms: 200,
                 proxy_singleflight_lock_wait_timeout_secs: 65,
+                public_mirror_mode: false,
+                public_mirror_anon_requests_per_window: 1200,
+                public_mirror_window_secs: 60,
+                public_mirror_anon_bytes_per_window: 53_687_091_200,
+                public_mirror_bandwidth_window_secs: 3600,
+                public_mirror_metadata_max_age_secs: 300,
+                public_mirror_artifact_max_age_secs: 31_536_000,
                 smtp_host: None,
                 smtp_port: 587,
                 smtp_username: None,
@@ -7556,6 +7691,18 @@ mod tests {
                 npm_upstream_feed_url:
                     crate::services::upstream_feed::NPM_REPLICATION_FEED_DEFAULT_URL.into(),
                 scan_token_ttl_seconds: 300,
+                load_test_mode: false,
+                storage_dedup_enabled: false,
+                storage_capacity_bytes: None,
+                storage_forecast_alert_days: 30,
+                quota_warning_threshold_percent: 80,
+                storage_cold_backend: None,
+                storage_cold_path: None,
+                storage_cold_rehydrated_days: 7,
+                api_token_stale_days: 0,
+                api_token_stale_warning_days: 7,
+                repository_key_alias_ttl_days: 90,
+                access_log_retention_days: 14,
             }
         }
 
@@ -137,7 +137,12 @@ async fn package_info(
 
         // Virtual repo: resolve through members in priority order
         if repo.repo_type == RepositoryType::Virtual {
-            let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+            // Route by the package's archive directory, the prefix of every
+            // `packages/{name}/versions/{version}.tar.gz` download.
+            let route_path = format!("packages/{}/", name);
+            let members =
+                proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path)
+                    .await?;
             for member in &members {
                 match member.repo_type {
                     RepositoryType::Local | RepositoryType::Staging => {
@@ -308,7 +313,10 @@ async fn version_info(
 
             // Virtual repo: resolve through members in priority order
             if repo.repo_type == RepositoryType::Virtual {
-                let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+                let route_path = format!("packages/{}/versions/{}.tar.gz", name, version);
+                let members =
+                    proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path)
+                        .await?;
                 for member in &members {
                     match member.repo_type {
                         RepositoryType::Local | RepositoryType::Staging => {
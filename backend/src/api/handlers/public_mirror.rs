@@ -0,0 +1,163 @@
+//! Public mirror mode reporting.
+//!
+//! Admin-only, nested under `/api/v1/admin/public-mirror`. Reports the
+//! mirror settings in effect and the per-repository bandwidth accounting
+//! collected while `PUBLIC_MIRROR_MODE` is enabled.
+
+use axum::{
+    extract::{Extension, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::public_mirror_service::{
+    usage_totals, MirrorUsageRow, MirrorUsageTotals, PublicMirrorService,
+};
+
+/// Admin routes, nested at `/api/v1/admin/public-mirror`.
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/usage", get(get_usage))
+}
+
+fn require_admin(auth: &AuthExtension) -> Result<()> {
+    if !auth.is_admin {
+        return Err(AppError::Authorization(
+            "Admin privileges required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MirrorUsageQuery {
+    /// Days to report, today included (default 7, max 366).
+    pub days: Option<u32>,
+    /// Only report this repository.
+    pub repository: Option<String>,
+}
+
+/// Mirror settings in effect on this replica.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicMirrorSettings {
+    pub enabled: bool,
+    pub anon_requests_per_window: u32,
+    pub window_secs: u64,
+    pub anon_bytes_per_window: u64,
+    pub bandwidth_window_secs: u64,
+    pub metadata_max_age_secs: u64,
+    pub artifact_max_age_secs: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicMirrorUsageResponse {
+    pub settings: PublicMirrorSettings,
+    pub days: u32,
+    pub items: Vec<MirrorUsageRow>,
+    pub totals: MirrorUsageTotals,
+}
+
+fn settings(config: &crate::config::Config) -> PublicMirrorSettings {
+    PublicMirrorSettings {
+        enabled: config.public_mirror_mode,
+        anon_requests_per_window: config.public_mirror_anon_requests_per_window,
+        window_secs: config.public_mirror_window_secs,
+        anon_bytes_per_window: config.public_mirror_anon_bytes_per_window,
+        bandwidth_window_secs: config.public_mirror_bandwidth_window_secs,
+        metadata_max_age_secs: config.public_mirror_metadata_max_age_secs,
+        artifact_max_age_secs: config.public_mirror_artifact_max_age_secs,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/usage",
+    context_path = "/api/v1/admin/public-mirror",
+    tag = "public_mirror",
+    params(MirrorUsageQuery),
+    responses(
+        (status = 200, description = "Mirror settings and bandwidth accounting", body = PublicMirrorUsageResponse),
+        (status = 403, description = "Admin privileges required"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_usage(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<MirrorUsageQuery>,
+) -> Result<Json<PublicMirrorUsageResponse>> {
+    require_admin(&auth)?;
+
+    let days = query
+        .days
+        .unwrap_or(7)
+        .clamp(1, crate::services::public_mirror_service::MAX_USAGE_DAYS);
+    let repository = query
+        .repository
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    let items = PublicMirrorService::new(state.db.clone())
+        .usage(days, repository)
+        .await?;
+    let totals = usage_totals(&items);
+
+    Ok(Json(PublicMirrorUsageResponse {
+        settings: settings(&state.config),
+        days,
+        items,
+        totals,
+    }))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_usage),
+    components(schemas(
+        PublicMirrorSettings,
+        PublicMirrorUsageResponse,
+        MirrorUsageRow,
+        MirrorUsageTotals,
+    ))
+)]
+pub struct PublicMirrorApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_reflect_config() {
+        let config = crate::config::Config {
+            public_mirror_mode: true,
+            public_mirror_anon_bytes_per_window: 1024,
+            ..Default::default()
+        };
+        let s = settings(&config);
+        assert!(s.enabled);
+        assert_eq!(s.anon_bytes_per_window, 1024);
+        assert_eq!(s.artifact_max_age_secs, 31_536_000);
+    }
+
+    #[test]
+    fn test_require_admin() {
+        let mut auth = AuthExtension {
+            user_id: uuid::Uuid::new_v4(),
+            username: "u".to_string(),
+            email: "u@example.com".to_string(),
+            is_admin: false,
+            is_api_token: false,
+            is_service_account: false,
+            scopes: None,
+            allowed_repo_ids: crate::models::access_scope::AccessScope::Admin,
+            iat_ms: None,
+        };
+        assert!(require_admin(&auth).is_err());
+        auth.is_admin = true;
+        assert!(require_admin(&auth).is_ok());
+    }
+}
@@ -12,7 +12,7 @@
 
 use axum::body::Body;
 use axum::extract::{Multipart, Path, State};
-use axum::http::header::{CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, ETAG};
+use axum::http::header::{ACCEPT, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, ETAG, VARY};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
@@ -39,7 +39,9 @@ use crate::error::AppError;
 use crate::formats::pypi::PypiHandler;
 use crate::models::repository::{RepositoryFormat, RepositoryType};
 use crate::services::age_gate_service::{AgeGateDecision, AgeGateService};
+use crate::services::package_docs_service::{self, PackageDocs};
 use crate::services::upstream_metadata::metadata_http_client;
+use crate::services::version_deprecation_service::{DeprecatedVersions, VersionDeprecationService};
 use chrono::Utc;
 
 // ---------------------------------------------------------------------------
@@ -47,15 +49,29 @@ use chrono::Utc;
 // ---------------------------------------------------------------------------
 
 pub fn router() -> Router<SharedState> {
-    Router::new()
-        // Twine upload
-        .route("/:repo_key/", post(upload))
+    // The simple index pages are negotiated on Accept (PEP 691), so shared
+    // caches must key them on it: otherwise a cached HTML page is handed to a
+    // JSON client, or the other way round.
+    let simple_index = Router::new()
         // Simple index root
         .route("/:repo_key/simple/", get(simple_root))
         .route("/:repo_key/simple", get(simple_root))
         // Package index
         .route("/:repo_key/simple/:project/", get(simple_project))
         .route("/:repo_key/simple/:project", get(simple_project))
+        .layer(axum::middleware::map_response(
+            |mut response: Response| async move {
+                response
+                    .headers_mut()
+                    .append(VARY, "Accept".parse().unwrap());
+                response
+            },
+        ));
+
+    Router::new()
+        // Twine upload
+        .route("/:repo_key/", post(upload))
+        .merge(simple_index)
         // Download & metadata
         .route(
             "/:repo_key/simple/:project/:filename",
@@ -191,6 +207,31 @@ struct SimpleProjectArtifact {
     /// Upload timestamp, surfaced as PEP 700 `upload-time` (RFC 3339) in the
     /// PEP 691 JSON response and `data-upload-time` in the HTML response.
     upload_time: Option<chrono::DateTime<chrono::Utc>>,
+    /// PEP 592 yank reason when the version was yanked through the
+    /// deprecation API (empty when no reason was given).
+    yanked: Option<String>,
+}
+
+/// The PEP 592 yank reason for a distribution of `version`, if yanked.
+fn yank_reason(yanked: &DeprecatedVersions, version: Option<&str>) -> Option<String> {
+    let message = yanked.get(version?)?;
+    Some(message.clone().unwrap_or_default())
+}
+
+/// PEP 592 `data-yanked` anchor attribute; the value is the yank reason.
+fn yanked_attr(yanked: Option<&str>) -> String {
+    yanked
+        .map(|reason| format!(" data-yanked=\"{}\"", html_escape(reason)))
+        .unwrap_or_default()
+}
+
+/// PEP 691 `yanked` value: the reason, or `true` when none was given.
+fn yanked_json(reason: &str) -> serde_json::Value {
+    if reason.is_empty() {
+        serde_json::Value::Bool(true)
+    } else {
+        serde_json::Value::String(reason.to_string())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -264,8 +305,12 @@ async fn simple_root(
     // available through the virtual endpoint.
     if merged.is_empty() && repo.repo_type == RepositoryType::Virtual {
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        // Each member only lists the projects its routing patterns let it
+        // serve, checked against the project's simple-index path.
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
 
         for member in &members {
+            let mut member_names: Vec<String> = Vec::new();
             if member.repo_type == RepositoryType::Local
                 || member.repo_type == RepositoryType::Staging
             {
@@ -281,16 +326,16 @@ async fn simple_root(
                 .await
                 .map_err(map_db_err)?;
 
-                merged.extend(member_raw.iter().map(|n| normalize_pep503(n)));
+                member_names.extend(member_raw.iter().map(|n| normalize_pep503(n)));
             } else if member.repo_type == RepositoryType::Remote {
                 if let Some(names) =
                     fetch_remote_simple_root(&state, &member.key, member.id, &member.upstream_url)
                         .await
                 {
-                    merged.extend(names);
+                    member_names.extend(names);
                 }
                 if let Some(proxy) = state.proxy_service.as_ref() {
-                    merged.extend(
+                    member_names.extend(
                         proxy
                             .list_cached_pypi_packages(&member.key)
                             .await
@@ -299,6 +344,11 @@ async fn simple_root(
                     );
                 }
             }
+            merged.extend(
+                member_names
+                    .into_iter()
+                    .filter(|n| routes.allows(member.id, &format!("simple/{}/", n))),
+            );
         }
     }
 
@@ -518,12 +568,7 @@ fn build_simple_root_response(
     // the request Content-Type, which is the media type of the request *body*,
     // not a negotiation signal — a client sending `Content-Type: ...+json`
     // with `Accept: text/html` would wrongly receive JSON.
-    let accept = headers
-        .get("accept")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-
-    if accept.contains("application/vnd.pypi.simple.v1+json") {
+    if negotiate_simple_format(headers) == SimpleFormat::Json {
         let json = serde_json::json!({
             "meta": { "api-version": "1.2" },
             "projects": packages.iter().map(|p| {
@@ -614,11 +659,7 @@ async fn simple_project(
     // PEP 691 content negotiation also governs the proxy path: a JSON client
     // must get the upstream's JSON representation (which carries PEP 700
     // `upload-time`), not its HTML index (which never does).
-    let wants_json = headers
-        .get("accept")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("")
-        .contains(PEP691_JSON_CONTENT_TYPE);
+    let wants_json = negotiate_simple_format(&headers) == SimpleFormat::Json;
 
     // Find all artifacts that belong to this package.
     // We normalize the name for matching: replace [_.-]+ with - then lowercase.
@@ -641,9 +682,15 @@ async fn simple_project(
     .await
     .map_err(map_db_err)?;
 
+    let yanked = VersionDeprecationService::new(state.db.clone())
+        .deprecated_pypi_versions(repo.id, &normalized)
+        .await
+        .map_err(|e| e.into_response())?;
+
     let simple_artifacts: Vec<SimpleProjectArtifact> = artifacts
         .into_iter()
         .map(|a| SimpleProjectArtifact {
+            yanked: yank_reason(&yanked, a.version.as_deref()),
             path: a.path,
             version: a.version,
             size_bytes: a.size_bytes,
@@ -788,7 +835,10 @@ async fn simple_project(
         // package that exists partially in a local member doesn't shadow
         // the rest of upstream. See #1230.
         if repo.repo_type == RepositoryType::Virtual {
-            let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+            let route_path = format!("simple/{}/", normalized);
+            let members =
+                proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path)
+                    .await?;
 
             if members.is_empty() {
                 return Err(
@@ -806,8 +856,13 @@ async fn simple_project(
             // cannot hide a higher-priority upstream's versions from pip.
             // The download path makes the same per-member decision, keeping
             // index and download consistent.
-            let owning_local_min_priority =
-                proxy_helpers::pypi_virtual_isolates_name(&state.db, repo.id, &normalized).await?;
+            let owning_local_min_priority = proxy_helpers::pypi_virtual_isolates_name(
+                &state.db,
+                repo.id,
+                &normalized,
+                &route_path,
+            )
+            .await?;
             let member_priorities = if owning_local_min_priority.is_some() {
                 proxy_helpers::fetch_virtual_member_priorities(&state.db, repo.id).await?
             } else {
@@ -846,7 +901,13 @@ async fn simple_project(
                 .await
                 .map_err(map_db_err)?;
 
+                let yanked = VersionDeprecationService::new(state.db.clone())
+                    .deprecated_pypi_versions(member.id, &normalized)
+                    .await
+                    .map_err(|e| e.into_response())?;
+
                 local_artifacts.extend(member_rows.into_iter().map(|a| SimpleProjectArtifact {
+                    yanked: yank_reason(&yanked, a.version.as_deref()),
                     path: a.path,
                     version: a.version,
                     size_bytes: a.size_bytes,
@@ -1112,12 +1173,7 @@ fn build_simple_project_response(
     artifacts: &[SimpleProjectArtifact],
     tracks: &[String],
 ) -> Result<Response, Response> {
-    let accept = headers
-        .get("accept")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-
-    if accept.contains("application/vnd.pypi.simple.v1+json") {
+    if negotiate_simple_format(headers) == SimpleFormat::Json {
         // PEP 691 JSON response
         let files: Vec<serde_json::Value> = artifacts
             .iter()
@@ -1146,6 +1202,10 @@ fn build_simple_project_response(
                     file["upload-time"] =
                         serde_json::Value::String(ut.format("%Y-%m-%dT%H:%M:%SZ").to_string());
                 }
+                // PEP 592: yanked files stay listed (and downloadable).
+                if let Some(reason) = &a.yanked {
+                    file["yanked"] = yanked_json(reason);
+                }
                 file
             })
             .collect();
@@ -1223,10 +1283,11 @@ fn build_simple_project_response(
             .upload_time
             .map(|ut| format!(" data-upload-time=\"{}\"", ut.format("%Y-%m-%dT%H:%M:%SZ")))
             .unwrap_or_default();
+        let yk_attr = yanked_attr(a.yanked.as_deref());
 
         html.push_str(&format!(
-            "<a href=\"{}\"{}{}>{}</a><br/>\n",
-            url, rp_attr, ut_attr, filename
+            "<a href=\"{}\"{}{}{}>{}</a><br/>\n",
+            url, rp_attr, ut_attr, yk_attr, filename
         ));
     }
 
@@ -1288,9 +1349,10 @@ fn merge_local_into_remote_simple_html(
             .upload_time
             .map(|ut| format!(" data-upload-time=\"{}\"", ut.format("%Y-%m-%dT%H:%M:%SZ")))
             .unwrap_or_default();
+        let yk_attr = yanked_attr(a.yanked.as_deref());
         local_lines.push_str(&format!(
-            "<a href=\"{}\"{}{}>{}</a><br/>\n",
-            url, rp_attr, ut_attr, filename
+            "<a href=\"{}\"{}{}{}>{}</a><br/>\n",
+            url, rp_attr, ut_attr, yk_attr, filename
         ));
     }
 
@@ -1707,7 +1769,11 @@ async fn serve_file(
             // index. We iterate members manually and delegate to
             // fetch_from_pypi_remote_streaming for each remote member.
             if repo.repo_type == RepositoryType::Virtual {
-                let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+                let normalized_project = normalize_pep503(project);
+                let route_path = build_pypi_proxy_cache_path(&normalized_project, filename);
+                let members =
+                    proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path)
+                        .await?;
 
                 if members.is_empty() {
                     return Err(AppError::NotFound(
@@ -1751,11 +1817,11 @@ async fn serve_file(
                 // member at equal or higher priority than the owning local
                 // still serves, mirroring the simple-index decision above so
                 // every version the index lists is downloadable.
-                let normalized_project = normalize_pep503(project);
                 let owning_local_min_priority = proxy_helpers::pypi_virtual_isolates_name(
                     &state.db,
                     repo.id,
                     &normalized_project,
+                    &route_path,
                 )
                 .await?;
                 let member_priorities = if owning_local_min_priority.is_some() {
@@ -2577,6 +2643,9 @@ async fn upload(
         AppError::Validation("Missing filename in content field".to_string()).into_response()
     })?;
 
+    let core_metadata = parse_upload_core_metadata(&pkg_name, &metadata_fields)
+        .map_err(|msg| AppError::Validation(msg).into_response())?;
+
     let normalized = PypiHandler::normalize_name(&pkg_name);
 
     // SHA-256 was computed incrementally while the body was spooled to disk.
@@ -2627,6 +2696,14 @@ async fn upload(
             pkg_info.insert("summary".to_string(), serde_json::Value::String(s.clone()));
         }
     }
+    if !core_metadata.is_empty() {
+        if !pkg_metadata["pkg_info"].is_object() {
+            pkg_metadata["pkg_info"] = serde_json::json!({});
+        }
+        if let Some(pkg_info) = pkg_metadata["pkg_info"].as_object_mut() {
+            pkg_info.extend(core_metadata);
+        }
+    }
     if !metadata_fields.is_empty() {
         pkg_metadata["upload_metadata"] = serde_json::Value::Object(metadata_fields);
     }
@@ -2665,13 +2742,37 @@ async fn upload(
         )
         .await
         .map_err(|e| e.into_response())?;
+
+    // README for search (best-effort): a wheel's long description from its
+    // METADATA, otherwise the `description` field twine sends from PKG-INFO.
+    let docs = if filename.ends_with(".whl") {
+        crate::util::bounded_archive::with_ingest_extraction(|| {
+            std::fs::File::open(staged_content.path())
+                .map(|f| package_docs_service::extract_from_wheel(std::io::BufReader::new(f)))
+                .unwrap_or_default()
+        })
+        .unwrap_or_default()
+    } else {
+        PackageDocs::default()
+    };
+    let docs = if docs.is_empty() {
+        pypi_description_docs(pkg_metadata["upload_metadata"].get("description"))
+    } else {
+        docs
+    };
     // Scratch file no longer needed once the service has consumed the stream.
     drop(staged_content);
 
     artifact_service
-        .set_metadata(artifact.id, "pypi", pkg_metadata, serde_json::json!({}))
+        .set_metadata(
+            artifact.id,
+            "pypi",
+            pkg_metadata,
+            crate::services::metadata_schema_service::upload_properties(),
+        )
         .await
         .map_err(|e| e.into_response())?;
+    package_docs_service::record_package_docs(&state.db, artifact.id, &docs).await;
 
     // Update repository timestamp
     let _ = sqlx::query!(
@@ -2719,6 +2820,16 @@ fn should_enqueue_pypi_sync_tasks(headers: &HeaderMap) -> bool {
     !super::is_replication_request(headers)
 }
 
+/// README docs from the upload's `description` form field. Twine sends it
+/// even when empty, and `UNKNOWN` is the core-metadata placeholder.
+fn pypi_description_docs(description: Option<&serde_json::Value>) -> PackageDocs {
+    description
+        .and_then(|v| v.as_str())
+        .filter(|d| d.trim() != "UNKNOWN")
+        .map(package_docs_service::from_readme_text)
+        .unwrap_or_default()
+}
+
 fn build_pypi_package_catalog_metadata(
     filename: &str,
     requires_python: Option<&str>,
@@ -2953,9 +3064,160 @@ fn rewrite_upstream_urls(html: &str, repo_key: &str, project: &str) -> String {
         .into_owned()
 }
 
+/// Core-metadata versions accepted from `twine upload` (the
+/// `metadata_version` form field).
+const SUPPORTED_METADATA_VERSIONS: &[&str] =
+    &["1.0", "1.1", "1.2", "2.0", "2.1", "2.2", "2.3", "2.4"];
+
+/// Upload form fields that carry a multiple-use core-metadata field (one form
+/// part per value). They are recorded as arrays even when sent once.
+const MULTI_USE_METADATA_FIELDS: &[&str] = &[
+    "classifiers",
+    "requires_dist",
+    "provides_dist",
+    "obsoletes_dist",
+    "requires_external",
+    "project_urls",
+    "provides_extra",
+    "platform",
+    "supported_platform",
+    "dynamic",
+    "license_file",
+];
+
+/// Single-use core-metadata fields recorded alongside `summary` and
+/// `requires_python` in `pkg_info`.
+const SINGLE_USE_METADATA_FIELDS: &[&str] = &[
+    "metadata_version",
+    "author",
+    "author_email",
+    "maintainer",
+    "maintainer_email",
+    "license",
+    "license_expression",
+    "home_page",
+    "download_url",
+    "keywords",
+    "description_content_type",
+];
+
+/// PEP 508 project name: ASCII letters and digits, with `.`, `_` and `-`
+/// allowed between them.
+static PROJECT_NAME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^([a-z0-9]|[a-z0-9][a-z0-9._-]*[a-z0-9])$").unwrap());
+
+/// Validate the core metadata of a `twine upload` form and extract the
+/// fields recorded in `pkg_info`.
+///
+/// `fields` holds every form part the upload handler does not consume
+/// itself. The declared `metadata_version` must be one this registry
+/// understands and `name` must be a valid PEP 508 project name. Empty values
+/// (twine sends a part for every field, set or not) are dropped.
+fn parse_upload_core_metadata(
+    name: &str,
+    fields: &serde_json::Map<String, serde_json::Value>,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    if let Some(version) = fields.get("metadata_version").and_then(|v| v.as_str()) {
+        if !SUPPORTED_METADATA_VERSIONS.contains(&version.trim()) {
+            return Err(format!("Unsupported metadata_version: {}", version));
+        }
+    }
+    if !PROJECT_NAME_RE.is_match(name) {
+        return Err(format!(
+            "Invalid project name '{}': must start and end with a letter or digit \
+             and contain only letters, digits, '.', '_' and '-'",
+            name
+        ));
+    }
+
+    let values = |key: &str| -> Vec<String> {
+        match fields.get(key) {
+            Some(serde_json::Value::String(v)) => vec![v.clone()],
+            Some(serde_json::Value::Array(vs)) => vs
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        }
+        .into_iter()
+        .filter(|v| !v.trim().is_empty())
+        .collect()
+    };
+
+    let mut core = serde_json::Map::new();
+    for key in SINGLE_USE_METADATA_FIELDS {
+        if let Some(value) = values(key).into_iter().last() {
+            core.insert((*key).to_string(), serde_json::Value::String(value));
+        }
+    }
+    for key in MULTI_USE_METADATA_FIELDS {
+        let list = values(key);
+        if !list.is_empty() {
+            core.insert((*key).to_string(), serde_json::json!(list));
+        }
+    }
+    Ok(core)
+}
+
 /// PEP 691 JSON simple-index media type.
 const PEP691_JSON_CONTENT_TYPE: &str = "application/vnd.pypi.simple.v1+json";
 
+/// Representation of a simple-index page chosen by content negotiation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SimpleFormat {
+    /// PEP 503 HTML (`text/html` / `application/vnd.pypi.simple.v1+html`).
+    Html,
+    /// PEP 691 JSON.
+    Json,
+}
+
+/// Pick the simple-index representation for a request per PEP 691.
+///
+/// The `Accept` header is parsed with its q-values: the highest-quality
+/// supported media type wins, a concrete type beats a wildcard of equal
+/// quality, `q=0` excludes a type, and the `latest` aliases resolve to v1.
+/// Requests that name no supported type (or send no header at all) get HTML,
+/// which is what every pre-PEP 691 installer expects.
+fn negotiate_simple_format(headers: &HeaderMap) -> SimpleFormat {
+    let accept = headers
+        .get(ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+
+    let mut best: Option<(f32, bool, SimpleFormat)> = None;
+    for range in accept.split(',') {
+        let mut params = range.split(';');
+        let media_type = params.next().unwrap_or("").trim().to_ascii_lowercase();
+        let (format, specific) = match media_type.as_str() {
+            "application/vnd.pypi.simple.v1+json" | "application/vnd.pypi.simple.latest+json" => {
+                (SimpleFormat::Json, true)
+            }
+            "application/vnd.pypi.simple.v1+html"
+            | "application/vnd.pypi.simple.latest+html"
+            | "text/html" => (SimpleFormat::Html, true),
+            "*/*" | "text/*" => (SimpleFormat::Html, false),
+            _ => continue,
+        };
+        let quality = params
+            .filter_map(|p| {
+                let (key, value) = p.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("q")
+                    .then(|| value.trim().parse::<f32>().ok())
+                    .flatten()
+            })
+            .next()
+            .unwrap_or(1.0);
+        if quality <= 0.0 {
+            continue;
+        }
+        if best.is_none_or(|(q, s, _)| quality > q || (quality == q && specific && !s)) {
+            best = Some((quality, specific, format));
+        }
+    }
+    best.map_or(SimpleFormat::Html, |(_, _, format)| format)
+}
+
 /// Rewrite the `files[].url` of a parsed PEP 691 JSON simple index to route
 /// downloads through Artifact Keeper's proxy, mirroring `rewrite_upstream_urls`
 /// for the HTML form, and strip the PEP 658/714 metadata signals the proxy
@@ -3070,6 +3332,9 @@ fn merge_local_into_remote_simple_json(
             file["upload-time"] =
                 serde_json::Value::String(ut.format("%Y-%m-%dT%H:%M:%SZ").to_string());
         }
+        if let Some(reason) = &a.yanked {
+            file["yanked"] = yanked_json(reason);
+        }
         appended.push(file);
     }
 
@@ -3414,6 +3679,122 @@ mod tests {
         assert_eq!(normalize_pep503("my-package_"), "my-package");
     }
 
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_negotiate_simple_format() {
+        assert_eq!(
+            negotiate_simple_format(&HeaderMap::new()),
+            SimpleFormat::Html
+        );
+        assert_eq!(
+            negotiate_simple_format(&accept("text/html")),
+            SimpleFormat::Html
+        );
+        assert_eq!(
+            negotiate_simple_format(&accept(PEP691_JSON_CONTENT_TYPE)),
+            SimpleFormat::Json
+        );
+        assert_eq!(
+            negotiate_simple_format(&accept("application/vnd.pypi.simple.latest+json")),
+            SimpleFormat::Json
+        );
+        // pip's header: JSON preferred, HTML as fallbacks.
+        assert_eq!(
+            negotiate_simple_format(&accept(
+                "application/vnd.pypi.simple.v1+json, \
+                 application/vnd.pypi.simple.v1+html; q=0.1, text/html; q=0.01"
+            )),
+            SimpleFormat::Json
+        );
+        // q-values, not list order, decide.
+        assert_eq!(
+            negotiate_simple_format(&accept(
+                "application/vnd.pypi.simple.v1+json; q=0.2, text/html"
+            )),
+            SimpleFormat::Html
+        );
+        // A concrete type beats a wildcard of equal quality.
+        assert_eq!(
+            negotiate_simple_format(&accept("*/*, application/vnd.pypi.simple.v1+json")),
+            SimpleFormat::Json
+        );
+        // q=0 means "not acceptable".
+        assert_eq!(
+            negotiate_simple_format(&accept(
+                "application/vnd.pypi.simple.v1+json;q=0, text/html;q=0.5"
+            )),
+            SimpleFormat::Html
+        );
+        assert_eq!(
+            negotiate_simple_format(&accept("application/json")),
+            SimpleFormat::Html
+        );
+    }
+
+    fn upload_fields(
+        pairs: &[(&str, serde_json::Value)],
+    ) -> serde_json::Map<String, serde_json::Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_upload_core_metadata() {
+        let fields = upload_fields(&[
+            ("metadata_version", serde_json::json!("2.1")),
+            ("author", serde_json::json!("Jane")),
+            ("home_page", serde_json::json!("")),
+            ("classifiers", serde_json::json!("License :: OSI Approved")),
+            (
+                "requires_dist",
+                serde_json::json!(["requests>=2", "click; extra == 'cli'"]),
+            ),
+            ("provides_extra", serde_json::json!(["cli", ""])),
+            ("comment", serde_json::json!("ignored")),
+        ]);
+        let core = parse_upload_core_metadata("My_Package.x", &fields).unwrap();
+        assert_eq!(core["metadata_version"], "2.1");
+        assert_eq!(core["author"], "Jane");
+        assert!(!core.contains_key("home_page"));
+        assert!(!core.contains_key("comment"));
+        assert_eq!(
+            core["classifiers"],
+            serde_json::json!(["License :: OSI Approved"])
+        );
+        assert_eq!(
+            core["requires_dist"],
+            serde_json::json!(["requests>=2", "click; extra == 'cli'"])
+        );
+        assert_eq!(core["provides_extra"], serde_json::json!(["cli"]));
+    }
+
+    #[test]
+    fn test_parse_upload_core_metadata_rejects_invalid_input() {
+        let v3 = upload_fields(&[("metadata_version", serde_json::json!("3.0"))]);
+        assert!(parse_upload_core_metadata("pkg", &v3).is_err());
+
+        let empty = serde_json::Map::new();
+        assert!(parse_upload_core_metadata("pkg", &empty)
+            .unwrap()
+            .is_empty());
+        for bad in ["", "-pkg", "pkg_", "my pkg", "pkg<script>", "paquet\u{e9}"] {
+            assert!(
+                parse_upload_core_metadata(bad, &empty).is_err(),
+                "{bad:?} must be rejected"
+            );
+        }
+        for good in ["a", "Django", "zope.interface", "my_pkg-2"] {
+            assert!(parse_upload_core_metadata(good, &empty).is_ok());
+        }
+    }
+
     #[test]
     fn test_normalize_pep503_leading_separator() {
         // Leading separators are collapsed and skipped
@@ -3962,6 +4343,7 @@ mod tests {
             checksum_sha256: "localhash".to_string(),
             metadata: None,
             upload_time: Some(upload_time),
+            yanked: None,
         }];
 
         let out = merge_local_into_remote_simple_json(
@@ -4022,6 +4404,7 @@ mod tests {
             checksum_sha256: "l".to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }];
         let out = merge_local_into_remote_simple_json(upstream.as_bytes(), "v", "p", &local, &[])
             .unwrap();
@@ -4044,6 +4427,7 @@ mod tests {
             checksum_sha256: "h".to_string(),
             metadata: Some(metadata),
             upload_time: None,
+            yanked: None,
         }];
         let tracks = vec!["https://pypi.org/simple/pkg/".to_string()];
 
@@ -4485,6 +4869,19 @@ mod tests {
         assert!(result.is_none());
     }
 
+    // -----------------------------------------------------------------------
+    // pypi_description_docs
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_pypi_description_docs() {
+        let docs = pypi_description_docs(Some(&serde_json::json!("# Demo\nDoes things.")));
+        assert_eq!(docs.readme.as_deref(), Some("# Demo\nDoes things."));
+        assert!(pypi_description_docs(Some(&serde_json::json!("UNKNOWN"))).is_empty());
+        assert!(pypi_description_docs(Some(&serde_json::json!(""))).is_empty());
+        assert!(pypi_description_docs(None).is_empty());
+    }
+
     // -----------------------------------------------------------------------
     // build_streaming_file_response
     // -----------------------------------------------------------------------
@@ -6101,6 +6498,7 @@ mod tests {
             checksum_sha256: "abc123def456".to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }];
 
         let headers = HeaderMap::new();
@@ -6131,6 +6529,7 @@ mod tests {
             checksum_sha256: "aaa111bbb222".to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }];
 
         let headers = HeaderMap::new();
@@ -6176,6 +6575,7 @@ mod tests {
             checksum_sha256: "deadbeef".to_string(),
             metadata: Some(metadata),
             upload_time: None,
+            yanked: None,
         }];
 
         let headers = HeaderMap::new();
@@ -6205,6 +6605,7 @@ mod tests {
                 checksum_sha256: "aaa".to_string(),
                 metadata: None,
                 upload_time: None,
+                yanked: None,
             },
             SimpleProjectArtifact {
                 path: "pkg-2.0.0.tar.gz".to_string(),
@@ -6213,6 +6614,7 @@ mod tests {
                 checksum_sha256: "bbb".to_string(),
                 metadata: None,
                 upload_time: None,
+                yanked: None,
             },
         ];
 
@@ -6246,6 +6648,7 @@ mod tests {
             checksum_sha256: "abc123".to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }];
 
         let mut headers = HeaderMap::new();
@@ -6310,6 +6713,7 @@ mod tests {
             checksum_sha256: "cafe".to_string(),
             metadata: Some(metadata),
             upload_time: None,
+            yanked: None,
         }];
 
         let mut headers = HeaderMap::new();
@@ -6350,6 +6754,7 @@ mod tests {
             checksum_sha256: "cafe".to_string(),
             metadata: None,
             upload_time: Some(upload_time),
+            yanked: None,
         }];
 
         let mut headers = HeaderMap::new();
@@ -6380,6 +6785,7 @@ mod tests {
             checksum_sha256: "abc".to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }];
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -6410,6 +6816,7 @@ mod tests {
             checksum_sha256: "abc".to_string(),
             metadata: None,
             upload_time: Some(upload_time),
+            yanked: None,
         }];
         let response =
             build_simple_project_response(&HeaderMap::new(), "repo", "pkg", &artifacts, &[])
@@ -6426,6 +6833,81 @@ mod tests {
         );
     }
 
+    fn yanked_artifacts() -> Vec<SimpleProjectArtifact> {
+        vec![
+            SimpleProjectArtifact {
+                path: "pkg-1.0.0.tar.gz".to_string(),
+                version: Some("1.0.0".to_string()),
+                size_bytes: 10,
+                checksum_sha256: "abc".to_string(),
+                metadata: None,
+                upload_time: None,
+                yanked: Some("broken <build>".to_string()),
+            },
+            SimpleProjectArtifact {
+                path: "pkg-1.1.0.tar.gz".to_string(),
+                version: Some("1.1.0".to_string()),
+                size_bytes: 10,
+                checksum_sha256: "def".to_string(),
+                metadata: None,
+                upload_time: None,
+                yanked: Some(String::new()),
+            },
+            SimpleProjectArtifact {
+                path: "pkg-2.0.0.tar.gz".to_string(),
+                version: Some("2.0.0".to_string()),
+                size_bytes: 10,
+                checksum_sha256: "fed".to_string(),
+                metadata: None,
+                upload_time: None,
+                yanked: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_build_simple_project_response_html_emits_data_yanked() {
+        let response = build_simple_project_response(
+            &HeaderMap::new(),
+            "repo",
+            "pkg",
+            &yanked_artifacts(),
+            &[],
+        )
+        .unwrap();
+        let body = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(axum::body::to_bytes(response.into_body(), usize::MAX))
+            .unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+        assert!(html.contains("data-yanked=\"broken &lt;build&gt;\">pkg-1.0.0.tar.gz</a>"));
+        assert!(html.contains("data-yanked=\"\">pkg-1.1.0.tar.gz</a>"));
+        assert!(html.contains("#sha256=fed\">pkg-2.0.0.tar.gz</a>"));
+    }
+
+    #[test]
+    fn test_build_simple_project_response_json_emits_yanked() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "accept",
+            "application/vnd.pypi.simple.v1+json".parse().unwrap(),
+        );
+        let response =
+            build_simple_project_response(&headers, "repo", "pkg", &yanked_artifacts(), &[])
+                .unwrap();
+        let body = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(axum::body::to_bytes(response.into_body(), usize::MAX))
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let files = json["files"].as_array().unwrap();
+        assert_eq!(files[0]["yanked"], "broken <build>");
+        assert_eq!(files[1]["yanked"], true);
+        assert!(files[2].get("yanked").is_none());
+        // Yanked versions stay advertised.
+        assert_eq!(json["versions"].as_array().unwrap().len(), 3);
+    }
+
     // -----------------------------------------------------------------------
     // build_simple_root_response (PEP 503 / PEP 691 root index)
     // -----------------------------------------------------------------------
@@ -6795,6 +7277,7 @@ mod tests {
             checksum_sha256: sha.to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }
     }
 
@@ -7023,6 +7506,7 @@ mod tests {
             checksum_sha256: "ffeeddccbbaa99887766554433221100".to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }];
 
         let merged = merge_local_into_remote_simple_html(&remote, "virt", "pkg", &local, &[]);
@@ -7058,6 +7542,7 @@ mod tests {
                 .to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }];
 
         let merged = merge_local_into_remote_simple_html(&remote, "virt", "pkg", &local, &[]);
@@ -7092,6 +7577,7 @@ mod tests {
             checksum_sha256: "deadbeef".to_string(),
             metadata: Some(metadata),
             upload_time: None,
+            yanked: None,
         }];
 
         let merged = merge_local_into_remote_simple_html(&remote, "virt", "pkg", &local, &[]);
@@ -7118,6 +7604,7 @@ mod tests {
             checksum_sha256: "cafebabe".to_string(),
             metadata: None,
             upload_time: None,
+            yanked: None,
         }];
 
         let merged = merge_local_into_remote_simple_html(&remote, "virt", "pkg", &local, &[]);
@@ -7580,7 +8067,7 @@ mod tests {
         //
         // This guards the wiring structurally (DB-free, runs in the offline lib
         // suite): the virtual branch of serve_file MUST authorize the member
-        // list returned by fetch_virtual_members before iterating members, so a
+        // list returned by fetch_virtual_members_for_path before iterating members, so a
         // private member behaves exactly as a 404 for a caller who could not
         // read it directly (its existence is never leaked).
         let src = include_str!("pypi.rs");
@@ -7594,8 +8081,8 @@ mod tests {
         let body = &src[fn_start..next];
 
         let fetch_pos = body
-            .find("fetch_virtual_members(")
-            .expect("serve_file virtual branch must fetch members");
+            .find("fetch_virtual_members_for_path(")
+            .expect("serve_file virtual branch must fetch routed members");
         let authz_pos = body.find("authorize_virtual_members(").expect(
             "serve_file MUST authorize virtual members per-caller before serving \
              any member's bytes (#2073)",
@@ -0,0 +1,117 @@
+//! Offline mode for remote (pull-through proxy) repositories.
+//!
+//! `PUT /repositories/:key/offline` stops a remote repository from contacting
+//! its upstream: cached artifacts keep being served past their TTL and
+//! anything uncached is a 404. `GET` reports the current state. See
+//! `services::remote_repo_service`.
+
+use axum::{
+    extract::{Extension, Path, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::{
+    require_repo_admin, require_repo_write_access, require_visible,
+};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::remote_repo_service::{RemoteOfflineState, RemoteRepoService};
+use crate::services::repository_service::RepositoryService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_offline_mode, set_offline_mode),
+    components(schemas(SetOfflineModeRequest, RemoteOfflineState)),
+    tags((name = "remote-repositories", description = "Offline mode for remote proxy repositories"))
+)]
+pub struct RemoteRepositoriesApiDoc;
+
+/// Create remote-repository routes (nested under /api/v1/repositories).
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/:key/offline", get(get_offline_mode).put(set_offline_mode))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetOfflineModeRequest {
+    pub offline: bool,
+}
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Get the offline state of a remote repository
+#[utoipa::path(
+    get,
+    path = "/{key}/offline",
+    context_path = "/api/v1/repositories",
+    tag = "remote-repositories",
+    params(("key" = String, Path, description = "Repository key")),
+    responses(
+        (status = 200, description = "Current offline state", body = RemoteOfflineState),
+        (status = 400, description = "Not a remote repository"),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_offline_mode(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<RemoteOfflineState>> {
+    let auth = require_auth(auth)?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &Some(auth), &repo_service).await?;
+
+    Ok(Json(
+        RemoteRepoService::new(state.db.clone())
+            .offline_state(&repo)
+            .await?,
+    ))
+}
+
+/// Take a remote repository offline or bring it back online
+#[utoipa::path(
+    put,
+    path = "/{key}/offline",
+    context_path = "/api/v1/repositories",
+    tag = "remote-repositories",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = SetOfflineModeRequest,
+    responses(
+        (status = 200, description = "Offline state updated", body = RemoteOfflineState),
+        (status = 400, description = "Not a remote repository"),
+        (status = 403, description = "Repository admin permission required"),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn set_offline_mode(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(body): Json<SetOfflineModeRequest>,
+) -> Result<Json<RemoteOfflineState>> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    let updated = RemoteRepoService::new(state.db.clone())
+        .set_offline(&repo, body.offline)
+        .await?;
+    tracing::info!(
+        repository = %repo.key,
+        offline = body.offline,
+        user = %auth.username,
+        "Remote repository offline mode changed"
+    );
+    Ok(Json(updated))
+}
@@ -14,7 +14,9 @@ use std::time::Duration;
 use utoipa::{IntoParams, OpenApi, ToSchema};
 use uuid::Uuid;
 
-use crate::api::download_response::{DownloadResponse, X_ARTIFACT_STORAGE};
+use crate::api::download_response::{
+    download_content_disposition, download_content_type, DownloadResponse, X_ARTIFACT_STORAGE,
+};
 use crate::api::dto::Pagination;
 // Use the crate-local `Json` extractor so any deserialization failure on a
 // request body surfaces as HTTP 400 + `{code: "VALIDATION_ERROR"}` instead of
@@ -31,14 +33,16 @@ use crate::formats::debian::{DebianConfigPatch, DebianRepositoryConfig, DEBIAN_C
 use crate::formats::maven::MavenHandler;
 use crate::models::access_scope::AccessScope;
 use crate::models::repository::{RepositoryFormat, RepositoryType};
-use crate::services::artifact_service::ArtifactService;
+use crate::services::artifact_service::{ArtifactService, DigestAlgorithm};
 use crate::services::audit_export::details as audit_details;
 use crate::services::audit_service::{
     audit_fire_and_forget, AuditAction, AuditEntry, ResourceType,
 };
+use crate::services::bootstrap_service;
 use crate::services::cache_classifier;
 use crate::services::permission_service::{SYSTEM_SENTINEL_ID, SYSTEM_TARGET_TYPE};
 use crate::services::proxy_service::DEFAULT_CACHE_TTL_SECS;
+use crate::services::repository_alias_service::RepositoryAliasService;
 use crate::services::repository_service::{
     derive_format_key, CreateRepositoryRequest as ServiceCreateRepoReq, RepoVisibility,
     RepositoryService, UpdateRepositoryRequest as ServiceUpdateRepoReq,
@@ -46,6 +50,7 @@ use crate::services::repository_service::{
 use crate::services::routing_rules::{self, RoutingRule};
 use crate::services::signing_service::SigningService;
 use crate::services::upload_service;
+use crate::services::virtual_member_routing::{self, MemberPathPatterns};
 
 /// Require that the request is authenticated, returning an error if not.
 fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
@@ -162,11 +167,12 @@ fn repo_fine_grained_action_allowed(
 /// already enforces, so the action actually maps to the granted permission.
 ///
 /// `action` is `"write"` for uploads and `"delete"` for deletes. Admins bypass;
-/// a repository with no permission rules falls through unchanged (the rules-less
-/// public-repo case is a separate global default-access decision, out of scope
-/// here). A permission-rule lookup error fails closed (503), mirroring
-/// `repo_visibility_middleware` and `create_session`.
-async fn require_repo_fine_grained_action(
+/// a repository with no permission rules falls through to the external
+/// authorization hook only (the rules-less public-repo case is a separate
+/// global default-access decision, out of scope here). A permission-rule lookup
+/// or hook error fails closed (503), mirroring `repo_visibility_middleware` and
+/// `create_session`.
+pub(crate) async fn require_repo_fine_grained_action(
     auth: &AuthExtension,
     repo_id: Uuid,
     action: &str,
@@ -182,18 +188,27 @@ async fn require_repo_fine_grained_action(
             tracing::error!("permission check failed: database unreachable");
             AppError::ServiceUnavailable("permission service temporarily unavailable".to_string())
         })?;
-    if !has_rules {
-        return Ok(());
+    if has_rules {
+        let has_action = permission_service
+            .check_permission(auth.user_id, "repository", repo_id, action, false)
+            .await
+            .unwrap_or(false);
+        let has_admin = permission_service
+            .check_permission(auth.user_id, "repository", repo_id, "admin", false)
+            .await
+            .unwrap_or(false);
+        if !repo_fine_grained_action_allowed(auth.is_admin, has_rules, has_action, has_admin) {
+            return Err(AppError::Authorization(
+                "You do not have permission to perform this action on this repository".to_string(),
+            ));
+        }
     }
-    let has_action = permission_service
-        .check_permission(auth.user_id, "repository", repo_id, action, false)
-        .await
-        .unwrap_or(false);
-    let has_admin = permission_service
-        .check_permission(auth.user_id, "repository", repo_id, "admin", false)
-        .await
-        .unwrap_or(false);
-    if repo_fine_grained_action_allowed(auth.is_admin, has_rules, has_action, has_admin) {
+    // The external authorization hook (if configured) can still refuse what
+    // the grants allowed; REST operations carry no native path.
+    if permission_service
+        .authorize_external(Some(auth.authz_user()), action, repo_id, None)
+        .await?
+    {
         Ok(())
     } else {
         Err(AppError::Authorization(
@@ -504,6 +519,11 @@ pub fn router() -> Router<SharedState> {
                 .put(update_virtual_members),
         )
         .route("/:key/members/:member_key", delete(remove_virtual_member))
+        // Per-member include/exclude path routing for virtual repositories
+        .route(
+            "/:key/members/:member_key/routing",
+            put(set_virtual_member_routing),
+        )
         // Artifact routes nested under repository
         .route(
             "/:key/artifacts",
@@ -524,6 +544,28 @@ pub fn router() -> Router<SharedState> {
         .merge(super::security::repo_security_router())
         // Label routes nested under repository
         .merge(super::repository_labels::repo_labels_router())
+        // Required upload properties (metadata schema) nested under repository
+        .merge(super::repository_metadata_schemas::router())
+        // Labels and policies pushed by replicating peers
+        .merge(super::repository_replicated_config::router())
+        // Version yank / deprecation routes nested under repository
+        .merge(super::version_deprecations::router())
+        // Change feed nested under repository
+        .merge(super::repository_events::router())
+        // Lockfile-driven cache warming nested under repository
+        .merge(super::cache_warming::router())
+        // Key renames and the old-key redirects they leave behind
+        .merge(super::repository_aliases::router())
+        // Offline mode for remote (proxy) repositories
+        .merge(super::remote_repositories::router())
+        // Provider-side WORM retention for compliance repositories
+        .merge(super::repository_worm::router())
+        // Artifact naming/version parsing rules
+        .merge(super::repository_version_rules::router())
+        // Deletion protection for artifacts still in use
+        .merge(super::repository_delete_protection::router())
+        // Dashboard statistics rollup
+        .merge(super::repository_stats::router())
         // Token management routes nested under repository
         .merge(super::repo_tokens::repo_tokens_router())
         // Email subscription routes nested under repository (#920 replacement
@@ -1074,7 +1116,7 @@ async fn with_debian_config(
 }
 
 /// Validate that a repository key is safe and well-formed.
-fn validate_repository_key(key: &str) -> Result<()> {
+pub(crate) fn validate_repository_key(key: &str) -> Result<()> {
     if key.is_empty() || key.len() > 128 {
         return Err(AppError::Validation(
             "Repository key must be between 1 and 128 characters".to_string(),
@@ -2457,12 +2499,16 @@ pub async fn create_repository(
         validate_debian_config(cfg)?;
     }
 
-    // Resolve storage backend: use the requested one or fall back to the default.
+    // Resolve storage backend: use the requested one or fall back to the
+    // default. The setup wizard may have recorded a different default than
+    // `STORAGE_BACKEND`; it only applies while that backend is still available.
+    let default_backend = bootstrap_service::stored_default_backend(&state.db)
+        .await?
+        .filter(|backend| state.storage_registry.is_available(backend))
+        .unwrap_or_else(|| state.config.storage_backend.clone());
     let storage_backend = match &payload.storage_backend {
-        None => state.config.storage_backend.clone(),
-        Some(requested) if requested == &state.config.storage_backend => {
-            state.config.storage_backend.clone()
-        }
+        None => default_backend,
+        Some(requested) if requested == &default_backend => default_backend,
         Some(requested) => {
             // Non-admin users cannot choose a non-default backend
             if !auth.is_admin {
@@ -2717,6 +2763,7 @@ pub async fn create_repository(
                 visibility: Some(if repo.is_public { "public" } else { "private" }.to_owned()),
                 age_gate_enabled: None,
                 age_gate_min_age_days: None,
+                previous_key: None,
             }),
     )
     .await;
@@ -3114,11 +3161,24 @@ pub async fn update_repository(
         );
     }
 
+    // A key change goes through the rename path so the old key keeps
+    // redirecting and in-flight uploads follow the repository.
+    if let Some(new_key) = payload.key.as_deref().filter(|k| *k != existing.key) {
+        RepositoryAliasService::new(state.db.clone())
+            .rename(
+                existing.id,
+                new_key,
+                state.config.repository_key_alias_ttl_days,
+                Some(auth.user_id),
+            )
+            .await?;
+    }
+
     let repo = service
         .update(
             existing.id,
             ServiceUpdateRepoReq {
-                key: payload.key,
+                key: None,
                 name: payload.name,
                 description: payload.description,
                 is_public: effective_is_public,
@@ -3394,6 +3454,7 @@ pub async fn update_repository(
                 visibility: Some(if repo.is_public { "public" } else { "private" }.to_owned()),
                 age_gate_enabled: None,
                 age_gate_min_age_days: None,
+                previous_key: (existing.key != repo.key).then(|| existing.key.clone()),
             }),
     )
     .await;
@@ -3566,6 +3627,10 @@ async fn purge_oci_upload_temp_objects(
 /// `oci_tags`/`oci_manifest_refs`/`oci_blobs` rows CASCADE away, any object
 /// they alone referenced becomes orphaned and is reclaimed on the next GC pass.
 ///
+/// Shared CAS objects (`cas/sha256/…`, storage dedup) are excluded for the
+/// same reason: their `storage_refs` rows cascade away with the artifacts and
+/// storage GC deletes the blob once no repository references it.
+///
 /// Never blocks the delete: storage-resolution and per-object failures are
 /// logged and swallowed.
 ///
@@ -3607,6 +3672,7 @@ async fn collect_repo_artifact_object_keys(
          WHERE a.repository_id = $1 \
            AND a.storage_key NOT LIKE 'oci-manifests/%' \
            AND a.storage_key NOT LIKE 'oci-blobs/%' \
+           AND a.storage_key NOT LIKE 'cas/sha256/%' \
            AND NOT EXISTS ( \
                SELECT 1 FROM artifacts b \
                WHERE b.storage_key = a.storage_key AND b.repository_id <> $1 \
@@ -3932,6 +3998,7 @@ pub async fn delete_repository(
                 visibility: Some(if repo.is_public { "public" } else { "private" }.to_owned()),
                 age_gate_enabled: None,
                 age_gate_min_age_days: None,
+                previous_key: None,
             }),
     )
     .await;
@@ -4132,6 +4199,12 @@ pub struct DockerTagResponse {
     ///   `completed` label.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scan_status: Option<String>,
+    /// Platforms an image index provides (`os/arch[/variant]`, e.g.
+    /// `linux/arm64/v8`), from the child descriptors recorded in
+    /// `oci_manifest_refs`. Empty for single-platform images. Attestation
+    /// entries (`unknown/unknown`) are omitted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub platforms: Vec<String>,
 }
 
 /// A Maven component grouped by GAV (groupId, artifactId, version).
@@ -4284,6 +4357,7 @@ pub async fn list_artifacts(
     let after_path = keyset.as_ref().map(|(path, _)| path.as_str());
     let offset = if after_path.is_some() { 0 } else { offset };
     let fetch = i64::from(per_page) + 1;
+    let mut member_routes = None;
 
     let (mut artifacts, exact_total) = if repo.repo_type == RepositoryType::Virtual {
         // For virtual repositories, aggregate artifacts from all member repos.
@@ -4297,6 +4371,13 @@ pub async fn list_artifacts(
             })?;
 
         let member_ids: Vec<uuid::Uuid> = members.iter().map(|m| m.id).collect();
+        member_routes = Some(
+            proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id)
+                .await
+                .map_err(|_| {
+                    AppError::Internal("Failed to resolve virtual member routing".to_string())
+                })?,
+        );
 
         let page_rows = artifact_service
             .list_for_repos_page(
@@ -4355,6 +4436,12 @@ pub async fn list_artifacts(
     } else {
         None
     };
+    // Drop rows a member's routing patterns keep it from serving. This runs
+    // after the cursor is taken so paging still walks the unfiltered keyset;
+    // a page can come back short but never skips or repeats a row.
+    if let Some(routes) = member_routes.as_ref() {
+        artifacts.retain(|a| routes.allows(a.repository_id, &a.path));
+    }
     let total = grouped_listing_total(exact_total, offset, artifacts.len(), has_more);
     let total_pages = grouped_total_pages(total, per_page);
 
@@ -5180,10 +5267,17 @@ async fn list_artifacts_grouped_by_maven_component(
             .map_err(|_| {
                 AppError::Internal("Failed to resolve virtual repository members".to_string())
             })?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id)
+            .await
+            .map_err(|_| {
+                AppError::Internal("Failed to resolve virtual member routing".to_string())
+            })?;
         let member_ids: Vec<uuid::Uuid> = members.iter().map(|m| m.id).collect();
-        artifact_service
+        let (mut rows, total_files) = artifact_service
             .list_for_repos(&member_ids, path_prefix, search_query, 0, MAX_FETCH)
-            .await?
+            .await?;
+        rows.retain(|a| routes.allows(a.repository_id, &a.path));
+        (rows, total_files)
     } else {
         artifact_service
             .list(repo.id, path_prefix, search_query, 0, MAX_FETCH)
@@ -5598,17 +5692,32 @@ async fn list_artifacts_grouped_by_docker_tag(
         .map(|r| r.manifest_digest.clone())
         .collect();
 
-    let child_sizes = if index_digests.is_empty() {
-        std::collections::HashMap::new()
+    let (child_sizes, mut platforms) = if index_digests.is_empty() {
+        (
+            std::collections::HashMap::new(),
+            std::collections::HashMap::new(),
+        )
     } else {
-        fetch_index_child_sizes(&state.db, repo.id, &index_digests).await?
+        (
+            fetch_index_child_sizes(&state.db, repo.id, &index_digests).await?,
+            fetch_index_platforms(&state.db, repo.id, &index_digests).await?,
+        )
     };
 
     // Rows arrive in (image, tag) order straight from the keyset index; no
     // in-memory re-sort or slicing is needed.
     let docker_tags: Vec<DockerTagResponse> = rows
         .into_iter()
-        .map(|row| build_docker_tag_response(row, repo_key, &child_sizes))
+        .map(|row| {
+            let mut tag = build_docker_tag_response(row, repo_key, &child_sizes);
+            if tag.is_index {
+                tag.platforms = platforms
+                    .get_mut(&tag.manifest_digest)
+                    .map(std::mem::take)
+                    .unwrap_or_default();
+            }
+            tag
+        })
         .collect();
 
     let exact_total = if count_exact {
@@ -5909,6 +6018,46 @@ async fn fetch_index_child_sizes(
     Ok(out)
 }
 
+/// Platforms provided by each image index, keyed by index digest, from the
+/// per-child descriptors recorded in `oci_manifest_refs` (migration 194).
+/// Children recorded before descriptor tracking, or without a `platform`
+/// object, contribute nothing.
+async fn fetch_index_platforms(
+    db: &sqlx::PgPool,
+    repository_id: Uuid,
+    index_digests: &[String],
+) -> Result<std::collections::HashMap<String, Vec<String>>> {
+    let rows: Vec<(String, String, String, Option<String>)> = sqlx::query_as(
+        r#"SELECT DISTINCT
+                r.parent_digest,
+                r.platform_os,
+                r.platform_architecture,
+                r.platform_variant
+            FROM oci_manifest_refs r
+            WHERE r.repository_id = $1
+              AND r.parent_digest = ANY($2)
+              AND r.platform_os IS NOT NULL
+              AND r.platform_architecture IS NOT NULL
+              AND r.platform_os <> 'unknown'
+            ORDER BY 1, 2, 3, 4 NULLS FIRST"#,
+    )
+    .bind(repository_id)
+    .bind(index_digests)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let mut out: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
+    for (parent, os, architecture, variant) in rows {
+        let platform = match variant {
+            Some(variant) => format!("{}/{}/{}", os, architecture, variant),
+            None => format!("{}/{}", os, architecture),
+        };
+        out.entry(parent).or_default().push(platform);
+    }
+    Ok(out)
+}
+
 /// Convert a fetched `DockerTagRow` into the response shape.
 ///
 /// Folds in the per-index child total (if any) so multi-arch tags
@@ -5945,6 +6094,7 @@ fn build_docker_tag_response(
         is_index,
         last_pushed_at: row.last_pushed_at,
         scan_status: row.scan_status,
+        platforms: Vec::new(),
     }
 }
 
@@ -6173,10 +6323,8 @@ pub async fn get_artifact_metadata(
             }
         }
 
-        let ct = result
-            .content_type
-            .unwrap_or_else(|| "application/octet-stream".to_string());
-        let filename = path.rsplit('/').next().unwrap_or(&path);
+        let ct = download_content_type(&repo.format, &path, result.content_type.as_deref());
+        let disposition = download_content_disposition(&ct, download_filename(&path));
 
         // Stream the member's artifact body straight through instead of
         // buffering it; Content-Length comes from the resolved member's
@@ -6184,10 +6332,7 @@ pub async fn get_artifact_metadata(
         let mut builder = Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, ct)
-            .header(
-                header::CONTENT_DISPOSITION,
-                format!("attachment; filename=\"{}\"", filename),
-            )
+            .header(header::CONTENT_DISPOSITION, disposition)
             .header(
                 header::HeaderName::from_static(X_ARTIFACT_STORAGE),
                 "virtual",
@@ -6283,6 +6428,12 @@ pub async fn list_artifact_versions(
 }
 
 /// Upload artifact
+///
+/// When `X-Checksum-Sha256` (or `X-Checksum-Sha512` / `X-Checksum-Blake3`)
+/// and `Content-Length` identify content this repository already stores, the
+/// artifact is created from the stored blob without reading the body. Any
+/// upload whose content was already stored answers with
+/// `x-ak-deduplicated: true`.
 #[utoipa::path(
     put,
     path = "/{key}/artifacts/{path}",
@@ -6324,6 +6475,12 @@ pub async fn upload_artifact(
 
     let (repo_service, repo) = authorize_generic_upload(&state, &auth, &key).await?;
 
+    if let Some(response) =
+        try_deduplicated_upload(&state, &auth, &repo_service, &repo, &key, &path, &headers).await?
+    {
+        return Ok(response);
+    }
+
     // Stream the request body straight to a bounded scratch file, computing
     // SHA-256/SHA-1/MD5 in a single pass — the whole artifact is never buffered
     // in memory (#2517). The stager enforces `max_upload_size_bytes` mid-stream
@@ -6348,7 +6505,7 @@ pub async fn upload_artifact(
 /// Authorize a generic artifact write: resolve the repository and enforce the
 /// write gates shared by the raw-`PUT` and multipart upload entry points, before
 /// any request body is consumed.
-async fn authorize_generic_upload(
+pub(crate) async fn authorize_generic_upload(
     state: &SharedState,
     auth: &AuthExtension,
     key: &str,
@@ -6387,11 +6544,12 @@ async fn authorize_generic_upload(
 ///
 /// The raw-`PUT` and multipart entry points authorize the request and stream the
 /// body to a bounded scratch file (computing the content digests in one pass);
-/// this shared tail verifies declared checksums, runs any WASM format plugin,
-/// derives the artifact coordinates, and persists via the streaming service
-/// method — never buffering the whole artifact in memory.
+/// this shared tail verifies declared checksums, applies the archive content
+/// policy, runs any WASM format plugin, derives the artifact coordinates, and
+/// persists via the streaming service method — never buffering the whole
+/// artifact in memory.
 #[allow(clippy::too_many_arguments)]
-async fn persist_generic_staged_upload(
+pub(crate) async fn persist_generic_staged_upload(
     state: &SharedState,
     auth: &AuthExtension,
     repo_service: &RepositoryService,
@@ -6417,14 +6575,24 @@ async fn persist_generic_staged_upload(
         declared_md5,
     )
     .map_err(|e| e.into_response())?;
+    for algorithm in [DigestAlgorithm::Sha512, DigestAlgorithm::Blake3] {
+        let declared = headers
+            .get(algorithm.header())
+            .and_then(|v| v.to_str().ok());
+        ArtifactService::verify_declared_digest(&digests, algorithm, declared)
+            .map_err(|e| e.into_response())?;
+    }
 
     let storage = state
         .storage_for_repo(&repo.storage_location())
         .map_err(|e| e.into_response())?;
     let artifact_service = state.create_artifact_service(storage);
 
-    // Extract name from path
-    let name = path.split('/').next_back().unwrap_or(&path).to_string();
+    // Archive content policy: reject bombs, deep nesting and zip-slip members
+    // before a WASM plugin or a downstream client unpacks the body.
+    crate::util::archive_policy::inspect_staged_upload(staged.path())
+        .await
+        .map_err(|e| e.into_response())?;
 
     // Check if this repo has a WASM plugin format handler
     let format_key = repo_service
@@ -6473,50 +6641,15 @@ async fn persist_generic_staged_upload(
         }
     }
 
-    // Use WASM-extracted metadata if available, otherwise try to derive
-    // name and version from the path segments (e.g. "pkg/v1/file.txt").
-    let (name, version) = if let Some(ref meta) = wasm_metadata {
-        (name, meta.version.clone())
-    } else {
-        let segments: Vec<&str> = path.split('/').collect();
-        if segments.len() >= 3 {
-            // Path follows {package_name}/{version}/{filename...} convention
-            (segments[0].to_string(), Some(segments[1].to_string()))
-        } else {
-            (name, None)
-        }
-    };
-
-    // #2367: on versioning-enabled Generic/Mlmodel repos an explicit
-    // `X-Artifact-Version` header supplies the human version label for the
-    // appended revision, taking precedence over the path-derived guess.
-    // Gated on the opt-in so non-versioned repos keep their exact semantics.
-    let versioning_active = crate::services::artifact_service::versioning_applies(
-        &repo.format,
-        repo.versioning_enabled,
+    let version_rules =
+        crate::services::version_rules_service::load_rule_set(&state.db, repo.id).await;
+    let coords = GenericUploadCoordinates::resolve(
+        repo,
+        &path,
+        headers,
+        wasm_metadata.as_ref(),
+        version_rules.as_ref(),
     );
-    let version = if versioning_active {
-        headers
-            .get("x-artifact-version")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .or(version)
-    } else {
-        version
-    };
-
-    // Content-Type resolution priority:
-    //   1. WASM plugin metadata (format-aware)
-    //   2. the request's declared Content-Type header (honour the client)
-    //   3. mime_guess from the path extension
-    let declared_content_type = headers
-        .get(header::CONTENT_TYPE)
-        .and_then(|v| v.to_str().ok());
-    let content_type = wasm_metadata
-        .as_ref()
-        .map(|m| m.content_type.clone())
-        .unwrap_or_else(|| resolve_upload_content_type(declared_content_type, &path));
 
     // No pre-cleanup here: this generic upload endpoint persists through
     // `artifact_service::upload_stream_with_sync_options`, whose
@@ -6531,13 +6664,13 @@ async fn persist_generic_staged_upload(
 
     let size_bytes = staged.size_bytes();
     let content_stream = proxy_helpers::open_staged_upload_stream(&staged).await?;
-    let artifact = artifact_service
-        .upload_stream_with_sync_options(
+    let (artifact, deduplicated) = artifact_service
+        .upload_stream_with_outcome(
             repo.id,
             &path,
-            &name,
-            version.as_deref(),
-            &content_type,
+            &coords.name,
+            coords.version.as_deref(),
+            &coords.content_type,
             content_stream,
             digests,
             size_bytes,
@@ -6549,11 +6682,241 @@ async fn persist_generic_staged_upload(
     // Scratch file no longer needed once the service has consumed the stream.
     drop(staged);
 
+    let metadata_json = wasm_metadata.map(|m| m.to_json());
+    generic_upload_response(
+        &artifact_service,
+        repo,
+        key,
+        artifact,
+        metadata_json,
+        coords.versioning_active,
+        deduplicated,
+    )
+    .await
+}
+
+/// Name, version and content type a generic upload is stored under.
+struct GenericUploadCoordinates {
+    name: String,
+    version: Option<String>,
+    content_type: String,
+    versioning_active: bool,
+}
+
+impl GenericUploadCoordinates {
+    fn resolve(
+        repo: &crate::models::repository::Repository,
+        path: &str,
+        headers: &HeaderMap,
+        wasm_metadata: Option<&crate::services::wasm_runtime::WasmMetadata>,
+        version_rules: Option<&crate::services::version_rules_service::VersionRuleSet>,
+    ) -> Self {
+        // Extract name from path
+        let name = path.split('/').next_back().unwrap_or(path).to_string();
+
+        // Use WASM-extracted metadata if available, otherwise try to derive
+        // name and version from the path segments (e.g. "pkg/v1/file.txt").
+        let (name, version) = if let Some(meta) = wasm_metadata {
+            (name, meta.version.clone())
+        } else {
+            let segments: Vec<&str> = path.split('/').collect();
+            let (name, version) = if segments.len() >= 3 {
+                // Path follows {package_name}/{version}/{filename...} convention
+                (segments[0].to_string(), Some(segments[1].to_string()))
+            } else {
+                (name, None)
+            };
+            // The repository's own naming rules take precedence over the
+            // convention; a rule without a name keeps the convention's.
+            match version_rules.and_then(|rules| rules.extract(path)) {
+                Some(extracted) => (extracted.name.unwrap_or(name), Some(extracted.version)),
+                None => (name, version),
+            }
+        };
+
+        // #2367: on versioning-enabled Generic/Mlmodel repos an explicit
+        // `X-Artifact-Version` header supplies the human version label for the
+        // appended revision, taking precedence over the path-derived guess.
+        // Gated on the opt-in so non-versioned repos keep their exact semantics.
+        let versioning_active = crate::services::artifact_service::versioning_applies(
+            &repo.format,
+            repo.versioning_enabled,
+        );
+        let version = if versioning_active {
+            headers
+                .get("x-artifact-version")
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .or(version)
+        } else {
+            version
+        };
+
+        // Content-Type resolution priority:
+        //   1. WASM plugin metadata (format-aware)
+        //   2. the request's declared Content-Type header (honour the client)
+        //   3. mime_guess from the path extension
+        let declared_content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok());
+        let content_type = wasm_metadata
+            .map(|m| m.content_type.clone())
+            .unwrap_or_else(|| resolve_upload_content_type(declared_content_type, path));
+
+        Self {
+            name,
+            version,
+            content_type,
+            versioning_active,
+        }
+    }
+}
+
+/// Response header set on a 201 when the uploaded content was already
+/// stored and the storage write was skipped.
+pub(crate) const DEDUPLICATED_HEADER: &str = "x-ak-deduplicated";
+
+/// Collision-resistant digests a dedup fast path may be keyed on, in order
+/// of preference. SHA-1 and MD5 are only ever checked, never used as a key.
+const DEDUP_KEY_ALGORITHMS: [DigestAlgorithm; 3] = [
+    DigestAlgorithm::Sha256,
+    DigestAlgorithm::Sha512,
+    DigestAlgorithm::Blake3,
+];
+
+/// Declared digest of a raw upload when the client also sent a usable
+/// `Content-Length`; the pair a dedup fast path can be decided on.
+fn declared_upload_identity(headers: &HeaderMap) -> Option<(DigestAlgorithm, String, i64)> {
+    let (algorithm, digest) = DEDUP_KEY_ALGORITHMS.into_iter().find_map(|algorithm| {
+        headers
+            .get(algorithm.header())
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| algorithm.is_valid_hex(v))
+            .map(|v| (algorithm, v))
+    })?;
+    let size = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|size| *size > 0)?;
+    Some((algorithm, digest, size))
+}
+
+/// Whether every declared `x-checksum-*` header agrees with the stored
+/// digests. A declared digest the row does not carry yet cannot be checked,
+/// so it fails the match and the body is uploaded and verified instead.
+fn declared_digests_match(
+    headers: &HeaderMap,
+    digests: &crate::services::artifact_service::ContentDigests,
+) -> bool {
+    DigestAlgorithm::ALL.into_iter().all(|algorithm| {
+        match headers
+            .get(algorithm.header())
+            .and_then(|v| v.to_str().ok())
+        {
+            Some(declared) => digests
+                .get(algorithm)
+                .is_some_and(|stored| declared.trim().eq_ignore_ascii_case(stored)),
+            None => true,
+        }
+    })
+}
+
+/// "Already exists" fast path for raw uploads.
+///
+/// When the client declares `X-Checksum-Sha256` (and `Content-Length`) for
+/// content this repository already stores, the artifact is created from the
+/// stored blob and the body is never read; a client that sent
+/// `Expect: 100-continue` never transfers it at all. Returns `None` when the
+/// regular upload path must run.
+#[allow(clippy::too_many_arguments)]
+async fn try_deduplicated_upload(
+    state: &SharedState,
+    auth: &AuthExtension,
+    repo_service: &RepositoryService,
+    repo: &crate::models::repository::Repository,
+    key: &str,
+    path: &str,
+    headers: &HeaderMap,
+) -> std::result::Result<Option<Response>, Response> {
+    let Some((algorithm, digest, size_bytes)) = declared_upload_identity(headers) else {
+        return Ok(None);
+    };
+
+    // WASM format plugins validate and parse the body itself.
+    let format_key = repo_service
+        .get_format_key(repo.id)
+        .await
+        .map_err(|e| e.into_response())?;
+    if let (Some(ref fk), Some(ref registry)) = (&format_key, &state.plugin_registry) {
+        if registry.has_format(fk).await {
+            return Ok(None);
+        }
+    }
+
+    let storage = state
+        .storage_for_repo(&repo.storage_location())
+        .map_err(|e| e.into_response())?;
+    let artifact_service = state.create_artifact_service(storage);
+    let Some(digests) = artifact_service
+        .find_existing_content_by(repo.id, algorithm, &digest, size_bytes)
+        .await
+        .map_err(|e| e.into_response())?
+    else {
+        return Ok(None);
+    };
+    if !declared_digests_match(headers, &digests) {
+        return Ok(None);
+    }
+
+    let version_rules =
+        crate::services::version_rules_service::load_rule_set(&state.db, repo.id).await;
+    let coords =
+        GenericUploadCoordinates::resolve(repo, path, headers, None, version_rules.as_ref());
+    let artifact = artifact_service
+        .upload_existing_content(
+            repo.id,
+            path,
+            &coords.name,
+            coords.version.as_deref(),
+            &coords.content_type,
+            &digests,
+            size_bytes,
+            Some(auth.user_id),
+            !is_replication_request(headers),
+        )
+        .await
+        .map_err(|e| e.into_response())?;
+
+    generic_upload_response(
+        &artifact_service,
+        repo,
+        key.to_string(),
+        artifact,
+        None,
+        coords.versioning_active,
+        true,
+    )
+    .await
+    .map(Some)
+}
+
+/// 201 response for a completed generic upload.
+async fn generic_upload_response(
+    artifact_service: &ArtifactService,
+    repo: &crate::models::repository::Repository,
+    key: String,
+    artifact: crate::models::artifact::Artifact,
+    metadata_json: Option<serde_json::Value>,
+    versioning_active: bool,
+    deduplicated: bool,
+) -> std::result::Result<Response, Response> {
     let downloads = artifact_service
         .get_download_stats(artifact.id)
         .await
         .map_err(|e| e.into_response())?;
-    let metadata_json = wasm_metadata.map(|m| m.to_json());
 
     // #2367: echo the revision this upload landed at for versioned repos.
     let (revision, version_label) = if versioning_active {
@@ -6567,8 +6930,17 @@ async fn persist_generic_staged_upload(
         (None, None)
     };
 
+    let mut response_headers = HeaderMap::new();
+    if deduplicated {
+        response_headers.insert(
+            header::HeaderName::from_static(DEDUPLICATED_HEADER),
+            header::HeaderValue::from_static("true"),
+        );
+    }
+
     Ok((
         StatusCode::CREATED,
+        response_headers,
         Json(ArtifactResponse {
             id: artifact.id,
             repository_key: key,
@@ -7005,6 +7377,22 @@ pub(crate) fn ranged_stream_response(
     total: u64,
     body: futures::stream::BoxStream<'static, Result<Bytes>>,
     base_headers: Vec<(header::HeaderName, String)>,
+) -> Result<Response> {
+    ranged_response(range_header, total, body, base_headers, false)
+}
+
+/// [`ranged_stream_response`] for a body that may already be windowed.
+///
+/// With `windowed` set, a satisfiable range is served from `body` as is:
+/// the caller opened it with
+/// [`crate::storage::StorageBackend::get_range_stream`] for the
+/// same header and total, so slicing again would drop bytes.
+fn ranged_response(
+    range_header: Option<&str>,
+    total: u64,
+    body: futures::stream::BoxStream<'static, Result<Bytes>>,
+    base_headers: Vec<(header::HeaderName, String)>,
+    windowed: bool,
 ) -> Result<Response> {
     let build_base = || {
         let mut b = Response::builder().header(header::ACCEPT_RANGES, "bytes");
@@ -7025,7 +7413,11 @@ pub(crate) fn ranged_stream_response(
                     header::CONTENT_RANGE,
                     format!("bytes {}-{}/{}", start, end, total),
                 )
-                .body(Body::from_stream(slice_byte_stream(body, start, end)))
+                .body(if windowed {
+                    Body::from_stream(body)
+                } else {
+                    Body::from_stream(slice_byte_stream(body, start, end))
+                })
                 .map_err(mk_err)?
         }
         RangeOutcome::Unsatisfiable => Response::builder()
@@ -7050,6 +7442,7 @@ pub(crate) fn ranged_stream_response(
 /// revision does not have). Range requests are honored like the HEAD path.
 async fn download_artifact_version(
     artifact_service: &ArtifactService,
+    format: &RepositoryFormat,
     repo_id: Uuid,
     path: &str,
     selector: &str,
@@ -7063,12 +7456,13 @@ async fn download_artifact_version(
 
     let total = stored.size_bytes.max(0) as u64;
     let checksum = stored.checksum_sha256.trim().to_string();
+    let content_type = download_content_type(format, path, Some(&stored.content_type));
     let base_headers = vec![
-        (header::CONTENT_TYPE, stored.content_type.clone()),
         (
             header::CONTENT_DISPOSITION,
-            format!("attachment; filename=\"{}\"", download_filename(path)),
+            download_content_disposition(&content_type, download_filename(path)),
         ),
+        (header::CONTENT_TYPE, content_type),
         (
             header::HeaderName::from_static("x-checksum-sha256"),
             checksum,
@@ -7197,6 +7591,7 @@ pub async fn download_artifact(
                 .map(|s| s.to_string());
             return download_artifact_version(
                 &artifact_service,
+                &repo.format,
                 repo.id,
                 &path,
                 selector,
@@ -7221,7 +7616,9 @@ pub async fn download_artifact(
     let storage = state.storage_for_repo(&repo.storage_location())?;
     let presigned_enabled = state.config.presigned_downloads_enabled && storage.supports_redirect();
     if presigned_enabled {
-        // Get artifact metadata first using query_as for runtime checking
+        // Get artifact metadata first using query_as for runtime checking.
+        // Artifacts in the cold storage tier have no hot object to sign; they
+        // fall through to the streaming path, which queues the rehydration.
         #[derive(sqlx::FromRow)]
         struct ArtifactRow {
             id: Uuid,
@@ -7230,8 +7627,12 @@ pub async fn download_artifact(
         if let Some(artifact) = sqlx::query_as::<_, ArtifactRow>(
             r#"
             SELECT id, storage_key
-            FROM artifacts
+            FROM artifacts a
             WHERE repository_id = $1 AND path = $2 AND is_deleted = false
+              AND NOT EXISTS (
+                  SELECT 1 FROM artifact_storage_tiers t
+                  WHERE t.artifact_id = a.id AND t.state IN ('cold', 'rehydrating')
+              )
             "#,
         )
         .bind(repo.id)
@@ -7275,6 +7676,10 @@ pub async fn download_artifact(
 
     // Fall back to proxied download (filesystem or S3 without redirect)
     let artifact_service = ArtifactService::new(state.db.clone(), storage);
+    let range_header = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok());
 
     let download_result = artifact_service
         .download_stream(
@@ -7285,6 +7690,11 @@ pub async fn download_artifact(
             user_agent.as_deref(),
             // #2260 §5: a HEAD serves no body, so it must not count.
             !is_head,
+            // A satisfiable `Range` is read from storage as just that window.
+            |total| match parse_byte_range(range_header, total) {
+                RangeOutcome::Satisfiable { start, end } => Some((start, end)),
+                _ => None,
+            },
         )
         .await;
 
@@ -7298,10 +7708,6 @@ pub async fn download_artifact(
             // requested path (e.g. `testpkg-1.0.0.tar.gz`), not the artifact's
             // package name — matching the virtual-repo download path.
             let total = artifact.size_bytes.max(0) as u64;
-            let range_header = request
-                .headers()
-                .get(header::RANGE)
-                .and_then(|v| v.to_str().ok());
             let checksum = artifact.checksum_sha256.trim().to_string();
             // `artifacts.checksum_sha256` is a CHAR(64) column, so Postgres
             // blank-pads shorter values on read; trim before emitting so the
@@ -7310,12 +7716,14 @@ pub async fn download_artifact(
             // Shared range-aware streaming (#1847): one primitive serves the
             // 200 / 206 / 416 cases for both this generic path and the format
             // handlers (e.g. incus image download).
+            let content_type =
+                download_content_type(&repo.format, &path, Some(&artifact.content_type));
             let base_headers = vec![
-                (header::CONTENT_TYPE, artifact.content_type),
                 (
                     header::CONTENT_DISPOSITION,
-                    format!("attachment; filename=\"{}\"", download_filename(&path)),
+                    download_content_disposition(&content_type, download_filename(&path)),
                 ),
+                (header::CONTENT_TYPE, content_type),
                 (
                     header::HeaderName::from_static("x-checksum-sha256"),
                     checksum,
@@ -7325,7 +7733,8 @@ pub async fn download_artifact(
                     "proxy".to_string(),
                 ),
             ];
-            let response = ranged_stream_response(range_header, total, body, base_headers)?;
+            // `body` already holds only the requested window.
+            let response = ranged_response(range_header, total, body, base_headers, true)?;
             Ok(response)
         }
         Err(AppError::NotFound(_)) if repo.repo_type == RepositoryType::Remote => {
@@ -7336,13 +7745,15 @@ pub async fn download_artifact(
                 let fetch_path = routing_rules::apply_routing_rules(&path, &rules)
                     .unwrap_or_else(|| path.clone());
 
+                // Upstreams that send no Content-Type get the format's type.
+                let default_content_type = download_content_type(&repo.format, &path, None);
                 match proxy_helpers::proxy_fetch_streaming(
                     proxy,
                     repo.id,
                     &key,
                     upstream_url,
                     &fetch_path,
-                    "application/octet-stream",
+                    &default_content_type,
                 )
                 .await
                 {
@@ -7439,18 +7850,13 @@ pub async fn download_artifact(
                 }
             }
 
-            let ct = result
-                .content_type
-                .unwrap_or_else(|| "application/octet-stream".to_string());
-            let filename = path.rsplit('/').next().unwrap_or(&path);
+            let ct = download_content_type(&repo.format, &path, result.content_type.as_deref());
+            let disposition = download_content_disposition(&ct, download_filename(&path));
 
             let mut builder = Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, ct)
-                .header(
-                    header::CONTENT_DISPOSITION,
-                    format!("attachment; filename=\"{}\"", filename),
-                )
+                .header(header::CONTENT_DISPOSITION, disposition)
                 .header(
                     header::HeaderName::from_static(X_ARTIFACT_STORAGE),
                     "virtual",
@@ -7493,6 +7899,14 @@ fn delete_blocked_by_immutability(
     !is_admin && !replication_trusted && cache_classifier::classify(format, path).is_immutable()
 }
 
+/// Query parameters of [`delete_artifact`].
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteArtifactQuery {
+    /// Confirm the delete of an artifact the repository's deletion
+    /// protection rule asks confirmation for.
+    pub confirm: Option<bool>,
+}
+
 /// Whether the replication escape hatch on the immutability delete guard may be
 /// honored for this request.
 ///
@@ -7519,19 +7933,21 @@ fn replication_exemption_trusted(
     params(
         ("key" = String, Path, description = "Repository key"),
         ("path" = String, Path, description = "Artifact path"),
+        DeleteArtifactQuery,
     ),
     security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Artifact deleted"),
         (status = 401, description = "Authentication required"),
         (status = 404, description = "Artifact not found"),
-        (status = 409, description = "Artifact is immutable (released) and cannot be deleted"),
+        (status = 409, description = "Artifact is immutable (released), or protected by recent download activity and cannot be deleted"),
     )
 )]
 pub async fn delete_artifact(
     State(state): State<SharedState>,
     Extension(auth): Extension<Option<AuthExtension>>,
     Path((key, path)): Path<(String, String)>,
+    Query(query): Query<DeleteArtifactQuery>,
     headers: HeaderMap,
 ) -> Result<()> {
     let auth = require_auth(auth)?;
@@ -7607,6 +8023,15 @@ pub async fn delete_artifact(
     .map_err(|e| AppError::Database(e.to_string()))?
     .ok_or_else(|| AppError::NotFound("Artifact not found".to_string()))?;
 
+    // Download-activity protection: artifacts production still pulls are
+    // refused, or need `confirm=true`, per the repository's rule. A peer
+    // mirrors a delete its origin already let through.
+    if !replication_trusted {
+        crate::services::delete_protection_service::DeleteProtectionService::new(state.db.clone())
+            .check_delete(repo.id, artifact, query.confirm.unwrap_or(false))
+            .await?;
+    }
+
     artifact_service
         .delete_with_sync_options(artifact, !is_replication)
         .await?;
@@ -7669,6 +8094,12 @@ pub struct VirtualMemberResponse {
     pub member_repo_name: String,
     pub member_repo_type: String,
     pub priority: i32,
+    /// Paths this member may serve through the virtual repository. Empty
+    /// means every path.
+    pub include_patterns: Vec<String>,
+    /// Paths this member must never serve. Takes precedence over
+    /// `include_patterns`.
+    pub exclude_patterns: Vec<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -7687,6 +8118,8 @@ struct VirtualMemberRow {
     member_key: String,
     member_name: String,
     repo_type: RepositoryType,
+    include_patterns: Vec<String>,
+    exclude_patterns: Vec<String>,
 }
 
 /// List virtual repository members
@@ -7739,7 +8172,9 @@ pub async fn list_virtual_members(
             vrm.created_at,
             r.key as member_key,
             r.name as member_name,
-            r.repo_type
+            r.repo_type,
+            vrm.include_patterns,
+            vrm.exclude_patterns
         FROM virtual_repo_members vrm
         INNER JOIN repositories r ON r.id = vrm.member_repo_id
         WHERE vrm.virtual_repo_id = $1
@@ -7819,7 +8254,9 @@ pub async fn add_virtual_member(
             vrm.created_at,
             r.key as member_key,
             r.name as member_name,
-            r.repo_type
+            r.repo_type,
+            vrm.include_patterns,
+            vrm.exclude_patterns
         FROM virtual_repo_members vrm
         INNER JOIN repositories r ON r.id = vrm.member_repo_id
         WHERE vrm.virtual_repo_id = $1 AND vrm.member_repo_id = $2
@@ -7831,15 +8268,7 @@ pub async fn add_virtual_member(
     .await
     .map_err(|e| AppError::Database(e.to_string()))?;
 
-    Ok(Json(VirtualMemberResponse {
-        id: member.id,
-        member_repo_id: member.member_repo_id,
-        member_repo_key: member.member_key,
-        member_repo_name: member.member_name,
-        member_repo_type: format!("{:?}", member.repo_type).to_lowercase(),
-        priority: member.priority,
-        created_at: member.created_at,
-    }))
+    Ok(Json(map_member_row(member)))
 }
 
 /// Remove a member from a virtual repository
@@ -8030,6 +8459,93 @@ pub async fn update_virtual_members(
     list_virtual_members(State(state), Extension(Some(auth)), Path(key)).await
 }
 
+/// Set the include/exclude path patterns of a virtual repository member.
+///
+/// Patterns restrict which paths the member may serve when the virtual
+/// repository resolves a request, e.g. `com/mycorp/*` only from the internal
+/// member and everything else from the proxy member. Sending empty lists
+/// clears the restriction.
+#[utoipa::path(
+    put,
+    path = "/{key}/members/{member_key}/routing",
+    context_path = "/api/v1/repositories",
+    tag = "repositories",
+    params(
+        ("key" = String, Path, description = "Virtual repository key"),
+        ("member_key" = String, Path, description = "Member repository key"),
+    ),
+    request_body = MemberPathPatterns,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Routing patterns updated", body = VirtualMemberResponse),
+        (status = 400, description = "Repository is not virtual, or a pattern is invalid"),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 404, description = "Repository or member not found"),
+    )
+)]
+pub async fn set_virtual_member_routing(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path((key, member_key)): Path<(String, String)>,
+    Json(payload): Json<MemberPathPatterns>,
+) -> Result<Json<VirtualMemberResponse>> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    let service = RepositoryService::new(state.db.clone());
+
+    let virtual_repo = service.get_by_key(&key).await?;
+    // Same ordering as remove_virtual_member: reject non-virtual repos before
+    // the access check so a 403 cannot be used to probe for repo keys.
+    if virtual_repo.repo_type != RepositoryType::Virtual {
+        return Err(AppError::Validation(
+            "Only virtual repositories have members".to_string(),
+        ));
+    }
+    let member_repo = service.get_by_key(&member_key).await?;
+    authorize_virtual_member_mutation(
+        &auth,
+        &virtual_repo,
+        &member_repo,
+        "update",
+        &state.permission_service,
+    )
+    .await?;
+
+    virtual_member_routing::set_member_patterns(
+        &state.db,
+        virtual_repo.id,
+        member_repo.id,
+        &payload,
+    )
+    .await?;
+
+    let member: VirtualMemberRow = sqlx::query_as(
+        r#"
+        SELECT
+            vrm.id,
+            vrm.member_repo_id,
+            vrm.priority,
+            vrm.created_at,
+            r.key as member_key,
+            r.name as member_name,
+            r.repo_type,
+            vrm.include_patterns,
+            vrm.exclude_patterns
+        FROM virtual_repo_members vrm
+        INNER JOIN repositories r ON r.id = vrm.member_repo_id
+        WHERE vrm.virtual_repo_id = $1 AND vrm.member_repo_id = $2
+        "#,
+    )
+    .bind(virtual_repo.id)
+    .bind(member_repo.id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    Ok(Json(map_member_row(member)))
+}
+
 // ---------------------------------------------------------------------------
 // Upstream auth management
 // ---------------------------------------------------------------------------
@@ -8410,6 +8926,7 @@ async fn load_routing_rules(db: &sqlx::PgPool, repo_id: Uuid) -> Vec<RoutingRule
         add_virtual_member,
         remove_virtual_member,
         update_virtual_members,
+        set_virtual_member_routing,
         set_upstream_auth,
         test_upstream,
         get_routing_rules,
@@ -8443,6 +8960,7 @@ async fn load_routing_rules(db: &sqlx::PgPool, repo_id: Uuid) -> Vec<RoutingRule
         AddVirtualMemberRequest,
         UpdateVirtualMembersRequest,
         VirtualMemberPriority,
+        MemberPathPatterns,
         VirtualMemberResponse,
         VirtualMembersListResponse,
         CreateVirtualMemberInput,
@@ -8520,6 +9038,8 @@ fn map_member_row(row: VirtualMemberRow) -> VirtualMemberResponse {
         member_repo_name: row.member_name,
         member_repo_type: format_repo_type(&row.repo_type),
         priority: row.priority,
+        include_patterns: row.include_patterns,
+        exclude_patterns: row.exclude_patterns,
         created_at: row.created_at,
     }
 }
@@ -9990,6 +10510,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generic_upload_coordinates_apply_version_rules() {
+        use crate::services::version_rules_service::{VersionRule, VersionRuleSet};
+        let repo = sample_repo();
+        let rules = VersionRuleSet::compile(&[VersionRule::Layout {
+            template: "firmware/{name}-{version}.bin".to_string(),
+        }])
+        .unwrap();
+
+        let coords = GenericUploadCoordinates::resolve(
+            &repo,
+            "firmware/acme-router-2.4.1.bin",
+            &HeaderMap::new(),
+            None,
+            Some(&rules),
+        );
+        assert_eq!(coords.name, "acme-router");
+        assert_eq!(coords.version.as_deref(), Some("2.4.1"));
+
+        // A path no rule matches keeps the {name}/{version}/{file} convention.
+        let coords = GenericUploadCoordinates::resolve(
+            &repo,
+            "tool/1.0/tool.tgz",
+            &HeaderMap::new(),
+            None,
+            Some(&rules),
+        );
+        assert_eq!(coords.name, "tool");
+        assert_eq!(coords.version.as_deref(), Some("1.0"));
+    }
+
     #[test]
     fn test_repository_response_redacts_key_exposes_only_boolean() {
         // #2568: the response serializes a `has_trusted_gpg_key` boolean and
@@ -10729,6 +11280,7 @@ mod tests {
             is_index: false,
             last_pushed_at: chrono::Utc::now(),
             scan_status: Some("completed".to_string()),
+            platforms: Vec::new(),
         };
         let resp = ArtifactListResponse {
             items: vec![],
@@ -11226,6 +11778,8 @@ mod tests {
             member_repo_name: "Upstream Repo".to_string(),
             member_repo_type: "remote".to_string(),
             priority: 1,
+            include_patterns: vec![],
+            exclude_patterns: vec![],
             created_at: chrono::Utc::now(),
         };
         let json = serde_json::to_string(&resp).unwrap();
@@ -12127,6 +12681,7 @@ mod tests {
             State(state.clone()),
             Extension(auth.clone()),
             Path((key.clone(), path.clone())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await;
@@ -12475,6 +13030,7 @@ mod tests {
             State(state.clone()),
             Extension(auth.clone()),
             Path((key.clone(), path.clone())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await;
@@ -12500,6 +13056,7 @@ mod tests {
             State(state.clone()),
             Extension(admin.clone()),
             Path((key.clone(), path.clone())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await;
@@ -12524,6 +13081,7 @@ mod tests {
             State(state.clone()),
             Extension(auth.clone()),
             Path((key.clone(), path2.clone())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await;
@@ -12570,6 +13128,7 @@ mod tests {
             State(state.clone()),
             Extension(auth.clone()),
             Path((key.clone(), path.clone())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await
@@ -12602,6 +13161,7 @@ mod tests {
             State(state.clone()),
             Extension(auth.clone()),
             Path((key.clone(), meta.clone())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await
@@ -13315,6 +13875,7 @@ mod tests {
             )),
             Extension(auth.clone()),
             Path((key.clone(), path.clone())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await;
@@ -13336,6 +13897,7 @@ mod tests {
             )),
             Extension(auth.clone()),
             Path((key.clone(), path.clone())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await;
@@ -14385,6 +14947,8 @@ mod tests {
                 member_repo_name: "Local Maven".to_string(),
                 member_repo_type: "local".to_string(),
                 priority: 1,
+                include_patterns: vec![],
+                exclude_patterns: vec![],
                 created_at: chrono::Utc::now(),
             }],
         };
@@ -14418,6 +14982,8 @@ mod tests {
                     member_repo_name: "First".to_string(),
                     member_repo_type: "local".to_string(),
                     priority: 1,
+                    include_patterns: vec![],
+                    exclude_patterns: vec![],
                     created_at: chrono::Utc::now(),
                 },
                 VirtualMemberResponse {
@@ -14427,6 +14993,8 @@ mod tests {
                     member_repo_name: "Second".to_string(),
                     member_repo_type: "remote".to_string(),
                     priority: 2,
+                    include_patterns: vec![],
+                    exclude_patterns: vec![],
                     created_at: chrono::Utc::now(),
                 },
             ],
@@ -17270,6 +17838,108 @@ mod tests {
         );
     }
 
+    // ---------------------------------------------------------------------
+    // Upload dedup fast path: only a well-formed declared SHA-256 plus a
+    // positive Content-Length qualifies, and declared SHA-1/MD5 must agree
+    // with the stored digests.
+    // ---------------------------------------------------------------------
+
+    fn dedup_headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_declared_upload_identity_requires_sha256_and_length() {
+        let sha = "AB".repeat(32);
+        let headers = dedup_headers(&[("x-checksum-sha256", &sha), ("content-length", "42")]);
+        assert_eq!(
+            declared_upload_identity(&headers),
+            Some((DigestAlgorithm::Sha256, "ab".repeat(32), 42))
+        );
+
+        let headers = dedup_headers(&[("x-checksum-sha256", &sha)]);
+        assert_eq!(declared_upload_identity(&headers), None);
+        let headers = dedup_headers(&[("x-checksum-sha256", &sha), ("content-length", "0")]);
+        assert_eq!(declared_upload_identity(&headers), None);
+        let headers = dedup_headers(&[("x-checksum-sha256", "abc"), ("content-length", "42")]);
+        assert_eq!(declared_upload_identity(&headers), None);
+        let not_hex = "zz".repeat(32);
+        let headers = dedup_headers(&[("x-checksum-sha256", &not_hex), ("content-length", "42")]);
+        assert_eq!(declared_upload_identity(&headers), None);
+    }
+
+    #[test]
+    fn test_declared_digests_match() {
+        let digests = crate::services::artifact_service::ContentDigests {
+            sha256: "a".repeat(64),
+            sha1: "b".repeat(40),
+            md5: "c".repeat(32),
+            sha512: None,
+            blake3: None,
+        };
+        assert!(declared_digests_match(&HeaderMap::new(), &digests));
+        let sha1 = "B".repeat(40);
+        assert!(declared_digests_match(
+            &dedup_headers(&[("x-checksum-sha1", &sha1)]),
+            &digests
+        ));
+        let wrong_md5 = "d".repeat(32);
+        assert!(!declared_digests_match(
+            &dedup_headers(&[("x-checksum-md5", &wrong_md5)]),
+            &digests
+        ));
+        // A declared digest the row does not carry cannot be verified.
+        let blake3 = "e".repeat(64);
+        assert!(!declared_digests_match(
+            &dedup_headers(&[("x-checksum-blake3", &blake3)]),
+            &digests
+        ));
+        let digests = crate::services::artifact_service::ContentDigests {
+            blake3: Some(blake3.clone()),
+            ..digests
+        };
+        assert!(declared_digests_match(
+            &dedup_headers(&[("x-checksum-blake3", &blake3.to_uppercase())]),
+            &digests
+        ));
+    }
+
+    #[test]
+    fn test_declared_upload_identity_accepts_sha512_and_blake3() {
+        let sha512 = "cd".repeat(64);
+        let headers = dedup_headers(&[("x-checksum-sha512", &sha512), ("content-length", "7")]);
+        assert_eq!(
+            declared_upload_identity(&headers),
+            Some((DigestAlgorithm::Sha512, sha512, 7))
+        );
+        let blake3 = "ef".repeat(32);
+        let headers = dedup_headers(&[("x-checksum-blake3", &blake3), ("content-length", "7")]);
+        assert_eq!(
+            declared_upload_identity(&headers),
+            Some((DigestAlgorithm::Blake3, blake3, 7))
+        );
+        // SHA-256 wins when several are declared; weak digests never key.
+        let sha256 = "ab".repeat(32);
+        let headers = dedup_headers(&[
+            ("x-checksum-blake3", &"ef".repeat(32)),
+            ("x-checksum-sha256", &sha256),
+            ("content-length", "7"),
+        ]);
+        assert_eq!(
+            declared_upload_identity(&headers).map(|(a, _, _)| a),
+            Some(DigestAlgorithm::Sha256)
+        );
+        let headers = dedup_headers(&[
+            ("x-checksum-sha1", &"b".repeat(40)),
+            ("content-length", "7"),
+        ]);
+        assert_eq!(declared_upload_identity(&headers), None);
+    }
+
     // ---------------------------------------------------------------------
     // Multipart staging helpers (#2517): the upload pipeline spools form-file
     // fields to a bounded scratch file, computing SHA-256/SHA-1/MD5 in one
@@ -19087,7 +19757,13 @@ mod tests {
         // priority-2 remote (the genuine dependency-confusion case).
         assert!(
             matches!(
-                proxy_helpers::pypi_virtual_isolates_name(&pool, virtual_id, "acme-sdk").await,
+                proxy_helpers::pypi_virtual_isolates_name(
+                    &pool,
+                    virtual_id,
+                    "acme-sdk",
+                    "simple/acme-sdk/"
+                )
+                .await,
                 Ok(Some(1))
             ),
             "a locally-owned name with no tracks declaration must be isolated \
@@ -19097,7 +19773,8 @@ mod tests {
         // A name the local member does not own -> proxy normally (no isolation).
         assert!(
             matches!(
-                proxy_helpers::pypi_virtual_isolates_name(&pool, virtual_id, "six").await,
+                proxy_helpers::pypi_virtual_isolates_name(&pool, virtual_id, "six", "simple/six/")
+                    .await,
                 Ok(None)
             ),
             "a name no local member owns must not be isolated"
@@ -19117,7 +19794,13 @@ mod tests {
 
         assert!(
             matches!(
-                proxy_helpers::pypi_virtual_isolates_name(&pool, virtual_id, "acme-sdk").await,
+                proxy_helpers::pypi_virtual_isolates_name(
+                    &pool,
+                    virtual_id,
+                    "acme-sdk",
+                    "simple/acme-sdk/"
+                )
+                .await,
                 Ok(None)
             ),
             "a tracks declaration must re-enable the cross-member union"
@@ -19189,9 +19872,14 @@ mod tests {
 
         // The guard reports the owning local member's priority (2), NOT a
         // blanket "suppress all remotes".
-        let owning = proxy_helpers::pypi_virtual_isolates_name(&pool, virtual_id, "mypackage")
-            .await
-            .expect("isolation query");
+        let owning = proxy_helpers::pypi_virtual_isolates_name(
+            &pool,
+            virtual_id,
+            "mypackage",
+            "simple/mypackage/",
+        )
+        .await
+        .expect("isolation query");
         assert_eq!(
             owning,
             Some(2),
@@ -19387,6 +20075,7 @@ mod tests {
             State(state.clone()),
             Extension(auth.clone()),
             Path((key.clone(), "@scope/pkg/-/pkg-2.1.0.tgz".to_string())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await;
@@ -19409,6 +20098,7 @@ mod tests {
             State(state.clone()),
             Extension(auth.clone()),
             Path((key.clone(), "npm-test/-/npm-test-1.0.0.tgz".to_string())),
+            Query(DeleteArtifactQuery { confirm: None }),
             HeaderMap::new(),
         )
         .await;
@@ -0,0 +1,216 @@
+//! Repository key renames and their redirect aliases.
+//!
+//! `POST /repositories/:key/rename` changes a repository's key and keeps the
+//! old key redirecting to the new one for `alias_ttl_days` (default
+//! `REPOSITORY_KEY_ALIAS_TTL_DAYS`). Aliases can be listed and dropped early
+//! under `/repositories/:key/aliases`. See
+//! `services::repository_alias_service` for what a rename updates.
+
+use axum::{
+    extract::{Extension, Path, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::handlers::repositories::{
+    require_repo_admin, require_repo_write_access, require_visible, validate_repository_key,
+};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_export::details as audit_details;
+use crate::services::audit_service::{
+    audit_fire_and_forget, AuditAction, AuditEntry, ResourceType,
+};
+use crate::services::repository_alias_service::{RepositoryAliasService, RepositoryKeyAlias};
+use crate::services::repository_service::{derive_format_key, RepositoryService};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(rename_repository, list_repository_aliases, delete_repository_alias),
+    components(schemas(RenameRepositoryRequest, RenameRepositoryResponse, RepositoryKeyAlias)),
+    tags((name = "repository-aliases", description = "Repository key renames and old-key redirects"))
+)]
+pub struct RepositoryAliasesApiDoc;
+
+/// Create rename/alias routes (nested under /api/v1/repositories).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/:key/rename", post(rename_repository))
+        .route("/:key/aliases", get(list_repository_aliases))
+        .route("/:key/aliases/:alias", delete(delete_repository_alias))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RenameRepositoryRequest {
+    pub new_key: String,
+    /// Days the old key keeps redirecting; 0 keeps no redirect. Defaults to
+    /// `REPOSITORY_KEY_ALIAS_TTL_DAYS`.
+    pub alias_ttl_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RenameRepositoryResponse {
+    pub repository_id: Uuid,
+    pub old_key: String,
+    pub new_key: String,
+    /// The redirect left at the old key, absent when none was requested.
+    pub alias: Option<RepositoryKeyAlias>,
+    /// In-flight resumable uploads moved to the new key.
+    pub upload_sessions_updated: u64,
+}
+
+/// Longest redirect a rename may request.
+const MAX_ALIAS_TTL_DAYS: u32 = 3650;
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Rename a repository, keeping the old key as a redirect
+#[utoipa::path(
+    post,
+    path = "/{key}/rename",
+    context_path = "/api/v1/repositories",
+    tag = "repository-aliases",
+    params(("key" = String, Path, description = "Current repository key")),
+    request_body = RenameRepositoryRequest,
+    responses(
+        (status = 200, description = "Repository renamed", body = RenameRepositoryResponse),
+        (status = 400, description = "Invalid key or TTL, or a remote repository"),
+        (status = 403, description = "Repository admin permission required"),
+        (status = 404, description = "Repository not found"),
+        (status = 409, description = "Key already in use or reserved by an alias"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn rename_repository(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(body): Json<RenameRepositoryRequest>,
+) -> Result<Json<RenameRepositoryResponse>> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    validate_repository_key(&body.new_key)?;
+    let alias_ttl_days = body
+        .alias_ttl_days
+        .unwrap_or(state.config.repository_key_alias_ttl_days);
+    if alias_ttl_days > MAX_ALIAS_TTL_DAYS {
+        return Err(AppError::Validation(format!(
+            "alias_ttl_days must be at most {}",
+            MAX_ALIAS_TTL_DAYS
+        )));
+    }
+
+    let repo_service = state.create_repository_service();
+    let repo = repo_service.get_by_key(&key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    let renamed = RepositoryAliasService::new(state.db.clone())
+        .rename(repo.id, &body.new_key, alias_ttl_days, Some(auth.user_id))
+        .await?;
+
+    let repo = repo_service.get_by_id(repo.id).await?;
+    repo_service.reindex_in_background(&repo);
+    state.event_bus.emit_repository_event(
+        "repository.updated",
+        repo.id,
+        Some(auth.username.clone()),
+    );
+    audit_fire_and_forget(
+        state.db.clone(),
+        AuditEntry::new(AuditAction::RepositoryUpdated, ResourceType::Repository)
+            .user(auth.user_id)
+            .resource(repo.id)
+            .actor_name(auth.username.clone())
+            .resource_name(repo.key.clone())
+            .details_typed(audit_details::RepositoryDetails {
+                actor_id: auth.user_id,
+                key: repo.key.clone(),
+                is_public: repo.is_public,
+                format: Some(derive_format_key(&repo.format)),
+                visibility: Some(if repo.is_public { "public" } else { "private" }.to_owned()),
+                age_gate_enabled: None,
+                age_gate_min_age_days: None,
+                previous_key: Some(renamed.old_key.clone()),
+            }),
+    )
+    .await;
+
+    Ok(Json(RenameRepositoryResponse {
+        repository_id: repo.id,
+        old_key: renamed.old_key,
+        new_key: renamed.new_key,
+        alias: renamed.alias,
+        upload_sessions_updated: renamed.upload_sessions_updated,
+    }))
+}
+
+/// List the old keys redirecting to a repository
+#[utoipa::path(
+    get,
+    path = "/{key}/aliases",
+    context_path = "/api/v1/repositories",
+    tag = "repository-aliases",
+    params(("key" = String, Path, description = "Current repository key")),
+    responses(
+        (status = 200, description = "Aliases, newest first, including expired ones", body = Vec<RepositoryKeyAlias>),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_repository_aliases(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<Vec<RepositoryKeyAlias>>> {
+    let auth = require_auth(auth)?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &Some(auth), &repo_service).await?;
+
+    Ok(Json(
+        RepositoryAliasService::new(state.db.clone())
+            .list_aliases(repo.id)
+            .await?,
+    ))
+}
+
+/// Drop a redirect alias before it expires, releasing the old key
+#[utoipa::path(
+    delete,
+    path = "/{key}/aliases/{alias}",
+    context_path = "/api/v1/repositories",
+    tag = "repository-aliases",
+    params(
+        ("key" = String, Path, description = "Current repository key"),
+        ("alias" = String, Path, description = "Old key to stop redirecting"),
+    ),
+    responses(
+        (status = 200, description = "Alias removed"),
+        (status = 403, description = "Repository admin permission required"),
+        (status = 404, description = "Repository or alias not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_repository_alias(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path((key, alias)): Path<(String, String)>,
+) -> Result<()> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    RepositoryAliasService::new(state.db.clone())
+        .delete_alias(repo.id, &alias)
+        .await
+}
@@ -0,0 +1,224 @@
+//! Download-activity based deletion protection of a repository.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Repository (/api/v1/repositories)
+//! GET    /:key/delete-protection  → get_delete_protection
+//! PUT    /:key/delete-protection  → set_delete_protection (repository admin)
+//! DELETE /:key/delete-protection  → clear_delete_protection (repository admin)
+//! ```
+//!
+//! See `services::delete_protection_service`.
+
+use axum::{
+    extract::{Extension, Path, State},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::{
+    require_repo_admin, require_repo_write_access, require_visible,
+};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{
+    audit_fire_and_forget, AuditAction, AuditEntry, ResourceType,
+};
+use crate::services::delete_protection_service::{
+    DeleteProtectionRule, DeleteProtectionService, ProtectionAction,
+};
+use crate::services::repository_service::RepositoryService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_delete_protection, set_delete_protection, clear_delete_protection),
+    components(schemas(
+        SetDeleteProtectionRequest,
+        DeleteProtectionResponse,
+        DeleteProtectionRule,
+        ProtectionAction,
+    )),
+    tags((name = "repository-delete-protection", description = "Deletion protection for artifacts still in use"))
+)]
+pub struct RepositoryDeleteProtectionApiDoc;
+
+/// Create deletion protection routes (nested under /api/v1/repositories).
+pub fn router() -> Router<SharedState> {
+    Router::new().route(
+        "/:key/delete-protection",
+        get(get_delete_protection)
+            .put(set_delete_protection)
+            .delete(clear_delete_protection),
+    )
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetDeleteProtectionRequest {
+    /// Protect artifacts downloaded within this many days.
+    pub recent_download_days: Option<i32>,
+    /// Protect artifacts with more than this many distinct consumers.
+    pub min_distinct_consumers: Option<i32>,
+    /// `block` (default) or `confirm`.
+    pub action: Option<ProtectionAction>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteProtectionResponse {
+    pub repository_key: String,
+    /// The rule, absent when the repository has none.
+    pub rule: Option<DeleteProtectionRule>,
+}
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Get the deletion protection rule of a repository
+#[utoipa::path(
+    get,
+    path = "/{key}/delete-protection",
+    context_path = "/api/v1/repositories",
+    tag = "repository-delete-protection",
+    params(("key" = String, Path, description = "Repository key")),
+    responses(
+        (status = 200, description = "Current rule", body = DeleteProtectionResponse),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_delete_protection(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<DeleteProtectionResponse>> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &auth, &repo_service).await?;
+
+    let rule = DeleteProtectionService::new(state.db.clone())
+        .get(repo.id)
+        .await?;
+    Ok(Json(DeleteProtectionResponse {
+        repository_key: repo.key,
+        rule,
+    }))
+}
+
+/// Create or replace the deletion protection rule of a repository
+#[utoipa::path(
+    put,
+    path = "/{key}/delete-protection",
+    context_path = "/api/v1/repositories",
+    tag = "repository-delete-protection",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = SetDeleteProtectionRequest,
+    responses(
+        (status = 200, description = "Rule set", body = DeleteProtectionResponse),
+        (status = 400, description = "Invalid thresholds"),
+        (status = 403, description = "Repository admin permission required"),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn set_delete_protection(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(body): Json<SetDeleteProtectionRequest>,
+) -> Result<Json<DeleteProtectionResponse>> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    let rule = DeleteProtectionService::new(state.db.clone())
+        .set(
+            repo.id,
+            body.recent_download_days,
+            body.min_distinct_consumers,
+            body.action.unwrap_or(ProtectionAction::Block),
+            Some(auth.user_id),
+        )
+        .await?;
+
+    audit_fire_and_forget(
+        state.db.clone(),
+        AuditEntry::new(AuditAction::RepositoryUpdated, ResourceType::Repository)
+            .user(auth.user_id)
+            .resource(repo.id)
+            .actor_name(auth.username.clone())
+            .resource_name(repo.key.clone())
+            .details(serde_json::json!({
+                "delete_protection": {
+                    "recent_download_days": rule.recent_download_days,
+                    "min_distinct_consumers": rule.min_distinct_consumers,
+                    "action": rule.action,
+                },
+            })),
+    )
+    .await;
+
+    Ok(Json(DeleteProtectionResponse {
+        repository_key: repo.key,
+        rule: Some(rule),
+    }))
+}
+
+/// Remove the deletion protection rule of a repository
+#[utoipa::path(
+    delete,
+    path = "/{key}/delete-protection",
+    context_path = "/api/v1/repositories",
+    tag = "repository-delete-protection",
+    params(("key" = String, Path, description = "Repository key")),
+    responses(
+        (status = 200, description = "Rule removed", body = DeleteProtectionResponse),
+        (status = 403, description = "Repository admin permission required"),
+        (status = 404, description = "Repository or rule not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn clear_delete_protection(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<DeleteProtectionResponse>> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    if !DeleteProtectionService::new(state.db.clone())
+        .clear(repo.id)
+        .await?
+    {
+        return Err(AppError::NotFound(format!(
+            "Repository '{}' has no deletion protection rule",
+            repo.key
+        )));
+    }
+
+    audit_fire_and_forget(
+        state.db.clone(),
+        AuditEntry::new(AuditAction::RepositoryUpdated, ResourceType::Repository)
+            .user(auth.user_id)
+            .resource(repo.id)
+            .actor_name(auth.username.clone())
+            .resource_name(repo.key.clone())
+            .details(serde_json::json!({ "delete_protection": null })),
+    )
+    .await;
+
+    Ok(Json(DeleteProtectionResponse {
+        repository_key: repo.key,
+        rule: None,
+    }))
+}
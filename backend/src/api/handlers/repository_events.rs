@@ -0,0 +1,162 @@
+//! Repository change feed handlers.
+//!
+//! External indexers and replication consumers follow a repository by
+//! polling `/repositories/:key/events?since=<cursor>` with the `next_cursor`
+//! of their previous page, instead of diffing full artifact listings.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::require_visible;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::repository_event_service::{
+    clamp_page_size, EventCursor, RepositoryEvent, RepositoryEventService,
+};
+use crate::services::repository_service::RepositoryService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_repository_events),
+    components(schemas(RepositoryEvent, RepositoryEventListResponse)),
+    tags((name = "repository-events", description = "Repository change feed"))
+)]
+pub struct RepositoryEventsApiDoc;
+
+/// Create change feed routes (nested under /api/v1/repositories/:key/events).
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/:key/events", get(list_repository_events))
+}
+
+// ---------------------------------------------------------------------------
+// Request / Response types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListEventsQuery {
+    /// `next_cursor` from the previous page, `0` (default) for the oldest
+    /// retained event, or `latest` to start at the current end of the feed.
+    pub since: Option<String>,
+    /// Page size (default 100, max 1000).
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepositoryEventListResponse {
+    /// Events after `since`, oldest first.
+    pub events: Vec<RepositoryEvent>,
+    /// Pass as `since` to continue. Unchanged when no new events exist.
+    pub next_cursor: String,
+    /// More events can be read right away; otherwise poll again later.
+    pub has_more: bool,
+}
+
+/// 410 for a cursor older than the retention window.
+fn cursor_expired_response() -> Response {
+    (
+        StatusCode::GONE,
+        Json(json!({
+            "code": "CURSOR_EXPIRED",
+            "message": "Events after this cursor have been pruned; resynchronise the repository and restart from since=latest",
+        })),
+    )
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// Read the repository change feed
+///
+/// Returns artifact `created`, `updated`, `deleted` and `properties_changed`
+/// events in order. Events from transactions that may still have uncommitted
+/// predecessors are held back until those finish, so following
+/// `next_cursor` never skips an event.
+#[utoipa::path(
+    get,
+    path = "/{key}/events",
+    context_path = "/api/v1/repositories",
+    tag = "repository-events",
+    params(
+        ("key" = String, Path, description = "Repository key"),
+        ListEventsQuery
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Events after the cursor", body = RepositoryEventListResponse),
+        (status = 400, description = "Malformed cursor"),
+        (status = 404, description = "Repository not found"),
+        (status = 410, description = "Cursor is older than the retention window")
+    )
+)]
+async fn list_repository_events(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Query(query): Query<ListEventsQuery>,
+) -> Result<Response> {
+    let cursor = EventCursor::parse(query.since.as_deref())?;
+    let limit = clamp_page_size(query.limit);
+
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &auth, &repo_service).await?;
+
+    let service = RepositoryEventService::new(state.db.clone());
+    let since = match cursor {
+        EventCursor::Latest => {
+            let latest = service.latest_cursor(repo.id).await?;
+            return Ok(Json(RepositoryEventListResponse {
+                events: Vec::new(),
+                next_cursor: latest.to_string(),
+                has_more: false,
+            })
+            .into_response());
+        }
+        EventCursor::After(since) => since,
+    };
+    if since > 0 && since < service.pruned_through().await? {
+        return Ok(cursor_expired_response());
+    }
+
+    let page = service.list(repo.id, since, limit).await?;
+    Ok(Json(RepositoryEventListResponse {
+        events: page.events,
+        next_cursor: page.next_cursor.to_string(),
+        has_more: page.has_more,
+    })
+    .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_expired_response() {
+        let response = cursor_expired_response();
+        assert_eq!(response.status(), StatusCode::GONE);
+    }
+
+    #[test]
+    fn test_response_serializes_cursor_as_string() {
+        let body = RepositoryEventListResponse {
+            events: Vec::new(),
+            next_cursor: "42".to_string(),
+            has_more: false,
+        };
+        let value = serde_json::to_value(&body).unwrap();
+        assert_eq!(value["next_cursor"], "42");
+        assert_eq!(value["has_more"], false);
+    }
+}
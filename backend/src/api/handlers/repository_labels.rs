@@ -197,6 +197,9 @@ async fn set_labels(
     let labels = label_service.set_labels(repo.id, &entries).await?;
 
     reevaluate_sync_policies(&state.db, repo.id).await;
+    // Label-selector permission grants may now cover (or stop covering) this
+    // repository. Other replicas are notified by the repository_labels trigger.
+    state.permission_service.invalidate_cache();
 
     Ok(Json(labels_list_response(labels)))
 }
@@ -239,6 +242,9 @@ async fn add_label(
         .await?;
 
     reevaluate_sync_policies(&state.db, repo.id).await;
+    // Label-selector permission grants may now cover (or stop covering) this
+    // repository. Other replicas are notified by the repository_labels trigger.
+    state.permission_service.invalidate_cache();
 
     Ok(Json(label_to_response(label)))
 }
@@ -277,6 +283,9 @@ async fn delete_label(
     label_service.remove_label(repo.id, &label_key).await?;
 
     reevaluate_sync_policies(&state.db, repo.id).await;
+    // Label-selector permission grants may now cover (or stop covering) this
+    // repository. Other replicas are notified by the repository_labels trigger.
+    state.permission_service.invalidate_cache();
 
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
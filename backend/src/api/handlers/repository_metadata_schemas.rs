@@ -0,0 +1,413 @@
+//! Per-repository metadata schema handlers.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Repository (/api/v1/repositories)
+//! GET    /:key/metadata-schema           → get_schema
+//! PUT    /:key/metadata-schema           → set_schema (admin)
+//! DELETE /:key/metadata-schema           → delete_schema (admin)
+//! POST   /:key/metadata-schema/validate  → validate_properties
+//! GET    /:key/default-properties        → get_default_properties
+//! PUT    /:key/default-properties        → set_default_properties (admin)
+//! DELETE /:key/default-properties        → delete_default_properties (admin)
+//! ```
+//!
+//! See [`crate::services::metadata_schema_service`] for the supported schema
+//! keywords, how uploads supply properties and how repository defaults are
+//! merged in.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::require_visible;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::metadata_schema_service::{
+    self, FieldError, MetadataSchema, RepositoryDefaultProperties, RepositoryMetadataSchema,
+};
+use crate::services::repository_service::RepositoryService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_schema,
+        set_schema,
+        delete_schema,
+        validate_properties,
+        get_default_properties,
+        set_default_properties,
+        delete_default_properties,
+    ),
+    components(schemas(
+        RepositoryMetadataSchema,
+        SetMetadataSchemaRequest,
+        ValidatePropertiesRequest,
+        ValidatePropertiesResponse,
+        FieldError,
+        RepositoryDefaultProperties,
+        SetDefaultPropertiesRequest,
+        SetDefaultPropertiesResponse,
+    )),
+    tags((name = "repository-metadata-schemas", description = "Required custom artifact properties per repository"))
+)]
+pub struct RepositoryMetadataSchemasApiDoc;
+
+/// Routes nested under /api/v1/repositories.
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route(
+            "/:key/metadata-schema",
+            get(get_schema).put(set_schema).delete(delete_schema),
+        )
+        .route("/:key/metadata-schema/validate", post(validate_properties))
+        .route(
+            "/:key/default-properties",
+            get(get_default_properties)
+                .put(set_default_properties)
+                .delete(delete_default_properties),
+        )
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetMetadataSchemaRequest {
+    /// JSON Schema for the properties, e.g.
+    /// `{"required": ["jira_ticket"], "properties": {"jira_ticket": {"type": "string"}}}`.
+    #[schema(value_type = Object)]
+    pub schema: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ValidatePropertiesRequest {
+    #[schema(value_type = Object)]
+    pub properties: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidatePropertiesResponse {
+    pub valid: bool,
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetDefaultPropertiesRequest {
+    /// Flat key/value pairs, e.g. `{"team": "payments", "cost-center": "cc-42"}`.
+    #[schema(value_type = Object)]
+    pub properties: serde_json::Map<String, serde_json::Value>,
+    /// Also stamp the defaults onto artifacts already in the repository,
+    /// keeping values they already have.
+    #[serde(default)]
+    pub apply_to_existing: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetDefaultPropertiesResponse {
+    pub defaults: RepositoryDefaultProperties,
+    /// Existing artifacts updated when `apply_to_existing` was set.
+    pub artifacts_updated: u64,
+}
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+async fn audit_repository_change(
+    state: &SharedState,
+    auth: &AuthExtension,
+    repo: &crate::models::repository::Repository,
+    details: serde_json::Value,
+) {
+    let _ = AuditService::new(state.db.clone())
+        .log(
+            AuditEntry::new(AuditAction::RepositoryUpdated, ResourceType::Repository)
+                .user(auth.user_id)
+                .resource(repo.id)
+                .actor_name(auth.username.clone())
+                .resource_name(repo.key.clone())
+                .details(details),
+        )
+        .await;
+}
+
+/// Get a repository's metadata schema
+#[utoipa::path(
+    get,
+    path = "/{key}/metadata-schema",
+    context_path = "/api/v1/repositories",
+    tag = "repository-metadata-schemas",
+    params(("key" = String, Path, description = "Repository key")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Schema uploads must satisfy", body = RepositoryMetadataSchema),
+        (status = 404, description = "Repository not found or has no schema")
+    )
+)]
+pub async fn get_schema(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<RepositoryMetadataSchema>> {
+    let auth = require_auth(auth)?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &Some(auth), &repo_service).await?;
+
+    metadata_schema_service::get(&state.db, repo.id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound("Repository has no metadata schema".to_string()))
+}
+
+/// Set (replace) a repository's metadata schema
+#[utoipa::path(
+    put,
+    path = "/{key}/metadata-schema",
+    context_path = "/api/v1/repositories",
+    tag = "repository-metadata-schemas",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = SetMetadataSchemaRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Schema stored", body = RepositoryMetadataSchema),
+        (status = 400, description = "Invalid or unsupported schema, or not a hosted repository"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Repository not found")
+    )
+)]
+pub async fn set_schema(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(payload): Json<SetMetadataSchemaRequest>,
+) -> Result<Json<RepositoryMetadataSchema>> {
+    let auth = require_auth(auth)?;
+    auth.require_admin()?;
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+    if !repo.repo_type.is_hosted() {
+        return Err(AppError::Validation(
+            "Metadata schemas apply only to hosted (local or staging) repositories".to_string(),
+        ));
+    }
+
+    let stored =
+        metadata_schema_service::set(&state.db, repo.id, &payload.schema, auth.user_id).await?;
+    audit_repository_change(
+        &state,
+        &auth,
+        &repo,
+        serde_json::json!({ "metadata_schema": "set" }),
+    )
+    .await;
+    Ok(Json(stored))
+}
+
+/// Remove a repository's metadata schema
+#[utoipa::path(
+    delete,
+    path = "/{key}/metadata-schema",
+    context_path = "/api/v1/repositories",
+    tag = "repository-metadata-schemas",
+    params(("key" = String, Path, description = "Repository key")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Schema removed"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Repository not found or has no schema")
+    )
+)]
+pub async fn delete_schema(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<StatusCode> {
+    let auth = require_auth(auth)?;
+    auth.require_admin()?;
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+
+    metadata_schema_service::delete(&state.db, repo.id).await?;
+    audit_repository_change(
+        &state,
+        &auth,
+        &repo,
+        serde_json::json!({ "metadata_schema": "deleted" }),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check properties against a repository's metadata schema without uploading.
+/// The repository's default properties are merged in as they would be on
+/// upload.
+#[utoipa::path(
+    post,
+    path = "/{key}/metadata-schema/validate",
+    context_path = "/api/v1/repositories",
+    tag = "repository-metadata-schemas",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = ValidatePropertiesRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Field-level validation result", body = ValidatePropertiesResponse),
+        (status = 404, description = "Repository not found or has no schema")
+    )
+)]
+pub async fn validate_properties(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(payload): Json<ValidatePropertiesRequest>,
+) -> Result<Json<ValidatePropertiesResponse>> {
+    let auth = require_auth(auth)?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &Some(auth), &repo_service).await?;
+
+    let stored = metadata_schema_service::get(&state.db, repo.id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Repository has no metadata schema".to_string()))?;
+    let defaults = metadata_schema_service::get_defaults(&state.db, repo.id)
+        .await?
+        .map(|d| d.properties)
+        .unwrap_or_default();
+    let properties = metadata_schema_service::with_defaults(&defaults, &payload.properties);
+    let errors = MetadataSchema::compile(&stored.schema)?.validate(&properties);
+    Ok(Json(ValidatePropertiesResponse {
+        valid: errors.is_empty(),
+        errors,
+    }))
+}
+
+/// Get a repository's default artifact properties
+#[utoipa::path(
+    get,
+    path = "/{key}/default-properties",
+    context_path = "/api/v1/repositories",
+    tag = "repository-metadata-schemas",
+    params(("key" = String, Path, description = "Repository key")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Properties stamped onto every artifact", body = RepositoryDefaultProperties),
+        (status = 404, description = "Repository not found or has no default properties")
+    )
+)]
+pub async fn get_default_properties(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<RepositoryDefaultProperties>> {
+    let auth = require_auth(auth)?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &Some(auth), &repo_service).await?;
+
+    metadata_schema_service::get_defaults(&state.db, repo.id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound("Repository has no default properties".to_string()))
+}
+
+/// Set (replace) a repository's default artifact properties
+#[utoipa::path(
+    put,
+    path = "/{key}/default-properties",
+    context_path = "/api/v1/repositories",
+    tag = "repository-metadata-schemas",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = SetDefaultPropertiesRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Defaults stored", body = SetDefaultPropertiesResponse),
+        (status = 400, description = "Empty, nested or schema-violating properties"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Repository not found")
+    )
+)]
+pub async fn set_default_properties(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(payload): Json<SetDefaultPropertiesRequest>,
+) -> Result<Json<SetDefaultPropertiesResponse>> {
+    let auth = require_auth(auth)?;
+    auth.require_admin()?;
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+
+    let defaults = metadata_schema_service::set_defaults(
+        &state.db,
+        repo.id,
+        &payload.properties,
+        auth.user_id,
+    )
+    .await?;
+    let artifacts_updated = if payload.apply_to_existing {
+        metadata_schema_service::apply_defaults_to_existing(&state.db, repo.id).await?
+    } else {
+        0
+    };
+    audit_repository_change(
+        &state,
+        &auth,
+        &repo,
+        serde_json::json!({
+            "default_properties": "set",
+            "properties": defaults.properties,
+            "artifacts_updated": artifacts_updated,
+        }),
+    )
+    .await;
+    Ok(Json(SetDefaultPropertiesResponse {
+        defaults,
+        artifacts_updated,
+    }))
+}
+
+/// Remove a repository's default artifact properties. Artifacts keep the
+/// values already stamped onto them.
+#[utoipa::path(
+    delete,
+    path = "/{key}/default-properties",
+    context_path = "/api/v1/repositories",
+    tag = "repository-metadata-schemas",
+    params(("key" = String, Path, description = "Repository key")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Defaults removed"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "Repository not found or has no default properties")
+    )
+)]
+pub async fn delete_default_properties(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<StatusCode> {
+    let auth = require_auth(auth)?;
+    auth.require_admin()?;
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+
+    metadata_schema_service::delete_defaults(&state.db, repo.id).await?;
+    audit_repository_change(
+        &state,
+        &auth,
+        &repo,
+        serde_json::json!({ "default_properties": "deleted" }),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,170 @@
+//! Peer endpoint for replicated repository configuration.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Repository (/api/v1/repositories)
+//! GET    /:key/replicated-config  → get_replicated_config (admin)
+//! PUT    /:key/replicated-config  → apply_replicated_config (admin)
+//! ```
+//!
+//! Peers enrolled in config replication push here; see
+//! [`crate::services::repository_config_replication_service`].
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::HeaderMap,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::repository_config_replication_service::{
+    ConfigDrift, ConfigSection, ReplicatedLifecyclePolicy, ReplicatedScanPolicy,
+    RepositoryConfigBundle, RepositoryConfigReplicationService,
+};
+use crate::services::repository_service::RepositoryService;
+use crate::services::sync_policy_service::SyncPolicyService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_replicated_config, apply_replicated_config),
+    components(schemas(
+        RepositoryConfigBundle,
+        ReplicatedLifecyclePolicy,
+        ReplicatedScanPolicy,
+        ApplyReplicatedConfigResponse,
+    )),
+    tags((name = "repository-replicated-config", description = "Repository configuration exchanged between peers"))
+)]
+pub struct RepositoryReplicatedConfigApiDoc;
+
+/// Routes nested under /api/v1/repositories.
+pub fn router() -> Router<SharedState> {
+    Router::new().route(
+        "/:key/replicated-config",
+        get(get_replicated_config).put(apply_replicated_config),
+    )
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApplyReplicatedConfigResponse {
+    pub changed: bool,
+    /// What was changed, with the received configuration as source.
+    pub changes: Vec<ConfigDrift>,
+}
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Get the repository's replicated configuration
+#[utoipa::path(
+    get,
+    path = "/{key}/replicated-config",
+    context_path = "/api/v1/repositories",
+    tag = "repository-replicated-config",
+    params(("key" = String, Path, description = "Repository key")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Labels, lifecycle policies and scan policies of the repository", body = RepositoryConfigBundle),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Repository not found")
+    )
+)]
+async fn get_replicated_config(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<RepositoryConfigBundle>> {
+    require_auth(auth)?.require_admin()?;
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+    let service = RepositoryConfigReplicationService::new(state.db.clone());
+    Ok(Json(service.load_bundle(repo.id).await?))
+}
+
+/// Replace the repository's labels and repository-scoped policies
+///
+/// Policies are matched by name. Nothing is written when the configuration
+/// already matches.
+#[utoipa::path(
+    put,
+    path = "/{key}/replicated-config",
+    context_path = "/api/v1/repositories",
+    tag = "repository-replicated-config",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = RepositoryConfigBundle,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Configuration applied", body = ApplyReplicatedConfigResponse),
+        (status = 400, description = "Invalid label or policy"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Repository not found")
+    )
+)]
+async fn apply_replicated_config(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+    Json(bundle): Json<RepositoryConfigBundle>,
+) -> Result<Json<ApplyReplicatedConfigResponse>> {
+    let auth = require_auth(auth)?;
+    auth.require_admin()?;
+    let repo = RepositoryService::new(state.db.clone())
+        .get_by_key(&key)
+        .await?;
+
+    let service = RepositoryConfigReplicationService::new(state.db.clone());
+    let changes = service.apply_bundle(repo.id, bundle).await?;
+
+    if changes.iter().any(|c| c.section == ConfigSection::Labels) {
+        if let Err(e) = SyncPolicyService::new(state.db.clone())
+            .evaluate_for_repository(repo.id)
+            .await
+        {
+            tracing::warn!(
+                "Sync policy re-evaluation failed for repo {}: {}",
+                repo.id,
+                e
+            );
+        }
+        state.permission_service.invalidate_cache();
+    }
+
+    if !changes.is_empty() {
+        let _ = AuditService::new(state.db.clone())
+            .log(
+                AuditEntry::new(AuditAction::RepositoryUpdated, ResourceType::Repository)
+                    .user(auth.user_id)
+                    .resource(repo.id)
+                    .actor_name(auth.username.clone())
+                    .resource_name(repo.key.clone())
+                    .details(serde_json::json!({
+                        "replicated_config": true,
+                        "from_peer": super::is_replication_request(&headers),
+                        "changes": changes
+                            .iter()
+                            .map(|c| serde_json::json!({
+                                "section": c.section,
+                                "name": c.name,
+                                "kind": c.kind,
+                            }))
+                            .collect::<Vec<_>>(),
+                    })),
+            )
+            .await;
+    }
+
+    Ok(Json(ApplyReplicatedConfigResponse {
+        changed: !changes.is_empty(),
+        changes,
+    }))
+}
@@ -0,0 +1,108 @@
+//! Dashboard statistics rollup of a repository.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Repository (/api/v1/repositories)
+//! GET    /:key/stats  → get_repository_stats
+//! ```
+//!
+//! Replaces the separate artifact, download, security and trend calls a
+//! repository card would otherwise make; see
+//! `services::repository_stats_service`.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::require_visible;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::repository_service::RepositoryService;
+use crate::services::repository_stats_service::{
+    DailyActivity, RepositoryActivity, RepositoryScanSummary, RepositoryStats,
+    RepositoryStatsService, StorageTrendPoint, TopPackage, DEFAULT_TOP_PACKAGES,
+    DEFAULT_WINDOW_DAYS,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_repository_stats),
+    components(schemas(
+        RepositoryStatsResponse,
+        RepositoryStats,
+        TopPackage,
+        RepositoryActivity,
+        DailyActivity,
+        RepositoryScanSummary,
+        StorageTrendPoint,
+    )),
+    tags((name = "repository-stats", description = "Dashboard statistics of a repository"))
+)]
+pub struct RepositoryStatsApiDoc;
+
+/// Create repository stats routes (nested under /api/v1/repositories).
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/:key/stats", get(get_repository_stats))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RepositoryStatsQuery {
+    /// Activity and trend window in days (default 30, max 90).
+    pub days: Option<i64>,
+    /// Number of top packages (default 5, max 25).
+    pub top: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepositoryStatsResponse {
+    pub repository_key: String,
+    pub quota_bytes: Option<i64>,
+    #[serde(flatten)]
+    pub stats: RepositoryStats,
+}
+
+/// Get the dashboard statistics of a repository
+#[utoipa::path(
+    get,
+    path = "/{key}/stats",
+    context_path = "/api/v1/repositories",
+    tag = "repository-stats",
+    params(
+        ("key" = String, Path, description = "Repository key"),
+        RepositoryStatsQuery,
+    ),
+    responses(
+        (status = 200, description = "Repository statistics", body = RepositoryStatsResponse),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_repository_stats(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Query(query): Query<RepositoryStatsQuery>,
+) -> Result<Json<RepositoryStatsResponse>> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &auth, &repo_service).await?;
+
+    let stats = RepositoryStatsService::new(state.db.clone())
+        .get(
+            repo.id,
+            query.days.unwrap_or(DEFAULT_WINDOW_DAYS),
+            query.top.unwrap_or(DEFAULT_TOP_PACKAGES),
+        )
+        .await?;
+    Ok(Json(RepositoryStatsResponse {
+        repository_key: repo.key,
+        quota_bytes: repo.quota_bytes,
+        stats,
+    }))
+}
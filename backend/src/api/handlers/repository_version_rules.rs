@@ -0,0 +1,227 @@
+//! Artifact naming/version parsing rules of a repository.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Repository (/api/v1/repositories)
+//! GET    /:key/version-rules          → get_version_rules
+//! PUT    /:key/version-rules          → set_version_rules (repository admin)
+//! POST   /:key/version-rules/preview  → preview_version_rules
+//! ```
+//!
+//! Rules apply to generic uploads made after they are set; see
+//! `services::version_rules_service`.
+
+use axum::{
+    extract::{Extension, Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::{
+    require_repo_admin, require_repo_write_access, require_visible,
+};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{
+    audit_fire_and_forget, AuditAction, AuditEntry, ResourceType,
+};
+use crate::services::repository_service::RepositoryService;
+use crate::services::version_rules_service::{
+    self, ExtractedCoordinates, VersionRule, VersionRuleSet,
+};
+
+/// Most sample paths a preview request may carry.
+const MAX_PREVIEW_PATHS: usize = 100;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_version_rules, set_version_rules, preview_version_rules),
+    components(schemas(
+        VersionRule,
+        VersionRulesResponse,
+        SetVersionRulesRequest,
+        PreviewVersionRulesRequest,
+        PreviewVersionRulesResponse,
+        VersionRulePreview,
+        ExtractedCoordinates,
+    )),
+    tags((name = "repository-version-rules", description = "Per-repository artifact naming and version parsing rules"))
+)]
+pub struct RepositoryVersionRulesApiDoc;
+
+/// Create version rule routes (nested under /api/v1/repositories).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route(
+            "/:key/version-rules",
+            get(get_version_rules).put(set_version_rules),
+        )
+        .route("/:key/version-rules/preview", post(preview_version_rules))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionRulesResponse {
+    pub repository_key: String,
+    /// Rules in evaluation order; empty when the path convention applies.
+    pub rules: Vec<VersionRule>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetVersionRulesRequest {
+    /// Rules in evaluation order. An empty list removes them.
+    pub rules: Vec<VersionRule>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PreviewVersionRulesRequest {
+    /// Rules to try instead of the stored ones.
+    pub rules: Option<Vec<VersionRule>>,
+    /// Artifact paths to parse.
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionRulePreview {
+    pub path: String,
+    /// What the first matching rule extracted, absent when none matched.
+    pub matched: Option<ExtractedCoordinates>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewVersionRulesResponse {
+    pub results: Vec<VersionRulePreview>,
+}
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Get the naming/version parsing rules of a repository
+#[utoipa::path(
+    get,
+    path = "/{key}/version-rules",
+    context_path = "/api/v1/repositories",
+    tag = "repository-version-rules",
+    params(("key" = String, Path, description = "Repository key")),
+    responses(
+        (status = 200, description = "Configured rules", body = VersionRulesResponse),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_version_rules(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<VersionRulesResponse>> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &auth, &repo_service).await?;
+
+    let rules = version_rules_service::load_rules(&state.db, repo.id).await?;
+    Ok(Json(VersionRulesResponse {
+        repository_key: repo.key,
+        rules,
+    }))
+}
+
+/// Replace the naming/version parsing rules of a repository
+#[utoipa::path(
+    put,
+    path = "/{key}/version-rules",
+    context_path = "/api/v1/repositories",
+    tag = "repository-version-rules",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = SetVersionRulesRequest,
+    responses(
+        (status = 200, description = "Rules stored", body = VersionRulesResponse),
+        (status = 400, description = "Invalid rule"),
+        (status = 403, description = "Repository admin permission required"),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn set_version_rules(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(body): Json<SetVersionRulesRequest>,
+) -> Result<Json<VersionRulesResponse>> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    version_rules_service::save_rules(&state.db, repo.id, &body.rules).await?;
+
+    audit_fire_and_forget(
+        state.db.clone(),
+        AuditEntry::new(AuditAction::RepositoryUpdated, ResourceType::Repository)
+            .user(auth.user_id)
+            .resource(repo.id)
+            .actor_name(auth.username.clone())
+            .resource_name(repo.key.clone())
+            .details(serde_json::json!({
+                "version_rules": body.rules,
+            })),
+    )
+    .await;
+
+    Ok(Json(VersionRulesResponse {
+        repository_key: repo.key,
+        rules: body.rules,
+    }))
+}
+
+/// Parse sample paths with the stored or the given rules
+#[utoipa::path(
+    post,
+    path = "/{key}/version-rules/preview",
+    context_path = "/api/v1/repositories",
+    tag = "repository-version-rules",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = PreviewVersionRulesRequest,
+    responses(
+        (status = 200, description = "Extraction result per path", body = PreviewVersionRulesResponse),
+        (status = 400, description = "Invalid rule or too many paths"),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn preview_version_rules(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(body): Json<PreviewVersionRulesRequest>,
+) -> Result<Json<PreviewVersionRulesResponse>> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &auth, &repo_service).await?;
+    if body.paths.len() > MAX_PREVIEW_PATHS {
+        return Err(AppError::Validation(format!(
+            "At most {} paths may be previewed at once",
+            MAX_PREVIEW_PATHS
+        )));
+    }
+
+    let rules = match body.rules {
+        Some(rules) => rules,
+        None => version_rules_service::load_rules(&state.db, repo.id).await?,
+    };
+    let set = VersionRuleSet::compile(&rules)?;
+    let results = body
+        .paths
+        .into_iter()
+        .map(|path| VersionRulePreview {
+            matched: set.extract(&path),
+            path,
+        })
+        .collect();
+    Ok(Json(PreviewVersionRulesResponse { results }))
+}
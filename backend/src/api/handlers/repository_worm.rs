@@ -0,0 +1,148 @@
+//! WORM retention for compliance repositories.
+//!
+//! `PUT /repositories/:key/worm` locks every object subsequently written to a
+//! repository at the storage provider for `retention_days`. Policies can be
+//! extended but never shortened or removed, and only instance administrators
+//! may set them. `GET` reports the current policy. See
+//! `services::worm_service`.
+
+use axum::{
+    extract::{Extension, Path, State},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::require_visible;
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{
+    audit_fire_and_forget, AuditAction, AuditEntry, ResourceType,
+};
+use crate::services::repository_service::RepositoryService;
+use crate::services::worm_service::{WormPolicy, WormService};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_worm_policy, set_worm_policy),
+    components(schemas(SetWormPolicyRequest, WormPolicyResponse, WormPolicy)),
+    tags((name = "repository-worm", description = "Provider-side WORM retention for compliance repositories"))
+)]
+pub struct RepositoryWormApiDoc;
+
+/// Create WORM policy routes (nested under /api/v1/repositories).
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/:key/worm", get(get_worm_policy).put(set_worm_policy))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetWormPolicyRequest {
+    /// Days each written object stays locked. Must not be lower than the
+    /// current policy.
+    pub retention_days: u32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WormPolicyResponse {
+    pub repository_key: String,
+    /// The policy, absent when the repository is not WORM.
+    pub policy: Option<WormPolicy>,
+}
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Get the WORM retention policy of a repository
+#[utoipa::path(
+    get,
+    path = "/{key}/worm",
+    context_path = "/api/v1/repositories",
+    tag = "repository-worm",
+    params(("key" = String, Path, description = "Repository key")),
+    responses(
+        (status = 200, description = "Current WORM policy", body = WormPolicyResponse),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_worm_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<WormPolicyResponse>> {
+    let auth = require_auth(auth)?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &Some(auth), &repo_service).await?;
+
+    let policy = WormService::new(state.db.clone()).get(repo.id).await?;
+    Ok(Json(WormPolicyResponse {
+        repository_key: repo.key,
+        policy,
+    }))
+}
+
+/// Create or extend the WORM retention policy of a repository
+#[utoipa::path(
+    put,
+    path = "/{key}/worm",
+    context_path = "/api/v1/repositories",
+    tag = "repository-worm",
+    params(("key" = String, Path, description = "Repository key")),
+    request_body = SetWormPolicyRequest,
+    responses(
+        (status = 200, description = "WORM policy set", body = WormPolicyResponse),
+        (status = 400, description = "Invalid retention, shortened policy, or backend without retention support"),
+        (status = 403, description = "Administrator required"),
+        (status = 404, description = "Repository not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn set_worm_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(body): Json<SetWormPolicyRequest>,
+) -> Result<Json<WormPolicyResponse>> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    auth.require_admin()?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+
+    let policy = WormService::new(state.db.clone())
+        .set(
+            &repo,
+            &state.storage_registry,
+            body.retention_days,
+            Some(auth.user_id),
+        )
+        .await?;
+
+    audit_fire_and_forget(
+        state.db.clone(),
+        AuditEntry::new(AuditAction::RepositoryUpdated, ResourceType::Repository)
+            .user(auth.user_id)
+            .resource(repo.id)
+            .actor_name(auth.username.clone())
+            .resource_name(repo.key.clone())
+            .details(serde_json::json!({
+                "worm_retention_days": policy.retention_days,
+            })),
+    )
+    .await;
+    tracing::info!(
+        repository = %repo.key,
+        retention_days = policy.retention_days,
+        user = %auth.username,
+        "Repository WORM retention set"
+    );
+
+    Ok(Json(WormPolicyResponse {
+        repository_key: repo.key,
+        policy: Some(policy),
+    }))
+}
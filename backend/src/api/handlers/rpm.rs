@@ -414,7 +414,8 @@ async fn list_rpm_artifacts(
 /// repo's packages — otherwise `repomd.xml`/`primary.xml.gz` advertise
 /// `packages="0"` and `dnf` treats the aggregate repo as empty even though
 /// the members hold packages (#1780). We resolve the member repo IDs via
-/// `fetch_virtual_members` and concatenate each member's artifact list.
+/// `fetch_virtual_members` and concatenate each member's artifact list,
+/// minus the paths a member's routing patterns exclude.
 ///
 /// The result is sorted into the same deterministic total order
 /// `list_rpm_artifacts` returns. Concatenating per-member lists inherits
@@ -431,9 +432,16 @@ async fn collect_repodata_artifacts(
     }
 
     let members = proxy_helpers::fetch_virtual_members(db, repo.id).await?;
+    let routes = proxy_helpers::fetch_virtual_member_routes(db, repo.id).await?;
     let mut artifacts = Vec::new();
     for member in &members {
-        artifacts.extend(list_rpm_artifacts(db, member.id).await?);
+        // Only advertise packages the member's routing patterns let it serve.
+        artifacts.extend(
+            list_rpm_artifacts(db, member.id)
+                .await?
+                .into_iter()
+                .filter(|a| routes.allows(member.id, &a.path)),
+        );
     }
     artifacts.sort_by(|a, b| {
         (&a.name, &a.version, &a.path, &a.id).cmp(&(&b.name, &b.version, &b.path, &b.id))
@@ -10,6 +10,9 @@
 //!   GET  /gems/{repo_key}/specs.4.8.gz                      - Full spec index
 //!   GET  /gems/{repo_key}/latest_specs.4.8.gz               - Latest spec index
 //!   GET  /gems/{repo_key}/api/v1/dependencies?gems={names}  - Dependency info
+//!   GET  /gems/{repo_key}/versions                          - Compact index versions
+//!   GET  /gems/{repo_key}/info/{name}                       - Compact index info
+//!   GET  /gems/{repo_key}/names                             - Compact index names
 
 use axum::body::Body;
 use axum::extract::{Path, Query, State};
@@ -34,6 +37,7 @@ use crate::api::middleware::auth::{require_auth_basic_scope, AuthExtension};
 use crate::api::SharedState;
 use crate::formats::rubygems::RubygemsHandler;
 use crate::models::repository::{Repository, RepositoryType};
+use crate::services::virtual_member_routing::MemberRoutes;
 
 // ---------------------------------------------------------------------------
 // Router
@@ -56,6 +60,10 @@ pub fn router() -> Router<SharedState> {
             "/:repo_key/prerelease_specs.4.8.gz",
             get(prerelease_specs_index),
         )
+        // Compact index, preferred by Bundler over the Marshal indices
+        .route("/:repo_key/versions", get(compact_versions_index))
+        .route("/:repo_key/info/:name", get(compact_info_index))
+        .route("/:repo_key/names", get(compact_names_index))
         // Quick gemspec (Marshal 4.8, zlib-deflated). `gem install` fetches this
         // to resolve a gem's dependencies before downloading the .gem.
         .route("/:repo_key/quick/Marshal.4.8/:spec_file", get(quick_spec))
@@ -131,6 +139,7 @@ async fn gem_info(
             state.proxy_service.as_deref(),
             repo.id,
             &format!("api/v1/gems/{}.json", gem_name),
+            &gem_route_prefix(&gem_name),
             |bytes, _member_key| async move {
                 Ok(Response::builder()
                     .status(StatusCode::OK)
@@ -235,8 +244,13 @@ async fn download_gem(
                 let suppress_upstream = if repo.repo_type == RepositoryType::Virtual {
                     match crate::formats::rubygems::package_name_from_gem_filename(filename) {
                         Some(pkg) => {
-                            proxy_helpers::virtual_non_remote_owns_name(&state.db, repo.id, &pkg)
-                                .await?
+                            proxy_helpers::virtual_non_remote_owns_name(
+                                &state.db,
+                                repo.id,
+                                &pkg,
+                                &upstream_path,
+                            )
+                            .await?
                         }
                         None => false,
                     }
@@ -457,18 +471,40 @@ fn spec_index_coordinates(path: &str, name: String, version: String) -> (String,
 async fn query_local_member_specs(
     db: &PgPool,
     members: &[Repository],
+    routes: &MemberRoutes,
     sql: &str,
 ) -> Result<Vec<serde_json::Value>, Response> {
     let mut all_specs = Vec::new();
     for member in members {
         if member.repo_type != RepositoryType::Remote {
             let specs = query_gem_specs(db, member.id, sql).await?;
-            all_specs.extend(specs);
+            all_specs.extend(
+                specs
+                    .into_iter()
+                    .filter(|spec| routes.allows(member.id, &spec_route_path(spec))),
+            );
         }
     }
     Ok(all_specs)
 }
 
+/// The `gems/{name}-{version}[-{platform}].gem` path a spec tuple downloads
+/// from, checked against virtual member routing patterns.
+fn spec_route_path(spec: &serde_json::Value) -> String {
+    let (name, version, platform) = spec_tuple(spec);
+    if platform.is_empty() || platform == "ruby" {
+        format!("gems/{}-{}.gem", name, version)
+    } else {
+        format!("gems/{}-{}-{}.gem", name, version, platform)
+    }
+}
+
+/// The `gems/{name}-` prefix shared by every `.gem` download of `name`, used
+/// to route name-level lookups (gem info, compact index).
+fn gem_route_prefix(name: &str) -> String {
+    format!("gems/{}-", name)
+}
+
 /// Decompress gzipped upstream spec data and parse as a JSON array of spec tuples.
 #[allow(clippy::result_large_err)]
 fn parse_upstream_specs(bytes: &[u8]) -> Result<Vec<serde_json::Value>, Response> {
@@ -492,6 +528,7 @@ fn parse_upstream_specs(bytes: &[u8]) -> Result<Vec<serde_json::Value>, Response
 async fn collect_remote_specs(
     state: &SharedState,
     virtual_repo_id: uuid::Uuid,
+    routes: &MemberRoutes,
     upstream_path: &str,
 ) -> Result<Vec<serde_json::Value>, Response> {
     let remote_specs = proxy_helpers::collect_virtual_metadata(
@@ -512,8 +549,12 @@ async fn collect_remote_specs(
     .await?;
 
     let mut all = Vec::new();
-    for (_key, specs) in remote_specs {
-        all.extend(specs);
+    for (member_id, specs) in remote_specs {
+        all.extend(
+            specs
+                .into_iter()
+                .filter(|spec| routes.allows(member_id, &spec_route_path(spec))),
+        );
     }
     Ok(all)
 }
@@ -574,9 +615,11 @@ async fn specs_index(
     // Virtual repo: merge specs from all local and remote members
     if repo.repo_type == RepositoryType::Virtual {
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
-        let mut all_specs = query_local_member_specs(&state.db, &members, SPECS_QUERY).await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
+        let mut all_specs =
+            query_local_member_specs(&state.db, &members, &routes, SPECS_QUERY).await?;
 
-        let remote = collect_remote_specs(&state, repo.id, "specs.4.8.gz").await?;
+        let remote = collect_remote_specs(&state, repo.id, &routes, "specs.4.8.gz").await?;
         all_specs.extend(remote);
 
         return specs_to_gzip_response(&all_specs);
@@ -600,10 +643,11 @@ async fn latest_specs_index(
     // then deduplicate by gem name (keep the first occurrence per name).
     if repo.repo_type == RepositoryType::Virtual {
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
         let mut all_specs =
-            query_local_member_specs(&state.db, &members, LATEST_SPECS_QUERY).await?;
+            query_local_member_specs(&state.db, &members, &routes, LATEST_SPECS_QUERY).await?;
 
-        let remote = collect_remote_specs(&state, repo.id, "latest_specs.4.8.gz").await?;
+        let remote = collect_remote_specs(&state, repo.id, &routes, "latest_specs.4.8.gz").await?;
         all_specs.extend(remote);
 
         // Deduplicate by gem name, keeping the first occurrence (higher-priority member wins)
@@ -638,10 +682,12 @@ async fn prerelease_specs_index(
     // Virtual repo: merge prerelease specs from all local and remote members.
     if repo.repo_type == RepositoryType::Virtual {
         let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
         let mut all_specs =
-            query_local_member_specs(&state.db, &members, PRERELEASE_SPECS_QUERY).await?;
+            query_local_member_specs(&state.db, &members, &routes, PRERELEASE_SPECS_QUERY).await?;
 
-        let remote = collect_remote_specs(&state, repo.id, "prerelease_specs.4.8.gz").await?;
+        let remote =
+            collect_remote_specs(&state, repo.id, &routes, "prerelease_specs.4.8.gz").await?;
         all_specs.extend(remote);
 
         return specs_to_gzip_response(&all_specs);
@@ -773,7 +819,9 @@ async fn quick_spec(
 
     if repo.repo_type == RepositoryType::Virtual {
         // Prefer a locally published member (mirrors the download shadowing rule).
-        let members = proxy_helpers::fetch_virtual_members(&state.db, repo.id).await?;
+        let route_path = format!("gems/{}.gem", full_name);
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(&state.db, repo.id, &route_path).await?;
         for member in &members {
             if member.repo_type != RepositoryType::Remote {
                 if let Some(spec) = find_local_quick_spec(&state.db, member.id, full_name).await? {
@@ -787,6 +835,7 @@ async fn quick_spec(
             state.proxy_service.as_deref(),
             repo.id,
             &format!("quick/Marshal.4.8/{}", spec_file),
+            &route_path,
             |bytes, _member_key| async move {
                 Ok(Response::builder()
                     .status(StatusCode::OK)
@@ -804,6 +853,331 @@ async fn quick_spec(
     }
 }
 
+// ---------------------------------------------------------------------------
+// Compact index — GET /gems/{repo_key}/versions, /info/{name}, /names
+// ---------------------------------------------------------------------------
+
+/// A locally stored gem version, as the compact index lists it.
+struct CompactEntry {
+    name: String,
+    spec: crate::formats::rubygems::GemSpec,
+    sha256: String,
+}
+
+/// Gem versions stored in one repository, oldest first. `name` narrows the
+/// result to one gem, matched exactly against the name the entry is indexed
+/// under (see [`spec_index_coordinates`]).
+async fn query_compact_entries(
+    db: &PgPool,
+    repo_id: uuid::Uuid,
+    name: Option<&str>,
+) -> Result<Vec<CompactEntry>, Response> {
+    let rows = sqlx::query(
+        r#"
+        SELECT a.name, a.version, a.path, a.checksum_sha256, am.metadata AS metadata
+        FROM artifacts a
+        LEFT JOIN artifact_metadata am ON am.artifact_id = a.id
+        WHERE a.repository_id = $1
+          AND a.is_deleted = false
+          AND ($2::text IS NULL OR a.name = $2 OR strpos(a.path, $2) > 0)
+        ORDER BY a.created_at, a.id
+        "#,
+    )
+    .bind(repo_id)
+    .bind(name)
+    .fetch_all(db)
+    .await
+    .map_err(crate::api::handlers::db_err)?;
+
+    // The SQL filter is a superset: the indexed name may come from the
+    // stored filename rather than the name column.
+    Ok(rows
+        .iter()
+        .map(|r| {
+            let path: String = r.get("path");
+            let version: Option<String> = r.get("version");
+            let (name, version) =
+                spec_index_coordinates(&path, r.get("name"), version.unwrap_or_default());
+            let metadata: Option<serde_json::Value> = r.try_get("metadata").ok().flatten();
+            let spec = build_gemspec(&name, &version, None, metadata);
+            let sha256: Option<String> = r.try_get("checksum_sha256").ok().flatten();
+            CompactEntry {
+                name,
+                spec,
+                sha256: sha256.unwrap_or_default(),
+            }
+        })
+        .filter(|entry| name.map(|n| entry.name == n).unwrap_or(true))
+        .collect())
+}
+
+/// [`query_compact_entries`] for one virtual member, minus the versions its
+/// routing patterns exclude.
+async fn query_routed_compact_entries(
+    db: &PgPool,
+    member_id: uuid::Uuid,
+    routes: &MemberRoutes,
+    name: Option<&str>,
+) -> Result<Vec<CompactEntry>, Response> {
+    let mut entries = query_compact_entries(db, member_id, name).await?;
+    entries.retain(|e| routes.allows(member_id, &compact_entry_route_path(e)));
+    Ok(entries)
+}
+
+/// The local gem versions the compact index serves for `repo`, so that
+/// `versions`, `info/<gem>` and `names` all describe the same set.
+///
+/// A virtual repository merges its non-remote members in priority order,
+/// minus what their routing patterns exclude. A version published by more
+/// than one member is listed once, from the member that serves its download.
+async fn local_compact_entries(
+    db: &PgPool,
+    repo: &RepoInfo,
+    name: Option<&str>,
+) -> Result<Vec<CompactEntry>, Response> {
+    if repo.repo_type != RepositoryType::Virtual {
+        return query_compact_entries(db, repo.id, name).await;
+    }
+
+    let members = proxy_helpers::fetch_virtual_members(db, repo.id).await?;
+    let routes = proxy_helpers::fetch_virtual_member_routes(db, repo.id).await?;
+    let mut seen = std::collections::HashSet::new();
+    let mut entries = Vec::new();
+    for member in members
+        .iter()
+        .filter(|m| m.repo_type != RepositoryType::Remote)
+    {
+        for entry in query_routed_compact_entries(db, member.id, &routes, name).await? {
+            if seen.insert(compact_entry_route_path(&entry)) {
+                entries.push(entry);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// The `.gem` download path of a compact-index entry.
+fn compact_entry_route_path(entry: &CompactEntry) -> String {
+    format!(
+        "gems/{}-{}.gem",
+        entry.name,
+        crate::formats::rubygems::compact_version(&entry.spec)
+    )
+}
+
+/// Compact-index `versions` lines of the given local entries, one per gem.
+fn compact_versions_lines(entries: &[CompactEntry]) -> Vec<String> {
+    use crate::formats::rubygems::{compact_info, compact_info_line, compact_version};
+
+    let mut by_gem: std::collections::BTreeMap<&str, (Vec<String>, Vec<String>)> =
+        std::collections::BTreeMap::new();
+    for entry in entries {
+        let (versions, lines) = by_gem.entry(entry.name.as_str()).or_default();
+        versions.push(compact_version(&entry.spec));
+        lines.push(compact_info_line(&entry.spec, &entry.sha256));
+    }
+    by_gem
+        .into_iter()
+        .map(|(name, (versions, lines))| {
+            crate::formats::rubygems::compact_versions_line(name, &versions, &compact_info(&lines))
+        })
+        .collect()
+}
+
+fn compact_text_response(body: String) -> Response {
+    use md5::{Digest, Md5};
+
+    let etag = format!("\"{:x}\"", Md5::digest(body.as_bytes()));
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(CONTENT_LENGTH, body.len().to_string())
+        .header(axum::http::header::ETAG, etag)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+/// Pass a compact-index file of a Remote repository through from upstream.
+async fn proxy_compact_file(
+    state: &SharedState,
+    repo: &RepoInfo,
+    upstream_path: &str,
+    max: usize,
+) -> Result<Response, Response> {
+    let (Some(upstream_url), Some(proxy)) = (&repo.upstream_url, &state.proxy_service) else {
+        return Err((StatusCode::NOT_FOUND, "Compact index not found").into_response());
+    };
+    let (content, _content_type) = proxy_helpers::proxy_fetch_capped(
+        proxy,
+        repo.id,
+        &repo.key,
+        upstream_url,
+        upstream_path,
+        max,
+    )
+    .await?;
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(Body::from(content))
+        .unwrap())
+}
+
+/// Lines after the `---` separator of an upstream compact-index file.
+fn compact_body_lines(bytes: &[u8]) -> Vec<String> {
+    let text = String::from_utf8_lossy(bytes);
+    text.split_once("---\n")
+        .map(|(_, body)| body)
+        .unwrap_or("")
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+async fn compact_versions_index(
+    State(state): State<SharedState>,
+    Path(repo_key): Path<String>,
+) -> Result<Response, Response> {
+    let repo = resolve_rubygems_repo(&state.db, &repo_key).await?;
+
+    if repo.repo_type == RepositoryType::Remote {
+        return proxy_compact_file(
+            &state,
+            &repo,
+            "versions",
+            proxy_helpers::LARGE_METADATA_MAX_BYTES,
+        )
+        .await;
+    }
+
+    let mut lines = Vec::new();
+    if repo.repo_type == RepositoryType::Virtual {
+        // Upstream lines first: Bundler keeps the last info checksum it reads
+        // for a gem, and `info/<gem>` serves a locally published gem over the
+        // upstream one, so local lines must come after.
+        let remote = proxy_helpers::collect_virtual_metadata(
+            &state.db,
+            state.proxy_service.as_deref(),
+            repo.id,
+            "versions",
+            |bytes, _member_key| async move { Ok(compact_body_lines(&bytes)) },
+        )
+        .await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
+        for (member_id, remote_lines) in remote {
+            lines.extend(remote_lines.into_iter().filter(|line| {
+                let name = line.split(' ').next().unwrap_or_default();
+                routes.allows(member_id, &gem_route_prefix(name))
+            }));
+        }
+    }
+    let entries = local_compact_entries(&state.db, &repo, None).await?;
+    lines.extend(compact_versions_lines(&entries));
+
+    let created_at = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    Ok(compact_text_response(
+        crate::formats::rubygems::compact_versions(&created_at, &lines),
+    ))
+}
+
+async fn compact_info_index(
+    State(state): State<SharedState>,
+    Path((repo_key, name)): Path<(String, String)>,
+) -> Result<Response, Response> {
+    use crate::formats::rubygems::{compact_info, compact_info_line};
+
+    let repo = resolve_rubygems_repo(&state.db, &repo_key).await?;
+    let upstream_path = format!("info/{}", name);
+
+    if repo.repo_type == RepositoryType::Remote {
+        return proxy_compact_file(
+            &state,
+            &repo,
+            &upstream_path,
+            proxy_helpers::DEFAULT_METADATA_MAX_BYTES,
+        )
+        .await;
+    }
+
+    // Built from the same entries as `versions`, so the info checksum listed
+    // there matches this file. A locally published gem is served over the
+    // upstream one; otherwise a virtual repository passes the upstream file
+    // through.
+    let entries = local_compact_entries(&state.db, &repo, Some(&name)).await?;
+    if !entries.is_empty() {
+        let lines: Vec<String> = entries
+            .iter()
+            .map(|e| compact_info_line(&e.spec, &e.sha256))
+            .collect();
+        return Ok(compact_text_response(compact_info(&lines)));
+    }
+
+    if repo.repo_type == RepositoryType::Virtual {
+        return proxy_helpers::resolve_virtual_metadata(
+            &state.db,
+            state.proxy_service.as_deref(),
+            repo.id,
+            &upstream_path,
+            &gem_route_prefix(&name),
+            |bytes, _member_key| async move {
+                Ok(Response::builder()
+                    .status(StatusCode::OK)
+                    .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+                    .body(Body::from(bytes))
+                    .unwrap())
+            },
+        )
+        .await;
+    }
+
+    Err((StatusCode::NOT_FOUND, "Gem not found").into_response())
+}
+
+async fn compact_names_index(
+    State(state): State<SharedState>,
+    Path(repo_key): Path<String>,
+) -> Result<Response, Response> {
+    let repo = resolve_rubygems_repo(&state.db, &repo_key).await?;
+
+    if repo.repo_type == RepositoryType::Remote {
+        return proxy_compact_file(
+            &state,
+            &repo,
+            "names",
+            proxy_helpers::LARGE_METADATA_MAX_BYTES,
+        )
+        .await;
+    }
+
+    let mut names = std::collections::BTreeSet::new();
+    if repo.repo_type == RepositoryType::Virtual {
+        let remote = proxy_helpers::collect_virtual_metadata(
+            &state.db,
+            state.proxy_service.as_deref(),
+            repo.id,
+            "names",
+            |bytes, _member_key| async move { Ok(compact_body_lines(&bytes)) },
+        )
+        .await?;
+        let routes = proxy_helpers::fetch_virtual_member_routes(&state.db, repo.id).await?;
+        for (member_id, remote_names) in remote {
+            names.extend(
+                remote_names
+                    .into_iter()
+                    .filter(|name| routes.allows(member_id, &gem_route_prefix(name))),
+            );
+        }
+    }
+    let entries = local_compact_entries(&state.db, &repo, None).await?;
+    names.extend(entries.into_iter().map(|e| e.name));
+
+    let names: Vec<String> = names.into_iter().collect();
+    Ok(compact_text_response(
+        crate::formats::rubygems::compact_names(&names),
+    ))
+}
+
 // ---------------------------------------------------------------------------
 // GET /gems/{repo_key}/api/v1/dependencies?gems={names} — Dependency info
 // ---------------------------------------------------------------------------
@@ -993,6 +1367,41 @@ mod tests {
         assert_eq!(version, "sha256-deadbeef");
     }
 
+    // -----------------------------------------------------------------------
+    // Compact index
+    // -----------------------------------------------------------------------
+
+    fn compact_entry(name: &str, version: &str) -> CompactEntry {
+        CompactEntry {
+            name: name.to_string(),
+            spec: build_gemspec(name, version, None, None),
+            sha256: format!("sha-{}", version),
+        }
+    }
+
+    #[test]
+    fn test_compact_versions_lines_groups_by_gem() {
+        let entries = vec![
+            compact_entry("rack", "1.0.0"),
+            compact_entry("actionpack", "7.0.0"),
+            compact_entry("rack", "1.1.0"),
+        ];
+        let lines = compact_versions_lines(&entries);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("actionpack 7.0.0 "));
+        assert!(lines[1].starts_with("rack 1.0.0,1.1.0 "));
+    }
+
+    #[test]
+    fn test_compact_body_lines_skips_header() {
+        let body = b"created_at: 2026-01-01T00:00:00Z\n---\nrack 1.0.0 abc\n\nrails 7.0.0 def\n";
+        assert_eq!(
+            compact_body_lines(body),
+            vec!["rack 1.0.0 abc".to_string(), "rails 7.0.0 def".to_string()]
+        );
+        assert!(compact_body_lines(b"not an index").is_empty());
+    }
+
     // -----------------------------------------------------------------------
     // DependencyQuery deserialization
     // -----------------------------------------------------------------------
@@ -1504,6 +1913,88 @@ mod tests {
         );
         f.teardown().await;
     }
+
+    /// The same gem published to two members of a virtual repository: the
+    /// compact `versions` line and `info/<gem>` are built from one merged
+    /// entry set, so a version both members carry is listed once and the info
+    /// checksum in `versions` matches the served info file.
+    #[tokio::test]
+    async fn test_rubygems_compact_index_virtual_merges_members_consistently() {
+        use md5::{Digest, Md5};
+
+        let Some(pool) = tdh::try_pool().await else {
+            return;
+        };
+        let (user_id, _username) = tdh::create_user(&pool).await;
+        let (first_id, _first_key, first_dir) = tdh::create_repo(&pool, "local", "rubygems").await;
+        let (second_id, _second_key, second_dir) =
+            tdh::create_repo(&pool, "local", "rubygems").await;
+        let (virtual_id, virtual_key, _virtual_dir) =
+            tdh::create_repo(&pool, "virtual", "rubygems").await;
+        let state = tdh::build_state(pool.clone(), first_dir.to_str().unwrap());
+
+        for (priority, member_id) in [(0, first_id), (1, second_id)] {
+            sqlx::query(
+                "INSERT INTO virtual_repo_members (virtual_repo_id, member_repo_id, priority) \
+                 VALUES ($1, $2, $3)",
+            )
+            .bind(virtual_id)
+            .bind(member_id)
+            .bind(priority)
+            .execute(&pool)
+            .await
+            .expect("link virtual member");
+        }
+
+        let first = tdh::make_repo_info(first_id, "first-gems", &first_dir, "local", None);
+        let second = tdh::make_repo_info(second_id, "second-gems", &second_dir, "local", None);
+        for (repo, version) in [(&first, "1.0.0"), (&second, "1.0.0"), (&second, "1.1.0")] {
+            tdh::seed_artifact(
+                &state,
+                &pool,
+                repo,
+                &format!("rubygems/rack/{version}/rack-{version}.gem"),
+                &format!("rack/{version}/rack-{version}.gem"),
+                "rack",
+                version,
+                "application/octet-stream",
+                bytes::Bytes::from_static(b"gem-data"),
+                user_id,
+            )
+            .await;
+        }
+
+        let app = tdh::router_anon(super::router(), state.clone());
+        let (status, versions) =
+            tdh::send(app, tdh::get(format!("/{}/versions", virtual_key))).await;
+        assert_eq!(status, StatusCode::OK);
+        let versions = String::from_utf8_lossy(&versions).to_string();
+        let rack: Vec<&str> = versions
+            .lines()
+            .filter(|l| l.starts_with("rack "))
+            .collect();
+        assert_eq!(rack.len(), 1, "one versions line per gem: {versions}");
+        let fields: Vec<&str> = rack[0].split(' ').collect();
+        assert_eq!(fields[1], "1.0.0,1.1.0");
+
+        let app = tdh::router_anon(super::router(), state.clone());
+        let (status, info) = tdh::send(app, tdh::get(format!("/{}/info/rack", virtual_key))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            String::from_utf8_lossy(&info).lines().skip(1).count(),
+            2,
+            "info lists each version once"
+        );
+        assert_eq!(
+            fields[2],
+            format!("{:x}", Md5::digest(&info[..])),
+            "versions must carry the checksum of the served info file"
+        );
+
+        tdh::cleanup(&pool, virtual_id, user_id).await;
+        tdh::cleanup(&pool, second_id, user_id).await;
+        tdh::cleanup(&pool, first_id, user_id).await;
+    }
 }
 
 #[cfg(test)]
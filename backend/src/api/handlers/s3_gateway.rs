@@ -0,0 +1,942 @@
+//! S3-compatible gateway over generic repositories.
+//!
+//! Path-style S3 endpoints under `/s3` (enabled with `S3_GATEWAY_ENABLED`)
+//! so tools that only speak S3 can read and write artifacts: each generic
+//! repository is a bucket and each artifact path an object key. Supported
+//! operations are ListBuckets, HeadBucket, GetBucketLocation, ListObjectsV2,
+//! GetObject, HeadObject and PutObject.
+//!
+//! Requests are authenticated with AWS Signature V4 only. Access keys are
+//! issued per API token under `/api/v1/s3/access-keys`; the client's secret
+//! access key is the API token itself, and each request is authorized with
+//! that token's scopes and repository restrictions. Reads and writes go
+//! through the same handlers as the REST artifact API, so visibility,
+//! quarantine, fine-grained permissions and upload gates all apply, and the
+//! external authorization hook is consulted on every bucket and object.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::handlers::packages::repo_visibility_for;
+use crate::api::handlers::proxy_helpers;
+use crate::api::handlers::repositories::{self, require_visible, ArtifactVersionQuery};
+use crate::api::middleware::auth::{validate_api_token_with_scopes, AuthExtension, TokenAuthError};
+use crate::api::middleware::download_telemetry::DownloadContext;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::models::repository::{Repository, RepositoryFormat};
+use crate::services::auth_service::AuthService;
+use crate::services::repository_service::RepositoryService;
+use crate::services::s3_gateway_service::{
+    self as s3, decode_continuation, ListPage, S3AccessKey, S3GatewayService, SigV4Error,
+};
+use crate::services::upload_service;
+
+const S3_XMLNS: &str = "http://s3.amazonaws.com/doc/2006-03-01/";
+
+/// Long-lived token validator for the gateway, so API-token validations are
+/// cached across requests like on the other format endpoints.
+#[derive(Clone)]
+struct GatewayAuth(Arc<AuthService>);
+
+/// S3 protocol routes, nested at `/s3`.
+pub fn router(state: &SharedState) -> Router<SharedState> {
+    let auth_service = Arc::new(AuthService::new(
+        state.db.clone(),
+        Arc::new(state.config.clone()),
+    ));
+    auth_service.register_for_global_flush();
+
+    Router::new()
+        .route("/", get(list_buckets))
+        .route("/:bucket", get(get_bucket).head(head_bucket))
+        .route("/:bucket/", get(get_bucket).head(head_bucket))
+        .route("/:bucket/*key", get(get_object).put(put_object))
+        .layer(Extension(GatewayAuth(auth_service)))
+}
+
+/// Access key management, nested at `/api/v1/s3/access-keys` behind
+/// `auth_middleware`.
+pub fn access_keys_router() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_access_keys).post(create_access_key))
+        .route("/:id", delete(delete_access_key))
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// An S3 `<Error>` document.
+#[derive(Debug)]
+struct S3Error {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    resource: String,
+}
+
+impl S3Error {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+            resource: String::new(),
+        }
+    }
+
+    fn at(mut self, resource: &str) -> Self {
+        self.resource = resource.to_string();
+        self
+    }
+
+    fn no_such_bucket(bucket: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "NoSuchBucket",
+            format!("The specified bucket does not exist: {}", bucket),
+        )
+    }
+
+    /// Map an error status from the shared artifact handlers onto the
+    /// closest S3 error code.
+    fn from_status(status: StatusCode, message: String) -> Self {
+        let (status, code) = match status.as_u16() {
+            400 | 422 => (StatusCode::BAD_REQUEST, "InvalidRequest"),
+            401 | 403 => (StatusCode::FORBIDDEN, "AccessDenied"),
+            404 => (StatusCode::NOT_FOUND, "NoSuchKey"),
+            409 => (StatusCode::CONFLICT, "InvalidObjectState"),
+            413 => (StatusCode::BAD_REQUEST, "EntityTooLarge"),
+            429 | 503 => (StatusCode::SERVICE_UNAVAILABLE, "SlowDown"),
+            501 => (StatusCode::NOT_IMPLEMENTED, "NotImplemented"),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "InternalError"),
+        };
+        Self::new(status, code, message)
+    }
+}
+
+impl From<SigV4Error> for S3Error {
+    fn from(e: SigV4Error) -> Self {
+        let (status, code) = match e {
+            SigV4Error::MissingAuthorization => (StatusCode::FORBIDDEN, "AccessDenied"),
+            SigV4Error::Malformed(_) => (StatusCode::BAD_REQUEST, "AuthorizationHeaderMalformed"),
+            SigV4Error::BadDate => (StatusCode::FORBIDDEN, "AccessDenied"),
+            SigV4Error::TimeSkewed => (StatusCode::FORBIDDEN, "RequestTimeTooSkewed"),
+            SigV4Error::SignatureMismatch => (StatusCode::FORBIDDEN, "SignatureDoesNotMatch"),
+            SigV4Error::StreamingPayload => (StatusCode::NOT_IMPLEMENTED, "NotImplemented"),
+        };
+        Self::new(status, code, e.to_string())
+    }
+}
+
+impl From<AppError> for S3Error {
+    fn from(e: AppError) -> Self {
+        let message = e.to_string();
+        let status = e.into_response().status();
+        // Internal error details stay in the server log.
+        let message = if status.is_server_error() {
+            status_reason(status)
+        } else {
+            message
+        };
+        Self::from_status(status, message)
+    }
+}
+
+fn status_reason(status: StatusCode) -> String {
+    status
+        .canonical_reason()
+        .unwrap_or("Request failed")
+        .to_string()
+}
+
+impl IntoResponse for S3Error {
+    fn into_response(self) -> Response {
+        let body = error_xml(self.code, &self.message, &self.resource);
+        (
+            self.status,
+            [(header::CONTENT_TYPE, "application/xml")],
+            body,
+        )
+            .into_response()
+    }
+}
+
+/// Pass a successful response from the shared artifact handlers through;
+/// re-render an error as an S3 `<Error>` using its JSON `message`.
+async fn s3_response(response: Response, resource: &str) -> Response {
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+    let body = axum::body::to_bytes(response.into_body(), 64 * 1024)
+        .await
+        .unwrap_or_default();
+    let message = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| {
+            v.get("message")
+                .and_then(|m| m.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| status_reason(status));
+    S3Error::from_status(status, message)
+        .at(resource)
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// XML
+// ---------------------------------------------------------------------------
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn s3_timestamp(t: DateTime<Utc>) -> String {
+    t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn error_xml(code: &str, message: &str, resource: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Error><Code>{}</Code><Message>{}</Message><Resource>{}</Resource></Error>",
+        code,
+        xml_escape(message),
+        xml_escape(resource)
+    )
+}
+
+fn list_buckets_xml(
+    owner_id: Uuid,
+    owner_name: &str,
+    buckets: &[(String, DateTime<Utc>)],
+) -> String {
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ListAllMyBucketsResult xmlns=\"{}\"><Owner><ID>{}</ID><DisplayName>{}</DisplayName></Owner><Buckets>",
+        S3_XMLNS,
+        owner_id,
+        xml_escape(owner_name)
+    );
+    for (name, created_at) in buckets {
+        out.push_str(&format!(
+            "<Bucket><Name>{}</Name><CreationDate>{}</CreationDate></Bucket>",
+            xml_escape(name),
+            s3_timestamp(*created_at)
+        ));
+    }
+    out.push_str("</Buckets></ListAllMyBucketsResult>");
+    out
+}
+
+fn location_xml(region: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<LocationConstraint xmlns=\"{}\">{}</LocationConstraint>",
+        S3_XMLNS,
+        xml_escape(region)
+    )
+}
+
+/// ListObjectsV2 query parameters.
+#[derive(Debug, Default, Deserialize)]
+struct ListObjectsQuery {
+    #[serde(rename = "list-type")]
+    list_type: Option<String>,
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    #[serde(rename = "max-keys")]
+    max_keys: Option<usize>,
+    #[serde(rename = "continuation-token")]
+    continuation_token: Option<String>,
+    #[serde(rename = "start-after")]
+    start_after: Option<String>,
+    #[serde(rename = "encoding-type")]
+    encoding_type: Option<String>,
+    location: Option<String>,
+}
+
+fn list_objects_xml(
+    bucket: &str,
+    query: &ListObjectsQuery,
+    max_keys: usize,
+    page: &ListPage,
+) -> String {
+    let url_encode = query.encoding_type.as_deref() == Some("url");
+    let enc = |s: &str| {
+        if url_encode {
+            s3::url_encode_key(s)
+        } else {
+            xml_escape(s)
+        }
+    };
+
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ListBucketResult xmlns=\"{}\"><Name>{}</Name><Prefix>{}</Prefix>",
+        S3_XMLNS,
+        xml_escape(bucket),
+        enc(query.prefix.as_deref().unwrap_or_default())
+    );
+    if let Some(d) = &query.delimiter {
+        out.push_str(&format!("<Delimiter>{}</Delimiter>", enc(d)));
+    }
+    if url_encode {
+        out.push_str("<EncodingType>url</EncodingType>");
+    }
+    out.push_str(&format!(
+        "<MaxKeys>{}</MaxKeys><KeyCount>{}</KeyCount><IsTruncated>{}</IsTruncated>",
+        max_keys,
+        page.contents.len() + page.common_prefixes.len(),
+        page.is_truncated
+    ));
+    if let Some(t) = &query.continuation_token {
+        out.push_str(&format!(
+            "<ContinuationToken>{}</ContinuationToken>",
+            xml_escape(t)
+        ));
+    }
+    if let Some(t) = &page.next_continuation_token {
+        out.push_str(&format!(
+            "<NextContinuationToken>{}</NextContinuationToken>",
+            xml_escape(t)
+        ));
+    }
+    if let Some(s) = &query.start_after {
+        out.push_str(&format!("<StartAfter>{}</StartAfter>", enc(s)));
+    }
+    for object in &page.contents {
+        out.push_str(&format!(
+            "<Contents><Key>{}</Key><LastModified>{}</LastModified><ETag>&quot;{}&quot;</ETag><Size>{}</Size><StorageClass>STANDARD</StorageClass></Contents>",
+            enc(&object.key),
+            s3_timestamp(object.last_modified),
+            xml_escape(object.etag.trim()),
+            object.size_bytes
+        ));
+    }
+    for cp in &page.common_prefixes {
+        out.push_str(&format!(
+            "<CommonPrefixes><Prefix>{}</Prefix></CommonPrefixes>",
+            enc(cp)
+        ));
+    }
+    out.push_str("</ListBucketResult>");
+    out
+}
+
+fn xml_response(body: String) -> Response {
+    ([(header::CONTENT_TYPE, "application/xml")], body).into_response()
+}
+
+// ---------------------------------------------------------------------------
+// Authentication
+// ---------------------------------------------------------------------------
+
+/// Verify the request's SigV4 signature and resolve the API token behind the
+/// access key. Returns the caller and the declared payload hash.
+async fn authenticate(
+    state: &SharedState,
+    auth_service: &AuthService,
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+) -> std::result::Result<(AuthExtension, String), S3Error> {
+    let header_str = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    let authorization = header_str("authorization").ok_or(SigV4Error::MissingAuthorization)?;
+    let sig = s3::parse_authorization(authorization)?;
+    let amz_date = header_str("x-amz-date").ok_or(SigV4Error::BadDate)?;
+    s3::check_request_time(amz_date, &sig.date, Utc::now())?;
+    let payload_hash = header_str("x-amz-content-sha256")
+        .unwrap_or(s3::UNSIGNED_PAYLOAD)
+        .to_string();
+    s3::check_payload_hash(&payload_hash)?;
+
+    let token = S3GatewayService::new(state.db.clone())
+        .resolve_token(&sig.access_key_id)
+        .await?
+        .ok_or_else(|| {
+            S3Error::new(
+                StatusCode::FORBIDDEN,
+                "InvalidAccessKeyId",
+                "The access key ID you provided does not exist in our records",
+            )
+        })?;
+
+    let canonical = s3::canonical_request(
+        method.as_str(),
+        uri.path(),
+        uri.query(),
+        headers,
+        &sig.signed_headers,
+        &payload_hash,
+    )?;
+    let string_to_sign = s3::string_to_sign(amz_date, &sig.scope(), &canonical);
+    s3::verify_signature(&token, &sig, &string_to_sign)?;
+
+    let auth = validate_api_token_with_scopes(auth_service, &token)
+        .await
+        .map_err(|e| match e {
+            TokenAuthError::Overloaded => S3Error::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "SlowDown",
+                "Please reduce your request rate",
+            ),
+            TokenAuthError::Invalid => S3Error::new(
+                StatusCode::FORBIDDEN,
+                "InvalidAccessKeyId",
+                "The API token behind this access key is revoked, expired or inactive",
+            ),
+        })?;
+
+    Ok((auth, payload_hash))
+}
+
+/// Resolve a bucket to a generic repository the caller can read. Other
+/// formats and invisible repositories are reported as missing; a read the
+/// external authorization hook refuses is `AccessDenied`. `key` is the
+/// object being read, if any.
+async fn resolve_bucket(
+    state: &SharedState,
+    auth: &AuthExtension,
+    bucket: &str,
+    key: Option<&str>,
+) -> std::result::Result<Repository, S3Error> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service
+        .get_by_key(bucket)
+        .await
+        .map_err(|_| S3Error::no_such_bucket(bucket))?;
+    if repo.format != RepositoryFormat::Generic {
+        return Err(S3Error::no_such_bucket(bucket));
+    }
+    require_visible(&repo, &Some(auth.clone()), &repo_service)
+        .await
+        .map_err(|_| S3Error::no_such_bucket(bucket))?;
+    if !state
+        .permission_service
+        .authorize_external(Some(auth.authz_user()), "read", repo.id, key)
+        .await?
+    {
+        return Err(S3Error::new(
+            StatusCode::FORBIDDEN,
+            "AccessDenied",
+            "Access denied by authorization policy",
+        ));
+    }
+    Ok(repo)
+}
+
+// ---------------------------------------------------------------------------
+// S3 operations
+// ---------------------------------------------------------------------------
+
+/// ListBuckets: generic repositories visible to the caller.
+async fn list_buckets(
+    State(state): State<SharedState>,
+    Extension(GatewayAuth(auth_service)): Extension<GatewayAuth>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let result = async {
+        let (auth, _) = authenticate(&state, &auth_service, &method, &uri, &headers).await?;
+        let visibility = repo_visibility_for(Some(&auth));
+        let buckets = S3GatewayService::new(state.db.clone())
+            .list_buckets(&visibility)
+            .await?;
+        Ok::<_, S3Error>(xml_response(list_buckets_xml(
+            auth.user_id,
+            &auth.username,
+            &buckets,
+        )))
+    }
+    .await;
+    result.unwrap_or_else(|e| e.at("/").into_response())
+}
+
+/// HeadBucket.
+async fn head_bucket(
+    State(state): State<SharedState>,
+    Extension(GatewayAuth(auth_service)): Extension<GatewayAuth>,
+    Path(bucket): Path<String>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let result = async {
+        let (auth, _) = authenticate(&state, &auth_service, &method, &uri, &headers).await?;
+        resolve_bucket(&state, &auth, &bucket, None).await?;
+        Ok::<_, S3Error>(StatusCode::OK.into_response())
+    }
+    .await;
+    // HEAD responses carry no body; the status is what clients read.
+    result.unwrap_or_else(|e| e.status.into_response())
+}
+
+/// ListObjectsV2 or GetBucketLocation.
+async fn get_bucket(
+    State(state): State<SharedState>,
+    Extension(GatewayAuth(auth_service)): Extension<GatewayAuth>,
+    Path(bucket): Path<String>,
+    Query(query): Query<ListObjectsQuery>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let resource = format!("/{}", bucket);
+    let result = async {
+        let (auth, _) = authenticate(&state, &auth_service, &method, &uri, &headers).await?;
+        let repo = resolve_bucket(&state, &auth, &bucket, None).await?;
+
+        if query.location.is_some() {
+            return Ok(xml_response(location_xml(&state.config.s3_gateway_region)));
+        }
+        if query.list_type.as_deref() != Some("2") {
+            return Err(S3Error::new(
+                StatusCode::NOT_IMPLEMENTED,
+                "NotImplemented",
+                "Only ListObjectsV2 (list-type=2) is supported",
+            ));
+        }
+
+        let max_keys = query
+            .max_keys
+            .unwrap_or(s3::MAX_LIST_KEYS)
+            .min(s3::MAX_LIST_KEYS);
+        let start_after = match &query.continuation_token {
+            Some(token) => Some(decode_continuation(token)?),
+            None => query.start_after.clone(),
+        };
+        let page = S3GatewayService::new(state.db.clone())
+            .list_objects(
+                repo.id,
+                query.prefix.as_deref().unwrap_or_default(),
+                query.delimiter.as_deref(),
+                start_after.as_deref(),
+                max_keys,
+            )
+            .await?;
+        Ok(xml_response(list_objects_xml(
+            &bucket, &query, max_keys, &page,
+        )))
+    }
+    .await;
+    result.unwrap_or_else(|e| e.at(&resource).into_response())
+}
+
+/// GetObject / HeadObject, served by the REST artifact download handler.
+async fn get_object(
+    State(state): State<SharedState>,
+    Extension(GatewayAuth(auth_service)): Extension<GatewayAuth>,
+    Path((bucket, key)): Path<(String, String)>,
+    mut ctx: DownloadContext,
+    request: Request,
+) -> Response {
+    let resource = format!("/{}/{}", bucket, key);
+    let auth = match authenticate(
+        &state,
+        &auth_service,
+        request.method(),
+        request.uri(),
+        request.headers(),
+    )
+    .await
+    {
+        Ok((auth, _)) => auth,
+        Err(e) => return e.at(&resource).into_response(),
+    };
+    if let Err(e) = resolve_bucket(&state, &auth, &bucket, Some(&key)).await {
+        return e.at(&resource).into_response();
+    }
+
+    ctx.user_id = Some(auth.user_id);
+    let response = repositories::download_artifact(
+        State(state),
+        Extension(Some(auth)),
+        Path((bucket, key)),
+        Query(ArtifactVersionQuery { version: None }),
+        ctx,
+        request,
+    )
+    .await
+    .into_response();
+    s3_response(response, &resource).await
+}
+
+/// PutObject, persisted through the generic upload path.
+async fn put_object(
+    State(state): State<SharedState>,
+    Extension(GatewayAuth(auth_service)): Extension<GatewayAuth>,
+    Path((bucket, key)): Path<(String, String)>,
+    request: Request,
+) -> Response {
+    let resource = format!("/{}/{}", bucket, key);
+    let (parts, body) = request.into_parts();
+    let (auth, payload_hash) = match authenticate(
+        &state,
+        &auth_service,
+        &parts.method,
+        &parts.uri,
+        &parts.headers,
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(e) => return e.at(&resource).into_response(),
+    };
+    if let Err(e) = auth.require_scope("write") {
+        return S3Error::from(e).at(&resource).into_response();
+    }
+    if let Err(e) = upload_service::validate_artifact_path(&key) {
+        return S3Error::new(StatusCode::BAD_REQUEST, "InvalidArgument", e.to_string())
+            .at(&resource)
+            .into_response();
+    }
+
+    let (repo_service, repo) =
+        match repositories::authorize_generic_upload(&state, &auth, &bucket).await {
+            Ok(v) => v,
+            Err(response) => return s3_response(response, &resource).await,
+        };
+    if repo.format != RepositoryFormat::Generic {
+        return S3Error::no_such_bucket(&bucket)
+            .at(&resource)
+            .into_response();
+    }
+
+    let (staged, digests) = match proxy_helpers::stage_stream_content_addressed(
+        &state,
+        body.into_data_stream(),
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(response) => return s3_response(response, &resource).await,
+    };
+    if let Err(e) = check_body_digests(&parts.headers, &payload_hash, &digests) {
+        return e.at(&resource).into_response();
+    }
+
+    let etag = format!("\"{}\"", digests.md5);
+    match repositories::persist_generic_staged_upload(
+        &state,
+        &auth,
+        &repo_service,
+        &repo,
+        bucket,
+        key,
+        &parts.headers,
+        staged,
+        digests,
+    )
+    .await
+    {
+        Ok(_) => {
+            let mut response = StatusCode::OK.into_response();
+            if let Ok(value) = HeaderValue::from_str(&etag) {
+                response.headers_mut().insert(header::ETAG, value);
+            }
+            response
+        }
+        Err(response) => s3_response(response, &resource).await,
+    }
+}
+
+/// Check the body against a signed `x-amz-content-sha256` and an optional
+/// `Content-MD5`.
+fn check_body_digests(
+    headers: &HeaderMap,
+    payload_hash: &str,
+    digests: &crate::services::artifact_service::ContentDigests,
+) -> std::result::Result<(), S3Error> {
+    if s3::is_sha256_hex(payload_hash) && !payload_hash.eq_ignore_ascii_case(&digests.sha256) {
+        return Err(S3Error::new(
+            StatusCode::BAD_REQUEST,
+            "XAmzContentSHA256Mismatch",
+            "The provided 'x-amz-content-sha256' header does not match what was computed",
+        ));
+    }
+    if let Some(content_md5) = headers.get("content-md5").and_then(|v| v.to_str().ok()) {
+        use base64::Engine as _;
+        let matches = base64::engine::general_purpose::STANDARD
+            .decode(content_md5.trim())
+            .map(|md5| hex::encode(md5) == digests.md5)
+            .unwrap_or(false);
+        if !matches {
+            return Err(S3Error::new(
+                StatusCode::BAD_REQUEST,
+                "BadDigest",
+                "The Content-MD5 you specified did not match what we received",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Access key management
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateS3AccessKeyRequest {
+    /// An API token owned by the caller. It becomes the secret access key.
+    pub token: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct S3AccessKeyListResponse {
+    pub items: Vec<S3AccessKey>,
+}
+
+/// List the caller's S3 access keys
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/s3/access-keys",
+    tag = "s3-gateway",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Access keys", body = S3AccessKeyListResponse),
+    )
+)]
+pub async fn list_access_keys(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<S3AccessKeyListResponse>> {
+    let items = S3GatewayService::new(state.db.clone())
+        .list_access_keys(auth.user_id)
+        .await?;
+    Ok(Json(S3AccessKeyListResponse { items }))
+}
+
+/// Issue an S3 access key for one of the caller's API tokens
+///
+/// S3 clients sign with the returned `access_key_id` and the API token as
+/// the secret access key.
+#[utoipa::path(
+    post,
+    path = "",
+    context_path = "/api/v1/s3/access-keys",
+    tag = "s3-gateway",
+    request_body = CreateS3AccessKeyRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Access key issued", body = S3AccessKey),
+        (status = 400, description = "Token is invalid or not owned by the caller"),
+    )
+)]
+pub async fn create_access_key(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<CreateS3AccessKeyRequest>,
+) -> Result<Json<S3AccessKey>> {
+    let description = validate_description(payload.description.as_deref())?;
+    let token = payload.token.trim();
+
+    let auth_service = AuthService::new(state.db.clone(), Arc::new(state.config.clone()));
+    let validation = auth_service
+        .validate_api_token(token)
+        .await
+        .map_err(|_| AppError::Validation("Invalid API token".to_string()))?;
+    if validation.user.id != auth.user_id {
+        return Err(AppError::Validation(
+            "The API token must belong to the caller".to_string(),
+        ));
+    }
+
+    let key = S3GatewayService::new(state.db.clone())
+        .create_access_key(auth.user_id, token, description.as_deref())
+        .await?;
+    Ok(Json(key))
+}
+
+/// Delete one of the caller's S3 access keys
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    context_path = "/api/v1/s3/access-keys",
+    tag = "s3-gateway",
+    params(("id" = Uuid, Path, description = "Access key record ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Access key deleted"),
+        (status = 404, description = "Access key not found"),
+    )
+)]
+pub async fn delete_access_key(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let removed = S3GatewayService::new(state.db.clone())
+        .delete_access_key(auth.user_id, id)
+        .await?;
+    if !removed {
+        return Err(AppError::NotFound("Access key not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn validate_description(description: Option<&str>) -> Result<Option<String>> {
+    let description = description.map(str::trim).filter(|d| !d.is_empty());
+    if description.is_some_and(|d| d.chars().count() > 255) {
+        return Err(AppError::Validation(
+            "description must be at most 255 characters".to_string(),
+        ));
+    }
+    Ok(description.map(str::to_string))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_access_keys, create_access_key, delete_access_key),
+    components(schemas(S3AccessKey, CreateS3AccessKeyRequest, S3AccessKeyListResponse)),
+    tags((name = "s3-gateway", description = "S3-compatible gateway access keys"))
+)]
+pub struct S3GatewayApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::s3_gateway_service::S3Object;
+
+    #[test]
+    fn test_error_xml_escapes() {
+        let xml = error_xml("NoSuchKey", "missing <a&b>", "/b/k");
+        assert!(xml.contains("<Code>NoSuchKey</Code>"));
+        assert!(xml.contains("missing &lt;a&amp;b&gt;"));
+        assert!(xml.contains("<Resource>/b/k</Resource>"));
+    }
+
+    #[test]
+    fn test_from_status_maps_codes() {
+        let e = S3Error::from_status(StatusCode::NOT_FOUND, "gone".to_string());
+        assert_eq!((e.status, e.code), (StatusCode::NOT_FOUND, "NoSuchKey"));
+        let e = S3Error::from_status(StatusCode::UNAUTHORIZED, String::new());
+        assert_eq!((e.status, e.code), (StatusCode::FORBIDDEN, "AccessDenied"));
+        let e = S3Error::from_status(StatusCode::PAYLOAD_TOO_LARGE, String::new());
+        assert_eq!(e.code, "EntityTooLarge");
+        let e = S3Error::from_status(StatusCode::BAD_GATEWAY, String::new());
+        assert_eq!(e.code, "InternalError");
+    }
+
+    #[test]
+    fn test_sigv4_errors_map_to_s3_codes() {
+        assert_eq!(
+            S3Error::from(SigV4Error::SignatureMismatch).code,
+            "SignatureDoesNotMatch"
+        );
+        assert_eq!(
+            S3Error::from(SigV4Error::TimeSkewed).code,
+            "RequestTimeTooSkewed"
+        );
+        assert_eq!(
+            S3Error::from(SigV4Error::StreamingPayload).status,
+            StatusCode::NOT_IMPLEMENTED
+        );
+    }
+
+    #[test]
+    fn test_list_objects_xml() {
+        let page = ListPage {
+            contents: vec![S3Object {
+                key: "pkg/a b.txt".to_string(),
+                size_bytes: 42,
+                etag: "abc".to_string(),
+                last_modified: DateTime::from_timestamp(0, 0).unwrap(),
+            }],
+            common_prefixes: vec!["docs/".to_string()],
+            is_truncated: true,
+            next_continuation_token: Some("tok".to_string()),
+        };
+        let query = ListObjectsQuery {
+            list_type: Some("2".to_string()),
+            delimiter: Some("/".to_string()),
+            ..Default::default()
+        };
+        let xml = list_objects_xml("bucket", &query, 2, &page);
+        assert!(xml.contains("<Key>pkg/a b.txt</Key>"));
+        assert!(xml.contains("<KeyCount>2</KeyCount>"));
+        assert!(xml.contains("<IsTruncated>true</IsTruncated>"));
+        assert!(xml.contains("<NextContinuationToken>tok</NextContinuationToken>"));
+        assert!(xml.contains("<CommonPrefixes><Prefix>docs/</Prefix></CommonPrefixes>"));
+        assert!(xml.contains("<LastModified>1970-01-01T00:00:00.000Z</LastModified>"));
+
+        let query = ListObjectsQuery {
+            encoding_type: Some("url".to_string()),
+            ..query
+        };
+        let xml = list_objects_xml("bucket", &query, 2, &page);
+        assert!(xml.contains("<Key>pkg/a%20b.txt</Key>"));
+        assert!(xml.contains("<EncodingType>url</EncodingType>"));
+    }
+
+    #[test]
+    fn test_list_buckets_xml() {
+        let xml = list_buckets_xml(
+            Uuid::nil(),
+            "alice",
+            &[(
+                "generic-local".to_string(),
+                DateTime::from_timestamp(0, 0).unwrap(),
+            )],
+        );
+        assert!(xml.contains("<Name>generic-local</Name>"));
+        assert!(xml.contains("<DisplayName>alice</DisplayName>"));
+    }
+
+    #[test]
+    fn test_check_body_digests() {
+        let digests = crate::services::artifact_service::ContentDigests {
+            sha256: "a".repeat(64),
+            sha1: String::new(),
+            md5: "d41d8cd98f00b204e9800998ecf8427e".to_string(),
+            sha512: None,
+            blake3: None,
+        };
+        let headers = HeaderMap::new();
+        assert!(check_body_digests(&headers, s3::UNSIGNED_PAYLOAD, &digests).is_ok());
+        assert!(check_body_digests(&headers, &"a".repeat(64), &digests).is_ok());
+        assert_eq!(
+            check_body_digests(&headers, &"b".repeat(64), &digests)
+                .unwrap_err()
+                .code,
+            "XAmzContentSHA256Mismatch"
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-md5", "1B2M2Y8AsgTpgAmY7PhCfg==".parse().unwrap());
+        assert!(check_body_digests(&headers, s3::UNSIGNED_PAYLOAD, &digests).is_ok());
+        headers.insert("content-md5", "AAAAAAAAAAAAAAAAAAAAAA==".parse().unwrap());
+        assert_eq!(
+            check_body_digests(&headers, s3::UNSIGNED_PAYLOAD, &digests)
+                .unwrap_err()
+                .code,
+            "BadDigest"
+        );
+    }
+
+    #[test]
+    fn test_validate_description() {
+        assert_eq!(validate_description(Some("  ")).unwrap(), None);
+        assert_eq!(
+            validate_description(Some(" backups ")).unwrap(),
+            Some("backups".to_string())
+        );
+        assert!(validate_description(Some(&"x".repeat(256))).is_err());
+    }
+}
@@ -129,7 +129,7 @@ pub(crate) fn build_search_result_item(r: SearchResult) -> SearchResultItem {
         version: r.version,
         size_bytes: Some(r.size_bytes),
         created_at: r.created_at,
-        highlights: None,
+        highlights: (!r.highlights.is_empty()).then_some(r.highlights),
     }
 }
 
@@ -177,8 +177,10 @@ pub(crate) fn resolve_checksum_column(algorithm: &str) -> Result<&'static str> {
         "sha256" => Ok("a.checksum_sha256"),
         "sha1" => Ok("a.checksum_sha1"),
         "md5" => Ok("a.checksum_md5"),
+        "sha512" => Ok("a.checksum_sha512"),
+        "blake3" => Ok("a.checksum_blake3"),
         other => Err(AppError::Validation(format!(
-            "Unsupported checksum algorithm: {other}. Use sha256, sha1, or md5."
+            "Unsupported checksum algorithm: {other}. Use sha256, sha1, md5, sha512, or blake3."
         ))),
     }
 }
@@ -1489,6 +1491,18 @@ mod tests {
         assert_eq!(resolve_checksum_column("md5").unwrap(), "a.checksum_md5");
     }
 
+    #[test]
+    fn test_resolve_checksum_column_sha512_and_blake3() {
+        assert_eq!(
+            resolve_checksum_column("sha512").unwrap(),
+            "a.checksum_sha512"
+        );
+        assert_eq!(
+            resolve_checksum_column("blake3").unwrap(),
+            "a.checksum_blake3"
+        );
+    }
+
     #[test]
     fn test_resolve_checksum_column_invalid() {
         let result = resolve_checksum_column("sha512");
@@ -1789,6 +1803,7 @@ mod tests {
             created_at: chrono::Utc::now(),
             download_count: 7,
             score: 0.42,
+            highlights: Vec::new(),
         }
     }
 
@@ -1810,6 +1825,17 @@ mod tests {
         assert!(item.highlights.is_none());
     }
 
+    #[test]
+    fn test_build_search_result_item_carries_doc_highlights() {
+        let mut r = mk_search_result("lib");
+        r.highlights = vec!["a <mark>fast</mark> parser".to_string()];
+        let item = build_search_result_item(r);
+        assert_eq!(
+            item.highlights,
+            Some(vec!["a <mark>fast</mark> parser".to_string()])
+        );
+    }
+
     #[test]
     fn test_build_search_result_item_result_type_is_always_artifact() {
         // The five handlers all populate `result_type = "artifact"`; this is
@@ -1,8 +1,8 @@
 //! Security scanning and policy management handlers.
 
 use axum::{
-    extract::{Extension, Path, Query, State},
-    routing::{delete, get, post},
+    extract::{DefaultBodyLimit, Extension, Path, Query, State},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
@@ -12,16 +12,38 @@ use uuid::Uuid;
 
 use crate::api::handlers::artifacts::check_artifact_visibility;
 use crate::api::handlers::repositories::{
-    require_repo_admin, require_repo_write_access, require_visible,
+    require_repo_admin, require_repo_id_visible, require_repo_write_access, require_visible,
 };
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
 use crate::models::security::ScanResult;
-use crate::services::policy_service::PolicyService;
+use crate::services::compliance_scan_service::{
+    self, AvailableProfiles, ComplianceProfile, ComplianceProfileRequest, ComplianceReport,
+    ComplianceScanSummary, ComplianceScanner, ScanTarget,
+};
+use crate::services::external_scan_ingest::{
+    self, ExternalReportFormat, ExternalScanIngestService,
+};
+use crate::services::policy_decision_service::{
+    PolicyDecisionQuery, PolicyDecisionRecord, PolicyDecisionService,
+};
+use crate::services::policy_service::{
+    EffectivePolicy, EffectiveRule, LayeredPolicy, PolicyLayer, PolicyService,
+};
+use crate::services::repo_scan_config_service::{
+    RepoScanConfig, RepoScanConfigService, ScanEngine, SetRepoScanConfigRequest,
+};
 use crate::services::repository_service::RepositoryService;
 use crate::services::scan_config_service::{ScanConfigService, UpsertScanConfigRequest};
+use crate::services::scan_queue_service::{
+    self, QueueStats, RepositoryPriority, ScanJob, ScanJobOptions, ScanTrigger,
+};
 use crate::services::scan_result_service::ScanResultService;
+use crate::services::vulnerability_service::{
+    self, ArtifactVulnerability, PackageVulnerabilityStatus, Vulnerability, VulnerabilityService,
+    VulnerabilitySummary,
+};
 
 /// Canonical 404 body for the scan-by-id read routes. Both "this scan id does
 /// not exist" (from `ScanResultService::get_scan`) and "the scan exists but the
@@ -41,6 +63,10 @@ fn unify_scan_not_found(e: AppError) -> AppError {
     }
 }
 
+/// Body limit for external scan reports. SARIF from a large monorepo can run
+/// to several MB; the finding cap in `external_scan_ingest` bounds the work.
+const MAX_INGEST_BODY_BYTES: usize = 32 * 1024 * 1024;
+
 /// Create security routes
 pub fn router() -> Router<SharedState> {
     Router::new()
@@ -53,9 +79,32 @@ pub fn router() -> Router<SharedState> {
         // Scan operations
         .route("/scan", post(trigger_scan))
         .route("/scans", get(list_scans))
+        .route(
+            "/scans/ingest",
+            post(ingest_scan).layer(DefaultBodyLimit::max(MAX_INGEST_BODY_BYTES)),
+        )
         .route("/scans/:id", get(get_scan))
         .route("/scans/:id/findings", get(list_findings))
         .route("/artifacts/:artifact_id/scans", get(list_artifact_scans))
+        .route(
+            "/artifacts/:artifact_id/vulnerabilities",
+            get(list_artifact_vulnerabilities),
+        )
+        // Vulnerabilities
+        .route("/vulnerabilities", get(list_vulnerabilities))
+        .route("/vulnerabilities/:id", get(get_vulnerability))
+        .route(
+            "/vulnerabilities/:id/versions",
+            get(get_vulnerability_versions),
+        )
+        // Scan queue
+        .route("/queue", get(get_scan_queue))
+        .route("/queue/jobs", get(list_scan_jobs))
+        .route("/queue/priorities", get(list_scan_priorities))
+        .route(
+            "/queue/priorities/:repository_id",
+            put(set_scan_priority).delete(delete_scan_priority),
+        )
         // Finding acknowledgment
         .route("/findings/:id/acknowledge", post(acknowledge_finding))
         .route("/findings/:id/acknowledge", delete(revoke_acknowledgment))
@@ -65,6 +114,28 @@ pub fn router() -> Router<SharedState> {
             "/policies/:id",
             get(get_policy).put(update_policy).delete(delete_policy),
         )
+        // Policy decision history
+        .route("/policy-decisions", get(list_policy_decisions))
+        .route("/policy-decisions/:id", get(get_policy_decision))
+        // Compliance scans
+        .route(
+            "/compliance/profiles",
+            get(list_compliance_profiles).post(create_compliance_profile),
+        )
+        .route(
+            "/compliance/profiles/:id",
+            put(update_compliance_profile).delete(delete_compliance_profile),
+        )
+        .route(
+            "/compliance/available-profiles",
+            get(list_available_compliance_profiles),
+        )
+        .route("/compliance/scans", get(list_compliance_scans))
+        .route("/compliance/scans/:id", get(get_compliance_report))
+        .route(
+            "/artifacts/:artifact_id/compliance-scans",
+            get(list_artifact_compliance_scans).post(trigger_compliance_scan),
+        )
 }
 
 /// Repository-scoped security routes (nested under /repositories/:key)
@@ -74,7 +145,17 @@ pub fn repo_security_router() -> Router<SharedState> {
             "/:key/security",
             get(get_repo_security).put(update_repo_security),
         )
+        .route(
+            "/:key/security/engines",
+            get(get_repo_scan_engines)
+                .put(set_repo_scan_engines)
+                .delete(delete_repo_scan_engines),
+        )
         .route("/:key/security/scans", get(list_repo_scans))
+        .route(
+            "/:key/security/effective-policy",
+            get(get_repo_effective_policy),
+        )
 }
 
 // ---------------------------------------------------------------------------
@@ -158,6 +239,31 @@ pub struct TriggerScanResponse {
     pub scan_result_ids: Vec<Uuid>,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IngestScanRequest {
+    pub artifact_id: Uuid,
+    pub format: ExternalReportFormat,
+    /// Tool that produced the report. Required for `custom`; for `sarif`
+    /// and `snyk` it overrides the name declared in the report.
+    pub tool_name: Option<String>,
+    pub tool_version: Option<String>,
+    /// The report document: a SARIF 2.1.0 log, `snyk test --json` output,
+    /// or `{"findings": [...]}` for `custom`.
+    #[schema(value_type = Object)]
+    pub report: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IngestScanResponse {
+    pub scan: ScanResponse,
+    pub source_tool: String,
+    pub source_format: ExternalReportFormat,
+    pub findings_ingested: usize,
+    /// Findings dropped because they repeated an earlier finding in the
+    /// same report (e.g. one Snyk vulnerability reached via several paths).
+    pub duplicates_skipped: usize,
+}
+
 #[derive(Debug, Default, Deserialize, IntoParams)]
 pub struct ListScansQuery {
     pub repository_id: Option<Uuid>,
@@ -220,6 +326,63 @@ pub struct ScanResponse {
     pub collapsed_scan_types: Option<Vec<String>>,
 }
 
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct ListScanJobsQuery {
+    /// `queued`, `running`, `completed` or `failed`; all when omitted.
+    pub status: Option<String>,
+    /// Maximum jobs to return (default 100, max 500).
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanJobListResponse {
+    pub items: Vec<ScanJob>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetScanPriorityRequest {
+    /// Added to the trigger priority of every scan job for the repository.
+    /// Positive values run the repository's scans sooner.
+    pub priority: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanPriorityListResponse {
+    pub items: Vec<RepositoryPriority>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct ListVulnerabilitiesQuery {
+    /// Substring match on the advisory id (e.g. `CVE-2024`).
+    pub cve_id: Option<String>,
+    pub severity: Option<String>,
+    /// Maximum rows to return (default 100, max 500).
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VulnerabilityListResponse {
+    pub items: Vec<VulnerabilitySummary>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct VulnerabilityVersionsQuery {
+    pub repository_id: Uuid,
+    /// Package (artifact) name whose versions are compared.
+    pub name: String,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct ArtifactVulnerabilitiesQuery {
+    /// Include vulnerabilities the latest scans no longer report.
+    pub include_resolved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArtifactVulnerabilityListResponse {
+    pub items: Vec<ArtifactVulnerability>,
+}
+
 // ---------------------------------------------------------------------------
 // Model-to-response conversions
 // ---------------------------------------------------------------------------
@@ -373,8 +536,11 @@ impl From<crate::models::security::ScanPolicy> for PolicyResponse {
     fn from(p: crate::models::security::ScanPolicy) -> Self {
         Self {
             id: p.id,
+            layer: PolicyLayer::of(&p),
             name: p.name,
             repository_id: p.repository_id,
+            project_id: p.project_id,
+            inherit: p.inherit,
             max_severity: p.max_severity,
             block_unscanned: p.block_unscanned,
             block_on_fail: p.block_on_fail,
@@ -506,7 +672,14 @@ fn default_block_unscanned() -> bool {
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreatePolicyRequest {
     pub name: String,
+    /// Scope to one repository. Leave both this and `project_id` unset for an
+    /// instance-wide default.
     pub repository_id: Option<Uuid>,
+    /// Scope to every repository in a project.
+    pub project_id: Option<Uuid>,
+    /// Merge with less specific layers (default) or override them.
+    #[serde(default)]
+    pub inherit: Option<bool>,
     pub max_severity: String,
     #[serde(default = "default_block_unscanned")]
     pub block_unscanned: bool,
@@ -551,13 +724,18 @@ pub struct UpdatePolicyRequest {
     pub max_artifact_age_days: Option<i32>,
     #[serde(default)]
     pub require_signature: Option<bool>,
+    #[serde(default)]
+    pub inherit: Option<bool>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct PolicyResponse {
     pub id: Uuid,
     pub name: String,
+    pub layer: PolicyLayer,
     pub repository_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub inherit: bool,
     pub max_severity: String,
     pub block_unscanned: bool,
     pub block_on_fail: bool,
@@ -728,8 +906,8 @@ async fn trigger_scan(
         .clone();
 
     // `bypass_dedup = true` skips the hash-based scan dedup short-circuit and
-    // fans out a fresh scanner run per artifact (and, at the repo level, one
-    // tokio::spawn worker per artifact in the repo). The whole handler is now
+    // queues a fresh scanner run per artifact (and, at the repo level, one
+    // scan job per artifact in the repo). The whole handler is now
     // admin-only (see the gate above), so no separate non-admin check is needed
     // here; the flag still selects the dedup behavior for the admin caller.
     let bypass_dedup = body.bypass_dedup.unwrap_or(false);
@@ -755,9 +933,9 @@ async fn trigger_scan(
         }
 
         // Pre-allocate one scan_result row per configured scanner so the IDs
-        // can be returned in this response. The actual scan work is still
-        // fire-and-forget (tokio::spawn) but uses these pre-committed IDs
-        // instead of inserting new rows. See artifact-keeper#906.
+        // can be returned in this response. The actual scan work runs from
+        // the scan queue but uses these pre-committed IDs instead of
+        // inserting new rows. See artifact-keeper#906.
         //
         // `bypass_dedup` (#1469) must be passed to BOTH prepare and execute:
         // prepare needs it so the same-artifact short-circuit doesn't return
@@ -771,15 +949,19 @@ async fn trigger_scan(
         let scan_result_ids = crate::services::scanner_service::extract_scan_result_ids(&prepared);
         let prepared_map = crate::services::scanner_service::prepared_pairs_to_map(prepared);
 
-        let scanner_for_spawn = scanner.clone();
-        tokio::spawn(async move {
-            if let Err(e) = scanner_for_spawn
-                .scan_artifact_with_prepared(artifact_id, prepared_map, true, bypass_dedup)
-                .await
-            {
-                tracing::error!("Scan failed for artifact {}: {}", artifact_id, e);
-            }
-        });
+        // Manual triggers outrank upload and rescan jobs in the queue, so a
+        // burst of uploads does not delay a scan the caller is waiting on.
+        scan_queue_service::enqueue(
+            &state.db,
+            artifact_id,
+            ScanTrigger::Manual,
+            ScanJobOptions {
+                force: true,
+                bypass_dedup,
+                prepared: Some(prepared_map),
+            },
+        )
+        .await?;
         return Ok(Json(TriggerScanResponse {
             message: crate::services::scanner_service::build_artifact_scan_message(artifact_id),
             artifacts_queued: 1,
@@ -794,22 +976,23 @@ async fn trigger_scan(
     // Use the same enumeration the scan worker uses (virtual repos resolve to
     // their member repos recursively) so the reported count always matches
     // what actually gets enqueued (#2228).
-    let count: i64 = scanner
-        .repository_scan_artifact_ids(repository_id)
-        .await?
-        .len() as i64;
-
-    tokio::spawn(async move {
-        if let Err(e) = scanner
-            .scan_repository_with_options(repository_id, true, bypass_dedup)
-            .await
-        {
-            tracing::error!("Repository scan failed for {}: {}", repository_id, e);
-        }
-    });
+    let artifact_ids = scanner.repository_scan_artifact_ids(repository_id).await?;
+    let count = artifact_ids.len() as i64;
+
+    scan_queue_service::enqueue_many(
+        &state.db,
+        &artifact_ids,
+        ScanTrigger::Rescan,
+        ScanJobOptions {
+            force: true,
+            bypass_dedup,
+            prepared: None,
+        },
+    )
+    .await?;
     // Repository-level triggers don't pre-allocate per-artifact rows because
-    // the count can be large and individual rows are still created inside the
-    // worker. Clients that need scan_result_ids must trigger artifact-level
+    // the count can be large and individual rows are still created by the
+    // queue worker. Clients that need scan_result_ids must trigger artifact-level
     // scans (one per artifact_id) instead.
     Ok(Json(TriggerScanResponse {
         message: crate::services::scanner_service::build_repository_scan_message(
@@ -821,6 +1004,89 @@ async fn trigger_scan(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/scans/ingest",
+    context_path = "/api/v1/security",
+    tag = "security",
+    request_body = IngestScanRequest,
+    responses(
+        (status = 201, description = "Report ingested as a completed scan", body = IngestScanResponse),
+        (status = 400, description = "Malformed or oversized report", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Repository admin permission required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Artifact not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn ingest_scan(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(body): Json<IngestScanRequest>,
+) -> Result<(axum::http::StatusCode, Json<IngestScanResponse>)> {
+    let repository_id: Option<Uuid> = sqlx::query_scalar(
+        "SELECT repository_id FROM artifacts WHERE id = $1 AND is_deleted = false",
+    )
+    .bind(body.artifact_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let repository_id = repository_id.ok_or_else(|| {
+        AppError::NotFound(crate::api::handlers::sbom::ARTIFACT_NOT_ANALYZABLE_MSG.into())
+    })?;
+
+    // Ingested findings feed the same policy and promotion gates as the
+    // built-in scanners, so writing them sits on the repository-admin tier
+    // alongside scan configuration (#2750), not on artifact publishing.
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_id(repository_id).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    let parsed = external_scan_ingest::parse_report(
+        body.format,
+        &body.report,
+        body.tool_name.as_deref(),
+        body.tool_version.as_deref(),
+    )?;
+    let scan = ExternalScanIngestService::new(state.db.clone())
+        .ingest(
+            body.artifact_id,
+            repository_id,
+            body.format,
+            &parsed,
+            auth.user_id,
+        )
+        .await?;
+
+    tracing::info!(
+        artifact_id = %body.artifact_id,
+        scan_id = %scan.id,
+        tool = %parsed.tool_name,
+        format = body.format.as_str(),
+        findings = parsed.findings.len(),
+        ingested_by = %auth.user_id,
+        "Ingested external scan report"
+    );
+    state.event_bus.emit_for_repo(
+        "scan.completed",
+        body.artifact_id,
+        repository_id,
+        Some(auth.username.clone()),
+    );
+
+    let mut items = enrich_scans(&state.db, vec![scan]).await?;
+    Ok((
+        axum::http::StatusCode::CREATED,
+        Json(IngestScanResponse {
+            scan: items.remove(0),
+            source_tool: parsed.tool_name,
+            source_format: body.format,
+            findings_ingested: parsed.findings.len(),
+            duplicates_skipped: parsed.duplicates_skipped,
+        }),
+    ))
+}
+
 #[utoipa::path(
     get,
     path = "/scans",
@@ -1078,7 +1344,8 @@ async fn list_policies(
     responses(
         (status = 200, description = "Policy created", body = PolicyResponse),
         (status = 400, description = "Invalid max_severity (must be one of critical, high, medium, low; case-insensitive)", body = crate::api::openapi::ErrorResponse),
-        (status = 404, description = "repository_id does not reference an existing repository", body = crate::api::openapi::ErrorResponse),
+        (status = 400, description = "Both repository_id and project_id were set", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "repository_id or project_id does not reference an existing repository or project", body = crate::api::openapi::ErrorResponse),
         (status = 422, description = "Validation error", body = crate::api::openapi::ErrorResponse),
     ),
     security(("bearer_auth" = []))
@@ -1094,12 +1361,14 @@ async fn create_policy(
         .create_policy(
             &body.name,
             body.repository_id,
+            body.project_id,
             &body.max_severity,
             body.block_unscanned,
             body.block_on_fail,
             body.min_staging_hours,
             body.max_artifact_age_days,
             body.require_signature,
+            body.inherit.unwrap_or(true),
         )
         .await?;
 
@@ -1167,6 +1436,7 @@ async fn update_policy(
             body.min_staging_hours,
             body.max_artifact_age_days,
             body.require_signature,
+            body.inherit,
         )
         .await?;
 
@@ -1198,6 +1468,253 @@ async fn delete_policy(
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
 
+// ---------------------------------------------------------------------------
+// Vulnerabilities
+// ---------------------------------------------------------------------------
+
+#[utoipa::path(
+    get,
+    path = "/vulnerabilities",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(ListVulnerabilitiesQuery),
+    responses(
+        (status = 200, description = "Vulnerabilities with affected-artifact counts", body = VulnerabilityListResponse),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_vulnerabilities(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ListVulnerabilitiesQuery>,
+) -> Result<Json<VulnerabilityListResponse>> {
+    // Counts span every repository, so this view is instance-wide.
+    auth.require_admin()?;
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let items = VulnerabilityService::new(state.db.clone())
+        .list(query.cve_id.as_deref(), query.severity.as_deref(), limit)
+        .await?;
+    Ok(Json(VulnerabilityListResponse { items }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/vulnerabilities/{id}",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(
+        ("id" = String, Path, description = "Vulnerability ID or advisory ID (e.g. CVE-2024-3094)")
+    ),
+    responses(
+        (status = 200, description = "Vulnerability", body = Vulnerability),
+        (status = 404, description = "Vulnerability not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_vulnerability(
+    State(state): State<SharedState>,
+    Extension(_auth): Extension<AuthExtension>,
+    Path(id): Path<String>,
+) -> Result<Json<Vulnerability>> {
+    Ok(Json(
+        VulnerabilityService::new(state.db.clone()).get(&id).await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/vulnerabilities/{id}/versions",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(
+        ("id" = String, Path, description = "Vulnerability ID or advisory ID (e.g. CVE-2024-3094)"),
+        VulnerabilityVersionsQuery,
+    ),
+    responses(
+        (status = 200, description = "Per-version status and the versions that carry the fix", body = PackageVulnerabilityStatus),
+        (status = 404, description = "Vulnerability or repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_vulnerability_versions(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<String>,
+    Query(query): Query<VulnerabilityVersionsQuery>,
+) -> Result<Json<PackageVulnerabilityStatus>> {
+    require_repo_id_visible(
+        &state.db,
+        &auth,
+        query.repository_id,
+        "Repository not found",
+    )
+    .await?;
+    let svc = VulnerabilityService::new(state.db.clone());
+    let vulnerability = svc.get(&id).await?;
+    Ok(Json(
+        svc.package_status(vulnerability, query.repository_id, &query.name)
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/artifacts/{artifact_id}/vulnerabilities",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(
+        ("artifact_id" = Uuid, Path, description = "Artifact ID"),
+        ArtifactVulnerabilitiesQuery,
+    ),
+    responses(
+        (status = 200, description = "The artifact's vulnerabilities across scans", body = ArtifactVulnerabilityListResponse),
+        (status = 404, description = "Artifact not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_artifact_vulnerabilities(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(artifact_id): Path<Uuid>,
+    Query(query): Query<ArtifactVulnerabilitiesQuery>,
+) -> Result<Json<ArtifactVulnerabilityListResponse>> {
+    check_artifact_visibility(&Some(auth), artifact_id, &state.db).await?;
+    let items = VulnerabilityService::new(state.db.clone())
+        .list_for_artifact(artifact_id, query.include_resolved.unwrap_or(false))
+        .await?;
+    Ok(Json(ArtifactVulnerabilityListResponse { items }))
+}
+
+// ---------------------------------------------------------------------------
+// Scan queue
+// ---------------------------------------------------------------------------
+
+#[utoipa::path(
+    get,
+    path = "/queue",
+    context_path = "/api/v1/security",
+    tag = "security",
+    responses(
+        (status = 200, description = "Scan queue depth, age and worker settings", body = QueueStats),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_scan_queue(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<QueueStats>> {
+    auth.require_admin()?;
+    let settings = scan_queue_service::QueueSettings::from_env();
+    Ok(Json(
+        scan_queue_service::queue_stats(&state.db, &settings).await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/queue/jobs",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(ListScanJobsQuery),
+    responses(
+        (status = 200, description = "Scan jobs, running and queued first", body = ScanJobListResponse),
+        (status = 400, description = "Unknown status filter", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_scan_jobs(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ListScanJobsQuery>,
+) -> Result<Json<ScanJobListResponse>> {
+    auth.require_admin()?;
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let items = scan_queue_service::list_jobs(&state.db, query.status.as_deref(), limit).await?;
+    Ok(Json(ScanJobListResponse { items }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/queue/priorities",
+    context_path = "/api/v1/security",
+    tag = "security",
+    responses(
+        (status = 200, description = "Per-repository scan priorities", body = ScanPriorityListResponse),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_scan_priorities(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<ScanPriorityListResponse>> {
+    auth.require_admin()?;
+    let items = scan_queue_service::list_priorities(&state.db).await?;
+    Ok(Json(ScanPriorityListResponse { items }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/queue/priorities/{repository_id}",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(
+        ("repository_id" = Uuid, Path, description = "Repository ID")
+    ),
+    request_body = SetScanPriorityRequest,
+    responses(
+        (status = 200, description = "Priority set; queued jobs re-prioritized", body = Object),
+        (status = 400, description = "Priority out of range", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn set_scan_priority(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(repository_id): Path<Uuid>,
+    Json(body): Json<SetScanPriorityRequest>,
+) -> Result<Json<serde_json::Value>> {
+    auth.require_admin()?;
+    RepositoryService::new(state.db.clone())
+        .get_by_id(repository_id)
+        .await?;
+    scan_queue_service::set_priority(&state.db, repository_id, body.priority).await?;
+    Ok(Json(serde_json::json!({
+        "repository_id": repository_id,
+        "priority": body.priority,
+    })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/queue/priorities/{repository_id}",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(
+        ("repository_id" = Uuid, Path, description = "Repository ID")
+    ),
+    responses(
+        (status = 200, description = "Priority removed", body = Object),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "No priority set for the repository", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn delete_scan_priority(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(repository_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    auth.require_admin()?;
+    scan_queue_service::delete_priority(&state.db, repository_id).await?;
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
 // ---------------------------------------------------------------------------
 // Repo-scoped security
 // ---------------------------------------------------------------------------
@@ -1286,9 +1803,125 @@ async fn update_repo_security(
     Ok(Json(ScanConfigResponse::from(c)))
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepoScanEnginesResponse {
+    pub repository_key: String,
+    /// The engine configuration; absent when every engine runs.
+    pub config: Option<RepoScanConfig>,
+}
+
 #[utoipa::path(
     get,
-    path = "/artifacts/{artifact_id}/scans",
+    path = "/{key}/security/engines",
+    context_path = "/api/v1/repositories",
+    tag = "security",
+    params(
+        ("key" = String, Path, description = "Repository key")
+    ),
+    responses(
+        (status = 200, description = "Repository scan engine configuration", body = RepoScanEnginesResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_repo_scan_engines(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<RepoScanEnginesResponse>> {
+    let auth =
+        auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &Some(auth), &repo_service).await?;
+
+    let config = RepoScanConfigService::new(state.db.clone())
+        .get(repo.id)
+        .await?;
+    Ok(Json(RepoScanEnginesResponse {
+        repository_key: repo.key,
+        config,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{key}/security/engines",
+    context_path = "/api/v1/repositories",
+    tag = "security",
+    params(
+        ("key" = String, Path, description = "Repository key")
+    ),
+    request_body = SetRepoScanConfigRequest,
+    responses(
+        (status = 200, description = "Repository scan engine configuration set", body = RepoScanEnginesResponse),
+        (status = 400, description = "Invalid engine, ignore file or scanner argument", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn set_repo_scan_engines(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(body): Json<SetRepoScanConfigRequest>,
+) -> Result<Json<RepoScanEnginesResponse>> {
+    let auth =
+        auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    // Choosing engines can switch scanning off, so it sits on the same
+    // repository-admin tier as `update_repo_security` (#2750).
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    let config = RepoScanConfigService::new(state.db.clone())
+        .set(repo.id, body)
+        .await?;
+    Ok(Json(RepoScanEnginesResponse {
+        repository_key: repo.key,
+        config: Some(config),
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{key}/security/engines",
+    context_path = "/api/v1/repositories",
+    tag = "security",
+    params(
+        ("key" = String, Path, description = "Repository key")
+    ),
+    responses(
+        (status = 200, description = "Repository returned to running every engine", body = RepoScanEnginesResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn delete_repo_scan_engines(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<RepoScanEnginesResponse>> {
+    let auth =
+        auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_admin(&auth, repo.id, &state.permission_service).await?;
+
+    RepoScanConfigService::new(state.db.clone())
+        .delete(repo.id)
+        .await?;
+    Ok(Json(RepoScanEnginesResponse {
+        repository_key: repo.key,
+        config: None,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/artifacts/{artifact_id}/scans",
     context_path = "/api/v1/security",
     tag = "security",
     params(
@@ -1383,6 +2016,415 @@ async fn list_repo_scans(
     Ok(Json(ScanListResponse { items, total }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/{key}/security/effective-policy",
+    context_path = "/api/v1/repositories",
+    tag = "security",
+    params(
+        ("key" = String, Path, description = "Repository key")
+    ),
+    responses(
+        (status = 200, description = "Policies in scope for the repository and the layer each resolved rule comes from", body = EffectivePolicy),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_repo_effective_policy(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+) -> Result<Json<EffectivePolicy>> {
+    let auth =
+        auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))?;
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &Some(auth), &repo_service).await?;
+
+    let effective = PolicyService::new(state.db.clone())
+        .effective_policy(repo.id)
+        .await?;
+    Ok(Json(effective))
+}
+
+// ---------------------------------------------------------------------------
+// Policy decision history
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PolicyDecisionListResponse {
+    pub items: Vec<PolicyDecisionRecord>,
+    pub total: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/policy-decisions",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(PolicyDecisionQuery),
+    responses(
+        (status = 200, description = "Recorded policy decisions, newest first", body = PolicyDecisionListResponse),
+        (status = 400, description = "Invalid action filter", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin required", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_policy_decisions(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<PolicyDecisionQuery>,
+) -> Result<Json<PolicyDecisionListResponse>> {
+    // Decision snapshots outlive the artifacts and repositories they describe,
+    // so there is no visibility gate to scope them by; forensics is admin-only.
+    auth.require_admin()?;
+    let (items, total) = PolicyDecisionService::new(state.db.clone())
+        .list(&query)
+        .await?;
+    Ok(Json(PolicyDecisionListResponse { items, total }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/policy-decisions/{id}",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(
+        ("id" = Uuid, Path, description = "Policy decision ID")
+    ),
+    responses(
+        (status = 200, description = "Policy decision with its snapshot", body = PolicyDecisionRecord),
+        (status = 403, description = "Admin required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Policy decision not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_policy_decision(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PolicyDecisionRecord>> {
+    auth.require_admin()?;
+    let record = PolicyDecisionService::new(state.db.clone()).get(id).await?;
+    Ok(Json(record))
+}
+
+// ---------------------------------------------------------------------------
+// Compliance scans (OpenSCAP / CIS benchmarks for container images)
+// ---------------------------------------------------------------------------
+
+/// Build the compliance scanner, or 503 when no OpenSCAP sidecar is set up.
+fn compliance_scanner(state: &SharedState) -> Result<ComplianceScanner> {
+    ComplianceScanner::new(
+        state.db.clone(),
+        state.storage_registry.clone(),
+        &state.config,
+    )
+    .ok_or_else(|| AppError::ServiceUnavailable("OpenSCAP is not configured".to_string()))
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct TriggerComplianceScanRequest {
+    /// Evaluate this saved compliance profile.
+    pub compliance_profile_id: Option<Uuid>,
+    /// Ad-hoc XCCDF profile id when no saved profile is given. Defaults to
+    /// the server's `OPENSCAP_PROFILE`.
+    pub xccdf_profile: Option<String>,
+    /// Ad-hoc SCAP content label; auto-detected when omitted.
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ComplianceScansQuery {
+    /// Only images in this repository.
+    pub repository_id: Option<Uuid>,
+    /// Only scans run with this compliance profile.
+    pub compliance_profile_id: Option<Uuid>,
+    /// Maximum number of scans (default 50, max 500).
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComplianceScanListResponse {
+    pub items: Vec<ComplianceScanSummary>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/compliance/profiles",
+    context_path = "/api/v1/security",
+    tag = "security",
+    responses(
+        (status = 200, description = "Compliance profiles", body = Vec<ComplianceProfile>),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_compliance_profiles(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<Vec<ComplianceProfile>>> {
+    auth.require_admin()?;
+    Ok(Json(
+        compliance_scan_service::list_profiles(&state.db).await?,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/compliance/profiles",
+    context_path = "/api/v1/security",
+    tag = "security",
+    request_body = ComplianceProfileRequest,
+    responses(
+        (status = 201, description = "Compliance profile created", body = ComplianceProfile),
+        (status = 400, description = "Invalid profile", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Name already in use", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn create_compliance_profile(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(body): Json<ComplianceProfileRequest>,
+) -> Result<(axum::http::StatusCode, Json<ComplianceProfile>)> {
+    auth.require_admin()?;
+    let profile = compliance_scan_service::create_profile(&state.db, &body, auth.user_id).await?;
+    tracing::info!(
+        profile = %profile.name,
+        xccdf_profile = %profile.xccdf_profile,
+        created_by = %auth.username,
+        "Compliance profile created"
+    );
+    Ok((axum::http::StatusCode::CREATED, Json(profile)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/compliance/profiles/{id}",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(("id" = Uuid, Path, description = "Compliance profile ID")),
+    request_body = ComplianceProfileRequest,
+    responses(
+        (status = 200, description = "Compliance profile replaced", body = ComplianceProfile),
+        (status = 400, description = "Invalid profile", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Compliance profile not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Name already in use", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn update_compliance_profile(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<ComplianceProfileRequest>,
+) -> Result<Json<ComplianceProfile>> {
+    auth.require_admin()?;
+    Ok(Json(
+        compliance_scan_service::update_profile(&state.db, id, &body).await?,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/compliance/profiles/{id}",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(("id" = Uuid, Path, description = "Compliance profile ID")),
+    responses(
+        (status = 204, description = "Compliance profile deleted; past scans are kept"),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Compliance profile not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn delete_compliance_profile(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<axum::http::StatusCode> {
+    auth.require_admin()?;
+    compliance_scan_service::delete_profile(&state.db, id).await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/compliance/available-profiles",
+    context_path = "/api/v1/security",
+    tag = "security",
+    responses(
+        (status = 200, description = "XCCDF profiles offered by each SCAP content file", body = AvailableProfiles),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+        (status = 503, description = "OpenSCAP is not configured", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_available_compliance_profiles(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<AvailableProfiles>> {
+    auth.require_admin()?;
+    Ok(Json(
+        compliance_scanner(&state)?.available_profiles().await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/compliance/scans",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(ComplianceScansQuery),
+    responses(
+        (status = 200, description = "Latest finished scan per image and profile", body = ComplianceScanListResponse),
+        (status = 403, description = "Unscoped listing requires admin", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_compliance_scans(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ComplianceScansQuery>,
+) -> Result<Json<ComplianceScanListResponse>> {
+    // Same gate as `list_scans`: scoped by repository for members, unscoped
+    // for admins only.
+    match query.repository_id {
+        Some(repository_id) => {
+            let repo_service = RepositoryService::new(state.db.clone());
+            let repo = repo_service.get_by_id(repository_id).await?;
+            require_visible(&repo, &Some(auth), &repo_service).await?;
+        }
+        None => {
+            if !auth.is_admin {
+                return Err(AppError::Authorization(
+                    "Listing all compliance scans requires admin; scope the request with \
+                     repository_id"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let items = compliance_scan_service::list_latest_scans(
+        &state.db,
+        query.repository_id,
+        query.compliance_profile_id,
+        limit,
+    )
+    .await?;
+    Ok(Json(ComplianceScanListResponse { items }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/compliance/scans/{id}",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(("id" = Uuid, Path, description = "Compliance scan ID")),
+    responses(
+        (status = 200, description = "Full compliance report", body = ComplianceReport),
+        (status = 404, description = "Compliance scan not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_compliance_report(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ComplianceReport>> {
+    let report = compliance_scan_service::get_report(&state.db, id).await?;
+    check_artifact_visibility(&Some(auth), report.summary.artifact_id, &state.db)
+        .await
+        .map_err(|e| match e {
+            AppError::NotFound(_) => AppError::NotFound("Compliance scan not found".to_string()),
+            other => other,
+        })?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/artifacts/{artifact_id}/compliance-scans",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(
+        ("artifact_id" = Uuid, Path, description = "Artifact ID"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of scans (default 50, max 500)"),
+    ),
+    responses(
+        (status = 200, description = "Compliance scans of the image, newest first", body = ComplianceScanListResponse),
+        (status = 404, description = "Artifact not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_artifact_compliance_scans(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(artifact_id): Path<Uuid>,
+    Query(query): Query<ComplianceScansQuery>,
+) -> Result<Json<ComplianceScanListResponse>> {
+    check_artifact_visibility(&Some(auth), artifact_id, &state.db).await?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let items = compliance_scan_service::list_artifact_scans(&state.db, artifact_id, limit).await?;
+    Ok(Json(ComplianceScanListResponse { items }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/artifacts/{artifact_id}/compliance-scans",
+    context_path = "/api/v1/security",
+    tag = "security",
+    params(("artifact_id" = Uuid, Path, description = "Artifact ID of an image manifest")),
+    request_body = TriggerComplianceScanRequest,
+    responses(
+        (status = 202, description = "Compliance scan started", body = ComplianceScanSummary),
+        (status = 400, description = "Not a container image, or invalid profile", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin privileges required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Artifact or compliance profile not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Same scan already running", body = crate::api::openapi::ErrorResponse),
+        (status = 503, description = "OpenSCAP is not configured", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn trigger_compliance_scan(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(artifact_id): Path<Uuid>,
+    body: Option<Json<TriggerComplianceScanRequest>>,
+) -> Result<(axum::http::StatusCode, Json<ComplianceScanSummary>)> {
+    // Admin-only like `trigger_scan`: a scan unpacks the whole image.
+    auth.require_admin()?;
+    let scanner = compliance_scanner(&state)?;
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+    let target = match body.compliance_profile_id {
+        Some(id) => ScanTarget::from(&compliance_scan_service::get_profile(&state.db, id).await?),
+        None => ScanTarget {
+            compliance_profile_id: None,
+            xccdf_profile: body
+                .xccdf_profile
+                .unwrap_or_else(|| state.config.openscap_profile.clone()),
+            content: body.content,
+        },
+    };
+    let scan = scanner
+        .start_scan(artifact_id, &target, "manual", Some(auth.user_id))
+        .await?;
+    let scan_id = scan.id;
+    tokio::spawn(async move { scanner.execute(scan_id).await });
+    tracing::info!(
+        scan_id = %scan_id,
+        artifact_id = %artifact_id,
+        xccdf_profile = %target.xccdf_profile,
+        started_by = %auth.username,
+        "Compliance scan started"
+    );
+    Ok((axum::http::StatusCode::ACCEPTED, Json(scan)))
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -1390,6 +2432,7 @@ async fn list_repo_scans(
         get_all_scores,
         list_scan_configs,
         trigger_scan,
+        ingest_scan,
         list_scans,
         get_scan,
         list_findings,
@@ -1400,16 +2443,43 @@ async fn list_repo_scans(
         get_policy,
         update_policy,
         delete_policy,
+        list_policy_decisions,
+        get_policy_decision,
         get_repo_security,
         update_repo_security,
+        get_repo_scan_engines,
+        set_repo_scan_engines,
+        delete_repo_scan_engines,
         list_artifact_scans,
         list_repo_scans,
+        get_repo_effective_policy,
+        list_vulnerabilities,
+        get_vulnerability,
+        get_vulnerability_versions,
+        list_artifact_vulnerabilities,
+        get_scan_queue,
+        list_scan_jobs,
+        list_scan_priorities,
+        set_scan_priority,
+        delete_scan_priority,
+        list_compliance_profiles,
+        create_compliance_profile,
+        update_compliance_profile,
+        delete_compliance_profile,
+        list_available_compliance_profiles,
+        list_compliance_scans,
+        get_compliance_report,
+        list_artifact_compliance_scans,
+        trigger_compliance_scan,
     ),
     components(schemas(
         DashboardResponse,
         ScoreResponse,
         TriggerScanRequest,
         TriggerScanResponse,
+        IngestScanRequest,
+        IngestScanResponse,
+        ExternalReportFormat,
         ScanListResponse,
         ScanResponse,
         FindingListResponse,
@@ -1418,8 +2488,43 @@ async fn list_repo_scans(
         CreatePolicyRequest,
         UpdatePolicyRequest,
         PolicyResponse,
+        PolicyLayer,
+        EffectivePolicy,
+        EffectiveRule,
+        LayeredPolicy,
+        PolicyDecisionListResponse,
+        PolicyDecisionRecord,
         RepoSecurityResponse,
         ScanConfigResponse,
+        RepoScanEnginesResponse,
+        RepoScanConfig,
+        SetRepoScanConfigRequest,
+        ScanEngine,
+        QueueStats,
+        scan_queue_service::TriggerDepth,
+        ScanJob,
+        ScanJobListResponse,
+        SetScanPriorityRequest,
+        RepositoryPriority,
+        ScanPriorityListResponse,
+        Vulnerability,
+        VulnerabilitySummary,
+        VulnerabilityListResponse,
+        ArtifactVulnerability,
+        ArtifactVulnerabilityListResponse,
+        PackageVulnerabilityStatus,
+        vulnerability_service::PackageVersionStatus,
+        vulnerability_service::VersionStatus,
+        ComplianceProfile,
+        ComplianceProfileRequest,
+        AvailableProfiles,
+        compliance_scan_service::AvailableProfile,
+        ComplianceScanSummary,
+        ComplianceScanListResponse,
+        ComplianceReport,
+        compliance_scan_service::ComplianceRuleResult,
+        compliance_scan_service::ComplianceVerdict,
+        TriggerComplianceScanRequest,
     ))
 )]
 pub struct SecurityApiDoc;
@@ -1457,7 +2562,20 @@ mod tests {
              tier; `write` (artifact publishing) must not suffice to disable \
              scanning or the block-on-severity gate"
         );
-        for reader in ["get_repo_security", "list_repo_scans"] {
+        for writer in ["set_repo_scan_engines", "delete_repo_scan_engines"] {
+            assert!(
+                body_of(writer).contains("require_repo_write_access(")
+                    && body_of(writer).contains("require_repo_admin("),
+                "{} must call require_repo_write_access and require_repo_admin",
+                writer
+            );
+        }
+        for reader in [
+            "get_repo_security",
+            "get_repo_scan_engines",
+            "list_repo_scans",
+            "get_repo_effective_policy",
+        ] {
             assert!(
                 body_of(reader).contains("require_visible("),
                 "{} must call require_visible (xtenant)",
@@ -1466,6 +2584,44 @@ mod tests {
         }
     }
 
+    /// Ingested reports feed policy gating, so the ingest handler must apply
+    /// the tenant gate AND the repository-admin tier before parsing.
+    #[test]
+    fn test_ingest_scan_requires_repo_admin() {
+        let source = include_str!("security.rs");
+        let start = source
+            .find("async fn ingest_scan(")
+            .expect("ingest_scan handler");
+        let body = &source[start..];
+        let body = &body[..body.find("\n}\n").unwrap_or(body.len())];
+        let write = body
+            .find("require_repo_write_access(")
+            .expect("tenant gate");
+        let admin = body.find("require_repo_admin(").expect("repo admin gate");
+        let parse = body.find("parse_report(").expect("parse");
+        assert!(write < parse && admin < parse, "authorize before parsing");
+    }
+
+    #[test]
+    fn test_ingest_request_deserializes() {
+        let req: IngestScanRequest = serde_json::from_value(serde_json::json!({
+            "artifact_id": Uuid::nil(),
+            "format": "sarif",
+            "report": { "runs": [] }
+        }))
+        .unwrap();
+        assert_eq!(req.format, ExternalReportFormat::Sarif);
+        assert!(req.tool_name.is_none());
+        assert!(
+            serde_json::from_value::<IngestScanRequest>(serde_json::json!({
+                "artifact_id": Uuid::nil(),
+                "format": "xml",
+                "report": {}
+            }))
+            .is_err()
+        );
+    }
+
     /// DB-backed (#2750, sibling of #2603): a non-admin member holding only
     /// `write` (developer role via `grant_repo_access`, no fine-grained `admin`
     /// grant) is DENIED `update_repo_security`, and the denied request must not
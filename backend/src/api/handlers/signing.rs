@@ -1,7 +1,10 @@
 //! Signing key management API handlers.
 
 use axum::{
+    body::Body,
     extract::{Extension, Path, Query, State},
+    http::{header, StatusCode},
+    response::Response,
     routing::{get, post},
     Json, Router,
 };
@@ -9,12 +12,16 @@ use serde::{Deserialize, Serialize};
 use utoipa::{OpenApi, ToSchema};
 use uuid::Uuid;
 
+use crate::api::handlers::artifacts::check_artifact_visibility;
 use crate::api::handlers::repositories::require_repo_id_visible;
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
 use crate::models::repository::RepositoryFormat;
 use crate::models::signing_key::{RepositorySigningConfig, SigningKeyPublic};
+use crate::services::promotion_signing_service::{
+    PromotionSigningConfig, PromotionSigningService, SignatureFormat, StoredSignature,
+};
 use crate::services::repository_service::RepositoryService;
 use crate::services::signing_service::{normalize_key_type, CreateKeyRequest, SigningService};
 
@@ -40,6 +47,21 @@ pub fn router() -> Router<SharedState> {
         // writer of the `used_for_signing` marker the promotion require_signature
         // gate reads.
         .route("/artifacts/:artifact_id/sign", post(sign_artifact))
+        // Sign-on-promotion config and the signatures it stores
+        .route(
+            "/repositories/:repo_id/promotion-signing",
+            get(get_promotion_signing)
+                .put(set_promotion_signing)
+                .delete(delete_promotion_signing),
+        )
+        .route(
+            "/artifacts/:artifact_id/signatures",
+            get(list_artifact_signatures),
+        )
+        .route(
+            "/artifacts/:artifact_id/signatures/:format",
+            get(download_artifact_signature),
+        )
 }
 
 // --- Request/Response DTOs ---
@@ -501,8 +523,9 @@ pub struct SignArtifactResponse {
 /// key and record the attestation the promotion `require_signature` gate reads
 /// (#2535).
 ///
-/// This is the ONLY writer of the per-artifact `used_for_signing` marker, and
-/// it is admin-gated: an artifact can satisfy `require_signature` only through a
+/// Apart from sign-on-promotion (configured per release repository by an
+/// admin), this is the ONLY writer of the per-artifact `used_for_signing`
+/// marker, and it is admin-gated: an artifact can satisfy `require_signature` only through a
 /// deliberate, authenticated signing action over its bytes — never as a side
 /// effect of an (anonymous) repository-metadata read. Format-agnostic: signs
 /// content bytes, so it works for every hosted format, not just the
@@ -574,6 +597,210 @@ async fn sign_artifact(
     }))
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PromotionSigningPayload {
+    /// Instance key (no repository) or one of this repository's keys.
+    pub signing_key_id: Uuid,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn promotion_signing_service(state: &SharedState) -> PromotionSigningService {
+    PromotionSigningService::new(state.db.clone(), signing_service(state))
+}
+
+/// Get the sign-on-promotion settings for a release repository.
+#[utoipa::path(
+    get,
+    path = "/repositories/{repo_id}/promotion-signing",
+    context_path = "/api/v1/signing",
+    tag = "signing",
+    params(
+        ("repo_id" = Uuid, Path, description = "Repository ID")
+    ),
+    responses(
+        (status = 200, description = "Sign-on-promotion settings", body = PromotionSigningConfig),
+        (status = 404, description = "Repository not found or sign-on-promotion not configured", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_promotion_signing(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<Json<PromotionSigningConfig>> {
+    require_repo_id_visible(&state.db, &auth, repo_id, "Repository not found").await?;
+    promotion_signing_service(&state)
+        .get_config(repo_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| {
+            AppError::NotFound(
+                "Sign-on-promotion is not configured for this repository".to_string(),
+            )
+        })
+}
+
+/// Sign every artifact promoted into this repository.
+#[utoipa::path(
+    put,
+    path = "/repositories/{repo_id}/promotion-signing",
+    context_path = "/api/v1/signing",
+    tag = "signing",
+    params(
+        ("repo_id" = Uuid, Path, description = "Repository ID")
+    ),
+    request_body = PromotionSigningPayload,
+    responses(
+        (status = 200, description = "Sign-on-promotion settings", body = PromotionSigningConfig),
+        (status = 400, description = "Key is inactive or belongs to another repository", body = crate::api::openapi::ErrorResponse),
+        (status = 403, description = "Admin privilege required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository or key not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn set_promotion_signing(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(repo_id): Path<Uuid>,
+    Json(payload): Json<PromotionSigningPayload>,
+) -> Result<Json<PromotionSigningConfig>> {
+    require_signing_admin(&auth)?;
+    RepositoryService::new(state.db.clone())
+        .get_by_id(repo_id)
+        .await?;
+    let config = promotion_signing_service(&state)
+        .set_config(
+            repo_id,
+            payload.signing_key_id,
+            payload.enabled,
+            Some(auth.user_id),
+        )
+        .await?;
+    Ok(Json(config))
+}
+
+/// Stop signing artifacts promoted into this repository.
+#[utoipa::path(
+    delete,
+    path = "/repositories/{repo_id}/promotion-signing",
+    context_path = "/api/v1/signing",
+    tag = "signing",
+    params(
+        ("repo_id" = Uuid, Path, description = "Repository ID")
+    ),
+    responses(
+        (status = 204, description = "Sign-on-promotion removed"),
+        (status = 403, description = "Admin privilege required", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Sign-on-promotion not configured", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn delete_promotion_signing(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_signing_admin(&auth)?;
+    promotion_signing_service(&state)
+        .delete_config(repo_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the stored signatures of an artifact.
+#[utoipa::path(
+    get,
+    path = "/artifacts/{artifact_id}/signatures",
+    context_path = "/api/v1/signing",
+    tag = "signing",
+    params(
+        ("artifact_id" = Uuid, Path, description = "Artifact ID")
+    ),
+    responses(
+        (status = 200, description = "Stored signatures", body = Vec<StoredSignature>),
+        (status = 404, description = "Artifact not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_artifact_signatures(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(artifact_id): Path<Uuid>,
+) -> Result<Json<Vec<StoredSignature>>> {
+    check_artifact_visibility(&Some(auth), artifact_id, &state.db).await?;
+    Ok(Json(
+        promotion_signing_service(&state)
+            .list_signatures(artifact_id)
+            .await?,
+    ))
+}
+
+/// Download a stored signature.
+///
+/// `format` is `openpgp` (or `asc`), `rsa` (or `sig`) or `cosign`. cosign
+/// signatures are also served by the registry under the
+/// `sha256-<digest>.sig` tag, which is what `cosign verify` reads.
+#[utoipa::path(
+    get,
+    path = "/artifacts/{artifact_id}/signatures/{format}",
+    context_path = "/api/v1/signing",
+    tag = "signing",
+    params(
+        ("artifact_id" = Uuid, Path, description = "Artifact ID"),
+        ("format" = String, Path, description = "Signature format: openpgp, rsa or cosign")
+    ),
+    responses(
+        (status = 200, description = "Signature bytes", content_type = "application/octet-stream"),
+        (status = 400, description = "Unknown signature format", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Artifact or signature not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn download_artifact_signature(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path((artifact_id, format)): Path<(Uuid, String)>,
+) -> Result<Response> {
+    let format = SignatureFormat::parse(&format)
+        .ok_or_else(|| AppError::Validation(format!("Unknown signature format: {}", format)))?;
+    check_artifact_visibility(&Some(auth), artifact_id, &state.db).await?;
+    let (_, signature, _) = promotion_signing_service(&state)
+        .get_signature(artifact_id, format)
+        .await?;
+
+    let name: Option<(String,)> = sqlx::query_as("SELECT name FROM artifacts WHERE id = $1")
+        .bind(artifact_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let file_name = name
+        .map(|(n,)| {
+            n.rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .replace(['"', ':'], "_")
+        })
+        .unwrap_or_else(|| artifact_id.to_string());
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}.{}\"",
+                file_name,
+                format.extension()
+            ),
+        )
+        .body(Body::from(signature))
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
 /// Admin gate shared by the signing-key/repo-config mutation handlers.
 ///
 /// Minting, deleting, revoking, rotating a repository signing key, or writing
@@ -614,6 +841,11 @@ fn signing_config_fields(
         update_repo_signing_config,
         get_repo_public_key,
         sign_artifact,
+        get_promotion_signing,
+        set_promotion_signing,
+        delete_promotion_signing,
+        list_artifact_signatures,
+        download_artifact_signature,
     ),
     components(schemas(
         ListKeysQuery,
@@ -622,6 +854,10 @@ fn signing_config_fields(
         KeyListResponse,
         SigningConfigResponse,
         SignArtifactResponse,
+        PromotionSigningPayload,
+        PromotionSigningConfig,
+        StoredSignature,
+        SignatureFormat,
     ))
 )]
 pub struct SigningApiDoc;
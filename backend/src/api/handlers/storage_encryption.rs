@@ -0,0 +1,154 @@
+//! Storage encryption: status and the migration job that encrypts existing
+//! objects and rewraps them after a key rotation.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/storage-encryption)
+//! GET    /status                    → get_storage_encryption_status
+//! POST   /migrations                → start_storage_encryption_migration
+//! GET    /migrations                → list_storage_encryption_migrations
+//! ```
+//!
+//! Encryption itself is configured with `STORAGE_ENCRYPTION`; a migration
+//! runs in the background and can be repeated safely.
+
+use axum::{
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::storage_encryption_service::{
+    StorageEncryptionRun, StorageEncryptionService, StorageEncryptionStatus,
+};
+
+/// Storage encryption routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/status", get(get_storage_encryption_status))
+        .route(
+            "/migrations",
+            get(list_storage_encryption_migrations).post(start_storage_encryption_migration),
+        )
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListMigrationsQuery {
+    /// Maximum records to return (default 50, max 500).
+    pub limit: Option<i64>,
+}
+
+/// GET /api/v1/admin/storage-encryption/status
+#[utoipa::path(
+    get,
+    path = "/status",
+    context_path = "/api/v1/admin/storage-encryption",
+    tag = "storage_encryption",
+    responses(
+        (status = 200, description = "Encryption configuration and latest migration", body = StorageEncryptionStatus),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_storage_encryption_status(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<StorageEncryptionStatus>> {
+    auth.require_admin()?;
+    Ok(Json(
+        StorageEncryptionService::new(state.db.clone(), state.storage_registry.clone())
+            .status()
+            .await?,
+    ))
+}
+
+/// POST /api/v1/admin/storage-encryption/migrations
+#[utoipa::path(
+    post,
+    path = "/migrations",
+    context_path = "/api/v1/admin/storage-encryption",
+    tag = "storage_encryption",
+    responses(
+        (status = 202, description = "Migration started", body = StorageEncryptionRun),
+        (status = 400, description = "Storage encryption is not enabled", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "A migration is already running", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn start_storage_encryption_migration(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<(StatusCode, Json<StorageEncryptionRun>)> {
+    auth.require_admin()?;
+    let run = Arc::new(StorageEncryptionService::new(
+        state.db.clone(),
+        state.storage_registry.clone(),
+    ))
+    .start_run(Some(auth.user_id))
+    .await?;
+
+    let _ = AuditService::new(state.db.clone())
+        .log(
+            AuditEntry::new(
+                AuditAction::StorageEncryptionMigrationStarted,
+                ResourceType::Setting,
+            )
+            .user(auth.user_id)
+            .resource(run.id)
+            .actor_name(auth.username.clone())
+            .details(serde_json::json!({ "active_key_id": run.active_key_id })),
+        )
+        .await;
+    tracing::info!(
+        run_id = %run.id,
+        active_key_id = %run.active_key_id,
+        requested_by = %auth.username,
+        "Storage encryption migration started"
+    );
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// GET /api/v1/admin/storage-encryption/migrations
+#[utoipa::path(
+    get,
+    path = "/migrations",
+    context_path = "/api/v1/admin/storage-encryption",
+    tag = "storage_encryption",
+    params(ListMigrationsQuery),
+    responses(
+        (status = 200, description = "Migration runs, newest first", body = Vec<StorageEncryptionRun>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_storage_encryption_migrations(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ListMigrationsQuery>,
+) -> Result<Json<Vec<StorageEncryptionRun>>> {
+    auth.require_admin()?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    Ok(Json(
+        StorageEncryptionService::new(state.db.clone(), state.storage_registry.clone())
+            .list_runs(limit)
+            .await?,
+    ))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_storage_encryption_status,
+        start_storage_encryption_migration,
+        list_storage_encryption_migrations,
+    ),
+    components(schemas(StorageEncryptionStatus, StorageEncryptionRun))
+)]
+pub struct StorageEncryptionApiDoc;
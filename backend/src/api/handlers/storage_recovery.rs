@@ -0,0 +1,230 @@
+//! Recovery of objects the storage backend itself soft-deleted.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/storage-recovery)
+//! GET    /                          → list_soft_deleted_objects
+//! POST   /restore                   → restore_soft_deleted_objects
+//! ```
+//!
+//! With Azure blob soft delete enabled, storage GC and artifact purges leave
+//! blobs recoverable for the account's retention period. These endpoints list
+//! such blobs and undelete them; the artifact rows are not touched.
+
+use axum::{
+    extract::{Extension, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::storage::{SoftDeletedObject, StorageBackend, StorageLocation};
+
+/// Most keys a single restore request may name.
+const MAX_RESTORE_KEYS: usize = 1000;
+
+/// Storage recovery routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_soft_deleted_objects))
+        .route("/restore", post(restore_soft_deleted_objects))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListSoftDeletedQuery {
+    /// Storage backend to list (e.g. `azure`).
+    pub backend: String,
+    /// Only keys starting with this prefix.
+    pub prefix: Option<String>,
+    /// Continuation marker from a previous page.
+    pub marker: Option<String>,
+    /// Maximum objects per page (default 100, max 5000).
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SoftDeletedObjectInfo {
+    pub key: String,
+    pub size_bytes: u64,
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Days until the backend purges the object for good.
+    pub remaining_retention_days: Option<u32>,
+}
+
+impl From<SoftDeletedObject> for SoftDeletedObjectInfo {
+    fn from(object: SoftDeletedObject) -> Self {
+        Self {
+            key: object.key,
+            size_bytes: object.size_bytes,
+            deleted_at: object.deleted_at,
+            remaining_retention_days: object.remaining_retention_days,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SoftDeletedListResponse {
+    pub backend: String,
+    pub items: Vec<SoftDeletedObjectInfo>,
+    /// Pass as `marker` to fetch the next page.
+    pub next_marker: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RestoreSoftDeletedRequest {
+    /// Storage backend holding the objects (e.g. `azure`).
+    pub backend: String,
+    /// Keys to undelete.
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreFailure {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreSoftDeletedResponse {
+    pub restored: Vec<String>,
+    pub failed: Vec<RestoreFailure>,
+}
+
+/// The raw handle of `backend`, provided it can recover soft-deleted objects.
+fn recovery_backend(
+    state: &SharedState,
+    backend: &str,
+) -> Result<std::sync::Arc<dyn StorageBackend>> {
+    let handle = state.storage_registry.raw_backend_for(&StorageLocation {
+        backend: backend.to_string(),
+        path: String::new(),
+    })?;
+    if handle.soft_delete_recovery().is_none() {
+        return Err(AppError::Validation(format!(
+            "Storage backend '{}' does not support soft-delete recovery",
+            backend
+        )));
+    }
+    Ok(handle)
+}
+
+/// GET /api/v1/admin/storage-recovery
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/admin/storage-recovery",
+    tag = "storage_recovery",
+    params(ListSoftDeletedQuery),
+    responses(
+        (status = 200, description = "Soft-deleted objects still within retention", body = SoftDeletedListResponse),
+        (status = 400, description = "The backend does not support soft-delete recovery", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_soft_deleted_objects(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ListSoftDeletedQuery>,
+) -> Result<Json<SoftDeletedListResponse>> {
+    auth.require_admin()?;
+    let handle = recovery_backend(&state, &query.backend)?;
+    let recovery = handle
+        .soft_delete_recovery()
+        .expect("checked by recovery_backend");
+    let page = recovery
+        .list_soft_deleted(
+            query.prefix.as_deref(),
+            query.marker.as_deref(),
+            query.limit.unwrap_or(100).clamp(1, 5000),
+        )
+        .await?;
+    Ok(Json(SoftDeletedListResponse {
+        backend: query.backend,
+        items: page.objects.into_iter().map(Into::into).collect(),
+        next_marker: page.next_marker,
+    }))
+}
+
+/// POST /api/v1/admin/storage-recovery/restore
+#[utoipa::path(
+    post,
+    path = "/restore",
+    context_path = "/api/v1/admin/storage-recovery",
+    tag = "storage_recovery",
+    request_body = RestoreSoftDeletedRequest,
+    responses(
+        (status = 200, description = "Per-key restore outcome", body = RestoreSoftDeletedResponse),
+        (status = 400, description = "No keys, too many keys, or the backend does not support soft-delete recovery", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn restore_soft_deleted_objects(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(body): Json<RestoreSoftDeletedRequest>,
+) -> Result<Json<RestoreSoftDeletedResponse>> {
+    auth.require_admin()?;
+    if body.keys.is_empty() || body.keys.len() > MAX_RESTORE_KEYS {
+        return Err(AppError::Validation(format!(
+            "keys must name between 1 and {} objects",
+            MAX_RESTORE_KEYS
+        )));
+    }
+    let handle = recovery_backend(&state, &body.backend)?;
+    let recovery = handle
+        .soft_delete_recovery()
+        .expect("checked by recovery_backend");
+
+    let mut restored = Vec::new();
+    let mut failed = Vec::new();
+    for key in body.keys {
+        match recovery.undelete(&key).await {
+            Ok(()) => restored.push(key),
+            Err(e) => failed.push(RestoreFailure {
+                key,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    let _ = AuditService::new(state.db.clone())
+        .log(
+            AuditEntry::new(AuditAction::StorageObjectsRestored, ResourceType::Setting)
+                .user(auth.user_id)
+                .actor_name(auth.username.clone())
+                .details(serde_json::json!({
+                    "backend": body.backend,
+                    "restored": restored,
+                    "failed": failed.len(),
+                })),
+        )
+        .await;
+    tracing::info!(
+        backend = %body.backend,
+        restored = restored.len(),
+        failed = failed.len(),
+        requested_by = %auth.username,
+        "Soft-deleted storage objects restored"
+    );
+    Ok(Json(RestoreSoftDeletedResponse { restored, failed }))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_soft_deleted_objects, restore_soft_deleted_objects),
+    components(schemas(
+        SoftDeletedListResponse,
+        SoftDeletedObjectInfo,
+        RestoreSoftDeletedRequest,
+        RestoreSoftDeletedResponse,
+        RestoreFailure,
+    ))
+)]
+pub struct StorageRecoveryApiDoc;
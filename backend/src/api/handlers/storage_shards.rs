@@ -0,0 +1,161 @@
+//! Sharded object storage: configured shards and the rebalance job that
+//! moves objects to their owning shard after a shard is added.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/storage-shards)
+//! GET    /                          → list_sharded_backends
+//! POST   /rebalance                 → start_storage_shard_rebalance
+//! GET    /rebalance                 → list_storage_shard_rebalances
+//! ```
+//!
+//! Shards are configured with `S3_SHARD_BUCKETS` or `AZURE_SHARD_CONTAINERS`;
+//! a rebalance runs in the background and can be repeated safely.
+
+use axum::{
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::storage_shard_service::{
+    ShardedBackendInfo, StorageShardRebalanceRun, StorageShardService,
+};
+
+/// Storage shard routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new().route("/", get(list_sharded_backends)).route(
+        "/rebalance",
+        get(list_storage_shard_rebalances).post(start_storage_shard_rebalance),
+    )
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StartRebalanceRequest {
+    /// Sharded backend to rebalance (`s3` or `azure`).
+    pub backend: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListRebalancesQuery {
+    /// Maximum records to return (default 50, max 500).
+    pub limit: Option<i64>,
+}
+
+/// GET /api/v1/admin/storage-shards
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/admin/storage-shards",
+    tag = "storage_shards",
+    responses(
+        (status = 200, description = "Sharded backends and their shards", body = Vec<ShardedBackendInfo>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_sharded_backends(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<Vec<ShardedBackendInfo>>> {
+    auth.require_admin()?;
+    Ok(Json(
+        StorageShardService::new(state.db.clone(), state.storage_registry.clone()).backends(),
+    ))
+}
+
+/// POST /api/v1/admin/storage-shards/rebalance
+#[utoipa::path(
+    post,
+    path = "/rebalance",
+    context_path = "/api/v1/admin/storage-shards",
+    tag = "storage_shards",
+    request_body = StartRebalanceRequest,
+    responses(
+        (status = 202, description = "Rebalance started", body = StorageShardRebalanceRun),
+        (status = 400, description = "The backend is not sharded", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "A rebalance of the backend is already running", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn start_storage_shard_rebalance(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(body): Json<StartRebalanceRequest>,
+) -> Result<(StatusCode, Json<StorageShardRebalanceRun>)> {
+    auth.require_admin()?;
+    let run = Arc::new(StorageShardService::new(
+        state.db.clone(),
+        state.storage_registry.clone(),
+    ))
+    .start_run(&body.backend, Some(auth.user_id))
+    .await?;
+
+    let _ = AuditService::new(state.db.clone())
+        .log(
+            AuditEntry::new(
+                AuditAction::StorageShardRebalanceStarted,
+                ResourceType::Setting,
+            )
+            .user(auth.user_id)
+            .resource(run.id)
+            .actor_name(auth.username.clone())
+            .details(serde_json::json!({
+                "backend": run.backend,
+                "shards": run.shards,
+            })),
+        )
+        .await;
+    tracing::info!(
+        run_id = %run.id,
+        backend = %run.backend,
+        requested_by = %auth.username,
+        "Storage shard rebalance started"
+    );
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// GET /api/v1/admin/storage-shards/rebalance
+#[utoipa::path(
+    get,
+    path = "/rebalance",
+    context_path = "/api/v1/admin/storage-shards",
+    tag = "storage_shards",
+    params(ListRebalancesQuery),
+    responses(
+        (status = 200, description = "Rebalance runs, newest first", body = Vec<StorageShardRebalanceRun>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_storage_shard_rebalances(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Query(query): Query<ListRebalancesQuery>,
+) -> Result<Json<Vec<StorageShardRebalanceRun>>> {
+    auth.require_admin()?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    Ok(Json(
+        StorageShardService::new(state.db.clone(), state.storage_registry.clone())
+            .list_runs(limit)
+            .await?,
+    ))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_sharded_backends,
+        start_storage_shard_rebalance,
+        list_storage_shard_rebalances,
+    ),
+    components(schemas(ShardedBackendInfo, StorageShardRebalanceRun, StartRebalanceRequest))
+)]
+pub struct StorageShardsApiDoc;
@@ -286,6 +286,12 @@ async fn query_release_versions(
         .collect())
 }
 
+/// The `{scope}/{name}/` directory a package's release archives download
+/// from, used for virtual member routing. `package_id` is `scope.name`.
+fn package_route_path(package_id: &str) -> String {
+    format!("{}/", package_id.replacen('.', "/", 1))
+}
+
 /// Fan out a `list_releases` lookup across the members of a virtual repo,
 /// aggregating the versions from ALL Local/Staging members that own the
 /// package. Members are visited in priority order and the result is
@@ -298,7 +304,12 @@ pub async fn query_release_versions_virtual(
     virtual_repo_id: uuid::Uuid,
     package_id: &str,
 ) -> Result<Vec<String>, Response> {
-    let members = proxy_helpers::fetch_virtual_members(db, virtual_repo_id).await?;
+    let members = proxy_helpers::fetch_virtual_members_for_path(
+        db,
+        virtual_repo_id,
+        &package_route_path(package_id),
+    )
+    .await?;
     let mut aggregated: Vec<String> = Vec::new();
     let mut seen_versions: std::collections::HashSet<String> = std::collections::HashSet::new();
     for member in &members {
@@ -401,7 +412,9 @@ pub async fn query_release_metadata_virtual(
     package_id: &str,
     version: &str,
 ) -> Result<Option<ReleaseRow>, Response> {
-    let members = proxy_helpers::fetch_virtual_members(db, virtual_repo_id).await?;
+    let route_path = format!("{}{}.zip", package_route_path(package_id), version);
+    let members =
+        proxy_helpers::fetch_virtual_members_for_path(db, virtual_repo_id, &route_path).await?;
     for member in &members {
         if member.repo_type != RepositoryType::Local && member.repo_type != RepositoryType::Staging
         {
@@ -681,7 +694,9 @@ async fn resolve_manifest_owner(
     version: &str,
 ) -> Result<(uuid::Uuid, crate::storage::StorageLocation, Option<String>), Response> {
     if repo.repo_type == RepositoryType::Virtual {
-        let members = proxy_helpers::fetch_virtual_members(db, repo.id).await?;
+        let route_path = format!("{}{}.zip", package_route_path(package_id), version);
+        let members =
+            proxy_helpers::fetch_virtual_members_for_path(db, repo.id, &route_path).await?;
         for member in &members {
             if member.repo_type != RepositoryType::Local
                 && member.repo_type != RepositoryType::Staging
@@ -1026,6 +1041,17 @@ mod tests {
     // -----------------------------------------------------------------------
     // extract_credentials
     // -----------------------------------------------------------------------
+    // -----------------------------------------------------------------------
+    // package_route_path
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_package_route_path_is_archive_directory() {
+        assert_eq!(package_route_path("mycorp.swift-log"), "mycorp/swift-log/");
+        assert!(format!("{}/{}/{}.zip", "mycorp", "swift-log", "1.0.0")
+            .starts_with(&package_route_path("mycorp.swift-log")));
+    }
+
     // -----------------------------------------------------------------------
     // swift_json_response
     // -----------------------------------------------------------------------
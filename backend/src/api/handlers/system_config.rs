@@ -185,6 +185,7 @@ pub async fn get_system_config(
 ) -> Json<SystemConfigResponse> {
     let config = &state.config;
     let is_admin = auth.as_ref().map(|a| a.is_admin).unwrap_or(false);
+    let demo_mode = crate::config_reload::demo_mode(config);
 
     // Public-safe fields: always returned. Login UI needs to know which
     // providers are available before the user authenticates.
@@ -217,7 +218,7 @@ pub async fn get_system_config(
     if !is_admin {
         return Json(SystemConfigResponse {
             max_upload_size_bytes: config.max_upload_size_bytes,
-            demo_mode: config.demo_mode,
+            demo_mode,
             guest_access_enabled: config.guest_access_enabled,
             auth: auth_config,
             oidc_issuer: config.oidc_issuer.clone(),
@@ -241,9 +242,10 @@ pub async fn get_system_config(
     // monitor; this is the fix for the mixed "Disabled" vs "unavailable"
     // banners reported in issue #1395, and the "monitoring green while DT
     // unavailable" inconsistency in issue #1480.
+    let scanner_endpoints = crate::config_reload::scanner_endpoints(config);
     let scanners = ScannersConfig {
-        trivy_enabled: config.trivy_url.is_some(),
-        openscap_enabled: config.openscap_url.is_some(),
+        trivy_enabled: scanner_endpoints.trivy_url.is_some(),
+        openscap_enabled: scanner_endpoints.openscap_url.is_some(),
         dependency_track_enabled: state.dependency_track.is_some(),
     };
 
@@ -277,7 +279,7 @@ pub async fn get_system_config(
 
     Json(SystemConfigResponse {
         max_upload_size_bytes: config.max_upload_size_bytes,
-        demo_mode: config.demo_mode,
+        demo_mode,
         guest_access_enabled: config.guest_access_enabled,
         auth: auth_config,
         oidc_issuer: config.oidc_issuer.clone(),
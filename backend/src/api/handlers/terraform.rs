@@ -19,12 +19,20 @@
 //!   GET  /terraform/{repo_key}/v1/providers/{namespace}/{type}/versions
 //!   GET  /terraform/{repo_key}/v1/providers/{namespace}/{type}/{version}/download/{os}/{arch}
 //!   GET  /terraform/{repo_key}/v1/providers/{namespace}/{type}/{version}/binary/{os}/{arch}
+//!   GET  /terraform/{repo_key}/v1/providers/{namespace}/{type}/{version}/SHA256SUMS
+//!   GET  /terraform/{repo_key}/v1/providers/{namespace}/{type}/{version}/SHA256SUMS.sig
 //!   PUT  /terraform/{repo_key}/v1/providers/{namespace}/{type}/{version}/{os}/{arch}
 //!
 //! Note the two distinct provider endpoints, which the Provider Registry
 //! Protocol keeps separate: `.../download/{os}/{arch}` returns the JSON
 //! *package document*, whose `download_url` field then points at
 //! `.../binary/{os}/{arch}`, which streams the `.zip` itself.
+//!
+//! `terraform init` refuses a provider package unless the `SHA256SUMS` document
+//! listing it carries a valid signature from a key in the package document's
+//! `signing_keys`. Both are produced with the repository's active OpenPGP
+//! signing key (`key_type = "gpg"`); without one the package document leaves
+//! the signature fields empty.
 
 use axum::body::Body;
 use axum::extract::{Path, Query, State};
@@ -45,11 +53,14 @@ use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 use tracing::info;
 
+use crate::api::handlers::error_helpers::{require_openpgp_capable_key, require_signing_key};
 use crate::api::handlers::proxy_helpers::{self, RepoInfo};
 use crate::api::middleware::auth::{require_auth_basic_scope, AuthExtension};
 use crate::api::validation::validate_outbound_url;
 use crate::api::SharedState;
 use crate::models::repository::{RepositoryFormat, RepositoryType};
+use crate::models::signing_key::SigningKey;
+use crate::services::signing_service::SigningService;
 
 // ---------------------------------------------------------------------------
 // Router
@@ -115,6 +126,17 @@ pub fn router() -> Router<SharedState> {
             "/:repo_key/v1/providers/:namespace/:type_name/:version/binary/:os/:arch",
             get(download_provider_binary),
         )
+        // Provider registry - the checksums document and its signature the
+        // package document advertises as `shasums_url` /
+        // `shasums_signature_url`.
+        .route(
+            "/:repo_key/v1/providers/:namespace/:type_name/:version/SHA256SUMS",
+            get(provider_shasums),
+        )
+        .route(
+            "/:repo_key/v1/providers/:namespace/:type_name/:version/SHA256SUMS.sig",
+            get(provider_shasums_signature),
+        )
         // Provider upload
         .route(
             "/:repo_key/v1/providers/:namespace/:type_name/:version/:os/:arch",
@@ -824,7 +846,6 @@ async fn download_provider(
 ) -> Result<Response, Response> {
     let repo = resolve_terraform_repo(&state.db, &repo_key).await?;
     let provider_name = format!("{}/{}", namespace, type_name);
-    let platform = build_platform(&os, &arch);
     // Resolve the package by the exact `artifacts.path` it is stored under —
     // the same coordinates the `binary` route this document advertises resolves
     // by, so a document can only be produced for a package that route can serve.
@@ -928,27 +949,38 @@ async fn download_provider(
     // Record download
     crate::services::artifact_service::record_download(&state.db, artifact.id, &ctx).await;
 
-    let filename = build_provider_filename(&type_name, &version, &platform);
-
     // Per the Provider Registry Protocol this endpoint returns the package
     // *document*, not the package: `download_url` points at the separate route
     // that streams the archive (`download_provider_binary`).
     let download_url =
         build_provider_binary_url(&repo_key, &namespace, &type_name, &version, &os, &arch);
+    let shasums_url = build_provider_shasums_url(&repo_key, &namespace, &type_name, &version);
 
-    let json = serde_json::json!({
-        "protocols": ["5.0"],
-        "os": os,
-        "arch": arch,
-        "filename": filename,
-        "download_url": download_url,
-        "shasum": artifact.checksum_sha256,
-        "shasums_url": "",
-        "shasums_signature_url": "",
-        "signing_keys": {
-            "gpg_public_keys": []
+    // Terraform only installs a package whose SHA256SUMS is signed by one of
+    // the advertised keys, so the signature and key are published only when
+    // the repository has an OpenPGP key to sign with.
+    let signing_key = SigningService::new(state.db.clone(), &state.config.jwt_secret)
+        .get_active_key_for_repo(repo.id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(repo = %repo_key, error = %e, "Failed to load signing key");
+            None
+        })
+        .filter(|key| key.supports_openpgp());
+
+    let json = build_provider_package_document(
+        &ProviderPackageRef {
+            namespace: &namespace,
+            type_name: &type_name,
+            version: &version,
+            os: &os,
+            arch: &arch,
         },
-    });
+        &download_url,
+        artifact.checksum_sha256.trim(),
+        &shasums_url,
+        signing_key.as_ref(),
+    );
 
     Ok(Response::builder()
         .status(StatusCode::OK)
@@ -986,6 +1018,59 @@ fn build_provider_filename(type_name: &str, version: &str, platform: &str) -> St
     )
 }
 
+/// The `SHA256SUMS` document [`download_provider`] advertises as `shasums_url`;
+/// its detached signature lives at the same URL plus `.sig`.
+fn build_provider_shasums_url(
+    repo_key: &str,
+    namespace: &str,
+    type_name: &str,
+    version: &str,
+) -> String {
+    format!(
+        "{}/{}/v1/providers/{}/{}/{}/SHA256SUMS",
+        MOUNT_PREFIX, repo_key, namespace, type_name, version
+    )
+}
+
+/// The registry-protocol package document for one provider package.
+///
+/// `signing_key` is the repository's OpenPGP key; without one the signature
+/// URL and key list stay empty.
+fn build_provider_package_document(
+    pkg: &ProviderPackageRef<'_>,
+    download_url: &str,
+    shasum: &str,
+    shasums_url: &str,
+    signing_key: Option<&SigningKey>,
+) -> serde_json::Value {
+    let (shasums_signature_url, gpg_public_keys) = match signing_key {
+        Some(key) => (
+            format!("{}.sig", shasums_url),
+            serde_json::json!([{
+                "key_id": key.key_id.as_deref().unwrap_or_default().to_ascii_uppercase(),
+                "ascii_armor": key.public_key_pem,
+                "trust_signature": "",
+                "source": "",
+                "source_url": null,
+            }]),
+        ),
+        None => (String::new(), serde_json::json!([])),
+    };
+    serde_json::json!({
+        "protocols": ["5.0"],
+        "os": pkg.os,
+        "arch": pkg.arch,
+        "filename": pkg.filename(),
+        "download_url": download_url,
+        "shasum": shasum,
+        "shasums_url": shasums_url,
+        "shasums_signature_url": shasums_signature_url,
+        "signing_keys": {
+            "gpg_public_keys": gpg_public_keys
+        },
+    })
+}
+
 /// The coordinates of one provider package (a single os/arch build). The five
 /// segments always travel together, so they are passed as one value.
 struct ProviderPackageRef<'a> {
@@ -1074,6 +1159,115 @@ async fn download_provider_binary(
     .await
 }
 
+// ---------------------------------------------------------------------------
+// GET /v1/providers/{namespace}/{type}/{version}/SHA256SUMS[.sig]
+// ---------------------------------------------------------------------------
+
+/// Build the `SHA256SUMS` document of one provider version: one
+/// `<sha256>  <filename>` line per package, ordered by filename, in the
+/// layout `terraform init` matches the package document's `shasum` against.
+fn build_provider_shasums_document(
+    type_name: &str,
+    version: &str,
+    packages: &[LocalProviderPackage],
+) -> String {
+    let mut lines: Vec<(String, &str)> = packages
+        .iter()
+        .map(|pkg| {
+            let platform = build_platform(&pkg.os, &pkg.arch);
+            (
+                build_provider_filename(type_name, version, &platform),
+                pkg.shasum.as_str(),
+            )
+        })
+        .collect();
+    lines.sort();
+    lines.dedup_by(|a, b| a.0 == b.0);
+    lines
+        .into_iter()
+        .map(|(filename, shasum)| format!("{}  {}\n", shasum, filename))
+        .collect()
+}
+
+/// The `SHA256SUMS` document of a hosted provider version.
+async fn provider_shasums_document(
+    state: &SharedState,
+    repo: &RepoInfo,
+    namespace: &str,
+    type_name: &str,
+    version: &str,
+) -> Result<String, Response> {
+    let packages = local_provider_packages(&state.db, repo, namespace, type_name, version).await?;
+    if packages.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!(
+                "Provider {}/{} version {} not found",
+                namespace, type_name, version
+            ),
+        )
+            .into_response());
+    }
+    Ok(build_provider_shasums_document(
+        type_name, version, &packages,
+    ))
+}
+
+/// Serve the `SHA256SUMS` document a package document's `shasums_url`
+/// points at.
+async fn provider_shasums(
+    State(state): State<SharedState>,
+    Path((repo_key, namespace, type_name, version)): Path<(String, String, String, String)>,
+) -> Result<Response, Response> {
+    let repo = resolve_terraform_repo(&state.db, &repo_key).await?;
+    let document =
+        provider_shasums_document(&state, &repo, &namespace, &type_name, &version).await?;
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(Body::from(document))
+        .unwrap())
+}
+
+/// Serve the binary detached OpenPGP signature of the `SHA256SUMS` document,
+/// made with the repository's active signing key.
+async fn provider_shasums_signature(
+    State(state): State<SharedState>,
+    Path((repo_key, namespace, type_name, version)): Path<(String, String, String, String)>,
+) -> Result<Response, Response> {
+    let repo = resolve_terraform_repo(&state.db, &repo_key).await?;
+    let signing_svc = SigningService::new(state.db.clone(), &state.config.jwt_secret);
+    let key = require_signing_key(signing_svc.get_active_key_for_repo(repo.id).await)?;
+    let key = require_openpgp_capable_key(key).map_err(|resp| {
+        tracing::warn!(
+            repo_id = %repo.id,
+            "SHA256SUMS.sig requested but the repository's active signing key cannot \
+             produce an OpenPGP signature (requires key_type='gpg')",
+        );
+        resp
+    })?;
+    let document =
+        provider_shasums_document(&state, &repo, &namespace, &type_name, &version).await?;
+
+    let signature = signing_svc
+        .sign_openpgp_detached_binary_with_key(&key, document.as_bytes())
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to sign SHA256SUMS: {}", e),
+            )
+                .into_response()
+        })?;
+    let _ = signing_svc.mark_key_used(key.id).await;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .body(Body::from(signature))
+        .unwrap())
+}
+
 async fn upload_provider(
     State(state): State<SharedState>,
     Extension(auth): Extension<Option<AuthExtension>>,
@@ -2766,6 +2960,129 @@ mod tests {
         assert_eq!(json["arch"], "arm64");
     }
 
+    // -----------------------------------------------------------------------
+    // build_provider_package_document / SHA256SUMS
+    // -----------------------------------------------------------------------
+
+    fn aws_package_ref() -> ProviderPackageRef<'static> {
+        ProviderPackageRef {
+            namespace: "hashicorp",
+            type_name: "aws",
+            version: "5.0.0",
+            os: "linux",
+            arch: "amd64",
+        }
+    }
+
+    fn gpg_signing_key() -> SigningKey {
+        SigningKey {
+            id: uuid::Uuid::new_v4(),
+            repository_id: None,
+            name: "terraform".to_string(),
+            key_type: "gpg".to_string(),
+            fingerprint: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+            key_id: Some("89abcdef01234567".to_string()),
+            public_key_pem: "-----BEGIN PGP PUBLIC KEY BLOCK-----\n...".to_string(),
+            private_key_enc: Vec::new(),
+            algorithm: "rsa4096".to_string(),
+            uid_name: None,
+            uid_email: None,
+            expires_at: None,
+            is_active: true,
+            created_at: chrono::Utc::now(),
+            created_by: None,
+            rotated_from: None,
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn test_build_provider_shasums_url() {
+        assert_eq!(
+            build_provider_shasums_url("tf", "hashicorp", "aws", "5.0.0"),
+            "/terraform/tf/v1/providers/hashicorp/aws/5.0.0/SHA256SUMS"
+        );
+    }
+
+    #[test]
+    fn test_provider_package_document_without_key_leaves_signature_empty() {
+        let json = build_provider_package_document(
+            &aws_package_ref(),
+            "/download/url",
+            "abc123",
+            "/terraform/tf/v1/providers/hashicorp/aws/5.0.0/SHA256SUMS",
+            None,
+        );
+        assert_eq!(
+            json["filename"],
+            "terraform-provider-aws_5.0.0_linux_amd64.zip"
+        );
+        assert_eq!(json["shasum"], "abc123");
+        assert_eq!(
+            json["shasums_url"],
+            "/terraform/tf/v1/providers/hashicorp/aws/5.0.0/SHA256SUMS"
+        );
+        assert_eq!(json["shasums_signature_url"], "");
+        assert!(json["signing_keys"]["gpg_public_keys"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_provider_package_document_with_key_advertises_signature() {
+        let key = gpg_signing_key();
+        let json = build_provider_package_document(
+            &aws_package_ref(),
+            "/download/url",
+            "abc123",
+            "/terraform/tf/v1/providers/hashicorp/aws/5.0.0/SHA256SUMS",
+            Some(&key),
+        );
+        assert_eq!(
+            json["shasums_signature_url"],
+            "/terraform/tf/v1/providers/hashicorp/aws/5.0.0/SHA256SUMS.sig"
+        );
+        let keys = json["signing_keys"]["gpg_public_keys"].as_array().unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0]["key_id"], "89ABCDEF01234567");
+        assert_eq!(keys[0]["ascii_armor"], key.public_key_pem);
+    }
+
+    #[test]
+    fn test_build_provider_shasums_document_sorted_by_filename() {
+        let packages = vec![
+            LocalProviderPackage {
+                os: "linux".to_string(),
+                arch: "amd64".to_string(),
+                shasum: "bbbb".to_string(),
+            },
+            LocalProviderPackage {
+                os: "darwin".to_string(),
+                arch: "arm64".to_string(),
+                shasum: "aaaa".to_string(),
+            },
+        ];
+        assert_eq!(
+            build_provider_shasums_document("aws", "5.0.0", &packages),
+            "aaaa  terraform-provider-aws_5.0.0_darwin_arm64.zip\n\
+             bbbb  terraform-provider-aws_5.0.0_linux_amd64.zip\n"
+        );
+    }
+
+    #[test]
+    fn test_build_provider_shasums_document_lists_each_platform_once() {
+        let package = || LocalProviderPackage {
+            os: "linux".to_string(),
+            arch: "amd64".to_string(),
+            shasum: "bbbb".to_string(),
+        };
+        assert_eq!(
+            build_provider_shasums_document("aws", "5.0.0", &[package(), package()]),
+            "bbbb  terraform-provider-aws_5.0.0_linux_amd64.zip\n"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // parse_module_name
     // -----------------------------------------------------------------------
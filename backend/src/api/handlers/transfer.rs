@@ -1,22 +1,24 @@
 //! Chunked transfer API handlers for swarm-based artifact distribution.
 
 use axum::{
-    extract::{Extension, Path, State},
-    routing::{get, post},
+    body::Bytes,
+    extract::{Extension, Path, Query, State},
+    routing::{get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-use utoipa::{OpenApi, ToSchema};
+use utoipa::{IntoParams, OpenApi, ToSchema};
 use uuid::Uuid;
 
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::Result;
-use crate::services::transfer_service::{InitTransferRequest, TransferService};
+use crate::services::transfer_service::{InitTransferRequest, TransferProgress, TransferService};
 
 /// Create transfer routes (nested under /api/v1/peers/:id/transfer)
 pub fn router() -> Router<SharedState> {
     Router::new()
+        .route("/", get(list_transfers))
         .route("/init", post(init_transfer))
         .route("/:session_id/chunks", get(get_chunk_manifest))
         .route("/:session_id", get(get_session))
@@ -26,6 +28,8 @@ pub fn router() -> Router<SharedState> {
         )
         .route("/:session_id/chunk/:chunk_index/fail", post(fail_chunk))
         .route("/:session_id/chunk/:chunk_index/retry", post(retry_chunk))
+        .route("/:session_id/chunk/:chunk_index/data", put(upload_chunk))
+        .route("/:session_id/resume", post(resume_transfer))
         .route("/:session_id/complete", post(complete_session))
         .route("/:session_id/fail", post(fail_session))
 }
@@ -77,6 +81,125 @@ pub struct FailBody {
     pub error: String,
 }
 
+/// Query parameters for `GET /api/v1/peers/:id/transfer`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListTransfersQuery {
+    /// Include each session's per-chunk manifest.
+    #[serde(default)]
+    pub include_chunks: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransferProgressResponse {
+    pub session_id: Uuid,
+    pub artifact_id: Uuid,
+    pub status: String,
+    pub total_size: i64,
+    pub total_chunks: i32,
+    pub completed_chunks: i64,
+    pub pending_chunks: i64,
+    pub failed_chunks: i64,
+    pub bytes_completed: i64,
+    pub percent_complete: f64,
+    /// Lowest chunk index not yet completed.
+    pub resume_from_chunk: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_activity_at: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunks: Option<Vec<ChunkEntry>>,
+}
+
+impl From<TransferProgress> for TransferProgressResponse {
+    fn from(p: TransferProgress) -> Self {
+        Self {
+            percent_complete: p.percent_complete(),
+            session_id: p.session_id,
+            artifact_id: p.artifact_id,
+            status: p.status,
+            total_size: p.total_size,
+            total_chunks: p.total_chunks,
+            completed_chunks: p.completed_chunks,
+            pending_chunks: p.pending_chunks,
+            failed_chunks: p.failed_chunks,
+            bytes_completed: p.bytes_completed,
+            resume_from_chunk: p.resume_from_chunk,
+            created_at: p.created_at,
+            last_activity_at: p.last_activity_at,
+            chunks: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResumeTransferResponse {
+    pub progress: TransferProgressResponse,
+    /// Chunk indices still to transfer, ascending.
+    pub remaining_chunks: Vec<i32>,
+    /// Chunks reset to pending because they could not be verified.
+    pub reset_chunks: i64,
+}
+
+/// Query parameters for the chunk data upload.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UploadChunkQuery {
+    /// Peer the chunk was fetched from; omitted when it came from the source.
+    pub source_peer_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UploadChunkResponse {
+    pub chunk_index: i32,
+    /// SHA-256 of the staged chunk bytes.
+    pub checksum: String,
+}
+
+/// GET /api/v1/peers/:id/transfer
+#[utoipa::path(
+    get,
+    path = "/{id}/transfer",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(
+        ("id" = Uuid, Path, description = "Peer instance ID"),
+        ListTransfersQuery,
+    ),
+    responses(
+        (status = 200, description = "Pending and in-progress transfer sessions of the peer", body = Vec<TransferProgressResponse>),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_transfers(
+    State(state): State<SharedState>,
+    Path(peer_id): Path<Uuid>,
+    Query(query): Query<ListTransfersQuery>,
+) -> Result<Json<Vec<TransferProgressResponse>>> {
+    let service = TransferService::new(state.db.clone());
+    let mut items = Vec::new();
+    for progress in service.list_active_progress(peer_id).await? {
+        let session_id = progress.session_id;
+        let mut item = TransferProgressResponse::from(progress);
+        if query.include_chunks {
+            item.chunks = Some(
+                service
+                    .get_chunk_manifest(session_id)
+                    .await?
+                    .into_iter()
+                    .map(|c| ChunkEntry {
+                        chunk_index: c.chunk_index,
+                        byte_offset: c.byte_offset,
+                        byte_length: c.byte_length,
+                        checksum: c.checksum,
+                        status: c.status,
+                        source_peer_id: c.source_peer_id,
+                    })
+                    .collect(),
+            );
+        }
+        items.push(item);
+    }
+    Ok(Json(items))
+}
+
 /// POST /api/v1/peers/:id/transfer/init
 #[utoipa::path(
     post,
@@ -283,6 +406,83 @@ async fn retry_chunk(
     service.retry_chunk(session_id, chunk_index).await
 }
 
+/// PUT /api/v1/peers/:id/transfer/:session_id/chunk/:chunk_index/data
+#[utoipa::path(
+    put,
+    path = "/{id}/transfer/{session_id}/chunk/{chunk_index}/data",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(
+        ("id" = Uuid, Path, description = "Peer instance ID"),
+        ("session_id" = Uuid, Path, description = "Transfer session ID"),
+        ("chunk_index" = i32, Path, description = "Chunk index"),
+        UploadChunkQuery,
+    ),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Chunk staged and marked complete", body = UploadChunkResponse),
+        (status = 400, description = "Body length does not match the chunk"),
+        (status = 409, description = "Session is not pending or in progress"),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn upload_chunk(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path((_peer_id, session_id, chunk_index)): Path<(Uuid, Uuid, i32)>,
+    Query(query): Query<UploadChunkQuery>,
+    body: Bytes,
+) -> Result<Json<UploadChunkResponse>> {
+    auth.require_admin()?;
+    let service = TransferService::new(state.db.clone());
+    let checksum = service
+        .stage_chunk(
+            state.storage.as_ref(),
+            session_id,
+            chunk_index,
+            body,
+            query.source_peer_id,
+        )
+        .await?;
+    Ok(Json(UploadChunkResponse {
+        chunk_index,
+        checksum,
+    }))
+}
+
+/// POST /api/v1/peers/:id/transfer/:session_id/resume
+#[utoipa::path(
+    post,
+    path = "/{id}/transfer/{session_id}/resume",
+    context_path = "/api/v1/peers",
+    tag = "peers",
+    params(
+        ("id" = Uuid, Path, description = "Peer instance ID"),
+        ("session_id" = Uuid, Path, description = "Transfer session ID"),
+    ),
+    responses(
+        (status = 200, description = "Session resumed from its verified chunks", body = ResumeTransferResponse),
+        (status = 409, description = "Session already completed or cancelled"),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn resume_transfer(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path((_peer_id, session_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ResumeTransferResponse>> {
+    auth.require_admin()?;
+    let service = TransferService::new(state.db.clone());
+    let resumed = service
+        .resume_session(state.storage.as_ref(), session_id)
+        .await?;
+    Ok(Json(ResumeTransferResponse {
+        progress: resumed.progress.into(),
+        remaining_chunks: resumed.remaining_chunks,
+        reset_chunks: resumed.reset_chunks,
+    }))
+}
+
 /// POST /api/v1/peers/:id/transfer/:session_id/complete
 #[utoipa::path(
     post,
@@ -338,12 +538,15 @@ async fn fail_session(
 #[derive(OpenApi)]
 #[openapi(
     paths(
+        list_transfers,
         init_transfer,
         get_chunk_manifest,
         get_session,
         complete_chunk,
         fail_chunk,
         retry_chunk,
+        upload_chunk,
+        resume_transfer,
         complete_session,
         fail_session,
     ),
@@ -354,6 +557,9 @@ async fn fail_session(
         ChunkEntry,
         CompleteChunkBody,
         FailBody,
+        TransferProgressResponse,
+        ResumeTransferResponse,
+        UploadChunkResponse,
     ))
 )]
 pub struct TransferApiDoc;
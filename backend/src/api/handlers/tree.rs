@@ -17,11 +17,14 @@ use uuid::Uuid;
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
+use crate::services::artifact_preview_service::{self as preview, ArtifactPreview};
+use crate::services::storage_tiering_service::rehydrate_on_miss;
 
 pub fn router() -> Router<SharedState> {
     Router::new()
         .route("/", get(get_tree))
         .route("/content", get(get_content))
+        .route("/preview", get(get_preview))
 }
 
 /// Pure authorization decision for a tree/content read of a single repository.
@@ -124,6 +127,18 @@ pub struct ContentQuery {
     pub max_bytes: Option<i64>,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PreviewQuery {
+    /// Repository key containing the artifact
+    pub repository_key: String,
+    /// Full artifact path within the repository
+    pub path: String,
+    /// Maximum text bytes to return (default 64 KiB, max 1 MiB)
+    pub max_bytes: Option<usize>,
+    /// Maximum archive members to list (default 500, max 5000)
+    pub max_entries: Option<usize>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct TreeNodeResponse {
     pub id: String,
@@ -405,10 +420,104 @@ pub async fn get_content(
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/preview",
+    context_path = "/api/v1/tree",
+    tag = "repositories",
+    params(PreviewQuery),
+    responses(
+        (status = 200, description = "Text prefix, archive listing and parsed manifest", body = ArtifactPreview),
+        (status = 400, description = "Validation error", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Artifact not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_preview(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Query(params): Query<PreviewQuery>,
+) -> Result<Json<ArtifactPreview>> {
+    let repo_row: Option<(Uuid, bool, String, String)> = sqlx::query_as(
+        "SELECT id, is_public, storage_backend, storage_path FROM repositories WHERE key = $1",
+    )
+    .bind(&params.repository_key)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let (repo_id, is_public, storage_backend, storage_path) = repo_row.ok_or_else(|| {
+        AppError::NotFound(format!("Repository '{}' not found", params.repository_key))
+    })?;
+
+    authorize_tree_read(&state, &auth, repo_id, is_public, &params.repository_key).await?;
+
+    let artifact: (Uuid, i64, String, String) = sqlx::query_as(
+        r#"
+        SELECT id, size_bytes, content_type, storage_key
+        FROM artifacts
+        WHERE repository_id = $1 AND path = $2 AND is_deleted = false
+        "#,
+    )
+    .bind(repo_id)
+    .bind(&params.path)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound(format!("Artifact '{}' not found", params.path)))?;
+    let (artifact_id, size_bytes, content_type, storage_key) = artifact;
+
+    let location = crate::storage::StorageLocation {
+        backend: storage_backend,
+        path: storage_path,
+    };
+    let storage = state.storage_for_repo(&location)?;
+    crate::services::quarantine_service::check_artifact_download(&state.db, artifact_id).await?;
+
+    let content_type = if content_type.is_empty() || content_type == "application/octet-stream" {
+        mime_guess::from_path(&params.path)
+            .first_or_octet_stream()
+            .to_string()
+    } else {
+        content_type
+    };
+    let max_bytes = params
+        .max_bytes
+        .unwrap_or(preview::DEFAULT_PREVIEW_TEXT_BYTES)
+        .clamp(1, preview::MAX_PREVIEW_TEXT_BYTES);
+    let max_entries = params
+        .max_entries
+        .unwrap_or(preview::DEFAULT_PREVIEW_ENTRIES)
+        .clamp(1, preview::MAX_PREVIEW_ENTRIES);
+
+    match preview::preview(
+        storage.as_ref(),
+        &storage_key,
+        &params.path,
+        size_bytes,
+        content_type,
+        max_bytes,
+        max_entries,
+    )
+    .await
+    {
+        Ok(body) => Ok(Json(body)),
+        Err(e) => Err(rehydrate_on_miss(&state.db, artifact_id, e).await),
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
-    paths(get_tree, get_content),
-    components(schemas(TreeResponse, TreeNodeResponse,))
+    paths(get_tree, get_content, get_preview),
+    components(schemas(
+        TreeResponse,
+        TreeNodeResponse,
+        ArtifactPreview,
+        preview::PreviewKind,
+        preview::ArchiveListing,
+        preview::ArchiveEntry,
+        preview::PreviewManifest,
+    ))
 )]
 pub struct TreeApiDoc;
 
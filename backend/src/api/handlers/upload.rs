@@ -22,8 +22,10 @@ use uuid::Uuid;
 
 use crate::api::handlers::proxy_helpers;
 use crate::api::handlers::repositories::require_repo_write_access;
+use crate::api::middleware::artifact_properties::current_upload_properties;
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
+use crate::services::metadata_schema_service;
 use crate::services::package_service::PackageService;
 use crate::services::repository_service::RepositoryService;
 use crate::services::upload_service::{self, UploadError, UploadService};
@@ -398,6 +400,20 @@ async fn create_session(
         }
     }
 
+    // Per-repository metadata schema, checked against the properties sent when
+    // the session is created. They are kept on the session and recorded on
+    // completion. Skipped for peer replication like the quota gate above;
+    // replicated artifacts carry the source row's properties instead.
+    let mut artifact_metadata_properties = replication_metadata.artifact_metadata_properties;
+    if !is_replication {
+        metadata_schema_service::check_upload(&state.db, repo_id)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        if let Some(properties) = current_upload_properties().filter(|p| !p.is_empty()) {
+            artifact_metadata_properties = Some(serde_json::Value::Object((*properties).clone()));
+        }
+    }
+
     let session = UploadService::create_session(upload_service::CreateSessionParams {
         db: &state.db,
         storage_path: &state.config.storage_path,
@@ -409,7 +425,7 @@ async fn create_session(
         artifact_version: req.artifact_version.as_deref(),
         artifact_metadata_format: replication_metadata.artifact_metadata_format,
         artifact_metadata: replication_metadata.artifact_metadata,
-        artifact_metadata_properties: replication_metadata.artifact_metadata_properties,
+        artifact_metadata_properties,
         package_description: replication_metadata.package_description,
         package_metadata: replication_metadata.package_metadata,
         is_replication,
@@ -747,6 +763,13 @@ async fn complete(
             .await;
         }
     }
+    // Client properties captured when the session was created. Replication
+    // sessions applied theirs through `set_metadata` above.
+    if session.artifact_metadata_format.is_none() {
+        if let Some(serde_json::Value::Object(properties)) = &session.artifact_metadata_properties {
+            metadata_schema_service::record_properties(&state.db, artifact_id, properties).await;
+        }
+    }
 
     if let Some((package_name, package_version)) =
         completed_package_catalog_entry(&session, &repo.format)
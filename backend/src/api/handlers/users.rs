@@ -11,6 +11,7 @@ use utoipa::{IntoParams, OpenApi, ToSchema};
 use uuid::Uuid;
 
 use crate::api::dto::Pagination;
+use crate::api::handlers::repositories::require_visible;
 use crate::api::middleware::auth::AuthExtension;
 use crate::api::SharedState;
 use crate::error::{AppError, Result};
@@ -24,6 +25,11 @@ use crate::services::auth_service::{
     invalidate_user_token_cache_entries, invalidate_user_tokens, AuthService,
 };
 use crate::services::password_policy::PasswordPolicyConfig;
+use crate::services::repository_service::RepositoryService;
+use crate::services::token_usage_service::{TokenIpUsage, TokenUsage, TokenUsageService};
+use crate::services::user_preference_service::{
+    UpdateUserPreferences, UserPreferenceService, UserPreferences,
+};
 use std::sync::atomic::Ordering;
 
 /// Admin-only user-management routes. Mount under `admin_middleware`.
@@ -58,6 +64,7 @@ pub fn self_or_admin_router() -> Router<SharedState> {
         .route("/:id", get(get_user))
         .route("/:id/tokens", get(list_user_tokens).post(create_api_token))
         .route("/:id/tokens/:token_id", delete(revoke_api_token))
+        .route("/:id/tokens/:token_id/usage", get(get_api_token_usage))
 }
 
 /// Self-service password change.
@@ -115,6 +122,10 @@ pub fn self_router() -> Router<SharedState> {
             "/me/tokens/:token_id",
             delete(revoke_current_user_api_token),
         )
+        .route(
+            "/me/preferences",
+            get(get_current_user_preferences).put(update_current_user_preferences),
+        )
 }
 
 /// Admin-only password administration routes (reset, force-change).
@@ -1044,6 +1055,36 @@ pub async fn revoke_api_token(
     revoke_api_token_inner(&state, &auth, user_id, token_id).await
 }
 
+/// Get usage of an API token
+#[utoipa::path(
+    get,
+    path = "/{id}/tokens/{token_id}/usage",
+    context_path = "/api/v1/users",
+    tag = "users",
+    operation_id = "get_user_api_token_usage",
+    params(
+        ("id" = Uuid, Path, description = "User ID"),
+        ("token_id" = Uuid, Path, description = "API token ID"),
+    ),
+    responses(
+        (status = 200, description = "Call count, last use and source IPs of the token", body = TokenUsage),
+        (status = 403, description = "Cannot view other users' tokens"),
+        (status = 404, description = "API token not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_api_token_usage(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path((user_id, token_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<TokenUsage>> {
+    auth.require_self_or_admin(user_id, "Cannot view other users' tokens")?;
+    let usage = TokenUsageService::new(state.db.clone())
+        .token_usage(user_id, token_id)
+        .await?;
+    Ok(Json(usage))
+}
+
 /// Shared token-revocation for both `DELETE /users/:id/tokens/:token_id`
 /// (self-or-admin) and `DELETE /users/me/tokens/:token_id` (self only).
 /// Ownership is scoped by `user_id`, which the `/me` alias resolves to
@@ -1702,6 +1743,71 @@ pub async fn change_current_user_password(
     change_password_inner(&state, &auth, auth.user_id, payload).await
 }
 
+/// Get the authenticated caller's preferences.
+///
+/// Unset values are `null` and mean the server default applies.
+#[utoipa::path(
+    get,
+    path = "/me/preferences",
+    context_path = "/api/v1/users",
+    tag = "users",
+    operation_id = "get_current_user_preferences",
+    responses(
+        (status = 200, description = "The caller's preferences", body = UserPreferences),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_current_user_preferences(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+) -> Result<Json<UserPreferences>> {
+    Ok(Json(
+        UserPreferenceService::new(state.db.clone())
+            .get(auth.user_id)
+            .await?,
+    ))
+}
+
+/// Update the authenticated caller's preferences.
+///
+/// Omitted fields are left unchanged; `null` resets a field to the server
+/// default. The default repository must be one the caller can see.
+#[utoipa::path(
+    put,
+    path = "/me/preferences",
+    context_path = "/api/v1/users",
+    tag = "users",
+    operation_id = "update_current_user_preferences",
+    request_body = UpdateUserPreferences,
+    responses(
+        (status = 200, description = "Preferences updated", body = UserPreferences),
+        (status = 404, description = "Default repository not found"),
+        (status = 422, description = "Validation error"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_current_user_preferences(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<UpdateUserPreferences>,
+) -> Result<Json<UserPreferences>> {
+    let default_repository_id = match payload.default_repository {
+        Some(Some(ref key)) => {
+            let repo_service = RepositoryService::new(state.db.clone());
+            let repo = repo_service.get_by_key(key).await?;
+            require_visible(&repo, &Some(auth.clone()), &repo_service).await?;
+            Some(Some(repo.id))
+        }
+        Some(None) => Some(None),
+        None => None,
+    };
+    Ok(Json(
+        UserPreferenceService::new(state.db.clone())
+            .update(auth.user_id, &payload, default_repository_id)
+            .await?,
+    ))
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -1716,6 +1822,7 @@ pub async fn change_current_user_password(
         list_user_tokens,
         create_api_token,
         revoke_api_token,
+        get_api_token_usage,
         change_password,
         reset_password,
         force_password_change,
@@ -1724,6 +1831,8 @@ pub async fn change_current_user_password(
         create_current_user_api_token,
         revoke_current_user_api_token,
         change_current_user_password,
+        get_current_user_preferences,
+        update_current_user_preferences,
     ),
     components(schemas(
         ListUsersQuery,
@@ -1739,9 +1848,13 @@ pub async fn change_current_user_password(
         ApiTokenResponse,
         ApiTokenCreatedResponse,
         ApiTokenListResponse,
+        TokenUsage,
+        TokenIpUsage,
         ChangePasswordRequest,
         ResetPasswordResponse,
         ForcePasswordChangeResponse,
+        UserPreferences,
+        UpdateUserPreferences,
     ))
 )]
 pub struct UsersApiDoc;
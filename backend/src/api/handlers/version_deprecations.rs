@@ -0,0 +1,337 @@
+//! Version yank / deprecation handlers.
+//!
+//! Marks package versions as deprecated without removing them: npm
+//! packuments carry `deprecated`, the Cargo index carries `yanked: true` and
+//! the PyPI simple index carries PEP 592 `yanked`, while the bytes stay
+//! downloadable for reproducible builds.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::api::handlers::repositories::{
+    require_repo_fine_grained_action, require_repo_write_access, require_visible,
+};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::models::repository::{Repository, RepositoryFormat};
+use crate::services::repository_service::RepositoryService;
+use crate::services::version_deprecation_service::{VersionDeprecation, VersionDeprecationService};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_deprecations, deprecate_version, undeprecate_version),
+    components(schemas(
+        VersionDeprecation,
+        DeprecateVersionRequest,
+        VersionDeprecationListResponse
+    )),
+    tags((name = "version-deprecations", description = "Package version yanking and deprecation"))
+)]
+pub struct VersionDeprecationsApiDoc;
+
+/// Create deprecation routes (nested under /api/v1/repositories/:key/deprecations).
+pub fn router() -> Router<SharedState> {
+    Router::new().route(
+        "/:key/deprecations",
+        get(list_deprecations)
+            .put(deprecate_version)
+            .delete(undeprecate_version),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Request / Response types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListDeprecationsQuery {
+    /// Only list deprecations of this package.
+    pub package: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UndeprecateQuery {
+    pub package: String,
+    pub version: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeprecateVersionRequest {
+    /// Package name as published (e.g. `@scope/pkg`, `serde`, `Django`).
+    pub package: String,
+    pub version: String,
+    /// Shown by npm and as the PEP 592 yank reason; ignored by Cargo.
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionDeprecationListResponse {
+    pub items: Vec<VersionDeprecation>,
+    pub total: usize,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Formats whose metadata can advertise a deprecated version.
+fn supports_deprecation(format: &RepositoryFormat) -> bool {
+    matches!(
+        format,
+        RepositoryFormat::Npm
+            | RepositoryFormat::Yarn
+            | RepositoryFormat::Bower
+            | RepositoryFormat::Pnpm
+            | RepositoryFormat::Cargo
+            | RepositoryFormat::Pypi
+            | RepositoryFormat::Poetry
+    )
+}
+
+/// Stored package name for a version in `repo`. Cargo stores crate names
+/// lowercased; every other format stores the name as published.
+fn stored_package_name(format: &RepositoryFormat, package: &str) -> String {
+    match format {
+        RepositoryFormat::Cargo => package.to_lowercase(),
+        _ => package.to_string(),
+    }
+}
+
+/// Resolve the repository and apply the write gates shared by both
+/// mutating handlers.
+async fn writable_repo(state: &SharedState, auth: &AuthExtension, key: &str) -> Result<Repository> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(key).await?;
+    // Tenant write gate: the /repositories nest bypasses repo_visibility_middleware,
+    // so enforce is_public + role-assignment membership here (see #xtenant).
+    require_repo_write_access(auth, &repo, &repo_service).await?;
+    require_repo_fine_grained_action(auth, repo.id, "write", &state.permission_service).await?;
+
+    if !supports_deprecation(&repo.format) {
+        return Err(AppError::Validation(format!(
+            "Version deprecation is not supported for {:?} repositories",
+            repo.format
+        )));
+    }
+    Ok(repo)
+}
+
+/// Drop cached metadata that embeds the deprecation marker.
+async fn invalidate_metadata(state: &SharedState, repo: &Repository, package: &str) {
+    match repo.format {
+        RepositoryFormat::Cargo => {
+            super::cargo::invalidate_crate_index(state, repo.id, &repo.key, package).await
+        }
+        RepositoryFormat::Npm
+        | RepositoryFormat::Yarn
+        | RepositoryFormat::Bower
+        | RepositoryFormat::Pnpm => {
+            super::npm::invalidate_packument_caches(state, repo.id, &repo.key, package).await
+        }
+        _ => {}
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// List deprecated versions in a repository
+#[utoipa::path(
+    get,
+    path = "/{key}/deprecations",
+    context_path = "/api/v1/repositories",
+    tag = "version-deprecations",
+    params(
+        ("key" = String, Path, description = "Repository key"),
+        ListDeprecationsQuery
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Deprecated versions", body = VersionDeprecationListResponse),
+        (status = 404, description = "Repository not found")
+    )
+)]
+async fn list_deprecations(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Query(query): Query<ListDeprecationsQuery>,
+) -> Result<Json<VersionDeprecationListResponse>> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(&key).await?;
+    require_visible(&repo, &auth, &repo_service).await?;
+
+    let package = query
+        .package
+        .as_deref()
+        .map(|p| stored_package_name(&repo.format, p));
+    let items = VersionDeprecationService::new(state.db.clone())
+        .list(repo.id, package.as_deref())
+        .await?;
+    let total = items.len();
+
+    Ok(Json(VersionDeprecationListResponse { items, total }))
+}
+
+/// Deprecate (yank) a package version
+///
+/// The version stays downloadable; only its metadata changes. Re-deprecating
+/// a version replaces its message.
+#[utoipa::path(
+    put,
+    path = "/{key}/deprecations",
+    context_path = "/api/v1/repositories",
+    tag = "version-deprecations",
+    params(
+        ("key" = String, Path, description = "Repository key")
+    ),
+    request_body = DeprecateVersionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Version deprecated", body = VersionDeprecation),
+        (status = 400, description = "Invalid message or unsupported repository format"),
+        (status = 404, description = "Repository or version not found")
+    )
+)]
+async fn deprecate_version(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Json(payload): Json<DeprecateVersionRequest>,
+) -> Result<Json<VersionDeprecation>> {
+    let auth = require_auth(auth)?;
+    let repo = writable_repo(&state, &auth, &key).await?;
+
+    let package = stored_package_name(&repo.format, &payload.package);
+    let deprecation = VersionDeprecationService::new(state.db.clone())
+        .deprecate(
+            repo.id,
+            &package,
+            &payload.version,
+            payload.message.as_deref(),
+            Some(auth.user_id),
+        )
+        .await?;
+
+    invalidate_metadata(&state, &repo, &package).await;
+
+    Ok(Json(deprecation))
+}
+
+/// Remove a version's deprecation (un-yank)
+#[utoipa::path(
+    delete,
+    path = "/{key}/deprecations",
+    context_path = "/api/v1/repositories",
+    tag = "version-deprecations",
+    params(
+        ("key" = String, Path, description = "Repository key"),
+        UndeprecateQuery
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Deprecation removed"),
+        (status = 404, description = "Repository not found or version not deprecated")
+    )
+)]
+async fn undeprecate_version(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(key): Path<String>,
+    Query(query): Query<UndeprecateQuery>,
+) -> Result<axum::http::StatusCode> {
+    let auth = require_auth(auth)?;
+    let repo = writable_repo(&state, &auth, &key).await?;
+
+    let package = stored_package_name(&repo.format, &query.package);
+    let removed = VersionDeprecationService::new(state.db.clone())
+        .undeprecate(repo.id, &package, &query.version)
+        .await?;
+    if !removed {
+        return Err(AppError::NotFound(format!(
+            "Version '{}' of package '{}' is not deprecated",
+            query.version, query.package
+        )));
+    }
+
+    invalidate_metadata(&state, &repo, &package).await;
+
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cross-tenant authz guard (xtenant-write-authz-systemic): the mutating
+    /// handlers go through `writable_repo`, which must apply the tenant write
+    /// gate, and the list handler must apply `require_visible`.
+    #[test]
+    fn test_deprecation_handlers_enforce_tenant_gate() {
+        let source = include_str!("version_deprecations.rs");
+        let section = |marker: &str| {
+            let start = source
+                .find(marker)
+                .unwrap_or_else(|| panic!("`{}` not found", marker));
+            let rest = &source[start + marker.len()..];
+            let end = rest.find("\nasync fn ").unwrap_or(rest.len());
+            rest[..end].to_string()
+        };
+        let gate = section("async fn writable_repo(");
+        assert!(gate.contains("require_repo_write_access("));
+        assert!(gate.contains("require_repo_fine_grained_action("));
+        for handler in ["deprecate_version", "undeprecate_version"] {
+            assert!(
+                section(&format!("async fn {}(", handler)).contains("writable_repo("),
+                "handler `{}` must call writable_repo",
+                handler
+            );
+        }
+        assert!(section("async fn list_deprecations(").contains("require_visible("));
+    }
+
+    #[test]
+    fn test_supports_deprecation_formats() {
+        assert!(supports_deprecation(&RepositoryFormat::Npm));
+        assert!(supports_deprecation(&RepositoryFormat::Pnpm));
+        assert!(supports_deprecation(&RepositoryFormat::Cargo));
+        assert!(supports_deprecation(&RepositoryFormat::Pypi));
+        assert!(!supports_deprecation(&RepositoryFormat::Maven));
+        assert!(!supports_deprecation(&RepositoryFormat::Docker));
+    }
+
+    #[test]
+    fn test_stored_package_name_lowercases_crates_only() {
+        assert_eq!(
+            stored_package_name(&RepositoryFormat::Cargo, "Serde_Json"),
+            "serde_json"
+        );
+        assert_eq!(
+            stored_package_name(&RepositoryFormat::Pypi, "Django"),
+            "Django"
+        );
+        assert_eq!(
+            stored_package_name(&RepositoryFormat::Npm, "@Scope/pkg"),
+            "@Scope/pkg"
+        );
+    }
+
+    #[test]
+    fn test_deprecate_request_message_optional() {
+        let req: DeprecateVersionRequest =
+            serde_json::from_str(r#"{"package": "serde", "version": "1.0.0"}"#).unwrap();
+        assert_eq!(req.package, "serde");
+        assert!(req.message.is_none());
+    }
+}
@@ -0,0 +1,287 @@
+//! Vulnerability watchlist administration.
+//!
+//! ## Route map
+//!
+//! ```text
+//! Admin (/api/v1/admin/vulnerability-watchlist)
+//! GET    /                          → list_entries
+//! POST   /                          → create_entry
+//! GET    /alerts                    → list_alerts
+//! POST   /alerts/acknowledge        → acknowledge_alerts
+//! GET    /alerts/:id                → get_alert
+//! GET    /:id                       → get_entry
+//! PUT    /:id                       → update_entry
+//! DELETE /:id                       → delete_entry
+//! ```
+//!
+//! See [`crate::services::watchlist_service`] for how uploads and scans are
+//! matched. `watchlist_matched` webhooks carry the alert id as `entity_id`.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::SharedState;
+use crate::error::Result;
+use crate::services::watchlist_service::{
+    self, WatchKind, WatchlistAlert, WatchlistEntry, WatchlistEntryRequest,
+};
+
+/// Watchlist routes (auth enforced by the outer admin_middleware).
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_entries).post(create_entry))
+        .route("/alerts", get(list_alerts))
+        .route("/alerts/acknowledge", post(acknowledge_alerts))
+        .route("/alerts/:id", get(get_alert))
+        .route(
+            "/:id",
+            get(get_entry).put(update_entry).delete(delete_entry),
+        )
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WatchlistAlertsQuery {
+    /// Only alerts raised by this entry.
+    pub watch_id: Option<Uuid>,
+    /// Only alerts not yet acknowledged.
+    #[serde(default)]
+    pub open: bool,
+    /// Maximum number of alerts (default 50, max 500).
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WatchlistAlertListResponse {
+    pub items: Vec<WatchlistAlert>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AcknowledgeWatchlistAlertsRequest {
+    /// Alerts to acknowledge. Omit to acknowledge every open alert.
+    pub ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AcknowledgeWatchlistAlertsResponse {
+    pub updated: u64,
+}
+
+/// GET /api/v1/admin/vulnerability-watchlist
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/admin/vulnerability-watchlist",
+    tag = "security",
+    responses(
+        (status = 200, description = "All watchlist entries", body = Vec<WatchlistEntry>),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_entries(State(state): State<SharedState>) -> Result<Json<Vec<WatchlistEntry>>> {
+    Ok(Json(watchlist_service::list(&state.db).await?))
+}
+
+/// POST /api/v1/admin/vulnerability-watchlist
+#[utoipa::path(
+    post,
+    path = "",
+    context_path = "/api/v1/admin/vulnerability-watchlist",
+    tag = "security",
+    request_body = WatchlistEntryRequest,
+    responses(
+        (status = 201, description = "Watchlist entry created", body = WatchlistEntry),
+        (status = 400, description = "Invalid pattern or version", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Repository not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Item already watched", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn create_entry(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<WatchlistEntryRequest>,
+) -> Result<(StatusCode, Json<WatchlistEntry>)> {
+    auth.require_admin()?;
+    let entry = watchlist_service::create(&state.db, &payload, auth.user_id).await?;
+    tracing::info!(
+        kind = entry.kind.as_str(),
+        pattern = %entry.pattern,
+        created_by = %auth.username,
+        "Watchlist entry created"
+    );
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// GET /api/v1/admin/vulnerability-watchlist/{id}
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    context_path = "/api/v1/admin/vulnerability-watchlist",
+    tag = "security",
+    params(("id" = Uuid, Path, description = "Watchlist entry ID")),
+    responses(
+        (status = 200, description = "Watchlist entry", body = WatchlistEntry),
+        (status = 404, description = "Watchlist entry not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_entry(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WatchlistEntry>> {
+    Ok(Json(watchlist_service::get(&state.db, id).await?))
+}
+
+/// PUT /api/v1/admin/vulnerability-watchlist/{id}
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    context_path = "/api/v1/admin/vulnerability-watchlist",
+    tag = "security",
+    params(("id" = Uuid, Path, description = "Watchlist entry ID")),
+    request_body = WatchlistEntryRequest,
+    responses(
+        (status = 200, description = "Watchlist entry replaced", body = WatchlistEntry),
+        (status = 400, description = "Invalid pattern or version", body = crate::api::openapi::ErrorResponse),
+        (status = 404, description = "Watchlist entry or repository not found", body = crate::api::openapi::ErrorResponse),
+        (status = 409, description = "Item already watched", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn update_entry(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<WatchlistEntryRequest>,
+) -> Result<Json<WatchlistEntry>> {
+    auth.require_admin()?;
+    let entry = watchlist_service::update(&state.db, id, &payload).await?;
+    tracing::info!(
+        watch_id = %id,
+        updated_by = %auth.username,
+        "Watchlist entry updated"
+    );
+    Ok(Json(entry))
+}
+
+/// DELETE /api/v1/admin/vulnerability-watchlist/{id}
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    context_path = "/api/v1/admin/vulnerability-watchlist",
+    tag = "security",
+    params(("id" = Uuid, Path, description = "Watchlist entry ID")),
+    responses(
+        (status = 204, description = "Watchlist entry and its alerts deleted"),
+        (status = 404, description = "Watchlist entry not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_entry(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    auth.require_admin()?;
+    watchlist_service::delete(&state.db, id).await?;
+    tracing::info!(watch_id = %id, deleted_by = %auth.username, "Watchlist entry deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/admin/vulnerability-watchlist/alerts
+#[utoipa::path(
+    get,
+    path = "/alerts",
+    context_path = "/api/v1/admin/vulnerability-watchlist",
+    tag = "security",
+    params(WatchlistAlertsQuery),
+    responses(
+        (status = 200, description = "Watchlist alerts, newest first", body = WatchlistAlertListResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_alerts(
+    State(state): State<SharedState>,
+    Query(query): Query<WatchlistAlertsQuery>,
+) -> Result<Json<WatchlistAlertListResponse>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let items =
+        watchlist_service::list_alerts(&state.db, query.watch_id, query.open, limit).await?;
+    Ok(Json(WatchlistAlertListResponse { items }))
+}
+
+/// GET /api/v1/admin/vulnerability-watchlist/alerts/{id}
+#[utoipa::path(
+    get,
+    path = "/alerts/{id}",
+    context_path = "/api/v1/admin/vulnerability-watchlist",
+    tag = "security",
+    params(("id" = Uuid, Path, description = "Watchlist alert ID")),
+    responses(
+        (status = 200, description = "Watchlist alert", body = WatchlistAlert),
+        (status = 404, description = "Watchlist alert not found", body = crate::api::openapi::ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_alert(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WatchlistAlert>> {
+    Ok(Json(watchlist_service::get_alert(&state.db, id).await?))
+}
+
+/// POST /api/v1/admin/vulnerability-watchlist/alerts/acknowledge
+#[utoipa::path(
+    post,
+    path = "/alerts/acknowledge",
+    context_path = "/api/v1/admin/vulnerability-watchlist",
+    tag = "security",
+    request_body = AcknowledgeWatchlistAlertsRequest,
+    responses(
+        (status = 200, description = "Alerts acknowledged", body = AcknowledgeWatchlistAlertsResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn acknowledge_alerts(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<AuthExtension>,
+    Json(payload): Json<AcknowledgeWatchlistAlertsRequest>,
+) -> Result<Json<AcknowledgeWatchlistAlertsResponse>> {
+    auth.require_admin()?;
+    let updated =
+        watchlist_service::acknowledge_alerts(&state.db, auth.user_id, payload.ids.as_deref())
+            .await?;
+    Ok(Json(AcknowledgeWatchlistAlertsResponse { updated }))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_entries,
+        create_entry,
+        get_entry,
+        update_entry,
+        delete_entry,
+        list_alerts,
+        get_alert,
+        acknowledge_alerts,
+    ),
+    components(schemas(
+        WatchKind,
+        WatchlistEntry,
+        WatchlistEntryRequest,
+        WatchlistAlert,
+        WatchlistAlertListResponse,
+        AcknowledgeWatchlistAlertsRequest,
+        AcknowledgeWatchlistAlertsResponse,
+    ))
+)]
+pub struct VulnerabilityWatchlistApiDoc;
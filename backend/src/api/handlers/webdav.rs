@@ -0,0 +1,614 @@
+//! WebDAV endpoint for generic repositories.
+//!
+//! Mounted at `/webdav/{repo_key}/` so OS file managers (Finder, Explorer,
+//! GNOME Files) and legacy tooling such as `cadaver` or `davfs2` can browse a
+//! generic repository and deposit files into it. Supported methods are
+//! OPTIONS, PROPFIND (depth 0 and 1), GET, HEAD, PUT, MKCOL and DELETE
+//! (WebDAV class 1, no locking).
+//!
+//! The route sits behind the repository visibility middleware like every
+//! other format endpoint, so Basic (password or API token) and Bearer
+//! credentials, API token repository restrictions and fine-grained
+//! permissions apply as usual: PROPFIND and GET need `read`, PUT and MKCOL
+//! need `write`, DELETE needs `delete`. File reads, writes and deletes go
+//! through the REST artifact handlers, so quarantine, promotion-only and
+//! immutability gates are shared too.
+
+use axum::{
+    extract::{Extension, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::any,
+    Router,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::api::handlers::repositories::{
+    self, require_repo_fine_grained_action, require_repo_write_access, require_visible,
+    ArtifactVersionQuery,
+};
+use crate::api::middleware::auth::AuthExtension;
+use crate::api::middleware::download_telemetry::DownloadContext;
+use crate::api::SharedState;
+use crate::error::{AppError, Result};
+use crate::models::repository::{Repository, RepositoryFormat};
+use crate::services::repository_service::RepositoryService;
+use crate::services::upload_service;
+use crate::services::webdav_service::{normalize_path, parent_path, DavEntry, WebDavService};
+
+/// Path prefix the WebDAV router is nested under.
+pub const MOUNT_PREFIX: &str = "/webdav";
+
+/// Methods advertised in `Allow`.
+const ALLOWED_METHODS: &str = "OPTIONS, PROPFIND, GET, HEAD, PUT, MKCOL, DELETE";
+
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/:repo_key", any(repository_root))
+        .route("/:repo_key/", any(repository_root))
+        .route("/:repo_key/*path", any(resource))
+}
+
+async fn repository_root(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path(repo_key): Path<String>,
+    ctx: DownloadContext,
+    request: Request,
+) -> Response {
+    dispatch(state, auth, repo_key, String::new(), ctx, request).await
+}
+
+async fn resource(
+    State(state): State<SharedState>,
+    Extension(auth): Extension<Option<AuthExtension>>,
+    Path((repo_key, path)): Path<(String, String)>,
+    ctx: DownloadContext,
+    request: Request,
+) -> Response {
+    dispatch(state, auth, repo_key, path, ctx, request).await
+}
+
+/// Axum has no method filters for PROPFIND or MKCOL, so every method is
+/// routed here and dispatched by name.
+async fn dispatch(
+    state: SharedState,
+    auth: Option<AuthExtension>,
+    repo_key: String,
+    path: String,
+    ctx: DownloadContext,
+    request: Request,
+) -> Response {
+    let path = normalize_path(&path).to_string();
+    let method = request.method().as_str().to_string();
+    let result = match method.as_str() {
+        "OPTIONS" => Ok(options_response()),
+        "PROPFIND" => propfind(&state, auth, &repo_key, &path, request.headers()).await,
+        "GET" | "HEAD" => get_file(state, auth, repo_key, path, ctx, request).await,
+        "PUT" => put_file(state, auth, repo_key, path, request).await,
+        "MKCOL" => mkcol(&state, auth, &repo_key, &path, request.headers()).await,
+        "DELETE" => delete_resource(&state, auth, &repo_key, &path, request.headers()).await,
+        _ => Ok(method_not_allowed()),
+    };
+    result.unwrap_or_else(|e| e.into_response())
+}
+
+// ---------------------------------------------------------------------------
+// Responses
+// ---------------------------------------------------------------------------
+
+fn options_response() -> Response {
+    (
+        StatusCode::OK,
+        [
+            ("DAV", "1"),
+            ("MS-Author-Via", "DAV"),
+            ("Allow", ALLOWED_METHODS),
+        ],
+    )
+        .into_response()
+}
+
+fn method_not_allowed() -> Response {
+    (
+        StatusCode::METHOD_NOT_ALLOWED,
+        [(header::ALLOW, ALLOWED_METHODS)],
+    )
+        .into_response()
+}
+
+fn multistatus_response(body: String) -> Response {
+    (
+        StatusCode::MULTI_STATUS,
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// XML
+// ---------------------------------------------------------------------------
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Absolute, percent-encoded href of a resource; folders end in `/`.
+fn href(repo_key: &str, path: &str, is_collection: bool) -> String {
+    let mut out = format!("{}/{}/", MOUNT_PREFIX, urlencoding::encode(repo_key));
+    let encoded: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect();
+    out.push_str(&encoded.join("/"));
+    if is_collection && !encoded.is_empty() {
+        out.push('/');
+    }
+    out
+}
+
+/// RFC 1123 date used by `getlastmodified`.
+fn http_date(t: DateTime<Utc>) -> String {
+    t.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn response_xml(repo_key: &str, entry: &DavEntry) -> String {
+    let name = if entry.path.is_empty() {
+        repo_key
+    } else {
+        entry.name()
+    };
+    let mut props = format!("<D:displayname>{}</D:displayname>", xml_escape(name));
+    if entry.is_collection {
+        props.push_str("<D:resourcetype><D:collection/></D:resourcetype>");
+    } else {
+        props.push_str("<D:resourcetype/>");
+    }
+    if let Some(size) = entry.size_bytes {
+        props.push_str(&format!(
+            "<D:getcontentlength>{}</D:getcontentlength>",
+            size
+        ));
+    }
+    if let Some(content_type) = &entry.content_type {
+        props.push_str(&format!(
+            "<D:getcontenttype>{}</D:getcontenttype>",
+            xml_escape(content_type)
+        ));
+    }
+    if let Some(etag) = &entry.etag {
+        props.push_str(&format!(
+            "<D:getetag>\"{}\"</D:getetag>",
+            xml_escape(etag.trim())
+        ));
+    }
+    if let Some(created_at) = entry.created_at {
+        props.push_str(&format!(
+            "<D:creationdate>{}</D:creationdate>",
+            created_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ));
+    }
+    if let Some(last_modified) = entry.last_modified {
+        props.push_str(&format!(
+            "<D:getlastmodified>{}</D:getlastmodified>",
+            http_date(last_modified)
+        ));
+    }
+    format!(
+        "<D:response><D:href>{}</D:href><D:propstat><D:prop>{}</D:prop>\
+         <D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>",
+        xml_escape(&href(repo_key, &entry.path, entry.is_collection)),
+        props
+    )
+}
+
+fn multistatus_xml(repo_key: &str, entries: &[DavEntry]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<D:multistatus xmlns:D=\"DAV:\">",
+    );
+    for entry in entries {
+        out.push_str(&response_xml(repo_key, entry));
+    }
+    out.push_str("</D:multistatus>");
+    out
+}
+
+/// Multistatus listing the members of a collection DELETE that failed.
+fn failures_xml(repo_key: &str, failures: &[(String, StatusCode)]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<D:multistatus xmlns:D=\"DAV:\">",
+    );
+    for (path, status) in failures {
+        out.push_str(&format!(
+            "<D:response><D:href>{}</D:href><D:status>HTTP/1.1 {} {}</D:status></D:response>",
+            xml_escape(&href(repo_key, path, false)),
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default()
+        ));
+    }
+    out.push_str("</D:multistatus>");
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// PROPFIND depth. `infinity` (also the default when the header is absent)
+/// is refused, as RFC 4918 permits, since it would walk the whole repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Depth {
+    Zero,
+    One,
+    Infinity,
+}
+
+fn parse_depth(headers: &HeaderMap) -> Depth {
+    match headers
+        .get("depth")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+    {
+        Some("0") => Depth::Zero,
+        Some("1") => Depth::One,
+        _ => Depth::Infinity,
+    }
+}
+
+/// True when the request carries a body, which MKCOL does not accept.
+fn has_body(headers: &HeaderMap) -> bool {
+    if headers.contains_key(header::TRANSFER_ENCODING) {
+        return true;
+    }
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .is_some_and(|len| len > 0)
+}
+
+fn require_auth(auth: Option<AuthExtension>) -> Result<AuthExtension> {
+    auth.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+}
+
+/// Look up a repository the caller can see. Only generic repositories are
+/// exposed over WebDAV.
+async fn resolve_repo(
+    state: &SharedState,
+    auth: &Option<AuthExtension>,
+    repo_key: &str,
+) -> Result<(RepositoryService, Repository)> {
+    let repo_service = RepositoryService::new(state.db.clone());
+    let repo = repo_service.get_by_key(repo_key).await?;
+    require_visible(&repo, auth, &repo_service).await?;
+    if repo.format != RepositoryFormat::Generic {
+        return Err(AppError::NotFound(
+            "WebDAV is only available for generic repositories".to_string(),
+        ));
+    }
+    Ok((repo_service, repo))
+}
+
+// ---------------------------------------------------------------------------
+// Methods
+// ---------------------------------------------------------------------------
+
+async fn propfind(
+    state: &SharedState,
+    auth: Option<AuthExtension>,
+    repo_key: &str,
+    path: &str,
+    headers: &HeaderMap,
+) -> Result<Response> {
+    let depth = parse_depth(headers);
+    if depth == Depth::Infinity {
+        let body = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+                    <D:error xmlns:D=\"DAV:\"><D:propfind-finite-depth/></D:error>";
+        return Ok((
+            StatusCode::FORBIDDEN,
+            [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+            body,
+        )
+            .into_response());
+    }
+
+    let (_, repo) = resolve_repo(state, &auth, repo_key).await?;
+    let service = WebDavService::new(state.db.clone());
+    let entry = service
+        .lookup(repo.id, path)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Resource not found".to_string()))?;
+
+    let mut entries = vec![entry];
+    if depth == Depth::One && entries[0].is_collection {
+        entries.extend(service.list_children(repo.id, path).await?);
+    }
+    Ok(multistatus_response(multistatus_xml(repo_key, &entries)))
+}
+
+async fn get_file(
+    state: SharedState,
+    auth: Option<AuthExtension>,
+    repo_key: String,
+    path: String,
+    ctx: DownloadContext,
+    request: Request,
+) -> Result<Response> {
+    let (_, repo) = resolve_repo(&state, &auth, &repo_key).await?;
+    let entry = WebDavService::new(state.db.clone())
+        .lookup(repo.id, &path)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Resource not found".to_string()))?;
+    if entry.is_collection {
+        return Ok(method_not_allowed());
+    }
+
+    let response = repositories::download_artifact(
+        State(state),
+        Extension(auth),
+        Path((repo_key, path)),
+        Query(ArtifactVersionQuery { version: None }),
+        ctx,
+        request,
+    )
+    .await?;
+    Ok(response.into_response())
+}
+
+async fn put_file(
+    state: SharedState,
+    auth: Option<AuthExtension>,
+    repo_key: String,
+    path: String,
+    request: Request,
+) -> Result<Response> {
+    let auth = require_auth(auth)?;
+    if path.is_empty() {
+        return Ok(method_not_allowed());
+    }
+    let (_, repo) = resolve_repo(&state, &Some(auth.clone()), &repo_key).await?;
+
+    // A folder cannot be overwritten by a file, and a file cannot be the
+    // parent of one. Missing parent folders are created implicitly, as with
+    // every other generic upload.
+    let service = WebDavService::new(state.db.clone());
+    if let Some(existing) = service.lookup(repo.id, &path).await? {
+        if existing.is_collection {
+            return Ok(method_not_allowed());
+        }
+    }
+    if let Some(parent) = parent_path(&path) {
+        if let Some(parent) = service.lookup(repo.id, parent).await? {
+            if !parent.is_collection {
+                return Err(AppError::Conflict(
+                    "Parent resource is a file, not a folder".to_string(),
+                ));
+            }
+        }
+    }
+
+    let (parts, body) = request.into_parts();
+    let result = repositories::upload_artifact(
+        State(state),
+        Extension(Some(auth)),
+        Path((repo_key, path)),
+        parts.headers,
+        body,
+    )
+    .await;
+    Ok(result.unwrap_or_else(|response| response))
+}
+
+async fn mkcol(
+    state: &SharedState,
+    auth: Option<AuthExtension>,
+    repo_key: &str,
+    path: &str,
+    headers: &HeaderMap,
+) -> Result<Response> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("write")?;
+    if has_body(headers) {
+        return Ok(StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response());
+    }
+    if path.is_empty() {
+        return Ok(method_not_allowed());
+    }
+    upload_service::validate_artifact_path(path)?;
+
+    let (repo_service, repo) = resolve_repo(state, &Some(auth.clone()), repo_key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_fine_grained_action(&auth, repo.id, "write", &state.permission_service).await?;
+
+    let service = WebDavService::new(state.db.clone());
+    if service.lookup(repo.id, path).await?.is_some() {
+        return Ok(method_not_allowed());
+    }
+    let parent = parent_path(path).unwrap_or_default();
+    match service.lookup(repo.id, parent).await? {
+        Some(entry) if entry.is_collection => {}
+        _ => {
+            return Err(AppError::Conflict(
+                "Parent folder does not exist".to_string(),
+            ))
+        }
+    }
+
+    service
+        .create_collection(repo.id, path, auth.user_id)
+        .await?;
+    Ok(StatusCode::CREATED.into_response())
+}
+
+async fn delete_resource(
+    state: &SharedState,
+    auth: Option<AuthExtension>,
+    repo_key: &str,
+    path: &str,
+    headers: &HeaderMap,
+) -> Result<Response> {
+    let auth = require_auth(auth)?;
+    auth.require_scope("delete")?;
+    if path.is_empty() {
+        return Ok(method_not_allowed());
+    }
+    let (repo_service, repo) = resolve_repo(state, &Some(auth.clone()), repo_key).await?;
+    require_repo_write_access(&auth, &repo, &repo_service).await?;
+    require_repo_fine_grained_action(&auth, repo.id, "delete", &state.permission_service).await?;
+
+    let service = WebDavService::new(state.db.clone());
+    let entry = service
+        .lookup(repo.id, path)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Resource not found".to_string()))?;
+
+    let targets = if entry.is_collection {
+        service.artifact_paths_under(repo.id, path).await?
+    } else {
+        vec![path.to_string()]
+    };
+
+    // Each file goes through the REST delete handler so its gates apply per
+    // artifact. A folder is removed only once everything under it is gone;
+    // otherwise the members that could not be deleted are reported.
+    let mut failures = Vec::new();
+    for target in targets {
+        let result = repositories::delete_artifact(
+            State(state.clone()),
+            Extension(Some(auth.clone())),
+            Path((repo_key.to_string(), target.clone())),
+            axum::extract::Query(repositories::DeleteArtifactQuery { confirm: None }),
+            headers.clone(),
+        )
+        .await;
+        if let Err(e) = result {
+            if !entry.is_collection {
+                return Err(e);
+            }
+            failures.push((target, e.into_response().status()));
+        }
+    }
+
+    if !failures.is_empty() {
+        return Ok(multistatus_response(failures_xml(repo_key, &failures)));
+    }
+    if entry.is_collection {
+        service.delete_collections(repo.id, path).await?;
+    }
+    let mut response = StatusCode::NO_CONTENT.into_response();
+    response
+        .headers_mut()
+        .insert("DAV", HeaderValue::from_static("1"));
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn file(path: &str) -> DavEntry {
+        let t = Utc.with_ymd_and_hms(2026, 3, 1, 12, 30, 0).unwrap();
+        DavEntry {
+            path: path.to_string(),
+            is_collection: false,
+            size_bytes: Some(42),
+            content_type: Some("application/octet-stream".to_string()),
+            etag: Some("abc123".to_string()),
+            created_at: Some(t),
+            last_modified: Some(t),
+        }
+    }
+
+    #[test]
+    fn test_href_encodes_segments() {
+        assert_eq!(href("repo", "", true), "/webdav/repo/");
+        assert_eq!(href("repo", "a b/c", true), "/webdav/repo/a%20b/c/");
+        assert_eq!(
+            href("repo", "dir/r&d #1.txt", false),
+            "/webdav/repo/dir/r%26d%20%231.txt"
+        );
+    }
+
+    #[test]
+    fn test_parse_depth() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_depth(&headers), Depth::Infinity);
+        headers.insert("depth", HeaderValue::from_static("0"));
+        assert_eq!(parse_depth(&headers), Depth::Zero);
+        headers.insert("depth", HeaderValue::from_static("1"));
+        assert_eq!(parse_depth(&headers), Depth::One);
+        headers.insert("depth", HeaderValue::from_static("infinity"));
+        assert_eq!(parse_depth(&headers), Depth::Infinity);
+    }
+
+    #[test]
+    fn test_has_body() {
+        let mut headers = HeaderMap::new();
+        assert!(!has_body(&headers));
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from_static("0"));
+        assert!(!has_body(&headers));
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from_static("12"));
+        assert!(has_body(&headers));
+        let mut chunked = HeaderMap::new();
+        chunked.insert(
+            header::TRANSFER_ENCODING,
+            HeaderValue::from_static("chunked"),
+        );
+        assert!(has_body(&chunked));
+    }
+
+    #[test]
+    fn test_http_date() {
+        let t = Utc.with_ymd_and_hms(2026, 3, 1, 12, 30, 0).unwrap();
+        assert_eq!(http_date(t), "Sun, 01 Mar 2026 12:30:00 GMT");
+    }
+
+    #[test]
+    fn test_multistatus_xml() {
+        let entries = vec![
+            DavEntry::collection("", None),
+            DavEntry::collection("docs", None),
+            file("a<b>.txt"),
+        ];
+        let xml = multistatus_xml("repo", &entries);
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<D:multistatus xmlns:D=\"DAV:\">"));
+        assert!(xml.contains("<D:href>/webdav/repo/</D:href>"));
+        assert!(xml.contains("<D:displayname>repo</D:displayname>"));
+        assert!(xml.contains("<D:href>/webdav/repo/docs/</D:href>"));
+        assert!(xml.contains("<D:resourcetype><D:collection/></D:resourcetype>"));
+        assert!(xml.contains("<D:href>/webdav/repo/a%3Cb%3E.txt</D:href>"));
+        assert!(xml.contains("<D:displayname>a&lt;b&gt;.txt</D:displayname>"));
+        assert!(xml.contains("<D:getcontentlength>42</D:getcontentlength>"));
+        assert!(xml.contains("<D:getetag>\"abc123\"</D:getetag>"));
+        assert!(xml.contains("<D:creationdate>2026-03-01T12:30:00Z</D:creationdate>"));
+        assert!(
+            xml.contains("<D:getlastmodified>Sun, 01 Mar 2026 12:30:00 GMT</D:getlastmodified>")
+        );
+        assert!(xml.ends_with("</D:multistatus>"));
+    }
+
+    #[test]
+    fn test_failures_xml() {
+        let xml = failures_xml("repo", &[("a/b.bin".to_string(), StatusCode::CONFLICT)]);
+        assert!(xml.contains("<D:href>/webdav/repo/a/b.bin</D:href>"));
+        assert!(xml.contains("<D:status>HTTP/1.1 409 Conflict</D:status>"));
+    }
+
+    #[test]
+    fn test_options_advertises_dav() {
+        let response = options_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["DAV"], "1");
+        assert!(response.headers()["Allow"]
+            .to_str()
+            .unwrap()
+            .contains("PROPFIND"));
+    }
+}
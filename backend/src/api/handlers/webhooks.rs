@@ -143,6 +143,7 @@ pub enum WebhookEvent {
     AgeGateQueued,
     AgeGateApproved,
     AgeGateRejected,
+    WatchlistMatched,
 }
 
 impl std::fmt::Display for WebhookEvent {
@@ -160,6 +161,7 @@ impl std::fmt::Display for WebhookEvent {
             WebhookEvent::AgeGateQueued => write!(f, "age_gate_queued"),
             WebhookEvent::AgeGateApproved => write!(f, "age_gate_approved"),
             WebhookEvent::AgeGateRejected => write!(f, "age_gate_rejected"),
+            WebhookEvent::WatchlistMatched => write!(f, "watchlist_matched"),
         }
     }
 }
@@ -0,0 +1,296 @@
+//! Protocol access log for native format endpoints.
+//!
+//! Wraps the format routes and the OCI `/v2` API. Every request is timed and
+//! handed to [`access_log_service::record_access`] with the format and
+//! repository taken from its path. What only the handlers know is noted
+//! through a task-local scope: the upload helpers call [`note_package`],
+//! download recording calls [`note_artifact`], and the visibility middleware
+//! calls [`note_user`] once it has resolved the caller.
+//!
+//! [`access_log_service::record_access`]: crate::services::access_log_service::record_access
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::{
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+use super::request_client::current_request_client;
+use super::tracing::current_correlation_id;
+use crate::api::SharedState;
+use crate::services::access_log_service::{record_access, AccessRecord};
+
+/// Longest User-Agent kept in the log.
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// What handlers learned about the request while serving it.
+#[derive(Debug, Default)]
+pub struct AccessNotes {
+    inner: Mutex<NotedAccess>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct NotedAccess {
+    package: Option<String>,
+    version: Option<String>,
+    artifact_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+}
+
+tokio::task_local! {
+    /// Notes of the format request currently being handled. Like the
+    /// correlation ID, a future detached with `tokio::spawn` does not inherit
+    /// the value, so notes from detached work are dropped.
+    static CURRENT_ACCESS: Arc<AccessNotes>;
+}
+
+fn with_notes(f: impl FnOnce(&mut NotedAccess)) {
+    let _ = CURRENT_ACCESS.try_with(|notes| {
+        if let Ok(mut noted) = notes.inner.lock() {
+            f(&mut noted);
+        }
+    });
+}
+
+/// Record the package (and version) the in-flight request is about.
+pub fn note_package(name: &str, version: Option<&str>) {
+    with_notes(|noted| {
+        noted.package = Some(name.to_string());
+        noted.version = version.map(str::to_string);
+    });
+}
+
+/// Record the artifact served to the in-flight request. Its name and version
+/// fill in the package when no handler noted one.
+pub fn note_artifact(artifact_id: Uuid) {
+    with_notes(|noted| noted.artifact_id = Some(artifact_id));
+}
+
+/// Record the authenticated caller of the in-flight request.
+pub fn note_user(user_id: Uuid) {
+    with_notes(|noted| noted.user_id = Some(user_id));
+}
+
+/// Format, repository and, where the path names them, package and version of
+/// a format request.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct AccessTarget {
+    pub format: String,
+    pub repository_key: Option<String>,
+    pub package: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Mount prefixes named differently from the format they serve.
+const MOUNT_FORMATS: &[(&str, &str)] = &[
+    ("general", "generic"),
+    ("gems", "rubygems"),
+    ("lfs", "gitlfs"),
+    ("ivy", "sbt"),
+    ("proto", "protobuf"),
+    ("v2", "oci"),
+];
+
+/// Resolve the format and repository of a request path. Mirrors the mounts
+/// in `routes.rs`: `/{mount}/{repo_key}/...`, `/conda/t/{token}/{repo_key}`,
+/// `/ext/{format_key}/{repo_key}` and `/v2/{repo_key}/{image}/...`.
+pub(crate) fn access_target(path: &str) -> Option<AccessTarget> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    let mount = segments.next().filter(|s| !s.is_empty())?;
+    let format = MOUNT_FORMATS
+        .iter()
+        .find(|(m, _)| *m == mount)
+        .map_or(mount, |(_, f)| *f);
+
+    let mut target = AccessTarget {
+        format: format.to_string(),
+        ..Default::default()
+    };
+    let mut segments = segments.filter(|s| !s.is_empty()).peekable();
+    match mount {
+        "conda" if segments.peek() == Some(&"t") => {
+            segments.next();
+            segments.next();
+        }
+        "ext" => target.format = segments.next()?.to_string(),
+        "v2" => {
+            let rest: Vec<&str> = segments.collect();
+            target.repository_key = rest.first().map(|s| s.to_string());
+            if let Some(i) = rest
+                .iter()
+                .rposition(|s| matches!(*s, "manifests" | "blobs" | "tags" | "referrers"))
+            {
+                if i > 1 {
+                    target.package = Some(rest[1..i].join("/"));
+                }
+                if rest[i] == "manifests" {
+                    target.version = rest.get(i + 1).map(|s| s.to_string());
+                }
+            }
+            return Some(target);
+        }
+        _ => {}
+    }
+    target.repository_key = segments.next().map(str::to_string);
+    Some(target)
+}
+
+/// The path as logged: credentials carried in the URL are masked.
+pub(crate) fn redact_path(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').collect();
+    let secret_at = match segments.as_slice() {
+        // /conda/t/<token>/...
+        ["", "conda", "t", _, ..] => Some(3),
+        // /npm/<repo>/-/user/token/<token>
+        ["", "npm", _, "-", "user", "token", _] => Some(6),
+        _ => None,
+    };
+    match secret_at {
+        Some(i) => segments
+            .iter()
+            .enumerate()
+            .map(|(n, s)| if n == i { "***" } else { *s })
+            .collect::<Vec<_>>()
+            .join("/"),
+        None => path.to_string(),
+    }
+}
+
+pub async fn access_log_middleware(
+    State(state): State<SharedState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if state.config.access_log_retention_days == 0 {
+        return next.run(request).await;
+    }
+    let path = request.uri().path().to_string();
+    let Some(target) = access_target(&path) else {
+        return next.run(request).await;
+    };
+
+    let started = Instant::now();
+    let occurred_at = chrono::Utc::now();
+    let method = request.method().to_string();
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect::<String>());
+
+    let notes = Arc::new(AccessNotes::default());
+    let response = CURRENT_ACCESS.scope(notes.clone(), next.run(request)).await;
+
+    let noted = notes
+        .inner
+        .lock()
+        .map(|noted| NotedAccess::clone(&noted))
+        .unwrap_or_default();
+    let (package, version) = match noted.package {
+        Some(package) => (Some(package), noted.version),
+        None => (target.package, target.version),
+    };
+    record_access(AccessRecord {
+        occurred_at,
+        format: target.format,
+        repository_key: target.repository_key,
+        method,
+        path: redact_path(&path),
+        package,
+        version,
+        artifact_id: noted.artifact_id,
+        user_agent,
+        user_id: noted.user_id,
+        client_ip: current_request_client().and_then(|c| c.ip.map(|ip| ip.to_string())),
+        status: response.status().as_u16(),
+        duration_ms: u32::try_from(started.elapsed().as_millis()).unwrap_or(u32::MAX),
+        correlation_id: current_correlation_id().map(|id| id.into_string()),
+    });
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(path: &str) -> AccessTarget {
+        access_target(path).unwrap()
+    }
+
+    #[test]
+    fn test_access_target_format_routes() {
+        let t = target("/npm/npm-public/lodash");
+        assert_eq!(t.format, "npm");
+        assert_eq!(t.repository_key.as_deref(), Some("npm-public"));
+
+        let t = target("/gems/gems-local/info/rack");
+        assert_eq!(t.format, "rubygems");
+        assert_eq!(t.repository_key.as_deref(), Some("gems-local"));
+
+        let t = target("/conda/t/secret-token/conda-main/noarch/repodata.json");
+        assert_eq!(t.format, "conda");
+        assert_eq!(t.repository_key.as_deref(), Some("conda-main"));
+
+        let t = target("/ext/rpm/rpm-local/repodata/repomd.xml");
+        assert_eq!(t.format, "rpm");
+        assert_eq!(t.repository_key.as_deref(), Some("rpm-local"));
+
+        assert_eq!(access_target("/"), None);
+    }
+
+    #[test]
+    fn test_access_target_oci() {
+        let t = target("/v2/docker-local/team/app/manifests/1.2.3");
+        assert_eq!(t.format, "oci");
+        assert_eq!(t.repository_key.as_deref(), Some("docker-local"));
+        assert_eq!(t.package.as_deref(), Some("team/app"));
+        assert_eq!(t.version.as_deref(), Some("1.2.3"));
+
+        let t = target("/v2/docker-local/app/blobs/sha256:abc");
+        assert_eq!(t.package.as_deref(), Some("app"));
+        assert_eq!(t.version, None);
+
+        let t = target("/v2/");
+        assert_eq!(t.repository_key, None);
+        assert_eq!(t.package, None);
+    }
+
+    #[test]
+    fn test_redact_path() {
+        assert_eq!(
+            redact_path("/conda/t/secret-token/conda-main/noarch/repodata.json"),
+            "/conda/t/***/conda-main/noarch/repodata.json"
+        );
+        assert_eq!(
+            redact_path("/npm/npm-local/-/user/token/npm_abc"),
+            "/npm/npm-local/-/user/token/***"
+        );
+        assert_eq!(
+            redact_path("/npm/npm-local/lodash"),
+            "/npm/npm-local/lodash"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notes_are_scoped_to_the_request() {
+        note_package("outside", None);
+        let notes = Arc::new(AccessNotes::default());
+        let artifact = Uuid::new_v4();
+        CURRENT_ACCESS
+            .scope(notes.clone(), async {
+                note_package("lodash", Some("4.17.21"));
+                note_artifact(artifact);
+            })
+            .await;
+        let noted = notes.inner.lock().unwrap().clone();
+        assert_eq!(noted.package.as_deref(), Some("lodash"));
+        assert_eq!(noted.version.as_deref(), Some("4.17.21"));
+        assert_eq!(noted.artifact_id, Some(artifact));
+        assert_eq!(noted.user_id, None);
+    }
+}
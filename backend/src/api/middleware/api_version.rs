@@ -0,0 +1,433 @@
+//! API versioning: `/api/v2` compatibility and `/api/v1` deprecation headers.
+//!
+//! `/api/v2` serves the same router as `/api/v1`. Breaking payload changes
+//! are declared once in [`BREAKING_CHANGES`] as field renames on specific
+//! routes:
+//!
+//! * [`v1_deprecation_middleware`] marks every affected v1 route with
+//!   `Deprecation` (RFC 9745), `Sunset` (RFC 8594) and a
+//!   `Link: <...>; rel="successor-version"` pointing at the v2 route, so
+//!   clients learn about the change well before it lands.
+//! * [`v2_compat_middleware`] maps between the shapes: JSON request bodies
+//!   sent to v2 are renamed to the names the handlers read, and JSON
+//!   responses are renamed to the v2 names on the way out.
+//!
+//! Handlers keep the v1 shape until the sunset date. At sunset the handler
+//! switches to the v2 names, v1 stops being served for that route and the
+//! entry is removed from the table.
+//!
+//! Guards that match on literal `/api/v1/...` paths use [`v1_path`] so a v2
+//! request gets the same treatment as its v1 counterpart.
+
+use std::borrow::Cow;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{NaiveDate, TimeZone, Utc};
+use serde_json::Value;
+
+use crate::error::AppError;
+
+/// Largest request body the v2 layer buffers to rename fields.
+const MAX_COMPAT_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+
+/// A JSON field whose name differs between v1 and v2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldRename {
+    pub v1: &'static str,
+    pub v2: &'static str,
+}
+
+/// A breaking change introduced by v2.
+#[derive(Debug)]
+pub struct BreakingChange {
+    /// Stable identifier, for changelogs and support.
+    pub id: &'static str,
+    /// `(method, path)` pairs relative to the version root. Path segments
+    /// starting with `:` match any single segment.
+    pub routes: &'static [(&'static str, &'static str)],
+    pub renames: &'static [FieldRename],
+    /// Date (`YYYY-MM-DD`, UTC) the v1 shape was deprecated.
+    pub deprecated_on: &'static str,
+    /// Date (`YYYY-MM-DD`, UTC) after which the v1 shape may be removed.
+    pub sunset_on: &'static str,
+}
+
+pub const BREAKING_CHANGES: &[BreakingChange] = &[BreakingChange {
+    id: "finding-component",
+    routes: &[
+        ("GET", "/security/scans/:id/findings"),
+        ("POST", "/security/findings/:id/acknowledge"),
+        ("DELETE", "/security/findings/:id/acknowledge"),
+    ],
+    renames: &[FieldRename {
+        v1: "affected_component",
+        v2: "component",
+    }],
+    deprecated_on: "2026-10-16",
+    sunset_on: "2027-04-16",
+}];
+
+/// Map a `/api/v2/...` path to its `/api/v1/...` equivalent; other paths
+/// are returned unchanged.
+pub fn v1_path(path: &str) -> Cow<'_, str> {
+    match path.strip_prefix("/api/v2") {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            Cow::Owned(format!("/api/v1{rest}"))
+        }
+        _ => Cow::Borrowed(path),
+    }
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern = pattern.trim_matches('/').split('/');
+    let mut path = path.trim_matches('/').split('/');
+    loop {
+        match (pattern.next(), path.next()) {
+            (None, None) => return true,
+            (Some(p), Some(s)) if p.starts_with(':') && !s.is_empty() => {}
+            (Some(p), Some(s)) if p == s => {}
+            _ => return false,
+        }
+    }
+}
+
+/// Breaking changes that apply to `method path` (path relative to the
+/// version root).
+pub fn changes_for(method: &Method, path: &str) -> Vec<&'static BreakingChange> {
+    BREAKING_CHANGES
+        .iter()
+        .filter(|change| {
+            change
+                .routes
+                .iter()
+                .any(|(m, p)| *m == method.as_str() && path_matches(p, path))
+        })
+        .collect()
+}
+
+fn date_start(date: &str) -> Option<chrono::DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
+
+/// Add `Deprecation`, `Sunset` and successor `Link` headers for `changes`.
+/// With several changes the earliest deprecation and sunset win.
+fn apply_deprecation_headers(
+    headers: &mut HeaderMap,
+    changes: &[&BreakingChange],
+    successor: &str,
+) {
+    let deprecated = changes
+        .iter()
+        .filter_map(|c| date_start(c.deprecated_on))
+        .min();
+    let sunset = changes.iter().filter_map(|c| date_start(c.sunset_on)).min();
+
+    if let Some(deprecated) = deprecated {
+        if let Ok(v) = HeaderValue::from_str(&format!("@{}", deprecated.timestamp())) {
+            headers.insert("deprecation", v);
+        }
+    }
+    if let Some(sunset) = sunset {
+        let http_date = sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        if let Ok(v) = HeaderValue::from_str(&http_date) {
+            headers.insert("sunset", v);
+        }
+    }
+    if let Ok(v) = HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\"")) {
+        headers.append(header::LINK, v);
+    }
+}
+
+/// Rename object keys throughout `value`. `to_v2` selects the direction.
+/// A key is left alone when the target name is already present.
+pub fn rename_fields(value: &mut Value, renames: &[FieldRename], to_v2: bool) {
+    match value {
+        Value::Object(map) => {
+            for rename in renames {
+                let (from, to) = if to_v2 {
+                    (rename.v1, rename.v2)
+                } else {
+                    (rename.v2, rename.v1)
+                };
+                if !map.contains_key(to) {
+                    if let Some(v) = map.remove(from) {
+                        map.insert(to.to_string(), v);
+                    }
+                }
+            }
+            for v in map.values_mut() {
+                rename_fields(v, renames, to_v2);
+            }
+        }
+        Value::Array(items) => {
+            for v in items {
+                rename_fields(v, renames, to_v2);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| {
+            let mime = v.split(';').next().unwrap_or("").trim();
+            mime.eq_ignore_ascii_case("application/json") || mime.ends_with("+json")
+        })
+        .unwrap_or(false)
+}
+
+/// Layer for the `/api/v1` router: flags routes that have a v2 shape.
+pub async fn v1_deprecation_middleware(request: Request, next: Next) -> Response {
+    let changes = changes_for(request.method(), request.uri().path());
+    if changes.is_empty() {
+        return next.run(request).await;
+    }
+    let successor = format!(
+        "/api/v2{}",
+        request
+            .uri()
+            .path_and_query()
+            .map(|pq| pq.as_str())
+            .unwrap_or_else(|| request.uri().path())
+    );
+
+    let mut response = next.run(request).await;
+    apply_deprecation_headers(response.headers_mut(), &changes, &successor);
+    response
+}
+
+/// Layer for the `/api/v2` router: maps request and response bodies between
+/// the v2 shape and the shape the handlers speak.
+pub async fn v2_compat_middleware(request: Request, next: Next) -> Response {
+    let renames: Vec<FieldRename> = changes_for(request.method(), request.uri().path())
+        .iter()
+        .flat_map(|c| c.renames.iter().copied())
+        .collect();
+    if renames.is_empty() {
+        return next.run(request).await;
+    }
+
+    let request = if is_json(request.headers()) {
+        let (mut parts, body) = request.into_parts();
+        let bytes = match to_bytes(body, MAX_COMPAT_REQUEST_BYTES).await {
+            Ok(b) => b,
+            Err(_) => {
+                return AppError::Validation("Request body too large".to_string()).into_response()
+            }
+        };
+        let body = match serde_json::from_slice::<Value>(&bytes) {
+            Ok(mut value) => {
+                rename_fields(&mut value, &renames, false);
+                parts.headers.remove(header::CONTENT_LENGTH);
+                Body::from(serde_json::to_vec(&value).unwrap_or_else(|_| bytes.to_vec()))
+            }
+            // Let the handler's extractor report the malformed body.
+            Err(_) => Body::from(bytes),
+        };
+        Request::from_parts(parts, body)
+    } else {
+        request
+    };
+
+    let response = next.run(request).await;
+    if !is_json(response.headers()) {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(b) => b,
+        Err(e) => {
+            return AppError::Internal(format!("Failed to read response body: {e}")).into_response()
+        }
+    };
+    let body = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(mut value) => {
+            rename_fields(&mut value, &renames, true);
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(serde_json::to_vec(&value).unwrap_or_else(|_| bytes.to_vec()))
+        }
+        Err(_) => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        middleware,
+        routing::{get, post},
+        Json, Router,
+    };
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let api = Router::new()
+            .route(
+                "/security/scans/:id/findings",
+                get(|| async {
+                    Json(json!({"items": [{"affected_component": "openssl"}], "total": 1}))
+                }),
+            )
+            .route(
+                "/security/findings/:id/acknowledge",
+                post(|Json(body): Json<Value>| async move {
+                    Json(json!({"handler_saw_v1_name": body.get("affected_component").is_some()}))
+                }),
+            )
+            .route(
+                "/other",
+                get(|| async { Json(json!({"affected_component": "x"})) }),
+            );
+        Router::new()
+            .nest(
+                "/api/v1",
+                api.clone()
+                    .layer(middleware::from_fn(v1_deprecation_middleware)),
+            )
+            .nest(
+                "/api/v2",
+                api.layer(middleware::from_fn(v2_compat_middleware)),
+            )
+    }
+
+    async fn send(method: Method, uri: &str, body: Option<Value>) -> (HeaderMap, Value) {
+        let mut req = Request::builder().method(method).uri(uri);
+        let body = match body {
+            Some(b) => {
+                req = req.header(header::CONTENT_TYPE, "application/json");
+                Body::from(serde_json::to_vec(&b).unwrap())
+            }
+            None => Body::empty(),
+        };
+        let resp = app().oneshot(req.body(body).unwrap()).await.unwrap();
+        let headers = resp.headers().clone();
+        let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        (headers, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[test]
+    fn test_breaking_change_dates_parse() {
+        for change in BREAKING_CHANGES {
+            let deprecated = date_start(change.deprecated_on).expect(change.id);
+            let sunset = date_start(change.sunset_on).expect(change.id);
+            assert!(deprecated < sunset, "{}", change.id);
+        }
+    }
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches(
+            "/security/scans/:id/findings",
+            "/security/scans/abc/findings"
+        ));
+        assert!(path_matches(
+            "/security/scans/:id/findings",
+            "/security/scans/abc/findings/"
+        ));
+        assert!(!path_matches(
+            "/security/scans/:id/findings",
+            "/security/scans//findings"
+        ));
+        assert!(!path_matches(
+            "/security/scans/:id/findings",
+            "/security/scans/abc"
+        ));
+        assert!(!path_matches(
+            "/security/scans/:id",
+            "/security/scans/abc/findings"
+        ));
+    }
+
+    #[test]
+    fn test_v1_path() {
+        assert_eq!(v1_path("/api/v2/auth/login"), "/api/v1/auth/login");
+        assert_eq!(v1_path("/api/v2"), "/api/v1");
+        assert_eq!(v1_path("/api/v1/auth/login"), "/api/v1/auth/login");
+        assert_eq!(v1_path("/api/v2x/auth"), "/api/v2x/auth");
+        assert_eq!(v1_path("/v2/library/alpine"), "/v2/library/alpine");
+    }
+
+    #[test]
+    fn test_rename_fields_nested_and_reversible() {
+        let renames = BREAKING_CHANGES[0].renames;
+        let mut value = json!({"items": [{"affected_component": "a"}], "affected_component": "b"});
+        rename_fields(&mut value, renames, true);
+        assert_eq!(
+            value,
+            json!({"items": [{"component": "a"}], "component": "b"})
+        );
+        rename_fields(&mut value, renames, false);
+        assert_eq!(
+            value,
+            json!({"items": [{"affected_component": "a"}], "affected_component": "b"})
+        );
+    }
+
+    #[test]
+    fn test_rename_fields_keeps_existing_target() {
+        let mut value = json!({"affected_component": "old", "component": "new"});
+        rename_fields(&mut value, BREAKING_CHANGES[0].renames, true);
+        assert_eq!(
+            value,
+            json!({"affected_component": "old", "component": "new"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_v1_route_carries_deprecation_headers() {
+        let (headers, body) = send(
+            Method::GET,
+            "/api/v1/security/scans/s1/findings?page=2",
+            None,
+        )
+        .await;
+        assert_eq!(body["items"][0]["affected_component"], "openssl");
+        assert!(headers["deprecation"].to_str().unwrap().starts_with('@'));
+        assert!(headers["sunset"].to_str().unwrap().ends_with(" GMT"));
+        assert_eq!(
+            headers[header::LINK],
+            "</api/v2/security/scans/s1/findings?page=2>; rel=\"successor-version\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unaffected_routes_are_untouched() {
+        let (headers, body) = send(Method::GET, "/api/v1/other", None).await;
+        assert!(headers.get("deprecation").is_none());
+        assert_eq!(body["affected_component"], "x");
+        let (_, body) = send(Method::GET, "/api/v2/other", None).await;
+        assert_eq!(body["affected_component"], "x");
+    }
+
+    #[tokio::test]
+    async fn test_v2_route_uses_new_shape() {
+        let (headers, body) = send(Method::GET, "/api/v2/security/scans/s1/findings", None).await;
+        assert!(headers.get("deprecation").is_none());
+        assert_eq!(body["items"][0]["component"], "openssl");
+        assert!(body["items"][0].get("affected_component").is_none());
+        assert_eq!(body["total"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_v2_request_body_is_mapped_for_handlers() {
+        let (_, body) = send(
+            Method::POST,
+            "/api/v2/security/findings/f1/acknowledge",
+            Some(json!({"component": "zlib"})),
+        )
+        .await;
+        assert_eq!(body["handler_saw_v1_name"], true);
+    }
+}
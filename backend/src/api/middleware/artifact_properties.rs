@@ -0,0 +1,122 @@
+//! Custom artifact properties supplied with an upload.
+//!
+//! Clients attach properties to any upload (native format clients included)
+//! with an `X-Artifact-Properties` header holding a flat JSON object, e.g.
+//! `{"jira_ticket": "REL-1042", "team": "payments"}`. The middleware parses the
+//! header once and scopes the result around the request future, so the shared
+//! upload chokepoints can validate it against the repository's metadata schema
+//! and store it on the new artifact without every format handler threading it
+//! through.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use axum::{extract::Request, middleware::Next, response::IntoResponse, response::Response};
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+
+/// Request header carrying upload properties.
+pub const ARTIFACT_PROPERTIES_HEADER: &str = "x-artifact-properties";
+
+/// Largest accepted header value.
+const MAX_PROPERTIES_BYTES: usize = 16 * 1024;
+
+/// Properties attached to one upload.
+pub type UploadProperties = Arc<Map<String, Value>>;
+
+tokio::task_local! {
+    /// Properties of the request currently being handled. Like the
+    /// correlation ID, a future detached with `tokio::spawn` does not inherit
+    /// the value.
+    static CURRENT_PROPERTIES: UploadProperties;
+}
+
+/// Properties sent with the in-flight request, `None` when the request had no
+/// `X-Artifact-Properties` header or outside a request.
+pub fn current_upload_properties() -> Option<UploadProperties> {
+    CURRENT_PROPERTIES.try_with(Arc::clone).ok()
+}
+
+/// Runs `fut` with [`current_upload_properties`] resolving to `properties`.
+/// Used by paths that carry properties some other way (e.g. peer replication
+/// sessions) before handing off to the shared upload code.
+pub async fn with_upload_properties<F: Future>(properties: UploadProperties, fut: F) -> F::Output {
+    CURRENT_PROPERTIES.scope(properties, fut).await
+}
+
+/// Parse an `X-Artifact-Properties` value. Values must be scalars; nested
+/// objects and arrays are rejected so properties stay searchable key/value
+/// pairs.
+pub fn parse_properties(raw: &str) -> Result<Map<String, Value>, String> {
+    if raw.len() > MAX_PROPERTIES_BYTES {
+        return Err(format!(
+            "X-Artifact-Properties exceeds {} bytes",
+            MAX_PROPERTIES_BYTES
+        ));
+    }
+    let value: Value = serde_json::from_str(raw)
+        .map_err(|e| format!("X-Artifact-Properties is not valid JSON: {}", e))?;
+    let Value::Object(map) = value else {
+        return Err("X-Artifact-Properties must be a JSON object".to_string());
+    };
+    check_flat_properties(&map).map_err(|e| format!("X-Artifact-Properties {}", e))?;
+    Ok(map)
+}
+
+/// Check that `map` is a flat key/value set: non-empty keys and scalar
+/// values. The error reads as a predicate of the caller's subject, e.g.
+/// "value for 'team' must be ...".
+pub fn check_flat_properties(map: &Map<String, Value>) -> Result<(), String> {
+    if let Some((key, _)) = map.iter().find(|(_, v)| v.is_object() || v.is_array()) {
+        return Err(format!(
+            "value for '{}' must be a string, number, boolean or null",
+            key
+        ));
+    }
+    if map.keys().any(|k| k.trim().is_empty()) {
+        return Err("keys must not be empty".to_string());
+    }
+    Ok(())
+}
+
+pub async fn artifact_properties_middleware(request: Request, next: Next) -> Response {
+    let Some(header) = request.headers().get(ARTIFACT_PROPERTIES_HEADER) else {
+        return next.run(request).await;
+    };
+    let parsed = header
+        .to_str()
+        .map_err(|_| "X-Artifact-Properties must be valid UTF-8".to_string())
+        .and_then(parse_properties);
+    match parsed {
+        Ok(properties) => with_upload_properties(Arc::new(properties), next.run(request)).await,
+        Err(message) => AppError::Validation(message).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_properties() {
+        let map = parse_properties(r#"{"jira_ticket": "REL-1", "build": 42, "rc": true}"#)
+            .expect("flat object parses");
+        assert_eq!(map["jira_ticket"], "REL-1");
+        assert_eq!(map["build"], 42);
+
+        assert!(parse_properties("not json").is_err());
+        assert!(parse_properties(r#"["a"]"#).is_err());
+        assert!(parse_properties(r#"{"nested": {"a": 1}}"#).is_err());
+        assert!(parse_properties(r#"{" ": "x"}"#).is_err());
+        assert!(parse_properties(&format!(r#"{{"a": "{}"}}"#, "x".repeat(20_000))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_properties_scope() {
+        assert!(current_upload_properties().is_none());
+        let props = Arc::new(parse_properties(r#"{"team": "payments"}"#).unwrap());
+        let seen = with_upload_properties(props, async { current_upload_properties() }).await;
+        assert_eq!(seen.unwrap()["team"], "payments");
+    }
+}
@@ -15,7 +15,7 @@ use std::time::Instant;
 use axum::{
     extract::{OriginalUri, Request, State},
     http::{
-        header::{AUTHORIZATION, COOKIE},
+        header::{AUTHORIZATION, COOKIE, LOCATION},
         HeaderMap, HeaderName, Method, StatusCode,
     },
     middleware::Next,
@@ -29,7 +29,10 @@ use crate::error::AppError;
 use crate::models::access_scope::AccessScope;
 use crate::models::user::User;
 use crate::services::auth_service::{AuthService, Claims};
+use crate::services::external_authz_service::AuthzUser;
+use crate::services::federation_service::{self, FederatedPrincipal};
 use crate::services::permission_service::PermissionService;
+use crate::services::repository_alias_service::{aliased_location, RepositoryAliasService};
 
 /// Custom header name for API key
 static X_API_KEY: HeaderName = HeaderName::from_static("x-api-key");
@@ -98,6 +101,16 @@ impl AuthExtension {
         self.iat_ms
     }
 
+    /// This principal as described to the external authorization hook.
+    pub fn authz_user(&self) -> AuthzUser {
+        AuthzUser {
+            id: self.user_id,
+            username: self.username.clone(),
+            is_admin: self.is_admin,
+            is_service_account: self.is_service_account,
+        }
+    }
+
     /// Check whether this auth context has a required scope.
     ///
     /// The action-scope ceiling is carried by `scopes`, NOT by `is_api_token`
@@ -245,6 +258,24 @@ impl From<User> for AuthExtension {
     }
 }
 
+impl From<FederatedPrincipal> for AuthExtension {
+    fn from(principal: FederatedPrincipal) -> Self {
+        Self {
+            user_id: principal.user_id,
+            username: principal.username,
+            email: principal.email,
+            // A federated peer is never an admin, whatever its local
+            // principal might later be granted.
+            is_admin: false,
+            is_api_token: true,
+            is_service_account: true,
+            scopes: Some(principal.scopes),
+            allowed_repo_ids: principal.allowed_repo_ids,
+            iat_ms: None,
+        }
+    }
+}
+
 /// Require that the request is authenticated, returning a 401 with a
 /// `WWW-Authenticate: Basic` challenge if not.
 ///
@@ -562,6 +593,15 @@ pub async fn auth_middleware(
         // (which only reads the in-memory map) would silently keep accepting
         // pre-change tokens across replicas — that's the architectural gap
         // PR #1190 was supposed to close.
+        // Token signed by a trusted federated instance (see
+        // `federation_service`). The prefix never collides with a JWT or an
+        // API token, so a failure here is final.
+        ExtractedToken::Bearer(token) if federation_service::is_federation_token(token) => {
+            federation_service::authenticate(auth_service.db(), token)
+                .await
+                .map(AuthExtension::from)
+                .ok_or("Invalid or expired token")
+        }
         ExtractedToken::Bearer(token) => {
             match auth_service.validate_access_token_async(token).await {
                 Ok(claims) => Ok(AuthExtension::from(claims)),
@@ -743,7 +783,7 @@ pub async fn auth_middleware(
 /// unit error is what made cargo/twine API-token clients receive a spurious
 /// 401 under a concurrent burst; they retry on 503 but abort on 401.
 #[derive(Debug, PartialEq, Eq)]
-enum TokenAuthError {
+pub(crate) enum TokenAuthError {
     /// The credential failed validation; the caller owes the client a 401.
     Invalid,
     /// The bcrypt-bound auth-concurrency cap is saturated; the caller must
@@ -773,7 +813,7 @@ fn classify_token_validation_err(err: AppError) -> TokenAuthError {
 }
 
 /// Validate an API token and create an AuthExtension with scopes and repo restrictions.
-async fn validate_api_token_with_scopes(
+pub(crate) async fn validate_api_token_with_scopes(
     auth_service: &AuthService,
     token: &str,
 ) -> Result<AuthExtension, TokenAuthError> {
@@ -871,6 +911,12 @@ pub(crate) async fn try_resolve_auth_outcome(
     allow_basic_api_token: bool,
 ) -> AuthOutcome {
     match extracted {
+        ExtractedToken::Bearer(token) if federation_service::is_federation_token(token) => {
+            match federation_service::authenticate(auth_service.db(), token).await {
+                Some(principal) => AuthOutcome::Resolved(AuthExtension::from(principal)),
+                None => AuthOutcome::InvalidCredential,
+            }
+        }
         ExtractedToken::Bearer(token) => {
             // See `auth_middleware` for why this is the async variant. Same
             // rationale: optional-auth routes still need to reject pre-change
@@ -1349,6 +1395,68 @@ pub(crate) fn extract_repo_key(path: &str) -> &str {
     segments.next().unwrap_or("")
 }
 
+/// Whether the request is an npm CouchDB user-API call (`PUT
+/// /npm/{repo}/-/user/org.couchdb.user:{name}` for `npm login`, `DELETE
+/// /npm/{repo}/-/user/token/{token}` for `npm logout`).
+pub(crate) fn is_npm_user_endpoint(method: &Method, path: &str) -> bool {
+    let mut segments = path.trim_start_matches('/').split('/');
+    if segments.next() != Some("npm") || segments.nth(1) != Some("-") {
+        return false;
+    }
+    if segments.next() != Some("user") {
+        return false;
+    }
+    match (method, segments.next(), segments.next(), segments.next()) {
+        (&Method::PUT, Some(user), None, _) => user.starts_with("org.couchdb.user:"),
+        (&Method::DELETE, Some("token"), Some(token), None) => !token.is_empty(),
+        _ => false,
+    }
+}
+
+/// `308 Permanent Redirect` from a renamed repository's old key to its new
+/// key, preserving the method and body so uploads follow it too. `None` when
+/// `repo_key` is not an active alias or the caller may not learn the target.
+async fn renamed_repo_redirect(
+    db: &sqlx::PgPool,
+    request: &Request,
+    repo_key: &str,
+    authenticated: bool,
+) -> Option<Response> {
+    let target = RepositoryAliasService::new(db.clone())
+        .resolve(repo_key)
+        .await
+        .ok()
+        .flatten()?;
+    if !target.is_public && !authenticated {
+        return None;
+    }
+    let path = request.uri().path();
+    let key_index = if path.starts_with("/conda/t/") { 4 } else { 2 };
+    let location = aliased_location(
+        path,
+        request.uri().query(),
+        key_index,
+        repo_key,
+        &target.key,
+    )?;
+    Some((StatusCode::PERMANENT_REDIRECT, [(LOCATION, location)]).into_response())
+}
+
+/// The part of a format handler request path after the repository key,
+/// e.g. `/pypi/my-repo/simple/foo/` -> `"simple/foo/"`.
+///
+/// Skips the conda `t/<TOKEN>` pair the same way [`extract_repo_key`] does,
+/// so the result never carries a path-embedded credential.
+pub(crate) fn extract_repo_relative_path(path: &str) -> &str {
+    let trimmed = path.trim_start_matches('/');
+    let skip = if trimmed.starts_with("conda/t/") {
+        4
+    } else {
+        2
+    };
+    trimmed.splitn(skip + 1, '/').nth(skip).unwrap_or("")
+}
+
 /// Extract the credential from a conda token-channel URL path.
 ///
 /// Conda clients embed the token directly in the path as
@@ -1466,7 +1574,7 @@ fn is_write_method(method: &Method) -> bool {
     matches!(
         *method,
         Method::POST | Method::PUT | Method::PATCH | Method::DELETE
-    )
+    ) || method.as_str() == "MKCOL"
 }
 
 /// Build a 401 response with `WWW-Authenticate` challenges for both Basic
@@ -1554,6 +1662,8 @@ pub(crate) fn action_for_method(method: &Method) -> &'static str {
         Method::GET | Method::HEAD | Method::OPTIONS => "read",
         Method::PUT | Method::POST | Method::PATCH => "write",
         Method::DELETE => "delete",
+        // WebDAV folder creation.
+        _ if method.as_str() == "MKCOL" => "write",
         _ => "read",
     }
 }
@@ -1606,6 +1716,13 @@ pub async fn repo_visibility_middleware(
         return next.run(request).await;
     }
 
+    // `npm login` / `npm logout` carry the credential being exchanged in the
+    // request itself; the handlers authenticate it.
+    if is_npm_user_endpoint(request.method(), &path) {
+        request.extensions_mut().insert(None::<AuthExtension>);
+        return next.run(request).await;
+    }
+
     // Check the shared repo cache first to avoid a DB round-trip on every
     // request.  The cache is populated with full repo metadata so that
     // format-handler resolvers (e.g. resolve_cargo_repo) can reuse it
@@ -1707,12 +1824,24 @@ pub async fn repo_visibility_middleware(
         if credential_invalid && auth_ext.is_none() {
             return unauthorized_response();
         }
+        // A renamed repository's old key redirects to the new one. An
+        // anonymous caller is only redirected to a public repository, so the
+        // old key of a private one stays indistinguishable from an unknown
+        // key (#1808).
+        if let Some(response) =
+            renamed_repo_redirect(&vis_state.db, &request, repo_key, auth_ext.is_some()).await
+        {
+            return response;
+        }
         if no_credential {
             return unauthorized_response();
         }
         // Note: `credential_invalid` was captured before the match consumed
         // `outcome`; this mirrors the pattern further down for the repo-hit
         // branch.
+        if let Some(ext) = &auth_ext {
+            super::access_log::note_user(ext.user_id);
+        }
         request.extensions_mut().insert(auth_ext);
         return next.run(request).await;
     };
@@ -1761,6 +1890,9 @@ pub async fn repo_visibility_middleware(
     }
 
     // Insert auth extension for downstream handlers.
+    if let Some(ext) = &auth_ext {
+        super::access_log::note_user(ext.user_id);
+    }
     request.extensions_mut().insert(auth_ext.clone());
     if authed_via_ticket {
         request.extensions_mut().insert(DownloadTicketAuth);
@@ -1895,6 +2027,37 @@ pub async fn repo_visibility_middleware(
         }
     }
 
+    // External authorization hook (AUTHZ_HOOK_URL): a restrictive-only last
+    // word on requests the checks above allowed. The path handed to the hook
+    // is repository-relative, so a conda channel token never leaves the
+    // process. A no-op when no hook is configured.
+    let action = action_for_method(request.method());
+    let relative_path = extract_repo_relative_path(&path);
+    match vis_state
+        .permission_service
+        .authorize_external(
+            auth_ext.as_ref().map(AuthExtension::authz_user),
+            action,
+            repo.id,
+            Some(relative_path),
+        )
+        .await
+    {
+        Ok(true) => {}
+        // Anonymous callers get the challenge so clients retry with
+        // credentials; authenticated ones the ACL denial.
+        Ok(false) if auth_ext.is_none() => return unauthorized_response(),
+        Ok(false) => return forbidden_permission_response(),
+        Err(_) => {
+            return Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .body(axum::body::Body::from(
+                    "authorization hook temporarily unavailable",
+                ))
+                .unwrap();
+        }
+    }
+
     next.run(request).await
 }
 
@@ -2354,6 +2517,24 @@ mod tests {
         assert_eq!(ext.caller_iat_ms(), None);
     }
 
+    #[test]
+    fn test_auth_extension_from_federated_principal_is_scoped_non_admin() {
+        let repo = Uuid::new_v4();
+        let ext = AuthExtension::from(FederatedPrincipal {
+            user_id: Uuid::new_v4(),
+            username: "_ak_federation_0123456789ab".to_string(),
+            email: "federation@artifact-keeper.internal".to_string(),
+            scopes: federation_service::FederationScope::Download.token_scopes(),
+            allowed_repo_ids: AccessScope::Restricted(vec![repo]),
+        });
+        assert!(!ext.is_admin);
+        assert!(ext.is_service_account);
+        assert!(ext.has_scope("read:artifacts"));
+        assert!(!ext.has_scope("write:artifacts"));
+        assert!(ext.can_access_repo(repo));
+        assert!(!ext.can_access_repo(Uuid::new_v4()));
+    }
+
     #[test]
     fn test_auth_extension_from_claims_non_admin() {
         let claims = Claims {
@@ -2952,6 +3133,36 @@ mod tests {
         assert_eq!(extract_repo_key("/npm/my-repo/package"), "my-repo");
     }
 
+    #[test]
+    fn test_is_npm_user_endpoint() {
+        assert!(is_npm_user_endpoint(
+            &Method::PUT,
+            "/npm/my-repo/-/user/org.couchdb.user:alice"
+        ));
+        assert!(is_npm_user_endpoint(
+            &Method::DELETE,
+            "/npm/my-repo/-/user/token/npm_abc123"
+        ));
+        // Other methods, formats and npm endpoints keep the normal gate.
+        assert!(!is_npm_user_endpoint(
+            &Method::GET,
+            "/npm/my-repo/-/user/org.couchdb.user:alice"
+        ));
+        assert!(!is_npm_user_endpoint(
+            &Method::PUT,
+            "/pypi/my-repo/-/user/org.couchdb.user:alice"
+        ));
+        assert!(!is_npm_user_endpoint(
+            &Method::PUT,
+            "/npm/my-repo/-/user/alice"
+        ));
+        assert!(!is_npm_user_endpoint(&Method::PUT, "/npm/my-repo/lodash"));
+        assert!(!is_npm_user_endpoint(
+            &Method::DELETE,
+            "/npm/my-repo/-/user/token/abc/extra"
+        ));
+    }
+
     #[test]
     fn test_extract_repo_key_deep_path() {
         assert_eq!(
@@ -2994,6 +3205,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_repo_relative_path() {
+        assert_eq!(
+            extract_repo_relative_path("/pypi/my-repo/simple/foo/"),
+            "simple/foo/"
+        );
+        assert_eq!(extract_repo_relative_path("/npm/my-repo"), "");
+        // The conda channel token must never appear in the relative path.
+        assert_eq!(
+            extract_repo_relative_path("/conda/t/abc123token/my-channel/noarch/repodata.json"),
+            "noarch/repodata.json"
+        );
+        assert_eq!(
+            extract_repo_relative_path("/conda/my-channel/noarch/repodata.json"),
+            "noarch/repodata.json"
+        );
+    }
+
     #[test]
     fn test_extract_repo_key_conda_non_token_unchanged() {
         // A plain conda channel (no /t/ prefix) is unaffected.
@@ -3155,6 +3384,12 @@ mod tests {
         assert!(!is_write_method(&Method::OPTIONS));
     }
 
+    #[test]
+    fn test_is_write_method_webdav() {
+        assert!(is_write_method(&Method::from_bytes(b"MKCOL").unwrap()));
+        assert!(!is_write_method(&Method::from_bytes(b"PROPFIND").unwrap()));
+    }
+
     // -----------------------------------------------------------------------
     // unauthorized_response / forbidden_repo_response
     // -----------------------------------------------------------------------
@@ -3599,6 +3834,18 @@ mod tests {
         assert_eq!(action_for_method(&Method::TRACE), "read");
     }
 
+    #[test]
+    fn test_action_for_method_webdav() {
+        assert_eq!(
+            action_for_method(&Method::from_bytes(b"MKCOL").unwrap()),
+            "write"
+        );
+        assert_eq!(
+            action_for_method(&Method::from_bytes(b"PROPFIND").unwrap()),
+            "read"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // public_read_satisfies_acl: public-repo read parity (#2329)
     //
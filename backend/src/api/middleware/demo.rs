@@ -11,6 +11,7 @@ use axum::{
 use serde_json::json;
 use std::sync::Arc;
 
+use crate::api::middleware::api_version::v1_path;
 use crate::api::AppState;
 
 /// Auth-related paths that may accept write methods even while the instance is
@@ -63,11 +64,11 @@ pub async fn demo_guard(
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    if !state.config.demo_mode {
+    if !crate::config_reload::demo_mode(&state.config) {
         return next.run(request).await;
     }
 
-    if should_block(request.method(), request.uri().path()) {
+    if should_block(request.method(), &v1_path(request.uri().path())) {
         return (
             StatusCode::FORBIDDEN,
             Json(json!({
@@ -12,6 +12,7 @@
 //! * `/health`, `/healthz`,
 //!   `/ready`, `/readyz`, `/livez`  Kubernetes / load-balancer probes
 //! * `/v2/`, `/v2/*`               OCI Distribution Spec challenge / push
+//! * `/s3`, `/s3/*`                S3 gateway (authenticates with SigV4 itself)
 //!
 //! When `guest_access_enabled` is `true` (the default), the middleware is a
 //! no-op so existing deployments are unaffected.
@@ -50,6 +51,7 @@ use axum::{
 };
 use serde_json::json;
 
+use crate::api::middleware::api_version::v1_path;
 use crate::api::middleware::auth::{
     extract_visibility_token, service_unavailable_response, try_resolve_auth_outcome, AuthOutcome,
 };
@@ -95,6 +97,10 @@ fn is_allowlisted(path: &str) -> bool {
         // fetch a bearer token; downstream OCI handlers continue to enforce
         // their own auth on the actual blob/manifest operations.
         || path.starts_with("/v2/")
+        // The S3 gateway rejects any request without a valid SigV4
+        // signature itself; the guard cannot resolve that credential.
+        || path == "/s3"
+        || path.starts_with("/s3/")
 }
 
 /// 401 response body returned when guest access is disabled.
@@ -157,7 +163,7 @@ pub async fn guest_access_guard(
     }
 
     let path = request.uri().path();
-    if is_allowlisted(path) {
+    if is_allowlisted(&v1_path(path)) {
         return next.run(request).await;
     }
 
@@ -210,6 +216,13 @@ mod tests {
         assert!(is_allowlisted("/api/v1/auth/totp/verify"));
     }
 
+    #[test]
+    fn allowlist_s3_gateway() {
+        assert!(is_allowlisted("/s3"));
+        assert!(is_allowlisted("/s3/generic-local/file.bin"));
+        assert!(!is_allowlisted("/s3x"));
+    }
+
     #[test]
     fn allowlist_setup_namespace() {
         assert!(is_allowlisted("/api/v1/setup"));
@@ -1,11 +1,16 @@
 //! API middleware.
 
+pub mod access_log;
+pub mod api_version;
+pub mod artifact_properties;
 pub mod auth;
 pub mod demo;
 pub mod download_telemetry;
 pub mod guest_access;
 pub mod metrics;
+pub mod public_mirror;
 pub mod rate_limit;
+pub mod request_client;
 pub mod security_headers;
 pub mod setup;
 pub mod tracing;
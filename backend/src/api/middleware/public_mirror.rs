@@ -0,0 +1,508 @@
+//! Public mirror mode for format endpoints.
+//!
+//! Enabled by `PUBLIC_MIRROR_MODE=true`. Layered on the native format routes
+//! inside [`super::auth::repo_visibility_middleware`], so the caller's auth
+//! state is already resolved. For anonymous `GET`/`HEAD` requests it:
+//!
+//! - throttles per client IP with a request budget and a byte budget, both
+//!   separate from the authenticated API limiters, answering `429` with
+//!   `Retry-After` once either is spent;
+//! - rewrites `Cache-Control` so CDNs and downstream caches hold package
+//!   files for a long time (`immutable`) and metadata for a short one.
+//!
+//! Every `GET`/`HEAD` (authenticated or not) is counted per repository into
+//! [`MirrorUsage`]; a background task adds the counts to
+//! `public_mirror_usage` (see [`spawn_usage_flusher`]). Authenticated
+//! requests are never throttled or given shared-cache headers.
+//!
+//! Like [`super::rate_limit::RateLimiter`], the budgets are per replica.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use futures::StreamExt;
+
+use super::auth::extract_repo_key;
+use super::rate_limit::{
+    auth_from_request, extract_client_ip, extract_client_ip_addr, too_many_requests, CidrRange,
+    RateLimiter,
+};
+use crate::config::Config;
+
+/// Per-key byte budget over a fixed window, the bandwidth counterpart of
+/// [`RateLimiter`]. Bytes are charged after they are served, so a client can
+/// overshoot by one response; the next request is then refused until the
+/// window resets.
+#[derive(Debug)]
+pub struct ByteBudget {
+    usage: Mutex<HashMap<String, (u64, Instant)>>,
+    max_bytes: u64,
+    window: Duration,
+}
+
+impl ByteBudget {
+    pub fn new(max_bytes: u64, window_secs: u64) -> Self {
+        Self {
+            usage: Mutex::new(HashMap::new()),
+            max_bytes,
+            window: Duration::from_secs(window_secs),
+        }
+    }
+
+    /// `Err(retry_after_secs)` when `key` has spent its budget.
+    pub fn check(&self, key: &str) -> Result<(), u64> {
+        let now = Instant::now();
+        let usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        match usage.get(key) {
+            Some((used, start))
+                if now.duration_since(*start) < self.window && *used >= self.max_bytes =>
+            {
+                let elapsed = now.duration_since(*start).as_secs();
+                Err(self.window.as_secs().saturating_sub(elapsed).max(1))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn charge(&self, key: &str, bytes: u64) {
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = usage.entry(key.to_string()).or_insert((0, now));
+        if now.duration_since(entry.1) >= self.window {
+            *entry = (0, now);
+        }
+        entry.0 = entry.0.saturating_add(bytes);
+    }
+
+    pub fn cleanup_expired(&self) {
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.retain(|_, (_, start)| now.duration_since(*start) < self.window);
+    }
+}
+
+/// Traffic counters for one repository since the last flush.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepoUsage {
+    pub requests: u64,
+    pub bytes_served: u64,
+    pub anonymous_requests: u64,
+    pub anonymous_bytes_served: u64,
+    pub throttled_requests: u64,
+}
+
+/// In-memory per-repository traffic counters, drained by the flusher.
+#[derive(Debug, Default)]
+pub struct MirrorUsage {
+    repos: Mutex<HashMap<String, RepoUsage>>,
+}
+
+impl MirrorUsage {
+    fn update(&self, repo_key: &str, f: impl FnOnce(&mut RepoUsage)) {
+        let mut repos = self.repos.lock().unwrap_or_else(|e| e.into_inner());
+        f(repos.entry(repo_key.to_string()).or_default());
+    }
+
+    pub fn record_request(&self, repo_key: &str, anonymous: bool) {
+        self.update(repo_key, |u| {
+            u.requests += 1;
+            if anonymous {
+                u.anonymous_requests += 1;
+            }
+        });
+    }
+
+    pub fn record_bytes(&self, repo_key: &str, anonymous: bool, bytes: u64) {
+        self.update(repo_key, |u| {
+            u.bytes_served += bytes;
+            if anonymous {
+                u.anonymous_bytes_served += bytes;
+            }
+        });
+    }
+
+    pub fn record_throttled(&self, repo_key: &str) {
+        self.update(repo_key, |u| u.throttled_requests += 1);
+    }
+
+    /// Take the counters accumulated since the previous drain.
+    pub fn drain(&self) -> Vec<(String, RepoUsage)> {
+        let mut repos = self.repos.lock().unwrap_or_else(|e| e.into_inner());
+        repos.drain().collect()
+    }
+}
+
+/// Shared state for [`public_mirror_middleware`].
+#[derive(Debug, Clone)]
+pub struct PublicMirrorState {
+    /// Anonymous request budget per IP; `None` when disabled (limit 0).
+    pub requests: Option<Arc<RateLimiter>>,
+    /// Anonymous byte budget per IP; `None` when disabled (limit 0).
+    pub bandwidth: Option<Arc<ByteBudget>>,
+    pub usage: Arc<MirrorUsage>,
+    /// Client IPs in these ranges are never throttled (`RATE_LIMIT_TRUSTED_CIDRS`).
+    pub trusted_cidrs: Arc<Vec<CidrRange>>,
+    pub trusted_proxies: Arc<Vec<CidrRange>>,
+    pub metadata_max_age_secs: u64,
+    pub artifact_max_age_secs: u64,
+}
+
+impl PublicMirrorState {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            requests: (config.public_mirror_anon_requests_per_window > 0).then(|| {
+                Arc::new(RateLimiter::new(
+                    config.public_mirror_anon_requests_per_window,
+                    config.public_mirror_window_secs,
+                ))
+            }),
+            bandwidth: (config.public_mirror_anon_bytes_per_window > 0).then(|| {
+                Arc::new(ByteBudget::new(
+                    config.public_mirror_anon_bytes_per_window,
+                    config.public_mirror_bandwidth_window_secs,
+                ))
+            }),
+            usage: Arc::new(MirrorUsage::default()),
+            trusted_cidrs: Arc::new(config.rate_limit_trusted_cidrs.clone()),
+            trusted_proxies: Arc::new(config.rate_limit_trusted_proxy_cidrs.clone()),
+            metadata_max_age_secs: config.public_mirror_metadata_max_age_secs,
+            artifact_max_age_secs: config.public_mirror_artifact_max_age_secs,
+        }
+    }
+}
+
+/// File suffixes of versioned package files, which never change once
+/// published.
+const IMMUTABLE_SUFFIXES: &[&str] = &[
+    ".tgz", ".tar.gz", ".tar.bz2", ".tar.xz", ".tar.zst", ".zip", ".jar", ".war", ".aar", ".pom",
+    ".whl", ".egg", ".crate", ".gem", ".nupkg", ".snupkg", ".deb", ".udeb", ".rpm", ".apk",
+    ".conda", ".vsix", ".phar",
+];
+
+/// Checksum / signature sidecars inherit the mutability of the file they
+/// describe.
+const SIDECAR_SUFFIXES: &[&str] = &[".sha1", ".sha256", ".sha512", ".md5", ".asc", ".sig"];
+
+/// Whether `path` names a versioned package file. Maven snapshots are
+/// republished under the same name, so they are treated as mutable.
+pub fn is_immutable_path(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    if lower.contains("-snapshot") {
+        return false;
+    }
+    let mut name = lower.rsplit('/').next().unwrap_or("");
+    for suffix in SIDECAR_SUFFIXES {
+        if let Some(stripped) = name.strip_suffix(suffix) {
+            name = stripped;
+            break;
+        }
+    }
+    // Alpine's index is a tarball but is rewritten on every publish.
+    if name.starts_with("apkindex") {
+        return false;
+    }
+    if IMMUTABLE_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+        return true;
+    }
+    // Cargo: /cargo/<repo>/api/v1/crates/<name>/<version>/download
+    if name == "download" && lower.contains("/api/v1/crates/") {
+        return true;
+    }
+    // Go module proxy: /@v/<version>.{info,mod} are per-version.
+    lower.contains("/@v/") && (name.ends_with(".mod") || name.ends_with(".info"))
+}
+
+/// `Cache-Control` for an anonymous mirror response, or `None` to leave the
+/// handler's header alone (errors, or a handler that opted out of shared
+/// caching).
+pub fn mirror_cache_control(
+    path: &str,
+    status: StatusCode,
+    existing: Option<&str>,
+    metadata_max_age_secs: u64,
+    artifact_max_age_secs: u64,
+) -> Option<String> {
+    if !(status.is_success() || status == StatusCode::NOT_MODIFIED) {
+        return None;
+    }
+    if let Some(existing) = existing {
+        let existing = existing.to_ascii_lowercase();
+        if ["no-store", "no-cache", "private"]
+            .iter()
+            .any(|d| existing.contains(d))
+        {
+            return None;
+        }
+    }
+    if is_immutable_path(path) {
+        Some(format!(
+            "public, max-age={}, immutable",
+            artifact_max_age_secs
+        ))
+    } else {
+        Some(format!(
+            "public, max-age={m}, stale-while-revalidate={m}",
+            m = metadata_max_age_secs
+        ))
+    }
+}
+
+/// `429` for a spent byte budget. Unlike [`too_many_requests`] there is no
+/// request limit to report.
+fn bandwidth_exceeded(retry_after: u64) -> Response {
+    let mut response = Response::new(Body::from(
+        "Bandwidth limit exceeded. Please try again later.",
+    ));
+    *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+    if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+    response
+}
+
+/// Public mirror middleware; see the module docs.
+pub async fn public_mirror_middleware(
+    State(state): State<PublicMirrorState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    if method != Method::GET && method != Method::HEAD {
+        return next.run(request).await;
+    }
+
+    let path = request.uri().path().to_string();
+    let repo_key = extract_repo_key(&path).to_string();
+    let anonymous = auth_from_request(&request).is_none();
+    let trusted = extract_client_ip_addr(&request, &state.trusted_proxies)
+        .is_some_and(|ip| state.trusted_cidrs.iter().any(|c| c.contains(ip)));
+
+    // Only untrusted anonymous clients are throttled.
+    let client_key =
+        (anonymous && !trusted).then(|| extract_client_ip(&request, &state.trusted_proxies));
+    if let Some(key) = &client_key {
+        if let Some(limiter) = &state.requests {
+            if let Err(retry_after) = limiter.check_rate_limit(key).await {
+                tracing::debug!(key = %key, retry_after, "public mirror request budget exceeded");
+                state.usage.record_throttled(&repo_key);
+                return too_many_requests(retry_after, limiter.max_requests());
+            }
+        }
+        if let Some(budget) = &state.bandwidth {
+            if let Err(retry_after) = budget.check(key) {
+                tracing::debug!(key = %key, retry_after, "public mirror bandwidth budget exceeded");
+                state.usage.record_throttled(&repo_key);
+                return bandwidth_exceeded(retry_after);
+            }
+        }
+    }
+
+    let mut response = next.run(request).await;
+    state.usage.record_request(&repo_key, anonymous);
+
+    if anonymous {
+        let existing = response
+            .headers()
+            .get(header::CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok());
+        if let Some(value) = mirror_cache_control(
+            &path,
+            response.status(),
+            existing,
+            state.metadata_max_age_secs,
+            state.artifact_max_age_secs,
+        ) {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                response.headers_mut().insert(header::CACHE_CONTROL, value);
+            }
+        }
+    }
+
+    if method == Method::HEAD {
+        return response;
+    }
+
+    // Known-length bodies are charged up front; streamed bodies as each
+    // chunk is sent.
+    let budget = client_key.and_then(|key| state.bandwidth.clone().map(|b| (b, key)));
+    if let Some(len) = response.body().size_hint().exact() {
+        state.usage.record_bytes(&repo_key, anonymous, len);
+        if let Some((budget, key)) = &budget {
+            budget.charge(key, len);
+        }
+        return response;
+    }
+
+    let usage = Arc::clone(&state.usage);
+    let (parts, body) = response.into_parts();
+    let stream = body.into_data_stream().inspect(move |chunk| {
+        if let Ok(bytes) = chunk {
+            let len = bytes.len() as u64;
+            usage.record_bytes(&repo_key, anonymous, len);
+            if let Some((budget, key)) = &budget {
+                budget.charge(key, len);
+            }
+        }
+    });
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+/// Periodically add the in-memory counters to `public_mirror_usage` and
+/// evict expired budget entries.
+pub fn spawn_usage_flusher(state: PublicMirrorState, db: sqlx::PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Some(limiter) = &state.requests {
+                limiter.cleanup_expired().await;
+            }
+            if let Some(budget) = &state.bandwidth {
+                budget.cleanup_expired();
+            }
+            let drained = state.usage.drain();
+            if drained.is_empty() {
+                continue;
+            }
+            let service =
+                crate::services::public_mirror_service::PublicMirrorService::new(db.clone());
+            if let Err(e) = service.add_usage(&drained).await {
+                tracing::warn!("Failed to record public mirror usage: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_immutable_path_package_files() {
+        assert!(is_immutable_path("/npm/public/lodash/-/lodash-4.17.21.tgz"));
+        assert!(is_immutable_path(
+            "/maven/central/org/slf4j/slf4j-api/2.0.9/slf4j-api-2.0.9.jar"
+        ));
+        assert!(is_immutable_path(
+            "/maven/central/org/slf4j/slf4j-api/2.0.9/slf4j-api-2.0.9.pom.sha1"
+        ));
+        assert!(is_immutable_path(
+            "/pypi/pypi/packages/requests-2.31.0-py3-none-any.whl"
+        ));
+        assert!(is_immutable_path(
+            "/cargo/crates/api/v1/crates/serde/1.0.0/download"
+        ));
+        assert!(is_immutable_path(
+            "/go/golang/golang.org/x/text/@v/v0.14.0.mod"
+        ));
+    }
+
+    #[test]
+    fn test_is_immutable_path_metadata_and_snapshots() {
+        assert!(!is_immutable_path("/npm/public/lodash"));
+        assert!(!is_immutable_path("/pypi/pypi/simple/requests/"));
+        assert!(!is_immutable_path("/debian/main/dists/stable/Release"));
+        assert!(!is_immutable_path(
+            "/maven/central/org/slf4j/slf4j-api/maven-metadata.xml"
+        ));
+        assert!(!is_immutable_path(
+            "/maven/snap/com/acme/app/1.0-SNAPSHOT/app-1.0-SNAPSHOT.jar"
+        ));
+        assert!(!is_immutable_path("/go/golang/golang.org/x/text/@v/list"));
+        assert!(!is_immutable_path(
+            "/alpine/main/v3.19/main/x86_64/APKINDEX.tar.gz"
+        ));
+    }
+
+    #[test]
+    fn test_mirror_cache_control_values() {
+        assert_eq!(
+            mirror_cache_control("/npm/r/a/-/a-1.0.0.tgz", StatusCode::OK, None, 300, 86400)
+                .as_deref(),
+            Some("public, max-age=86400, immutable")
+        );
+        assert_eq!(
+            mirror_cache_control(
+                "/npm/r/a",
+                StatusCode::OK,
+                Some("public, max-age=60"),
+                300,
+                86400
+            )
+            .as_deref(),
+            Some("public, max-age=300, stale-while-revalidate=300")
+        );
+    }
+
+    #[test]
+    fn test_mirror_cache_control_leaves_errors_and_opt_outs() {
+        assert_eq!(
+            mirror_cache_control("/npm/r/a", StatusCode::NOT_FOUND, None, 300, 86400),
+            None
+        );
+        assert_eq!(
+            mirror_cache_control("/npm/r/a", StatusCode::OK, Some("no-store"), 300, 86400),
+            None
+        );
+        assert_eq!(
+            mirror_cache_control("/npm/r/a", StatusCode::OK, Some("Private"), 300, 86400),
+            None
+        );
+        assert!(
+            mirror_cache_control("/npm/r/a", StatusCode::NOT_MODIFIED, None, 300, 86400).is_some()
+        );
+    }
+
+    #[test]
+    fn test_byte_budget_blocks_after_spending() {
+        let budget = ByteBudget::new(100, 60);
+        assert!(budget.check("ip:1.2.3.4").is_ok());
+        budget.charge("ip:1.2.3.4", 60);
+        assert!(budget.check("ip:1.2.3.4").is_ok());
+        budget.charge("ip:1.2.3.4", 60);
+        let retry = budget.check("ip:1.2.3.4").unwrap_err();
+        assert!((1..=60).contains(&retry));
+        assert!(budget.check("ip:5.6.7.8").is_ok(), "budgets are per key");
+    }
+
+    #[test]
+    fn test_byte_budget_window_resets() {
+        let budget = ByteBudget::new(10, 0);
+        budget.charge("k", 50);
+        assert!(budget.check("k").is_ok(), "zero-length window never blocks");
+        budget.cleanup_expired();
+        assert!(budget.usage.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mirror_usage_counts_and_drains() {
+        let usage = MirrorUsage::default();
+        usage.record_request("npm-public", true);
+        usage.record_request("npm-public", false);
+        usage.record_bytes("npm-public", true, 100);
+        usage.record_bytes("npm-public", false, 50);
+        usage.record_throttled("npm-public");
+
+        let drained = usage.drain();
+        assert_eq!(drained.len(), 1);
+        assert_eq!(
+            drained[0].1,
+            RepoUsage {
+                requests: 2,
+                bytes_served: 150,
+                anonymous_requests: 1,
+                anonymous_bytes_served: 100,
+                throttled_requests: 1,
+            }
+        );
+        assert!(usage.drain().is_empty());
+    }
+}
@@ -4,6 +4,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
@@ -177,7 +178,7 @@ pub fn rate_limiting_active(enabled: bool) -> bool {
 /// Pull an `AuthExtension` out of request extensions, whether it was inserted
 /// directly (required-auth middleware) or as an `Option` (optional-auth
 /// middleware). Shared by every rate-limit middleware.
-fn auth_from_request(request: &Request) -> Option<AuthExtension> {
+pub(crate) fn auth_from_request(request: &Request) -> Option<AuthExtension> {
     request
         .extensions()
         .get::<AuthExtension>()
@@ -257,9 +258,9 @@ pub struct RateLimiter {
     /// Map of key -> (request count, window start time)
     requests: Arc<Mutex<HashMap<String, (u32, Instant)>>>,
     /// Maximum number of requests allowed per window
-    pub(crate) max_requests: u32,
-    /// Duration of the rate limiting window
-    window: Duration,
+    max_requests: AtomicU32,
+    /// Duration of the rate limiting window in seconds
+    window_secs: AtomicU64,
 }
 
 impl RateLimiter {
@@ -271,46 +272,66 @@ impl RateLimiter {
     pub fn new(max_requests: u32, window_secs: u64) -> Self {
         Self {
             requests: Arc::new(Mutex::new(HashMap::new())),
-            max_requests,
-            window: Duration::from_secs(window_secs),
+            max_requests: AtomicU32::new(max_requests),
+            window_secs: AtomicU64::new(window_secs),
         }
     }
 
+    /// Maximum number of requests allowed per window.
+    pub fn max_requests(&self) -> u32 {
+        self.max_requests.load(Ordering::Relaxed)
+    }
+
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs.load(Ordering::Relaxed))
+    }
+
+    /// Change the limits of a live limiter (configuration reload). Counters
+    /// already in flight keep their window start, so a client is judged
+    /// against the new limits from its next request on.
+    pub fn set_limits(&self, max_requests: u32, window_secs: u64) {
+        self.max_requests.store(max_requests, Ordering::Relaxed);
+        self.window_secs.store(window_secs, Ordering::Relaxed);
+    }
+
     /// Check if a request should be rate limited.
     ///
     /// Returns `Ok(remaining)` with the number of remaining requests if allowed,
     /// or `Err(retry_after_secs)` if the rate limit has been exceeded.
     pub async fn check_rate_limit(&self, key: &str) -> Result<u32, u64> {
         let now = Instant::now();
+        let max_requests = self.max_requests();
+        let window = self.window();
         let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
 
         let entry = requests.entry(key.to_string()).or_insert((0, now));
 
         // Check if the window has expired
-        if now.duration_since(entry.1) >= self.window {
+        if now.duration_since(entry.1) >= window {
             // Reset the window
             entry.0 = 1;
             entry.1 = now;
-            return Ok(self.max_requests.saturating_sub(1));
+            return Ok(max_requests.saturating_sub(1));
         }
 
         // Check if we've exceeded the limit
-        if entry.0 >= self.max_requests {
-            let retry_after = self.window.as_secs() - now.duration_since(entry.1).as_secs();
+        if entry.0 >= max_requests {
+            let retry_after = window.as_secs() - now.duration_since(entry.1).as_secs();
             return Err(retry_after.max(1));
         }
 
         // Increment the counter
         entry.0 += 1;
-        Ok(self.max_requests.saturating_sub(entry.0))
+        Ok(max_requests.saturating_sub(entry.0))
     }
 
     /// Clean up expired entries from the rate limiter.
     /// Call this periodically to prevent memory bloat.
     pub async fn cleanup_expired(&self) {
         let now = Instant::now();
+        let window = self.window();
         let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
-        requests.retain(|_, (_, window_start)| now.duration_since(*window_start) < self.window);
+        requests.retain(|_, (_, window_start)| now.duration_since(*window_start) < window);
     }
 }
 
@@ -358,12 +379,12 @@ pub async fn rate_limit_middleware(
     match state.limiter.check_rate_limit(&key).await {
         Ok(remaining) => tag_allowed(
             next.run(request).await,
-            state.limiter.max_requests,
+            state.limiter.max_requests(),
             remaining,
         ),
         Err(retry_after) => {
             tracing::debug!(key = %key, retry_after, "rate limit exceeded");
-            too_many_requests(retry_after, state.limiter.max_requests)
+            too_many_requests(retry_after, state.limiter.max_requests())
         }
     }
 }
@@ -411,12 +432,12 @@ pub async fn rate_limit_by_ip_middleware(
     match state.limiter.check_rate_limit(&key).await {
         Ok(remaining) => tag_allowed(
             next.run(request).await,
-            state.limiter.max_requests,
+            state.limiter.max_requests(),
             remaining,
         ),
         Err(retry_after) => {
             tracing::debug!(key = %key, retry_after, "presign-mint rate limit exceeded");
-            too_many_requests(retry_after, state.limiter.max_requests)
+            too_many_requests(retry_after, state.limiter.max_requests())
         }
     }
 }
@@ -544,24 +565,24 @@ async fn run_login_with_key(
     // Global backstop first: sheds (rather than starves) once total login
     // volume per window exceeds its high ceiling.
     if let Err(retry_after) = state.backstop.check_rate_limit("login:global").await {
-        return too_many_requests(retry_after, state.backstop.max_requests);
+        return too_many_requests(retry_after, state.backstop.max_requests());
     }
 
     match state.inner.limiter.check_rate_limit(key).await {
         Ok(remaining) => tag_allowed(
             next.run(request).await,
-            state.inner.limiter.max_requests,
+            state.inner.limiter.max_requests(),
             remaining,
         ),
         Err(retry_after) => {
             tracing::debug!(key = %key, retry_after, "login rate limit exceeded");
-            too_many_requests(retry_after, state.inner.limiter.max_requests)
+            too_many_requests(retry_after, state.inner.limiter.max_requests())
         }
     }
 }
 
 /// Build a 429 response with `Retry-After` and `X-RateLimit-*` headers.
-fn too_many_requests(retry_after: u64, max_requests: u32) -> Response {
+pub(crate) fn too_many_requests(retry_after: u64, max_requests: u32) -> Response {
     let mut response = (
         StatusCode::TOO_MANY_REQUESTS,
         "Rate limit exceeded. Please try again later.",
@@ -627,7 +648,7 @@ fn peer_is_trusted_proxy(request: &Request, trusted_proxies: &[CidrRange]) -> bo
 /// directly, with no socket peer), `XFF` is used as a last-resort fallback so
 /// keying still distinguishes callers; otherwise all such requests would share
 /// a single `ip:unknown` bucket.
-fn extract_client_ip(request: &Request, trusted_proxies: &[CidrRange]) -> String {
+pub(crate) fn extract_client_ip(request: &Request, trusted_proxies: &[CidrRange]) -> String {
     if let Some(ip) = extract_client_ip_addr(request, trusted_proxies) {
         return format!("ip:{}", ip);
     }
@@ -657,7 +678,10 @@ fn extract_client_ip(request: &Request, trusted_proxies: &[CidrRange]) -> String
 /// returned and `XFF` is ignored. When `ConnectInfo` is absent entirely, fall
 /// back to a parseable `XFF` (direct-test / pre-ConnectInfo topology).
 /// Returns `None` if the address cannot be resolved or does not parse.
-fn extract_client_ip_addr(request: &Request, trusted_proxies: &[CidrRange]) -> Option<IpAddr> {
+pub(crate) fn extract_client_ip_addr(
+    request: &Request,
+    trusted_proxies: &[CidrRange],
+) -> Option<IpAddr> {
     let peer = request
         .extensions()
         .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
@@ -787,6 +811,20 @@ mod tests {
         assert!(limiter.check_rate_limit("k").await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_rate_limiter_set_limits_applies_to_live_counters() {
+        let limiter = RateLimiter::new(1, 60);
+        assert_eq!(limiter.check_rate_limit("k").await, Ok(0));
+        assert!(limiter.check_rate_limit("k").await.is_err());
+
+        // Raising the limit lets the same client through within its window.
+        limiter.set_limits(3, 60);
+        assert_eq!(limiter.max_requests(), 3);
+        assert_eq!(limiter.check_rate_limit("k").await, Ok(1));
+        assert_eq!(limiter.check_rate_limit("k").await, Ok(0));
+        assert!(limiter.check_rate_limit("k").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_rate_limiter_window_reset() {
         // Use a very short window (1 second) to test reset
@@ -0,0 +1,112 @@
+//! The client behind the request currently being handled.
+//!
+//! The middleware resolves the client IP once, with the rate limiter's
+//! trusted-proxy rules ([`resolve_client_ip_addr`]), and scopes it around the
+//! request future. Code far from the HTTP layer, such as API token usage
+//! tracking inside `AuthService`, reads it without threading the request
+//! through. A token validated several times in one request (guest-access
+//! guard, then the route's own auth middleware) is still counted once.
+
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+use super::rate_limit::resolve_client_ip_addr;
+use crate::api::SharedState;
+
+/// Per-request client context.
+#[derive(Debug, Default)]
+pub struct RequestClient {
+    /// Resolved client IP, `None` when unresolvable.
+    pub ip: Option<IpAddr>,
+    tokens_used: Mutex<Vec<Uuid>>,
+}
+
+impl RequestClient {
+    pub fn new(ip: Option<IpAddr>) -> Self {
+        Self {
+            ip,
+            tokens_used: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// True the first time `token_id` is seen during this request.
+    pub fn first_use_of(&self, token_id: Uuid) -> bool {
+        let Ok(mut seen) = self.tokens_used.lock() else {
+            return true;
+        };
+        if seen.contains(&token_id) {
+            false
+        } else {
+            seen.push(token_id);
+            true
+        }
+    }
+}
+
+tokio::task_local! {
+    /// Client of the request currently being handled. Like the correlation
+    /// ID, a future detached with `tokio::spawn` does not inherit the value.
+    static CURRENT_CLIENT: Arc<RequestClient>;
+}
+
+/// The in-flight request's client, `None` outside a request.
+pub fn current_request_client() -> Option<Arc<RequestClient>> {
+    CURRENT_CLIENT.try_with(Arc::clone).ok()
+}
+
+/// Runs `fut` with [`current_request_client`] resolving to `client`.
+pub async fn with_request_client<F: std::future::Future>(
+    client: Arc<RequestClient>,
+    fut: F,
+) -> F::Output {
+    CURRENT_CLIENT.scope(client, fut).await
+}
+
+pub async fn request_client_middleware(
+    State(state): State<SharedState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        .map(|ci| ci.0.ip());
+    let ip = resolve_client_ip_addr(
+        request.headers(),
+        peer,
+        &state.config.rate_limit_trusted_proxy_cidrs,
+    );
+    with_request_client(Arc::new(RequestClient::new(ip)), next.run(request)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_use_of_counts_each_token_once() {
+        let client = RequestClient::new(None);
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(client.first_use_of(a));
+        assert!(!client.first_use_of(a));
+        assert!(client.first_use_of(b));
+    }
+
+    #[tokio::test]
+    async fn test_request_client_scope() {
+        assert!(current_request_client().is_none());
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let seen = with_request_client(Arc::new(RequestClient::new(Some(ip))), async {
+            current_request_client().and_then(|c| c.ip)
+        })
+        .await;
+        assert_eq!(seen, Some(ip));
+    }
+}
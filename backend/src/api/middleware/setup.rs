@@ -12,13 +12,14 @@ use serde_json::json;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+use crate::api::middleware::api_version::v1_path;
 use crate::api::AppState;
 
 /// Middleware that blocks most API requests when setup is required.
 ///
 /// When `state.setup_required` is true, only health/readiness checks,
 /// auth endpoints (login, refresh), the password-change endpoint, and
-/// the setup status endpoint are allowed. Everything else gets a 403
+/// the setup status and bootstrap endpoints are allowed. Everything else gets a 403
 /// with instructions on how to complete setup.
 pub async fn setup_guard(
     State(state): State<Arc<AppState>>,
@@ -29,7 +30,8 @@ pub async fn setup_guard(
         return next.run(request).await;
     }
 
-    let path = request.uri().path();
+    let path = v1_path(request.uri().path());
+    let path = path.as_ref();
 
     let is_allowed = matches!(
         path,
@@ -40,6 +42,7 @@ pub async fn setup_guard(
             | "/livez"
             | "/metrics"
             | "/api/v1/setup/status"
+            | "/api/v1/setup/bootstrap"
     ) || path.starts_with("/api/v1/auth")
         || (path.starts_with("/api/v1/users/") && path.ends_with("/password"));
 
@@ -72,7 +75,8 @@ pub async fn setup_guard(
                 "2. Login: POST /api/v1/auth/login with {\"username\":\"admin\",\"password\":\"<from-file>\"}",
                 "3. Change password: POST /api/v1/users/<id>/password with {\"new_password\":\"<your-password>\"}",
                 "4. The API will unlock automatically after the password is changed.",
-                "If the password file is missing, restart the container. A new password will be generated automatically."
+                "If the password file is missing, restart the container. A new password will be generated automatically.",
+                "If the server runs with SETUP_WIZARD=true, instead POST /api/v1/setup/bootstrap with the bootstrap token printed in the server log."
             ]
         })),
     )
@@ -264,8 +264,9 @@ impl AppState {
     /// every OTHER replica kept blocking the API (and reporting setup mode)
     /// until it was restarted (#2492). This method makes the DB row the
     /// authority: while the local flag is still `true`, consult
-    /// `users.must_change_password` for the admin account and latch the flag
-    /// to `false` once the DB confirms setup completed. The flag never flips
+    /// `users.must_change_password` for the admin account (and, for the
+    /// setup wizard, any outstanding `bootstrap_tokens` row) and latch the
+    /// flag to `false` once the DB confirms setup completed. The flag never flips
     /// back to `true` at runtime, so after the first confirmation no further
     /// queries are issued.
     ///
@@ -278,7 +279,8 @@ impl AppState {
             return false;
         }
         match sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM users WHERE is_admin = true AND must_change_password = true)",
+            "SELECT EXISTS(SELECT 1 FROM users WHERE is_admin = true AND must_change_password = true) \
+             OR EXISTS(SELECT 1 FROM bootstrap_tokens WHERE consumed_at IS NULL)",
         )
         .fetch_one(&self.db)
         .await
@@ -352,6 +354,18 @@ impl AppState {
         self.dependency_track = Some(dt);
     }
 
+    /// Attach the external authorization hook to the permission service.
+    ///
+    /// Replaces `permission_service`, so call it before the state is cloned
+    /// into routers or background tasks.
+    pub fn set_external_authz(
+        &mut self,
+        hook: Arc<crate::services::external_authz_service::ExternalAuthzService>,
+    ) {
+        self.permission_service =
+            Arc::new(PermissionService::new(self.db.clone()).with_external_authz(Some(hook)));
+    }
+
     /// Set the proxy service for remote repository proxying.
     pub fn set_proxy_service(&mut self, proxy_service: Arc<ProxyService>) {
         self.proxy_service = Some(proxy_service);
@@ -385,6 +399,8 @@ impl AppState {
         if let Some(ref qc) = self.quality_check_service {
             svc.set_quality_check_service(qc.clone());
         }
+        svc.set_event_bus(self.event_bus.clone());
+        svc.set_storage_dedup(self.config.storage_dedup_enabled);
         svc
     }
 
@@ -49,7 +49,25 @@ Bearer header. This Basic-with-token fallback applies to format endpoints only,
         (name = "signing", description = "Signing key management"),
         (name = "plugins", description = "WASM plugin lifecycle"),
         (name = "webhooks", description = "Event webhook management"),
+        (name = "chat_integrations", description = "Slack and Microsoft Teams notification integrations"),
+        (name = "issue_trackers", description = "Jira issues for policy-violating scan findings"),
+        (name = "public_mirror", description = "Public mirror mode bandwidth accounting"),
+        (name = "blocklist", description = "Instance block list of checksums and package purls"),
+        (name = "package_protection", description = "Dependency-confusion and typosquatting protection for remote repositories"),
         (name = "peers", description = "Peer replication and sync"),
+        (name = "federation", description = "Instance-to-instance trust and federated authentication"),
+        (name = "airlock", description = "Signed artifact bundles for air-gapped transfer"),
+        (name = "freeze_windows", description = "Release freeze windows blocking uploads and promotions"),
+        (name = "data_subjects", description = "GDPR data-subject erasure with per-table reports"),
+        (name = "load_test", description = "Synthetic load-test data and the benchmark harness"),
+        (name = "authz", description = "External authorization hook (OPA / webhook) and inline Rego policies"),
+        (name = "storage_encryption", description = "At-rest storage encryption status and key migration runs"),
+        (name = "storage_shards", description = "Bucket / container sharding and shard rebalance runs"),
+        (name = "storage_recovery", description = "Listing and restoring objects soft-deleted by the storage backend"),
+        (name = "api_tokens", description = "API token usage and the stale-token report"),
+        (name = "exports", description = "Streaming CSV/JSON Lines exports of findings, policy violations, audit log and artifact inventory"),
+        (name = "badges", description = "Embeddable SVG status badges for packages"),
+        (name = "deploy_gate", description = "Pre-deploy allow/deny verification for deployment systems"),
         (name = "admin", description = "System administration"),
         (name = "analytics", description = "Storage and download analytics"),
         (name = "lifecycle", description = "Retention policies and cleanup"),
@@ -147,10 +165,27 @@ pub(crate) fn module_docs() -> Vec<(&'static str, utoipa::openapi::OpenApi)> {
         ("health", handlers::health::HealthApiDoc::openapi()),
         ("plugins", handlers::plugins::PluginsApiDoc::openapi()),
         ("webhooks", handlers::webhooks::WebhooksApiDoc::openapi()),
+        (
+            "chat_integrations",
+            handlers::chat_integrations::ChatIntegrationsApiDoc::openapi(),
+        ),
         (
             "email_subscriptions",
             handlers::email_subscriptions::EmailSubscriptionsApiDoc::openapi(),
         ),
+        (
+            "issue_trackers",
+            handlers::issue_trackers::IssueTrackersApiDoc::openapi(),
+        ),
+        (
+            "public_mirror",
+            handlers::public_mirror::PublicMirrorApiDoc::openapi(),
+        ),
+        ("blocklist", handlers::blocklist::BlocklistApiDoc::openapi()),
+        (
+            "package_protection",
+            handlers::package_protection::PackageProtectionApiDoc::openapi(),
+        ),
         ("signing", handlers::signing::SigningApiDoc::openapi()),
         ("security", handlers::security::SecurityApiDoc::openapi()),
         ("sbom", handlers::sbom::SbomApiDoc::openapi()),
@@ -171,6 +206,43 @@ pub(crate) fn module_docs() -> Vec<(&'static str, utoipa::openapi::OpenApi)> {
         ),
         ("telemetry", handlers::telemetry::TelemetryApiDoc::openapi()),
         ("peers", handlers::peers::PeersApiDoc::openapi()),
+        ("peer_join", handlers::peer_join::PeerJoinApiDoc::openapi()),
+        (
+            "edge_diagnostics",
+            handlers::edge_diagnostics::EdgeDiagnosticsApiDoc::openapi(),
+        ),
+        ("airlock", handlers::airlock::AirlockApiDoc::openapi()),
+        (
+            "freeze_windows",
+            handlers::freeze_windows::FreezeWindowsApiDoc::openapi(),
+        ),
+        (
+            "data_subjects",
+            handlers::data_subjects::DataSubjectsApiDoc::openapi(),
+        ),
+        ("load_test", handlers::load_test::LoadTestApiDoc::openapi()),
+        ("authz", handlers::authz_hook::AuthzHookApiDoc::openapi()),
+        (
+            "storage_encryption",
+            handlers::storage_encryption::StorageEncryptionApiDoc::openapi(),
+        ),
+        (
+            "storage_shards",
+            handlers::storage_shards::StorageShardsApiDoc::openapi(),
+        ),
+        (
+            "storage_recovery",
+            handlers::storage_recovery::StorageRecoveryApiDoc::openapi(),
+        ),
+        (
+            "api_tokens",
+            handlers::api_token_usage::ApiTokenUsageApiDoc::openapi(),
+        ),
+        ("exports", handlers::exports::ExportsApiDoc::openapi()),
+        (
+            "vulnerability_watchlist",
+            handlers::vulnerability_watchlist::VulnerabilityWatchlistApiDoc::openapi(),
+        ),
         (
             "permissions",
             handlers::permissions::PermissionsApiDoc::openapi(),
@@ -188,6 +260,10 @@ pub(crate) fn module_docs() -> Vec<(&'static str, utoipa::openapi::OpenApi)> {
             "dependency_track",
             handlers::dependency_track::DependencyTrackApiDoc::openapi(),
         ),
+        (
+            "dependency_graph",
+            handlers::dependency_graph::DependencyGraphApiDoc::openapi(),
+        ),
         ("peer", handlers::peer::PeerApiDoc::openapi()),
         ("transfer", handlers::transfer::TransferApiDoc::openapi()),
         ("tree", handlers::tree::TreeApiDoc::openapi()),
@@ -195,6 +271,55 @@ pub(crate) fn module_docs() -> Vec<(&'static str, utoipa::openapi::OpenApi)> {
             "repository_labels",
             handlers::repository_labels::RepositoryLabelsApiDoc::openapi(),
         ),
+        (
+            "repository_metadata_schemas",
+            handlers::repository_metadata_schemas::RepositoryMetadataSchemasApiDoc::openapi(),
+        ),
+        (
+            "repository_replicated_config",
+            handlers::repository_replicated_config::RepositoryReplicatedConfigApiDoc::openapi(),
+        ),
+        ("badges", handlers::badges::BadgesApiDoc::openapi()),
+        (
+            "deploy_gate",
+            handlers::deploy_gate::DeployGateApiDoc::openapi(),
+        ),
+        (
+            "version_deprecations",
+            handlers::version_deprecations::VersionDeprecationsApiDoc::openapi(),
+        ),
+        (
+            "repository_events",
+            handlers::repository_events::RepositoryEventsApiDoc::openapi(),
+        ),
+        (
+            "cache_warming",
+            handlers::cache_warming::CacheWarmingApiDoc::openapi(),
+        ),
+        (
+            "repository_aliases",
+            handlers::repository_aliases::RepositoryAliasesApiDoc::openapi(),
+        ),
+        (
+            "remote_repositories",
+            handlers::remote_repositories::RemoteRepositoriesApiDoc::openapi(),
+        ),
+        (
+            "repository_worm",
+            handlers::repository_worm::RepositoryWormApiDoc::openapi(),
+        ),
+        (
+            "repository_version_rules",
+            handlers::repository_version_rules::RepositoryVersionRulesApiDoc::openapi(),
+        ),
+        (
+            "repository_delete_protection",
+            handlers::repository_delete_protection::RepositoryDeleteProtectionApiDoc::openapi(),
+        ),
+        (
+            "repository_stats",
+            handlers::repository_stats::RepositoryStatsApiDoc::openapi(),
+        ),
         (
             "sync_policies",
             handlers::sync_policies::SyncPoliciesApiDoc::openapi(),
@@ -221,6 +346,14 @@ pub(crate) fn module_docs() -> Vec<(&'static str, utoipa::openapi::OpenApi)> {
             "artifact_labels",
             handlers::artifact_labels::ArtifactLabelsApiDoc::openapi(),
         ),
+        (
+            "artifact_consumers",
+            handlers::artifact_consumers::ArtifactConsumersApiDoc::openapi(),
+        ),
+        (
+            "direct_uploads",
+            handlers::direct_uploads::DirectUploadsApiDoc::openapi(),
+        ),
         ("curation", handlers::curation::CurationApiDoc::openapi()),
         (
             "quarantine",
@@ -235,12 +368,20 @@ pub(crate) fn module_docs() -> Vec<(&'static str, utoipa::openapi::OpenApi)> {
             "repo_tokens",
             handlers::repo_tokens::RepoTokensApiDoc::openapi(),
         ),
+        (
+            "s3_gateway",
+            handlers::s3_gateway::S3GatewayApiDoc::openapi(),
+        ),
         ("smtp", handlers::smtp::SmtpApiDoc::openapi()),
         ("ci_auth", handlers::ci_auth::CiAuthApiDoc::openapi()),
         (
             "ci_auth_admin",
             handlers::ci_auth_admin::CiAuthAdminApiDoc::openapi(),
         ),
+        (
+            "federation",
+            handlers::federation::FederationApiDoc::openapi(),
+        ),
     ]
 }
 
@@ -259,10 +400,54 @@ pub fn build_openapi() -> utoipa::openapi::OpenApi {
     doc
 }
 
+/// Merge the OpenAPI fragments exported by loaded WASM plugins (already
+/// rebased under `/ext/<format_key>`). The merge is first-wins, so a plugin
+/// cannot replace a core schema by reusing its name.
+pub fn merge_plugin_fragments(
+    doc: &mut utoipa::openapi::OpenApi,
+    fragments: &[std::sync::Arc<utoipa::openapi::OpenApi>],
+) {
+    for fragment in fragments {
+        doc.merge(fragment.as_ref().clone());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_merge_plugin_fragments_keeps_core_schemas() {
+        let mut spec = build_openapi();
+        let core_paths = spec.paths.paths.len();
+        let fragment: utoipa::openapi::OpenApi = serde_json::from_str(
+            r#"{
+                "openapi": "3.1.0",
+                "info": {"title": "rpm", "version": "1.0.0"},
+                "paths": {
+                    "/ext/rpm/{repo_key}/repodata/repomd.xml": {
+                        "get": {"responses": {"200": {"description": "ok"}}}
+                    }
+                },
+                "components": {"schemas": {"ErrorResponse": {"type": "integer"}}}
+            }"#,
+        )
+        .unwrap();
+        let core_error =
+            serde_json::to_value(&spec.components.as_ref().unwrap().schemas["ErrorResponse"])
+                .unwrap();
+
+        merge_plugin_fragments(&mut spec, &[std::sync::Arc::new(fragment)]);
+
+        assert_eq!(spec.paths.paths.len(), core_paths + 1);
+        assert!(spec
+            .paths
+            .paths
+            .contains_key("/ext/rpm/{repo_key}/repodata/repomd.xml"));
+        let merged = &spec.components.as_ref().unwrap().schemas["ErrorResponse"];
+        assert_eq!(serde_json::to_value(merged).unwrap(), core_error);
+    }
+
     #[test]
     fn test_openapi_spec_is_valid() {
         let spec = build_openapi();
@@ -652,6 +837,70 @@ mod tests {
                 "/api/v1/admin/sso/",
                 vec![include_str!("handlers/sso_admin.rs")],
             ),
+            (
+                "/api/v1/admin/issue-trackers/",
+                vec![include_str!("handlers/issue_trackers.rs")],
+            ),
+            (
+                "/api/v1/admin/public-mirror/",
+                vec![include_str!("handlers/public_mirror.rs")],
+            ),
+            (
+                "/api/v1/admin/blocklist/",
+                vec![include_str!("handlers/blocklist.rs")],
+            ),
+            (
+                "/api/v1/admin/package-protection/",
+                vec![include_str!("handlers/package_protection.rs")],
+            ),
+            (
+                "/api/v1/admin/edge-nodes/",
+                vec![include_str!("handlers/edge_diagnostics.rs")],
+            ),
+            (
+                "/api/v1/admin/airlock/",
+                vec![include_str!("handlers/airlock.rs")],
+            ),
+            (
+                "/api/v1/admin/freeze-windows/",
+                vec![include_str!("handlers/freeze_windows.rs")],
+            ),
+            (
+                "/api/v1/admin/data-subjects/",
+                vec![include_str!("handlers/data_subjects.rs")],
+            ),
+            (
+                "/api/v1/admin/load-test/",
+                vec![include_str!("handlers/load_test.rs")],
+            ),
+            (
+                "/api/v1/admin/authz/",
+                vec![include_str!("handlers/authz_hook.rs")],
+            ),
+            (
+                "/api/v1/admin/storage-encryption/",
+                vec![include_str!("handlers/storage_encryption.rs")],
+            ),
+            (
+                "/api/v1/admin/storage-shards/",
+                vec![include_str!("handlers/storage_shards.rs")],
+            ),
+            (
+                "/api/v1/admin/storage-recovery/",
+                vec![include_str!("handlers/storage_recovery.rs")],
+            ),
+            (
+                "/api/v1/admin/api-tokens/",
+                vec![include_str!("handlers/api_token_usage.rs")],
+            ),
+            (
+                "/api/v1/admin/exports/",
+                vec![include_str!("handlers/exports.rs")],
+            ),
+            (
+                "/api/v1/admin/vulnerability-watchlist/",
+                vec![include_str!("handlers/vulnerability_watchlist.rs")],
+            ),
             // --- Nested auth sub-modules ---
             (
                 "/api/v1/auth/ci/",
@@ -672,6 +921,17 @@ mod tests {
                 vec![
                     include_str!("handlers/repositories.rs"),
                     include_str!("handlers/repository_labels.rs"),
+                    include_str!("handlers/repository_metadata_schemas.rs"),
+                    include_str!("handlers/repository_replicated_config.rs"),
+                    include_str!("handlers/version_deprecations.rs"),
+                    include_str!("handlers/repository_events.rs"),
+                    include_str!("handlers/cache_warming.rs"),
+                    include_str!("handlers/repository_aliases.rs"),
+                    include_str!("handlers/remote_repositories.rs"),
+                    include_str!("handlers/repository_worm.rs"),
+                    include_str!("handlers/repository_version_rules.rs"),
+                    include_str!("handlers/repository_delete_protection.rs"),
+                    include_str!("handlers/repository_stats.rs"),
                     include_str!("handlers/security.rs"),
                     include_str!("handlers/repo_tokens.rs"),
                 ],
@@ -681,6 +941,8 @@ mod tests {
                 vec![
                     include_str!("handlers/artifacts.rs"),
                     include_str!("handlers/artifact_labels.rs"),
+                    include_str!("handlers/artifact_consumers.rs"),
+                    include_str!("handlers/direct_uploads.rs"),
                 ],
             ),
             ("/api/v1/groups/", vec![include_str!("handlers/groups.rs")]),
@@ -693,6 +955,15 @@ mod tests {
                 "/api/v1/packages/",
                 vec![include_str!("handlers/packages.rs")],
             ),
+            ("/api/v1/badges/", vec![include_str!("handlers/badges.rs")]),
+            (
+                "/api/v1/deploy-gate/",
+                vec![include_str!("handlers/deploy_gate.rs")],
+            ),
+            (
+                "/api/v1/dependency-graph/",
+                vec![include_str!("handlers/dependency_graph.rs")],
+            ),
             ("/api/v1/tree/", vec![include_str!("handlers/tree.rs")]),
             ("/api/v1/search", vec![include_str!("handlers/search.rs")]),
             (
@@ -702,6 +973,7 @@ mod tests {
                     include_str!("handlers/peer.rs"),
                     include_str!("handlers/transfer.rs"),
                     include_str!("handlers/peer_instance_labels.rs"),
+                    include_str!("handlers/peer_join.rs"),
                 ],
             ),
             (
@@ -730,6 +1002,14 @@ mod tests {
                 "/api/v1/webhooks/",
                 vec![include_str!("handlers/webhooks.rs")],
             ),
+            (
+                "/api/v1/chat-integrations/",
+                vec![include_str!("handlers/chat_integrations.rs")],
+            ),
+            (
+                "/api/v1/chat-callbacks/",
+                vec![include_str!("handlers/chat_integrations.rs")],
+            ),
             (
                 "/api/v1/signing/",
                 vec![include_str!("handlers/signing.rs")],
@@ -767,6 +1047,10 @@ mod tests {
                 "/api/v1/instances/",
                 vec![include_str!("handlers/remote_instances.rs")],
             ),
+            (
+                "/api/v1/s3/access-keys/",
+                vec![include_str!("handlers/s3_gateway.rs")],
+            ),
             (
                 "/api/v1/service-accounts/",
                 vec![include_str!("handlers/service_accounts.rs")],
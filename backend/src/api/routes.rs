@@ -1,8 +1,11 @@
 //! Route definitions for the API.
 
 use axum::{
-    error_handling::HandleErrorLayer, extract::DefaultBodyLimit, middleware, routing::get,
-    BoxError, Router,
+    error_handling::HandleErrorLayer,
+    extract::{DefaultBodyLimit, State},
+    middleware,
+    routing::get,
+    BoxError, Json, Router,
 };
 use std::sync::Arc;
 use std::time::Duration;
@@ -15,21 +18,41 @@ use utoipa_swagger_ui::SwaggerUi;
 use crate::error::AppError;
 
 use super::handlers;
+use super::middleware::access_log::access_log_middleware;
+use super::middleware::api_version::{v1_deprecation_middleware, v2_compat_middleware};
+use super::middleware::artifact_properties::artifact_properties_middleware;
 use super::middleware::auth::{
     admin_middleware, auth_middleware, optional_auth_middleware, repo_visibility_middleware,
     RepoVisibilityState,
 };
 use super::middleware::demo::demo_guard;
 use super::middleware::guest_access::{guest_access_guard, GuestAccessState};
+use super::middleware::public_mirror::{
+    public_mirror_middleware, spawn_usage_flusher, PublicMirrorState,
+};
 use super::middleware::rate_limit::{
     login_rate_limit_middleware, rate_limit_by_ip_middleware, rate_limit_middleware,
     LoginRateLimitState, RateLimitExemptions, RateLimitState, RateLimiter,
 };
+use super::middleware::request_client::request_client_middleware;
 use super::middleware::setup::setup_guard;
 use super::middleware::tracing::correlation_id_middleware;
 use super::SharedState;
 use crate::services::auth_service::AuthService;
 
+/// Serve the OpenAPI spec: the core document built at startup plus the
+/// fragments exported by currently loaded WASM plugins.
+async fn openapi_spec(
+    State(state): State<SharedState>,
+    base: Arc<utoipa::openapi::OpenApi>,
+) -> Json<utoipa::openapi::OpenApi> {
+    let mut doc = base.as_ref().clone();
+    if let Some(registry) = &state.plugin_registry {
+        super::openapi::merge_plugin_fragments(&mut doc, &registry.openapi_fragments().await);
+    }
+    Json(doc)
+}
+
 /// Create the main API router
 pub fn create_router(state: SharedState) -> Router {
     // Build OpenAPI spec once at startup
@@ -106,10 +129,26 @@ pub fn create_router(state: SharedState) -> Router {
         // URLs; making those URLs prefix-aware is tracked as a follow-up.
         .nest("/lxc", handlers::incus::router())
         .nest("/ext", handlers::wasm_proxy::router())
-        .layer(middleware::from_fn_with_state(
-            vis_state,
-            repo_visibility_middleware,
-        ));
+        .nest(handlers::webdav::MOUNT_PREFIX, handlers::webdav::router());
+
+    // Public mirror mode: anonymous throttling, shared-cache headers and
+    // bandwidth accounting. Layered before (i.e. inside) the visibility
+    // middleware so the caller's auth state is already resolved.
+    let format_routes = if state.config.public_mirror_mode {
+        let mirror_state = PublicMirrorState::from_config(&state.config);
+        spawn_usage_flusher(mirror_state.clone(), state.db.clone());
+        tracing::info!("Public mirror mode enabled for format endpoints");
+        format_routes.layer(middleware::from_fn_with_state(
+            mirror_state,
+            public_mirror_middleware,
+        ))
+    } else {
+        format_routes
+    };
+    let format_routes = format_routes.layer(middleware::from_fn_with_state(
+        vis_state,
+        repo_visibility_middleware,
+    ));
 
     // Apply the configurable upload body limit to all format handler routes.
     // Handlers that need a different limit (e.g. OCI, incus, Git LFS,
@@ -120,6 +159,13 @@ pub fn create_router(state: SharedState) -> Router {
         format_routes.layer(DefaultBodyLimit::max(upload_limit as usize))
     };
 
+    // Protocol access log. Outside the visibility middleware so requests it
+    // rejects are logged too.
+    let format_routes = format_routes.layer(middleware::from_fn_with_state(
+        state.clone(),
+        access_log_middleware,
+    ));
+
     let swagger_enabled = {
         let env = std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".into());
         env == "development" || std::env::var("ENABLE_SWAGGER").is_ok()
@@ -133,20 +179,61 @@ pub fn create_router(state: SharedState) -> Router {
         .route("/readyz", get(handlers::health::readiness_check))
         .route("/livez", get(handlers::health::liveness_check));
 
-    // Only mount Swagger UI and OpenAPI spec in development or when explicitly enabled
+    // Only mount Swagger UI and OpenAPI spec in development or when explicitly enabled.
+    // The spec is served by our own handler so fragments of WASM plugins
+    // loaded after startup show up without a restart.
     if swagger_enabled {
-        router = router.merge(SwaggerUi::new("/swagger-ui").url("/api/v1/openapi.json", openapi));
+        let openapi = Arc::new(openapi);
+        router = router
+            .route(
+                "/api/v1/openapi.json",
+                get(move |state: State<SharedState>| openapi_spec(state, openapi.clone())),
+            )
+            .merge(
+                SwaggerUi::new("/swagger-ui")
+                    .config(utoipa_swagger_ui::Config::from("/api/v1/openapi.json")),
+            );
     }
 
+    // `/api/v2` shares the v1 router (and its rate limiters); the version
+    // layers map payloads for routes whose shape changed in v2 and flag the
+    // v1 originals with Deprecation/Sunset headers.
+    let api_routes = api_v1_routes(state.clone());
+
     let mut router = router
         // API v1 routes
-        .nest("/api/v1", api_v1_routes(state.clone()))
+        .nest(
+            "/api/v1",
+            api_routes
+                .clone()
+                .layer(middleware::from_fn(v1_deprecation_middleware)),
+        )
+        // API v2 routes
+        .nest(
+            "/api/v2",
+            api_routes.layer(middleware::from_fn(v2_compat_middleware)),
+        )
         // Docker Registry V2 API (OCI Distribution Spec)
-        .route("/v2/", handlers::oci_v2::version_check_handler())
-        .nest("/v2", handlers::oci_v2::router())
+        .merge(
+            Router::new()
+                .route("/v2/", handlers::oci_v2::version_check_handler())
+                .nest("/v2", handlers::oci_v2::router())
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    access_log_middleware,
+                )),
+        )
         // All native-protocol format handler routes (repo visibility enforced)
         .merge(format_routes);
 
+    // S3-compatible gateway over generic repositories. Every request carries
+    // a SigV4 signature that the gateway verifies itself, so it stays outside
+    // the visibility layer (which would not recognise the credential).
+    if state.config.s3_gateway_enabled {
+        tracing::info!("S3 gateway enabled at /s3");
+        router = router.nest("/s3", handlers::s3_gateway::router(&state));
+    }
+
     // Disable the global body limit. This is an artifact registry — uploads
     // can be multiple GB. Without this, Axum's 2 MB default silently truncates
     // uploads on routes that lack an explicit limit. Individual format handlers
@@ -183,11 +270,23 @@ pub fn create_router(state: SharedState) -> Router {
     // Apply setup guard (locks API until admin password is changed)
     router = router.layer(middleware::from_fn_with_state(state.clone(), setup_guard));
 
-    // Apply demo mode guard if enabled
+    // Demo mode guard. Always layered because a configuration reload can
+    // switch demo mode on; the guard passes requests through while it is off.
     if state.config.demo_mode {
         tracing::info!("Demo mode enabled — write operations will be blocked");
-        router = router.layer(middleware::from_fn_with_state(state.clone(), demo_guard));
     }
+    router = router.layer(middleware::from_fn_with_state(state.clone(), demo_guard));
+
+    // Upload properties (`X-Artifact-Properties`), scoped around the request
+    // for the shared upload paths.
+    router = router.layer(middleware::from_fn(artifact_properties_middleware));
+
+    // Client IP for API token usage tracking. Outside the guest-access guard,
+    // which validates tokens too.
+    router = router.layer(middleware::from_fn_with_state(
+        state.clone(),
+        request_client_middleware,
+    ));
 
     // Correlation ID middleware (runs first on every request after the global
     // backstop below). Extracts or generates a correlation ID and sets the
@@ -341,6 +440,17 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
         state.config.rate_limit_password_change_window_secs,
     ));
 
+    // Registered so a configuration reload can change the limits in place.
+    crate::config_reload::install_rate_limiters(crate::config_reload::RateLimiters {
+        auth: Arc::clone(&auth_rate_limiter),
+        api: Arc::clone(&api_rate_limiter),
+        search: Arc::clone(&search_rate_limiter),
+        presign: Arc::clone(&presign_rate_limiter),
+        login_global: Arc::clone(&login_global_rate_limiter),
+        login: Arc::clone(&login_rate_limiter),
+        password_change: Arc::clone(&password_change_rate_limiter),
+    });
+
     // Master on/off switch (#1602). When disabled, every rate-limit layer
     // short-circuits before touching its limiter so no request is limited.
     let rate_limit_enabled = state.config.rate_limit_enabled;
@@ -377,6 +487,24 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
         enabled: rate_limit_enabled,
         trusted_proxies: Arc::clone(&trusted_proxies),
     };
+    // The federation handshake is unauthenticated and writes a row, so it
+    // shares the auth limiter's budget like the other public credential
+    // endpoints.
+    let federation_rate_limit_state = RateLimitState {
+        limiter: Arc::clone(&auth_rate_limiter),
+        exemptions: Arc::clone(&exemptions),
+        enabled: rate_limit_enabled,
+        trusted_proxies: Arc::clone(&trusted_proxies),
+    };
+    // Edge node join requests are unauthenticated (the join token is the
+    // credential), so they get the same budget as the other credential
+    // endpoints.
+    let peer_join_rate_limit_state = RateLimitState {
+        limiter: Arc::clone(&auth_rate_limiter),
+        exemptions: Arc::clone(&exemptions),
+        enabled: rate_limit_enabled,
+        trusted_proxies: Arc::clone(&trusted_proxies),
+    };
     let api_rate_limit_state = RateLimitState {
         limiter: Arc::clone(&api_rate_limiter),
         exemptions: Arc::clone(&exemptions),
@@ -464,8 +592,36 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
             )),
         )
         .nest("/auth/sso", handlers::sso::router())
+        // Slack interactivity callbacks (public, authenticated by the
+        // integration's signing secret)
+        .nest(
+            "/chat-callbacks",
+            handlers::chat_integrations::public_router().layer(DefaultBodyLimit::max(64 * 1024)),
+        )
         // CI OIDC token exchange (public, no auth — JWT is the credential)
         .nest("/auth/ci", handlers::ci_auth::router())
+        // Federation identity exchange (public; documents are self-signed and
+        // an inbound handshake grants nothing until an admin approves it).
+        .nest(
+            "/federation",
+            handlers::federation::public_router()
+                .layer(DefaultBodyLimit::max(64 * 1024))
+                .layer(middleware::from_fn_with_state(
+                    federation_rate_limit_state,
+                    rate_limit_middleware,
+                )),
+        )
+        // Promotions pushed by trusted peers (federation-token authenticated).
+        // The content upload streams and is capped by max_upload_size_bytes.
+        .nest(
+            "/federation",
+            handlers::federation::peer_router()
+                .layer(DefaultBodyLimit::max(16 * 1024 * 1024))
+                .layer(middleware::from_fn_with_state(
+                    auth_service.clone(),
+                    auth_middleware,
+                )),
+        )
         .nest(
             "/auth",
             handlers::auth::protected_router().layer(middleware::from_fn_with_state(
@@ -652,6 +808,22 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
                 optional_auth_middleware,
             )),
         )
+        // Package status badges: public repositories are served anonymously
+        .nest(
+            "/badges",
+            handlers::badges::router().layer(middleware::from_fn_with_state(
+                auth_service.clone(),
+                optional_auth_middleware,
+            )),
+        )
+        // Dependency graph routes with optional auth
+        .nest(
+            "/dependency-graph",
+            handlers::dependency_graph::router().layer(middleware::from_fn_with_state(
+                auth_service.clone(),
+                optional_auth_middleware,
+            )),
+        )
         // Tree browser routes with optional auth
         .nest(
             "/tree",
@@ -688,10 +860,22 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
                     optional_auth_middleware,
                 )),
         )
+        // Edge node auto-registration (public; authenticated by join token,
+        // then by the node secret returned on join)
+        .nest(
+            "/peers/join",
+            handlers::peer_join::public_router()
+                .layer(DefaultBodyLimit::max(16 * 1024))
+                .layer(middleware::from_fn_with_state(
+                    peer_join_rate_limit_state,
+                    rate_limit_middleware,
+                )),
+        )
         // Peer instance routes with auth middleware
         .nest(
             "/peers",
             handlers::peers::router()
+                .merge(handlers::peer_join::router())
                 .merge(handlers::peer_instance_labels::peer_labels_router())
                 .nest("/:id/transfer", handlers::transfer::router())
                 .nest("/:id/connections", handlers::peer::peer_router())
@@ -734,8 +918,16 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
             .nest("/security", handlers::admin_security::router())
             .nest("/telemetry", handlers::telemetry::router())
             .nest("/monitoring", handlers::monitoring::router())
+            .nest("/issue-trackers", handlers::issue_trackers::router())
+            .nest("/public-mirror", handlers::public_mirror::router())
+            .nest("/blocklist", handlers::blocklist::router())
+            .nest(
+                "/package-protection",
+                handlers::package_protection::router(),
+            )
             .nest("/sso", handlers::sso_admin::router())
             .nest("/ci-oidc", handlers::ci_auth_admin::router())
+            .nest("/federation", handlers::federation::admin_router())
             .nest("/smtp", handlers::smtp::router())
             .nest("/age-gate", handlers::age_gate::admin_router())
             // Admin quality-checks list-all (#2419). Kept inside the `/admin`
@@ -743,6 +935,24 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
             // `/quality/checks` (auth-only, 400s without artifact_id) is the
             // separate #2334 contract and is unchanged.
             .nest("/quality-checks", handlers::quality_gates::admin_router())
+            .nest("/edge-nodes", handlers::edge_diagnostics::router())
+            .nest("/airlock", handlers::airlock::router())
+            .nest("/freeze-windows", handlers::freeze_windows::router())
+            .nest("/data-subjects", handlers::data_subjects::router())
+            .nest("/load-test", handlers::load_test::router())
+            .nest("/authz", handlers::authz_hook::router())
+            .nest(
+                "/storage-encryption",
+                handlers::storage_encryption::router(),
+            )
+            .nest("/storage-shards", handlers::storage_shards::router())
+            .nest("/storage-recovery", handlers::storage_recovery::router())
+            .nest("/api-tokens", handlers::api_token_usage::router())
+            .nest("/exports", handlers::exports::router())
+            .nest(
+                "/vulnerability-watchlist",
+                handlers::vulnerability_watchlist::router(),
+            )
             .layer(DefaultBodyLimit::max(1024 * 1024)) // 1 MB
             .layer(middleware::from_fn_with_state(
                 auth_service.clone(),
@@ -791,6 +1001,14 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
                     auth_middleware,
                 )),
         )
+        // Slack / Teams chat integrations with auth middleware
+        .nest(
+            "/chat-integrations",
+            handlers::chat_integrations::router().layer(middleware::from_fn_with_state(
+                auth_service.clone(),
+                auth_middleware,
+            )),
+        )
         // Domain event stream (SSE) with auth middleware
         .nest(
             "/events",
@@ -799,6 +1017,22 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
                 auth_middleware,
             )),
         )
+        // Pre-deploy verification gate with auth middleware
+        .nest(
+            "/deploy-gate",
+            handlers::deploy_gate::router().layer(middleware::from_fn_with_state(
+                auth_service.clone(),
+                auth_middleware,
+            )),
+        )
+        // Kubernetes admission webhook with auth middleware
+        .nest(
+            "/admission",
+            handlers::admission::router().layer(middleware::from_fn_with_state(
+                auth_service.clone(),
+                auth_middleware,
+            )),
+        )
         // Signing key management routes with auth middleware
         .nest(
             "/signing",
@@ -913,6 +1147,14 @@ fn api_v1_routes(state: SharedState) -> Router<SharedState> {
                     auth_middleware,
                 )),
         )
+        // S3 gateway access keys (per-caller, auth required)
+        .nest(
+            "/s3/access-keys",
+            handlers::s3_gateway::access_keys_router().layer(middleware::from_fn_with_state(
+                auth_service.clone(),
+                auth_middleware,
+            )),
+        )
         // Service account management routes with auth middleware
         .nest(
             "/service-accounts",
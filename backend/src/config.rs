@@ -12,6 +12,18 @@ fn env_parse<T: std::str::FromStr>(key: &str, default: T) -> T {
         .unwrap_or(default)
 }
 
+/// Parse a comma-separated list from env var `key`, falling back to
+/// `default` when unset. Entries are trimmed and empty ones dropped.
+fn parse_list_env(key: &str, default: &str) -> Vec<String> {
+    env::var(key)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 /// Parse a comma-separated list of CIDR ranges from env var `key`.
 ///
 /// Whitespace around each entry is trimmed and empty entries are dropped.
@@ -284,6 +296,12 @@ pub struct Config {
     /// the existing built-in text is shown unchanged. Env `SETUP_PASSWORD_HINT`.
     pub setup_password_hint: Option<String>,
 
+    /// When true, a first start against a database with no admin issues a
+    /// one-time bootstrap token instead of provisioning `admin` with a
+    /// generated password (see [`crate::services::bootstrap_service`]).
+    /// Ignored when `ADMIN_PASSWORD` is set. Env `SETUP_WIZARD`, default false.
+    pub setup_wizard: bool,
+
     /// When true, the gRPC server registers the tonic server-reflection
     /// service, which lets clients enumerate the full service catalog, every
     /// RPC method, and message schemas without authentication. Reflection is
@@ -294,6 +312,69 @@ pub struct Config {
     /// JWT auth interceptor irrespective of this flag.
     pub grpc_reflection_enabled: bool,
 
+    /// Interval between HTTP/2 PING frames the gRPC server sends on idle
+    /// connections, so long-lived client channels survive idle-timeout
+    /// proxies and dead peers are noticed. 0 disables. Env
+    /// `GRPC_KEEPALIVE_INTERVAL_SECS` (default 30).
+    pub grpc_keepalive_interval_secs: u64,
+
+    /// How long the gRPC server waits for a keepalive PING to be acknowledged
+    /// before closing the connection. Env `GRPC_KEEPALIVE_TIMEOUT_SECS`
+    /// (default 20).
+    pub grpc_keepalive_timeout_secs: u64,
+
+    /// TCP keepalive on accepted gRPC connections. 0 disables. Env
+    /// `GRPC_TCP_KEEPALIVE_SECS` (default 60).
+    pub grpc_tcp_keepalive_secs: u64,
+
+    /// How often the `grpc.health.v1` service re-checks database
+    /// connectivity. Env `GRPC_HEALTH_PROBE_INTERVAL_SECS` (default 10).
+    pub grpc_health_probe_interval_secs: u64,
+
+    /// Serve the S3-compatible gateway over generic repositories at `/s3`
+    /// (SigV4 auth with access keys issued per API token). Off by default.
+    /// Env `S3_GATEWAY_ENABLED`.
+    pub s3_gateway_enabled: bool,
+
+    /// Region reported by the S3 gateway's GetBucketLocation. Signatures are
+    /// accepted for any region. Env `S3_GATEWAY_REGION` (default
+    /// `us-east-1`).
+    pub s3_gateway_region: String,
+
+    /// Days to keep repository change-feed events
+    /// (`/api/v1/repositories/:key/events`). 0 keeps them forever. Env
+    /// `REPOSITORY_EVENT_RETENTION_DAYS` (default 30).
+    pub repository_event_retention_days: u32,
+
+    /// Days to keep client IP addresses and user agents on download
+    /// statistics and audit rows. Older values are cleared (the rows and
+    /// their counts stay). 0 keeps them forever. Env `PII_RETENTION_DAYS`
+    /// (default 0).
+    pub pii_retention_days: u32,
+
+    /// Base URLs of edge caches / CDN nodes in front of this instance that
+    /// `POST /api/v1/repositories/:key/warm-cache` should also pull every
+    /// warmed path through, e.g. `https://eu.cdn.example.com`. Env
+    /// `CACHE_WARM_EDGE_URLS` (comma-separated, default empty).
+    pub cache_warm_edge_urls: Vec<String>,
+
+    /// Registry hostnames (with port, if not the default) that refer to this
+    /// instance in image references, e.g. `registry.example.com`. The
+    /// Kubernetes admission endpoint only admits images pulled through one
+    /// of these. Env `ADMISSION_REGISTRY_HOSTS` (comma-separated, default
+    /// empty, which denies every image).
+    pub admission_registry_hosts: Vec<String>,
+
+    /// Repository keys admitted images must come from. Empty admits any
+    /// repository the webhook's token can read. Env
+    /// `ADMISSION_ALLOWED_REPOSITORIES` (comma-separated, default empty).
+    pub admission_allowed_repositories: Vec<String>,
+
+    /// Namespaces whose workloads the admission endpoint always allows.
+    /// Env `ADMISSION_EXEMPT_NAMESPACES` (comma-separated, default
+    /// `kube-system`).
+    pub admission_exempt_namespaces: Vec<String>,
+
     /// When true (the default), a WASM plugin may only be installed (via ZIP,
     /// Git, or reload) if it ships a detached Ed25519 signature
     /// (`plugin.wasm.sig`) over its raw WASM bytes that verifies against the
@@ -390,6 +471,17 @@ pub struct Config {
     /// Env var: `STUCK_SCAN_REAP_LIMIT`. Default: 1000. PR #1212 audit M1.
     pub stuck_scan_reap_limit: i64,
 
+    /// Run the scheduled audit-log retention sweep, which removes `audit_log`
+    /// rows older than the `audit_retention_days` system setting. Opt-in so
+    /// upgrading never starts deleting audit history on its own.
+    /// Env var: `AUDIT_RETENTION_ENABLED`. Default: false.
+    pub audit_retention_enabled: bool,
+
+    /// Export aged audit rows as compressed JSONL to primary storage before
+    /// they are deleted, both for the scheduled sweep and for the manual
+    /// `/admin/cleanup` path. Env var: `AUDIT_ARCHIVE_ENABLED`. Default: true.
+    pub audit_archive_enabled: bool,
+
     /// Maximum upload size in bytes for artifact uploads.
     /// Defaults to 10 GB (10737418240 bytes). Set to 0 to disable the limit.
     pub max_upload_size_bytes: u64,
@@ -636,6 +728,13 @@ pub struct Config {
     /// `presigned_downloads_enabled` is true. Default: 300 (5 minutes).
     pub presigned_download_expiry_secs: u64,
 
+    /// How often the presigned-redirect canary mints a URL for the primary
+    /// storage backend, fetches it and compares the bytes with a direct
+    /// read, reporting `presigned-redirect` through the health monitor. Only
+    /// runs while `presigned_downloads_enabled` is true. Env
+    /// `PRESIGNED_CANARY_INTERVAL_SECS`, default 600; 0 disables the canary.
+    pub presigned_canary_interval_secs: u64,
+
     // -- Proxy pull-through cache cross-replica single-flight (#1609) --
     /// Enable the cross-replica single-flight coordinator for pull-through cache
     /// fills: a PostgreSQL advisory lock keyed on the cache key so exactly ONE
@@ -656,6 +755,41 @@ pub struct Config {
     /// Default: 65.
     pub proxy_singleflight_lock_wait_timeout_secs: u64,
 
+    // -- Public mirror mode --
+    /// Serve format endpoints as a public mirror: anonymous reads get
+    /// long-lived `Cache-Control` headers, per-IP request and bandwidth
+    /// budgets (separate from the authenticated API limits), and per-repo
+    /// bandwidth accounting. Authenticated traffic is unaffected. Env var:
+    /// `PUBLIC_MIRROR_MODE`. Default: false.
+    pub public_mirror_mode: bool,
+
+    /// Anonymous requests allowed per client IP per
+    /// `public_mirror_window_secs`. Env var:
+    /// `PUBLIC_MIRROR_ANON_REQUESTS_PER_WINDOW`. Default: 1200.
+    pub public_mirror_anon_requests_per_window: u32,
+
+    /// Request-budget window in seconds. Env var:
+    /// `PUBLIC_MIRROR_WINDOW_SECS`. Default: 60.
+    pub public_mirror_window_secs: u64,
+
+    /// Response bytes an anonymous client IP may download per
+    /// `public_mirror_bandwidth_window_secs`. 0 disables the byte budget.
+    /// Env var: `PUBLIC_MIRROR_ANON_BYTES_PER_WINDOW`. Default: 50 GiB.
+    pub public_mirror_anon_bytes_per_window: u64,
+
+    /// Bandwidth-budget window in seconds. Env var:
+    /// `PUBLIC_MIRROR_BANDWIDTH_WINDOW_SECS`. Default: 3600.
+    pub public_mirror_bandwidth_window_secs: u64,
+
+    /// `max-age` for mutable metadata (indexes, packuments) served to
+    /// anonymous clients. Env var: `PUBLIC_MIRROR_METADATA_MAX_AGE_SECS`.
+    /// Default: 300.
+    pub public_mirror_metadata_max_age_secs: u64,
+
+    /// `max-age` for immutable package files served to anonymous clients.
+    /// Env var: `PUBLIC_MIRROR_ARTIFACT_MAX_AGE_SECS`. Default: 31536000.
+    pub public_mirror_artifact_max_age_secs: u64,
+
     // -- SMTP (optional, notifications are disabled when smtp_host is None) --
     /// SMTP server hostname. When absent, email delivery is disabled and the
     /// SMTP service operates as a no-op.
@@ -717,6 +851,70 @@ pub struct Config {
     /// Endpoint of the npm replication feed. Env `NPM_UPSTREAM_FEED_URL`,
     /// default `https://replicate.npmjs.com/_changes`.
     pub npm_upstream_feed_url: String,
+
+    /// Enables the admin load-test endpoints that generate synthetic
+    /// repositories, artifacts and download history. Off by default so a
+    /// production instance cannot be filled with fake data by accident. Env
+    /// `LOAD_TEST_MODE`, default `false`.
+    pub load_test_mode: bool,
+
+    /// Store generic uploads once per backend under the shared
+    /// content-addressed `cas/sha256/` namespace, tracking per-repository
+    /// references in `storage_refs` so identical content pushed to several
+    /// repositories occupies storage once. Existing objects keep their keys.
+    /// Env `STORAGE_DEDUP_ENABLED`, default `false`.
+    pub storage_dedup_enabled: bool,
+
+    /// Usable storage capacity in bytes, for forecasting when total storage
+    /// will run out. Object stores have no capacity to query, so this is
+    /// operator-declared. Env `STORAGE_CAPACITY_BYTES`, unset by default.
+    pub storage_capacity_bytes: Option<i64>,
+
+    /// Alert through the health monitor when storage or a repository quota
+    /// is forecast to fill within this many days. Env
+    /// `STORAGE_FORECAST_ALERT_DAYS`, default 30; 0 disables the alert.
+    pub storage_forecast_alert_days: i64,
+
+    /// Share of a repository quota, in percent, above which the repository
+    /// is reported as nearing its quota and quota warning emails go out.
+    /// Env `QUOTA_WARNING_THRESHOLD_PERCENT`, default 80, clamped to 1-100.
+    /// Reloadable at runtime.
+    pub quota_warning_threshold_percent: u8,
+
+    /// Registered storage backend (`filesystem`, `s3`, `azure` or `gcs`) that
+    /// `cold_tier_after_days` lifecycle policies move old artifacts to.
+    /// Archive pricing (S3 Glacier, Azure Archive) is configured on the
+    /// bucket or container itself. Env `STORAGE_COLD_BACKEND`, unset
+    /// (tiering off) by default.
+    pub storage_cold_backend: Option<String>,
+
+    /// Root directory of the cold tier when `STORAGE_COLD_BACKEND` is
+    /// `filesystem`. Env `STORAGE_COLD_PATH`, default `<STORAGE_PATH>/.cold`.
+    pub storage_cold_path: Option<String>,
+
+    /// Days a rehydrated artifact keeps its hot copy before it is freed
+    /// again. Env `STORAGE_COLD_REHYDRATED_DAYS`, default 7.
+    pub storage_cold_rehydrated_days: i64,
+
+    /// Revoke API tokens unused for this many days (since their last use, or
+    /// creation when never used). Env `API_TOKEN_STALE_DAYS`, default 0
+    /// (disabled).
+    pub api_token_stale_days: u32,
+
+    /// Days before a stale token is revoked that its owner is warned. The
+    /// token is never revoked sooner than this after the warning. Env
+    /// `API_TOKEN_STALE_WARNING_DAYS`, default 7.
+    pub api_token_stale_warning_days: u32,
+
+    /// Days a renamed repository's old key keeps redirecting to the new one,
+    /// unless the rename request sets its own. Env
+    /// `REPOSITORY_KEY_ALIAS_TTL_DAYS`, default 90.
+    pub repository_key_alias_ttl_days: u32,
+
+    /// Days protocol access log entries (one per native format request) are
+    /// kept for `/api/v1/admin/access-log` and client analytics. Env
+    /// `ACCESS_LOG_RETENTION_DAYS`, default 14; 0 turns the access log off.
+    pub access_log_retention_days: u32,
 }
 
 redacted_debug!(Config {
@@ -753,7 +951,20 @@ redacted_debug!(Config {
     show guest_access_enabled,
     show expose_detailed_health,
     show setup_password_hint,
+    show setup_wizard,
     show grpc_reflection_enabled,
+    show grpc_keepalive_interval_secs,
+    show grpc_keepalive_timeout_secs,
+    show grpc_tcp_keepalive_secs,
+    show grpc_health_probe_interval_secs,
+    show s3_gateway_enabled,
+    show s3_gateway_region,
+    show repository_event_retention_days,
+    show pii_retention_days,
+    show cache_warm_edge_urls,
+    show admission_registry_hosts,
+    show admission_allowed_repositories,
+    show admission_exempt_namespaces,
     show plugins_require_signed,
     redact_option plugins_trusted_pubkey,
     show peer_instance_name,
@@ -771,6 +982,8 @@ redacted_debug!(Config {
     show stuck_scan_threshold_secs,
     show stuck_scan_check_interval_secs,
     show stuck_scan_reap_limit,
+    show audit_retention_enabled,
+    show audit_archive_enabled,
     show max_upload_size_bytes,
     show allow_local_admin_login,
     show sso_disable_admin_break_glass,
@@ -812,9 +1025,17 @@ redacted_debug!(Config {
     show password_min_strength,
     show presigned_downloads_enabled,
     show presigned_download_expiry_secs,
+    show presigned_canary_interval_secs,
     show proxy_singleflight_advisory_locks_enabled,
     show proxy_singleflight_lock_poll_interval_ms,
     show proxy_singleflight_lock_wait_timeout_secs,
+    show public_mirror_mode,
+    show public_mirror_anon_requests_per_window,
+    show public_mirror_window_secs,
+    show public_mirror_anon_bytes_per_window,
+    show public_mirror_bandwidth_window_secs,
+    show public_mirror_metadata_max_age_secs,
+    show public_mirror_artifact_max_age_secs,
     show smtp_host,
     show smtp_port,
     show smtp_username,
@@ -827,6 +1048,18 @@ redacted_debug!(Config {
     redact_option npm_packument_cache_redis_url,
     show npm_upstream_feed_enabled,
     redact npm_upstream_feed_url,
+    show load_test_mode,
+    show storage_dedup_enabled,
+    show storage_capacity_bytes,
+    show storage_forecast_alert_days,
+    show quota_warning_threshold_percent,
+    show storage_cold_backend,
+    show storage_cold_path,
+    show storage_cold_rehydrated_days,
+    show api_token_stale_days,
+    show api_token_stale_warning_days,
+    show repository_key_alias_ttl_days,
+    show access_log_retention_days,
 });
 
 impl Default for Config {
@@ -865,7 +1098,20 @@ impl Default for Config {
             guest_access_enabled: true,
             expose_detailed_health: false,
             setup_password_hint: None,
+            setup_wizard: false,
             grpc_reflection_enabled: false,
+            grpc_keepalive_interval_secs: 30,
+            grpc_keepalive_timeout_secs: 20,
+            grpc_tcp_keepalive_secs: 60,
+            grpc_health_probe_interval_secs: 10,
+            s3_gateway_enabled: false,
+            s3_gateway_region: "us-east-1".to_string(),
+            repository_event_retention_days: 30,
+            pii_retention_days: 0,
+            cache_warm_edge_urls: Vec::new(),
+            admission_registry_hosts: Vec::new(),
+            admission_allowed_repositories: Vec::new(),
+            admission_exempt_namespaces: vec!["kube-system".to_string()],
             plugins_require_signed: true,
             plugins_trusted_pubkey: None,
             peer_instance_name: "test-instance".into(),
@@ -883,6 +1129,8 @@ impl Default for Config {
             stuck_scan_threshold_secs: 1800,
             stuck_scan_check_interval_secs: 600,
             stuck_scan_reap_limit: 1000,
+            audit_retention_enabled: false,
+            audit_archive_enabled: true,
             max_upload_size_bytes: 10_737_418_240,
             allow_local_admin_login: false,
             sso_disable_admin_break_glass: false,
@@ -927,9 +1175,17 @@ impl Default for Config {
             password_min_strength: 0,
             presigned_downloads_enabled: false,
             presigned_download_expiry_secs: 300,
+            presigned_canary_interval_secs: 600,
             proxy_singleflight_advisory_locks_enabled: false,
             proxy_singleflight_lock_poll_interval_ms: 200,
             proxy_singleflight_lock_wait_timeout_secs: 65,
+            public_mirror_mode: false,
+            public_mirror_anon_requests_per_window: 1200,
+            public_mirror_window_secs: 60,
+            public_mirror_anon_bytes_per_window: 53_687_091_200,
+            public_mirror_bandwidth_window_secs: 3600,
+            public_mirror_metadata_max_age_secs: 300,
+            public_mirror_artifact_max_age_secs: 31_536_000,
             smtp_host: None,
             smtp_port: 587,
             smtp_username: None,
@@ -945,6 +1201,18 @@ impl Default for Config {
             npm_upstream_feed_enabled: false,
             npm_upstream_feed_url: crate::services::upstream_feed::NPM_REPLICATION_FEED_DEFAULT_URL
                 .into(),
+            load_test_mode: false,
+            storage_dedup_enabled: false,
+            storage_capacity_bytes: None,
+            storage_forecast_alert_days: 30,
+            quota_warning_threshold_percent: 80,
+            storage_cold_backend: None,
+            storage_cold_path: None,
+            storage_cold_rehydrated_days: 7,
+            api_token_stale_days: 0,
+            api_token_stale_warning_days: 7,
+            repository_key_alias_ttl_days: 90,
+            access_log_retention_days: 14,
         }
     }
 }
@@ -1033,12 +1301,44 @@ impl Config {
                 .ok()
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty()),
+            setup_wizard: parse_opt_in_flag(env::var("SETUP_WIZARD").ok().as_deref()),
             // Info-disclosure hardening (#2226): gRPC server reflection exposes
             // the whole service catalog + schemas to unauthenticated peers, so
             // it is OFF unless explicitly enabled (dev/CI grpcurl tooling).
             grpc_reflection_enabled: parse_opt_in_flag(
                 env::var("GRPC_REFLECTION_ENABLED").ok().as_deref(),
             ),
+            grpc_keepalive_interval_secs: env_parse("GRPC_KEEPALIVE_INTERVAL_SECS", 30),
+            grpc_keepalive_timeout_secs: env_parse("GRPC_KEEPALIVE_TIMEOUT_SECS", 20),
+            grpc_tcp_keepalive_secs: env_parse("GRPC_TCP_KEEPALIVE_SECS", 60),
+            grpc_health_probe_interval_secs: env_parse("GRPC_HEALTH_PROBE_INTERVAL_SECS", 10)
+                .max(1),
+            s3_gateway_enabled: parse_opt_in_flag(env::var("S3_GATEWAY_ENABLED").ok().as_deref()),
+            s3_gateway_region: env::var("S3_GATEWAY_REGION")
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "us-east-1".to_string()),
+            repository_event_retention_days: env_parse("REPOSITORY_EVENT_RETENTION_DAYS", 30),
+            pii_retention_days: env_parse("PII_RETENTION_DAYS", 0),
+            cache_warm_edge_urls: env::var("CACHE_WARM_EDGE_URLS")
+                .ok()
+                .map(|s| {
+                    s.split(',')
+                        .map(|u| u.trim().trim_end_matches('/').to_string())
+                        .filter(|u| !u.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            admission_registry_hosts: parse_list_env("ADMISSION_REGISTRY_HOSTS", "")
+                .into_iter()
+                .map(|h| h.to_ascii_lowercase())
+                .collect(),
+            admission_allowed_repositories: parse_list_env("ADMISSION_ALLOWED_REPOSITORIES", ""),
+            admission_exempt_namespaces: parse_list_env(
+                "ADMISSION_EXEMPT_NAMESPACES",
+                "kube-system",
+            ),
             // Fail-closed supply-chain control: defaults to true so an
             // unsigned WASM plugin cannot be installed out of the box. Only an
             // explicit, recognized negative ("false"/"0", case/whitespace-
@@ -1103,6 +1403,12 @@ impl Config {
                     "STUCK_SCAN_REAP_LIMIT",
                     1000,
                 )),
+            audit_retention_enabled: parse_opt_in_flag(
+                env::var("AUDIT_RETENTION_ENABLED").ok().as_deref(),
+            ),
+            audit_archive_enabled: parse_opt_out_flag(
+                env::var("AUDIT_ARCHIVE_ENABLED").ok().as_deref(),
+            ),
             max_upload_size_bytes: env_parse("MAX_UPLOAD_SIZE", 10_737_418_240_u64),
             allow_local_admin_login: matches!(
                 env::var("ALLOW_LOCAL_ADMIN_LOGIN").as_deref(),
@@ -1221,6 +1527,7 @@ impl Config {
                 Ok("true" | "1")
             ),
             presigned_download_expiry_secs: env_parse("PRESIGNED_DOWNLOAD_EXPIRY_SECS", 300),
+            presigned_canary_interval_secs: env_parse("PRESIGNED_CANARY_INTERVAL_SECS", 600),
             proxy_singleflight_advisory_locks_enabled: matches!(
                 env::var("PROXY_SINGLEFLIGHT_ADVISORY_LOCKS_ENABLED").as_deref(),
                 Ok("true" | "1")
@@ -1233,6 +1540,29 @@ impl Config {
                 "PROXY_SINGLEFLIGHT_LOCK_WAIT_TIMEOUT_SECS",
                 65,
             ),
+            public_mirror_mode: parse_opt_in_flag(env::var("PUBLIC_MIRROR_MODE").ok().as_deref()),
+            public_mirror_anon_requests_per_window: env_parse(
+                "PUBLIC_MIRROR_ANON_REQUESTS_PER_WINDOW",
+                1200,
+            ),
+            public_mirror_window_secs: env_parse("PUBLIC_MIRROR_WINDOW_SECS", 60_u64).max(1),
+            public_mirror_anon_bytes_per_window: env_parse(
+                "PUBLIC_MIRROR_ANON_BYTES_PER_WINDOW",
+                53_687_091_200_u64,
+            ),
+            public_mirror_bandwidth_window_secs: env_parse(
+                "PUBLIC_MIRROR_BANDWIDTH_WINDOW_SECS",
+                3600_u64,
+            )
+            .max(1),
+            public_mirror_metadata_max_age_secs: env_parse(
+                "PUBLIC_MIRROR_METADATA_MAX_AGE_SECS",
+                300,
+            ),
+            public_mirror_artifact_max_age_secs: env_parse(
+                "PUBLIC_MIRROR_ARTIFACT_MAX_AGE_SECS",
+                31_536_000,
+            ),
             smtp_host: env::var("SMTP_HOST").ok().filter(|s| !s.is_empty()),
             smtp_port: env_parse("SMTP_PORT", 587),
             smtp_username: env::var("SMTP_USERNAME").ok().filter(|s| !s.is_empty()),
@@ -1284,6 +1614,29 @@ impl Config {
                 .unwrap_or_else(|| {
                     crate::services::upstream_feed::NPM_REPLICATION_FEED_DEFAULT_URL.into()
                 }),
+            load_test_mode: parse_opt_in_flag(env::var("LOAD_TEST_MODE").ok().as_deref()),
+            storage_dedup_enabled: parse_opt_in_flag(
+                env::var("STORAGE_DEDUP_ENABLED").ok().as_deref(),
+            ),
+            storage_capacity_bytes: env::var("STORAGE_CAPACITY_BYTES")
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .filter(|v| *v > 0),
+            storage_forecast_alert_days: env_parse("STORAGE_FORECAST_ALERT_DAYS", 30i64).max(0),
+            quota_warning_threshold_percent: env_parse("QUOTA_WARNING_THRESHOLD_PERCENT", 80u8)
+                .clamp(1, 100),
+            storage_cold_backend: env::var("STORAGE_COLD_BACKEND")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+            storage_cold_path: env::var("STORAGE_COLD_PATH")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            storage_cold_rehydrated_days: env_parse("STORAGE_COLD_REHYDRATED_DAYS", 7i64).max(1),
+            api_token_stale_days: env_parse("API_TOKEN_STALE_DAYS", 0u32),
+            api_token_stale_warning_days: env_parse("API_TOKEN_STALE_WARNING_DAYS", 7u32),
+            repository_key_alias_ttl_days: env_parse("REPOSITORY_KEY_ALIAS_TTL_DAYS", 90u32),
+            access_log_retention_days: env_parse("ACCESS_LOG_RETENTION_DAYS", 14u32),
         };
 
         config.validate_jwt_secret()?;
@@ -2417,6 +2770,28 @@ mod tests {
         restore_env("EXPOSE_DETAILED_HEALTH", saved_flag);
     }
 
+    #[test]
+    fn test_config_load_test_mode_opt_in() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        let saved_db = env::var("DATABASE_URL").ok();
+        let saved_jwt = env::var("JWT_SECRET").ok();
+        let saved_flag = env::var("LOAD_TEST_MODE").ok();
+
+        env::set_var("DATABASE_URL", "postgresql://localhost/testdb");
+        env::set_var("JWT_SECRET", STRONG_SECRET);
+
+        env::remove_var("LOAD_TEST_MODE");
+        assert!(!Config::from_env().unwrap().load_test_mode);
+        env::set_var("LOAD_TEST_MODE", "true");
+        assert!(Config::from_env().unwrap().load_test_mode);
+        env::set_var("LOAD_TEST_MODE", "yes");
+        assert!(!Config::from_env().unwrap().load_test_mode);
+
+        restore_env("DATABASE_URL", saved_db);
+        restore_env("JWT_SECRET", saved_jwt);
+        restore_env("LOAD_TEST_MODE", saved_flag);
+    }
+
     #[test]
     fn test_config_expose_detailed_health_explicit_values() {
         // Only an explicit, recognized affirmative enables the detail; garbage,
@@ -2467,6 +2842,35 @@ mod tests {
         restore_env("GRPC_REFLECTION_ENABLED", saved_flag);
     }
 
+    #[test]
+    fn test_config_grpc_keepalive_env() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        let saved_db = env::var("DATABASE_URL").ok();
+        let saved_jwt = env::var("JWT_SECRET").ok();
+        let saved_interval = env::var("GRPC_KEEPALIVE_INTERVAL_SECS").ok();
+        let saved_probe = env::var("GRPC_HEALTH_PROBE_INTERVAL_SECS").ok();
+
+        env::set_var("DATABASE_URL", "postgresql://localhost/testdb");
+        env::set_var("JWT_SECRET", STRONG_SECRET);
+        env::remove_var("GRPC_KEEPALIVE_INTERVAL_SECS");
+        env::remove_var("GRPC_HEALTH_PROBE_INTERVAL_SECS");
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.grpc_keepalive_interval_secs, 30);
+        assert_eq!(config.grpc_health_probe_interval_secs, 10);
+
+        env::set_var("GRPC_KEEPALIVE_INTERVAL_SECS", "0");
+        env::set_var("GRPC_HEALTH_PROBE_INTERVAL_SECS", "0");
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.grpc_keepalive_interval_secs, 0);
+        // A zero probe interval would panic in tokio::time::interval.
+        assert_eq!(config.grpc_health_probe_interval_secs, 1);
+
+        restore_env("DATABASE_URL", saved_db);
+        restore_env("JWT_SECRET", saved_jwt);
+        restore_env("GRPC_KEEPALIVE_INTERVAL_SECS", saved_interval);
+        restore_env("GRPC_HEALTH_PROBE_INTERVAL_SECS", saved_probe);
+    }
+
     #[test]
     fn test_config_grpc_reflection_enabled_explicit_values() {
         // Only "true"/"1" enable reflection; everything else keeps it disabled.
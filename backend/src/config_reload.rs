@@ -0,0 +1,376 @@
+//! Hot reload of the configuration subset that can change without a restart:
+//! the log filter, rate limits, scanner endpoints, demo mode and the quota
+//! warning threshold.
+//!
+//! A reload is triggered by `SIGHUP` or `POST /api/v1/admin/config/reload`.
+//! It re-reads the reloadable keys from the env file (`AK_ENV_FILE`, else
+//! `.env`) over the process environment, parses the configuration again and
+//! applies only the settings whose value changed. Listeners, connections and
+//! in-flight transfers are untouched; every other setting still needs a
+//! restart.
+//!
+//! `AppState::config` keeps the startup values, so code reading a reloadable
+//! setting goes through the accessors here ([`demo_mode`],
+//! [`quota_warning_threshold`], [`scanner_endpoints`]).
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::api::middleware::rate_limit::RateLimiter;
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{AuditAction, AuditEntry, ResourceType};
+use crate::services::scanner_service::{ScannerEndpoints, ScannerService};
+
+/// Env keys a reload takes from the env file. Keys of the non-reloadable
+/// settings are left alone so the file cannot half-apply a change that only
+/// takes effect after a restart.
+pub const RELOADABLE_ENV_KEYS: &[&str] = &[
+    "RUST_LOG",
+    "RATE_LIMIT_AUTH_PER_MIN",
+    "RATE_LIMIT_API_PER_MIN",
+    "RATE_LIMIT_SEARCH_PER_MIN",
+    "RATE_LIMIT_PRESIGN_PER_MIN",
+    "RATE_LIMIT_LOGIN_GLOBAL_PER_WINDOW",
+    "RATE_LIMIT_LOGIN_PER_WINDOW",
+    "RATE_LIMIT_LOGIN_WINDOW_SECS",
+    "RATE_LIMIT_PASSWORD_CHANGE_PER_WINDOW",
+    "RATE_LIMIT_PASSWORD_CHANGE_WINDOW_SECS",
+    "RATE_LIMIT_WINDOW_SECS",
+    "TRIVY_URL",
+    "TRIVY_ADAPTER_URL",
+    "OPENSCAP_URL",
+    "OPENSCAP_PROFILE",
+    "DEMO_MODE",
+    "QUOTA_WARNING_THRESHOLD_PERCENT",
+];
+
+/// Per-window budgets and windows of the live rate limiters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitSettings {
+    pub auth_per_window: u32,
+    pub api_per_window: u32,
+    pub search_per_window: u32,
+    pub presign_per_window: u32,
+    pub login_global_per_window: u32,
+    pub login_per_window: u32,
+    pub login_window_secs: u64,
+    pub password_change_per_window: u32,
+    pub password_change_window_secs: u64,
+    pub window_secs: u64,
+}
+
+impl RateLimitSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            auth_per_window: config.rate_limit_auth_per_window,
+            api_per_window: config.rate_limit_api_per_window,
+            search_per_window: config.rate_limit_search_per_window,
+            presign_per_window: config.rate_limit_presign_per_window,
+            login_global_per_window: config.rate_limit_login_global_per_window,
+            login_per_window: config.rate_limit_login_per_window,
+            login_window_secs: config.rate_limit_login_window_secs,
+            password_change_per_window: config.rate_limit_password_change_per_window,
+            password_change_window_secs: config.rate_limit_password_change_window_secs,
+            window_secs: config.rate_limit_window_secs,
+        }
+    }
+}
+
+/// The live rate limiters built by the router, registered so a reload can
+/// change their limits in place.
+pub struct RateLimiters {
+    pub auth: Arc<RateLimiter>,
+    pub api: Arc<RateLimiter>,
+    pub search: Arc<RateLimiter>,
+    pub presign: Arc<RateLimiter>,
+    pub login_global: Arc<RateLimiter>,
+    pub login: Arc<RateLimiter>,
+    pub password_change: Arc<RateLimiter>,
+}
+
+impl RateLimiters {
+    fn apply(&self, limits: &RateLimitSettings) {
+        let window = limits.window_secs;
+        self.auth.set_limits(limits.auth_per_window, window);
+        self.api.set_limits(limits.api_per_window, window);
+        self.search.set_limits(limits.search_per_window, window);
+        self.presign.set_limits(limits.presign_per_window, window);
+        self.login_global
+            .set_limits(limits.login_global_per_window, window);
+        self.login
+            .set_limits(limits.login_per_window, limits.login_window_secs);
+        self.password_change.set_limits(
+            limits.password_change_per_window,
+            limits.password_change_window_secs,
+        );
+    }
+}
+
+/// Values of the reloadable settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReloadableSettings {
+    pub log_filter: String,
+    pub rate_limits: RateLimitSettings,
+    pub scanner_endpoints: ScannerEndpoints,
+    pub demo_mode: bool,
+    pub quota_warning_threshold_percent: u8,
+}
+
+impl ReloadableSettings {
+    pub fn from_config(config: &Config, log_filter: String) -> Self {
+        Self {
+            log_filter,
+            rate_limits: RateLimitSettings::from_config(config),
+            scanner_endpoints: ScannerEndpoints::from_config(config),
+            demo_mode: config.demo_mode,
+            quota_warning_threshold_percent: config.quota_warning_threshold_percent,
+        }
+    }
+}
+
+/// Outcome of a reload.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReloadReport {
+    /// Settings whose value changed and now apply.
+    pub changed: Vec<String>,
+    /// Env file the reloadable keys were read from, when one was found.
+    pub env_file: Option<String>,
+}
+
+impl ReloadReport {
+    /// Audit record of the reload. The actor is set by the caller.
+    pub fn audit_entry(&self) -> AuditEntry {
+        AuditEntry::new(AuditAction::SettingChanged, ResourceType::Setting)
+            .resource_name("config_reload")
+            .details(serde_json::json!({
+                "changed": self.changed,
+                "env_file": self.env_file,
+            }))
+    }
+}
+
+/// Settings applied at startup or by the last reload.
+static CURRENT: RwLock<Option<ReloadableSettings>> = RwLock::new(None);
+static RATE_LIMITERS: RwLock<Option<RateLimiters>> = RwLock::new(None);
+
+const DEMO_MODE_UNSET: u8 = 0;
+const DEMO_MODE_OFF: u8 = 1;
+const DEMO_MODE_ON: u8 = 2;
+/// Demo mode set by a reload; unset until the first reload that changes
+/// it, so the startup `Config` value stays authoritative until then.
+static DEMO_MODE: AtomicU8 = AtomicU8::new(DEMO_MODE_UNSET);
+static QUOTA_WARNING_THRESHOLD_PERCENT: AtomicU8 = AtomicU8::new(80);
+
+/// Record the startup values. Called once from `main` after the config is
+/// loaded and tracing is initialised.
+pub fn init(config: &Config) {
+    QUOTA_WARNING_THRESHOLD_PERCENT
+        .store(config.quota_warning_threshold_percent, Ordering::Relaxed);
+    let settings = ReloadableSettings::from_config(config, crate::telemetry::log_filter_from_env());
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Some(settings);
+}
+
+/// Register the router's rate limiters. The last registration wins, which
+/// is the router actually serving.
+pub fn install_rate_limiters(limiters: RateLimiters) {
+    *RATE_LIMITERS.write().unwrap_or_else(|e| e.into_inner()) = Some(limiters);
+}
+
+/// Whether demo mode is on, honouring reloads.
+pub fn demo_mode(config: &Config) -> bool {
+    match DEMO_MODE.load(Ordering::Relaxed) {
+        DEMO_MODE_UNSET => config.demo_mode,
+        value => value == DEMO_MODE_ON,
+    }
+}
+
+/// Share of a repository quota above which it counts as nearly full.
+pub fn quota_warning_threshold() -> f64 {
+    f64::from(QUOTA_WARNING_THRESHOLD_PERCENT.load(Ordering::Relaxed)) / 100.0
+}
+
+/// Scanner sidecar endpoints in effect, honouring reloads.
+pub fn scanner_endpoints(config: &Config) -> ScannerEndpoints {
+    CURRENT
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|s| s.scanner_endpoints.clone())
+        .unwrap_or_else(|| ScannerEndpoints::from_config(config))
+}
+
+/// Names of the settings that differ between `old` and `new`; every
+/// setting when nothing was recorded yet.
+fn changed_settings(old: Option<&ReloadableSettings>, new: &ReloadableSettings) -> Vec<String> {
+    let mut changed = Vec::new();
+    if old.is_none_or(|o| o.log_filter != new.log_filter) {
+        changed.push("log_filter");
+    }
+    if old.is_none_or(|o| o.rate_limits != new.rate_limits) {
+        changed.push("rate_limits");
+    }
+    if old.is_none_or(|o| o.scanner_endpoints != new.scanner_endpoints) {
+        changed.push("scanner_endpoints");
+    }
+    if old.is_none_or(|o| o.demo_mode != new.demo_mode) {
+        changed.push("demo_mode");
+    }
+    if old.is_none_or(|o| o.quota_warning_threshold_percent != new.quota_warning_threshold_percent)
+    {
+        changed.push("quota_warning_threshold_percent");
+    }
+    changed.into_iter().map(String::from).collect()
+}
+
+/// Copy the reloadable keys of the env file into the process environment.
+/// Returns the file read, or `None` when there is none.
+fn reload_env_file() -> Result<Option<String>> {
+    let (path, iter) = match std::env::var("AK_ENV_FILE") {
+        Ok(path) => {
+            let iter = dotenvy::from_path_iter(&path)
+                .map_err(|e| AppError::Config(format!("cannot read env file {path}: {e}")))?;
+            (path, iter)
+        }
+        Err(_) => match dotenvy::dotenv_iter() {
+            Ok(iter) => (".env".to_string(), iter),
+            Err(_) => return Ok(None),
+        },
+    };
+    for item in iter {
+        let (key, value) =
+            item.map_err(|e| AppError::Config(format!("cannot parse env file {path}: {e}")))?;
+        if RELOADABLE_ENV_KEYS.contains(&key.as_str()) {
+            std::env::set_var(key, value);
+        }
+    }
+    Ok(Some(path))
+}
+
+/// Re-read the configuration and apply the reloadable settings that
+/// changed. Nothing is applied when the new configuration does not parse.
+pub fn reload(scanner_service: Option<&ScannerService>) -> Result<ReloadReport> {
+    let env_file = reload_env_file()?;
+    let config = Config::from_env()?;
+    let new = ReloadableSettings::from_config(&config, crate::telemetry::log_filter_from_env());
+
+    let mut current = CURRENT.write().unwrap_or_else(|e| e.into_inner());
+    let changed = changed_settings(current.as_ref(), &new);
+    let is_changed = |name: &str| changed.iter().any(|c| c == name);
+
+    // Applied first: the only setting that can still be rejected.
+    if is_changed("log_filter") {
+        crate::telemetry::reload_log_filter(&new.log_filter).map_err(AppError::BadRequest)?;
+    }
+    if is_changed("rate_limits") {
+        if let Some(limiters) = RATE_LIMITERS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            limiters.apply(&new.rate_limits);
+        }
+    }
+    if is_changed("scanner_endpoints") {
+        if let Some(scanner_service) = scanner_service {
+            scanner_service.reload_endpoints(&new.scanner_endpoints);
+        }
+    }
+    if is_changed("demo_mode") {
+        let value = if new.demo_mode {
+            DEMO_MODE_ON
+        } else {
+            DEMO_MODE_OFF
+        };
+        DEMO_MODE.store(value, Ordering::Relaxed);
+    }
+    QUOTA_WARNING_THRESHOLD_PERCENT.store(new.quota_warning_threshold_percent, Ordering::Relaxed);
+
+    *current = Some(new);
+    tracing::info!(changed = ?changed, env_file = ?env_file, "Configuration reloaded");
+    Ok(ReloadReport { changed, env_file })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> ReloadableSettings {
+        ReloadableSettings::from_config(&Config::default(), "info".to_string())
+    }
+
+    #[test]
+    fn test_changed_settings_reports_only_differences() {
+        let old = settings();
+        assert!(changed_settings(Some(&old), &old).is_empty());
+
+        let mut new = old.clone();
+        new.demo_mode = !old.demo_mode;
+        new.rate_limits.api_per_window += 1;
+        assert_eq!(
+            changed_settings(Some(&old), &new),
+            ["rate_limits", "demo_mode"]
+        );
+
+        let mut new = old.clone();
+        new.scanner_endpoints.trivy_adapter_url = Some("http://adapter:8080".into());
+        new.log_filter = "debug".into();
+        assert_eq!(
+            changed_settings(Some(&old), &new),
+            ["log_filter", "scanner_endpoints"]
+        );
+    }
+
+    #[test]
+    fn test_changed_settings_without_baseline_reports_everything() {
+        assert_eq!(changed_settings(None, &settings()).len(), 5);
+    }
+
+    #[test]
+    fn test_rate_limiters_apply_sets_each_budget() {
+        let limiter = || Arc::new(RateLimiter::new(1, 60));
+        let limiters = RateLimiters {
+            auth: limiter(),
+            api: limiter(),
+            search: limiter(),
+            presign: limiter(),
+            login_global: limiter(),
+            login: limiter(),
+            password_change: limiter(),
+        };
+        let limits = RateLimitSettings::from_config(&Config::default());
+        limiters.apply(&limits);
+        assert_eq!(limiters.api.max_requests(), limits.api_per_window);
+        assert_eq!(limiters.login.max_requests(), limits.login_per_window);
+        assert_eq!(
+            limiters.password_change.max_requests(),
+            limits.password_change_per_window
+        );
+    }
+
+    #[test]
+    fn test_demo_mode_follows_config_until_reloaded() {
+        let config = Config {
+            demo_mode: true,
+            ..Config::default()
+        };
+        assert!(demo_mode(&config));
+        assert!(!demo_mode(&Config::default()));
+    }
+
+    #[test]
+    fn test_reloadable_keys_cover_the_parsed_settings() {
+        for key in [
+            "RUST_LOG",
+            "DEMO_MODE",
+            "TRIVY_ADAPTER_URL",
+            "QUOTA_WARNING_THRESHOLD_PERCENT",
+        ] {
+            assert!(RELOADABLE_ENV_KEYS.contains(&key), "{key}");
+        }
+        assert!(!RELOADABLE_ENV_KEYS.contains(&"DATABASE_URL"));
+    }
+}
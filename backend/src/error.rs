@@ -137,6 +137,13 @@ pub enum AppError {
     /// output) are NOT `NotFound` and stay on the `Internal` -> `failed` path.
     #[error("Scanner engine unavailable: {0}")]
     ScannerEngineUnavailable(String),
+
+    /// The artifact body sits in the cold storage tier and a rehydration has
+    /// been queued. Not a failure: it maps to 202 Accepted with a
+    /// `Retry-After` of `retry_after_secs`, so a download returns it through
+    /// the ordinary `?` path and the client simply polls.
+    #[error("Artifact is being restored from cold storage")]
+    Rehydrating { retry_after_secs: u64 },
 }
 
 impl AppError {
@@ -213,6 +220,7 @@ impl AppError {
                 StatusCode::SERVICE_UNAVAILABLE,
                 "SCANNER_ENGINE_UNAVAILABLE",
             ),
+            Self::Rehydrating { .. } => (StatusCode::ACCEPTED, "REHYDRATING"),
         }
     }
 
@@ -246,7 +254,7 @@ impl AppError {
             Self::Authentication(_) | Self::Unauthorized(_) | Self::Authorization(_) => {
                 tracing::Level::WARN
             }
-            Self::QuotaExceeded(_) => tracing::Level::INFO,
+            Self::QuotaExceeded(_) | Self::Rehydrating { .. } => tracing::Level::INFO,
             _ if self.status_and_code().0.is_client_error() => tracing::Level::INFO,
             _ => tracing::Level::ERROR,
         }
@@ -290,6 +298,9 @@ impl AppError {
             | Self::ServiceUnavailable(msg)
             | Self::ScannerEngineUnavailable(msg) => msg.clone(),
             Self::Json(_) => "Invalid JSON".to_string(),
+            Self::Rehydrating { retry_after_secs } => format!(
+                "Artifact is being restored from cold storage; retry in {retry_after_secs} seconds"
+            ),
         }
     }
 }
@@ -298,6 +309,10 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, code) = self.status_and_code();
         let message = self.user_message();
+        let retry_after = match self {
+            Self::Rehydrating { retry_after_secs } => Some(retry_after_secs),
+            _ => None,
+        };
 
         match self.log_level() {
             tracing::Level::ERROR => tracing::error!(error = %self, code = code, "Request error"),
@@ -319,6 +334,11 @@ impl IntoResponse for AppError {
                 HeaderValue::from_static(RETRY_AFTER_SECS_ON_503),
             );
         }
+        if let Some(secs) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
         response
     }
 }
@@ -667,6 +687,17 @@ mod tests {
         assert!(response.headers().get(header::RETRY_AFTER).is_none());
     }
 
+    #[test]
+    fn test_rehydrating_is_202_with_its_own_retry_after() {
+        let err = AppError::Rehydrating {
+            retry_after_secs: 45,
+        };
+        assert_eq!(err.log_level(), tracing::Level::INFO);
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "45");
+    }
+
     // -----------------------------------------------------------------------
     // `log_level`: routine client-caused conditions must not log at `error`,
     // or error-log-volume dashboards/alerts can't distinguish them from
@@ -1,17 +1,21 @@
 //! Helm chart format handler.
 //!
 //! Implements Helm chart repository for Kubernetes Helm charts.
-//! Supports .tgz chart packages and index.yaml generation.
+//! Supports .tgz chart packages, index.yaml generation, and extraction of
+//! the container images a chart deploys.
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::Read;
 use tar::Archive;
 
 use crate::error::{AppError, Result};
+use crate::formats::oci::ImageReference;
 use crate::formats::FormatHandler;
 use crate::models::repository::RepositoryFormat;
 
@@ -48,6 +52,39 @@ fn read_capped_entry_to_string<R: Read>(entry: R, what: &str) -> Result<String>
     Ok(content)
 }
 
+/// A literal `image:` line in a template. Templated values
+/// (`image: {{ .Values.image }}`) are captured too and rejected by
+/// [`ImageReference::parse`]; the values they render from are scanned
+/// separately.
+static TEMPLATE_IMAGE_LINE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*(?:-\s*)?image:\s*["']?([^"'\s#]+)"#).expect("valid regex"));
+
+/// Where in a chart an image reference was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartImageSource {
+    Values,
+    Template,
+}
+
+impl ChartImageSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Values => "values",
+            Self::Template => "template",
+        }
+    }
+}
+
+/// A container image a chart deploys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartImageRef {
+    pub image: ImageReference,
+    pub source: ChartImageSource,
+    /// Values key path (`mychart/values.yaml#controller.image`) or template
+    /// file (`mychart/templates/job.yaml`).
+    pub location: String,
+}
+
 /// Helm format handler
 pub struct HelmHandler;
 
@@ -182,6 +219,74 @@ impl HelmHandler {
         }
     }
 
+    /// Collect the container images a chart package deploys, from every
+    /// `values.yaml` (the chart's and its bundled subcharts') and every
+    /// template under a `templates/` directory.
+    ///
+    /// Values are matched by key: an `image` key (or one ending in `Image` /
+    /// `_image`) holding either a reference string or a map with
+    /// `repository` (or `name`) plus optional `registry`, `tag` and `digest`.
+    /// An empty or missing `tag` falls back to `app_version`, which is what
+    /// the conventional `{{ .Values.image.tag | default .Chart.AppVersion }}`
+    /// template renders. Templates contribute literal `image:` lines only.
+    ///
+    /// Best-effort: entries that are unreadable or not valid YAML are
+    /// skipped. The archive is read through the same decompression budget
+    /// and entry-count cap as [`Self::find_capped_chart_entry`].
+    pub fn extract_image_refs_from_reader<R: Read>(
+        reader: R,
+        app_version: Option<&str>,
+    ) -> Result<Vec<ChartImageRef>> {
+        use crate::util::bounded_archive::{budgeted, MAX_INGEST_ARCHIVE_ENTRIES};
+
+        let gz = GzDecoder::new(reader);
+        let mut archive = Archive::new(budgeted(gz));
+
+        let mut refs = Vec::new();
+        let mut entries_seen: u64 = 0;
+        for entry in archive
+            .entries()
+            .map_err(|e| AppError::Validation(format!("Invalid chart package: {}", e)))?
+        {
+            let mut entry =
+                entry.map_err(|e| AppError::Validation(format!("Invalid chart entry: {}", e)))?;
+
+            entries_seen += 1;
+            if entries_seen > MAX_INGEST_ARCHIVE_ENTRIES {
+                return Err(AppError::Validation(format!(
+                    "Chart package contains too many entries (> {}); refusing suspected decompression bomb",
+                    MAX_INGEST_ARCHIVE_ENTRIES
+                )));
+            }
+
+            let path = match entry.path() {
+                Ok(path) => path.to_string_lossy().into_owned(),
+                Err(_) => continue,
+            };
+            let is_values = path.rsplit('/').next() == Some("values.yaml");
+            let is_template = path.split('/').any(|c| c == "templates")
+                && (path.ends_with(".yaml") || path.ends_with(".yml") || path.ends_with(".tpl"));
+            if !is_values && !is_template {
+                continue;
+            }
+            let Ok(content) = read_capped_entry_to_string(&mut entry, &path) else {
+                continue;
+            };
+
+            if is_values {
+                if let Ok(values) = serde_yaml::from_str::<serde_yaml::Value>(&content) {
+                    collect_value_images(&values, "", &path, app_version, &mut refs);
+                }
+            } else {
+                collect_template_images(&content, &path, &mut refs);
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        refs.retain(|r| seen.insert((r.image.to_string(), r.location.clone())));
+        Ok(refs)
+    }
+
     /// Locate a metadata entry (`Chart.yaml` / `values.yaml`) inside a chart
     /// `.tgz`, returning its capped contents (`None` when absent).
     ///
@@ -227,6 +332,103 @@ impl HelmHandler {
     }
 }
 
+/// Whether a values key names an image (`image`, `initImage`, `sidecar_image`).
+fn is_image_key(key: &str) -> bool {
+    key == "image" || key.ends_with("Image") || key.ends_with("_image")
+}
+
+/// Scalar YAML value as a non-empty string (tags are often written as bare
+/// numbers, e.g. `tag: 1.25`).
+fn yaml_scalar(value: Option<&serde_yaml::Value>) -> Option<String> {
+    let s = match value? {
+        serde_yaml::Value::String(s) => s.trim().to_string(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!s.is_empty()).then_some(s)
+}
+
+/// Image reference held by an image-named values key, either as a string or
+/// as a `repository`/`tag` map.
+fn image_from_value(
+    value: &serde_yaml::Value,
+    app_version: Option<&str>,
+) -> Option<ImageReference> {
+    match value {
+        serde_yaml::Value::String(s) => ImageReference::parse(s),
+        serde_yaml::Value::Mapping(map) => {
+            let repository =
+                yaml_scalar(map.get("repository")).or_else(|| yaml_scalar(map.get("name")))?;
+            let mut reference = match yaml_scalar(map.get("registry")) {
+                Some(registry) => format!("{}/{}", registry.trim_end_matches('/'), repository),
+                None => repository,
+            };
+            if let Some(tag) =
+                yaml_scalar(map.get("tag")).or_else(|| app_version.map(str::to_string))
+            {
+                reference = format!("{}:{}", reference, tag);
+            }
+            if let Some(digest) = yaml_scalar(map.get("digest")) {
+                reference = format!("{}@{}", reference, digest);
+            }
+            ImageReference::parse(&reference)
+        }
+        _ => None,
+    }
+}
+
+fn collect_value_images(
+    value: &serde_yaml::Value,
+    key_path: &str,
+    file: &str,
+    app_version: Option<&str>,
+    out: &mut Vec<ChartImageRef>,
+) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, child) in map {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                let child_path = if key_path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", key_path, key)
+                };
+                if is_image_key(key) {
+                    if let Some(image) = image_from_value(child, app_version) {
+                        out.push(ChartImageRef {
+                            image,
+                            source: ChartImageSource::Values,
+                            location: format!("{}#{}", file, child_path),
+                        });
+                    }
+                }
+                collect_value_images(child, &child_path, file, app_version, out);
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for (i, child) in items.iter().enumerate() {
+                let child_path = format!("{}[{}]", key_path, i);
+                collect_value_images(child, &child_path, file, app_version, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_template_images(content: &str, file: &str, out: &mut Vec<ChartImageRef>) {
+    for captures in TEMPLATE_IMAGE_LINE.captures_iter(content) {
+        if let Some(image) = ImageReference::parse(&captures[1]) {
+            out.push(ChartImageRef {
+                image,
+                source: ChartImageSource::Template,
+                location: file.to_string(),
+            });
+        }
+    }
+}
+
 impl Default for HelmHandler {
     fn default() -> Self {
         Self::new()
@@ -1267,4 +1469,100 @@ entries:
         let values = HelmHandler::extract_values_yaml(&tgz).unwrap();
         assert!(values.is_some());
     }
+
+    // ---- chart image extraction ----
+
+    #[test]
+    fn test_extract_image_refs_from_values_and_templates() {
+        let values = br#"
+image:
+  repository: bitnami/nginx
+  tag: ""
+sidecar:
+  image: quay.io/prometheus/node-exporter:v1.7.0
+initImage:
+  registry: ghcr.io
+  repository: acme/init
+  tag: 2
+  digest: sha256:abc123
+jobs:
+  - image: busybox:1.36
+unrelated: nginx:1.0
+"#;
+        let template = br#"
+spec:
+  containers:
+    - name: app
+      image: "{{ .Values.image.repository }}:{{ .Values.image.tag }}"
+    - name: proxy
+      image: envoyproxy/envoy:v1.29.0 # pinned
+"#;
+        let tgz = build_multi_tgz(&[
+            (
+                "web/Chart.yaml",
+                b"apiVersion: v2\nname: web\nversion: 1.0.0",
+            ),
+            ("web/values.yaml", values),
+            ("web/templates/deployment.yaml", template),
+            ("web/templates/NOTES.txt", b"image: ignored:1"),
+        ]);
+
+        let refs = HelmHandler::extract_image_refs_from_reader(&tgz[..], Some("1.25.3")).unwrap();
+        let found: Vec<(String, &str, &str)> = refs
+            .iter()
+            .map(|r| (r.image.to_string(), r.source.as_str(), r.location.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "docker.io/bitnami/nginx:1.25.3".to_string(),
+                    "values",
+                    "web/values.yaml#image"
+                ),
+                (
+                    "quay.io/prometheus/node-exporter:v1.7.0".to_string(),
+                    "values",
+                    "web/values.yaml#sidecar.image"
+                ),
+                (
+                    "ghcr.io/acme/init:2@sha256:abc123".to_string(),
+                    "values",
+                    "web/values.yaml#initImage"
+                ),
+                (
+                    "docker.io/library/busybox:1.36".to_string(),
+                    "values",
+                    "web/values.yaml#jobs[0].image"
+                ),
+                (
+                    "docker.io/envoyproxy/envoy:v1.29.0".to_string(),
+                    "template",
+                    "web/templates/deployment.yaml"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_image_refs_skips_invalid_values_yaml() {
+        let tgz = build_multi_tgz(&[
+            ("web/values.yaml", b"image: [unterminated"),
+            ("web/charts/db/values.yaml", b"image: postgres:16"),
+        ]);
+        let refs = HelmHandler::extract_image_refs_from_reader(&tgz[..], None).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].image.to_string(), "docker.io/library/postgres:16");
+        assert_eq!(refs[0].location, "web/charts/db/values.yaml#image");
+    }
+
+    #[test]
+    fn test_extract_image_refs_without_tag_or_app_version() {
+        let tgz = build_tgz("web/values.yaml", b"image:\n  repository: redis\n");
+        let refs = HelmHandler::extract_image_refs_from_reader(&tgz[..], None).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].image.tag, None);
+        assert_eq!(refs[0].image.to_string(), "docker.io/library/redis");
+    }
 }
@@ -292,6 +292,96 @@ pub struct OciPlatform {
     pub variant: Option<String>,
 }
 
+/// Registry implied by an image reference without a registry host.
+pub const DEFAULT_IMAGE_REGISTRY: &str = "docker.io";
+
+/// A container image reference (`[registry/]repository[:tag][@digest]`),
+/// normalized the way `docker pull` resolves it: a missing registry is
+/// Docker Hub, and a single-segment Docker Hub repository lives under
+/// `library/` (`nginx` -> `docker.io/library/nginx`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageReference {
+    pub registry: String,
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl ImageReference {
+    /// Parse an image reference. Returns `None` for anything that cannot be
+    /// one: empty input, whitespace, or unrendered template syntax.
+    pub fn parse(reference: &str) -> Option<Self> {
+        let reference = reference.trim();
+        if reference.is_empty()
+            || reference
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, '{' | '}' | '$' | '"' | '\''))
+        {
+            return None;
+        }
+
+        let (name, digest) = match reference.split_once('@') {
+            Some((name, digest)) if digest.contains(':') => (name, Some(digest.to_string())),
+            Some(_) => return None,
+            None => (reference, None),
+        };
+        // A tag separator is a ':' after the last '/', so a registry port
+        // (`host:5000/app`) is not mistaken for one.
+        let last_slash = name.rfind('/').map(|i| i + 1).unwrap_or(0);
+        let (name, tag) = match name[last_slash..].rfind(':') {
+            Some(i) => {
+                let tag = &name[last_slash + i + 1..];
+                if tag.is_empty() {
+                    return None;
+                }
+                (&name[..last_slash + i], Some(tag.to_string()))
+            }
+            None => (name, None),
+        };
+
+        let (registry, repository) = match name.split_once('/') {
+            Some((host, rest))
+                if host.contains('.') || host.contains(':') || host == "localhost" =>
+            {
+                (host.to_ascii_lowercase(), rest.to_string())
+            }
+            _ => (DEFAULT_IMAGE_REGISTRY.to_string(), name.to_string()),
+        };
+        let registry = match registry.as_str() {
+            "index.docker.io" | "registry-1.docker.io" => DEFAULT_IMAGE_REGISTRY.to_string(),
+            _ => registry,
+        };
+        if repository.is_empty() || repository.split('/').any(str::is_empty) {
+            return None;
+        }
+        let repository = if registry == DEFAULT_IMAGE_REGISTRY && !repository.contains('/') {
+            format!("library/{}", repository)
+        } else {
+            repository
+        };
+
+        Some(Self {
+            registry,
+            repository,
+            tag,
+            digest,
+        })
+    }
+}
+
+impl std::fmt::Display for ImageReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.registry, self.repository)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{}", tag)?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{}", digest)?;
+        }
+        Ok(())
+    }
+}
+
 // OCI media types
 pub mod media_types {
     pub const MANIFEST_V2: &str = "application/vnd.docker.distribution.manifest.v2+json";
@@ -511,4 +601,51 @@ mod tests {
         let handler = OciHandler::new();
         assert!(!handler.is_wasm_plugin());
     }
+
+    #[test]
+    fn test_image_reference_normalizes_docker_hub() {
+        let r = ImageReference::parse("nginx").unwrap();
+        assert_eq!(r.to_string(), "docker.io/library/nginx");
+        assert_eq!(r.tag, None);
+
+        let r = ImageReference::parse("bitnami/redis:7.2").unwrap();
+        assert_eq!(r.registry, "docker.io");
+        assert_eq!(r.repository, "bitnami/redis");
+        assert_eq!(r.tag.as_deref(), Some("7.2"));
+
+        let r = ImageReference::parse("index.docker.io/library/busybox:1.36").unwrap();
+        assert_eq!(r.to_string(), "docker.io/library/busybox:1.36");
+    }
+
+    #[test]
+    fn test_image_reference_registry_port_and_digest() {
+        let r = ImageReference::parse("registry.local:5000/team/app:v1@sha256:abc").unwrap();
+        assert_eq!(r.registry, "registry.local:5000");
+        assert_eq!(r.repository, "team/app");
+        assert_eq!(r.tag.as_deref(), Some("v1"));
+        assert_eq!(r.digest.as_deref(), Some("sha256:abc"));
+
+        let r = ImageReference::parse("localhost/app").unwrap();
+        assert_eq!(r.registry, "localhost");
+        assert_eq!(r.repository, "app");
+    }
+
+    #[test]
+    fn test_image_reference_rejects_non_references() {
+        for bad in [
+            "",
+            "  ",
+            "{{ .Values.image }}",
+            "repo:tag with space",
+            "app:",
+            "app@digest",
+            "ghcr.io/",
+            "a//b",
+        ] {
+            assert!(
+                ImageReference::parse(bad).is_none(),
+                "{bad:?} must not parse"
+            );
+        }
+    }
 }
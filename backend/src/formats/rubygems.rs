@@ -323,6 +323,9 @@ impl RubygemsHandler {
                 gemspec.dependencies = Some(deps);
             }
         }
+        let (ruby, rubygems) = parse_gemspec_platform_requirements(content);
+        gemspec.required_ruby_version = ruby;
+        gemspec.required_rubygems_version = rubygems;
 
         Ok(gemspec)
     }
@@ -514,6 +517,38 @@ fn yaml_uses_anchors_or_aliases(content: &str) -> bool {
     false
 }
 
+/// A `Gem::Requirement` as serialized in `metadata.gz`:
+/// `requirements: [[op, Gem::Version{version}], ...]`.
+#[derive(serde::Deserialize, Default)]
+struct YamlRequirement {
+    #[serde(default)]
+    requirements: Vec<Vec<serde_yaml::Value>>,
+}
+
+impl YamlRequirement {
+    /// Render as `"op ver, op ver"`; `None` when there are no constraints.
+    fn render(&self) -> Option<String> {
+        let parts: Vec<String> = self
+            .requirements
+            .iter()
+            .filter_map(|c| {
+                let op = c.first().and_then(|v| v.as_str())?;
+                let ver = c.get(1).and_then(|v| {
+                    v.get("version")
+                        .and_then(yaml_scalar_to_string)
+                        .or_else(|| yaml_scalar_to_string(v))
+                })?;
+                Some(format!("{} {}", op, ver))
+            })
+            .collect();
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(", "))
+        }
+    }
+}
+
 /// Extract dependencies from a gem's `metadata.gz` YAML into
 /// `[name, requirement-string, type]` triples. Returns `None` if the content is
 /// not parseable as a gemspec document.
@@ -526,16 +561,11 @@ fn parse_gemspec_dependencies(content: &str) -> Option<Vec<GemDependency>> {
         return None;
     }
 
-    #[derive(serde::Deserialize, Default)]
-    struct YReq {
-        #[serde(default)]
-        requirements: Vec<Vec<serde_yaml::Value>>,
-    }
     #[derive(serde::Deserialize)]
     struct YDep {
         name: String,
         #[serde(default)]
-        requirement: YReq,
+        requirement: YamlRequirement,
         #[serde(rename = "type", default)]
         dep_type: Option<String>,
     }
@@ -550,22 +580,6 @@ fn parse_gemspec_dependencies(content: &str) -> Option<Vec<GemDependency>> {
         .dependencies
         .into_iter()
         .map(|d| {
-            // requirement.requirements is `[[op, Gem::Version{version}], ...]`.
-            let requirements = d
-                .requirement
-                .requirements
-                .iter()
-                .filter_map(|c| {
-                    let op = c.first().and_then(|v| v.as_str())?;
-                    let ver = c.get(1).and_then(|v| {
-                        v.get("version")
-                            .and_then(yaml_scalar_to_string)
-                            .or_else(|| yaml_scalar_to_string(v))
-                    })?;
-                    Some(format!("{} {}", op, ver))
-                })
-                .collect::<Vec<_>>()
-                .join(", ");
             let dep_type = d
                 .dep_type
                 .unwrap_or_default()
@@ -573,11 +587,7 @@ fn parse_gemspec_dependencies(content: &str) -> Option<Vec<GemDependency>> {
                 .to_string();
             GemDependency {
                 name: d.name,
-                requirements: if requirements.is_empty() {
-                    ">= 0".to_string()
-                } else {
-                    requirements
-                },
+                requirements: d.requirement.render().unwrap_or_else(|| ">= 0".to_string()),
                 dep_type,
             }
         })
@@ -585,6 +595,32 @@ fn parse_gemspec_dependencies(content: &str) -> Option<Vec<GemDependency>> {
     Some(deps)
 }
 
+/// Extract `required_ruby_version` and `required_rubygems_version` from a gem's
+/// `metadata.gz` YAML. The `>= 0` that `gem build` writes when the gemspec
+/// leaves a bound unset is reported as `None`.
+fn parse_gemspec_platform_requirements(content: &str) -> (Option<String>, Option<String>) {
+    if yaml_uses_anchors_or_aliases(content) {
+        return (None, None);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct YSpec {
+        #[serde(default)]
+        required_ruby_version: YamlRequirement,
+        #[serde(default)]
+        required_rubygems_version: YamlRequirement,
+    }
+
+    let Ok(spec) = serde_yaml::from_str::<YSpec>(content) else {
+        return (None, None);
+    };
+    let bound = |r: &YamlRequirement| r.render().filter(|s| s != ">= 0");
+    (
+        bound(&spec.required_ruby_version),
+        bound(&spec.required_rubygems_version),
+    )
+}
+
 /// Parse the gem name out of a `<name>-<version>[-<platform>].gem` filename.
 ///
 /// Returns `None` if the filename does not parse as a gem (no `.gem`
@@ -788,6 +824,8 @@ pub struct GemSpec {
     #[serde(default)]
     pub required_ruby_version: Option<String>,
     #[serde(default)]
+    pub required_rubygems_version: Option<String>,
+    #[serde(default)]
     pub dependencies: Option<Vec<GemDependency>>,
 }
 
@@ -875,6 +913,98 @@ pub fn generate_gem_info(gemspec: &GemSpec, sha256: &str, downloads: u64) -> Gem
     }
 }
 
+// ---------------------------------------------------------------------------
+// Compact index (`/versions`, `/info/<gem>`, `/names`)
+// ---------------------------------------------------------------------------
+//
+// The plain-text index Bundler prefers over the legacy Marshal index and the
+// dependency API. `versions` lists every gem with its versions and the MD5 of
+// its `info/<gem>` file; Bundler re-fetches an info file only when that
+// checksum changes. Each info line carries a version's runtime dependencies,
+// its SHA-256 and its Ruby/RubyGems requirements.
+
+/// `version` or `version-platform`, as the compact index spells a version.
+pub fn compact_version(spec: &GemSpec) -> String {
+    match spec.platform.as_deref() {
+        Some(platform) if !platform.is_empty() && platform != "ruby" => {
+            format!("{}-{}", spec.version, platform)
+        }
+        _ => spec.version.clone(),
+    }
+}
+
+/// Compact-index form of a requirement: `">= 1.0, < 2"` becomes `">= 1.0&< 2"`.
+fn compact_requirement(requirements: &str) -> String {
+    let parts: Vec<&str> = requirements
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.is_empty() {
+        ">= 0".to_string()
+    } else {
+        parts.join("&")
+    }
+}
+
+/// One `info/<gem>` line:
+/// `VERSION[-PLATFORM] DEP:REQ&REQ,DEP:REQ|checksum:SHA256[,ruby:REQ][,rubygems:REQ]`.
+/// Development dependencies are left out, as on rubygems.org.
+pub fn compact_info_line(spec: &GemSpec, sha256: &str) -> String {
+    let deps = spec
+        .dependencies
+        .iter()
+        .flatten()
+        .filter(|d| d.dep_type.is_empty() || d.dep_type == "runtime")
+        .map(|d| format!("{}:{}", d.name, compact_requirement(&d.requirements)))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut line = format!("{} {}|checksum:{}", compact_version(spec), deps, sha256);
+    if let Some(ruby) = &spec.required_ruby_version {
+        line.push_str(&format!(",ruby:{}", compact_requirement(ruby)));
+    }
+    if let Some(rubygems) = &spec.required_rubygems_version {
+        line.push_str(&format!(",rubygems:{}", compact_requirement(rubygems)));
+    }
+    line
+}
+
+/// Append `lines` to `body`, one per line.
+fn push_lines(mut body: String, lines: &[String]) -> String {
+    for line in lines {
+        body.push_str(line);
+        body.push('\n');
+    }
+    body
+}
+
+/// Body of an `info/<gem>` file from its lines, oldest version first.
+pub fn compact_info(lines: &[String]) -> String {
+    push_lines(String::from("---\n"), lines)
+}
+
+/// One `versions` line: `NAME V1,V2 INFO_MD5`.
+pub fn compact_versions_line(name: &str, versions: &[String], info: &str) -> String {
+    use md5::{Digest, Md5};
+    format!(
+        "{} {} {:x}",
+        name,
+        versions.join(","),
+        Md5::digest(info.as_bytes())
+    )
+}
+
+/// Body of the `versions` file. `created_at` is an RFC 3339 timestamp.
+pub fn compact_versions(created_at: &str, lines: &[String]) -> String {
+    push_lines(format!("created_at: {}\n---\n", created_at), lines)
+}
+
+/// Body of the `names` file.
+pub fn compact_names(names: &[String]) -> String {
+    push_lines(String::from("---\n"), names)
+}
+
 // ---------------------------------------------------------------------------
 // Ruby Marshal 4.8 encoding for the legacy specs index
 // ---------------------------------------------------------------------------
@@ -1813,6 +1943,85 @@ summary: A summary
         assert_eq!(spec.version, "4.5.6");
     }
 
+    // ========================================================================
+    // Compact index tests
+    // ========================================================================
+
+    #[test]
+    fn test_parse_gemspec_platform_requirements() {
+        let yaml = r#"--- !ruby/object:Gem::Specification
+name: dtf-app
+version: !ruby/object:Gem::Version
+  version: 1.0.0
+required_ruby_version: !ruby/object:Gem::Requirement
+  requirements:
+  - - ">="
+    - !ruby/object:Gem::Version
+      version: 3.0.0
+  - - "<"
+    - !ruby/object:Gem::Version
+      version: '4'
+required_rubygems_version: !ruby/object:Gem::Requirement
+  requirements:
+  - - ">="
+    - !ruby/object:Gem::Version
+      version: '0'
+"#;
+        let spec = RubygemsHandler::parse_gemspec_yaml(yaml).unwrap();
+        assert_eq!(spec.required_ruby_version.as_deref(), Some(">= 3.0.0, < 4"));
+        assert_eq!(spec.required_rubygems_version, None);
+    }
+
+    #[test]
+    fn test_compact_info_line() {
+        let spec = GemSpec {
+            name: "nokogiri".to_string(),
+            version: "1.16.0".to_string(),
+            platform: Some("x86_64-linux".to_string()),
+            required_ruby_version: Some(">= 3.0, < 3.4.dev".to_string()),
+            required_rubygems_version: Some(">= 3.3.22".to_string()),
+            dependencies: Some(vec![
+                GemDependency {
+                    name: "racc".to_string(),
+                    requirements: "~> 1.4".to_string(),
+                    dep_type: "runtime".to_string(),
+                },
+                GemDependency {
+                    name: "rake".to_string(),
+                    requirements: ">= 12.0".to_string(),
+                    dep_type: "development".to_string(),
+                },
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            compact_info_line(&spec, "abc"),
+            "1.16.0-x86_64-linux racc:~> 1.4|checksum:abc,ruby:>= 3.0&< 3.4.dev,rubygems:>= 3.3.22"
+        );
+    }
+
+    #[test]
+    fn test_compact_info_line_without_dependencies() {
+        let spec = GemSpec {
+            name: "rack".to_string(),
+            version: "1.0.0".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(compact_info_line(&spec, "abc"), "1.0.0 |checksum:abc");
+    }
+
+    #[test]
+    fn test_compact_versions_checksums_info_file() {
+        let info = compact_info(&["1.0.0 |checksum:abc".to_string()]);
+        assert_eq!(info, "---\n1.0.0 |checksum:abc\n");
+        let line = compact_versions_line("rack", &["1.0.0".to_string()], &info);
+        assert_eq!(line, "rack 1.0.0 9d3bf8fb8cd44b74ef8659572c034f5f");
+        assert_eq!(
+            compact_versions("2026-01-01T00:00:00Z", &[line]),
+            "created_at: 2026-01-01T00:00:00Z\n---\nrack 1.0.0 9d3bf8fb8cd44b74ef8659572c034f5f\n"
+        );
+    }
+
     // ========================================================================
     // generate_gem_info tests
     // ========================================================================
@@ -1831,6 +2040,7 @@ summary: A summary
             license: Some("MIT".to_string()),
             licenses: Some(vec!["MIT".to_string()]),
             required_ruby_version: None,
+            required_rubygems_version: None,
             dependencies: None,
         };
         let info = generate_gem_info(&gemspec, "sha256hash", 100);
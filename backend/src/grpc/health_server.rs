@@ -0,0 +1,268 @@
+//! Standard `grpc.health.v1` health service.
+//!
+//! Registered without the auth interceptor so Kubernetes gRPC probes,
+//! service meshes and `grpcurl` can check the endpoint without a token. The
+//! reported status only reveals whether the backend can reach its database;
+//! it carries no data.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::Stream;
+use sqlx::PgPool;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tonic::server::NamedService;
+use tonic::{Request, Response, Status};
+
+use super::generated::{
+    cve_history_service_server::CveHistoryServiceServer, sbom_service_server::SbomServiceServer,
+    security_policy_service_server::SecurityPolicyServiceServer,
+};
+use super::sbom_server::{CveHistoryGrpcServer, SbomGrpcServer, SecurityPolicyGrpcServer};
+
+#[allow(clippy::all)]
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/grpc.health.v1.rs"));
+}
+
+use proto::health_check_response::ServingStatus;
+use proto::health_server::Health;
+use proto::{HealthCheckRequest, HealthCheckResponse};
+
+pub use proto::health_server::HealthServer;
+
+/// Fully-qualified names of the services the health endpoint reports on.
+/// The empty name (whole server) is always accepted as well.
+pub fn served_services() -> Vec<&'static str> {
+    vec![
+        <SbomServiceServer<SbomGrpcServer> as NamedService>::NAME,
+        <CveHistoryServiceServer<CveHistoryGrpcServer> as NamedService>::NAME,
+        <SecurityPolicyServiceServer<SecurityPolicyGrpcServer> as NamedService>::NAME,
+    ]
+}
+
+/// Status for `service`, or `None` when the name is not served here.
+fn status_for(services: &[&str], service: &str, serving: bool) -> Option<ServingStatus> {
+    if !service.is_empty() && !services.contains(&service) {
+        return None;
+    }
+    Some(if serving {
+        ServingStatus::Serving
+    } else {
+        ServingStatus::NotServing
+    })
+}
+
+/// Updates the status reported by [`HealthGrpcServer`].
+#[derive(Clone)]
+pub struct HealthReporter {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl HealthReporter {
+    /// Set whether the server is serving. Logs transitions only.
+    pub fn set_serving(&self, serving: bool) {
+        let changed = self.tx.send_if_modified(|current| {
+            let changed = *current != serving;
+            *current = serving;
+            changed
+        });
+        if changed {
+            if serving {
+                tracing::info!("gRPC health: SERVING");
+            } else {
+                tracing::warn!("gRPC health: NOT_SERVING");
+            }
+        }
+    }
+}
+
+pub struct HealthGrpcServer {
+    services: Arc<Vec<&'static str>>,
+    serving: watch::Receiver<bool>,
+}
+
+/// Create a health service for `services`, initially serving, and the
+/// reporter that updates it.
+pub fn health_service(services: Vec<&'static str>) -> (HealthReporter, HealthGrpcServer) {
+    let (tx, rx) = watch::channel(true);
+    (
+        HealthReporter { tx: Arc::new(tx) },
+        HealthGrpcServer {
+            services: Arc::new(services),
+            serving: rx,
+        },
+    )
+}
+
+type WatchStream = Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Health for HealthGrpcServer {
+    async fn check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        let service = request.into_inner().service;
+        let status = status_for(&self.services, &service, *self.serving.borrow())
+            .ok_or_else(|| Status::not_found(format!("unknown service: {}", service)))?;
+        Ok(Response::new(HealthCheckResponse {
+            status: status as i32,
+        }))
+    }
+
+    type WatchStream = WatchStream;
+
+    async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let service = request.into_inner().service;
+        let services = self.services.clone();
+        let rx = self.serving.clone();
+
+        // Send the current status, then one message per change. Unknown
+        // services get SERVICE_UNKNOWN and the stream stays open, as the
+        // protocol requires. The stream ends when the server shuts down.
+        let stream = futures::stream::unfold((rx, None), move |(mut rx, last)| {
+            let services = services.clone();
+            let service = service.clone();
+            async move {
+                loop {
+                    let status = status_for(&services, &service, *rx.borrow_and_update())
+                        .unwrap_or(ServingStatus::ServiceUnknown);
+                    if last != Some(status) {
+                        let response = HealthCheckResponse {
+                            status: status as i32,
+                        };
+                        return Some((Ok(response), (rx, Some(status))));
+                    }
+                    rx.changed().await.ok()?;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Report NOT_SERVING while the database is unreachable, and from the moment
+/// shutdown starts so load balancers drain the replica before it exits.
+pub fn spawn_db_probe(
+    reporter: HealthReporter,
+    db: PgPool,
+    interval: Duration,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => {
+                    reporter.set_serving(false);
+                    break;
+                }
+                _ = ticker.tick() => {
+                    match sqlx::query("SELECT 1").execute(&db).await {
+                        Ok(_) => reporter.set_serving(true),
+                        Err(e) => {
+                            tracing::warn!("gRPC health probe failed: {}", e);
+                            reporter.set_serving(false);
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[test]
+    fn test_served_services_names() {
+        let services = served_services();
+        assert!(services.contains(&"artifact_keeper.sbom.v1.SbomService"));
+        assert_eq!(services.len(), 3);
+    }
+
+    #[test]
+    fn test_status_for() {
+        let services = ["a.B"];
+        assert_eq!(
+            status_for(&services, "", true),
+            Some(ServingStatus::Serving)
+        );
+        assert_eq!(
+            status_for(&services, "a.B", false),
+            Some(ServingStatus::NotServing)
+        );
+        assert_eq!(status_for(&services, "x.Y", true), None);
+    }
+
+    #[tokio::test]
+    async fn test_check_unknown_service_not_found() {
+        let (_reporter, server) = health_service(vec!["a.B"]);
+        let err = server
+            .check(Request::new(HealthCheckRequest {
+                service: "x.Y".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_check_follows_reporter() {
+        let (reporter, server) = health_service(vec!["a.B"]);
+        let check = |service: &str| {
+            server.check(Request::new(HealthCheckRequest {
+                service: service.to_string(),
+            }))
+        };
+        let status = check("a.B").await.unwrap().into_inner().status;
+        assert_eq!(status, ServingStatus::Serving as i32);
+        reporter.set_serving(false);
+        let status = check("").await.unwrap().into_inner().status;
+        assert_eq!(status, ServingStatus::NotServing as i32);
+    }
+
+    #[tokio::test]
+    async fn test_watch_streams_changes() {
+        let (reporter, server) = health_service(vec!["a.B"]);
+        let mut stream = server
+            .watch(Request::new(HealthCheckRequest {
+                service: "a.B".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.status, ServingStatus::Serving as i32);
+
+        reporter.set_serving(false);
+        let second = stream.next().await.unwrap().unwrap();
+        assert_eq!(second.status, ServingStatus::NotServing as i32);
+
+        drop(reporter);
+        drop(server);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_watch_unknown_service() {
+        let (_reporter, server) = health_service(vec!["a.B"]);
+        let mut stream = server
+            .watch(Request::new(HealthCheckRequest {
+                service: "x.Y".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.status, ServingStatus::ServiceUnknown as i32);
+    }
+}
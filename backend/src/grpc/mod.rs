@@ -1,6 +1,7 @@
 //! gRPC service implementations.
 
 pub mod auth_interceptor;
+pub mod health_server;
 pub mod sbom_server;
 
 #[allow(clippy::all)]
@@ -9,6 +9,7 @@ pub mod api;
 pub mod build_info;
 pub mod cli;
 pub mod config;
+pub mod config_reload;
 pub mod db;
 pub mod error;
 pub mod formats;
@@ -16,6 +17,7 @@ pub mod grpc;
 pub mod migration_repair;
 pub mod models;
 pub mod services;
+pub mod startup_check;
 pub mod storage;
 pub mod telemetry;
 pub mod util;
@@ -45,13 +45,14 @@ use artifact_keeper_backend::{
     },
     services::{
         auth_service::AuthService,
-        cache_invalidation,
+        bootstrap_service, cache_invalidation,
         dependency_track_service::DependencyTrackService,
         metrics_service,
         opensearch_service::OpenSearchService,
         plugin_registry::PluginRegistry,
         proxy_service::ProxyService,
         scan_config_service::ScanConfigService,
+        scan_queue_service,
         scan_result_service::ScanResultService,
         scanner_service::{AdvisoryClient, ScannerService},
         scheduler_service,
@@ -96,6 +97,45 @@ async fn shutdown_signal() {
     }
 }
 
+/// Reload the runtime-tunable configuration on every SIGHUP until shutdown.
+/// A reload that fails leaves the previous settings in place.
+#[cfg(unix)]
+fn spawn_config_reload_on_sighup(state: api::SharedState, shutdown: CancellationToken) {
+    use artifact_keeper_backend::{config_reload, services::audit_service};
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            tracing::warn!("SIGHUP configuration reload unavailable: {}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                received = hangup.recv() => {
+                    if received.is_none() {
+                        break;
+                    }
+                }
+            }
+            tracing::info!("received SIGHUP, reloading configuration");
+            match config_reload::reload(state.scanner_service.as_deref()) {
+                Ok(report) => {
+                    audit_service::audit_fire_and_forget(
+                        state.db.clone(),
+                        report.audit_entry().system_actor("system:sighup"),
+                    )
+                    .await;
+                }
+                Err(e) => tracing::error!("Configuration reload failed: {}", e),
+            }
+        }
+    });
+}
+
 /// Core server logic extracted so it can be called from both the normal entrypoint
 /// and the Windows Service entrypoint with an externally-managed shutdown token.
 pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()> {
@@ -125,12 +165,7 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         }
     };
 
-    // Load environment variables
-    if let Ok(env_file) = std::env::var("AK_ENV_FILE") {
-        dotenvy::from_path(&env_file).ok();
-    } else {
-        dotenvy::dotenv().ok();
-    }
+    load_env_file();
 
     // Initialize tracing (with optional OpenTelemetry OTLP export).
     // Read OTel config directly from env since Config::from_env() might fail
@@ -155,6 +190,7 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
 
     // Load configuration
     let config = Config::from_env()?;
+    artifact_keeper_backend::config_reload::init(&config);
 
     // Log active allocator
     #[cfg(all(feature = "jemalloc", not(target_os = "windows")))]
@@ -221,7 +257,14 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
     }
 
     // Provision admin user on first boot; returns true when setup lock is needed
-    let setup_required = provision_admin_user(&db_pool, &config.storage_path).await?;
+    let setup_required =
+        provision_admin_user(&db_pool, &config.storage_path, config.setup_wizard).await?;
+
+    // Base URL recorded by a completed setup wizard; `AK_EXTERNAL_URL` still
+    // takes precedence when set.
+    if let Some(base_url) = bootstrap_service::stored_base_url(&db_pool).await? {
+        api::extractors::set_stored_external_url(&base_url);
+    }
 
     // Log loudly at WARN level when setup is still required so log-based
     // alerting and SIEM rules can surface "this server has not had its
@@ -233,7 +276,7 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
     if setup_required {
         tracing::warn!(
             event = "setup_required",
-            "Default admin password has not been changed. API mutations are gated by the setup middleware until the change-password flow runs. See the deployment documentation for credential bootstrap details."
+            "Initial setup is not complete (default admin password unchanged, or setup wizard pending). API mutations are gated by the setup middleware until the change-password or bootstrap flow runs. See the deployment documentation for credential bootstrap details."
         );
     }
 
@@ -391,11 +434,31 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         }
     }
 
+    // Bucket / container sharding (S3_SHARD_BUCKETS, AZURE_SHARD_CONTAINERS).
+    // A sharded backend replaces the single-bucket one under the same name.
+    let s3_shards = artifact_keeper_backend::storage::sharded::s3_from_env()
+        .await?
+        .map(Arc::new);
+    let azure_shards = artifact_keeper_backend::storage::sharded::azure_from_env()
+        .await?
+        .map(Arc::new);
+    for (name, sharded) in [("s3", &s3_shards), ("azure", &azure_shards)] {
+        if let Some(sharded) = sharded {
+            tracing::info!(
+                backend = name,
+                shards = ?sharded.shard_names(),
+                "Sharded storage backend initialized"
+            );
+        }
+    }
+
     // Create primary storage backend based on STORAGE_BACKEND config
     let primary_storage: Arc<dyn artifact_keeper_backend::storage::StorageBackend> = match config
         .storage_backend
         .as_str()
     {
+        "s3" if s3_shards.is_some() => s3_shards.clone().expect("checked above"),
+        "azure" if azure_shards.is_some() => azure_shards.clone().expect("checked above"),
         "s3" => {
             let s3 = artifact_keeper_backend::storage::s3::S3Backend::from_env().await?;
             tracing::info!("S3 storage backend initialized");
@@ -414,6 +477,10 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
                      this is fixed (issue #981)"
                 ),
             }
+            // Unlike connectivity, a CMK the service cannot use is a hard
+            // configuration error: every write would fail or land unencrypted
+            // under a mandatory-encryption policy.
+            s3.verify_encryption().await?;
             Arc::new(s3)
         }
         "azure" => {
@@ -442,6 +509,18 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         }
     };
 
+    // Client-side envelope encryption. A configured but unusable key provider
+    // refuses to start rather than writing plaintext.
+    let storage_encryption =
+        artifact_keeper_backend::storage::encryption::EnvelopeEncryption::from_env()?;
+    if let Some(ref encryption) = storage_encryption {
+        tracing::info!(
+            provider = encryption.provider(),
+            allow_plaintext = encryption.allow_plaintext(),
+            "Storage encryption enabled"
+        );
+    }
+
     // Build the storage registry for per-repo backend routing.
     // The registry maps backend names to initialized StorageBackend instances.
     // "filesystem" is always available (handled dynamically by the registry).
@@ -459,13 +538,13 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
 
         // Try to register additional backends if credentials are available and
         // they are not already the primary backend.
-        if config.storage_backend != "s3" {
+        if config.storage_backend != "s3" && s3_shards.is_none() {
             if let Ok(s3) = artifact_keeper_backend::storage::s3::S3Backend::from_env().await {
                 tracing::info!("Additional S3 storage backend registered");
                 backends.insert("s3".to_string(), Arc::new(s3));
             }
         }
-        if config.storage_backend != "azure" {
+        if config.storage_backend != "azure" && azure_shards.is_none() {
             if let Ok(azure_cfg) = artifact_keeper_backend::storage::azure::AzureConfig::from_env()
             {
                 if let Ok(azure) =
@@ -490,15 +569,76 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         let available: Vec<String> = {
             let mut names = vec!["filesystem".to_string()];
             names.extend(backends.keys().cloned());
+            for (name, sharded) in [("s3", &s3_shards), ("azure", &azure_shards)] {
+                if sharded.is_some() && !backends.contains_key(name) {
+                    names.push(name.to_string());
+                }
+            }
             names
         };
         tracing::info!("Storage backends available: {:?}", available);
 
-        Arc::new(artifact_keeper_backend::storage::StorageRegistry::new(
+        let mut registry = artifact_keeper_backend::storage::StorageRegistry::new(
             backends,
             config.storage_backend.clone(),
-        ))
+        );
+        for (name, sharded) in [("s3", &s3_shards), ("azure", &azure_shards)] {
+            if let Some(sharded) = sharded {
+                registry = registry.with_sharded_backend(name, sharded.clone());
+            }
+        }
+        // Storage dedup: filesystem repositories share one CAS root beside
+        // their per-repository directories. The leading dot keeps it clear of
+        // repository keys, which cannot start with one.
+        let registry = if config.storage_dedup_enabled {
+            tracing::info!("Storage dedup enabled: generic uploads are stored once per backend");
+            registry.with_filesystem_cas_root(format!("{}/.cas", config.storage_path))
+        } else {
+            registry
+        };
+        match storage_encryption {
+            Some(ref encryption) => Arc::new(registry.with_encryption(encryption.clone())),
+            None => Arc::new(registry),
+        }
     };
+    // The registry holds the raw primary handle; everything else reads and
+    // writes through the encrypting one.
+    let primary_storage = storage_registry.encrypt(primary_storage);
+
+    // Verify storage, scanners, SSO metadata and webhooks now rather than at
+    // first use. STARTUP_VALIDATION=warn logs failures without aborting.
+    let validation = artifact_keeper_backend::startup_check::StartupValidation::from_env()?;
+    if validation != artifact_keeper_backend::startup_check::StartupValidation::Off {
+        let report = artifact_keeper_backend::startup_check::validate_startup(
+            &config,
+            &db_pool,
+            primary_storage.as_ref(),
+        )
+        .await;
+        report.log();
+        if report.has_errors()
+            && validation == artifact_keeper_backend::startup_check::StartupValidation::Enforce
+        {
+            return Err(artifact_keeper_backend::error::AppError::Config(
+                report.failure_summary(),
+            ));
+        }
+    }
+
+    // Load WORM retention policies before anything writes through the
+    // registry; the scheduler keeps them current afterwards.
+    match artifact_keeper_backend::services::worm_service::refresh_registry(
+        &db_pool,
+        &storage_registry,
+    )
+    .await
+    {
+        Ok(count) if count > 0 => {
+            tracing::info!("Loaded {} WORM retention policies", count);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to load WORM retention policies: {}", e),
+    }
 
     // One-shot backfill of oci_manifest_refs for index manifests that
     // pre-date migration 092 (artifact-keeper#1179). Runs after the
@@ -681,8 +821,6 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         scanner_service.set_dependency_track(dt.clone());
     }
 
-    let scanner_service = Arc::new(scanner_service);
-
     // Create application state with WASM plugin support
     let scheduler_storage = primary_storage.clone();
     let mut app_state = api::AppState::with_wasm_plugins(
@@ -693,7 +831,12 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         plugin_registry,
         wasm_plugin_service,
     );
-    app_state.set_scanner_service(scanner_service);
+    scanner_service.set_event_bus(app_state.event_bus.clone());
+    let scanner_service = Arc::new(scanner_service);
+    app_state.set_scanner_service(scanner_service.clone());
+
+    // Scan triggers enqueue jobs; this worker pool drains them by priority.
+    scan_queue_service::start_workers(db_pool.clone(), scanner_service);
 
     // Initialize quality check service for health scoring and quality gates
     let quality_check_service = Arc::new(
@@ -709,11 +852,39 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         app_state.set_dependency_track(dt);
     }
 
+    // External authorization hook. A configured but malformed hook refuses to
+    // start rather than silently dropping the org policy layer.
+    if let Some(hook) =
+        artifact_keeper_backend::services::external_authz_service::ExternalAuthzService::from_env()
+    {
+        let hook = Arc::new(hook?);
+        tracing::info!(
+            url = %hook.config().url,
+            kind = ?hook.config().kind,
+            fail_open = hook.config().fail_open,
+            "External authorization hook enabled"
+        );
+        let sync_hook = hook.clone();
+        let sync_db = db_pool.clone();
+        tokio::spawn(async move {
+            match sync_hook.sync_policies(&sync_db).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Synced {} authorization policies to OPA", n),
+                Err(e) => tracing::warn!("Failed to sync authorization policies: {}", e),
+            }
+        });
+        app_state.set_external_authz(hook);
+    }
+
     app_state.set_metrics_handle(metrics_handle);
 
     // Initialize proxy service for remote repository caching
     match StorageService::from_config(&config).await {
         Ok(storage_svc) => {
+            let storage_svc = match storage_encryption {
+                Some(ref encryption) => storage_svc.with_encryption(encryption.clone()),
+                None => storage_svc,
+            };
             let proxy_service = Arc::new(ProxyService::new(db_pool.clone(), Arc::new(storage_svc)));
             app_state.set_proxy_service(proxy_service);
             tracing::info!("Proxy service initialized for remote repositories");
@@ -836,6 +1007,29 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
     );
     tracing::info!("Email dispatcher started");
 
+    // Start saved-search evaluator: checks each uploaded or scanned artifact
+    // against users' notifying saved searches and records match alerts.
+    artifact_keeper_backend::services::saved_search_service::start_evaluator(
+        app_state.event_bus.clone(),
+        app_state.db.clone(),
+    );
+
+    // Start vulnerability watchlist evaluator: matches each uploaded or
+    // scanned artifact against watched advisories and packages.
+    artifact_keeper_backend::services::watchlist_service::start_evaluator(
+        app_state.event_bus.clone(),
+        app_state.db.clone(),
+    );
+    tracing::info!("Saved search evaluator started");
+
+    // Start chat dispatcher: posts events to Slack / Teams integrations whose
+    // routing rules match. Idle when no chat integrations are configured.
+    artifact_keeper_backend::services::chat_integration_service::start_chat_dispatcher(
+        app_state.event_bus.clone(),
+        app_state.db.clone(),
+    );
+    tracing::info!("Chat dispatcher started");
+
     // Start webhooks v2 producer: subscribes to EventBus and enqueues rows
     // into webhook_deliveries. The retry scheduler (every 30s) drives
     // actual HTTP delivery. See backend/src/services/webhook_producer.rs.
@@ -864,6 +1058,11 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         .store(setup_required, std::sync::atomic::Ordering::Relaxed);
     let state = Arc::new(app_state);
 
+    // Log filter, rate limits, scanner endpoints, demo mode and the quota
+    // warning threshold reload on SIGHUP (or POST /api/v1/admin/config/reload).
+    #[cfg(unix)]
+    spawn_config_reload_on_sighup(state.clone(), runtime_shutdown_token.clone());
+
     // Fan out authorization-cache and npm computed-packument invalidations
     // from other replicas via Postgres LISTEN/NOTIFY (migration 142 triggers
     // + services/cache_invalidation.rs; the packument event is emitted
@@ -905,6 +1104,37 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         state.event_bus.clone(),
     );
 
+    // Optional push of per-repository business metrics to a Prometheus
+    // remote-write receiver (AK_METRICS_REMOTE_WRITE_URL); no-op when unset.
+    artifact_keeper_backend::services::metrics_remote_write::start_exporter(db_pool.clone());
+
+    // Edge nodes forward their metrics and recent warnings to the primary
+    // (AK_EDGE_PRIMARY_URL); no-op when unset.
+    artifact_keeper_backend::services::edge_diagnostics_service::start_forwarder(
+        state.metrics_handle.clone(),
+    );
+
+    // Periodic re-hash of stored blobs with purge of corrupted entries; on by
+    // default for edge nodes (AK_EDGE_PRIMARY_URL), see AK_CACHE_INTEGRITY_*.
+    artifact_keeper_backend::services::cache_integrity_service::start_verifier(
+        db_pool.clone(),
+        state.storage_registry.clone(),
+        state.proxy_service.clone(),
+    );
+
+    // Load (or generate on first start) the Ed25519 identity this instance
+    // presents to federated peers. The sync worker mints peer tokens from it.
+    if let Err(e) = artifact_keeper_backend::services::federation_service::init(
+        &db_pool,
+        &config.jwt_secret,
+        &config.peer_instance_name,
+        &config.peer_public_endpoint,
+    )
+    .await
+    {
+        tracing::warn!("Federation identity unavailable: {}", e);
+    }
+
     // Keep a handle for the gRPC server before the sync worker consumes db_pool
     let grpc_db_pool = db_pool.clone();
 
@@ -1050,7 +1280,7 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
     // password reset / TOTP change on a peer replica.
     let grpc_auth = artifact_keeper_backend::grpc::auth_interceptor::AuthInterceptor::new(
         &config.jwt_secret,
-        Some(grpc_db_pool),
+        Some(grpc_db_pool.clone()),
     );
 
     // Info-disclosure hardening (#2226): gRPC server reflection lets an
@@ -1060,6 +1290,26 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
     // interceptor either way.
     let grpc_reflection_enabled = config.grpc_reflection_enabled;
 
+    // grpc.health.v1 for probes and meshes. Unauthenticated by design: it
+    // only reports whether this replica can reach its database, and flips
+    // to NOT_SERVING once shutdown starts.
+    let (grpc_health_reporter, grpc_health_server) =
+        artifact_keeper_backend::grpc::health_server::health_service(
+            artifact_keeper_backend::grpc::health_server::served_services(),
+        );
+    artifact_keeper_backend::grpc::health_server::spawn_db_probe(
+        grpc_health_reporter,
+        grpc_db_pool,
+        std::time::Duration::from_secs(config.grpc_health_probe_interval_secs),
+        shutdown_token.clone(),
+    );
+
+    // 0 disables a keepalive setting.
+    let keepalive = |secs: u64| (secs > 0).then(|| std::time::Duration::from_secs(secs));
+    let grpc_keepalive_interval = keepalive(config.grpc_keepalive_interval_secs);
+    let grpc_keepalive_timeout = keepalive(config.grpc_keepalive_timeout_secs);
+    let grpc_tcp_keepalive = keepalive(config.grpc_tcp_keepalive_secs);
+
     let grpc_auth_sbom = grpc_auth.clone();
     let grpc_auth_cve = grpc_auth.clone();
     let grpc_auth_policy = grpc_auth;
@@ -1073,6 +1323,12 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
         #[allow(clippy::result_large_err)]
         let policy_interceptor = move |req| grpc_auth_policy.intercept(req);
         let mut server = TonicServer::builder()
+            .http2_keepalive_interval(grpc_keepalive_interval)
+            .http2_keepalive_timeout(grpc_keepalive_timeout)
+            .tcp_keepalive(grpc_tcp_keepalive)
+            .add_service(
+                artifact_keeper_backend::grpc::health_server::HealthServer::new(grpc_health_server),
+            )
             .add_service(SbomServiceServer::with_interceptor(
                 sbom_server,
                 sbom_interceptor,
@@ -1086,15 +1342,25 @@ pub async fn run_server(shutdown_token: Option<CancellationToken>) -> Result<()>
                 policy_interceptor,
             ));
         if grpc_reflection_enabled {
-            // Include file descriptor for gRPC reflection
+            // Include file descriptors for gRPC reflection. Both protocol
+            // versions are served: v1alpha is still the only one older
+            // grpcurl and mesh tooling speak.
+            let sbom_descriptor = include_bytes!(concat!(env!("OUT_DIR"), "/sbom_descriptor.bin"));
+            let health_descriptor =
+                include_bytes!(concat!(env!("OUT_DIR"), "/health_descriptor.bin"));
             let reflection_service = tonic_reflection::server::Builder::configure()
-                .register_encoded_file_descriptor_set(include_bytes!(concat!(
-                    env!("OUT_DIR"),
-                    "/sbom_descriptor.bin"
-                )))
+                .register_encoded_file_descriptor_set(sbom_descriptor)
+                .register_encoded_file_descriptor_set(health_descriptor)
                 .build_v1()
                 .expect("Failed to build reflection service");
-            server = server.add_service(reflection_service);
+            let reflection_service_v1alpha = tonic_reflection::server::Builder::configure()
+                .register_encoded_file_descriptor_set(sbom_descriptor)
+                .register_encoded_file_descriptor_set(health_descriptor)
+                .build_v1alpha()
+                .expect("Failed to build reflection service");
+            server = server
+                .add_service(reflection_service)
+                .add_service(reflection_service_v1alpha);
             tracing::info!("gRPC server reflection enabled");
         }
         if let Err(e) = server
@@ -1257,9 +1523,45 @@ async fn assert_connect_info_wired(app: Router) {
 // Platform-specific entrypoints
 // ---------------------------------------------------------------------------
 
+/// Load environment variables from `AK_ENV_FILE`, or `.env` when unset.
+fn load_env_file() {
+    if let Ok(env_file) = std::env::var("AK_ENV_FILE") {
+        dotenvy::from_path(&env_file).ok();
+    } else {
+        dotenvy::dotenv().ok();
+    }
+}
+
+/// `--check-config`: validate the configuration, print the report and exit
+/// without serving. Exits with status 1 when any check failed.
+async fn run_config_check(json: bool) -> Result<()> {
+    rustls::crypto::ring::default_provider()
+        .install_default()
+        .expect("Failed to install rustls CryptoProvider");
+    load_env_file();
+
+    let report = artifact_keeper_backend::startup_check::check_config().await;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("report serializes")
+        );
+    } else {
+        print!("{}", report.render_text());
+    }
+    if report.has_errors() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 #[cfg(not(windows))]
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--check-config") {
+        return run_config_check(args.iter().any(|a| a == "--json")).await;
+    }
     run_server(None).await
 }
 
@@ -1290,6 +1592,9 @@ fn main() -> Result<()> {
             .enable_all()
             .build()
             .expect("Failed to create tokio runtime");
+        if args.iter().any(|a| a == "--check-config") {
+            return runtime.block_on(run_config_check(args.iter().any(|a| a == "--json")));
+        }
         runtime.block_on(run_server(None))
     }
 }
@@ -1741,7 +2046,11 @@ fn build_ldap_request_from_values(
 /// Uses a PostgreSQL advisory lock to prevent race conditions when multiple
 /// replicas start simultaneously.  The lock is held for the duration of the
 /// check-and-create sequence so only one replica performs the initial insert.
-async fn provision_admin_user(db: &sqlx::PgPool, storage_path: &str) -> Result<bool> {
+async fn provision_admin_user(
+    db: &sqlx::PgPool,
+    storage_path: &str,
+    setup_wizard: bool,
+) -> Result<bool> {
     use std::path::Path;
 
     // Skip admin provisioning when SSO handles admin assignment (issue #211)
@@ -1925,6 +2234,21 @@ async fn provision_admin_user(db: &sqlx::PgPool, storage_path: &str) -> Result<b
 
     // --- No admin user exists yet: create one. ---
 
+    // With the setup wizard enabled and no ADMIN_PASSWORD supplied, an empty
+    // install gets a one-time bootstrap token instead of a generated admin
+    // password; the first admin is created through POST /api/v1/setup/bootstrap.
+    // A token reissued on every restart while setup is pending replaces the
+    // previous one, so a lost log line is recovered by restarting.
+    let env_password_set = std::env::var("ADMIN_PASSWORD").is_ok_and(|p| !p.is_empty());
+    if setup_wizard && !env_password_set {
+        let token = bootstrap_service::issue_token(&mut tx).await?;
+        tx.commit()
+            .await
+            .map_err(|e| artifact_keeper_backend::error::AppError::Database(e.to_string()))?;
+        bootstrap_service::log_bootstrap_banner(&token);
+        return Ok(true);
+    }
+
     let (password, must_change) = match std::env::var("ADMIN_PASSWORD") {
         Ok(p) if !p.is_empty() => {
             if is_insecure_default_password(&p) && !demo_mode {
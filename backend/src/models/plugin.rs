@@ -114,6 +114,10 @@ pub struct PluginCapabilities {
     /// Plugin can handle native protocol HTTP requests (v2 WIT)
     #[serde(default)]
     pub handle_request: bool,
+    /// Plugin exports an OpenAPI fragment of the endpoints it serves
+    /// (`format-plugin-v2-openapi` WIT world)
+    #[serde(default)]
+    pub openapi_fragment: bool,
 }
 
 impl Default for PluginCapabilities {
@@ -123,6 +127,7 @@ impl Default for PluginCapabilities {
             generate_index: false,
             validate_artifact: true,
             handle_request: false,
+            openapi_fragment: false,
         }
     }
 }
@@ -228,6 +233,7 @@ mod tests {
             generate_index: true,
             validate_artifact: false,
             handle_request: false,
+            openapi_fragment: false,
         };
         let json = serde_json::to_string(&caps).unwrap();
         let deserialized: PluginCapabilities = serde_json::from_str(&json).unwrap();
@@ -244,6 +250,7 @@ mod tests {
             generate_index: true,
             validate_artifact: true,
             handle_request: true,
+            openapi_fragment: false,
         };
         let json = serde_json::to_string(&caps).unwrap();
         assert!(json.contains("handle_request"));
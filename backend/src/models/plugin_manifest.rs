@@ -65,6 +65,10 @@ pub struct CapabilitiesConfig {
     /// Plugin can handle native protocol HTTP requests (v2 WIT)
     #[serde(default)]
     pub handle_request: bool,
+    /// Plugin exports an OpenAPI fragment of its endpoints (requires
+    /// `handle_request`)
+    #[serde(default)]
+    pub openapi_fragment: bool,
 }
 
 impl Default for CapabilitiesConfig {
@@ -74,6 +78,7 @@ impl Default for CapabilitiesConfig {
             generate_index: false,
             validate_artifact: true,
             handle_request: false,
+            openapi_fragment: false,
         }
     }
 }
@@ -156,6 +161,11 @@ impl PluginManifest {
             }
         }
 
+        // The fragment describes endpoints served through handle_request
+        if self.capabilities.openapi_fragment && !self.capabilities.handle_request {
+            return Err(ManifestValidationError::OpenApiWithoutHandleRequest);
+        }
+
         // Validate resource limits
         if self.requirements.max_memory_mb < self.requirements.min_memory_mb {
             return Err(ManifestValidationError::InvalidMemoryLimits {
@@ -180,6 +190,7 @@ impl PluginManifest {
             generate_index: self.capabilities.generate_index,
             validate_artifact: self.capabilities.validate_artifact,
             handle_request: self.capabilities.handle_request,
+            openapi_fragment: self.capabilities.openapi_fragment,
         }
     }
 
@@ -213,6 +224,9 @@ pub enum ManifestValidationError {
 
     #[error("Invalid timeout {0}: must be between 1 and 300 seconds")]
     InvalidTimeout(u32),
+
+    #[error("openapi_fragment requires the handle_request capability")]
+    OpenApiWithoutHandleRequest,
 }
 
 /// Check if a string is a valid identifier (lowercase, hyphens, starts with letter).
@@ -455,6 +469,36 @@ timeout_secs = 301
         ));
     }
 
+    #[test]
+    fn test_validate_openapi_fragment_requires_handle_request() {
+        let toml = r#"
+[plugin]
+name = "valid-name"
+version = "1.0.0"
+
+[capabilities]
+openapi_fragment = true
+"#;
+        let manifest = PluginManifest::from_toml(toml).unwrap();
+        assert!(matches!(
+            manifest.validate(),
+            Err(ManifestValidationError::OpenApiWithoutHandleRequest)
+        ));
+
+        let toml = r#"
+[plugin]
+name = "valid-name"
+version = "1.0.0"
+
+[capabilities]
+handle_request = true
+openapi_fragment = true
+"#;
+        let manifest = PluginManifest::from_toml(toml).unwrap();
+        assert!(manifest.validate().is_ok());
+        assert!(manifest.to_capabilities().openapi_fragment);
+    }
+
     #[test]
     fn test_validate_timeout_boundary_300() {
         let toml = r#"
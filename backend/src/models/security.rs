@@ -230,6 +230,11 @@ pub struct ScanPolicy {
     pub id: Uuid,
     pub name: String,
     pub repository_id: Option<Uuid>,
+    /// Project scope. Never set together with `repository_id`; a policy with
+    /// neither is an instance default.
+    pub project_id: Option<Uuid>,
+    /// Merge with the less specific layers (`true`) or override them.
+    pub inherit: bool,
     pub max_severity: String,
     pub block_unscanned: bool,
     pub block_on_fail: bool,
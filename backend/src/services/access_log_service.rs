@@ -0,0 +1,604 @@
+//! Protocol access log for native format endpoints.
+//!
+//! The access log middleware hands every request to a format endpoint to
+//! [`record_access`], which buffers it in memory together with the client
+//! tool parsed from the User-Agent (`npm/10.2.4 node/v20.11.0` -> `npm`
+//! `10.2.4`). The scheduler flushes the buffer into `protocol_access_log`
+//! every few seconds, so the request path never waits on the database, and
+//! prunes rows older than `ACCESS_LOG_RETENTION_DAYS`.
+//!
+//! The log backs `GET /api/v1/admin/access-log`, for debugging what a given
+//! client actually asked for, and the per-client breakdown in analytics.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::Result;
+
+/// Requests buffered between flushes. Further requests are dropped from the
+/// log (and counted) until the next flush.
+const MAX_PENDING_ENTRIES: usize = 50_000;
+
+/// Rows written per INSERT.
+const FLUSH_BATCH: usize = 1_000;
+
+/// Longest client tool name or version kept from a User-Agent.
+const MAX_CLIENT_FIELD_LEN: usize = 64;
+
+// ---------------------------------------------------------------------------
+// Client tool
+// ---------------------------------------------------------------------------
+
+/// The tool behind a request, parsed from its User-Agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientTool {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// User-Agent product names that differ from the tool's usual name.
+const CLIENT_TOOL_ALIASES: &[(&str, &str)] = &[
+    ("apache-maven", "maven"),
+    ("go-http-client", "go"),
+    ("rubygems", "gem"),
+    ("mozilla", "browser"),
+];
+
+/// Parse the client tool from a User-Agent. The first product token names
+/// the tool (`pip/24.0 {...}`, `docker/26.0.0 go/go1.21.8 ...`,
+/// `Apache-Maven/3.9.6 (Java 17)`); a bare name followed by a version
+/// (`cargo 1.75.0 (...)`) is accepted too.
+pub fn parse_client_tool(user_agent: &str) -> Option<ClientTool> {
+    let mut words = user_agent.split_whitespace();
+    let first = words.next()?;
+    let (name, version) = match first.split_once('/') {
+        Some((name, version)) => (name, Some(version)),
+        None => (
+            first,
+            words
+                .next()
+                .filter(|w| w.starts_with(|c: char| c.is_ascii_digit())),
+        ),
+    };
+
+    let name = name.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    if name.is_empty() || name.len() > MAX_CLIENT_FIELD_LEN {
+        return None;
+    }
+    let name = name.to_ascii_lowercase();
+    let name = CLIENT_TOOL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, tool)| tool.to_string())
+        .unwrap_or(name);
+
+    let version = version
+        .map(|v| v.trim_end_matches([';', ',', ')']))
+        .map(|v| match v.strip_prefix('v') {
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+            _ => v,
+        })
+        .filter(|v| !v.is_empty() && v.len() <= MAX_CLIENT_FIELD_LEN)
+        .map(str::to_string);
+
+    Some(ClientTool { name, version })
+}
+
+// ---------------------------------------------------------------------------
+// Outcome
+// ---------------------------------------------------------------------------
+
+/// How a protocol request ended, derived from its status code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessOutcome {
+    Success,
+    NotModified,
+    Redirect,
+    Denied,
+    NotFound,
+    Throttled,
+    ClientError,
+    ServerError,
+}
+
+impl AccessOutcome {
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            304 => Self::NotModified,
+            300..=399 => Self::Redirect,
+            401 | 403 => Self::Denied,
+            404 | 410 => Self::NotFound,
+            429 => Self::Throttled,
+            400..=499 => Self::ClientError,
+            500..=599 => Self::ServerError,
+            _ => Self::Success,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::NotModified => "not_modified",
+            Self::Redirect => "redirect",
+            Self::Denied => "denied",
+            Self::NotFound => "not_found",
+            Self::Throttled => "throttled",
+            Self::ClientError => "client_error",
+            Self::ServerError => "server_error",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// In-memory buffer
+// ---------------------------------------------------------------------------
+
+/// One request to a format endpoint, as the middleware saw it.
+#[derive(Debug, Clone)]
+pub struct AccessRecord {
+    pub occurred_at: DateTime<Utc>,
+    pub format: String,
+    pub repository_key: Option<String>,
+    pub method: String,
+    /// Request path without the query string, credentials redacted.
+    pub path: String,
+    pub package: Option<String>,
+    pub version: Option<String>,
+    /// Artifact served, when a handler noted one. Its name and version fill
+    /// in `package` and `version` at flush time.
+    pub artifact_id: Option<Uuid>,
+    pub user_agent: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub client_ip: Option<String>,
+    pub status: u16,
+    pub duration_ms: u32,
+    pub correlation_id: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct AccessLogBuffer {
+    entries: Mutex<Vec<AccessRecord>>,
+    dropped: AtomicU64,
+}
+
+impl AccessLogBuffer {
+    pub fn push(&self, record: AccessRecord) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        if entries.len() >= MAX_PENDING_ENTRIES {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        entries.push(record);
+    }
+
+    /// Take every buffered record and the number dropped since the last drain.
+    pub fn drain(&self) -> (Vec<AccessRecord>, u64) {
+        let entries = match self.entries.lock() {
+            Ok(mut entries) => std::mem::take(&mut *entries),
+            Err(_) => Vec::new(),
+        };
+        (entries, self.dropped.swap(0, Ordering::Relaxed))
+    }
+}
+
+static PENDING_ACCESS: Lazy<AccessLogBuffer> = Lazy::new(AccessLogBuffer::default);
+
+/// Log one protocol request. Also emitted as an `access_log` tracing event
+/// at debug level, so `RUST_LOG=access_log=debug` streams the same fields.
+pub fn record_access(record: AccessRecord) {
+    if tracing::enabled!(target: "access_log", tracing::Level::DEBUG) {
+        trace_access(&record);
+    }
+    PENDING_ACCESS.push(record);
+}
+
+fn trace_access(record: &AccessRecord) {
+    let client = record.user_agent.as_deref().and_then(parse_client_tool);
+    tracing::debug!(
+        target: "access_log",
+        format = %record.format,
+        repository = record.repository_key.as_deref().unwrap_or(""),
+        method = %record.method,
+        path = %record.path,
+        package = record.package.as_deref().unwrap_or(""),
+        version = record.version.as_deref().unwrap_or(""),
+        client_tool = client.as_ref().map_or("", |c| c.name.as_str()),
+        client_version = client.as_ref().and_then(|c| c.version.as_deref()).unwrap_or(""),
+        status = record.status,
+        outcome = AccessOutcome::from_status(record.status).as_str(),
+        duration_ms = record.duration_ms,
+        "protocol request"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// A logged protocol request.
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct AccessLogEntry {
+    pub id: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub format: String,
+    pub repository_key: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub package: Option<String>,
+    pub version: Option<String>,
+    pub artifact_id: Option<Uuid>,
+    pub client_tool: Option<String>,
+    pub client_version: Option<String>,
+    pub user_agent: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub client_ip: Option<String>,
+    pub status: i32,
+    pub outcome: String,
+    pub duration_ms: i32,
+    pub correlation_id: Option<String>,
+}
+
+/// Filters of an access log query. Text filters match exactly, except
+/// `package`, which is case-insensitive.
+#[derive(Debug, Default)]
+pub struct AccessLogFilter<'a> {
+    pub format: Option<&'a str>,
+    pub repository_key: Option<&'a str>,
+    pub package: Option<&'a str>,
+    pub client_tool: Option<&'a str>,
+    pub outcome: Option<AccessOutcome>,
+    pub status: Option<i32>,
+    pub user_id: Option<Uuid>,
+    pub client_ip: Option<&'a str>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Protocol requests of one client tool against one format.
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ClientToolUsage {
+    pub format: String,
+    /// `None` for requests without a recognisable User-Agent.
+    pub client_tool: Option<String>,
+    pub requests: i64,
+    /// Requests answered with a 4xx or 5xx status.
+    pub failed: i64,
+    /// Distinct versions of the tool seen.
+    pub client_versions: i64,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+pub struct AccessLogService {
+    db: PgPool,
+}
+
+impl AccessLogService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Write the buffered requests. Returns how many were written.
+    pub async fn flush_pending(&self) -> Result<usize> {
+        let (records, dropped) = PENDING_ACCESS.drain();
+        if dropped > 0 {
+            tracing::warn!(dropped, "Access log buffer full; requests were not logged");
+        }
+        for batch in records.chunks(FLUSH_BATCH) {
+            self.insert_batch(batch).await?;
+        }
+        Ok(records.len())
+    }
+
+    async fn insert_batch(&self, batch: &[AccessRecord]) -> Result<()> {
+        let mut occurred_at = Vec::with_capacity(batch.len());
+        let mut format = Vec::with_capacity(batch.len());
+        let mut repository_key = Vec::with_capacity(batch.len());
+        let mut method = Vec::with_capacity(batch.len());
+        let mut path = Vec::with_capacity(batch.len());
+        let mut package = Vec::with_capacity(batch.len());
+        let mut version = Vec::with_capacity(batch.len());
+        let mut artifact_id = Vec::with_capacity(batch.len());
+        let mut client_tool = Vec::with_capacity(batch.len());
+        let mut client_version = Vec::with_capacity(batch.len());
+        let mut user_agent = Vec::with_capacity(batch.len());
+        let mut user_id = Vec::with_capacity(batch.len());
+        let mut client_ip = Vec::with_capacity(batch.len());
+        let mut status = Vec::with_capacity(batch.len());
+        let mut outcome = Vec::with_capacity(batch.len());
+        let mut duration_ms = Vec::with_capacity(batch.len());
+        let mut correlation_id = Vec::with_capacity(batch.len());
+
+        for r in batch {
+            let client = r.user_agent.as_deref().and_then(parse_client_tool);
+            occurred_at.push(r.occurred_at);
+            format.push(r.format.clone());
+            repository_key.push(r.repository_key.clone());
+            method.push(r.method.clone());
+            path.push(r.path.clone());
+            package.push(r.package.clone());
+            version.push(r.version.clone());
+            artifact_id.push(r.artifact_id);
+            client_tool.push(client.as_ref().map(|c| c.name.clone()));
+            client_version.push(client.and_then(|c| c.version));
+            user_agent.push(r.user_agent.clone());
+            user_id.push(r.user_id);
+            client_ip.push(r.client_ip.clone());
+            status.push(i32::from(r.status));
+            outcome.push(AccessOutcome::from_status(r.status).as_str().to_string());
+            duration_ms.push(i32::try_from(r.duration_ms).unwrap_or(i32::MAX));
+            correlation_id.push(r.correlation_id.clone());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO protocol_access_log (
+                occurred_at, format, repository_key, method, path, package, version,
+                artifact_id, client_tool, client_version, user_agent, user_id,
+                client_ip, status, outcome, duration_ms, correlation_id
+            )
+            SELECT u.occurred_at, u.format, u.repository_key, u.method, u.path,
+                   COALESCE(u.package, a.name), COALESCE(u.version, a.version),
+                   u.artifact_id, u.client_tool, u.client_version, u.user_agent,
+                   u.user_id, u.client_ip, u.status, u.outcome, u.duration_ms,
+                   u.correlation_id
+            FROM UNNEST(
+                $1::timestamptz[], $2::text[], $3::text[], $4::text[], $5::text[],
+                $6::text[], $7::text[], $8::uuid[], $9::text[], $10::text[],
+                $11::text[], $12::uuid[], $13::text[], $14::int[], $15::text[],
+                $16::int[], $17::text[]
+            ) AS u(
+                occurred_at, format, repository_key, method, path, package, version,
+                artifact_id, client_tool, client_version, user_agent, user_id,
+                client_ip, status, outcome, duration_ms, correlation_id
+            )
+            LEFT JOIN artifacts a ON a.id = u.artifact_id
+            "#,
+        )
+        .bind(&occurred_at)
+        .bind(&format)
+        .bind(&repository_key)
+        .bind(&method)
+        .bind(&path)
+        .bind(&package)
+        .bind(&version)
+        .bind(&artifact_id)
+        .bind(&client_tool)
+        .bind(&client_version)
+        .bind(&user_agent)
+        .bind(&user_id)
+        .bind(&client_ip)
+        .bind(&status)
+        .bind(&outcome)
+        .bind(&duration_ms)
+        .bind(&correlation_id)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Logged requests matching `filter`, newest first, with the total count.
+    pub async fn query(
+        &self,
+        filter: &AccessLogFilter<'_>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<AccessLogEntry>, i64)> {
+        const WHERE: &str = r#"
+            WHERE ($1::text IS NULL OR format = $1)
+              AND ($2::text IS NULL OR repository_key = $2)
+              AND ($3::text IS NULL OR LOWER(package) = LOWER($3))
+              AND ($4::text IS NULL OR client_tool = $4)
+              AND ($5::text IS NULL OR outcome = $5)
+              AND ($6::int IS NULL OR status = $6)
+              AND ($7::uuid IS NULL OR user_id = $7)
+              AND ($8::text IS NULL OR client_ip = $8)
+              AND ($9::timestamptz IS NULL OR occurred_at >= $9)
+              AND ($10::timestamptz IS NULL OR occurred_at <= $10)
+        "#;
+
+        let outcome = filter.outcome.map(AccessOutcome::as_str);
+        let entries = sqlx::query_as::<_, AccessLogEntry>(&format!(
+            r#"
+            SELECT id, occurred_at, format, repository_key, method, path, package,
+                   version, artifact_id, client_tool, client_version, user_agent,
+                   user_id, client_ip, status, outcome, duration_ms, correlation_id
+            FROM protocol_access_log
+            {WHERE}
+            ORDER BY occurred_at DESC, id DESC
+            OFFSET $11 LIMIT $12
+            "#
+        ))
+        .bind(filter.format)
+        .bind(filter.repository_key)
+        .bind(filter.package)
+        .bind(filter.client_tool)
+        .bind(outcome)
+        .bind(filter.status)
+        .bind(filter.user_id)
+        .bind(filter.client_ip)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        let total: i64 =
+            sqlx::query_scalar(&format!("SELECT COUNT(*) FROM protocol_access_log {WHERE}"))
+                .bind(filter.format)
+                .bind(filter.repository_key)
+                .bind(filter.package)
+                .bind(filter.client_tool)
+                .bind(outcome)
+                .bind(filter.status)
+                .bind(filter.user_id)
+                .bind(filter.client_ip)
+                .bind(filter.from)
+                .bind(filter.to)
+                .fetch_one(&self.db)
+                .await?;
+
+        Ok((entries, total))
+    }
+
+    /// Requests per format and client tool in `[from, to]`, busiest first.
+    pub async fn client_tool_usage(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ClientToolUsage>> {
+        let usage = sqlx::query_as::<_, ClientToolUsage>(
+            r#"
+            SELECT format,
+                   client_tool,
+                   COUNT(*) AS requests,
+                   COUNT(*) FILTER (WHERE status >= 400) AS failed,
+                   COUNT(DISTINCT client_version) AS client_versions,
+                   MAX(occurred_at) AS last_seen_at
+            FROM protocol_access_log
+            WHERE occurred_at >= $1 AND occurred_at <= $2
+            GROUP BY format, client_tool
+            ORDER BY requests DESC, format, client_tool
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(usage)
+    }
+
+    /// Delete entries older than `retention_days`. Returns the rows removed.
+    pub async fn prune(&self, retention_days: u32) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM protocol_access_log \
+             WHERE occurred_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(retention_days as i32)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(ua: &str) -> Option<(String, Option<String>)> {
+        parse_client_tool(ua).map(|t| (t.name, t.version))
+    }
+
+    #[test]
+    fn test_parse_client_tool_common_clients() {
+        let cases = [
+            (
+                "npm/10.2.4 node/v20.11.0 linux x64 workspaces/false",
+                "npm",
+                Some("10.2.4"),
+            ),
+            (
+                r#"pip/24.0 {"ci":null,"cpu":"x86_64"}"#,
+                "pip",
+                Some("24.0"),
+            ),
+            (
+                "docker/26.0.0 go/go1.21.8 git-commit/8b79278 kernel/6.5.0 os/linux",
+                "docker",
+                Some("26.0.0"),
+            ),
+            (
+                "Apache-Maven/3.9.6 (Java 17.0.9; Linux 6.5.0)",
+                "maven",
+                Some("3.9.6"),
+            ),
+            (
+                "bundler/2.5.6 rubygems/3.5.6 ruby/3.3.0 (x86_64-linux)",
+                "bundler",
+                Some("2.5.6"),
+            ),
+            (
+                "RubyGems/3.5.6 x86_64-linux Ruby/3.3.0",
+                "gem",
+                Some("3.5.6"),
+            ),
+            ("containerd/v1.7.13", "containerd", Some("1.7.13")),
+            (
+                "cargo 1.75.0 (1d8b05cdd 2023-11-20)",
+                "cargo",
+                Some("1.75.0"),
+            ),
+            (
+                "NuGet Command Line/6.8.0 (Microsoft Windows NT 10.0)",
+                "nuget",
+                None,
+            ),
+        ];
+        for (ua, name, version) in cases {
+            assert_eq!(
+                tool(ua),
+                Some((name.to_string(), version.map(str::to_string))),
+                "{ua}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_client_tool_rejects_empty() {
+        assert_eq!(tool(""), None);
+        assert_eq!(tool("   "), None);
+        assert_eq!(tool("/1.0"), None);
+    }
+
+    #[test]
+    fn test_access_outcome_from_status() {
+        assert_eq!(AccessOutcome::from_status(200), AccessOutcome::Success);
+        assert_eq!(AccessOutcome::from_status(304), AccessOutcome::NotModified);
+        assert_eq!(AccessOutcome::from_status(307), AccessOutcome::Redirect);
+        assert_eq!(AccessOutcome::from_status(401), AccessOutcome::Denied);
+        assert_eq!(AccessOutcome::from_status(404), AccessOutcome::NotFound);
+        assert_eq!(AccessOutcome::from_status(429), AccessOutcome::Throttled);
+        assert_eq!(AccessOutcome::from_status(422), AccessOutcome::ClientError);
+        assert_eq!(AccessOutcome::from_status(502), AccessOutcome::ServerError);
+    }
+
+    #[test]
+    fn test_buffer_caps_pending_entries() {
+        let buffer = AccessLogBuffer::default();
+        let record = AccessRecord {
+            occurred_at: Utc::now(),
+            format: "npm".to_string(),
+            repository_key: Some("npm-public".to_string()),
+            method: "GET".to_string(),
+            path: "/npm/npm-public/lodash".to_string(),
+            package: None,
+            version: None,
+            artifact_id: None,
+            user_agent: None,
+            user_id: None,
+            client_ip: None,
+            status: 200,
+            duration_ms: 3,
+            correlation_id: None,
+        };
+        for _ in 0..MAX_PENDING_ENTRIES + 2 {
+            buffer.push(record.clone());
+        }
+        let (entries, dropped) = buffer.drain();
+        assert_eq!(entries.len(), MAX_PENDING_ENTRIES);
+        assert_eq!(dropped, 2);
+        let (entries, dropped) = buffer.drain();
+        assert!(entries.is_empty());
+        assert_eq!(dropped, 0);
+    }
+}
@@ -0,0 +1,420 @@
+//! Kubernetes validating admission support.
+//!
+//! The API server posts an `admission.k8s.io/v1` `AdmissionReview` for each
+//! pod (or pod-template workload) being created or updated. Every container
+//! image in it must be pulled through this instance (one of the configured
+//! registry hosts), come from an allowed repository, exist there, and pass
+//! the deploy gate (quarantine and scan policies, see
+//! [`crate::services::deploy_gate_service`]). Anything else is denied, which
+//! lets a cluster enforce "only images from our registry".
+//!
+//! Image references map onto the OCI layout: `host/<repo-key>/<image>:<tag>`
+//! is the manifest artifact `v2/<image>/manifests/<tag>` of repository
+//! `<repo-key>`, and a digest-pinned reference matches the manifest by
+//! checksum within that repository.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::services::deploy_gate_service::GateTarget;
+
+pub const ADMISSION_API_VERSION: &str = "admission.k8s.io/v1";
+
+/// Longest message returned to the API server (it is shown to `kubectl`
+/// users and stored in events).
+const MAX_MESSAGE_CHARS: usize = 1024;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionReview {
+    pub api_version: String,
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<AdmissionRequest>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<AdmissionResponse>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GroupVersionKind {
+    #[serde(default)]
+    pub group: String,
+    #[serde(default)]
+    pub version: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionRequest {
+    pub uid: String,
+    pub kind: GroupVersionKind,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// CREATE, UPDATE, DELETE or CONNECT.
+    pub operation: String,
+    #[serde(default)]
+    pub object: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AdmissionStatus {
+    pub code: u16,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AdmissionResponse {
+    pub uid: String,
+    pub allowed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<AdmissionStatus>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl AdmissionReview {
+    /// Wrap a response in a review echoing the request's API version.
+    pub fn respond(api_version: &str, response: AdmissionResponse) -> Self {
+        Self {
+            api_version: api_version.to_string(),
+            kind: "AdmissionReview".to_string(),
+            request: None,
+            response: Some(response),
+        }
+    }
+}
+
+impl AdmissionResponse {
+    pub fn allow(uid: &str) -> Self {
+        Self {
+            uid: uid.to_string(),
+            allowed: true,
+            status: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn deny(uid: &str, reasons: &[String]) -> Self {
+        let mut message = reasons.join("; ");
+        if message.chars().count() > MAX_MESSAGE_CHARS {
+            message = message.chars().take(MAX_MESSAGE_CHARS - 1).collect();
+            message.push('…');
+        }
+        Self {
+            uid: uid.to_string(),
+            allowed: false,
+            status: Some(AdmissionStatus { code: 403, message }),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// Registry, repository and exemption rules, from [`Config`].
+#[derive(Debug, Clone, Default)]
+pub struct AdmissionPolicy {
+    pub registry_hosts: Vec<String>,
+    pub allowed_repositories: Vec<String>,
+    pub exempt_namespaces: Vec<String>,
+}
+
+impl AdmissionPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            registry_hosts: config.admission_registry_hosts.clone(),
+            allowed_repositories: config.admission_allowed_repositories.clone(),
+            exempt_namespaces: config.admission_exempt_namespaces.clone(),
+        }
+    }
+
+    pub fn is_exempt(&self, namespace: Option<&str>) -> bool {
+        namespace.is_some_and(|ns| self.exempt_namespaces.iter().any(|e| e == ns))
+    }
+
+    /// Map an image reference to the artifact lookup that must succeed for
+    /// it to be admitted, or the reason it is denied outright.
+    pub fn target_for(&self, image: &ImageReference) -> Result<GateTarget, String> {
+        let host = image.host.to_ascii_lowercase();
+        if !self.registry_hosts.iter().any(|h| *h == host) {
+            return Err(format!(
+                "{} is not pulled from an approved registry",
+                image.original
+            ));
+        }
+        let Some((repository, name)) = image.path.split_once('/') else {
+            return Err(format!(
+                "{} must be <registry>/<repository>/<image>",
+                image.original
+            ));
+        };
+        if !self.allowed_repositories.is_empty()
+            && !self.allowed_repositories.iter().any(|r| r == repository)
+        {
+            return Err(format!(
+                "{} is not from an approved repository",
+                image.original
+            ));
+        }
+        Ok(match (&image.digest, &image.tag) {
+            (Some(sha256), _) => GateTarget::Digest {
+                sha256: sha256.clone(),
+                repository: Some(repository.to_string()),
+            },
+            (None, tag) => GateTarget::Path {
+                repository: repository.to_string(),
+                path: format!(
+                    "v2/{}/manifests/{}",
+                    name,
+                    tag.as_deref().unwrap_or("latest")
+                ),
+            },
+        })
+    }
+}
+
+/// A parsed container image reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageReference {
+    pub original: String,
+    /// Registry host (with port); `docker.io` when the reference has none.
+    pub host: String,
+    /// Path within the registry.
+    pub path: String,
+    pub tag: Option<String>,
+    /// Lowercase hex of a `sha256:` digest.
+    pub digest: Option<String>,
+}
+
+impl ImageReference {
+    /// Parse using the Docker reference rules: the first component is a host
+    /// only if it contains `.` or `:` or is `localhost`.
+    pub fn parse(reference: &str) -> Result<Self, String> {
+        let original = reference.trim().to_string();
+        if original.is_empty() || original.chars().any(char::is_whitespace) {
+            return Err(format!("invalid image reference '{}'", reference));
+        }
+        let (rest, digest) = match original.split_once('@') {
+            Some((rest, digest)) => {
+                let hex = digest
+                    .strip_prefix("sha256:")
+                    .filter(|h| h.len() == 64 && h.chars().all(|c| c.is_ascii_hexdigit()))
+                    .ok_or_else(|| format!("unsupported digest in image '{}'", original))?;
+                (rest, Some(hex.to_ascii_lowercase()))
+            }
+            None => (original.as_str(), None),
+        };
+        let (name, tag) = match rest.rfind(':') {
+            Some(idx) if !rest[idx + 1..].contains('/') => {
+                (&rest[..idx], Some(rest[idx + 1..].to_string()))
+            }
+            _ => (rest, None),
+        };
+        let (host, path) = match name.split_once('/') {
+            Some((first, path))
+                if first.contains('.') || first.contains(':') || first == "localhost" =>
+            {
+                (first.to_string(), path.to_string())
+            }
+            _ => ("docker.io".to_string(), name.to_string()),
+        };
+        if path.is_empty() || tag.as_deref() == Some("") {
+            return Err(format!("invalid image reference '{}'", original));
+        }
+        Ok(Self {
+            original,
+            host,
+            path,
+            tag,
+            digest,
+        })
+    }
+}
+
+/// The pod spec inside an admitted object, for pods and the built-in
+/// workload kinds that embed a pod template.
+fn pod_spec<'a>(kind: &str, object: &'a Value) -> Option<&'a Value> {
+    match kind {
+        "Pod" => object.get("spec"),
+        "Deployment"
+        | "ReplicaSet"
+        | "StatefulSet"
+        | "DaemonSet"
+        | "Job"
+        | "ReplicationController" => object.pointer("/spec/template/spec"),
+        "CronJob" => object.pointer("/spec/jobTemplate/spec/template/spec"),
+        _ => None,
+    }
+}
+
+/// Every distinct container image referenced by an admitted object, in
+/// order of first appearance. Unknown kinds yield none.
+pub fn extract_images(kind: &str, object: &Value) -> Vec<String> {
+    let Some(spec) = pod_spec(kind, object) else {
+        return Vec::new();
+    };
+    let mut images: Vec<String> = Vec::new();
+    for list in ["initContainers", "containers", "ephemeralContainers"] {
+        let containers = spec.get(list).and_then(Value::as_array);
+        for container in containers.into_iter().flatten() {
+            if let Some(image) = container.get("image").and_then(Value::as_str) {
+                if !images.iter().any(|i| i == image) {
+                    images.push(image.to_string());
+                }
+            }
+        }
+    }
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const HEX: &str = "4f1c0a9e2b3d4c5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6";
+
+    fn policy() -> AdmissionPolicy {
+        AdmissionPolicy {
+            registry_hosts: vec!["registry.example.com".to_string()],
+            allowed_repositories: vec![],
+            exempt_namespaces: vec!["kube-system".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_image_reference() {
+        let image = ImageReference::parse("registry.example.com/docker-prod/team/app:1.2").unwrap();
+        assert_eq!(image.host, "registry.example.com");
+        assert_eq!(image.path, "docker-prod/team/app");
+        assert_eq!(image.tag.as_deref(), Some("1.2"));
+        assert_eq!(image.digest, None);
+
+        let image = ImageReference::parse("nginx").unwrap();
+        assert_eq!(image.host, "docker.io");
+        assert_eq!(image.path, "nginx");
+        assert_eq!(image.tag, None);
+
+        let image =
+            ImageReference::parse(&format!("localhost:5000/repo/app:v1@sha256:{}", HEX)).unwrap();
+        assert_eq!(image.host, "localhost:5000");
+        assert_eq!(image.tag.as_deref(), Some("v1"));
+        assert_eq!(image.digest.as_deref(), Some(HEX));
+
+        assert!(ImageReference::parse("").is_err());
+        assert!(ImageReference::parse("app@sha256:short").is_err());
+        assert!(ImageReference::parse("app:").is_err());
+    }
+
+    #[test]
+    fn test_target_for() {
+        let policy = policy();
+        let tagged = ImageReference::parse("registry.example.com/docker-prod/app:1.0").unwrap();
+        assert_eq!(
+            policy.target_for(&tagged),
+            Ok(GateTarget::Path {
+                repository: "docker-prod".to_string(),
+                path: "v2/app/manifests/1.0".to_string(),
+            })
+        );
+        let untagged = ImageReference::parse("REGISTRY.example.com/docker-prod/app").unwrap();
+        assert!(matches!(
+            policy.target_for(&untagged),
+            Ok(GateTarget::Path { path, .. }) if path == "v2/app/manifests/latest"
+        ));
+        let pinned = ImageReference::parse(&format!(
+            "registry.example.com/docker-prod/app@sha256:{}",
+            HEX
+        ))
+        .unwrap();
+        assert!(matches!(
+            policy.target_for(&pinned),
+            Ok(GateTarget::Digest { sha256, .. }) if sha256 == HEX
+        ));
+
+        assert!(policy
+            .target_for(&ImageReference::parse("docker.io/library/nginx:1").unwrap())
+            .is_err());
+        assert!(policy
+            .target_for(&ImageReference::parse("registry.example.com/app:1").unwrap())
+            .is_err());
+
+        let restricted = AdmissionPolicy {
+            allowed_repositories: vec!["docker-prod".to_string()],
+            ..policy
+        };
+        assert!(restricted.target_for(&tagged).is_ok());
+        assert!(restricted
+            .target_for(&ImageReference::parse("registry.example.com/docker-dev/app:1").unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_extract_images() {
+        let pod = json!({"spec": {
+            "initContainers": [{"name": "init", "image": "reg/a/init:1"}],
+            "containers": [
+                {"name": "app", "image": "reg/a/app:1"},
+                {"name": "sidecar", "image": "reg/a/init:1"}
+            ]
+        }});
+        assert_eq!(
+            extract_images("Pod", &pod),
+            vec!["reg/a/init:1".to_string(), "reg/a/app:1".to_string()]
+        );
+
+        let cron = json!({"spec": {"jobTemplate": {"spec": {"template": {"spec": {
+            "containers": [{"name": "job", "image": "reg/a/job:1"}]
+        }}}}}});
+        assert_eq!(extract_images("CronJob", &cron), vec!["reg/a/job:1"]);
+
+        let deployment = json!({"spec": {"template": {"spec": {
+            "containers": [{"name": "web", "image": "reg/a/web:2"}]
+        }}}});
+        assert_eq!(
+            extract_images("Deployment", &deployment),
+            vec!["reg/a/web:2"]
+        );
+        assert!(extract_images("ConfigMap", &json!({"data": {}})).is_empty());
+    }
+
+    #[test]
+    fn test_review_round_trip() {
+        let review: AdmissionReview = serde_json::from_value(json!({
+            "apiVersion": "admission.k8s.io/v1",
+            "kind": "AdmissionReview",
+            "request": {
+                "uid": "705ab4f5-6393-11e8-b7cc-42010a800002",
+                "kind": {"group": "", "version": "v1", "kind": "Pod"},
+                "namespace": "default",
+                "operation": "CREATE",
+                "object": {"spec": {"containers": []}}
+            }
+        }))
+        .unwrap();
+        let request = review.request.unwrap();
+        assert_eq!(request.kind.kind, "Pod");
+        assert!(policy().is_exempt(Some("kube-system")));
+        assert!(!policy().is_exempt(request.namespace.as_deref()));
+
+        let denied = AdmissionReview::respond(
+            &review.api_version,
+            AdmissionResponse::deny(&request.uid, &["x".repeat(2000)]),
+        );
+        let value = serde_json::to_value(&denied).unwrap();
+        assert_eq!(value["response"]["uid"], request.uid);
+        assert_eq!(value["response"]["allowed"], false);
+        assert_eq!(value["response"]["status"]["code"], 403);
+        assert!(value.get("request").is_none());
+        assert!(
+            value["response"]["status"]["message"]
+                .as_str()
+                .unwrap()
+                .chars()
+                .count()
+                <= MAX_MESSAGE_CHARS
+        );
+    }
+}
@@ -0,0 +1,891 @@
+//! Air-gap transfer bundles ("airlock").
+//!
+//! An export packs the artifacts of one repository into an uncompressed tar
+//! that can be carried into an isolated network on removable media:
+//!
+//! ```text
+//! manifest.json        AirlockManifest
+//! manifest.json.sig    base64 Ed25519 signature by the exporting instance
+//! blobs/<sha256>       artifact content, one entry per distinct digest
+//! ```
+//!
+//! The manifest embeds the exporter's self-signed identity document and, per
+//! artifact, the same [`PromotionManifest`] a federated promotion carries:
+//! path, checksums, format metadata, the latest scan results and recorded
+//! signatures.
+//!
+//! An import verifies everything before it stores anything: the signature,
+//! the signer (a trusted federation peer or a fingerprint supplied out of
+//! band), every blob's digest and size, and the importer's scan policy. Only
+//! then is content written and are all artifacts registered, in one
+//! transaction.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+use base64::Engine as _;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::artifact::Artifact;
+use crate::services::artifact_service::{ContentDigests, MultiHasher};
+use crate::services::federation_service::{self, InstanceIdentity, LocalIdentity};
+use crate::services::remote_promotion_service::{self, ManifestTarget, PromotionManifest};
+use crate::storage::StorageBackend;
+
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+pub const MANIFEST_ENTRY: &str = "manifest.json";
+pub const SIGNATURE_ENTRY: &str = "manifest.json.sig";
+const BLOB_PREFIX: &str = "blobs/";
+
+/// Upper bound on artifacts per bundle.
+pub const MAX_BUNDLE_ARTIFACTS: usize = 10_000;
+
+/// Upper bound on the manifest entry; scan findings dominate its size.
+const MAX_MANIFEST_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Prefixed to the manifest bytes before signing.
+const SIGNATURE_DOMAIN: &[u8] = b"artifact-keeper-airlock-v1\n";
+
+const SEVERITY_RANK: [&str; 5] = ["info", "low", "medium", "high", "critical"];
+
+/// Contents of `manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AirlockManifest {
+    pub format_version: u32,
+    pub bundle_id: Uuid,
+    pub exported_at: DateTime<Utc>,
+    pub exported_by: String,
+    /// Identity of the exporting instance; its key signs the manifest.
+    pub source: InstanceIdentity,
+    pub repository_key: String,
+    pub format: String,
+    /// One entry per artifact. `promotion_id` is the bundle id and
+    /// `target_repository` the source repository key.
+    pub artifacts: Vec<PromotionManifest>,
+}
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+/// Live artifacts of a repository, optionally limited to a path prefix.
+pub async fn export_candidates(
+    db: &PgPool,
+    repository_id: Uuid,
+    path_prefix: Option<&str>,
+) -> Result<Vec<Artifact>> {
+    let prefix = path_prefix.unwrap_or("").trim_start_matches('/');
+    let artifacts: Vec<Artifact> = sqlx::query_as(
+        "SELECT id, repository_id, path, name, version, size_bytes, \
+                checksum_sha256, checksum_md5, checksum_sha1, \
+                content_type, storage_key, is_deleted, uploaded_by, \
+                quarantine_status, quarantine_until, created_at, updated_at \
+         FROM artifacts \
+         WHERE repository_id = $1 AND is_deleted = false \
+           AND LEFT(path, LENGTH($2)) = $2 \
+         ORDER BY path \
+         LIMIT $3",
+    )
+    .bind(repository_id)
+    .bind(prefix)
+    .bind(MAX_BUNDLE_ARTIFACTS as i64 + 1)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    if artifacts.is_empty() {
+        return Err(AppError::NotFound(
+            "No artifacts match the export selection".to_string(),
+        ));
+    }
+    if artifacts.len() > MAX_BUNDLE_ARTIFACTS {
+        return Err(AppError::Validation(format!(
+            "An airlock bundle may carry at most {} artifacts; narrow the path prefix",
+            MAX_BUNDLE_ARTIFACTS
+        )));
+    }
+    Ok(artifacts)
+}
+
+/// Build the manifest for `artifacts` of the repository `repository_key`.
+pub async fn build_manifest(
+    db: &PgPool,
+    identity: &LocalIdentity,
+    repository_key: &str,
+    format: &str,
+    artifacts: &[Artifact],
+    exported_by: &str,
+) -> Result<AirlockManifest> {
+    let bundle_id = Uuid::new_v4();
+    let mut entries = Vec::with_capacity(artifacts.len());
+    for artifact in artifacts {
+        entries.push(
+            remote_promotion_service::build_manifest(
+                db,
+                identity,
+                artifact,
+                ManifestTarget {
+                    promotion_id: bundle_id,
+                    source_repository: repository_key,
+                    format,
+                    target_repository: repository_key,
+                    promoted_by: exported_by,
+                    notes: None,
+                },
+            )
+            .await?,
+        );
+    }
+    Ok(AirlockManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        bundle_id,
+        exported_at: Utc::now(),
+        exported_by: exported_by.to_string(),
+        source: identity.document(),
+        repository_key: repository_key.to_string(),
+        format: format.to_string(),
+        artifacts: entries,
+    })
+}
+
+/// Serialize and sign a manifest, returning the `manifest.json` and
+/// `manifest.json.sig` entry contents.
+pub fn sign_manifest(
+    identity: &LocalIdentity,
+    manifest: &AirlockManifest,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let bytes =
+        serde_json::to_vec_pretty(manifest).map_err(|e| AppError::Internal(e.to_string()))?;
+    let signature = identity.sign(&signing_input(&bytes));
+    let encoded = base64::engine::general_purpose::STANDARD.encode(signature.to_bytes());
+    Ok((bytes, encoded.into_bytes()))
+}
+
+fn signing_input(manifest: &[u8]) -> Vec<u8> {
+    let mut input = Vec::with_capacity(SIGNATURE_DOMAIN.len() + manifest.len());
+    input.extend_from_slice(SIGNATURE_DOMAIN);
+    input.extend_from_slice(manifest);
+    input
+}
+
+/// Content of one `blobs/<sha256>` entry in an export.
+pub struct ExportBlob {
+    pub sha256: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+}
+
+/// Distinct blobs referenced by `artifacts`, in manifest order.
+pub fn export_blobs(artifacts: &[Artifact]) -> Vec<ExportBlob> {
+    let mut seen = HashSet::new();
+    artifacts
+        .iter()
+        .filter(|a| seen.insert(a.checksum_sha256.to_ascii_lowercase()))
+        .map(|a| ExportBlob {
+            sha256: a.checksum_sha256.to_ascii_lowercase(),
+            size_bytes: a.size_bytes,
+            storage_key: a.storage_key.clone(),
+        })
+        .collect()
+}
+
+/// Stream the bundle as a tar archive. Each blob is re-hashed on the way out;
+/// content that no longer matches its recorded digest aborts the stream
+/// rather than producing a bundle the import would reject.
+pub fn bundle_stream(
+    manifest: Vec<u8>,
+    signature: Vec<u8>,
+    blobs: Vec<ExportBlob>,
+    storage: Arc<dyn StorageBackend>,
+) -> BoxStream<'static, Result<Bytes>> {
+    let mtime = Utc::now().timestamp().max(0) as u64;
+    Box::pin(async_stream::try_stream! {
+        for (name, content) in [(MANIFEST_ENTRY, manifest), (SIGNATURE_ENTRY, signature)] {
+            let size = content.len() as u64;
+            yield Bytes::copy_from_slice(&tar_header(name, size, mtime)?);
+            yield Bytes::from(content);
+            yield tar_padding(size);
+        }
+
+        for blob in blobs {
+            let size = blob.size_bytes as u64;
+            let name = format!("{}{}", BLOB_PREFIX, blob.sha256);
+            yield Bytes::copy_from_slice(&tar_header(&name, size, mtime)?);
+
+            let mut content = storage.get_stream(&blob.storage_key).await?;
+            let mut hasher = Sha256::new();
+            let mut written: u64 = 0;
+            while let Some(chunk) = content.next().await {
+                let chunk = chunk?;
+                written += chunk.len() as u64;
+                if written > size {
+                    break;
+                }
+                hasher.update(&chunk);
+                yield chunk;
+            }
+            let digest = format!("{:x}", hasher.finalize());
+            if written != size || digest != blob.sha256 {
+                Err(AppError::Storage(format!(
+                    "Stored content for {} does not match its recorded checksum; \
+                     aborting airlock export",
+                    blob.sha256
+                )))?;
+            }
+            yield tar_padding(size);
+        }
+
+        yield Bytes::from_static(&[0u8; 1024]);
+    })
+}
+
+fn tar_header(name: &str, size: u64, mtime: u64) -> Result<[u8; 512]> {
+    let mut header = tar::Header::new_ustar();
+    header
+        .set_path(name)
+        .map_err(|e| AppError::Internal(format!("Invalid bundle entry name: {}", e)))?;
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_cksum();
+    Ok(*header.as_bytes())
+}
+
+fn tar_padding(size: u64) -> Bytes {
+    let pad = ((512 - size % 512) % 512) as usize;
+    Bytes::from(vec![0u8; pad])
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// A blob located inside a staged bundle.
+#[derive(Debug, Clone)]
+pub struct BundleBlob {
+    /// Byte offset of the content within the bundle file.
+    pub offset: u64,
+    pub size_bytes: i64,
+    pub digests: ContentDigests,
+}
+
+/// The entries of a staged bundle, read but not yet verified.
+#[derive(Debug)]
+pub struct StagedBundle {
+    pub manifest: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub blobs: HashMap<String, BundleBlob>,
+}
+
+/// Read a bundle from disk, hashing every blob. Blocking; run it on the
+/// blocking pool.
+///
+/// Rejects anything that is not a regular `manifest.json`,
+/// `manifest.json.sig` or `blobs/<sha256>` entry, duplicate entries, and
+/// blobs whose content does not hash to their name.
+pub fn read_bundle(path: &Path) -> Result<StagedBundle> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| AppError::Internal(format!("Failed to open staged bundle: {}", e)))?;
+    let mut archive = tar::Archive::new(std::io::BufReader::new(file));
+    let invalid = |e: std::io::Error| AppError::Validation(format!("Invalid bundle: {}", e));
+
+    let mut manifest = None;
+    let mut signature = None;
+    let mut blobs = HashMap::new();
+    for entry in archive.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        let name = entry
+            .path()
+            .map_err(invalid)?
+            .to_str()
+            .map(str::to_string)
+            .ok_or_else(|| AppError::Validation("Bundle entry name is not UTF-8".to_string()))?;
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            return Err(AppError::Validation(format!(
+                "Bundle entry '{}' is not a regular file",
+                name
+            )));
+        }
+
+        if name == MANIFEST_ENTRY || name == SIGNATURE_ENTRY {
+            let slot = if name == MANIFEST_ENTRY {
+                &mut manifest
+            } else {
+                &mut signature
+            };
+            if slot.is_some() {
+                return Err(AppError::Validation(format!(
+                    "Duplicate bundle entry '{}'",
+                    name
+                )));
+            }
+            if entry.size() > MAX_MANIFEST_BYTES {
+                return Err(AppError::Validation(format!(
+                    "Bundle entry '{}' is too large",
+                    name
+                )));
+            }
+            let mut content = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut content).map_err(invalid)?;
+            *slot = Some(content);
+            continue;
+        }
+
+        let sha256 = name
+            .strip_prefix(BLOB_PREFIX)
+            .filter(|d| is_sha256_hex(d))
+            .ok_or_else(|| AppError::Validation(format!("Unexpected bundle entry '{}'", name)))?
+            .to_string();
+        if blobs.contains_key(&sha256) {
+            return Err(AppError::Validation(format!(
+                "Duplicate bundle entry '{}'",
+                name
+            )));
+        }
+        let offset = entry.raw_file_position();
+        let mut hasher = MultiHasher::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut size: u64 = 0;
+        loop {
+            let n = entry.read(&mut buf).map_err(invalid)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            size += n as u64;
+        }
+        let digests = hasher.finalize();
+        if digests.sha256 != sha256 {
+            return Err(AppError::Validation(format!(
+                "Blob {} is corrupted: content hashes to {}",
+                sha256, digests.sha256
+            )));
+        }
+        blobs.insert(
+            sha256,
+            BundleBlob {
+                offset,
+                size_bytes: size as i64,
+                digests,
+            },
+        );
+    }
+
+    Ok(StagedBundle {
+        manifest: manifest.ok_or_else(|| {
+            AppError::Validation(format!("Bundle has no {} entry", MANIFEST_ENTRY))
+        })?,
+        signature: signature.ok_or_else(|| {
+            AppError::Validation(format!("Bundle has no {} entry", SIGNATURE_ENTRY))
+        })?,
+        blobs,
+    })
+}
+
+/// Stream one blob's content straight out of the staged bundle file.
+pub async fn open_blob(
+    bundle_path: &Path,
+    blob: &BundleBlob,
+) -> Result<BoxStream<'static, Result<Bytes>>> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut file = tokio::fs::File::open(bundle_path)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to reopen staged bundle: {}", e)))?;
+    file.seek(std::io::SeekFrom::Start(blob.offset))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to seek staged bundle: {}", e)))?;
+    let reader = file.take(blob.size_bytes as u64);
+    Ok(Box::pin(tokio_util::io::ReaderStream::new(reader).map(
+        |r| r.map_err(|e| AppError::Storage(format!("bundle read: {}", e))),
+    )))
+}
+
+fn is_sha256_hex(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Check the manifest signature and that the manifest and the blobs describe
+/// each other exactly. Returns the parsed manifest and the signer's key.
+pub fn verify_bundle(bundle: &StagedBundle) -> Result<(AirlockManifest, VerifyingKey)> {
+    let manifest: AirlockManifest = serde_json::from_slice(&bundle.manifest)
+        .map_err(|e| AppError::Validation(format!("Invalid bundle manifest: {}", e)))?;
+    if manifest.format_version != BUNDLE_FORMAT_VERSION {
+        return Err(AppError::Validation(format!(
+            "Unsupported bundle format version {}",
+            manifest.format_version
+        )));
+    }
+
+    let key = manifest.source.verify()?;
+    let signature: [u8; 64] = std::str::from_utf8(&bundle.signature)
+        .ok()
+        .and_then(|s| {
+            base64::engine::general_purpose::STANDARD
+                .decode(s.trim())
+                .ok()
+        })
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| AppError::Validation("Invalid bundle signature".to_string()))?;
+    key.verify(
+        &signing_input(&bundle.manifest),
+        &Signature::from_bytes(&signature),
+    )
+    .map_err(|_| AppError::Validation("Bundle signature does not verify".to_string()))?;
+
+    if manifest.artifacts.is_empty() || manifest.artifacts.len() > MAX_BUNDLE_ARTIFACTS {
+        return Err(AppError::Validation(format!(
+            "A bundle must carry between 1 and {} artifacts",
+            MAX_BUNDLE_ARTIFACTS
+        )));
+    }
+    let mut paths = HashSet::new();
+    let mut referenced = HashSet::new();
+    for entry in &manifest.artifacts {
+        entry.validate()?;
+        if entry.format != manifest.format
+            || entry.source_instance_id != manifest.source.instance_id
+        {
+            return Err(AppError::Validation(format!(
+                "Manifest entry '{}' does not belong to this bundle",
+                entry.path
+            )));
+        }
+        if !paths.insert(entry.path.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Duplicate artifact path '{}' in bundle",
+                entry.path
+            )));
+        }
+        let sha256 = entry.checksum_sha256.to_ascii_lowercase();
+        let blob = bundle.blobs.get(&sha256).ok_or_else(|| {
+            AppError::Validation(format!("Bundle is missing the content of '{}'", entry.path))
+        })?;
+        remote_promotion_service::verify_content(entry, &blob.digests, blob.size_bytes)?;
+        referenced.insert(sha256);
+    }
+    if let Some(extra) = bundle.blobs.keys().find(|d| !referenced.contains(*d)) {
+        return Err(AppError::Validation(format!(
+            "Bundle carries blob {} that no manifest entry references",
+            extra
+        )));
+    }
+    Ok((manifest, key))
+}
+
+/// Accept the signer if its key matches `expected_fingerprint` (obtained out
+/// of band) or a trusted federation peer with the same instance id. Returns
+/// the signer's fingerprint.
+pub async fn check_signer(
+    db: &PgPool,
+    manifest: &AirlockManifest,
+    key: &VerifyingKey,
+    expected_fingerprint: Option<&str>,
+) -> Result<String> {
+    let fingerprint = federation_service::fingerprint(&key.to_bytes());
+    if let Some(expected) = expected_fingerprint
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        if !expected.eq_ignore_ascii_case(&fingerprint) {
+            return Err(AppError::Authorization(format!(
+                "Bundle is signed by {}, not the expected {}",
+                fingerprint, expected
+            )));
+        }
+        return Ok(fingerprint);
+    }
+
+    let trusted: Option<String> = sqlx::query_scalar(
+        "SELECT key_fingerprint FROM federation_trusted_peers \
+         WHERE instance_id = $1 AND status = 'trusted'",
+    )
+    .bind(manifest.source.instance_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    match trusted {
+        Some(known) if known == fingerprint => Ok(fingerprint),
+        _ => Err(AppError::Authorization(format!(
+            "Bundle signer '{}' ({}) is not a trusted federation peer; \
+             supply its fingerprint obtained out of band",
+            manifest.source.name, fingerprint
+        ))),
+    }
+}
+
+/// Scan requirements an import enforces on the carried scan results.
+#[derive(Debug, Clone, Default)]
+pub struct ScanPolicy {
+    /// Every artifact must carry at least one completed scan.
+    pub require_scan: bool,
+    /// Reject the bundle if any finding is at or above this severity.
+    pub block_severity: Option<String>,
+}
+
+impl ScanPolicy {
+    /// Violations of the policy, one message per offending artifact.
+    pub fn violations(&self, manifest: &AirlockManifest) -> Result<Vec<String>> {
+        let threshold = match &self.block_severity {
+            Some(s) => Some(
+                severity_rank(s)
+                    .ok_or_else(|| AppError::Validation(format!("Unknown severity '{}'", s)))?,
+            ),
+            None => None,
+        };
+        let mut violations = Vec::new();
+        for entry in &manifest.artifacts {
+            if self.require_scan && entry.scans.is_empty() {
+                violations.push(format!("{}: no scan results", entry.path));
+                continue;
+            }
+            let Some(threshold) = threshold else {
+                continue;
+            };
+            let blocking = entry
+                .scans
+                .iter()
+                .flat_map(|s| &s.findings)
+                .filter(|f| severity_rank(&f.severity).is_some_and(|r| r >= threshold))
+                .count();
+            if blocking > 0 {
+                violations.push(format!(
+                    "{}: {} finding(s) at or above {}",
+                    entry.path, blocking, SEVERITY_RANK[threshold]
+                ));
+            }
+        }
+        Ok(violations)
+    }
+}
+
+fn severity_rank(severity: &str) -> Option<usize> {
+    SEVERITY_RANK.iter().position(|s| *s == severity)
+}
+
+/// Outcome of an import (or of a dry run, which stops after verification).
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AirlockImportReport {
+    pub bundle_id: Uuid,
+    pub source_instance_name: String,
+    pub key_fingerprint: String,
+    pub repository_key: String,
+    pub artifact_count: usize,
+    pub total_bytes: i64,
+    pub dry_run: bool,
+    /// Id of the audit record; absent for dry runs.
+    pub import_id: Option<Uuid>,
+    pub artifact_ids: Vec<Uuid>,
+}
+
+/// Register every artifact of a verified bundle and record the import, in one
+/// transaction. The content must already be stored under each
+/// `storage_keys` entry (keyed by SHA-256).
+#[allow(clippy::too_many_arguments)]
+pub async fn register_bundle(
+    db: &PgPool,
+    repository_id: Uuid,
+    manifest: &AirlockManifest,
+    key_fingerprint: &str,
+    blobs: &HashMap<String, BundleBlob>,
+    storage_keys: &HashMap<String, String>,
+    imported_by: Uuid,
+) -> Result<(Uuid, Vec<Uuid>)> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let mut artifact_ids = Vec::with_capacity(manifest.artifacts.len());
+    for entry in &manifest.artifacts {
+        let sha256 = entry.checksum_sha256.to_ascii_lowercase();
+        let (Some(blob), Some(storage_key)) = (blobs.get(&sha256), storage_keys.get(&sha256))
+        else {
+            return Err(AppError::Internal(format!(
+                "Content of '{}' was not stored",
+                entry.path
+            )));
+        };
+        artifact_ids.push(
+            remote_promotion_service::insert_artifact(
+                &mut tx,
+                repository_id,
+                entry,
+                storage_key,
+                &blob.digests,
+                imported_by,
+            )
+            .await?,
+        );
+    }
+
+    let total_bytes: i64 = manifest.artifacts.iter().map(|a| a.size_bytes).sum();
+    let import_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO airlock_imports ( \
+             bundle_id, source_instance_id, source_instance_name, key_fingerprint, \
+             repository_id, artifact_count, total_bytes, imported_by \
+         ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+         RETURNING id",
+    )
+    .bind(manifest.bundle_id)
+    .bind(manifest.source.instance_id)
+    .bind(&manifest.source.name)
+    .bind(key_fingerprint)
+    .bind(repository_id)
+    .bind(manifest.artifacts.len() as i32)
+    .bind(total_bytes)
+    .bind(imported_by)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok((import_id, artifact_ids))
+}
+
+/// A completed import.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AirlockImport {
+    pub id: Uuid,
+    pub bundle_id: Uuid,
+    pub source_instance_id: Uuid,
+    pub source_instance_name: String,
+    pub key_fingerprint: String,
+    pub repository_key: Option<String>,
+    pub artifact_count: i32,
+    pub total_bytes: i64,
+    pub imported_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub async fn list_imports(db: &PgPool, limit: i64) -> Result<Vec<AirlockImport>> {
+    let rows = sqlx::query(
+        "SELECT i.id, i.bundle_id, i.source_instance_id, i.source_instance_name, \
+                i.key_fingerprint, r.key AS repository_key, i.artifact_count, \
+                i.total_bytes, u.username AS imported_by, i.created_at \
+         FROM airlock_imports i \
+         LEFT JOIN repositories r ON r.id = i.repository_id \
+         LEFT JOIN users u ON u.id = i.imported_by \
+         ORDER BY i.created_at DESC \
+         LIMIT $1",
+    )
+    .bind(limit)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(rows
+        .iter()
+        .map(|row| AirlockImport {
+            id: row.get("id"),
+            bundle_id: row.get("bundle_id"),
+            source_instance_id: row.get("source_instance_id"),
+            source_instance_name: row.get("source_instance_name"),
+            key_fingerprint: row.get("key_fingerprint"),
+            repository_key: row.get("repository_key"),
+            artifact_count: row.get("artifact_count"),
+            total_bytes: row.get("total_bytes"),
+            imported_by: row.get("imported_by"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::federation_service::tests_support::identity_with_seed;
+
+    const CONTENT: &[u8] = b"airlock test content";
+
+    fn manifest(identity: &LocalIdentity) -> AirlockManifest {
+        let sha256 = format!("{:x}", Sha256::digest(CONTENT));
+        let entry: PromotionManifest = serde_json::from_value(serde_json::json!({
+            "promotion_id": Uuid::new_v4(),
+            "source_instance_id": identity.instance_id,
+            "source_instance_name": identity.name,
+            "source_repository": "libs-release",
+            "source_artifact_id": Uuid::new_v4(),
+            "target_repository": "libs-release",
+            "format": "generic",
+            "path": "acme/1.0/acme-1.0.tar.gz",
+            "name": "acme-1.0.tar.gz",
+            "version": "1.0",
+            "size_bytes": CONTENT.len(),
+            "checksum_sha256": sha256,
+            "checksum_sha1": null,
+            "checksum_md5": null,
+            "content_type": "application/gzip",
+            "metadata": null,
+            "promoted_by": "release-bot",
+            "notes": null
+        }))
+        .unwrap();
+        AirlockManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            bundle_id: Uuid::new_v4(),
+            exported_at: Utc::now(),
+            exported_by: "release-bot".to_string(),
+            source: identity.document(),
+            repository_key: "libs-release".to_string(),
+            format: "generic".to_string(),
+            artifacts: vec![entry],
+        }
+    }
+
+    fn write_bundle(entries: &[(&str, &[u8])]) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut builder = tar::Builder::new(file.reopen().unwrap());
+        for (name, content) in entries {
+            let mut header = tar::Header::new_ustar();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *content).unwrap();
+        }
+        builder.finish().unwrap();
+        file
+    }
+
+    fn blob_name() -> String {
+        format!("blobs/{:x}", Sha256::digest(CONTENT))
+    }
+
+    #[test]
+    fn test_signed_bundle_round_trip() {
+        let identity = identity_with_seed(7);
+        let (manifest_bytes, signature) = sign_manifest(&identity, &manifest(&identity)).unwrap();
+        let blob = blob_name();
+        let file = write_bundle(&[
+            (MANIFEST_ENTRY, &manifest_bytes),
+            (SIGNATURE_ENTRY, &signature),
+            (&blob, CONTENT),
+        ]);
+
+        let bundle = read_bundle(file.path()).unwrap();
+        let (parsed, key) = verify_bundle(&bundle).unwrap();
+        assert_eq!(parsed.artifacts.len(), 1);
+        assert_eq!(key.to_bytes(), identity.public_key_bytes());
+
+        // The recorded offset points at the blob content.
+        let located = bundle.blobs.values().next().unwrap();
+        let raw = std::fs::read(file.path()).unwrap();
+        let start = located.offset as usize;
+        assert_eq!(&raw[start..start + CONTENT.len()], CONTENT);
+    }
+
+    #[test]
+    fn test_tampered_manifest_is_rejected() {
+        let identity = identity_with_seed(7);
+        let (manifest_bytes, signature) = sign_manifest(&identity, &manifest(&identity)).unwrap();
+        let tampered = String::from_utf8(manifest_bytes)
+            .unwrap()
+            .replace("acme-1.0.tar.gz\"", "acme-1.1.tar.gz\"");
+        let blob = blob_name();
+        let file = write_bundle(&[
+            (MANIFEST_ENTRY, tampered.as_bytes()),
+            (SIGNATURE_ENTRY, &signature),
+            (&blob, CONTENT),
+        ]);
+        let bundle = read_bundle(file.path()).unwrap();
+        assert!(verify_bundle(&bundle).is_err());
+    }
+
+    #[test]
+    fn test_corrupted_or_unexpected_entries_are_rejected() {
+        let identity = identity_with_seed(7);
+        let (manifest_bytes, signature) = sign_manifest(&identity, &manifest(&identity)).unwrap();
+        let blob = blob_name();
+
+        let corrupted = write_bundle(&[
+            (MANIFEST_ENTRY, &manifest_bytes),
+            (SIGNATURE_ENTRY, &signature),
+            (&blob, b"something else entirely"),
+        ]);
+        assert!(read_bundle(corrupted.path()).is_err());
+
+        let unexpected = write_bundle(&[
+            (MANIFEST_ENTRY, &manifest_bytes),
+            (SIGNATURE_ENTRY, &signature),
+            ("notes.txt", CONTENT),
+        ]);
+        assert!(read_bundle(unexpected.path()).is_err());
+
+        let missing_blob = write_bundle(&[
+            (MANIFEST_ENTRY, &manifest_bytes),
+            (SIGNATURE_ENTRY, &signature),
+        ]);
+        let bundle = read_bundle(missing_blob.path()).unwrap();
+        assert!(verify_bundle(&bundle).is_err());
+    }
+
+    #[test]
+    fn test_scan_policy() {
+        let identity = identity_with_seed(7);
+        let mut m = manifest(&identity);
+        let policy = ScanPolicy {
+            require_scan: true,
+            block_severity: None,
+        };
+        assert_eq!(policy.violations(&m).unwrap().len(), 1);
+
+        m.artifacts[0].scans = serde_json::from_value(serde_json::json!([{
+            "scan_type": "dependency",
+            "scanner_version": null,
+            "source_tool": null,
+            "completed_at": null,
+            "findings": [{
+                "severity": "high",
+                "title": "CVE-2024-0001",
+                "description": null,
+                "cve_id": "CVE-2024-0001",
+                "affected_component": null,
+                "affected_version": null,
+                "fixed_version": null,
+                "source": null,
+                "source_url": null
+            }]
+        }]))
+        .unwrap();
+        assert!(policy.violations(&m).unwrap().is_empty());
+
+        let blocking = ScanPolicy {
+            require_scan: true,
+            block_severity: Some("high".to_string()),
+        };
+        assert_eq!(blocking.violations(&m).unwrap().len(), 1);
+        let lenient = ScanPolicy {
+            require_scan: false,
+            block_severity: Some("critical".to_string()),
+        };
+        assert!(lenient.violations(&m).unwrap().is_empty());
+        let unknown = ScanPolicy {
+            require_scan: false,
+            block_severity: Some("catastrophic".to_string()),
+        };
+        assert!(unknown.violations(&m).is_err());
+    }
+
+    #[test]
+    fn test_tar_framing() {
+        let header = tar_header("blobs/abc", 10, 0).unwrap();
+        let parsed = tar::Header::from_byte_slice(&header);
+        assert_eq!(parsed.size().unwrap(), 10);
+        assert_eq!(tar_padding(10).len(), 502);
+        assert_eq!(tar_padding(512).len(), 0);
+    }
+}
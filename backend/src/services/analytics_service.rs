@@ -1,7 +1,8 @@
 //! Storage analytics and reporting service.
 //!
 //! Provides time-series storage metrics, artifact aging reports,
-//! per-repository breakdowns, and scheduled metric snapshots.
+//! per-repository breakdowns, container layer and upload deduplication
+//! reports, and scheduled metric snapshots.
 
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
@@ -60,6 +61,19 @@ pub struct RepositoryStorageBreakdown {
     pub last_upload_at: Option<DateTime<Utc>>,
 }
 
+/// Usage grouped by the value of one artifact property (e.g. `team`), which
+/// artifacts typically inherit from their repository's default properties.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct PropertyBreakdown {
+    /// Property value; `None` groups artifacts without the property.
+    pub value: Option<String>,
+    pub repository_count: i64,
+    pub artifact_count: i64,
+    pub storage_bytes: i64,
+    /// Hosted downloads within the requested date range.
+    pub download_count: i64,
+}
+
 /// Artifact aging report entry.
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct StaleArtifact {
@@ -87,6 +101,162 @@ pub struct GrowthSummary {
     pub artifacts_end: i64,
     pub artifacts_added: i64,
     pub downloads_in_period: i64,
+    /// Projected total storage, fitted over the snapshots up to
+    /// `period_end`. `None` until there are at least two snapshots.
+    #[serde(default)]
+    pub forecast: Option<StorageForecast>,
+}
+
+/// What a forecast measures time-to-full against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CapacityLimit {
+    /// The repository's `quota_bytes`.
+    Quota,
+    /// The operator-declared `STORAGE_CAPACITY_BYTES`.
+    Disk,
+}
+
+/// Projected storage from a fit over daily snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StorageForecast {
+    /// `linear`, or `seasonal` once there are enough snapshots to fit a
+    /// day-of-week pattern on top of the trend.
+    pub method: String,
+    /// Snapshots the fit used.
+    pub samples: i64,
+    pub current_bytes: i64,
+    /// Fitted trend, in bytes per day (negative when storage is shrinking).
+    pub daily_growth_bytes: f64,
+    pub projected_bytes_30d: i64,
+    pub projected_bytes_90d: i64,
+    pub projected_bytes_180d: i64,
+    pub capacity_bytes: Option<i64>,
+    pub capacity_limit: Option<CapacityLimit>,
+    /// Days until the trend reaches the capacity; 0 when already at or over
+    /// it, `None` when there is no capacity or the trend never reaches it.
+    pub days_until_full: Option<i64>,
+    pub estimated_full_date: Option<NaiveDate>,
+}
+
+/// Storage forecast for one repository.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RepositoryForecast {
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub repository_name: String,
+    pub forecast: StorageForecast,
+}
+
+/// Global and per-repository storage forecasts.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StorageForecastReport {
+    pub global: Option<StorageForecast>,
+    /// Repositories with a forecast, soonest to fill first.
+    pub repositories: Vec<RepositoryForecast>,
+}
+
+/// Snapshots fitted by default: long enough to smooth bursts, short enough
+/// to follow a change in growth rate.
+pub const FORECAST_LOOKBACK_DAYS: i64 = 90;
+
+/// Four full weeks are needed before a day-of-week pattern is fitted.
+const SEASONAL_MIN_SAMPLES: usize = 28;
+
+/// Forecast horizons reported, in days.
+const FORECAST_HORIZONS: [i64; 3] = [30, 90, 180];
+
+/// Fit `points` (snapshot date, bytes) and project forward from `today`.
+///
+/// Least-squares linear trend; with four or more weeks of snapshots the
+/// mean residual per weekday is added back as a weekly seasonal component,
+/// which matters for build-driven repositories that grow on weekdays only.
+/// Time-to-full follows the trend alone so a weekend dip cannot push the
+/// date around. Returns `None` with fewer than two distinct days.
+pub fn forecast_storage(
+    points: &[(NaiveDate, i64)],
+    capacity: Option<(i64, CapacityLimit)>,
+    today: NaiveDate,
+) -> Option<StorageForecast> {
+    use chrono::Datelike;
+
+    let first = points.iter().map(|(d, _)| *d).min()?;
+    let xs: Vec<f64> = points
+        .iter()
+        .map(|(d, _)| (*d - first).num_days() as f64)
+        .collect();
+    let ys: Vec<f64> = points.iter().map(|(_, b)| *b as f64).collect();
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let sxy: f64 = xs
+        .iter()
+        .zip(&ys)
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let trend = |x: f64| intercept + slope * x;
+
+    let mut seasonal = [0.0f64; 7];
+    let method = if points.len() >= SEASONAL_MIN_SAMPLES {
+        let mut counts = [0u32; 7];
+        for ((d, _), (x, y)) in points.iter().zip(xs.iter().zip(&ys)) {
+            let weekday = d.weekday().num_days_from_monday() as usize;
+            seasonal[weekday] += y - trend(*x);
+            counts[weekday] += 1;
+        }
+        for (offset, count) in seasonal.iter_mut().zip(counts) {
+            if count > 0 {
+                *offset /= count as f64;
+            }
+        }
+        "seasonal"
+    } else {
+        "linear"
+    };
+
+    let current_bytes = points
+        .iter()
+        .max_by_key(|(d, _)| *d)
+        .map(|(_, b)| *b)
+        .unwrap_or_default();
+    let today_x = (today - first).num_days() as f64;
+    let project = |days: i64| {
+        let date = today + chrono::Duration::days(days);
+        let weekday = date.weekday().num_days_from_monday() as usize;
+        (trend(today_x + days as f64) + seasonal[weekday])
+            .max(0.0)
+            .round() as i64
+    };
+
+    let days_until_full = capacity.and_then(|(limit, _)| {
+        if current_bytes >= limit {
+            Some(0)
+        } else if slope > 0.0 {
+            Some(((limit as f64 - trend(today_x)) / slope).ceil().max(0.0) as i64)
+        } else {
+            None
+        }
+    });
+
+    Some(StorageForecast {
+        method: method.to_string(),
+        samples: points.len() as i64,
+        current_bytes,
+        daily_growth_bytes: slope,
+        projected_bytes_30d: project(FORECAST_HORIZONS[0]),
+        projected_bytes_90d: project(FORECAST_HORIZONS[1]),
+        projected_bytes_180d: project(FORECAST_HORIZONS[2]),
+        capacity_bytes: capacity.map(|(limit, _)| limit),
+        capacity_limit: capacity.map(|(_, kind)| kind),
+        days_until_full,
+        estimated_full_date: days_until_full.map(|d| today + chrono::Duration::days(d)),
+    })
 }
 
 /// Download trend data point.
@@ -102,6 +272,173 @@ pub struct DownloadTrend {
     pub proxy_download_count: i64,
 }
 
+/// Shared-layer savings across container repositories.
+///
+/// Built on demand from the manifest -> layer edges in `manifest_blob_refs`
+/// (kind `layer`) joined to `oci_blobs` for sizes. `logical_bytes` is what
+/// the layers would occupy if every image manifest stored its own copy;
+/// `stored_bytes` counts each physical layer object once, with the same
+/// ownership model as the blob GC (one object per digest on shared
+/// backends, one copy per repository `storage_path` on `filesystem`).
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LayerDedupReport {
+    /// Image manifests with at least one recorded layer.
+    pub manifests_scanned: i64,
+    /// Distinct layer digests.
+    pub distinct_layers: i64,
+    /// Layer digests referenced by more than one image manifest.
+    pub shared_layers: i64,
+    pub logical_bytes: i64,
+    pub stored_bytes: i64,
+    /// `logical_bytes - stored_bytes`.
+    pub deduplicated_bytes: i64,
+    /// `deduplicated_bytes` as a percentage of `logical_bytes`.
+    pub savings_percent: f64,
+    /// Layers saving the most bytes through sharing, largest first.
+    pub top_shared_layers: Vec<LayerUsage>,
+    /// Heaviest layers used by a single image manifest, largest first.
+    /// Candidates for moving into a common base image.
+    pub top_unshared_layers: Vec<LayerUsage>,
+}
+
+/// One layer in a [`LayerDedupReport`] ranking.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct LayerUsage {
+    pub digest: String,
+    pub size_bytes: i64,
+    /// Image manifests referencing the layer.
+    pub manifest_count: i64,
+    pub repository_count: i64,
+    pub repository_keys: Vec<String>,
+    /// Bytes not stored thanks to sharing: one `size_bytes` per manifest
+    /// reference beyond the physical copies that exist.
+    pub saved_bytes: i64,
+}
+
+/// Uploads that skipped the storage write because their content was
+/// already stored, over a date range.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UploadDedupReport {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub deduplicated_uploads: i64,
+    /// Uploads answered from the declared checksum without receiving a body.
+    pub body_skipped_uploads: i64,
+    /// Storage writes avoided.
+    pub bytes_saved: i64,
+    /// Request bytes never transferred (the `body_skipped_uploads` share).
+    pub transfer_bytes_saved: i64,
+    /// Repositories saving the most bytes, largest first.
+    pub repositories: Vec<RepositoryUploadDedup>,
+}
+
+/// Per-repository row of an [`UploadDedupReport`].
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct RepositoryUploadDedup {
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub deduplicated_uploads: i64,
+    pub body_skipped_uploads: i64,
+    pub bytes_saved: i64,
+    pub transfer_bytes_saved: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct UploadDedupTotals {
+    deduplicated_uploads: i64,
+    body_skipped_uploads: i64,
+    bytes_saved: i64,
+    transfer_bytes_saved: i64,
+}
+
+/// Record an upload whose storage write was skipped. Best-effort: failures
+/// are logged, never surfaced to the uploader.
+pub async fn record_upload_dedup(
+    db: &PgPool,
+    repository_id: Uuid,
+    artifact_id: Uuid,
+    checksum_sha256: &str,
+    bytes_saved: i64,
+    body_skipped: bool,
+) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO upload_dedup_events
+            (repository_id, artifact_id, checksum_sha256, bytes_saved, body_skipped)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(repository_id)
+    .bind(artifact_id)
+    .bind(checksum_sha256)
+    .bind(bytes_saved)
+    .bind(body_skipped)
+    .execute(db)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(
+            repository_id = %repository_id,
+            artifact_id = %artifact_id,
+            "Failed to record upload deduplication: {}",
+            e
+        );
+    }
+}
+
+/// Aggregate half of [`LayerDedupReport`].
+#[derive(Debug, sqlx::FromRow)]
+struct LayerDedupTotals {
+    manifests_scanned: i64,
+    distinct_layers: i64,
+    shared_layers: i64,
+    logical_bytes: i64,
+    stored_bytes: i64,
+}
+
+/// `layer_refs` CTE shared by the layer dedup queries: one row per
+/// (repository, image manifest, layer) with the layer size and the physical
+/// location it is stored under (`storage_path` only matters on
+/// `filesystem`, where each repository keeps its own copy).
+const LAYER_REFS_CTE_SQL: &str = r#"
+layer_refs AS (
+    SELECT DISTINCT
+        mbr.repository_id,
+        mbr.manifest_digest,
+        mbr.blob_digest,
+        ob.size_bytes,
+        r.key::TEXT AS repository_key,
+        r.storage_backend,
+        CASE WHEN r.storage_backend = 'filesystem' THEN r.storage_path ELSE '' END AS location
+    FROM manifest_blob_refs mbr
+    JOIN oci_blobs ob
+      ON ob.repository_id = mbr.repository_id
+     AND ob.digest = mbr.blob_digest
+    JOIN repositories r ON r.id = mbr.repository_id
+    WHERE mbr.kind = 'layer'
+),
+layer_usage AS (
+    SELECT
+        blob_digest AS digest,
+        MAX(size_bytes)::BIGINT AS size_bytes,
+        COUNT(*)::BIGINT AS manifest_count,
+        COUNT(DISTINCT repository_id)::BIGINT AS repository_count,
+        ARRAY_AGG(DISTINCT repository_key) AS repository_keys,
+        ((COUNT(*) - COUNT(DISTINCT (storage_backend, location))) * MAX(size_bytes))::BIGINT
+            AS saved_bytes
+    FROM layer_refs
+    GROUP BY blob_digest
+)
+"#;
+
+/// Share of `logical` bytes that deduplication avoids storing.
+fn dedup_savings_percent(logical_bytes: i64, stored_bytes: i64) -> f64 {
+    if logical_bytes > 0 {
+        ((logical_bytes - stored_bytes) as f64 / logical_bytes as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
 impl AnalyticsService {
     pub fn new(db: PgPool) -> Self {
         Self { db }
@@ -276,6 +613,45 @@ impl AnalyticsService {
         Ok(breakdown)
     }
 
+    /// Storage and downloads of live artifacts grouped by the value of the
+    /// `property` key in their properties.
+    pub async fn get_property_breakdown(
+        &self,
+        property: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PropertyBreakdown>> {
+        let breakdown = sqlx::query_as::<_, PropertyBreakdown>(
+            r#"
+            SELECT
+                m.properties ->> $1 as value,
+                COUNT(DISTINCT a.repository_id) as repository_count,
+                COUNT(a.id) as artifact_count,
+                COALESCE(SUM(a.size_bytes), 0)::BIGINT as storage_bytes,
+                COALESCE(SUM(dl.cnt), 0)::BIGINT as download_count
+            FROM artifacts a
+            LEFT JOIN artifact_metadata m ON m.artifact_id = a.id
+            LEFT JOIN (
+                SELECT artifact_id, COUNT(*) as cnt
+                FROM download_statistics
+                WHERE downloaded_at::DATE BETWEEN $2 AND $3
+                GROUP BY artifact_id
+            ) dl ON dl.artifact_id = a.id
+            WHERE a.is_deleted = false
+            GROUP BY m.properties ->> $1
+            ORDER BY COALESCE(SUM(a.size_bytes), 0) DESC
+            "#,
+        )
+        .bind(property)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(breakdown)
+    }
+
     /// Get stale artifacts that haven't been downloaded in N days.
     pub async fn get_stale_artifacts(
         &self,
@@ -327,11 +703,13 @@ impl AnalyticsService {
         Ok(stale)
     }
 
-    /// Get growth summary for a date range.
+    /// Get growth summary for a date range, with a forecast fitted over the
+    /// snapshots leading up to `to` against `capacity_bytes`.
     pub async fn get_growth_summary(
         &self,
         from: NaiveDate,
         to: NaiveDate,
+        capacity_bytes: Option<i64>,
     ) -> Result<GrowthSummary> {
         let start = sqlx::query_as::<_, StorageSnapshot>(
             r#"
@@ -392,9 +770,86 @@ impl AnalyticsService {
             artifacts_end: end_artifacts,
             artifacts_added: end_artifacts - start_artifacts,
             downloads_in_period: end_downloads - start.map(|s| s.total_downloads).unwrap_or(0),
+            forecast: self.get_storage_forecast(to, capacity_bytes).await?,
         })
     }
 
+    /// Forecast total storage from the snapshots in the lookback window
+    /// ending at `as_of`.
+    pub async fn get_storage_forecast(
+        &self,
+        as_of: NaiveDate,
+        capacity_bytes: Option<i64>,
+    ) -> Result<Option<StorageForecast>> {
+        let points = sqlx::query_as::<_, (NaiveDate, i64)>(
+            r#"
+            SELECT snapshot_date, total_storage_bytes
+            FROM storage_metrics
+            WHERE snapshot_date > $1 - $2::INT AND snapshot_date <= $1
+            ORDER BY snapshot_date ASC
+            "#,
+        )
+        .bind(as_of)
+        .bind(FORECAST_LOOKBACK_DAYS as i32)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(forecast_storage(
+            &points,
+            capacity_bytes.map(|c| (c, CapacityLimit::Disk)),
+            as_of,
+        ))
+    }
+
+    /// Forecast every repository with snapshots in the lookback window,
+    /// measured against its quota. Sorted soonest-to-fill first, then by
+    /// projected 90-day growth.
+    pub async fn get_repository_forecasts(
+        &self,
+        as_of: NaiveDate,
+    ) -> Result<Vec<RepositoryForecast>> {
+        let rows = sqlx::query_as::<_, (Uuid, String, String, Option<i64>, NaiveDate, i64)>(
+            r#"
+            SELECT r.id, r.key, r.name, r.quota_bytes, rm.snapshot_date, rm.storage_bytes
+            FROM repository_metrics rm
+            JOIN repositories r ON r.id = rm.repository_id
+            WHERE rm.snapshot_date > $1 - $2::INT AND rm.snapshot_date <= $1
+            ORDER BY r.id, rm.snapshot_date ASC
+            "#,
+        )
+        .bind(as_of)
+        .bind(FORECAST_LOOKBACK_DAYS as i32)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut forecasts = Vec::new();
+        for chunk in rows.chunk_by(|a, b| a.0 == b.0) {
+            let (id, key, name, quota, _, _) = &chunk[0];
+            let points: Vec<(NaiveDate, i64)> = chunk.iter().map(|r| (r.4, r.5)).collect();
+            let capacity = quota.filter(|q| *q > 0).map(|q| (q, CapacityLimit::Quota));
+            if let Some(forecast) = forecast_storage(&points, capacity, as_of) {
+                forecasts.push(RepositoryForecast {
+                    repository_id: *id,
+                    repository_key: key.clone(),
+                    repository_name: name.clone(),
+                    forecast,
+                });
+            }
+        }
+        forecasts.sort_by(|a, b| {
+            let full = |f: &RepositoryForecast| f.forecast.days_until_full.unwrap_or(i64::MAX);
+            full(a).cmp(&full(b)).then_with(|| {
+                let growth = |f: &RepositoryForecast| {
+                    f.forecast.projected_bytes_90d - f.forecast.current_bytes
+                };
+                growth(b).cmp(&growth(a))
+            })
+        });
+        Ok(forecasts)
+    }
+
     /// Get download trends (daily counts) for a date range.
     ///
     /// Hosted serves (`download_statistics`) and proxy pull-through serves
@@ -435,6 +890,132 @@ impl AnalyticsService {
         Ok(trends)
     }
 
+    /// Compute the container layer deduplication report, with `limit`
+    /// entries in each layer ranking.
+    pub async fn get_layer_dedup_report(&self, limit: i64) -> Result<LayerDedupReport> {
+        let totals = sqlx::query_as::<_, LayerDedupTotals>(&format!(
+            r#"
+            WITH {LAYER_REFS_CTE_SQL}
+            SELECT
+                (SELECT COUNT(DISTINCT (repository_id, manifest_digest)) FROM layer_refs)::BIGINT
+                    AS manifests_scanned,
+                COUNT(*)::BIGINT AS distinct_layers,
+                COUNT(*) FILTER (WHERE manifest_count > 1)::BIGINT AS shared_layers,
+                COALESCE(SUM(size_bytes * manifest_count), 0)::BIGINT AS logical_bytes,
+                (COALESCE(SUM(size_bytes * manifest_count), 0) - COALESCE(SUM(saved_bytes), 0))::BIGINT
+                    AS stored_bytes
+            FROM layer_usage
+            "#
+        ))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let top_shared_layers = sqlx::query_as::<_, LayerUsage>(&format!(
+            r#"
+            WITH {LAYER_REFS_CTE_SQL}
+            SELECT digest, size_bytes, manifest_count, repository_count, repository_keys, saved_bytes
+            FROM layer_usage
+            WHERE manifest_count > 1
+            ORDER BY saved_bytes DESC, size_bytes DESC, digest
+            LIMIT $1
+            "#
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let top_unshared_layers = sqlx::query_as::<_, LayerUsage>(&format!(
+            r#"
+            WITH {LAYER_REFS_CTE_SQL}
+            SELECT digest, size_bytes, manifest_count, repository_count, repository_keys, saved_bytes
+            FROM layer_usage
+            WHERE manifest_count = 1
+            ORDER BY size_bytes DESC, digest
+            LIMIT $1
+            "#
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(LayerDedupReport {
+            manifests_scanned: totals.manifests_scanned,
+            distinct_layers: totals.distinct_layers,
+            shared_layers: totals.shared_layers,
+            logical_bytes: totals.logical_bytes,
+            stored_bytes: totals.stored_bytes,
+            deduplicated_bytes: totals.logical_bytes - totals.stored_bytes,
+            savings_percent: dedup_savings_percent(totals.logical_bytes, totals.stored_bytes),
+            top_shared_layers,
+            top_unshared_layers,
+        })
+    }
+
+    /// Upload deduplication savings between `from` and `to` (inclusive),
+    /// with up to `limit` repositories.
+    pub async fn get_upload_dedup_report(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i64,
+    ) -> Result<UploadDedupReport> {
+        let totals = sqlx::query_as::<_, UploadDedupTotals>(
+            r#"
+            SELECT
+                COUNT(*)::BIGINT AS deduplicated_uploads,
+                COUNT(*) FILTER (WHERE body_skipped)::BIGINT AS body_skipped_uploads,
+                COALESCE(SUM(bytes_saved), 0)::BIGINT AS bytes_saved,
+                COALESCE(SUM(bytes_saved) FILTER (WHERE body_skipped), 0)::BIGINT
+                    AS transfer_bytes_saved
+            FROM upload_dedup_events
+            WHERE created_at::DATE BETWEEN $1 AND $2
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let repositories = sqlx::query_as::<_, RepositoryUploadDedup>(
+            r#"
+            SELECT
+                e.repository_id,
+                r.key::TEXT AS repository_key,
+                COUNT(*)::BIGINT AS deduplicated_uploads,
+                COUNT(*) FILTER (WHERE e.body_skipped)::BIGINT AS body_skipped_uploads,
+                COALESCE(SUM(e.bytes_saved), 0)::BIGINT AS bytes_saved,
+                COALESCE(SUM(e.bytes_saved) FILTER (WHERE e.body_skipped), 0)::BIGINT
+                    AS transfer_bytes_saved
+            FROM upload_dedup_events e
+            JOIN repositories r ON r.id = e.repository_id
+            WHERE e.created_at::DATE BETWEEN $1 AND $2
+            GROUP BY e.repository_id, r.key
+            ORDER BY bytes_saved DESC, r.key
+            LIMIT $3
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(UploadDedupReport {
+            period_start: from,
+            period_end: to,
+            deduplicated_uploads: totals.deduplicated_uploads,
+            body_skipped_uploads: totals.body_skipped_uploads,
+            bytes_saved: totals.bytes_saved,
+            transfer_bytes_saved: totals.transfer_bytes_saved,
+            repositories,
+        })
+    }
+
     /// Cleanup old metric snapshots beyond retention period.
     pub async fn cleanup_old_snapshots(&self, keep_days: i32) -> Result<u64> {
         let result = sqlx::query(
@@ -656,6 +1237,7 @@ mod tests {
             artifacts_end: 250,
             artifacts_added: 150,
             downloads_in_period: 5000,
+            forecast: None,
         };
         let json = serde_json::to_value(&summary).unwrap();
         assert_eq!(json["storage_growth_percent"], 100.0);
@@ -675,6 +1257,7 @@ mod tests {
             artifacts_end: 10,
             artifacts_added: 0,
             downloads_in_period: 50,
+            forecast: None,
         };
         let json = serde_json::to_value(&summary).unwrap();
         assert_eq!(json["storage_growth_bytes"], 0);
@@ -773,6 +1356,52 @@ mod tests {
         assert_eq!(trend.proxy_download_count, 0);
     }
 
+    // -----------------------------------------------------------------------
+    // Layer dedup report
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_dedup_savings_percent() {
+        assert!((dedup_savings_percent(1_000, 250) - 75.0).abs() < 0.001);
+        assert_eq!(dedup_savings_percent(1_000, 1_000), 0.0);
+        assert_eq!(dedup_savings_percent(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_layer_dedup_report_serialization() {
+        let report = LayerDedupReport {
+            manifests_scanned: 3,
+            distinct_layers: 2,
+            shared_layers: 1,
+            logical_bytes: 300,
+            stored_bytes: 200,
+            deduplicated_bytes: 100,
+            savings_percent: dedup_savings_percent(300, 200),
+            top_shared_layers: vec![LayerUsage {
+                digest: "sha256:base".to_string(),
+                size_bytes: 100,
+                manifest_count: 2,
+                repository_count: 2,
+                repository_keys: vec!["app-a".to_string(), "app-b".to_string()],
+                saved_bytes: 100,
+            }],
+            top_unshared_layers: vec![],
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["deduplicated_bytes"], 100);
+        assert_eq!(json["top_shared_layers"][0]["repository_keys"][1], "app-b");
+        assert_eq!(json["top_unshared_layers"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_layer_refs_cte_counts_layers_per_physical_copy() {
+        // Only layer edges count, and savings are measured against physical
+        // copies (one per filesystem storage_path), not distinct digests.
+        assert!(LAYER_REFS_CTE_SQL.contains("mbr.kind = 'layer'"));
+        assert!(LAYER_REFS_CTE_SQL.contains("r.storage_backend = 'filesystem'"));
+        assert!(LAYER_REFS_CTE_SQL.contains("COUNT(DISTINCT (storage_backend, location))"));
+    }
+
     // -----------------------------------------------------------------------
     // #2704: proxy downloads are readable through the analytics read APIs
     // (DB-backed; skips cleanly when DATABASE_URL is unset)
@@ -859,4 +1488,105 @@ mod tests {
             "downloads/trend must include today's proxy serve in proxy_download_count (#2704), got {today_proxy}"
         );
     }
+
+    // -----------------------------------------------------------------------
+    // Storage forecasting
+    // -----------------------------------------------------------------------
+
+    fn daily(start: NaiveDate, values: impl IntoIterator<Item = i64>) -> Vec<(NaiveDate, i64)> {
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| (start + chrono::Duration::days(i as i64), v))
+            .collect()
+    }
+
+    #[test]
+    fn test_forecast_linear_growth_and_time_to_full() {
+        let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let points = daily(start, (0..10).map(|i| 1_000 + i * 100));
+        let today = start + chrono::Duration::days(9);
+        let forecast =
+            forecast_storage(&points, Some((10_000, CapacityLimit::Quota)), today).unwrap();
+
+        assert_eq!(forecast.method, "linear");
+        assert_eq!(forecast.samples, 10);
+        assert_eq!(forecast.current_bytes, 1_900);
+        assert!((forecast.daily_growth_bytes - 100.0).abs() < 1e-6);
+        assert_eq!(forecast.projected_bytes_30d, 4_900);
+        assert_eq!(forecast.projected_bytes_90d, 10_900);
+        assert_eq!(forecast.projected_bytes_180d, 19_900);
+        assert_eq!(forecast.days_until_full, Some(81));
+        assert_eq!(
+            forecast.estimated_full_date,
+            Some(today + chrono::Duration::days(81))
+        );
+        assert_eq!(forecast.capacity_limit, Some(CapacityLimit::Quota));
+    }
+
+    #[test]
+    fn test_forecast_flat_or_shrinking_never_fills() {
+        let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let today = start + chrono::Duration::days(4);
+        let flat = forecast_storage(
+            &daily(start, [500; 5]),
+            Some((1_000, CapacityLimit::Disk)),
+            today,
+        )
+        .unwrap();
+        assert_eq!(flat.days_until_full, None);
+        assert_eq!(flat.projected_bytes_180d, 500);
+
+        let shrinking =
+            forecast_storage(&daily(start, [500, 400, 300, 200, 100]), None, today).unwrap();
+        assert!(shrinking.daily_growth_bytes < 0.0);
+        assert_eq!(
+            shrinking.projected_bytes_30d, 0,
+            "projections never go negative"
+        );
+    }
+
+    #[test]
+    fn test_forecast_over_capacity_is_due_now() {
+        let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let forecast = forecast_storage(
+            &daily(start, [900, 1_100]),
+            Some((1_000, CapacityLimit::Quota)),
+            start + chrono::Duration::days(1),
+        )
+        .unwrap();
+        assert_eq!(forecast.days_until_full, Some(0));
+    }
+
+    #[test]
+    fn test_forecast_needs_two_days() {
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        assert!(forecast_storage(&[], None, day).is_none());
+        assert!(forecast_storage(&[(day, 10)], None, day).is_none());
+    }
+
+    #[test]
+    fn test_forecast_seasonal_weekday_pattern() {
+        use chrono::Datelike;
+
+        // Monday start; growth only on weekdays.
+        let start = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+        let mut bytes = 0;
+        let points: Vec<(NaiveDate, i64)> = (0..35)
+            .map(|i| {
+                let date = start + chrono::Duration::days(i);
+                if date.weekday().num_days_from_monday() < 5 {
+                    bytes += 1_000;
+                }
+                (date, bytes)
+            })
+            .collect();
+        let today = start + chrono::Duration::days(34);
+        let forecast = forecast_storage(&points, None, today).unwrap();
+        assert_eq!(forecast.method, "seasonal");
+        // ~5000 bytes per week.
+        assert!((forecast.daily_growth_bytes - 5_000.0 / 7.0).abs() < 50.0);
+        let linear_30d = forecast.current_bytes as f64 + 30.0 * 5_000.0 / 7.0;
+        assert!((forecast.projected_bytes_30d as f64 - linear_30d).abs() < 2_000.0);
+    }
 }
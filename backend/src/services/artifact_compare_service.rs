@@ -0,0 +1,495 @@
+//! Differential comparison of two versions of the same package.
+//!
+//! A comparison reports, between artifact `a` (usually the older version) and
+//! artifact `b`:
+//!
+//! - archive members added, removed or changed (size, or CRC-32 for ZIPs),
+//!   read from the archive index the same way previews are;
+//! - the size delta and whether the bytes are identical;
+//! - leaf-level differences in stored metadata and in the parsed manifest
+//!   (`MANIFEST.MF`, `package.json`);
+//! - declared dependencies added, removed or re-ranged, for the ecosystems
+//!   the dependency graph understands.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::artifact_preview_service::{self as preview, ArchiveEntry};
+use crate::services::dependency_graph_service::{ecosystem_for_format, extract_edges};
+use crate::storage::{StorageBackend, StorageLocation};
+
+/// Archive members read from each side.
+pub const MAX_COMPARE_ENTRIES: usize = 10_000;
+
+/// Metadata and manifest differences reported per comparison.
+const MAX_FIELD_CHANGES: usize = 500;
+
+/// Object nesting followed when diffing metadata; deeper values compare
+/// whole.
+const MAX_FIELD_DEPTH: usize = 6;
+
+/// One side of a comparison, as loaded from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CompareSide {
+    pub id: Uuid,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub format: String,
+    pub storage_backend: String,
+    pub storage_path: String,
+    pub path: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub size_bytes: i64,
+    pub checksum_sha256: String,
+    pub storage_key: String,
+    pub metadata: Option<Value>,
+}
+
+impl CompareSide {
+    /// Load a live artifact with its repository and stored metadata.
+    pub async fn load(db: &PgPool, id: Uuid) -> Result<Self> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            SELECT a.id, a.repository_id, r.key AS repository_key,
+                   r.format::text AS format, r.storage_backend, r.storage_path,
+                   a.path, a.name, a.version, a.size_bytes, a.checksum_sha256,
+                   a.storage_key, m.metadata
+            FROM artifacts a
+            JOIN repositories r ON r.id = a.repository_id
+            LEFT JOIN artifact_metadata m ON m.artifact_id = a.id
+            WHERE a.id = $1 AND a.is_deleted = false
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Artifact {} not found", id)))
+    }
+
+    pub fn storage_location(&self) -> StorageLocation {
+        StorageLocation {
+            backend: self.storage_backend.clone(),
+            path: self.storage_path.clone(),
+        }
+    }
+}
+
+/// Summary of one compared artifact.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComparedArtifact {
+    pub id: Uuid,
+    pub repository_key: String,
+    pub path: String,
+    pub version: Option<String>,
+    pub size_bytes: i64,
+    pub checksum_sha256: String,
+}
+
+impl From<&CompareSide> for ComparedArtifact {
+    fn from(side: &CompareSide) -> Self {
+        Self {
+            id: side.id,
+            repository_key: side.repository_key.clone(),
+            path: side.path.clone(),
+            version: side.version.clone(),
+            size_bytes: side.size_bytes,
+            checksum_sha256: side.checksum_sha256.trim().to_string(),
+        }
+    }
+}
+
+/// The difference between two artifacts.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArtifactComparison {
+    pub name: String,
+    pub a: ComparedArtifact,
+    pub b: ComparedArtifact,
+    /// `b.size_bytes - a.size_bytes`.
+    pub size_delta: i64,
+    /// Whether both artifacts have the same SHA-256.
+    pub identical: bool,
+    /// Member changes, when both artifacts are archives.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<ArchiveDiff>,
+    pub metadata_changes: Vec<FieldChange>,
+    pub manifest_changes: Vec<FieldChange>,
+    /// Dependency changes, for ecosystems with dependency metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<DependencyDiff>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ArchiveDiff {
+    pub added: Vec<ArchiveEntry>,
+    pub removed: Vec<ArchiveEntry>,
+    pub changed: Vec<MemberChange>,
+    pub unchanged_count: u64,
+    /// Whether either listing stopped before the end of its archive, so
+    /// members past that point are not compared.
+    pub truncated: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct MemberChange {
+    pub path: String,
+    pub size_a: u64,
+    pub size_b: u64,
+    pub size_delta: i64,
+}
+
+/// A value that differs between the two sides. `a` or `b` is absent when the
+/// field exists on one side only.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct FieldChange {
+    /// Dotted path to the field, e.g. `version_data.engines.node`.
+    pub field: String,
+    #[schema(value_type = Option<Object>)]
+    pub a: Option<Value>,
+    #[schema(value_type = Option<Object>)]
+    pub b: Option<Value>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct DependencyDiff {
+    pub ecosystem: String,
+    pub added: Vec<DependencyChange>,
+    pub removed: Vec<DependencyChange>,
+    pub changed: Vec<DependencyChange>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DependencyChange {
+    pub name: String,
+    pub requirement_a: Option<String>,
+    pub requirement_b: Option<String>,
+}
+
+/// Reject pairs that are not two versions of one package.
+pub fn check_comparable(a: &CompareSide, b: &CompareSide) -> Result<()> {
+    if a.id == b.id {
+        return Err(AppError::Validation(
+            "Cannot compare an artifact with itself".to_string(),
+        ));
+    }
+    if a.format != b.format || a.name != b.name {
+        return Err(AppError::Validation(format!(
+            "Artifacts are not versions of the same package ({} '{}' vs {} '{}')",
+            a.format, a.name, b.format, b.name
+        )));
+    }
+    Ok(())
+}
+
+/// Compare two artifacts whose storage has been resolved by the caller.
+pub async fn compare(
+    a: &CompareSide,
+    a_storage: &dyn StorageBackend,
+    b: &CompareSide,
+    b_storage: &dyn StorageBackend,
+) -> Result<ArtifactComparison> {
+    check_comparable(a, b)?;
+    let identical = a.checksum_sha256.trim() == b.checksum_sha256.trim();
+
+    let mut archive = None;
+    let mut manifest_changes = Vec::new();
+    if !identical {
+        let listing_a = list(a, a_storage).await?;
+        let listing_b = list(b, b_storage).await?;
+        if let (Some((list_a, manifest_a)), Some((list_b, manifest_b))) = (listing_a, listing_b) {
+            let mut diff = diff_members(&list_a.entries, &list_b.entries);
+            diff.truncated = list_a.truncated || list_b.truncated;
+            archive = Some(diff);
+            let content = |m: Option<preview::PreviewManifest>| m.map(|m| m.content);
+            manifest_changes =
+                diff_fields(content(manifest_a).as_ref(), content(manifest_b).as_ref());
+        }
+    }
+
+    let dependencies = ecosystem_for_format(&a.format).map(|ecosystem| {
+        let deps = |side: &CompareSide| {
+            side.metadata
+                .as_ref()
+                .map(|m| extract_edges(ecosystem, m))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|e| (e.dependency_key, e.requirement))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let mut diff = diff_dependencies(&deps(a), &deps(b));
+        diff.ecosystem = ecosystem.to_string();
+        diff
+    });
+
+    Ok(ArtifactComparison {
+        name: a.name.clone(),
+        a: a.into(),
+        b: b.into(),
+        size_delta: b.size_bytes - a.size_bytes,
+        identical,
+        archive,
+        metadata_changes: diff_fields(a.metadata.as_ref(), b.metadata.as_ref()),
+        manifest_changes,
+        dependencies,
+    })
+}
+
+async fn list(
+    side: &CompareSide,
+    storage: &dyn StorageBackend,
+) -> Result<Option<(preview::ArchiveListing, Option<preview::PreviewManifest>)>> {
+    preview::list_archive(
+        storage,
+        &side.storage_key,
+        &side.path,
+        side.size_bytes.max(0) as u64,
+        MAX_COMPARE_ENTRIES,
+    )
+    .await
+}
+
+/// Match archive members by path. A member changed when its size differs,
+/// or when both sides carry a CRC-32 and it differs.
+pub fn diff_members(a: &[ArchiveEntry], b: &[ArchiveEntry]) -> ArchiveDiff {
+    let by_path_a: BTreeMap<&str, &ArchiveEntry> = a.iter().map(|e| (e.path.as_str(), e)).collect();
+    let by_path_b: BTreeMap<&str, &ArchiveEntry> = b.iter().map(|e| (e.path.as_str(), e)).collect();
+
+    let mut diff = ArchiveDiff::default();
+    for (path, entry_a) in &by_path_a {
+        match by_path_b.get(path) {
+            None => diff.removed.push((*entry_a).clone()),
+            Some(entry_b) => {
+                let crc_differs = matches!(
+                    (entry_a.crc32, entry_b.crc32),
+                    (Some(x), Some(y)) if x != y
+                );
+                if entry_a.size_bytes != entry_b.size_bytes || crc_differs {
+                    diff.changed.push(MemberChange {
+                        path: path.to_string(),
+                        size_a: entry_a.size_bytes,
+                        size_b: entry_b.size_bytes,
+                        size_delta: entry_b.size_bytes as i64 - entry_a.size_bytes as i64,
+                    });
+                } else {
+                    diff.unchanged_count += 1;
+                }
+            }
+        }
+    }
+    diff.added = by_path_b
+        .iter()
+        .filter(|(path, _)| !by_path_a.contains_key(*path))
+        .map(|(_, entry)| (*entry).clone())
+        .collect();
+    diff
+}
+
+/// Leaf-level differences between two JSON documents, in field order.
+/// Objects are followed up to [`MAX_FIELD_DEPTH`]; arrays and scalars compare
+/// whole.
+pub fn diff_fields(a: Option<&Value>, b: Option<&Value>) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_value("", a, b, 0, &mut changes);
+    changes
+}
+
+fn diff_value(
+    field: &str,
+    a: Option<&Value>,
+    b: Option<&Value>,
+    depth: usize,
+    out: &mut Vec<FieldChange>,
+) {
+    if out.len() >= MAX_FIELD_CHANGES || a == b {
+        return;
+    }
+    // Objects are followed field by field, including against a missing
+    // side, so newly added metadata lists what was added.
+    let objects = matches!(
+        (a, b),
+        (Some(Value::Object(_)) | None, Some(Value::Object(_)) | None)
+    );
+    if objects && depth < MAX_FIELD_DEPTH {
+        let empty = serde_json::Map::new();
+        let map_a = match a {
+            Some(Value::Object(map)) => map,
+            _ => &empty,
+        };
+        let map_b = match b {
+            Some(Value::Object(map)) => map,
+            _ => &empty,
+        };
+        let keys: BTreeSet<&String> = map_a.keys().chain(map_b.keys()).collect();
+        for key in keys {
+            diff_value(
+                &join_field(field, key),
+                map_a.get(key),
+                map_b.get(key),
+                depth + 1,
+                out,
+            );
+        }
+        return;
+    }
+    out.push(FieldChange {
+        field: field.to_string(),
+        a: a.cloned(),
+        b: b.cloned(),
+    });
+}
+
+fn join_field(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Compare declared dependencies keyed by package.
+pub fn diff_dependencies(
+    a: &BTreeMap<String, Option<String>>,
+    b: &BTreeMap<String, Option<String>>,
+) -> DependencyDiff {
+    let mut diff = DependencyDiff::default();
+    for (name, req_a) in a {
+        match b.get(name) {
+            None => diff.removed.push(DependencyChange {
+                name: name.clone(),
+                requirement_a: req_a.clone(),
+                requirement_b: None,
+            }),
+            Some(req_b) if req_b != req_a => diff.changed.push(DependencyChange {
+                name: name.clone(),
+                requirement_a: req_a.clone(),
+                requirement_b: req_b.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (name, req_b) in b {
+        if !a.contains_key(name) {
+            diff.added.push(DependencyChange {
+                name: name.clone(),
+                requirement_a: None,
+                requirement_b: req_b.clone(),
+            });
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(path: &str, size: u64, crc32: Option<u32>) -> ArchiveEntry {
+        ArchiveEntry {
+            path: path.to_string(),
+            size_bytes: size,
+            compressed_bytes: None,
+            crc32,
+            is_dir: false,
+        }
+    }
+
+    fn side(id: u128, format: &str, name: &str) -> CompareSide {
+        CompareSide {
+            id: Uuid::from_u128(id),
+            repository_id: Uuid::nil(),
+            repository_key: "npm-local".to_string(),
+            format: format.to_string(),
+            storage_backend: "filesystem".to_string(),
+            storage_path: "/tmp".to_string(),
+            path: format!("{name}/-/{name}-1.0.0.tgz"),
+            name: name.to_string(),
+            version: Some("1.0.0".to_string()),
+            size_bytes: 10,
+            checksum_sha256: "abc".to_string(),
+            storage_key: "k".to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_members_classifies_paths() {
+        let a = vec![
+            entry("lib/a.js", 10, Some(1)),
+            entry("lib/b.js", 20, Some(2)),
+            entry("README.md", 5, Some(3)),
+        ];
+        let b = vec![
+            entry("lib/a.js", 10, Some(9)),
+            entry("README.md", 5, Some(3)),
+            entry("lib/c.js", 7, Some(4)),
+        ];
+        let diff = diff_members(&a, &b);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].path, "lib/b.js");
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path, "lib/c.js");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].path, "lib/a.js");
+        assert_eq!(diff.unchanged_count, 1);
+    }
+
+    #[test]
+    fn test_diff_members_without_crc_uses_size() {
+        let diff = diff_members(&[entry("x", 1, None)], &[entry("x", 3, None)]);
+        assert_eq!(diff.changed[0].size_delta, 2);
+    }
+
+    #[test]
+    fn test_diff_fields_reports_leaf_paths() {
+        let a = json!({"version": "1.2.3", "engines": {"node": ">=14"}, "keep": [1, 2]});
+        let b =
+            json!({"version": "1.2.4", "engines": {"node": ">=16", "npm": ">=8"}, "keep": [1, 2]});
+        let changes = diff_fields(Some(&a), Some(&b));
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["engines.node", "engines.npm", "version"]);
+        assert_eq!(changes[1].a, None);
+        assert_eq!(changes[1].b, Some(json!(">=8")));
+    }
+
+    #[test]
+    fn test_diff_fields_missing_side() {
+        let b = json!({"Main-Class": "a.B"});
+        let changes = diff_fields(None, Some(&b));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "Main-Class");
+        assert!(diff_fields(None, None).is_empty());
+    }
+
+    #[test]
+    fn test_diff_dependencies() {
+        let a = BTreeMap::from([
+            ("left-pad".to_string(), Some("^1.0.0".to_string())),
+            ("lodash".to_string(), Some("^4.17.0".to_string())),
+        ]);
+        let b = BTreeMap::from([
+            ("lodash".to_string(), Some("^4.17.21".to_string())),
+            ("chalk".to_string(), Some("^5.0.0".to_string())),
+        ]);
+        let diff = diff_dependencies(&a, &b);
+        assert_eq!(diff.added[0].name, "chalk");
+        assert_eq!(diff.removed[0].name, "left-pad");
+        assert_eq!(diff.changed[0].requirement_b.as_deref(), Some("^4.17.21"));
+    }
+
+    #[test]
+    fn test_check_comparable() {
+        let a = side(1, "npm", "lodash");
+        assert!(check_comparable(&a, &side(2, "npm", "lodash")).is_ok());
+        assert!(check_comparable(&a, &a.clone()).is_err());
+        assert!(check_comparable(&a, &side(2, "npm", "chalk")).is_err());
+        assert!(check_comparable(&a, &side(2, "cargo", "lodash")).is_err());
+    }
+}
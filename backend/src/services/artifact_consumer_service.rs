@@ -0,0 +1,236 @@
+//! Artifact usage tracing.
+//!
+//! Records where an artifact was consumed (environment, host and optional
+//! deployment) as reported by download clients or CI plugins, building a
+//! per-artifact deployment map for incident response.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// Longest accepted environment name.
+pub const MAX_ENVIRONMENT_LEN: usize = 128;
+/// Longest accepted host or deployment name.
+pub const MAX_HOST_LEN: usize = 255;
+/// Longest accepted report source.
+pub const MAX_SOURCE_LEN: usize = 64;
+
+/// A place an artifact was consumed.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ArtifactConsumer {
+    pub id: Uuid,
+    pub artifact_id: Uuid,
+    pub environment: String,
+    pub host: String,
+    /// Deployment or service name; empty when not reported.
+    pub deployment: String,
+    /// Reporting client (e.g. `github-actions`, `argocd`).
+    pub source: Option<String>,
+    pub reported_by: Option<Uuid>,
+    pub report_count: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// A validated consumption report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsumerReport {
+    pub environment: String,
+    pub host: String,
+    pub deployment: String,
+    pub source: Option<String>,
+}
+
+fn validate_field(field: &str, value: &str, max: usize) -> Result<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::Validation(format!("{} is required", field)));
+    }
+    if value.chars().count() > max {
+        return Err(AppError::Validation(format!(
+            "{} must be at most {} characters",
+            field, max
+        )));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(AppError::Validation(format!(
+            "{} must not contain control characters",
+            field
+        )));
+    }
+    Ok(value.to_string())
+}
+
+impl ConsumerReport {
+    /// Validate and trim the reported fields. Blank optional fields are
+    /// treated as absent.
+    pub fn new(
+        environment: &str,
+        host: &str,
+        deployment: Option<&str>,
+        source: Option<&str>,
+    ) -> Result<Self> {
+        let environment = validate_field("environment", environment, MAX_ENVIRONMENT_LEN)?;
+        let host = validate_field("host", host, MAX_HOST_LEN)?;
+        let deployment = match deployment.map(str::trim).filter(|d| !d.is_empty()) {
+            Some(d) => validate_field("deployment", d, MAX_HOST_LEN)?,
+            None => String::new(),
+        };
+        let source = source
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| validate_field("source", s, MAX_SOURCE_LEN))
+            .transpose()?;
+        Ok(Self {
+            environment,
+            host,
+            deployment,
+            source,
+        })
+    }
+}
+
+pub struct ArtifactConsumerService {
+    db: PgPool,
+}
+
+impl ArtifactConsumerService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Record a consumption. Re-reporting the same environment, host and
+    /// deployment updates `last_seen_at` and increments `report_count`.
+    pub async fn record(
+        &self,
+        artifact_id: Uuid,
+        report: &ConsumerReport,
+        reported_by: Option<Uuid>,
+    ) -> Result<ArtifactConsumer> {
+        let consumer: ArtifactConsumer = sqlx::query_as(
+            r#"
+            INSERT INTO artifact_consumers
+                (artifact_id, environment, host, deployment, source, reported_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (artifact_id, environment, host, deployment)
+            DO UPDATE SET report_count = artifact_consumers.report_count + 1,
+                          last_seen_at = NOW(),
+                          source = COALESCE(EXCLUDED.source, artifact_consumers.source),
+                          reported_by = EXCLUDED.reported_by
+            RETURNING id, artifact_id, environment, host, deployment, source,
+                      reported_by, report_count, first_seen_at, last_seen_at
+            "#,
+        )
+        .bind(artifact_id)
+        .bind(&report.environment)
+        .bind(&report.host)
+        .bind(&report.deployment)
+        .bind(&report.source)
+        .bind(reported_by)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(consumer)
+    }
+
+    /// Consumers of an artifact, most recently seen first, optionally for
+    /// one environment.
+    pub async fn list(
+        &self,
+        artifact_id: Uuid,
+        environment: Option<&str>,
+    ) -> Result<Vec<ArtifactConsumer>> {
+        let consumers: Vec<ArtifactConsumer> = sqlx::query_as(
+            r#"
+            SELECT id, artifact_id, environment, host, deployment, source,
+                   reported_by, report_count, first_seen_at, last_seen_at
+            FROM artifact_consumers
+            WHERE artifact_id = $1
+              AND ($2::TEXT IS NULL OR environment = $2)
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(artifact_id)
+        .bind(environment)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(consumers)
+    }
+
+    /// Remove a consumer record (e.g. a decommissioned host). Returns
+    /// `false` when no such record exists for the artifact.
+    pub async fn remove(&self, artifact_id: Uuid, consumer_id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM artifact_consumers WHERE id = $1 AND artifact_id = $2")
+                .bind(consumer_id)
+                .bind(artifact_id)
+                .execute(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Distinct environments in a consumer list, sorted.
+pub fn environments(consumers: &[ArtifactConsumer]) -> Vec<String> {
+    let mut envs: Vec<String> = consumers.iter().map(|c| c.environment.clone()).collect();
+    envs.sort();
+    envs.dedup();
+    envs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consumer_report_trims_and_defaults() {
+        let report =
+            ConsumerReport::new(" production ", "web-01.internal", Some("  "), None).unwrap();
+        assert_eq!(report.environment, "production");
+        assert_eq!(report.host, "web-01.internal");
+        assert_eq!(report.deployment, "");
+        assert_eq!(report.source, None);
+    }
+
+    #[test]
+    fn test_consumer_report_requires_environment_and_host() {
+        assert!(ConsumerReport::new("", "web-01", None, None).is_err());
+        assert!(ConsumerReport::new("prod", "  ", None, None).is_err());
+    }
+
+    #[test]
+    fn test_consumer_report_rejects_overlong_and_control_chars() {
+        let long_env = "e".repeat(MAX_ENVIRONMENT_LEN + 1);
+        assert!(ConsumerReport::new(&long_env, "web-01", None, None).is_err());
+        let long_source = "s".repeat(MAX_SOURCE_LEN + 1);
+        assert!(ConsumerReport::new("prod", "web-01", None, Some(&long_source)).is_err());
+        assert!(ConsumerReport::new("prod", "web\n01", None, None).is_err());
+    }
+
+    #[test]
+    fn test_environments_sorted_and_deduplicated() {
+        let consumer = |env: &str| ArtifactConsumer {
+            id: Uuid::new_v4(),
+            artifact_id: Uuid::nil(),
+            environment: env.to_string(),
+            host: "h".to_string(),
+            deployment: String::new(),
+            source: None,
+            reported_by: None,
+            report_count: 1,
+            first_seen_at: Utc::now(),
+            last_seen_at: Utc::now(),
+        };
+        let list = vec![consumer("staging"), consumer("prod"), consumer("staging")];
+        assert_eq!(environments(&list), vec!["prod", "staging"]);
+    }
+}
@@ -0,0 +1,762 @@
+//! Inline artifact previews for the file inspector.
+//!
+//! A preview reads only what it needs through ranged storage reads:
+//!
+//! - text files: the first `max_bytes` bytes;
+//! - ZIP-family archives (`.zip`, `.jar`, `.whl`, `.nupkg`, …): the end of
+//!   central directory and the central directory itself, plus the one member
+//!   holding the manifest;
+//! - tar archives (`.tar`, `.tar.gz`, `.tgz`, `.crate`, …): a bounded prefix
+//!   of the stream, so very large tarballs list only their leading members;
+//! - `META-INF/MANIFEST.MF` and `package.json`, standalone or inside an
+//!   archive, are parsed into JSON.
+//!
+//! Everything else is reported as binary. Decoding runs under the read-path
+//! extraction budget ([`with_registry_extraction`]).
+
+use std::io::Read;
+
+use futures::StreamExt;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::error::{AppError, Result};
+use crate::storage::StorageBackend;
+use crate::util::bounded_archive::{budgeted_to, read_capped, with_registry_extraction};
+
+/// Default and maximum text bytes returned.
+pub const DEFAULT_PREVIEW_TEXT_BYTES: usize = 64 * 1024;
+pub const MAX_PREVIEW_TEXT_BYTES: usize = 1024 * 1024;
+
+/// Default and maximum archive members listed.
+pub const DEFAULT_PREVIEW_ENTRIES: usize = 500;
+pub const MAX_PREVIEW_ENTRIES: usize = 5_000;
+
+/// Largest ZIP central directory read.
+const MAX_CENTRAL_DIRECTORY_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Compressed prefix of a tar stream scanned for members.
+const MAX_TAR_SCAN_BYTES: usize = 8 * 1024 * 1024;
+
+/// Decompressed bytes a tar walk may inflate.
+const MAX_TAR_DECODED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Largest manifest member read from an archive.
+const MAX_MANIFEST_BYTES: u64 = 1024 * 1024;
+
+const ZIP_EOCD_SIG: u32 = 0x0605_4b50;
+const ZIP64_EOCD_LOCATOR_SIG: u32 = 0x0706_4b50;
+const ZIP64_EOCD_SIG: u32 = 0x0606_4b50;
+const ZIP_CENTRAL_SIG: u32 = 0x0201_4b50;
+const ZIP_LOCAL_SIG: u32 = 0x0403_4b50;
+const ZIP_EOCD_LEN: usize = 22;
+
+/// What the preview contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewKind {
+    Text,
+    Archive,
+    Binary,
+}
+
+/// An artifact preview.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArtifactPreview {
+    pub path: String,
+    pub kind: PreviewKind,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Leading text of a text file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Whether `text` stops before the end of the file.
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<ArchiveListing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<PreviewManifest>,
+}
+
+/// Members of an archive artifact.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArchiveListing {
+    /// `zip`, `tar` or `tar.gz`.
+    pub format: String,
+    pub entries: Vec<ArchiveEntry>,
+    /// Member count from the ZIP central directory. Unknown for tar, which
+    /// has no index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_entries: Option<u64>,
+    /// Whether members exist beyond `entries`.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub size_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compressed_bytes: Option<u64>,
+    /// CRC-32 from the ZIP central directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<u32>,
+    pub is_dir: bool,
+}
+
+/// A parsed package manifest.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewManifest {
+    /// `jar_manifest` or `package_json`.
+    pub kind: String,
+    /// Member path inside the archive, or the artifact path.
+    pub path: String,
+    #[schema(value_type = Object)]
+    pub content: serde_json::Value,
+}
+
+/// How a path is previewed, from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let lower = path.to_ascii_lowercase();
+        const ZIP: &[&str] = &[
+            ".zip", ".jar", ".war", ".ear", ".aar", ".whl", ".nupkg", ".snupkg", ".vsix", ".egg",
+            ".apk",
+        ];
+        const TAR_GZ: &[&str] = &[".tar.gz", ".tgz", ".crate"];
+        if ZIP.iter().any(|ext| lower.ends_with(ext)) {
+            Some(Self::Zip)
+        } else if TAR_GZ.iter().any(|ext| lower.ends_with(ext)) {
+            Some(Self::TarGz)
+        } else if lower.ends_with(".tar") || lower.ends_with(".gem") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Tar => "tar",
+            Self::TarGz => "tar.gz",
+        }
+    }
+}
+
+/// Build a preview of the object at `storage_key`.
+pub async fn preview(
+    storage: &dyn StorageBackend,
+    storage_key: &str,
+    path: &str,
+    size_bytes: i64,
+    content_type: String,
+    max_text_bytes: usize,
+    max_entries: usize,
+) -> Result<ArtifactPreview> {
+    let size = size_bytes.max(0) as u64;
+    let mut preview = ArtifactPreview {
+        path: path.to_string(),
+        kind: PreviewKind::Binary,
+        content_type,
+        size_bytes,
+        text: None,
+        truncated: false,
+        archive: None,
+        manifest: None,
+    };
+
+    if let Some((listing, manifest)) =
+        list_archive(storage, storage_key, path, size, max_entries).await?
+    {
+        preview.kind = PreviewKind::Archive;
+        preview.archive = Some(listing);
+        preview.manifest = manifest;
+        return Ok(preview);
+    }
+
+    if size == 0 {
+        preview.kind = PreviewKind::Text;
+        preview.text = Some(String::new());
+        return Ok(preview);
+    }
+    let want = (max_text_bytes as u64).min(size) as usize;
+    let head = storage.get_range(storage_key, 0, want).await?;
+    if let Some(text) = decode_text_prefix(&head) {
+        preview.kind = PreviewKind::Text;
+        preview.truncated = (head.len() as u64) < size;
+        if !preview.truncated {
+            preview.manifest = parse_manifest(path, text.as_bytes());
+        }
+        preview.text = Some(text);
+    }
+    Ok(preview)
+}
+
+/// List the members of an archive artifact, with its manifest when one is
+/// found. Returns `None` when `path` does not name an archive format.
+pub async fn list_archive(
+    storage: &dyn StorageBackend,
+    storage_key: &str,
+    path: &str,
+    size: u64,
+    max_entries: usize,
+) -> Result<Option<(ArchiveListing, Option<PreviewManifest>)>> {
+    let Some(format) = ArchiveFormat::from_path(path) else {
+        return Ok(None);
+    };
+    let listing = match format {
+        ArchiveFormat::Zip => preview_zip(storage, storage_key, size, max_entries).await?,
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            preview_tar(storage, storage_key, format, max_entries).await?
+        }
+    };
+    Ok(Some(listing))
+}
+
+/// Decode a file prefix as UTF-8 text. A multi-byte character cut off by the
+/// read is dropped; NUL bytes or invalid sequences mean binary.
+pub fn decode_text_prefix(bytes: &[u8]) -> Option<String> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Some(s.to_string()),
+        Err(e) if e.error_len().is_none() => {
+            Some(String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned())
+        }
+        Err(_) => None,
+    }
+}
+
+/// Parse `bytes` as a manifest when `member_path` names one.
+pub fn parse_manifest(member_path: &str, bytes: &[u8]) -> Option<PreviewManifest> {
+    let name = member_path.rsplit('/').next().unwrap_or(member_path);
+    let (kind, content) = if name.eq_ignore_ascii_case("MANIFEST.MF") {
+        (
+            "jar_manifest",
+            parse_jar_manifest(&String::from_utf8_lossy(bytes)),
+        )
+    } else if name == "package.json" {
+        ("package_json", serde_json::from_slice(bytes).ok()?)
+    } else {
+        return None;
+    };
+    Some(PreviewManifest {
+        kind: kind.to_string(),
+        path: member_path.to_string(),
+        content,
+    })
+}
+
+/// Main-section attributes of a JAR manifest. Continuation lines start with
+/// a single space; the main section ends at the first blank line.
+pub fn parse_jar_manifest(text: &str) -> serde_json::Value {
+    let mut attrs = serde_json::Map::new();
+    let mut current: Option<(String, String)> = None;
+    for line in text.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() {
+            break;
+        }
+        if let Some(cont) = line.strip_prefix(' ') {
+            if let Some((_, value)) = current.as_mut() {
+                value.push_str(cont);
+            }
+            continue;
+        }
+        if let Some((key, value)) = current.take() {
+            attrs.insert(key, serde_json::Value::String(value));
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            current = Some((key.trim().to_string(), value.trim_start().to_string()));
+        }
+    }
+    if let Some((key, value)) = current {
+        attrs.insert(key, serde_json::Value::String(value));
+    }
+    serde_json::Value::Object(attrs)
+}
+
+/// Whether a member path is the package manifest a preview parses.
+fn is_manifest_member(path: &str) -> bool {
+    let path = path.trim_start_matches("./");
+    path.eq_ignore_ascii_case("META-INF/MANIFEST.MF")
+        || path == "package.json"
+        || (path.split('/').count() == 2 && path.ends_with("/package.json"))
+}
+
+fn le_u16(b: &[u8], at: usize) -> Option<u64> {
+    b.get(at..at + 2)
+        .map(|s| u16::from_le_bytes([s[0], s[1]]) as u64)
+}
+
+fn le_u32(b: &[u8], at: usize) -> Option<u64> {
+    b.get(at..at + 4)
+        .map(|s| u32::from_le_bytes([s[0], s[1], s[2], s[3]]) as u64)
+}
+
+fn le_u64(b: &[u8], at: usize) -> Option<u64> {
+    b.get(at..at + 8)
+        .map(|s| u64::from_le_bytes(s.try_into().expect("8-byte slice")))
+}
+
+fn invalid_zip(what: &str) -> AppError {
+    AppError::Validation(format!("Invalid ZIP archive: {what}"))
+}
+
+/// Location of a ZIP central directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CentralDirectory {
+    pub offset: u64,
+    pub size: u64,
+    pub entries: u64,
+}
+
+/// Where the end-of-central-directory search found the directory.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EocdLookup {
+    Found(CentralDirectory),
+    /// ZIP64: the real record is at this absolute offset.
+    Zip64At(u64),
+}
+
+/// Find the end of central directory record in `tail`, the last bytes of an
+/// archive.
+pub fn find_eocd(tail: &[u8]) -> Result<EocdLookup> {
+    if tail.len() < ZIP_EOCD_LEN {
+        return Err(invalid_zip("too short"));
+    }
+    let pos = (0..=tail.len() - ZIP_EOCD_LEN)
+        .rev()
+        .find(|&i| {
+            le_u32(tail, i) == Some(ZIP_EOCD_SIG as u64)
+                && le_u16(tail, i + 20).map(|c| i + ZIP_EOCD_LEN + c as usize) == Some(tail.len())
+        })
+        .ok_or_else(|| invalid_zip("no end of central directory record"))?;
+    let entries = le_u16(tail, pos + 10).unwrap_or(0);
+    let cd_size = le_u32(tail, pos + 12).unwrap_or(0);
+    let cd_offset = le_u32(tail, pos + 16).unwrap_or(0);
+    if entries == 0xFFFF || cd_size == 0xFFFF_FFFF || cd_offset == 0xFFFF_FFFF {
+        let locator = pos
+            .checked_sub(20)
+            .filter(|&l| le_u32(tail, l) == Some(ZIP64_EOCD_LOCATOR_SIG as u64))
+            .ok_or_else(|| invalid_zip("missing ZIP64 locator"))?;
+        let record = le_u64(tail, locator + 8).ok_or_else(|| invalid_zip("bad ZIP64 locator"))?;
+        return Ok(EocdLookup::Zip64At(record));
+    }
+    Ok(EocdLookup::Found(CentralDirectory {
+        offset: cd_offset,
+        size: cd_size,
+        entries,
+    }))
+}
+
+/// Parse a ZIP64 end of central directory record.
+pub fn parse_zip64_eocd(record: &[u8]) -> Result<CentralDirectory> {
+    if le_u32(record, 0) != Some(ZIP64_EOCD_SIG as u64) {
+        return Err(invalid_zip("bad ZIP64 end of central directory"));
+    }
+    match (le_u64(record, 32), le_u64(record, 40), le_u64(record, 48)) {
+        (Some(entries), Some(size), Some(offset)) => Ok(CentralDirectory {
+            offset,
+            size,
+            entries,
+        }),
+        _ => Err(invalid_zip("truncated ZIP64 end of central directory")),
+    }
+}
+
+/// A central directory record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipMember {
+    pub name: String,
+    pub method: u16,
+    pub crc32: u32,
+    pub compressed_size: u64,
+    pub size: u64,
+    pub local_header_offset: u64,
+}
+
+/// Parse up to `limit` central directory records. Returns the members and
+/// whether more records followed.
+pub fn parse_central_directory(cd: &[u8], limit: usize) -> Result<(Vec<ZipMember>, bool)> {
+    let mut members = Vec::new();
+    let mut at = 0usize;
+    while le_u32(cd, at) == Some(ZIP_CENTRAL_SIG as u64) {
+        if members.len() == limit {
+            return Ok((members, true));
+        }
+        let field = |off: usize, len: usize| -> Result<u64> {
+            match len {
+                2 => le_u16(cd, at + off),
+                _ => le_u32(cd, at + off),
+            }
+            .ok_or_else(|| invalid_zip("truncated central directory"))
+        };
+        let method = field(10, 2)? as u16;
+        let crc32 = field(16, 4)? as u32;
+        let mut compressed_size = field(20, 4)?;
+        let mut size = field(24, 4)?;
+        let name_len = field(28, 2)? as usize;
+        let extra_len = field(30, 2)? as usize;
+        let comment_len = field(32, 2)? as usize;
+        let mut local_header_offset = field(42, 4)?;
+        let name_start = at + 46;
+        let extra_start = name_start + name_len;
+        let name = cd
+            .get(name_start..extra_start)
+            .ok_or_else(|| invalid_zip("truncated member name"))?;
+        let extra = cd
+            .get(extra_start..extra_start + extra_len)
+            .ok_or_else(|| invalid_zip("truncated extra field"))?;
+
+        // ZIP64 extended information carries, in order, only the fields
+        // saturated in the fixed record.
+        let mut e = 0usize;
+        while e + 4 <= extra.len() {
+            let id = le_u16(extra, e).unwrap_or(0);
+            let len = le_u16(extra, e + 2).unwrap_or(0) as usize;
+            if id == 0x0001 {
+                let mut p = e + 4;
+                for value in [&mut size, &mut compressed_size, &mut local_header_offset] {
+                    if *value == 0xFFFF_FFFF {
+                        if let Some(v) = le_u64(extra, p) {
+                            *value = v;
+                            p += 8;
+                        }
+                    }
+                }
+            }
+            e += 4 + len;
+        }
+
+        members.push(ZipMember {
+            name: String::from_utf8_lossy(name).into_owned(),
+            method,
+            crc32,
+            compressed_size,
+            size,
+            local_header_offset,
+        });
+        at = extra_start + extra_len + comment_len;
+    }
+    Ok((members, false))
+}
+
+async fn preview_zip(
+    storage: &dyn StorageBackend,
+    key: &str,
+    size: u64,
+    max_entries: usize,
+) -> Result<(ArchiveListing, Option<PreviewManifest>)> {
+    let tail_len = size.min((ZIP_EOCD_LEN + u16::MAX as usize) as u64);
+    let tail = storage
+        .get_range(key, size - tail_len, tail_len as usize)
+        .await?;
+    let cd = match find_eocd(&tail)? {
+        EocdLookup::Found(cd) => cd,
+        EocdLookup::Zip64At(offset) => {
+            parse_zip64_eocd(&storage.get_range(key, offset, 56).await?)?
+        }
+    };
+    let cd_len = cd.size.min(MAX_CENTRAL_DIRECTORY_BYTES);
+    let cd_bytes = storage.get_range(key, cd.offset, cd_len as usize).await?;
+    let (members, more) = parse_central_directory(&cd_bytes, max_entries)?;
+    let truncated = more || cd.size > cd_len || (members.len() as u64) < cd.entries;
+
+    let manifest_member = members
+        .iter()
+        .find(|m| is_manifest_member(&m.name) && m.size <= MAX_MANIFEST_BYTES)
+        .cloned();
+    let manifest = match manifest_member {
+        Some(member) => match read_zip_member(storage, key, &member).await {
+            Ok(bytes) => parse_manifest(&member.name, &bytes),
+            Err(e) => {
+                tracing::debug!(member = %member.name, "Skipping unreadable manifest: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let entries = members
+        .into_iter()
+        .map(|m| ArchiveEntry {
+            is_dir: m.name.ends_with('/'),
+            path: m.name,
+            size_bytes: m.size,
+            compressed_bytes: Some(m.compressed_size),
+            crc32: Some(m.crc32),
+        })
+        .collect();
+    Ok((
+        ArchiveListing {
+            format: ArchiveFormat::Zip.as_str().to_string(),
+            entries,
+            total_entries: Some(cd.entries),
+            truncated,
+        },
+        manifest,
+    ))
+}
+
+/// Read one stored or deflated member through two ranged reads.
+async fn read_zip_member(
+    storage: &dyn StorageBackend,
+    key: &str,
+    member: &ZipMember,
+) -> Result<Vec<u8>> {
+    if member.compressed_size > MAX_MANIFEST_BYTES {
+        return Err(invalid_zip("manifest member too large"));
+    }
+    let header = storage
+        .get_range(key, member.local_header_offset, 30)
+        .await?;
+    if le_u32(&header, 0) != Some(ZIP_LOCAL_SIG as u64) {
+        return Err(invalid_zip("bad local file header"));
+    }
+    let name_len = le_u16(&header, 26).unwrap_or(0);
+    let extra_len = le_u16(&header, 28).unwrap_or(0);
+    let data_offset = member.local_header_offset + 30 + name_len + extra_len;
+    let data = storage
+        .get_range(key, data_offset, member.compressed_size as usize)
+        .await?;
+    let method = member.method;
+    with_registry_extraction(move || match method {
+        0 => read_capped(&data[..], MAX_MANIFEST_BYTES, "ZIP manifest entry"),
+        8 => read_capped(
+            flate2::read::DeflateDecoder::new(&data[..]),
+            MAX_MANIFEST_BYTES,
+            "ZIP manifest entry",
+        ),
+        other => Err(invalid_zip(&format!(
+            "unsupported compression method {other}"
+        ))),
+    })?
+}
+
+async fn preview_tar(
+    storage: &dyn StorageBackend,
+    key: &str,
+    format: ArchiveFormat,
+    max_entries: usize,
+) -> Result<(ArchiveListing, Option<PreviewManifest>)> {
+    let mut stream = storage.get_stream(key).await?;
+    let mut prefix = Vec::new();
+    let mut complete = true;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if prefix.len() + chunk.len() > MAX_TAR_SCAN_BYTES {
+            prefix.extend_from_slice(&chunk[..MAX_TAR_SCAN_BYTES - prefix.len()]);
+            complete = false;
+            break;
+        }
+        prefix.extend_from_slice(&chunk);
+    }
+    drop(stream);
+
+    // `.tar.gz` by name can still be plain tar; trust the magic bytes.
+    let gzipped = prefix.starts_with(&[0x1f, 0x8b]);
+    let (entries, truncated, manifest) = with_registry_extraction(move || {
+        if gzipped {
+            list_tar(
+                flate2::read::GzDecoder::new(&prefix[..]),
+                max_entries,
+                complete,
+            )
+        } else {
+            list_tar(&prefix[..], max_entries, complete)
+        }
+    })??;
+    let format = if gzipped {
+        ArchiveFormat::TarGz
+    } else if format == ArchiveFormat::TarGz {
+        ArchiveFormat::Tar
+    } else {
+        format
+    };
+    Ok((
+        ArchiveListing {
+            format: format.as_str().to_string(),
+            entries,
+            total_entries: None,
+            truncated,
+        },
+        manifest,
+    ))
+}
+
+/// Walk a tar stream listing up to `max_entries` members. `complete` says
+/// whether `reader` holds the whole archive; when it does not, running out of
+/// data is a truncated listing rather than an error.
+pub fn list_tar<R: Read>(
+    reader: R,
+    max_entries: usize,
+    complete: bool,
+) -> Result<(Vec<ArchiveEntry>, bool, Option<PreviewManifest>)> {
+    let mut archive = tar::Archive::new(budgeted_to(reader, MAX_TAR_DECODED_BYTES));
+    let mut entries = Vec::new();
+    let mut manifest = None;
+    let iter = archive
+        .entries()
+        .map_err(|e| AppError::Validation(format!("Invalid tar archive: {e}")))?;
+    for entry in iter {
+        let mut entry = match entry {
+            Ok(entry) => entry,
+            Err(_) if !complete || !entries.is_empty() => return Ok((entries, true, manifest)),
+            Err(e) => return Err(AppError::Validation(format!("Invalid tar archive: {e}"))),
+        };
+        if entries.len() == max_entries {
+            return Ok((entries, true, manifest));
+        }
+        let path = match entry.path() {
+            Ok(p) => p.to_string_lossy().into_owned(),
+            Err(_) => continue,
+        };
+        let size = entry.header().size().unwrap_or(0);
+        let is_dir = entry.header().entry_type().is_dir();
+        if manifest.is_none() && !is_dir && is_manifest_member(&path) && size <= MAX_MANIFEST_BYTES
+        {
+            if let Ok(bytes) = read_capped(&mut entry, MAX_MANIFEST_BYTES, "tar manifest entry") {
+                manifest = parse_manifest(path.trim_start_matches("./"), &bytes);
+            }
+        }
+        entries.push(ArchiveEntry {
+            path,
+            size_bytes: size,
+            compressed_bytes: None,
+            crc32: None,
+            is_dir,
+        });
+    }
+    Ok((entries, false, manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip_bytes(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buf);
+            let options = zip::write::SimpleFileOptions::default();
+            for (name, body) in files {
+                zip.start_file(*name, options).unwrap();
+                zip.write_all(body).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        buf.into_inner()
+    }
+
+    #[test]
+    fn test_archive_format_from_path() {
+        assert_eq!(
+            ArchiveFormat::from_path("lib/app-1.0.JAR"),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path("lodash-4.17.21.tgz"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path("backup.tar"),
+            Some(ArchiveFormat::Tar)
+        );
+        assert_eq!(ArchiveFormat::from_path("README.md"), None);
+    }
+
+    #[test]
+    fn test_decode_text_prefix() {
+        assert_eq!(decode_text_prefix(b"hello").as_deref(), Some("hello"));
+        // "é" cut in half by the ranged read.
+        assert_eq!(decode_text_prefix(b"caf\xc3").as_deref(), Some("caf"));
+        assert!(decode_text_prefix(b"\x7fELF\0\0").is_none());
+        assert!(decode_text_prefix(b"\xff\xfeabc").is_none());
+    }
+
+    #[test]
+    fn test_parse_jar_manifest_joins_continuations() {
+        let text = "Manifest-Version: 1.0\r\nImplementation-Title: very long\r\n  title\r\nMain-Class: a.B\r\n\r\nName: x\r\nSHA-256: y\r\n";
+        let value = parse_jar_manifest(text);
+        assert_eq!(value["Manifest-Version"], "1.0");
+        assert_eq!(value["Implementation-Title"], "very long title");
+        assert_eq!(value["Main-Class"], "a.B");
+        assert!(value.get("Name").is_none());
+    }
+
+    #[test]
+    fn test_zip_central_directory_round_trip() {
+        let bytes = zip_bytes(&[
+            ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\r\n"),
+            ("com/example/App.class", &[0xca, 0xfe, 0xba, 0xbe]),
+        ]);
+        let cd = match find_eocd(&bytes).unwrap() {
+            EocdLookup::Found(cd) => cd,
+            other => panic!("unexpected {other:?}"),
+        };
+        assert_eq!(cd.entries, 2);
+        let cd_bytes = &bytes[cd.offset as usize..(cd.offset + cd.size) as usize];
+        let (members, more) = parse_central_directory(cd_bytes, 10).unwrap();
+        assert!(!more);
+        assert_eq!(members[0].name, "META-INF/MANIFEST.MF");
+        assert_eq!(members[1].size, 4);
+        assert!(is_manifest_member(&members[0].name));
+
+        let (first, more) = parse_central_directory(cd_bytes, 1).unwrap();
+        assert_eq!(first.len(), 1);
+        assert!(more);
+    }
+
+    #[test]
+    fn test_find_eocd_rejects_non_zip() {
+        assert!(find_eocd(&[0u8; 64]).is_err());
+    }
+
+    #[test]
+    fn test_list_tar_gz_reads_package_json() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let body = br#"{"name":"left-pad","version":"1.3.0"}"#;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(body.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "package/package.json", &body[..])
+            .unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_size(3);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "package/index.js", &b"x=1"[..])
+            .unwrap();
+        let gz = builder.into_inner().unwrap().finish().unwrap();
+
+        let (entries, truncated, manifest) =
+            list_tar(flate2::read::GzDecoder::new(&gz[..]), 10, true).unwrap();
+        assert!(!truncated);
+        assert_eq!(entries.len(), 2);
+        let manifest = manifest.expect("package.json parsed");
+        assert_eq!(manifest.kind, "package_json");
+        assert_eq!(manifest.content["version"], "1.3.0");
+
+        let (entries, truncated, _) =
+            list_tar(flate2::read::GzDecoder::new(&gz[..]), 1, true).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(truncated);
+    }
+}
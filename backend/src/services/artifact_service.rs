@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use futures::stream::BoxStream;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 use sqlx::PgPool;
 use tracing::warn;
 use uuid::Uuid;
@@ -15,11 +15,14 @@ use crate::api::middleware::download_telemetry::DownloadContext;
 use crate::error::{AppError, Result};
 use crate::models::artifact::{Artifact, ArtifactMetadata, ArtifactVersion};
 use crate::models::repository::RepositoryFormat;
+use crate::services::event_bus::EventBus;
 use crate::services::opensearch_service::{ArtifactDocument, OpenSearchService};
 use crate::services::plugin_service::{ArtifactInfo, PluginEventType, PluginService};
 use crate::services::quality_check_service::QualityCheckService;
 use crate::services::repository_service::RepositoryService;
+use crate::services::scan_queue_service::{self, ScanJobOptions, ScanTrigger};
 use crate::services::scanner_service::ScannerService;
+use crate::services::storage_tiering_service::rehydrate_on_miss;
 use crate::storage::StorageBackend;
 
 /// Cancel any in-flight push retries for an artifact that is being deleted, so
@@ -60,7 +63,7 @@ const PUSH_MIRROR_SUBSCRIPTIONS_SQL: &str = r#"
                       AND prs.replication_mode::text IN ('push', 'mirror')
                     "#;
 
-/// The three content digests persisted on every artifact row.
+/// The content digests persisted on every artifact row.
 ///
 /// Registry clients look artifacts up by any of SHA-256, SHA-1, or MD5 (Maven
 /// `.sha1` sidecars, PyPI MD5 digests, ...), so all three are stored. The
@@ -68,6 +71,9 @@ const PUSH_MIRROR_SUBSCRIPTIONS_SQL: &str = r#"
 /// a scratch file and hands them to [`ArtifactService::upload_stream_with_sync_options`].
 /// `storage.put_stream` only computes SHA-256, so SHA-1 / MD5 MUST be supplied
 /// here out-of-band or checksum-search by those two algorithms regresses.
+///
+/// SHA-512 and BLAKE3 are optional: rows written before they were recorded
+/// lack them until the checksum backfill reaches them.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ContentDigests {
     /// Lowercase-hex SHA-256 (also the content-addressed storage key).
@@ -76,19 +82,112 @@ pub struct ContentDigests {
     pub sha1: String,
     /// Lowercase-hex MD5.
     pub md5: String,
+    /// Lowercase-hex SHA-512, when known.
+    pub sha512: Option<String>,
+    /// Lowercase-hex BLAKE3 (256-bit), when known.
+    pub blake3: Option<String>,
+}
+
+/// A checksum algorithm artifacts can be looked up by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha1,
+    Md5,
+    Sha512,
+    Blake3,
+}
+
+impl DigestAlgorithm {
+    /// Every supported algorithm, SHA-256 first.
+    pub const ALL: [DigestAlgorithm; 5] = [
+        Self::Sha256,
+        Self::Sha1,
+        Self::Md5,
+        Self::Sha512,
+        Self::Blake3,
+    ];
+
+    /// Parse a lowercase algorithm name (`sha256`, `sha1`, `md5`, `sha512`,
+    /// `blake3`).
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha1 => "sha1",
+            Self::Md5 => "md5",
+            Self::Sha512 => "sha512",
+            Self::Blake3 => "blake3",
+        }
+    }
+
+    /// The `artifacts` column holding this digest.
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Sha256 => "checksum_sha256",
+            Self::Sha1 => "checksum_sha1",
+            Self::Md5 => "checksum_md5",
+            Self::Sha512 => "checksum_sha512",
+            Self::Blake3 => "checksum_blake3",
+        }
+    }
+
+    /// The `x-checksum-*` header a client declares this digest in.
+    pub fn header(self) -> &'static str {
+        match self {
+            Self::Sha256 => "x-checksum-sha256",
+            Self::Sha1 => "x-checksum-sha1",
+            Self::Md5 => "x-checksum-md5",
+            Self::Sha512 => "x-checksum-sha512",
+            Self::Blake3 => "x-checksum-blake3",
+        }
+    }
+
+    /// Length of the lowercase-hex digest.
+    pub fn hex_len(self) -> usize {
+        match self {
+            Self::Sha256 | Self::Blake3 => 64,
+            Self::Sha1 => 40,
+            Self::Md5 => 32,
+            Self::Sha512 => 128,
+        }
+    }
+
+    /// Whether `value` is a well-formed hex digest for this algorithm.
+    pub fn is_valid_hex(self, value: &str) -> bool {
+        value.len() == self.hex_len() && value.bytes().all(|b| b.is_ascii_hexdigit())
+    }
 }
 
-/// Incremental SHA-256 + SHA-1 + MD5 accumulator.
+impl ContentDigests {
+    /// The digest for `algorithm`, if known.
+    pub fn get(&self, algorithm: DigestAlgorithm) -> Option<&str> {
+        match algorithm {
+            DigestAlgorithm::Sha256 => Some(&self.sha256),
+            DigestAlgorithm::Sha1 => Some(&self.sha1),
+            DigestAlgorithm::Md5 => Some(&self.md5),
+            DigestAlgorithm::Sha512 => self.sha512.as_deref(),
+            DigestAlgorithm::Blake3 => self.blake3.as_deref(),
+        }
+    }
+}
+
+/// Incremental SHA-256 + SHA-1 + MD5 + SHA-512 + BLAKE3 accumulator.
 ///
 /// Feed chunks with [`MultiHasher::update`], then [`MultiHasher::finalize`] into
 /// a [`ContentDigests`]. Extracted as a pure, side-effect-free helper so the
 /// streaming ingest path and its unit tests share one hashing implementation and
-/// the three-way finalize is covered without a live storage backend.
+/// the finalize is covered without a live storage backend.
 #[derive(Default)]
 pub struct MultiHasher {
     sha256: Sha256,
     sha1: sha1::Sha1,
     md5: md5::Md5,
+    sha512: Sha512,
+    blake3: blake3::Hasher,
 }
 
 impl MultiHasher {
@@ -97,11 +196,13 @@ impl MultiHasher {
         Self::default()
     }
 
-    /// Fold `data` into all three running digests.
+    /// Fold `data` into all running digests.
     pub fn update(&mut self, data: &[u8]) {
         Digest::update(&mut self.sha256, data);
         sha1::Digest::update(&mut self.sha1, data);
         md5::Digest::update(&mut self.md5, data);
+        Digest::update(&mut self.sha512, data);
+        self.blake3.update(data);
     }
 
     /// Finish hashing and produce the lowercase-hex [`ContentDigests`].
@@ -110,8 +211,17 @@ impl MultiHasher {
             sha256: format!("{:x}", self.sha256.finalize()),
             sha1: format!("{:x}", sha1::Digest::finalize(self.sha1)),
             md5: format!("{:x}", md5::Digest::finalize(self.md5)),
+            sha512: Some(format!("{:x}", self.sha512.finalize())),
+            blake3: Some(self.blake3.finalize().to_hex().to_string()),
         }
     }
+
+    /// Digest a complete in-memory body.
+    pub fn digest(data: &[u8]) -> ContentDigests {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
 }
 
 /// Whether uploads to a repository append immutable revisions to
@@ -221,6 +331,8 @@ pub struct ArtifactService {
     scanner_service: Option<Arc<ScannerService>>,
     quality_check_service: Option<Arc<QualityCheckService>>,
     search_service: Option<Arc<OpenSearchService>>,
+    event_bus: Option<Arc<EventBus>>,
+    storage_dedup: bool,
 }
 
 impl ArtifactService {
@@ -235,6 +347,8 @@ impl ArtifactService {
             scanner_service: None,
             quality_check_service: None,
             search_service: None,
+            event_bus: None,
+            storage_dedup: false,
         }
     }
 
@@ -253,6 +367,8 @@ impl ArtifactService {
             scanner_service: None,
             quality_check_service: None,
             search_service,
+            event_bus: None,
+            storage_dedup: false,
         }
     }
 
@@ -271,6 +387,8 @@ impl ArtifactService {
             scanner_service: None,
             quality_check_service: None,
             search_service: None,
+            event_bus: None,
+            storage_dedup: false,
         }
     }
 
@@ -294,6 +412,19 @@ impl ArtifactService {
         self.search_service = Some(search_service);
     }
 
+    /// Set the event bus that uploads publish `artifact.created` on.
+    pub fn set_event_bus(&mut self, event_bus: Arc<EventBus>) {
+        self.event_bus = Some(event_bus);
+    }
+
+    /// Store uploaded content in the shared CAS namespace with ref counting
+    /// (`STORAGE_DEDUP_ENABLED`). The storage handle must resolve
+    /// `cas/sha256/` keys to the backend's shared store (see
+    /// [`crate::storage::StorageRegistry::with_filesystem_cas_root`]).
+    pub fn set_storage_dedup(&mut self, enabled: bool) {
+        self.storage_dedup = enabled;
+    }
+
     /// Trigger a plugin hook, logging but not failing if plugin service is unavailable.
     async fn trigger_hook(
         &self,
@@ -430,12 +561,109 @@ impl ArtifactService {
         Ok(())
     }
 
+    /// Verify one client-declared digest against the computed ones. Used for
+    /// the algorithms [`Self::verify_declared_digests`] does not cover.
+    pub fn verify_declared_digest(
+        digests: &ContentDigests,
+        algorithm: DigestAlgorithm,
+        declared: Option<&str>,
+    ) -> Result<()> {
+        let (Some(declared), Some(actual)) = (declared, digests.get(algorithm)) else {
+            return Ok(());
+        };
+        if !declared.trim().eq_ignore_ascii_case(actual) {
+            return Err(AppError::Validation(format!(
+                "{} checksum mismatch: declared {} but actual content hashes to {}",
+                algorithm.name().to_ascii_uppercase(),
+                declared,
+                actual
+            )));
+        }
+        Ok(())
+    }
+
     /// Generate content-addressable storage key from checksum
     pub fn storage_key_from_checksum(checksum: &str) -> String {
         // Use first 4 chars for directory sharding: ab/cd/abcd...
         format!("{}/{}/{}", &checksum[..2], &checksum[2..4], checksum)
     }
 
+    /// Key new content is stored under: the shared CAS key with storage
+    /// dedup on, otherwise the repository's own content key.
+    fn content_storage_key(&self, checksum_sha256: &str) -> String {
+        if self.storage_dedup {
+            crate::storage::keys::cas_storage_key(checksum_sha256)
+        } else {
+            Self::storage_key_from_checksum(checksum_sha256)
+        }
+    }
+
+    /// With storage dedup on, claim the shared CAS object before its blob is
+    /// checked or written so GC cannot reclaim it underneath the upload.
+    async fn claim_content(
+        &self,
+        repository_id: Uuid,
+        checksum_sha256: &str,
+        size_bytes: i64,
+    ) -> Result<()> {
+        if !self.storage_dedup {
+            return Ok(());
+        }
+        crate::services::storage_dedup_service::claim_cas_object(
+            &self.db,
+            repository_id,
+            checksum_sha256,
+            size_bytes,
+        )
+        .await
+    }
+
+    /// Store the SHA-512 / BLAKE3 digests of a just-finalized artifact.
+    ///
+    /// Best effort, like the other post-finalize bookkeeping: a row left
+    /// without them is filled in by the checksum backfill.
+    async fn record_extended_digests(
+        &self,
+        artifact_id: Uuid,
+        sha512: Option<&str>,
+        blake3: Option<&str>,
+    ) {
+        if sha512.is_none() && blake3.is_none() {
+            return;
+        }
+        if let Err(e) = sqlx::query(
+            r#"
+            UPDATE artifacts
+            SET checksum_sha512 = COALESCE($2, checksum_sha512),
+                checksum_blake3 = COALESCE($3, checksum_blake3)
+            WHERE id = $1
+            "#,
+        )
+        .bind(artifact_id)
+        .bind(sha512)
+        .bind(blake3)
+        .execute(&self.db)
+        .await
+        {
+            warn!(artifact_id = %artifact_id, "Failed to record SHA-512/BLAKE3 digests: {}", e);
+        }
+    }
+
+    /// With storage dedup on, count `artifact` as a reference to its shared
+    /// CAS object.
+    async fn record_content_ref(&self, artifact: &Artifact) {
+        if !self.storage_dedup {
+            return;
+        }
+        crate::services::storage_dedup_service::record_storage_ref(
+            &self.db,
+            artifact.id,
+            artifact.repository_id,
+            artifact.checksum_sha256.trim(),
+        )
+        .await;
+    }
+
     /// Upload an artifact
     #[allow(clippy::too_many_arguments)]
     pub async fn upload(
@@ -478,13 +706,18 @@ impl ArtifactService {
 
         // Calculate checksums.
         //
-        // We persist SHA-256, SHA-1, and MD5 so the checksum-search endpoint can
-        // locate an artifact by any of the three (registry clients lean heavily
-        // on SHA-1 and MD5 for legacy reasons). All three are lowercase hex.
-        let checksum_sha256 = Self::calculate_sha256(&data);
-        let checksum_sha1 = Self::calculate_sha1(&data);
-        let checksum_md5 = Self::calculate_md5(&data);
-        let storage_key = Self::storage_key_from_checksum(&checksum_sha256);
+        // We persist SHA-256, SHA-1, MD5, SHA-512 and BLAKE3 so the
+        // checksum-search endpoint can locate an artifact by any of them
+        // (registry clients lean heavily on SHA-1 and MD5 for legacy reasons).
+        // All are lowercase hex.
+        let ContentDigests {
+            sha256: checksum_sha256,
+            sha1: checksum_sha1,
+            md5: checksum_md5,
+            sha512: checksum_sha512,
+            blake3: checksum_blake3,
+        } = MultiHasher::digest(&data);
+        let storage_key = self.content_storage_key(&checksum_sha256);
 
         // Quota, plugin BeforeUpload hook, live-overwrite check, and the
         // release-immutability backstop — shared with the streaming path.
@@ -501,6 +734,8 @@ impl ArtifactService {
         .await?;
 
         // Check if content already exists (deduplication)
+        self.claim_content(repository_id, &checksum_sha256, size_bytes)
+            .await?;
         let content_exists = self.storage.exists(&storage_key).await?;
 
         if !content_exists {
@@ -508,21 +743,33 @@ impl ArtifactService {
             self.storage.put(&storage_key, data).await?;
         }
 
-        self.finalize_upload(
-            repository_id,
-            path,
-            name,
-            version,
-            content_type,
-            size_bytes,
-            &checksum_sha256,
-            &checksum_sha1,
-            &checksum_md5,
-            &storage_key,
-            uploaded_by,
-            enqueue_sync_tasks,
+        let artifact = self
+            .finalize_upload(
+                repository_id,
+                path,
+                name,
+                version,
+                content_type,
+                size_bytes,
+                &checksum_sha256,
+                &checksum_sha1,
+                &checksum_md5,
+                &storage_key,
+                uploaded_by,
+                enqueue_sync_tasks,
+            )
+            .await?;
+        self.record_extended_digests(
+            artifact.id,
+            checksum_sha512.as_deref(),
+            checksum_blake3.as_deref(),
         )
-        .await
+        .await;
+        self.record_content_ref(&artifact).await;
+        if content_exists {
+            self.record_upload_dedup(&artifact, false).await;
+        }
+        Ok(artifact)
     }
 
     /// Stream an artifact's content into content-addressed storage, mirroring
@@ -553,7 +800,39 @@ impl ArtifactService {
         uploaded_by: Option<Uuid>,
         enqueue_sync_tasks: bool,
     ) -> Result<Artifact> {
-        let storage_key = Self::storage_key_from_checksum(&digests.sha256);
+        self.upload_stream_with_outcome(
+            repository_id,
+            path,
+            name,
+            version,
+            content_type,
+            stream,
+            digests,
+            size_bytes,
+            uploaded_by,
+            enqueue_sync_tasks,
+        )
+        .await
+        .map(|(artifact, _)| artifact)
+    }
+
+    /// [`Self::upload_stream_with_sync_options`], also reporting whether the
+    /// content was already stored and the storage write was skipped.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upload_stream_with_outcome(
+        &self,
+        repository_id: Uuid,
+        path: &str,
+        name: &str,
+        version: Option<&str>,
+        content_type: &str,
+        stream: BoxStream<'static, Result<Bytes>>,
+        digests: ContentDigests,
+        size_bytes: i64,
+        uploaded_by: Option<Uuid>,
+        enqueue_sync_tasks: bool,
+    ) -> Result<(Artifact, bool)> {
+        let storage_key = self.content_storage_key(&digests.sha256);
 
         self.preflight_upload(
             repository_id,
@@ -570,6 +849,8 @@ impl ArtifactService {
         // Dedup check FIRST: skip `put_stream` on a warm blob so we never
         // rewrite content that is already present under its content-addressed
         // key (== its SHA-256).
+        self.claim_content(repository_id, &digests.sha256, size_bytes)
+            .await?;
         let content_exists = self.storage.exists(&storage_key).await?;
 
         if !content_exists {
@@ -585,7 +866,135 @@ impl ArtifactService {
             }
         }
 
-        self.finalize_upload(
+        let artifact = self
+            .finalize_upload(
+                repository_id,
+                path,
+                name,
+                version,
+                content_type,
+                size_bytes,
+                &digests.sha256,
+                &digests.sha1,
+                &digests.md5,
+                &storage_key,
+                uploaded_by,
+                enqueue_sync_tasks,
+            )
+            .await?;
+        self.record_extended_digests(
+            artifact.id,
+            digests.sha512.as_deref(),
+            digests.blake3.as_deref(),
+        )
+        .await;
+        self.record_content_ref(&artifact).await;
+        if content_exists {
+            self.record_upload_dedup(&artifact, false).await;
+        }
+        Ok((artifact, content_exists))
+    }
+
+    /// Digests of content already stored in `repository_id` with this
+    /// SHA-256 and size, if its blob is still present in storage.
+    ///
+    /// Scoped to the target repository on purpose: a client that only knows
+    /// a digest must not be able to link content from a repository it
+    /// cannot read.
+    pub async fn find_existing_content(
+        &self,
+        repository_id: Uuid,
+        checksum_sha256: &str,
+        size_bytes: i64,
+    ) -> Result<Option<ContentDigests>> {
+        self.find_existing_content_by(
+            repository_id,
+            DigestAlgorithm::Sha256,
+            checksum_sha256,
+            size_bytes,
+        )
+        .await
+    }
+
+    /// [`Self::find_existing_content`] keyed by any supported digest.
+    pub async fn find_existing_content_by(
+        &self,
+        repository_id: Uuid,
+        algorithm: DigestAlgorithm,
+        checksum: &str,
+        size_bytes: i64,
+    ) -> Result<Option<ContentDigests>> {
+        #[allow(clippy::type_complexity)]
+        let row: Option<(
+            String,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )> = sqlx::query_as(&format!(
+            r#"
+            SELECT checksum_sha256, checksum_sha1, checksum_md5,
+                   checksum_sha512, checksum_blake3
+            FROM artifacts
+            WHERE repository_id = $1
+              AND {} = $2
+              AND size_bytes = $3
+              AND is_deleted = false
+            LIMIT 1
+            "#,
+            algorithm.column()
+        ))
+        .bind(repository_id)
+        .bind(checksum.trim().to_ascii_lowercase())
+        .bind(size_bytes)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let Some((sha256, sha1, md5, sha512, blake3)) = row else {
+            return Ok(None);
+        };
+        // Rows predating SHA-1/MD5 persistence cannot back a fast path: the
+        // new row would carry empty checksums.
+        let (Some(sha1), Some(md5)) = (sha1, md5) else {
+            return Ok(None);
+        };
+        let sha256 = sha256.trim().to_ascii_lowercase();
+        if !self
+            .storage
+            .exists(&self.content_storage_key(&sha256))
+            .await?
+        {
+            return Ok(None);
+        }
+        Ok(Some(ContentDigests {
+            sha256,
+            sha1: sha1.trim().to_ascii_lowercase(),
+            md5: md5.trim().to_ascii_lowercase(),
+            sha512: sha512.map(|v| v.trim().to_ascii_lowercase()),
+            blake3: blake3.map(|v| v.trim().to_ascii_lowercase()),
+        }))
+    }
+
+    /// Create an artifact at `path` from content already in storage, without
+    /// receiving or writing the body. `digests` must come from
+    /// [`Self::find_existing_content`]. Runs the same preflight and
+    /// finalization as a regular upload.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upload_existing_content(
+        &self,
+        repository_id: Uuid,
+        path: &str,
+        name: &str,
+        version: Option<&str>,
+        content_type: &str,
+        digests: &ContentDigests,
+        size_bytes: i64,
+        uploaded_by: Option<Uuid>,
+        enqueue_sync_tasks: bool,
+    ) -> Result<Artifact> {
+        let storage_key = self.content_storage_key(&digests.sha256);
+        self.preflight_upload(
             repository_id,
             path,
             name,
@@ -593,13 +1002,58 @@ impl ArtifactService {
             content_type,
             size_bytes,
             &digests.sha256,
-            &digests.sha1,
-            &digests.md5,
-            &storage_key,
             uploaded_by,
-            enqueue_sync_tasks,
         )
-        .await
+        .await?;
+        if self.storage_dedup {
+            // The shared blob may have been reclaimed since the lookup; the
+            // claim pins it from here on, so check once more under it.
+            self.claim_content(repository_id, &digests.sha256, size_bytes)
+                .await?;
+            if !self.storage.exists(&storage_key).await? {
+                return Err(AppError::Conflict(
+                    "Content is no longer stored; upload the body".to_string(),
+                ));
+            }
+        }
+
+        let artifact = self
+            .finalize_upload(
+                repository_id,
+                path,
+                name,
+                version,
+                content_type,
+                size_bytes,
+                &digests.sha256,
+                &digests.sha1,
+                &digests.md5,
+                &storage_key,
+                uploaded_by,
+                enqueue_sync_tasks,
+            )
+            .await?;
+        self.record_extended_digests(
+            artifact.id,
+            digests.sha512.as_deref(),
+            digests.blake3.as_deref(),
+        )
+        .await;
+        self.record_content_ref(&artifact).await;
+        self.record_upload_dedup(&artifact, true).await;
+        Ok(artifact)
+    }
+
+    async fn record_upload_dedup(&self, artifact: &Artifact, body_skipped: bool) {
+        crate::services::analytics_service::record_upload_dedup(
+            &self.db,
+            artifact.repository_id,
+            artifact.id,
+            artifact.checksum_sha256.trim(),
+            artifact.size_bytes,
+            body_skipped,
+        )
+        .await;
     }
 
     /// Pre-storage validation shared by the buffered and streaming upload paths:
@@ -618,6 +1072,8 @@ impl ArtifactService {
         checksum_sha256: &str,
         uploaded_by: Option<Uuid>,
     ) -> Result<()> {
+        crate::api::middleware::access_log::note_package(name, version);
+
         // Check quota
         if !self
             .repo_service
@@ -629,6 +1085,24 @@ impl ArtifactService {
             ));
         }
 
+        // Instance block list: known-malicious digests and packages
+        crate::services::blocklist_service::check_upload(
+            &self.db,
+            repository_id,
+            name,
+            version,
+            path,
+            checksum_sha256,
+        )
+        .await?;
+
+        // Release freeze windows
+        crate::services::freeze_window_service::check_upload(&self.db, repository_id, uploaded_by)
+            .await?;
+
+        // Required custom properties (per-repository metadata schema)
+        crate::services::metadata_schema_service::check_upload(&self.db, repository_id).await?;
+
         // Build artifact info for plugin hooks (before artifact is created)
         let pre_artifact_info = ArtifactInfo {
             id: Uuid::nil(), // Will be set after creation
@@ -883,6 +1357,10 @@ impl ArtifactService {
         )
         .await;
 
+        // Custom properties sent with the upload (`X-Artifact-Properties`).
+        crate::services::metadata_schema_service::record_upload_properties(&self.db, artifact.id)
+            .await;
+
         // Check quota warning threshold after successful upload.
         //
         // PF-007 (#2523): reuse the usage computed during atomic admission
@@ -1032,9 +1510,8 @@ impl ArtifactService {
             });
         }
 
-        // Trigger scan-on-upload if scanner service is configured
-        if let Some(ref scanner) = self.scanner_service {
-            let scanner = scanner.clone();
+        // Queue scan-on-upload if scanner service is configured
+        if self.scanner_service.is_some() {
             let artifact_id = artifact.id;
             let repo_id = artifact.repository_id;
             let db = self.db.clone();
@@ -1051,8 +1528,19 @@ impl ArtifactService {
                 .unwrap_or(false);
 
                 if should_scan {
-                    if let Err(e) = scanner.scan_artifact(artifact_id).await {
-                        tracing::warn!("Auto-scan failed for artifact {}: {}", artifact_id, e);
+                    if let Err(e) = scan_queue_service::enqueue(
+                        &db,
+                        artifact_id,
+                        ScanTrigger::Upload,
+                        ScanJobOptions::default(),
+                    )
+                    .await
+                    {
+                        tracing::warn!(
+                            "Failed to queue auto-scan for artifact {}: {}",
+                            artifact_id,
+                            e
+                        );
                     }
                 }
             });
@@ -1158,6 +1646,15 @@ impl ArtifactService {
             audit_fire_and_forget(self.db.clone(), entry).await;
         }
 
+        if let Some(ref bus) = self.event_bus {
+            bus.emit_for_repo(
+                "artifact.created",
+                artifact.id,
+                artifact.repository_id,
+                None,
+            );
+        }
+
         Ok(artifact)
     }
 
@@ -1520,8 +2017,12 @@ impl ArtifactService {
     ) -> Result<(Artifact, Bytes)> {
         let (artifact, artifact_info) = self.prepare_download(repository_id, path).await?;
 
-        // Get content from storage
-        let content = self.storage.get(&artifact.storage_key).await?;
+        // Get content from storage. A miss on an artifact in the cold tier
+        // queues its rehydration (202 + Retry-After).
+        let content = match self.storage.get(&artifact.storage_key).await {
+            Ok(content) => content,
+            Err(e) => return Err(rehydrate_on_miss(&self.db, artifact.id, e).await),
+        };
 
         self.finish_download(
             artifact.id,
@@ -1549,7 +2050,13 @@ impl ArtifactService {
     /// [`AppError::NotFound`] exactly as the buffered path did, preserving the
     /// handler's Remote/Virtual fallback contract.
     ///
+    /// `range` maps the artifact's size to the inclusive `(start, end)` byte
+    /// window a ranged request asks for, or `None` for the whole body. A
+    /// window is read with [`StorageBackend::get_range_stream`], so the
+    /// skipped prefix is never fetched from backends with native ranges.
+    ///
     /// [`download`]: Self::download
+    #[allow(clippy::too_many_arguments)]
     pub async fn download_stream(
         &self,
         repository_id: Uuid,
@@ -1558,13 +2065,26 @@ impl ArtifactService {
         ip_address: Option<String>,
         user_agent: Option<&str>,
         count_download: bool,
+        range: impl FnOnce(u64) -> Option<(u64, u64)> + Send,
     ) -> Result<(Artifact, BoxStream<'static, Result<Bytes>>)> {
         let (artifact, artifact_info) = self.prepare_download(repository_id, path).await?;
 
         // Open the body as a stream so large artifacts never buffer in memory.
         // `get_stream` resolves a missing key eagerly to `AppError::NotFound`,
-        // matching the buffered `get` path's NotFound contract.
-        let body = self.storage.get_stream(&artifact.storage_key).await?;
+        // matching the buffered `get` path's NotFound contract, unless the
+        // artifact is in the cold tier and is being rehydrated.
+        let opened = match range(artifact.size_bytes.max(0) as u64) {
+            Some((start, end)) => {
+                self.storage
+                    .get_range_stream(&artifact.storage_key, start, end - start + 1)
+                    .await
+            }
+            None => self.storage.get_stream(&artifact.storage_key).await,
+        };
+        let body = match opened {
+            Ok(body) => body,
+            Err(e) => return Err(rehydrate_on_miss(&self.db, artifact.id, e).await),
+        };
 
         // `count_download` is false for a HEAD request: it returns identical
         // headers but serves no body, so it must not write a download-statistics
@@ -2262,6 +2782,7 @@ pub async fn record_download(db: &PgPool, artifact_id: Uuid, ctx: &DownloadConte
     // makes "one row == one real body served" hold for the axum `get()`-
     // registered format routes that auto-dispatch HEAD to their GET handler,
     // mirroring the explicit guards on the generic / OCI / incus paths.
+    crate::api::middleware::access_log::note_artifact(artifact_id);
     if ctx.is_head {
         return;
     }
@@ -2599,6 +3120,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_multi_hasher_sha512_and_blake3() {
+        let d = MultiHasher::new().finalize();
+        // Well-known empty-input digests.
+        assert_eq!(
+            d.sha512.as_deref(),
+            Some(
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+                 47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+            )
+        );
+        assert_eq!(
+            d.blake3.as_deref(),
+            Some("af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262")
+        );
+
+        let chunked = {
+            let mut h = MultiHasher::new();
+            h.update(b"content-");
+            h.update(b"addressed");
+            h.finalize()
+        };
+        assert_eq!(chunked, MultiHasher::digest(b"content-addressed"));
+    }
+
+    #[test]
+    fn test_digest_algorithm_parse_and_shape() {
+        for algorithm in DigestAlgorithm::ALL {
+            assert_eq!(DigestAlgorithm::parse(algorithm.name()), Some(algorithm));
+            assert!(algorithm.header().ends_with(algorithm.name()));
+            assert!(algorithm.column().ends_with(algorithm.name()));
+        }
+        assert_eq!(DigestAlgorithm::parse("SHA512"), None);
+        assert_eq!(DigestAlgorithm::parse("blake2b"), None);
+
+        let d = MultiHasher::digest(b"x");
+        for algorithm in DigestAlgorithm::ALL {
+            let value = d.get(algorithm).unwrap();
+            assert!(algorithm.is_valid_hex(value), "{}", algorithm.name());
+        }
+        assert!(!DigestAlgorithm::Sha512.is_valid_hex(&d.sha256));
+    }
+
     // -----------------------------------------------------------------------
     // calculate_sha256: edge cases
     // -----------------------------------------------------------------------
@@ -3921,4 +4485,90 @@ mod tests {
         tdh::cleanup(&pool, repo_id, user_id).await;
         let _ = std::fs::remove_dir_all(&storage_dir);
     }
+
+    /// Storage dedup: the same bytes uploaded to two filesystem repositories
+    /// land once in the shared CAS root, with one ref per artifact.
+    #[tokio::test]
+    async fn test_storage_dedup_stores_identical_content_once_across_repos() {
+        use crate::api::handlers::test_db_helpers as tdh;
+        use crate::storage::{StorageLocation, StorageRegistry};
+
+        let Some(pool) = tdh::try_pool().await else {
+            return;
+        };
+        let (user_id, _username) = tdh::create_user(&pool).await;
+        let (repo_a, _, dir_a) = tdh::create_repo(&pool, "local", "generic").await;
+        let (repo_b, _, dir_b) = tdh::create_repo(&pool, "local", "generic").await;
+        let cas_root = tempfile::TempDir::new().unwrap();
+        let registry = StorageRegistry::new(Default::default(), "filesystem".to_string())
+            .with_filesystem_cas_root(cas_root.path().to_str().unwrap());
+        let service_for = |dir: &std::path::Path| {
+            let storage = registry
+                .backend_for(&StorageLocation {
+                    backend: "filesystem".to_string(),
+                    path: dir.to_string_lossy().into_owned(),
+                })
+                .unwrap();
+            let mut svc = ArtifactService::new(pool.clone(), storage);
+            svc.set_storage_dedup(true);
+            svc
+        };
+
+        let body = Bytes::from(format!("dedup-body-{}", Uuid::new_v4()));
+        let a = service_for(&dir_a)
+            .upload(
+                repo_a,
+                "lib/a.bin",
+                "a.bin",
+                None,
+                "application/octet-stream",
+                body.clone(),
+                Some(user_id),
+            )
+            .await
+            .expect("first upload");
+        let b = service_for(&dir_b)
+            .upload(
+                repo_b,
+                "lib/b.bin",
+                "b.bin",
+                None,
+                "application/octet-stream",
+                body,
+                Some(user_id),
+            )
+            .await
+            .expect("second upload");
+
+        let cas_key = crate::storage::keys::cas_storage_key(&a.checksum_sha256);
+        assert_eq!(a.storage_key, cas_key);
+        assert_eq!(b.storage_key, cas_key);
+        assert!(cas_root.path().join(&cas_key).is_file());
+        assert!(!dir_a.join(&cas_key).exists());
+
+        let (objects, refs): (i64, i64) = sqlx::query_as(
+            "SELECT \
+                 (SELECT COUNT(*) FROM storage_cas_objects \
+                  WHERE storage_backend = 'filesystem' AND checksum_sha256 = $1), \
+                 (SELECT COUNT(*) FROM storage_refs \
+                  WHERE storage_backend = 'filesystem' AND checksum_sha256 = $1)",
+        )
+        .bind(&a.checksum_sha256)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((objects, refs), (1, 2));
+
+        tdh::cleanup(&pool, repo_a, user_id).await;
+        tdh::cleanup(&pool, repo_b, user_id).await;
+        let _ = sqlx::query(
+            "DELETE FROM storage_cas_objects \
+             WHERE storage_backend = 'filesystem' AND checksum_sha256 = $1",
+        )
+        .bind(&a.checksum_sha256)
+        .execute(&pool)
+        .await;
+        let _ = std::fs::remove_dir_all(&dir_a);
+        let _ = std::fs::remove_dir_all(&dir_b);
+    }
 }
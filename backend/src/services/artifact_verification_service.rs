@@ -0,0 +1,493 @@
+//! Signed verification bundles for deployment-time artifact checks.
+//!
+//! A bundle collects, in one document, everything a deployment gate needs
+//! to decide whether an artifact may roll out: its checksum, the latest scan
+//! per scanner with open finding counts, the current policy decision, its
+//! signature status, and references to its SBOMs, builds and promotions.
+//!
+//! The bundle is wrapped in a DSSE envelope
+//! (<https://github.com/secure-systems-lab/dsse>) and signed with the
+//! repository's active signing key, so tooling can verify it offline against
+//! `GET /api/v1/signing/keys/{key_id}/public`. A repository without an
+//! active signing key still gets the bundle, with an empty `signatures` list;
+//! verifiers must treat that as unsigned.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::policy_service::PolicyService;
+use crate::services::scan_state::{classify_scan_state, ScanStateRow, SCAN_STATE_SQL};
+use crate::services::signing_service::SigningService;
+
+/// DSSE payload type of a verification bundle.
+pub const VERIFICATION_PAYLOAD_TYPE: &str = "application/vnd.artifact-keeper.verification+json";
+
+/// Schema identifier embedded in every bundle.
+pub const VERIFICATION_SCHEMA: &str = "artifact-keeper.verification/v1";
+
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct VerifiedArtifact {
+    pub id: Uuid,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub path: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub size_bytes: i64,
+    pub checksum_sha256: String,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// Latest scan of one scanner type.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct VerificationScan {
+    pub scan_id: Uuid,
+    pub scan_type: String,
+    pub status: String,
+    pub scanner_version: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub critical_count: i32,
+    pub high_count: i32,
+    pub medium_count: i32,
+    pub low_count: i32,
+    pub info_count: i32,
+}
+
+/// Non-acknowledged findings across the latest completed scans.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct VerificationFindingCounts {
+    pub critical: i64,
+    pub high: i64,
+    pub medium: i64,
+    pub low: i64,
+    pub info: i64,
+    pub acknowledged: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VerificationScanSummary {
+    /// completed, scan_in_progress, scan_failed, never_scanned or
+    /// not_applicable.
+    pub state: String,
+    pub scans: Vec<VerificationScan>,
+    pub open_findings: VerificationFindingCounts,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VerificationPolicy {
+    /// Whether the artifact currently passes every enabled scan policy.
+    pub allowed: bool,
+    pub violations: Vec<String>,
+}
+
+/// Whether the artifact carries a content signature from a key that is
+/// still active (the same rule the promotion `require_signature` gate uses).
+#[derive(Debug, Clone, Default, Serialize, ToSchema, sqlx::FromRow)]
+pub struct VerificationSignatureStatus {
+    pub signed: bool,
+    pub key_id: Option<Uuid>,
+    pub key_fingerprint: Option<String>,
+    pub algorithm: Option<String>,
+    pub signature_sha256: Option<String>,
+    pub signed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct SbomReference {
+    pub id: Uuid,
+    pub format: String,
+    pub format_version: String,
+    pub content_hash: String,
+    pub generator: Option<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A build that recorded an output with this artifact's checksum.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct BuildReference {
+    pub id: Uuid,
+    pub name: String,
+    pub build_number: i32,
+    pub status: String,
+    pub vcs_url: Option<String>,
+    pub vcs_revision: Option<String>,
+    pub vcs_branch: Option<String>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct PromotionReference {
+    pub id: Uuid,
+    pub source_repository: String,
+    pub target_repository: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VerificationProvenance {
+    pub sbom_documents: Vec<SbomReference>,
+    pub builds: Vec<BuildReference>,
+    pub promotions: Vec<PromotionReference>,
+}
+
+/// The signed statement about an artifact.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VerificationBundle {
+    pub schema: String,
+    pub generated_at: DateTime<Utc>,
+    pub artifact: VerifiedArtifact,
+    pub scan_summary: VerificationScanSummary,
+    pub policy: VerificationPolicy,
+    pub signature: VerificationSignatureStatus,
+    pub provenance: VerificationProvenance,
+}
+
+/// One DSSE signature over the envelope's PAE encoding.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EnvelopeSignature {
+    /// Artifact Keeper signing key ID.
+    pub keyid: String,
+    /// Base64 signature. For RSA keys, a PKCS#1 v1.5 SHA-256 signature; for
+    /// OpenPGP keys, an ASCII-armored detached signature.
+    pub sig: String,
+    /// `rsa-pkcs1v15-sha256` or `openpgp`.
+    pub algorithm: String,
+    pub public_key_url: String,
+}
+
+/// DSSE envelope wrapping a [`VerificationBundle`]. `payload` holds the
+/// exact signed bytes; `bundle` repeats them decoded for convenience and
+/// must not be used for verification.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VerificationEnvelope {
+    #[serde(rename = "payloadType")]
+    pub payload_type: String,
+    /// Base64 of the JSON-serialized bundle.
+    pub payload: String,
+    pub signatures: Vec<EnvelopeSignature>,
+    pub bundle: VerificationBundle,
+}
+
+/// DSSE pre-authentication encoding:
+/// `"DSSEv1" SP LEN(type) SP type SP LEN(body) SP body`.
+pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut out = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Public key URL for a signing key.
+pub fn public_key_url(key_id: Uuid) -> String {
+    format!("/api/v1/signing/keys/{}/public", key_id)
+}
+
+/// Builds and signs verification bundles.
+pub struct ArtifactVerificationService {
+    db: PgPool,
+    signing: SigningService,
+}
+
+impl ArtifactVerificationService {
+    pub fn new(db: PgPool, signing: SigningService) -> Self {
+        Self { db, signing }
+    }
+
+    /// Build the bundle for an artifact and sign it with the repository's
+    /// active signing key, if any.
+    pub async fn signed_bundle(&self, artifact_id: Uuid) -> Result<VerificationEnvelope> {
+        let bundle = self.bundle(artifact_id).await?;
+        let payload = serde_json::to_vec(&bundle)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize bundle: {}", e)))?;
+        let to_sign = pae(VERIFICATION_PAYLOAD_TYPE, &payload);
+
+        let mut signatures = Vec::new();
+        if let Some(key) = self
+            .signing
+            .get_active_key_for_repo(bundle.artifact.repository_id)
+            .await?
+        {
+            let (sig, algorithm) = if key.key_type == "gpg" {
+                let armored = self
+                    .signing
+                    .sign_openpgp_detached_with_key(&key, &to_sign)
+                    .await?;
+                (armored.into_bytes(), "openpgp")
+            } else {
+                (
+                    self.signing.sign_with_key(&key, &to_sign)?,
+                    "rsa-pkcs1v15-sha256",
+                )
+            };
+            self.signing.mark_key_used(key.id).await?;
+            signatures.push(EnvelopeSignature {
+                keyid: key.id.to_string(),
+                sig: STANDARD.encode(sig),
+                algorithm: algorithm.to_string(),
+                public_key_url: public_key_url(key.id),
+            });
+        }
+
+        Ok(VerificationEnvelope {
+            payload_type: VERIFICATION_PAYLOAD_TYPE.to_string(),
+            payload: STANDARD.encode(&payload),
+            signatures,
+            bundle,
+        })
+    }
+
+    /// Assemble the (unsigned) bundle.
+    pub async fn bundle(&self, artifact_id: Uuid) -> Result<VerificationBundle> {
+        let artifact: VerifiedArtifact = sqlx::query_as(
+            r#"
+            SELECT a.id, a.repository_id, r.key AS repository_key, a.path, a.name,
+                   a.version, a.size_bytes, a.checksum_sha256, a.created_at AS uploaded_at
+            FROM artifacts a
+            JOIN repositories r ON r.id = a.repository_id
+            WHERE a.id = $1 AND a.is_deleted = false
+            "#,
+        )
+        .bind(artifact_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound("Artifact not found".to_string()))?;
+
+        let scan_summary = self.scan_summary(artifact_id).await?;
+        let policy = PolicyService::new(self.db.clone())
+            .evaluate_artifact(artifact_id, artifact.repository_id)
+            .await?;
+        let signature = self
+            .signature_status(artifact_id, artifact.repository_id)
+            .await?;
+        let provenance = self.provenance(&artifact).await?;
+
+        Ok(VerificationBundle {
+            schema: VERIFICATION_SCHEMA.to_string(),
+            generated_at: Utc::now(),
+            artifact,
+            scan_summary,
+            policy: VerificationPolicy {
+                allowed: policy.allowed,
+                violations: policy.violations,
+            },
+            signature,
+            provenance,
+        })
+    }
+
+    async fn scan_summary(&self, artifact_id: Uuid) -> Result<VerificationScanSummary> {
+        let state_rows: Vec<ScanStateRow> = sqlx::query_as(SCAN_STATE_SQL)
+            .bind(artifact_id)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let state = classify_scan_state(&state_rows);
+
+        let scans: Vec<VerificationScan> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT ON (scan_type)
+                   id AS scan_id, scan_type, status, scanner_version, completed_at,
+                   critical_count, high_count, medium_count, low_count, info_count
+            FROM scan_results
+            WHERE artifact_id = $1
+            ORDER BY scan_type, created_at DESC
+            "#,
+        )
+        .bind(artifact_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let completed: Vec<Uuid> = scans
+            .iter()
+            .filter(|s| s.status == "completed")
+            .map(|s| s.scan_id)
+            .collect();
+        let open_findings: VerificationFindingCounts = sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE NOT is_acknowledged AND severity = 'critical') AS critical,
+                COUNT(*) FILTER (WHERE NOT is_acknowledged AND severity = 'high') AS high,
+                COUNT(*) FILTER (WHERE NOT is_acknowledged AND severity = 'medium') AS medium,
+                COUNT(*) FILTER (WHERE NOT is_acknowledged AND severity = 'low') AS low,
+                COUNT(*) FILTER (WHERE NOT is_acknowledged AND severity = 'info') AS info,
+                COUNT(*) FILTER (WHERE is_acknowledged) AS acknowledged
+            FROM scan_findings
+            WHERE scan_result_id = ANY($1)
+            "#,
+        )
+        .bind(&completed)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(VerificationScanSummary {
+            state: state.reason_token().to_string(),
+            scans,
+            open_findings,
+        })
+    }
+
+    async fn signature_status(
+        &self,
+        artifact_id: Uuid,
+        repository_id: Uuid,
+    ) -> Result<VerificationSignatureStatus> {
+        let status: Option<VerificationSignatureStatus> = sqlx::query_as(
+            r#"
+            SELECT true AS signed, sk.id AS key_id, sk.fingerprint AS key_fingerprint,
+                   ska.details->>'algorithm' AS algorithm,
+                   ska.details->>'signature_sha256' AS signature_sha256,
+                   ska.created_at AS signed_at
+            FROM signing_key_audit ska
+            JOIN signing_keys sk ON sk.id = ska.signing_key_id
+            WHERE sk.repository_id = $2
+              AND sk.is_active = true
+              AND ska.action = 'used_for_signing'
+              AND ska.details->>'artifact_id' = $1::TEXT
+            ORDER BY ska.created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(artifact_id)
+        .bind(repository_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(status.unwrap_or_default())
+    }
+
+    async fn provenance(&self, artifact: &VerifiedArtifact) -> Result<VerificationProvenance> {
+        let sbom_documents: Vec<SbomReference> = sqlx::query_as(
+            r#"
+            SELECT id, format, format_version, content_hash, generator, generated_at
+            FROM sbom_documents
+            WHERE artifact_id = $1
+            ORDER BY generated_at DESC
+            "#,
+        )
+        .bind(artifact.id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let builds: Vec<BuildReference> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT b.id, b.name, b.build_number, b.status, b.vcs_url,
+                   b.vcs_revision, b.vcs_branch, b.finished_at
+            FROM builds b
+            JOIN build_artifacts ba ON ba.build_id = b.id
+            WHERE ba.checksum_sha256 = $1
+            ORDER BY b.finished_at DESC NULLS LAST
+            LIMIT 20
+            "#,
+        )
+        .bind(&artifact.checksum_sha256)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let promotions: Vec<PromotionReference> = sqlx::query_as(
+            r#"
+            SELECT ph.id, src.key AS source_repository, tgt.key AS target_repository,
+                   ph.status, ph.created_at
+            FROM promotion_history ph
+            JOIN repositories src ON src.id = ph.source_repo_id
+            JOIN repositories tgt ON tgt.id = ph.target_repo_id
+            WHERE ph.artifact_id = $1
+            ORDER BY ph.created_at DESC
+            LIMIT 50
+            "#,
+        )
+        .bind(artifact.id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(VerificationProvenance {
+            sbom_documents,
+            builds,
+            promotions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pae_matches_dsse_spec() {
+        // Test vector from the DSSE protocol document.
+        assert_eq!(
+            pae("http://example.com/HelloWorld", b"hello world"),
+            b"DSSEv1 29 http://example.com/HelloWorld 11 hello world".to_vec()
+        );
+        assert_eq!(pae("", b""), b"DSSEv1 0  0 ".to_vec());
+    }
+
+    #[test]
+    fn test_public_key_url() {
+        let id = Uuid::nil();
+        assert_eq!(
+            public_key_url(id),
+            "/api/v1/signing/keys/00000000-0000-0000-0000-000000000000/public"
+        );
+    }
+
+    #[test]
+    fn test_envelope_uses_dsse_field_names() {
+        let envelope = VerificationEnvelope {
+            payload_type: VERIFICATION_PAYLOAD_TYPE.to_string(),
+            payload: String::new(),
+            signatures: vec![],
+            bundle: VerificationBundle {
+                schema: VERIFICATION_SCHEMA.to_string(),
+                generated_at: Utc::now(),
+                artifact: VerifiedArtifact {
+                    id: Uuid::nil(),
+                    repository_id: Uuid::nil(),
+                    repository_key: "libs".to_string(),
+                    path: "a/b.jar".to_string(),
+                    name: "b".to_string(),
+                    version: None,
+                    size_bytes: 1,
+                    checksum_sha256: "00".repeat(32),
+                    uploaded_at: Utc::now(),
+                },
+                scan_summary: VerificationScanSummary {
+                    state: "never_scanned".to_string(),
+                    scans: vec![],
+                    open_findings: VerificationFindingCounts::default(),
+                },
+                policy: VerificationPolicy {
+                    allowed: true,
+                    violations: vec![],
+                },
+                signature: VerificationSignatureStatus::default(),
+                provenance: VerificationProvenance {
+                    sbom_documents: vec![],
+                    builds: vec![],
+                    promotions: vec![],
+                },
+            },
+        };
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["payloadType"], VERIFICATION_PAYLOAD_TYPE);
+        assert!(json.get("payload_type").is_none());
+        assert_eq!(json["bundle"]["signature"]["signed"], false);
+        assert_eq!(json["bundle"]["schema"], VERIFICATION_SCHEMA);
+    }
+}
@@ -0,0 +1,416 @@
+//! Audit-log retention with archival to object storage.
+//!
+//! `audit_log` grows with every authenticated action, so it needs a
+//! retention bound. Deleting aged rows outright loses history that compliance
+//! reviews still ask for, so the sweep first exports each batch as a
+//! gzip-compressed JSONL object in primary storage, records the covered time
+//! range in `audit_log_archives`, and only then deletes the rows.
+//!
+//! Each batch runs in one transaction: the rows are locked with
+//! `FOR UPDATE SKIP LOCKED`, the object is written, and the archive row insert
+//! and the delete commit together. A concurrent sweep (the manual
+//! `/admin/cleanup` racing the scheduled one) therefore never archives the
+//! same row twice, and a failed storage write leaves the rows in place for
+//! the next run. A crash between the object write and the commit can leave an
+//! unreferenced object behind; it is harmless and the rows are archived again.
+
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::audit_service::AuditService;
+use crate::storage::StorageBackend;
+
+/// Rows exported per archive object. Bounds both the transaction size and the
+/// memory needed to read an archive back through the API.
+pub const ARCHIVE_BATCH_SIZE: i64 = 10_000;
+
+/// Upper bound on batches per sweep so one run cannot hold the scheduler for
+/// hours after retention is first enabled on a large table. The remainder is
+/// picked up on the next tick.
+pub const MAX_BATCHES_PER_RUN: usize = 100;
+
+/// Upper bound on the decompressed size of an archive read back through the
+/// API. Archives are written by this service and stay far below this; the
+/// cap only guards against a corrupted or replaced object.
+const MAX_DECODED_ARCHIVE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Retention applied when the `audit_retention_days` system setting is unset,
+/// matching the default reported by `GET /api/v1/admin/settings`.
+pub const DEFAULT_AUDIT_RETENTION_DAYS: i32 = 90;
+
+/// Storage key prefix for audit archives.
+const ARCHIVE_KEY_PREFIX: &str = "audit-archive";
+
+/// One `audit_log` row as written to an archive (one JSON object per line).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct ArchivedAuditRecord {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<Uuid>,
+    pub details: Option<serde_json::Value>,
+    pub ip_address: Option<String>,
+    pub correlation_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Metadata for one archive object.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct AuditArchive {
+    pub id: Uuid,
+    pub storage_key: String,
+    /// Timestamp of the oldest record in the archive.
+    pub range_start: DateTime<Utc>,
+    /// Timestamp of the newest record in the archive.
+    pub range_end: DateTime<Utc>,
+    pub record_count: i64,
+    /// Size of the compressed object in bytes.
+    pub size_bytes: i64,
+    /// SHA-256 of the compressed object.
+    pub checksum_sha256: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Totals from one retention sweep.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionOutcome {
+    pub archives_written: u64,
+    pub records_deleted: u64,
+}
+
+/// Encode records as gzip-compressed JSONL.
+pub fn encode_archive(records: &[ArchivedAuditRecord]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for record in records {
+        serde_json::to_writer(&mut encoder, record)
+            .map_err(|e| AppError::Internal(format!("Failed to encode audit record: {}", e)))?;
+        encoder.write_all(b"\n")?;
+    }
+    Ok(encoder.finish()?)
+}
+
+/// Decode a gzip-compressed JSONL archive. Blank lines are ignored.
+pub fn decode_archive(data: &[u8]) -> Result<Vec<ArchivedAuditRecord>> {
+    let mut jsonl = String::new();
+    GzDecoder::new(data)
+        .take(MAX_DECODED_ARCHIVE_BYTES)
+        .read_to_string(&mut jsonl)
+        .map_err(|e| AppError::Internal(format!("Failed to decompress audit archive: {}", e)))?;
+    jsonl
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|e| AppError::Internal(format!("Corrupt audit archive record: {}", e)))
+        })
+        .collect()
+}
+
+/// Storage key for an archive starting at `range_start`. Partitioned by
+/// year/month so a bucket listing stays navigable by hand.
+pub fn archive_storage_key(range_start: DateTime<Utc>, archive_id: Uuid) -> String {
+    format!(
+        "{}/{}/{}-{}.jsonl.gz",
+        ARCHIVE_KEY_PREFIX,
+        range_start.format("%Y/%m"),
+        range_start.format("%Y%m%dT%H%M%SZ"),
+        archive_id
+    )
+}
+
+/// Audit retention and archive service.
+pub struct AuditArchiveService {
+    db: PgPool,
+    storage: Arc<dyn StorageBackend>,
+}
+
+impl AuditArchiveService {
+    pub fn new(db: PgPool, storage: Arc<dyn StorageBackend>) -> Self {
+        Self { db, storage }
+    }
+
+    /// Current `audit_retention_days` system setting, or
+    /// [`DEFAULT_AUDIT_RETENTION_DAYS`] when it has never been set.
+    pub async fn configured_retention_days(&self) -> Result<i32> {
+        let value: Option<serde_json::Value> = sqlx::query_scalar(
+            "SELECT value FROM system_settings WHERE key = 'audit_retention_days'",
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(value
+            .and_then(|v| v.as_i64())
+            .map(|days| days as i32)
+            .unwrap_or(DEFAULT_AUDIT_RETENTION_DAYS))
+    }
+
+    /// Remove audit rows older than `retention_days`, exporting them first
+    /// when `archive` is set. With `archive` unset this is a plain delete.
+    pub async fn apply_retention(
+        &self,
+        retention_days: i32,
+        archive: bool,
+    ) -> Result<RetentionOutcome> {
+        if retention_days <= 0 {
+            return Err(AppError::Validation(
+                "audit_retention_days must be positive".to_string(),
+            ));
+        }
+        if !archive {
+            let deleted = AuditService::new(self.db.clone())
+                .cleanup(retention_days)
+                .await?;
+            return Ok(RetentionOutcome {
+                archives_written: 0,
+                records_deleted: deleted,
+            });
+        }
+
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(retention_days));
+        let mut outcome = RetentionOutcome::default();
+        for _ in 0..MAX_BATCHES_PER_RUN {
+            let archived = self.archive_batch(cutoff).await?;
+            if archived == 0 {
+                break;
+            }
+            outcome.archives_written += 1;
+            outcome.records_deleted += archived;
+            if archived < ARCHIVE_BATCH_SIZE as u64 {
+                break;
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Archive and delete one batch of rows created before `cutoff`.
+    /// Returns the number of rows archived (0 when nothing is due).
+    async fn archive_batch(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let mut tx = self.db.begin().await?;
+
+        let records: Vec<ArchivedAuditRecord> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, action, resource_type, resource_id,
+                   details, ip_address, correlation_id, created_at
+            FROM audit_log
+            WHERE created_at < $1
+            ORDER BY created_at, id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(cutoff)
+        .bind(ARCHIVE_BATCH_SIZE)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let (Some(first), Some(last)) = (records.first(), records.last()) else {
+            return Ok(0);
+        };
+        let range_start = first.created_at;
+        let range_end = last.created_at;
+
+        let encoded = encode_archive(&records)?;
+        let checksum = hex::encode(Sha256::digest(&encoded));
+        let size_bytes = encoded.len() as i64;
+        let archive_id = Uuid::new_v4();
+        let storage_key = archive_storage_key(range_start, archive_id);
+
+        self.storage.put(&storage_key, Bytes::from(encoded)).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log_archives
+                (id, storage_key, range_start, range_end, record_count, size_bytes, checksum_sha256)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(archive_id)
+        .bind(&storage_key)
+        .bind(range_start)
+        .bind(range_end)
+        .bind(records.len() as i64)
+        .bind(size_bytes)
+        .bind(&checksum)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let ids: Vec<Uuid> = records.iter().map(|r| r.id).collect();
+        let deleted = sqlx::query("DELETE FROM audit_log WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .rows_affected();
+
+        tx.commit().await?;
+
+        tracing::info!(
+            archive_id = %archive_id,
+            storage_key = %storage_key,
+            records = deleted,
+            "Archived audit log batch"
+        );
+        Ok(deleted)
+    }
+
+    /// List archives whose range overlaps `[from, to]`, oldest first.
+    /// Either bound may be omitted.
+    pub async fn list_archives(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<AuditArchive>, i64)> {
+        let archives: Vec<AuditArchive> = sqlx::query_as(
+            r#"
+            SELECT id, storage_key, range_start, range_end, record_count,
+                   size_bytes, checksum_sha256, created_at
+            FROM audit_log_archives
+            WHERE ($1::timestamptz IS NULL OR range_end >= $1)
+              AND ($2::timestamptz IS NULL OR range_start <= $2)
+            ORDER BY range_start, id
+            OFFSET $3
+            LIMIT $4
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM audit_log_archives
+            WHERE ($1::timestamptz IS NULL OR range_end >= $1)
+              AND ($2::timestamptz IS NULL OR range_start <= $2)
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((archives, total))
+    }
+
+    /// Fetch one archive's metadata.
+    pub async fn get_archive(&self, id: Uuid) -> Result<AuditArchive> {
+        sqlx::query_as(
+            r#"
+            SELECT id, storage_key, range_start, range_end, record_count,
+                   size_bytes, checksum_sha256, created_at
+            FROM audit_log_archives
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound("Audit archive not found".to_string()))
+    }
+
+    /// Read an archive back from storage, verifying its checksum.
+    pub async fn read_archive(&self, archive: &AuditArchive) -> Result<Vec<ArchivedAuditRecord>> {
+        let data = self.storage.get(&archive.storage_key).await?;
+        let checksum = hex::encode(Sha256::digest(&data));
+        if checksum != archive.checksum_sha256 {
+            return Err(AppError::Internal(format!(
+                "Audit archive {} failed checksum verification",
+                archive.id
+            )));
+        }
+        decode_archive(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(action: &str, at: DateTime<Utc>) -> ArchivedAuditRecord {
+        ArchivedAuditRecord {
+            id: Uuid::new_v4(),
+            user_id: Some(Uuid::new_v4()),
+            action: action.to_string(),
+            resource_type: "repository".to_string(),
+            resource_id: None,
+            details: Some(serde_json::json!({"key": "libs-release"})),
+            ip_address: Some("10.0.0.1".to_string()),
+            correlation_id: "req-123".to_string(),
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn test_archive_roundtrip_preserves_records() {
+        let t0 = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let records = vec![
+            record("LOGIN", t0),
+            record("REPOSITORY_CREATED", t0 + chrono::Duration::seconds(1)),
+        ];
+        let encoded = encode_archive(&records).unwrap();
+        assert_eq!(&encoded[..2], &[0x1f, 0x8b], "archive must be gzip");
+        assert_eq!(decode_archive(&encoded).unwrap(), records);
+    }
+
+    #[test]
+    fn test_archive_is_one_json_object_per_line() {
+        let t0 = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let encoded = encode_archive(&[record("LOGIN", t0), record("LOGOUT", t0)]).unwrap();
+        let mut jsonl = String::new();
+        GzDecoder::new(&encoded[..])
+            .read_to_string(&mut jsonl)
+            .unwrap();
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(value.get("action").is_some());
+            assert!(value.get("created_at").is_some());
+        }
+    }
+
+    #[test]
+    fn test_empty_archive_roundtrip() {
+        let encoded = encode_archive(&[]).unwrap();
+        assert!(decode_archive(&encoded).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_rejects_non_gzip() {
+        assert!(decode_archive(b"{\"not\":\"gzip\"}\n").is_err());
+    }
+
+    #[test]
+    fn test_archive_storage_key_is_partitioned_by_month() {
+        let start = Utc.with_ymd_and_hms(2024, 11, 30, 23, 59, 1).unwrap();
+        let id = Uuid::nil();
+        assert_eq!(
+            archive_storage_key(start, id),
+            "audit-archive/2024/11/20241130T235901Z-00000000-0000-0000-0000-000000000000.jsonl.gz"
+        );
+    }
+}
@@ -121,7 +121,16 @@ impl AuditAction {
             | AuditAction::SessionsInvalidated
             | AuditAction::AgeGateQueued
             | AuditAction::AgeGateApproved
-            | AuditAction::CurationSyncTriggered => Outcome::Success,
+            | AuditAction::CurationSyncTriggered
+            | AuditAction::FreezeOverridden
+            | AuditAction::DataSubjectErased
+            | AuditAction::DeployGateEvaluated
+            | AuditAction::ArtifactSigned
+            | AuditAction::DataExported
+            | AuditAction::AuthzPolicyChanged
+            | AuditAction::StorageEncryptionMigrationStarted
+            | AuditAction::StorageShardRebalanceStarted
+            | AuditAction::StorageObjectsRestored => Outcome::Success,
         }
     }
 }
@@ -374,6 +383,9 @@ pub mod details {
         /// Minimum upstream publish age for that policy change.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub age_gate_min_age_days: Option<i32>,
+        /// The key before a rename, when `REPOSITORY_UPDATED` records one.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub previous_key: Option<String>,
     }
 
     /// `ROLE_ASSIGNED` / `ROLE_REVOKED` / `REPOSITORY_PERMISSION_CHANGED`.
@@ -1092,6 +1104,7 @@ mod tests {
             visibility: None,
             age_gate_enabled: None,
             age_gate_min_age_days: None,
+            previous_key: None,
         };
         let v = serde_json::to_value(&d).unwrap();
         assert_eq!(v["key"], "maven-releases");
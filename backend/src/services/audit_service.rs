@@ -102,6 +102,34 @@ pub enum AuditAction {
     // outbound sync and when. Appended at the END of the enum to keep the
     // additive change conflict-free with in-flight taxonomy work.
     CurationSyncTriggered,
+
+    // Release freeze windows. Recorded when a principal writes through an
+    // active freeze window using the `freeze_override` permission.
+    FreezeOverridden,
+    // GDPR data-subject erasure: a user's personal data was anonymized or
+    // deleted. Details carry the mode and row counts, never the erased values.
+    DataSubjectErased,
+    // Pre-deploy verification gate. Recorded for every decision so a
+    // rollout can be traced back to the checks it passed (or was denied by).
+    DeployGateEvaluated,
+    // Sign-on-promotion: an artifact promoted into a signing release
+    // repository was signed (or signing it failed).
+    ArtifactSigned,
+    // Compliance evidence export (findings, policy violations, audit log,
+    // artifact inventory). Details carry the dataset, format and filters.
+    DataExported,
+    // External authorization hook: an inline Rego policy was created,
+    // replaced or deleted. Details carry the policy name and operation.
+    AuthzPolicyChanged,
+    // Storage encryption migration started. Details carry the active key id
+    // objects are brought under.
+    StorageEncryptionMigrationStarted,
+    // Storage shard rebalance started. Details carry the backend and its
+    // shards.
+    StorageShardRebalanceStarted,
+    // Objects soft-deleted by the storage backend were undeleted. Details
+    // carry the backend and the restored keys.
+    StorageObjectsRestored,
 }
 
 impl AuditAction {
@@ -155,6 +183,17 @@ impl AuditAction {
             AuditAction::AgeGateRejected => "AGE_GATE_REJECTED",
             AuditAction::PermissionDenied => "PERMISSION_DENIED",
             AuditAction::CurationSyncTriggered => "CURATION_SYNC_TRIGGERED",
+            AuditAction::FreezeOverridden => "FREEZE_OVERRIDDEN",
+            AuditAction::DataSubjectErased => "DATA_SUBJECT_ERASED",
+            AuditAction::DeployGateEvaluated => "DEPLOY_GATE_EVALUATED",
+            AuditAction::ArtifactSigned => "ARTIFACT_SIGNED",
+            AuditAction::DataExported => "DATA_EXPORTED",
+            AuditAction::AuthzPolicyChanged => "AUTHZ_POLICY_CHANGED",
+            AuditAction::StorageEncryptionMigrationStarted => {
+                "STORAGE_ENCRYPTION_MIGRATION_STARTED"
+            }
+            AuditAction::StorageShardRebalanceStarted => "STORAGE_SHARD_REBALANCE_STARTED",
+            AuditAction::StorageObjectsRestored => "STORAGE_OBJECTS_RESTORED",
         }
     }
 }
@@ -172,6 +211,7 @@ pub enum ResourceType {
     Setting,
     Plugin,
     ScanResult,
+    Export,
 }
 
 impl ResourceType {
@@ -187,6 +227,7 @@ impl ResourceType {
             ResourceType::Setting => "setting",
             ResourceType::Plugin => "plugin",
             ResourceType::ScanResult => "scan_result",
+            ResourceType::Export => "export",
         }
     }
 }
@@ -1098,6 +1139,7 @@ mod tests {
         assert_eq!(ResourceType::Setting.as_str(), "setting");
         assert_eq!(ResourceType::Plugin.as_str(), "plugin");
         assert_eq!(ResourceType::ScanResult.as_str(), "scan_result");
+        assert_eq!(ResourceType::Export.as_str(), "export");
     }
 
     // -----------------------------------------------------------------------
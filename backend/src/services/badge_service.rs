@@ -0,0 +1,408 @@
+//! Package status badges.
+//!
+//! Renders shields.io-style flat SVG badges for a package's latest version,
+//! security scan status and license, so wikis and READMEs can embed live
+//! status with a plain `<img>` tag.
+
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// Badge colors (the shields.io palette).
+pub const COLOR_GREEN: &str = "#4c1";
+pub const COLOR_YELLOWGREEN: &str = "#a4a61d";
+pub const COLOR_YELLOW: &str = "#dfb317";
+pub const COLOR_ORANGE: &str = "#fe7d37";
+pub const COLOR_RED: &str = "#e05d44";
+pub const COLOR_BLUE: &str = "#007ec6";
+pub const COLOR_GREY: &str = "#9f9f9f";
+
+/// Longest label or message rendered before truncation.
+const MAX_TEXT_CHARS: usize = 48;
+
+/// What a badge shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeKind {
+    Version,
+    Scan,
+    License,
+}
+
+impl BadgeKind {
+    /// Parse the final path segment of a badge URL (`version.svg`, ...).
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name.strip_suffix(".svg").unwrap_or(name) {
+            "version" => Some(Self::Version),
+            "scan" | "security" => Some(Self::Scan),
+            "license" => Some(Self::License),
+            _ => None,
+        }
+    }
+
+    pub fn default_label(&self) -> &'static str {
+        match self {
+            Self::Version => "version",
+            Self::Scan => "security",
+            Self::License => "license",
+        }
+    }
+}
+
+/// Aggregate security scan state of a package version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanStatus {
+    NotScanned,
+    Pending,
+    Failed,
+    Completed {
+        critical: i64,
+        high: i64,
+        medium: i64,
+        low: i64,
+    },
+}
+
+impl ScanStatus {
+    pub fn message_and_color(&self) -> (String, &'static str) {
+        match *self {
+            ScanStatus::NotScanned => ("not scanned".to_string(), COLOR_GREY),
+            ScanStatus::Pending => ("pending".to_string(), COLOR_BLUE),
+            ScanStatus::Failed => ("scan failed".to_string(), COLOR_GREY),
+            ScanStatus::Completed { critical, .. } if critical > 0 => {
+                (format!("{} critical", critical), COLOR_RED)
+            }
+            ScanStatus::Completed { high, .. } if high > 0 => {
+                (format!("{} high", high), COLOR_ORANGE)
+            }
+            ScanStatus::Completed { medium, .. } if medium > 0 => {
+                (format!("{} medium", medium), COLOR_YELLOW)
+            }
+            ScanStatus::Completed { low, .. } if low > 0 => {
+                (format!("{} low", low), COLOR_YELLOWGREEN)
+            }
+            ScanStatus::Completed { .. } => ("passing".to_string(), COLOR_GREEN),
+        }
+    }
+}
+
+/// The latest version of a package and what its badges report.
+#[derive(Debug, Clone)]
+pub struct PackageBadgeData {
+    pub version: String,
+    pub scan: ScanStatus,
+    pub license: Option<String>,
+}
+
+/// Artifacts of the package's current version: the version's representative
+/// asset by checksum plus any artifact recorded under the same name/version.
+const VERSION_ARTIFACTS: &str = "\
+    SELECT a.id FROM packages p \
+    JOIN artifacts a ON a.repository_id = p.repository_id AND a.is_deleted = false \
+    LEFT JOIN package_versions pv ON pv.package_id = p.id AND pv.version = p.version \
+    WHERE p.id = $1 \
+      AND (a.checksum_sha256 = pv.checksum_sha256 OR (a.name = p.name AND a.version = p.version))";
+
+/// Resolve badge data for `name` in a repository; `None` when the package
+/// does not exist.
+pub async fn package_badge_data(
+    db: &PgPool,
+    repository_id: Uuid,
+    name: &str,
+) -> Result<Option<PackageBadgeData>> {
+    let row = sqlx::query(
+        "SELECT id, version, metadata FROM packages WHERE repository_id = $1 AND name = $2",
+    )
+    .bind(repository_id)
+    .bind(name)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let package_id: Uuid = row.get("id");
+    let version: String = row.get("version");
+    let metadata: Option<Value> = row.get("metadata");
+
+    // Latest result per artifact and scanner.
+    let scans = sqlx::query(&format!(
+        "SELECT DISTINCT ON (sr.artifact_id, sr.scan_type) \
+             sr.status, sr.critical_count, sr.high_count, sr.medium_count, sr.low_count \
+         FROM scan_results sr \
+         WHERE sr.artifact_id IN ({VERSION_ARTIFACTS}) \
+         ORDER BY sr.artifact_id, sr.scan_type, sr.created_at DESC"
+    ))
+    .bind(package_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let scan = summarize_scans(scans.iter().map(|r| {
+        (
+            r.get::<String, _>("status"),
+            [
+                r.get::<i32, _>("critical_count"),
+                r.get::<i32, _>("high_count"),
+                r.get::<i32, _>("medium_count"),
+                r.get::<i32, _>("low_count"),
+            ],
+        )
+    }));
+
+    let mut license = metadata.as_ref().and_then(license_from_metadata);
+    if license.is_none() {
+        let artifact_metadata: Vec<Value> = sqlx::query_scalar(&format!(
+            "SELECT am.metadata FROM artifact_metadata am \
+             WHERE am.artifact_id IN ({VERSION_ARTIFACTS})"
+        ))
+        .bind(package_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        license = artifact_metadata.iter().find_map(license_from_metadata);
+    }
+
+    Ok(Some(PackageBadgeData {
+        version,
+        scan,
+        license,
+    }))
+}
+
+/// Fold per-scan `(status, [critical, high, medium, low])` rows into one
+/// status. Completed scans win over in-flight ones so a rescan does not
+/// blank out a known result.
+pub fn summarize_scans(rows: impl IntoIterator<Item = (String, [i32; 4])>) -> ScanStatus {
+    let (mut completed, mut pending, mut failed) = (false, false, false);
+    let mut counts = [0i64; 4];
+    for (status, row) in rows {
+        match status.as_str() {
+            "completed" => {
+                completed = true;
+                for (total, n) in counts.iter_mut().zip(row) {
+                    *total += n as i64;
+                }
+            }
+            "pending" | "running" => pending = true,
+            "failed" => failed = true,
+            _ => {}
+        }
+    }
+    if completed {
+        let [critical, high, medium, low] = counts;
+        ScanStatus::Completed {
+            critical,
+            high,
+            medium,
+            low,
+        }
+    } else if pending {
+        ScanStatus::Pending
+    } else if failed {
+        ScanStatus::Failed
+    } else {
+        ScanStatus::NotScanned
+    }
+}
+
+/// Pull a declared license out of format metadata: a `license` string or
+/// object (`{"type": ...}` / `{"name": ...}`), or a `licenses` array.
+pub fn license_from_metadata(metadata: &Value) -> Option<String> {
+    fn one(value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+            Value::Object(o) => ["spdx", "type", "name", "id"]
+                .iter()
+                .find_map(|k| o.get(*k).and_then(one)),
+            _ => None,
+        }
+    }
+    if let Some(license) = metadata.get("license").and_then(one) {
+        return Some(license);
+    }
+    let licenses: Vec<String> = match metadata.get("licenses") {
+        Some(Value::Array(items)) => items.iter().filter_map(one).collect(),
+        Some(other) => one(other).into_iter().collect(),
+        None => Vec::new(),
+    };
+    (!licenses.is_empty()).then(|| licenses.join(" OR "))
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+/// Approximate rendered width of `text` in 11px Verdana, close enough to size
+/// the badge without shipping font metrics.
+fn text_width(text: &str) -> u32 {
+    let width: f32 = text
+        .chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '\'' | '|' | '!' | ' ' => 3.5,
+            'f' | 'r' | 't' | 'I' | '(' | ')' | '[' | ']' | '-' | '/' => 4.8,
+            'm' | 'w' | 'M' | 'W' | '@' | '%' => 10.5,
+            c if c.is_ascii_uppercase() => 7.6,
+            c if c.is_ascii() => 6.6,
+            _ => 11.0,
+        })
+        .sum();
+    width.ceil() as u32
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_TEXT_CHARS {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(MAX_TEXT_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render a two-part flat badge.
+pub fn render(label: &str, message: &str, color: &str) -> String {
+    let (label, message) = (truncate(label), truncate(message));
+    let label_width = text_width(&label) + 10;
+    let message_width = text_width(&message) + 10;
+    let width = label_width + message_width;
+    let label_x = label_width as f32 / 2.0;
+    let message_x = label_width as f32 + message_width as f32 / 2.0;
+    let (label, message) = (escape_xml(&label), escape_xml(&message));
+    format!(
+        concat!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">"##,
+            r##"<title>{label}: {message}</title>"##,
+            r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+            r##"<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>"##,
+            r##"<g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="#555"/><rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g>"##,
+            r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##,
+            r##"<text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text><text x="{label_x}" y="14">{label}</text>"##,
+            r##"<text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text><text x="{message_x}" y="14">{message}</text>"##,
+            r##"</g></svg>"##,
+        ),
+        width = width,
+        label_width = label_width,
+        message_width = message_width,
+        label_x = label_x,
+        message_x = message_x,
+        label = label,
+        message = message,
+        color = color,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_badge_kind_from_file_name() {
+        assert_eq!(
+            BadgeKind::from_file_name("version.svg"),
+            Some(BadgeKind::Version)
+        );
+        assert_eq!(BadgeKind::from_file_name("scan"), Some(BadgeKind::Scan));
+        assert_eq!(
+            BadgeKind::from_file_name("security.svg"),
+            Some(BadgeKind::Scan)
+        );
+        assert_eq!(BadgeKind::from_file_name("downloads.svg"), None);
+    }
+
+    #[test]
+    fn test_summarize_scans() {
+        assert_eq!(summarize_scans(vec![]), ScanStatus::NotScanned);
+        assert_eq!(
+            summarize_scans(vec![("running".to_string(), [0; 4])]),
+            ScanStatus::Pending
+        );
+        assert_eq!(
+            summarize_scans(vec![("failed".to_string(), [0; 4])]),
+            ScanStatus::Failed
+        );
+        let status = summarize_scans(vec![
+            ("completed".to_string(), [0, 2, 1, 0]),
+            ("completed".to_string(), [1, 0, 0, 3]),
+            ("running".to_string(), [0; 4]),
+        ]);
+        assert_eq!(
+            status,
+            ScanStatus::Completed {
+                critical: 1,
+                high: 2,
+                medium: 1,
+                low: 3
+            }
+        );
+        assert_eq!(
+            status.message_and_color(),
+            ("1 critical".to_string(), COLOR_RED)
+        );
+        assert_eq!(
+            summarize_scans(vec![("completed".to_string(), [0; 4])]).message_and_color(),
+            ("passing".to_string(), COLOR_GREEN)
+        );
+    }
+
+    #[test]
+    fn test_license_from_metadata() {
+        assert_eq!(
+            license_from_metadata(&json!({"license": "MIT"})).as_deref(),
+            Some("MIT")
+        );
+        assert_eq!(
+            license_from_metadata(&json!({"license": {"type": "Apache-2.0"}})).as_deref(),
+            Some("Apache-2.0")
+        );
+        assert_eq!(
+            license_from_metadata(&json!({"licenses": ["MIT", {"name": "BSD-3-Clause"}]}))
+                .as_deref(),
+            Some("MIT OR BSD-3-Clause")
+        );
+        assert_eq!(license_from_metadata(&json!({"license": "  "})), None);
+        assert_eq!(license_from_metadata(&json!({})), None);
+    }
+
+    #[test]
+    fn test_render_escapes_and_sizes() {
+        let svg = render("version", "1.0.0<script>", COLOR_BLUE);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("1.0.0&lt;script&gt;"));
+        assert!(!svg.contains("<script>"));
+        assert!(svg.contains(COLOR_BLUE));
+
+        let short = render("v", "1", COLOR_GREEN);
+        let long = render("version", "1.0.0-rc.1+build.42", COLOR_GREEN);
+        let width = |svg: &str| -> u32 {
+            let start = svg.find("width=\"").unwrap() + 7;
+            svg[start..start + svg[start..].find('"').unwrap()]
+                .parse()
+                .unwrap()
+        };
+        assert!(width(&long) > width(&short));
+    }
+
+    #[test]
+    fn test_render_truncates_long_text() {
+        let svg = render("license", &"A".repeat(200), COLOR_BLUE);
+        assert!(svg.contains('…'));
+        assert!(!svg.contains(&"A".repeat(MAX_TEXT_CHARS)));
+    }
+}
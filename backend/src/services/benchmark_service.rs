@@ -0,0 +1,473 @@
+//! Benchmark harness for performance-sensitive server paths.
+//!
+//! Times a fixed set of scenarios (database search, storage GC candidate
+//! selection, scan policy evaluation) against whatever data the instance
+//! holds, typically a run from [`crate::services::synthetic_data_service`].
+//! Each report is stored and compared with the latest earlier report taken
+//! at a similar data volume, so a slowdown shows up as a flagged regression
+//! rather than a number someone has to remember.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::policy_service::PolicyService;
+use crate::services::search_service::{SearchQuery, SearchService};
+use crate::services::storage_gc_service::StorageGcService;
+use crate::services::synthetic_data_service::PACKAGE_PREFIXES;
+use crate::storage::StorageRegistry;
+
+pub const MAX_ITERATIONS: u32 = 200;
+/// A GC dry run scans every deleted artifact, so it is repeated fewer times.
+const GC_MAX_ITERATIONS: u32 = 5;
+/// A baseline is only comparable when its live artifact count is within
+/// this fraction of the current one.
+const BASELINE_VOLUME_TOLERANCE: f64 = 0.10;
+/// Slowdowns smaller than this are treated as noise whatever the percentage.
+const MIN_REGRESSION_MS: f64 = 1.0;
+
+fn default_iterations() -> u32 {
+    20
+}
+
+fn default_regression_threshold_pct() -> f64 {
+    25.0
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BenchmarkOptions {
+    /// Timed iterations per scenario (default 20, max 200).
+    #[serde(default = "default_iterations")]
+    pub iterations: u32,
+    /// A scenario regresses when its median is this many percent slower
+    /// than the baseline's (default 25).
+    #[serde(default = "default_regression_threshold_pct")]
+    pub regression_threshold_pct: f64,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
+        Self {
+            iterations: default_iterations(),
+            regression_threshold_pct: default_regression_threshold_pct(),
+        }
+    }
+}
+
+impl BenchmarkOptions {
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_ITERATIONS).contains(&self.iterations) {
+            return Err(AppError::Validation(format!(
+                "iterations must be between 1 and {}",
+                MAX_ITERATIONS
+            )));
+        }
+        if self.regression_threshold_pct.is_nan() || self.regression_threshold_pct <= 0.0 {
+            return Err(AppError::Validation(
+                "regression_threshold_pct must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Timing summary for one scenario, in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScenarioTiming {
+    pub name: String,
+    pub iterations: u32,
+    pub min_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    /// Why the scenario did not run (no data, or the path returned an error).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_p50_ms: Option<f64>,
+    /// Change in median against the baseline, in percent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_pct: Option<f64>,
+    #[serde(default)]
+    pub regressed: bool,
+}
+
+impl ScenarioTiming {
+    /// Summarise raw iteration timings.
+    pub fn from_samples(name: &str, mut samples: Vec<f64>) -> Self {
+        samples.sort_by(f64::total_cmp);
+        let n = samples.len();
+        let mean = if n == 0 {
+            0.0
+        } else {
+            samples.iter().sum::<f64>() / n as f64
+        };
+        Self {
+            name: name.to_string(),
+            iterations: n as u32,
+            min_ms: samples.first().copied().unwrap_or(0.0),
+            p50_ms: percentile(&samples, 50.0),
+            p95_ms: percentile(&samples, 95.0),
+            max_ms: samples.last().copied().unwrap_or(0.0),
+            mean_ms: mean,
+            skipped: None,
+            baseline_p50_ms: None,
+            change_pct: None,
+            regressed: false,
+        }
+    }
+
+    fn skipped(name: &str, reason: String) -> Self {
+        Self {
+            skipped: Some(reason),
+            ..Self::from_samples(name, Vec::new())
+        }
+    }
+}
+
+/// Nearest-rank percentile of ascending `sorted` samples.
+pub fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Annotate `scenarios` with their change against `baseline` and flag
+/// regressions. Returns the number of regressed scenarios.
+pub fn compare_to_baseline(
+    scenarios: &mut [ScenarioTiming],
+    baseline: &[ScenarioTiming],
+    threshold_pct: f64,
+) -> i32 {
+    let mut regressions = 0;
+    for scenario in scenarios.iter_mut().filter(|s| s.skipped.is_none()) {
+        let Some(base) = baseline
+            .iter()
+            .find(|b| b.name == scenario.name && b.skipped.is_none() && b.p50_ms > 0.0)
+        else {
+            continue;
+        };
+        let change_pct = (scenario.p50_ms - base.p50_ms) / base.p50_ms * 100.0;
+        scenario.baseline_p50_ms = Some(base.p50_ms);
+        scenario.change_pct = Some(change_pct);
+        scenario.regressed =
+            change_pct > threshold_pct && scenario.p50_ms - base.p50_ms >= MIN_REGRESSION_MS;
+        if scenario.regressed {
+            regressions += 1;
+        }
+    }
+    regressions
+}
+
+/// A stored benchmark report.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct BenchmarkRun {
+    pub id: Uuid,
+    pub created_by: Option<Uuid>,
+    /// Live artifacts when the benchmark ran.
+    pub artifact_count: i64,
+    #[schema(value_type = Vec<ScenarioTiming>)]
+    pub scenarios: serde_json::Value,
+    pub regressions: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Result of a benchmark run.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BenchmarkReport {
+    pub id: Uuid,
+    pub artifact_count: i64,
+    /// Earlier report the timings were compared with, if any was taken at a
+    /// comparable data volume.
+    pub baseline_run_id: Option<Uuid>,
+    pub regression_threshold_pct: f64,
+    pub scenarios: Vec<ScenarioTiming>,
+    pub regressions: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct BenchmarkService {
+    db: PgPool,
+    storage_registry: Arc<StorageRegistry>,
+}
+
+impl BenchmarkService {
+    pub fn new(db: PgPool, storage_registry: Arc<StorageRegistry>) -> Self {
+        Self {
+            db,
+            storage_registry,
+        }
+    }
+
+    /// Run every scenario, store the report and compare it with the baseline.
+    pub async fn run(
+        &self,
+        options: &BenchmarkOptions,
+        created_by: Option<Uuid>,
+    ) -> Result<BenchmarkReport> {
+        options.validate()?;
+        let artifact_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM artifacts WHERE is_deleted = false")
+                .fetch_one(&self.db)
+                .await?;
+
+        let iterations = options.iterations;
+        let mut scenarios = vec![
+            self.bench_search("search.fulltext", iterations, |term| SearchQuery {
+                q: Some(term.to_string()),
+                limit: Some(20),
+                ..Default::default()
+            })
+            .await,
+            self.bench_search("search.name", iterations, |term| SearchQuery {
+                name: Some(term.to_string()),
+                limit: Some(20),
+                ..Default::default()
+            })
+            .await,
+            self.bench_search("search.by_downloads", iterations, |term| SearchQuery {
+                q: Some(term.to_string()),
+                limit: Some(20),
+                sort_by: Some("downloads".to_string()),
+                ..Default::default()
+            })
+            .await,
+            self.bench_gc_dry_run(iterations.min(GC_MAX_ITERATIONS))
+                .await,
+            self.bench_policy_evaluation(iterations).await,
+        ];
+
+        let baseline = self.find_baseline(artifact_count).await?;
+        let regressions = match &baseline {
+            Some((_, base)) => {
+                compare_to_baseline(&mut scenarios, base, options.regression_threshold_pct)
+            }
+            None => 0,
+        };
+
+        let (id, created_at): (Uuid, DateTime<Utc>) = sqlx::query_as(
+            r#"
+            INSERT INTO benchmark_runs (created_by, artifact_count, scenarios, regressions)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, created_at
+            "#,
+        )
+        .bind(created_by)
+        .bind(artifact_count)
+        .bind(serde_json::to_value(&scenarios).map_err(|e| AppError::Internal(e.to_string()))?)
+        .bind(regressions)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(BenchmarkReport {
+            id,
+            artifact_count,
+            baseline_run_id: baseline.map(|(id, _)| id),
+            regression_threshold_pct: options.regression_threshold_pct,
+            scenarios,
+            regressions,
+            created_at,
+        })
+    }
+
+    async fn bench_search(
+        &self,
+        name: &str,
+        iterations: u32,
+        build: impl Fn(&str) -> SearchQuery,
+    ) -> ScenarioTiming {
+        let search = SearchService::new(self.db.clone());
+        let mut samples = Vec::with_capacity(iterations as usize);
+        for i in 0..iterations as usize {
+            let query = build(PACKAGE_PREFIXES[i % PACKAGE_PREFIXES.len()]);
+            let started = Instant::now();
+            if let Err(e) = search.search(query).await {
+                return ScenarioTiming::skipped(name, e.to_string());
+            }
+            samples.push(elapsed_ms(started));
+        }
+        ScenarioTiming::from_samples(name, samples)
+    }
+
+    async fn bench_gc_dry_run(&self, iterations: u32) -> ScenarioTiming {
+        let name = "gc.dry_run";
+        let gc = StorageGcService::new(self.db.clone(), self.storage_registry.clone());
+        let mut samples = Vec::with_capacity(iterations as usize);
+        for _ in 0..iterations {
+            let started = Instant::now();
+            if let Err(e) = gc.run_gc(true).await {
+                return ScenarioTiming::skipped(name, e.to_string());
+            }
+            samples.push(elapsed_ms(started));
+        }
+        ScenarioTiming::from_samples(name, samples)
+    }
+
+    async fn bench_policy_evaluation(&self, iterations: u32) -> ScenarioTiming {
+        let name = "policy.evaluate_artifact";
+        // Sampling is setup, not part of the timed path.
+        let sample: Vec<(Uuid, Uuid)> = match sqlx::query_as(
+            r#"
+            SELECT id, repository_id FROM artifacts
+            WHERE is_deleted = false
+            ORDER BY random()
+            LIMIT $1
+            "#,
+        )
+        .bind(i64::from(iterations))
+        .fetch_all(&self.db)
+        .await
+        {
+            Ok(sample) => sample,
+            Err(e) => return ScenarioTiming::skipped(name, e.to_string()),
+        };
+        if sample.is_empty() {
+            return ScenarioTiming::skipped(name, "no artifacts to evaluate".to_string());
+        }
+
+        let policies = PolicyService::new(self.db.clone());
+        let mut samples = Vec::with_capacity(iterations as usize);
+        for (artifact_id, repository_id) in sample.iter().cycle().take(iterations as usize) {
+            let started = Instant::now();
+            if let Err(e) = policies
+                .evaluate_artifact(*artifact_id, *repository_id)
+                .await
+            {
+                return ScenarioTiming::skipped(name, e.to_string());
+            }
+            samples.push(elapsed_ms(started));
+        }
+        ScenarioTiming::from_samples(name, samples)
+    }
+
+    /// Latest earlier report taken at a comparable artifact count.
+    async fn find_baseline(
+        &self,
+        artifact_count: i64,
+    ) -> Result<Option<(Uuid, Vec<ScenarioTiming>)>> {
+        let low = (artifact_count as f64 * (1.0 - BASELINE_VOLUME_TOLERANCE)).floor() as i64;
+        let high = (artifact_count as f64 * (1.0 + BASELINE_VOLUME_TOLERANCE)).ceil() as i64;
+        let row: Option<(Uuid, serde_json::Value)> = sqlx::query_as(
+            r#"
+            SELECT id, scenarios FROM benchmark_runs
+            WHERE artifact_count BETWEEN $1 AND $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(low)
+        .bind(high)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row.and_then(|(id, scenarios)| {
+            serde_json::from_value(scenarios)
+                .ok()
+                .map(|scenarios| (id, scenarios))
+        }))
+    }
+
+    /// Stored reports, newest first.
+    pub async fn list_runs(&self, limit: i64) -> Result<Vec<BenchmarkRun>> {
+        let runs = sqlx::query_as::<_, BenchmarkRun>(
+            "SELECT * FROM benchmark_runs ORDER BY created_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(runs)
+    }
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(name: &str, p50_ms: f64) -> ScenarioTiming {
+        ScenarioTiming::from_samples(name, vec![p50_ms])
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let samples: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert_eq!(percentile(&samples, 50.0), 10.0);
+        assert_eq!(percentile(&samples, 95.0), 19.0);
+        assert_eq!(percentile(&samples, 100.0), 20.0);
+        assert_eq!(percentile(&samples, 0.0), 1.0);
+        assert_eq!(percentile(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn test_from_samples_summarises_unsorted_input() {
+        let timing = ScenarioTiming::from_samples("x", vec![4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(timing.iterations, 4);
+        assert_eq!(timing.min_ms, 1.0);
+        assert_eq!(timing.max_ms, 4.0);
+        assert_eq!(timing.p50_ms, 2.0);
+        assert_eq!(timing.mean_ms, 2.5);
+    }
+
+    #[test]
+    fn test_compare_flags_slowdowns_beyond_threshold() {
+        let baseline = vec![timing("search", 10.0), timing("gc", 100.0)];
+        let mut current = vec![timing("search", 14.0), timing("gc", 110.0)];
+        assert_eq!(compare_to_baseline(&mut current, &baseline, 25.0), 1);
+        assert!(current[0].regressed);
+        assert_eq!(current[0].change_pct, Some(40.0));
+        assert!(!current[1].regressed);
+        assert_eq!(current[1].baseline_p50_ms, Some(100.0));
+    }
+
+    #[test]
+    fn test_compare_ignores_sub_millisecond_noise() {
+        let baseline = vec![timing("search", 0.25)];
+        let mut current = vec![timing("search", 0.75)];
+        assert_eq!(compare_to_baseline(&mut current, &baseline, 25.0), 0);
+        assert_eq!(current[0].change_pct, Some(200.0));
+    }
+
+    #[test]
+    fn test_compare_skips_missing_and_skipped_scenarios() {
+        let baseline = vec![ScenarioTiming::skipped("policy", "no data".to_string())];
+        let mut current = vec![timing("policy", 50.0), timing("new", 5.0)];
+        assert_eq!(compare_to_baseline(&mut current, &baseline, 25.0), 0);
+        assert!(current.iter().all(|s| s.change_pct.is_none()));
+    }
+
+    #[test]
+    fn test_options_validation() {
+        assert!(BenchmarkOptions::default().validate().is_ok());
+        let zero = BenchmarkOptions {
+            iterations: 0,
+            ..Default::default()
+        };
+        assert!(zero.validate().is_err());
+        let threshold = BenchmarkOptions {
+            regression_threshold_pct: 0.0,
+            ..Default::default()
+        };
+        assert!(threshold.validate().is_err());
+    }
+
+    #[test]
+    fn test_scenario_timing_round_trips_through_json() {
+        let mut scenario = timing("search", 12.5);
+        scenario.change_pct = Some(3.0);
+        let value = serde_json::to_value(vec![scenario]).unwrap();
+        let parsed: Vec<ScenarioTiming> = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed[0].p50_ms, 12.5);
+        assert_eq!(parsed[0].change_pct, Some(3.0));
+    }
+}
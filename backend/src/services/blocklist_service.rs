@@ -0,0 +1,1025 @@
+//! Instance-level block list of SHA-256 digests and package purls.
+//!
+//! Entries come from admins or from threat-intel feeds (see [`BlocklistFeed`]).
+//! Uploads that match an entry are rejected at the two shared upload
+//! chokepoints (`ArtifactService::preflight_upload` and
+//! `proxy_helpers::insert_artifact`). Artifacts already stored, or written by
+//! paths that bypass those chokepoints, are moved to the `rejected` quarantine
+//! state by [`BlocklistService::sweep`], which runs after every change to the
+//! list and periodically from the scheduler.
+//!
+//! A purl entry names a package as `pkg:type/namespace/name`, optionally with
+//! an exact `@version` or a version range: comparators such as `>=1.0.0` or
+//! `<1.2.0` joined by `,` (all must hold), with alternatives separated by
+//! `||`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::curation_service::CurationService;
+use crate::services::scanner_service::format_to_purl_type;
+
+/// How long the in-memory copy of the list used by upload checks is reused.
+const SNAPSHOT_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Source recorded for entries created one at a time through the API.
+pub const MANUAL_SOURCE: &str = "manual";
+
+/// One block-list entry.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct BlocklistEntry {
+    pub id: Uuid,
+    /// `sha256` or `purl`.
+    pub kind: String,
+    pub sha256: Option<String>,
+    pub purl_type: Option<String>,
+    pub purl_namespace: Option<String>,
+    pub purl_name: Option<String>,
+    /// Blocked versions; absent blocks every version.
+    pub version_range: Option<String>,
+    pub reason: Option<String>,
+    /// Feed the entry was imported from, or `manual`.
+    pub source: String,
+    /// Identifier of the entry in its feed (e.g. an advisory id).
+    pub external_id: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A feed entry or API request: exactly one of `sha256` and `purl`.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct FeedEntry {
+    pub sha256: Option<String>,
+    /// `pkg:type/namespace/name`, optionally `@version`.
+    pub purl: Option<String>,
+    /// Version range for a purl without `@version`, e.g. `>=1.0.0, <1.2.0`.
+    pub versions: Option<String>,
+    pub reason: Option<String>,
+    /// Identifier of the entry in its feed.
+    pub id: Option<String>,
+}
+
+/// Threat-intel feed import document.
+///
+/// Also accepted as plain text: one digest or purl per line, optionally
+/// followed by whitespace and a reason; `#` starts a comment.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BlocklistFeed {
+    /// Name of the feed; entries are upserted under it.
+    pub source: String,
+    /// Remove entries of this source that are not in the feed.
+    #[serde(default)]
+    pub replace: bool,
+    pub entries: Vec<FeedEntry>,
+}
+
+/// Parse the plain-text feed format into entries.
+pub fn parse_text_feed(body: &str) -> Vec<FeedEntry> {
+    body.lines()
+        .map(|line| line.split_once('#').map(|(l, _)| l).unwrap_or(line).trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (token, reason) = match line.split_once(char::is_whitespace) {
+                Some((token, reason)) => (token, Some(reason.trim().to_string())),
+                None => (line, None),
+            };
+            let mut entry = FeedEntry {
+                reason: reason.filter(|r| !r.is_empty()),
+                ..FeedEntry::default()
+            };
+            if token.to_ascii_lowercase().starts_with("pkg:") {
+                entry.purl = Some(token.to_string());
+            } else {
+                entry.sha256 = Some(token.to_string());
+            }
+            entry
+        })
+        .collect()
+}
+
+/// Parsed package URL. Qualifiers and subpath are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageUrl {
+    pub purl_type: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl PackageUrl {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let invalid = || AppError::Validation(format!("Invalid purl: {}", raw));
+        let trimmed = raw.trim();
+        let rest = trimmed
+            .get(..4)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("pkg:"))
+            .map(|_| &trimmed[4..])
+            .ok_or_else(invalid)?;
+        let rest = rest.split('#').next().unwrap_or_default();
+        let rest = rest.split('?').next().unwrap_or_default();
+        let rest = rest.trim_start_matches('/');
+
+        let last_slash = rest.rfind('/').ok_or_else(invalid)?;
+        let (path, version) = match rest.rfind('@') {
+            Some(at) if at > last_slash => (&rest[..at], Some(decode(&rest[at + 1..])?)),
+            _ => (rest, None),
+        };
+
+        let mut segments: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(decode)
+            .collect::<Result<_>>()?;
+        if segments.len() < 2 {
+            return Err(invalid());
+        }
+        let purl_type = segments.remove(0).to_ascii_lowercase();
+        let name = segments.pop().ok_or_else(invalid)?;
+        let namespace = (!segments.is_empty()).then(|| segments.join("/"));
+
+        Ok(Self {
+            purl_type,
+            namespace,
+            name,
+            version: version.filter(|v| !v.is_empty()),
+        })
+    }
+}
+
+fn decode(segment: &str) -> Result<String> {
+    urlencoding::decode(segment)
+        .map(|s| s.into_owned())
+        .map_err(|_| AppError::Validation(format!("Invalid purl segment: {}", segment)))
+}
+
+/// Whether `version` falls in `range` (see the module docs for the syntax).
+pub fn version_in_range(range: &str, version: &str) -> bool {
+    range.split("||").any(|alternative| {
+        let mut comparators = alternative
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .peekable();
+        comparators.peek().is_some()
+            && comparators.all(|c| CurationService::version_matches(c, version))
+    })
+}
+
+/// Reject a range that has an empty alternative.
+fn validate_range(range: &str) -> Result<()> {
+    let valid = range
+        .split("||")
+        .all(|alt| alt.split(',').any(|c| !c.trim().is_empty()));
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid version range: {}",
+            range
+        )));
+    }
+    Ok(())
+}
+
+/// Package name as compared: case-insensitive, and for PyPI with runs of
+/// `-`, `_` and `.` treated alike (PEP 503).
+fn normalize_name(purl_type: &str, name: &str) -> String {
+    let lower = name.to_lowercase();
+    if purl_type == "pypi" {
+        loose_name(&lower)
+    } else {
+        lower
+    }
+}
+
+/// Lower-case with separator runs collapsed to `-`. Matches the SQL
+/// prefilter of the sweep, which therefore never misses a candidate.
+fn loose_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut in_separator = false;
+    for c in name.to_lowercase().chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !in_separator {
+                out.push('-');
+            }
+            in_separator = true;
+        } else {
+            out.push(c);
+            in_separator = false;
+        }
+    }
+    out
+}
+
+/// A validated entry ready to upsert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NewEntry {
+    Sha256 {
+        sha256: String,
+        reason: Option<String>,
+        external_id: Option<String>,
+    },
+    Purl {
+        purl: PackageUrl,
+        version_range: Option<String>,
+        reason: Option<String>,
+        external_id: Option<String>,
+    },
+}
+
+impl NewEntry {
+    pub fn from_feed(entry: &FeedEntry) -> Result<Self> {
+        let reason = entry.reason.clone().filter(|r| !r.trim().is_empty());
+        let external_id = entry.id.clone().filter(|id| !id.trim().is_empty());
+        match (entry.sha256.as_deref(), entry.purl.as_deref()) {
+            (Some(sha256), None) => {
+                let sha256 = sha256.trim().to_ascii_lowercase();
+                if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(AppError::Validation(format!(
+                        "Invalid SHA-256 digest: {}",
+                        sha256
+                    )));
+                }
+                Ok(Self::Sha256 {
+                    sha256,
+                    reason,
+                    external_id,
+                })
+            }
+            (None, Some(purl)) => {
+                let mut purl = PackageUrl::parse(purl)?;
+                let versions = entry
+                    .versions
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty());
+                let version_range = match (purl.version.take(), versions) {
+                    (Some(_), Some(_)) => {
+                        return Err(AppError::Validation(
+                            "Give either a purl @version or versions, not both".to_string(),
+                        ))
+                    }
+                    (Some(exact), None) => Some(exact),
+                    (None, Some(range)) => {
+                        validate_range(range)?;
+                        Some(range.to_string())
+                    }
+                    (None, None) => None,
+                };
+                Ok(Self::Purl {
+                    purl,
+                    version_range,
+                    reason,
+                    external_id,
+                })
+            }
+            _ => Err(AppError::Validation(
+                "Each block-list entry needs exactly one of sha256 or purl".to_string(),
+            )),
+        }
+    }
+}
+
+/// A purl entry as matched against artifacts.
+#[derive(Debug, Clone)]
+struct PurlRule {
+    entry_id: Uuid,
+    namespace: Option<String>,
+    /// Normalized `namespace/name` (just `name` without a namespace).
+    full_key: String,
+    /// Normalized `name`.
+    name_key: String,
+    version_range: Option<String>,
+    reason: Option<String>,
+}
+
+impl PurlRule {
+    fn matches(&self, name_key: &str, path: &str, version: Option<&str>) -> bool {
+        let package = if name_key == self.full_key {
+            true
+        } else if name_key == self.name_key {
+            // Formats that store only the bare name (Maven artifactId) keep
+            // the namespace in the path.
+            match &self.namespace {
+                None => true,
+                Some(ns) => {
+                    let ns = ns.to_lowercase().replace('.', "/");
+                    path.to_lowercase().contains(&format!("{}/", ns))
+                }
+            }
+        } else {
+            false
+        };
+        package
+            && match (&self.version_range, version) {
+                (None, _) => true,
+                (Some(range), Some(version)) => version_in_range(range, version),
+                (Some(_), None) => false,
+            }
+    }
+}
+
+/// The whole list, indexed for matching.
+#[derive(Debug, Default)]
+pub struct BlocklistSnapshot {
+    sha256: HashMap<String, (Uuid, Option<String>)>,
+    /// Rules by purl type, then by normalized full and bare name.
+    purls: HashMap<String, HashMap<String, Vec<PurlRule>>>,
+}
+
+/// What an artifact matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlocklistHit {
+    pub entry_id: Uuid,
+    pub reason: Option<String>,
+}
+
+impl BlocklistSnapshot {
+    fn from_entries(entries: Vec<BlocklistEntry>) -> Self {
+        let mut snapshot = Self::default();
+        for entry in entries {
+            if let Some(sha256) = entry.sha256 {
+                snapshot
+                    .sha256
+                    .insert(sha256.trim().to_ascii_lowercase(), (entry.id, entry.reason));
+                continue;
+            }
+            let (Some(purl_type), Some(name)) = (entry.purl_type, entry.purl_name) else {
+                continue;
+            };
+            let name_key = normalize_name(&purl_type, &name);
+            let full_key = match &entry.purl_namespace {
+                Some(ns) => normalize_name(&purl_type, &format!("{}/{}", ns, name)),
+                None => name_key.clone(),
+            };
+            let rule = PurlRule {
+                entry_id: entry.id,
+                namespace: entry.purl_namespace,
+                full_key: full_key.clone(),
+                name_key: name_key.clone(),
+                version_range: entry.version_range,
+                reason: entry.reason,
+            };
+            let by_name = snapshot.purls.entry(purl_type).or_default();
+            if full_key != name_key {
+                by_name.entry(full_key).or_default().push(rule.clone());
+            }
+            by_name.entry(name_key).or_default().push(rule);
+        }
+        snapshot
+    }
+
+    pub fn has_purls(&self) -> bool {
+        !self.purls.is_empty()
+    }
+
+    pub fn match_sha256(&self, sha256: &str) -> Option<BlocklistHit> {
+        self.sha256
+            .get(&sha256.trim().to_ascii_lowercase())
+            .map(|(entry_id, reason)| BlocklistHit {
+                entry_id: *entry_id,
+                reason: reason.clone(),
+            })
+    }
+
+    /// Every purl entry an artifact of a repository with `purl_type` matches.
+    pub fn match_package(
+        &self,
+        purl_type: &str,
+        name: &str,
+        path: &str,
+        version: Option<&str>,
+    ) -> Vec<BlocklistHit> {
+        let name_key = normalize_name(purl_type, name);
+        let Some(rules) = self
+            .purls
+            .get(purl_type)
+            .and_then(|by_name| by_name.get(&name_key))
+        else {
+            return Vec::new();
+        };
+        let mut seen = HashSet::new();
+        rules
+            .iter()
+            .filter(|rule| rule.matches(&name_key, path, version))
+            .filter(|rule| seen.insert(rule.entry_id))
+            .map(|rule| BlocklistHit {
+                entry_id: rule.entry_id,
+                reason: rule.reason.clone(),
+            })
+            .collect()
+    }
+
+    /// Loose names of every purl entry, for the sweep's SQL prefilter.
+    fn candidate_names(&self) -> Vec<String> {
+        let mut names: HashSet<String> = HashSet::new();
+        for by_name in self.purls.values() {
+            names.extend(by_name.keys().map(|key| loose_name(key)));
+        }
+        names.into_iter().collect()
+    }
+}
+
+fn snapshot_cache() -> &'static RwLock<Option<(Instant, Arc<BlocklistSnapshot>)>> {
+    static CACHE: OnceLock<RwLock<Option<(Instant, Arc<BlocklistSnapshot>)>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// Drop the cached list after it changes. Other replicas pick the change up
+/// within [`SNAPSHOT_TTL`].
+pub fn invalidate_cache() {
+    match snapshot_cache().write() {
+        Ok(mut cache) => *cache = None,
+        Err(poisoned) => *poisoned.into_inner() = None,
+    }
+}
+
+async fn load_snapshot(db: &PgPool) -> Result<BlocklistSnapshot> {
+    let entries: Vec<BlocklistEntry> = sqlx::query_as("SELECT * FROM blocklist_entries")
+        .fetch_all(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(BlocklistSnapshot::from_entries(entries))
+}
+
+async fn cached_snapshot(db: &PgPool) -> Result<Arc<BlocklistSnapshot>> {
+    if let Ok(cache) = snapshot_cache().read() {
+        if let Some((loaded, snapshot)) = cache.as_ref() {
+            if loaded.elapsed() < SNAPSHOT_TTL {
+                return Ok(snapshot.clone());
+            }
+        }
+    }
+    let snapshot = Arc::new(load_snapshot(db).await?);
+    let mut cache = match snapshot_cache().write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *cache = Some((Instant::now(), snapshot.clone()));
+    Ok(snapshot)
+}
+
+fn blocked_error(hit: &BlocklistHit) -> AppError {
+    AppError::Authorization(match &hit.reason {
+        Some(reason) => format!("Upload rejected by the instance block list: {}", reason),
+        None => "Upload rejected by the instance block list".to_string(),
+    })
+}
+
+/// Reject an upload whose digest or package coordinates are on the list.
+pub async fn check_upload(
+    db: &PgPool,
+    repository_id: Uuid,
+    name: &str,
+    version: Option<&str>,
+    path: &str,
+    checksum_sha256: &str,
+) -> Result<()> {
+    let snapshot = cached_snapshot(db).await?;
+    if let Some(hit) = snapshot.match_sha256(checksum_sha256) {
+        return Err(blocked_error(&hit));
+    }
+    if !snapshot.has_purls() {
+        return Ok(());
+    }
+
+    let format: Option<String> =
+        sqlx::query_scalar("SELECT format::text FROM repositories WHERE id = $1")
+            .bind(repository_id)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    let Some(format) = format else {
+        return Ok(());
+    };
+    let version = version.filter(|v| !v.is_empty());
+    match snapshot
+        .match_package(format_to_purl_type(&format), name, path, version)
+        .first()
+    {
+        Some(hit) => Err(blocked_error(hit)),
+        None => Ok(()),
+    }
+}
+
+/// Outcome of a feed import.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ImportSummary {
+    /// Entries created or updated.
+    pub upserted: u64,
+    /// Entries of the source removed in replace mode.
+    pub removed: u64,
+    /// Stored artifacts newly rejected.
+    pub rejected_artifacts: u64,
+}
+
+pub struct BlocklistService {
+    db: PgPool,
+}
+
+impl BlocklistService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list(
+        &self,
+        source: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<BlocklistEntry>, i64)> {
+        let entries: Vec<BlocklistEntry> = sqlx::query_as(
+            r#"
+            SELECT * FROM blocklist_entries
+            WHERE ($1::TEXT IS NULL OR source = $1)
+            ORDER BY created_at DESC, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(source)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM blocklist_entries WHERE ($1::TEXT IS NULL OR source = $1)",
+        )
+        .bind(source)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((entries, total))
+    }
+
+    async fn upsert<'e, E>(
+        executor: E,
+        entry: &NewEntry,
+        source: &str,
+        created_by: Option<Uuid>,
+    ) -> Result<BlocklistEntry>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
+        let query = match entry {
+            NewEntry::Sha256 {
+                sha256,
+                reason,
+                external_id,
+            } => sqlx::query_as(
+                r#"
+                INSERT INTO blocklist_entries (kind, sha256, reason, source, external_id, created_by)
+                VALUES ('sha256', $1, $2, $3, $4, $5)
+                ON CONFLICT (sha256) WHERE kind = 'sha256' DO UPDATE
+                SET reason = EXCLUDED.reason, source = EXCLUDED.source,
+                    external_id = EXCLUDED.external_id, updated_at = NOW()
+                RETURNING *
+                "#,
+            )
+            .bind(sha256)
+            .bind(reason)
+            .bind(source)
+            .bind(external_id)
+            .bind(created_by),
+            NewEntry::Purl {
+                purl,
+                version_range,
+                reason,
+                external_id,
+            } => sqlx::query_as(
+                r#"
+                INSERT INTO blocklist_entries
+                    (kind, purl_type, purl_namespace, purl_name, version_range,
+                     reason, source, external_id, created_by)
+                VALUES ('purl', $1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (purl_type, COALESCE(purl_namespace, ''), purl_name,
+                             COALESCE(version_range, ''))
+                    WHERE kind = 'purl'
+                DO UPDATE
+                SET reason = EXCLUDED.reason, source = EXCLUDED.source,
+                    external_id = EXCLUDED.external_id, updated_at = NOW()
+                RETURNING *
+                "#,
+            )
+            .bind(&purl.purl_type)
+            .bind(&purl.namespace)
+            .bind(&purl.name)
+            .bind(version_range)
+            .bind(reason)
+            .bind(source)
+            .bind(external_id)
+            .bind(created_by),
+        };
+        query
+            .fetch_one(executor)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Add (or update) one entry and reject stored artifacts that match it.
+    pub async fn create(
+        &self,
+        entry: &NewEntry,
+        created_by: Option<Uuid>,
+    ) -> Result<(BlocklistEntry, u64)> {
+        let created = Self::upsert(&self.db, entry, MANUAL_SOURCE, created_by).await?;
+        invalidate_cache();
+        let rejected = self.sweep().await?;
+        Ok((created, rejected))
+    }
+
+    /// Import a feed: upsert its entries under `feed.source`, optionally
+    /// remove the source's entries missing from the feed, then sweep.
+    pub async fn import(
+        &self,
+        feed: &BlocklistFeed,
+        created_by: Option<Uuid>,
+    ) -> Result<ImportSummary> {
+        let source = feed.source.trim();
+        if source.is_empty() || source.len() > 255 {
+            return Err(AppError::Validation(
+                "Feed source must be 1-255 characters".to_string(),
+            ));
+        }
+        let entries = feed
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                NewEntry::from_feed(e)
+                    .map_err(|err| AppError::Validation(format!("Feed entry {}: {}", i + 1, err)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let mut kept = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            kept.push(Self::upsert(&mut *tx, entry, source, created_by).await?.id);
+        }
+        let mut summary = ImportSummary {
+            upserted: kept.len() as u64,
+            ..ImportSummary::default()
+        };
+        if feed.replace {
+            let stale: Vec<Uuid> = sqlx::query_scalar(
+                "SELECT id FROM blocklist_entries WHERE source = $1 AND id <> ALL($2)",
+            )
+            .bind(source)
+            .bind(&kept)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            summary.removed = Self::remove_in(&mut tx, &stale).await?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        invalidate_cache();
+
+        summary.rejected_artifacts = self.sweep().await?;
+        Ok(summary)
+    }
+
+    /// Remove an entry, restoring the quarantine status of artifacts that
+    /// no other entry still matches.
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if Self::remove_in(&mut tx, &[id]).await? == 0 {
+            return Err(AppError::NotFound("Block-list entry not found".to_string()));
+        }
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        invalidate_cache();
+        Ok(())
+    }
+
+    async fn remove_in(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        ids: &[Uuid],
+    ) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        sqlx::query(
+            r#"
+            UPDATE artifacts a SET quarantine_status = m.previous_status
+            FROM blocklist_matches m
+            WHERE m.entry_id = ANY($1) AND a.id = m.artifact_id
+              AND a.quarantine_status = 'rejected'
+              AND NOT EXISTS (
+                  SELECT 1 FROM blocklist_matches o
+                  WHERE o.artifact_id = m.artifact_id AND o.entry_id <> ALL($1)
+              )
+            "#,
+        )
+        .bind(ids)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let result = sqlx::query("DELETE FROM blocklist_entries WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Reject every stored artifact that matches an entry it has not been
+    /// matched against before. Returns the number of artifacts rejected.
+    pub async fn sweep(&self) -> Result<u64> {
+        let mut pairs: Vec<(Uuid, Uuid)> = sqlx::query_as(
+            r#"
+            SELECT e.id, a.id
+            FROM blocklist_entries e
+            JOIN artifacts a ON a.checksum_sha256 = e.sha256
+            WHERE e.kind = 'sha256' AND a.is_deleted = false
+              AND NOT EXISTS (
+                  SELECT 1 FROM blocklist_matches m
+                  WHERE m.entry_id = e.id AND m.artifact_id = a.id
+              )
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let snapshot = load_snapshot(&self.db).await?;
+        if snapshot.has_purls() {
+            #[derive(sqlx::FromRow)]
+            struct Candidate {
+                id: Uuid,
+                name: String,
+                path: String,
+                version: Option<String>,
+                format: String,
+            }
+            let candidates: Vec<Candidate> = sqlx::query_as(
+                r#"
+                SELECT a.id, a.name, a.path, a.version, r.format::text AS format
+                FROM artifacts a
+                JOIN repositories r ON r.id = a.repository_id
+                WHERE a.is_deleted = false
+                  AND regexp_replace(lower(a.name), '[-_.]+', '-', 'g') = ANY($1)
+                "#,
+            )
+            .bind(snapshot.candidate_names())
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+            for c in candidates {
+                let purl_type = format_to_purl_type(&c.format);
+                for hit in snapshot.match_package(purl_type, &c.name, &c.path, c.version.as_deref())
+                {
+                    pairs.push((hit.entry_id, c.id));
+                }
+            }
+        }
+
+        if pairs.is_empty() {
+            return Ok(0);
+        }
+        let (entry_ids, artifact_ids): (Vec<Uuid>, Vec<Uuid>) = pairs.into_iter().unzip();
+
+        // The first match of an artifact records its status before any
+        // entry rejected it; later matches copy that.
+        let result = sqlx::query(
+            r#"
+            WITH pairs AS (
+                SELECT * FROM UNNEST($1::UUID[], $2::UUID[]) AS p(entry_id, artifact_id)
+            ),
+            recorded AS (
+                INSERT INTO blocklist_matches (entry_id, artifact_id, previous_status)
+                SELECT p.entry_id, p.artifact_id,
+                       CASE WHEN EXISTS (SELECT 1 FROM blocklist_matches m
+                                         WHERE m.artifact_id = p.artifact_id)
+                            THEN (SELECT m.previous_status FROM blocklist_matches m
+                                  WHERE m.artifact_id = p.artifact_id LIMIT 1)
+                            ELSE a.quarantine_status
+                       END
+                FROM pairs p
+                JOIN artifacts a ON a.id = p.artifact_id
+                ON CONFLICT (entry_id, artifact_id) DO NOTHING
+                RETURNING artifact_id
+            )
+            UPDATE artifacts
+            SET quarantine_status = 'rejected', quarantine_until = NULL
+            WHERE id IN (SELECT artifact_id FROM recorded)
+              AND quarantine_status IS DISTINCT FROM 'rejected'
+            "#,
+        )
+        .bind(&entry_ids)
+        .bind(&artifact_ids)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rejected = result.rows_affected();
+        if rejected > 0 {
+            tracing::warn!(
+                "Block list rejected {} stored artifact(s) matching its entries",
+                rejected
+            );
+        }
+        Ok(rejected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: &str) -> BlocklistEntry {
+        BlocklistEntry {
+            id: Uuid::new_v4(),
+            kind: kind.to_string(),
+            sha256: None,
+            purl_type: None,
+            purl_namespace: None,
+            purl_name: None,
+            version_range: None,
+            reason: Some("malware".to_string()),
+            source: MANUAL_SOURCE.to_string(),
+            external_id: None,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn purl_entry(purl: &str, range: Option<&str>) -> BlocklistEntry {
+        let parsed = PackageUrl::parse(purl).unwrap();
+        BlocklistEntry {
+            purl_type: Some(parsed.purl_type),
+            purl_namespace: parsed.namespace,
+            purl_name: Some(parsed.name),
+            version_range: range.map(String::from),
+            ..entry("purl")
+        }
+    }
+
+    #[test]
+    fn test_parse_purl() {
+        let p = PackageUrl::parse("pkg:npm/%40evil/left-pad@1.3.0?arch=x#sub").unwrap();
+        assert_eq!(p.purl_type, "npm");
+        assert_eq!(p.namespace.as_deref(), Some("@evil"));
+        assert_eq!(p.name, "left-pad");
+        assert_eq!(p.version.as_deref(), Some("1.3.0"));
+
+        let p = PackageUrl::parse("pkg:npm/@evil/left-pad").unwrap();
+        assert_eq!(p.namespace.as_deref(), Some("@evil"));
+        assert_eq!(p.version, None);
+
+        let p = PackageUrl::parse("PKG:Maven/com.example/lib@2.0").unwrap();
+        assert_eq!(p.purl_type, "maven");
+        assert_eq!(p.namespace.as_deref(), Some("com.example"));
+
+        assert!(PackageUrl::parse("npm/left-pad").is_err());
+        assert!(PackageUrl::parse("pkg:npm").is_err());
+    }
+
+    #[test]
+    fn test_version_in_range() {
+        assert!(version_in_range("1.2.3", "1.2.3"));
+        assert!(!version_in_range("1.2.3", "1.2.4"));
+        assert!(version_in_range(">=1.0.0, <1.2.0", "1.1.9"));
+        assert!(!version_in_range(">=1.0.0, <1.2.0", "1.2.0"));
+        assert!(version_in_range("<1.0 || >=2.0", "2.5"));
+        assert!(!version_in_range("<1.0 || >=2.0", "1.5"));
+        assert!(validate_range(">=1.0 ||").is_err());
+    }
+
+    #[test]
+    fn test_new_entry_validation() {
+        let ok = NewEntry::from_feed(&FeedEntry {
+            sha256: Some("AB".repeat(32)),
+            ..FeedEntry::default()
+        })
+        .unwrap();
+        assert!(matches!(ok, NewEntry::Sha256 { ref sha256, .. } if sha256 == &"ab".repeat(32)));
+
+        assert!(NewEntry::from_feed(&FeedEntry {
+            sha256: Some("xyz".to_string()),
+            ..FeedEntry::default()
+        })
+        .is_err());
+        assert!(NewEntry::from_feed(&FeedEntry::default()).is_err());
+        assert!(NewEntry::from_feed(&FeedEntry {
+            purl: Some("pkg:npm/evil@1.0.0".to_string()),
+            versions: Some(">=1.0".to_string()),
+            ..FeedEntry::default()
+        })
+        .is_err());
+
+        let exact = NewEntry::from_feed(&FeedEntry {
+            purl: Some("pkg:npm/evil@1.0.0".to_string()),
+            ..FeedEntry::default()
+        })
+        .unwrap();
+        assert!(
+            matches!(exact, NewEntry::Purl { ref version_range, .. } if version_range.as_deref() == Some("1.0.0"))
+        );
+    }
+
+    #[test]
+    fn test_parse_text_feed() {
+        let entries = parse_text_feed(
+            "# threat feed\n\npkg:npm/evil@1.0.0 typosquat\n  abcdef  # trailing comment\n",
+        );
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].purl.as_deref(), Some("pkg:npm/evil@1.0.0"));
+        assert_eq!(entries[0].reason.as_deref(), Some("typosquat"));
+        assert_eq!(entries[1].sha256.as_deref(), Some("abcdef"));
+        assert_eq!(entries[1].reason, None);
+    }
+
+    #[test]
+    fn test_snapshot_sha256_match_is_case_insensitive() {
+        let digest = "ab".repeat(32);
+        let snapshot = BlocklistSnapshot::from_entries(vec![BlocklistEntry {
+            sha256: Some(digest.clone()),
+            ..entry("sha256")
+        }]);
+        assert!(snapshot.match_sha256(&digest.to_uppercase()).is_some());
+        assert!(snapshot.match_sha256(&"cd".repeat(32)).is_none());
+        assert!(!snapshot.has_purls());
+    }
+
+    #[test]
+    fn test_snapshot_npm_scoped_and_range() {
+        let snapshot = BlocklistSnapshot::from_entries(vec![purl_entry(
+            "pkg:npm/%40evil/pad",
+            Some(">=1.0.0, <2.0.0"),
+        )]);
+        assert_eq!(
+            snapshot
+                .match_package(
+                    "npm",
+                    "@evil/pad",
+                    "@evil/pad/-/pad-1.5.0.tgz",
+                    Some("1.5.0")
+                )
+                .len(),
+            1
+        );
+        assert!(snapshot
+            .match_package("npm", "@evil/pad", "x", Some("2.0.0"))
+            .is_empty());
+        assert!(snapshot
+            .match_package("npm", "@evil/pad", "x", None)
+            .is_empty());
+        // Same name, other scope or other ecosystem.
+        assert!(snapshot
+            .match_package("npm", "pad", "pad/-/pad-1.5.0.tgz", Some("1.5.0"))
+            .is_empty());
+        assert!(snapshot
+            .match_package("pypi", "@evil/pad", "x", Some("1.5.0"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_maven_namespace_from_path() {
+        let snapshot =
+            BlocklistSnapshot::from_entries(vec![purl_entry("pkg:maven/com.evil/lib", None)]);
+        assert_eq!(
+            snapshot
+                .match_package("maven", "lib", "com/evil/lib/1.0/lib-1.0.jar", Some("1.0"))
+                .len(),
+            1
+        );
+        assert!(snapshot
+            .match_package("maven", "lib", "org/good/lib/1.0/lib-1.0.jar", Some("1.0"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_pypi_name_normalization() {
+        let snapshot = BlocklistSnapshot::from_entries(vec![purl_entry("pkg:pypi/evil.pkg", None)]);
+        assert_eq!(
+            snapshot
+                .match_package("pypi", "Evil_Pkg", "evil_pkg-0.1.tar.gz", Some("0.1"))
+                .len(),
+            1
+        );
+        assert!(snapshot.candidate_names().contains(&"evil-pkg".to_string()));
+    }
+}
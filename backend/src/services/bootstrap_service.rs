@@ -0,0 +1,542 @@
+//! First-run setup wizard.
+//!
+//! With `SETUP_WIZARD=true`, a server that starts against a database with no
+//! admin does not provision the built-in `admin` user with a generated
+//! password. It issues a one-time bootstrap token instead, prints it in the
+//! startup log, and keeps the API locked (see
+//! [`crate::api::middleware::setup`]) until `POST /api/v1/setup/bootstrap` is
+//! called with that token. The call creates the first admin and records the
+//! initial instance settings in `system_settings`, replacing the
+//! env-var-and-SQL steps of a manual setup:
+//! * `server.base_url` becomes the external base URL when `AK_EXTERNAL_URL` is
+//!   unset (see [`crate::api::extractors::set_stored_external_url`]);
+//! * `storage.default_backend` is the backend new repositories use when the
+//!   create request names none;
+//! * `license.key` holds an imported license. It is stored as a sensitive
+//!   setting and only its fingerprint is ever served back, in the admin
+//!   settings (see [`stored_license`]).
+//!
+//! Only the token's SHA-256 is stored. Tokens expire after
+//! [`BOOTSTRAP_TOKEN_TTL_HOURS`]; restarting the server while setup is still
+//! pending issues a fresh one.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::auth_service::AuthService;
+use crate::services::password_policy::{validate_password, PasswordPolicyConfig};
+
+/// Lifetime of an issued bootstrap token.
+pub const BOOTSTRAP_TOKEN_TTL_HOURS: i64 = 24;
+
+/// Prefix that makes bootstrap tokens recognizable in logs and secret scans.
+pub const BOOTSTRAP_TOKEN_PREFIX: &str = "akbt_";
+
+/// Storage backends the wizard accepts as the instance default.
+pub const STORAGE_BACKENDS: &[&str] = &["filesystem", "s3", "azure", "gcs"];
+
+/// `system_settings` key holding the wizard's external base URL.
+pub const BASE_URL_SETTING: &str = "server.base_url";
+
+/// `system_settings` key holding the wizard's default storage backend.
+pub const DEFAULT_BACKEND_SETTING: &str = "storage.default_backend";
+
+/// `system_settings` key holding the imported license.
+pub const LICENSE_SETTING: &str = "license.key";
+
+/// Upper bound on an imported license blob.
+const MAX_LICENSE_BYTES: usize = 64 * 1024;
+
+/// Generate a new bootstrap token (prefix + 64 hex chars of OS randomness).
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    format!("{}{}", BOOTSTRAP_TOKEN_PREFIX, hex::encode(bytes))
+}
+
+/// SHA-256 of a token, as stored in `bootstrap_tokens.token_hash`.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
+/// Issue a fresh bootstrap token, invalidating any outstanding one. Runs on
+/// the caller's connection so it can share the provisioning transaction.
+pub async fn issue_token(conn: &mut PgConnection) -> Result<String> {
+    sqlx::query("DELETE FROM bootstrap_tokens WHERE consumed_at IS NULL")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let token = generate_token();
+    sqlx::query("INSERT INTO bootstrap_tokens (token_hash, expires_at) VALUES ($1, $2)")
+        .bind(hash_token(&token))
+        .bind(Utc::now() + Duration::hours(BOOTSTRAP_TOKEN_TTL_HOURS))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(token)
+}
+
+/// A string-valued `system_settings` row, if present.
+async fn stored_string_setting(db: &PgPool, key: &str) -> Result<Option<String>> {
+    let value: Option<serde_json::Value> =
+        sqlx::query_scalar("SELECT value FROM system_settings WHERE key = $1")
+            .bind(key)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(value.and_then(|v| v.as_str().map(str::to_string)))
+}
+
+/// The external base URL recorded by the wizard, if any.
+pub async fn stored_base_url(db: &PgPool) -> Result<Option<String>> {
+    stored_string_setting(db, BASE_URL_SETTING).await
+}
+
+/// The default storage backend recorded by the wizard, if any.
+pub async fn stored_default_backend(db: &PgPool) -> Result<Option<String>> {
+    stored_string_setting(db, DEFAULT_BACKEND_SETTING).await
+}
+
+/// An imported license, identified without revealing its contents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct InstalledLicense {
+    /// SHA-256 of the license contents.
+    pub sha256: String,
+    pub size_bytes: usize,
+    pub installed_at: DateTime<Utc>,
+}
+
+impl InstalledLicense {
+    fn of(license: &str, installed_at: DateTime<Utc>) -> Self {
+        Self {
+            sha256: format!("{:x}", Sha256::digest(license.as_bytes())),
+            size_bytes: license.len(),
+            installed_at,
+        }
+    }
+}
+
+/// The license imported by the wizard, if any.
+pub async fn stored_license(db: &PgPool) -> Result<Option<InstalledLicense>> {
+    let row: Option<(serde_json::Value, DateTime<Utc>)> =
+        sqlx::query_as("SELECT value, updated_at FROM system_settings WHERE key = $1")
+            .bind(LICENSE_SETTING)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(row.and_then(|(value, installed_at)| {
+        value
+            .as_str()
+            .map(|license| InstalledLicense::of(license, installed_at))
+    }))
+}
+
+/// Whether a bootstrap token is outstanding, i.e. the wizard has not been
+/// completed yet.
+pub async fn is_pending(db: &PgPool) -> Result<bool> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM bootstrap_tokens WHERE consumed_at IS NULL)")
+        .fetch_one(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// The first admin account created by the wizard. Deliberately not `Debug`:
+/// it carries the plaintext password.
+#[derive(Clone, Deserialize, ToSchema)]
+pub struct BootstrapAdmin {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub display_name: Option<String>,
+}
+
+/// Body of `POST /api/v1/setup/bootstrap`.
+#[derive(Clone, Deserialize, ToSchema)]
+pub struct BootstrapRequest {
+    /// The bootstrap token printed in the server log.
+    pub token: String,
+    pub admin: BootstrapAdmin,
+    /// Externally reachable base URL of this instance.
+    pub base_url: Option<String>,
+    /// Default storage backend for new repositories. Must be one the server
+    /// is configured for.
+    pub storage_backend: Option<String>,
+    /// License key or license file contents.
+    pub license_key: Option<String>,
+}
+
+/// Result of a completed bootstrap.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BootstrapResponse {
+    pub user_id: Uuid,
+    pub username: String,
+    /// `system_settings` keys written by the wizard.
+    pub settings_applied: Vec<String>,
+}
+
+/// One `system_settings` row the wizard writes.
+#[derive(Debug, Clone, PartialEq)]
+struct SettingWrite {
+    key: &'static str,
+    value: serde_json::Value,
+    description: &'static str,
+    is_sensitive: bool,
+}
+
+fn validate_username(username: &str) -> Result<()> {
+    let valid = !username.is_empty()
+        && username.len() <= 64
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !valid {
+        return Err(AppError::Validation(
+            "Username must be 1-64 characters of letters, digits, '_', '-' or '.'".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Normalize a base URL: http(s) only, no credentials, query or fragment, no
+/// trailing `/`.
+fn normalize_base_url(raw: &str) -> Result<String> {
+    let parsed = url::Url::parse(raw.trim())
+        .map_err(|e| AppError::Validation(format!("Invalid base_url: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(AppError::Validation(
+            "base_url must be an http or https URL with a host".to_string(),
+        ));
+    }
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return Err(AppError::Validation(
+            "base_url must not contain credentials".to_string(),
+        ));
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        return Err(AppError::Validation(
+            "base_url must not contain a query or fragment".to_string(),
+        ));
+    }
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
+
+impl BootstrapRequest {
+    /// Validate the request and derive the settings it writes.
+    /// `backend_available` reports whether the server has a storage backend
+    /// configured, so the wizard cannot default repositories to one it lacks.
+    fn settings(
+        &self,
+        policy: &PasswordPolicyConfig,
+        backend_available: impl Fn(&str) -> bool,
+    ) -> Result<Vec<SettingWrite>> {
+        validate_username(self.admin.username.trim())?;
+        if !self.admin.email.contains('@') {
+            return Err(AppError::Validation(
+                "A valid email is required".to_string(),
+            ));
+        }
+        validate_password(&self.admin.password, policy)
+            .map_err(|violations| AppError::Validation(violations.join("; ")))?;
+
+        let mut settings = Vec::new();
+        if let Some(base_url) = self.base_url.as_deref().filter(|u| !u.trim().is_empty()) {
+            settings.push(SettingWrite {
+                key: BASE_URL_SETTING,
+                value: serde_json::json!(normalize_base_url(base_url)?),
+                description: "Externally reachable base URL of this instance",
+                is_sensitive: false,
+            });
+        }
+        if let Some(backend) = self.storage_backend.as_deref() {
+            let backend = backend.trim().to_ascii_lowercase();
+            if !STORAGE_BACKENDS.contains(&backend.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "storage_backend must be one of: {}",
+                    STORAGE_BACKENDS.join(", ")
+                )));
+            }
+            if !backend_available(&backend) {
+                return Err(AppError::Validation(format!(
+                    "Storage backend '{}' is not configured on this server",
+                    backend
+                )));
+            }
+            settings.push(SettingWrite {
+                key: DEFAULT_BACKEND_SETTING,
+                value: serde_json::json!(backend),
+                description: "Default storage backend for new repositories",
+                is_sensitive: false,
+            });
+        }
+        if let Some(license) = self.license_key.as_deref().map(str::trim) {
+            if license.is_empty() || license.len() > MAX_LICENSE_BYTES {
+                return Err(AppError::Validation(format!(
+                    "license_key must be 1-{} bytes",
+                    MAX_LICENSE_BYTES
+                )));
+            }
+            settings.push(SettingWrite {
+                key: LICENSE_SETTING,
+                value: serde_json::json!(license),
+                description: "Imported license",
+                is_sensitive: true,
+            });
+        }
+        Ok(settings)
+    }
+}
+
+/// Complete the wizard: consume the token, create the first admin and write
+/// the initial settings, all in one transaction.
+pub async fn complete(
+    db: &PgPool,
+    request: &BootstrapRequest,
+    policy: &PasswordPolicyConfig,
+    backend_available: impl Fn(&str) -> bool,
+) -> Result<BootstrapResponse> {
+    let settings = request.settings(policy, backend_available)?;
+    let password_hash = AuthService::hash_password(&request.admin.password).await?;
+    let username = request.admin.username.trim();
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    // Same lock as startup admin provisioning, so a replica booting mid-setup
+    // cannot reissue the token or provision an admin concurrently.
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('admin_password_init'))")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let token_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM bootstrap_tokens
+        WHERE token_hash = $1 AND consumed_at IS NULL AND expires_at > NOW()
+        FOR UPDATE
+        "#,
+    )
+    .bind(hash_token(&request.token))
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let token_id = token_id.ok_or_else(|| {
+        AppError::Authentication(
+            "Invalid or expired bootstrap token. Restart the server to issue a new one."
+                .to_string(),
+        )
+    })?;
+
+    let admin_exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE is_admin = true)")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    if admin_exists {
+        return Err(AppError::Conflict(
+            "Setup has already been completed".to_string(),
+        ));
+    }
+
+    let user_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO users
+            (username, email, display_name, password_hash, is_admin, must_change_password, auth_provider)
+        VALUES ($1, $2, $3, $4, true, false, 'local')
+        RETURNING id
+        "#,
+    )
+    .bind(username)
+    .bind(request.admin.email.trim())
+    .bind(request.admin.display_name.as_deref())
+    .bind(&password_hash)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::Conflict("Username or email already exists".to_string())
+        }
+        _ => AppError::Database(e.to_string()),
+    })?;
+
+    for setting in &settings {
+        sqlx::query(
+            r#"
+            INSERT INTO system_settings (key, value, description, is_sensitive, updated_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (key) DO UPDATE
+            SET value = EXCLUDED.value,
+                is_sensitive = EXCLUDED.is_sensitive,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(setting.key)
+        .bind(&setting.value)
+        .bind(setting.description)
+        .bind(setting.is_sensitive)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    }
+
+    sqlx::query("UPDATE bootstrap_tokens SET consumed_at = NOW(), consumed_by = $2 WHERE id = $1")
+        .bind(token_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    Ok(BootstrapResponse {
+        user_id,
+        username: username.to_string(),
+        settings_applied: settings.iter().map(|s| s.key.to_string()).collect(),
+    })
+}
+
+/// Log the bootstrap banner with the one-time token.
+pub fn log_bootstrap_banner(token: &str) {
+    tracing::info!(
+        "\n\
+        ===========================================================\n\
+        \n\
+          First-run setup wizard is enabled (SETUP_WIZARD=true).\n\
+        \n\
+          Bootstrap token:  {}\n\
+        \n\
+          The API is LOCKED until setup is completed. Open the web UI\n\
+          or call POST /api/v1/setup/bootstrap with this token to\n\
+          create the first admin and set the base URL, default storage\n\
+          backend and license. The token is single-use and expires in\n\
+          {} hours; restart the server to issue a new one.\n\
+        \n\
+        ===========================================================",
+        token,
+        BOOTSTRAP_TOKEN_TTL_HOURS,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> BootstrapRequest {
+        BootstrapRequest {
+            token: "akbt_x".to_string(),
+            admin: BootstrapAdmin {
+                username: "root-admin".to_string(),
+                email: "ops@example.com".to_string(),
+                password: "correct horse battery staple".to_string(),
+                display_name: None,
+            },
+            base_url: None,
+            storage_backend: None,
+            license_key: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_token_format() {
+        let token = generate_token();
+        assert!(token.starts_with(BOOTSTRAP_TOKEN_PREFIX));
+        assert_eq!(token.len(), BOOTSTRAP_TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, generate_token());
+    }
+
+    #[test]
+    fn test_hash_token_trims_and_is_stable() {
+        assert_eq!(hash_token("akbt_abc"), hash_token(" akbt_abc\n"));
+        assert_eq!(hash_token("akbt_abc").len(), 64);
+        assert_ne!(hash_token("akbt_abc"), hash_token("akbt_abd"));
+    }
+
+    #[test]
+    fn test_minimal_request_writes_no_settings() {
+        let settings = request()
+            .settings(&PasswordPolicyConfig::default(), |_| true)
+            .unwrap();
+        assert!(settings.is_empty());
+    }
+
+    #[test]
+    fn test_full_request_settings() {
+        let mut req = request();
+        req.base_url = Some("https://artifacts.example.com/".to_string());
+        req.storage_backend = Some("S3".to_string());
+        req.license_key = Some("  LICENSE-123  ".to_string());
+        let settings = req
+            .settings(&PasswordPolicyConfig::default(), |_| true)
+            .unwrap();
+        let keys: Vec<&str> = settings.iter().map(|s| s.key).collect();
+        assert_eq!(
+            keys,
+            vec![BASE_URL_SETTING, DEFAULT_BACKEND_SETTING, LICENSE_SETTING]
+        );
+        assert_eq!(settings[0].value, "https://artifacts.example.com");
+        assert_eq!(settings[1].value, "s3");
+        assert_eq!(settings[2].value, "LICENSE-123");
+        assert!(settings[2].is_sensitive);
+    }
+
+    #[test]
+    fn test_installed_license_does_not_reveal_contents() {
+        let license = InstalledLicense::of("LICENSE-123", Utc::now());
+        assert_eq!(license.size_bytes, 11);
+        assert_eq!(license.sha256.len(), 64);
+        assert!(!serde_json::to_string(&license)
+            .unwrap()
+            .contains("LICENSE-123"));
+    }
+
+    #[test]
+    fn test_request_validation_errors() {
+        let policy = PasswordPolicyConfig::default();
+
+        let mut req = request();
+        req.admin.username = "bad name".to_string();
+        assert!(req.settings(&policy, |_| true).is_err());
+
+        let mut req = request();
+        req.admin.email = "nobody".to_string();
+        assert!(req.settings(&policy, |_| true).is_err());
+
+        let mut req = request();
+        req.admin.password = "short".to_string();
+        assert!(req.settings(&policy, |_| true).is_err());
+
+        let mut req = request();
+        req.storage_backend = Some("tape".to_string());
+        assert!(req.settings(&policy, |_| true).is_err());
+
+        let mut req = request();
+        req.storage_backend = Some("gcs".to_string());
+        assert!(req.settings(&policy, |b| b == "filesystem").is_err());
+
+        let mut req = request();
+        req.license_key = Some("   ".to_string());
+        assert!(req.settings(&policy, |_| true).is_err());
+    }
+
+    #[test]
+    fn test_normalize_base_url() {
+        assert_eq!(
+            normalize_base_url("http://localhost:8080/ak/").unwrap(),
+            "http://localhost:8080/ak"
+        );
+        assert!(normalize_base_url("ftp://example.com").is_err());
+        assert!(normalize_base_url("https://example.com/?x=1").is_err());
+        assert!(normalize_base_url("https://user:pw@example.com").is_err());
+        assert!(normalize_base_url("not a url").is_err());
+    }
+}
@@ -0,0 +1,741 @@
+//! Edge cache integrity verification and self-healing.
+//!
+//! Edge nodes keep content on whatever disks the branch office has, so bit
+//! rot and half-written objects are expected. A periodic job re-hashes
+//! stored objects, oldest-verified first in bounded batches, and compares
+//! them with their recorded SHA-256:
+//!
+//! - **Artifacts** (content replicated from the primary or uploaded locally):
+//!   a corrupted or missing blob is deleted and every live artifact row that
+//!   points at it is soft-deleted, so the node stops serving bad bytes.
+//! - **Proxy-cache objects**: the cache entry is invalidated; the next request
+//!   re-fetches it from upstream.
+//!
+//! Each run is reported to the primary over the edge channel
+//! (`POST /api/v1/peers/join/:id/integrity`, see
+//! [`crate::services::edge_diagnostics_service`]). The primary stores the
+//! report, counts corruptions in `ak_edge_cache_corruptions_total`, and
+//! re-queues a push for every purged artifact the node is subscribed to
+//! (pinned by a push or mirror subscription), which restores it. Purged
+//! artifacts with no pinning subscription are logged at error level and
+//! cannot be restored automatically.
+//!
+//! Configuration (environment):
+//!
+//! | Variable | Default | Meaning |
+//! |---|---|---|
+//! | `AK_CACHE_INTEGRITY_ENABLED` | on for edge nodes | Run the verifier; defaults to on when `AK_EDGE_PRIMARY_URL` is set |
+//! | `AK_CACHE_INTEGRITY_INTERVAL_SECS` | 3600 | Interval between runs (min 60) |
+//! | `AK_CACHE_INTEGRITY_BATCH_SIZE` | 200 | Objects of each kind verified per run (1-1000) |
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tokio::time::{interval, MissedTickBehavior};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::cluster_lock::{ClusterLock, PgAdvisoryLock};
+use crate::services::edge_diagnostics_service::ForwarderConfig;
+use crate::services::metrics_service::{record_cache_integrity_run, record_edge_cache_corruption};
+use crate::services::proxy_service::ProxyService;
+use crate::storage::{StorageLocation, StorageRegistry};
+
+/// Advisory-lock class serializing verifier runs across replicas.
+pub const CACHE_INTEGRITY_LOCK_CLASS: i32 = 0x4349; // "CI"
+
+const DEFAULT_INTERVAL_SECS: u64 = 3600;
+const MIN_INTERVAL_SECS: u64 = 60;
+const DEFAULT_BATCH_SIZE: i64 = 200;
+const MAX_BATCH_SIZE: i64 = 1000;
+const REPORT_TIMEOUT_SECS: u64 = 30;
+
+/// Most corrupted entries carried by one report.
+pub const MAX_REPORT_ENTRIES: usize = 1000;
+
+const KIND_ARTIFACT: &str = "artifact";
+const KIND_PROXY_CACHE: &str = "proxy_cache";
+
+/// One corrupted object found (and purged) by the verifier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CorruptEntry {
+    /// `artifact` or `proxy_cache`.
+    pub kind: String,
+    pub repository_key: String,
+    pub path: String,
+    pub expected_sha256: String,
+    /// SHA-256 of the stored bytes; absent when the object was missing.
+    pub actual_sha256: Option<String>,
+}
+
+/// Result of one verifier run, as reported to the primary.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct IntegrityReport {
+    /// Objects re-hashed in this run.
+    pub checked: i32,
+    /// Objects that could not be read for reasons other than being missing.
+    #[serde(default)]
+    pub errors: i32,
+    #[serde(default)]
+    pub corrupted: Vec<CorruptEntry>,
+}
+
+impl IntegrityReport {
+    pub fn validate(&self) -> Result<()> {
+        if self.checked < 0 || self.errors < 0 {
+            return Err(AppError::Validation(
+                "checked and errors must not be negative".to_string(),
+            ));
+        }
+        if self.corrupted.len() > MAX_REPORT_ENTRIES {
+            return Err(AppError::Validation(format!(
+                "at most {} corrupted entries may be reported at once",
+                MAX_REPORT_ENTRIES
+            )));
+        }
+        if self
+            .corrupted
+            .iter()
+            .any(|e| e.kind != KIND_ARTIFACT && e.kind != KIND_PROXY_CACHE)
+        {
+            return Err(AppError::Validation(
+                "kind must be 'artifact' or 'proxy_cache'".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The primary's answer to a report.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IntegrityReportResponse {
+    /// Purged artifacts the primary re-queued for sync to the node.
+    pub refetch_queued: i32,
+}
+
+/// A stored report, as listed to admins on the primary.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PeerIntegrityReport {
+    pub id: Uuid,
+    pub peer_instance_id: Uuid,
+    pub checked: i32,
+    pub corrupted: i32,
+    pub refetch_queued: i32,
+    pub entries: Vec<CorruptEntry>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ---------------------------------------------------------------------------
+// Verification (edge side)
+// ---------------------------------------------------------------------------
+
+/// Outcome of re-hashing one stored object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Verdict {
+    Intact,
+    Corrupted { actual_sha256: String },
+    Missing,
+    Unreadable(String),
+}
+
+impl Verdict {
+    fn actual_sha256(&self) -> Option<String> {
+        match self {
+            Verdict::Corrupted { actual_sha256 } => Some(actual_sha256.clone()),
+            _ => None,
+        }
+    }
+}
+
+fn classify(expected_sha256: &str, actual_sha256: String) -> Verdict {
+    if actual_sha256.eq_ignore_ascii_case(expected_sha256.trim()) {
+        Verdict::Intact
+    } else {
+        Verdict::Corrupted { actual_sha256 }
+    }
+}
+
+async fn hash_stream(
+    stream: Result<futures::stream::BoxStream<'static, Result<bytes::Bytes>>>,
+    expected_sha256: &str,
+) -> Verdict {
+    let mut stream = match stream {
+        Ok(stream) => stream,
+        Err(AppError::NotFound(_)) => return Verdict::Missing,
+        Err(e) => return Verdict::Unreadable(e.to_string()),
+    };
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(bytes) => hasher.update(&bytes),
+            Err(AppError::NotFound(_)) => return Verdict::Missing,
+            Err(e) => return Verdict::Unreadable(e.to_string()),
+        }
+    }
+    classify(expected_sha256, format!("{:x}", hasher.finalize()))
+}
+
+async fn mark_checked(db: &PgPool, kind: &str, ids: &[Uuid]) -> Result<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT INTO cache_integrity_checks (object_kind, object_id, checked_at) \
+         SELECT $1, id, NOW() FROM UNNEST($2::uuid[]) AS id \
+         ON CONFLICT (object_kind, object_id) DO UPDATE SET checked_at = NOW()",
+    )
+    .bind(kind)
+    .bind(ids)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct ArtifactCandidate {
+    id: Uuid,
+    storage_key: String,
+    checksum_sha256: String,
+    storage_backend: String,
+    storage_path: String,
+}
+
+/// Delete a corrupted artifact blob and soft-delete every live row that
+/// points at it. Returns the affected `(repository_key, path)` pairs.
+async fn purge_artifact_blob(
+    db: &PgPool,
+    storage: &dyn crate::storage::StorageBackend,
+    candidate: &ArtifactCandidate,
+) -> Result<Vec<(String, String)>> {
+    match storage.delete(&candidate.storage_key).await {
+        Ok(()) | Err(AppError::NotFound(_)) => {}
+        Err(e) => tracing::warn!(
+            "Failed to delete corrupted blob '{}': {}",
+            candidate.storage_key,
+            e
+        ),
+    }
+    sqlx::query_as(
+        "UPDATE artifacts a SET is_deleted = true, updated_at = NOW() \
+         FROM repositories r \
+         WHERE r.id = a.repository_id \
+           AND r.storage_backend = $1 AND r.storage_path = $2 \
+           AND a.storage_key = $3 AND a.is_deleted = false \
+         RETURNING r.key, a.path",
+    )
+    .bind(&candidate.storage_backend)
+    .bind(&candidate.storage_path)
+    .bind(&candidate.storage_key)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))
+}
+
+async fn verify_artifacts(
+    db: &PgPool,
+    registry: &StorageRegistry,
+    batch_size: i64,
+    report: &mut IntegrityReport,
+) -> Result<()> {
+    let candidates: Vec<ArtifactCandidate> = sqlx::query_as(
+        "SELECT a.id, a.storage_key, a.checksum_sha256, r.storage_backend, r.storage_path \
+         FROM artifacts a \
+         JOIN repositories r ON r.id = a.repository_id \
+         LEFT JOIN cache_integrity_checks c \
+           ON c.object_kind = 'artifact' AND c.object_id = a.id \
+         WHERE a.is_deleted = false \
+         ORDER BY c.checked_at NULLS FIRST, a.created_at \
+         LIMIT $1",
+    )
+    .bind(batch_size)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let mut checked_ids = Vec::with_capacity(candidates.len());
+    for candidate in &candidates {
+        checked_ids.push(candidate.id);
+        let location = StorageLocation {
+            backend: candidate.storage_backend.clone(),
+            path: candidate.storage_path.clone(),
+        };
+        let storage = match registry.backend_for(&location) {
+            Ok(storage) => storage,
+            Err(e) => {
+                report.errors += 1;
+                tracing::warn!(
+                    "Integrity check skipped: storage backend '{}' unavailable: {}",
+                    location.backend,
+                    e
+                );
+                continue;
+            }
+        };
+        let verdict = hash_stream(
+            storage.get_stream(&candidate.storage_key).await,
+            &candidate.checksum_sha256,
+        )
+        .await;
+        report.checked += 1;
+        match verdict {
+            Verdict::Intact => {}
+            Verdict::Unreadable(e) => {
+                report.errors += 1;
+                tracing::warn!(
+                    "Integrity check could not read '{}': {}",
+                    candidate.storage_key,
+                    e
+                );
+            }
+            Verdict::Corrupted { .. } | Verdict::Missing => {
+                let purged = purge_artifact_blob(db, storage.as_ref(), candidate).await?;
+                for (repository_key, path) in purged {
+                    report.corrupted.push(CorruptEntry {
+                        kind: KIND_ARTIFACT.to_string(),
+                        repository_key,
+                        path,
+                        expected_sha256: candidate.checksum_sha256.clone(),
+                        actual_sha256: verdict.actual_sha256(),
+                    });
+                }
+            }
+        }
+    }
+    mark_checked(db, KIND_ARTIFACT, &checked_ids).await
+}
+
+#[derive(sqlx::FromRow)]
+struct ProxyCandidate {
+    id: Uuid,
+    repository_key: String,
+    path: String,
+    storage_key: String,
+    checksum_sha256: String,
+}
+
+async fn verify_proxy_cache(
+    db: &PgPool,
+    proxy: &ProxyService,
+    batch_size: i64,
+    report: &mut IntegrityReport,
+) -> Result<()> {
+    let candidates: Vec<ProxyCandidate> = sqlx::query_as(
+        "SELECT p.id, r.key AS repository_key, p.path, p.storage_key, p.checksum_sha256 \
+         FROM proxy_cache_artifacts p \
+         JOIN repositories r ON r.id = p.repository_id \
+         LEFT JOIN cache_integrity_checks c \
+           ON c.object_kind = 'proxy_cache' AND c.object_id = p.id \
+         WHERE p.checksum_sha256 IS NOT NULL \
+         ORDER BY c.checked_at NULLS FIRST, p.cached_at \
+         LIMIT $1",
+    )
+    .bind(batch_size)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let mut checked_ids = Vec::with_capacity(candidates.len());
+    for candidate in &candidates {
+        checked_ids.push(candidate.id);
+        let verdict = hash_stream(
+            proxy.cached_content_stream(&candidate.storage_key).await,
+            &candidate.checksum_sha256,
+        )
+        .await;
+        report.checked += 1;
+        match verdict {
+            Verdict::Intact => {}
+            Verdict::Unreadable(e) => {
+                report.errors += 1;
+                tracing::warn!(
+                    "Integrity check could not read '{}': {}",
+                    candidate.storage_key,
+                    e
+                );
+            }
+            Verdict::Corrupted { .. } | Verdict::Missing => {
+                proxy
+                    .invalidate_cache_by_key(&candidate.repository_key, &candidate.path)
+                    .await?;
+                report.corrupted.push(CorruptEntry {
+                    kind: KIND_PROXY_CACHE.to_string(),
+                    repository_key: candidate.repository_key.clone(),
+                    path: candidate.path.clone(),
+                    expected_sha256: candidate.checksum_sha256.clone(),
+                    actual_sha256: verdict.actual_sha256(),
+                });
+            }
+        }
+    }
+    mark_checked(db, KIND_PROXY_CACHE, &checked_ids).await
+}
+
+/// Verify one batch of artifacts and proxy-cache objects, purging corrupted
+/// entries.
+pub async fn verify_once(
+    db: &PgPool,
+    registry: &StorageRegistry,
+    proxy: Option<&ProxyService>,
+    batch_size: i64,
+) -> Result<IntegrityReport> {
+    let mut report = IntegrityReport::default();
+    verify_artifacts(db, registry, batch_size, &mut report).await?;
+    if let Some(proxy) = proxy {
+        verify_proxy_cache(db, proxy, batch_size, &mut report).await?;
+    }
+    report.corrupted.truncate(MAX_REPORT_ENTRIES);
+    Ok(report)
+}
+
+/// Verifier settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityConfig {
+    pub interval: Duration,
+    pub batch_size: i64,
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl IntegrityConfig {
+    /// Load settings from the environment. `None` when the verifier is off.
+    pub fn from_env() -> Option<Self> {
+        let enabled = match non_empty_env("AK_CACHE_INTEGRITY_ENABLED") {
+            Some(v) => matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"),
+            None => non_empty_env("AK_EDGE_PRIMARY_URL").is_some(),
+        };
+        if !enabled {
+            return None;
+        }
+        let interval_secs = non_empty_env("AK_CACHE_INTEGRITY_INTERVAL_SECS")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_INTERVAL_SECS)
+            .max(MIN_INTERVAL_SECS);
+        let batch_size = non_empty_env("AK_CACHE_INTEGRITY_BATCH_SIZE")
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_BATCH_SIZE)
+            .clamp(1, MAX_BATCH_SIZE);
+        Some(Self {
+            interval: Duration::from_secs(interval_secs),
+            batch_size,
+        })
+    }
+}
+
+async fn send_report(
+    client: &reqwest::Client,
+    link: &ForwarderConfig,
+    report: &IntegrityReport,
+) -> Result<IntegrityReportResponse> {
+    let resp = client
+        .post(link.endpoint("integrity"))
+        .bearer_auth(&link.node_secret)
+        .json(report)
+        .send()
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Integrity report failed: {}", e)))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let detail = resp.text().await.unwrap_or_default();
+        return Err(AppError::BadGateway(format!(
+            "Primary rejected integrity report with {}: {}",
+            status,
+            detail.chars().take(512).collect::<String>()
+        )));
+    }
+    resp.json()
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Invalid integrity report response: {}", e)))
+}
+
+/// Start the periodic verifier when enabled (see module docs).
+pub fn start_verifier(
+    db: PgPool,
+    registry: Arc<StorageRegistry>,
+    proxy: Option<Arc<ProxyService>>,
+) {
+    let Some(config) = IntegrityConfig::from_env() else {
+        return;
+    };
+    let link = ForwarderConfig::from_env();
+    let client = match crate::services::http_client::internal_service_client_builder()
+        .timeout(Duration::from_secs(REPORT_TIMEOUT_SECS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!(
+                "Cache integrity verifier disabled: HTTP client error: {}",
+                e
+            );
+            return;
+        }
+    };
+    tracing::info!(
+        interval_secs = config.interval.as_secs(),
+        batch_size = config.batch_size,
+        reports_to_primary = link.is_some(),
+        "Cache integrity verifier enabled"
+    );
+
+    tokio::spawn(async move {
+        let lock = PgAdvisoryLock::new(db.clone());
+        let mut ticker = interval(config.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let lease = match lock.try_acquire(CACHE_INTEGRITY_LOCK_CLASS, 0).await {
+                Ok(Some(lease)) => lease,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!("Cache integrity verifier could not take its lock: {}", e);
+                    continue;
+                }
+            };
+            let result = verify_once(&db, &registry, proxy.as_deref(), config.batch_size).await;
+            lease.release().await;
+            let report = match result {
+                Ok(report) => report,
+                Err(e) => {
+                    tracing::warn!("Cache integrity verification failed: {}", e);
+                    continue;
+                }
+            };
+            record_cache_integrity_run(report.checked as u64, report.corrupted.len() as u64);
+            if !report.corrupted.is_empty() {
+                tracing::warn!(
+                    checked = report.checked,
+                    corrupted = report.corrupted.len(),
+                    "Cache integrity verifier purged corrupted entries"
+                );
+            }
+
+            let Some(link) = &link else {
+                continue;
+            };
+            match send_report(&client, link, &report).await {
+                Ok(resp) => {
+                    let purged_artifacts = report
+                        .corrupted
+                        .iter()
+                        .filter(|e| e.kind == KIND_ARTIFACT)
+                        .count();
+                    let unrestorable =
+                        purged_artifacts.saturating_sub(resp.refetch_queued as usize);
+                    if unrestorable > 0 {
+                        tracing::error!(
+                            unrestorable,
+                            "Purged corrupted artifacts that the primary does not replicate \
+                             to this node; they must be re-uploaded"
+                        );
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to report cache integrity to primary: {}", e),
+            }
+        }
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Reports (primary side)
+// ---------------------------------------------------------------------------
+
+/// Store an edge node's report and re-queue pushes for the purged artifacts
+/// the node is subscribed to. Returns the number of re-queued pushes.
+pub async fn record_report(
+    db: &PgPool,
+    peer_instance_id: Uuid,
+    peer_name: &str,
+    report: &IntegrityReport,
+) -> Result<i32> {
+    report.validate()?;
+    let (keys, paths): (Vec<String>, Vec<String>) = report
+        .corrupted
+        .iter()
+        .filter(|e| e.kind == KIND_ARTIFACT)
+        .map(|e| (e.repository_key.clone(), e.path.clone()))
+        .unzip();
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let refetch_queued = if keys.is_empty() {
+        0
+    } else {
+        sqlx::query(
+            r#"
+            INSERT INTO sync_tasks (peer_instance_id, artifact_id, priority, task_type)
+            SELECT DISTINCT $1::uuid, a.id, 10, 'push'
+            FROM UNNEST($2::text[], $3::text[]) AS c(repository_key, path)
+            JOIN repositories r ON r.key = c.repository_key
+            JOIN artifacts a ON a.repository_id = r.id AND a.path = c.path AND a.is_deleted = false
+            JOIN peer_repo_subscriptions prs
+              ON prs.repository_id = r.id AND prs.peer_instance_id = $1
+            WHERE prs.sync_enabled = true
+              AND prs.replication_mode::text IN ('push', 'mirror')
+            ON CONFLICT (peer_instance_id, artifact_id, task_type)
+            DO UPDATE SET
+                status = 'pending',
+                priority = GREATEST(sync_tasks.priority, EXCLUDED.priority),
+                retry_count = 0,
+                error_message = NULL,
+                claimed_by = NULL,
+                claim_token = NULL,
+                claim_expires_at = NULL
+            WHERE sync_tasks.status != 'in_progress'
+            "#,
+        )
+        .bind(peer_instance_id)
+        .bind(&keys)
+        .bind(&paths)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .rows_affected() as i32
+    };
+
+    let entries = serde_json::to_value(&report.corrupted)
+        .map_err(|e| AppError::Internal(format!("Failed to encode report entries: {}", e)))?;
+    sqlx::query(
+        "INSERT INTO peer_integrity_reports \
+             (peer_instance_id, checked, corrupted, refetch_queued, entries) \
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(peer_instance_id)
+    .bind(report.checked)
+    .bind(report.corrupted.len() as i32)
+    .bind(refetch_queued)
+    .bind(entries)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    if !report.corrupted.is_empty() {
+        record_edge_cache_corruption(peer_name, report.corrupted.len() as u64);
+        tracing::warn!(
+            peer = peer_name,
+            corrupted = report.corrupted.len(),
+            refetch_queued,
+            "Edge node reported corrupted cache entries"
+        );
+    }
+    Ok(refetch_queued)
+}
+
+/// Most recent reports from a peer, newest first.
+pub async fn list_reports(
+    db: &PgPool,
+    peer_instance_id: Uuid,
+    limit: i64,
+) -> Result<Vec<PeerIntegrityReport>> {
+    let rows: Vec<(Uuid, i32, i32, i32, serde_json::Value, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT id, checked, corrupted, refetch_queued, entries, created_at \
+         FROM peer_integrity_reports \
+         WHERE peer_instance_id = $1 \
+         ORDER BY created_at DESC \
+         LIMIT $2",
+    )
+    .bind(peer_instance_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(id, checked, corrupted, refetch_queued, entries, created_at)| PeerIntegrityReport {
+                id,
+                peer_instance_id,
+                checked,
+                corrupted,
+                refetch_queued,
+                entries: serde_json::from_value(entries).unwrap_or_default(),
+                created_at,
+            },
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    fn stream_of(
+        chunks: Vec<Result<Bytes>>,
+    ) -> Result<futures::stream::BoxStream<'static, Result<Bytes>>> {
+        Ok(futures::stream::iter(chunks).boxed())
+    }
+
+    #[tokio::test]
+    async fn test_hash_stream_verdicts() {
+        let intact = hash_stream(
+            stream_of(vec![Ok(Bytes::from("hel")), Ok(Bytes::from("lo"))]),
+            HELLO_SHA256,
+        )
+        .await;
+        assert_eq!(intact, Verdict::Intact);
+
+        let corrupted = hash_stream(stream_of(vec![Ok(Bytes::from("hellO"))]), HELLO_SHA256).await;
+        assert!(matches!(corrupted, Verdict::Corrupted { .. }));
+        assert!(corrupted.actual_sha256().is_some());
+
+        let missing = hash_stream(Err(AppError::NotFound("gone".to_string())), HELLO_SHA256).await;
+        assert_eq!(missing, Verdict::Missing);
+
+        let unreadable = hash_stream(
+            stream_of(vec![Err(AppError::Storage("io".to_string()))]),
+            HELLO_SHA256,
+        )
+        .await;
+        assert!(matches!(unreadable, Verdict::Unreadable(_)));
+    }
+
+    #[test]
+    fn test_classify_ignores_case() {
+        assert_eq!(
+            classify(&HELLO_SHA256.to_uppercase(), HELLO_SHA256.to_string()),
+            Verdict::Intact
+        );
+    }
+
+    #[test]
+    fn test_report_validation() {
+        let entry = CorruptEntry {
+            kind: KIND_ARTIFACT.to_string(),
+            repository_key: "npm-local".to_string(),
+            path: "left-pad/-/left-pad-1.3.0.tgz".to_string(),
+            expected_sha256: HELLO_SHA256.to_string(),
+            actual_sha256: None,
+        };
+        let mut report = IntegrityReport {
+            checked: 10,
+            errors: 0,
+            corrupted: vec![entry.clone()],
+        };
+        report.validate().unwrap();
+
+        report.corrupted[0].kind = "other".to_string();
+        assert!(report.validate().is_err());
+
+        report.corrupted = vec![entry; MAX_REPORT_ENTRIES + 1];
+        assert!(report.validate().is_err());
+    }
+}
@@ -0,0 +1,830 @@
+//! Lockfile-driven warming of the pull-through cache.
+//!
+//! A lockfile (`package-lock.json`, `poetry.lock`, `go.sum` or `Cargo.lock`)
+//! is resolved into the exact client requests a package manager would make
+//! against the repository's native format endpoint. Those requests are then
+//! replayed against this instance over loopback, so each one goes through the
+//! same handler a real client hits: virtual-member resolution, visibility,
+//! curation, age gate and package protection all apply unchanged, and the
+//! proxy's cache tee stores whatever the upstream returns. With
+//! `CACHE_WARM_EDGE_URLS` set the same paths are also pulled through every
+//! edge cache in front of the instance.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::{AppError, Result};
+use crate::formats::go::GoHandler;
+use crate::models::repository::RepositoryFormat;
+
+/// Largest number of packages one warm request may name.
+pub const MAX_WARM_PACKAGES: usize = 5000;
+
+/// Parallel requests per target (loopback or one edge).
+const WARM_CONCURRENCY: usize = 8;
+
+/// Per-request ceiling; archives are streamed to completion, so this bounds
+/// the slowest single download rather than the whole run.
+const WARM_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Failures reported back in the summary; the rest are only counted.
+const MAX_REPORTED_FAILURES: usize = 50;
+
+/// Supported lockfile flavours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LockfileKind {
+    /// `package-lock.json` / `npm-shrinkwrap.json` (lockfile v1, v2 and v3).
+    Npm,
+    /// `poetry.lock`.
+    Poetry,
+    /// `go.sum`.
+    Go,
+    /// `Cargo.lock`.
+    Cargo,
+}
+
+impl LockfileKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "npm" | "package-lock" | "package-lock.json" | "npm-shrinkwrap.json" => Some(Self::Npm),
+            "poetry" | "poetry.lock" | "pypi" => Some(Self::Poetry),
+            "go" | "go.sum" | "gosum" => Some(Self::Go),
+            "cargo" | "cargo.lock" => Some(Self::Cargo),
+            _ => None,
+        }
+    }
+
+    /// Native endpoint prefix serving this lockfile's packages, if `format`
+    /// is one the lockfile can be warmed into.
+    pub fn route_prefix(self, format: &RepositoryFormat) -> Option<&'static str> {
+        match (self, format) {
+            (
+                Self::Npm,
+                RepositoryFormat::Npm | RepositoryFormat::Yarn | RepositoryFormat::Pnpm,
+            ) => Some("/npm"),
+            (Self::Poetry, RepositoryFormat::Pypi | RepositoryFormat::Poetry) => Some("/pypi"),
+            (Self::Go, RepositoryFormat::Go) => Some("/go"),
+            (Self::Cargo, RepositoryFormat::Cargo) => Some("/cargo"),
+            _ => None,
+        }
+    }
+
+    /// Guess the flavour from the content.
+    pub fn detect(content: &str) -> Option<Self> {
+        let trimmed = content.trim_start();
+        if trimmed.starts_with('{') {
+            return Some(Self::Npm);
+        }
+        if let Ok(doc) = toml::from_str::<toml::Table>(content) {
+            let is_poetry = doc
+                .get("metadata")
+                .and_then(|m| m.as_table())
+                .is_some_and(|m| m.contains_key("content-hash") || m.contains_key("lock-version"));
+            if is_poetry {
+                return Some(Self::Poetry);
+            }
+            if doc.get("package").is_some_and(|p| p.is_array()) {
+                return Some(Self::Cargo);
+            }
+        }
+        let looks_like_go_sum = trimmed.lines().filter(|l| !l.trim().is_empty()).all(|l| {
+            let fields: Vec<&str> = l.split_whitespace().collect();
+            fields.len() == 3 && fields[2].starts_with("h1:")
+        });
+        (looks_like_go_sum && !trimmed.is_empty()).then_some(Self::Go)
+    }
+}
+
+/// One package pinned by a lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// Distribution file names, where the lockfile records them (npm tarball,
+    /// Poetry wheels and sdists).
+    pub files: Vec<String>,
+    /// go.sum lines ending in `/go.mod` only pin the module's `go.mod`.
+    pub go_mod_only: bool,
+}
+
+impl LockedPackage {
+    fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            files: Vec::new(),
+            go_mod_only: false,
+        }
+    }
+}
+
+/// Parse `content` as a lockfile of `kind`, dropping packages that do not
+/// come from a registry (git, path and link dependencies). Duplicates are
+/// merged.
+pub fn parse_lockfile(kind: LockfileKind, content: &str) -> Result<Vec<LockedPackage>> {
+    let mut packages = match kind {
+        LockfileKind::Npm => parse_package_lock(content)?,
+        LockfileKind::Poetry => parse_poetry_lock(content)?,
+        LockfileKind::Go => parse_go_sum(content)?,
+        LockfileKind::Cargo => parse_cargo_lock(content)?,
+    };
+    packages.sort_by(|a, b| {
+        (&a.name, &a.version, a.go_mod_only).cmp(&(&b.name, &b.version, b.go_mod_only))
+    });
+    packages.dedup_by(|next, kept| {
+        if next.name == kept.name && next.version == kept.version {
+            kept.go_mod_only &= next.go_mod_only;
+            for file in next.files.drain(..) {
+                if !kept.files.contains(&file) {
+                    kept.files.push(file);
+                }
+            }
+            true
+        } else {
+            false
+        }
+    });
+    Ok(packages)
+}
+
+fn invalid(kind: &str, err: impl std::fmt::Display) -> AppError {
+    AppError::Validation(format!("Invalid {}: {}", kind, err))
+}
+
+/// Tarball name from an npm `resolved` URL, or `None` for non-registry
+/// sources (git, file, links).
+fn npm_registry_file(resolved: &str) -> Option<Option<String>> {
+    if resolved.is_empty() {
+        return Some(None);
+    }
+    if !(resolved.starts_with("https://") || resolved.starts_with("http://")) {
+        return None;
+    }
+    let path = resolved.split(['?', '#']).next().unwrap_or(resolved);
+    let (_, file) = path.rsplit_once("/-/")?;
+    Some((!file.is_empty() && !file.contains('/')).then(|| file.to_string()))
+}
+
+fn parse_package_lock(content: &str) -> Result<Vec<LockedPackage>> {
+    let doc: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| invalid("package-lock.json", e))?;
+    let mut out = Vec::new();
+
+    // v2/v3: flat `packages` map keyed by install path.
+    if let Some(packages) = doc.get("packages").and_then(|p| p.as_object()) {
+        for (path, entry) in packages {
+            let Some((_, name)) = path.rsplit_once("node_modules/") else {
+                continue; // "" is the root project, other keys are workspaces
+            };
+            let name = entry.get("name").and_then(|n| n.as_str()).unwrap_or(name);
+            push_npm_entry(&mut out, name, entry);
+        }
+        return Ok(out);
+    }
+
+    // v1: nested `dependencies` tree.
+    fn walk(out: &mut Vec<LockedPackage>, deps: &serde_json::Map<String, serde_json::Value>) {
+        for (name, entry) in deps {
+            push_npm_entry(out, name, entry);
+            if let Some(nested) = entry.get("dependencies").and_then(|d| d.as_object()) {
+                walk(out, nested);
+            }
+        }
+    }
+    match doc.get("dependencies").and_then(|d| d.as_object()) {
+        Some(deps) => walk(&mut out, deps),
+        None => {
+            return Err(invalid(
+                "package-lock.json",
+                "neither `packages` nor `dependencies` present",
+            ))
+        }
+    }
+    Ok(out)
+}
+
+fn push_npm_entry(out: &mut Vec<LockedPackage>, name: &str, entry: &serde_json::Value) {
+    let flag = |key: &str| entry.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+    if flag("link") || flag("bundled") || flag("inBundle") {
+        return;
+    }
+    let Some(version) = entry.get("version").and_then(|v| v.as_str()) else {
+        return;
+    };
+    let resolved = entry.get("resolved").and_then(|r| r.as_str()).unwrap_or("");
+    let Some(file) = npm_registry_file(resolved) else {
+        return;
+    };
+    // v1 aliases and git deps put a URL or spec in `version`.
+    if version.contains(':') || version.contains('/') {
+        return;
+    }
+    let mut pkg = LockedPackage::new(name, version);
+    let base = name.rsplit('/').next().unwrap_or(name);
+    pkg.files
+        .push(file.unwrap_or_else(|| format!("{}-{}.tgz", base, version)));
+    out.push(pkg);
+}
+
+fn toml_files(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|f| f.as_array())
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|f| f.get("file").and_then(|n| n.as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_poetry_lock(content: &str) -> Result<Vec<LockedPackage>> {
+    let doc: toml::Table = toml::from_str(content).map_err(|e| invalid("poetry.lock", e))?;
+    // Poetry < 1.2 keeps file lists under `[metadata.files]`.
+    let legacy_files = doc
+        .get("metadata")
+        .and_then(|m| m.get("files"))
+        .and_then(|f| f.as_table());
+
+    let mut out = Vec::new();
+    for entry in doc
+        .get("package")
+        .and_then(|p| p.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let (Some(name), Some(version)) = (
+            entry.get("name").and_then(|n| n.as_str()),
+            entry.get("version").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        // `legacy` is a secondary package index and still fetched by name;
+        // git, directory, file and url sources bypass the registry.
+        let source_type = entry
+            .get("source")
+            .and_then(|s| s.get("type"))
+            .and_then(|t| t.as_str());
+        if source_type.is_some_and(|t| t != "legacy") {
+            continue;
+        }
+        let mut pkg = LockedPackage::new(&pep503_normalize(name), version);
+        pkg.files = toml_files(entry.get("files"));
+        if pkg.files.is_empty() {
+            pkg.files = toml_files(legacy_files.and_then(|f| f.get(name)));
+        }
+        out.push(pkg);
+    }
+    Ok(out)
+}
+
+fn parse_go_sum(content: &str) -> Result<Vec<LockedPackage>> {
+    let mut out = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 3 {
+            return Err(invalid("go.sum", format!("malformed line {}", idx + 1)));
+        }
+        let (version, go_mod_only) = match fields[1].strip_suffix("/go.mod") {
+            Some(v) => (v, true),
+            None => (fields[1], false),
+        };
+        let mut pkg = LockedPackage::new(fields[0], version);
+        pkg.go_mod_only = go_mod_only;
+        out.push(pkg);
+    }
+    Ok(out)
+}
+
+fn parse_cargo_lock(content: &str) -> Result<Vec<LockedPackage>> {
+    let doc: toml::Table = toml::from_str(content).map_err(|e| invalid("Cargo.lock", e))?;
+    let mut out = Vec::new();
+    for entry in doc
+        .get("package")
+        .and_then(|p| p.as_array())
+        .into_iter()
+        .flatten()
+    {
+        // Workspace members have no source; git and path sources are not
+        // served by a registry.
+        let from_registry = entry
+            .get("source")
+            .and_then(|s| s.as_str())
+            .is_some_and(|s| s.starts_with("registry+") || s.starts_with("sparse+"));
+        if !from_registry {
+            continue;
+        }
+        if let (Some(name), Some(version)) = (
+            entry.get("name").and_then(|n| n.as_str()),
+            entry.get("version").and_then(|v| v.as_str()),
+        ) {
+            // Crate names are ASCII; anything else cannot map to an index path.
+            if !name.is_empty() && name.is_ascii() {
+                out.push(LockedPackage::new(name, version));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn pep503_normalize(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut in_sep = false;
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !in_sep {
+                out.push('-');
+            }
+            in_sep = true;
+        } else {
+            out.push(c.to_ascii_lowercase());
+            in_sep = false;
+        }
+    }
+    out
+}
+
+fn cargo_index_path(name: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+/// Paths to request, split into metadata that has to be cached first (npm
+/// packuments, PyPI project pages, cargo index files) and the artifacts whose
+/// upstream location the proxy resolves from it.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WarmPlan {
+    pub metadata: Vec<String>,
+    pub artifacts: Vec<String>,
+}
+
+impl WarmPlan {
+    pub fn len(&self) -> usize {
+        self.metadata.len() + self.artifacts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metadata.is_empty() && self.artifacts.is_empty()
+    }
+}
+
+/// Build the request paths for `packages` under `{prefix}/{repo_key}`.
+pub fn plan_requests(
+    kind: LockfileKind,
+    prefix: &str,
+    repo_key: &str,
+    packages: &[LockedPackage],
+) -> WarmPlan {
+    let base = format!("{}/{}", prefix, urlencoding::encode(repo_key));
+    let mut plan = WarmPlan::default();
+    for pkg in packages {
+        match kind {
+            LockfileKind::Npm => {
+                // Scoped names keep a literal `@scope/` prefix, which is
+                // what the scoped npm routes match on.
+                let name = match pkg.name.strip_prefix('@').and_then(|n| n.split_once('/')) {
+                    Some((scope, name)) => format!(
+                        "@{}/{}",
+                        urlencoding::encode(scope),
+                        urlencoding::encode(name)
+                    ),
+                    None => urlencoding::encode(&pkg.name).into_owned(),
+                };
+                plan.metadata.push(format!("{}/{}", base, name));
+                for file in &pkg.files {
+                    plan.artifacts.push(format!(
+                        "{}/{}/-/{}",
+                        base,
+                        name,
+                        urlencoding::encode(file)
+                    ));
+                }
+            }
+            LockfileKind::Poetry => {
+                let project = urlencoding::encode(&pkg.name);
+                plan.metadata.push(format!("{}/simple/{}/", base, project));
+                for file in &pkg.files {
+                    plan.artifacts.push(format!(
+                        "{}/simple/{}/{}",
+                        base,
+                        project,
+                        urlencoding::encode(file)
+                    ));
+                }
+            }
+            LockfileKind::Go => {
+                let module = GoHandler::encode_module_path(&pkg.name);
+                let version = GoHandler::encode_module_path(&pkg.version);
+                plan.artifacts
+                    .push(format!("{}/{}/@v/{}.mod", base, module, version));
+                if !pkg.go_mod_only {
+                    plan.artifacts
+                        .push(format!("{}/{}/@v/{}.info", base, module, version));
+                    plan.artifacts
+                        .push(format!("{}/{}/@v/{}.zip", base, module, version));
+                }
+            }
+            LockfileKind::Cargo => {
+                plan.metadata
+                    .push(format!("{}/index/{}", base, cargo_index_path(&pkg.name)));
+                plan.artifacts.push(format!(
+                    "{}/api/v1/crates/{}/{}/download",
+                    base,
+                    urlencoding::encode(&pkg.name),
+                    urlencoding::encode(&pkg.version)
+                ));
+            }
+        }
+    }
+    plan.metadata.dedup();
+    plan
+}
+
+/// Base URL reaching this instance's own listener, derived from
+/// `BIND_ADDRESS` (a wildcard bind is reached through loopback).
+pub fn loopback_base_url(bind_address: &str) -> Option<String> {
+    let addr: SocketAddr = bind_address.parse().ok()?;
+    let ip = match addr.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ip => ip,
+    };
+    Some(format!("http://{}", SocketAddr::new(ip, addr.port())))
+}
+
+/// Outcome of one warm target (the local instance or an edge cache).
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct WarmTargetResult {
+    /// `local` or the edge base URL.
+    pub target: String,
+    pub requested: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Up to 50 failing paths with the status or error seen.
+    pub failures: Vec<WarmFailure>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WarmFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// Replays warm plans against the local instance and configured edges.
+pub struct CacheWarmer {
+    local_client: reqwest::Client,
+    edge_client: reqwest::Client,
+    local_base: String,
+    edges: Vec<String>,
+    credentials: HeaderMap,
+}
+
+impl CacheWarmer {
+    /// `credentials` are the caller's auth headers; they are replayed so the
+    /// warm runs with exactly the caller's repository access.
+    pub fn new(local_base: String, edges: Vec<String>, credentials: HeaderMap) -> Result<Self> {
+        // The loopback target is derived from our own bind address, never
+        // from the request, so it deliberately bypasses the SSRF guard that
+        // refuses loopback. Redirects are not followed: a 3xx means the
+        // handler served a presigned/storage redirect for a cached object.
+        let local_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(WARM_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to build warm client: {}", e)))?;
+        let edge_client = crate::services::http_client::internal_service_client_builder()
+            .timeout(WARM_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to build warm client: {}", e)))?;
+        Ok(Self {
+            local_client,
+            edge_client,
+            local_base,
+            edges,
+            credentials,
+        })
+    }
+
+    /// Warm the local cache first, then every edge (edges fill from us).
+    pub async fn run(&self, plan: &WarmPlan) -> Vec<WarmTargetResult> {
+        let mut results = vec![
+            self.warm_target(&self.local_client, "local", &self.local_base, plan)
+                .await,
+        ];
+        for edge in &self.edges {
+            results.push(self.warm_target(&self.edge_client, edge, edge, plan).await);
+        }
+        results
+    }
+
+    async fn warm_target(
+        &self,
+        client: &reqwest::Client,
+        label: &str,
+        base: &str,
+        plan: &WarmPlan,
+    ) -> WarmTargetResult {
+        let mut result = WarmTargetResult {
+            target: label.to_string(),
+            requested: plan.len(),
+            ..Default::default()
+        };
+        for phase in [&plan.metadata, &plan.artifacts] {
+            let outcomes: Vec<(String, std::result::Result<(), String>)> =
+                stream::iter(phase.iter())
+                    .map(|path| async move {
+                        let outcome = self.fetch(client, &format!("{}{}", base, path)).await;
+                        (path.clone(), outcome)
+                    })
+                    .buffer_unordered(WARM_CONCURRENCY)
+                    .collect()
+                    .await;
+            for (path, outcome) in outcomes {
+                match outcome {
+                    Ok(()) => result.succeeded += 1,
+                    Err(error) => {
+                        result.failed += 1;
+                        if result.failures.len() < MAX_REPORTED_FAILURES {
+                            result.failures.push(WarmFailure { path, error });
+                        }
+                    }
+                }
+            }
+        }
+        if result.failed > 0 {
+            tracing::warn!(
+                warm_target = label,
+                failed = result.failed,
+                requested = result.requested,
+                "Cache warming finished with failures"
+            );
+        }
+        result
+    }
+
+    async fn fetch(&self, client: &reqwest::Client, url: &str) -> std::result::Result<(), String> {
+        let response = client
+            .get(url)
+            .headers(self.credentials.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        if status.is_redirection() {
+            return Ok(());
+        }
+        if !status.is_success() {
+            return Err(format!("HTTP {}", status.as_u16()));
+        }
+        // Read the body to the end so the proxy's cache tee completes.
+        let mut body = response.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            chunk.map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_lockfile_kinds() {
+        assert_eq!(
+            LockfileKind::detect(r#"{"lockfileVersion": 3, "packages": {}}"#),
+            Some(LockfileKind::Npm)
+        );
+        assert_eq!(
+            LockfileKind::detect(
+                "[[package]]\nname = \"a\"\nversion = \"1\"\n\n[metadata]\nlock-version = \"2.0\"\n"
+            ),
+            Some(LockfileKind::Poetry)
+        );
+        assert_eq!(
+            LockfileKind::detect("version = 3\n\n[[package]]\nname = \"a\"\nversion = \"1.0.0\"\n"),
+            Some(LockfileKind::Cargo)
+        );
+        assert_eq!(
+            LockfileKind::detect(
+                "golang.org/x/text v0.3.0 h1:abc=\ngolang.org/x/text v0.3.0/go.mod h1:def=\n"
+            ),
+            Some(LockfileKind::Go)
+        );
+        assert_eq!(LockfileKind::detect("hello world"), None);
+        assert_eq!(LockfileKind::detect(""), None);
+    }
+
+    #[test]
+    fn parses_package_lock_v3() {
+        let lock = r#"{
+          "lockfileVersion": 3,
+          "packages": {
+            "": {"name": "app", "version": "1.0.0"},
+            "node_modules/lodash": {"version": "4.17.21", "resolved": "https://registry.npmjs.org/lodash/-/lodash-4.17.21.tgz"},
+            "node_modules/@babel/core": {"version": "7.24.0", "resolved": "https://registry.npmjs.org/@babel/core/-/core-7.24.0.tgz"},
+            "node_modules/a/node_modules/lodash": {"version": "4.17.21", "resolved": "https://registry.npmjs.org/lodash/-/lodash-4.17.21.tgz"},
+            "node_modules/local": {"resolved": "packages/local", "link": true},
+            "node_modules/from-git": {"version": "1.0.0", "resolved": "git+ssh://git@github.com/x/y.git#abc"}
+          }
+        }"#;
+        let pkgs = parse_lockfile(LockfileKind::Npm, lock).unwrap();
+        let names: Vec<_> = pkgs.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["@babel/core", "lodash"]);
+        assert_eq!(pkgs[0].files, vec!["core-7.24.0.tgz"]);
+    }
+
+    #[test]
+    fn parses_package_lock_v1() {
+        let lock = r#"{
+          "lockfileVersion": 1,
+          "dependencies": {
+            "express": {
+              "version": "4.18.2",
+              "resolved": "https://registry.npmjs.org/express/-/express-4.18.2.tgz",
+              "dependencies": {
+                "debug": {"version": "2.6.9", "resolved": "https://registry.npmjs.org/debug/-/debug-2.6.9.tgz"}
+              }
+            },
+            "aliased": {"version": "npm:other@1.0.0"}
+          }
+        }"#;
+        let pkgs = parse_lockfile(LockfileKind::Npm, lock).unwrap();
+        let names: Vec<_> = pkgs.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["debug", "express"]);
+    }
+
+    #[test]
+    fn parses_poetry_lock_both_layouts() {
+        let modern = r#"
+[[package]]
+name = "Requests"
+version = "2.31.0"
+files = [
+    {file = "requests-2.31.0-py3-none-any.whl", hash = "sha256:aa"},
+    {file = "requests-2.31.0.tar.gz", hash = "sha256:bb"},
+]
+
+[[package]]
+name = "mylib"
+version = "0.1.0"
+[package.source]
+type = "git"
+url = "https://example.com/mylib.git"
+
+[metadata]
+lock-version = "2.0"
+"#;
+        let pkgs = parse_lockfile(LockfileKind::Poetry, modern).unwrap();
+        assert_eq!(pkgs.len(), 1);
+        assert_eq!(pkgs[0].name, "requests");
+        assert_eq!(pkgs[0].files.len(), 2);
+
+        let legacy = r#"
+[[package]]
+name = "zope.interface"
+version = "6.0"
+
+[metadata]
+content-hash = "x"
+
+[metadata.files]
+"zope.interface" = [{file = "zope.interface-6.0.tar.gz", hash = "sha256:cc"}]
+"#;
+        let pkgs = parse_lockfile(LockfileKind::Poetry, legacy).unwrap();
+        assert_eq!(pkgs[0].name, "zope-interface");
+        assert_eq!(pkgs[0].files, vec!["zope.interface-6.0.tar.gz"]);
+    }
+
+    #[test]
+    fn parses_go_sum_and_merges_mod_lines() {
+        let sum = "github.com/Foo/bar v1.2.0 h1:x=\n\
+                   github.com/Foo/bar v1.2.0/go.mod h1:y=\n\
+                   golang.org/x/sys v0.1.0/go.mod h1:z=\n";
+        let pkgs = parse_lockfile(LockfileKind::Go, sum).unwrap();
+        assert_eq!(pkgs.len(), 2);
+        assert!(!pkgs[0].go_mod_only);
+        assert!(pkgs[1].go_mod_only);
+
+        assert!(parse_lockfile(LockfileKind::Go, "bad line\n").is_err());
+    }
+
+    #[test]
+    fn parses_cargo_lock_registry_packages_only() {
+        let lock = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "forked"
+version = "0.2.0"
+source = "git+https://example.com/forked#abc"
+"#;
+        let pkgs = parse_lockfile(LockfileKind::Cargo, lock).unwrap();
+        assert_eq!(pkgs, vec![LockedPackage::new("serde", "1.0.200")]);
+    }
+
+    #[test]
+    fn plans_native_paths() {
+        let mut scoped = LockedPackage::new("@babel/core", "7.24.0");
+        scoped.files.push("core-7.24.0.tgz".into());
+        let plan = plan_requests(LockfileKind::Npm, "/npm", "npm-remote", &[scoped]);
+        assert_eq!(plan.metadata, vec!["/npm/npm-remote/@babel/core"]);
+        assert_eq!(
+            plan.artifacts,
+            vec!["/npm/npm-remote/@babel/core/-/core-7.24.0.tgz"]
+        );
+
+        let plan = plan_requests(
+            LockfileKind::Go,
+            "/go",
+            "go",
+            &[LockedPackage::new("github.com/Foo/bar", "v1.2.0")],
+        );
+        assert_eq!(
+            plan.artifacts[0],
+            "/go/go/github.com/!foo/bar/@v/v1.2.0.mod"
+        );
+        assert_eq!(plan.artifacts.len(), 3);
+
+        let plan = plan_requests(
+            LockfileKind::Cargo,
+            "/cargo",
+            "crates",
+            &[
+                LockedPackage::new("a", "1.0.0"),
+                LockedPackage::new("syn", "2.0.0"),
+                LockedPackage::new("Serde", "1.0.0"),
+            ],
+        );
+        assert_eq!(
+            plan.metadata,
+            vec![
+                "/cargo/crates/index/1/a",
+                "/cargo/crates/index/3/s/syn",
+                "/cargo/crates/index/se/rd/serde",
+            ]
+        );
+        assert_eq!(
+            plan.artifacts[2],
+            "/cargo/crates/api/v1/crates/Serde/1.0.0/download"
+        );
+    }
+
+    #[test]
+    fn route_prefix_requires_matching_format() {
+        assert_eq!(
+            LockfileKind::Npm.route_prefix(&RepositoryFormat::Yarn),
+            Some("/npm")
+        );
+        assert_eq!(
+            LockfileKind::Poetry.route_prefix(&RepositoryFormat::Pypi),
+            Some("/pypi")
+        );
+        assert_eq!(
+            LockfileKind::Cargo.route_prefix(&RepositoryFormat::Npm),
+            None
+        );
+    }
+
+    #[test]
+    fn loopback_base_url_from_bind_address() {
+        assert_eq!(
+            loopback_base_url("0.0.0.0:8080").as_deref(),
+            Some("http://127.0.0.1:8080")
+        );
+        assert_eq!(
+            loopback_base_url("[::]:9000").as_deref(),
+            Some("http://[::1]:9000")
+        );
+        assert_eq!(
+            loopback_base_url("10.0.0.5:8080").as_deref(),
+            Some("http://10.0.0.5:8080")
+        );
+        assert_eq!(loopback_base_url("not-an-addr"), None);
+    }
+}
@@ -0,0 +1,136 @@
+//! Helm chart → container image relations.
+//!
+//! A Helm upload records the images its chart deploys (found by
+//! [`HelmHandler::extract_image_refs_from_reader`] in the chart's values and
+//! templates) in `chart_image_refs`. Read backwards, the table answers "which
+//! charts deploy image X" — the question CVE impact analysis asks once an
+//! image is known to be vulnerable.
+//!
+//! [`HelmHandler::extract_image_refs_from_reader`]:
+//! crate::formats::helm::HelmHandler::extract_image_refs_from_reader
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::formats::helm::ChartImageRef;
+use crate::formats::oci::ImageReference;
+
+/// One chart (artifact) that deploys the looked-up image.
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ChartUsingImage {
+    pub artifact_id: Uuid,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub chart_name: String,
+    pub chart_version: Option<String>,
+    pub path: String,
+    /// Normalized image reference as recorded for this chart.
+    pub image: String,
+    /// `values` or `template`.
+    pub source: String,
+    /// Values key path or template file the reference came from.
+    pub location: String,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// Record the images a chart artifact deploys. Best-effort: a failure is
+/// logged and never fails the upload that produced the chart.
+pub async fn record_chart_images(
+    db: &PgPool,
+    artifact_id: Uuid,
+    repository_id: Uuid,
+    refs: &[ChartImageRef],
+) {
+    if refs.is_empty() {
+        return;
+    }
+
+    let mut images = Vec::with_capacity(refs.len());
+    let mut registries = Vec::with_capacity(refs.len());
+    let mut repositories = Vec::with_capacity(refs.len());
+    let mut tags = Vec::with_capacity(refs.len());
+    let mut digests = Vec::with_capacity(refs.len());
+    let mut sources = Vec::with_capacity(refs.len());
+    let mut locations = Vec::with_capacity(refs.len());
+    for r in refs {
+        images.push(r.image.to_string());
+        registries.push(r.image.registry.clone());
+        repositories.push(r.image.repository.clone());
+        tags.push(r.image.tag.clone());
+        digests.push(r.image.digest.clone());
+        sources.push(r.source.as_str().to_string());
+        locations.push(r.location.clone());
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO chart_image_refs
+            (artifact_id, repository_id, image, image_registry, image_repository,
+             image_tag, image_digest, source, location)
+        SELECT $1, $2, u.image, u.registry, u.repository, u.tag, u.digest, u.source, u.location
+        FROM UNNEST($3::text[], $4::text[], $5::text[], $6::text[], $7::text[],
+                    $8::text[], $9::text[])
+            AS u(image, registry, repository, tag, digest, source, location)
+        ON CONFLICT (artifact_id, image, location) DO NOTHING
+        "#,
+    )
+    .bind(artifact_id)
+    .bind(repository_id)
+    .bind(&images)
+    .bind(&registries)
+    .bind(&repositories)
+    .bind(&tags)
+    .bind(&digests)
+    .bind(&sources)
+    .bind(&locations)
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!(
+            "Failed to record image references for chart artifact {}: {}",
+            artifact_id,
+            e
+        );
+    }
+}
+
+/// Charts (live artifacts only) that deploy `image`.
+///
+/// Registry and repository always match. A tag or digest in `image` narrows
+/// the match to charts pinning exactly that tag or digest; without one, every
+/// tag of the repository matches.
+pub async fn find_charts_using_image(
+    db: &PgPool,
+    image: &ImageReference,
+    limit: i64,
+) -> Result<Vec<ChartUsingImage>> {
+    sqlx::query_as::<_, ChartUsingImage>(
+        r#"
+        SELECT a.id AS artifact_id, a.repository_id, r.key AS repository_key,
+               a.name AS chart_name, a.version AS chart_version, a.path,
+               c.image, c.source, c.location, a.created_at AS uploaded_at
+        FROM chart_image_refs c
+        JOIN artifacts a ON a.id = c.artifact_id AND a.is_deleted = false
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE c.image_registry = $1
+          AND c.image_repository = $2
+          AND ($3::text IS NULL OR c.image_tag = $3)
+          AND ($4::text IS NULL OR c.image_digest = $4)
+        ORDER BY a.created_at DESC, c.location
+        LIMIT $5
+        "#,
+    )
+    .bind(&image.registry)
+    .bind(&image.repository)
+    .bind(image.tag.as_deref())
+    .bind(image.digest.as_deref())
+    .bind(limit)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))
+}
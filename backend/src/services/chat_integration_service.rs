@@ -0,0 +1,1514 @@
+//! Native Slack and Microsoft Teams integrations.
+//!
+//! Subscribes to the EventBus and posts each event to every enabled chat
+//! integration whose routing rules match the event type and repository.
+//! Integrations run in one of two modes:
+//!
+//! - **webhook**: an incoming-webhook URL bound to a single channel. No
+//!   threading and no interactivity; the platform does not return a message
+//!   reference to reply to.
+//! - **app**: a Slack bot token (`chat.postMessage`) or a Teams Bot Framework
+//!   app (client-credentials token, `v3/conversations`). Routing rules can
+//!   target different channels, and related events (`scan.started` ->
+//!   `scan.completed`, `approval.requested` -> `approval.approved`) are
+//!   posted as replies to the first message of the sequence.
+//!
+//! Slack app-mode integrations with a signing secret additionally render
+//! Approve / Reject buttons on `approval.requested` messages; the click is
+//! delivered to the interactivity endpoint in
+//! `crate::api::handlers::chat_integrations`, which verifies the request
+//! signature with [`verify_slack_signature`]. Teams cards carry no buttons:
+//! Bot Framework invoke callbacks would need a full bot endpoint.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::PgPool;
+use tokio::sync::{broadcast, Mutex};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::event_bus::{DomainEvent, EventBus};
+use crate::services::webhook_secret_crypto;
+
+/// Slack Web API base URL.
+const SLACK_API_BASE: &str = "https://slack.com/api";
+
+/// Bot Framework token scope for the client-credentials grant.
+const BOT_FRAMEWORK_SCOPE: &str = "https://api.botframework.com/.default";
+
+/// Tenant used for multi-tenant Bot Framework apps.
+const BOT_FRAMEWORK_DEFAULT_TENANT: &str = "botframework.com";
+
+/// Maximum age of a Slack interactivity request, per Slack's guidance.
+pub const SLACK_SIGNATURE_MAX_AGE_SECS: i64 = 300;
+
+/// Slack `action_id` values for the approval buttons.
+pub const SLACK_ACTION_APPROVE: &str = "ak_approval_approve";
+pub const SLACK_ACTION_REJECT: &str = "ak_approval_reject";
+
+/// Upper bound on event types per routing rule.
+const MAX_RULE_EVENT_TYPES: usize = 50;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// Chat platform an integration posts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatPlatform {
+    Slack,
+    MicrosoftTeams,
+}
+
+impl ChatPlatform {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Slack => "slack",
+            Self::MicrosoftTeams => "microsoft_teams",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "slack" => Some(Self::Slack),
+            "microsoft_teams" => Some(Self::MicrosoftTeams),
+            _ => None,
+        }
+    }
+}
+
+/// How an integration delivers messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatMode {
+    Webhook,
+    App,
+}
+
+impl ChatMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Webhook => "webhook",
+            Self::App => "app",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "webhook" => Some(Self::Webhook),
+            "app" => Some(Self::App),
+            _ => None,
+        }
+    }
+}
+
+/// A chat integration as returned by the API. Credentials are never exposed.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ChatIntegration {
+    pub id: Uuid,
+    pub name: String,
+    pub platform: String,
+    pub mode: String,
+    pub default_channel: Option<String>,
+    pub app_id: Option<String>,
+    pub app_tenant_id: Option<String>,
+    pub app_service_url: Option<String>,
+    pub is_enabled: bool,
+    /// Whether a Slack signing secret is configured, which enables the
+    /// Approve / Reject buttons.
+    pub interactive: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A routing rule attached to an integration.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ChatRoutingRule {
+    pub id: Uuid,
+    pub integration_id: Uuid,
+    /// Event types this rule matches (e.g. `scan.completed`, or `scan.*`).
+    /// Empty matches every event.
+    pub event_types: Vec<String>,
+    /// Repository this rule is limited to; `null` matches every repository.
+    pub repository_id: Option<Uuid>,
+    /// Channel override (app mode only); `null` uses the integration default.
+    pub channel: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Routing rule as supplied on create / replace.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct ChatRoutingRuleInput {
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    pub repository_id: Option<Uuid>,
+    pub channel: Option<String>,
+}
+
+/// Fields for a new integration.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateChatIntegration {
+    pub name: String,
+    pub platform: ChatPlatform,
+    pub mode: ChatMode,
+    /// Incoming-webhook URL (webhook mode).
+    pub webhook_url: Option<String>,
+    /// Slack bot token (`xoxb-...`) or Teams app client secret (app mode).
+    pub token: Option<String>,
+    /// Slack signing secret; enables interactive approval buttons.
+    pub signing_secret: Option<String>,
+    /// Default channel: a Slack channel ID or a Teams channel conversation ID.
+    pub default_channel: Option<String>,
+    /// Teams app (client) ID.
+    pub app_id: Option<String>,
+    /// Teams tenant ID for single-tenant bots; multi-tenant bots omit it.
+    pub app_tenant_id: Option<String>,
+    /// Teams Bot Framework service URL (e.g. `https://smba.trafficmanager.net/amer/`).
+    pub app_service_url: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<ChatRoutingRuleInput>,
+}
+
+/// Partial update of an integration. Supplying a credential replaces it.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct UpdateChatIntegration {
+    pub name: Option<String>,
+    pub is_enabled: Option<bool>,
+    pub default_channel: Option<String>,
+    pub webhook_url: Option<String>,
+    pub token: Option<String>,
+    pub signing_secret: Option<String>,
+    pub app_service_url: Option<String>,
+}
+
+/// Reviewer decision carried by a Slack button click.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    Approve,
+    Reject,
+}
+
+/// A parsed Slack approval button click.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlackApprovalAction {
+    pub decision: ApprovalDecision,
+    pub approval_id: Uuid,
+    pub slack_user_id: String,
+    pub response_url: Option<String>,
+}
+
+/// Credentials and platform settings needed to post, decrypted on use.
+#[derive(Debug, sqlx::FromRow)]
+struct IntegrationTarget {
+    id: Uuid,
+    platform: String,
+    mode: String,
+    webhook_url_encrypted: Option<Vec<u8>>,
+    token_encrypted: Option<Vec<u8>>,
+    signing_secret_encrypted: Option<Vec<u8>>,
+    default_channel: Option<String>,
+    app_id: Option<String>,
+    app_tenant_id: Option<String>,
+    app_service_url: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Pure helpers
+// ---------------------------------------------------------------------------
+
+/// Whether an event type pattern matches. A trailing `.*` matches every
+/// event in that family (`scan.*` matches `scan.completed`).
+pub fn event_type_matches(pattern: &str, event_type: &str) -> bool {
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => event_type
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => pattern == event_type,
+    }
+}
+
+/// Whether a routing rule matches an event.
+pub fn rule_matches(
+    event_types: &[String],
+    rule_repository_id: Option<Uuid>,
+    event_type: &str,
+    event_repository_id: Option<Uuid>,
+) -> bool {
+    let type_ok = event_types.is_empty()
+        || event_types
+            .iter()
+            .any(|p| event_type_matches(p, event_type));
+    let repo_ok = match rule_repository_id {
+        None => true,
+        Some(r) => event_repository_id == Some(r),
+    };
+    type_ok && repo_ok
+}
+
+/// Channels an event should be posted to, deduplicated in rule order.
+///
+/// Webhook integrations are bound to one channel, so any match yields a
+/// single empty channel name. App integrations use each matching rule's
+/// channel, falling back to the integration default; matches with neither
+/// are dropped.
+pub fn resolve_channels(
+    mode: ChatMode,
+    default_channel: Option<&str>,
+    rules: &[ChatRoutingRule],
+    event: &DomainEvent,
+) -> Vec<String> {
+    let mut channels: Vec<String> = Vec::new();
+    for rule in rules {
+        if !rule_matches(
+            &rule.event_types,
+            rule.repository_id,
+            &event.event_type,
+            event.repository_id,
+        ) {
+            continue;
+        }
+        let channel = match mode {
+            ChatMode::Webhook => Some(String::new()),
+            ChatMode::App => rule
+                .channel
+                .as_deref()
+                .or(default_channel)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string),
+        };
+        if let Some(channel) = channel {
+            if !channels.contains(&channel) {
+                channels.push(channel);
+            }
+        }
+    }
+    channels
+}
+
+/// Key that groups related events into one thread, if the event belongs to
+/// a sequence.
+pub fn thread_key(event: &DomainEvent) -> Option<String> {
+    let family = event.event_type.split('.').next()?;
+    match family {
+        "scan" | "approval" => Some(format!("{}:{}", family, event.entity_id)),
+        _ => None,
+    }
+}
+
+/// Whether an event closes its thread, so the stored reference can be
+/// dropped after replying.
+pub fn closes_thread(event_type: &str) -> bool {
+    matches!(
+        event_type,
+        "scan.completed" | "scan.failed" | "approval.approved" | "approval.rejected"
+    )
+}
+
+/// One-line human summary of an event.
+pub fn event_summary(event: &DomainEvent) -> String {
+    let id = &event.entity_id;
+    match event.event_type.as_str() {
+        "scan.started" => format!("Security scan started for artifact {}", id),
+        "scan.completed" => format!("Security scan completed for artifact {}", id),
+        "scan.failed" => format!("Security scan failed for artifact {}", id),
+        "approval.requested" => format!("Promotion approval requested ({})", id),
+        "approval.approved" => format!("Promotion approved ({})", id),
+        "approval.rejected" => format!("Promotion rejected ({})", id),
+        "repository.created" => format!("Repository created: {}", id),
+        "repository.updated" => format!("Repository updated: {}", id),
+        "repository.deleted" => format!("Repository deleted: {}", id),
+        other => format!("{}: {}", other, id),
+    }
+}
+
+fn event_context_line(event: &DomainEvent) -> String {
+    match &event.actor {
+        Some(actor) => format!("{} by {} at {}", event.event_type, actor, event.timestamp),
+        None => format!("{} at {}", event.event_type, event.timestamp),
+    }
+}
+
+/// Render a Slack message body (`text` + Block Kit `blocks`). When
+/// `interactive` is set and the event is an approval request, Approve and
+/// Reject buttons carrying the approval ID are appended.
+pub fn render_slack_message(event: &DomainEvent, interactive: bool) -> serde_json::Value {
+    let summary = event_summary(event);
+    let mut blocks = vec![
+        serde_json::json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": summary },
+        }),
+        serde_json::json!({
+            "type": "context",
+            "elements": [{ "type": "mrkdwn", "text": event_context_line(event) }],
+        }),
+    ];
+    if interactive && event.event_type == "approval.requested" {
+        blocks.push(serde_json::json!({
+            "type": "actions",
+            "elements": [
+                {
+                    "type": "button",
+                    "style": "primary",
+                    "text": { "type": "plain_text", "text": "Approve" },
+                    "action_id": SLACK_ACTION_APPROVE,
+                    "value": event.entity_id,
+                },
+                {
+                    "type": "button",
+                    "style": "danger",
+                    "text": { "type": "plain_text", "text": "Reject" },
+                    "action_id": SLACK_ACTION_REJECT,
+                    "value": event.entity_id,
+                },
+            ],
+        }));
+    }
+    serde_json::json!({ "text": summary, "blocks": blocks })
+}
+
+/// Render a Teams Adaptive Card for an event.
+pub fn render_teams_card(event: &DomainEvent) -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "type": "AdaptiveCard",
+        "version": "1.4",
+        "body": [
+            {
+                "type": "TextBlock",
+                "text": event_summary(event),
+                "weight": "Bolder",
+                "wrap": true,
+            },
+            {
+                "type": "TextBlock",
+                "text": event_context_line(event),
+                "isSubtle": true,
+                "wrap": true,
+            },
+        ],
+    })
+}
+
+/// Wrap an Adaptive Card in a message activity / webhook envelope. Teams
+/// incoming webhooks and Bot Framework accept the same shape.
+pub fn teams_activity(card: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "type": "message",
+        "attachments": [{
+            "contentType": "application/vnd.microsoft.card.adaptive",
+            "content": card,
+        }],
+    })
+}
+
+/// Verify a Slack request signature (`X-Slack-Signature`) against the raw
+/// body and `X-Slack-Request-Timestamp`. Requests older than
+/// [`SLACK_SIGNATURE_MAX_AGE_SECS`] are rejected to prevent replay.
+pub fn verify_slack_signature(
+    signing_secret: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+    now_unix: i64,
+) -> bool {
+    let Ok(ts) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now_unix - ts).abs() > SLACK_SIGNATURE_MAX_AGE_SECS {
+        return false;
+    }
+    let Some(hex_sig) = signature.strip_prefix("v0=") else {
+        return false;
+    };
+    let Ok(expected) = hex::decode(hex_sig) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(signing_secret.as_bytes())
+        .expect("HMAC accepts any key length");
+    mac.update(b"v0:");
+    mac.update(timestamp.as_bytes());
+    mac.update(b":");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Extract an approval button click from a Slack `block_actions` payload.
+/// Returns `None` for any other interaction.
+pub fn parse_slack_interaction(payload: &serde_json::Value) -> Option<SlackApprovalAction> {
+    if payload.get("type")?.as_str()? != "block_actions" {
+        return None;
+    }
+    let action = payload.get("actions")?.as_array()?.first()?;
+    let decision = match action.get("action_id")?.as_str()? {
+        SLACK_ACTION_APPROVE => ApprovalDecision::Approve,
+        SLACK_ACTION_REJECT => ApprovalDecision::Reject,
+        _ => return None,
+    };
+    let approval_id = Uuid::parse_str(action.get("value")?.as_str()?).ok()?;
+    let slack_user_id = payload.get("user")?.get("id")?.as_str()?.to_string();
+    let response_url = payload
+        .get("response_url")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    Some(SlackApprovalAction {
+        decision,
+        approval_id,
+        slack_user_id,
+        response_url,
+    })
+}
+
+fn non_empty(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|v| !v.trim().is_empty())
+}
+
+/// Validate routing rules.
+pub fn validate_rules(rules: &[ChatRoutingRuleInput]) -> Result<()> {
+    for rule in rules {
+        if rule.event_types.len() > MAX_RULE_EVENT_TYPES {
+            return Err(AppError::Validation(format!(
+                "A routing rule may list at most {} event types",
+                MAX_RULE_EVENT_TYPES
+            )));
+        }
+        for pattern in &rule.event_types {
+            let valid = !pattern.is_empty()
+                && pattern.len() <= 64
+                && pattern
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c == '_' || c == '.' || c == '*')
+                && (!pattern.contains('*') || pattern.ends_with(".*"))
+                && pattern.matches('*').count() <= 1;
+            if !valid {
+                return Err(AppError::Validation(format!(
+                    "Invalid event type pattern '{}'",
+                    pattern
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate a new integration's mode-specific fields. URL reachability
+/// (anti-SSRF) is checked by the handler.
+pub fn validate_new_integration(req: &CreateChatIntegration) -> Result<()> {
+    if req.name.trim().is_empty() || req.name.len() > 255 {
+        return Err(AppError::Validation(
+            "Integration name must be 1-255 characters".to_string(),
+        ));
+    }
+    match req.mode {
+        ChatMode::Webhook => {
+            if !non_empty(&req.webhook_url) {
+                return Err(AppError::Validation(
+                    "webhook_url is required in webhook mode".to_string(),
+                ));
+            }
+            if non_empty(&req.signing_secret) {
+                return Err(AppError::Validation(
+                    "signing_secret requires app mode: interactive buttons need a bot token"
+                        .to_string(),
+                ));
+            }
+        }
+        ChatMode::App => {
+            if !non_empty(&req.token) {
+                return Err(AppError::Validation(
+                    "token is required in app mode".to_string(),
+                ));
+            }
+            if req.platform == ChatPlatform::MicrosoftTeams
+                && (!non_empty(&req.app_id) || !non_empty(&req.app_service_url))
+            {
+                return Err(AppError::Validation(
+                    "Teams app mode requires app_id and app_service_url".to_string(),
+                ));
+            }
+            if req.platform == ChatPlatform::MicrosoftTeams && non_empty(&req.signing_secret) {
+                return Err(AppError::Validation(
+                    "signing_secret is only supported for Slack".to_string(),
+                ));
+            }
+            let any_channel =
+                non_empty(&req.default_channel) || req.rules.iter().any(|r| non_empty(&r.channel));
+            if !req.rules.is_empty() && !any_channel {
+                return Err(AppError::Validation(
+                    "App mode needs a default_channel or a channel on each rule".to_string(),
+                ));
+            }
+        }
+    }
+    validate_rules(&req.rules)
+}
+
+fn encrypt(value: &str) -> Result<Vec<u8>> {
+    webhook_secret_crypto::encrypt_secret(value).map_err(|e| {
+        tracing::error!("chat integration credential encryption failed: {}", e);
+        AppError::Internal("chat integration credential encryption failed".to_string())
+    })
+}
+
+fn encrypt_opt(value: &Option<String>) -> Result<Option<Vec<u8>>> {
+    match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => encrypt(v).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn decrypt(ciphertext: &Option<Vec<u8>>, what: &str) -> Result<String> {
+    let bytes = ciphertext
+        .as_deref()
+        .ok_or_else(|| AppError::Internal(format!("chat integration has no {}", what)))?;
+    webhook_secret_crypto::decrypt_secret(bytes)
+        .map_err(|e| AppError::Internal(format!("failed to decrypt chat {}: {}", what, e)))
+}
+
+// ---------------------------------------------------------------------------
+// Service
+// ---------------------------------------------------------------------------
+
+const SELECT_INTEGRATION: &str = r#"
+    SELECT id, name, platform, mode, default_channel, app_id, app_tenant_id,
+           app_service_url, is_enabled,
+           (signing_secret_encrypted IS NOT NULL) AS interactive,
+           created_by, created_at, updated_at
+    FROM chat_integrations
+"#;
+
+const SELECT_TARGET: &str = r#"
+    SELECT id, platform, mode, webhook_url_encrypted, token_encrypted,
+           signing_secret_encrypted, default_channel, app_id, app_tenant_id,
+           app_service_url
+    FROM chat_integrations
+"#;
+
+/// Manages chat integrations and delivers events to them.
+#[derive(Clone)]
+pub struct ChatIntegrationService {
+    db: PgPool,
+    http: reqwest::Client,
+    /// Bot Framework access tokens keyed by integration, with expiry.
+    teams_tokens: Arc<Mutex<HashMap<Uuid, (String, Instant)>>>,
+}
+
+impl ChatIntegrationService {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            db,
+            http: crate::services::http_client::webhook_client(),
+            teams_tokens: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<ChatIntegration>> {
+        sqlx::query_as(&format!("{} ORDER BY name", SELECT_INTEGRATION))
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<ChatIntegration> {
+        sqlx::query_as(&format!("{} WHERE id = $1", SELECT_INTEGRATION))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .ok_or_else(|| AppError::NotFound("Chat integration not found".to_string()))
+    }
+
+    pub async fn list_rules(&self, integration_id: Uuid) -> Result<Vec<ChatRoutingRule>> {
+        sqlx::query_as(
+            r#"
+            SELECT id, integration_id, event_types, repository_id, channel, created_at
+            FROM chat_routing_rules
+            WHERE integration_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(integration_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    pub async fn create(
+        &self,
+        req: &CreateChatIntegration,
+        created_by: Uuid,
+    ) -> Result<ChatIntegration> {
+        validate_new_integration(req)?;
+        let webhook_url = encrypt_opt(&req.webhook_url)?;
+        let token = encrypt_opt(&req.token)?;
+        let signing_secret = encrypt_opt(&req.signing_secret)?;
+
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO chat_integrations
+                (name, platform, mode, webhook_url_encrypted, token_encrypted,
+                 signing_secret_encrypted, default_channel, app_id, app_tenant_id,
+                 app_service_url, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id
+            "#,
+        )
+        .bind(req.name.trim())
+        .bind(req.platform.as_str())
+        .bind(req.mode.as_str())
+        .bind(webhook_url)
+        .bind(token)
+        .bind(signing_secret)
+        .bind(&req.default_channel)
+        .bind(&req.app_id)
+        .bind(&req.app_tenant_id)
+        .bind(&req.app_service_url)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("duplicate key") {
+                AppError::Conflict(format!("Chat integration '{}' already exists", req.name))
+            } else {
+                AppError::Database(e.to_string())
+            }
+        })?;
+        Self::insert_rules(&mut tx, id, &req.rules).await?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        self.get(id).await
+    }
+
+    pub async fn update(&self, id: Uuid, req: &UpdateChatIntegration) -> Result<ChatIntegration> {
+        if let Some(name) = &req.name {
+            if name.trim().is_empty() || name.len() > 255 {
+                return Err(AppError::Validation(
+                    "Integration name must be 1-255 characters".to_string(),
+                ));
+            }
+        }
+        let current = self.get(id).await?;
+        let mode = ChatMode::parse(&current.mode);
+        if non_empty(&req.webhook_url) && mode != Some(ChatMode::Webhook) {
+            return Err(AppError::Validation(
+                "webhook_url only applies to webhook-mode integrations".to_string(),
+            ));
+        }
+        if (non_empty(&req.token) || non_empty(&req.default_channel)) && mode != Some(ChatMode::App)
+        {
+            return Err(AppError::Validation(
+                "token and default_channel only apply to app-mode integrations".to_string(),
+            ));
+        }
+        if non_empty(&req.signing_secret)
+            && (mode != Some(ChatMode::App) || current.platform != ChatPlatform::Slack.as_str())
+        {
+            return Err(AppError::Validation(
+                "signing_secret only applies to Slack app-mode integrations".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE chat_integrations SET
+                name = COALESCE($2, name),
+                is_enabled = COALESCE($3, is_enabled),
+                default_channel = COALESCE($4, default_channel),
+                webhook_url_encrypted = COALESCE($5, webhook_url_encrypted),
+                token_encrypted = COALESCE($6, token_encrypted),
+                signing_secret_encrypted = COALESCE($7, signing_secret_encrypted),
+                app_service_url = COALESCE($8, app_service_url),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(req.is_enabled)
+        .bind(&req.default_channel)
+        .bind(encrypt_opt(&req.webhook_url)?)
+        .bind(encrypt_opt(&req.token)?)
+        .bind(encrypt_opt(&req.signing_secret)?)
+        .bind(&req.app_service_url)
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("duplicate key") {
+                AppError::Conflict("A chat integration with that name already exists".to_string())
+            } else {
+                AppError::Database(e.to_string())
+            }
+        })?;
+
+        self.teams_tokens.lock().await.remove(&id);
+        self.get(id).await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM chat_integrations WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Chat integration not found".to_string()));
+        }
+        self.teams_tokens.lock().await.remove(&id);
+        Ok(())
+    }
+
+    /// Replace an integration's routing rules.
+    pub async fn replace_rules(
+        &self,
+        integration_id: Uuid,
+        rules: &[ChatRoutingRuleInput],
+    ) -> Result<Vec<ChatRoutingRule>> {
+        validate_rules(rules)?;
+        let integration = self.get(integration_id).await?;
+        if ChatMode::parse(&integration.mode) == Some(ChatMode::App)
+            && !non_empty(&integration.default_channel)
+            && rules.iter().any(|r| !non_empty(&r.channel))
+        {
+            return Err(AppError::Validation(
+                "Every rule needs a channel when the integration has no default_channel"
+                    .to_string(),
+            ));
+        }
+
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        sqlx::query("DELETE FROM chat_routing_rules WHERE integration_id = $1")
+            .bind(integration_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Self::insert_rules(&mut tx, integration_id, rules).await?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        self.list_rules(integration_id).await
+    }
+
+    async fn insert_rules(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        integration_id: Uuid,
+        rules: &[ChatRoutingRuleInput],
+    ) -> Result<()> {
+        for rule in rules {
+            sqlx::query(
+                r#"
+                INSERT INTO chat_routing_rules (integration_id, event_types, repository_id, channel)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(integration_id)
+            .bind(&rule.event_types)
+            .bind(rule.repository_id)
+            .bind(rule.channel.as_deref().filter(|c| !c.is_empty()))
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    async fn target(&self, id: Uuid) -> Result<IntegrationTarget> {
+        sqlx::query_as(&format!("{} WHERE id = $1", SELECT_TARGET))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .ok_or_else(|| AppError::NotFound("Chat integration not found".to_string()))
+    }
+
+    /// Decrypted Slack signing secret for an enabled Slack app integration,
+    /// or `None` when the integration does not accept interactions.
+    pub async fn slack_signing_secret(&self, id: Uuid) -> Result<Option<String>> {
+        let target: Option<IntegrationTarget> = sqlx::query_as(&format!(
+            "{} WHERE id = $1 AND is_enabled = true AND platform = 'slack' AND mode = 'app'",
+            SELECT_TARGET
+        ))
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        match target {
+            Some(t) if t.signing_secret_encrypted.is_some() => {
+                decrypt(&t.signing_secret_encrypted, "signing secret").map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Look up the email address of a Slack user with the integration's bot
+    /// token (requires the `users:read.email` scope).
+    pub async fn slack_user_email(&self, id: Uuid, slack_user_id: &str) -> Result<Option<String>> {
+        let target = self.target(id).await?;
+        let token = decrypt(&target.token_encrypted, "bot token")?;
+        let resp: serde_json::Value = self
+            .http
+            .get(format!(
+                "{}/users.info?user={}",
+                SLACK_API_BASE,
+                urlencoding::encode(slack_user_id)
+            ))
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Slack users.info failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Slack users.info failed: {}", e)))?;
+        if resp.get("ok").and_then(|v| v.as_bool()) != Some(true) {
+            return Ok(None);
+        }
+        Ok(resp
+            .pointer("/user/profile/email")
+            .and_then(|v| v.as_str())
+            .map(str::to_string))
+    }
+
+    /// Post a plain reply to a Slack interaction's `response_url`.
+    pub async fn respond_to_slack_interaction(&self, response_url: &str, text: &str) {
+        let body = serde_json::json!({
+            "response_type": "in_channel",
+            "replace_original": false,
+            "text": text,
+        });
+        if let Err(e) = self.http.post(response_url).json(&body).send().await {
+            tracing::warn!("Failed to answer Slack interaction: {}", e);
+        }
+    }
+
+    /// Send a test message through an integration to its default channel
+    /// (app mode) or its webhook.
+    pub async fn send_test(&self, id: Uuid, actor: &str) -> Result<()> {
+        let target = self.target(id).await?;
+        let event = DomainEvent::now("test", id.to_string(), Some(actor.to_string()));
+        let channel = match ChatMode::parse(&target.mode) {
+            Some(ChatMode::App) => target
+                .default_channel
+                .clone()
+                .filter(|c| !c.is_empty())
+                .ok_or_else(|| {
+                    AppError::Validation("Set a default_channel to send a test message".to_string())
+                })?,
+            _ => String::new(),
+        };
+        self.post(&target, &channel, &event, None).await.map(|_| ())
+    }
+
+    /// Deliver one event to every enabled integration with a matching rule.
+    pub async fn dispatch(&self, event: &DomainEvent) -> Result<()> {
+        let targets: Vec<IntegrationTarget> =
+            sqlx::query_as(&format!("{} WHERE is_enabled = true", SELECT_TARGET))
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+
+        for target in &targets {
+            let Some(mode) = ChatMode::parse(&target.mode) else {
+                continue;
+            };
+            let rules = self.list_rules(target.id).await?;
+            let channels = resolve_channels(mode, target.default_channel.as_deref(), &rules, event);
+            for channel in channels {
+                if let Err(e) = self.deliver_threaded(target, mode, &channel, event).await {
+                    tracing::warn!(
+                        integration_id = %target.id,
+                        event_type = %event.event_type,
+                        "Chat notification delivery failed: {}",
+                        e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn deliver_threaded(
+        &self,
+        target: &IntegrationTarget,
+        mode: ChatMode,
+        channel: &str,
+        event: &DomainEvent,
+    ) -> Result<()> {
+        let key = match mode {
+            ChatMode::App => thread_key(event),
+            ChatMode::Webhook => None,
+        };
+        let parent = match &key {
+            Some(k) => self.thread_parent(target.id, channel, k).await?,
+            None => None,
+        };
+        let message_ref = self.post(target, channel, event, parent.as_deref()).await?;
+
+        if let Some(k) = key {
+            if closes_thread(&event.event_type) {
+                self.forget_thread(target.id, channel, &k).await?;
+            } else if let (None, Some(r)) = (&parent, message_ref) {
+                self.remember_thread(target.id, channel, &k, &r).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn thread_parent(&self, id: Uuid, channel: &str, key: &str) -> Result<Option<String>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT message_ref FROM chat_message_threads
+            WHERE integration_id = $1 AND channel = $2 AND thread_key = $3
+            "#,
+        )
+        .bind(id)
+        .bind(channel)
+        .bind(key)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    async fn remember_thread(
+        &self,
+        id: Uuid,
+        channel: &str,
+        key: &str,
+        message_ref: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO chat_message_threads (integration_id, channel, thread_key, message_ref)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (integration_id, channel, thread_key)
+            DO UPDATE SET message_ref = EXCLUDED.message_ref, created_at = NOW()
+            "#,
+        )
+        .bind(id)
+        .bind(channel)
+        .bind(key)
+        .bind(message_ref)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn forget_thread(&self, id: Uuid, channel: &str, key: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM chat_message_threads
+            WHERE integration_id = $1 AND channel = $2 AND thread_key = $3
+            "#,
+        )
+        .bind(id)
+        .bind(channel)
+        .bind(key)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Post one message. Returns the platform's message reference in app
+    /// mode (Slack `ts`, Teams activity ID) so later events can reply to it.
+    async fn post(
+        &self,
+        target: &IntegrationTarget,
+        channel: &str,
+        event: &DomainEvent,
+        thread_parent: Option<&str>,
+    ) -> Result<Option<String>> {
+        let platform = ChatPlatform::parse(&target.platform).ok_or_else(|| {
+            AppError::Internal(format!("unknown chat platform '{}'", target.platform))
+        })?;
+        let mode = ChatMode::parse(&target.mode)
+            .ok_or_else(|| AppError::Internal(format!("unknown chat mode '{}'", target.mode)))?;
+
+        match (platform, mode) {
+            (ChatPlatform::Slack, ChatMode::Webhook) => {
+                let url = decrypt(&target.webhook_url_encrypted, "webhook URL")?;
+                self.post_json(&url, None, &render_slack_message(event, false))
+                    .await?;
+                Ok(None)
+            }
+            (ChatPlatform::Slack, ChatMode::App) => {
+                let token = decrypt(&target.token_encrypted, "bot token")?;
+                let mut body =
+                    render_slack_message(event, target.signing_secret_encrypted.is_some());
+                body["channel"] = serde_json::json!(channel);
+                if let Some(ts) = thread_parent {
+                    body["thread_ts"] = serde_json::json!(ts);
+                }
+                let resp = self
+                    .post_json(
+                        &format!("{}/chat.postMessage", SLACK_API_BASE),
+                        Some(&token),
+                        &body,
+                    )
+                    .await?;
+                if resp.get("ok").and_then(|v| v.as_bool()) != Some(true) {
+                    return Err(AppError::Internal(format!(
+                        "Slack chat.postMessage failed: {}",
+                        resp.get("error")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown error")
+                    )));
+                }
+                Ok(resp.get("ts").and_then(|v| v.as_str()).map(str::to_string))
+            }
+            (ChatPlatform::MicrosoftTeams, ChatMode::Webhook) => {
+                let url = decrypt(&target.webhook_url_encrypted, "webhook URL")?;
+                self.post_json(&url, None, &teams_activity(render_teams_card(event)))
+                    .await?;
+                Ok(None)
+            }
+            (ChatPlatform::MicrosoftTeams, ChatMode::App) => {
+                let token = self.teams_token(target).await?;
+                let service_url = target
+                    .app_service_url
+                    .as_deref()
+                    .ok_or_else(|| AppError::Internal("Teams app has no service URL".into()))?
+                    .trim_end_matches('/');
+                let conversation = match thread_parent {
+                    Some(parent) => format!("{};messageid={}", channel, parent),
+                    None => channel.to_string(),
+                };
+                let url = format!(
+                    "{}/v3/conversations/{}/activities",
+                    service_url,
+                    urlencoding::encode(&conversation)
+                );
+                let resp = self
+                    .post_json(
+                        &url,
+                        Some(&token),
+                        &teams_activity(render_teams_card(event)),
+                    )
+                    .await?;
+                Ok(resp.get("id").and_then(|v| v.as_str()).map(str::to_string))
+            }
+        }
+    }
+
+    async fn post_json(
+        &self,
+        url: &str,
+        bearer: Option<&str>,
+        body: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let mut req = self.http.post(url).json(body);
+        if let Some(token) = bearer {
+            req = req.bearer_auth(token);
+        }
+        let resp = req
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("chat delivery failed: {}", e)))?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(AppError::Internal(format!(
+                "chat delivery returned HTTP {}",
+                status
+            )));
+        }
+        // Incoming webhooks answer with plain text ("ok" / "1"); only the
+        // app APIs return JSON.
+        let text = resp.text().await.unwrap_or_default();
+        Ok(serde_json::from_str(&text).unwrap_or(serde_json::Value::Null))
+    }
+
+    /// Bot Framework access token for a Teams app integration, cached until
+    /// shortly before it expires.
+    async fn teams_token(&self, target: &IntegrationTarget) -> Result<String> {
+        let mut cache = self.teams_tokens.lock().await;
+        if let Some((token, expires)) = cache.get(&target.id) {
+            if Instant::now() < *expires {
+                return Ok(token.clone());
+            }
+        }
+
+        let secret = decrypt(&target.token_encrypted, "client secret")?;
+        let app_id = target
+            .app_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Teams app has no app_id".to_string()))?;
+        let tenant = target
+            .app_tenant_id
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .unwrap_or(BOT_FRAMEWORK_DEFAULT_TENANT);
+        let resp: serde_json::Value = self
+            .http
+            .post(format!(
+                "https://login.microsoftonline.com/{}/oauth2/v2.0/token",
+                urlencoding::encode(tenant)
+            ))
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", app_id),
+                ("client_secret", secret.as_str()),
+                ("scope", BOT_FRAMEWORK_SCOPE),
+            ])
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Teams token request failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Teams token request failed: {}", e)))?;
+        let token = resp
+            .get("access_token")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                AppError::Internal("Teams token response had no access_token".to_string())
+            })?
+            .to_string();
+        let ttl = resp
+            .get("expires_in")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(3600)
+            .saturating_sub(300);
+        cache.insert(
+            target.id,
+            (token.clone(), Instant::now() + Duration::from_secs(ttl)),
+        );
+        Ok(token)
+    }
+}
+
+/// Start the chat dispatcher background task.
+///
+/// Listens on the EventBus and delivers each event to matching chat
+/// integrations. The task exits when the broadcast channel closes.
+pub fn start_chat_dispatcher(event_bus: Arc<EventBus>, db: PgPool) {
+    let service = ChatIntegrationService::new(db);
+    let mut rx = event_bus.subscribe();
+
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if let Err(e) = service.dispatch(&event).await {
+                        tracing::warn!(
+                            event_type = %event.event_type,
+                            error = %e,
+                            "Failed to dispatch chat notification"
+                        );
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!(
+                        skipped = n,
+                        "Chat dispatcher lagged, some events were dropped"
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    tracing::info!("EventBus closed, chat dispatcher shutting down");
+                    break;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: &str, repo: Option<Uuid>) -> DomainEvent {
+        DomainEvent {
+            event_type: event_type.to_string(),
+            entity_id: "11111111-1111-1111-1111-111111111111".to_string(),
+            repository_id: repo,
+            actor: Some("alice".to_string()),
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn rule(event_types: &[&str], repo: Option<Uuid>, channel: Option<&str>) -> ChatRoutingRule {
+        ChatRoutingRule {
+            id: Uuid::new_v4(),
+            integration_id: Uuid::nil(),
+            event_types: event_types.iter().map(|s| s.to_string()).collect(),
+            repository_id: repo,
+            channel: channel.map(str::to_string),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn create_req(platform: ChatPlatform, mode: ChatMode) -> CreateChatIntegration {
+        CreateChatIntegration {
+            name: "ops".to_string(),
+            platform,
+            mode,
+            webhook_url: None,
+            token: None,
+            signing_secret: None,
+            default_channel: None,
+            app_id: None,
+            app_tenant_id: None,
+            app_service_url: None,
+            rules: vec![],
+        }
+    }
+
+    #[test]
+    fn test_platform_and_mode_round_trip() {
+        for p in [ChatPlatform::Slack, ChatPlatform::MicrosoftTeams] {
+            assert_eq!(ChatPlatform::parse(p.as_str()), Some(p));
+        }
+        for m in [ChatMode::Webhook, ChatMode::App] {
+            assert_eq!(ChatMode::parse(m.as_str()), Some(m));
+        }
+        assert_eq!(ChatPlatform::parse("discord"), None);
+    }
+
+    #[test]
+    fn test_event_type_wildcard() {
+        assert!(event_type_matches("scan.*", "scan.completed"));
+        assert!(event_type_matches("scan.completed", "scan.completed"));
+        assert!(!event_type_matches("scan.*", "scanner.completed"));
+        assert!(!event_type_matches("scan.started", "scan.completed"));
+    }
+
+    #[test]
+    fn test_rule_matches_repository_scope() {
+        let repo = Uuid::new_v4();
+        let types = vec!["scan.*".to_string()];
+        assert!(rule_matches(&types, Some(repo), "scan.started", Some(repo)));
+        assert!(!rule_matches(
+            &types,
+            Some(repo),
+            "scan.started",
+            Some(Uuid::new_v4())
+        ));
+        assert!(!rule_matches(&types, Some(repo), "scan.started", None));
+        assert!(rule_matches(&[], None, "user.created", None));
+    }
+
+    #[test]
+    fn test_resolve_channels_app_mode() {
+        let repo = Uuid::new_v4();
+        let rules = vec![
+            rule(&["scan.*"], None, Some("C-SEC")),
+            rule(&[], Some(repo), None),
+            rule(&["scan.completed"], None, Some("C-SEC")),
+        ];
+        let ev = event("scan.completed", Some(repo));
+        assert_eq!(
+            resolve_channels(ChatMode::App, Some("C-DEFAULT"), &rules, &ev),
+            vec!["C-SEC".to_string(), "C-DEFAULT".to_string()]
+        );
+        // No default: the rule without a channel is dropped.
+        assert_eq!(
+            resolve_channels(ChatMode::App, None, &rules, &ev),
+            vec!["C-SEC".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_resolve_channels_webhook_mode_posts_once() {
+        let rules = vec![rule(&["scan.*"], None, None), rule(&[], None, None)];
+        let ev = event("scan.started", None);
+        assert_eq!(
+            resolve_channels(ChatMode::Webhook, None, &rules, &ev),
+            vec![String::new()]
+        );
+        assert!(resolve_channels(ChatMode::Webhook, None, &[], &ev).is_empty());
+    }
+
+    #[test]
+    fn test_thread_key_groups_related_events() {
+        let started = event("scan.started", None);
+        let completed = event("scan.completed", None);
+        assert_eq!(thread_key(&started), thread_key(&completed));
+        assert!(thread_key(&started).unwrap().starts_with("scan:"));
+        assert_eq!(thread_key(&event("repository.created", None)), None);
+        assert!(closes_thread("scan.completed"));
+        assert!(closes_thread("approval.rejected"));
+        assert!(!closes_thread("scan.started"));
+        assert!(!closes_thread("approval.requested"));
+    }
+
+    #[test]
+    fn test_slack_message_buttons_only_for_interactive_approvals() {
+        let req = event("approval.requested", None);
+        let msg = render_slack_message(&req, true);
+        let blocks = msg["blocks"].as_array().unwrap();
+        let actions = blocks.iter().find(|b| b["type"] == "actions").unwrap();
+        assert_eq!(actions["elements"][0]["action_id"], SLACK_ACTION_APPROVE);
+        assert_eq!(actions["elements"][1]["action_id"], SLACK_ACTION_REJECT);
+        assert_eq!(actions["elements"][0]["value"], req.entity_id.as_str());
+
+        let plain = render_slack_message(&req, false);
+        assert!(!plain["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|b| b["type"] == "actions"));
+
+        let scan = render_slack_message(&event("scan.started", None), true);
+        assert!(!scan["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|b| b["type"] == "actions"));
+    }
+
+    #[test]
+    fn test_teams_activity_wraps_adaptive_card() {
+        let activity = teams_activity(render_teams_card(&event("scan.failed", None)));
+        assert_eq!(activity["type"], "message");
+        let attachment = &activity["attachments"][0];
+        assert_eq!(
+            attachment["contentType"],
+            "application/vnd.microsoft.card.adaptive"
+        );
+        assert_eq!(attachment["content"]["type"], "AdaptiveCard");
+        assert!(attachment["content"]["body"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("scan failed"));
+    }
+
+    fn slack_sign(secret: &str, ts: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("v0:{}:", ts).as_bytes());
+        mac.update(body);
+        format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_verify_slack_signature() {
+        let body = b"payload=%7B%7D";
+        let sig = slack_sign("s3cret", "1700000000", body);
+        assert!(verify_slack_signature(
+            "s3cret",
+            "1700000000",
+            body,
+            &sig,
+            1700000010
+        ));
+        // Wrong secret, tampered body, stale timestamp, malformed header.
+        assert!(!verify_slack_signature(
+            "other",
+            "1700000000",
+            body,
+            &sig,
+            1700000010
+        ));
+        assert!(!verify_slack_signature(
+            "s3cret",
+            "1700000000",
+            b"payload=x",
+            &sig,
+            1700000010
+        ));
+        assert!(!verify_slack_signature(
+            "s3cret",
+            "1700000000",
+            body,
+            &sig,
+            1700000000 + SLACK_SIGNATURE_MAX_AGE_SECS + 1
+        ));
+        assert!(!verify_slack_signature(
+            "s3cret",
+            "1700000000",
+            body,
+            "deadbeef",
+            1700000010
+        ));
+        assert!(!verify_slack_signature(
+            "s3cret", "not-a-ts", body, &sig, 1700000010
+        ));
+    }
+
+    #[test]
+    fn test_parse_slack_interaction() {
+        let approval_id = Uuid::new_v4();
+        let payload = serde_json::json!({
+            "type": "block_actions",
+            "user": { "id": "U123" },
+            "response_url": "https://hooks.slack.com/actions/x",
+            "actions": [{ "action_id": SLACK_ACTION_REJECT, "value": approval_id.to_string() }],
+        });
+        let action = parse_slack_interaction(&payload).unwrap();
+        assert_eq!(action.decision, ApprovalDecision::Reject);
+        assert_eq!(action.approval_id, approval_id);
+        assert_eq!(action.slack_user_id, "U123");
+        assert!(action.response_url.is_some());
+
+        let other = serde_json::json!({
+            "type": "block_actions",
+            "user": { "id": "U123" },
+            "actions": [{ "action_id": "something_else", "value": approval_id.to_string() }],
+        });
+        assert!(parse_slack_interaction(&other).is_none());
+        assert!(parse_slack_interaction(&serde_json::json!({"type": "view_submission"})).is_none());
+    }
+
+    #[test]
+    fn test_validate_new_integration_mode_requirements() {
+        let mut webhook = create_req(ChatPlatform::Slack, ChatMode::Webhook);
+        assert!(validate_new_integration(&webhook).is_err());
+        webhook.webhook_url = Some("https://hooks.slack.com/services/x".to_string());
+        assert!(validate_new_integration(&webhook).is_ok());
+        webhook.signing_secret = Some("s".to_string());
+        assert!(validate_new_integration(&webhook).is_err());
+
+        let mut app = create_req(ChatPlatform::Slack, ChatMode::App);
+        assert!(validate_new_integration(&app).is_err());
+        app.token = Some("xoxb-1".to_string());
+        assert!(validate_new_integration(&app).is_ok());
+        app.rules = vec![ChatRoutingRuleInput::default()];
+        assert!(validate_new_integration(&app).is_err());
+        app.default_channel = Some("C1".to_string());
+        assert!(validate_new_integration(&app).is_ok());
+
+        let mut teams = create_req(ChatPlatform::MicrosoftTeams, ChatMode::App);
+        teams.token = Some("secret".to_string());
+        assert!(validate_new_integration(&teams).is_err());
+        teams.app_id = Some("app".to_string());
+        teams.app_service_url = Some("https://smba.trafficmanager.net/amer/".to_string());
+        assert!(validate_new_integration(&teams).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rules_patterns() {
+        let ok = ChatRoutingRuleInput {
+            event_types: vec!["scan.*".to_string(), "approval.requested".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_rules(&[ok]).is_ok());
+        for bad in ["", "*", "scan*", "Scan.started", "a.*.b", "scan.**"] {
+            let rule = ChatRoutingRuleInput {
+                event_types: vec![bad.to_string()],
+                ..Default::default()
+            };
+            assert!(
+                validate_rules(&[rule]).is_err(),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+}
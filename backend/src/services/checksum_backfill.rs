@@ -0,0 +1,118 @@
+//! Backfill of SHA-512 and BLAKE3 digests.
+//!
+//! Uploads through `ArtifactService` record SHA-512 and BLAKE3 at ingest.
+//! Artifacts stored before that, or inserted directly by format handlers,
+//! have neither until this job re-hashes their blob. It runs in small batches
+//! from the scheduler, oldest first, and only records digests when the blob
+//! still hashes to the row's SHA-256.
+
+use std::collections::HashSet;
+
+use futures::StreamExt;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::services::artifact_service::MultiHasher;
+use crate::storage::{StorageLocation, StorageRegistry};
+
+/// Artifacts re-hashed per run.
+pub const BATCH_SIZE: i64 = 200;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillStats {
+    pub updated: u64,
+    pub failed: u64,
+}
+
+#[derive(sqlx::FromRow)]
+struct PendingRow {
+    id: Uuid,
+    storage_key: String,
+    checksum_sha256: String,
+    storage_backend: String,
+    storage_path: String,
+}
+
+/// Re-hash one batch of artifacts lacking SHA-512 or BLAKE3.
+///
+/// Artifacts whose blob cannot be read or does not match its SHA-256 are
+/// added to `skip` so later batches in this process move past them.
+pub async fn run_batch(
+    db: &PgPool,
+    registry: &StorageRegistry,
+    skip: &mut HashSet<Uuid>,
+) -> Result<BackfillStats> {
+    let skipped: Vec<Uuid> = skip.iter().copied().collect();
+    let rows = sqlx::query_as::<_, PendingRow>(
+        r#"
+        SELECT a.id, a.storage_key, a.checksum_sha256, r.storage_backend, r.storage_path
+        FROM artifacts a
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE a.is_deleted = false
+          AND (a.checksum_sha512 IS NULL OR a.checksum_blake3 IS NULL)
+          AND NOT (a.id = ANY($2))
+        ORDER BY a.created_at
+        LIMIT $1
+        "#,
+    )
+    .bind(BATCH_SIZE)
+    .bind(&skipped)
+    .fetch_all(db)
+    .await?;
+
+    let mut stats = BackfillStats::default();
+    for row in rows {
+        match rehash(db, registry, &row).await {
+            Ok(true) => stats.updated += 1,
+            Ok(false) => {
+                tracing::warn!(
+                    artifact_id = %row.id,
+                    "Stored content does not match the artifact's SHA-256; not backfilling"
+                );
+                skip.insert(row.id);
+                stats.failed += 1;
+            }
+            Err(e) => {
+                tracing::debug!(artifact_id = %row.id, "Checksum backfill failed: {}", e);
+                skip.insert(row.id);
+                stats.failed += 1;
+            }
+        }
+    }
+    Ok(stats)
+}
+
+async fn rehash(db: &PgPool, registry: &StorageRegistry, row: &PendingRow) -> Result<bool> {
+    let storage = registry.backend_for(&StorageLocation {
+        backend: row.storage_backend.clone(),
+        path: row.storage_path.clone(),
+    })?;
+    let mut stream = storage.get_stream(&row.storage_key).await?;
+    let mut hasher = MultiHasher::new();
+    while let Some(chunk) = stream.next().await {
+        hasher.update(&chunk?);
+    }
+    let digests = hasher.finalize();
+    if !digests
+        .sha256
+        .eq_ignore_ascii_case(row.checksum_sha256.trim())
+    {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE artifacts
+        SET checksum_sha512 = COALESCE(checksum_sha512, $2),
+            checksum_blake3 = COALESCE(checksum_blake3, $3)
+        WHERE id = $1
+        "#,
+    )
+    .bind(row.id)
+    .bind(&digests.sha512)
+    .bind(&digests.blake3)
+    .execute(db)
+    .await?;
+    Ok(true)
+}
@@ -0,0 +1,1502 @@
+//! OpenSCAP / CIS benchmark compliance scans for container images.
+//!
+//! The vulnerability pipeline hands the OpenSCAP sidecar only the artifact
+//! bytes, which for an OCI image is the manifest JSON. A compliance scan
+//! instead assembles the image's root filesystem from its stored layers in
+//! the shared scan workspace and asks the wrapper to evaluate an XCCDF
+//! profile against it, keeping every rule result:
+//!
+//! 1. [`ComplianceScanner::start_scan`] validates the image and records a
+//!    `running` row (one in-flight scan per image, profile and content);
+//! 2. [`ComplianceScanner::execute`] resolves an image index to the runner
+//!    platform's child, streams each layer blob out of storage, applies it
+//!    (whiteouts included) under `{workspace}/compliance-{scan_id}/rootfs`,
+//!    calls the wrapper's `POST /scan` with `all_results` and stores the
+//!    per-rule results, counts and benchmark score;
+//! 3. [`ComplianceScanner::run_due_schedules`] re-scans the newest tagged
+//!    image of every repository in scope of profiles that carry a
+//!    `schedule_interval_hours`.
+//!
+//! Extraction reuses the scan-workspace ceilings of the vulnerability
+//! pipeline (`MAX_SCAN_EXTRACTED_BYTES`, entry count). File ownership is not
+//! preserved (the backend does not run as root), directories are kept
+//! owner-writable so the workspace can be removed, and device nodes are
+//! skipped; CIS rules that inspect ownership or devices should therefore be
+//! read with that in mind.
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::io::AsyncWriteExt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::formats::oci::OciHandler;
+use crate::models::artifact::Artifact;
+use crate::services::scanner_service::{
+    copy_entry_bounded, is_oci_image_artifact, max_scan_extracted_bytes,
+    oci_manifest_is_scannable_image, positive_env_or, resolve_scan_reference,
+    ScanReferenceResolution, MAX_SCAN_EXTRACTED_ENTRIES,
+};
+use crate::storage::{StorageBackend, StorageLocation, StorageRegistry};
+
+/// Env var capping the summed (compressed) layer size of an image accepted
+/// for compliance scanning, in bytes.
+const MAX_IMAGE_BYTES_ENV: &str = "COMPLIANCE_SCAN_MAX_IMAGE_BYTES";
+const DEFAULT_MAX_IMAGE_BYTES: u64 = 8 * 1024 * 1024 * 1024;
+
+/// `oscap` over a full distribution rootfs takes minutes; allow for large
+/// benchmark profiles.
+const WRAPPER_TIMEOUT: Duration = Duration::from_secs(1800);
+
+/// Running scans older than this are assumed to belong to a crashed or
+/// restarted instance and are marked failed.
+const STALE_SCAN_AFTER_MINUTES: i64 = 120;
+
+/// Symlinks followed while resolving a path inside the extracted rootfs
+/// before giving up (matches the kernel's MAXSYMLINKS).
+const MAX_SYMLINK_HOPS: usize = 40;
+
+const MAX_NAME_LEN: usize = 255;
+const MAX_CONTENT_LABEL_LEN: usize = 64;
+
+/// Repository formats whose manifests are runnable container images.
+const IMAGE_FORMATS: &[&str] = &["docker", "podman", "buildx", "oras"];
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// Create or replace a compliance profile.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ComplianceProfileRequest {
+    pub name: String,
+    /// XCCDF profile id, e.g. `xccdf_org.ssgproject.content_profile_cis`.
+    pub xccdf_profile: String,
+    /// SCAP content label (e.g. `rhel9`, `ubuntu2204`). Omit to pick the
+    /// content matching each image's `/etc/os-release`.
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Restrict scheduled scans to one repository. Omit for every image
+    /// repository.
+    #[serde(default)]
+    pub repository_id: Option<Uuid>,
+    /// Re-scan the newest image of each repository in scope this often.
+    /// Omit for on-demand only.
+    #[serde(default)]
+    pub schedule_interval_hours: Option<i32>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A named XCCDF profile configuration.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ComplianceProfile {
+    pub id: Uuid,
+    pub name: String,
+    pub xccdf_profile: String,
+    pub content: Option<String>,
+    pub description: Option<String>,
+    pub repository_id: Option<Uuid>,
+    pub repository_key: Option<String>,
+    pub schedule_interval_hours: Option<i32>,
+    pub enabled: bool,
+    pub last_scheduled_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One evaluated XCCDF rule.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ComplianceRuleResult {
+    pub rule_id: String,
+    /// `pass`, `fail`, `error`, `unknown`, `notapplicable`, `notchecked`,
+    /// `notselected`, `informational` or `fixed`.
+    pub result: String,
+    pub severity: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub references: Vec<String>,
+}
+
+/// Overall outcome of a compliance scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ComplianceVerdict {
+    /// Still running.
+    Running,
+    /// Every evaluated rule passed or did not apply.
+    Pass,
+    /// At least one rule failed.
+    Fail,
+    /// The scan failed, or some rules could not be evaluated and none failed.
+    Error,
+}
+
+/// Derive the verdict from a scan's status and counts.
+pub fn verdict(status: &str, failed: i32, errored: i32) -> ComplianceVerdict {
+    match status {
+        "running" => ComplianceVerdict::Running,
+        "completed" if failed > 0 => ComplianceVerdict::Fail,
+        "completed" if errored > 0 => ComplianceVerdict::Error,
+        "completed" => ComplianceVerdict::Pass,
+        _ => ComplianceVerdict::Error,
+    }
+}
+
+/// Pass/fail summary of one compliance scan.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComplianceScanSummary {
+    pub id: Uuid,
+    pub artifact_id: Uuid,
+    pub artifact_name: String,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub compliance_profile_id: Option<Uuid>,
+    pub profile_name: Option<String>,
+    pub xccdf_profile: String,
+    pub content: Option<String>,
+    /// `manual` or `schedule`.
+    pub trigger_type: String,
+    /// `running`, `completed` or `failed`.
+    pub status: String,
+    pub verdict: ComplianceVerdict,
+    pub passed: i32,
+    pub failed: i32,
+    /// Rules that could not be evaluated (`error` / `unknown`).
+    pub errored: i32,
+    /// Rules that did not apply or were informational.
+    pub other: i32,
+    /// XCCDF benchmark score (0-100) when reported.
+    pub score: Option<f64>,
+    pub error_message: Option<String>,
+    pub started_by: Option<Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Full compliance report: the summary plus every rule result.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComplianceReport {
+    #[serde(flatten)]
+    pub summary: ComplianceScanSummary,
+    pub results: Vec<ComplianceRuleResult>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ScanRow {
+    id: Uuid,
+    artifact_id: Uuid,
+    artifact_name: String,
+    repository_id: Uuid,
+    repository_key: String,
+    compliance_profile_id: Option<Uuid>,
+    profile_name: Option<String>,
+    xccdf_profile: String,
+    content: Option<String>,
+    trigger_type: String,
+    status: String,
+    passed: i32,
+    failed: i32,
+    errored: i32,
+    other: i32,
+    score: Option<f64>,
+    error_message: Option<String>,
+    started_by: Option<Uuid>,
+    started_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<ScanRow> for ComplianceScanSummary {
+    fn from(row: ScanRow) -> Self {
+        Self {
+            verdict: verdict(&row.status, row.failed, row.errored),
+            id: row.id,
+            artifact_id: row.artifact_id,
+            artifact_name: row.artifact_name,
+            repository_id: row.repository_id,
+            repository_key: row.repository_key,
+            compliance_profile_id: row.compliance_profile_id,
+            profile_name: row.profile_name,
+            xccdf_profile: row.xccdf_profile,
+            content: row.content,
+            trigger_type: row.trigger_type,
+            status: row.status,
+            passed: row.passed,
+            failed: row.failed,
+            errored: row.errored,
+            other: row.other,
+            score: row.score,
+            error_message: row.error_message,
+            started_by: row.started_by,
+            started_at: row.started_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+/// An XCCDF profile offered by the sidecar's SCAP content.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AvailableProfile {
+    pub id: String,
+    pub title: String,
+}
+
+/// Profiles available per SCAP content label.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AvailableProfiles {
+    pub content: std::collections::BTreeMap<String, Vec<AvailableProfile>>,
+}
+
+/// Rule counts of a finished scan.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RuleCounts {
+    pub passed: i32,
+    pub failed: i32,
+    pub errored: i32,
+    pub other: i32,
+}
+
+/// Count rule results by outcome.
+pub fn tally(results: &[ComplianceRuleResult]) -> RuleCounts {
+    let mut counts = RuleCounts::default();
+    for rule in results {
+        match rule.result.as_str() {
+            "pass" => counts.passed += 1,
+            "fail" => counts.failed += 1,
+            "error" | "unknown" => counts.errored += 1,
+            _ => counts.other += 1,
+        }
+    }
+    counts
+}
+
+#[derive(Debug, Deserialize)]
+struct WrapperScanResponse {
+    #[serde(default)]
+    results: Option<Vec<ComplianceRuleResult>>,
+    #[serde(default)]
+    score: Option<f64>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Profiles
+// ---------------------------------------------------------------------------
+
+const PROFILE_COLUMNS: &str = r#"
+    p.id, p.name, p.xccdf_profile, p.content, p.description, p.repository_id,
+    r.key AS repository_key, p.schedule_interval_hours, p.enabled,
+    p.last_scheduled_at, p.created_by, p.created_at, p.updated_at
+"#;
+
+const SCAN_COLUMNS: &str = r#"
+    cs.id, cs.artifact_id, a.name AS artifact_name, cs.repository_id,
+    r.key AS repository_key, cs.compliance_profile_id, p.name AS profile_name,
+    cs.xccdf_profile, cs.content, cs.trigger_type, cs.status, cs.passed,
+    cs.failed, cs.errored, cs.other, cs.score, cs.error_message, cs.started_by,
+    cs.started_at, cs.completed_at
+"#;
+
+const SCAN_JOINS: &str = r#"
+    FROM compliance_scans cs
+    JOIN artifacts a ON a.id = cs.artifact_id
+    JOIN repositories r ON r.id = cs.repository_id
+    LEFT JOIN compliance_profiles p ON p.id = cs.compliance_profile_id
+"#;
+
+fn db_err(e: sqlx::Error) -> AppError {
+    AppError::Database(e.to_string())
+}
+
+/// XCCDF profile ids and content labels are passed to `oscap`; the wrapper
+/// rejects anything outside this alphabet, so reject it up front too.
+pub fn is_valid_scap_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+fn validate_profile_request(request: &ComplianceProfileRequest) -> Result<()> {
+    let name = request.name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "name must be between 1 and {} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    if request.xccdf_profile.len() > MAX_NAME_LEN
+        || !is_valid_scap_identifier(&request.xccdf_profile)
+    {
+        return Err(AppError::Validation(
+            "xccdf_profile may only contain letters, digits, '.', '_' and '-'".to_string(),
+        ));
+    }
+    if let Some(content) = &request.content {
+        if content.len() > MAX_CONTENT_LABEL_LEN || !is_valid_scap_identifier(content) {
+            return Err(AppError::Validation(
+                "content may only contain letters, digits, '.', '_' and '-'".to_string(),
+            ));
+        }
+    }
+    if request.schedule_interval_hours.is_some_and(|h| h <= 0) {
+        return Err(AppError::Validation(
+            "schedule_interval_hours must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn profile_conflict(e: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db) = &e {
+        if db.is_unique_violation() {
+            return AppError::Conflict(
+                "A compliance profile with this name already exists".to_string(),
+            );
+        }
+        if db.is_foreign_key_violation() {
+            return AppError::NotFound("Repository not found".to_string());
+        }
+    }
+    db_err(e)
+}
+
+/// All compliance profiles, by name.
+pub async fn list_profiles(db: &PgPool) -> Result<Vec<ComplianceProfile>> {
+    let sql = format!(
+        "SELECT {} FROM compliance_profiles p \
+         LEFT JOIN repositories r ON r.id = p.repository_id \
+         ORDER BY p.name",
+        PROFILE_COLUMNS
+    );
+    sqlx::query_as(&sql).fetch_all(db).await.map_err(db_err)
+}
+
+pub async fn get_profile(db: &PgPool, id: Uuid) -> Result<ComplianceProfile> {
+    let sql = format!(
+        "SELECT {} FROM compliance_profiles p \
+         LEFT JOIN repositories r ON r.id = p.repository_id \
+         WHERE p.id = $1",
+        PROFILE_COLUMNS
+    );
+    sqlx::query_as(&sql)
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(db_err)?
+        .ok_or_else(|| AppError::NotFound("Compliance profile not found".to_string()))
+}
+
+pub async fn create_profile(
+    db: &PgPool,
+    request: &ComplianceProfileRequest,
+    created_by: Uuid,
+) -> Result<ComplianceProfile> {
+    validate_profile_request(request)?;
+    let id: Uuid = sqlx::query_scalar(
+        "INSERT INTO compliance_profiles \
+         (name, xccdf_profile, content, description, repository_id, \
+          schedule_interval_hours, enabled, created_by) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+    )
+    .bind(request.name.trim())
+    .bind(&request.xccdf_profile)
+    .bind(&request.content)
+    .bind(&request.description)
+    .bind(request.repository_id)
+    .bind(request.schedule_interval_hours)
+    .bind(request.enabled)
+    .bind(created_by)
+    .fetch_one(db)
+    .await
+    .map_err(profile_conflict)?;
+    get_profile(db, id).await
+}
+
+pub async fn update_profile(
+    db: &PgPool,
+    id: Uuid,
+    request: &ComplianceProfileRequest,
+) -> Result<ComplianceProfile> {
+    validate_profile_request(request)?;
+    let updated = sqlx::query(
+        "UPDATE compliance_profiles SET name = $2, xccdf_profile = $3, content = $4, \
+         description = $5, repository_id = $6, schedule_interval_hours = $7, \
+         enabled = $8, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(id)
+    .bind(request.name.trim())
+    .bind(&request.xccdf_profile)
+    .bind(&request.content)
+    .bind(&request.description)
+    .bind(request.repository_id)
+    .bind(request.schedule_interval_hours)
+    .bind(request.enabled)
+    .execute(db)
+    .await
+    .map_err(profile_conflict)?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "Compliance profile not found".to_string(),
+        ));
+    }
+    get_profile(db, id).await
+}
+
+/// Delete a profile. Past scans keep their snapshot of the profile id.
+pub async fn delete_profile(db: &PgPool, id: Uuid) -> Result<()> {
+    let deleted = sqlx::query("DELETE FROM compliance_profiles WHERE id = $1")
+        .bind(id)
+        .execute(db)
+        .await
+        .map_err(db_err)?;
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "Compliance profile not found".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Scan reads
+// ---------------------------------------------------------------------------
+
+/// Compliance scans of one image, newest first.
+pub async fn list_artifact_scans(
+    db: &PgPool,
+    artifact_id: Uuid,
+    limit: i64,
+) -> Result<Vec<ComplianceScanSummary>> {
+    let sql = format!(
+        "SELECT {} {} WHERE cs.artifact_id = $1 ORDER BY cs.started_at DESC LIMIT $2",
+        SCAN_COLUMNS, SCAN_JOINS
+    );
+    let rows: Vec<ScanRow> = sqlx::query_as(&sql)
+        .bind(artifact_id)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+        .map_err(db_err)?;
+    Ok(rows.into_iter().map(ComplianceScanSummary::from).collect())
+}
+
+/// Latest finished scan per image and XCCDF profile, optionally limited to
+/// one repository or profile.
+pub async fn list_latest_scans(
+    db: &PgPool,
+    repository_id: Option<Uuid>,
+    compliance_profile_id: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<ComplianceScanSummary>> {
+    let sql = format!(
+        "SELECT * FROM ( \
+           SELECT DISTINCT ON (cs.artifact_id, cs.xccdf_profile, COALESCE(cs.content, '')) {} {} \
+           WHERE cs.status <> 'running' AND NOT a.is_deleted \
+             AND ($1::uuid IS NULL OR cs.repository_id = $1) \
+             AND ($2::uuid IS NULL OR cs.compliance_profile_id = $2) \
+           ORDER BY cs.artifact_id, cs.xccdf_profile, COALESCE(cs.content, ''), \
+                    cs.started_at DESC \
+         ) latest ORDER BY started_at DESC LIMIT $3",
+        SCAN_COLUMNS, SCAN_JOINS
+    );
+    let rows: Vec<ScanRow> = sqlx::query_as(&sql)
+        .bind(repository_id)
+        .bind(compliance_profile_id)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+        .map_err(db_err)?;
+    Ok(rows.into_iter().map(ComplianceScanSummary::from).collect())
+}
+
+pub async fn get_scan(db: &PgPool, id: Uuid) -> Result<ComplianceScanSummary> {
+    let sql = format!("SELECT {} {} WHERE cs.id = $1", SCAN_COLUMNS, SCAN_JOINS);
+    let row: Option<ScanRow> = sqlx::query_as(&sql)
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(db_err)?;
+    row.map(ComplianceScanSummary::from)
+        .ok_or_else(|| AppError::NotFound("Compliance scan not found".to_string()))
+}
+
+/// The scan summary plus every rule result.
+pub async fn get_report(db: &PgPool, id: Uuid) -> Result<ComplianceReport> {
+    let summary = get_scan(db, id).await?;
+    let results: serde_json::Value =
+        sqlx::query_scalar("SELECT results FROM compliance_scans WHERE id = $1")
+            .bind(id)
+            .fetch_one(db)
+            .await
+            .map_err(db_err)?;
+    let results = serde_json::from_value(results).unwrap_or_default();
+    Ok(ComplianceReport { summary, results })
+}
+
+/// Mark running scans that outlived [`STALE_SCAN_AFTER_MINUTES`] as failed,
+/// e.g. after the instance running them restarted.
+pub async fn fail_stale_scans(db: &PgPool) -> Result<u64> {
+    let result = sqlx::query(
+        "UPDATE compliance_scans SET status = 'failed', completed_at = NOW(), \
+         error_message = 'Scan did not finish (interrupted or timed out)' \
+         WHERE status = 'running' AND started_at < NOW() - make_interval(mins => $1)",
+    )
+    .bind(STALE_SCAN_AFTER_MINUTES as i32)
+    .execute(db)
+    .await
+    .map_err(db_err)?;
+    Ok(result.rows_affected())
+}
+
+// ---------------------------------------------------------------------------
+// Scanner
+// ---------------------------------------------------------------------------
+
+/// What to evaluate in a new scan.
+#[derive(Debug, Clone)]
+pub struct ScanTarget {
+    pub compliance_profile_id: Option<Uuid>,
+    pub xccdf_profile: String,
+    pub content: Option<String>,
+}
+
+impl From<&ComplianceProfile> for ScanTarget {
+    fn from(profile: &ComplianceProfile) -> Self {
+        Self {
+            compliance_profile_id: Some(profile.id),
+            xccdf_profile: profile.xccdf_profile.clone(),
+            content: profile.content.clone(),
+        }
+    }
+}
+
+/// Runs compliance scans through the OpenSCAP wrapper sidecar.
+#[derive(Clone)]
+pub struct ComplianceScanner {
+    db: PgPool,
+    storage_registry: Arc<StorageRegistry>,
+    http: Client,
+    openscap_url: String,
+    scan_workspace: PathBuf,
+}
+
+impl ComplianceScanner {
+    /// `None` when no OpenSCAP sidecar is configured.
+    pub fn new(
+        db: PgPool,
+        storage_registry: Arc<StorageRegistry>,
+        config: &crate::config::Config,
+    ) -> Option<Self> {
+        let openscap_url = config.openscap_url.as_deref()?.trim_end_matches('/');
+        let http = Client::builder()
+            .timeout(WRAPPER_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Some(Self {
+            db,
+            storage_registry,
+            http,
+            openscap_url: openscap_url.to_string(),
+            scan_workspace: PathBuf::from(&config.scan_workspace_path),
+        })
+    }
+
+    /// XCCDF profiles the sidecar's SCAP content offers.
+    pub async fn available_profiles(&self) -> Result<AvailableProfiles> {
+        let response = self
+            .http
+            .get(format!("{}/profiles", self.openscap_url))
+            .timeout(Duration::from_secs(60))
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::ServiceUnavailable(format!("OpenSCAP sidecar unreachable: {}", e))
+            })?;
+        if !response.status().is_success() {
+            return Err(AppError::ServiceUnavailable(format!(
+                "OpenSCAP sidecar does not list profiles (HTTP {}); update the openscap image",
+                response.status()
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Invalid OpenSCAP profiles response: {}", e)))
+    }
+
+    /// Validate the image and record a running scan. The caller runs
+    /// [`Self::execute`] with the returned id.
+    pub async fn start_scan(
+        &self,
+        artifact_id: Uuid,
+        target: &ScanTarget,
+        trigger_type: &str,
+        started_by: Option<Uuid>,
+    ) -> Result<ComplianceScanSummary> {
+        if !is_valid_scap_identifier(&target.xccdf_profile)
+            || target
+                .content
+                .as_deref()
+                .is_some_and(|c| !is_valid_scap_identifier(c))
+        {
+            return Err(AppError::Validation(
+                "Invalid XCCDF profile or SCAP content label".to_string(),
+            ));
+        }
+        let artifact = load_artifact(&self.db, artifact_id).await?;
+        if !is_oci_image_artifact(&artifact) {
+            return Err(AppError::Validation(
+                "Compliance scans are only available for container image manifests".to_string(),
+            ));
+        }
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO compliance_scans \
+             (artifact_id, repository_id, compliance_profile_id, xccdf_profile, content, \
+              trigger_type, started_by) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id",
+        )
+        .bind(artifact.id)
+        .bind(artifact.repository_id)
+        .bind(target.compliance_profile_id)
+        .bind(&target.xccdf_profile)
+        .bind(&target.content)
+        .bind(trigger_type)
+        .bind(started_by)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(db) = &e {
+                if db.is_unique_violation() {
+                    return AppError::Conflict(
+                        "A compliance scan of this image with this profile is already running"
+                            .to_string(),
+                    );
+                }
+            }
+            db_err(e)
+        })?;
+        get_scan(&self.db, id).await
+    }
+
+    /// Run a scan recorded by [`Self::start_scan`] to completion, storing
+    /// either the results or the failure.
+    pub async fn execute(&self, scan_id: Uuid) {
+        let dir = self.scan_workspace.join(format!("compliance-{}", scan_id));
+        let outcome = self.run(scan_id, &dir).await;
+        if let Err(e) = tokio::fs::remove_dir_all(&dir).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!(
+                    scan_id = %scan_id,
+                    path = %dir.display(),
+                    "Failed to clean up compliance scan workspace: {}",
+                    e
+                );
+            }
+        }
+
+        let stored = match outcome {
+            Ok(response) => {
+                let results = response.results.unwrap_or_default();
+                let counts = tally(&results);
+                tracing::info!(
+                    scan_id = %scan_id,
+                    passed = counts.passed,
+                    failed = counts.failed,
+                    "Compliance scan completed"
+                );
+                sqlx::query(
+                    "UPDATE compliance_scans SET status = 'completed', passed = $2, failed = $3, \
+                     errored = $4, other = $5, score = $6, results = $7, \
+                     content = COALESCE(content, $8), completed_at = NOW() \
+                     WHERE id = $1 AND status = 'running'",
+                )
+                .bind(scan_id)
+                .bind(counts.passed)
+                .bind(counts.failed)
+                .bind(counts.errored)
+                .bind(counts.other)
+                .bind(response.score)
+                .bind(serde_json::to_value(&results).unwrap_or_default())
+                .bind(response.content)
+                .execute(&self.db)
+                .await
+            }
+            Err(e) => {
+                let message = match e {
+                    AppError::Validation(m)
+                    | AppError::NotFound(m)
+                    | AppError::Internal(m)
+                    | AppError::Storage(m)
+                    | AppError::ServiceUnavailable(m) => m,
+                    other => other.to_string(),
+                };
+                tracing::warn!(scan_id = %scan_id, "Compliance scan failed: {}", message);
+                sqlx::query(
+                    "UPDATE compliance_scans SET status = 'failed', error_message = $2, \
+                     completed_at = NOW() WHERE id = $1 AND status = 'running'",
+                )
+                .bind(scan_id)
+                .bind(message)
+                .execute(&self.db)
+                .await
+            }
+        };
+        if let Err(e) = stored {
+            tracing::error!(scan_id = %scan_id, "Failed to store compliance scan outcome: {}", e);
+        }
+    }
+
+    async fn run(&self, scan_id: Uuid, dir: &Path) -> Result<WrapperScanResponse> {
+        let (artifact_id, xccdf_profile, content): (Uuid, String, Option<String>) = sqlx::query_as(
+            "SELECT artifact_id, xccdf_profile, content FROM compliance_scans WHERE id = $1",
+        )
+        .bind(scan_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(db_err)?;
+        let artifact = load_artifact(&self.db, artifact_id).await?;
+        let storage = self.resolve_repo_storage(artifact.repository_id).await?;
+
+        let layers = self.image_layers(&artifact, storage.as_ref()).await?;
+        let total: u64 = layers.iter().map(|(_, size)| *size).sum();
+        let max_image_bytes = positive_env_or(MAX_IMAGE_BYTES_ENV, DEFAULT_MAX_IMAGE_BYTES);
+        if total > max_image_bytes {
+            return Err(AppError::Validation(format!(
+                "Image layers total {} bytes, above the {} byte compliance scan limit ({})",
+                total, max_image_bytes, MAX_IMAGE_BYTES_ENV
+            )));
+        }
+
+        let rootfs = dir.join("rootfs");
+        tokio::fs::create_dir_all(&rootfs).await.map_err(|e| {
+            AppError::Storage(format!(
+                "Failed to create compliance workspace {}: {}",
+                rootfs.display(),
+                e
+            ))
+        })?;
+
+        let mut budget = ExtractionBudget::new();
+        for (index, (digest, _)) in layers.iter().enumerate() {
+            let blob_path = dir.join(format!("layer-{}", index));
+            download_blob(
+                storage.as_ref(),
+                &crate::api::handlers::oci_v2::blob_storage_key(digest),
+                &blob_path,
+            )
+            .await?;
+            let layer_root = rootfs.clone();
+            let layer_path = blob_path.clone();
+            budget = tokio::task::spawn_blocking(move || {
+                apply_layer(&layer_path, &layer_root, &mut budget).map(|()| budget)
+            })
+            .await
+            .map_err(|e| AppError::Internal(format!("Layer extraction panicked: {}", e)))??;
+            let _ = tokio::fs::remove_file(&blob_path).await;
+        }
+
+        self.evaluate(&rootfs, &xccdf_profile, content.as_deref())
+            .await
+    }
+
+    /// Digest and size of each layer of the image, resolving an index to the
+    /// runner platform's child manifest.
+    async fn image_layers(
+        &self,
+        artifact: &Artifact,
+        storage: &dyn StorageBackend,
+    ) -> Result<Vec<(String, u64)>> {
+        let mut body = storage.get(&artifact.storage_key).await?;
+        let reference = artifact.version.clone().unwrap_or_default();
+        match resolve_scan_reference(&body, &reference) {
+            ScanReferenceResolution::Passthrough(_) => {}
+            ScanReferenceResolution::ResolvedIndexChild(digest) => {
+                body = storage
+                    .get(&crate::api::handlers::oci_v2::manifest_storage_key(&digest))
+                    .await
+                    .map_err(|e| match e {
+                        AppError::NotFound(_) => AppError::NotFound(format!(
+                            "Platform manifest {} of this index is not stored in the registry",
+                            digest
+                        )),
+                        other => other,
+                    })?;
+            }
+            ScanReferenceResolution::UnresolvableIndex(_) => {
+                return Err(AppError::Validation(
+                    "Image index has no scannable platform image".to_string(),
+                ));
+            }
+        }
+        if !oci_manifest_is_scannable_image(&body) {
+            return Err(AppError::Validation(
+                "Manifest does not describe a container image".to_string(),
+            ));
+        }
+        let manifest = OciHandler::parse_manifest(&body)
+            .map_err(|e| AppError::Validation(format!("Invalid image manifest: {}", e)))?;
+        if manifest.layers.is_empty() {
+            return Err(AppError::Validation(
+                "Image manifest has no layers".to_string(),
+            ));
+        }
+        Ok(manifest
+            .layers
+            .into_iter()
+            .map(|layer| (layer.digest, layer.size.max(0) as u64))
+            .collect())
+    }
+
+    async fn evaluate(
+        &self,
+        rootfs: &Path,
+        xccdf_profile: &str,
+        content: Option<&str>,
+    ) -> Result<WrapperScanResponse> {
+        let response = self
+            .http
+            .post(format!("{}/scan", self.openscap_url))
+            .json(&serde_json::json!({
+                "path": rootfs.to_string_lossy(),
+                "profile": xccdf_profile,
+                "content": content,
+                "all_results": true,
+                "strict_profile": true,
+            }))
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::ServiceUnavailable(format!("OpenSCAP sidecar unreachable: {}", e))
+            })?;
+        let status = response.status();
+        let body: WrapperScanResponse = response.json().await.map_err(|e| {
+            AppError::Internal(format!(
+                "Invalid OpenSCAP response (HTTP {}): {}",
+                status, e
+            ))
+        })?;
+        if let Some(error) = &body.error {
+            return Err(AppError::Internal(format!("OpenSCAP: {}", error)));
+        }
+        if !status.is_success() {
+            return Err(AppError::Internal(format!(
+                "OpenSCAP returned HTTP {}",
+                status
+            )));
+        }
+        if body.results.is_none() {
+            return Err(AppError::Internal(
+                "OpenSCAP sidecar did not return per-rule results; update the openscap image"
+                    .to_string(),
+            ));
+        }
+        Ok(body)
+    }
+
+    async fn resolve_repo_storage(&self, repository_id: Uuid) -> Result<Arc<dyn StorageBackend>> {
+        let (backend, path): (String, String) =
+            sqlx::query_as("SELECT storage_backend, storage_path FROM repositories WHERE id = $1")
+                .bind(repository_id)
+                .fetch_one(&self.db)
+                .await
+                .map_err(db_err)?;
+        self.storage_registry
+            .backend_for(&StorageLocation { backend, path })
+    }
+
+    /// Scan the newest tagged image of every repository in scope of each
+    /// enabled profile whose schedule interval has elapsed. Returns the
+    /// number of scans run.
+    pub async fn run_due_schedules(&self, max_images_per_profile: i64) -> Result<usize> {
+        let sql = format!(
+            "SELECT {} FROM compliance_profiles p \
+             LEFT JOIN repositories r ON r.id = p.repository_id \
+             WHERE p.enabled AND p.schedule_interval_hours IS NOT NULL \
+               AND (p.last_scheduled_at IS NULL \
+                    OR p.last_scheduled_at + make_interval(hours => p.schedule_interval_hours) \
+                       <= NOW()) \
+             ORDER BY p.last_scheduled_at NULLS FIRST",
+            PROFILE_COLUMNS
+        );
+        let profiles: Vec<ComplianceProfile> = sqlx::query_as(&sql)
+            .fetch_all(&self.db)
+            .await
+            .map_err(db_err)?;
+
+        let mut ran = 0;
+        for profile in profiles {
+            // Claim the slot first so a slow or failing run is not retried
+            // on every tick.
+            sqlx::query("UPDATE compliance_profiles SET last_scheduled_at = NOW() WHERE id = $1")
+                .bind(profile.id)
+                .execute(&self.db)
+                .await
+                .map_err(db_err)?;
+
+            let images =
+                latest_images(&self.db, profile.repository_id, max_images_per_profile).await?;
+            let target = ScanTarget::from(&profile);
+            for artifact_id in images {
+                match self
+                    .start_scan(artifact_id, &target, "schedule", None)
+                    .await
+                {
+                    Ok(scan) => {
+                        self.execute(scan.id).await;
+                        ran += 1;
+                    }
+                    Err(AppError::Conflict(_)) => {}
+                    Err(e) => tracing::warn!(
+                        profile = %profile.name,
+                        artifact_id = %artifact_id,
+                        "Scheduled compliance scan not started: {}",
+                        e
+                    ),
+                }
+            }
+        }
+        Ok(ran)
+    }
+}
+
+async fn load_artifact(db: &PgPool, artifact_id: Uuid) -> Result<Artifact> {
+    sqlx::query_as(
+        "SELECT id, repository_id, path, name, version, size_bytes, checksum_sha256, \
+         checksum_md5, checksum_sha1, content_type, storage_key, is_deleted, uploaded_by, \
+         quarantine_status, quarantine_until, created_at, updated_at \
+         FROM artifacts WHERE id = $1 AND is_deleted = false",
+    )
+    .bind(artifact_id)
+    .fetch_optional(db)
+    .await
+    .map_err(db_err)?
+    .ok_or_else(|| AppError::NotFound("Artifact not found".to_string()))
+}
+
+/// Newest tagged manifest of each image name in container repositories,
+/// optionally limited to one repository. Digest references and cosign-style
+/// `sha256-...` tags are skipped.
+async fn latest_images(db: &PgPool, repository_id: Option<Uuid>, limit: i64) -> Result<Vec<Uuid>> {
+    let formats: Vec<String> = IMAGE_FORMATS.iter().map(|f| f.to_string()).collect();
+    sqlx::query_scalar(
+        "SELECT id FROM ( \
+           SELECT DISTINCT ON (a.repository_id, split_part(a.path, '/manifests/', 1)) \
+                  a.id, a.created_at \
+           FROM artifacts a \
+           JOIN repositories r ON r.id = a.repository_id \
+           WHERE NOT a.is_deleted \
+             AND r.format::text = ANY($1) \
+             AND a.path LIKE '%/manifests/%' \
+             AND a.version IS NOT NULL AND a.version NOT LIKE 'sha256%' \
+             AND ($2::uuid IS NULL OR a.repository_id = $2) \
+           ORDER BY a.repository_id, split_part(a.path, '/manifests/', 1), a.created_at DESC \
+         ) latest ORDER BY created_at DESC LIMIT $3",
+    )
+    .bind(&formats)
+    .bind(repository_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)
+}
+
+async fn download_blob(storage: &dyn StorageBackend, key: &str, dest: &Path) -> Result<()> {
+    let mut stream = storage.get_stream(key).await.map_err(|e| match e {
+        AppError::NotFound(_) => {
+            AppError::NotFound(format!("Image layer {} is not stored in the registry", key))
+        }
+        other => other,
+    })?;
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .map_err(|e| AppError::Storage(format!("Failed to create {}: {}", dest.display(), e)))?;
+    while let Some(chunk) = stream.next().await {
+        file.write_all(&chunk?)
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to write {}: {}", dest.display(), e)))?;
+    }
+    file.flush()
+        .await
+        .map_err(|e| AppError::Storage(format!("Failed to write {}: {}", dest.display(), e)))
+}
+
+// ---------------------------------------------------------------------------
+// Layer extraction
+// ---------------------------------------------------------------------------
+
+/// Byte and entry ceilings shared by every layer of one image.
+struct ExtractionBudget {
+    bytes: u64,
+    entries: u64,
+}
+
+impl ExtractionBudget {
+    fn new() -> Self {
+        Self {
+            bytes: max_scan_extracted_bytes(),
+            entries: MAX_SCAN_EXTRACTED_ENTRIES,
+        }
+    }
+}
+
+/// An OCI whiteout marker found in a layer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Whiteout<'a> {
+    /// `.wh..wh..opq`: hide everything lower layers put in the directory.
+    Opaque,
+    /// `.wh.<name>`: delete `<name>` from lower layers.
+    Remove(&'a str),
+}
+
+/// Classify a layer entry's file name as a whiteout marker.
+pub fn classify_whiteout(file_name: &str) -> Option<Whiteout<'_>> {
+    if file_name == ".wh..wh..opq" {
+        return Some(Whiteout::Opaque);
+    }
+    file_name
+        .strip_prefix(".wh.")
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .map(Whiteout::Remove)
+}
+
+/// Normalize a tar entry path to a relative path inside the rootfs. Leading
+/// `/` and `.` components are dropped; paths with `..` are rejected.
+pub fn normalize_entry_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::RootDir | Component::CurDir => {}
+            Component::ParentDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!normalized.as_os_str().is_empty()).then_some(normalized)
+}
+
+/// Permission bits for an extracted entry. Special bits are kept (CIS rules
+/// audit setuid/setgid files); directories stay owner-writable and
+/// traversable so later layers and cleanup can modify them.
+pub fn extracted_mode(mode: u32, is_dir: bool) -> u32 {
+    let mode = mode & 0o7777;
+    if is_dir {
+        mode | 0o700
+    } else {
+        mode
+    }
+}
+
+/// Resolve `rel` inside `root` the way a chroot would: symlinks are followed
+/// with absolute targets re-rooted at `root`, and `..` never climbs above it.
+/// Components that do not exist yet are appended as-is.
+pub fn resolve_in_root(root: &Path, rel: &Path) -> Result<PathBuf> {
+    let mut pending: Vec<OsString> = Vec::new();
+    push_components(rel, &mut pending);
+    pending.reverse();
+
+    let mut resolved = PathBuf::new();
+    let mut hops = 0;
+    while let Some(part) = pending.pop() {
+        if part == ".." {
+            resolved.pop();
+            continue;
+        }
+        let candidate = resolved.join(&part);
+        let full = root.join(&candidate);
+        match std::fs::symlink_metadata(&full) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                hops += 1;
+                if hops > MAX_SYMLINK_HOPS {
+                    return Err(AppError::Validation(format!(
+                        "Too many levels of symbolic links resolving /{}",
+                        rel.display()
+                    )));
+                }
+                let target = std::fs::read_link(&full).map_err(|e| {
+                    AppError::Internal(format!("Failed to read link {}: {}", full.display(), e))
+                })?;
+                if target.is_absolute() {
+                    resolved = PathBuf::new();
+                }
+                let mut expanded = Vec::new();
+                push_components(&target, &mut expanded);
+                pending.extend(expanded.into_iter().rev());
+            }
+            _ => resolved = candidate,
+        }
+    }
+    Ok(root.join(resolved))
+}
+
+fn push_components(path: &Path, out: &mut Vec<OsString>) {
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part.to_os_string()),
+            Component::ParentDir => out.push(OsString::from("..")),
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+        }
+    }
+}
+
+fn io_err(path: &Path, e: std::io::Error) -> AppError {
+    AppError::Internal(format!("Failed to extract {}: {}", path.display(), e))
+}
+
+/// Remove whatever is at `path` without following symlinks.
+fn remove_existing(path: &Path) -> Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path).map_err(|e| io_err(path, e)),
+        Ok(_) => std::fs::remove_file(path).map_err(|e| io_err(path, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(io_err(path, e)),
+    }
+}
+
+/// Apply one (optionally gzip- or zstd-compressed) layer tarball onto
+/// `root`.
+fn apply_layer(layer: &Path, root: &Path, budget: &mut ExtractionBudget) -> Result<()> {
+    use std::io::Read;
+
+    let mut magic = [0u8; 4];
+    let read = std::fs::File::open(layer)
+        .and_then(|mut f| f.read(&mut magic))
+        .map_err(|e| io_err(layer, e))?;
+    let file = std::fs::File::open(layer).map_err(|e| io_err(layer, e))?;
+    let reader: Box<dyn Read> = if read >= 2 && magic[..2] == [0x1f, 0x8b] {
+        Box::new(flate2::read::GzDecoder::new(file))
+    } else if read == 4 && magic == [0x28, 0xb5, 0x2f, 0xfd] {
+        Box::new(zstd::stream::read::Decoder::new(file).map_err(|e| io_err(layer, e))?)
+    } else {
+        Box::new(file)
+    };
+
+    let mut archive = tar::Archive::new(reader);
+    // Paths written by this layer; an opaque whiteout only hides lower ones.
+    let mut written: HashSet<PathBuf> = HashSet::new();
+    for entry in archive.entries().map_err(|e| io_err(layer, e))? {
+        let mut entry = entry.map_err(|e| io_err(layer, e))?;
+        if budget.entries == 0 {
+            return Err(AppError::Validation(
+                "Image has too many files to extract for a compliance scan".to_string(),
+            ));
+        }
+        budget.entries -= 1;
+
+        let raw_path = entry.path().map_err(|e| io_err(layer, e))?.into_owned();
+        let Some(rel) = normalize_entry_path(&raw_path) else {
+            continue;
+        };
+        let Some(file_name) = rel.file_name().map(|n| n.to_os_string()) else {
+            continue;
+        };
+        let parent_rel = rel.parent().unwrap_or(Path::new(""));
+        let parent = resolve_in_root(root, parent_rel)?;
+
+        if let Some(whiteout) = file_name.to_str().and_then(classify_whiteout) {
+            match whiteout {
+                Whiteout::Opaque => {
+                    if std::fs::symlink_metadata(&parent).is_ok_and(|m| m.is_dir()) {
+                        for child in std::fs::read_dir(&parent).map_err(|e| io_err(&parent, e))? {
+                            let child = child.map_err(|e| io_err(&parent, e))?.path();
+                            if !written.contains(&child) {
+                                remove_existing(&child)?;
+                            }
+                        }
+                    }
+                }
+                Whiteout::Remove(name) => remove_existing(&parent.join(name))?,
+            }
+            continue;
+        }
+
+        std::fs::create_dir_all(&parent).map_err(|e| io_err(&parent, e))?;
+        let target = parent.join(&file_name);
+        let mode = entry.header().mode().unwrap_or(0o644);
+        let entry_type = entry.header().entry_type();
+
+        if entry_type.is_dir() {
+            if !std::fs::symlink_metadata(&target).is_ok_and(|m| m.is_dir()) {
+                remove_existing(&target)?;
+                std::fs::create_dir(&target).map_err(|e| io_err(&target, e))?;
+            }
+            set_mode(&target, extracted_mode(mode, true))?;
+        } else if entry_type.is_file() || entry_type == tar::EntryType::Continuous {
+            remove_existing(&target)?;
+            let out = std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&target)
+                .map_err(|e| io_err(&target, e))?;
+            copy_entry_bounded(&mut entry, out, &mut budget.bytes)?;
+            set_mode(&target, extracted_mode(mode, false))?;
+        } else if entry_type.is_symlink() {
+            let Some(link) = entry.link_name().map_err(|e| io_err(layer, e))? else {
+                continue;
+            };
+            remove_existing(&target)?;
+            create_symlink(&link, &target)?;
+        } else if entry_type.is_hard_link() {
+            let Some(link) = entry.link_name().map_err(|e| io_err(layer, e))? else {
+                continue;
+            };
+            let Some(link_rel) = normalize_entry_path(&link) else {
+                continue;
+            };
+            let source = resolve_in_root(root, &link_rel)?;
+            if !std::fs::symlink_metadata(&source).is_ok_and(|m| m.is_file()) {
+                continue;
+            }
+            remove_existing(&target)?;
+            if std::fs::hard_link(&source, &target).is_err() {
+                let size = std::fs::metadata(&source)
+                    .map_err(|e| io_err(&source, e))?
+                    .len();
+                if size > budget.bytes {
+                    return Err(AppError::Validation(
+                        "Image expands beyond the extraction budget".to_string(),
+                    ));
+                }
+                budget.bytes -= size;
+                std::fs::copy(&source, &target).map_err(|e| io_err(&target, e))?;
+            }
+        } else {
+            // Devices, FIFOs and PAX/GNU metadata entries.
+            continue;
+        }
+        written.insert(target);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+        .map_err(|e| io_err(path, e))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(link: &Path, target: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(link, target).map_err(|e| io_err(target, e))
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_link: &Path, _target: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(result: &str) -> ComplianceRuleResult {
+        ComplianceRuleResult {
+            rule_id: format!("xccdf_rule_{}", result),
+            result: result.to_string(),
+            severity: "medium".to_string(),
+            title: String::new(),
+            description: None,
+            references: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_tally_groups_results() {
+        let results: Vec<_> = [
+            "pass",
+            "pass",
+            "fail",
+            "error",
+            "unknown",
+            "notapplicable",
+            "notchecked",
+        ]
+        .into_iter()
+        .map(rule)
+        .collect();
+        assert_eq!(
+            tally(&results),
+            RuleCounts {
+                passed: 2,
+                failed: 1,
+                errored: 2,
+                other: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(verdict("running", 0, 0), ComplianceVerdict::Running);
+        assert_eq!(verdict("completed", 0, 0), ComplianceVerdict::Pass);
+        assert_eq!(verdict("completed", 3, 1), ComplianceVerdict::Fail);
+        assert_eq!(verdict("completed", 0, 1), ComplianceVerdict::Error);
+        assert_eq!(verdict("failed", 0, 0), ComplianceVerdict::Error);
+    }
+
+    #[test]
+    fn test_scap_identifier_validation() {
+        assert!(is_valid_scap_identifier(
+            "xccdf_org.ssgproject.content_profile_cis"
+        ));
+        assert!(is_valid_scap_identifier("ubuntu2204"));
+        assert!(!is_valid_scap_identifier(""));
+        assert!(!is_valid_scap_identifier("cis; rm -rf /"));
+        assert!(!is_valid_scap_identifier("../rhel9"));
+    }
+
+    #[test]
+    fn test_classify_whiteout() {
+        assert_eq!(classify_whiteout(".wh..wh..opq"), Some(Whiteout::Opaque));
+        assert_eq!(
+            classify_whiteout(".wh.passwd-"),
+            Some(Whiteout::Remove("passwd-"))
+        );
+        assert_eq!(classify_whiteout(".wh."), None);
+        assert_eq!(classify_whiteout(".wh..."), None);
+        assert_eq!(classify_whiteout("passwd"), None);
+    }
+
+    #[test]
+    fn test_normalize_entry_path() {
+        assert_eq!(
+            normalize_entry_path(Path::new("./etc/passwd")),
+            Some(PathBuf::from("etc/passwd"))
+        );
+        assert_eq!(
+            normalize_entry_path(Path::new("/usr/bin/")),
+            Some(PathBuf::from("usr/bin"))
+        );
+        assert_eq!(normalize_entry_path(Path::new("../etc/shadow")), None);
+        assert_eq!(normalize_entry_path(Path::new("etc/../../x")), None);
+        assert_eq!(normalize_entry_path(Path::new("./")), None);
+    }
+
+    #[test]
+    fn test_extracted_mode() {
+        assert_eq!(extracted_mode(0o100644, false), 0o644);
+        assert_eq!(extracted_mode(0o4755, false), 0o4755);
+        assert_eq!(extracted_mode(0o000, false), 0o000);
+        assert_eq!(extracted_mode(0o555, true), 0o755);
+        assert_eq!(extracted_mode(0o1777, true), 0o1777);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_in_root_reroots_symlinks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("usr/lib")).unwrap();
+        std::os::unix::fs::symlink("/usr/lib", root.join("lib")).unwrap();
+        std::os::unix::fs::symlink("../../../../etc", root.join("usr/escape")).unwrap();
+
+        assert_eq!(
+            resolve_in_root(root, Path::new("lib/x.so")).unwrap(),
+            root.join("usr/lib/x.so")
+        );
+        assert_eq!(
+            resolve_in_root(root, Path::new("usr/escape/passwd")).unwrap(),
+            root.join("etc/passwd")
+        );
+        assert_eq!(
+            resolve_in_root(root, Path::new("new/dir")).unwrap(),
+            root.join("new/dir")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_in_root_rejects_loops() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::os::unix::fs::symlink("b", root.join("a")).unwrap();
+        std::os::unix::fs::symlink("a", root.join("b")).unwrap();
+        assert!(resolve_in_root(root, Path::new("a/file")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_layer_handles_whiteouts_and_links() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("rootfs");
+        std::fs::create_dir_all(&root).unwrap();
+
+        let build = |name: &str, entries: &[(&str, tar::EntryType, &str, Option<&str>)]| {
+            let path = tmp.path().join(name);
+            let mut builder = tar::Builder::new(std::fs::File::create(&path).unwrap());
+            for (entry_path, kind, data, link) in entries {
+                let mut header = tar::Header::new_gnu();
+                header.set_entry_type(*kind);
+                header.set_mode(if kind.is_dir() { 0o755 } else { 0o644 });
+                header.set_size(data.len() as u64);
+                if let Some(link) = link {
+                    header.set_link_name(link).unwrap();
+                }
+                builder
+                    .append_data(&mut header, entry_path, data.as_bytes())
+                    .unwrap();
+            }
+            builder.finish().unwrap();
+            path
+        };
+
+        let lower = build(
+            "lower.tar",
+            &[
+                ("etc/", tar::EntryType::Directory, "", None),
+                ("etc/passwd", tar::EntryType::Regular, "root", None),
+                ("etc/old", tar::EntryType::Regular, "x", None),
+                ("var/cache/", tar::EntryType::Directory, "", None),
+                ("var/cache/a", tar::EntryType::Regular, "a", None),
+                ("lib", tar::EntryType::Symlink, "", Some("/usr/lib")),
+            ],
+        );
+        let upper = build(
+            "upper.tar",
+            &[
+                ("etc/.wh.old", tar::EntryType::Regular, "", None),
+                ("var/cache/.wh..wh..opq", tar::EntryType::Regular, "", None),
+                ("var/cache/b", tar::EntryType::Regular, "b", None),
+                ("lib/libc.so", tar::EntryType::Regular, "elf", None),
+                ("etc/passwd2", tar::EntryType::Link, "", Some("etc/passwd")),
+            ],
+        );
+
+        let mut budget = ExtractionBudget::new();
+        apply_layer(&lower, &root, &mut budget).unwrap();
+        apply_layer(&upper, &root, &mut budget).unwrap();
+
+        assert!(!root.join("etc/old").exists());
+        assert!(!root.join("var/cache/a").exists());
+        assert_eq!(std::fs::read(root.join("var/cache/b")).unwrap(), b"b");
+        assert_eq!(std::fs::read(root.join("usr/lib/libc.so")).unwrap(), b"elf");
+        assert_eq!(std::fs::read(root.join("etc/passwd2")).unwrap(), b"root");
+    }
+}
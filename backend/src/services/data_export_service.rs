@@ -0,0 +1,566 @@
+//! Streaming CSV / JSON Lines exports of compliance evidence.
+//!
+//! Each [`ExportDataset`] is a fixed, whitelisted projection over the live
+//! tables: callers pick columns by name, never by SQL, so the generated query
+//! only ever contains expressions defined here. Rows are pulled from a
+//! cursor and written out in chunks, so an export of millions of audit rows
+//! holds one chunk in memory rather than the whole result.
+//!
+//! Audit-log exports cover the live `audit_log` table only; rows already
+//! moved to `audit_log_archives` are downloaded through the archive API.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// Flush the output buffer once it grows past this many bytes.
+const CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportDataset {
+    /// Every scan finding with its artifact and repository.
+    Findings,
+    /// One row per violated rule of a failed quality-gate evaluation.
+    PolicyViolations,
+    /// Live audit-log entries.
+    AuditLog,
+    /// Inventory of non-deleted artifacts.
+    Artifacts,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// RFC 4180 CSV with a header row.
+    #[default]
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// An exportable column: the public name and the SQL expression behind it.
+struct Column {
+    name: &'static str,
+    expr: &'static str,
+}
+
+const fn col(name: &'static str, expr: &'static str) -> Column {
+    Column { name, expr }
+}
+
+const FINDING_COLUMNS: &[Column] = &[
+    col("finding_id", "f.id"),
+    col("detected_at", "f.created_at"),
+    col("repository", "r.key"),
+    col("artifact_id", "a.id"),
+    col("artifact_path", "a.path"),
+    col("artifact_version", "a.version"),
+    col("scan_type", "sr.scan_type"),
+    col("severity", "f.severity"),
+    col("cve_id", "f.cve_id"),
+    col("title", "f.title"),
+    col("affected_component", "f.affected_component"),
+    col("affected_version", "f.affected_version"),
+    col("fixed_version", "f.fixed_version"),
+    col("source", "f.source"),
+    col("source_url", "f.source_url"),
+    col("acknowledged", "f.is_acknowledged"),
+    col("acknowledged_reason", "f.acknowledged_reason"),
+    col("acknowledged_at", "f.acknowledged_at"),
+];
+
+const POLICY_VIOLATION_COLUMNS: &[Column] = &[
+    col("evaluation_id", "e.id"),
+    col("evaluated_at", "e.evaluated_at"),
+    col("repository", "r.key"),
+    col("artifact_id", "a.id"),
+    col("artifact_path", "a.path"),
+    col("artifact_version", "a.version"),
+    col("gate", "g.name"),
+    col("action", "e.action"),
+    col("health_score", "e.health_score"),
+    col("rule", "v->>'rule'"),
+    col("expected", "v->>'expected'"),
+    col("actual", "v->>'actual'"),
+    col("message", "v->>'message'"),
+];
+
+const AUDIT_LOG_COLUMNS: &[Column] = &[
+    col("event_id", "l.id"),
+    col("created_at", "l.created_at"),
+    col("user_id", "l.user_id"),
+    col("username", "u.username"),
+    col("action", "l.action"),
+    col("resource_type", "l.resource_type"),
+    col("resource_id", "l.resource_id"),
+    col("ip_address", "l.ip_address"),
+    col("correlation_id", "l.correlation_id"),
+    col("details", "l.details"),
+];
+
+const ARTIFACT_COLUMNS: &[Column] = &[
+    col("artifact_id", "a.id"),
+    col("repository", "r.key"),
+    col("format", "r.format"),
+    col("name", "a.name"),
+    col("version", "a.version"),
+    col("path", "a.path"),
+    col("size_bytes", "a.size_bytes"),
+    col("checksum_sha256", "a.checksum_sha256"),
+    col("content_type", "a.content_type"),
+    col("quarantine_status", "a.quarantine_status"),
+    col("uploaded_by", "u.username"),
+    col("created_at", "a.created_at"),
+    col("updated_at", "a.updated_at"),
+];
+
+/// Query shape of a dataset: its columns, source, fixed predicate, and the
+/// expressions the time-range and repository filters apply to.
+struct DatasetQuery {
+    columns: &'static [Column],
+    from: &'static str,
+    predicate: &'static str,
+    time_expr: &'static str,
+    repository_expr: Option<&'static str>,
+    id_expr: &'static str,
+}
+
+impl ExportDataset {
+    fn query(&self) -> DatasetQuery {
+        match self {
+            ExportDataset::Findings => DatasetQuery {
+                columns: FINDING_COLUMNS,
+                from: "scan_findings f \
+                       JOIN scan_results sr ON sr.id = f.scan_result_id \
+                       JOIN artifacts a ON a.id = f.artifact_id \
+                       JOIN repositories r ON r.id = a.repository_id",
+                predicate: "TRUE",
+                time_expr: "f.created_at",
+                repository_expr: Some("a.repository_id"),
+                id_expr: "f.id",
+            },
+            ExportDataset::PolicyViolations => DatasetQuery {
+                columns: POLICY_VIOLATION_COLUMNS,
+                from: "quality_gate_evaluations e \
+                       JOIN quality_gates g ON g.id = e.quality_gate_id \
+                       JOIN artifacts a ON a.id = e.artifact_id \
+                       JOIN repositories r ON r.id = a.repository_id \
+                       CROSS JOIN LATERAL jsonb_array_elements( \
+                           CASE WHEN jsonb_typeof(e.details->'violations') = 'array' \
+                                THEN e.details->'violations' ELSE '[]'::jsonb END) v",
+                predicate: "e.passed = false",
+                time_expr: "e.evaluated_at",
+                repository_expr: Some("a.repository_id"),
+                id_expr: "e.id",
+            },
+            ExportDataset::AuditLog => DatasetQuery {
+                columns: AUDIT_LOG_COLUMNS,
+                from: "audit_log l LEFT JOIN users u ON u.id = l.user_id",
+                predicate: "TRUE",
+                time_expr: "l.created_at",
+                repository_expr: None,
+                id_expr: "l.id",
+            },
+            ExportDataset::Artifacts => DatasetQuery {
+                columns: ARTIFACT_COLUMNS,
+                from: "artifacts a \
+                       JOIN repositories r ON r.id = a.repository_id \
+                       LEFT JOIN users u ON u.id = a.uploaded_by",
+                predicate: "a.is_deleted = false",
+                time_expr: "a.created_at",
+                repository_expr: Some("a.repository_id"),
+                id_expr: "a.id",
+            },
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportDataset::Findings => "findings",
+            ExportDataset::PolicyViolations => "policy-violations",
+            ExportDataset::AuditLog => "audit-log",
+            ExportDataset::Artifacts => "artifacts",
+        }
+    }
+
+    /// Names of all exportable columns, in default output order.
+    pub fn column_names(&self) -> Vec<&'static str> {
+        self.query().columns.iter().map(|c| c.name).collect()
+    }
+
+    pub const ALL: [ExportDataset; 4] = [
+        ExportDataset::Findings,
+        ExportDataset::PolicyViolations,
+        ExportDataset::AuditLog,
+        ExportDataset::Artifacts,
+    ];
+}
+
+/// Filters shared by every dataset. The time range applies to the dataset's
+/// primary timestamp (detection, evaluation, event or upload time).
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub repository_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+/// A validated export: dataset, output format and selected columns.
+#[derive(Debug)]
+pub struct ExportPlan {
+    dataset: ExportDataset,
+    format: ExportFormat,
+    columns: Vec<&'static Column>,
+    filter: ExportFilter,
+}
+
+impl std::fmt::Debug for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl ExportPlan {
+    /// Validate `columns` (a comma-separated list; all columns when absent)
+    /// and the filter against `dataset`.
+    pub fn new(
+        dataset: ExportDataset,
+        format: ExportFormat,
+        columns: Option<&str>,
+        filter: ExportFilter,
+    ) -> Result<Self> {
+        let query = dataset.query();
+        let columns = select_columns(query.columns, columns)?;
+        if filter.repository_id.is_some() && query.repository_expr.is_none() {
+            return Err(AppError::Validation(format!(
+                "The {} export cannot be filtered by repository",
+                dataset.as_str()
+            )));
+        }
+        if let (Some(since), Some(until)) = (filter.since, filter.until) {
+            if since >= until {
+                return Err(AppError::Validation(
+                    "'since' must be before 'until'".to_string(),
+                ));
+            }
+        }
+        if filter.limit.is_some_and(|l| l < 1) {
+            return Err(AppError::Validation(
+                "'limit' must be at least 1".to_string(),
+            ));
+        }
+        Ok(Self {
+            dataset,
+            format,
+            columns,
+            filter,
+        })
+    }
+
+    pub fn dataset(&self) -> ExportDataset {
+        self.dataset
+    }
+
+    pub fn format(&self) -> ExportFormat {
+        self.format
+    }
+
+    pub fn column_names(&self) -> Vec<&'static str> {
+        self.columns.iter().map(|c| c.name).collect()
+    }
+
+    /// SQL returning one JSON text value per row: an array of the selected
+    /// columns for CSV, an object keyed by column name for JSON Lines. `json`
+    /// (not `jsonb`) keeps the column order.
+    fn sql(&self) -> String {
+        let query = self.dataset.query();
+        let row = match self.format {
+            ExportFormat::Csv => {
+                let values: Vec<&str> = self.columns.iter().map(|c| c.expr).collect();
+                format!("json_build_array({})", values.join(", "))
+            }
+            ExportFormat::Jsonl => {
+                let pairs: Vec<String> = self
+                    .columns
+                    .iter()
+                    .map(|c| format!("'{}', {}", c.name, c.expr))
+                    .collect();
+                format!("json_build_object({})", pairs.join(", "))
+            }
+        };
+        let repository = query
+            .repository_expr
+            .map(|expr| format!(" AND ($3::uuid IS NULL OR {} = $3)", expr))
+            // Still reference $3 so every dataset binds the same parameters.
+            .unwrap_or_else(|| " AND $3::uuid IS NULL".to_string());
+        format!(
+            "SELECT {row}::text FROM {from} \
+             WHERE {predicate} \
+             AND ($1::timestamptz IS NULL OR {time} >= $1) \
+             AND ($2::timestamptz IS NULL OR {time} < $2){repository} \
+             ORDER BY {time}, {id} LIMIT $4",
+            row = row,
+            from = query.from,
+            predicate = query.predicate,
+            time = query.time_expr,
+            repository = repository,
+            id = query.id_expr,
+        )
+    }
+
+    /// Stream the export body. Database errors after the first row abort the
+    /// stream, which surfaces to the client as a truncated transfer.
+    pub fn stream(self, db: PgPool) -> BoxStream<'static, Result<Bytes>> {
+        let sql = self.sql();
+        let format = self.format;
+        let header = self.column_names();
+        let filter = self.filter;
+        Box::pin(async_stream::try_stream! {
+            let mut buf = BytesMut::with_capacity(CHUNK_BYTES);
+            if format == ExportFormat::Csv {
+                let fields: Vec<String> = header.iter().map(|h| csv_field(h)).collect();
+                buf.put_slice(fields.join(",").as_bytes());
+                buf.put_slice(b"\r\n");
+            }
+
+            let mut rows = sqlx::query_scalar::<_, String>(&sql)
+                .bind(filter.since)
+                .bind(filter.until)
+                .bind(filter.repository_id)
+                .bind(filter.limit)
+                .fetch(&db);
+            while let Some(row) = rows
+                .try_next()
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?
+            {
+                match format {
+                    ExportFormat::Csv => {
+                        buf.put_slice(csv_row(&row)?.as_bytes());
+                        buf.put_slice(b"\r\n");
+                    }
+                    ExportFormat::Jsonl => {
+                        buf.put_slice(row.as_bytes());
+                        buf.put_u8(b'\n');
+                    }
+                }
+                if buf.len() >= CHUNK_BYTES {
+                    yield buf.split().freeze();
+                }
+            }
+            if !buf.is_empty() {
+                yield buf.freeze();
+            }
+        })
+    }
+}
+
+fn select_columns(
+    available: &'static [Column],
+    requested: Option<&str>,
+) -> Result<Vec<&'static Column>> {
+    let Some(requested) = requested.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(available.iter().collect());
+    };
+    let mut selected: Vec<&'static Column> = Vec::new();
+    for name in requested.split(',').map(str::trim) {
+        let column = available.iter().find(|c| c.name == name).ok_or_else(|| {
+            let known: Vec<&str> = available.iter().map(|c| c.name).collect();
+            AppError::Validation(format!(
+                "Unknown column '{}'; available: {}",
+                name,
+                known.join(", ")
+            ))
+        })?;
+        if selected.iter().any(|c| c.name == name) {
+            return Err(AppError::Validation(format!(
+                "Column '{}' selected more than once",
+                name
+            )));
+        }
+        selected.push(column);
+    }
+    Ok(selected)
+}
+
+/// Render one row (a JSON array of column values) as a CSV line without the
+/// terminator. Nulls become empty fields; strings are written verbatim;
+/// objects such as audit `details` are written as compact JSON.
+fn csv_row(row: &str) -> Result<String> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(row)
+        .map_err(|e| AppError::Internal(format!("Malformed export row: {}", e)))?;
+    let fields: Vec<String> = values
+        .iter()
+        .map(|v| match v {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => csv_field(s),
+            other => csv_field(&other.to_string()),
+        })
+        .collect();
+    Ok(fields.join(","))
+}
+
+/// Quote a CSV field per RFC 4180. Text that a spreadsheet would evaluate as
+/// a formula is prefixed with `'` so opening an export never runs content
+/// taken from package metadata.
+fn csv_field(value: &str) -> String {
+    let formula = value.starts_with(['=', '+', '@', '\t', '\r'])
+        || (value.starts_with('-') && value.parse::<f64>().is_err());
+    let value = if formula {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn test_csv_field_neutralizes_formulas() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1+1"), "'+1+1");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("-cmd"), "'-cmd");
+        assert_eq!(csv_field("-42"), "-42");
+    }
+
+    #[test]
+    fn test_csv_row_renders_json_values() {
+        let row = r#"["a", null, 3, true, {"k": "v"}, "2026-01-01T00:00:00+00:00"]"#;
+        assert_eq!(
+            csv_row(row).unwrap(),
+            "a,,3,true,\"{\"\"k\"\":\"\"v\"\"}\",2026-01-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_select_columns() {
+        let all = select_columns(FINDING_COLUMNS, None).unwrap();
+        assert_eq!(all.len(), FINDING_COLUMNS.len());
+        let picked = select_columns(FINDING_COLUMNS, Some(" cve_id, severity ")).unwrap();
+        let names: Vec<&str> = picked.iter().map(|c| c.name).collect();
+        assert_eq!(names, vec!["cve_id", "severity"]);
+        assert!(select_columns(FINDING_COLUMNS, Some("cve_id,nope")).is_err());
+        assert!(select_columns(FINDING_COLUMNS, Some("cve_id,cve_id")).is_err());
+        assert!(select_columns(FINDING_COLUMNS, Some("f.id")).is_err());
+    }
+
+    #[test]
+    fn test_column_names_are_unique() {
+        for dataset in ExportDataset::ALL {
+            let names = dataset.column_names();
+            let unique: std::collections::HashSet<_> = names.iter().collect();
+            assert_eq!(unique.len(), names.len(), "{}", dataset.as_str());
+        }
+    }
+
+    #[test]
+    fn test_plan_validation() {
+        let repo_filter = ExportFilter {
+            repository_id: Some(Uuid::new_v4()),
+            ..Default::default()
+        };
+        assert!(ExportPlan::new(
+            ExportDataset::AuditLog,
+            ExportFormat::Csv,
+            None,
+            repo_filter.clone()
+        )
+        .is_err());
+        assert!(ExportPlan::new(
+            ExportDataset::Findings,
+            ExportFormat::Csv,
+            None,
+            repo_filter
+        )
+        .is_ok());
+
+        let now = Utc::now();
+        let inverted = ExportFilter {
+            since: Some(now),
+            until: Some(now),
+            ..Default::default()
+        };
+        assert!(ExportPlan::new(
+            ExportDataset::Artifacts,
+            ExportFormat::Jsonl,
+            None,
+            inverted
+        )
+        .is_err());
+        let zero = ExportFilter {
+            limit: Some(0),
+            ..Default::default()
+        };
+        assert!(ExportPlan::new(ExportDataset::Artifacts, ExportFormat::Csv, None, zero).is_err());
+    }
+
+    #[test]
+    fn test_sql_uses_only_selected_expressions() {
+        let plan = ExportPlan::new(
+            ExportDataset::Findings,
+            ExportFormat::Jsonl,
+            Some("severity,cve_id"),
+            ExportFilter::default(),
+        )
+        .unwrap();
+        let sql = plan.sql();
+        assert!(sql.starts_with(
+            "SELECT json_build_object('severity', f.severity, 'cve_id', f.cve_id)::text"
+        ));
+        assert!(sql.contains("$3::uuid IS NULL OR a.repository_id = $3"));
+        assert!(sql.ends_with("ORDER BY f.created_at, f.id LIMIT $4"));
+
+        let audit = ExportPlan::new(
+            ExportDataset::AuditLog,
+            ExportFormat::Csv,
+            Some("action"),
+            ExportFilter::default(),
+        )
+        .unwrap();
+        let sql = audit.sql();
+        assert!(sql.starts_with("SELECT json_build_array(l.action)::text"));
+        assert!(sql.contains("AND $3::uuid IS NULL ORDER BY l.created_at"));
+    }
+}
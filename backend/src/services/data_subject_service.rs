@@ -0,0 +1,614 @@
+//! GDPR data-subject erasure and PII retention.
+//!
+//! An erasure removes a user's personal data from every place it is stored,
+//! in one transaction, and reports each table and column it touched:
+//!
+//! * `anonymize` keeps the user row as a disabled tombstone under a
+//!   pseudonym (`erased-<id prefix>`), so ownership columns, audit rows and
+//!   per-user aggregates keep pointing at one stable identity.
+//! * `delete` removes the user row. Every foreign key to `users` is resolved
+//!   explicitly (detached or deleted per its `ON DELETE` rule, including the
+//!   ones declared without one), so the report lists rows the cascade would
+//!   otherwise change silently.
+//!
+//! Both modes scrub the places a username, email or network identifier is
+//! copied as text rather than referenced: download statistics lose their
+//! user, IP and user agent (the rows, and so the download counts, stay),
+//! audit rows lose the subject's IP and have the username and email replaced
+//! by the pseudonym inside `details`, and credentials and sessions are
+//! deleted outright.
+//!
+//! A dry run executes the same statements and rolls back, which yields the
+//! report without changing anything.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, PgPool, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::auth_service::{invalidate_user_token_cache_entries, invalidate_user_tokens};
+
+/// How an erasure treats the user account itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErasureMode {
+    /// Replace identifying fields with a pseudonym and disable the account.
+    Anonymize,
+    /// Remove the account and resolve every reference to it.
+    Delete,
+}
+
+impl ErasureMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErasureMode::Anonymize => "anonymize",
+            ErasureMode::Delete => "delete",
+        }
+    }
+}
+
+/// What happened to the matched rows of one table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TableAction {
+    /// Personal values overwritten or cleared; the row stays.
+    Anonymized,
+    /// Reference to the user cleared; the row stays.
+    Detached,
+    /// Row removed.
+    Deleted,
+}
+
+/// One table touched by an erasure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TableReport {
+    pub table: String,
+    /// Columns changed, or the column the rows were matched on for deletes.
+    pub columns: Vec<String>,
+    pub action: TableAction,
+    pub rows: i64,
+}
+
+/// Result of an erasure (or of a dry run).
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErasureReport {
+    /// Recorded request id; absent for dry runs.
+    pub request_id: Option<Uuid>,
+    pub subject_user_id: Uuid,
+    pub mode: ErasureMode,
+    pub dry_run: bool,
+    /// Name that replaces the subject's username in retained records.
+    pub pseudonym: String,
+    pub tables: Vec<TableReport>,
+    pub rows_affected: i64,
+}
+
+/// A recorded erasure.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DataSubjectRequest {
+    pub id: Uuid,
+    pub subject_user_id: Uuid,
+    pub mode: String,
+    pub reason: Option<String>,
+    pub report: ErasureReport,
+    pub rows_affected: i64,
+    pub requested_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tables holding credentials, sessions and per-user settings, matched on a
+/// column referencing the user. They are deleted in both modes: none of it is
+/// needed once the account is gone or disabled.
+const PERSONAL_TABLES: &[(&str, &str)] = &[
+    ("api_tokens", "user_id"),
+    ("refresh_token_jti", "user_id"),
+    ("totp_pending_jti", "user_id"),
+    ("s3_access_keys", "user_id"),
+    ("password_history", "user_id"),
+    ("password_expiry_notifications", "user_id"),
+    ("saved_searches", "user_id"),
+    ("user_notification_preferences", "user_id"),
+    ("user_preferences", "user_id"),
+    ("remote_instances", "user_id"),
+    ("download_tickets", "user_id"),
+    ("upload_sessions", "user_id"),
+    ("oci_upload_sessions", "user_id"),
+    ("incus_upload_sessions", "user_id"),
+    ("direct_uploads", "user_id"),
+];
+
+/// Pseudonym that replaces a subject's username. Derived from the user id so
+/// re-running an erasure is idempotent and the result stays unique.
+pub fn pseudonym_for(user_id: Uuid) -> String {
+    format!("erased-{}", &user_id.simple().to_string()[..12])
+}
+
+/// Email address stored for an anonymized user (`users.email` is unique and
+/// not nullable).
+pub fn pseudonym_email(pseudonym: &str) -> String {
+    format!("{}@erased.invalid", pseudonym)
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// How to resolve a foreign key to `users` before deleting the user row,
+/// from `pg_constraint.confdeltype` and whether the column is nullable.
+/// Keys declared without `ON DELETE` (no action / restrict) are detached
+/// when they can be and deleted otherwise.
+fn fk_resolution(confdeltype: &str, nullable: bool) -> TableAction {
+    match confdeltype {
+        "c" => TableAction::Deleted,
+        "n" | "d" => TableAction::Detached,
+        _ if nullable => TableAction::Detached,
+        _ => TableAction::Deleted,
+    }
+}
+
+fn db_err(e: sqlx::Error) -> AppError {
+    AppError::Database(e.to_string())
+}
+
+/// Collects table reports, skipping statements that matched nothing.
+#[derive(Default)]
+struct ReportBuilder(Vec<TableReport>);
+
+impl ReportBuilder {
+    fn record(&mut self, table: &str, columns: &[&str], action: TableAction, rows: u64) {
+        if rows == 0 {
+            return;
+        }
+        self.0.push(TableReport {
+            table: table.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            action,
+            rows: rows as i64,
+        });
+    }
+}
+
+struct Subject {
+    id: Uuid,
+    username: String,
+    email: String,
+    pseudonym: String,
+}
+
+pub struct DataSubjectService {
+    db: PgPool,
+}
+
+impl DataSubjectService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Erase a user's personal data. With `dry_run` the statements run inside
+    /// a transaction that is rolled back and nothing is recorded.
+    pub async fn erase(
+        &self,
+        user_id: Uuid,
+        mode: ErasureMode,
+        dry_run: bool,
+        reason: Option<&str>,
+        requested_by: Option<Uuid>,
+    ) -> Result<ErasureReport> {
+        let mut tx = self.db.begin().await.map_err(db_err)?;
+        let row = sqlx::query(
+            "SELECT username, email, is_admin, is_active FROM users WHERE id = $1 FOR UPDATE",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_err)?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        if row.get::<bool, _>("is_admin") && row.get::<bool, _>("is_active") {
+            let other_admins: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM users WHERE is_admin = true AND is_active = true AND id <> $1",
+            )
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_err)?;
+            if other_admins == 0 {
+                return Err(AppError::Validation(
+                    "Cannot erase the last active administrator".to_string(),
+                ));
+            }
+        }
+
+        let subject = Subject {
+            id: user_id,
+            username: row.get("username"),
+            email: row.get("email"),
+            pseudonym: pseudonym_for(user_id),
+        };
+
+        let mut report = ReportBuilder::default();
+        scrub_copied_identifiers(&mut tx, &subject, &mut report).await?;
+        for (table, column) in PERSONAL_TABLES {
+            let result = sqlx::query(&format!(
+                "DELETE FROM {} WHERE {} = $1",
+                quote_ident(table),
+                quote_ident(column)
+            ))
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_err)?;
+            report.record(
+                table,
+                &[column],
+                TableAction::Deleted,
+                result.rows_affected(),
+            );
+        }
+        match mode {
+            ErasureMode::Anonymize => anonymize_account(&mut tx, &subject, &mut report).await?,
+            ErasureMode::Delete => delete_account(&mut tx, &subject, &mut report).await?,
+        }
+
+        let tables = report.0;
+        let rows_affected = tables.iter().map(|t| t.rows).sum();
+        let mut erasure = ErasureReport {
+            request_id: None,
+            subject_user_id: user_id,
+            mode,
+            dry_run,
+            pseudonym: subject.pseudonym,
+            tables,
+            rows_affected,
+        };
+
+        if dry_run {
+            tx.rollback().await.map_err(db_err)?;
+            return Ok(erasure);
+        }
+
+        let request_id = Uuid::new_v4();
+        erasure.request_id = Some(request_id);
+        sqlx::query(
+            "INSERT INTO data_subject_requests \
+                 (id, subject_user_id, mode, reason, report, rows_affected, requested_by) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(request_id)
+        .bind(user_id)
+        .bind(mode.as_str())
+        .bind(reason)
+        .bind(serde_json::to_value(&erasure).map_err(|e| AppError::Internal(e.to_string()))?)
+        .bind(rows_affected)
+        .bind(requested_by)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_err)?;
+
+        // Fail-secure, as on user delete: evict cached token validations
+        // before the credentials disappear. Done only once the erasure has
+        // passed validation, so a rejected request leaves sessions intact.
+        invalidate_user_token_cache_entries(user_id);
+        invalidate_user_tokens(user_id);
+
+        tx.commit().await.map_err(db_err)?;
+        Ok(erasure)
+    }
+
+    /// Recorded erasures, newest first.
+    pub async fn list_requests(&self, limit: i64) -> Result<Vec<DataSubjectRequest>> {
+        let rows = sqlx::query(
+            "SELECT id, subject_user_id, mode, reason, report, rows_affected, requested_by, created_at \
+             FROM data_subject_requests ORDER BY created_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(db_err)?;
+        rows.iter().map(request_from_row).collect()
+    }
+
+    pub async fn get_request(&self, id: Uuid) -> Result<DataSubjectRequest> {
+        let row = sqlx::query(
+            "SELECT id, subject_user_id, mode, reason, report, rows_affected, requested_by, created_at \
+             FROM data_subject_requests WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(db_err)?
+        .ok_or_else(|| AppError::NotFound("Data subject request not found".to_string()))?;
+        request_from_row(&row)
+    }
+
+    /// Clear client IP addresses and user agents older than `days` from
+    /// download statistics and the audit log. Rows are kept, so download
+    /// counts and the audit trail are unchanged. Returns rows updated.
+    pub async fn clear_expired_network_identifiers(&self, days: u32) -> Result<u64> {
+        if days == 0 {
+            return Ok(0);
+        }
+        let days = days.min(i32::MAX as u32) as i32;
+        let mut cleared = 0;
+        for table in ["download_statistics", "proxy_download_statistics"] {
+            cleared += sqlx::query(&format!(
+                "UPDATE {table} SET ip_address = NULL, user_agent = NULL \
+                 WHERE downloaded_at < NOW() - make_interval(days => $1) \
+                   AND (ip_address IS NOT NULL OR user_agent IS NOT NULL)"
+            ))
+            .bind(days)
+            .execute(&self.db)
+            .await
+            .map_err(db_err)?
+            .rows_affected();
+        }
+        cleared += sqlx::query(
+            "UPDATE audit_log SET ip_address = NULL \
+             WHERE created_at < NOW() - make_interval(days => $1) AND ip_address IS NOT NULL",
+        )
+        .bind(days)
+        .execute(&self.db)
+        .await
+        .map_err(db_err)?
+        .rows_affected();
+        Ok(cleared)
+    }
+}
+
+fn request_from_row(row: &sqlx::postgres::PgRow) -> Result<DataSubjectRequest> {
+    let report: serde_json::Value = row.get("report");
+    Ok(DataSubjectRequest {
+        id: row.get("id"),
+        subject_user_id: row.get("subject_user_id"),
+        mode: row.get("mode"),
+        reason: row.get("reason"),
+        report: serde_json::from_value(report)
+            .map_err(|e| AppError::Internal(format!("Corrupt erasure report: {}", e)))?,
+        rows_affected: row.get("rows_affected"),
+        requested_by: row.get("requested_by"),
+        created_at: row.get("created_at"),
+    })
+}
+
+/// Scrub places that hold the subject's identity as copied text or network
+/// identifiers rather than as a reference that the account handling covers.
+async fn scrub_copied_identifiers(
+    conn: &mut PgConnection,
+    subject: &Subject,
+    report: &mut ReportBuilder,
+) -> Result<()> {
+    for table in ["download_statistics", "proxy_download_statistics"] {
+        let result = sqlx::query(&format!(
+            "UPDATE {table} SET user_id = NULL, ip_address = NULL, user_agent = NULL \
+             WHERE user_id = $1"
+        ))
+        .bind(subject.id)
+        .execute(&mut *conn)
+        .await
+        .map_err(db_err)?;
+        report.record(
+            table,
+            &["user_id", "ip_address", "user_agent"],
+            TableAction::Anonymized,
+            result.rows_affected(),
+        );
+    }
+
+    // Usernames and emails appear as JSON string values in audit details
+    // (login failures, user administration events). Matching the encoded
+    // string replaces whole values only, never substrings of other values.
+    let encode = |s: &str| serde_json::Value::from(s).to_string();
+    let result = sqlx::query(
+        "UPDATE audit_log SET \
+             ip_address = CASE WHEN user_id = $1 THEN NULL ELSE ip_address END, \
+             details = replace(replace(details::text, $2, $3), $4, $5)::jsonb \
+         WHERE (user_id = $1 AND ip_address IS NOT NULL) \
+            OR strpos(details::text, $2) > 0 \
+            OR strpos(details::text, $4) > 0",
+    )
+    .bind(subject.id)
+    .bind(encode(&subject.username))
+    .bind(encode(&subject.pseudonym))
+    .bind(encode(&subject.email))
+    .bind(encode(&pseudonym_email(&subject.pseudonym)))
+    .execute(&mut *conn)
+    .await
+    .map_err(db_err)?;
+    report.record(
+        "audit_log",
+        &["ip_address", "details"],
+        TableAction::Anonymized,
+        result.rows_affected(),
+    );
+
+    let result = sqlx::query(
+        "UPDATE email_deliveries SET user_id = NULL, recipient = $2 \
+         WHERE user_id = $1 OR recipient = $3",
+    )
+    .bind(subject.id)
+    .bind(pseudonym_email(&subject.pseudonym))
+    .bind(&subject.email)
+    .execute(&mut *conn)
+    .await
+    .map_err(db_err)?;
+    report.record(
+        "email_deliveries",
+        &["user_id", "recipient"],
+        TableAction::Anonymized,
+        result.rows_affected(),
+    );
+
+    let result = sqlx::query(
+        "UPDATE lfs_locks SET owner_name = $2 WHERE owner_id = $1 AND owner_name <> $2",
+    )
+    .bind(subject.id)
+    .bind(&subject.pseudonym)
+    .execute(&mut *conn)
+    .await
+    .map_err(db_err)?;
+    report.record(
+        "lfs_locks",
+        &["owner_name"],
+        TableAction::Anonymized,
+        result.rows_affected(),
+    );
+    Ok(())
+}
+
+async fn anonymize_account(
+    conn: &mut PgConnection,
+    subject: &Subject,
+    report: &mut ReportBuilder,
+) -> Result<()> {
+    let result = sqlx::query(
+        "UPDATE users SET \
+             username = $2, email = $3, display_name = NULL, description = NULL, \
+             password_hash = NULL, external_id = NULL, \
+             totp_secret = NULL, totp_enabled = false, totp_backup_codes = NULL, \
+             totp_verified_at = NULL, is_active = false, is_admin = false, \
+             last_login_at = NULL, last_failed_login_at = NULL, \
+             failed_login_attempts = 0, locked_until = NULL, \
+             privileges_changed_at = NOW(), updated_at = NOW() \
+         WHERE id = $1",
+    )
+    .bind(subject.id)
+    .bind(&subject.pseudonym)
+    .bind(pseudonym_email(&subject.pseudonym))
+    .execute(&mut *conn)
+    .await
+    .map_err(db_err)?;
+    report.record(
+        "users",
+        &[
+            "username",
+            "email",
+            "display_name",
+            "description",
+            "password_hash",
+            "external_id",
+            "totp_secret",
+            "totp_backup_codes",
+            "last_login_at",
+        ],
+        TableAction::Anonymized,
+        result.rows_affected(),
+    );
+    Ok(())
+}
+
+async fn delete_account(
+    conn: &mut PgConnection,
+    subject: &Subject,
+    report: &mut ReportBuilder,
+) -> Result<()> {
+    let references = sqlx::query(
+        "SELECT cl.relname::text AS table_name, att.attname::text AS column_name, \
+                con.confdeltype::text AS on_delete, NOT att.attnotnull AS nullable \
+         FROM pg_constraint con \
+         JOIN pg_class cl ON cl.oid = con.conrelid \
+         JOIN pg_namespace ns ON ns.oid = cl.relnamespace \
+         JOIN pg_attribute att ON att.attrelid = con.conrelid AND att.attnum = con.conkey[1] \
+         WHERE con.contype = 'f' AND con.confrelid = 'users'::regclass \
+           AND ns.nspname = current_schema() \
+         ORDER BY 1, 2",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(db_err)?;
+
+    for reference in &references {
+        let table: String = reference.get("table_name");
+        let column: String = reference.get("column_name");
+        let on_delete: String = reference.get("on_delete");
+        let action = fk_resolution(&on_delete, reference.get("nullable"));
+        let sql = match action {
+            TableAction::Deleted => format!(
+                "DELETE FROM {} WHERE {} = $1",
+                quote_ident(&table),
+                quote_ident(&column)
+            ),
+            _ => format!(
+                "UPDATE {t} SET {c} = {v} WHERE {c} = $1",
+                t = quote_ident(&table),
+                c = quote_ident(&column),
+                v = if on_delete == "d" { "DEFAULT" } else { "NULL" },
+            ),
+        };
+        let result = sqlx::query(&sql)
+            .bind(subject.id)
+            .execute(&mut *conn)
+            .await
+            .map_err(db_err)?;
+        report.record(&table, &[&column], action, result.rows_affected());
+    }
+
+    let result = sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(subject.id)
+        .execute(&mut *conn)
+        .await
+        .map_err(db_err)?;
+    report.record(
+        "users",
+        &["id"],
+        TableAction::Deleted,
+        result.rows_affected(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudonym_is_stable_and_unique_per_user() {
+        let id = Uuid::parse_str("7f9c2ba4-e88f-4c2b-9c64-5d9e0b1a2c3d").unwrap();
+        assert_eq!(pseudonym_for(id), "erased-7f9c2ba4e88f");
+        assert_eq!(pseudonym_for(id), pseudonym_for(id));
+        assert_ne!(pseudonym_for(id), pseudonym_for(Uuid::new_v4()));
+        assert_eq!(
+            pseudonym_email("erased-7f9c2ba4e88f"),
+            "erased-7f9c2ba4e88f@erased.invalid"
+        );
+    }
+
+    #[test]
+    fn test_fk_resolution() {
+        assert_eq!(fk_resolution("c", false), TableAction::Deleted);
+        assert_eq!(fk_resolution("n", true), TableAction::Detached);
+        assert_eq!(fk_resolution("d", false), TableAction::Detached);
+        // Declared without ON DELETE: detach when nullable, else delete.
+        assert_eq!(fk_resolution("a", true), TableAction::Detached);
+        assert_eq!(fk_resolution("a", false), TableAction::Deleted);
+        assert_eq!(fk_resolution("r", false), TableAction::Deleted);
+    }
+
+    #[test]
+    fn test_quote_ident() {
+        assert_eq!(quote_ident("audit_log"), "\"audit_log\"");
+        assert_eq!(quote_ident("we\"ird"), "\"we\"\"ird\"");
+    }
+
+    #[test]
+    fn test_report_builder_skips_untouched_tables() {
+        let mut report = ReportBuilder::default();
+        report.record("api_tokens", &["user_id"], TableAction::Deleted, 0);
+        report.record("audit_log", &["details"], TableAction::Anonymized, 3);
+        assert_eq!(report.0.len(), 1);
+        assert_eq!(report.0[0].table, "audit_log");
+        assert_eq!(report.0[0].rows, 3);
+    }
+
+    #[test]
+    fn test_mode_serialization() {
+        assert_eq!(
+            serde_json::to_value(ErasureMode::Anonymize).unwrap(),
+            "anonymize"
+        );
+        let mode: ErasureMode = serde_json::from_str("\"delete\"").unwrap();
+        assert_eq!(mode, ErasureMode::Delete);
+        assert_eq!(mode.as_str(), "delete");
+    }
+}
@@ -0,0 +1,442 @@
+//! Download-activity based deletion protection.
+//!
+//! A repository with a row in `repository_delete_protection` refuses to
+//! delete artifacts production may still depend on:
+//!
+//! * `recent_download_days` — the artifact was downloaded (or a consumer
+//!   reported using it) within the last N days;
+//! * `min_distinct_consumers` — more than M distinct consumers fetched it.
+//!   Downloads count per user, anonymous downloads per client IP, and every
+//!   reported `environment/host` pair (see `artifact_consumer_service`) is a
+//!   consumer of its own.
+//!
+//! With `action = block` a protected artifact cannot be deleted until the
+//! rule is relaxed; with `action = confirm` the delete goes through once the
+//! caller confirms it explicitly. The rule applies to REST artifact deletes
+//! (and the npm and WebDAV deletes built on them), OCI manifest deletes and
+//! Helm chart deletes; lifecycle policies skip protected artifacts.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// Longest accepted `recent_download_days`.
+pub const MAX_RECENT_DOWNLOAD_DAYS: i32 = 3650;
+
+/// What happens to a delete of a protected artifact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProtectionAction {
+    /// The delete is refused.
+    Block,
+    /// The delete needs an explicit confirmation.
+    Confirm,
+}
+
+impl ProtectionAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProtectionAction::Block => "block",
+            ProtectionAction::Confirm => "confirm",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "block" => Some(ProtectionAction::Block),
+            "confirm" => Some(ProtectionAction::Confirm),
+            _ => None,
+        }
+    }
+}
+
+/// The deletion protection rule of a repository.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeleteProtectionRule {
+    pub repository_id: Uuid,
+    /// Protect artifacts downloaded within this many days.
+    pub recent_download_days: Option<i32>,
+    /// Protect artifacts with more than this many distinct consumers.
+    pub min_distinct_consumers: Option<i32>,
+    pub action: ProtectionAction,
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct RuleRow {
+    repository_id: Uuid,
+    recent_download_days: Option<i32>,
+    min_distinct_consumers: Option<i32>,
+    action: String,
+    updated_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<RuleRow> for DeleteProtectionRule {
+    fn from(row: RuleRow) -> Self {
+        Self {
+            repository_id: row.repository_id,
+            recent_download_days: row.recent_download_days,
+            min_distinct_consumers: row.min_distinct_consumers,
+            // The column CHECK only admits the two known values.
+            action: ProtectionAction::parse(&row.action).unwrap_or(ProtectionAction::Block),
+            updated_by: row.updated_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// How an artifact has been consumed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct DownloadActivity {
+    /// Latest download or consumer report.
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub distinct_consumers: i64,
+}
+
+/// Why an artifact is protected, if it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectionVerdict {
+    pub action: ProtectionAction,
+    pub reasons: Vec<String>,
+}
+
+impl ProtectionVerdict {
+    /// The error a delete of the artifact fails with.
+    pub fn to_error(&self) -> AppError {
+        let reasons = self.reasons.join("; ");
+        match self.action {
+            ProtectionAction::Block => {
+                AppError::Conflict(format!("Artifact is protected from deletion: {}", reasons))
+            }
+            ProtectionAction::Confirm => AppError::Conflict(format!(
+                "Artifact is protected from deletion: {}. Repeat the request with \
+                 confirm=true to delete it anyway",
+                reasons
+            )),
+        }
+    }
+}
+
+/// Check a rule's thresholds.
+pub fn validate_rule(
+    recent_download_days: Option<i32>,
+    min_distinct_consumers: Option<i32>,
+) -> Result<()> {
+    if recent_download_days.is_none() && min_distinct_consumers.is_none() {
+        return Err(AppError::Validation(
+            "Set recent_download_days, min_distinct_consumers or both".to_string(),
+        ));
+    }
+    if let Some(days) = recent_download_days {
+        if !(1..=MAX_RECENT_DOWNLOAD_DAYS).contains(&days) {
+            return Err(AppError::Validation(format!(
+                "recent_download_days must be between 1 and {}",
+                MAX_RECENT_DOWNLOAD_DAYS
+            )));
+        }
+    }
+    if min_distinct_consumers.is_some_and(|m| m < 0) {
+        return Err(AppError::Validation(
+            "min_distinct_consumers must not be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `rule` protects an artifact with `activity`.
+pub fn evaluate(
+    rule: &DeleteProtectionRule,
+    activity: &DownloadActivity,
+    now: DateTime<Utc>,
+) -> Option<ProtectionVerdict> {
+    let mut reasons = Vec::new();
+    if let (Some(days), Some(last_used_at)) = (rule.recent_download_days, activity.last_used_at) {
+        if last_used_at > now - Duration::days(days as i64) {
+            reasons.push(format!(
+                "downloaded {} (within the last {} days)",
+                last_used_at.format("%Y-%m-%d %H:%M UTC"),
+                days
+            ));
+        }
+    }
+    if let Some(min) = rule.min_distinct_consumers {
+        if activity.distinct_consumers > min as i64 {
+            reasons.push(format!(
+                "{} distinct consumers (more than {})",
+                activity.distinct_consumers, min
+            ));
+        }
+    }
+    if reasons.is_empty() {
+        None
+    } else {
+        Some(ProtectionVerdict {
+            action: rule.action,
+            reasons,
+        })
+    }
+}
+
+/// SQL condition that holds when the artifact aliased `alias` is protected by
+/// its repository's rule: the same two thresholds as [`evaluate`], for
+/// statements that delete in bulk. Unattended deletes cannot confirm, so a
+/// `confirm` rule protects here just like a `block` rule.
+pub fn protected_artifact_sql(alias: &str) -> String {
+    format!(
+        r#"EXISTS (
+    SELECT 1 FROM repository_delete_protection dp
+    WHERE dp.repository_id = {alias}.repository_id
+      AND (
+          (dp.recent_download_days IS NOT NULL AND (
+              EXISTS (
+                  SELECT 1 FROM download_statistics ds
+                  WHERE ds.artifact_id = {alias}.id
+                    AND ds.downloaded_at > NOW() - make_interval(days => dp.recent_download_days)
+              )
+              OR EXISTS (
+                  SELECT 1 FROM artifact_consumers ac
+                  WHERE ac.artifact_id = {alias}.id
+                    AND ac.last_seen_at > NOW() - make_interval(days => dp.recent_download_days)
+              )
+          ))
+          OR (dp.min_distinct_consumers IS NOT NULL AND (
+              SELECT COUNT(*) FROM (
+                  SELECT COALESCE('user:' || ds.user_id::text, 'ip:' || ds.ip_address)
+                  FROM download_statistics ds
+                  WHERE ds.artifact_id = {alias}.id
+                    AND (ds.user_id IS NOT NULL OR ds.ip_address IS NOT NULL)
+                  UNION
+                  SELECT 'host:' || ac.environment || '/' || ac.host
+                  FROM artifact_consumers ac
+                  WHERE ac.artifact_id = {alias}.id
+              ) consumers
+          ) > dp.min_distinct_consumers)
+      )
+)"#
+    )
+}
+
+pub struct DeleteProtectionService {
+    db: PgPool,
+}
+
+impl DeleteProtectionService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn get(&self, repository_id: Uuid) -> Result<Option<DeleteProtectionRule>> {
+        let row: Option<RuleRow> = sqlx::query_as(
+            "SELECT repository_id, recent_download_days, min_distinct_consumers, action, \
+                    updated_by, created_at, updated_at \
+             FROM repository_delete_protection WHERE repository_id = $1",
+        )
+        .bind(repository_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(row.map(Into::into))
+    }
+
+    pub async fn set(
+        &self,
+        repository_id: Uuid,
+        recent_download_days: Option<i32>,
+        min_distinct_consumers: Option<i32>,
+        action: ProtectionAction,
+        updated_by: Option<Uuid>,
+    ) -> Result<DeleteProtectionRule> {
+        validate_rule(recent_download_days, min_distinct_consumers)?;
+        let row: RuleRow = sqlx::query_as(
+            r#"
+            INSERT INTO repository_delete_protection
+                (repository_id, recent_download_days, min_distinct_consumers, action, updated_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (repository_id) DO UPDATE SET
+                recent_download_days = EXCLUDED.recent_download_days,
+                min_distinct_consumers = EXCLUDED.min_distinct_consumers,
+                action = EXCLUDED.action,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING repository_id, recent_download_days, min_distinct_consumers, action,
+                      updated_by, created_at, updated_at
+            "#,
+        )
+        .bind(repository_id)
+        .bind(recent_download_days)
+        .bind(min_distinct_consumers)
+        .bind(action.as_str())
+        .bind(updated_by)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(row.into())
+    }
+
+    /// Remove the rule; returns whether there was one.
+    pub async fn clear(&self, repository_id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM repository_delete_protection WHERE repository_id = $1")
+                .bind(repository_id)
+                .execute(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Download and consumer-report activity of an artifact.
+    pub async fn activity(&self, artifact_id: Uuid) -> Result<DownloadActivity> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                GREATEST(
+                    (SELECT MAX(downloaded_at) FROM download_statistics WHERE artifact_id = $1),
+                    (SELECT MAX(last_seen_at) FROM artifact_consumers WHERE artifact_id = $1)
+                ) AS last_used_at,
+                (SELECT COUNT(*) FROM (
+                    SELECT COALESCE('user:' || user_id::text, 'ip:' || ip_address)
+                    FROM download_statistics
+                    WHERE artifact_id = $1
+                      AND (user_id IS NOT NULL OR ip_address IS NOT NULL)
+                    UNION
+                    SELECT 'host:' || environment || '/' || host
+                    FROM artifact_consumers
+                    WHERE artifact_id = $1
+                ) consumers) AS distinct_consumers
+            "#,
+        )
+        .bind(artifact_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Refuse the delete of a protected artifact unless `confirmed` and the
+    /// repository's rule only asks for confirmation.
+    pub async fn check_delete(
+        &self,
+        repository_id: Uuid,
+        artifact_id: Uuid,
+        confirmed: bool,
+    ) -> Result<()> {
+        self.check_delete_all(repository_id, &[artifact_id], confirmed)
+            .await
+    }
+
+    /// [`Self::check_delete`] for a delete that removes several artifacts at
+    /// once (a chart and its provenance, every path of a manifest); any
+    /// protected one refuses the whole delete.
+    pub async fn check_delete_all(
+        &self,
+        repository_id: Uuid,
+        artifact_ids: &[Uuid],
+        confirmed: bool,
+    ) -> Result<()> {
+        let Some(rule) = self.get(repository_id).await? else {
+            return Ok(());
+        };
+        let now = Utc::now();
+        for &artifact_id in artifact_ids {
+            let activity = self.activity(artifact_id).await?;
+            match evaluate(&rule, &activity, now) {
+                None => {}
+                Some(verdict) if verdict.action == ProtectionAction::Confirm && confirmed => {
+                    tracing::warn!(
+                        artifact_id = %artifact_id,
+                        reasons = %verdict.reasons.join("; "),
+                        "Confirmed delete of a protected artifact"
+                    );
+                }
+                Some(verdict) => return Err(verdict.to_error()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        recent_download_days: Option<i32>,
+        min_distinct_consumers: Option<i32>,
+        action: ProtectionAction,
+    ) -> DeleteProtectionRule {
+        let now = Utc::now();
+        DeleteProtectionRule {
+            repository_id: Uuid::nil(),
+            recent_download_days,
+            min_distinct_consumers,
+            action,
+            updated_by: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn activity(days_ago: Option<i64>, consumers: i64, now: DateTime<Utc>) -> DownloadActivity {
+        DownloadActivity {
+            last_used_at: days_ago.map(|d| now - Duration::days(d)),
+            distinct_consumers: consumers,
+        }
+    }
+
+    #[test]
+    fn recent_download_protects() {
+        let now = Utc::now();
+        let rule = rule(Some(30), None, ProtectionAction::Block);
+        assert!(evaluate(&rule, &activity(Some(3), 1, now), now).is_some());
+        assert!(evaluate(&rule, &activity(Some(31), 1, now), now).is_none());
+        assert!(evaluate(&rule, &activity(None, 0, now), now).is_none());
+    }
+
+    #[test]
+    fn consumer_threshold_is_exclusive() {
+        let now = Utc::now();
+        let rule = rule(None, Some(2), ProtectionAction::Block);
+        assert!(evaluate(&rule, &activity(Some(400), 2, now), now).is_none());
+        let verdict = evaluate(&rule, &activity(Some(400), 3, now), now).unwrap();
+        assert_eq!(verdict.reasons, vec!["3 distinct consumers (more than 2)"]);
+    }
+
+    #[test]
+    fn verdict_lists_every_reason_and_maps_to_conflict() {
+        let now = Utc::now();
+        let rule = rule(Some(7), Some(0), ProtectionAction::Confirm);
+        let verdict = evaluate(&rule, &activity(Some(1), 5, now), now).unwrap();
+        assert_eq!(verdict.reasons.len(), 2);
+        assert_eq!(verdict.action, ProtectionAction::Confirm);
+        match verdict.to_error() {
+            AppError::Conflict(msg) => assert!(msg.contains("confirm=true"), "{msg}"),
+            other => panic!("expected Conflict, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn validate_rule_bounds() {
+        assert!(validate_rule(Some(30), None).is_ok());
+        assert!(validate_rule(None, Some(0)).is_ok());
+        assert!(validate_rule(None, None).is_err());
+        assert!(validate_rule(Some(0), None).is_err());
+        assert!(validate_rule(Some(MAX_RECENT_DOWNLOAD_DAYS + 1), None).is_err());
+        assert!(validate_rule(None, Some(-1)).is_err());
+    }
+
+    #[test]
+    fn action_round_trips() {
+        for action in [ProtectionAction::Block, ProtectionAction::Confirm] {
+            assert_eq!(ProtectionAction::parse(action.as_str()), Some(action));
+        }
+        assert_eq!(ProtectionAction::parse("warn"), None);
+    }
+}
@@ -0,0 +1,653 @@
+//! Instance-wide dependency graph built from stored package metadata.
+//!
+//! Declared dependencies are extracted from each artifact's stored manifest
+//! metadata into `package_dependency_edges` by [`DependencyGraphService::index_pending`]
+//! (run by the scheduler). Queries walk the edges breadth-first with a depth
+//! limit:
+//!
+//!   - [`DependencyGraphService::dependents`] answers "what depends on X"
+//!     (reverse edges), the impact-analysis question before deleting or
+//!     deprecating X.
+//!   - [`DependencyGraphService::dependencies`] walks forward from a package,
+//!     following each dependency through the newest stored version of it.
+//!
+//! Requirements are declared ranges and are not resolved; the graph is at
+//! package granularity.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::formats::pypi::PypiHandler;
+use crate::services::declared_dependencies as dd;
+use crate::services::repository_service::{
+    build_visibility_clause_for, RepoVisibility, VisibilityBind,
+};
+
+/// Deepest traversal a query may request.
+pub const MAX_GRAPH_DEPTH: u32 = 10;
+/// Most nodes a single query returns.
+pub const MAX_GRAPH_NODES: usize = 2000;
+/// Artifacts indexed per batch.
+const INDEX_BATCH_SIZE: i64 = 500;
+
+/// Ecosystems with dependency metadata, keyed by the repository formats that
+/// store it.
+pub fn ecosystem_for_format(format: &str) -> Option<&'static str> {
+    match format {
+        "npm" | "yarn" | "pnpm" | "bower" => Some("npm"),
+        "cargo" => Some("cargo"),
+        "pypi" | "poetry" => Some("pypi"),
+        "maven" | "gradle" => Some("maven"),
+        "helm" | "helm_oci" => Some("helm"),
+        _ => None,
+    }
+}
+
+/// Repository formats indexed into the graph.
+const INDEXED_FORMATS: &[&str] = &[
+    "npm", "yarn", "pnpm", "bower", "cargo", "pypi", "poetry", "maven", "gradle", "helm",
+    "helm_oci",
+];
+
+/// Normalize a package name to the key used for graph matching.
+pub fn package_key(ecosystem: &str, name: &str) -> String {
+    match ecosystem {
+        "cargo" => name.to_lowercase(),
+        "pypi" => PypiHandler::normalize_name(name),
+        _ => name.to_string(),
+    }
+}
+
+/// The graph key of a stored artifact, or `None` when it cannot be derived
+/// (a Maven artifact without a recorded `groupId`).
+pub fn artifact_package_key(ecosystem: &str, name: &str, metadata: &Value) -> Option<String> {
+    if ecosystem == "maven" {
+        let group = metadata.get("groupId").and_then(|v| v.as_str())?;
+        if group.is_empty() {
+            return None;
+        }
+        return Some(format!("{}:{}", group, name));
+    }
+    Some(package_key(ecosystem, name))
+}
+
+/// A declared dependency edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclaredEdge {
+    pub dependency_key: String,
+    pub requirement: Option<String>,
+}
+
+/// Split a PEP 508 requirement into its project name and the remainder.
+/// Requirements that only apply to an extra (`; extra == "test"`) return
+/// `None`: they are not installed by default.
+pub fn parse_requires_dist(requirement: &str) -> Option<(String, Option<String>)> {
+    let (spec, marker) = match requirement.split_once(';') {
+        Some((spec, marker)) => (spec.trim(), Some(marker)),
+        None => (requirement.trim(), None),
+    };
+    if marker.is_some_and(|m| m.contains("extra")) {
+        return None;
+    }
+    let end = spec
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(spec.len());
+    let name = &spec[..end];
+    if name.is_empty() {
+        return None;
+    }
+    let rest = spec[end..].trim();
+    // Drop an extras list (`requests[socks]>=2`), keep the version spec.
+    let rest = match rest.strip_prefix('[') {
+        Some(r) => r.split_once(']').map(|(_, v)| v.trim()).unwrap_or(""),
+        None => rest,
+    };
+    let rest = rest.trim_start_matches('(').trim_end_matches(')').trim();
+    Some((
+        name.to_string(),
+        (!rest.is_empty()).then(|| rest.to_string()),
+    ))
+}
+
+/// Extract declared runtime dependencies from stored artifact metadata.
+/// Dev / test-only dependencies are skipped.
+pub fn extract_edges(ecosystem: &str, metadata: &Value) -> Vec<DeclaredEdge> {
+    let mut edges: Vec<DeclaredEdge> = match ecosystem {
+        "npm" => {
+            let vd = metadata.get("version_data").cloned().unwrap_or(Value::Null);
+            dd::npm_deps_from_version_data(&vd)
+                .into_iter()
+                .map(|d| DeclaredEdge {
+                    dependency_key: d.name,
+                    requirement: d.version,
+                })
+                .collect()
+        }
+        "helm" => {
+            let chart = metadata.get("chart").cloned().unwrap_or(Value::Null);
+            dd::helm_deps_from_chart(&chart)
+                .into_iter()
+                .map(|d| DeclaredEdge {
+                    dependency_key: d.name,
+                    requirement: d.version,
+                })
+                .collect()
+        }
+        "maven" => metadata
+            .get("dependencies")
+            .map(dd::maven_deps_from_metadata)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|d| DeclaredEdge {
+                dependency_key: d.name,
+                requirement: d.version,
+            })
+            .collect(),
+        "cargo" => metadata
+            .get("deps")
+            .and_then(|d| d.as_array())
+            .map(|deps| {
+                deps.iter()
+                    .filter(|d| d.get("kind").and_then(|k| k.as_str()) != Some("dev"))
+                    .filter_map(|d| {
+                        // A renamed dependency records the real crate in `package`.
+                        let name = d
+                            .get("package")
+                            .and_then(|v| v.as_str())
+                            .or_else(|| d.get("name").and_then(|v| v.as_str()))?;
+                        let req = d
+                            .get("version_req")
+                            .or_else(|| d.get("req"))
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string);
+                        Some(DeclaredEdge {
+                            dependency_key: package_key("cargo", name),
+                            requirement: req,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "pypi" => {
+            let requires = metadata
+                .get("upload_metadata")
+                .and_then(|m| m.get("requires_dist"));
+            let list: Vec<&str> = match requires {
+                Some(Value::String(s)) => vec![s.as_str()],
+                Some(Value::Array(a)) => a.iter().filter_map(|v| v.as_str()).collect(),
+                _ => Vec::new(),
+            };
+            list.into_iter()
+                .filter_map(parse_requires_dist)
+                .map(|(name, req)| DeclaredEdge {
+                    dependency_key: package_key("pypi", &name),
+                    requirement: req,
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    // One edge per dependency key (the table's primary key).
+    let mut seen = HashSet::new();
+    edges.retain(|e| !e.dependency_key.is_empty() && seen.insert(e.dependency_key.clone()));
+    edges
+}
+
+/// A package reached by a graph query.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct GraphNode {
+    pub artifact_id: Uuid,
+    pub repository_key: String,
+    pub package: String,
+    pub package_key: String,
+    pub version: Option<String>,
+    /// Dependency key of the edge that reached this node.
+    pub via: String,
+    /// Declared requirement on `via`.
+    pub requirement: Option<String>,
+    #[sqlx(default)]
+    pub depth: i32,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GraphQueryResult {
+    pub ecosystem: String,
+    pub package_key: String,
+    pub depth: u32,
+    pub nodes: Vec<GraphNode>,
+    /// True when the node limit cut the traversal short.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct IndexRunSummary {
+    pub artifacts_indexed: u64,
+    pub edges_written: u64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PendingArtifact {
+    id: Uuid,
+    name: String,
+    format: String,
+    updated_at: DateTime<Utc>,
+    metadata: Value,
+}
+
+pub struct DependencyGraphService {
+    db: PgPool,
+}
+
+impl DependencyGraphService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Index artifacts that were never indexed or changed since, in batches,
+    /// until none are left or `max_batches` is reached.
+    pub async fn index_pending(&self, max_batches: usize) -> Result<IndexRunSummary> {
+        let mut summary = IndexRunSummary::default();
+        for _ in 0..max_batches {
+            let pending: Vec<PendingArtifact> = sqlx::query_as(
+                r#"
+                SELECT a.id, a.name, r.format::text AS format, a.updated_at, am.metadata
+                FROM artifacts a
+                JOIN repositories r ON r.id = a.repository_id
+                JOIN artifact_metadata am ON am.artifact_id = a.id
+                LEFT JOIN package_dependency_index pdi ON pdi.artifact_id = a.id
+                WHERE a.is_deleted = false
+                  AND r.format::text = ANY($1)
+                  AND (pdi.artifact_id IS NULL OR pdi.source_updated_at < a.updated_at)
+                ORDER BY a.updated_at
+                LIMIT $2
+                "#,
+            )
+            .bind(INDEXED_FORMATS)
+            .bind(INDEX_BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+            if pending.is_empty() {
+                break;
+            }
+            for artifact in &pending {
+                summary.edges_written += self.index_artifact(artifact).await?;
+                summary.artifacts_indexed += 1;
+            }
+            if (pending.len() as i64) < INDEX_BATCH_SIZE {
+                break;
+            }
+        }
+        Ok(summary)
+    }
+
+    async fn index_artifact(&self, artifact: &PendingArtifact) -> Result<u64> {
+        let Some(ecosystem) = ecosystem_for_format(&artifact.format) else {
+            return Ok(0);
+        };
+        // An artifact without a derivable key is still marked indexed (with
+        // no edges) so it is not retried every run.
+        let key = artifact_package_key(ecosystem, &artifact.name, &artifact.metadata);
+        let edges = if key.is_some() {
+            extract_edges(ecosystem, &artifact.metadata)
+        } else {
+            Vec::new()
+        };
+        let key = key.unwrap_or_default();
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query("DELETE FROM package_dependency_edges WHERE artifact_id = $1")
+            .bind(artifact.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for edge in &edges {
+            sqlx::query(
+                r#"
+                INSERT INTO package_dependency_edges
+                    (artifact_id, ecosystem, package_key, dependency_key, requirement)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(artifact.id)
+            .bind(ecosystem)
+            .bind(&key)
+            .bind(&edge.dependency_key)
+            .bind(&edge.requirement)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO package_dependency_index
+                (artifact_id, ecosystem, package_key, source_updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (artifact_id) DO UPDATE
+            SET ecosystem = EXCLUDED.ecosystem,
+                package_key = EXCLUDED.package_key,
+                source_updated_at = EXCLUDED.source_updated_at,
+                indexed_at = NOW()
+            "#,
+        )
+        .bind(artifact.id)
+        .bind(ecosystem)
+        .bind(&key)
+        .bind(artifact.updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.commit().await?;
+
+        Ok(edges.len() as u64)
+    }
+
+    /// Repository ids visible to the caller, or `None` for no restriction.
+    async fn visible_repo_ids(&self, visibility: &RepoVisibility) -> Result<Option<Vec<Uuid>>> {
+        if matches!(visibility, RepoVisibility::All) {
+            return Ok(None);
+        }
+        let (clause, bind) = build_visibility_clause_for(visibility, "r", 1);
+        let sql = format!("SELECT r.id FROM repositories r WHERE {clause}");
+        let query = sqlx::query_scalar::<_, Uuid>(&sql);
+        // `PublicOnly` produces a clause without a placeholder.
+        let query = match bind {
+            VisibilityBind::User(Some(uid)) => query.bind(uid),
+            VisibilityBind::User(None) => query,
+            VisibilityBind::Ids(ids) => query.bind(ids),
+        };
+        let ids = query
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(Some(ids))
+    }
+
+    /// Packages that (transitively, up to `depth`) declare a dependency on
+    /// `name`.
+    pub async fn dependents(
+        &self,
+        ecosystem: &str,
+        name: &str,
+        depth: u32,
+        visibility: &RepoVisibility,
+    ) -> Result<GraphQueryResult> {
+        let root = package_key(ecosystem, name);
+        let visible = self.visible_repo_ids(visibility).await?;
+        self.traverse(ecosystem, root, depth, visible, |frontier, visible| {
+            let db = self.db.clone();
+            async move {
+                sqlx::query_as::<_, GraphNode>(
+                    r#"
+                    SELECT e.artifact_id, r.key AS repository_key, a.name AS package,
+                           e.package_key, a.version, e.dependency_key AS via, e.requirement
+                    FROM package_dependency_edges e
+                    JOIN artifacts a ON a.id = e.artifact_id AND a.is_deleted = false
+                    JOIN repositories r ON r.id = a.repository_id
+                    WHERE e.ecosystem = $1
+                      AND e.dependency_key = ANY($2)
+                      AND e.package_key <> ''
+                      AND ($3::uuid[] IS NULL OR a.repository_id = ANY($3))
+                    ORDER BY e.package_key, a.created_at DESC
+                    "#,
+                )
+                .bind(ecosystem)
+                .bind(frontier)
+                .bind(visible)
+                .fetch_all(&db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))
+            }
+        })
+        .await
+    }
+
+    /// Declared dependencies of `name` (at `version`, or its newest stored
+    /// version), followed transitively through the newest stored version of
+    /// each dependency. Dependencies not stored in this instance appear as
+    /// leaves with a nil `artifact_id`.
+    pub async fn dependencies(
+        &self,
+        ecosystem: &str,
+        name: &str,
+        version: Option<&str>,
+        depth: u32,
+        visibility: &RepoVisibility,
+    ) -> Result<GraphQueryResult> {
+        let root = package_key(ecosystem, name);
+        let visible = self.visible_repo_ids(visibility).await?;
+        let root_version = version.map(str::to_string);
+        let root_key = root.clone();
+        self.traverse(ecosystem, root, depth, visible, |frontier, visible| {
+            let db = self.db.clone();
+            let root_key = root_key.clone();
+            let root_version = root_version.clone();
+            async move {
+                // For each package in the frontier pick one artifact: the
+                // requested version for the root, else the newest stored.
+                sqlx::query_as::<_, GraphNode>(
+                    r#"
+                    WITH chosen AS (
+                        SELECT DISTINCT ON (pdi.package_key)
+                               pdi.artifact_id, pdi.package_key
+                        FROM package_dependency_index pdi
+                        JOIN artifacts a ON a.id = pdi.artifact_id AND a.is_deleted = false
+                        WHERE pdi.ecosystem = $1
+                          AND pdi.package_key = ANY($2)
+                          AND ($3::uuid[] IS NULL OR a.repository_id = ANY($3))
+                          AND (pdi.package_key <> $4 OR $5::TEXT IS NULL OR a.version = $5)
+                        ORDER BY pdi.package_key, a.created_at DESC
+                    )
+                    SELECT COALESCE(dep.id, '00000000-0000-0000-0000-000000000000'::uuid) AS artifact_id,
+                           COALESCE(dr.key, '') AS repository_key,
+                           COALESCE(dep.name, e.dependency_key) AS package,
+                           e.dependency_key AS package_key,
+                           dep.version,
+                           e.package_key AS via,
+                           e.requirement
+                    FROM chosen c
+                    JOIN package_dependency_edges e ON e.artifact_id = c.artifact_id
+                    LEFT JOIN LATERAL (
+                        SELECT a2.id, a2.name, a2.version, a2.repository_id
+                        FROM package_dependency_index p2
+                        JOIN artifacts a2 ON a2.id = p2.artifact_id AND a2.is_deleted = false
+                        WHERE p2.ecosystem = $1
+                          AND p2.package_key = e.dependency_key
+                          AND ($3::uuid[] IS NULL OR a2.repository_id = ANY($3))
+                        ORDER BY a2.created_at DESC
+                        LIMIT 1
+                    ) dep ON true
+                    LEFT JOIN repositories dr ON dr.id = dep.repository_id
+                    ORDER BY e.package_key, e.dependency_key
+                    "#,
+                )
+                .bind(ecosystem)
+                .bind(frontier)
+                .bind(visible)
+                .bind(root_key)
+                .bind(root_version)
+                .fetch_all(&db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))
+            }
+        })
+        .await
+    }
+
+    /// Breadth-first walk shared by both directions. `step` returns the
+    /// nodes one edge away from the frontier keys; each returned node's
+    /// `package_key` becomes part of the next frontier.
+    async fn traverse<F, Fut>(
+        &self,
+        ecosystem: &str,
+        root: String,
+        depth: u32,
+        visible: Option<Vec<Uuid>>,
+        step: F,
+    ) -> Result<GraphQueryResult>
+    where
+        F: Fn(Vec<String>, Option<Vec<Uuid>>) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<GraphNode>>>,
+    {
+        let depth = depth.clamp(1, MAX_GRAPH_DEPTH);
+        let mut visited: HashSet<String> = HashSet::from([root.clone()]);
+        let mut frontier = vec![root.clone()];
+        let mut nodes: Vec<GraphNode> = Vec::new();
+        let mut seen: HashSet<(Uuid, String)> = HashSet::new();
+        let mut truncated = false;
+
+        'levels: for level in 1..=depth {
+            if frontier.is_empty() {
+                break;
+            }
+            let found = step(std::mem::take(&mut frontier), visible.clone()).await?;
+            for mut node in found {
+                if !seen.insert((node.artifact_id, node.package_key.clone())) {
+                    continue;
+                }
+                if nodes.len() >= MAX_GRAPH_NODES {
+                    truncated = true;
+                    break 'levels;
+                }
+                if visited.insert(node.package_key.clone()) {
+                    frontier.push(node.package_key.clone());
+                }
+                node.depth = level as i32;
+                nodes.push(node);
+            }
+        }
+
+        Ok(GraphQueryResult {
+            ecosystem: ecosystem.to_string(),
+            package_key: root,
+            depth,
+            nodes,
+            truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ecosystem_for_format() {
+        assert_eq!(ecosystem_for_format("pnpm"), Some("npm"));
+        assert_eq!(ecosystem_for_format("cargo"), Some("cargo"));
+        assert_eq!(ecosystem_for_format("poetry"), Some("pypi"));
+        assert_eq!(ecosystem_for_format("gradle"), Some("maven"));
+        assert_eq!(ecosystem_for_format("helm_oci"), Some("helm"));
+        assert_eq!(ecosystem_for_format("docker"), None);
+        for format in INDEXED_FORMATS {
+            assert!(ecosystem_for_format(format).is_some(), "{}", format);
+        }
+    }
+
+    #[test]
+    fn test_package_key_normalization() {
+        assert_eq!(package_key("cargo", "Serde_JSON"), "serde_json");
+        assert_eq!(package_key("pypi", "Zope.Interface"), "zope-interface");
+        assert_eq!(package_key("npm", "@Scope/pkg"), "@Scope/pkg");
+    }
+
+    #[test]
+    fn test_artifact_package_key_maven_needs_group() {
+        assert_eq!(
+            artifact_package_key("maven", "guava", &json!({"groupId": "com.google.guava"})),
+            Some("com.google.guava:guava".to_string())
+        );
+        assert_eq!(artifact_package_key("maven", "guava", &json!({})), None);
+    }
+
+    #[test]
+    fn test_parse_requires_dist() {
+        assert_eq!(
+            parse_requires_dist("requests (>=2.0)"),
+            Some(("requests".to_string(), Some(">=2.0".to_string())))
+        );
+        assert_eq!(
+            parse_requires_dist("urllib3[socks]<3,>=1.21.1"),
+            Some(("urllib3".to_string(), Some("<3,>=1.21.1".to_string())))
+        );
+        assert_eq!(
+            parse_requires_dist("colorama; sys_platform == \"win32\""),
+            Some(("colorama".to_string(), None))
+        );
+        assert_eq!(parse_requires_dist("pytest; extra == \"test\""), None);
+        assert_eq!(parse_requires_dist(""), None);
+    }
+
+    #[test]
+    fn test_extract_edges_cargo_skips_dev_and_follows_renames() {
+        let meta = json!({"deps": [
+            {"name": "Serde", "version_req": "^1", "kind": "normal"},
+            {"name": "json", "package": "serde_json", "req": "^1"},
+            {"name": "tokio-test", "version_req": "*", "kind": "dev"}
+        ]});
+        let edges = extract_edges("cargo", &meta);
+        assert_eq!(
+            edges,
+            vec![
+                DeclaredEdge {
+                    dependency_key: "serde".to_string(),
+                    requirement: Some("^1".to_string())
+                },
+                DeclaredEdge {
+                    dependency_key: "serde_json".to_string(),
+                    requirement: Some("^1".to_string())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_edges_pypi_single_and_repeated_field() {
+        let single = json!({"upload_metadata": {"requires_dist": "Flask_Login>=0.6"}});
+        assert_eq!(
+            extract_edges("pypi", &single)[0].dependency_key,
+            "flask-login"
+        );
+
+        let many = json!({"upload_metadata": {"requires_dist": [
+            "requests>=2", "pytest; extra == 'dev'", "Requests>=2.1"
+        ]}});
+        let edges = extract_edges("pypi", &many);
+        assert_eq!(edges.len(), 1, "extras skipped and keys deduplicated");
+        assert_eq!(edges[0].dependency_key, "requests");
+    }
+
+    #[test]
+    fn test_extract_edges_npm_and_maven() {
+        let npm = json!({"version_data": {
+            "dependencies": {"lodash": "^4.17.0"},
+            "devDependencies": {"jest": "^29"}
+        }});
+        let edges = extract_edges("npm", &npm);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].dependency_key, "lodash");
+
+        let maven = json!({"dependencies": [
+            {"groupId": "org.slf4j", "artifactId": "slf4j-api", "version": "2.0.9"},
+            {"groupId": "junit", "artifactId": "junit", "version": "4.13", "scope": "test"}
+        ]});
+        let edges = extract_edges("maven", &maven);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].dependency_key, "org.slf4j:slf4j-api");
+    }
+
+    #[test]
+    fn test_extract_edges_unknown_ecosystem_is_empty() {
+        assert!(extract_edges("docker", &json!({"deps": []})).is_empty());
+    }
+}
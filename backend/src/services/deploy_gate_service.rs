@@ -0,0 +1,540 @@
+//! Pre-deploy verification gate.
+//!
+//! Deployment systems (Argo CD PreSync hooks, Spinnaker webhook stages, CI
+//! jobs) ask, right before rolling out, whether an artifact may be deployed.
+//! The target is an image digest or artifact coordinates; every stored copy
+//! the caller can see is evaluated against the artifact's quarantine state,
+//! the enabled scan policies (which already cover `block_unscanned`,
+//! `require_signature` and severity limits), and optional per-request
+//! requirements for a signature or a completed scan. The decision is `allow`
+//! only when at least one copy matched and every matched copy passes, so an
+//! unknown or partially rejected artifact fails closed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::artifact_verification_service::{
+    ArtifactVerificationService, VerificationBundle,
+};
+use crate::services::quarantine_service;
+
+/// Most artifacts evaluated for one digest.
+const MAX_MATCHES: i64 = 50;
+
+/// What to verify and how strictly.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct DeployGateRequest {
+    /// Content digest, e.g. `sha256:4f1c...`. An image reference ending in
+    /// `@sha256:...` is accepted too.
+    pub digest: Option<String>,
+    /// Repository key. Narrows a digest lookup; required with `path` or
+    /// `name`/`version`.
+    pub repository: Option<String>,
+    /// Artifact path within the repository.
+    pub path: Option<String>,
+    /// Package name, used with `version`.
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// Deny unless the artifact carries a signature from an active key, even
+    /// when no scan policy requires one.
+    #[serde(default)]
+    pub require_signature: bool,
+    /// Deny unless every scanner has completed, even when no scan policy
+    /// blocks unscanned artifacts.
+    #[serde(default)]
+    pub require_scan: bool,
+    /// Target environment, recorded in the audit log (e.g. `production`).
+    pub environment: Option<String>,
+}
+
+/// How the artifact is looked up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateTarget {
+    Digest {
+        sha256: String,
+        repository: Option<String>,
+    },
+    Path {
+        repository: String,
+        path: String,
+    },
+    Coordinates {
+        repository: String,
+        name: String,
+        version: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GateDecision {
+    Allow,
+    Deny,
+}
+
+/// Result of one check against one artifact.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct GateCheck {
+    /// `quarantine`, `policy`, `signature` or `scan`.
+    pub check: String,
+    pub passed: bool,
+    pub message: String,
+}
+
+/// Evaluation of one stored copy of the target.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ArtifactGateResult {
+    pub artifact_id: Uuid,
+    pub repository_key: String,
+    pub path: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub checksum_sha256: String,
+    pub allowed: bool,
+    pub checks: Vec<GateCheck>,
+}
+
+/// The gate's answer.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeployGateResponse {
+    pub decision: GateDecision,
+    pub allowed: bool,
+    /// Why the decision is `deny`; empty when allowed.
+    pub reasons: Vec<String>,
+    pub artifacts: Vec<ArtifactGateResult>,
+    pub environment: Option<String>,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+/// Normalize a digest or digest-pinned image reference to lowercase hex.
+pub fn parse_digest(raw: &str) -> Result<String> {
+    let raw = raw.trim();
+    let digest = raw.rsplit_once('@').map_or(raw, |(_, d)| d);
+    let hex = match digest.split_once(':') {
+        Some((algorithm, hex)) if algorithm.eq_ignore_ascii_case("sha256") => hex,
+        Some((algorithm, _)) => {
+            return Err(AppError::Validation(format!(
+                "Unsupported digest algorithm '{}'; only sha256 is supported",
+                algorithm
+            )))
+        }
+        None => digest,
+    };
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::Validation(
+            "digest must be sha256:<64 hex characters>".to_string(),
+        ));
+    }
+    Ok(hex.to_ascii_lowercase())
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+impl DeployGateRequest {
+    /// Resolve the request to a lookup, rejecting ambiguous combinations.
+    pub fn target(&self) -> Result<GateTarget> {
+        let repository = non_empty(&self.repository);
+        let path = non_empty(&self.path);
+        let name = non_empty(&self.name);
+        let version = non_empty(&self.version);
+
+        if let Some(digest) = non_empty(&self.digest) {
+            if path.is_some() || name.is_some() || version.is_some() {
+                return Err(AppError::Validation(
+                    "Give either a digest or artifact coordinates, not both".to_string(),
+                ));
+            }
+            return Ok(GateTarget::Digest {
+                sha256: parse_digest(&digest)?,
+                repository,
+            });
+        }
+
+        let Some(repository) = repository else {
+            return Err(AppError::Validation(
+                "Either digest, or repository with path or name and version, is required"
+                    .to_string(),
+            ));
+        };
+        match (path, name, version) {
+            (Some(path), None, None) => Ok(GateTarget::Path { repository, path }),
+            (None, Some(name), Some(version)) => Ok(GateTarget::Coordinates {
+                repository,
+                name,
+                version,
+            }),
+            _ => Err(AppError::Validation(
+                "Coordinates must be repository with either path, or name and version".to_string(),
+            )),
+        }
+    }
+}
+
+/// Ids of the live artifacts a target refers to.
+pub async fn resolve_artifacts(db: &PgPool, target: &GateTarget) -> Result<Vec<Uuid>> {
+    let query = match target {
+        GateTarget::Digest { sha256, repository } => sqlx::query_scalar(
+            "SELECT a.id FROM artifacts a JOIN repositories r ON r.id = a.repository_id \
+             WHERE a.checksum_sha256 = $1 AND a.is_deleted = false \
+               AND ($2::text IS NULL OR r.key = $2) \
+             ORDER BY a.created_at LIMIT $3",
+        )
+        .bind(sha256)
+        .bind(repository)
+        .bind(MAX_MATCHES),
+        GateTarget::Path { repository, path } => sqlx::query_scalar(
+            "SELECT a.id FROM artifacts a JOIN repositories r ON r.id = a.repository_id \
+             WHERE r.key = $1 AND a.path = $2 AND a.is_deleted = false",
+        )
+        .bind(repository)
+        .bind(path),
+        GateTarget::Coordinates {
+            repository,
+            name,
+            version,
+        } => sqlx::query_scalar(
+            "SELECT a.id FROM artifacts a JOIN repositories r ON r.id = a.repository_id \
+             WHERE r.key = $1 AND a.name = $2 AND a.version = $3 AND a.is_deleted = false \
+             ORDER BY a.created_at LIMIT $4",
+        )
+        .bind(repository)
+        .bind(name)
+        .bind(version)
+        .bind(MAX_MATCHES),
+    };
+    query
+        .fetch_all(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Everything the checks read about one artifact.
+pub struct GateInputs<'a> {
+    pub bundle: &'a VerificationBundle,
+    pub quarantine_status: Option<&'a str>,
+    pub quarantine_until: Option<DateTime<Utc>>,
+}
+
+fn check(name: &str, passed: bool, message: impl Into<String>) -> GateCheck {
+    GateCheck {
+        check: name.to_string(),
+        passed,
+        message: message.into(),
+    }
+}
+
+/// Run every check for one artifact. Pure, so the decision rules are
+/// testable without a database.
+pub fn evaluate_checks(
+    inputs: &GateInputs<'_>,
+    request: &DeployGateRequest,
+    now: DateTime<Utc>,
+) -> Vec<GateCheck> {
+    let bundle = inputs.bundle;
+    let quarantine = match quarantine_service::check_download_allowed(
+        inputs.quarantine_status,
+        inputs.quarantine_until,
+        now,
+    ) {
+        Ok(()) => check(
+            "quarantine",
+            true,
+            format!(
+                "Quarantine status: {}",
+                inputs.quarantine_status.unwrap_or("none")
+            ),
+        ),
+        Err(AppError::Conflict(message) | AppError::Authorization(message)) => {
+            check("quarantine", false, message)
+        }
+        Err(e) => check("quarantine", false, e.to_string()),
+    };
+    let policy = if bundle.policy.allowed {
+        check("policy", true, "All enabled scan policies pass")
+    } else {
+        check("policy", false, bundle.policy.violations.join("; "))
+    };
+    let mut checks = vec![quarantine, policy];
+
+    if request.require_signature {
+        checks.push(match bundle.signature.key_fingerprint.as_deref() {
+            _ if !bundle.signature.signed => check(
+                "signature",
+                false,
+                "No signature from an active signing key",
+            ),
+            Some(fingerprint) => check(
+                "signature",
+                true,
+                format!("Signed with key {}", fingerprint),
+            ),
+            None => check("signature", true, "Signed with an active key"),
+        });
+    }
+
+    if request.require_scan {
+        let state = bundle.scan_summary.state.as_str();
+        checks.push(check(
+            "scan",
+            state == "completed",
+            format!("Scan state: {}", state),
+        ));
+    }
+
+    checks
+}
+
+/// Combine per-artifact results into the gate's answer.
+pub fn decide(
+    artifacts: Vec<ArtifactGateResult>,
+    environment: Option<String>,
+    now: DateTime<Utc>,
+) -> DeployGateResponse {
+    let reasons: Vec<String> = if artifacts.is_empty() {
+        vec!["No artifact matches the requested digest or coordinates".to_string()]
+    } else {
+        artifacts
+            .iter()
+            .flat_map(|artifact| {
+                artifact.checks.iter().filter(|c| !c.passed).map(move |c| {
+                    format!(
+                        "{}/{}: {}",
+                        artifact.repository_key, artifact.path, c.message
+                    )
+                })
+            })
+            .collect()
+    };
+    let allowed = reasons.is_empty();
+    DeployGateResponse {
+        decision: if allowed {
+            GateDecision::Allow
+        } else {
+            GateDecision::Deny
+        },
+        allowed,
+        reasons,
+        artifacts,
+        environment,
+        evaluated_at: now,
+    }
+}
+
+/// Evaluate one artifact.
+pub async fn evaluate_artifact(
+    db: &PgPool,
+    verification: &ArtifactVerificationService,
+    artifact_id: Uuid,
+    request: &DeployGateRequest,
+) -> Result<ArtifactGateResult> {
+    let bundle = verification.bundle(artifact_id).await?;
+    let (quarantine_status, quarantine_until) =
+        quarantine_service::get_status(db, artifact_id).await?;
+    let checks = evaluate_checks(
+        &GateInputs {
+            bundle: &bundle,
+            quarantine_status: quarantine_status.as_deref(),
+            quarantine_until,
+        },
+        request,
+        Utc::now(),
+    );
+    let artifact = bundle.artifact;
+    Ok(ArtifactGateResult {
+        artifact_id,
+        repository_key: artifact.repository_key,
+        path: artifact.path,
+        name: artifact.name,
+        version: artifact.version,
+        checksum_sha256: artifact.checksum_sha256,
+        allowed: checks.iter().all(|c| c.passed),
+        checks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::artifact_verification_service::{
+        VerificationPolicy, VerificationProvenance, VerificationScanSummary,
+        VerificationSignatureStatus, VerifiedArtifact,
+    };
+
+    const HEX: &str = "4f1c0a9e2b3d4c5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6";
+
+    fn bundle(policy_allowed: bool, signed: bool, scan_state: &str) -> VerificationBundle {
+        VerificationBundle {
+            schema: "test".to_string(),
+            generated_at: Utc::now(),
+            artifact: VerifiedArtifact {
+                id: Uuid::new_v4(),
+                repository_id: Uuid::new_v4(),
+                repository_key: "docker-prod".to_string(),
+                path: "v2/app/manifests/1.0".to_string(),
+                name: "app".to_string(),
+                version: Some("1.0".to_string()),
+                size_bytes: 1,
+                checksum_sha256: HEX.to_string(),
+                uploaded_at: Utc::now(),
+            },
+            scan_summary: VerificationScanSummary {
+                state: scan_state.to_string(),
+                scans: vec![],
+                open_findings: Default::default(),
+            },
+            policy: VerificationPolicy {
+                allowed: policy_allowed,
+                violations: if policy_allowed {
+                    vec![]
+                } else {
+                    vec!["2 critical vulnerabilities".to_string()]
+                },
+            },
+            signature: VerificationSignatureStatus {
+                signed,
+                ..Default::default()
+            },
+            provenance: VerificationProvenance {
+                sbom_documents: vec![],
+                builds: vec![],
+                promotions: vec![],
+            },
+        }
+    }
+
+    fn passed(checks: &[GateCheck], name: &str) -> Option<bool> {
+        checks.iter().find(|c| c.check == name).map(|c| c.passed)
+    }
+
+    #[test]
+    fn test_parse_digest() {
+        assert_eq!(parse_digest(&format!("sha256:{}", HEX)).unwrap(), HEX);
+        assert_eq!(
+            parse_digest(&format!("registry.example.com/app@sha256:{}", HEX)).unwrap(),
+            HEX
+        );
+        assert_eq!(parse_digest(&HEX.to_uppercase()).unwrap(), HEX);
+        assert!(parse_digest("sha512:abcd").is_err());
+        assert!(parse_digest("sha256:abcd").is_err());
+    }
+
+    #[test]
+    fn test_request_target() {
+        let digest = DeployGateRequest {
+            digest: Some(format!("sha256:{}", HEX)),
+            ..Default::default()
+        };
+        assert!(matches!(digest.target(), Ok(GateTarget::Digest { .. })));
+
+        let coords = DeployGateRequest {
+            repository: Some("maven-releases".to_string()),
+            name: Some("app".to_string()),
+            version: Some("1.2.3".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            coords.target(),
+            Ok(GateTarget::Coordinates { .. })
+        ));
+
+        let mixed = DeployGateRequest {
+            digest: Some(HEX.to_string()),
+            path: Some("a/b".to_string()),
+            ..Default::default()
+        };
+        assert!(mixed.target().is_err());
+        assert!(DeployGateRequest::default().target().is_err());
+        let name_only = DeployGateRequest {
+            repository: Some("r".to_string()),
+            name: Some("app".to_string()),
+            ..Default::default()
+        };
+        assert!(name_only.target().is_err());
+    }
+
+    #[test]
+    fn test_evaluate_checks() {
+        let now = Utc::now();
+        let request = DeployGateRequest::default();
+        let b = bundle(true, false, "never_scanned");
+        let checks = evaluate_checks(
+            &GateInputs {
+                bundle: &b,
+                quarantine_status: Some("clean"),
+                quarantine_until: None,
+            },
+            &request,
+            now,
+        );
+        assert!(checks.iter().all(|c| c.passed));
+        assert_eq!(passed(&checks, "signature"), None);
+
+        let strict = DeployGateRequest {
+            require_signature: true,
+            require_scan: true,
+            ..Default::default()
+        };
+        let checks = evaluate_checks(
+            &GateInputs {
+                bundle: &b,
+                quarantine_status: Some("quarantined"),
+                quarantine_until: Some(now + chrono::Duration::hours(1)),
+            },
+            &strict,
+            now,
+        );
+        assert_eq!(passed(&checks, "quarantine"), Some(false));
+        assert_eq!(passed(&checks, "signature"), Some(false));
+        assert_eq!(passed(&checks, "scan"), Some(false));
+
+        let rejected = bundle(false, true, "completed");
+        let checks = evaluate_checks(
+            &GateInputs {
+                bundle: &rejected,
+                quarantine_status: None,
+                quarantine_until: None,
+            },
+            &strict,
+            now,
+        );
+        assert_eq!(passed(&checks, "policy"), Some(false));
+        assert_eq!(passed(&checks, "signature"), Some(true));
+        assert_eq!(passed(&checks, "scan"), Some(true));
+    }
+
+    #[test]
+    fn test_decide_fails_closed() {
+        let now = Utc::now();
+        let empty = decide(vec![], None, now);
+        assert_eq!(empty.decision, GateDecision::Deny);
+        assert_eq!(empty.reasons.len(), 1);
+
+        let result = |allowed: bool| ArtifactGateResult {
+            artifact_id: Uuid::new_v4(),
+            repository_key: "docker-prod".to_string(),
+            path: "app".to_string(),
+            name: "app".to_string(),
+            version: None,
+            checksum_sha256: HEX.to_string(),
+            allowed,
+            checks: vec![check("policy", allowed, "msg")],
+        };
+        let allow = decide(vec![result(true)], Some("prod".to_string()), now);
+        assert_eq!(allow.decision, GateDecision::Allow);
+        assert!(allow.reasons.is_empty());
+
+        let deny = decide(vec![result(true), result(false)], None, now);
+        assert_eq!(deny.decision, GateDecision::Deny);
+        assert_eq!(deny.reasons, vec!["docker-prod/app: msg".to_string()]);
+    }
+}
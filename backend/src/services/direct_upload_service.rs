@@ -0,0 +1,310 @@
+//! Direct-to-storage upload sessions.
+//!
+//! A client declares an artifact's size and SHA-256, receives presigned part
+//! URLs for a multipart upload into a staging key, PUTs the parts straight to
+//! the object store and then asks the backend to register the result. This
+//! service owns the session rows and part planning; the storage side lives
+//! behind [`crate::storage::DirectUploadStore`].
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::storage::{StorageLocation, StorageRegistry};
+
+/// Smallest part handed out. Above S3's 5 MiB floor so every part but the
+/// last is valid, and large enough that typical artifacts need few URLs.
+const MIN_PART_SIZE: i64 = 16 * 1024 * 1024;
+/// S3's per-part ceiling (5 GiB).
+const MAX_PART_SIZE: i64 = 5 * 1024 * 1024 * 1024;
+/// Parts are sized so an upload needs about this many URLs; the 5 GiB
+/// ceiling lets the largest S3 object (5 TiB) use at most 1,024.
+const TARGET_PARTS: i64 = 1_000;
+/// S3's maximum object size (5 TiB).
+const MAX_OBJECT_SIZE: i64 = 5 * 1024 * 1024 * 1024 * 1024;
+/// How long a session and its part URLs stay valid.
+pub const SESSION_TTL_HOURS: i64 = 24;
+
+/// A direct upload session row.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DirectUpload {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub repository_id: Uuid,
+    pub artifact_path: String,
+    pub content_type: Option<String>,
+    pub total_size: i64,
+    pub part_size: i64,
+    pub part_count: i32,
+    pub checksum_sha256: String,
+    pub staging_key: String,
+    pub multipart_upload_id: String,
+    pub status: String,
+    pub artifact_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Values for a new session.
+pub struct NewDirectUpload<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub repository_id: Uuid,
+    pub artifact_path: &'a str,
+    pub content_type: Option<&'a str>,
+    pub total_size: i64,
+    pub checksum_sha256: &'a str,
+    pub staging_key: &'a str,
+    pub multipart_upload_id: &'a str,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Part size and count for an object of `total_size` bytes.
+pub fn plan_parts(total_size: i64) -> Result<(i64, i32)> {
+    if total_size <= 0 {
+        return Err(AppError::Validation(
+            "size must be a positive integer".to_string(),
+        ));
+    }
+    if total_size > MAX_OBJECT_SIZE {
+        return Err(AppError::Validation(format!(
+            "size {} exceeds the 5 TiB object storage limit",
+            total_size
+        )));
+    }
+    // Round up to whole MiB so part boundaries are easy to reproduce.
+    let mib = 1024 * 1024;
+    let part_size =
+        (total_size.div_ceil(TARGET_PARTS).div_ceil(mib) * mib).clamp(MIN_PART_SIZE, MAX_PART_SIZE);
+    let part_count = total_size.div_ceil(part_size) as i32;
+    Ok((part_size, part_count))
+}
+
+/// Key the parts are assembled under before the content is registered.
+pub fn staging_key(id: Uuid) -> String {
+    format!("direct-uploads/{}", id)
+}
+
+pub struct DirectUploadService {
+    db: PgPool,
+}
+
+impl DirectUploadService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, new: NewDirectUpload<'_>) -> Result<DirectUpload> {
+        let (part_size, part_count) = plan_parts(new.total_size)?;
+        let upload = sqlx::query_as::<_, DirectUpload>(
+            r#"
+            INSERT INTO direct_uploads (
+                id, user_id, repository_id, artifact_path, content_type,
+                total_size, part_size, part_count, checksum_sha256,
+                staging_key, multipart_upload_id, expires_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING *
+            "#,
+        )
+        .bind(new.id)
+        .bind(new.user_id)
+        .bind(new.repository_id)
+        .bind(new.artifact_path)
+        .bind(new.content_type)
+        .bind(new.total_size)
+        .bind(part_size)
+        .bind(part_count)
+        .bind(new.checksum_sha256)
+        .bind(new.staging_key)
+        .bind(new.multipart_upload_id)
+        .bind(new.expires_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(upload)
+    }
+
+    /// Move a pending, unexpired session owned by `user_id` to `completing`.
+    /// Exactly one concurrent caller wins; the rest get a conflict.
+    pub async fn claim(&self, id: Uuid, user_id: Uuid) -> Result<DirectUpload> {
+        let claimed = sqlx::query_as::<_, DirectUpload>(
+            r#"
+            UPDATE direct_uploads SET status = 'completing'
+            WHERE id = $1 AND user_id = $2 AND status = 'pending' AND expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        if let Some(upload) = claimed {
+            return Ok(upload);
+        }
+
+        match self.get_owned(id, user_id).await? {
+            upload if upload.status != "pending" => Err(AppError::Conflict(format!(
+                "Upload {} is already {}",
+                id, upload.status
+            ))),
+            _ => Err(AppError::Validation(format!("Upload {} has expired", id))),
+        }
+    }
+
+    /// A session owned by `user_id`.
+    pub async fn get_owned(&self, id: Uuid, user_id: Uuid) -> Result<DirectUpload> {
+        sqlx::query_as::<_, DirectUpload>(
+            "SELECT * FROM direct_uploads WHERE id = $1 AND user_id = $2",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Upload {} not found", id)))
+    }
+
+    /// Mark a session completed and link the artifact registered at its path.
+    pub async fn mark_completed(&self, id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE direct_uploads d SET status = 'completed', artifact_id = (
+                SELECT a.id FROM artifacts a
+                WHERE a.repository_id = d.repository_id
+                  AND a.path = d.artifact_path
+                  AND a.is_deleted = false
+            )
+            WHERE d.id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Move a pending session owned by `user_id` to `failed` so it can no
+    /// longer be completed, returning it for the caller to abort.
+    pub async fn cancel(&self, id: Uuid, user_id: Uuid) -> Result<DirectUpload> {
+        let cancelled = sqlx::query_as::<_, DirectUpload>(
+            r#"
+            UPDATE direct_uploads SET status = 'failed'
+            WHERE id = $1 AND user_id = $2 AND status = 'pending'
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        match cancelled {
+            Some(upload) => Ok(upload),
+            None => {
+                let upload = self.get_owned(id, user_id).await?;
+                Err(AppError::Conflict(format!(
+                    "Upload {} is already {}",
+                    id, upload.status
+                )))
+            }
+        }
+    }
+
+    pub async fn mark_failed(&self, id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE direct_uploads SET status = 'failed' WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Abort the multipart uploads of expired sessions, remove any staged
+    /// object and delete the rows. Returns the number of sessions removed.
+    pub async fn cleanup_expired(&self, registry: &StorageRegistry) -> Result<u64> {
+        let expired: Vec<(Uuid, String, String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT d.id, d.staging_key, d.multipart_upload_id,
+                   r.storage_backend, r.storage_path
+            FROM direct_uploads d
+            JOIN repositories r ON r.id = d.repository_id
+            WHERE d.expires_at < NOW()
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut removed = 0;
+        for (id, staging_key, upload_id, backend, path) in expired {
+            let storage = match registry.backend_for(&StorageLocation { backend, path }) {
+                Ok(storage) => storage,
+                Err(e) => {
+                    tracing::warn!(upload_id = %id, "Skipping expired direct upload: {}", e);
+                    continue;
+                }
+            };
+            if let Some(direct) = storage.direct_uploads() {
+                if let Err(e) = direct
+                    .abort_multipart_upload(&staging_key, &upload_id)
+                    .await
+                {
+                    tracing::warn!(upload_id = %id, "Failed to abort expired direct upload: {}", e);
+                    continue;
+                }
+            }
+            if let Err(e) = storage.delete(&staging_key).await {
+                tracing::debug!(upload_id = %id, "No staged object to remove: {}", e);
+            }
+            sqlx::query("DELETE FROM direct_uploads WHERE id = $1")
+                .bind(id)
+                .execute(&self.db)
+                .await?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: i64 = 1024 * 1024;
+    const GIB: i64 = 1024 * MIB;
+
+    #[test]
+    fn test_plan_parts_small_object_is_one_part() {
+        assert_eq!(plan_parts(1).unwrap(), (MIN_PART_SIZE, 1));
+        assert_eq!(plan_parts(MIN_PART_SIZE).unwrap(), (MIN_PART_SIZE, 1));
+        assert_eq!(plan_parts(MIN_PART_SIZE + 1).unwrap(), (MIN_PART_SIZE, 2));
+    }
+
+    #[test]
+    fn test_plan_parts_large_object_targets_part_count() {
+        let (part_size, part_count) = plan_parts(100 * GIB).unwrap();
+        assert_eq!(part_size % MIB, 0);
+        assert!(part_count <= TARGET_PARTS as i32);
+        assert!(part_size * part_count as i64 >= 100 * GIB);
+    }
+
+    #[test]
+    fn test_plan_parts_max_object_fits_s3_limits() {
+        let (part_size, part_count) = plan_parts(MAX_OBJECT_SIZE).unwrap();
+        assert_eq!(part_size, MAX_PART_SIZE);
+        assert_eq!(part_count, 1024);
+    }
+
+    #[test]
+    fn test_plan_parts_rejects_invalid_sizes() {
+        assert!(plan_parts(0).is_err());
+        assert!(plan_parts(-5).is_err());
+        assert!(plan_parts(MAX_OBJECT_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn test_staging_key() {
+        let id = Uuid::nil();
+        assert_eq!(
+            staging_key(id),
+            "direct-uploads/00000000-0000-0000-0000-000000000000"
+        );
+    }
+}
@@ -0,0 +1,495 @@
+//! Edge node diagnostics forwarding.
+//!
+//! Edge nodes often run on branch hardware nobody can SSH into. A node that
+//! joined through the approval queue (see [`crate::services::peer_join_service`])
+//! can forward its own diagnostics to the primary on a dedicated channel:
+//! every interval it posts its rendered Prometheus metrics and the most
+//! recent warning/error log lines to `POST /api/v1/peers/join/:id/diagnostics`,
+//! authenticated with its node secret. The primary keeps the latest snapshot
+//! per peer and serves it at `GET /api/v1/admin/edge-nodes/:id/diagnostics`.
+//!
+//! Log lines are captured in-process by [`LogCaptureLayer`], which the
+//! tracing subscriber installs unconditionally; it keeps a small ring buffer
+//! of `WARN` and `ERROR` events and costs nothing for lower levels.
+//!
+//! Forwarding is off unless the node is configured (environment):
+//!
+//! | Variable | Default | Meaning |
+//! |---|---|---|
+//! | `AK_EDGE_PRIMARY_URL` | unset | Base URL of the primary; unset disables forwarding |
+//! | `AK_EDGE_JOIN_REQUEST_ID` | unset | Join request id returned when the node joined |
+//! | `AK_EDGE_NODE_SECRET` | unset | Node secret returned when the node joined |
+//! | `AK_EDGE_DIAGNOSTICS_INTERVAL_SECS` | 60 | Forward interval (min 15) |
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use metrics_exporter_prometheus::PrometheusHandle;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+const MIN_INTERVAL_SECS: u64 = 15;
+const PUSH_TIMEOUT_SECS: u64 = 30;
+
+/// Log lines kept in memory and forwarded per batch.
+pub const MAX_LOG_EXCERPTS: usize = 200;
+/// Longest message kept per log line.
+pub const MAX_LOG_MESSAGE_BYTES: usize = 2048;
+/// Largest metrics exposition accepted (and sent) per batch.
+pub const MAX_METRICS_BYTES: usize = 2 * 1024 * 1024;
+
+/// One captured log line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LogExcerpt {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// A diagnostics batch as sent by an edge node.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiagnosticsBatch {
+    pub collected_at: DateTime<Utc>,
+    /// Version of the node's artifact-keeper build.
+    pub version: Option<String>,
+    /// Prometheus text exposition of the node's `/metrics`.
+    #[serde(default)]
+    pub metrics: String,
+    /// Most recent warning and error log lines, oldest first.
+    #[serde(default)]
+    pub logs: Vec<LogExcerpt>,
+}
+
+impl DiagnosticsBatch {
+    pub fn validate(&self) -> Result<()> {
+        if self.metrics.len() > MAX_METRICS_BYTES {
+            return Err(AppError::Validation(format!(
+                "metrics must be at most {} bytes",
+                MAX_METRICS_BYTES
+            )));
+        }
+        if self.logs.len() > MAX_LOG_EXCERPTS {
+            return Err(AppError::Validation(format!(
+                "at most {} log lines may be sent per batch",
+                MAX_LOG_EXCERPTS
+            )));
+        }
+        if matches!(&self.version, Some(v) if v.len() > 64) {
+            return Err(AppError::Validation(
+                "version must be at most 64 characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The latest diagnostics stored for a peer.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EdgeDiagnostics {
+    pub peer_instance_id: Uuid,
+    pub peer_name: String,
+    pub node_version: Option<String>,
+    pub metrics: String,
+    pub logs: Vec<LogExcerpt>,
+    pub collected_at: DateTime<Utc>,
+    pub received_at: DateTime<Utc>,
+}
+
+// ---------------------------------------------------------------------------
+// Log capture
+// ---------------------------------------------------------------------------
+
+static RECENT_LOGS: Mutex<VecDeque<LogExcerpt>> = Mutex::new(VecDeque::new());
+
+/// Tracing layer that keeps the most recent `WARN`/`ERROR` events in memory
+/// for [`recent_logs`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogCaptureLayer;
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogCaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let level = *event.metadata().level();
+        if level > Level::WARN {
+            return;
+        }
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let mut message = visitor.message;
+        message.push_str(&visitor.fields);
+        push_log(LogExcerpt {
+            timestamp: Utc::now(),
+            level: level.to_string(),
+            target: event.metadata().target().to_string(),
+            message: truncate_utf8(&message, MAX_LOG_MESSAGE_BYTES).to_string(),
+        });
+    }
+}
+
+fn push_log(excerpt: LogExcerpt) {
+    let Ok(mut logs) = RECENT_LOGS.lock() else {
+        return;
+    };
+    if logs.len() == MAX_LOG_EXCERPTS {
+        logs.pop_front();
+    }
+    logs.push_back(excerpt);
+}
+
+/// Snapshot of the captured log lines, oldest first.
+pub fn recent_logs() -> Vec<LogExcerpt> {
+    RECENT_LOGS
+        .lock()
+        .map(|logs| logs.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Longest prefix of `s` that fits in `max` bytes without splitting a
+/// character.
+fn truncate_utf8(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// Cap a metrics exposition at `max` bytes, cutting at a line boundary so
+/// the receiver never sees a partial sample.
+fn truncate_metrics(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let head = truncate_utf8(text, max);
+    match head.rfind('\n') {
+        Some(idx) => &head[..=idx],
+        None => "",
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Primary side
+// ---------------------------------------------------------------------------
+
+/// Store a node's batch as the latest diagnostics for its peer instance.
+pub async fn record(db: &PgPool, peer_instance_id: Uuid, batch: &DiagnosticsBatch) -> Result<()> {
+    batch.validate()?;
+    let logs = serde_json::to_value(&batch.logs)
+        .map_err(|e| AppError::Internal(format!("Failed to encode log excerpts: {}", e)))?;
+    sqlx::query(
+        "INSERT INTO peer_diagnostics \
+             (peer_instance_id, node_version, metrics_text, logs, collected_at) \
+         VALUES ($1, $2, $3, $4, $5) \
+         ON CONFLICT (peer_instance_id) DO UPDATE SET \
+             node_version = EXCLUDED.node_version, \
+             metrics_text = EXCLUDED.metrics_text, \
+             logs = EXCLUDED.logs, \
+             collected_at = EXCLUDED.collected_at, \
+             received_at = NOW()",
+    )
+    .bind(peer_instance_id)
+    .bind(&batch.version)
+    .bind(&batch.metrics)
+    .bind(logs)
+    .bind(batch.collected_at)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Latest diagnostics for a peer instance.
+pub async fn get(db: &PgPool, peer_instance_id: Uuid) -> Result<EdgeDiagnostics> {
+    let row: Option<(
+        String,
+        Option<String>,
+        String,
+        serde_json::Value,
+        DateTime<Utc>,
+        DateTime<Utc>,
+    )> = sqlx::query_as(
+        "SELECT p.name, d.node_version, d.metrics_text, d.logs, d.collected_at, d.received_at \
+         FROM peer_diagnostics d \
+         JOIN peer_instances p ON p.id = d.peer_instance_id \
+         WHERE d.peer_instance_id = $1",
+    )
+    .bind(peer_instance_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let (peer_name, node_version, metrics, logs, collected_at, received_at) =
+        row.ok_or_else(|| {
+            AppError::NotFound("No diagnostics have been received from this node".to_string())
+        })?;
+    Ok(EdgeDiagnostics {
+        peer_instance_id,
+        peer_name,
+        node_version,
+        metrics,
+        logs: serde_json::from_value(logs).unwrap_or_default(),
+        collected_at,
+        received_at,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Edge side
+// ---------------------------------------------------------------------------
+
+/// Forwarder settings: how this node reaches the primary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwarderConfig {
+    /// URL of this node's join request on the primary
+    /// (`{primary}/api/v1/peers/join/{id}`).
+    pub node_url: String,
+    pub node_secret: String,
+    pub interval: Duration,
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl ForwarderConfig {
+    /// Load settings from the environment. `None` when forwarding is not
+    /// configured; a partial configuration is logged and ignored.
+    pub fn from_env() -> Option<Self> {
+        let primary = non_empty_env("AK_EDGE_PRIMARY_URL")?;
+        let (Some(request_id), Some(node_secret)) = (
+            non_empty_env("AK_EDGE_JOIN_REQUEST_ID"),
+            non_empty_env("AK_EDGE_NODE_SECRET"),
+        ) else {
+            tracing::warn!(
+                "AK_EDGE_PRIMARY_URL is set but AK_EDGE_JOIN_REQUEST_ID or \
+                 AK_EDGE_NODE_SECRET is missing; diagnostics forwarding disabled"
+            );
+            return None;
+        };
+        let Ok(request_id) = request_id.parse::<Uuid>() else {
+            tracing::warn!(
+                "AK_EDGE_JOIN_REQUEST_ID is not a UUID; diagnostics forwarding disabled"
+            );
+            return None;
+        };
+        let interval_secs = non_empty_env("AK_EDGE_DIAGNOSTICS_INTERVAL_SECS")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_INTERVAL_SECS)
+            .max(MIN_INTERVAL_SECS);
+        Some(Self {
+            node_url: node_url(&primary, request_id),
+            node_secret,
+            interval: Duration::from_secs(interval_secs),
+        })
+    }
+
+    /// URL of a node-facing action on the primary, e.g. `diagnostics`.
+    pub fn endpoint(&self, action: &str) -> String {
+        format!("{}/{}", self.node_url, action)
+    }
+}
+
+fn node_url(primary: &str, request_id: Uuid) -> String {
+    format!(
+        "{}/api/v1/peers/join/{}",
+        primary.trim_end_matches('/'),
+        request_id
+    )
+}
+
+/// Assemble the batch this node would send now.
+pub fn collect_batch(metrics_handle: Option<&PrometheusHandle>) -> DiagnosticsBatch {
+    let metrics = metrics_handle
+        .map(|h| truncate_metrics(&h.render(), MAX_METRICS_BYTES).to_string())
+        .unwrap_or_default();
+    DiagnosticsBatch {
+        collected_at: Utc::now(),
+        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        metrics,
+        logs: recent_logs(),
+    }
+}
+
+async fn forward(
+    client: &reqwest::Client,
+    config: &ForwarderConfig,
+    batch: &DiagnosticsBatch,
+) -> Result<()> {
+    let resp = client
+        .post(config.endpoint("diagnostics"))
+        .bearer_auth(&config.node_secret)
+        .json(batch)
+        .send()
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Diagnostics upload failed: {}", e)))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let detail = resp.text().await.unwrap_or_default();
+        return Err(AppError::BadGateway(format!(
+            "Primary rejected diagnostics with {}: {}",
+            status,
+            detail.chars().take(512).collect::<String>()
+        )));
+    }
+    Ok(())
+}
+
+/// Start forwarding diagnostics to the primary when `AK_EDGE_PRIMARY_URL`
+/// is set.
+pub fn start_forwarder(metrics_handle: Option<Arc<PrometheusHandle>>) {
+    let Some(config) = ForwarderConfig::from_env() else {
+        return;
+    };
+    // The primary is operator-configured and commonly on a private network.
+    let client = match crate::services::http_client::internal_service_client_builder()
+        .timeout(Duration::from_secs(PUSH_TIMEOUT_SECS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!(
+                "Edge diagnostics forwarding disabled: HTTP client error: {}",
+                e
+            );
+            return;
+        }
+    };
+    tracing::info!(
+        interval_secs = config.interval.as_secs(),
+        "Edge diagnostics forwarding enabled"
+    );
+
+    tokio::spawn(async move {
+        let mut ticker = interval(config.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let batch = collect_batch(metrics_handle.as_deref());
+            match forward(&client, &config, &batch).await {
+                Ok(()) => tracing::debug!(
+                    logs = batch.logs.len(),
+                    metrics_bytes = batch.metrics.len(),
+                    "Forwarded diagnostics to primary"
+                ),
+                // Logged at info so a primary outage does not feed its own
+                // failure into the captured warning buffer every interval.
+                Err(e) => tracing::info!("Edge diagnostics forwarding failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn excerpt(message: &str) -> LogExcerpt {
+        LogExcerpt {
+            timestamp: Utc::now(),
+            level: "WARN".to_string(),
+            target: "test".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_truncate_utf8_respects_char_boundaries() {
+        assert_eq!(truncate_utf8("abc", 10), "abc");
+        assert_eq!(truncate_utf8("héllo", 2), "h");
+        assert_eq!(truncate_utf8("héllo", 3), "hé");
+    }
+
+    #[test]
+    fn test_truncate_metrics_cuts_at_line_boundary() {
+        let text = "a 1\nb 2\nc 3\n";
+        assert_eq!(truncate_metrics(text, 100), text);
+        assert_eq!(truncate_metrics(text, 9), "a 1\nb 2\n");
+        assert_eq!(truncate_metrics(text, 2), "");
+    }
+
+    #[test]
+    fn test_endpoint_urls() {
+        let id = Uuid::nil();
+        let config = ForwarderConfig {
+            node_url: node_url("https://primary.example.com/", id),
+            node_secret: "akns_x".to_string(),
+            interval: Duration::from_secs(60),
+        };
+        assert_eq!(
+            config.endpoint("diagnostics"),
+            format!("https://primary.example.com/api/v1/peers/join/{id}/diagnostics")
+        );
+    }
+
+    #[test]
+    fn test_batch_validation_limits() {
+        let mut batch = DiagnosticsBatch {
+            collected_at: Utc::now(),
+            version: Some("1.0.0".to_string()),
+            metrics: "up 1\n".to_string(),
+            logs: vec![excerpt("disk almost full")],
+        };
+        batch.validate().unwrap();
+
+        batch.logs = vec![excerpt("x"); MAX_LOG_EXCERPTS + 1];
+        assert!(batch.validate().is_err());
+
+        batch.logs.clear();
+        batch.metrics = "x".repeat(MAX_METRICS_BYTES + 1);
+        assert!(batch.validate().is_err());
+    }
+
+    #[test]
+    fn test_log_buffer_is_bounded() {
+        for i in 0..MAX_LOG_EXCERPTS + 5 {
+            push_log(excerpt(&format!("line {i}")));
+        }
+        let logs = recent_logs();
+        assert_eq!(logs.len(), MAX_LOG_EXCERPTS);
+        assert!(logs
+            .iter()
+            .any(|l| l.message == format!("line {}", MAX_LOG_EXCERPTS + 4)));
+    }
+}
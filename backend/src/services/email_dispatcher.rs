@@ -221,7 +221,7 @@ pub fn build_email_body_text(event: &DomainEvent) -> String {
 ///
 /// Fix for #920 security review M2 (stored-XSS-in-email via event
 /// fields rendered by Gmail / Outlook web clients).
-fn html_escape(s: &str) -> String {
+pub(crate) fn html_escape(s: &str) -> String {
     let mut out = String::with_capacity(s.len());
     for ch in s.chars() {
         match ch {
@@ -0,0 +1,825 @@
+//! External authorization hook (OPA / Rego or a plain webhook).
+//!
+//! Lets operators layer org-specific rules on top of the built-in RBAC
+//! without forking it. When `AUTHZ_HOOK_URL` is set, every repository access
+//! the built-in checks have already allowed is also put to the hook as an
+//! [`AuthzInput`]: the caller, the action, the repository with its labels and
+//! the repository-relative path. The hook can only narrow access — a deny
+//! turns an allowed request into a 403, an allow never overrides an RBAC
+//! denial — and admins bypass it the same way they bypass fine-grained rules,
+//! so a broken policy cannot lock operators out.
+//!
+//! Two wire protocols are supported (`AUTHZ_HOOK_KIND`):
+//!
+//! * `opa` (default): `POST {"input": ...}` to an OPA data API URL such as
+//!   `http://opa:8181/v1/data/artifact_keeper/authz`. `result` may be a bare
+//!   boolean or an object with `allow` and an optional `reason`; an undefined
+//!   result (no policy loaded at that path) denies.
+//! * `webhook`: `POST` the input itself; the response is
+//!   `{"allow": bool, "reason": "..."}`.
+//!
+//! Decisions are cached per input for [`DECISION_CACHE_TTL`], so label or
+//! policy changes take up to that long to apply. When the hook cannot be
+//! reached the request fails closed (503) unless `AUTHZ_HOOK_FAIL_OPEN=true`.
+//!
+//! Inline Rego policies live in `authz_policies` and are pushed to OPA's
+//! policy API as module `artifact-keeper/<name>` whenever they change, and
+//! again at startup by [`ExternalAuthzService::sync_policies`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// How long a hook decision is reused for an identical input.
+pub const DECISION_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Cached decisions beyond this count are dropped wholesale rather than
+/// letting a scan over many paths grow the cache without bound.
+const DECISION_CACHE_MAX_ENTRIES: usize = 10_000;
+
+const DEFAULT_TIMEOUT_MS: u64 = 2_000;
+
+/// OPA module id prefix for policies managed through the admin API.
+const OPA_POLICY_ID_PREFIX: &str = "artifact-keeper/";
+
+/// Wire protocol spoken to the hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthzHookKind {
+    /// OPA data API: the input is wrapped in `{"input": ...}` and the decision
+    /// is read from `result`.
+    Opa,
+    /// Generic webhook: the input is the body and the decision is the body.
+    Webhook,
+}
+
+impl AuthzHookKind {
+    fn parse(value: Option<&str>) -> Result<Self> {
+        match value.map(|v| v.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("opa") => Ok(Self::Opa),
+            Some("webhook") => Ok(Self::Webhook),
+            Some(other) => Err(AppError::Config(format!(
+                "AUTHZ_HOOK_KIND must be 'opa' or 'webhook', got '{other}'"
+            ))),
+        }
+    }
+}
+
+/// Hook configuration read from `AUTHZ_HOOK_*`.
+#[derive(Clone)]
+pub struct ExternalAuthzConfig {
+    pub url: String,
+    pub kind: AuthzHookKind,
+    pub timeout: Duration,
+    /// Allow requests when the hook is unreachable or answers garbage.
+    pub fail_open: bool,
+    /// Sent as `Authorization: Bearer <token>` when set.
+    pub token: Option<String>,
+}
+
+impl std::fmt::Debug for ExternalAuthzConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalAuthzConfig")
+            .field("url", &self.url)
+            .field("kind", &self.kind)
+            .field("timeout", &self.timeout)
+            .field("fail_open", &self.fail_open)
+            .field("token", &self.token.as_ref().map(|_| "[REDACTED]"))
+            .finish()
+    }
+}
+
+impl ExternalAuthzConfig {
+    /// Read the hook configuration, or `None` when `AUTHZ_HOOK_URL` is unset
+    /// (the hook is disabled). A set but malformed configuration is an error
+    /// so a typo cannot silently turn the policy layer off.
+    pub fn from_env() -> Option<Result<Self>> {
+        let var = |name: &str| std::env::var(name).ok();
+        Self::from_values(
+            var("AUTHZ_HOOK_URL").as_deref(),
+            var("AUTHZ_HOOK_KIND").as_deref(),
+            var("AUTHZ_HOOK_TIMEOUT_MS").as_deref(),
+            var("AUTHZ_HOOK_FAIL_OPEN").as_deref(),
+            var("AUTHZ_HOOK_TOKEN").as_deref(),
+        )
+    }
+
+    fn from_values(
+        url: Option<&str>,
+        kind: Option<&str>,
+        timeout_ms: Option<&str>,
+        fail_open: Option<&str>,
+        token: Option<&str>,
+    ) -> Option<Result<Self>> {
+        let url = url.map(str::trim).filter(|u| !u.is_empty())?;
+        Some((|| -> Result<Self> {
+            let parsed = url::Url::parse(url)
+                .map_err(|e| AppError::Config(format!("AUTHZ_HOOK_URL is not a valid URL: {e}")))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(AppError::Config(
+                    "AUTHZ_HOOK_URL must be an http(s) URL".to_string(),
+                ));
+            }
+            let timeout_ms = match timeout_ms.map(str::trim).filter(|v| !v.is_empty()) {
+                None => DEFAULT_TIMEOUT_MS,
+                Some(v) => v.parse::<u64>().ok().filter(|ms| *ms > 0).ok_or_else(|| {
+                    AppError::Config(format!(
+                        "AUTHZ_HOOK_TIMEOUT_MS must be a positive integer, got '{v}'"
+                    ))
+                })?,
+            };
+            Ok(Self {
+                url: url.to_string(),
+                kind: AuthzHookKind::parse(kind)?,
+                timeout: Duration::from_millis(timeout_ms),
+                fail_open: matches!(
+                    fail_open.map(|v| v.trim().to_lowercase()).as_deref(),
+                    Some("true" | "1")
+                ),
+                token: token
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string),
+            })
+        })())
+    }
+}
+
+/// The caller as seen by the hook. `None` in [`AuthzInput::user`] means an
+/// anonymous request.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuthzUser {
+    pub id: Uuid,
+    pub username: String,
+    pub is_admin: bool,
+    pub is_service_account: bool,
+}
+
+/// The repository being accessed.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuthzRepository {
+    pub id: Uuid,
+    pub key: String,
+    pub format: String,
+    pub repo_type: String,
+    pub is_public: bool,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Everything the hook is told about one access.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuthzInput {
+    pub user: Option<AuthzUser>,
+    /// `read`, `write` or `delete`.
+    pub action: String,
+    pub repository: AuthzRepository,
+    /// Repository-relative path for native-protocol requests; absent for
+    /// REST operations that are not addressed by path.
+    pub path: Option<String>,
+}
+
+/// The hook's answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuthzDecision {
+    pub allow: bool,
+    pub reason: Option<String>,
+}
+
+impl AuthzDecision {
+    fn deny(reason: &str) -> Self {
+        Self {
+            allow: false,
+            reason: Some(reason.to_string()),
+        }
+    }
+}
+
+/// Read a decision out of a hook response body.
+fn parse_decision(kind: AuthzHookKind, body: &serde_json::Value) -> Result<AuthzDecision> {
+    let from_object = |obj: &serde_json::Value| -> Option<AuthzDecision> {
+        Some(AuthzDecision {
+            allow: obj.get("allow")?.as_bool()?,
+            reason: obj
+                .get("reason")
+                .and_then(|r| r.as_str())
+                .map(str::to_string),
+        })
+    };
+    let decision = match kind {
+        AuthzHookKind::Opa => match body.get("result") {
+            None | Some(serde_json::Value::Null) => {
+                Some(AuthzDecision::deny("policy decision is undefined"))
+            }
+            Some(serde_json::Value::Bool(allow)) => Some(AuthzDecision {
+                allow: *allow,
+                reason: None,
+            }),
+            // An object without `allow` is an undefined rule inside the
+            // package: deny, like an undefined result.
+            Some(result @ serde_json::Value::Object(_)) => {
+                Some(from_object(result).unwrap_or_else(|| {
+                    AuthzDecision::deny("policy decision has no boolean 'allow'")
+                }))
+            }
+            Some(_) => None,
+        },
+        AuthzHookKind::Webhook => from_object(body),
+    };
+    decision.ok_or_else(|| {
+        AppError::Internal("authorization hook returned an unrecognised decision".to_string())
+    })
+}
+
+/// An inline Rego policy managed through the admin API.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct AuthzPolicy {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub rego: String,
+    pub enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fields for creating or replacing an inline policy.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct AuthzPolicyInput {
+    /// Lowercase letters, digits, `-` and `_`; becomes the OPA module id
+    /// `artifact-keeper/<name>`.
+    pub name: String,
+    pub description: Option<String>,
+    pub rego: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn validate_policy_input(input: &AuthzPolicyInput) -> Result<()> {
+    let name_ok = !input.name.is_empty()
+        && input.name.len() <= 128
+        && input
+            .name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !name_ok {
+        return Err(AppError::Validation(
+            "Policy name must be 1-128 characters of lowercase letters, digits, '-' or '_'"
+                .to_string(),
+        ));
+    }
+    if input.rego.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Policy rego must not be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+const POLICY_COLUMNS: &str =
+    "id, name, description, rego, enabled, created_by, created_at, updated_at";
+
+/// Client for the configured authorization hook.
+pub struct ExternalAuthzService {
+    config: ExternalAuthzConfig,
+    client: reqwest::Client,
+    cache: RwLock<HashMap<String, (AuthzDecision, Instant)>>,
+}
+
+impl ExternalAuthzService {
+    pub fn new(config: ExternalAuthzConfig) -> Result<Self> {
+        // The hook URL is operator configuration, so private addresses are
+        // reachable; loopback stays blocked, so point it at a service name or
+        // pod address rather than `localhost`.
+        let client = crate::services::http_client::internal_service_client_builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            config,
+            client,
+            cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Create from environment variables, returns None if not enabled
+    pub fn from_env() -> Option<Result<Self>> {
+        ExternalAuthzConfig::from_env().map(|config| config.and_then(Self::new))
+    }
+
+    pub fn config(&self) -> &ExternalAuthzConfig {
+        &self.config
+    }
+
+    /// Build the hook input for `repository_id`, loading the repository and
+    /// its labels.
+    pub async fn build_input(
+        db: &PgPool,
+        user: Option<AuthzUser>,
+        action: &str,
+        repository_id: Uuid,
+        path: Option<&str>,
+    ) -> Result<AuthzInput> {
+        let (id, key, format, repo_type, is_public) =
+            sqlx::query_as::<_, (Uuid, String, String, String, bool)>(
+                "SELECT id, key, format::text, repo_type::text, is_public \
+                 FROM repositories WHERE id = $1",
+            )
+            .bind(repository_id)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .ok_or_else(|| AppError::NotFound(format!("Repository {repository_id} not found")))?;
+        let labels = sqlx::query_as::<_, (String, String)>(
+            "SELECT label_key, label_value FROM repository_labels WHERE repository_id = $1",
+        )
+        .bind(repository_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .into_iter()
+        .collect();
+        Ok(AuthzInput {
+            user,
+            action: action.to_string(),
+            repository: AuthzRepository {
+                id,
+                key,
+                format,
+                repo_type,
+                is_public,
+                labels,
+            },
+            path: path.map(str::to_string),
+        })
+    }
+
+    /// Decide an access the built-in checks already allowed.
+    ///
+    /// Admins are allowed without a call. A hook failure yields
+    /// `ServiceUnavailable`, or an allow when the hook is configured to fail
+    /// open.
+    pub async fn authorize(
+        &self,
+        db: &PgPool,
+        user: Option<AuthzUser>,
+        action: &str,
+        repository_id: Uuid,
+        path: Option<&str>,
+    ) -> Result<AuthzDecision> {
+        if user.as_ref().is_some_and(|u| u.is_admin) {
+            return Ok(AuthzDecision {
+                allow: true,
+                reason: None,
+            });
+        }
+        let cache_key = format!(
+            "{}|{action}|{repository_id}|{}",
+            user.as_ref().map(|u| u.id.to_string()).unwrap_or_default(),
+            path.unwrap_or("")
+        );
+        if let Some(decision) = self.cached(&cache_key) {
+            return Ok(decision);
+        }
+
+        let input = Self::build_input(db, user, action, repository_id, path).await?;
+        match self.evaluate(&input).await {
+            Ok(decision) => {
+                self.remember(cache_key, decision.clone());
+                Ok(decision)
+            }
+            Err(e) if self.config.fail_open => {
+                tracing::warn!(
+                    repository_id = %repository_id,
+                    action,
+                    "Authorization hook unavailable, failing open: {}",
+                    e
+                );
+                Ok(AuthzDecision {
+                    allow: true,
+                    reason: Some("authorization hook unavailable (fail open)".to_string()),
+                })
+            }
+            Err(e) => {
+                tracing::error!(
+                    repository_id = %repository_id,
+                    action,
+                    "Authorization hook unavailable, failing closed: {}",
+                    e
+                );
+                Err(AppError::ServiceUnavailable(
+                    "authorization hook temporarily unavailable".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Send `input` to the hook, bypassing the cache and the fail-open
+    /// setting.
+    pub async fn evaluate(&self, input: &AuthzInput) -> Result<AuthzDecision> {
+        let body = match self.config.kind {
+            AuthzHookKind::Opa => serde_json::json!({ "input": input }),
+            AuthzHookKind::Webhook => serde_json::to_value(input)
+                .map_err(|e| AppError::Internal(format!("Failed to encode hook input: {e}")))?,
+        };
+        let mut request = self.client.post(&self.config.url).json(&body);
+        if let Some(token) = &self.config.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Authorization hook request failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(AppError::Internal(format!(
+                "Authorization hook returned HTTP {status}"
+            )));
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| {
+            AppError::Internal(format!("Authorization hook returned invalid JSON: {e}"))
+        })?;
+        parse_decision(self.config.kind, &body)
+    }
+
+    fn cached(&self, key: &str) -> Option<AuthzDecision> {
+        let cache = self.cache.read().ok()?;
+        cache
+            .get(key)
+            .filter(|(_, at)| at.elapsed() < DECISION_CACHE_TTL)
+            .map(|(decision, _)| decision.clone())
+    }
+
+    fn remember(&self, key: String, decision: AuthzDecision) {
+        if let Ok(mut cache) = self.cache.write() {
+            if cache.len() >= DECISION_CACHE_MAX_ENTRIES {
+                cache.retain(|_, (_, at)| at.elapsed() < DECISION_CACHE_TTL);
+                if cache.len() >= DECISION_CACHE_MAX_ENTRIES {
+                    cache.clear();
+                }
+            }
+            cache.insert(key, (decision, Instant::now()));
+        }
+    }
+
+    /// Drop every cached decision, e.g. after a policy change.
+    pub fn invalidate_cache(&self) {
+        if let Ok(mut cache) = self.cache.write() {
+            cache.clear();
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Inline Rego policies
+    // -----------------------------------------------------------------------
+
+    /// `{origin}/v1/policies/artifact-keeper/<name>` on the configured OPA.
+    fn policy_url(&self, name: &str) -> Result<String> {
+        if self.config.kind != AuthzHookKind::Opa {
+            return Err(AppError::Validation(
+                "Inline Rego policies require AUTHZ_HOOK_KIND=opa".to_string(),
+            ));
+        }
+        let url = url::Url::parse(&self.config.url)
+            .map_err(|e| AppError::Internal(format!("Invalid AUTHZ_HOOK_URL: {e}")))?;
+        Ok(format!(
+            "{}/v1/policies/{OPA_POLICY_ID_PREFIX}{name}",
+            url.origin().ascii_serialization()
+        ))
+    }
+
+    /// Upload a policy module to OPA. OPA compiles it on upload, so a Rego
+    /// error comes back as a validation error.
+    pub async fn push_policy(&self, name: &str, rego: &str) -> Result<()> {
+        let mut request = self
+            .client
+            .put(self.policy_url(name)?)
+            .header(reqwest::header::CONTENT_TYPE, "text/plain")
+            .body(rego.to_string());
+        if let Some(token) = &self.config.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::ServiceUnavailable(format!("OPA request failed: {e}")))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::BAD_REQUEST {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(AppError::Validation(format!(
+                "OPA rejected policy '{name}': {detail}"
+            )));
+        }
+        if !status.is_success() {
+            return Err(AppError::ServiceUnavailable(format!(
+                "OPA returned HTTP {status} for policy '{name}'"
+            )));
+        }
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Remove a policy module from OPA. A module OPA does not know is fine.
+    pub async fn delete_policy(&self, name: &str) -> Result<()> {
+        let mut request = self.client.delete(self.policy_url(name)?);
+        if let Some(token) = &self.config.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::ServiceUnavailable(format!("OPA request failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+            return Err(AppError::ServiceUnavailable(format!(
+                "OPA returned HTTP {status} deleting policy '{name}'"
+            )));
+        }
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Push every enabled policy to OPA. Called at startup so a restarted or
+    /// freshly deployed OPA gets the policies back; failures are logged.
+    pub async fn sync_policies(&self, db: &PgPool) -> Result<usize> {
+        if self.config.kind != AuthzHookKind::Opa {
+            return Ok(0);
+        }
+        let policies = list_policies(db).await?;
+        let mut pushed = 0;
+        for policy in policies.iter().filter(|p| p.enabled) {
+            match self.push_policy(&policy.name, &policy.rego).await {
+                Ok(()) => pushed += 1,
+                Err(e) => tracing::warn!(
+                    policy = %policy.name,
+                    "Failed to sync authorization policy to OPA: {}",
+                    e
+                ),
+            }
+        }
+        Ok(pushed)
+    }
+
+    /// Create a policy and push it to OPA. The row is only committed once OPA
+    /// has accepted (compiled) the module.
+    pub async fn create_policy(
+        &self,
+        db: &PgPool,
+        input: &AuthzPolicyInput,
+        created_by: Option<Uuid>,
+    ) -> Result<AuthzPolicy> {
+        validate_policy_input(input)?;
+        let mut tx = db
+            .begin()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let policy = sqlx::query_as::<_, AuthzPolicy>(&format!(
+            "INSERT INTO authz_policies (name, description, rego, enabled, created_by) \
+             VALUES ($1, $2, $3, $4, $5) RETURNING {POLICY_COLUMNS}"
+        ))
+        .bind(&input.name)
+        .bind(&input.description)
+        .bind(&input.rego)
+        .bind(input.enabled)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| map_policy_write_error(e, &input.name))?;
+        if policy.enabled {
+            self.push_policy(&policy.name, &policy.rego).await?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(policy)
+    }
+
+    /// Replace a policy. Renaming or disabling it removes the old module from
+    /// OPA.
+    pub async fn update_policy(
+        &self,
+        db: &PgPool,
+        id: Uuid,
+        input: &AuthzPolicyInput,
+    ) -> Result<AuthzPolicy> {
+        validate_policy_input(input)?;
+        let mut tx = db
+            .begin()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let previous_name: String =
+            sqlx::query_scalar("SELECT name FROM authz_policies WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!("Authorization policy {id} not found"))
+                })?;
+        let policy = sqlx::query_as::<_, AuthzPolicy>(&format!(
+            "UPDATE authz_policies \
+             SET name = $2, description = $3, rego = $4, enabled = $5, updated_at = NOW() \
+             WHERE id = $1 RETURNING {POLICY_COLUMNS}"
+        ))
+        .bind(id)
+        .bind(&input.name)
+        .bind(&input.description)
+        .bind(&input.rego)
+        .bind(input.enabled)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| map_policy_write_error(e, &input.name))?;
+        if policy.enabled {
+            self.push_policy(&policy.name, &policy.rego).await?;
+        }
+        if !policy.enabled || previous_name != policy.name {
+            self.delete_policy(&previous_name).await?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(policy)
+    }
+
+    /// Delete a policy and remove it from OPA.
+    pub async fn remove_policy(&self, db: &PgPool, id: Uuid) -> Result<()> {
+        let mut tx = db
+            .begin()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let name: String =
+            sqlx::query_scalar("DELETE FROM authz_policies WHERE id = $1 RETURNING name")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!("Authorization policy {id} not found"))
+                })?;
+        self.delete_policy(&name).await?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
+
+fn map_policy_write_error(e: sqlx::Error, name: &str) -> AppError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::Conflict(format!("Authorization policy '{name}' already exists"))
+        }
+        _ => AppError::Database(e.to_string()),
+    }
+}
+
+/// Every stored policy, by name.
+pub async fn list_policies(db: &PgPool) -> Result<Vec<AuthzPolicy>> {
+    sqlx::query_as::<_, AuthzPolicy>(&format!(
+        "SELECT {POLICY_COLUMNS} FROM authz_policies ORDER BY name"
+    ))
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// One stored policy.
+pub async fn get_policy(db: &PgPool, id: Uuid) -> Result<AuthzPolicy> {
+    sqlx::query_as::<_, AuthzPolicy>(&format!(
+        "SELECT {POLICY_COLUMNS} FROM authz_policies WHERE id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound(format!("Authorization policy {id} not found")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn config_is_disabled_without_a_url() {
+        assert!(ExternalAuthzConfig::from_values(None, Some("opa"), None, None, None).is_none());
+        assert!(ExternalAuthzConfig::from_values(Some("  "), None, None, None, None).is_none());
+    }
+
+    #[test]
+    fn config_defaults_and_overrides() {
+        let config = ExternalAuthzConfig::from_values(
+            Some("http://opa:8181/v1/data/artifact_keeper/authz"),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(config.kind, AuthzHookKind::Opa);
+        assert_eq!(config.timeout, Duration::from_millis(DEFAULT_TIMEOUT_MS));
+        assert!(!config.fail_open);
+        assert!(config.token.is_none());
+
+        let config = ExternalAuthzConfig::from_values(
+            Some("https://authz.internal/check"),
+            Some("Webhook"),
+            Some("500"),
+            Some("true"),
+            Some("secret"),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(config.kind, AuthzHookKind::Webhook);
+        assert_eq!(config.timeout, Duration::from_millis(500));
+        assert!(config.fail_open);
+        assert_eq!(config.token.as_deref(), Some("secret"));
+        assert!(!format!("{config:?}").contains("secret"));
+    }
+
+    #[test]
+    fn malformed_config_is_an_error() {
+        let url = Some("http://opa:8181/v1/data/authz");
+        assert!(
+            ExternalAuthzConfig::from_values(url, Some("ldap"), None, None, None)
+                .unwrap()
+                .is_err()
+        );
+        assert!(
+            ExternalAuthzConfig::from_values(url, None, Some("0"), None, None)
+                .unwrap()
+                .is_err()
+        );
+        assert!(
+            ExternalAuthzConfig::from_values(Some("opa:8181"), None, None, None, None)
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn opa_decisions() {
+        let opa = AuthzHookKind::Opa;
+        assert!(parse_decision(opa, &json!({"result": true})).unwrap().allow);
+        assert!(
+            !parse_decision(opa, &json!({"result": false}))
+                .unwrap()
+                .allow
+        );
+        let decision = parse_decision(
+            opa,
+            &json!({"result": {"allow": false, "reason": "frozen"}}),
+        )
+        .unwrap();
+        assert_eq!(
+            decision,
+            AuthzDecision {
+                allow: false,
+                reason: Some("frozen".to_string())
+            }
+        );
+        // Undefined results deny rather than erroring.
+        assert!(!parse_decision(opa, &json!({})).unwrap().allow);
+        assert!(!parse_decision(opa, &json!({"result": {}})).unwrap().allow);
+        assert!(parse_decision(opa, &json!({"result": "yes"})).is_err());
+    }
+
+    #[test]
+    fn webhook_decisions() {
+        let webhook = AuthzHookKind::Webhook;
+        assert!(
+            parse_decision(webhook, &json!({"allow": true}))
+                .unwrap()
+                .allow
+        );
+        assert!(parse_decision(webhook, &json!({"result": true})).is_err());
+        assert!(parse_decision(webhook, &json!(true)).is_err());
+    }
+
+    #[test]
+    fn policy_names_are_restricted() {
+        let input = |name: &str| AuthzPolicyInput {
+            name: name.to_string(),
+            description: None,
+            rego: "package artifact_keeper.authz\ndefault allow := true".to_string(),
+            enabled: true,
+        };
+        assert!(validate_policy_input(&input("prod-freeze_2")).is_ok());
+        assert!(validate_policy_input(&input("")).is_err());
+        assert!(validate_policy_input(&input("../system")).is_err());
+        assert!(validate_policy_input(&input("Prod")).is_err());
+    }
+}
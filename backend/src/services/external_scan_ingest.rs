@@ -0,0 +1,726 @@
+//! Ingestion of scan reports produced by external scanners.
+//!
+//! Organizations that already run SAST/SCA tools can feed their results
+//! into Artifact Keeper's policy engine instead of (or alongside) the
+//! built-in Trivy/Grype scanners. Three report formats are accepted:
+//!
+//! * **SARIF 2.1.0**: the tool is taken from `runs[].tool.driver`, severity
+//!   from the `security-severity` property (CVSS score) when present and the
+//!   result `level` otherwise.
+//! * **Snyk JSON** (`snyk test --json`): a single project object or the
+//!   array produced by `--all-projects`. Snyk repeats a vulnerability once
+//!   per dependency path; those repeats are collapsed.
+//! * **Custom**: `{"findings": [{"severity", "title", ...}]}` for tools
+//!   without a standard output format.
+//!
+//! A report becomes one completed `scan_results` row with
+//! `scan_type = "external:<tool-slug>"` (see migration 184), so a new report
+//! from a tool supersedes only that tool's previous report. Quarantine
+//! status is deliberately left alone: an external report must not release a
+//! quarantined artifact.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::security::{RawFinding, ScanResult, Severity};
+use crate::services::scan_result_service::ScanResultService;
+
+/// Upper bound on findings accepted from one report.
+pub const MAX_INGESTED_FINDINGS: usize = 10_000;
+
+/// Maximum length of the tool slug in `external:<slug>` (the column is
+/// VARCHAR(30)).
+const MAX_TOOL_SLUG_LEN: usize = 21;
+
+/// Format of an ingested report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalReportFormat {
+    Sarif,
+    Snyk,
+    Custom,
+}
+
+impl ExternalReportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sarif => "sarif",
+            Self::Snyk => "snyk",
+            Self::Custom => "custom",
+        }
+    }
+}
+
+/// A parsed report, ready to persist.
+#[derive(Debug, Clone)]
+pub struct ParsedReport {
+    pub tool_name: String,
+    pub tool_version: Option<String>,
+    pub findings: Vec<RawFinding>,
+    /// Findings dropped because they repeated an earlier one.
+    pub duplicates_skipped: usize,
+}
+
+/// One finding in a custom-format report.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CustomFinding {
+    /// critical, high, medium (or moderate), low, info.
+    pub severity: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub cve_id: Option<String>,
+    pub component: Option<String>,
+    pub version: Option<String>,
+    pub fixed_version: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CustomReport {
+    findings: Vec<CustomFinding>,
+}
+
+// ---------------------------------------------------------------------------
+// Pure helpers
+// ---------------------------------------------------------------------------
+
+/// Truncate to at most `max` characters (the DB columns are VARCHAR(n)).
+fn truncate(s: &str, max: usize) -> String {
+    s.chars().take(max).collect()
+}
+
+fn truncate_opt(s: Option<&str>, max: usize) -> Option<String> {
+    s.map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| truncate(s, max))
+}
+
+/// Build a `RawFinding`, clamping every field to its column width.
+#[allow(clippy::too_many_arguments)]
+fn finding(
+    severity: Severity,
+    title: &str,
+    description: Option<&str>,
+    cve_id: Option<&str>,
+    component: Option<&str>,
+    version: Option<&str>,
+    fixed_version: Option<&str>,
+    source: &str,
+    source_url: Option<&str>,
+) -> RawFinding {
+    RawFinding {
+        severity,
+        title: truncate(title.trim(), 500),
+        description: description.map(str::to_string),
+        cve_id: truncate_opt(cve_id, 30),
+        affected_component: truncate_opt(component, 255),
+        affected_version: truncate_opt(version, 100),
+        fixed_version: truncate_opt(fixed_version, 100),
+        source: Some(truncate(source, 100)),
+        source_url: truncate_opt(source_url, 512),
+    }
+}
+
+/// `external:<slug>` scan type for a tool name, e.g. "Snyk Open Source" ->
+/// `external:snyk-open-source`.
+pub fn scan_type_for_tool(tool_name: &str) -> Result<String> {
+    let mut slug = String::new();
+    for c in tool_name.trim().to_ascii_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.chars().take(MAX_TOOL_SLUG_LEN).collect();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        return Err(AppError::Validation(
+            "tool_name must contain at least one letter or digit".to_string(),
+        ));
+    }
+    Ok(format!("external:{}", slug))
+}
+
+/// Whether an identifier looks like a CVE or GHSA ID.
+pub fn looks_like_vuln_id(id: &str) -> bool {
+    let upper = id.to_ascii_uppercase();
+    (upper.starts_with("CVE-") || upper.starts_with("GHSA-")) && id.len() <= 30
+}
+
+/// Map a CVSS score (SARIF `security-severity`) to a severity.
+pub fn severity_from_cvss(score: f64) -> Severity {
+    if score >= 9.0 {
+        Severity::Critical
+    } else if score >= 7.0 {
+        Severity::High
+    } else if score >= 4.0 {
+        Severity::Medium
+    } else if score > 0.0 {
+        Severity::Low
+    } else {
+        Severity::Info
+    }
+}
+
+/// Map a SARIF result `level` to a severity. SARIF's default level is
+/// `warning`.
+pub fn severity_from_sarif_level(level: Option<&str>) -> Severity {
+    match level.unwrap_or("warning") {
+        "error" => Severity::High,
+        "warning" => Severity::Medium,
+        "note" => Severity::Low,
+        _ => Severity::Info,
+    }
+}
+
+fn security_severity(props: Option<&Value>) -> Option<f64> {
+    let v = props?.get("security-severity")?;
+    v.as_f64().or_else(|| v.as_str()?.trim().parse().ok())
+}
+
+fn text<'a>(v: Option<&'a Value>, field: &str) -> Option<&'a str> {
+    v?.get(field)?.get("text")?.as_str()
+}
+
+/// Parse a SARIF 2.1.0 log.
+pub fn parse_sarif(report: &Value) -> Result<ParsedReport> {
+    let runs = report
+        .get("runs")
+        .and_then(Value::as_array)
+        .ok_or_else(|| AppError::Validation("SARIF report has no runs array".to_string()))?;
+
+    let mut tool_name: Option<String> = None;
+    let mut tool_version: Option<String> = None;
+    let mut findings = Vec::new();
+
+    for run in runs {
+        let driver = run.pointer("/tool/driver");
+        let name = driver
+            .and_then(|d| d.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or("sarif");
+        if tool_name.is_none() {
+            tool_name = Some(name.to_string());
+            tool_version = driver
+                .and_then(|d| d.get("semanticVersion").or_else(|| d.get("version")))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+        }
+        let rules: Vec<&Value> = driver
+            .and_then(|d| d.get("rules"))
+            .and_then(Value::as_array)
+            .map(|r| r.iter().collect())
+            .unwrap_or_default();
+
+        for result in run
+            .get("results")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let rule_id = result.get("ruleId").and_then(Value::as_str);
+            let rule = result
+                .get("ruleIndex")
+                .and_then(Value::as_u64)
+                .and_then(|i| rules.get(i as usize).copied())
+                .or_else(|| {
+                    rules
+                        .iter()
+                        .find(|r| r.get("id").and_then(Value::as_str) == rule_id)
+                        .copied()
+                });
+
+            let severity = security_severity(result.get("properties"))
+                .or_else(|| security_severity(rule.and_then(|r| r.get("properties"))))
+                .map(severity_from_cvss)
+                .unwrap_or_else(|| {
+                    let level = result.get("level").and_then(Value::as_str).or_else(|| {
+                        rule.and_then(|r| r.pointer("/defaultConfiguration/level"))
+                            .and_then(Value::as_str)
+                    });
+                    severity_from_sarif_level(level)
+                });
+
+            let message = text(Some(result), "message");
+            let title = text(rule, "shortDescription")
+                .or_else(|| rule.and_then(|r| r.get("name")).and_then(Value::as_str))
+                .or_else(|| message.and_then(|m| m.lines().next()))
+                .or(rule_id)
+                .unwrap_or("Unnamed finding");
+            let description = message.or_else(|| text(rule, "fullDescription"));
+            let cve_id = rule_id.filter(|id| looks_like_vuln_id(id));
+            let location = result
+                .pointer("/locations/0/physicalLocation/artifactLocation/uri")
+                .and_then(Value::as_str);
+            let help_uri = rule.and_then(|r| r.get("helpUri")).and_then(Value::as_str);
+
+            findings.push(finding(
+                severity,
+                title,
+                description,
+                cve_id,
+                location,
+                None,
+                None,
+                name,
+                help_uri,
+            ));
+        }
+    }
+
+    let (findings, duplicates_skipped) = dedup(findings);
+    Ok(ParsedReport {
+        tool_name: tool_name.unwrap_or_else(|| "sarif".to_string()),
+        tool_version,
+        findings,
+        duplicates_skipped,
+    })
+}
+
+/// Parse `snyk test --json` output (one project or an array of projects).
+pub fn parse_snyk(report: &Value) -> Result<ParsedReport> {
+    let projects: Vec<&Value> = match report {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![report],
+        _ => {
+            return Err(AppError::Validation(
+                "Snyk report must be a JSON object or array".to_string(),
+            ))
+        }
+    };
+
+    let mut findings = Vec::new();
+    for project in projects {
+        let vulns = project
+            .get("vulnerabilities")
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                AppError::Validation("Snyk report has no vulnerabilities array".to_string())
+            })?;
+        for v in vulns {
+            let severity = v
+                .get("severity")
+                .and_then(Value::as_str)
+                .and_then(Severity::from_str_loose)
+                .unwrap_or(Severity::Info);
+            let id = v.get("id").and_then(Value::as_str);
+            let cve = v
+                .pointer("/identifiers/CVE/0")
+                .and_then(Value::as_str)
+                .or_else(|| id.filter(|i| looks_like_vuln_id(i)));
+            let title = v
+                .get("title")
+                .and_then(Value::as_str)
+                .or(id)
+                .unwrap_or("Unnamed vulnerability");
+            let fixed = v.pointer("/fixedIn/0").and_then(Value::as_str);
+            let url = v
+                .get("url")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| id.map(|i| format!("https://security.snyk.io/vuln/{}", i)));
+
+            findings.push(finding(
+                severity,
+                title,
+                v.get("description").and_then(Value::as_str),
+                cve,
+                v.get("packageName").and_then(Value::as_str),
+                v.get("version").and_then(Value::as_str),
+                fixed,
+                "snyk",
+                url.as_deref(),
+            ));
+        }
+    }
+
+    let (findings, duplicates_skipped) = dedup(findings);
+    Ok(ParsedReport {
+        tool_name: "snyk".to_string(),
+        tool_version: None,
+        findings,
+        duplicates_skipped,
+    })
+}
+
+/// Parse a custom-format report. `tool_name` is required because the format
+/// does not carry one.
+pub fn parse_custom(report: &Value, tool_name: &str) -> Result<ParsedReport> {
+    let parsed: CustomReport = serde_json::from_value(report.clone())
+        .map_err(|e| AppError::Validation(format!("Invalid custom report: {}", e)))?;
+
+    let mut findings = Vec::with_capacity(parsed.findings.len());
+    for (i, f) in parsed.findings.iter().enumerate() {
+        let severity = Severity::from_str_loose(&f.severity).ok_or_else(|| {
+            AppError::Validation(format!(
+                "findings[{}]: invalid severity '{}'",
+                i, f.severity
+            ))
+        })?;
+        if f.title.trim().is_empty() {
+            return Err(AppError::Validation(format!(
+                "findings[{}]: title is required",
+                i
+            )));
+        }
+        findings.push(finding(
+            severity,
+            &f.title,
+            f.description.as_deref(),
+            f.cve_id.as_deref(),
+            f.component.as_deref(),
+            f.version.as_deref(),
+            f.fixed_version.as_deref(),
+            tool_name,
+            f.url.as_deref(),
+        ));
+    }
+
+    let (findings, duplicates_skipped) = dedup(findings);
+    Ok(ParsedReport {
+        tool_name: tool_name.to_string(),
+        tool_version: None,
+        findings,
+        duplicates_skipped,
+    })
+}
+
+/// Parse a report. An explicit `tool_name` / `tool_version` overrides what
+/// the report declares.
+pub fn parse_report(
+    format: ExternalReportFormat,
+    report: &Value,
+    tool_name: Option<&str>,
+    tool_version: Option<&str>,
+) -> Result<ParsedReport> {
+    let tool_name = tool_name.map(str::trim).filter(|t| !t.is_empty());
+    let mut parsed = match format {
+        ExternalReportFormat::Sarif => parse_sarif(report)?,
+        ExternalReportFormat::Snyk => parse_snyk(report)?,
+        ExternalReportFormat::Custom => parse_custom(
+            report,
+            tool_name.ok_or_else(|| {
+                AppError::Validation("tool_name is required for custom reports".to_string())
+            })?,
+        )?,
+    };
+    if let Some(name) = tool_name {
+        parsed.tool_name = truncate(name, 100);
+    }
+    if let Some(version) = tool_version.map(str::trim).filter(|v| !v.is_empty()) {
+        parsed.tool_version = Some(version.to_string());
+    }
+    parsed.tool_version = parsed.tool_version.map(|v| truncate(&v, 50));
+    if parsed.findings.len() > MAX_INGESTED_FINDINGS {
+        return Err(AppError::Validation(format!(
+            "Report contains {} findings; at most {} are accepted",
+            parsed.findings.len(),
+            MAX_INGESTED_FINDINGS
+        )));
+    }
+    Ok(parsed)
+}
+
+/// Drop findings that repeat (severity, title, cve, component, version).
+fn dedup(findings: Vec<RawFinding>) -> (Vec<RawFinding>, usize) {
+    let total = findings.len();
+    let mut seen = std::collections::HashSet::new();
+    let kept: Vec<RawFinding> = findings
+        .into_iter()
+        .filter(|f| {
+            seen.insert((
+                f.severity,
+                f.title.clone(),
+                f.cve_id.clone(),
+                f.affected_component.clone(),
+                f.affected_version.clone(),
+            ))
+        })
+        .collect();
+    let skipped = total - kept.len();
+    (kept, skipped)
+}
+
+/// Per-severity counts in `(critical, high, medium, low, info)` order.
+pub fn severity_counts(findings: &[RawFinding]) -> (i32, i32, i32, i32, i32) {
+    let mut counts = (0, 0, 0, 0, 0);
+    for f in findings {
+        match f.severity {
+            Severity::Critical => counts.0 += 1,
+            Severity::High => counts.1 += 1,
+            Severity::Medium => counts.2 += 1,
+            Severity::Low => counts.3 += 1,
+            Severity::Info => counts.4 += 1,
+        }
+    }
+    counts
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// Persists parsed external reports as scan results.
+pub struct ExternalScanIngestService {
+    db: PgPool,
+    scan_results: ScanResultService,
+}
+
+impl ExternalScanIngestService {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            scan_results: ScanResultService::new(db.clone()),
+            db,
+        }
+    }
+
+    /// Store a parsed report as a completed scan for the artifact and
+    /// recalculate the repository's security score.
+    pub async fn ingest(
+        &self,
+        artifact_id: Uuid,
+        repository_id: Uuid,
+        format: ExternalReportFormat,
+        report: &ParsedReport,
+        ingested_by: Uuid,
+    ) -> Result<ScanResult> {
+        let scan_type = scan_type_for_tool(&report.tool_name)?;
+        let started_at = chrono::Utc::now();
+        let scan = self
+            .scan_results
+            .create_scan_result(artifact_id, repository_id, &scan_type)
+            .await?;
+
+        sqlx::query(
+            "UPDATE scan_results SET source_tool = $2, source_format = $3, ingested_by = $4 WHERE id = $1",
+        )
+        .bind(scan.id)
+        .bind(&report.tool_name)
+        .bind(format.as_str())
+        .bind(ingested_by)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        if let Err(e) = self
+            .scan_results
+            .create_findings(scan.id, artifact_id, &report.findings)
+            .await
+        {
+            self.scan_results
+                .fail_scan(
+                    scan.id,
+                    &e.to_string(),
+                    report.tool_version.as_deref(),
+                    started_at,
+                )
+                .await?;
+            return Err(e);
+        }
+
+        let (critical, high, medium, low, info) = severity_counts(&report.findings);
+        self.scan_results
+            .complete_scan(
+                scan.id,
+                report.findings.len() as i32,
+                critical,
+                high,
+                medium,
+                low,
+                info,
+                report.tool_version.as_deref(),
+                started_at,
+                "complete",
+            )
+            .await?;
+
+        self.scan_results.recalculate_score(repository_id).await?;
+        self.scan_results.get_scan(scan.id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_scan_type_for_tool() {
+        assert_eq!(scan_type_for_tool("snyk").unwrap(), "external:snyk");
+        assert_eq!(
+            scan_type_for_tool("Snyk Open Source").unwrap(),
+            "external:snyk-open-source"
+        );
+        assert_eq!(
+            scan_type_for_tool("  CodeQL (v2)  ").unwrap(),
+            "external:codeql-v2"
+        );
+        let long = scan_type_for_tool("a-very-long-scanner-product-name").unwrap();
+        assert!(long.len() <= 30, "{long}");
+        assert!(!long.ends_with('-'));
+        assert!(scan_type_for_tool("***").is_err());
+    }
+
+    #[test]
+    fn test_severity_mappings() {
+        assert_eq!(severity_from_cvss(9.8), Severity::Critical);
+        assert_eq!(severity_from_cvss(7.0), Severity::High);
+        assert_eq!(severity_from_cvss(5.5), Severity::Medium);
+        assert_eq!(severity_from_cvss(0.5), Severity::Low);
+        assert_eq!(severity_from_cvss(0.0), Severity::Info);
+        assert_eq!(severity_from_sarif_level(Some("error")), Severity::High);
+        assert_eq!(severity_from_sarif_level(None), Severity::Medium);
+        assert_eq!(severity_from_sarif_level(Some("note")), Severity::Low);
+        assert_eq!(severity_from_sarif_level(Some("none")), Severity::Info);
+    }
+
+    #[test]
+    fn test_parse_sarif() {
+        let report = json!({
+            "version": "2.1.0",
+            "runs": [{
+                "tool": { "driver": {
+                    "name": "CodeQL",
+                    "semanticVersion": "2.15.0",
+                    "rules": [
+                        {
+                            "id": "js/sql-injection",
+                            "shortDescription": { "text": "SQL injection" },
+                            "helpUri": "https://codeql.github.com/js-sql-injection",
+                            "properties": { "security-severity": "8.8" }
+                        },
+                        { "id": "CVE-2024-1234", "defaultConfiguration": { "level": "note" } }
+                    ]
+                }},
+                "results": [
+                    {
+                        "ruleId": "js/sql-injection",
+                        "ruleIndex": 0,
+                        "message": { "text": "Query built from user input" },
+                        "locations": [{ "physicalLocation": { "artifactLocation": { "uri": "src/db.js" } } }]
+                    },
+                    { "ruleId": "CVE-2024-1234", "message": { "text": "Vulnerable lib" } },
+                    { "ruleId": "CVE-2024-1234", "message": { "text": "Vulnerable lib" } },
+                    { "ruleId": "other", "level": "error", "message": { "text": "Bad\nthing" } }
+                ]
+            }]
+        });
+        let parsed = parse_sarif(&report).unwrap();
+        assert_eq!(parsed.tool_name, "CodeQL");
+        assert_eq!(parsed.tool_version.as_deref(), Some("2.15.0"));
+        assert_eq!(parsed.findings.len(), 3);
+        assert_eq!(parsed.duplicates_skipped, 1);
+
+        let sqli = &parsed.findings[0];
+        assert_eq!(sqli.severity, Severity::High);
+        assert_eq!(sqli.title, "SQL injection");
+        assert_eq!(sqli.affected_component.as_deref(), Some("src/db.js"));
+        assert_eq!(sqli.source.as_deref(), Some("CodeQL"));
+        assert!(sqli.cve_id.is_none());
+
+        let cve = &parsed.findings[1];
+        assert_eq!(cve.severity, Severity::Low);
+        assert_eq!(cve.cve_id.as_deref(), Some("CVE-2024-1234"));
+
+        let other = &parsed.findings[2];
+        assert_eq!(other.severity, Severity::High);
+        assert_eq!(other.title, "Bad");
+    }
+
+    #[test]
+    fn test_parse_sarif_requires_runs() {
+        assert!(parse_sarif(&json!({ "version": "2.1.0" })).is_err());
+    }
+
+    #[test]
+    fn test_parse_snyk_collapses_paths() {
+        let vuln = json!({
+            "id": "SNYK-JS-LODASH-567746",
+            "title": "Prototype Pollution",
+            "severity": "high",
+            "packageName": "lodash",
+            "version": "4.17.15",
+            "fixedIn": ["4.17.19"],
+            "identifiers": { "CVE": ["CVE-2020-8203"] }
+        });
+        let report = json!([
+            { "projectName": "web", "vulnerabilities": [vuln.clone(), vuln] },
+            { "projectName": "api", "vulnerabilities": [{
+                "id": "SNYK-JS-AXIOS-1", "title": "SSRF", "severity": "medium",
+                "packageName": "axios", "version": "0.21.0"
+            }] }
+        ]);
+        let parsed = parse_snyk(&report).unwrap();
+        assert_eq!(parsed.tool_name, "snyk");
+        assert_eq!(parsed.findings.len(), 2);
+        assert_eq!(parsed.duplicates_skipped, 1);
+        let lodash = &parsed.findings[0];
+        assert_eq!(lodash.cve_id.as_deref(), Some("CVE-2020-8203"));
+        assert_eq!(lodash.fixed_version.as_deref(), Some("4.17.19"));
+        assert_eq!(
+            lodash.source_url.as_deref(),
+            Some("https://security.snyk.io/vuln/SNYK-JS-LODASH-567746")
+        );
+        assert_eq!(parsed.findings[1].severity, Severity::Medium);
+
+        assert!(parse_snyk(&json!({ "ok": true })).is_err());
+        assert!(parse_snyk(&json!("nope")).is_err());
+    }
+
+    #[test]
+    fn test_parse_custom() {
+        let report = json!({ "findings": [
+            { "severity": "Critical", "title": "Hardcoded key", "component": "config.yml" },
+            { "severity": "moderate", "title": "Weak TLS" }
+        ]});
+        let parsed = parse_report(
+            ExternalReportFormat::Custom,
+            &report,
+            Some("inhouse"),
+            Some("1.2"),
+        )
+        .unwrap();
+        assert_eq!(parsed.tool_name, "inhouse");
+        assert_eq!(parsed.tool_version.as_deref(), Some("1.2"));
+        assert_eq!(severity_counts(&parsed.findings), (1, 0, 1, 0, 0));
+
+        // tool_name is mandatory for custom reports.
+        assert!(parse_report(ExternalReportFormat::Custom, &report, None, None).is_err());
+        let bad = json!({ "findings": [{ "severity": "urgent", "title": "x" }] });
+        assert!(parse_custom(&bad, "inhouse").is_err());
+        let untitled = json!({ "findings": [{ "severity": "low", "title": " " }] });
+        assert!(parse_custom(&untitled, "inhouse").is_err());
+    }
+
+    #[test]
+    fn test_parse_report_overrides_tool_and_clamps_fields() {
+        let long_title = "x".repeat(600);
+        let report = json!({ "runs": [{
+            "tool": { "driver": { "name": "trivy" } },
+            "results": [{ "ruleId": "CVE-2023-0001", "message": { "text": long_title } }]
+        }]});
+        let parsed = parse_report(
+            ExternalReportFormat::Sarif,
+            &report,
+            Some("Trivy Enterprise"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(parsed.tool_name, "Trivy Enterprise");
+        assert_eq!(parsed.findings[0].title.chars().count(), 500);
+    }
+
+    #[test]
+    fn test_parse_report_rejects_oversized_reports() {
+        let findings: Vec<Value> = (0..=MAX_INGESTED_FINDINGS)
+            .map(|i| json!({ "severity": "low", "title": format!("f{}", i) }))
+            .collect();
+        let report = json!({ "findings": findings });
+        assert!(parse_report(ExternalReportFormat::Custom, &report, Some("t"), None).is_err());
+    }
+}
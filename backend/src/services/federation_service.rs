@@ -0,0 +1,994 @@
+//! Instance-to-instance federation trust.
+//!
+//! Every instance owns an Ed25519 identity key. Two instances establish trust
+//! by exchanging self-signed [`InstanceIdentity`] documents
+//! (`POST /api/v1/federation/handshake`); once an admin approves the remote
+//! identity, the remote may authenticate with short-lived tokens it signs
+//! itself (`akfed.<header>.<claims>.<signature>`) instead of a pre-shared
+//! service-account API key.
+//!
+//! An approved peer is mapped onto a local, non-login service principal
+//! (`_ak_federation_<id>`) so audit rows, download attribution and role
+//! assignments keep working unchanged. The token's scope is capped by the
+//! scopes the admin granted at approval time.
+
+use std::sync::{Arc, OnceLock};
+
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+use crate::error::{AppError, Result};
+use crate::models::access_scope::AccessScope;
+use crate::services::encryption::CredentialEncryption;
+
+/// Prefix that distinguishes a federation token from a JWT or API token so
+/// the auth middleware can route it without trying every validator.
+pub const TOKEN_PREFIX: &str = "akfed.";
+
+/// Lifetime of tokens this instance mints for its peers.
+pub const DEFAULT_TOKEN_LIFETIME_SECS: i64 = 300;
+
+/// Longest lifetime a peer-minted token may claim. Anything longer is
+/// rejected outright, so a leaked token stays useful for minutes, not days.
+pub const MAX_TOKEN_LIFETIME_SECS: i64 = 600;
+
+/// Tolerated clock drift between federated instances.
+const CLOCK_SKEW_SECS: i64 = 60;
+
+/// Domain-separation tag signed into every identity document.
+const IDENTITY_DOMAIN: &str = "akfed-identity-v1";
+
+/// Cap on untrusted handshake records. The handshake endpoint is public, so
+/// without a bound it would let anyone grow the table.
+const MAX_PENDING_PEERS: i64 = 50;
+
+const STATUS_PENDING: &str = "pending";
+const STATUS_TRUSTED: &str = "trusted";
+
+/// What a federation token may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FederationScope {
+    /// Push replication: read, write and delete artifacts.
+    Replication,
+    /// Remote downloads: read-only.
+    Download,
+}
+
+impl FederationScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Replication => "replication",
+            Self::Download => "download",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "replication" => Some(Self::Replication),
+            "download" => Some(Self::Download),
+            _ => None,
+        }
+    }
+
+    /// API-token scopes the federated principal holds under this scope.
+    pub fn token_scopes(&self) -> Vec<String> {
+        let scopes: &[&str] = match self {
+            Self::Replication => &[
+                "read:artifacts",
+                "write:artifacts",
+                "delete:artifacts",
+                "read:repositories",
+            ],
+            Self::Download => &["read:artifacts", "read:repositories"],
+        };
+        scopes.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Built-in role granted on each approved repository.
+    fn role_name(scopes: &[FederationScope]) -> &'static str {
+        if scopes.contains(&Self::Replication) {
+            "developer"
+        } else {
+            "reader"
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Identity documents
+// ---------------------------------------------------------------------------
+
+/// A self-signed statement of an instance's identity and public key.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InstanceIdentity {
+    pub instance_id: Uuid,
+    pub name: String,
+    pub endpoint_url: String,
+    /// Base64-encoded 32-byte Ed25519 public key.
+    pub public_key: String,
+    pub issued_at: DateTime<Utc>,
+    /// Base64-encoded Ed25519 signature over the fields above.
+    pub signature: String,
+}
+
+fn identity_payload(
+    instance_id: Uuid,
+    name: &str,
+    endpoint_url: &str,
+    public_key: &str,
+    issued_at: &DateTime<Utc>,
+) -> Vec<u8> {
+    format!(
+        "{IDENTITY_DOMAIN}\n{instance_id}\n{name}\n{endpoint_url}\n{public_key}\n{}",
+        issued_at.timestamp()
+    )
+    .into_bytes()
+}
+
+/// `SHA256:<hex>` fingerprint of a raw public key, for out-of-band comparison.
+pub fn fingerprint(public_key: &[u8]) -> String {
+    format!("SHA256:{}", hex::encode(Sha256::digest(public_key)))
+}
+
+impl InstanceIdentity {
+    /// Check the self-signature and return the verified public key.
+    pub fn verify(&self) -> Result<VerifyingKey> {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let key_bytes: [u8; 32] = engine
+            .decode(&self.public_key)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| AppError::Validation("Invalid identity public key".to_string()))?;
+        let key = VerifyingKey::from_bytes(&key_bytes)
+            .map_err(|_| AppError::Validation("Invalid identity public key".to_string()))?;
+        let sig_bytes: [u8; 64] = engine
+            .decode(&self.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| AppError::Validation("Invalid identity signature".to_string()))?;
+        let payload = identity_payload(
+            self.instance_id,
+            &self.name,
+            &self.endpoint_url,
+            &self.public_key,
+            &self.issued_at,
+        );
+        key.verify(&payload, &Signature::from_bytes(&sig_bytes))
+            .map_err(|_| AppError::Validation("Identity signature does not verify".to_string()))?;
+        Ok(key)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tokens
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenHeader {
+    alg: String,
+    typ: String,
+}
+
+/// Claims carried by a federation token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FederationClaims {
+    /// Issuing instance.
+    pub iss: Uuid,
+    /// Instance the token is meant for.
+    pub aud: Uuid,
+    pub scope: FederationScope,
+    pub iat: i64,
+    pub exp: i64,
+    pub jti: Uuid,
+}
+
+/// A token whose claims have been decoded but not yet checked.
+#[derive(Debug)]
+pub struct UnverifiedToken {
+    pub claims: FederationClaims,
+    signing_input: String,
+    signature: [u8; 64],
+}
+
+/// Whether `token` is shaped like a federation token.
+pub fn is_federation_token(token: &str) -> bool {
+    token.starts_with(TOKEN_PREFIX)
+}
+
+/// Decode a federation token without checking its signature. The caller
+/// needs the issuer to know which trusted key to verify against.
+pub fn parse_token(token: &str) -> Option<UnverifiedToken> {
+    let body = token.strip_prefix(TOKEN_PREFIX)?;
+    let mut parts = body.split('.');
+    let (header, claims, signature) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    let header: TokenHeader = serde_json::from_slice(&engine.decode(header).ok()?).ok()?;
+    if header.alg != "EdDSA" {
+        return None;
+    }
+    let decoded_claims: FederationClaims =
+        serde_json::from_slice(&engine.decode(claims).ok()?).ok()?;
+    let signature: [u8; 64] = engine.decode(signature).ok()?.try_into().ok()?;
+    let (signing_input, _) = body.rsplit_once('.')?;
+    Some(UnverifiedToken {
+        claims: decoded_claims,
+        signing_input: signing_input.to_string(),
+        signature,
+    })
+}
+
+impl UnverifiedToken {
+    /// Check signature, audience and validity window.
+    pub fn verify(
+        &self,
+        key: &VerifyingKey,
+        audience: Uuid,
+        now: i64,
+    ) -> std::result::Result<(), &'static str> {
+        key.verify(
+            self.signing_input.as_bytes(),
+            &Signature::from_bytes(&self.signature),
+        )
+        .map_err(|_| "bad signature")?;
+        let c = &self.claims;
+        if c.aud != audience {
+            return Err("wrong audience");
+        }
+        if c.exp <= c.iat || c.exp - c.iat > MAX_TOKEN_LIFETIME_SECS {
+            return Err("invalid lifetime");
+        }
+        if c.iat > now + CLOCK_SKEW_SECS {
+            return Err("issued in the future");
+        }
+        if c.exp + CLOCK_SKEW_SECS <= now {
+            return Err("expired");
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Local identity
+// ---------------------------------------------------------------------------
+
+/// This instance's identity key and advertised name/endpoint.
+pub struct LocalIdentity {
+    pub instance_id: Uuid,
+    pub name: String,
+    pub endpoint_url: String,
+    signing_key: SigningKey,
+}
+
+impl LocalIdentity {
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.public_key_bytes())
+    }
+
+    /// Produce a freshly signed identity document.
+    pub fn document(&self) -> InstanceIdentity {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let public_key = engine.encode(self.public_key_bytes());
+        let issued_at = Utc::now();
+        let payload = identity_payload(
+            self.instance_id,
+            &self.name,
+            &self.endpoint_url,
+            &public_key,
+            &issued_at,
+        );
+        InstanceIdentity {
+            instance_id: self.instance_id,
+            name: self.name.clone(),
+            endpoint_url: self.endpoint_url.clone(),
+            public_key,
+            issued_at,
+            signature: engine.encode(self.signing_key.sign(&payload).to_bytes()),
+        }
+    }
+
+    /// Sign an arbitrary message with the identity key. Callers prefix their
+    /// own domain string so signatures cannot be replayed across uses.
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        self.signing_key.sign(message)
+    }
+
+    /// Mint a token for `audience` that it will accept once it trusts us.
+    pub fn mint_token(&self, audience: Uuid, scope: FederationScope, now: i64) -> String {
+        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let header = TokenHeader {
+            alg: "EdDSA".to_string(),
+            typ: "akfed".to_string(),
+        };
+        let claims = FederationClaims {
+            iss: self.instance_id,
+            aud: audience,
+            scope,
+            iat: now,
+            exp: now + DEFAULT_TOKEN_LIFETIME_SECS,
+            jti: Uuid::new_v4(),
+        };
+        let signing_input = format!(
+            "{}.{}",
+            engine.encode(serde_json::to_vec(&header).unwrap_or_default()),
+            engine.encode(serde_json::to_vec(&claims).unwrap_or_default()),
+        );
+        let signature = self.signing_key.sign(signing_input.as_bytes());
+        format!(
+            "{TOKEN_PREFIX}{signing_input}.{}",
+            engine.encode(signature.to_bytes())
+        )
+    }
+}
+
+static LOCAL_IDENTITY: OnceLock<Arc<LocalIdentity>> = OnceLock::new();
+
+/// Load (creating on first start) the local identity and cache it for the
+/// process. Called once from `main` before the sync worker starts.
+pub async fn init(db: &PgPool, passphrase: &str, name: &str, endpoint_url: &str) -> Result<()> {
+    let identity = load_or_create(db, passphrase, name, endpoint_url).await?;
+    tracing::info!(
+        "Federation identity {} ({})",
+        identity.instance_id,
+        identity.fingerprint()
+    );
+    let _ = LOCAL_IDENTITY.set(Arc::new(identity));
+    Ok(())
+}
+
+/// The process-wide identity, loading it on demand if `init` has not run
+/// (tests, tools).
+pub async fn local_identity(
+    db: &PgPool,
+    passphrase: &str,
+    name: &str,
+    endpoint_url: &str,
+) -> Result<Arc<LocalIdentity>> {
+    if let Some(identity) = LOCAL_IDENTITY.get() {
+        return Ok(identity.clone());
+    }
+    Ok(Arc::new(
+        load_or_create(db, passphrase, name, endpoint_url).await?,
+    ))
+}
+
+async fn load_or_create(
+    db: &PgPool,
+    passphrase: &str,
+    name: &str,
+    endpoint_url: &str,
+) -> Result<LocalIdentity> {
+    let encryption = CredentialEncryption::from_passphrase(passphrase);
+
+    // Racing replicas may both generate a key; the singleton primary key
+    // lets exactly one insert win and everyone reads the winner back.
+    let seed = Zeroizing::new(rand::random::<[u8; 32]>());
+    let candidate = SigningKey::from_bytes(&seed);
+    sqlx::query(
+        "INSERT INTO federation_identity (public_key, private_key_enc) \
+         VALUES ($1, $2) ON CONFLICT (singleton) DO NOTHING",
+    )
+    .bind(candidate.verifying_key().to_bytes().to_vec())
+    .bind(encryption.encrypt(seed.as_slice()))
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let row = sqlx::query("SELECT instance_id, private_key_enc FROM federation_identity")
+        .fetch_one(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let enc: Vec<u8> = row.get("private_key_enc");
+    let decrypted = Zeroizing::new(encryption.decrypt(&enc).map_err(|e| {
+        AppError::Internal(format!("Failed to decrypt federation identity key: {}", e))
+    })?);
+    let seed: Zeroizing<[u8; 32]> = Zeroizing::new(
+        decrypted
+            .as_slice()
+            .try_into()
+            .map_err(|_| AppError::Internal("Corrupt federation identity key".to_string()))?,
+    );
+
+    Ok(LocalIdentity {
+        instance_id: row.get("instance_id"),
+        name: name.to_string(),
+        endpoint_url: endpoint_url.to_string(),
+        signing_key: SigningKey::from_bytes(&seed),
+    })
+}
+
+async fn local_instance_id(db: &PgPool) -> Option<Uuid> {
+    if let Some(identity) = LOCAL_IDENTITY.get() {
+        return Some(identity.instance_id);
+    }
+    sqlx::query_scalar("SELECT instance_id FROM federation_identity")
+        .fetch_optional(db)
+        .await
+        .ok()
+        .flatten()
+}
+
+// ---------------------------------------------------------------------------
+// Trusted peers
+// ---------------------------------------------------------------------------
+
+/// A remote instance known through a handshake.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TrustedPeer {
+    pub id: Uuid,
+    pub instance_id: Uuid,
+    pub name: String,
+    pub endpoint_url: String,
+    pub key_fingerprint: String,
+    /// `pending` until an admin approves the peer, then `trusted`.
+    pub status: String,
+    pub allowed_scopes: Vec<FederationScope>,
+    pub repository_ids: Vec<Uuid>,
+    pub service_user_id: Option<Uuid>,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Scopes and repository grants applied when approving a peer.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct TrustGrant {
+    pub scopes: Vec<FederationScope>,
+    /// Repositories the peer may use. Empty limits it to public repositories.
+    #[serde(default)]
+    pub repository_ids: Vec<Uuid>,
+    /// Fingerprint obtained out of band; the approval fails if the recorded
+    /// key does not match.
+    pub expected_fingerprint: Option<String>,
+}
+
+impl TrustGrant {
+    pub fn validate(&self) -> Result<()> {
+        if self.scopes.is_empty() {
+            return Err(AppError::Validation(
+                "At least one federation scope is required".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+const PEER_COLUMNS: &str = "id, instance_id, name, endpoint_url, key_fingerprint, status, \
+     allowed_scopes, repository_ids, service_user_id, approved_at, last_used_at, created_at";
+
+fn peer_from_row(row: &sqlx::postgres::PgRow) -> TrustedPeer {
+    let scopes: Vec<String> = row.get("allowed_scopes");
+    TrustedPeer {
+        id: row.get("id"),
+        instance_id: row.get("instance_id"),
+        name: row.get("name"),
+        endpoint_url: row.get("endpoint_url"),
+        key_fingerprint: row.get("key_fingerprint"),
+        status: row.get("status"),
+        allowed_scopes: scopes
+            .iter()
+            .filter_map(|s| FederationScope::parse(s))
+            .collect(),
+        repository_ids: row.get("repository_ids"),
+        service_user_id: row.get("service_user_id"),
+        approved_at: row.get("approved_at"),
+        last_used_at: row.get("last_used_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub async fn list_peers(db: &PgPool) -> Result<Vec<TrustedPeer>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {PEER_COLUMNS} FROM federation_trusted_peers ORDER BY created_at"
+    ))
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(rows.iter().map(peer_from_row).collect())
+}
+
+pub async fn get_peer(db: &PgPool, id: Uuid) -> Result<TrustedPeer> {
+    let row = sqlx::query(&format!(
+        "SELECT {PEER_COLUMNS} FROM federation_trusted_peers WHERE id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Federated peer not found".to_string()))?;
+    Ok(peer_from_row(&row))
+}
+
+/// The trusted peer whose service principal is `user_id`, if any.
+pub async fn peer_for_principal(db: &PgPool, user_id: Uuid) -> Result<Option<TrustedPeer>> {
+    let row = sqlx::query(&format!(
+        "SELECT {PEER_COLUMNS} FROM federation_trusted_peers \
+         WHERE service_user_id = $1 AND status = $2"
+    ))
+    .bind(user_id)
+    .bind(STATUS_TRUSTED)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(row.as_ref().map(peer_from_row))
+}
+
+/// Record the identity a remote presented during a handshake.
+///
+/// A new instance lands as `pending`. A known instance has its name and
+/// endpoint refreshed; a changed key on a trusted peer is refused rather than
+/// silently replacing the key the admin approved.
+pub async fn record_handshake(
+    db: &PgPool,
+    identity: &InstanceIdentity,
+    local_instance: Uuid,
+) -> Result<TrustedPeer> {
+    let key = identity.verify()?;
+    if identity.instance_id == local_instance {
+        return Err(AppError::Validation(
+            "Refusing to federate with this instance's own identity".to_string(),
+        ));
+    }
+    let key_bytes = key.to_bytes().to_vec();
+
+    let existing = sqlx::query(
+        "SELECT id, status, public_key FROM federation_trusted_peers WHERE instance_id = $1",
+    )
+    .bind(identity.instance_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let id: Uuid = match existing {
+        Some(row) => {
+            let status: String = row.get("status");
+            let stored_key: Vec<u8> = row.get("public_key");
+            if status == STATUS_TRUSTED && stored_key != key_bytes {
+                return Err(AppError::Conflict(
+                    "Peer identity key changed; remove and re-approve the peer".to_string(),
+                ));
+            }
+            let id: Uuid = row.get("id");
+            sqlx::query(
+                "UPDATE federation_trusted_peers \
+                 SET name = $2, endpoint_url = $3, public_key = $4, key_fingerprint = $5, \
+                     updated_at = NOW() \
+                 WHERE id = $1",
+            )
+            .bind(id)
+            .bind(&identity.name)
+            .bind(&identity.endpoint_url)
+            .bind(&key_bytes)
+            .bind(fingerprint(&key_bytes))
+            .execute(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            id
+        }
+        None => {
+            let pending: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM federation_trusted_peers WHERE status = 'pending'",
+            )
+            .fetch_one(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            if pending >= MAX_PENDING_PEERS {
+                return Err(AppError::Conflict(
+                    "Too many pending federation handshakes".to_string(),
+                ));
+            }
+            sqlx::query_scalar(
+                "INSERT INTO federation_trusted_peers \
+                     (instance_id, name, endpoint_url, public_key, key_fingerprint, status) \
+                 VALUES ($1, $2, $3, $4, $5, $6) \
+                 ON CONFLICT (instance_id) DO UPDATE SET updated_at = NOW() \
+                 RETURNING id",
+            )
+            .bind(identity.instance_id)
+            .bind(&identity.name)
+            .bind(&identity.endpoint_url)
+            .bind(&key_bytes)
+            .bind(fingerprint(&key_bytes))
+            .bind(STATUS_PENDING)
+            .fetch_one(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+        }
+    };
+
+    get_peer(db, id).await
+}
+
+/// Approve (or re-scope) a peer: provision its service principal, replace
+/// its role assignments and mark it trusted, all in one transaction.
+pub async fn approve_peer(
+    db: &PgPool,
+    id: Uuid,
+    grant: &TrustGrant,
+    approved_by: Uuid,
+) -> Result<TrustedPeer> {
+    grant.validate()?;
+    let peer = get_peer(db, id).await?;
+    if let Some(expected) = grant.expected_fingerprint.as_deref() {
+        if !expected.trim().eq_ignore_ascii_case(&peer.key_fingerprint) {
+            return Err(AppError::Validation(
+                "Peer key fingerprint does not match the expected value".to_string(),
+            ));
+        }
+    }
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let simple_id = peer.instance_id.simple().to_string();
+    let username = format!("_ak_federation_{}", &simple_id[..12]);
+    let user_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO users ( \
+             username, email, auth_provider, is_active, is_admin, is_service_account, \
+             must_change_password, password_hash, display_name \
+         ) \
+         VALUES ($1, $2, 'local', true, false, true, false, NULL, $3) \
+         ON CONFLICT (username) DO UPDATE SET is_active = true, display_name = EXCLUDED.display_name \
+         RETURNING id",
+    )
+    .bind(&username)
+    .bind(format!("federation-{}@artifact-keeper.internal", simple_id))
+    .bind(format!("Federated peer: {}", peer.name))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    sqlx::query("DELETE FROM role_assignments WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    sqlx::query(
+        "INSERT INTO role_assignments (user_id, role_id, repository_id) \
+         SELECT $1, r.id, repo FROM roles r, UNNEST($2::uuid[]) AS repo \
+         WHERE r.name = $3 \
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(&grant.repository_ids)
+    .bind(FederationScope::role_name(&grant.scopes))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let scopes: Vec<&str> = grant.scopes.iter().map(|s| s.as_str()).collect();
+    sqlx::query(
+        "UPDATE federation_trusted_peers \
+         SET status = $2, allowed_scopes = $3, repository_ids = $4, service_user_id = $5, \
+             approved_by = $6, approved_at = NOW(), updated_at = NOW() \
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(STATUS_TRUSTED)
+    .bind(&scopes)
+    .bind(&grant.repository_ids)
+    .bind(user_id)
+    .bind(approved_by)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    get_peer(db, id).await
+}
+
+/// Forget a peer and deactivate its service principal. Tokens it mints stop
+/// working immediately.
+pub async fn remove_peer(db: &PgPool, id: Uuid) -> Result<()> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let user_id: Option<Option<Uuid>> = sqlx::query_scalar(
+        "DELETE FROM federation_trusted_peers WHERE id = $1 RETURNING service_user_id",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let Some(user_id) = user_id else {
+        return Err(AppError::NotFound("Federated peer not found".to_string()));
+    };
+    if let Some(user_id) = user_id {
+        sqlx::query("UPDATE users SET is_active = false, updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        sqlx::query("DELETE FROM role_assignments WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Send our identity to `endpoint_url` and return the verified identity it
+/// answers with.
+pub async fn perform_handshake(
+    endpoint_url: &str,
+    local: &InstanceIdentity,
+) -> Result<InstanceIdentity> {
+    let url = format!(
+        "{}/api/v1/federation/handshake",
+        endpoint_url.trim_end_matches('/')
+    );
+    let response = crate::services::http_client::default_client()
+        .post(&url)
+        .json(local)
+        .send()
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Federation handshake failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::BadGateway(format!(
+            "Federation handshake returned HTTP {}",
+            response.status()
+        )));
+    }
+    let remote: InstanceIdentity = response.json().await.map_err(|e| {
+        AppError::BadGateway(format!("Invalid federation handshake response: {}", e))
+    })?;
+    remote.verify()?;
+    Ok(remote)
+}
+
+// ---------------------------------------------------------------------------
+// Authentication
+// ---------------------------------------------------------------------------
+
+/// The local principal a verified federation token resolves to.
+#[derive(Debug, Clone)]
+pub struct FederatedPrincipal {
+    pub user_id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub scopes: Vec<String>,
+    pub allowed_repo_ids: AccessScope,
+}
+
+/// Verify a federation token against the trusted peer that issued it.
+///
+/// Returns `None` for any failure (unknown or pending issuer, bad signature,
+/// wrong audience, expired, scope not granted, principal deactivated).
+pub async fn authenticate(db: &PgPool, token: &str) -> Option<FederatedPrincipal> {
+    let parsed = parse_token(token)?;
+    let audience = local_instance_id(db).await?;
+
+    let row = sqlx::query(
+        "SELECT p.id, p.public_key, p.allowed_scopes, p.repository_ids, \
+                u.id AS user_id, u.username, u.email \
+         FROM federation_trusted_peers p \
+         JOIN users u ON u.id = p.service_user_id \
+         WHERE p.instance_id = $1 AND p.status = 'trusted' AND u.is_active = true",
+    )
+    .bind(parsed.claims.iss)
+    .fetch_optional(db)
+    .await
+    .ok()
+    .flatten()?;
+
+    let key_bytes: [u8; 32] = row.get::<Vec<u8>, _>("public_key").try_into().ok()?;
+    let key = VerifyingKey::from_bytes(&key_bytes).ok()?;
+    if let Err(reason) = parsed.verify(&key, audience, Utc::now().timestamp()) {
+        tracing::debug!(
+            "Rejected federation token from {}: {}",
+            parsed.claims.iss,
+            reason
+        );
+        return None;
+    }
+    let granted: Vec<String> = row.get("allowed_scopes");
+    if !granted.iter().any(|s| s == parsed.claims.scope.as_str()) {
+        return None;
+    }
+
+    let peer_id: Uuid = row.get("id");
+    let _ = sqlx::query(
+        "UPDATE federation_trusted_peers SET last_used_at = NOW() \
+         WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')",
+    )
+    .bind(peer_id)
+    .execute(db)
+    .await;
+
+    let repository_ids: Vec<Uuid> = row.get("repository_ids");
+    Some(FederatedPrincipal {
+        user_id: row.get("user_id"),
+        username: row.get("username"),
+        email: row.get("email"),
+        scopes: parsed.claims.scope.token_scopes(),
+        allowed_repo_ids: if repository_ids.is_empty() {
+            AccessScope::Admin
+        } else {
+            AccessScope::Restricted(repository_ids)
+        },
+    })
+}
+
+/// Credential to present to a replication peer.
+///
+/// A peer registered with an API key keeps using it. A peer registered
+/// without one gets a freshly minted federation token, provided a handshake
+/// with that endpoint has told us its instance id.
+pub async fn peer_credential(
+    db: &PgPool,
+    endpoint_url: &str,
+    api_key: &str,
+    scope: FederationScope,
+) -> String {
+    if !api_key.is_empty() {
+        return api_key.to_string();
+    }
+    let Some(identity) = LOCAL_IDENTITY.get() else {
+        return String::new();
+    };
+    let audience: Option<Uuid> = sqlx::query_scalar(
+        "SELECT instance_id FROM federation_trusted_peers \
+         WHERE TRIM(TRAILING '/' FROM endpoint_url) = $1 LIMIT 1",
+    )
+    .bind(endpoint_url.trim_end_matches('/'))
+    .fetch_optional(db)
+    .await
+    .ok()
+    .flatten();
+    match audience {
+        Some(aud) => identity.mint_token(aud, scope, Utc::now().timestamp()),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests_support {
+    use super::*;
+
+    /// A remote-looking identity with a deterministic key and fresh id.
+    pub(crate) fn identity_with_seed(seed: u8) -> LocalIdentity {
+        LocalIdentity {
+            instance_id: Uuid::new_v4(),
+            name: "ak-east".to_string(),
+            endpoint_url: "https://ak-east.example.com".to_string(),
+            signing_key: SigningKey::from_bytes(&[seed; 32]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tests_support::identity_with_seed as identity;
+    use super::*;
+
+    #[test]
+    fn identity_document_verifies() {
+        let local = identity(1);
+        let doc = local.document();
+        let key = doc.verify().expect("self-signature verifies");
+        assert_eq!(key.to_bytes(), local.public_key_bytes());
+    }
+
+    #[test]
+    fn tampered_identity_document_is_rejected() {
+        let mut doc = identity(1).document();
+        doc.endpoint_url = "https://attacker.example.com".to_string();
+        assert!(doc.verify().is_err());
+
+        let mut doc = identity(1).document();
+        doc.public_key = base64::engine::general_purpose::STANDARD
+            .encode(SigningKey::from_bytes(&[2; 32]).verifying_key().to_bytes());
+        assert!(doc.verify().is_err());
+    }
+
+    #[test]
+    fn fingerprint_is_stable_and_prefixed() {
+        let local = identity(3);
+        assert_eq!(local.fingerprint(), fingerprint(&local.public_key_bytes()));
+        assert!(local.fingerprint().starts_with("SHA256:"));
+        assert_eq!(local.fingerprint().len(), "SHA256:".len() + 64);
+    }
+
+    #[test]
+    fn minted_token_round_trips() {
+        let issuer = identity(4);
+        let audience = Uuid::new_v4();
+        let now = 1_700_000_000;
+        let token = issuer.mint_token(audience, FederationScope::Download, now);
+        assert!(is_federation_token(&token));
+
+        let parsed = parse_token(&token).expect("parses");
+        assert_eq!(parsed.claims.iss, issuer.instance_id);
+        assert_eq!(parsed.claims.scope, FederationScope::Download);
+        let key = issuer.signing_key.verifying_key();
+        assert_eq!(parsed.verify(&key, audience, now + 10), Ok(()));
+    }
+
+    #[test]
+    fn token_checks_audience_expiry_and_signature() {
+        let issuer = identity(5);
+        let audience = Uuid::new_v4();
+        let now = 1_700_000_000;
+        let token = issuer.mint_token(audience, FederationScope::Replication, now);
+        let parsed = parse_token(&token).unwrap();
+        let key = issuer.signing_key.verifying_key();
+
+        assert_eq!(
+            parsed.verify(&key, Uuid::new_v4(), now),
+            Err("wrong audience")
+        );
+        assert_eq!(
+            parsed.verify(
+                &key,
+                audience,
+                now + DEFAULT_TOKEN_LIFETIME_SECS + CLOCK_SKEW_SECS
+            ),
+            Err("expired")
+        );
+        assert_eq!(
+            parsed.verify(&key, audience, now - CLOCK_SKEW_SECS - 1),
+            Err("issued in the future")
+        );
+        let other = SigningKey::from_bytes(&[6; 32]).verifying_key();
+        assert_eq!(parsed.verify(&other, audience, now), Err("bad signature"));
+    }
+
+    #[test]
+    fn parse_rejects_foreign_tokens() {
+        assert!(parse_token("eyJhbGciOiJIUzI1NiJ9.e30.sig").is_none());
+        assert!(parse_token("akfed.only.two").is_none());
+        assert!(parse_token("akfed.a.b.c.d").is_none());
+    }
+
+    #[test]
+    fn scopes_map_to_least_privilege() {
+        let download = FederationScope::Download.token_scopes();
+        assert!(download.contains(&"read:artifacts".to_string()));
+        assert!(!download.iter().any(|s| s.starts_with("write")));
+        assert!(FederationScope::Replication
+            .token_scopes()
+            .contains(&"write:artifacts".to_string()));
+        assert_eq!(
+            FederationScope::role_name(&[FederationScope::Download]),
+            "reader"
+        );
+        assert_eq!(
+            FederationScope::role_name(&[FederationScope::Download, FederationScope::Replication]),
+            "developer"
+        );
+    }
+
+    #[test]
+    fn grant_requires_a_scope() {
+        let grant = TrustGrant {
+            scopes: vec![],
+            repository_ids: vec![],
+            expected_fingerprint: None,
+        };
+        assert!(grant.validate().is_err());
+    }
+}
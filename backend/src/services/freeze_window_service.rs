@@ -0,0 +1,737 @@
+//! Release freeze windows.
+//!
+//! A freeze window blocks uploads and/or promotions into the repositories it
+//! covers while it is active. It covers either one repository or every
+//! repository whose labels match a selector, and runs either once (an
+//! absolute interval) or weekly (e.g. `fri 18:00` to `mon 06:00` at a fixed
+//! UTC offset).
+//!
+//! Uploads are checked at the two shared upload chokepoints
+//! (`ArtifactService::preflight_upload` and `proxy_helpers::insert_artifact`);
+//! promotions are checked by the promotion, approval and inbound federation
+//! handlers. Remote and virtual repositories are never frozen, so proxy cache
+//! fills continue during a window.
+//!
+//! Holders of the [`OVERRIDE_ACTION`] permission on the repository may write
+//! through a freeze. Uploads pass automatically for them; promotions also
+//! require an explicit override reason, and instance admins may override a
+//! promotion without the grant. Every override is written to the audit log.
+
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Instant;
+
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::permission_service::PermissionService;
+use crate::services::repository_label_service::{LabelEntry, RepositoryLabelService};
+
+/// Permission action that lets a principal write through a freeze.
+pub const OVERRIDE_ACTION: &str = "freeze_override";
+
+/// How long the in-memory copy of the windows used by write checks is reused.
+const SNAPSHOT_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+const MINUTES_PER_WEEK: u32 = 7 * 24 * 60;
+const DAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// When a window is active.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FreezeSchedule {
+    /// A single interval.
+    Once {
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    },
+    /// Every week from `start` to `end`, each written as `<day> <HH:MM>`
+    /// (e.g. `fri 18:00`). The end may fall in the following week.
+    Weekly {
+        start: String,
+        end: String,
+        /// Offset from UTC in minutes of the times above (e.g. `-300`).
+        #[serde(default)]
+        utc_offset_minutes: i32,
+    },
+}
+
+/// Parse `<day> <HH:MM>` into minutes since Monday 00:00.
+fn parse_week_time(s: &str) -> Option<u32> {
+    let (day, time) = s.trim().split_once(char::is_whitespace)?;
+    let day = day.trim().to_ascii_lowercase();
+    let day = DAYS.iter().position(|d| day.starts_with(d))? as u32;
+    let (hour, minute) = time.trim().split_once(':')?;
+    let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+    Some(day * 24 * 60 + hour * 60 + minute)
+}
+
+impl FreezeSchedule {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            FreezeSchedule::Once { starts_at, ends_at } => {
+                if ends_at <= starts_at {
+                    return Err(AppError::Validation(
+                        "A freeze window must end after it starts".to_string(),
+                    ));
+                }
+            }
+            FreezeSchedule::Weekly {
+                start,
+                end,
+                utc_offset_minutes,
+            } => {
+                let (Some(s), Some(e)) = (parse_week_time(start), parse_week_time(end)) else {
+                    return Err(AppError::Validation(
+                        "Weekly freeze times must look like 'fri 18:00'".to_string(),
+                    ));
+                };
+                if s == e {
+                    return Err(AppError::Validation(
+                        "A weekly freeze window must not start and end at the same time"
+                            .to_string(),
+                    ));
+                }
+                if utc_offset_minutes.abs() > 14 * 60 {
+                    return Err(AppError::Validation(
+                        "utc_offset_minutes must be between -840 and 840".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// If the window is active at `now`, when the current occurrence ends.
+    pub fn active_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            FreezeSchedule::Once { starts_at, ends_at } => {
+                (*starts_at <= now && now < *ends_at).then_some(*ends_at)
+            }
+            FreezeSchedule::Weekly {
+                start,
+                end,
+                utc_offset_minutes,
+            } => {
+                let (start, end) = (parse_week_time(start)?, parse_week_time(end)?);
+                let local = now + Duration::minutes(*utc_offset_minutes as i64);
+                let minute = local.weekday().num_days_from_monday() * 24 * 60
+                    + local.hour() * 60
+                    + local.minute();
+                let remaining = if start < end {
+                    (start <= minute && minute < end).then(|| end - minute)
+                } else if minute >= start {
+                    Some(MINUTES_PER_WEEK - minute + end)
+                } else {
+                    (minute < end).then(|| end - minute)
+                }?;
+                let minute_start = now
+                    - Duration::seconds(now.second() as i64)
+                    - Duration::nanoseconds(now.nanosecond() as i64);
+                Some(minute_start + Duration::minutes(remaining as i64))
+            }
+        }
+    }
+}
+
+/// A configured freeze window.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FreezeWindow {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// The covered repository, when the window targets one.
+    pub repository_id: Option<Uuid>,
+    /// The covered repositories, when the window targets labels. An empty
+    /// value matches any value of the key.
+    pub label_selector: Option<Vec<LabelEntry>>,
+    pub schedule: FreezeSchedule,
+    pub block_uploads: bool,
+    pub block_promotions: bool,
+    pub enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create or replace request.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct FreezeWindowRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Repository key; exclusive with `label_selector`.
+    pub repository_key: Option<String>,
+    pub label_selector: Option<Vec<LabelEntry>>,
+    pub schedule: FreezeSchedule,
+    #[serde(default = "default_true")]
+    pub block_uploads: bool,
+    #[serde(default = "default_true")]
+    pub block_promotions: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl FreezeWindowRequest {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() || self.name.len() > 255 {
+            return Err(AppError::Validation(
+                "name must be between 1 and 255 characters".to_string(),
+            ));
+        }
+        match (&self.repository_key, &self.label_selector) {
+            (Some(_), None) => {}
+            (None, Some(selector)) => {
+                if selector.is_empty() || selector.iter().any(|l| l.key.trim().is_empty()) {
+                    return Err(AppError::Validation(
+                        "label_selector needs at least one entry, each with a key".to_string(),
+                    ));
+                }
+            }
+            _ => {
+                return Err(AppError::Validation(
+                    "Exactly one of repository_key and label_selector is required".to_string(),
+                ))
+            }
+        }
+        if !self.block_uploads && !self.block_promotions {
+            return Err(AppError::Validation(
+                "A freeze window must block uploads, promotions, or both".to_string(),
+            ));
+        }
+        self.schedule.validate()
+    }
+}
+
+/// The write a freeze check is guarding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreezeAction {
+    Upload,
+    Promotion,
+}
+
+impl FreezeAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FreezeAction::Upload => "upload",
+            FreezeAction::Promotion => "promotion",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+const WINDOW_COLUMNS: &str = "id, name, description, repository_id, label_selector, schedule, \
+     block_uploads, block_promotions, enabled, created_by, created_at, updated_at";
+
+fn window_from_row(row: &sqlx::postgres::PgRow) -> Result<FreezeWindow> {
+    let schedule: serde_json::Value = row.get("schedule");
+    let selector: Option<serde_json::Value> = row.get("label_selector");
+    Ok(FreezeWindow {
+        id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        repository_id: row.get("repository_id"),
+        label_selector: selector
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| AppError::Internal(format!("Corrupt freeze label selector: {}", e)))?,
+        schedule: serde_json::from_value(schedule)
+            .map_err(|e| AppError::Internal(format!("Corrupt freeze schedule: {}", e)))?,
+        block_uploads: row.get("block_uploads"),
+        block_promotions: row.get("block_promotions"),
+        enabled: row.get("enabled"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+async fn resolve_repository(db: &PgPool, key: Option<&str>) -> Result<Option<Uuid>> {
+    let Some(key) = key else {
+        return Ok(None);
+    };
+    let id: Option<Uuid> = sqlx::query_scalar("SELECT id FROM repositories WHERE key = $1")
+        .bind(key)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    id.map(Some)
+        .ok_or_else(|| AppError::NotFound(format!("Repository '{}' not found", key)))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<serde_json::Value> {
+    serde_json::to_value(value).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+pub async fn list(db: &PgPool) -> Result<Vec<FreezeWindow>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {WINDOW_COLUMNS} FROM freeze_windows ORDER BY name"
+    ))
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    rows.iter().map(window_from_row).collect()
+}
+
+pub async fn get(db: &PgPool, id: Uuid) -> Result<FreezeWindow> {
+    let row = sqlx::query(&format!(
+        "SELECT {WINDOW_COLUMNS} FROM freeze_windows WHERE id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Freeze window not found".to_string()))?;
+    window_from_row(&row)
+}
+
+pub async fn create(
+    db: &PgPool,
+    req: &FreezeWindowRequest,
+    created_by: Uuid,
+) -> Result<FreezeWindow> {
+    req.validate()?;
+    let repository_id = resolve_repository(db, req.repository_key.as_deref()).await?;
+    let selector = req.label_selector.as_ref().map(to_json).transpose()?;
+    let row = sqlx::query(&format!(
+        "INSERT INTO freeze_windows ( \
+             name, description, repository_id, label_selector, schedule, \
+             block_uploads, block_promotions, enabled, created_by \
+         ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+         RETURNING {WINDOW_COLUMNS}"
+    ))
+    .bind(req.name.trim())
+    .bind(&req.description)
+    .bind(repository_id)
+    .bind(selector)
+    .bind(to_json(&req.schedule)?)
+    .bind(req.block_uploads)
+    .bind(req.block_promotions)
+    .bind(req.enabled)
+    .bind(created_by)
+    .fetch_one(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    invalidate_cache();
+    window_from_row(&row)
+}
+
+pub async fn update(db: &PgPool, id: Uuid, req: &FreezeWindowRequest) -> Result<FreezeWindow> {
+    req.validate()?;
+    let repository_id = resolve_repository(db, req.repository_key.as_deref()).await?;
+    let selector = req.label_selector.as_ref().map(to_json).transpose()?;
+    let row = sqlx::query(&format!(
+        "UPDATE freeze_windows SET \
+             name = $2, description = $3, repository_id = $4, label_selector = $5, \
+             schedule = $6, block_uploads = $7, block_promotions = $8, enabled = $9, \
+             updated_at = NOW() \
+         WHERE id = $1 \
+         RETURNING {WINDOW_COLUMNS}"
+    ))
+    .bind(id)
+    .bind(req.name.trim())
+    .bind(&req.description)
+    .bind(repository_id)
+    .bind(selector)
+    .bind(to_json(&req.schedule)?)
+    .bind(req.block_uploads)
+    .bind(req.block_promotions)
+    .bind(req.enabled)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Freeze window not found".to_string()))?;
+    invalidate_cache();
+    window_from_row(&row)
+}
+
+pub async fn delete(db: &PgPool, id: Uuid) -> Result<()> {
+    let result = sqlx::query("DELETE FROM freeze_windows WHERE id = $1")
+        .bind(id)
+        .execute(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Freeze window not found".to_string()));
+    }
+    invalidate_cache();
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Enforcement
+// ---------------------------------------------------------------------------
+
+fn snapshot_cache() -> &'static RwLock<Option<(Instant, Arc<Vec<FreezeWindow>>)>> {
+    static CACHE: OnceLock<RwLock<Option<(Instant, Arc<Vec<FreezeWindow>>)>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// Drop the cached windows after they change. Other replicas pick the change
+/// up within [`SNAPSHOT_TTL`].
+pub fn invalidate_cache() {
+    match snapshot_cache().write() {
+        Ok(mut cache) => *cache = None,
+        Err(poisoned) => *poisoned.into_inner() = None,
+    }
+}
+
+async fn cached_windows(db: &PgPool) -> Result<Arc<Vec<FreezeWindow>>> {
+    if let Ok(cache) = snapshot_cache().read() {
+        if let Some((loaded, windows)) = cache.as_ref() {
+            if loaded.elapsed() < SNAPSHOT_TTL {
+                return Ok(windows.clone());
+            }
+        }
+    }
+    let rows = sqlx::query(&format!(
+        "SELECT {WINDOW_COLUMNS} FROM freeze_windows WHERE enabled = true"
+    ))
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let windows = Arc::new(
+        rows.iter()
+            .map(window_from_row)
+            .collect::<Result<Vec<_>>>()?,
+    );
+    let mut cache = match snapshot_cache().write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *cache = Some((Instant::now(), windows.clone()));
+    Ok(windows)
+}
+
+fn selector_matches(selector: &[LabelEntry], labels: &[(String, String)]) -> bool {
+    selector.iter().all(|s| {
+        labels
+            .iter()
+            .any(|(k, v)| *k == s.key && (s.value.is_empty() || *v == s.value))
+    })
+}
+
+/// A window currently freezing a repository.
+#[derive(Debug, Clone)]
+pub struct ActiveFreeze {
+    pub window_id: Uuid,
+    pub name: String,
+    pub until: DateTime<Utc>,
+}
+
+/// Windows freezing `action` on `repository_id` at `now`.
+pub async fn active_freezes(
+    db: &PgPool,
+    repository_id: Uuid,
+    action: FreezeAction,
+    now: DateTime<Utc>,
+) -> Result<Vec<ActiveFreeze>> {
+    let windows = cached_windows(db).await?;
+    let candidates: Vec<(&FreezeWindow, DateTime<Utc>)> = windows
+        .iter()
+        .filter(|w| match action {
+            FreezeAction::Upload => w.block_uploads,
+            FreezeAction::Promotion => w.block_promotions,
+        })
+        .filter(|w| w.repository_id.is_none_or(|id| id == repository_id))
+        .filter_map(|w| w.schedule.active_until(now).map(|until| (w, until)))
+        .collect();
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Only hosted repositories are frozen; proxy cache fills keep working.
+    let repo_type: Option<String> =
+        sqlx::query_scalar("SELECT repo_type::text FROM repositories WHERE id = $1")
+            .bind(repository_id)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    if matches!(
+        repo_type.as_deref(),
+        None | Some("remote") | Some("virtual")
+    ) {
+        return Ok(Vec::new());
+    }
+
+    let labels: Vec<(String, String)> =
+        if candidates.iter().any(|(w, _)| w.label_selector.is_some()) {
+            RepositoryLabelService::new(db.clone())
+                .get_labels(repository_id)
+                .await?
+                .into_iter()
+                .map(|l| (l.label_key, l.label_value))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+    Ok(candidates
+        .into_iter()
+        .filter(|(w, _)| {
+            w.label_selector
+                .as_deref()
+                .is_none_or(|s| selector_matches(s, &labels))
+        })
+        .map(|(w, until)| ActiveFreeze {
+            window_id: w.id,
+            name: w.name.clone(),
+            until,
+        })
+        .collect())
+}
+
+fn frozen_error(freeze: &ActiveFreeze, action: FreezeAction) -> AppError {
+    AppError::Conflict(format!(
+        "Repository is frozen for {}s by freeze window '{}' until {}",
+        action.as_str(),
+        freeze.name,
+        freeze.until.to_rfc3339()
+    ))
+}
+
+async fn holds_override(db: &PgPool, user_id: Uuid, repository_id: Uuid) -> Result<bool> {
+    // Admin status alone does not override an upload freeze; the grant must be
+    // explicit.
+    PermissionService::new(db.clone())
+        .check_permission(user_id, "repository", repository_id, OVERRIDE_ACTION, false)
+        .await
+}
+
+async fn audit_override(
+    db: &PgPool,
+    freezes: &[ActiveFreeze],
+    repository_id: Uuid,
+    user_id: Uuid,
+    action: FreezeAction,
+    reason: Option<&str>,
+) {
+    tracing::warn!(
+        repository_id = %repository_id,
+        user_id = %user_id,
+        action = action.as_str(),
+        windows = ?freezes.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+        "Freeze window overridden"
+    );
+    let _ = AuditService::new(db.clone())
+        .log(
+            AuditEntry::new(AuditAction::FreezeOverridden, ResourceType::Repository)
+                .user(user_id)
+                .resource(repository_id)
+                .details(serde_json::json!({
+                    "action": action.as_str(),
+                    "reason": reason,
+                    "windows": freezes.iter().map(|f| serde_json::json!({
+                        "id": f.window_id,
+                        "name": f.name,
+                        "until": f.until,
+                    })).collect::<Vec<_>>(),
+                })),
+        )
+        .await;
+}
+
+/// Reject an upload into a frozen repository unless the uploader holds the
+/// override permission.
+pub async fn check_upload(
+    db: &PgPool,
+    repository_id: Uuid,
+    uploaded_by: Option<Uuid>,
+) -> Result<()> {
+    let freezes = active_freezes(db, repository_id, FreezeAction::Upload, Utc::now()).await?;
+    let Some(first) = freezes.first() else {
+        return Ok(());
+    };
+    let Some(user_id) = uploaded_by else {
+        return Err(frozen_error(first, FreezeAction::Upload));
+    };
+    if !holds_override(db, user_id, repository_id).await? {
+        return Err(frozen_error(first, FreezeAction::Upload));
+    }
+    audit_override(
+        db,
+        &freezes,
+        repository_id,
+        user_id,
+        FreezeAction::Upload,
+        None,
+    )
+    .await;
+    Ok(())
+}
+
+/// Reject a promotion into a frozen repository unless the promoter gives an
+/// override reason and is an admin or holds the override permission.
+pub async fn check_promotion(
+    db: &PgPool,
+    target_repository_id: Uuid,
+    user_id: Uuid,
+    is_admin: bool,
+    override_reason: Option<&str>,
+) -> Result<()> {
+    let freezes = active_freezes(
+        db,
+        target_repository_id,
+        FreezeAction::Promotion,
+        Utc::now(),
+    )
+    .await?;
+    let Some(first) = freezes.first() else {
+        return Ok(());
+    };
+    let Some(reason) = override_reason.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Err(frozen_error(first, FreezeAction::Promotion));
+    };
+    if !is_admin && !holds_override(db, user_id, target_repository_id).await? {
+        return Err(AppError::Authorization(format!(
+            "Overriding freeze window '{}' requires the '{}' permission",
+            first.name, OVERRIDE_ACTION
+        )));
+    }
+    audit_override(
+        db,
+        &freezes,
+        target_repository_id,
+        user_id,
+        FreezeAction::Promotion,
+        Some(reason),
+    )
+    .await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn weekly(start: &str, end: &str, offset: i32) -> FreezeSchedule {
+        FreezeSchedule::Weekly {
+            start: start.to_string(),
+            end: end.to_string(),
+            utc_offset_minutes: offset,
+        }
+    }
+
+    #[test]
+    fn test_parse_week_time() {
+        assert_eq!(parse_week_time("mon 00:00"), Some(0));
+        assert_eq!(parse_week_time("Friday 18:00"), Some(4 * 1440 + 18 * 60));
+        assert_eq!(parse_week_time("sun 23:59"), Some(MINUTES_PER_WEEK - 1));
+        assert_eq!(parse_week_time("fri 24:00"), None);
+        assert_eq!(parse_week_time("someday 10:00"), None);
+        assert_eq!(parse_week_time("fri"), None);
+    }
+
+    #[test]
+    fn test_weekend_freeze_wraps_the_week() {
+        let schedule = weekly("fri 18:00", "mon 06:00", 0);
+        schedule.validate().unwrap();
+
+        // 2026-10-16 is a Friday.
+        let friday_evening = Utc.with_ymd_and_hms(2026, 10, 16, 19, 30, 15).unwrap();
+        assert_eq!(
+            schedule.active_until(friday_evening),
+            Some(Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap())
+        );
+        let monday_early = Utc.with_ymd_and_hms(2026, 10, 19, 5, 59, 0).unwrap();
+        assert!(schedule.active_until(monday_early).is_some());
+        let monday_morning = Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap();
+        assert!(schedule.active_until(monday_morning).is_none());
+        let wednesday = Utc.with_ymd_and_hms(2026, 10, 14, 12, 0, 0).unwrap();
+        assert!(schedule.active_until(wednesday).is_none());
+    }
+
+    #[test]
+    fn test_weekly_freeze_honours_offset() {
+        // 18:00 at UTC-5 is 23:00 UTC.
+        let schedule = weekly("fri 18:00", "mon 06:00", -300);
+        let before = Utc.with_ymd_and_hms(2026, 10, 16, 22, 59, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 10, 16, 23, 0, 0).unwrap();
+        assert!(schedule.active_until(before).is_none());
+        assert_eq!(
+            schedule.active_until(after),
+            Some(Utc.with_ymd_and_hms(2026, 10, 19, 11, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_once_schedule() {
+        let starts_at = Utc.with_ymd_and_hms(2026, 12, 20, 0, 0, 0).unwrap();
+        let ends_at = Utc.with_ymd_and_hms(2027, 1, 4, 0, 0, 0).unwrap();
+        let schedule = FreezeSchedule::Once { starts_at, ends_at };
+        schedule.validate().unwrap();
+        assert_eq!(schedule.active_until(starts_at), Some(ends_at));
+        assert!(schedule.active_until(ends_at).is_none());
+
+        let backwards = FreezeSchedule::Once {
+            starts_at: ends_at,
+            ends_at: starts_at,
+        };
+        assert!(backwards.validate().is_err());
+        assert!(weekly("fri 18:00", "fri 18:00", 0).validate().is_err());
+    }
+
+    #[test]
+    fn test_request_scope_validation() {
+        let request = |repository_key: Option<&str>, label_selector: Option<Vec<LabelEntry>>| {
+            FreezeWindowRequest {
+                name: "release freeze".to_string(),
+                description: None,
+                repository_key: repository_key.map(str::to_string),
+                label_selector,
+                schedule: weekly("fri 18:00", "mon 06:00", 0),
+                block_uploads: true,
+                block_promotions: true,
+                enabled: true,
+            }
+        };
+        let env_prod = vec![LabelEntry {
+            key: "env".to_string(),
+            value: "prod".to_string(),
+        }];
+        request(Some("libs-release"), None).validate().unwrap();
+        request(None, Some(env_prod.clone())).validate().unwrap();
+        assert!(request(None, None).validate().is_err());
+        assert!(request(Some("libs-release"), Some(env_prod))
+            .validate()
+            .is_err());
+        assert!(request(None, Some(vec![])).validate().is_err());
+    }
+
+    #[test]
+    fn test_selector_matches() {
+        let labels = vec![
+            ("env".to_string(), "prod".to_string()),
+            ("team".to_string(), "payments".to_string()),
+        ];
+        let entry = |key: &str, value: &str| LabelEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        assert!(selector_matches(&[entry("env", "prod")], &labels));
+        assert!(selector_matches(&[entry("team", "")], &labels));
+        assert!(selector_matches(
+            &[entry("env", "prod"), entry("team", "payments")],
+            &labels
+        ));
+        assert!(!selector_matches(&[entry("env", "staging")], &labels));
+        assert!(!selector_matches(
+            &[entry("env", "prod"), entry("tier", "")],
+            &labels
+        ));
+    }
+}
@@ -676,6 +676,7 @@ impl GrypeScanner {
         artifact: &Artifact,
         image_ref: String,
         repo_key: Option<&str>,
+        extra_args: &[String],
     ) -> Result<ScanOutput> {
         let target = format!("registry:{}", image_ref);
         info!("Grype OCI registry scan target: {}", target);
@@ -684,7 +685,7 @@ impl GrypeScanner {
         // know the owning repo (production `scan_target` path). Legacy keyless
         // scans pull anonymously (empty env), preserving prior behavior.
         let auth_env = self.registry_auth_env_for_repo(repo_key);
-        let report = match self.run_grype_target(&target, &auth_env).await {
+        let report = match self.run_grype_target(&target, &auth_env, extra_args).await {
             Ok(report) => report,
             Err(e) => {
                 return Err(
@@ -730,7 +731,10 @@ impl GrypeScanner {
         let grype_target = format!("oci-dir:{}", layout_dir.to_string_lossy());
         info!("Grype OCI local layout scan target: {}", grype_target);
         // Local OCI layout: no registry pull, so no registry-auth env.
-        let result = match self.run_grype_target(&grype_target, &[]).await {
+        let result = match self
+            .run_grype_target(&grype_target, &[], target.scanner_args)
+            .await
+        {
             Ok(report) => {
                 let findings = Self::convert_findings(&report);
                 let packages = Self::convert_packages(&report);
@@ -966,16 +970,17 @@ impl GrypeScanner {
     }
 
     /// Run grype against the workspace directory.
-    async fn run_grype(&self, workspace: &Path) -> Result<GrypeReport> {
+    async fn run_grype(&self, workspace: &Path, extra_args: &[String]) -> Result<GrypeReport> {
         let dir_arg = format!("dir:{}", workspace.to_string_lossy());
         // Directory (local layout) scans never touch the registry, so no
         // registry-auth env is needed.
-        self.run_grype_target(&dir_arg, &[]).await
+        self.run_grype_target(&dir_arg, &[], extra_args).await
     }
 
     /// Run grype against an arbitrary target string (e.g. `dir:/path`,
     /// `registry:host/name:tag`). Centralized so both modes share output
-    /// parsing and "binary not installed" handling.
+    /// parsing and "binary not installed" handling. `extra_args` are the
+    /// repository's allowlisted scanner arguments (`repo_scan_config`).
     ///
     /// Two behaviors worth calling out:
     ///
@@ -1002,6 +1007,7 @@ impl GrypeScanner {
         &self,
         target: &str,
         auth_env: &[(&'static str, String)],
+        extra_args: &[String],
     ) -> Result<GrypeReport> {
         // Issue #1465: detect "grype binary missing from PATH" via the
         // io::ErrorKind of the spawn failure, NOT a substring search on
@@ -1018,6 +1024,7 @@ impl GrypeScanner {
         let mut command = tokio::process::Command::new("grype");
         command
             .args([target, "-o", "json"])
+            .args(extra_args)
             .env("GRYPE_DB_AUTO_UPDATE", "false")
             .env("GRYPE_DB_VALIDATE_AGE", "false")
             .env("GRYPE_CHECK_FOR_APP_UPDATE", "false");
@@ -1347,6 +1354,55 @@ impl Scanner for GrypeScanner {
         artifact: &Artifact,
         _metadata: Option<&ArtifactMetadata>,
         content: &Bytes,
+    ) -> Result<ScanOutput> {
+        self.scan_with_args(artifact, content, &[]).await
+    }
+
+    async fn scan_target(
+        &self,
+        target: &ScanTarget<'_>,
+        _metadata: Option<&ArtifactMetadata>,
+        content: &Bytes,
+    ) -> Result<ScanOutput> {
+        let artifact = target.artifact;
+        if is_oci_image_artifact(artifact) {
+            if let Some(output) = self.scan_oci_layout_dir(artifact, target, content).await? {
+                return Ok(output);
+            }
+
+            // #1971: thread the in-hand manifest body for index→child resolution.
+            let image_ref =
+                Self::oci_registry_target(artifact, target, Some(content)).ok_or_else(|| {
+                    AppError::Internal(
+                        "Grype OCI scan: failed to reconstruct repository-qualified registry image ref \
+                     (is_applicable_for_target should have rejected this artifact)"
+                            .to_string(),
+                    )
+                })?;
+            // Repository-aware path: mint a pull token scoped to this repo.
+            return self
+                .scan_oci_registry_ref(
+                    artifact,
+                    image_ref,
+                    Some(target.repository_key),
+                    target.scanner_args,
+                )
+                .await;
+        }
+
+        self.scan_with_args(artifact, content, target.scanner_args)
+            .await
+    }
+}
+
+impl GrypeScanner {
+    /// The keyless scan path shared by [`Scanner::scan`] and the non-OCI
+    /// branch of [`Scanner::scan_target`].
+    async fn scan_with_args(
+        &self,
+        artifact: &Artifact,
+        content: &Bytes,
+        extra_args: &[String],
     ) -> Result<ScanOutput> {
         info!(
             "Starting Grype scan for artifact: {} ({})",
@@ -1372,13 +1428,15 @@ impl Scanner for GrypeScanner {
                     )
                 })?;
             // Legacy keyless path: no owning repository key, anonymous pull.
-            return self.scan_oci_registry_ref(artifact, image_ref, None).await;
+            return self
+                .scan_oci_registry_ref(artifact, image_ref, None, extra_args)
+                .await;
         }
 
         let workspace =
             ScanWorkspace::prepare(&self.scan_workspace, None, artifact, content).await?;
 
-        let report = match self.run_grype(&workspace).await {
+        let report = match self.run_grype(&workspace, extra_args).await {
             Ok(report) => report,
             Err(e) => {
                 return Err(
@@ -1416,36 +1474,6 @@ impl Scanner for GrypeScanner {
             scan_completeness: crate::services::scanner_service::ScanCompleteness::Complete,
         })
     }
-
-    async fn scan_target(
-        &self,
-        target: &ScanTarget<'_>,
-        metadata: Option<&ArtifactMetadata>,
-        content: &Bytes,
-    ) -> Result<ScanOutput> {
-        let artifact = target.artifact;
-        if is_oci_image_artifact(artifact) {
-            if let Some(output) = self.scan_oci_layout_dir(artifact, target, content).await? {
-                return Ok(output);
-            }
-
-            // #1971: thread the in-hand manifest body for index→child resolution.
-            let image_ref =
-                Self::oci_registry_target(artifact, target, Some(content)).ok_or_else(|| {
-                    AppError::Internal(
-                        "Grype OCI scan: failed to reconstruct repository-qualified registry image ref \
-                     (is_applicable_for_target should have rejected this artifact)"
-                            .to_string(),
-                    )
-                })?;
-            // Repository-aware path: mint a pull token scoped to this repo.
-            return self
-                .scan_oci_registry_ref(artifact, image_ref, Some(target.repository_key))
-                .await;
-        }
-
-        self.scan(artifact, metadata, content).await
-    }
 }
 
 #[cfg(test)]
@@ -1801,6 +1829,7 @@ mod tests {
             db: None,
             storage: None,
             manifest_body: Some(helm_body),
+            scanner_args: &[],
         };
         assert!(
             !grype().is_applicable_for_target(&target),
@@ -1828,6 +1857,7 @@ mod tests {
             db: None,
             storage: None,
             manifest_body: Some(image_body),
+            scanner_args: &[],
         };
         assert!(grype().is_applicable_for_target(&target));
     }
@@ -2115,6 +2145,7 @@ mod tests {
             db: None,
             storage: None,
             manifest_body: None,
+            scanner_args: &[],
         };
 
         // The scan dispatch resolves a routable, repository-scoped ref.
@@ -2161,6 +2192,7 @@ mod tests {
             db: None,
             storage: None,
             manifest_body: None,
+            scanner_args: &[],
         };
         assert!(
             grype().is_applicable_for_target(&target),
@@ -2227,6 +2259,7 @@ mod tests {
             db: Some(&fx.pool),
             storage: Some(fx.state.storage.as_ref()),
             manifest_body: None,
+            scanner_args: &[],
         };
 
         let layout = scanner
@@ -2331,6 +2364,7 @@ mod tests {
             db: Some(&fx.pool),
             storage: Some(fx.state.storage.as_ref()),
             manifest_body: None,
+            scanner_args: &[],
         };
 
         let layout = scanner
@@ -2409,6 +2443,7 @@ mod tests {
             db: None,
             storage: Some(fx.state.storage.as_ref()),
             manifest_body: None,
+            scanner_args: &[],
         };
 
         let manifest = Bytes::from_static(TEST_IMAGE_MANIFEST);
@@ -2455,6 +2490,7 @@ mod tests {
             db: Some(&fx.pool),
             storage: None,
             manifest_body: None,
+            scanner_args: &[],
         };
         let manifest = Bytes::from_static(TEST_IMAGE_MANIFEST);
 
@@ -1,7 +1,8 @@
 //! Health monitoring and alert pipeline service.
 //!
 //! Monitors service health, tracks state transitions, fires webhook alerts,
-//! and manages alert suppression to prevent spam during extended outages.
+//! escalates them to PagerDuty / Opsgenie, and manages alert suppression to
+//! prevent spam during extended outages.
 
 use chrono::{DateTime, Utc};
 use reqwest::Client;
@@ -12,6 +13,13 @@ use utoipa::ToSchema;
 
 use crate::config::Config;
 use crate::error::{AppError, Result};
+use crate::services::analytics_service::{
+    AnalyticsService, CapacityLimit, RepositoryForecast, StorageForecast,
+};
+use crate::services::incident_escalation_service::IncidentEscalationService;
+use crate::services::notification_email_service::NotificationEmailService;
+use crate::services::presigned_canary::{self, CanaryOutcome};
+use crate::storage::StorageBackend;
 
 /// A health check result for a single service.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
@@ -83,6 +91,8 @@ pub struct HealthMonitorService {
     db: PgPool,
     config: MonitorConfig,
     http_client: Client,
+    email_notifier: Option<NotificationEmailService>,
+    incident_escalation: Option<IncidentEscalationService>,
 }
 
 /// Determine whether the periodic health monitor should probe
@@ -103,6 +113,49 @@ pub(crate) fn dependency_track_probe_url(config: &Config) -> Option<&str> {
     config.dependency_track_url.as_deref()
 }
 
+/// Forecasts that reach their capacity within `window_days`, soonest first,
+/// as alert message fragments.
+pub(crate) fn forecast_warnings(
+    global: Option<&StorageForecast>,
+    repositories: &[RepositoryForecast],
+    window_days: i64,
+) -> Vec<String> {
+    let due = |f: &StorageForecast| f.days_until_full.filter(|d| *d <= window_days);
+    let describe = |what: &str, f: &StorageForecast, days: i64| {
+        let limit = match f.capacity_limit {
+            Some(CapacityLimit::Quota) => "its quota",
+            _ => "storage capacity",
+        };
+        match (days, f.estimated_full_date) {
+            (0, _) | (_, None) => format!("{what} has reached {limit}"),
+            (_, Some(date)) => {
+                format!("{what} is forecast to reach {limit} in {days} days ({date})")
+            }
+        }
+    };
+
+    let mut warnings: Vec<(i64, String)> = Vec::new();
+    if let Some((f, days)) = global.and_then(|f| due(f).map(|d| (f, d))) {
+        warnings.push((days, describe("Total storage", f, days)));
+    }
+    for repo in repositories {
+        if let Some(days) = due(&repo.forecast) {
+            let what = format!("Repository {}", repo.repository_key);
+            warnings.push((days, describe(&what, &repo.forecast, days)));
+        }
+    }
+    warnings.sort_by_key(|(days, _)| *days);
+    warnings.into_iter().map(|(_, w)| w).collect()
+}
+
+/// Most forecast warnings spelled out in one alert message.
+const MAX_FORECAST_WARNINGS: usize = 5;
+
+/// Whether a check result is a transition back to healthy.
+fn is_recovery(entry: &ServiceHealthEntry) -> bool {
+    entry.status == "healthy" && entry.previous_status.as_deref() != Some("healthy")
+}
+
 impl HealthMonitorService {
     pub fn new(db: PgPool, config: MonitorConfig) -> Self {
         let http_client = crate::services::http_client::internal_service_client_builder()
@@ -114,7 +167,54 @@ impl HealthMonitorService {
             db,
             config,
             http_client,
+            email_notifier: None,
+            incident_escalation: None,
+        }
+    }
+
+    /// Email opted-in admins whenever an alert fires.
+    pub fn with_email_notifier(mut self, notifier: NotificationEmailService) -> Self {
+        self.email_notifier = Some(notifier);
+        self
+    }
+
+    /// Open and resolve PagerDuty / Opsgenie incidents as alerts fire.
+    pub fn with_incident_escalation(mut self, escalation: IncidentEscalationService) -> Self {
+        self.incident_escalation = Some(escalation);
+        self
+    }
+
+    /// Update alert state and forward any fired event to the email notifier
+    /// and incident escalation. Notification failures are logged and never
+    /// fail the health check.
+    async fn record_alert_state(&self, entry: &ServiceHealthEntry) -> Result<()> {
+        let event = self.update_alert_state(entry).await?;
+        if let (Some(event), Some(notifier)) = (&event, &self.email_notifier) {
+            if let Err(e) = notifier.notify_health_event(event).await {
+                tracing::warn!(
+                    service = %entry.service_name,
+                    "Failed to send health alert email: {}",
+                    e
+                );
+            }
         }
+        if let Some(escalation) = &self.incident_escalation {
+            let result = match &event {
+                Some(event) => escalation.handle_event(event).await,
+                // A recovery inside a suppression window fires no event, but
+                // an incident opened before the window must still resolve.
+                None if is_recovery(entry) => escalation.resolve_service(&entry.service_name).await,
+                None => Ok(()),
+            };
+            if let Err(e) = result {
+                tracing::warn!(
+                    service = %entry.service_name,
+                    "Failed to escalate health alert: {}",
+                    e
+                );
+            }
+        }
+        Ok(())
     }
 
     /// Check a single service's health and record the result.
@@ -176,7 +276,7 @@ impl HealthMonitorService {
         .map_err(|e| AppError::Database(e.to_string()))?;
 
         // Update alert state
-        self.update_alert_state(&entry).await?;
+        self.record_alert_state(&entry).await?;
 
         Ok(entry)
     }
@@ -229,21 +329,155 @@ impl HealthMonitorService {
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
 
-            self.update_alert_state(&entry).await?;
+            self.record_alert_state(&entry).await?;
         }
 
         Ok(entry)
     }
 
+    /// Report `storage-forecast` as degraded while total storage or any
+    /// repository quota is forecast to fill within
+    /// `STORAGE_FORECAST_ALERT_DAYS`, so the usual alert pipeline (webhooks,
+    /// email, incident escalation) warns before uploads start failing.
+    /// Returns `None` when the alert is disabled.
+    pub async fn check_storage_forecast(
+        &self,
+        app_config: &Config,
+    ) -> Result<Option<ServiceHealthEntry>> {
+        let window_days = app_config.storage_forecast_alert_days;
+        if window_days <= 0 {
+            return Ok(None);
+        }
+        let start = std::time::Instant::now();
+        let analytics = AnalyticsService::new(self.db.clone());
+        let today = Utc::now().date_naive();
+        let global = analytics
+            .get_storage_forecast(today, app_config.storage_capacity_bytes)
+            .await?;
+        let repositories = analytics.get_repository_forecasts(today).await?;
+        let warnings = forecast_warnings(global.as_ref(), &repositories, window_days);
+
+        let (status, message) = if warnings.is_empty() {
+            ("healthy".to_string(), None)
+        } else {
+            let mut message = warnings
+                .iter()
+                .take(MAX_FORECAST_WARNINGS)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ");
+            if warnings.len() > MAX_FORECAST_WARNINGS {
+                message.push_str(&format!(
+                    "; and {} more",
+                    warnings.len() - MAX_FORECAST_WARNINGS
+                ));
+            }
+            ("degraded".to_string(), Some(message))
+        };
+
+        let previous = sqlx::query_scalar::<_, String>(
+            r#"SELECT current_status FROM alert_state WHERE service_name = $1"#,
+        )
+        .bind("storage-forecast")
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let entry = ServiceHealthEntry {
+            service_name: "storage-forecast".to_string(),
+            status,
+            previous_status: previous,
+            message,
+            response_time_ms: Some(start.elapsed().as_millis() as i32),
+            checked_at: Utc::now(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO service_health_log (service_name, status, previous_status, message, response_time_ms)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&entry.service_name)
+        .bind(&entry.status)
+        .bind(&entry.previous_status)
+        .bind(&entry.message)
+        .bind(entry.response_time_ms)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        self.record_alert_state(&entry).await?;
+
+        Ok(Some(entry))
+    }
+
+    /// Probe presigned-redirect downloads on `storage` (see
+    /// `presigned_canary`) and record the outcome as `presigned-redirect`:
+    /// unhealthy when clients can no longer follow a redirect, degraded when
+    /// they still can but the provider clock has drifted. Returns `None` when
+    /// the backend does not serve redirect downloads.
+    pub async fn check_presigned_redirect(
+        &self,
+        storage: &dyn StorageBackend,
+    ) -> Result<Option<ServiceHealthEntry>> {
+        let start = std::time::Instant::now();
+        let Some(outcome) = presigned_canary::probe(storage, &self.http_client).await else {
+            return Ok(None);
+        };
+        let (status, message) = match outcome {
+            CanaryOutcome::Healthy => ("healthy".to_string(), None),
+            CanaryOutcome::Degraded(message) => ("degraded".to_string(), Some(message)),
+            CanaryOutcome::Failed(message) => ("unhealthy".to_string(), Some(message)),
+        };
+
+        let previous = sqlx::query_scalar::<_, String>(
+            r#"SELECT current_status FROM alert_state WHERE service_name = $1"#,
+        )
+        .bind(presigned_canary::SERVICE_NAME)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let entry = ServiceHealthEntry {
+            service_name: presigned_canary::SERVICE_NAME.to_string(),
+            status,
+            previous_status: previous,
+            message,
+            response_time_ms: Some(start.elapsed().as_millis() as i32),
+            checked_at: Utc::now(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO service_health_log (service_name, status, previous_status, message, response_time_ms)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&entry.service_name)
+        .bind(&entry.status)
+        .bind(&entry.previous_status)
+        .bind(&entry.message)
+        .bind(entry.response_time_ms)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        self.record_alert_state(&entry).await?;
+
+        Ok(Some(entry))
+    }
+
     /// Run health checks against all configured services.
     pub async fn check_all_services(&self, app_config: &Config) -> Result<Vec<ServiceHealthEntry>> {
         let mut results = Vec::new();
+        let scanner_endpoints = crate::config_reload::scanner_endpoints(app_config);
 
         // Database
         results.push(self.check_database().await?);
 
         // Trivy
-        if let Some(url) = &app_config.trivy_url {
+        if let Some(url) = &scanner_endpoints.trivy_url {
             results.push(self.check_service("trivy", url, "/healthz").await?);
         }
 
@@ -256,7 +490,7 @@ impl HealthMonitorService {
         }
 
         // OpenSCAP
-        if let Some(url) = &app_config.openscap_url {
+        if let Some(url) = &scanner_endpoints.openscap_url {
             results.push(self.check_service("openscap", url, "/health").await?);
         }
 
@@ -280,6 +514,11 @@ impl HealthMonitorService {
             );
         }
 
+        // Storage forecast
+        if let Some(entry) = self.check_storage_forecast(app_config).await? {
+            results.push(entry);
+        }
+
         Ok(results)
     }
 
@@ -326,7 +565,7 @@ impl HealthMonitorService {
         }
 
         // Service recovered
-        if is_healthy && entry.previous_status.as_deref() != Some("healthy") {
+        if is_recovery(entry) {
             let event = HealthEvent::ServiceRecovered {
                 service_name: entry.service_name.clone(),
                 downtime_started: alert_state.last_alert_sent_at,
@@ -710,6 +949,22 @@ mod tests {
     // Recovery event detection logic
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn test_is_recovery_transition() {
+        let entry = |status: &str, previous: Option<&str>| ServiceHealthEntry {
+            service_name: "database".to_string(),
+            status: status.to_string(),
+            previous_status: previous.map(str::to_string),
+            message: None,
+            response_time_ms: None,
+            checked_at: Utc::now(),
+        };
+        assert!(is_recovery(&entry("healthy", Some("unhealthy"))));
+        assert!(is_recovery(&entry("healthy", None)));
+        assert!(!is_recovery(&entry("healthy", Some("healthy"))));
+        assert!(!is_recovery(&entry("unhealthy", Some("unhealthy"))));
+    }
+
     #[test]
     fn test_recovery_detection_from_unhealthy() {
         let is_healthy = true;
@@ -856,4 +1111,62 @@ mod tests {
         assert!(!cfg.dependency_track_enabled);
         assert!(dependency_track_probe_url(&cfg).is_none());
     }
+
+    // -----------------------------------------------------------------------
+    // Storage forecast warnings
+    // -----------------------------------------------------------------------
+
+    fn forecast(days_until_full: Option<i64>, limit: CapacityLimit) -> StorageForecast {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        StorageForecast {
+            method: "linear".to_string(),
+            samples: 10,
+            current_bytes: 100,
+            daily_growth_bytes: 1.0,
+            projected_bytes_30d: 130,
+            projected_bytes_90d: 190,
+            projected_bytes_180d: 280,
+            capacity_bytes: Some(1_000),
+            capacity_limit: Some(limit),
+            days_until_full,
+            estimated_full_date: days_until_full.map(|d| today + chrono::Duration::days(d)),
+        }
+    }
+
+    fn repo(key: &str, days_until_full: Option<i64>) -> RepositoryForecast {
+        RepositoryForecast {
+            repository_id: uuid::Uuid::new_v4(),
+            repository_key: key.to_string(),
+            repository_name: key.to_string(),
+            forecast: forecast(days_until_full, CapacityLimit::Quota),
+        }
+    }
+
+    #[test]
+    fn test_forecast_warnings_within_window_soonest_first() {
+        let global = forecast(Some(20), CapacityLimit::Disk);
+        let repos = vec![
+            repo("far", Some(200)),
+            repo("soon", Some(5)),
+            repo("never", None),
+            repo("full", Some(0)),
+        ];
+        let warnings = forecast_warnings(Some(&global), &repos, 30);
+        assert_eq!(
+            warnings,
+            vec![
+                "Repository full has reached its quota".to_string(),
+                "Repository soon is forecast to reach its quota in 5 days (2026-10-06)".to_string(),
+                "Total storage is forecast to reach storage capacity in 20 days (2026-10-21)"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_forecast_warnings_empty_when_nothing_due() {
+        let global = forecast(Some(45), CapacityLimit::Disk);
+        assert!(forecast_warnings(Some(&global), &[repo("r", None)], 30).is_empty());
+        assert!(forecast_warnings(None, &[], 30).is_empty());
+    }
 }
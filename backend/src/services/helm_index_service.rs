@@ -0,0 +1,289 @@
+//! Incrementally maintained Helm chart indexes.
+//!
+//! `helm repo update` fetches `index.yaml` on every refresh, and a repository
+//! with thousands of chart versions used to re-read and re-parse every
+//! chart's stored `Chart.yaml` metadata for each fetch. [`HelmIndexService`]
+//! keeps the parsed entries of each hosted repository in process and brings
+//! them up to date per request from a cheap listing of the live chart
+//! artifacts (id and `updated_at` only): entries whose artifact was deleted
+//! are dropped, and only new or changed charts are loaded. An upload, delete
+//! or lifecycle cleanup on any replica therefore shows up in the next index
+//! served by every replica, without a full rebuild.
+//!
+//! Provenance rows are excluded: a `.prov` is stored as its own artifact under
+//! the same `name`/`version` as its chart (#2635), so without the filter every
+//! signed chart would render a duplicate `index.yaml` entry.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use sqlx::{PgPool, Row};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::formats::helm::ChartYaml;
+
+/// Upper bound on how long an entry whose artifact changed without a
+/// `updated_at` bump can be served; the repository's index is then rebuilt.
+const INDEX_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+static INDEXES: Lazy<Cache<Uuid, Arc<Mutex<RepoIndex>>>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(1_024)
+        .time_to_live(INDEX_CACHE_TTL)
+        .build()
+});
+
+/// One chart version of a repository index.
+#[derive(Debug, Clone)]
+pub struct IndexedChart {
+    pub chart: ChartYaml,
+    /// Storage path of the chart package; the download URL is derived from
+    /// its basename.
+    pub path: String,
+    pub created_at: DateTime<Utc>,
+    pub digest: String,
+}
+
+#[derive(Default)]
+struct RepoIndex {
+    charts: HashMap<Uuid, (DateTime<Utc>, Arc<IndexedChart>)>,
+}
+
+impl RepoIndex {
+    /// Drop entries not in `live` and return the ids that must be (re)loaded.
+    fn reconcile(&mut self, live: &HashMap<Uuid, DateTime<Utc>>) -> Vec<Uuid> {
+        self.charts.retain(|id, _| live.contains_key(id));
+        live.iter()
+            .filter(|(id, updated_at)| {
+                self.charts
+                    .get(id)
+                    .is_none_or(|(cached_at, _)| cached_at != *updated_at)
+            })
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Charts ordered by name, newest first within a name.
+    fn sorted(&self) -> Vec<Arc<IndexedChart>> {
+        let mut charts: Vec<Arc<IndexedChart>> =
+            self.charts.values().map(|(_, c)| c.clone()).collect();
+        charts.sort_by(|a, b| {
+            a.chart
+                .name
+                .cmp(&b.chart.name)
+                .then(b.created_at.cmp(&a.created_at))
+        });
+        charts
+    }
+}
+
+/// The index entry for a chart artifact. Prefers the `Chart.yaml` captured
+/// at upload; charts without one get a minimal entry from the artifact's
+/// name and version.
+pub fn chart_yaml_from_metadata(
+    name: &str,
+    version: &str,
+    metadata: Option<&serde_json::Value>,
+) -> ChartYaml {
+    if let Some(chart) = metadata
+        .and_then(|m| m.get("chart"))
+        .and_then(|c| serde_json::from_value::<ChartYaml>(c.clone()).ok())
+    {
+        return chart;
+    }
+    let field = |key: &str| {
+        metadata
+            .and_then(|m| m.get(key))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    ChartYaml {
+        api_version: "v2".to_string(),
+        name: name.to_string(),
+        version: version.to_string(),
+        kube_version: None,
+        description: field("description"),
+        chart_type: None,
+        keywords: None,
+        home: None,
+        sources: None,
+        dependencies: None,
+        maintainers: None,
+        icon: None,
+        app_version: field("appVersion"),
+        deprecated: None,
+        annotations: None,
+    }
+}
+
+pub struct HelmIndexService;
+
+impl HelmIndexService {
+    /// The charts of hosted repository `repo_id`, brought up to date with the
+    /// database.
+    pub async fn charts(db: &PgPool, repo_id: Uuid) -> Result<Vec<Arc<IndexedChart>>> {
+        let live: HashMap<Uuid, DateTime<Utc>> = sqlx::query(
+            r#"
+            SELECT id, updated_at FROM artifacts
+            WHERE repository_id = $1
+              AND is_deleted = false
+              AND version IS NOT NULL
+              AND path NOT LIKE '%.prov'
+            "#,
+        )
+        .bind(repo_id)
+        .fetch_all(db)
+        .await?
+        .iter()
+        .map(|row| (row.get("id"), row.get("updated_at")))
+        .collect();
+
+        let index = INDEXES
+            .get_with(repo_id, async {
+                Arc::new(Mutex::new(RepoIndex::default()))
+            })
+            .await;
+        let mut index = index.lock().await;
+        let stale = index.reconcile(&live);
+        if !stale.is_empty() {
+            tracing::debug!(
+                repository_id = %repo_id,
+                charts = stale.len(),
+                "Loading changed Helm index entries"
+            );
+            for (id, updated_at, chart) in Self::load(db, &stale).await? {
+                index.charts.insert(id, (updated_at, Arc::new(chart)));
+            }
+        }
+        Ok(index.sorted())
+    }
+
+    async fn load(db: &PgPool, ids: &[Uuid]) -> Result<Vec<(Uuid, DateTime<Utc>, IndexedChart)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.name, a.version, a.path, a.checksum_sha256,
+                   a.created_at, a.updated_at, am.metadata
+            FROM artifacts a
+            LEFT JOIN artifact_metadata am ON am.artifact_id = a.id
+            WHERE a.id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(db)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let name: String = row.get("name");
+                let version: String = row.get::<Option<String>, _>("version")?;
+                let metadata: Option<serde_json::Value> = row.get("metadata");
+                Some((
+                    row.get("id"),
+                    row.get("updated_at"),
+                    IndexedChart {
+                        chart: chart_yaml_from_metadata(&name, &version, metadata.as_ref()),
+                        path: row.get("path"),
+                        created_at: row.get("created_at"),
+                        digest: row.get("checksum_sha256"),
+                    },
+                ))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn indexed(name: &str, version: &str, created_secs: i64) -> IndexedChart {
+        IndexedChart {
+            chart: chart_yaml_from_metadata(name, version, None),
+            path: format!("{name}/{version}/{name}-{version}.tgz"),
+            created_at: DateTime::from_timestamp(created_secs, 0).unwrap(),
+            digest: "0".repeat(64),
+        }
+    }
+
+    #[test]
+    fn test_chart_yaml_from_metadata() {
+        let metadata = json!({
+            "chart": {"apiVersion": "v2", "name": "nginx", "version": "1.2.0", "appVersion": "1.25"}
+        });
+        let chart = chart_yaml_from_metadata("ignored", "0.0.0", Some(&metadata));
+        assert_eq!(chart.name, "nginx");
+        assert_eq!(chart.app_version.as_deref(), Some("1.25"));
+
+        let metadata = json!({"description": "A web server", "appVersion": "1.25"});
+        let chart = chart_yaml_from_metadata("nginx", "1.2.0", Some(&metadata));
+        assert_eq!(chart.version, "1.2.0");
+        assert_eq!(chart.description.as_deref(), Some("A web server"));
+        assert_eq!(chart.app_version.as_deref(), Some("1.25"));
+
+        let chart = chart_yaml_from_metadata("nginx", "1.2.0", None);
+        assert_eq!(chart.api_version, "v2");
+        assert!(chart.description.is_none());
+    }
+
+    #[test]
+    fn test_reconcile_loads_only_new_and_changed_charts() {
+        let (kept, changed, removed, added) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let t0 = DateTime::from_timestamp(1_000, 0).unwrap();
+        let t1 = DateTime::from_timestamp(2_000, 0).unwrap();
+
+        let mut index = RepoIndex::default();
+        for id in [kept, changed, removed] {
+            index
+                .charts
+                .insert(id, (t0, Arc::new(indexed("app", "1.0.0", 0))));
+        }
+        let live = HashMap::from([(kept, t0), (changed, t1), (added, t0)]);
+
+        let mut stale = index.reconcile(&live);
+        stale.sort();
+        let mut expected = vec![changed, added];
+        expected.sort();
+        assert_eq!(stale, expected);
+        assert!(!index.charts.contains_key(&removed));
+        assert!(index.charts.contains_key(&kept));
+    }
+
+    #[test]
+    fn test_sorted_by_name_then_newest_first() {
+        let mut index = RepoIndex::default();
+        let t = DateTime::from_timestamp(0, 0).unwrap();
+        for chart in [
+            indexed("redis", "1.0.0", 10),
+            indexed("nginx", "1.0.0", 10),
+            indexed("nginx", "1.1.0", 20),
+        ] {
+            index.charts.insert(Uuid::new_v4(), (t, Arc::new(chart)));
+        }
+        let order: Vec<(String, String)> = index
+            .sorted()
+            .iter()
+            .map(|c| (c.chart.name.clone(), c.chart.version.clone()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("nginx".to_string(), "1.1.0".to_string()),
+                ("nginx".to_string(), "1.0.0".to_string()),
+                ("redis".to_string(), "1.0.0".to_string()),
+            ]
+        );
+    }
+}
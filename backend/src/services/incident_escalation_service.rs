@@ -0,0 +1,1064 @@
+//! PagerDuty / Opsgenie incident escalation for health monitor alerts.
+//!
+//! [`HealthMonitorService`](crate::services::health_monitor_service::HealthMonitorService)
+//! hands every fired [`HealthEvent`] to [`IncidentEscalationService::handle_event`]:
+//! `ServiceDown` / `ServiceDegraded` trigger an incident on each enabled
+//! integration that covers the service, and `ServiceRecovered` resolves the
+//! incidents still open for it. Threshold, cooldown and suppression are
+//! decided upstream by the monitor's alert state, so a suppressed service
+//! never pages; the only exception is resolution, which always goes through
+//! so an incident opened before a suppression window does not stay open.
+//!
+//! Each service maps to a stable dedup key (PagerDuty `dedup_key`, Opsgenie
+//! `alias`), so repeat alerts after the cooldown update the open incident
+//! rather than opening another one.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::health_monitor_service::HealthEvent;
+use crate::services::webhook_secret_crypto;
+
+/// PagerDuty Events API v2 endpoint.
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// Value reported as the event source to both providers.
+const EVENT_SOURCE: &str = "artifact-keeper";
+
+/// Maximum number of services an integration can be limited to.
+const MAX_SERVICES: usize = 100;
+
+/// On-call provider an integration escalates to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentProvider {
+    Pagerduty,
+    Opsgenie,
+}
+
+impl IncidentProvider {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pagerduty => "pagerduty",
+            Self::Opsgenie => "opsgenie",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pagerduty" => Some(Self::Pagerduty),
+            "opsgenie" => Some(Self::Opsgenie),
+            _ => None,
+        }
+    }
+}
+
+/// Incident severity, in PagerDuty's vocabulary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentSeverity {
+    Critical,
+    Error,
+    Warning,
+    Info,
+}
+
+impl IncidentSeverity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Critical => "critical",
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "critical" => Some(Self::Critical),
+            "error" => Some(Self::Error),
+            "warning" => Some(Self::Warning),
+            "info" => Some(Self::Info),
+            _ => None,
+        }
+    }
+
+    /// Opsgenie alert priority for this severity.
+    pub fn opsgenie_priority(self) -> &'static str {
+        match self {
+            Self::Critical => "P1",
+            Self::Error => "P2",
+            Self::Warning => "P3",
+            Self::Info => "P5",
+        }
+    }
+}
+
+/// An incident integration as returned by the API. The credential is never
+/// exposed.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct IncidentIntegration {
+    pub id: Uuid,
+    pub name: String,
+    pub provider: String,
+    pub opsgenie_region: String,
+    /// Services this integration pages for; empty means all.
+    pub services: Vec<String>,
+    pub down_severity: String,
+    pub degraded_severity: String,
+    pub is_enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An incident opened by Artifact Keeper.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct IncidentEscalation {
+    pub integration_id: Uuid,
+    pub service_name: String,
+    pub dedup_key: String,
+    pub severity: String,
+    pub status: String,
+    pub opened_at: DateTime<Utc>,
+    pub last_triggered_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// Fields for a new incident integration.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateIncidentIntegration {
+    pub name: String,
+    pub provider: IncidentProvider,
+    /// PagerDuty Events v2 routing key, or Opsgenie API key.
+    pub credential: String,
+    /// Opsgenie instance region, `us` (default) or `eu`.
+    pub opsgenie_region: Option<String>,
+    #[serde(default)]
+    pub services: Vec<String>,
+    pub down_severity: Option<IncidentSeverity>,
+    pub degraded_severity: Option<IncidentSeverity>,
+}
+
+/// Partial update of an incident integration.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct UpdateIncidentIntegration {
+    pub name: Option<String>,
+    pub credential: Option<String>,
+    pub opsgenie_region: Option<String>,
+    pub services: Option<Vec<String>>,
+    pub down_severity: Option<IncidentSeverity>,
+    pub degraded_severity: Option<IncidentSeverity>,
+    pub is_enabled: Option<bool>,
+}
+
+/// What an alert asks the providers to do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IncidentAction {
+    Trigger {
+        service_name: String,
+        degraded: bool,
+        summary: String,
+        details: serde_json::Value,
+        timestamp: DateTime<Utc>,
+    },
+    Resolve {
+        service_name: String,
+    },
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct IntegrationTarget {
+    id: Uuid,
+    provider: String,
+    credential_encrypted: Vec<u8>,
+    opsgenie_region: String,
+    services: Vec<String>,
+    down_severity: String,
+    degraded_severity: String,
+}
+
+// ---------------------------------------------------------------------------
+// Pure helpers
+// ---------------------------------------------------------------------------
+
+/// Stable dedup key / alias for a monitored service.
+pub fn dedup_key(service_name: &str) -> String {
+    format!("{}/health/{}", EVENT_SOURCE, service_name)
+}
+
+/// Whether an integration limited to `services` covers `service_name`.
+pub fn covers_service(services: &[String], service_name: &str) -> bool {
+    services.is_empty() || services.iter().any(|s| s == service_name)
+}
+
+/// Translate a health event into an incident action.
+pub fn incident_action(event: &HealthEvent) -> IncidentAction {
+    match event {
+        HealthEvent::ServiceDown {
+            service_name,
+            message,
+            consecutive_failures,
+            timestamp,
+        } => IncidentAction::Trigger {
+            service_name: service_name.clone(),
+            degraded: false,
+            summary: format!("Artifact Keeper: {} is down", service_name),
+            details: serde_json::json!({
+                "message": message,
+                "consecutive_failures": consecutive_failures,
+            }),
+            timestamp: *timestamp,
+        },
+        HealthEvent::ServiceDegraded {
+            service_name,
+            response_time_ms,
+            threshold_ms,
+            timestamp,
+        } => IncidentAction::Trigger {
+            service_name: service_name.clone(),
+            degraded: true,
+            summary: format!(
+                "Artifact Keeper: {} is degraded ({} ms > {} ms)",
+                service_name, response_time_ms, threshold_ms
+            ),
+            details: serde_json::json!({
+                "response_time_ms": response_time_ms,
+                "threshold_ms": threshold_ms,
+            }),
+            timestamp: *timestamp,
+        },
+        HealthEvent::ServiceRecovered { service_name, .. } => IncidentAction::Resolve {
+            service_name: service_name.clone(),
+        },
+    }
+}
+
+/// PagerDuty Events v2 trigger payload.
+pub fn pagerduty_trigger_body(
+    routing_key: &str,
+    dedup: &str,
+    summary: &str,
+    severity: IncidentSeverity,
+    component: &str,
+    timestamp: DateTime<Utc>,
+    details: &serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "routing_key": routing_key,
+        "event_action": "trigger",
+        "dedup_key": dedup,
+        "payload": {
+            "summary": summary,
+            "source": EVENT_SOURCE,
+            "severity": severity.as_str(),
+            "component": component,
+            "timestamp": timestamp.to_rfc3339(),
+            "custom_details": details,
+        },
+    })
+}
+
+/// PagerDuty Events v2 resolve payload.
+pub fn pagerduty_resolve_body(routing_key: &str, dedup: &str) -> serde_json::Value {
+    serde_json::json!({
+        "routing_key": routing_key,
+        "event_action": "resolve",
+        "dedup_key": dedup,
+    })
+}
+
+/// Opsgenie create-alert payload.
+pub fn opsgenie_create_body(
+    alias: &str,
+    summary: &str,
+    severity: IncidentSeverity,
+    component: &str,
+    details: &serde_json::Value,
+) -> serde_json::Value {
+    // Opsgenie `details` is a flat string map.
+    let details: serde_json::Map<String, serde_json::Value> = details
+        .as_object()
+        .map(|m| {
+            m.iter()
+                .map(|(k, v)| {
+                    let s = v
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| v.to_string());
+                    (k.clone(), serde_json::Value::String(s))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    serde_json::json!({
+        "message": summary,
+        "alias": alias,
+        "priority": severity.opsgenie_priority(),
+        "source": EVENT_SOURCE,
+        "entity": component,
+        "tags": [EVENT_SOURCE, component],
+        "details": details,
+    })
+}
+
+/// Opsgenie API base URL for a region.
+pub fn opsgenie_base_url(region: &str) -> &'static str {
+    match region {
+        "eu" => "https://api.eu.opsgenie.com",
+        _ => "https://api.opsgenie.com",
+    }
+}
+
+fn validate_region(region: &str) -> Result<()> {
+    if region != "us" && region != "eu" {
+        return Err(AppError::Validation(
+            "opsgenie_region must be 'us' or 'eu'".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.len() > 255 {
+        return Err(AppError::Validation(
+            "Integration name must be 1-255 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_credential(credential: &str) -> Result<()> {
+    if credential.trim().is_empty() || credential.len() > 256 {
+        return Err(AppError::Validation(
+            "credential must be 1-256 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_services(services: &[String]) -> Result<()> {
+    if services.len() > MAX_SERVICES {
+        return Err(AppError::Validation(format!(
+            "An integration may list at most {} services",
+            MAX_SERVICES
+        )));
+    }
+    if services
+        .iter()
+        .any(|s| s.trim().is_empty() || s.len() > 255)
+    {
+        return Err(AppError::Validation(
+            "Service names must be 1-255 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a new integration.
+pub fn validate_new_integration(req: &CreateIncidentIntegration) -> Result<()> {
+    validate_name(&req.name)?;
+    validate_credential(&req.credential)?;
+    if let Some(region) = &req.opsgenie_region {
+        validate_region(region)?;
+    }
+    validate_services(&req.services)
+}
+
+/// Validate a partial update.
+pub fn validate_update(req: &UpdateIncidentIntegration) -> Result<()> {
+    if let Some(name) = &req.name {
+        validate_name(name)?;
+    }
+    if let Some(credential) = &req.credential {
+        validate_credential(credential)?;
+    }
+    if let Some(region) = &req.opsgenie_region {
+        validate_region(region)?;
+    }
+    if let Some(services) = &req.services {
+        validate_services(services)?;
+    }
+    Ok(())
+}
+
+fn map_unique_violation(e: sqlx::Error, name: &str) -> AppError {
+    if e.to_string().contains("duplicate key") {
+        AppError::Conflict(format!("Incident integration '{}' already exists", name))
+    } else {
+        AppError::Database(e.to_string())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Service
+// ---------------------------------------------------------------------------
+
+const SELECT_INTEGRATION: &str = r#"
+    SELECT id, name, provider, opsgenie_region, services, down_severity,
+           degraded_severity, is_enabled, created_by, created_at, updated_at
+    FROM incident_integrations
+"#;
+
+const SELECT_TARGET: &str = r#"
+    SELECT id, provider, credential_encrypted, opsgenie_region, services,
+           down_severity, degraded_severity
+    FROM incident_integrations
+"#;
+
+/// Manages incident integrations and forwards health alerts to them.
+#[derive(Clone)]
+pub struct IncidentEscalationService {
+    db: PgPool,
+    http: reqwest::Client,
+}
+
+impl IncidentEscalationService {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            db,
+            http: crate::services::http_client::webhook_client(),
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<IncidentIntegration>> {
+        sqlx::query_as(&format!("{} ORDER BY name", SELECT_INTEGRATION))
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<IncidentIntegration> {
+        sqlx::query_as(&format!("{} WHERE id = $1", SELECT_INTEGRATION))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .ok_or_else(|| AppError::NotFound("Incident integration not found".to_string()))
+    }
+
+    pub async fn create(
+        &self,
+        req: &CreateIncidentIntegration,
+        created_by: Uuid,
+    ) -> Result<IncidentIntegration> {
+        validate_new_integration(req)?;
+        let credential = encrypt(req.credential.trim())?;
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO incident_integrations
+                (name, provider, credential_encrypted, opsgenie_region, services,
+                 down_severity, degraded_severity, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+        )
+        .bind(req.name.trim())
+        .bind(req.provider.as_str())
+        .bind(credential)
+        .bind(req.opsgenie_region.as_deref().unwrap_or("us"))
+        .bind(&req.services)
+        .bind(
+            req.down_severity
+                .unwrap_or(IncidentSeverity::Critical)
+                .as_str(),
+        )
+        .bind(
+            req.degraded_severity
+                .unwrap_or(IncidentSeverity::Warning)
+                .as_str(),
+        )
+        .bind(created_by)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| map_unique_violation(e, &req.name))?;
+        self.get(id).await
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        req: &UpdateIncidentIntegration,
+    ) -> Result<IncidentIntegration> {
+        validate_update(req)?;
+        let credential = match &req.credential {
+            Some(c) => Some(encrypt(c.trim())?),
+            None => None,
+        };
+        let result = sqlx::query(
+            r#"
+            UPDATE incident_integrations SET
+                name = COALESCE($2, name),
+                credential_encrypted = COALESCE($3, credential_encrypted),
+                opsgenie_region = COALESCE($4, opsgenie_region),
+                services = COALESCE($5, services),
+                down_severity = COALESCE($6, down_severity),
+                degraded_severity = COALESCE($7, degraded_severity),
+                is_enabled = COALESCE($8, is_enabled),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(credential)
+        .bind(&req.opsgenie_region)
+        .bind(&req.services)
+        .bind(req.down_severity.map(IncidentSeverity::as_str))
+        .bind(req.degraded_severity.map(IncidentSeverity::as_str))
+        .bind(req.is_enabled)
+        .execute(&self.db)
+        .await
+        .map_err(|e| map_unique_violation(e, req.name.as_deref().unwrap_or_default()))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Incident integration not found".to_string(),
+            ));
+        }
+        self.get(id).await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM incident_integrations WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Incident integration not found".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Incidents opened by Artifact Keeper, newest first.
+    pub async fn list_incidents(
+        &self,
+        open_only: bool,
+        limit: i64,
+    ) -> Result<Vec<IncidentEscalation>> {
+        sqlx::query_as(
+            r#"
+            SELECT integration_id, service_name, dedup_key, severity, status,
+                   opened_at, last_triggered_at, resolved_at, last_error
+            FROM incident_escalations
+            WHERE (NOT $1 OR status = 'open')
+            ORDER BY last_triggered_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(open_only)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Forward a fired health event to every covering integration. Delivery
+    /// failures are logged and recorded on the incident row; they never fail
+    /// the health check.
+    pub async fn handle_event(&self, event: &HealthEvent) -> Result<()> {
+        match incident_action(event) {
+            IncidentAction::Trigger {
+                service_name,
+                degraded,
+                summary,
+                details,
+                timestamp,
+            } => {
+                let targets: Vec<IntegrationTarget> =
+                    sqlx::query_as(&format!("{} WHERE is_enabled = true", SELECT_TARGET))
+                        .fetch_all(&self.db)
+                        .await
+                        .map_err(|e| AppError::Database(e.to_string()))?;
+                for target in targets
+                    .iter()
+                    .filter(|t| covers_service(&t.services, &service_name))
+                {
+                    let severity_str = if degraded {
+                        &target.degraded_severity
+                    } else {
+                        &target.down_severity
+                    };
+                    let severity =
+                        IncidentSeverity::parse(severity_str).unwrap_or(IncidentSeverity::Critical);
+                    let dedup = dedup_key(&service_name);
+                    let outcome = self
+                        .trigger(
+                            target,
+                            &dedup,
+                            &summary,
+                            severity,
+                            &service_name,
+                            timestamp,
+                            &details,
+                        )
+                        .await;
+                    self.record_trigger(target.id, &service_name, &dedup, severity, outcome)
+                        .await?;
+                }
+                Ok(())
+            }
+            IncidentAction::Resolve { service_name } => self.resolve_service(&service_name).await,
+        }
+    }
+
+    /// Resolve every incident still open for a service, including on
+    /// disabled integrations, so nothing is left paging after recovery.
+    pub async fn resolve_service(&self, service_name: &str) -> Result<()> {
+        let open: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT integration_id, dedup_key FROM incident_escalations
+            WHERE service_name = $1 AND status = 'open'
+            "#,
+        )
+        .bind(service_name)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        for (integration_id, dedup) in open {
+            let target: Option<IntegrationTarget> =
+                sqlx::query_as(&format!("{} WHERE id = $1", SELECT_TARGET))
+                    .bind(integration_id)
+                    .fetch_optional(&self.db)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+            let Some(target) = target else { continue };
+            match self.resolve(&target, &dedup).await {
+                Ok(()) => {
+                    sqlx::query(
+                        r#"
+                        UPDATE incident_escalations
+                        SET status = 'resolved', resolved_at = NOW(), last_error = NULL
+                        WHERE integration_id = $1 AND service_name = $2
+                        "#,
+                    )
+                    .bind(integration_id)
+                    .bind(service_name)
+                    .execute(&self.db)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                    tracing::info!(
+                        integration_id = %integration_id,
+                        service = %service_name,
+                        "Resolved incident"
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        integration_id = %integration_id,
+                        service = %service_name,
+                        "Failed to resolve incident: {}",
+                        e
+                    );
+                    self.record_error(integration_id, service_name, &e.to_string())
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Trigger and immediately resolve a test incident.
+    pub async fn send_test(&self, id: Uuid) -> Result<()> {
+        let target: IntegrationTarget = sqlx::query_as(&format!("{} WHERE id = $1", SELECT_TARGET))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .ok_or_else(|| AppError::NotFound("Incident integration not found".to_string()))?;
+        let dedup = format!("{}/test/{}", EVENT_SOURCE, id);
+        self.trigger(
+            &target,
+            &dedup,
+            "Artifact Keeper: test incident (resolves automatically)",
+            IncidentSeverity::Info,
+            "test",
+            Utc::now(),
+            &serde_json::json!({}),
+        )
+        .await?;
+        self.resolve(&target, &dedup).await
+    }
+
+    async fn record_trigger(
+        &self,
+        integration_id: Uuid,
+        service_name: &str,
+        dedup: &str,
+        severity: IncidentSeverity,
+        outcome: Result<()>,
+    ) -> Result<()> {
+        match outcome {
+            Ok(()) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO incident_escalations
+                        (integration_id, service_name, dedup_key, severity, status)
+                    VALUES ($1, $2, $3, $4, 'open')
+                    ON CONFLICT (integration_id, service_name) DO UPDATE SET
+                        dedup_key = EXCLUDED.dedup_key,
+                        severity = EXCLUDED.severity,
+                        opened_at = CASE
+                            WHEN incident_escalations.status = 'resolved' THEN NOW()
+                            ELSE incident_escalations.opened_at
+                        END,
+                        status = 'open',
+                        last_triggered_at = NOW(),
+                        resolved_at = NULL,
+                        last_error = NULL
+                    "#,
+                )
+                .bind(integration_id)
+                .bind(service_name)
+                .bind(dedup)
+                .bind(severity.as_str())
+                .execute(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+                tracing::info!(
+                    integration_id = %integration_id,
+                    service = %service_name,
+                    severity = severity.as_str(),
+                    "Escalated health alert"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(
+                    integration_id = %integration_id,
+                    service = %service_name,
+                    "Failed to escalate health alert: {}",
+                    e
+                );
+                self.record_error(integration_id, service_name, &e.to_string())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn record_error(
+        &self,
+        integration_id: Uuid,
+        service_name: &str,
+        error: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE incident_escalations SET last_error = $3
+            WHERE integration_id = $1 AND service_name = $2
+            "#,
+        )
+        .bind(integration_id)
+        .bind(service_name)
+        .bind(error)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn trigger(
+        &self,
+        target: &IntegrationTarget,
+        dedup: &str,
+        summary: &str,
+        severity: IncidentSeverity,
+        component: &str,
+        timestamp: DateTime<Utc>,
+        details: &serde_json::Value,
+    ) -> Result<()> {
+        let credential = decrypt(&target.credential_encrypted)?;
+        match IncidentProvider::parse(&target.provider) {
+            Some(IncidentProvider::Pagerduty) => {
+                let body = pagerduty_trigger_body(
+                    &credential,
+                    dedup,
+                    summary,
+                    severity,
+                    component,
+                    timestamp,
+                    details,
+                );
+                self.send(PAGERDUTY_EVENTS_URL, None, &body).await
+            }
+            Some(IncidentProvider::Opsgenie) => {
+                let url = format!("{}/v2/alerts", opsgenie_base_url(&target.opsgenie_region));
+                let body = opsgenie_create_body(dedup, summary, severity, component, details);
+                self.send(&url, Some(&credential), &body).await
+            }
+            None => Err(AppError::Internal(format!(
+                "unknown incident provider '{}'",
+                target.provider
+            ))),
+        }
+    }
+
+    async fn resolve(&self, target: &IntegrationTarget, dedup: &str) -> Result<()> {
+        let credential = decrypt(&target.credential_encrypted)?;
+        match IncidentProvider::parse(&target.provider) {
+            Some(IncidentProvider::Pagerduty) => {
+                self.send(
+                    PAGERDUTY_EVENTS_URL,
+                    None,
+                    &pagerduty_resolve_body(&credential, dedup),
+                )
+                .await
+            }
+            Some(IncidentProvider::Opsgenie) => {
+                let url = format!(
+                    "{}/v2/alerts/{}/close?identifierType=alias",
+                    opsgenie_base_url(&target.opsgenie_region),
+                    urlencoding::encode(dedup)
+                );
+                let body = serde_json::json!({
+                    "source": EVENT_SOURCE,
+                    "note": "Service recovered",
+                });
+                self.send(&url, Some(&credential), &body).await
+            }
+            None => Err(AppError::Internal(format!(
+                "unknown incident provider '{}'",
+                target.provider
+            ))),
+        }
+    }
+
+    async fn send(
+        &self,
+        url: &str,
+        opsgenie_key: Option<&str>,
+        body: &serde_json::Value,
+    ) -> Result<()> {
+        let mut req = self
+            .http
+            .post(url)
+            .timeout(Duration::from_secs(10))
+            .json(body);
+        if let Some(key) = opsgenie_key {
+            req = req.header("Authorization", format!("GenieKey {}", key));
+        }
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("incident delivery failed: {}", e)))?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(AppError::Internal(format!(
+                "incident provider returned HTTP {}: {}",
+                status,
+                text.chars().take(200).collect::<String>()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn encrypt(value: &str) -> Result<Vec<u8>> {
+    webhook_secret_crypto::encrypt_secret(value).map_err(|e| {
+        tracing::error!("incident credential encryption failed: {}", e);
+        AppError::Internal("incident credential encryption failed".to_string())
+    })
+}
+
+fn decrypt(ciphertext: &[u8]) -> Result<String> {
+    webhook_secret_crypto::decrypt_secret(ciphertext)
+        .map_err(|e| AppError::Internal(format!("failed to decrypt incident credential: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_req() -> CreateIncidentIntegration {
+        CreateIncidentIntegration {
+            name: "on-call".to_string(),
+            provider: IncidentProvider::Pagerduty,
+            credential: "R0UTINGKEY".to_string(),
+            opsgenie_region: None,
+            services: vec![],
+            down_severity: None,
+            degraded_severity: None,
+        }
+    }
+
+    #[test]
+    fn test_enum_round_trips() {
+        for p in [IncidentProvider::Pagerduty, IncidentProvider::Opsgenie] {
+            assert_eq!(IncidentProvider::parse(p.as_str()), Some(p));
+        }
+        for s in [
+            IncidentSeverity::Critical,
+            IncidentSeverity::Error,
+            IncidentSeverity::Warning,
+            IncidentSeverity::Info,
+        ] {
+            assert_eq!(IncidentSeverity::parse(s.as_str()), Some(s));
+        }
+        assert_eq!(IncidentProvider::parse("victorops"), None);
+    }
+
+    #[test]
+    fn test_opsgenie_priority_mapping() {
+        assert_eq!(IncidentSeverity::Critical.opsgenie_priority(), "P1");
+        assert_eq!(IncidentSeverity::Error.opsgenie_priority(), "P2");
+        assert_eq!(IncidentSeverity::Warning.opsgenie_priority(), "P3");
+        assert_eq!(IncidentSeverity::Info.opsgenie_priority(), "P5");
+    }
+
+    #[test]
+    fn test_dedup_key_is_stable_per_service() {
+        assert_eq!(dedup_key("trivy"), dedup_key("trivy"));
+        assert_ne!(dedup_key("trivy"), dedup_key("database"));
+        assert_eq!(dedup_key("database"), "artifact-keeper/health/database");
+    }
+
+    #[test]
+    fn test_covers_service() {
+        assert!(covers_service(&[], "database"));
+        let only = vec!["database".to_string()];
+        assert!(covers_service(&only, "database"));
+        assert!(!covers_service(&only, "trivy"));
+    }
+
+    #[test]
+    fn test_incident_action_mapping() {
+        let now = Utc::now();
+        let down = HealthEvent::ServiceDown {
+            service_name: "database".to_string(),
+            message: "connection refused".to_string(),
+            consecutive_failures: 3,
+            timestamp: now,
+        };
+        match incident_action(&down) {
+            IncidentAction::Trigger {
+                service_name,
+                degraded,
+                summary,
+                details,
+                ..
+            } => {
+                assert_eq!(service_name, "database");
+                assert!(!degraded);
+                assert!(summary.contains("database is down"));
+                assert_eq!(details["consecutive_failures"], 3);
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+
+        let degraded = HealthEvent::ServiceDegraded {
+            service_name: "trivy".to_string(),
+            response_time_ms: 900,
+            threshold_ms: 500,
+            timestamp: now,
+        };
+        assert!(matches!(
+            incident_action(&degraded),
+            IncidentAction::Trigger { degraded: true, .. }
+        ));
+
+        let recovered = HealthEvent::ServiceRecovered {
+            service_name: "database".to_string(),
+            downtime_started: None,
+            timestamp: now,
+        };
+        assert_eq!(
+            incident_action(&recovered),
+            IncidentAction::Resolve {
+                service_name: "database".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_pagerduty_bodies() {
+        let now = Utc::now();
+        let body = pagerduty_trigger_body(
+            "key",
+            "artifact-keeper/health/database",
+            "down",
+            IncidentSeverity::Error,
+            "database",
+            now,
+            &serde_json::json!({"message": "x"}),
+        );
+        assert_eq!(body["event_action"], "trigger");
+        assert_eq!(body["dedup_key"], "artifact-keeper/health/database");
+        assert_eq!(body["payload"]["severity"], "error");
+        assert_eq!(body["payload"]["component"], "database");
+        assert_eq!(body["payload"]["custom_details"]["message"], "x");
+
+        let resolve = pagerduty_resolve_body("key", "artifact-keeper/health/database");
+        assert_eq!(resolve["event_action"], "resolve");
+        assert_eq!(resolve["dedup_key"], "artifact-keeper/health/database");
+        assert!(resolve.get("payload").is_none());
+    }
+
+    #[test]
+    fn test_opsgenie_body_flattens_details() {
+        let body = opsgenie_create_body(
+            "artifact-keeper/health/database",
+            "down",
+            IncidentSeverity::Critical,
+            "database",
+            &serde_json::json!({"consecutive_failures": 3, "message": "refused"}),
+        );
+        assert_eq!(body["alias"], "artifact-keeper/health/database");
+        assert_eq!(body["priority"], "P1");
+        assert_eq!(body["details"]["consecutive_failures"], "3");
+        assert_eq!(body["details"]["message"], "refused");
+    }
+
+    #[test]
+    fn test_opsgenie_region_base_url() {
+        assert_eq!(opsgenie_base_url("us"), "https://api.opsgenie.com");
+        assert_eq!(opsgenie_base_url("eu"), "https://api.eu.opsgenie.com");
+    }
+
+    #[test]
+    fn test_validate_new_integration() {
+        assert!(validate_new_integration(&create_req()).is_ok());
+
+        let mut req = create_req();
+        req.credential = " ".to_string();
+        assert!(validate_new_integration(&req).is_err());
+
+        let mut req = create_req();
+        req.opsgenie_region = Some("apac".to_string());
+        assert!(validate_new_integration(&req).is_err());
+
+        let mut req = create_req();
+        req.services = vec!["".to_string()];
+        assert!(validate_new_integration(&req).is_err());
+
+        let mut req = create_req();
+        req.name = String::new();
+        assert!(validate_new_integration(&req).is_err());
+    }
+
+    #[test]
+    fn test_validate_update() {
+        assert!(validate_update(&UpdateIncidentIntegration::default()).is_ok());
+        let req = UpdateIncidentIntegration {
+            opsgenie_region: Some("eu".to_string()),
+            services: Some(vec!["database".to_string()]),
+            ..Default::default()
+        };
+        assert!(validate_update(&req).is_ok());
+        let bad = UpdateIncidentIntegration {
+            services: Some(vec!["x".repeat(256)]),
+            ..Default::default()
+        };
+        assert!(validate_update(&bad).is_err());
+    }
+}
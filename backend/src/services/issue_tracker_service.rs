@@ -0,0 +1,1637 @@
+//! Jira issue tracker integration for policy-violating scan findings.
+//!
+//! [`IssueTrackerService::sync_integration`] reconciles an integration's
+//! Jira issues with the current findings:
+//!
+//! 1. Open issues resolved in Jira are pulled back first. A resolution in
+//!    the integration's `acknowledge_resolutions` acknowledges the group's
+//!    findings in Artifact Keeper; any other resolution just marks the link
+//!    closed.
+//! 2. Every group (artifact, or repository + component) with violating,
+//!    non-acknowledged findings gets an issue if it does not have an open
+//!    one. A group whose issue was closed is re-filed only when it has
+//!    findings the closed issue did not cover.
+//! 3. Open issues whose findings are all gone are closed; those whose
+//!    remaining findings are all acknowledged are transitioned with the
+//!    acknowledge transition.
+//!
+//! A finding violates policy when an enabled scan policy applying to its
+//! repository (repository, project or instance layer, after overrides) has a
+//! `max_severity` at or below the finding's severity, matching [`PolicyService::evaluate_artifact`](crate::services::policy_service::PolicyService::evaluate_artifact).
+//! Only findings from each artifact's latest completed scan per scan type
+//! are considered.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::sbom_service::severity_rank;
+use crate::services::webhook_secret_crypto;
+
+/// Upper bound on findings loaded per integration per sync.
+const MAX_FINDINGS_PER_SYNC: i64 = 5000;
+
+/// Upper bound on project mapping entries.
+const MAX_PROJECT_MAPPINGS: usize = 50;
+
+/// Label always applied to created issues, used to find them in Jira.
+const DEFAULT_LABEL: &str = "artifact-keeper";
+
+/// How findings are grouped into issues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueGrouping {
+    /// One issue per artifact.
+    Artifact,
+    /// One issue per (repository, affected component).
+    Component,
+}
+
+impl IssueGrouping {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Artifact => "artifact",
+            Self::Component => "component",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "artifact" => Some(Self::Artifact),
+            "component" => Some(Self::Component),
+            _ => None,
+        }
+    }
+}
+
+/// Routes a group to a Jira project / issue type. The first entry whose
+/// repository and minimum severity match wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProjectMapping {
+    /// Repository this mapping applies to; omit to match every repository.
+    pub repository_id: Option<Uuid>,
+    /// Lowest group severity this mapping applies to (critical, high,
+    /// medium, low); omit to match every severity.
+    pub min_severity: Option<String>,
+    pub project_key: String,
+    /// Issue type; omit to use the integration default.
+    pub issue_type: Option<String>,
+}
+
+/// An issue tracker integration as returned by the API. The API token is
+/// never exposed.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct IssueTrackerIntegration {
+    pub id: Uuid,
+    pub name: String,
+    pub provider: String,
+    pub base_url: String,
+    pub auth_email: Option<String>,
+    pub project_key: String,
+    pub issue_type: String,
+    pub grouping: String,
+    pub repository_id: Option<Uuid>,
+    pub project_mappings: Vec<ProjectMapping>,
+    pub labels: Vec<String>,
+    pub backlink_base_url: Option<String>,
+    pub backlink_field_id: Option<String>,
+    pub close_transition: String,
+    pub acknowledge_transition: Option<String>,
+    pub acknowledge_resolutions: Vec<String>,
+    pub is_enabled: bool,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_sync_error: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct IntegrationRow {
+    id: Uuid,
+    name: String,
+    provider: String,
+    base_url: String,
+    auth_email: Option<String>,
+    api_token_encrypted: Vec<u8>,
+    project_key: String,
+    issue_type: String,
+    grouping: String,
+    repository_id: Option<Uuid>,
+    project_mappings: serde_json::Value,
+    labels: Vec<String>,
+    backlink_base_url: Option<String>,
+    backlink_field_id: Option<String>,
+    close_transition: String,
+    acknowledge_transition: Option<String>,
+    acknowledge_resolutions: Vec<String>,
+    is_enabled: bool,
+    last_synced_at: Option<DateTime<Utc>>,
+    last_sync_error: Option<String>,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl IntegrationRow {
+    fn mappings(&self) -> Vec<ProjectMapping> {
+        serde_json::from_value(self.project_mappings.clone()).unwrap_or_default()
+    }
+
+    fn grouping(&self) -> IssueGrouping {
+        IssueGrouping::parse(&self.grouping).unwrap_or(IssueGrouping::Artifact)
+    }
+
+    fn into_response(self) -> IssueTrackerIntegration {
+        let project_mappings = self.mappings();
+        IssueTrackerIntegration {
+            id: self.id,
+            name: self.name,
+            provider: self.provider,
+            base_url: self.base_url,
+            auth_email: self.auth_email,
+            project_key: self.project_key,
+            issue_type: self.issue_type,
+            grouping: self.grouping,
+            repository_id: self.repository_id,
+            project_mappings,
+            labels: self.labels,
+            backlink_base_url: self.backlink_base_url,
+            backlink_field_id: self.backlink_field_id,
+            close_transition: self.close_transition,
+            acknowledge_transition: self.acknowledge_transition,
+            acknowledge_resolutions: self.acknowledge_resolutions,
+            is_enabled: self.is_enabled,
+            last_synced_at: self.last_synced_at,
+            last_sync_error: self.last_sync_error,
+            created_by: self.created_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// Fields for a new integration.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateIssueTrackerIntegration {
+    pub name: String,
+    /// Jira base URL, e.g. `https://example.atlassian.net`.
+    pub base_url: String,
+    /// Jira Cloud account email. Omit to authenticate with a Data Center
+    /// personal access token.
+    pub auth_email: Option<String>,
+    pub api_token: String,
+    pub project_key: String,
+    pub issue_type: Option<String>,
+    pub grouping: Option<IssueGrouping>,
+    /// Only sync findings from this repository.
+    pub repository_id: Option<Uuid>,
+    #[serde(default)]
+    pub project_mappings: Vec<ProjectMapping>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Artifact Keeper UI base URL used for backlinks.
+    pub backlink_base_url: Option<String>,
+    /// Jira custom field (e.g. `customfield_10050`) that receives the
+    /// backlink URL.
+    pub backlink_field_id: Option<String>,
+    pub close_transition: Option<String>,
+    pub acknowledge_transition: Option<String>,
+    #[serde(default)]
+    pub acknowledge_resolutions: Vec<String>,
+}
+
+/// Partial update of an integration.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct UpdateIssueTrackerIntegration {
+    pub name: Option<String>,
+    pub base_url: Option<String>,
+    pub auth_email: Option<String>,
+    pub api_token: Option<String>,
+    pub project_key: Option<String>,
+    pub issue_type: Option<String>,
+    pub project_mappings: Option<Vec<ProjectMapping>>,
+    pub labels: Option<Vec<String>>,
+    pub backlink_base_url: Option<String>,
+    pub backlink_field_id: Option<String>,
+    pub close_transition: Option<String>,
+    pub acknowledge_transition: Option<String>,
+    pub acknowledge_resolutions: Option<Vec<String>>,
+    pub is_enabled: Option<bool>,
+}
+
+/// A Jira issue filed for a finding group.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct IssueTrackerLink {
+    pub id: Uuid,
+    pub integration_id: Uuid,
+    pub group_key: String,
+    pub repository_id: Option<Uuid>,
+    pub artifact_id: Option<Uuid>,
+    pub component: Option<String>,
+    pub issue_key: String,
+    pub issue_url: String,
+    pub status: String,
+    pub fingerprints: Vec<String>,
+    pub highest_severity: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of one sync run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct IssueSyncSummary {
+    pub issues_created: u32,
+    pub issues_closed: u32,
+    pub issues_acknowledged: u32,
+    /// Findings acknowledged in Artifact Keeper because their issue was
+    /// resolved as accepted risk in Jira.
+    pub findings_acknowledged: u32,
+    pub errors: u32,
+}
+
+/// A policy-violating finding from an artifact's latest scan.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ViolatingFinding {
+    pub id: Uuid,
+    pub artifact_id: Uuid,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub artifact_name: String,
+    pub artifact_version: Option<String>,
+    pub severity: String,
+    pub title: String,
+    pub cve_id: Option<String>,
+    pub affected_component: Option<String>,
+    pub affected_version: Option<String>,
+    pub fixed_version: Option<String>,
+    pub is_acknowledged: bool,
+}
+
+/// Findings that share an issue.
+#[derive(Debug, Clone)]
+pub struct FindingGroup {
+    pub key: String,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub artifact_id: Option<Uuid>,
+    pub artifact_label: String,
+    pub component: Option<String>,
+    /// Non-acknowledged findings.
+    pub open: Vec<ViolatingFinding>,
+    pub acknowledged: Vec<ViolatingFinding>,
+}
+
+// ---------------------------------------------------------------------------
+// Pure helpers
+// ---------------------------------------------------------------------------
+
+/// Identity of a finding across rescans: CVE (or title) plus component.
+pub fn fingerprint(f: &ViolatingFinding) -> String {
+    format!(
+        "{}|{}",
+        f.cve_id.as_deref().unwrap_or(&f.title),
+        f.affected_component.as_deref().unwrap_or("")
+    )
+}
+
+fn artifact_label(f: &ViolatingFinding) -> String {
+    match &f.artifact_version {
+        Some(v) if !v.is_empty() => format!("{} {}", f.artifact_name, v),
+        _ => f.artifact_name.clone(),
+    }
+}
+
+/// Group key for a finding.
+pub fn group_key(grouping: IssueGrouping, f: &ViolatingFinding) -> String {
+    match grouping {
+        IssueGrouping::Artifact => format!("artifact:{}", f.artifact_id),
+        IssueGrouping::Component => format!(
+            "component:{}:{}",
+            f.repository_id,
+            f.affected_component.as_deref().unwrap_or(&f.title)
+        ),
+    }
+}
+
+/// Group findings into issues, ordered by group key.
+pub fn group_findings(
+    grouping: IssueGrouping,
+    findings: Vec<ViolatingFinding>,
+) -> Vec<FindingGroup> {
+    let mut groups: BTreeMap<String, FindingGroup> = BTreeMap::new();
+    for f in findings {
+        let key = group_key(grouping, &f);
+        let group = groups.entry(key.clone()).or_insert_with(|| FindingGroup {
+            key,
+            repository_id: f.repository_id,
+            repository_key: f.repository_key.clone(),
+            artifact_id: match grouping {
+                IssueGrouping::Artifact => Some(f.artifact_id),
+                IssueGrouping::Component => None,
+            },
+            artifact_label: artifact_label(&f),
+            component: match grouping {
+                IssueGrouping::Artifact => None,
+                IssueGrouping::Component => Some(
+                    f.affected_component
+                        .clone()
+                        .unwrap_or_else(|| f.title.clone()),
+                ),
+            },
+            open: Vec::new(),
+            acknowledged: Vec::new(),
+        });
+        if f.is_acknowledged {
+            group.acknowledged.push(f);
+        } else {
+            group.open.push(f);
+        }
+    }
+    groups.into_values().collect()
+}
+
+/// Highest severity among findings, `low` when empty.
+pub fn highest_severity(findings: &[ViolatingFinding]) -> String {
+    findings
+        .iter()
+        .max_by_key(|f| severity_rank(&f.severity))
+        .map(|f| f.severity.to_ascii_lowercase())
+        .unwrap_or_else(|| "low".to_string())
+}
+
+/// Sorted, deduplicated fingerprints of findings.
+pub fn fingerprints(findings: &[ViolatingFinding]) -> Vec<String> {
+    let mut fps: Vec<String> = findings.iter().map(fingerprint).collect();
+    fps.sort();
+    fps.dedup();
+    fps
+}
+
+/// Whether a group whose issue is closed should get a new issue: only when
+/// it has findings the closed issue did not cover.
+pub fn should_refile(covered: &[String], current: &[String]) -> bool {
+    let covered: HashSet<&str> = covered.iter().map(String::as_str).collect();
+    current.iter().any(|fp| !covered.contains(fp.as_str()))
+}
+
+/// Pick the project and issue type for a group.
+pub fn select_project(
+    mappings: &[ProjectMapping],
+    default_project: &str,
+    default_issue_type: &str,
+    repository_id: Uuid,
+    severity: &str,
+) -> (String, String) {
+    for m in mappings {
+        let repo_ok = m.repository_id.is_none_or(|r| r == repository_id);
+        let sev_ok = m
+            .min_severity
+            .as_deref()
+            .is_none_or(|min| severity_rank(severity) >= severity_rank(min));
+        if repo_ok && sev_ok {
+            return (
+                m.project_key.clone(),
+                m.issue_type
+                    .clone()
+                    .unwrap_or_else(|| default_issue_type.to_string()),
+            );
+        }
+    }
+    (default_project.to_string(), default_issue_type.to_string())
+}
+
+/// Link back to Artifact Keeper for a group.
+pub fn backlink_url(base: &str, group: &FindingGroup) -> String {
+    let base = base.trim_end_matches('/');
+    match group.artifact_id {
+        Some(id) => format!("{}/artifacts/{}", base, id),
+        None => format!("{}/repositories/{}", base, group.repository_key),
+    }
+}
+
+/// Jira issue summary for a group (Jira caps summaries at 255 characters).
+pub fn issue_summary(group: &FindingGroup) -> String {
+    let subject = match &group.component {
+        Some(component) => format!("{} in {}", component, group.repository_key),
+        None => format!("{} in {}", group.artifact_label, group.repository_key),
+    };
+    let summary = format!(
+        "[{}] {} policy-violating finding(s): {}",
+        highest_severity(&group.open).to_ascii_uppercase(),
+        group.open.len(),
+        subject
+    );
+    summary.chars().take(255).collect()
+}
+
+/// Jira issue description (wiki markup) for a group.
+pub fn issue_description(group: &FindingGroup, backlink: Option<&str>) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "Artifact Keeper found findings that violate a scan policy in repository *{}*.\n\n",
+        group.repository_key
+    ));
+    out.push_str("||Severity||Finding||Component||Installed||Fixed in||\n");
+    let mut findings: Vec<&ViolatingFinding> = group.open.iter().collect();
+    findings.sort_by_key(|f| std::cmp::Reverse(severity_rank(&f.severity)));
+    for f in findings {
+        out.push_str(&format!(
+            "|{}|{}|{}|{}|{}|\n",
+            f.severity,
+            f.cve_id.as_deref().unwrap_or(&f.title).replace('|', "/"),
+            f.affected_component.as_deref().unwrap_or("-"),
+            f.affected_version.as_deref().unwrap_or("-"),
+            f.fixed_version.as_deref().unwrap_or("-"),
+        ));
+    }
+    if let Some(url) = backlink {
+        out.push_str(&format!("\n[View in Artifact Keeper|{}]\n", url));
+    }
+    out.push_str(
+        "\nThis issue is managed by Artifact Keeper and closes automatically once the \
+         findings are fixed or acknowledged.",
+    );
+    out
+}
+
+/// Find a transition ID by (case-insensitive) name in a Jira
+/// `GET /issue/{key}/transitions` response.
+pub fn find_transition(response: &serde_json::Value, name: &str) -> Option<String> {
+    response
+        .get("transitions")?
+        .as_array()?
+        .iter()
+        .find(|t| {
+            t.get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        })
+        .and_then(|t| t.get("id"))
+        .and_then(|id| id.as_str())
+        .map(str::to_string)
+}
+
+/// Resolution state of a Jira issue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteIssueState {
+    Open,
+    /// Resolved; carries the resolution name when Jira set one.
+    Resolved(Option<String>),
+}
+
+/// Read the resolution state from a Jira `GET /issue/{key}` response.
+pub fn remote_issue_state(issue: &serde_json::Value) -> RemoteIssueState {
+    let fields = issue.get("fields");
+    let resolution = fields
+        .and_then(|f| f.get("resolution"))
+        .and_then(|r| r.get("name"))
+        .and_then(|n| n.as_str())
+        .map(str::to_string);
+    let done = fields
+        .and_then(|f| f.pointer("/status/statusCategory/key"))
+        .and_then(|k| k.as_str())
+        == Some("done");
+    if done || resolution.is_some() {
+        RemoteIssueState::Resolved(resolution)
+    } else {
+        RemoteIssueState::Open
+    }
+}
+
+/// Whether a resolution counts as accepted risk.
+pub fn is_acknowledge_resolution(accepted: &[String], resolution: Option<&str>) -> bool {
+    resolution.is_some_and(|r| accepted.iter().any(|a| a.eq_ignore_ascii_case(r)))
+}
+
+fn validate_short(value: &str, field: &str, max: usize) -> Result<()> {
+    if value.trim().is_empty() || value.len() > max {
+        return Err(AppError::Validation(format!(
+            "{} must be 1-{} characters",
+            field, max
+        )));
+    }
+    Ok(())
+}
+
+fn validate_project_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && key.len() <= 64
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid Jira project key '{}'",
+            key
+        )));
+    }
+    Ok(())
+}
+
+fn validate_mappings(mappings: &[ProjectMapping]) -> Result<()> {
+    if mappings.len() > MAX_PROJECT_MAPPINGS {
+        return Err(AppError::Validation(format!(
+            "At most {} project mappings are allowed",
+            MAX_PROJECT_MAPPINGS
+        )));
+    }
+    for m in mappings {
+        validate_project_key(&m.project_key)?;
+        if let Some(sev) = &m.min_severity {
+            if severity_rank(sev) == 0 {
+                return Err(AppError::Validation(format!(
+                    "Invalid min_severity '{}': must be critical, high, medium or low",
+                    sev
+                )));
+            }
+        }
+        if let Some(t) = &m.issue_type {
+            validate_short(t, "issue_type", 64)?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_field_id(field: &str) -> Result<()> {
+    let digits = field.strip_prefix("customfield_").unwrap_or_default();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::Validation(
+            "backlink_field_id must look like customfield_12345".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a new integration. Outbound URL safety is checked by the
+/// handler.
+pub fn validate_new_integration(req: &CreateIssueTrackerIntegration) -> Result<()> {
+    validate_short(&req.name, "name", 255)?;
+    validate_short(&req.api_token, "api_token", 1024)?;
+    validate_project_key(&req.project_key)?;
+    if let Some(t) = &req.issue_type {
+        validate_short(t, "issue_type", 64)?;
+    }
+    validate_mappings(&req.project_mappings)?;
+    if let Some(f) = &req.backlink_field_id {
+        validate_field_id(f)?;
+    }
+    if req.backlink_field_id.is_some() && req.backlink_base_url.is_none() {
+        return Err(AppError::Validation(
+            "backlink_field_id requires backlink_base_url".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a partial update.
+pub fn validate_update(req: &UpdateIssueTrackerIntegration) -> Result<()> {
+    if let Some(n) = &req.name {
+        validate_short(n, "name", 255)?;
+    }
+    if let Some(t) = &req.api_token {
+        validate_short(t, "api_token", 1024)?;
+    }
+    if let Some(k) = &req.project_key {
+        validate_project_key(k)?;
+    }
+    if let Some(t) = &req.issue_type {
+        validate_short(t, "issue_type", 64)?;
+    }
+    if let Some(m) = &req.project_mappings {
+        validate_mappings(m)?;
+    }
+    if let Some(f) = &req.backlink_field_id {
+        validate_field_id(f)?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Jira REST client
+// ---------------------------------------------------------------------------
+
+struct JiraClient {
+    http: reqwest::Client,
+    base_url: String,
+    email: Option<String>,
+    token: String,
+}
+
+impl JiraClient {
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/rest/api/2{}", self.base_url.trim_end_matches('/'), path);
+        let req = self
+            .http
+            .request(method, url)
+            .timeout(Duration::from_secs(15))
+            .header("Accept", "application/json");
+        match &self.email {
+            Some(email) => req.basic_auth(email, Some(&self.token)),
+            None => req.bearer_auth(&self.token),
+        }
+    }
+
+    async fn send(&self, req: reqwest::RequestBuilder) -> Result<serde_json::Value> {
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Jira request failed: {}", e)))?;
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(AppError::Internal(format!(
+                "Jira returned HTTP {}: {}",
+                status,
+                text.chars().take(300).collect::<String>()
+            )));
+        }
+        Ok(serde_json::from_str(&text).unwrap_or(serde_json::Value::Null))
+    }
+
+    async fn get_project(&self, key: &str) -> Result<serde_json::Value> {
+        self.send(self.request(reqwest::Method::GET, &format!("/project/{}", key)))
+            .await
+    }
+
+    async fn create_issue(&self, fields: serde_json::Value) -> Result<String> {
+        let resp = self
+            .send(
+                self.request(reqwest::Method::POST, "/issue")
+                    .json(&serde_json::json!({ "fields": fields })),
+            )
+            .await?;
+        resp.get("key")
+            .and_then(|k| k.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| AppError::Internal("Jira create issue returned no key".to_string()))
+    }
+
+    async fn add_remote_link(&self, key: &str, url: &str, title: &str) -> Result<()> {
+        self.send(
+            self.request(reqwest::Method::POST, &format!("/issue/{}/remotelink", key))
+                .json(&serde_json::json!({ "object": { "url": url, "title": title } })),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn add_comment(&self, key: &str, body: &str) -> Result<()> {
+        self.send(
+            self.request(reqwest::Method::POST, &format!("/issue/{}/comment", key))
+                .json(&serde_json::json!({ "body": body })),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Apply a named transition. Returns false when the issue's workflow
+    /// has no transition by that name from its current status.
+    async fn transition(&self, key: &str, name: &str) -> Result<bool> {
+        let path = format!("/issue/{}/transitions", key);
+        let available = self.send(self.request(reqwest::Method::GET, &path)).await?;
+        let Some(id) = find_transition(&available, name) else {
+            return Ok(false);
+        };
+        self.send(
+            self.request(reqwest::Method::POST, &path)
+                .json(&serde_json::json!({ "transition": { "id": id } })),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    async fn issue_state(&self, key: &str) -> Result<RemoteIssueState> {
+        let issue = self
+            .send(self.request(
+                reqwest::Method::GET,
+                &format!("/issue/{}?fields=status,resolution", key),
+            ))
+            .await?;
+        Ok(remote_issue_state(&issue))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Service
+// ---------------------------------------------------------------------------
+
+const SELECT_INTEGRATION: &str = r#"
+    SELECT id, name, provider, base_url, auth_email, api_token_encrypted,
+           project_key, issue_type, grouping, repository_id, project_mappings,
+           labels, backlink_base_url, backlink_field_id, close_transition,
+           acknowledge_transition, acknowledge_resolutions, is_enabled,
+           last_synced_at, last_sync_error, created_by, created_at, updated_at
+    FROM issue_tracker_integrations
+"#;
+
+const SELECT_LINK: &str = r#"
+    SELECT id, integration_id, group_key, repository_id, artifact_id, component,
+           issue_key, issue_url, status, fingerprints, highest_severity,
+           created_at, updated_at, closed_at
+    FROM issue_tracker_links
+"#;
+
+/// Policy-violating findings from each artifact's latest completed scan per
+/// scan type. The severity comparison mirrors `PolicyService`'s threshold.
+const VIOLATING_FINDINGS_SQL: &str = r#"
+    WITH latest AS (
+        SELECT DISTINCT ON (sr.artifact_id, sr.scan_type) sr.id
+        FROM scan_results sr
+        WHERE sr.status = 'completed'
+          AND ($1::UUID IS NULL OR sr.repository_id = $1)
+        ORDER BY sr.artifact_id, sr.scan_type, sr.created_at DESC
+    )
+    SELECT f.id, f.artifact_id, a.repository_id, r.key AS repository_key,
+           a.name AS artifact_name, a.version AS artifact_version,
+           f.severity, f.title, f.cve_id, f.affected_component,
+           f.affected_version, f.fixed_version, f.is_acknowledged
+    FROM scan_findings f
+    JOIN latest l ON l.id = f.scan_result_id
+    JOIN artifacts a ON a.id = f.artifact_id AND a.is_deleted = false
+    JOIN repositories r ON r.id = a.repository_id
+    WHERE EXISTS (
+        SELECT 1 FROM scan_policies p
+        WHERE p.is_enabled = true
+          AND (p.repository_id = a.repository_id
+               OR p.project_id = r.project_id
+               OR (p.repository_id IS NULL AND p.project_id IS NULL))
+          -- Skip layers masked by a more specific `inherit = false` policy.
+          AND CASE WHEN p.repository_id IS NOT NULL THEN 2
+                   WHEN p.project_id IS NOT NULL THEN 1 ELSE 0 END
+              >= COALESCE((
+                  SELECT MAX(CASE WHEN o.repository_id IS NOT NULL THEN 2
+                                  WHEN o.project_id IS NOT NULL THEN 1 ELSE 0 END)
+                  FROM scan_policies o
+                  WHERE o.is_enabled = true AND o.inherit = false
+                    AND (o.repository_id = a.repository_id
+                         OR o.project_id = r.project_id
+                         OR (o.repository_id IS NULL AND o.project_id IS NULL))
+              ), 0)
+          AND array_position(ARRAY['low', 'medium', 'high', 'critical'], f.severity::TEXT)
+              >= array_position(ARRAY['low', 'medium', 'high', 'critical'], p.max_severity::TEXT)
+    )
+    ORDER BY f.artifact_id, f.id
+    LIMIT $2
+"#;
+
+/// Manages Jira integrations and syncs findings into issues.
+#[derive(Clone)]
+pub struct IssueTrackerService {
+    db: PgPool,
+    http: reqwest::Client,
+}
+
+impl IssueTrackerService {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            db,
+            http: crate::services::http_client::webhook_client(),
+        }
+    }
+
+    async fn row(&self, id: Uuid) -> Result<IntegrationRow> {
+        sqlx::query_as(&format!("{} WHERE id = $1", SELECT_INTEGRATION))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .ok_or_else(|| AppError::NotFound("Issue tracker integration not found".to_string()))
+    }
+
+    fn client(&self, row: &IntegrationRow) -> Result<JiraClient> {
+        let token = webhook_secret_crypto::decrypt_secret(&row.api_token_encrypted)
+            .map_err(|e| AppError::Internal(format!("failed to decrypt Jira API token: {}", e)))?;
+        Ok(JiraClient {
+            http: self.http.clone(),
+            base_url: row.base_url.clone(),
+            email: row.auth_email.clone(),
+            token,
+        })
+    }
+
+    pub async fn list(&self) -> Result<Vec<IssueTrackerIntegration>> {
+        let rows: Vec<IntegrationRow> =
+            sqlx::query_as(&format!("{} ORDER BY name", SELECT_INTEGRATION))
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(IntegrationRow::into_response)
+            .collect())
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<IssueTrackerIntegration> {
+        Ok(self.row(id).await?.into_response())
+    }
+
+    pub async fn create(
+        &self,
+        req: &CreateIssueTrackerIntegration,
+        created_by: Uuid,
+    ) -> Result<IssueTrackerIntegration> {
+        validate_new_integration(req)?;
+        let token = encrypt(req.api_token.trim())?;
+        let mut labels = req.labels.clone();
+        if !labels.iter().any(|l| l == DEFAULT_LABEL) {
+            labels.insert(0, DEFAULT_LABEL.to_string());
+        }
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO issue_tracker_integrations
+                (name, base_url, auth_email, api_token_encrypted, project_key,
+                 issue_type, grouping, repository_id, project_mappings, labels,
+                 backlink_base_url, backlink_field_id, close_transition,
+                 acknowledge_transition, acknowledge_resolutions, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            RETURNING id
+            "#,
+        )
+        .bind(req.name.trim())
+        .bind(req.base_url.trim_end_matches('/'))
+        .bind(&req.auth_email)
+        .bind(token)
+        .bind(&req.project_key)
+        .bind(req.issue_type.as_deref().unwrap_or("Bug"))
+        .bind(req.grouping.unwrap_or(IssueGrouping::Artifact).as_str())
+        .bind(req.repository_id)
+        .bind(serde_json::to_value(&req.project_mappings).unwrap_or_default())
+        .bind(&labels)
+        .bind(
+            req.backlink_base_url
+                .as_deref()
+                .map(|u| u.trim_end_matches('/')),
+        )
+        .bind(&req.backlink_field_id)
+        .bind(req.close_transition.as_deref().unwrap_or("Done"))
+        .bind(&req.acknowledge_transition)
+        .bind(&req.acknowledge_resolutions)
+        .bind(created_by)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| map_write_error(e, &req.name))?;
+        self.get(id).await
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        req: &UpdateIssueTrackerIntegration,
+    ) -> Result<IssueTrackerIntegration> {
+        validate_update(req)?;
+        let token = match &req.api_token {
+            Some(t) => Some(encrypt(t.trim())?),
+            None => None,
+        };
+        let mappings = req
+            .project_mappings
+            .as_ref()
+            .map(|m| serde_json::to_value(m).unwrap_or_default());
+        let result = sqlx::query(
+            r#"
+            UPDATE issue_tracker_integrations SET
+                name = COALESCE($2, name),
+                base_url = COALESCE($3, base_url),
+                auth_email = COALESCE($4, auth_email),
+                api_token_encrypted = COALESCE($5, api_token_encrypted),
+                project_key = COALESCE($6, project_key),
+                issue_type = COALESCE($7, issue_type),
+                project_mappings = COALESCE($8, project_mappings),
+                labels = COALESCE($9, labels),
+                backlink_base_url = COALESCE($10, backlink_base_url),
+                backlink_field_id = COALESCE($11, backlink_field_id),
+                close_transition = COALESCE($12, close_transition),
+                acknowledge_transition = COALESCE($13, acknowledge_transition),
+                acknowledge_resolutions = COALESCE($14, acknowledge_resolutions),
+                is_enabled = COALESCE($15, is_enabled),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(req.base_url.as_deref().map(|u| u.trim_end_matches('/')))
+        .bind(&req.auth_email)
+        .bind(token)
+        .bind(&req.project_key)
+        .bind(&req.issue_type)
+        .bind(mappings)
+        .bind(&req.labels)
+        .bind(
+            req.backlink_base_url
+                .as_deref()
+                .map(|u| u.trim_end_matches('/')),
+        )
+        .bind(&req.backlink_field_id)
+        .bind(&req.close_transition)
+        .bind(&req.acknowledge_transition)
+        .bind(&req.acknowledge_resolutions)
+        .bind(req.is_enabled)
+        .execute(&self.db)
+        .await
+        .map_err(|e| map_write_error(e, req.name.as_deref().unwrap_or_default()))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Issue tracker integration not found".to_string(),
+            ));
+        }
+        self.get(id).await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM issue_tracker_integrations WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Issue tracker integration not found".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn list_links(
+        &self,
+        integration_id: Uuid,
+        status: Option<&str>,
+    ) -> Result<Vec<IssueTrackerLink>> {
+        sqlx::query_as(&format!(
+            "{} WHERE integration_id = $1 AND ($2::TEXT IS NULL OR status = $2) ORDER BY updated_at DESC",
+            SELECT_LINK
+        ))
+        .bind(integration_id)
+        .bind(status)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Check credentials and that the default project is reachable.
+    pub async fn test_connection(&self, id: Uuid) -> Result<()> {
+        let row = self.row(id).await?;
+        self.client(&row)?.get_project(&row.project_key).await?;
+        Ok(())
+    }
+
+    /// Sync every enabled integration. Errors are recorded per integration.
+    pub async fn sync_all(&self) -> Result<IssueSyncSummary> {
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM issue_tracker_integrations WHERE is_enabled = true ORDER BY name",
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut total = IssueSyncSummary::default();
+        for id in ids {
+            match self.sync_integration(id).await {
+                Ok(s) => {
+                    total.issues_created += s.issues_created;
+                    total.issues_closed += s.issues_closed;
+                    total.issues_acknowledged += s.issues_acknowledged;
+                    total.findings_acknowledged += s.findings_acknowledged;
+                    total.errors += s.errors;
+                }
+                Err(e) => {
+                    tracing::warn!(integration_id = %id, "Issue tracker sync failed: {}", e);
+                    total.errors += 1;
+                }
+            }
+        }
+        Ok(total)
+    }
+
+    /// Reconcile one integration's Jira issues with current findings.
+    pub async fn sync_integration(&self, id: Uuid) -> Result<IssueSyncSummary> {
+        let row = self.row(id).await?;
+        let result = self.sync_row(&row).await;
+        let error = result.as_ref().err().map(|e| e.to_string()).or_else(|| {
+            result
+                .as_ref()
+                .ok()
+                .filter(|s| s.errors > 0)
+                .map(|s| format!("{} issue operation(s) failed; see server logs", s.errors))
+        });
+        sqlx::query(
+            "UPDATE issue_tracker_integrations SET last_synced_at = NOW(), last_sync_error = $2 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(error)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        result
+    }
+
+    async fn sync_row(&self, row: &IntegrationRow) -> Result<IssueSyncSummary> {
+        let client = self.client(row)?;
+        let findings: Vec<ViolatingFinding> = sqlx::query_as(VIOLATING_FINDINGS_SQL)
+            .bind(row.repository_id)
+            .bind(MAX_FINDINGS_PER_SYNC)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let mut groups = group_findings(row.grouping(), findings);
+        let mut links: HashMap<String, IssueTrackerLink> = self
+            .list_links(row.id, None)
+            .await?
+            .into_iter()
+            .map(|l| (l.group_key.clone(), l))
+            .collect();
+        let mut summary = IssueSyncSummary::default();
+
+        // 1. Pull resolutions made in Jira.
+        let open_keys: Vec<String> = links
+            .values()
+            .filter(|l| l.status == "open")
+            .map(|l| l.group_key.clone())
+            .collect();
+        for key in open_keys {
+            let link = &links[&key];
+            let state = match client.issue_state(&link.issue_key).await {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::warn!(issue = %link.issue_key, "Failed to read Jira issue: {}", e);
+                    summary.errors += 1;
+                    continue;
+                }
+            };
+            let RemoteIssueState::Resolved(resolution) = state else {
+                continue;
+            };
+            let accepted =
+                is_acknowledge_resolution(&row.acknowledge_resolutions, resolution.as_deref());
+            if accepted {
+                if let Some(group) = groups.iter_mut().find(|g| g.key == key) {
+                    let ids: Vec<Uuid> = group.open.iter().map(|f| f.id).collect();
+                    let reason = format!(
+                        "Accepted in Jira {} ({})",
+                        link.issue_key,
+                        resolution.as_deref().unwrap_or("resolved")
+                    );
+                    summary.findings_acknowledged += self
+                        .acknowledge_findings(&ids, row.created_by, &reason)
+                        .await?;
+                    let acked = std::mem::take(&mut group.open);
+                    group.acknowledged.extend(acked.into_iter().map(|mut f| {
+                        f.is_acknowledged = true;
+                        f
+                    }));
+                }
+            }
+            let status = if accepted { "acknowledged" } else { "closed" };
+            self.set_link_status(link.id, status).await?;
+            if let Some(l) = links.get_mut(&key) {
+                l.status = status.to_string();
+            }
+        }
+
+        // 2. File issues for groups with open findings; 3. settle groups
+        // whose findings are all acknowledged.
+        let mut seen: HashSet<String> = HashSet::new();
+        for group in &groups {
+            seen.insert(group.key.clone());
+            let current = fingerprints(&group.open);
+            match links.get(&group.key) {
+                None if !group.open.is_empty() => {
+                    match self.file_issue(row, &client, group, None).await {
+                        Ok(()) => summary.issues_created += 1,
+                        Err(e) => {
+                            tracing::warn!(group = %group.key, "Failed to create Jira issue: {}", e);
+                            summary.errors += 1;
+                        }
+                    }
+                }
+                Some(link) if link.status == "open" => {
+                    if group.open.is_empty() {
+                        let transition = row
+                            .acknowledge_transition
+                            .as_deref()
+                            .unwrap_or(&row.close_transition);
+                        match self
+                            .settle(
+                                &client,
+                                link,
+                                transition,
+                                "All findings were acknowledged in Artifact Keeper.",
+                            )
+                            .await
+                        {
+                            Ok(()) => {
+                                self.set_link_status(link.id, "acknowledged").await?;
+                                summary.issues_acknowledged += 1;
+                            }
+                            Err(e) => {
+                                tracing::warn!(issue = %link.issue_key, "Failed to update Jira issue: {}", e);
+                                summary.errors += 1;
+                            }
+                        }
+                    } else if should_refile(&link.fingerprints, &current) {
+                        let mut merged = link.fingerprints.clone();
+                        merged.extend(current);
+                        merged.sort();
+                        merged.dedup();
+                        self.set_link_fingerprints(
+                            link.id,
+                            &merged,
+                            &highest_severity(&group.open),
+                        )
+                        .await?;
+                    }
+                }
+                Some(link)
+                    if !group.open.is_empty() && should_refile(&link.fingerprints, &current) =>
+                {
+                    match self.file_issue(row, &client, group, Some(link.id)).await {
+                        Ok(()) => summary.issues_created += 1,
+                        Err(e) => {
+                            tracing::warn!(group = %group.key, "Failed to re-file Jira issue: {}", e);
+                            summary.errors += 1;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // 3. Close open issues whose findings are gone (fixed, rescanned
+        // clean, artifact deleted, or no longer violating policy).
+        for link in links
+            .values()
+            .filter(|l| l.status == "open" && !seen.contains(&l.group_key))
+        {
+            match self
+                .settle(
+                    &client,
+                    link,
+                    &row.close_transition,
+                    "No policy-violating findings remain in Artifact Keeper.",
+                )
+                .await
+            {
+                Ok(()) => {
+                    self.set_link_status(link.id, "closed").await?;
+                    summary.issues_closed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(issue = %link.issue_key, "Failed to close Jira issue: {}", e);
+                    summary.errors += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Create a Jira issue for a group and record (or re-point) its link.
+    async fn file_issue(
+        &self,
+        row: &IntegrationRow,
+        client: &JiraClient,
+        group: &FindingGroup,
+        existing_link: Option<Uuid>,
+    ) -> Result<()> {
+        let severity = highest_severity(&group.open);
+        let (project, issue_type) = select_project(
+            &row.mappings(),
+            &row.project_key,
+            &row.issue_type,
+            group.repository_id,
+            &severity,
+        );
+        let backlink = row
+            .backlink_base_url
+            .as_deref()
+            .map(|base| backlink_url(base, group));
+        let mut fields = serde_json::json!({
+            "project": { "key": project },
+            "issuetype": { "name": issue_type },
+            "summary": issue_summary(group),
+            "description": issue_description(group, backlink.as_deref()),
+            "labels": row.labels,
+        });
+        if let (Some(field), Some(url)) = (&row.backlink_field_id, &backlink) {
+            fields[field.as_str()] = serde_json::json!(url);
+        }
+        let key = client.create_issue(fields).await?;
+        if let Some(url) = &backlink {
+            if let Err(e) = client
+                .add_remote_link(&key, url, "Artifact Keeper findings")
+                .await
+            {
+                tracing::warn!(issue = %key, "Failed to add Jira remote link: {}", e);
+            }
+        }
+        let issue_url = format!("{}/browse/{}", row.base_url.trim_end_matches('/'), key);
+        let fps = fingerprints(&group.open);
+
+        match existing_link {
+            Some(link_id) => {
+                sqlx::query(
+                    r#"
+                    UPDATE issue_tracker_links SET
+                        issue_key = $2, issue_url = $3, status = 'open',
+                        fingerprints = $4, highest_severity = $5,
+                        updated_at = NOW(), closed_at = NULL
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(link_id)
+                .bind(&key)
+                .bind(&issue_url)
+                .bind(&fps)
+                .bind(&severity)
+                .execute(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+            None => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO issue_tracker_links
+                        (integration_id, group_key, repository_id, artifact_id, component,
+                         issue_key, issue_url, fingerprints, highest_severity)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    "#,
+                )
+                .bind(row.id)
+                .bind(&group.key)
+                .bind(group.repository_id)
+                .bind(group.artifact_id)
+                .bind(&group.component)
+                .bind(&key)
+                .bind(&issue_url)
+                .bind(&fps)
+                .bind(&severity)
+                .execute(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+        }
+        tracing::info!(issue = %key, group = %group.key, "Filed Jira issue for findings");
+        Ok(())
+    }
+
+    /// Comment on and transition an issue being closed by Artifact Keeper.
+    async fn settle(
+        &self,
+        client: &JiraClient,
+        link: &IssueTrackerLink,
+        transition: &str,
+        comment: &str,
+    ) -> Result<()> {
+        client.add_comment(&link.issue_key, comment).await?;
+        if !client.transition(&link.issue_key, transition).await? {
+            tracing::warn!(
+                issue = %link.issue_key,
+                transition = %transition,
+                "Jira issue has no matching transition; left in its current status"
+            );
+        }
+        Ok(())
+    }
+
+    async fn set_link_status(&self, link_id: Uuid, status: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE issue_tracker_links
+            SET status = $2, updated_at = NOW(),
+                closed_at = CASE WHEN $2 = 'open' THEN NULL ELSE NOW() END
+            WHERE id = $1
+            "#,
+        )
+        .bind(link_id)
+        .bind(status)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn set_link_fingerprints(
+        &self,
+        link_id: Uuid,
+        fingerprints: &[String],
+        severity: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE issue_tracker_links
+            SET fingerprints = $2, highest_severity = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(link_id)
+        .bind(fingerprints)
+        .bind(severity)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn acknowledge_findings(
+        &self,
+        ids: &[Uuid],
+        acknowledged_by: Option<Uuid>,
+        reason: &str,
+    ) -> Result<u32> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let result = sqlx::query(
+            r#"
+            UPDATE scan_findings
+            SET is_acknowledged = true, acknowledged_by = $2,
+                acknowledged_reason = $3, acknowledged_at = NOW()
+            WHERE id = ANY($1) AND is_acknowledged = false
+            "#,
+        )
+        .bind(ids)
+        .bind(acknowledged_by)
+        .bind(reason)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(result.rows_affected() as u32)
+    }
+}
+
+fn encrypt(value: &str) -> Result<Vec<u8>> {
+    webhook_secret_crypto::encrypt_secret(value).map_err(|e| {
+        tracing::error!("Jira API token encryption failed: {}", e);
+        AppError::Internal("Jira API token encryption failed".to_string())
+    })
+}
+
+fn map_write_error(e: sqlx::Error, name: &str) -> AppError {
+    let msg = e.to_string();
+    if msg.contains("duplicate key") {
+        AppError::Conflict(format!(
+            "Issue tracker integration '{}' already exists",
+            name
+        ))
+    } else if msg.contains("foreign key") {
+        AppError::NotFound("Repository not found".to_string())
+    } else {
+        AppError::Database(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(
+        artifact: Uuid,
+        severity: &str,
+        cve: &str,
+        component: &str,
+        acked: bool,
+    ) -> ViolatingFinding {
+        ViolatingFinding {
+            id: Uuid::new_v4(),
+            artifact_id: artifact,
+            repository_id: Uuid::nil(),
+            repository_key: "libs".to_string(),
+            artifact_name: "app".to_string(),
+            artifact_version: Some("1.0.0".to_string()),
+            severity: severity.to_string(),
+            title: format!("{} in {}", cve, component),
+            cve_id: Some(cve.to_string()),
+            affected_component: Some(component.to_string()),
+            affected_version: Some("1.0".to_string()),
+            fixed_version: Some("1.1".to_string()),
+            is_acknowledged: acked,
+        }
+    }
+
+    fn create_req() -> CreateIssueTrackerIntegration {
+        CreateIssueTrackerIntegration {
+            name: "jira".to_string(),
+            base_url: "https://example.atlassian.net".to_string(),
+            auth_email: Some("bot@example.com".to_string()),
+            api_token: "token".to_string(),
+            project_key: "SEC".to_string(),
+            issue_type: None,
+            grouping: None,
+            repository_id: None,
+            project_mappings: vec![],
+            labels: vec![],
+            backlink_base_url: None,
+            backlink_field_id: None,
+            close_transition: None,
+            acknowledge_transition: None,
+            acknowledge_resolutions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_grouping_round_trip() {
+        for g in [IssueGrouping::Artifact, IssueGrouping::Component] {
+            assert_eq!(IssueGrouping::parse(g.as_str()), Some(g));
+        }
+        assert_eq!(IssueGrouping::parse("cve"), None);
+    }
+
+    #[test]
+    fn test_group_findings_per_artifact_splits_acknowledged() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let groups = group_findings(
+            IssueGrouping::Artifact,
+            vec![
+                finding(a, "high", "CVE-1", "openssl", false),
+                finding(a, "critical", "CVE-2", "zlib", true),
+                finding(b, "high", "CVE-1", "openssl", false),
+            ],
+        );
+        assert_eq!(groups.len(), 2);
+        let ga = groups.iter().find(|g| g.artifact_id == Some(a)).unwrap();
+        assert_eq!(ga.open.len(), 1);
+        assert_eq!(ga.acknowledged.len(), 1);
+        assert!(ga.component.is_none());
+    }
+
+    #[test]
+    fn test_group_findings_per_component_spans_artifacts() {
+        let groups = group_findings(
+            IssueGrouping::Component,
+            vec![
+                finding(Uuid::new_v4(), "high", "CVE-1", "openssl", false),
+                finding(Uuid::new_v4(), "high", "CVE-3", "openssl", false),
+                finding(Uuid::new_v4(), "low", "CVE-2", "zlib", false),
+            ],
+        );
+        assert_eq!(groups.len(), 2);
+        let openssl = groups
+            .iter()
+            .find(|g| g.component.as_deref() == Some("openssl"))
+            .unwrap();
+        assert_eq!(openssl.open.len(), 2);
+        assert!(openssl.artifact_id.is_none());
+    }
+
+    #[test]
+    fn test_highest_severity_and_fingerprints() {
+        let a = Uuid::new_v4();
+        let fs = vec![
+            finding(a, "medium", "CVE-1", "x", false),
+            finding(a, "critical", "CVE-2", "y", false),
+            finding(a, "medium", "CVE-1", "x", false),
+        ];
+        assert_eq!(highest_severity(&fs), "critical");
+        assert_eq!(highest_severity(&[]), "low");
+        assert_eq!(fingerprints(&fs), vec!["CVE-1|x", "CVE-2|y"]);
+    }
+
+    #[test]
+    fn test_should_refile_only_for_new_findings() {
+        let covered = vec!["CVE-1|x".to_string(), "CVE-2|y".to_string()];
+        assert!(!should_refile(&covered, &["CVE-1|x".to_string()]));
+        assert!(should_refile(&covered, &["CVE-3|z".to_string()]));
+        assert!(!should_refile(&covered, &[]));
+    }
+
+    #[test]
+    fn test_select_project_first_match_wins() {
+        let repo = Uuid::new_v4();
+        let mappings = vec![
+            ProjectMapping {
+                repository_id: Some(repo),
+                min_severity: Some("critical".to_string()),
+                project_key: "CRIT".to_string(),
+                issue_type: Some("Incident".to_string()),
+            },
+            ProjectMapping {
+                repository_id: Some(repo),
+                min_severity: None,
+                project_key: "TEAM".to_string(),
+                issue_type: None,
+            },
+        ];
+        assert_eq!(
+            select_project(&mappings, "SEC", "Bug", repo, "critical"),
+            ("CRIT".to_string(), "Incident".to_string())
+        );
+        assert_eq!(
+            select_project(&mappings, "SEC", "Bug", repo, "high"),
+            ("TEAM".to_string(), "Bug".to_string())
+        );
+        assert_eq!(
+            select_project(&mappings, "SEC", "Bug", Uuid::new_v4(), "critical"),
+            ("SEC".to_string(), "Bug".to_string())
+        );
+    }
+
+    #[test]
+    fn test_issue_text_and_backlink() {
+        let a = Uuid::new_v4();
+        let groups = group_findings(
+            IssueGrouping::Artifact,
+            vec![
+                finding(a, "high", "CVE-1", "openssl", false),
+                finding(a, "critical", "CVE-2", "zlib", false),
+            ],
+        );
+        let group = &groups[0];
+        let summary = issue_summary(group);
+        assert!(summary.starts_with("[CRITICAL] 2 policy-violating"));
+        assert!(summary.contains("app 1.0.0 in libs"));
+
+        let url = backlink_url("https://ak.example.com/", group);
+        assert_eq!(url, format!("https://ak.example.com/artifacts/{}", a));
+        let description = issue_description(group, Some(&url));
+        assert!(description.contains("|critical|CVE-2|zlib|1.0|1.1|"));
+        assert!(description.contains(&url));
+        // Critical rows come first.
+        assert!(description.find("CVE-2").unwrap() < description.find("CVE-1").unwrap());
+    }
+
+    #[test]
+    fn test_find_transition_case_insensitive() {
+        let resp = serde_json::json!({
+            "transitions": [
+                { "id": "11", "name": "In Progress" },
+                { "id": "31", "name": "Done" },
+            ]
+        });
+        assert_eq!(find_transition(&resp, "done"), Some("31".to_string()));
+        assert_eq!(find_transition(&resp, "Won't Fix"), None);
+        assert_eq!(find_transition(&serde_json::json!({}), "Done"), None);
+    }
+
+    #[test]
+    fn test_remote_issue_state() {
+        let open = serde_json::json!({
+            "fields": { "status": { "statusCategory": { "key": "indeterminate" } }, "resolution": null }
+        });
+        assert_eq!(remote_issue_state(&open), RemoteIssueState::Open);
+        let wont_fix = serde_json::json!({
+            "fields": {
+                "status": { "statusCategory": { "key": "done" } },
+                "resolution": { "name": "Won't Fix" }
+            }
+        });
+        assert_eq!(
+            remote_issue_state(&wont_fix),
+            RemoteIssueState::Resolved(Some("Won't Fix".to_string()))
+        );
+        let done_no_resolution = serde_json::json!({
+            "fields": { "status": { "statusCategory": { "key": "done" } } }
+        });
+        assert_eq!(
+            remote_issue_state(&done_no_resolution),
+            RemoteIssueState::Resolved(None)
+        );
+    }
+
+    #[test]
+    fn test_is_acknowledge_resolution() {
+        let accepted = vec!["Won't Fix".to_string(), "Risk Accepted".to_string()];
+        assert!(is_acknowledge_resolution(&accepted, Some("won't fix")));
+        assert!(!is_acknowledge_resolution(&accepted, Some("Done")));
+        assert!(!is_acknowledge_resolution(&accepted, None));
+        assert!(!is_acknowledge_resolution(&[], Some("Won't Fix")));
+    }
+
+    #[test]
+    fn test_validate_new_integration() {
+        assert!(validate_new_integration(&create_req()).is_ok());
+
+        let mut req = create_req();
+        req.project_key = "sec".to_string();
+        assert!(validate_new_integration(&req).is_err());
+
+        let mut req = create_req();
+        req.api_token = String::new();
+        assert!(validate_new_integration(&req).is_err());
+
+        let mut req = create_req();
+        req.backlink_field_id = Some("customfield_10050".to_string());
+        assert!(validate_new_integration(&req).is_err());
+        req.backlink_base_url = Some("https://ak.example.com".to_string());
+        assert!(validate_new_integration(&req).is_ok());
+        req.backlink_field_id = Some("summary".to_string());
+        assert!(validate_new_integration(&req).is_err());
+
+        let mut req = create_req();
+        req.project_mappings = vec![ProjectMapping {
+            repository_id: None,
+            min_severity: Some("urgent".to_string()),
+            project_key: "SEC".to_string(),
+            issue_type: None,
+        }];
+        assert!(validate_new_integration(&req).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+//! Layer-digest scan cache for container images.
+//!
+//! Hash-based dedup (`find_reusable_scan`) keys on the manifest checksum, so
+//! an image whose layers were already scanned under another tag, repository
+//! or manifest (different annotations, rebuilt config) is scanned again.
+//! Image scans are instead keyed here by their ordered layer digests:
+//!
+//! - every completed `image` / `grype` scan records the layer stack it
+//!   covered in `scan_layer_stacks`;
+//! - a later scan of an image with the identical stack copies that result
+//!   through the regular reuse path instead of invoking the scanner;
+//! - for a partially shared stack the scanner runs, and the number of layers
+//!   it has already analyzed is logged. The Trivy scanner-adapter keeps its
+//!   per-layer analysis cache keyed by the same digests (see
+//!   `SCANNER_TRIVY_CACHE_BACKEND`), so only the new layers are unpacked and
+//!   analyzed.
+//!
+//! Results are not composed from per-layer findings: whether a package in a
+//! lower layer is vulnerable depends on what the layers above it replace, so
+//! only an identical stack can reuse a result wholesale.
+
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::formats::oci::OciHandler;
+use crate::models::artifact::Artifact;
+use crate::services::scanner_service::{
+    parse_oci_manifest_path, resolve_scan_reference, ScanReferenceResolution,
+};
+use crate::storage::StorageBackend;
+
+/// Scanners whose result depends only on the image's layers.
+const LAYER_CACHED_SCAN_TYPES: &[&str] = &["image", "grype"];
+
+/// Whether results of `scan_type` may be reused across identical layer
+/// stacks.
+pub fn is_layer_cached(scan_type: &str) -> bool {
+    LAYER_CACHED_SCAN_TYPES.contains(&scan_type)
+}
+
+/// Cache key of an ordered layer stack.
+pub fn stack_key(layers: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for digest in layers {
+        hasher.update(digest.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Layer digests listed by a single-image manifest, bottom first. `None`
+/// for indexes, non-JSON bodies and manifests without layers.
+pub fn manifest_layers(body: &[u8]) -> Option<Vec<String>> {
+    let manifest = OciHandler::parse_manifest(body).ok()?;
+    if !manifest.manifests.is_empty() || manifest.layers.is_empty() {
+        return None;
+    }
+    Some(manifest.layers.into_iter().map(|l| l.digest).collect())
+}
+
+/// Layer digests of the image the scanners will evaluate for `artifact`:
+/// the manifest itself, or for an index the child the scanners resolve to.
+/// `None` when the layers cannot be determined; the scan then proceeds
+/// without the cache.
+pub async fn image_layers(
+    storage: &dyn StorageBackend,
+    artifact: &Artifact,
+    manifest_body: &[u8],
+) -> Option<Vec<String>> {
+    let (_, reference) = parse_oci_manifest_path(&artifact.path)?;
+    match resolve_scan_reference(manifest_body, reference) {
+        ScanReferenceResolution::Passthrough(_) => manifest_layers(manifest_body),
+        ScanReferenceResolution::ResolvedIndexChild(digest) => {
+            let key = crate::api::handlers::oci_v2::manifest_storage_key(&digest);
+            let child = storage.get(&key).await.ok()?;
+            manifest_layers(&child)
+        }
+        ScanReferenceResolution::UnresolvableIndex(_) => None,
+    }
+}
+
+fn db_err(e: sqlx::Error) -> AppError {
+    AppError::Database(e.to_string())
+}
+
+/// Most recent completed scan of another artifact with the same layer stack
+/// and scan type, within the same TTL windows as hash-based dedup.
+pub async fn find_reusable_scan(
+    db: &PgPool,
+    stack_key: &str,
+    scan_type: &str,
+    artifact_id: Uuid,
+    ttl_days: i32,
+    zero_findings_ttl_days: i32,
+) -> Result<Option<Uuid>> {
+    sqlx::query_scalar(
+        r#"
+        SELECT sr.id
+        FROM scan_layer_stacks s
+        JOIN scan_results sr ON sr.id = s.scan_result_id
+        WHERE s.stack_key = $1
+          AND s.scan_type = $2
+          AND sr.artifact_id <> $3
+          AND sr.status = 'completed'
+          AND sr.completed_at > NOW() - make_interval(
+              days => CASE WHEN sr.findings_count = 0 THEN $5 ELSE $4 END
+          )
+        ORDER BY sr.completed_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(stack_key)
+    .bind(scan_type)
+    .bind(artifact_id)
+    .bind(ttl_days)
+    .bind(zero_findings_ttl_days)
+    .fetch_optional(db)
+    .await
+    .map_err(db_err)
+}
+
+/// How many of `layers` a previous scan of this type already covered.
+pub async fn known_layer_count(db: &PgPool, scan_type: &str, layers: &[String]) -> Result<i64> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(DISTINCT d)
+        FROM scan_layer_stacks s, unnest(s.layer_digests) AS d
+        WHERE s.scan_type = $1 AND s.layer_digests && $2 AND d = ANY($2)
+        "#,
+    )
+    .bind(scan_type)
+    .bind(layers)
+    .fetch_one(db)
+    .await
+    .map_err(db_err)
+}
+
+/// Record the layer stack a completed scan covered.
+pub async fn record_scan(
+    db: &PgPool,
+    scan_result_id: Uuid,
+    scan_type: &str,
+    layers: &[String],
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO scan_layer_stacks (scan_result_id, scan_type, stack_key, layer_digests) \
+         VALUES ($1, $2, $3, $4) ON CONFLICT (scan_result_id) DO NOTHING",
+    )
+    .bind(scan_result_id)
+    .bind(scan_type)
+    .bind(stack_key(layers))
+    .bind(layers)
+    .execute(db)
+    .await
+    .map_err(db_err)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_key_depends_on_order_and_content() {
+        let a = vec!["sha256:aa".to_string(), "sha256:bb".to_string()];
+        let b = vec!["sha256:bb".to_string(), "sha256:aa".to_string()];
+        assert_eq!(stack_key(&a), stack_key(&a.clone()));
+        assert_ne!(stack_key(&a), stack_key(&b));
+        assert_ne!(stack_key(&a), stack_key(&a[..1]));
+        // Digest boundaries are part of the key.
+        assert_ne!(
+            stack_key(&["ab".to_string(), "c".to_string()]),
+            stack_key(&["a".to_string(), "bc".to_string()])
+        );
+        assert_eq!(stack_key(&a).len(), 64);
+    }
+
+    #[test]
+    fn test_manifest_layers() {
+        let image = br#"{"schemaVersion":2,
+          "config":{"mediaType":"application/vnd.oci.image.config.v1+json","digest":"sha256:c","size":1},
+          "layers":[{"mediaType":"application/vnd.oci.image.layer.v1.tar+gzip","digest":"sha256:l1","size":9},
+                    {"mediaType":"application/vnd.oci.image.layer.v1.tar+gzip","digest":"sha256:l2","size":9}]}"#;
+        assert_eq!(
+            manifest_layers(image),
+            Some(vec!["sha256:l1".to_string(), "sha256:l2".to_string()])
+        );
+
+        let index = br#"{"schemaVersion":2,"manifests":[
+          {"mediaType":"application/vnd.oci.image.manifest.v1+json","digest":"sha256:m","size":1}]}"#;
+        assert_eq!(manifest_layers(index), None);
+        assert_eq!(manifest_layers(br#"{"schemaVersion":2,"layers":[]}"#), None);
+        assert_eq!(manifest_layers(b"not json"), None);
+    }
+
+    #[test]
+    fn test_is_layer_cached() {
+        assert!(is_layer_cached("image"));
+        assert!(is_layer_cached("grype"));
+        assert!(!is_layer_cached("openscap"));
+        assert!(!is_layer_cached("filesystem"));
+    }
+}
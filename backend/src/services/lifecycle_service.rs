@@ -16,6 +16,28 @@
 //!   `tag_pattern_keep` for backward compatibility. See issue #1905.
 //! - tag_pattern_delete: delete artifacts matching a regex pattern
 //! - size_quota_bytes: enforce per-repo storage quotas
+//! - oci_untagged_days: delete container manifests that no tag (and no
+//!   tagged image index) references and that were last pushed N+ days ago
+//! - oci_tag_keep_last: per image, keep the N most recently pushed tags
+//!   matching a regex and delete the older matching tags
+//! - cold_tier_after_days: move artifacts older than N days to the cold
+//!   storage tier (`STORAGE_COLD_BACKEND`). Nothing is deleted: the
+//!   artifact keeps its metadata and is rehydrated on download. See
+//!   [`storage_tiering_service`](crate::services::storage_tiering_service).
+//!
+//! The two `oci_*` types work on `oci_tags` / manifest digests rather than
+//! artifact names, so they never touch a live tag outside their pattern or a
+//! per-platform child of a tagged index. Neither removes a manifest that is
+//! recorded as an artifact of a build (`build_artifacts`).
+//!
+//! Every deleting policy skips artifacts the repository's deletion
+//! protection rule (`delete_protection_service`) protects, whatever the
+//! rule's action: an unattended run has nobody to confirm the delete.
+//!
+//! A policy is scoped by exactly one of: a `repository_id`, a
+//! `label_selector` (resolved against repository labels on every run, so
+//! repositories that acquire the label later are covered automatically), or
+//! neither (a global policy).
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -25,6 +47,8 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
+use crate::services::delete_protection_service::protected_artifact_sql;
+use crate::services::repository_label_service::{LabelEntry, RepositoryLabelService};
 use crate::services::scheduler_service::normalize_cron_expression;
 use crate::storage::keys::prefix_matches;
 
@@ -129,6 +153,75 @@ WHERE a.is_deleted = true
 /// SQL literal and the write-path constant from drifting (#1413).
 const _: () = assert!(prefix_matches("oci-manifests/"));
 
+/// Shared `WHERE` clause (artifact aliased `a`) for `oci_untagged_days`.
+/// `$1::UUID` is the repo filter, `$2` the age in days.
+///
+/// A manifest artifact is untagged when no `oci_tags` row in its repository
+/// carries its digest and no tagged image index reaches it, directly or
+/// through nested indexes (`ak_oci_manifest_index_referenced`, migration
+/// 194). Age is measured from the artifact's last push (`updated_at`), since
+/// the moment a tag moved away is not recorded. Manifests pinned by a build
+/// are skipped: a build records the digest's hex as `checksum_sha256`.
+const OCI_UNTAGGED_MANIFEST_FILTER_SQL: &str = r#"
+a.is_deleted = false
+AND ($1::UUID IS NULL OR a.repository_id = $1)
+AND a.storage_key LIKE 'oci-manifests/%'
+AND a.updated_at < NOW() - make_interval(days => $2::INT)
+AND NOT EXISTS (
+    SELECT 1 FROM oci_tags ot
+    WHERE ot.repository_id = a.repository_id
+      AND ot.manifest_digest = SUBSTRING(a.storage_key FROM LENGTH('oci-manifests/') + 1)
+)
+AND NOT ak_oci_manifest_index_referenced(
+    a.repository_id,
+    SUBSTRING(a.storage_key FROM LENGTH('oci-manifests/') + 1)
+)
+AND NOT EXISTS (
+    SELECT 1 FROM build_artifacts ba
+    WHERE a.storage_key LIKE 'oci-manifests/sha256:%'
+      AND ba.checksum_sha256 = SUBSTRING(a.storage_key FROM LENGTH('oci-manifests/sha256:') + 1)
+)
+"#;
+
+/// `doomed` CTE body for `oci_tag_keep_last`: tags matching `$2` ranked per
+/// `(repository_id, image)` by last push, past the first `$3`. `$1::UUID` is
+/// the repo filter. A tag whose manifest is recorded as an artifact of a
+/// build (hex `checksum_sha256` = digest without `sha256:`) still counts
+/// toward the kept `N` but is never selected.
+const OCI_TAG_KEEP_LAST_DOOMED_SQL: &str = r#"
+SELECT ot.id, ot.repository_id, ot.name, ot.tag, ot.manifest_digest
+FROM (
+    SELECT t.id, t.repository_id, t.name, t.tag, t.manifest_digest,
+           ROW_NUMBER() OVER (
+               PARTITION BY t.repository_id, t.name
+               ORDER BY t.updated_at DESC, t.created_at DESC, t.tag DESC
+           ) AS recency
+    FROM oci_tags t
+    WHERE ($1::UUID IS NULL OR t.repository_id = $1)
+      AND t.tag ~ $2
+) ot
+WHERE ot.recency > $3
+  AND NOT EXISTS (
+      SELECT 1 FROM build_artifacts ba
+      WHERE ot.manifest_digest LIKE 'sha256:%'
+        AND ba.checksum_sha256 = SUBSTRING(ot.manifest_digest FROM LENGTH('sha256:') + 1)
+  )
+"#;
+
+/// Shared `WHERE` clause (artifact aliased `a`) for `cold_tier_after_days`.
+/// `$1::UUID` is the repo filter, `$2` the age in days. Artifacts the tier
+/// already tracks are excluded, and so are OCI manifests: registry clients
+/// cannot follow a 202 on a manifest pull.
+const COLD_TIER_CANDIDATE_FILTER_SQL: &str = r#"
+a.is_deleted = false
+AND ($1::UUID IS NULL OR a.repository_id = $1)
+AND a.created_at < NOW() - make_interval(days => $2::INT)
+AND a.storage_key NOT LIKE 'oci-manifests/%'
+AND NOT EXISTS (
+    SELECT 1 FROM artifact_storage_tiers t WHERE t.artifact_id = a.id
+)
+"#;
+
 /// Scope of a lifecycle policy execution: either a specific repository or
 /// every repository in the cluster (a "global" policy with `repository_id`
 /// NULL). Pulled out as a strongly-typed wrapper around `Option<Uuid>` so
@@ -166,8 +259,9 @@ impl CascadeScope {
 /// and `size_quota_bytes` enforces a *per-repo* storage budget; both
 /// `execute_*` implementations hard-require `policy.repository_id` and fail
 /// at runtime if it is NULL (see `execute_max_versions` /
-/// `execute_size_quota`). The other four types (`max_age_days`,
-/// `no_downloads_days`, `tag_pattern_keep`, `tag_pattern_delete`) gate on
+/// `execute_size_quota`). The other types (`max_age_days`,
+/// `no_downloads_days`, `tag_pattern_keep`, `tag_pattern_delete` and the two
+/// `oci_*` types) gate on
 /// `($1::UUID IS NULL OR a.repository_id = $1)` and run cluster-wide when
 /// `repository_id` is NULL, so a global policy of those types is legitimate.
 ///
@@ -178,6 +272,46 @@ pub(crate) fn policy_type_requires_repository_id(policy_type: &str) -> bool {
     matches!(policy_type, "max_versions" | "size_quota_bytes")
 }
 
+/// Validate a policy's label selector against its `repository_id`.
+///
+/// The two scoping mechanisms are mutually exclusive: a policy pinned to one
+/// repository cannot also fan out by label. Every selector entry needs a
+/// non-empty key; an empty value is allowed and means "any value".
+pub(crate) fn validate_label_selector(
+    repository_id: Option<Uuid>,
+    label_selector: &[LabelEntry],
+) -> Result<()> {
+    if label_selector.is_empty() {
+        return Ok(());
+    }
+    if repository_id.is_some() {
+        return Err(AppError::Validation(
+            "'label_selector' and 'repository_id' are mutually exclusive".to_string(),
+        ));
+    }
+    if label_selector
+        .iter()
+        .any(|entry| entry.key.trim().is_empty())
+    {
+        return Err(AppError::Validation(
+            "label_selector entries require a non-empty 'key'".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fold one repository's execution result into the running total of a
+/// label-selected policy run.
+pub(crate) fn merge_execution_result(
+    total: &mut PolicyExecutionResult,
+    result: PolicyExecutionResult,
+) {
+    total.artifacts_matched += result.artifacts_matched;
+    total.artifacts_removed += result.artifacts_removed;
+    total.bytes_freed += result.bytes_freed;
+    total.errors.extend(result.errors);
+}
+
 impl From<Option<Uuid>> for CascadeScope {
     fn from(value: Option<Uuid>) -> Self {
         match value {
@@ -187,7 +321,7 @@ impl From<Option<Uuid>> for CascadeScope {
     }
 }
 
-/// Strongly-typed enum of the six policy types accepted by
+/// Strongly-typed enum of the policy types accepted by
 /// `dispatch_execute`. Centralises the string -> dispatcher mapping so the
 /// "unsupported policy type" branch is reachable from unit tests without
 /// going through the DB. Kept `pub(crate)` (not exported) because the wire
@@ -206,6 +340,13 @@ pub(crate) enum PolicyType {
     TagPatternKeep,
     TagPatternDelete,
     SizeQuotaBytes,
+    /// Container manifests no tag or tagged index references, older than
+    /// `days`.
+    OciUntaggedDays,
+    /// Per image, tags matching `pattern` beyond the `keep` most recent.
+    OciTagKeepLast,
+    /// Artifacts older than `days` are queued for the cold storage tier.
+    ColdTierAfterDays,
 }
 
 impl PolicyType {
@@ -220,6 +361,9 @@ impl PolicyType {
             "tag_pattern_keep" => Ok(Self::TagPatternKeep),
             "tag_pattern_delete" => Ok(Self::TagPatternDelete),
             "size_quota_bytes" => Ok(Self::SizeQuotaBytes),
+            "oci_untagged_days" => Ok(Self::OciUntaggedDays),
+            "oci_tag_keep_last" => Ok(Self::OciTagKeepLast),
+            "cold_tier_after_days" => Ok(Self::ColdTierAfterDays),
             other => Err(AppError::Internal(format!(
                 "Unsupported policy type: {other}",
             ))),
@@ -236,6 +380,9 @@ impl PolicyType {
             Self::TagPatternKeep => "tag_pattern_keep",
             Self::TagPatternDelete => "tag_pattern_delete",
             Self::SizeQuotaBytes => "size_quota_bytes",
+            Self::OciUntaggedDays => "oci_untagged_days",
+            Self::OciTagKeepLast => "oci_tag_keep_last",
+            Self::ColdTierAfterDays => "cold_tier_after_days",
         }
     }
 }
@@ -313,8 +460,9 @@ pub(crate) fn select_size_quota_evictions(
     (to_remove, accumulated)
 }
 
-/// A lifecycle policy attached to a repository (or global if repository_id is NULL).
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+/// A lifecycle policy attached to a repository, to every repository matching
+/// a label selector, or global if both are unset.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct LifecyclePolicy {
     pub id: Uuid,
     pub repository_id: Option<Uuid>,
@@ -328,6 +476,12 @@ pub struct LifecyclePolicy {
     pub last_run_at: Option<DateTime<Utc>>,
     pub last_run_items_removed: Option<i64>,
     pub cron_schedule: Option<String>,
+    /// Label selectors (AND semantics; an empty value matches any value of
+    /// the key). When non-empty the policy runs against every repository
+    /// carrying all the labels at execution time.
+    #[sqlx(json)]
+    #[serde(default)]
+    pub label_selector: Vec<LabelEntry>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -343,6 +497,10 @@ pub struct CreateLifecyclePolicyRequest {
     pub config: serde_json::Value,
     pub priority: Option<i32>,
     pub cron_schedule: Option<String>,
+    /// Target repositories by label instead of `repository_id`. Mutually
+    /// exclusive with `repository_id`.
+    #[serde(default)]
+    pub label_selector: Vec<LabelEntry>,
 }
 
 /// Request to update a lifecycle policy.
@@ -355,6 +513,9 @@ pub struct UpdateLifecyclePolicyRequest {
     pub config: Option<serde_json::Value>,
     pub priority: Option<i32>,
     pub cron_schedule: Option<String>,
+    /// Replace the label selector. Only valid on policies without a
+    /// `repository_id`.
+    pub label_selector: Option<Vec<LabelEntry>>,
 }
 
 /// Result of a lifecycle policy dry-run or execution.
@@ -411,6 +572,9 @@ impl LifecycleService {
             "tag_pattern_keep",
             "tag_pattern_delete",
             "size_quota_bytes",
+            "oci_untagged_days",
+            "oci_tag_keep_last",
+            "cold_tier_after_days",
         ];
         if !valid_types.contains(&req.policy_type.as_str()) {
             return Err(AppError::Validation(format!(
@@ -423,7 +587,14 @@ impl LifecycleService {
         // Reject repo-scoped policy types created without a repository_id.
         // These (`max_versions`, `size_quota_bytes`) require a repository_id
         // at execute time and would otherwise fail on every run (#1850).
-        if req.repository_id.is_none() && policy_type_requires_repository_id(&req.policy_type) {
+        validate_label_selector(req.repository_id, &req.label_selector)?;
+
+        // A label-selected policy runs once per matched repository, so the
+        // repo-scoped types are usable there too.
+        if req.repository_id.is_none()
+            && req.label_selector.is_empty()
+            && policy_type_requires_repository_id(&req.policy_type)
+        {
             return Err(AppError::Validation(format!(
                 "policy_type '{}' is repository-scoped and requires a 'repository_id'; \
                  it cannot be created as a global policy",
@@ -445,11 +616,11 @@ impl LifecycleService {
 
         let policy = sqlx::query_as::<_, LifecyclePolicy>(
             r#"
-            INSERT INTO lifecycle_policies (repository_id, name, description, policy_type, config, priority, cron_schedule)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO lifecycle_policies (repository_id, name, description, policy_type, config, priority, cron_schedule, label_selector)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             RETURNING id, repository_id, name, description, enabled,
                       policy_type, config, priority, last_run_at,
-                      last_run_items_removed, cron_schedule, created_at, updated_at
+                      last_run_items_removed, cron_schedule, label_selector, created_at, updated_at
             "#,
         )
         .bind(req.repository_id)
@@ -459,6 +630,7 @@ impl LifecycleService {
         .bind(&req.config)
         .bind(req.priority.unwrap_or(0))
         .bind(&req.cron_schedule)
+        .bind(sqlx::types::Json(&req.label_selector))
         .fetch_one(&self.db)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -467,12 +639,16 @@ impl LifecycleService {
     }
 
     /// List lifecycle policies, optionally filtered by repository.
+    ///
+    /// With a repository, the result is the set of policies that would run
+    /// against it: its own policies, global policies, and label-selected
+    /// policies whose selector currently matches the repository's labels.
     pub async fn list_policies(&self, repository_id: Option<Uuid>) -> Result<Vec<LifecyclePolicy>> {
         let policies = sqlx::query_as::<_, LifecyclePolicy>(
             r#"
             SELECT id, repository_id, name, description, enabled,
                    policy_type, config, priority, last_run_at,
-                   last_run_items_removed, cron_schedule, created_at, updated_at
+                   last_run_items_removed, cron_schedule, label_selector, created_at, updated_at
             FROM lifecycle_policies
             WHERE ($1::UUID IS NULL OR repository_id = $1 OR repository_id IS NULL)
             ORDER BY priority DESC, created_at ASC
@@ -483,7 +659,23 @@ impl LifecycleService {
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(policies)
+        let Some(repository_id) = repository_id else {
+            return Ok(policies);
+        };
+        let labels = RepositoryLabelService::new(self.db.clone());
+        let mut applicable = Vec::with_capacity(policies.len());
+        for policy in policies {
+            if policy.repository_id.is_some() || policy.label_selector.is_empty() {
+                applicable.push(policy);
+                continue;
+            }
+            let matched = labels.find_repos_by_labels(&policy.label_selector).await?;
+            if matched.contains(&repository_id) {
+                applicable.push(policy);
+            }
+        }
+
+        Ok(applicable)
     }
 
     /// Get a single policy by ID.
@@ -492,7 +684,7 @@ impl LifecycleService {
             r#"
             SELECT id, repository_id, name, description, enabled,
                    policy_type, config, priority, last_run_at,
-                   last_run_items_removed, cron_schedule, created_at, updated_at
+                   last_run_items_removed, cron_schedule, label_selector, created_at, updated_at
             FROM lifecycle_policies
             WHERE id = $1
             "#,
@@ -518,12 +710,16 @@ impl LifecycleService {
         let config = req.config.unwrap_or(existing.config);
         let priority = req.priority.unwrap_or(existing.priority);
         let cron_schedule = req.cron_schedule.or(existing.cron_schedule);
+        let label_selector = req.label_selector.unwrap_or(existing.label_selector);
+
+        validate_label_selector(existing.repository_id, &label_selector)?;
 
         // Mirror the create-time guard (#1850): a repo-scoped policy type
         // (`max_versions`, `size_quota_bytes`) must have a repository_id.
         // `repository_id` and `policy_type` are immutable via update, so this
         // only rejects updates to pre-existing unusable global policies.
         if existing.repository_id.is_none()
+            && label_selector.is_empty()
             && policy_type_requires_repository_id(&existing.policy_type)
         {
             return Err(AppError::Validation(format!(
@@ -549,11 +745,12 @@ impl LifecycleService {
             r#"
             UPDATE lifecycle_policies
             SET name = $2, description = $3, enabled = $4,
-                config = $5, priority = $6, cron_schedule = $7, updated_at = NOW()
+                config = $5, priority = $6, cron_schedule = $7, label_selector = $8,
+                updated_at = NOW()
             WHERE id = $1
             RETURNING id, repository_id, name, description, enabled,
                       policy_type, config, priority, last_run_at,
-                      last_run_items_removed, cron_schedule, created_at, updated_at
+                      last_run_items_removed, cron_schedule, label_selector, created_at, updated_at
             "#,
         )
         .bind(id)
@@ -563,6 +760,7 @@ impl LifecycleService {
         .bind(&config)
         .bind(priority)
         .bind(&cron_schedule)
+        .bind(sqlx::types::Json(&label_selector))
         .fetch_one(&self.db)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -624,6 +822,37 @@ impl LifecycleService {
             ));
         }
 
+        let result = if policy.label_selector.is_empty() {
+            self.run_scoped(&policy, dry_run).await?
+        } else {
+            self.run_label_selected(&policy, dry_run).await?
+        };
+
+        if dry_run {
+            return Ok(result);
+        }
+
+        // Bookkeeping: single-row update, no transaction needed.
+        sqlx::query(
+            "UPDATE lifecycle_policies SET last_run_at = NOW(), last_run_items_removed = $2 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(result.artifacts_removed)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Run `policy` once against the scope its `repository_id` names (a
+    /// single repository, or every repository when NULL). See
+    /// [`Self::execute_policy`] for the transaction layout.
+    async fn run_scoped(
+        &self,
+        policy: &LifecyclePolicy,
+        dry_run: bool,
+    ) -> Result<PolicyExecutionResult> {
         // Dry-run reads only. Take a regular connection, skip the
         // transaction overhead (and skip the cascade entirely — dry_run
         // must not mutate oci_tags).
@@ -633,7 +862,7 @@ impl LifecycleService {
                 .acquire()
                 .await
                 .map_err(|e| AppError::Database(e.to_string()))?;
-            return Self::dispatch_execute(&mut conn, &policy, true).await;
+            return Self::dispatch_execute(&mut conn, policy, true).await;
         }
 
         // Transaction 1: per-type soft-delete. Commit immediately so the
@@ -644,7 +873,7 @@ impl LifecycleService {
             .begin()
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
-        let result = Self::dispatch_execute(&mut tx, &policy, false).await?;
+        let result = Self::dispatch_execute(&mut tx, policy, false).await?;
         tx.commit()
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -664,19 +893,45 @@ impl LifecycleService {
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
 
-        // Bookkeeping: single-row update, no transaction needed.
-        sqlx::query(
-            "UPDATE lifecycle_policies SET last_run_at = NOW(), last_run_items_removed = $2 WHERE id = $1",
-        )
-        .bind(id)
-        .bind(result.artifacts_removed)
-        .execute(&self.db)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
-
         Ok(result)
     }
 
+    /// Resolve the policy's label selector to the repositories carrying the
+    /// labels right now and run the policy once per repository, folding the
+    /// per-repository outcomes into a single result. A failure on one
+    /// repository is recorded in `errors` and does not stop the others.
+    async fn run_label_selected(
+        &self,
+        policy: &LifecyclePolicy,
+        dry_run: bool,
+    ) -> Result<PolicyExecutionResult> {
+        let repo_ids = RepositoryLabelService::new(self.db.clone())
+            .find_repos_by_labels(&policy.label_selector)
+            .await?;
+
+        let mut total = Self::build_execution_result(policy, dry_run, 0, 0, 0);
+        for repo_id in repo_ids {
+            let scoped = LifecyclePolicy {
+                repository_id: Some(repo_id),
+                label_selector: Vec::new(),
+                ..policy.clone()
+            };
+            match self.run_scoped(&scoped, dry_run).await {
+                Ok(result) => merge_execution_result(&mut total, result),
+                Err(e) => {
+                    tracing::warn!(
+                        policy = %policy.name,
+                        repository_id = %repo_id,
+                        "Label-selected lifecycle policy failed for repository: {}",
+                        e
+                    );
+                    total.errors.push(format!("repository {repo_id}: {e}"));
+                }
+            }
+        }
+        Ok(total)
+    }
+
     /// Dispatch to the per-type implementation against a single
     /// `PgConnection`. Real runs pass `&mut *tx` (a transaction
     /// re-borrowed as a connection) so the per-type soft-delete and the
@@ -697,6 +952,11 @@ impl LifecycleService {
                 Self::execute_tag_pattern_delete(conn, policy, dry_run).await
             }
             PolicyType::SizeQuotaBytes => Self::execute_size_quota(conn, policy, dry_run).await,
+            PolicyType::OciUntaggedDays => Self::execute_oci_untagged(conn, policy, dry_run).await,
+            PolicyType::OciTagKeepLast => {
+                Self::execute_oci_tag_keep_last(conn, policy, dry_run).await
+            }
+            PolicyType::ColdTierAfterDays => Self::execute_cold_tier(conn, policy, dry_run).await,
         }
     }
 
@@ -746,7 +1006,7 @@ impl LifecycleService {
             r#"
             SELECT id, repository_id, name, description, enabled,
                    policy_type, config, priority, last_run_at,
-                   last_run_items_removed, cron_schedule, created_at, updated_at
+                   last_run_items_removed, cron_schedule, label_selector, created_at, updated_at
             FROM lifecycle_policies
             WHERE enabled = true
             ORDER BY priority DESC
@@ -896,31 +1156,34 @@ impl LifecycleService {
         dry_run: bool,
     ) -> Result<PolicyExecutionResult> {
         let days = parse_i64_field(&policy.config, PolicyType::MaxAgeDays.as_wire_str(), "days")?;
+        let protected = protected_artifact_sql("artifacts");
 
         let matched = if policy.repository_id.is_some() {
-            sqlx::query_as::<_, CountBytes>(
+            sqlx::query_as::<_, CountBytes>(&format!(
                 r#"
                 SELECT COUNT(*) as count, COALESCE(SUM(size_bytes), 0)::BIGINT as bytes
                 FROM artifacts
                 WHERE repository_id = $1
                   AND is_deleted = false
                   AND created_at < NOW() - make_interval(days => $2::INT)
-                "#,
-            )
+                  AND NOT {protected}
+                "#
+            ))
             .bind(policy.repository_id)
             .bind(days as i32)
             .fetch_one(&mut *conn)
             .await
             .map_err(|e| AppError::Database(e.to_string()))?
         } else {
-            sqlx::query_as::<_, CountBytes>(
+            sqlx::query_as::<_, CountBytes>(&format!(
                 r#"
                 SELECT COUNT(*) as count, COALESCE(SUM(size_bytes), 0)::BIGINT as bytes
                 FROM artifacts
                 WHERE is_deleted = false
                   AND created_at < NOW() - make_interval(days => $1::INT)
-                "#,
-            )
+                  AND NOT {protected}
+                "#
+            ))
             .bind(days as i32)
             .fetch_one(&mut *conn)
             .await
@@ -930,27 +1193,29 @@ impl LifecycleService {
         let mut removed = 0i64;
         if !dry_run && matched.count > 0 {
             let result = if policy.repository_id.is_some() {
-                sqlx::query(
+                sqlx::query(&format!(
                     r#"
                     UPDATE artifacts SET is_deleted = true
                     WHERE repository_id = $1
                       AND is_deleted = false
                       AND created_at < NOW() - make_interval(days => $2::INT)
-                    "#,
-                )
+                      AND NOT {protected}
+                    "#
+                ))
                 .bind(policy.repository_id)
                 .bind(days as i32)
                 .execute(&mut *conn)
                 .await
                 .map_err(|e| AppError::Database(e.to_string()))?
             } else {
-                sqlx::query(
+                sqlx::query(&format!(
                     r#"
                     UPDATE artifacts SET is_deleted = true
                     WHERE is_deleted = false
                       AND created_at < NOW() - make_interval(days => $1::INT)
-                    "#,
-                )
+                      AND NOT {protected}
+                    "#
+                ))
                 .bind(days as i32)
                 .execute(&mut *conn)
                 .await
@@ -984,7 +1249,7 @@ impl LifecycleService {
         })?;
 
         // Find artifacts to remove: for each (name), keep only the latest N
-        let matched = sqlx::query_as::<_, CountBytes>(
+        let matched = sqlx::query_as::<_, CountBytes>(&format!(
             r#"
             SELECT COUNT(*) as count, COALESCE(SUM(a.size_bytes), 0)::BIGINT as bytes
             FROM artifacts a
@@ -998,8 +1263,10 @@ impl LifecycleService {
                   ORDER BY a2.created_at DESC
                   LIMIT $2
               )
+              AND NOT {}
             "#,
-        )
+            protected_artifact_sql("a")
+        ))
         .bind(repo_id)
         .bind(keep)
         .fetch_one(&mut *conn)
@@ -1008,7 +1275,7 @@ impl LifecycleService {
 
         let mut removed = 0i64;
         if !dry_run && matched.count > 0 {
-            let result = sqlx::query(
+            let result = sqlx::query(&format!(
                 r#"
                 UPDATE artifacts SET is_deleted = true
                 WHERE repository_id = $1
@@ -1021,8 +1288,10 @@ impl LifecycleService {
                       ORDER BY a2.created_at DESC
                       LIMIT $2
                   )
+                  AND NOT {}
                 "#,
-            )
+                protected_artifact_sql("artifacts")
+            ))
             .bind(repo_id)
             .bind(keep)
             .execute(&mut *conn)
@@ -1053,7 +1322,7 @@ impl LifecycleService {
 
         let repo_filter = policy.repository_id;
 
-        let matched = sqlx::query_as::<_, CountBytes>(
+        let matched = sqlx::query_as::<_, CountBytes>(&format!(
             r#"
             SELECT COUNT(*) as count, COALESCE(SUM(a.size_bytes), 0)::BIGINT as bytes
             FROM artifacts a
@@ -1065,8 +1334,10 @@ impl LifecycleService {
                     AND ds.downloaded_at > NOW() - make_interval(days => $2::INT)
               )
               AND a.created_at < NOW() - make_interval(days => $2::INT)
+              AND NOT {}
             "#,
-        )
+            protected_artifact_sql("a")
+        ))
         .bind(repo_filter)
         .bind(days as i32)
         .fetch_one(&mut *conn)
@@ -1075,7 +1346,7 @@ impl LifecycleService {
 
         let mut removed = 0i64;
         if !dry_run && matched.count > 0 {
-            let result = sqlx::query(
+            let result = sqlx::query(&format!(
                 r#"
                 UPDATE artifacts SET is_deleted = true
                 WHERE is_deleted = false
@@ -1086,8 +1357,10 @@ impl LifecycleService {
                         AND ds.downloaded_at > NOW() - make_interval(days => $2::INT)
                   )
                   AND created_at < NOW() - make_interval(days => $2::INT)
+                  AND NOT {}
                 "#,
-            )
+                protected_artifact_sql("artifacts")
+            ))
             .bind(repo_filter)
             .bind(days as i32)
             .execute(&mut *conn)
@@ -1151,7 +1424,9 @@ impl LifecycleService {
             WHERE a.is_deleted = false
               AND ($1::UUID IS NULL OR a.repository_id = $1)
               AND a.name {op} $2
-            "#
+              AND NOT {protected}
+            "#,
+            protected = protected_artifact_sql("a")
         ))
         .bind(repo_filter)
         .bind(pattern)
@@ -1167,7 +1442,9 @@ impl LifecycleService {
                 WHERE is_deleted = false
                   AND ($1::UUID IS NULL OR repository_id = $1)
                   AND name {op} $2
-                "#
+                  AND NOT {protected}
+                "#,
+                protected = protected_artifact_sql("artifacts")
             ))
             .bind(repo_filter)
             .bind(pattern)
@@ -1223,7 +1500,7 @@ impl LifecycleService {
         // Find least-recently-used artifacts to evict first (LRU).
         // Never-downloaded artifacts are evicted before downloaded ones,
         // then by least-recent download, then by creation time as tiebreaker.
-        let candidates = sqlx::query_as::<_, SizeCandidate>(
+        let candidates = sqlx::query_as::<_, SizeCandidate>(&format!(
             r#"
             SELECT a.id, a.size_bytes
             FROM artifacts a
@@ -1233,9 +1510,11 @@ impl LifecycleService {
                 WHERE ds.artifact_id = a.id
             ) ds ON true
             WHERE a.repository_id = $1 AND a.is_deleted = false
+              AND NOT {}
             ORDER BY ds.last_downloaded_at ASC NULLS FIRST, a.created_at ASC
             "#,
-        )
+            protected_artifact_sql("a")
+        ))
         .bind(repo_id)
         .fetch_all(&mut *conn)
         .await
@@ -1270,6 +1549,208 @@ impl LifecycleService {
         ))
     }
 
+    /// Soft-delete container manifests that nothing tagged references any
+    /// more. See [`OCI_UNTAGGED_MANIFEST_FILTER_SQL`] for what counts as
+    /// untagged. The lifecycle cascade has no `oci_tags` rows to prune here;
+    /// storage GC reclaims the manifest bodies and their blob refs.
+    async fn execute_oci_untagged(
+        conn: &mut sqlx::PgConnection,
+        policy: &LifecyclePolicy,
+        dry_run: bool,
+    ) -> Result<PolicyExecutionResult> {
+        let days = parse_i64_field(
+            &policy.config,
+            PolicyType::OciUntaggedDays.as_wire_str(),
+            "days",
+        )?;
+
+        let matched = sqlx::query_as::<_, CountBytes>(&format!(
+            r#"
+            SELECT COUNT(*) as count, COALESCE(SUM(a.size_bytes), 0)::BIGINT as bytes
+            FROM artifacts a
+            WHERE {OCI_UNTAGGED_MANIFEST_FILTER_SQL}
+              AND NOT {protected}
+            "#,
+            protected = protected_artifact_sql("a")
+        ))
+        .bind(policy.repository_id)
+        .bind(days as i32)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut removed = 0i64;
+        if !dry_run && matched.count > 0 {
+            let result = sqlx::query(&format!(
+                r#"
+                UPDATE artifacts a SET is_deleted = true
+                WHERE {OCI_UNTAGGED_MANIFEST_FILTER_SQL}
+                  AND NOT {protected}
+                "#,
+                protected = protected_artifact_sql("a")
+            ))
+            .bind(policy.repository_id)
+            .bind(days as i32)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            removed = result.rows_affected() as i64;
+        }
+
+        Ok(Self::build_execution_result(
+            policy,
+            dry_run,
+            matched.count,
+            removed,
+            matched.bytes,
+        ))
+    }
+
+    /// Queue artifacts created more than `days` ago for the cold storage
+    /// tier. Only an `artifact_storage_tiers` row is written here; the tiering
+    /// worker copies the bytes and frees the hot copy, so "removed" counts
+    /// queued artifacts and the bytes are what the hot tier will give up.
+    /// Artifacts already tracked by the tier (cold, rehydrating or
+    /// rehydrated) are left alone.
+    async fn execute_cold_tier(
+        conn: &mut sqlx::PgConnection,
+        policy: &LifecyclePolicy,
+        dry_run: bool,
+    ) -> Result<PolicyExecutionResult> {
+        let days = parse_i64_field(
+            &policy.config,
+            PolicyType::ColdTierAfterDays.as_wire_str(),
+            "days",
+        )?;
+
+        let matched = sqlx::query_as::<_, CountBytes>(&format!(
+            r#"
+            SELECT COUNT(*) as count, COALESCE(SUM(a.size_bytes), 0)::BIGINT as bytes
+            FROM artifacts a
+            WHERE {COLD_TIER_CANDIDATE_FILTER_SQL}
+            "#
+        ))
+        .bind(policy.repository_id)
+        .bind(days as i32)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut removed = 0i64;
+        if !dry_run && matched.count > 0 {
+            let result = sqlx::query(&format!(
+                r#"
+                INSERT INTO artifact_storage_tiers (artifact_id, policy_id)
+                SELECT a.id, $3
+                FROM artifacts a
+                WHERE {COLD_TIER_CANDIDATE_FILTER_SQL}
+                ON CONFLICT (artifact_id) DO NOTHING
+                "#
+            ))
+            .bind(policy.repository_id)
+            .bind(days as i32)
+            .bind(policy.id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            removed = result.rows_affected() as i64;
+        }
+
+        Ok(Self::build_execution_result(
+            policy,
+            dry_run,
+            matched.count,
+            removed,
+            matched.bytes,
+        ))
+    }
+
+    /// Retire tags matching `pattern` beyond the `keep` most recently pushed
+    /// per image. Counts are in tags; bytes are the tag manifests' sizes.
+    ///
+    /// Unlike the name-based policies this deletes the `oci_tags` rows itself
+    /// instead of leaving them to [`CASCADE_OCI_TAGS_SQL`]: the cascade's
+    /// last-protecting-tag guard (#1682) would keep every tag that is the
+    /// only name for its digest, which is exactly what a retention rule
+    /// means to remove. The tag's manifest artifact is soft-deleted in the
+    /// same statement, so it leaves the listing together with the tag. A
+    /// manifest that another tag, or a tagged index, still references stays
+    /// reachable for storage GC.
+    async fn execute_oci_tag_keep_last(
+        conn: &mut sqlx::PgConnection,
+        policy: &LifecyclePolicy,
+        dry_run: bool,
+    ) -> Result<PolicyExecutionResult> {
+        let label = PolicyType::OciTagKeepLast.as_wire_str();
+        let pattern = parse_pattern_field(&policy.config, label)?;
+        let keep = parse_i64_field(&policy.config, label, "keep")?;
+        // A tag whose manifest is protected stays, and so does its manifest.
+        let doomed = format!(
+            r#"
+            SELECT d.* FROM ({OCI_TAG_KEEP_LAST_DOOMED_SQL}) d
+            WHERE NOT EXISTS (
+                SELECT 1 FROM artifacts pa
+                WHERE pa.repository_id = d.repository_id
+                  AND pa.path = 'v2/' || d.name || '/manifests/' || d.tag
+                  AND pa.is_deleted = false
+                  AND {}
+            )
+            "#,
+            protected_artifact_sql("pa")
+        );
+
+        let matched = sqlx::query_as::<_, CountBytes>(&format!(
+            r#"
+            WITH doomed AS ({doomed})
+            SELECT COUNT(*) as count, COALESCE(SUM(a.size_bytes), 0)::BIGINT as bytes
+            FROM doomed d
+            LEFT JOIN artifacts a
+              ON a.repository_id = d.repository_id
+             AND a.path = 'v2/' || d.name || '/manifests/' || d.tag
+             AND a.is_deleted = false
+            "#
+        ))
+        .bind(policy.repository_id)
+        .bind(&pattern)
+        .bind(keep)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut removed = 0i64;
+        if !dry_run && matched.count > 0 {
+            let result = sqlx::query(&format!(
+                r#"
+                WITH doomed AS ({doomed}),
+                retired AS (
+                    UPDATE artifacts a SET is_deleted = true
+                    FROM doomed d
+                    WHERE a.repository_id = d.repository_id
+                      AND a.path = 'v2/' || d.name || '/manifests/' || d.tag
+                      AND a.is_deleted = false
+                )
+                DELETE FROM oci_tags
+                WHERE id IN (SELECT id FROM doomed)
+                "#
+            ))
+            .bind(policy.repository_id)
+            .bind(&pattern)
+            .bind(keep)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            removed = result.rows_affected() as i64;
+        }
+
+        Ok(Self::build_execution_result(
+            policy,
+            dry_run,
+            matched.count,
+            removed,
+            matched.bytes,
+        ))
+    }
+
     /// Validate policy config based on type.
     ///
     /// Each numeric policy type historically accepted **two** wire shapes:
@@ -1278,7 +1759,11 @@ impl LifecycleService {
     /// CLIs and several integration tests still post the flat form, so we
     /// accept either here and in `parse_i64_field`. The error message
     /// still names the canonical key for forward guidance.
-    fn validate_policy_config(&self, policy_type: &str, config: &serde_json::Value) -> Result<()> {
+    pub(crate) fn validate_policy_config(
+        &self,
+        policy_type: &str,
+        config: &serde_json::Value,
+    ) -> Result<()> {
         // Lookup helper: prefer canonical key, fall back to flat policy_type alias.
         let read_positive_i64 = |canonical: &str| -> Option<i64> {
             config
@@ -1333,6 +1818,40 @@ impl LifecycleService {
                     )
                 })?;
             }
+            "oci_untagged_days" => {
+                read_positive_i64("days").ok_or_else(|| {
+                    AppError::Validation(
+                        "oci_untagged_days requires 'days' (positive integer) in config"
+                            .to_string(),
+                    )
+                })?;
+            }
+            "cold_tier_after_days" => {
+                read_positive_i64("days").ok_or_else(|| {
+                    AppError::Validation(
+                        "cold_tier_after_days requires 'days' (positive integer) in config"
+                            .to_string(),
+                    )
+                })?;
+            }
+            "oci_tag_keep_last" => {
+                let pattern = config
+                    .get("pattern")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        AppError::Validation(
+                            "oci_tag_keep_last requires 'pattern' (string) in config".to_string(),
+                        )
+                    })?;
+                regex::Regex::new(pattern)
+                    .map_err(|e| AppError::Validation(format!("Invalid regex pattern: {}", e)))?;
+                read_positive_i64("keep").ok_or_else(|| {
+                    AppError::Validation(
+                        "oci_tag_keep_last requires 'keep' (positive integer) in config"
+                            .to_string(),
+                    )
+                })?;
+            }
             _ => {}
         }
         Ok(())
@@ -1631,6 +2150,7 @@ mod tests {
             config: json!({"keep": 5}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         };
         let err = svc.create_policy(req).await.unwrap_err();
         assert!(matches!(err, AppError::Validation(_)), "got {err:?}");
@@ -1648,6 +2168,7 @@ mod tests {
             config: json!({"quota_bytes": 1024}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         };
         let err = svc.create_policy(req).await.unwrap_err();
         assert!(matches!(err, AppError::Validation(_)), "got {err:?}");
@@ -1670,6 +2191,7 @@ mod tests {
             config: json!({"days": 90}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         };
         match svc.create_policy(req).await {
             // No live DB in the unit harness: INSERT fails as a Database error.
@@ -1680,6 +2202,139 @@ mod tests {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Label selector tests
+    // -----------------------------------------------------------------------
+
+    fn label(key: &str, value: &str) -> LabelEntry {
+        LabelEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_label_selector_empty_is_ok() {
+        assert!(validate_label_selector(None, &[]).is_ok());
+        assert!(validate_label_selector(Some(Uuid::new_v4()), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_label_selector_rejects_repository_id() {
+        let err =
+            validate_label_selector(Some(Uuid::new_v4()), &[label("env", "dev")]).unwrap_err();
+        assert!(matches!(err, AppError::Validation(msg) if msg.contains("mutually exclusive")));
+    }
+
+    #[test]
+    fn test_validate_label_selector_rejects_empty_key() {
+        let err = validate_label_selector(None, &[label("  ", "dev")]).unwrap_err();
+        assert!(matches!(err, AppError::Validation(msg) if msg.contains("non-empty 'key'")));
+    }
+
+    #[test]
+    fn test_validate_label_selector_allows_empty_value() {
+        // An empty value means "any repository carrying the key".
+        assert!(validate_label_selector(None, &[label("ephemeral", "")]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_repo_scoped_type_with_label_selector_passes_repo_guard() {
+        // max_versions needs a per-repo scope; a label selector provides one
+        // at execution time, so it must not trip the repository_id guard.
+        let svc = make_service_for_validation();
+        let req = CreateLifecyclePolicyRequest {
+            repository_id: None,
+            name: "dev-keep-5".to_string(),
+            description: None,
+            policy_type: "max_versions".to_string(),
+            config: json!({"keep": 5}),
+            priority: None,
+            cron_schedule: None,
+            label_selector: vec![label("env", "dev")],
+        };
+        match svc.create_policy(req).await {
+            Err(AppError::Database(_)) => {}
+            Ok(_) => {}
+            other => panic!("expected to pass the repo guard, got {other:?}"),
+        }
+    }
+
+    /// Listing for a repository only shows label-selected policies whose
+    /// selector matches that repository's labels.
+    #[tokio::test]
+    async fn test_list_policies_for_repo_matches_label_selector() {
+        use crate::api::handlers::test_db_helpers as tdh;
+
+        let Some(pool) = tdh::try_pool().await else {
+            return;
+        };
+        let (labelled, _, _) = tdh::create_repo(&pool, "local", "generic").await;
+        let (unlabelled, _, _) = tdh::create_repo(&pool, "local", "generic").await;
+        let key = format!("lp-{}", Uuid::new_v4().simple());
+        RepositoryLabelService::new(pool.clone())
+            .add_label(labelled, &key, "dev")
+            .await
+            .expect("label repo");
+
+        let svc = LifecycleService::new(pool.clone());
+        let policy = svc
+            .create_policy(CreateLifecyclePolicyRequest {
+                repository_id: None,
+                name: format!("{key}-keep"),
+                description: None,
+                policy_type: "max_versions".to_string(),
+                config: json!({"keep": 5}),
+                priority: None,
+                cron_schedule: None,
+                label_selector: vec![label(&key, "dev")],
+            })
+            .await
+            .expect("create policy");
+
+        let for_labelled = svc.list_policies(Some(labelled)).await;
+        let for_unlabelled = svc.list_policies(Some(unlabelled)).await;
+        let all = svc.list_policies(None).await;
+
+        let _ = svc.delete_policy(policy.id).await;
+        tdh::cleanup(&pool, labelled, Uuid::nil()).await;
+        tdh::cleanup(&pool, unlabelled, Uuid::nil()).await;
+
+        let listed = |r: Result<Vec<LifecyclePolicy>>| {
+            r.expect("list policies").iter().any(|p| p.id == policy.id)
+        };
+        assert!(listed(for_labelled), "selector matches the labelled repo");
+        assert!(!listed(for_unlabelled), "selector does not match");
+        assert!(listed(all), "unfiltered listing shows every policy");
+    }
+
+    #[test]
+    fn test_merge_execution_result_accumulates() {
+        let mut total = PolicyExecutionResult {
+            policy_id: Uuid::nil(),
+            policy_name: "labels".to_string(),
+            dry_run: true,
+            artifacts_matched: 2,
+            artifacts_removed: 0,
+            bytes_freed: 100,
+            errors: vec![],
+        };
+        let per_repo = PolicyExecutionResult {
+            policy_id: Uuid::nil(),
+            policy_name: "labels".to_string(),
+            dry_run: true,
+            artifacts_matched: 3,
+            artifacts_removed: 1,
+            bytes_freed: 50,
+            errors: vec!["repo x: boom".to_string()],
+        };
+        merge_execution_result(&mut total, per_repo);
+        assert_eq!(total.artifacts_matched, 5);
+        assert_eq!(total.artifacts_removed, 1);
+        assert_eq!(total.bytes_freed, 150);
+        assert_eq!(total.errors, vec!["repo x: boom".to_string()]);
+    }
+
     // -----------------------------------------------------------------------
     // Struct serialization tests
     // -----------------------------------------------------------------------
@@ -1699,6 +2354,7 @@ mod tests {
             last_run_at: None,
             last_run_items_removed: None,
             cron_schedule: None,
+            label_selector: vec![],
             created_at: now,
             updated_at: now,
         };
@@ -1826,6 +2482,9 @@ mod tests {
             "tag_pattern_keep",
             "tag_pattern_delete",
             "size_quota_bytes",
+            "oci_untagged_days",
+            "oci_tag_keep_last",
+            "cold_tier_after_days",
         ];
         for t in &valid_types {
             assert!(valid_types.contains(t));
@@ -1875,6 +2534,91 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // -----------------------------------------------------------------------
+    // oci_untagged_days / oci_tag_keep_last validation
+    // -----------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_validate_oci_untagged_days_valid() {
+        let svc = make_service_for_validation();
+        let config = json!({"days": 14});
+        assert!(svc
+            .validate_policy_config("oci_untagged_days", &config)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_oci_untagged_days_zero_rejected() {
+        let svc = make_service_for_validation();
+        let result = svc.validate_policy_config("oci_untagged_days", &json!({"days": 0}));
+        assert!(result.unwrap_err().to_string().contains("days"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_cold_tier_after_days() {
+        let svc = make_service_for_validation();
+        assert!(svc
+            .validate_policy_config("cold_tier_after_days", &json!({"days": 90}))
+            .is_ok());
+        let missing = svc.validate_policy_config("cold_tier_after_days", &json!({}));
+        assert!(missing.unwrap_err().to_string().contains("days"));
+        assert!(!policy_type_requires_repository_id("cold_tier_after_days"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_oci_tag_keep_last_valid() {
+        let svc = make_service_for_validation();
+        let config = json!({"pattern": "^v", "keep": 10});
+        assert!(svc
+            .validate_policy_config("oci_tag_keep_last", &config)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_oci_tag_keep_last_requires_pattern_and_keep() {
+        let svc = make_service_for_validation();
+        let missing_pattern = svc.validate_policy_config("oci_tag_keep_last", &json!({"keep": 3}));
+        assert!(missing_pattern.unwrap_err().to_string().contains("pattern"));
+
+        let missing_keep =
+            svc.validate_policy_config("oci_tag_keep_last", &json!({"pattern": "^v"}));
+        assert!(missing_keep.unwrap_err().to_string().contains("keep"));
+
+        let bad_regex =
+            svc.validate_policy_config("oci_tag_keep_last", &json!({"pattern": "(", "keep": 3}));
+        assert!(bad_regex.unwrap_err().to_string().contains("regex"));
+    }
+
+    #[test]
+    fn test_oci_policy_types_are_not_repo_scoped() {
+        assert!(!policy_type_requires_repository_id("oci_untagged_days"));
+        assert!(!policy_type_requires_repository_id("oci_tag_keep_last"));
+    }
+
+    #[test]
+    fn test_oci_untagged_filter_keeps_reachable_and_pinned_manifests() {
+        // A manifest is only untagged when neither a tag nor a tagged index
+        // (at any depth) reaches it, and a build pin always wins.
+        let sql = OCI_UNTAGGED_MANIFEST_FILTER_SQL;
+        assert!(sql.contains("a.is_deleted = false"));
+        assert!(sql.contains("$1::UUID IS NULL OR a.repository_id = $1"));
+        assert!(sql.contains("a.storage_key LIKE 'oci-manifests/%'"));
+        assert!(sql.contains("FROM oci_tags ot"));
+        assert!(sql.contains("NOT ak_oci_manifest_index_referenced("));
+        assert!(sql.contains("FROM build_artifacts ba"));
+        assert!(sql.contains("make_interval(days => $2::INT)"));
+    }
+
+    #[test]
+    fn test_oci_tag_keep_last_ranks_per_image_and_skips_build_pins() {
+        let sql = OCI_TAG_KEEP_LAST_DOOMED_SQL;
+        assert!(sql.contains("PARTITION BY t.repository_id, t.name"));
+        assert!(sql.contains("ORDER BY t.updated_at DESC"));
+        assert!(sql.contains("t.tag ~ $2"));
+        assert!(sql.contains("ot.recency > $3"));
+        assert!(sql.contains("FROM build_artifacts ba"));
+    }
+
     // -----------------------------------------------------------------------
     // Verify execute_policy match coverage for all policy types
     // -----------------------------------------------------------------------
@@ -1894,6 +2638,9 @@ mod tests {
             "tag_pattern_keep",
             "tag_pattern_delete",
             "size_quota_bytes",
+            "oci_untagged_days",
+            "oci_tag_keep_last",
+            "cold_tier_after_days",
         ];
         // These are the types handled in execute_policy match arms
         // (this list must be kept in sync manually — if a type is added to
@@ -1905,6 +2652,9 @@ mod tests {
             "tag_pattern_keep",
             "tag_pattern_delete",
             "size_quota_bytes",
+            "oci_untagged_days",
+            "oci_tag_keep_last",
+            "cold_tier_after_days",
         ];
         for t in &create_types {
             assert!(
@@ -1936,6 +2686,7 @@ mod tests {
             last_run_at: None,
             last_run_items_removed: None,
             cron_schedule: None,
+            label_selector: vec![],
             created_at: now,
             updated_at: now,
         }
@@ -2097,6 +2848,9 @@ mod tests {
             "tag_pattern_keep",
             "tag_pattern_delete",
             "size_quota_bytes",
+            "oci_untagged_days",
+            "oci_tag_keep_last",
+            "cold_tier_after_days",
         ];
         for pt in types {
             let policy = make_policy(Uuid::new_v4(), &format!("{} policy", pt), pt);
@@ -2473,6 +3227,7 @@ mod tests {
             last_run_at: None,
             last_run_items_removed: None,
             cron_schedule: Some("0 30 1 * * *".to_string()),
+            label_selector: vec![],
             created_at: now,
             updated_at: now,
         };
@@ -2905,6 +3660,9 @@ mod tests {
             ("tag_pattern_keep", PolicyType::TagPatternKeep),
             ("tag_pattern_delete", PolicyType::TagPatternDelete),
             ("size_quota_bytes", PolicyType::SizeQuotaBytes),
+            ("oci_untagged_days", PolicyType::OciUntaggedDays),
+            ("oci_tag_keep_last", PolicyType::OciTagKeepLast),
+            ("cold_tier_after_days", PolicyType::ColdTierAfterDays),
         ];
         for (wire, expected) in cases {
             let got = PolicyType::parse(wire).expect("valid wire string");
@@ -2961,6 +3719,9 @@ mod tests {
             PolicyType::TagPatternKeep,
             PolicyType::TagPatternDelete,
             PolicyType::SizeQuotaBytes,
+            PolicyType::OciUntaggedDays,
+            PolicyType::OciTagKeepLast,
+            PolicyType::ColdTierAfterDays,
         ];
         for v in variants {
             let s = v.as_wire_str();
@@ -2986,6 +3747,9 @@ mod tests {
             "tag_pattern_keep",
             "tag_pattern_delete",
             "size_quota_bytes",
+            "oci_untagged_days",
+            "oci_tag_keep_last",
+            "cold_tier_after_days",
         ];
         for s in whitelist {
             PolicyType::parse(s)
@@ -3352,6 +4116,9 @@ mod tests {
             "tag_pattern_keep",
             "tag_pattern_delete",
             "size_quota_bytes",
+            "oci_untagged_days",
+            "oci_tag_keep_last",
+            "cold_tier_after_days",
         ];
         let dispatch_variants = [
             PolicyType::MaxAgeDays,
@@ -3360,6 +4127,9 @@ mod tests {
             PolicyType::TagPatternKeep,
             PolicyType::TagPatternDelete,
             PolicyType::SizeQuotaBytes,
+            PolicyType::OciUntaggedDays,
+            PolicyType::OciTagKeepLast,
+            PolicyType::ColdTierAfterDays,
         ];
         assert_eq!(create_policy_whitelist.len(), dispatch_variants.len());
         for v in dispatch_variants {
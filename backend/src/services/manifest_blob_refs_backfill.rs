@@ -290,7 +290,8 @@ async fn select_unbackfilled_manifests(db: &PgPool) -> sqlx::Result<Vec<Backfill
 /// Blob-GC readiness gate (#1408; design from #1409 review, finding 3).
 ///
 /// Returns `true` while any *live* image manifest (a tagged non-index
-/// manifest, or a per-architecture child of a tagged index) still has
+/// manifest, or a non-index child reachable from a tagged index, directly
+/// or through nested indexes) still has
 /// zero rows in `manifest_blob_refs` — i.e. a successful backfill has not
 /// yet established the full live blob set.
 ///
@@ -311,6 +312,19 @@ async fn select_unbackfilled_manifests(db: &PgPool) -> sqlx::Result<Vec<Backfill
 pub async fn any_live_manifest_missing_refs(db: &PgPool) -> sqlx::Result<bool> {
     sqlx::query_scalar::<_, bool>(
         r#"
+        WITH RECURSIVE index_children(repository_id, digest) AS (
+            SELECT omr.repository_id, omr.child_digest
+            FROM oci_manifest_refs omr
+            JOIN oci_tags ot_parent
+              ON ot_parent.repository_id = omr.repository_id
+             AND ot_parent.manifest_digest = omr.parent_digest
+            UNION
+            SELECT omr.repository_id, omr.child_digest
+            FROM oci_manifest_refs omr
+            JOIN index_children ic
+              ON ic.repository_id = omr.repository_id
+             AND ic.digest = omr.parent_digest
+        )
         SELECT EXISTS (
             SELECT 1
             FROM (
@@ -327,12 +341,16 @@ pub async fn any_live_manifest_missing_refs(db: &PgPool) -> sqlx::Result<bool> {
                           AND omr_parent.parent_digest = ot.manifest_digest
                     )
                 UNION
-                SELECT omr.child_digest AS manifest_digest,
-                       omr.repository_id AS repository_id
-                FROM oci_manifest_refs omr
-                JOIN oci_tags ot_parent
-                  ON ot_parent.repository_id = omr.repository_id
-                 AND ot_parent.manifest_digest = omr.parent_digest
+                -- Nested indexes carry no blobs of their own; only their
+                -- image-manifest leaves need refs.
+                SELECT ic.digest AS manifest_digest,
+                       ic.repository_id AS repository_id
+                FROM index_children ic
+                WHERE NOT EXISTS (
+                        SELECT 1 FROM oci_manifest_refs nested
+                        WHERE nested.repository_id = ic.repository_id
+                          AND nested.parent_digest = ic.digest
+                    )
             ) AS live
             WHERE NOT EXISTS (
                 SELECT 1 FROM manifest_blob_refs mbr
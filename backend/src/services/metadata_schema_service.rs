@@ -0,0 +1,819 @@
+//! Per-repository metadata schemas.
+//!
+//! An admin can attach a schema to a hosted repository describing the custom
+//! properties every upload must carry (e.g. `jira_ticket` and `team`).
+//! Uploads send properties in the `X-Artifact-Properties` header (see
+//! [`crate::api::middleware::artifact_properties`]); the shared upload
+//! chokepoints validate them here before anything is stored and then merge
+//! them into `artifact_metadata.properties`.
+//!
+//! Schemas are written in JSON Schema, restricted to the keywords that make
+//! sense for flat key/value properties:
+//!
+//! * top level: `type` (`object`), `required`, `properties`,
+//!   `additionalProperties` (boolean), plus the annotations `$schema`, `$id`,
+//!   `title` and `description`;
+//! * per property: `type` (`string`, `number`, `integer`, `boolean`), `enum`,
+//!   `pattern`, `minLength`, `maxLength`, `minimum`, `maximum`, plus `title`
+//!   and `description`.
+//!
+//! Any other keyword is rejected when the schema is saved rather than being
+//! silently ignored at upload time.
+//!
+//! A repository can also carry default properties (e.g. `team`,
+//! `cost-center`). A database trigger (migration 215) stamps them onto every
+//! artifact stored in the repository, beneath any properties the upload sent,
+//! and schema validation sees the same merged set.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::{PgPool, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::middleware::artifact_properties::current_upload_properties;
+use crate::error::{AppError, Result};
+
+/// How long the in-memory copy of the schemas used by uploads is reused.
+const SNAPSHOT_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Longest accepted `pattern`.
+const MAX_PATTERN_LEN: usize = 1024;
+
+const TOP_LEVEL_KEYWORDS: &[&str] = &[
+    "$schema",
+    "$id",
+    "title",
+    "description",
+    "type",
+    "required",
+    "properties",
+    "additionalProperties",
+];
+const PROPERTY_KEYWORDS: &[&str] = &[
+    "title",
+    "description",
+    "type",
+    "enum",
+    "pattern",
+    "minLength",
+    "maxLength",
+    "minimum",
+    "maximum",
+];
+
+/// One property that failed validation.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PropertyType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+}
+
+impl PropertyType {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "string" => Some(Self::String),
+            "number" => Some(Self::Number),
+            "integer" => Some(Self::Integer),
+            "boolean" => Some(Self::Boolean),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::Boolean => "boolean",
+        }
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Integer => {
+                value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            Self::Boolean => value.is_boolean(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct PropertyRule {
+    kind: Option<PropertyType>,
+    allowed: Option<Vec<Value>>,
+    pattern: Option<Regex>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+}
+
+impl PropertyRule {
+    fn check(&self, field: &str, value: &Value, errors: &mut Vec<FieldError>) {
+        if let Some(kind) = self.kind {
+            if !kind.matches(value) {
+                errors.push(FieldError::new(
+                    field,
+                    format!("must be of type {}", kind.as_str()),
+                ));
+                return;
+            }
+        }
+        if let Some(allowed) = &self.allowed {
+            if !allowed.contains(value) {
+                let options: Vec<String> = allowed.iter().map(Value::to_string).collect();
+                errors.push(FieldError::new(
+                    field,
+                    format!("must be one of {}", options.join(", ")),
+                ));
+            }
+        }
+        if let Some(s) = value.as_str() {
+            let len = s.chars().count();
+            if let Some(min) = self.min_length.filter(|min| len < *min) {
+                errors.push(FieldError::new(
+                    field,
+                    format!("must be at least {} characters", min),
+                ));
+            }
+            if let Some(max) = self.max_length.filter(|max| len > *max) {
+                errors.push(FieldError::new(
+                    field,
+                    format!("must be at most {} characters", max),
+                ));
+            }
+            if let Some(pattern) = &self.pattern {
+                if !pattern.is_match(s) {
+                    errors.push(FieldError::new(
+                        field,
+                        format!("must match pattern {}", pattern.as_str()),
+                    ));
+                }
+            }
+        }
+        if let Some(n) = value.as_f64() {
+            if let Some(min) = self.minimum.filter(|min| n < *min) {
+                errors.push(FieldError::new(field, format!("must be >= {}", min)));
+            }
+            if let Some(max) = self.maximum.filter(|max| n > *max) {
+                errors.push(FieldError::new(field, format!("must be <= {}", max)));
+            }
+        }
+    }
+}
+
+/// A compiled repository metadata schema.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataSchema {
+    required: Vec<String>,
+    properties: BTreeMap<String, PropertyRule>,
+    additional_properties: bool,
+}
+
+fn invalid(message: impl Into<String>) -> AppError {
+    AppError::Validation(format!("Invalid metadata schema: {}", message.into()))
+}
+
+fn non_negative(value: &Value, path: &str) -> Result<usize> {
+    value
+        .as_u64()
+        .map(|n| n as usize)
+        .ok_or_else(|| invalid(format!("{} must be a non-negative integer", path)))
+}
+
+fn number(value: &Value, path: &str) -> Result<f64> {
+    value
+        .as_f64()
+        .ok_or_else(|| invalid(format!("{} must be a number", path)))
+}
+
+fn compile_property(name: &str, schema: &Value) -> Result<PropertyRule> {
+    let Value::Object(schema) = schema else {
+        return Err(invalid(format!("properties.{} must be an object", name)));
+    };
+    let mut rule = PropertyRule::default();
+    for (keyword, value) in schema {
+        let path = format!("properties.{}.{}", name, keyword);
+        match keyword.as_str() {
+            "title" | "description" => {}
+            "type" => {
+                rule.kind = Some(value.as_str().and_then(PropertyType::parse).ok_or_else(
+                    || {
+                        invalid(format!(
+                            "{} must be one of string, number, integer, boolean",
+                            path
+                        ))
+                    },
+                )?);
+            }
+            "enum" => match value {
+                Value::Array(values) if !values.is_empty() => rule.allowed = Some(values.clone()),
+                _ => return Err(invalid(format!("{} must be a non-empty array", path))),
+            },
+            "pattern" => {
+                let pattern = value
+                    .as_str()
+                    .filter(|p| p.len() <= MAX_PATTERN_LEN)
+                    .ok_or_else(|| {
+                        invalid(format!(
+                            "{} must be a string of at most {} bytes",
+                            path, MAX_PATTERN_LEN
+                        ))
+                    })?;
+                rule.pattern = Some(
+                    Regex::new(pattern)
+                        .map_err(|e| invalid(format!("{} is not a valid regex: {}", path, e)))?,
+                );
+            }
+            "minLength" => rule.min_length = Some(non_negative(value, &path)?),
+            "maxLength" => rule.max_length = Some(non_negative(value, &path)?),
+            "minimum" => rule.minimum = Some(number(value, &path)?),
+            "maximum" => rule.maximum = Some(number(value, &path)?),
+            _ => {
+                return Err(invalid(format!(
+                    "unsupported keyword '{}' (supported: {})",
+                    path,
+                    PROPERTY_KEYWORDS.join(", ")
+                )))
+            }
+        }
+    }
+    Ok(rule)
+}
+
+impl MetadataSchema {
+    /// Compile a stored or submitted schema document.
+    pub fn compile(schema: &Value) -> Result<Self> {
+        let Value::Object(schema) = schema else {
+            return Err(invalid("the schema must be a JSON object"));
+        };
+        let mut compiled = MetadataSchema {
+            additional_properties: true,
+            ..Default::default()
+        };
+        for (keyword, value) in schema {
+            match keyword.as_str() {
+                "$schema" | "$id" | "title" | "description" => {}
+                "type" => {
+                    if value.as_str() != Some("object") {
+                        return Err(invalid("type must be \"object\""));
+                    }
+                }
+                "required" => {
+                    let names = value
+                        .as_array()
+                        .and_then(|names| {
+                            names
+                                .iter()
+                                .map(|n| n.as_str().map(str::to_string))
+                                .collect::<Option<Vec<_>>>()
+                        })
+                        .ok_or_else(|| invalid("required must be an array of strings"))?;
+                    compiled.required = names;
+                }
+                "properties" => {
+                    let Value::Object(properties) = value else {
+                        return Err(invalid("properties must be an object"));
+                    };
+                    for (name, property) in properties {
+                        compiled
+                            .properties
+                            .insert(name.clone(), compile_property(name, property)?);
+                    }
+                }
+                "additionalProperties" => {
+                    compiled.additional_properties = value
+                        .as_bool()
+                        .ok_or_else(|| invalid("additionalProperties must be a boolean"))?;
+                }
+                _ => {
+                    return Err(invalid(format!(
+                        "unsupported keyword '{}' (supported: {})",
+                        keyword,
+                        TOP_LEVEL_KEYWORDS.join(", ")
+                    )))
+                }
+            }
+        }
+        Ok(compiled)
+    }
+
+    /// Every problem with `properties`, in field order.
+    pub fn validate(&self, properties: &Map<String, Value>) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        for name in &self.required {
+            if properties.get(name).is_none_or(Value::is_null) {
+                errors.push(FieldError::new(name, "is required"));
+            }
+        }
+        for (name, value) in properties {
+            if value.is_null() {
+                continue;
+            }
+            match self.properties.get(name) {
+                Some(rule) => rule.check(name, value, &mut errors),
+                None if !self.additional_properties => {
+                    errors.push(FieldError::new(name, "is not allowed by the schema"))
+                }
+                None => {}
+            }
+        }
+        errors
+    }
+}
+
+/// Format field errors as one client-facing message.
+pub fn describe_errors(errors: &[FieldError]) -> String {
+    let fields: Vec<String> = errors
+        .iter()
+        .map(|e| format!("{}: {}", e.field, e.message))
+        .collect();
+    format!(
+        "Artifact metadata does not satisfy the repository schema: {}",
+        fields.join("; ")
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// A repository's stored schema.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RepositoryMetadataSchema {
+    pub repository_id: Uuid,
+    #[schema(value_type = Object)]
+    pub schema: Value,
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn schema_from_row(row: &sqlx::postgres::PgRow) -> RepositoryMetadataSchema {
+    RepositoryMetadataSchema {
+        repository_id: row.get("repository_id"),
+        schema: row.get("schema"),
+        updated_by: row.get("updated_by"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+pub async fn get(db: &PgPool, repository_id: Uuid) -> Result<Option<RepositoryMetadataSchema>> {
+    let row = sqlx::query(
+        "SELECT repository_id, schema, updated_by, created_at, updated_at \
+         FROM repository_metadata_schemas WHERE repository_id = $1",
+    )
+    .bind(repository_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(row.as_ref().map(schema_from_row))
+}
+
+/// Validate and store a schema, replacing any existing one.
+pub async fn set(
+    db: &PgPool,
+    repository_id: Uuid,
+    schema: &Value,
+    updated_by: Uuid,
+) -> Result<RepositoryMetadataSchema> {
+    MetadataSchema::compile(schema)?;
+    let row = sqlx::query(
+        "INSERT INTO repository_metadata_schemas (repository_id, schema, updated_by) \
+         VALUES ($1, $2, $3) \
+         ON CONFLICT (repository_id) DO UPDATE \
+         SET schema = EXCLUDED.schema, updated_by = EXCLUDED.updated_by, updated_at = NOW() \
+         RETURNING repository_id, schema, updated_by, created_at, updated_at",
+    )
+    .bind(repository_id)
+    .bind(schema)
+    .bind(updated_by)
+    .fetch_one(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    invalidate_cache();
+    Ok(schema_from_row(&row))
+}
+
+pub async fn delete(db: &PgPool, repository_id: Uuid) -> Result<()> {
+    let result = sqlx::query("DELETE FROM repository_metadata_schemas WHERE repository_id = $1")
+        .bind(repository_id)
+        .execute(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "Repository has no metadata schema".to_string(),
+        ));
+    }
+    invalidate_cache();
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Default properties
+// ---------------------------------------------------------------------------
+
+/// Properties stamped onto every artifact in a repository.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RepositoryDefaultProperties {
+    pub repository_id: Uuid,
+    #[schema(value_type = Object)]
+    pub properties: Map<String, Value>,
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn defaults_from_row(row: &sqlx::postgres::PgRow) -> RepositoryDefaultProperties {
+    let properties: Value = row.get("properties");
+    RepositoryDefaultProperties {
+        repository_id: row.get("repository_id"),
+        properties: match properties {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        },
+        updated_by: row.get("updated_by"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/// `defaults` overlaid with `properties`: upload values win key by key, the
+/// same merge the database trigger applies.
+pub fn with_defaults(
+    defaults: &Map<String, Value>,
+    properties: &Map<String, Value>,
+) -> Map<String, Value> {
+    let mut merged = defaults.clone();
+    merged.extend(properties.iter().map(|(k, v)| (k.clone(), v.clone())));
+    merged
+}
+
+pub async fn get_defaults(
+    db: &PgPool,
+    repository_id: Uuid,
+) -> Result<Option<RepositoryDefaultProperties>> {
+    let row = sqlx::query(
+        "SELECT repository_id, properties, updated_by, created_at, updated_at \
+         FROM repository_default_properties WHERE repository_id = $1",
+    )
+    .bind(repository_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(row.as_ref().map(defaults_from_row))
+}
+
+async fn default_properties(db: &PgPool, repository_id: Uuid) -> Result<Map<String, Value>> {
+    Ok(get_defaults(db, repository_id)
+        .await?
+        .map(|d| d.properties)
+        .unwrap_or_default())
+}
+
+/// Validate and store a repository's default properties, replacing any
+/// existing set. Values the repository's schema would reject are refused
+/// here rather than failing every later upload.
+pub async fn set_defaults(
+    db: &PgPool,
+    repository_id: Uuid,
+    properties: &Map<String, Value>,
+    updated_by: Uuid,
+) -> Result<RepositoryDefaultProperties> {
+    if properties.is_empty() {
+        return Err(AppError::Validation(
+            "Default properties must not be empty; delete them instead".to_string(),
+        ));
+    }
+    crate::api::middleware::artifact_properties::check_flat_properties(properties)
+        .map_err(|e| AppError::Validation(format!("Default property {}", e)))?;
+    if let Some(stored) = get(db, repository_id).await? {
+        let errors: Vec<FieldError> = MetadataSchema::compile(&stored.schema)?
+            .validate(properties)
+            .into_iter()
+            .filter(|e| properties.contains_key(&e.field))
+            .collect();
+        if !errors.is_empty() {
+            return Err(AppError::Validation(describe_errors(&errors)));
+        }
+    }
+
+    let row = sqlx::query(
+        "INSERT INTO repository_default_properties (repository_id, properties, updated_by) \
+         VALUES ($1, $2, $3) \
+         ON CONFLICT (repository_id) DO UPDATE \
+         SET properties = EXCLUDED.properties, updated_by = EXCLUDED.updated_by, \
+             updated_at = NOW() \
+         RETURNING repository_id, properties, updated_by, created_at, updated_at",
+    )
+    .bind(repository_id)
+    .bind(Value::Object(properties.clone()))
+    .bind(updated_by)
+    .fetch_one(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(defaults_from_row(&row))
+}
+
+pub async fn delete_defaults(db: &PgPool, repository_id: Uuid) -> Result<()> {
+    let result = sqlx::query("DELETE FROM repository_default_properties WHERE repository_id = $1")
+        .bind(repository_id)
+        .execute(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "Repository has no default properties".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Stamp the current defaults onto the repository's existing artifacts,
+/// keeping any value an artifact already has for a key. Returns the number of
+/// artifacts changed.
+pub async fn apply_defaults_to_existing(db: &PgPool, repository_id: Uuid) -> Result<u64> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    // Artifacts without a metadata row get one; the insert trigger fills in
+    // the defaults.
+    let inserted = sqlx::query(
+        "INSERT INTO artifact_metadata (artifact_id, format, metadata, properties) \
+         SELECT a.id, r.format::text, '{}'::jsonb, '{}'::jsonb \
+         FROM artifacts a JOIN repositories r ON r.id = a.repository_id \
+         WHERE a.repository_id = $1 AND a.is_deleted = false \
+         ON CONFLICT (artifact_id) DO NOTHING",
+    )
+    .bind(repository_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let updated = sqlx::query(
+        "UPDATE artifact_metadata m \
+         SET properties = d.properties || m.properties \
+         FROM artifacts a, repository_default_properties d \
+         WHERE m.artifact_id = a.id AND a.repository_id = $1 AND a.is_deleted = false \
+           AND d.repository_id = a.repository_id \
+           AND jsonb_typeof(m.properties) = 'object' \
+           AND NOT m.properties ?& ARRAY(SELECT jsonb_object_keys(d.properties))",
+    )
+    .bind(repository_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(inserted.rows_affected() + updated.rows_affected())
+}
+
+// ---------------------------------------------------------------------------
+// Enforcement
+// ---------------------------------------------------------------------------
+
+type SchemaSnapshot = HashMap<Uuid, Arc<MetadataSchema>>;
+
+fn snapshot_cache() -> &'static RwLock<Option<(Instant, Arc<SchemaSnapshot>)>> {
+    static CACHE: OnceLock<RwLock<Option<(Instant, Arc<SchemaSnapshot>)>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// Drop the cached schemas after they change. Other replicas pick the change
+/// up within [`SNAPSHOT_TTL`].
+pub fn invalidate_cache() {
+    match snapshot_cache().write() {
+        Ok(mut cache) => *cache = None,
+        Err(poisoned) => *poisoned.into_inner() = None,
+    }
+}
+
+async fn cached_schemas(db: &PgPool) -> Result<Arc<SchemaSnapshot>> {
+    if let Ok(cache) = snapshot_cache().read() {
+        if let Some((loaded, schemas)) = cache.as_ref() {
+            if loaded.elapsed() < SNAPSHOT_TTL {
+                return Ok(schemas.clone());
+            }
+        }
+    }
+    let rows = sqlx::query("SELECT repository_id, schema FROM repository_metadata_schemas")
+        .fetch_all(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let mut schemas = HashMap::with_capacity(rows.len());
+    for row in rows {
+        let repository_id: Uuid = row.get("repository_id");
+        let schema: Value = row.get("schema");
+        match MetadataSchema::compile(&schema) {
+            Ok(compiled) => {
+                schemas.insert(repository_id, Arc::new(compiled));
+            }
+            // Stored schemas were compiled on write; a failure here means the
+            // row was edited by hand. Fail closed for that repository.
+            Err(e) => {
+                return Err(AppError::Internal(format!(
+                    "Stored metadata schema for repository {} is invalid: {}",
+                    repository_id, e
+                )))
+            }
+        }
+    }
+    let schemas = Arc::new(schemas);
+    let mut cache = match snapshot_cache().write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *cache = Some((Instant::now(), schemas.clone()));
+    Ok(schemas)
+}
+
+/// Reject an upload whose properties do not satisfy the repository's schema.
+pub async fn check_upload(db: &PgPool, repository_id: Uuid) -> Result<()> {
+    let schemas = cached_schemas(db).await?;
+    let Some(schema) = schemas.get(&repository_id) else {
+        return Ok(());
+    };
+    let properties = with_defaults(
+        &default_properties(db, repository_id).await?,
+        &current_upload_properties().unwrap_or_default(),
+    );
+    let errors = schema.validate(&properties);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(describe_errors(&errors)))
+    }
+}
+
+/// The upload's properties as a JSON object, empty when the request carried
+/// none. For handlers that write `artifact_metadata.properties` themselves.
+pub fn upload_properties() -> Value {
+    Value::Object(
+        current_upload_properties()
+            .map(|p| p.as_ref().clone())
+            .unwrap_or_default(),
+    )
+}
+
+/// Merge the upload's properties into the artifact's metadata row. A no-op
+/// when the request carried none.
+pub async fn record_upload_properties(db: &PgPool, artifact_id: Uuid) {
+    if let Some(properties) = current_upload_properties() {
+        record_properties(db, artifact_id, &properties).await;
+    }
+}
+
+/// Merge `properties` into the artifact's metadata row. Best-effort: the
+/// artifact row already exists, so a failure is logged rather than failing the
+/// upload.
+pub async fn record_properties(db: &PgPool, artifact_id: Uuid, properties: &Map<String, Value>) {
+    if properties.is_empty() {
+        return;
+    }
+    let result = sqlx::query(
+        "INSERT INTO artifact_metadata (artifact_id, format, metadata, properties) \
+         SELECT a.id, r.format::text, '{}'::jsonb, $2 \
+         FROM artifacts a JOIN repositories r ON r.id = a.repository_id \
+         WHERE a.id = $1 \
+         ON CONFLICT (artifact_id) DO UPDATE \
+         SET properties = artifact_metadata.properties || EXCLUDED.properties",
+    )
+    .bind(artifact_id)
+    .bind(Value::Object(properties.clone()))
+    .execute(db)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(
+            artifact_id = %artifact_id,
+            "Failed to record upload properties: {}",
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn props(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    fn release_schema() -> MetadataSchema {
+        MetadataSchema::compile(&json!({
+            "type": "object",
+            "required": ["jira_ticket", "team"],
+            "properties": {
+                "jira_ticket": {"type": "string", "pattern": "^[A-Z]+-[0-9]+$"},
+                "team": {"type": "string", "enum": ["payments", "platform"]},
+                "build": {"type": "integer", "minimum": 1}
+            },
+            "additionalProperties": false
+        }))
+        .expect("schema compiles")
+    }
+
+    #[test]
+    fn test_valid_properties_pass() {
+        let errors = release_schema().validate(&props(json!({
+            "jira_ticket": "REL-1042",
+            "team": "payments",
+            "build": 7
+        })));
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_field_level_errors() {
+        let errors = release_schema().validate(&props(json!({
+            "jira_ticket": "rel 1042",
+            "build": 0,
+            "owner": "me"
+        })));
+        let mut fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        fields.sort_unstable();
+        assert_eq!(fields, vec!["build", "jira_ticket", "owner", "team"]);
+        assert_eq!(errors[0], FieldError::new("team", "is required"));
+        assert!(describe_errors(&errors).contains("team: is required"));
+    }
+
+    #[test]
+    fn test_missing_properties_reported_as_required() {
+        let errors = release_schema().validate(&Map::new());
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.message == "is required"));
+    }
+
+    #[test]
+    fn test_type_mismatch_skips_other_checks() {
+        let errors = release_schema().validate(&props(json!({
+            "jira_ticket": 42,
+            "team": "payments"
+        })));
+        assert_eq!(
+            errors,
+            vec![FieldError::new("jira_ticket", "must be of type string")]
+        );
+    }
+
+    #[test]
+    fn test_compile_rejects_unsupported_keywords() {
+        assert!(MetadataSchema::compile(&json!({"oneOf": []})).is_err());
+        assert!(MetadataSchema::compile(&json!({"type": "array"})).is_err());
+        assert!(
+            MetadataSchema::compile(&json!({"properties": {"a": {"type": "object"}}})).is_err()
+        );
+        assert!(MetadataSchema::compile(&json!({"properties": {"a": {"pattern": "("}}})).is_err());
+        assert!(MetadataSchema::compile(&json!({"required": "team"})).is_err());
+        assert!(MetadataSchema::compile(&json!({})).is_ok());
+    }
+
+    #[test]
+    fn test_defaults_fill_required_properties() {
+        let defaults = props(json!({"team": "payments", "cost-center": "cc-1"}));
+        let upload = props(json!({"jira_ticket": "REL-7", "cost-center": "cc-2"}));
+        let merged = with_defaults(&defaults, &upload);
+        assert_eq!(merged["team"], "payments");
+        assert_eq!(merged["cost-center"], "cc-2");
+        assert_eq!(merged["jira_ticket"], "REL-7");
+
+        let schema = MetadataSchema::compile(&json!({
+            "required": ["jira_ticket", "team"],
+            "properties": {"team": {"type": "string"}}
+        }))
+        .unwrap();
+        assert!(schema.validate(&upload).iter().any(|e| e.field == "team"));
+        assert!(schema.validate(&merged).is_empty());
+    }
+}
@@ -0,0 +1,506 @@
+//! Optional Prometheus remote-write export of business metrics.
+//!
+//! `/metrics` only helps when something scrapes it. Instances without a
+//! Prometheus deployment can instead push per-repository business metrics to
+//! any remote-write receiver (Prometheus with `--web.enable-remote-write-receiver`,
+//! Mimir, Cortex, Thanos Receive, VictoriaMetrics) and keep their history
+//! there. Export is off unless `AK_METRICS_REMOTE_WRITE_URL` is set.
+//!
+//! Every interval the exporter reads, per repository:
+//!
+//! - `ak_repository_uploads_total` — artifacts ever uploaded (live + soft-deleted);
+//! - `ak_repository_downloads_total` — recorded hosted downloads;
+//! - `ak_repository_artifacts` — live artifacts;
+//! - `ak_repository_storage_bytes` — logical bytes of live artifacts,
+//!
+//! from the database (so the values are cluster-wide, not per replica) and
+//! pushes them as one remote-write 1.0 request. Sample timestamps are aligned
+//! to the interval boundary and the collect-and-push runs under a cluster
+//! lock, so replicas pushing in the same interval reuse one timestamp instead
+//! of interleaving extra points. A receiver may reject such a repeat as a
+//! duplicate sample; that is logged and otherwise harmless.
+//!
+//! Configuration (environment):
+//!
+//! | Variable | Default | Meaning |
+//! |---|---|---|
+//! | `AK_METRICS_REMOTE_WRITE_URL` | unset | Receiver URL; unset disables export |
+//! | `AK_METRICS_REMOTE_WRITE_INTERVAL_SECS` | 300 | Push interval (min 15) |
+//! | `AK_METRICS_REMOTE_WRITE_USERNAME` / `_PASSWORD` | unset | Basic auth |
+//! | `AK_METRICS_REMOTE_WRITE_BEARER_TOKEN` | unset | Bearer auth (wins over basic) |
+//! | `AK_METRICS_REMOTE_WRITE_TENANT` | unset | Sent as `X-Scope-OrgID` (Mimir/Cortex) |
+//! | `AK_METRICS_REMOTE_WRITE_LABELS` | unset | Extra labels, `k=v,k2=v2` |
+
+use std::time::Duration;
+
+use prost::Message;
+use sqlx::PgPool;
+use tokio::time::{interval, MissedTickBehavior};
+
+use crate::error::{AppError, Result};
+use crate::services::cluster_lock::{ClusterLock, PgAdvisoryLock};
+use crate::services::metrics_service::record_metrics_remote_write;
+
+/// Generated remote-write protobuf types (`proto/prometheus_remote.proto`).
+pub mod pb {
+    include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
+}
+
+/// Advisory-lock class serializing pushes across replicas.
+pub const METRICS_REMOTE_WRITE_LOCK_CLASS: i32 = 0x5257; // "RW"
+
+const DEFAULT_INTERVAL_SECS: u64 = 300;
+const MIN_INTERVAL_SECS: u64 = 15;
+const PUSH_TIMEOUT_SECS: u64 = 30;
+
+/// How the exporter authenticates to the receiver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteWriteAuth {
+    None,
+    Basic { username: String, password: String },
+    Bearer(String),
+}
+
+/// Remote-write exporter settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteWriteConfig {
+    pub url: String,
+    pub interval: Duration,
+    pub auth: RemoteWriteAuth,
+    pub tenant: Option<String>,
+    /// Labels added to every series, sorted by name.
+    pub external_labels: Vec<(String, String)>,
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl RemoteWriteConfig {
+    /// Load settings from the environment. `None` when no URL is configured.
+    pub fn from_env() -> Option<Self> {
+        let url = non_empty_env("AK_METRICS_REMOTE_WRITE_URL")?;
+        let interval_secs = non_empty_env("AK_METRICS_REMOTE_WRITE_INTERVAL_SECS")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_INTERVAL_SECS)
+            .max(MIN_INTERVAL_SECS);
+        let auth = if let Some(token) = non_empty_env("AK_METRICS_REMOTE_WRITE_BEARER_TOKEN") {
+            RemoteWriteAuth::Bearer(token)
+        } else if let Some(username) = non_empty_env("AK_METRICS_REMOTE_WRITE_USERNAME") {
+            RemoteWriteAuth::Basic {
+                username,
+                password: std::env::var("AK_METRICS_REMOTE_WRITE_PASSWORD").unwrap_or_default(),
+            }
+        } else {
+            RemoteWriteAuth::None
+        };
+        let external_labels = non_empty_env("AK_METRICS_REMOTE_WRITE_LABELS")
+            .map(|raw| parse_external_labels(&raw))
+            .unwrap_or_default();
+
+        Some(Self {
+            url,
+            interval: Duration::from_secs(interval_secs),
+            auth,
+            tenant: non_empty_env("AK_METRICS_REMOTE_WRITE_TENANT"),
+            external_labels,
+        })
+    }
+}
+
+fn is_valid_label_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with("__")
+}
+
+/// Parse `k=v,k2=v2` into sorted external labels. Malformed entries, reserved
+/// (`__`-prefixed) names and names the exporter sets itself are skipped with
+/// a warning.
+pub fn parse_external_labels(raw: &str) -> Vec<(String, String)> {
+    let mut labels: Vec<(String, String)> = Vec::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let Some((name, value)) = entry.split_once('=') else {
+            tracing::warn!("Ignoring remote-write label without '=': {}", entry);
+            continue;
+        };
+        let name = name.trim();
+        if !is_valid_label_name(name) || matches!(name, "repository" | "format") {
+            tracing::warn!("Ignoring invalid remote-write label name: {}", name);
+            continue;
+        }
+        labels.retain(|(n, _)| n != name);
+        labels.push((name.to_string(), value.trim().to_string()));
+    }
+    labels.sort();
+    labels
+}
+
+/// Business metrics for one repository.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct RepositoryMetrics {
+    pub repository_key: String,
+    pub format: String,
+    pub uploads_total: i64,
+    pub downloads_total: i64,
+    pub artifacts: i64,
+    pub storage_bytes: i64,
+}
+
+/// Read current per-repository metrics.
+pub async fn collect_repository_metrics(db: &PgPool) -> Result<Vec<RepositoryMetrics>> {
+    sqlx::query_as(
+        r#"
+        WITH uploads AS (
+            SELECT repository_id,
+                   COUNT(*) AS uploads_total,
+                   COUNT(*) FILTER (WHERE is_deleted = false) AS artifacts,
+                   COALESCE(SUM(size_bytes) FILTER (WHERE is_deleted = false), 0)::BIGINT
+                       AS storage_bytes
+            FROM artifacts
+            GROUP BY repository_id
+        ),
+        downloads AS (
+            SELECT a.repository_id, COUNT(*) AS downloads_total
+            FROM download_statistics ds
+            JOIN artifacts a ON a.id = ds.artifact_id
+            GROUP BY a.repository_id
+        )
+        SELECT r.key AS repository_key,
+               r.format::text AS format,
+               COALESCE(u.uploads_total, 0) AS uploads_total,
+               COALESCE(d.downloads_total, 0) AS downloads_total,
+               COALESCE(u.artifacts, 0) AS artifacts,
+               COALESCE(u.storage_bytes, 0) AS storage_bytes
+        FROM repositories r
+        LEFT JOIN uploads u ON u.repository_id = r.id
+        LEFT JOIN downloads d ON d.repository_id = r.id
+        ORDER BY r.key
+        "#,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Build the remote-write request for `rows`, one sample per series at
+/// `timestamp_ms`.
+pub fn build_write_request(
+    rows: &[RepositoryMetrics],
+    external_labels: &[(String, String)],
+    timestamp_ms: i64,
+) -> pb::WriteRequest {
+    let mut timeseries = Vec::with_capacity(rows.len() * 4);
+    for row in rows {
+        let series = [
+            ("ak_repository_uploads_total", row.uploads_total),
+            ("ak_repository_downloads_total", row.downloads_total),
+            ("ak_repository_artifacts", row.artifacts),
+            ("ak_repository_storage_bytes", row.storage_bytes),
+        ];
+        for (name, value) in series {
+            let mut labels: Vec<pb::Label> = [
+                ("__name__", name),
+                ("format", row.format.as_str()),
+                ("repository", row.repository_key.as_str()),
+            ]
+            .into_iter()
+            .chain(
+                external_labels
+                    .iter()
+                    .map(|(n, v)| (n.as_str(), v.as_str())),
+            )
+            .map(|(name, value)| pb::Label {
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+            labels.sort_by(|a, b| a.name.cmp(&b.name));
+            timeseries.push(pb::TimeSeries {
+                labels,
+                samples: vec![pb::Sample {
+                    value: value as f64,
+                    timestamp: timestamp_ms,
+                }],
+            });
+        }
+    }
+    pb::WriteRequest { timeseries }
+}
+
+/// Snappy block-format encoding using literal elements only.
+///
+/// Remote write requires the block format (not the framed stream format).
+/// A literal-only block is valid snappy that every decoder accepts; the
+/// payload is a few kilobytes of protobuf per push, so skipping
+/// back-reference compression costs little and avoids a codec dependency.
+pub fn snappy_encode(input: &[u8]) -> Vec<u8> {
+    const MAX_LITERAL: usize = 1 << 16;
+    let mut out = Vec::with_capacity(input.len() + input.len() / MAX_LITERAL * 3 + 8);
+
+    let mut len = input.len() as u64;
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+
+    for chunk in input.chunks(MAX_LITERAL) {
+        let n = chunk.len() - 1;
+        if n < 60 {
+            out.push((n as u8) << 2);
+        } else if n < 1 << 8 {
+            out.push(60 << 2);
+            out.push(n as u8);
+        } else {
+            out.push(61 << 2);
+            out.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        out.extend_from_slice(chunk);
+    }
+    out
+}
+
+/// Timestamp (ms) of the start of the interval containing `now_ms`.
+fn aligned_timestamp_ms(now_ms: i64, interval: Duration) -> i64 {
+    let step = interval.as_millis().max(1) as i64;
+    now_ms - now_ms.rem_euclid(step)
+}
+
+async fn push(
+    client: &reqwest::Client,
+    config: &RemoteWriteConfig,
+    request: &pb::WriteRequest,
+) -> Result<()> {
+    let body = snappy_encode(&request.encode_to_vec());
+    let mut req = client
+        .post(&config.url)
+        .header("Content-Type", "application/x-protobuf")
+        .header("Content-Encoding", "snappy")
+        .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+        .header(
+            "User-Agent",
+            concat!("artifact-keeper/", env!("CARGO_PKG_VERSION")),
+        )
+        .body(body);
+    req = match &config.auth {
+        RemoteWriteAuth::None => req,
+        RemoteWriteAuth::Basic { username, password } => req.basic_auth(username, Some(password)),
+        RemoteWriteAuth::Bearer(token) => req.bearer_auth(token),
+    };
+    if let Some(tenant) = &config.tenant {
+        req = req.header("X-Scope-OrgID", tenant);
+    }
+
+    let resp = req
+        .send()
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Remote write request failed: {}", e)))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let detail = resp.text().await.unwrap_or_default();
+        return Err(AppError::BadGateway(format!(
+            "Remote write rejected with {}: {}",
+            status,
+            detail.chars().take(512).collect::<String>()
+        )));
+    }
+    Ok(())
+}
+
+/// Collect and push one round of metrics. Returns the number of series sent,
+/// or `None` when another replica held the push lock.
+async fn export_once(
+    db: &PgPool,
+    lock: &PgAdvisoryLock,
+    client: &reqwest::Client,
+    config: &RemoteWriteConfig,
+) -> Result<Option<usize>> {
+    let Some(lease) = lock.try_acquire(METRICS_REMOTE_WRITE_LOCK_CLASS, 0).await? else {
+        return Ok(None);
+    };
+    let result = async {
+        let rows = collect_repository_metrics(db).await?;
+        let timestamp =
+            aligned_timestamp_ms(chrono::Utc::now().timestamp_millis(), config.interval);
+        let request = build_write_request(&rows, &config.external_labels, timestamp);
+        push(client, config, &request).await?;
+        Ok(request.timeseries.len())
+    }
+    .await;
+    lease.release().await;
+    result.map(Some)
+}
+
+/// Start the remote-write exporter when `AK_METRICS_REMOTE_WRITE_URL` is set.
+pub fn start_exporter(db: PgPool) {
+    let Some(config) = RemoteWriteConfig::from_env() else {
+        return;
+    };
+    // The receiver is operator-configured, so it may live on a private
+    // network like the other internal-service endpoints.
+    let client = match crate::services::http_client::internal_service_client_builder()
+        .timeout(Duration::from_secs(PUSH_TIMEOUT_SECS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("Metrics remote write disabled: HTTP client error: {}", e);
+            return;
+        }
+    };
+    tracing::info!(
+        interval_secs = config.interval.as_secs(),
+        external_labels = config.external_labels.len(),
+        "Metrics remote write enabled"
+    );
+
+    tokio::spawn(async move {
+        let lock = PgAdvisoryLock::new(db.clone());
+        let mut ticker = interval(config.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match export_once(&db, &lock, &client, &config).await {
+                Ok(Some(series)) => {
+                    record_metrics_remote_write("success", series);
+                    tracing::debug!(series, "Pushed metrics via remote write");
+                }
+                Ok(None) => {
+                    tracing::debug!("Metrics remote write skipped: another replica is pushing");
+                }
+                Err(e) => {
+                    record_metrics_remote_write("failure", 0);
+                    tracing::warn!("Metrics remote write failed: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(key: &str) -> RepositoryMetrics {
+        RepositoryMetrics {
+            repository_key: key.to_string(),
+            format: "npm".to_string(),
+            uploads_total: 12,
+            downloads_total: 340,
+            artifacts: 10,
+            storage_bytes: 4096,
+        }
+    }
+
+    /// Reference decoder for the literal-only subset `snappy_encode` emits.
+    fn snappy_decode_literals(mut input: &[u8]) -> Vec<u8> {
+        let mut len = 0usize;
+        let mut shift = 0;
+        loop {
+            let b = input[0];
+            input = &input[1..];
+            len |= ((b & 0x7f) as usize) << shift;
+            shift += 7;
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut out = Vec::with_capacity(len);
+        while !input.is_empty() {
+            let tag = input[0];
+            assert_eq!(tag & 0x03, 0, "only literal elements are expected");
+            let (n, header) = match tag >> 2 {
+                60 => (input[1] as usize, 2),
+                61 => (u16::from_le_bytes([input[1], input[2]]) as usize, 3),
+                n => (n as usize, 1),
+            };
+            out.extend_from_slice(&input[header..header + n + 1]);
+            input = &input[header + n + 1..];
+        }
+        assert_eq!(out.len(), len);
+        out
+    }
+
+    #[test]
+    fn test_snappy_encode_small_inputs() {
+        assert_eq!(snappy_encode(b""), vec![0]);
+        assert_eq!(snappy_encode(b"abc"), vec![3, 2 << 2, b'a', b'b', b'c']);
+    }
+
+    #[test]
+    fn test_snappy_encode_round_trips_all_literal_sizes() {
+        for size in [59, 60, 61, 255, 256, 257, 65_536, 65_537, 200_000] {
+            let input: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+            assert_eq!(
+                snappy_decode_literals(&snappy_encode(&input)),
+                input,
+                "size {}",
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_write_request_series_and_sorted_labels() {
+        let external = parse_external_labels("instance=prod,cluster=eu-1");
+        let req = build_write_request(&[row("npm-local")], &external, 1_700_000_000_000);
+        assert_eq!(req.timeseries.len(), 4);
+
+        let downloads = req
+            .timeseries
+            .iter()
+            .find(|ts| ts.labels[0].value == "ak_repository_downloads_total")
+            .unwrap();
+        let names: Vec<&str> = downloads.labels.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["__name__", "cluster", "format", "instance", "repository"]
+        );
+        assert_eq!(downloads.samples.len(), 1);
+        assert_eq!(downloads.samples[0].value, 340.0);
+        assert_eq!(downloads.samples[0].timestamp, 1_700_000_000_000);
+    }
+
+    #[test]
+    fn test_write_request_encodes() {
+        let req = build_write_request(&[row("a"), row("b")], &[], 1);
+        let decoded = pb::WriteRequest::decode(req.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, req);
+        assert!(build_write_request(&[], &[], 1).timeseries.is_empty());
+    }
+
+    #[test]
+    fn test_parse_external_labels() {
+        assert_eq!(
+            parse_external_labels(" instance = prod , env=staging,env=prod "),
+            vec![
+                ("env".to_string(), "prod".to_string()),
+                ("instance".to_string(), "prod".to_string()),
+            ]
+        );
+        assert!(parse_external_labels("noequals,__name__=x,1bad=y,repository=z").is_empty());
+        assert!(parse_external_labels("").is_empty());
+    }
+
+    #[test]
+    fn test_aligned_timestamp() {
+        let step = Duration::from_secs(300);
+        assert_eq!(
+            aligned_timestamp_ms(1_700_000_123_456, step),
+            1_699_999_800_000
+        );
+        assert_eq!(
+            aligned_timestamp_ms(1_699_999_800_000, step),
+            1_699_999_800_000
+        );
+    }
+}
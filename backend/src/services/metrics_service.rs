@@ -93,6 +93,34 @@ pub fn record_security_scan(scanner: &str, success: bool, duration_secs: f64) {
         .record(duration_secs);
 }
 
+/// Record one attempt at a queued scan job. `outcome` is `completed`,
+/// `failed`, `timed_out` or `crashed`; `wait_secs` is the time from enqueue
+/// to this attempt starting.
+pub fn record_scan_job(trigger: &str, outcome: &str, wait_secs: f64, run_secs: f64) {
+    counter!(
+        "ak_scan_jobs_total",
+        "trigger" => trigger.to_string(),
+        "outcome" => outcome.to_string()
+    )
+    .increment(1);
+    histogram!("ak_scan_job_wait_seconds", "trigger" => trigger.to_string()).record(wait_secs);
+    histogram!("ak_scan_job_duration_seconds", "trigger" => trigger.to_string()).record(run_secs);
+}
+
+/// Update scan queue depth and age gauges. Triggers with nothing queued are
+/// reported as zero so a drained queue does not leave stale gauges behind.
+pub fn set_scan_queue_gauges(stats: &crate::services::scan_queue_service::QueueStats) {
+    gauge!("ak_scan_queue_running").set(stats.running as f64);
+    gauge!("ak_scan_queue_oldest_age_seconds").set(stats.oldest_queued_age_seconds.unwrap_or(0.0));
+    for trigger in ["manual", "rescan", "upload"] {
+        let depth = stats.by_trigger.iter().find(|d| d.trigger_type == trigger);
+        gauge!("ak_scan_queue_depth", "trigger" => trigger)
+            .set(depth.map(|d| d.queued).unwrap_or(0) as f64);
+        gauge!("ak_scan_queue_oldest_age_seconds_by_trigger", "trigger" => trigger)
+            .set(depth.and_then(|d| d.oldest_age_seconds).unwrap_or(0.0));
+    }
+}
+
 /// Record a scanner backend health-check failure. Distinct from
 /// `record_security_scan` so dashboards can separate "Trivy was down" from
 /// "scan ran and failed mid-execution". `reason` is "unreachable" (network
@@ -217,6 +245,26 @@ pub fn record_cleanup(cleanup_type: &str, items_removed: u64) {
         .increment(items_removed);
 }
 
+/// Record one remote-write push of business metrics. `status` is `"success"`
+/// or `"failure"`.
+pub fn record_metrics_remote_write(status: &'static str, series: usize) {
+    counter!("ak_metrics_remote_write_pushes_total", "status" => status).increment(1);
+    if status == "success" {
+        counter!("ak_metrics_remote_write_series_total").increment(series as u64);
+    }
+}
+
+/// Record one cache integrity verifier run on this node.
+pub fn record_cache_integrity_run(checked: u64, corrupted: u64) {
+    counter!("ak_cache_integrity_checked_total").increment(checked);
+    counter!("ak_cache_integrity_corrupted_total").increment(corrupted);
+}
+
+/// Record corrupted cache entries reported by an edge node.
+pub fn record_edge_cache_corruption(peer: &str, corrupted: u64) {
+    counter!("ak_edge_cache_corruptions_total", "peer" => peer.to_string()).increment(corrupted);
+}
+
 /// Record a structured audit-stream delivery failure. `reason` is a bounded
 /// internal label (`queue_full`, `writer_disconnected`, or `write_error`) so
 /// backpressure and broken stdout delivery are observable without logging back
@@ -1,72 +1,126 @@
 //! Business logic services.
 
+pub mod access_log_service;
+pub mod admission_service;
+pub mod airlock_service;
+pub mod artifact_compare_service;
+pub mod artifact_consumer_service;
 pub mod artifact_label_service;
 pub mod artifact_metadata;
+pub mod artifact_preview_service;
 pub mod artifact_service;
+pub mod artifact_verification_service;
 pub mod artifactory_client;
 pub mod artifactory_import;
+pub mod audit_archive_service;
 pub mod audit_export;
 pub mod audit_schema;
 pub mod audit_service;
 pub mod auth_config_service;
 pub mod auth_service;
 pub mod backup_service;
+pub mod badge_service;
+pub mod benchmark_service;
+pub mod blocklist_service;
+pub mod bootstrap_service;
 pub mod build_service;
 pub mod cache_classifier;
+pub mod cache_integrity_service;
 pub mod cache_invalidation;
+pub mod cache_warming_service;
+pub mod chart_image_service;
+pub mod chat_integration_service;
+pub mod checksum_backfill;
 pub mod ci_oidc_service;
 pub mod cluster_lock;
 pub mod cluster_work;
+pub mod compliance_scan_service;
+pub mod data_export_service;
+pub mod data_subject_service;
 pub mod declared_dependencies;
+pub mod delete_protection_service;
+pub mod dependency_graph_service;
 pub mod dependency_track_service;
+pub mod deploy_gate_service;
+pub mod direct_upload_service;
+pub mod edge_diagnostics_service;
 pub mod email_dispatcher;
 pub mod email_rate_limiter;
 pub mod encryption;
 pub mod event_bus;
+pub mod external_authz_service;
+pub mod external_scan_ingest;
+pub mod federation_service;
+pub mod freeze_window_service;
 pub mod grype_scanner;
+pub mod helm_index_service;
 pub mod helm_lint_checker;
 pub mod http_client;
 pub mod image_scanner;
 pub mod incus_scanner;
+pub mod issue_tracker_service;
+pub mod layer_scan_cache;
 pub mod ldap_service;
 pub mod manifest_blob_refs_backfill;
 pub mod maven_flat_attribution;
 pub mod metadata_checker;
+pub mod metadata_schema_service;
+pub mod metrics_remote_write;
 pub mod migration_service;
 pub mod migration_worker;
 pub mod nexus_client;
+pub mod notification_email_service;
 pub mod npm_packument_cache;
 pub mod oci_manifest_refs_backfill;
 pub mod oci_migration_reindex;
 pub mod oci_referenced_content;
+pub mod oci_referrers;
 pub mod oidc_service;
 pub mod openscap_scanner;
 pub mod opensearch_service;
+pub mod package_docs_service;
+pub mod package_protection_service;
 pub mod package_service;
 pub mod password_expiry_service;
 pub mod password_policy;
 pub mod peer_instance_label_service;
 pub mod peer_instance_service;
+pub mod peer_join_service;
 pub mod peer_service;
 pub mod permission_service;
 pub mod plugin_registry;
 pub mod plugin_service;
+pub mod policy_decision_service;
 pub mod policy_service;
+pub mod presigned_canary;
 pub mod promotion_policy_service;
 pub mod promotion_rule_service;
+pub mod promotion_signing_service;
 pub mod proxy_catalog;
 pub mod proxy_hydration;
 pub mod proxy_service;
+pub mod public_mirror_service;
 pub mod quality_check_service;
 pub mod quarantine_service;
+pub mod registry_scope;
 pub mod remote_instance_service;
+pub mod remote_promotion_service;
+pub mod remote_repo_service;
+pub mod repo_scan_config_service;
 pub mod repo_selector_service;
+pub mod repository_alias_service;
+pub mod repository_config_replication_service;
+pub mod repository_event_service;
 pub mod repository_label_service;
 pub mod repository_service;
+pub mod repository_stats_service;
 pub mod routing_rules;
+pub mod s3_gateway_service;
 pub mod saml_service;
+pub mod saved_search_service;
 pub mod sbom_service;
 pub mod scan_config_service;
+pub mod scan_queue_service;
 pub mod scan_result_service;
 pub mod scan_state;
 pub mod scanner_adapter_client;
@@ -78,25 +132,39 @@ pub mod smtp_service;
 pub mod source_registry;
 pub mod spdx_licenses;
 pub mod ssrf_dns;
+pub mod storage_dedup_service;
+pub mod storage_encryption_service;
 pub mod storage_gc_service;
 pub mod storage_service;
+pub mod storage_shard_service;
 pub mod storage_stats_service;
+pub mod storage_tiering_service;
 pub mod sync_policy_service;
+pub mod synthetic_data_service;
 pub mod token_service;
+pub mod token_usage_service;
 pub mod transfer_service;
 pub mod trivy_fs_scanner;
 pub mod upload_service;
 pub mod upstream_auth;
 pub mod upstream_feed;
 pub mod upstream_metadata;
+pub mod user_preference_service;
+pub mod version_deprecation_service;
+pub mod version_rules_service;
+pub mod virtual_member_routing;
+pub mod vulnerability_service;
 pub mod wasm_bindings;
 pub mod wasm_plugin_service;
 pub mod wasm_runtime;
+pub mod watchlist_service;
+pub mod webdav_service;
 pub mod webhook_notifier;
 pub mod webhook_payloads;
 pub mod webhook_producer;
 pub mod webhook_secret_crypto;
 pub mod webhook_signing;
+pub mod worm_service;
 
 // Observability & lifecycle
 pub mod age_gate_service;
@@ -105,6 +173,7 @@ pub mod crash_reporting_service;
 pub mod curation_service;
 pub mod curation_sync;
 pub mod health_monitor_service;
+pub mod incident_escalation_service;
 pub mod lifecycle_service;
 pub mod metrics_service;
 pub mod scheduler_service;
@@ -0,0 +1,928 @@
+//! User-addressed email notifications.
+//!
+//! Unlike the `email_dispatcher`, which fans domain events out to the
+//! per-repository `email_subscriptions` recipient lists, this service sends
+//! operational notifications to individual users: health alerts, promotion
+//! approval requests, quota warnings, and a weekly digest. Each user opts in
+//! or out per kind via `user_notification_preferences`, and every attempted
+//! send is recorded in `email_deliveries` with its final status.
+//!
+//! The template builders are pure functions so they can be unit-tested
+//! without a database or an SMTP transport.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::email_dispatcher::html_escape;
+use crate::services::health_monitor_service::HealthEvent;
+use crate::services::repository_service::{
+    exceeds_quota_warning_threshold, quota_usage_percentage, RepositoryService,
+};
+use crate::services::smtp_service::SmtpService;
+
+/// Minimum gap between two quota warnings for the same repository.
+const QUOTA_WARNING_INTERVAL_HOURS: i64 = 24;
+
+/// Minimum gap between two weekly digests. Slightly under seven days so an
+/// hourly sweep does not drift the send time forward by one tick per week.
+const DIGEST_INTERVAL_HOURS: i64 = 7 * 24 - 1;
+
+/// Dedupe key shared by every weekly digest delivery.
+const DIGEST_DEDUPE_KEY: &str = "weekly-digest";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// The kinds of user-addressed notification this service sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    HealthAlert,
+    ApprovalRequest,
+    QuotaWarning,
+    WeeklyDigest,
+}
+
+impl NotificationKind {
+    /// Value stored in `email_deliveries.notification_type`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::HealthAlert => "health_alert",
+            Self::ApprovalRequest => "approval_request",
+            Self::QuotaWarning => "quota_warning",
+            Self::WeeklyDigest => "weekly_digest",
+        }
+    }
+
+    /// Parse a stored `notification_type` value.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "health_alert" => Some(Self::HealthAlert),
+            "approval_request" => Some(Self::ApprovalRequest),
+            "quota_warning" => Some(Self::QuotaWarning),
+            "weekly_digest" => Some(Self::WeeklyDigest),
+            _ => None,
+        }
+    }
+
+    /// SQL expression deciding whether a user (joined as `u`, preferences
+    /// left-joined as `p`) wants this kind. Missing preference rows fall
+    /// back to the column defaults from migration 180.
+    fn preference_predicate(self) -> &'static str {
+        match self {
+            Self::HealthAlert => "COALESCE(p.health_alerts, false)",
+            Self::ApprovalRequest => "COALESCE(p.approval_requests, true)",
+            Self::QuotaWarning => "COALESCE(p.quota_warnings, true)",
+            Self::WeeklyDigest => "COALESCE(p.weekly_digest, false)",
+        }
+    }
+}
+
+/// A user's notification preferences.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct NotificationPreferences {
+    pub health_alerts: bool,
+    pub approval_requests: bool,
+    pub quota_warnings: bool,
+    pub weekly_digest: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            health_alerts: false,
+            approval_requests: true,
+            quota_warnings: true,
+            weekly_digest: false,
+        }
+    }
+}
+
+/// Partial update of a user's notification preferences. Omitted fields keep
+/// their current value.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct UpdateNotificationPreferences {
+    pub health_alerts: Option<bool>,
+    pub approval_requests: Option<bool>,
+    pub quota_warnings: Option<bool>,
+    pub weekly_digest: Option<bool>,
+}
+
+impl UpdateNotificationPreferences {
+    /// Apply this update on top of `current`.
+    pub fn apply(&self, current: &NotificationPreferences) -> NotificationPreferences {
+        NotificationPreferences {
+            health_alerts: self.health_alerts.unwrap_or(current.health_alerts),
+            approval_requests: self.approval_requests.unwrap_or(current.approval_requests),
+            quota_warnings: self.quota_warnings.unwrap_or(current.quota_warnings),
+            weekly_digest: self.weekly_digest.unwrap_or(current.weekly_digest),
+        }
+    }
+}
+
+/// One recorded delivery attempt.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct EmailDelivery {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub recipient: String,
+    pub notification_type: String,
+    pub subject: String,
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+/// A rendered notification, ready to send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Details of a newly requested promotion approval.
+#[derive(Debug, Clone)]
+pub struct ApprovalNotice {
+    pub approval_id: Uuid,
+    pub artifact_id: Uuid,
+    pub source_repository: String,
+    pub target_repository: String,
+    pub requested_by: String,
+    pub notes: Option<String>,
+}
+
+/// Instance-wide activity figures for the weekly digest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, sqlx::FromRow)]
+pub struct DigestStats {
+    pub artifacts_uploaded: i64,
+    pub downloads: i64,
+    pub repositories_created: i64,
+    pub pending_approvals: i64,
+    pub failed_deliveries: i64,
+}
+
+/// Per-status counts from one `deliver` call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeliverySummary {
+    pub sent: u32,
+    pub failed: u32,
+    pub skipped: u32,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct Recipient {
+    id: Uuid,
+    email: String,
+}
+
+// ---------------------------------------------------------------------------
+// Pure template builders
+// ---------------------------------------------------------------------------
+
+fn wrap_html(title: &str, body: &str) -> String {
+    format!(
+        "<h2>{}</h2>{}<p style=\"color:#666;font-size:12px\">\
+         You are receiving this because of your Artifact Keeper notification \
+         preferences.</p>",
+        html_escape(title),
+        body
+    )
+}
+
+/// Whether a health event warrants an email. Recoveries are only reported
+/// when an outage alert was actually sent, so the first healthy probe of a
+/// fresh install does not produce a "recovered" message.
+pub fn should_email_health_event(event: &HealthEvent) -> bool {
+    match event {
+        HealthEvent::ServiceRecovered {
+            downtime_started, ..
+        } => downtime_started.is_some(),
+        HealthEvent::ServiceDown { .. } | HealthEvent::ServiceDegraded { .. } => true,
+    }
+}
+
+/// Render a health alert email.
+pub fn render_health_alert(event: &HealthEvent) -> RenderedEmail {
+    let (subject, text) = match event {
+        HealthEvent::ServiceDown {
+            service_name,
+            message,
+            consecutive_failures,
+            timestamp,
+        } => (
+            format!("[Artifact Keeper] Service down: {}", service_name),
+            format!(
+                "Service '{}' failed {} consecutive health checks.\n\
+                 Last error: {}\nDetected at: {}",
+                service_name,
+                consecutive_failures,
+                if message.is_empty() {
+                    "(none)"
+                } else {
+                    message
+                },
+                timestamp.to_rfc3339()
+            ),
+        ),
+        HealthEvent::ServiceRecovered {
+            service_name,
+            downtime_started,
+            timestamp,
+        } => (
+            format!("[Artifact Keeper] Service recovered: {}", service_name),
+            format!(
+                "Service '{}' is healthy again.\nOutage reported at: {}\nRecovered at: {}",
+                service_name,
+                downtime_started
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                timestamp.to_rfc3339()
+            ),
+        ),
+        HealthEvent::ServiceDegraded {
+            service_name,
+            response_time_ms,
+            threshold_ms,
+            timestamp,
+        } => (
+            format!("[Artifact Keeper] Service degraded: {}", service_name),
+            format!(
+                "Service '{}' responded in {} ms (threshold {} ms).\nDetected at: {}",
+                service_name,
+                response_time_ms,
+                threshold_ms,
+                timestamp.to_rfc3339()
+            ),
+        ),
+    };
+    let html = wrap_html(
+        "Service health alert",
+        &format!("<pre>{}</pre>", html_escape(&text)),
+    );
+    RenderedEmail {
+        subject,
+        text,
+        html,
+    }
+}
+
+/// Render a promotion approval request email.
+pub fn render_approval_request(notice: &ApprovalNotice) -> RenderedEmail {
+    let subject = format!(
+        "[Artifact Keeper] Promotion approval requested: {} -> {}",
+        notice.source_repository, notice.target_repository
+    );
+    let mut text = format!(
+        "{} requested approval to promote artifact {} from '{}' to '{}'.\n\
+         Approval ID: {}\n",
+        notice.requested_by,
+        notice.artifact_id,
+        notice.source_repository,
+        notice.target_repository,
+        notice.approval_id
+    );
+    if let Some(notes) = notice.notes.as_deref().filter(|n| !n.is_empty()) {
+        text.push_str(&format!("Notes: {}\n", notes));
+    }
+    let mut rows = format!(
+        "<tr><td><strong>Requested by</strong></td><td>{}</td></tr>\
+         <tr><td><strong>Artifact</strong></td><td>{}</td></tr>\
+         <tr><td><strong>Source</strong></td><td>{}</td></tr>\
+         <tr><td><strong>Target</strong></td><td>{}</td></tr>\
+         <tr><td><strong>Approval ID</strong></td><td>{}</td></tr>",
+        html_escape(&notice.requested_by),
+        notice.artifact_id,
+        html_escape(&notice.source_repository),
+        html_escape(&notice.target_repository),
+        notice.approval_id
+    );
+    if let Some(notes) = notice.notes.as_deref().filter(|n| !n.is_empty()) {
+        rows.push_str(&format!(
+            "<tr><td><strong>Notes</strong></td><td>{}</td></tr>",
+            html_escape(notes)
+        ));
+    }
+    let html = wrap_html(
+        "Promotion approval requested",
+        &format!("<table>{}</table>", rows),
+    );
+    RenderedEmail {
+        subject,
+        text,
+        html,
+    }
+}
+
+/// Render a repository quota warning email.
+pub fn render_quota_warning(repo_key: &str, used_bytes: i64, quota_bytes: i64) -> RenderedEmail {
+    let pct = quota_usage_percentage(used_bytes, quota_bytes) * 100.0;
+    let subject = format!(
+        "[Artifact Keeper] Repository '{}' is at {:.0}% of its quota",
+        repo_key, pct
+    );
+    let text = format!(
+        "Repository '{}' is using {} of its {} byte quota ({:.1}%).\n\
+         Uploads will be rejected once the quota is reached. Free space or \
+         raise the quota to avoid failed uploads.",
+        repo_key, used_bytes, quota_bytes, pct
+    );
+    let html = wrap_html(
+        "Repository quota warning",
+        &format!(
+            "<p>Repository <strong>{}</strong> is using {} of its {} byte quota \
+             (<strong>{:.1}%</strong>).</p>\
+             <p>Uploads will be rejected once the quota is reached. Free space \
+             or raise the quota to avoid failed uploads.</p>",
+            html_escape(repo_key),
+            used_bytes,
+            quota_bytes,
+            pct
+        ),
+    );
+    RenderedEmail {
+        subject,
+        text,
+        html,
+    }
+}
+
+/// Render the weekly digest email.
+pub fn render_weekly_digest(
+    stats: &DigestStats,
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+) -> RenderedEmail {
+    let period = format!(
+        "{} to {}",
+        period_start.format("%Y-%m-%d"),
+        period_end.format("%Y-%m-%d")
+    );
+    let subject = format!("[Artifact Keeper] Weekly digest ({})", period);
+    let lines = [
+        ("Artifacts uploaded", stats.artifacts_uploaded),
+        ("Downloads", stats.downloads),
+        ("Repositories created", stats.repositories_created),
+        ("Pending promotion approvals", stats.pending_approvals),
+        ("Failed email deliveries", stats.failed_deliveries),
+    ];
+    let mut text = format!("Activity summary for {}:\n\n", period);
+    let mut rows = String::new();
+    for (label, value) in lines {
+        text.push_str(&format!("  {}: {}\n", label, value));
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td><strong>{}</strong></td></tr>",
+            label, value
+        ));
+    }
+    let html = wrap_html(
+        "Weekly digest",
+        &format!(
+            "<p>Activity summary for {}:</p><table>{}</table>",
+            html_escape(&period),
+            rows
+        ),
+    );
+    RenderedEmail {
+        subject,
+        text,
+        html,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Service
+// ---------------------------------------------------------------------------
+
+/// Sends user-addressed notifications and records their delivery status.
+#[derive(Clone)]
+pub struct NotificationEmailService {
+    db: PgPool,
+    smtp: Option<Arc<SmtpService>>,
+}
+
+impl NotificationEmailService {
+    pub fn new(db: PgPool, smtp: Option<Arc<SmtpService>>) -> Self {
+        Self { db, smtp }
+    }
+
+    /// Fetch a user's preferences, falling back to the defaults when the
+    /// user has never saved any.
+    pub async fn get_preferences(&self, user_id: Uuid) -> Result<NotificationPreferences> {
+        let prefs: Option<NotificationPreferences> = sqlx::query_as(
+            r#"
+            SELECT health_alerts, approval_requests, quota_warnings, weekly_digest
+            FROM user_notification_preferences
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(prefs.unwrap_or_default())
+    }
+
+    /// Apply a partial update to a user's preferences and return the result.
+    pub async fn update_preferences(
+        &self,
+        user_id: Uuid,
+        update: &UpdateNotificationPreferences,
+    ) -> Result<NotificationPreferences> {
+        let merged = update.apply(&self.get_preferences(user_id).await?);
+        sqlx::query(
+            r#"
+            INSERT INTO user_notification_preferences
+                (user_id, health_alerts, approval_requests, quota_warnings, weekly_digest, updated_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (user_id) DO UPDATE SET
+                health_alerts = EXCLUDED.health_alerts,
+                approval_requests = EXCLUDED.approval_requests,
+                quota_warnings = EXCLUDED.quota_warnings,
+                weekly_digest = EXCLUDED.weekly_digest,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(merged.health_alerts)
+        .bind(merged.approval_requests)
+        .bind(merged.quota_warnings)
+        .bind(merged.weekly_digest)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(merged)
+    }
+
+    /// Active administrators who opted into `kind`. All four kinds carry
+    /// instance-wide operational detail, so they are addressed to admins
+    /// only; service accounts and users without an email are excluded.
+    async fn recipients_for(
+        &self,
+        kind: NotificationKind,
+        exclude: Option<Uuid>,
+    ) -> Result<Vec<Recipient>> {
+        let sql = format!(
+            r#"
+            SELECT u.id, u.email
+            FROM users u
+            LEFT JOIN user_notification_preferences p ON p.user_id = u.id
+            WHERE u.is_active = true
+              AND u.is_admin = true
+              AND u.is_service_account = false
+              AND u.email <> ''
+              AND ($1::uuid IS NULL OR u.id <> $1)
+              AND {}
+            ORDER BY u.username
+            "#,
+            kind.preference_predicate()
+        );
+        sqlx::query_as(&sql)
+            .bind(exclude)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Whether a delivery with `dedupe_key` was recorded within `window`.
+    async fn recently_delivered(&self, dedupe_key: &str, window: Duration) -> Result<bool> {
+        let since = Utc::now() - window;
+        sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM email_deliveries
+                WHERE dedupe_key = $1 AND created_at > $2
+            )
+            "#,
+        )
+        .bind(dedupe_key)
+        .bind(since)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    async fn record_delivery(
+        &self,
+        recipient: &Recipient,
+        kind: NotificationKind,
+        subject: &str,
+        dedupe_key: Option<&str>,
+    ) -> Result<Uuid> {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO email_deliveries (user_id, recipient, notification_type, subject, dedupe_key)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(recipient.id)
+        .bind(&recipient.email)
+        .bind(kind.as_str())
+        .bind(subject)
+        .bind(dedupe_key)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    async fn finish_delivery(&self, id: Uuid, status: &str, error: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE email_deliveries
+            SET status = $2,
+                error = $3,
+                sent_at = CASE WHEN $2 = 'sent' THEN NOW() ELSE NULL END
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(error)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Send `email` to every opted-in recipient of `kind`, recording one
+    /// `email_deliveries` row per recipient. When SMTP is not configured the
+    /// rows are recorded as `skipped` so the attempt is still visible.
+    async fn deliver(
+        &self,
+        kind: NotificationKind,
+        email: &RenderedEmail,
+        dedupe_key: Option<&str>,
+        exclude: Option<Uuid>,
+    ) -> Result<DeliverySummary> {
+        let recipients = self.recipients_for(kind, exclude).await?;
+        let smtp = self.smtp.as_ref().filter(|s| s.is_configured());
+        let mut summary = DeliverySummary::default();
+
+        for recipient in &recipients {
+            let id = self
+                .record_delivery(recipient, kind, &email.subject, dedupe_key)
+                .await?;
+            let Some(smtp) = smtp else {
+                self.finish_delivery(id, "skipped", Some("SMTP is not configured"))
+                    .await?;
+                summary.skipped += 1;
+                continue;
+            };
+            match smtp
+                .send_email(&recipient.email, &email.subject, &email.html, &email.text)
+                .await
+            {
+                Ok(()) => {
+                    self.finish_delivery(id, "sent", None).await?;
+                    summary.sent += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        notification = kind.as_str(),
+                        user_id = %recipient.id,
+                        "Notification email delivery failed: {}",
+                        e
+                    );
+                    self.finish_delivery(id, "failed", Some(&e.to_string()))
+                        .await?;
+                    summary.failed += 1;
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Email opted-in admins about a health monitor event.
+    pub async fn notify_health_event(&self, event: &HealthEvent) -> Result<DeliverySummary> {
+        if !should_email_health_event(event) {
+            return Ok(DeliverySummary::default());
+        }
+        self.deliver(
+            NotificationKind::HealthAlert,
+            &render_health_alert(event),
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Email opted-in admins about a new promotion approval request. The
+    /// requester is excluded even when they are an admin themselves.
+    pub async fn notify_approval_requested(
+        &self,
+        notice: &ApprovalNotice,
+        requested_by: Uuid,
+    ) -> Result<DeliverySummary> {
+        self.deliver(
+            NotificationKind::ApprovalRequest,
+            &render_approval_request(notice),
+            None,
+            Some(requested_by),
+        )
+        .await
+    }
+
+    /// Check every repository with a finite quota and warn about those past
+    /// the warning threshold, at most once per repository per day. Returns
+    /// the number of repositories warned about.
+    pub async fn run_quota_warning_sweep(&self) -> Result<u32> {
+        let repos: Vec<(Uuid, String, i64)> = sqlx::query_as(
+            r#"
+            SELECT id, key, quota_bytes
+            FROM repositories
+            WHERE quota_bytes IS NOT NULL
+              AND quota_bytes > 0
+              AND repo_type::text <> 'virtual'
+            ORDER BY key
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let repo_service = RepositoryService::new(self.db.clone());
+        let mut warned = 0u32;
+        for (repo_id, key, quota) in repos {
+            let used = repo_service.get_storage_usage(repo_id).await?;
+            if !exceeds_quota_warning_threshold(used, quota) {
+                continue;
+            }
+            let dedupe_key = format!("quota:{}", repo_id);
+            if self
+                .recently_delivered(&dedupe_key, Duration::hours(QUOTA_WARNING_INTERVAL_HOURS))
+                .await?
+            {
+                continue;
+            }
+            self.deliver(
+                NotificationKind::QuotaWarning,
+                &render_quota_warning(&key, used, quota),
+                Some(&dedupe_key),
+                None,
+            )
+            .await?;
+            warned += 1;
+        }
+        Ok(warned)
+    }
+
+    /// Collect activity figures for the digest window.
+    pub async fn digest_stats(
+        &self,
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+    ) -> Result<DigestStats> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM artifacts
+                  WHERE is_deleted = false AND created_at >= $1 AND created_at < $2)
+                    AS artifacts_uploaded,
+                (SELECT COUNT(*) FROM download_statistics
+                  WHERE downloaded_at >= $1 AND downloaded_at < $2) AS downloads,
+                (SELECT COUNT(*) FROM repositories
+                  WHERE created_at >= $1 AND created_at < $2) AS repositories_created,
+                (SELECT COUNT(*) FROM promotion_approvals WHERE status = 'pending')
+                    AS pending_approvals,
+                (SELECT COUNT(*) FROM email_deliveries
+                  WHERE status = 'failed' AND created_at >= $1 AND created_at < $2)
+                    AS failed_deliveries
+            "#,
+        )
+        .bind(period_start)
+        .bind(period_end)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Send the weekly digest if one has not gone out in the last week.
+    /// Returns `true` when a digest was sent (or recorded as skipped).
+    pub async fn run_weekly_digest(&self) -> Result<bool> {
+        if self
+            .recently_delivered(DIGEST_DEDUPE_KEY, Duration::hours(DIGEST_INTERVAL_HOURS))
+            .await?
+        {
+            return Ok(false);
+        }
+        let period_end = Utc::now();
+        let period_start = period_end - Duration::days(7);
+        let stats = self.digest_stats(period_start, period_end).await?;
+        let summary = self
+            .deliver(
+                NotificationKind::WeeklyDigest,
+                &render_weekly_digest(&stats, period_start, period_end),
+                Some(DIGEST_DEDUPE_KEY),
+                None,
+            )
+            .await?;
+        Ok(summary != DeliverySummary::default())
+    }
+
+    /// List recorded deliveries, newest first, with optional filters.
+    pub async fn list_deliveries(
+        &self,
+        status: Option<&str>,
+        notification_type: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<EmailDelivery>, i64)> {
+        let items: Vec<EmailDelivery> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, recipient, notification_type, subject, status, error,
+                   created_at, sent_at
+            FROM email_deliveries
+            WHERE ($1::text IS NULL OR status = $1)
+              AND ($2::text IS NULL OR notification_type = $2)
+            ORDER BY created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(status)
+        .bind(notification_type)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM email_deliveries
+            WHERE ($1::text IS NULL OR status = $1)
+              AND ($2::text IS NULL OR notification_type = $2)
+            "#,
+        )
+        .bind(status)
+        .bind(notification_type)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((items, total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_notification_kind_round_trip() {
+        for kind in [
+            NotificationKind::HealthAlert,
+            NotificationKind::ApprovalRequest,
+            NotificationKind::QuotaWarning,
+            NotificationKind::WeeklyDigest,
+        ] {
+            assert_eq!(NotificationKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(NotificationKind::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_default_preferences_match_migration_defaults() {
+        let prefs = NotificationPreferences::default();
+        assert!(!prefs.health_alerts);
+        assert!(prefs.approval_requests);
+        assert!(prefs.quota_warnings);
+        assert!(!prefs.weekly_digest);
+        assert!(NotificationKind::HealthAlert
+            .preference_predicate()
+            .ends_with("false)"));
+        assert!(NotificationKind::ApprovalRequest
+            .preference_predicate()
+            .ends_with("true)"));
+    }
+
+    #[test]
+    fn test_update_preferences_keeps_omitted_fields() {
+        let current = NotificationPreferences::default();
+        let update = UpdateNotificationPreferences {
+            weekly_digest: Some(true),
+            quota_warnings: Some(false),
+            ..Default::default()
+        };
+        let merged = update.apply(&current);
+        assert!(merged.weekly_digest);
+        assert!(!merged.quota_warnings);
+        assert_eq!(merged.approval_requests, current.approval_requests);
+        assert_eq!(merged.health_alerts, current.health_alerts);
+    }
+
+    #[test]
+    fn test_recovery_without_prior_alert_is_not_emailed() {
+        let first_probe = HealthEvent::ServiceRecovered {
+            service_name: "trivy".to_string(),
+            downtime_started: None,
+            timestamp: Utc::now(),
+        };
+        assert!(!should_email_health_event(&first_probe));
+
+        let real_recovery = HealthEvent::ServiceRecovered {
+            service_name: "trivy".to_string(),
+            downtime_started: Some(Utc::now()),
+            timestamp: Utc::now(),
+        };
+        assert!(should_email_health_event(&real_recovery));
+    }
+
+    #[test]
+    fn test_render_health_alert_down() {
+        let email = render_health_alert(&HealthEvent::ServiceDown {
+            service_name: "opensearch".to_string(),
+            message: "Connection failed: <refused>".to_string(),
+            consecutive_failures: 3,
+            timestamp: ts("2026-01-02T03:04:05Z"),
+        });
+        assert_eq!(email.subject, "[Artifact Keeper] Service down: opensearch");
+        assert!(email.text.contains("3 consecutive"));
+        assert!(email.text.contains("Connection failed: <refused>"));
+        assert!(email.html.contains("&lt;refused&gt;"));
+        assert!(!email.html.contains("<refused>"));
+    }
+
+    #[test]
+    fn test_render_approval_request_escapes_fields() {
+        let notice = ApprovalNotice {
+            approval_id: Uuid::nil(),
+            artifact_id: Uuid::nil(),
+            source_repository: "staging".to_string(),
+            target_repository: "release".to_string(),
+            requested_by: "<script>alice</script>".to_string(),
+            notes: Some("please & thanks".to_string()),
+        };
+        let email = render_approval_request(&notice);
+        assert!(email.subject.contains("staging -> release"));
+        assert!(email.text.contains("Notes: please & thanks"));
+        assert!(email.html.contains("&lt;script&gt;alice&lt;/script&gt;"));
+        assert!(email.html.contains("please &amp; thanks"));
+    }
+
+    #[test]
+    fn test_render_approval_request_omits_empty_notes() {
+        let notice = ApprovalNotice {
+            approval_id: Uuid::nil(),
+            artifact_id: Uuid::nil(),
+            source_repository: "a".to_string(),
+            target_repository: "b".to_string(),
+            requested_by: "bob".to_string(),
+            notes: Some(String::new()),
+        };
+        let email = render_approval_request(&notice);
+        assert!(!email.text.contains("Notes:"));
+        assert!(!email.html.contains("Notes"));
+    }
+
+    #[test]
+    fn test_render_quota_warning() {
+        let email = render_quota_warning("npm-local", 900, 1000);
+        assert_eq!(
+            email.subject,
+            "[Artifact Keeper] Repository 'npm-local' is at 90% of its quota"
+        );
+        assert!(email.text.contains("(90.0%)"));
+        assert!(email.html.contains("<strong>npm-local</strong>"));
+    }
+
+    #[test]
+    fn test_render_weekly_digest() {
+        let stats = DigestStats {
+            artifacts_uploaded: 12,
+            downloads: 340,
+            repositories_created: 1,
+            pending_approvals: 2,
+            failed_deliveries: 0,
+        };
+        let email = render_weekly_digest(
+            &stats,
+            ts("2026-01-01T00:00:00Z"),
+            ts("2026-01-08T00:00:00Z"),
+        );
+        assert_eq!(
+            email.subject,
+            "[Artifact Keeper] Weekly digest (2026-01-01 to 2026-01-08)"
+        );
+        assert!(email.text.contains("Artifacts uploaded: 12"));
+        assert!(email.text.contains("Downloads: 340"));
+        assert!(email.html.contains("<strong>2</strong>"));
+    }
+
+    #[test]
+    fn test_digest_interval_is_under_a_week() {
+        assert!(DIGEST_INTERVAL_HOURS < 7 * 24);
+        assert!(DIGEST_INTERVAL_HOURS > 6 * 24);
+    }
+}
@@ -11,8 +11,10 @@
 //!
 //! This module walks the index-typed `oci_tags` rows that have zero
 //! refs, loads each manifest body from storage, parses the JSON, and
-//! inserts the (parent, child, repository_id) edges. The backfill is
-//! idempotent (`ON CONFLICT DO NOTHING`) and best-effort: a missing
+//! inserts the (parent, child, repository_id) edges. Index-typed tags whose
+//! edges predate per-platform tracking (migration 194) are re-read the same
+//! way to fill in each child's platform, media type and size. The backfill is
+//! idempotent (existing edges are kept) and best-effort: a missing
 //! storage file or a malformed manifest is logged at WARN and skipped,
 //! it does not stop the backfill or fail server startup.
 //!
@@ -34,7 +36,8 @@ pub struct BackfillStats {
     /// Number of (parent_digest, repository_id) candidates we tried to
     /// process. Equals the number of distinct index manifests visited.
     pub candidates_scanned: usize,
-    /// Number of edges (parent -> child) inserted into the table.
+    /// Number of edges (parent -> child) inserted into the table, or
+    /// existing edges that had their descriptor metadata filled in.
     pub edges_inserted: usize,
     /// Number of candidates we could not process (manifest missing from
     /// storage, malformed JSON, DB write failure). These are logged at
@@ -106,7 +109,8 @@ struct BackfillCandidate {
 
 /// Select the distinct (parent_digest, repository_id) tuples whose
 /// content-type marks them as an image index and that have zero rows in
-/// `oci_manifest_refs`. We pull `storage_backend` / `storage_path` from
+/// `oci_manifest_refs`, or rows not yet described (`described_at IS
+/// NULL`). We pull `storage_backend` / `storage_path` from
 /// the repositories table along the way so the per-candidate work can
 /// resolve the correct backend without a second query.
 ///
@@ -128,10 +132,20 @@ async fn select_unbackfilled_indexes(db: &PgPool) -> sqlx::Result<Vec<BackfillCa
                 'application/vnd.oci.image.index.v1+json',
                 'application/vnd.docker.distribution.manifest.list.v2+json'
             )
-          AND NOT EXISTS (
-                SELECT 1 FROM oci_manifest_refs omr
-                WHERE omr.parent_digest = ot.manifest_digest
-                  AND omr.repository_id = ot.repository_id
+          AND (
+                NOT EXISTS (
+                    SELECT 1 FROM oci_manifest_refs omr
+                    WHERE omr.parent_digest = ot.manifest_digest
+                      AND omr.repository_id = ot.repository_id
+                )
+                -- Edges recorded before per-platform tracking (migration
+                -- 194) are re-read once to pick up their descriptors.
+                OR EXISTS (
+                    SELECT 1 FROM oci_manifest_refs omr
+                    WHERE omr.parent_digest = ot.manifest_digest
+                      AND omr.repository_id = ot.repository_id
+                      AND omr.described_at IS NULL
+                )
           )
         "#,
     )
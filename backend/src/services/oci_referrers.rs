@@ -0,0 +1,234 @@
+//! OCI referrers (Distribution Spec v1.1).
+//!
+//! A manifest whose body carries a `subject` descriptor refers to that
+//! subject — cosign signatures, `oras attach` SBOMs, notation attestations.
+//! The manifest-PUT path calls [`parse_referrer`] on every pushed manifest and
+//! records the edge in `oci_referrers`; `GET /v2/<name>/referrers/<digest>`
+//! lists the recorded referrers as an OCI image index built by
+//! [`referrers_index`], optionally filtered by `artifactType`.
+
+use serde_json::{json, Map, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Media type of the image index the referrers API answers with.
+pub const REFERRERS_INDEX_MEDIA_TYPE: &str = "application/vnd.oci.image.index.v1+json";
+
+/// One entry in a referrers response: the descriptor of a referring manifest.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct ReferrerDescriptor {
+    pub media_type: String,
+    pub manifest_digest: String,
+    pub size_bytes: i64,
+    pub artifact_type: Option<String>,
+    pub annotations: Option<Value>,
+}
+
+/// Extract the subject digest and referrer descriptor from a pushed manifest.
+///
+/// Returns `None` when the body has no `subject.digest`. Per the spec the
+/// artifact type is the manifest's `artifactType`, falling back to its
+/// `config.mediaType` (an image manifest used as an artifact).
+pub fn parse_referrer(
+    body: &[u8],
+    manifest_digest: &str,
+    media_type: &str,
+) -> Option<(String, ReferrerDescriptor)> {
+    let json: Value = serde_json::from_slice(body).ok()?;
+    let subject = json.get("subject")?.get("digest")?.as_str()?.to_string();
+    let artifact_type = json
+        .get("artifactType")
+        .and_then(Value::as_str)
+        .or_else(|| {
+            json.get("config")
+                .and_then(|c| c.get("mediaType"))
+                .and_then(Value::as_str)
+        })
+        .map(str::to_string);
+    let annotations = json
+        .get("annotations")
+        .filter(|a| a.as_object().is_some_and(|o| !o.is_empty()))
+        .cloned();
+    Some((
+        subject,
+        ReferrerDescriptor {
+            media_type: media_type.to_string(),
+            manifest_digest: manifest_digest.to_string(),
+            size_bytes: body.len() as i64,
+            artifact_type,
+            annotations,
+        },
+    ))
+}
+
+/// Build the image index returned by the referrers API.
+pub fn referrers_index(referrers: &[ReferrerDescriptor]) -> Value {
+    let manifests: Vec<Value> = referrers
+        .iter()
+        .map(|r| {
+            let mut descriptor = Map::new();
+            descriptor.insert("mediaType".into(), json!(r.media_type));
+            descriptor.insert("digest".into(), json!(r.manifest_digest));
+            descriptor.insert("size".into(), json!(r.size_bytes));
+            if let Some(artifact_type) = &r.artifact_type {
+                descriptor.insert("artifactType".into(), json!(artifact_type));
+            }
+            if let Some(annotations) = &r.annotations {
+                descriptor.insert("annotations".into(), annotations.clone());
+            }
+            Value::Object(descriptor)
+        })
+        .collect();
+    json!({
+        "schemaVersion": 2,
+        "mediaType": REFERRERS_INDEX_MEDIA_TYPE,
+        "manifests": manifests,
+    })
+}
+
+/// Record that `referrer` refers to `subject_digest` in image `name`.
+/// Idempotent: a re-push refreshes the stored descriptor.
+pub async fn record_referrer(
+    db: &PgPool,
+    repo_id: Uuid,
+    name: &str,
+    subject_digest: &str,
+    referrer: &ReferrerDescriptor,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO oci_referrers (
+            repository_id, name, subject_digest, manifest_digest,
+            media_type, artifact_type, size_bytes, annotations
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (repository_id, name, subject_digest, manifest_digest) DO UPDATE SET
+            media_type = EXCLUDED.media_type,
+            artifact_type = EXCLUDED.artifact_type,
+            size_bytes = EXCLUDED.size_bytes,
+            annotations = EXCLUDED.annotations
+        "#,
+    )
+    .bind(repo_id)
+    .bind(name)
+    .bind(subject_digest)
+    .bind(&referrer.manifest_digest)
+    .bind(&referrer.media_type)
+    .bind(&referrer.artifact_type)
+    .bind(referrer.size_bytes)
+    .bind(&referrer.annotations)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Referrers of `subject_digest` still present in the repository, oldest
+/// first, optionally restricted to one `artifact_type`.
+pub async fn list_referrers(
+    db: &PgPool,
+    repo_id: Uuid,
+    name: &str,
+    subject_digest: &str,
+    artifact_type: Option<&str>,
+) -> Result<Vec<ReferrerDescriptor>, sqlx::Error> {
+    sqlx::query_as::<_, ReferrerDescriptor>(
+        r#"
+        SELECT r.media_type, r.manifest_digest, r.size_bytes, r.artifact_type, r.annotations
+        FROM oci_referrers r
+        WHERE r.repository_id = $1
+          AND r.name = $2
+          AND r.subject_digest = $3
+          AND ($4::text IS NULL OR r.artifact_type = $4)
+          AND EXISTS (
+              SELECT 1 FROM oci_tags t
+              WHERE t.repository_id = r.repository_id
+                AND t.name = r.name
+                AND t.manifest_digest = r.manifest_digest
+          )
+        ORDER BY r.created_at, r.manifest_digest
+        "#,
+    )
+    .bind(repo_id)
+    .bind(name)
+    .bind(subject_digest)
+    .bind(artifact_type)
+    .fetch_all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUBJECT: &str = "sha256:1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_parse_referrer_with_artifact_type() {
+        let body = json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "artifactType": "application/spdx+json",
+            "config": {"mediaType": "application/vnd.oci.empty.v1+json", "digest": "sha256:44", "size": 2},
+            "layers": [],
+            "subject": {"mediaType": "application/vnd.oci.image.manifest.v1+json", "digest": SUBJECT, "size": 10},
+            "annotations": {"org.opencontainers.image.created": "2026-01-01T00:00:00Z"}
+        });
+        let bytes = serde_json::to_vec(&body).unwrap();
+        let (subject, referrer) = parse_referrer(
+            &bytes,
+            "sha256:abc",
+            "application/vnd.oci.image.manifest.v1+json",
+        )
+        .unwrap();
+        assert_eq!(subject, SUBJECT);
+        assert_eq!(
+            referrer.artifact_type.as_deref(),
+            Some("application/spdx+json")
+        );
+        assert_eq!(referrer.size_bytes, bytes.len() as i64);
+        assert!(referrer.annotations.is_some());
+    }
+
+    #[test]
+    fn test_parse_referrer_falls_back_to_config_media_type() {
+        let body = json!({
+            "config": {"mediaType": "application/vnd.dev.cosign.simplesigning.v1+json", "digest": "sha256:44"},
+            "subject": {"digest": SUBJECT},
+            "annotations": {}
+        });
+        let (_, referrer) =
+            parse_referrer(&serde_json::to_vec(&body).unwrap(), "sha256:abc", "m").unwrap();
+        assert_eq!(
+            referrer.artifact_type.as_deref(),
+            Some("application/vnd.dev.cosign.simplesigning.v1+json")
+        );
+        assert_eq!(referrer.annotations, None);
+    }
+
+    #[test]
+    fn test_parse_referrer_requires_subject() {
+        let body = json!({"config": {"mediaType": "x", "digest": "sha256:44"}, "layers": []});
+        assert!(parse_referrer(&serde_json::to_vec(&body).unwrap(), "sha256:abc", "m").is_none());
+        assert!(parse_referrer(b"not json", "sha256:abc", "m").is_none());
+    }
+
+    #[test]
+    fn test_referrers_index_shape() {
+        let index = referrers_index(&[ReferrerDescriptor {
+            media_type: "application/vnd.oci.image.manifest.v1+json".into(),
+            manifest_digest: "sha256:abc".into(),
+            size_bytes: 42,
+            artifact_type: Some("application/spdx+json".into()),
+            annotations: None,
+        }]);
+        assert_eq!(index["schemaVersion"], 2);
+        assert_eq!(index["mediaType"], REFERRERS_INDEX_MEDIA_TYPE);
+        let entry = &index["manifests"][0];
+        assert_eq!(entry["digest"], "sha256:abc");
+        assert_eq!(entry["size"], 42);
+        assert_eq!(entry["artifactType"], "application/spdx+json");
+        assert!(entry.get("annotations").is_none());
+
+        assert_eq!(referrers_index(&[])["manifests"], json!([]));
+    }
+}
@@ -880,7 +880,20 @@ mod tests {
             guest_access_enabled: true,
             expose_detailed_health: false,
             setup_password_hint: None,
+            setup_wizard: false,
             grpc_reflection_enabled: false,
+            grpc_keepalive_interval_secs: 30,
+            grpc_keepalive_timeout_secs: 20,
+            grpc_tcp_keepalive_secs: 60,
+            grpc_health_probe_interval_secs: 10,
+            s3_gateway_enabled: false,
+            s3_gateway_region: "us-east-1".to_string(),
+            repository_event_retention_days: 30,
+            pii_retention_days: 0,
+            cache_warm_edge_urls: Vec::new(),
+            admission_registry_hosts: Vec::new(),
+            admission_allowed_repositories: Vec::new(),
+            admission_exempt_namespaces: vec!["kube-system".to_string()],
             plugins_require_signed: true,
             plugins_trusted_pubkey: None,
             peer_instance_name: "test".into(),
@@ -898,6 +911,8 @@ mod tests {
             stuck_scan_threshold_secs: 1800,
             stuck_scan_check_interval_secs: 600,
             stuck_scan_reap_limit: 1000,
+            audit_retention_enabled: false,
+            audit_archive_enabled: true,
             max_upload_size_bytes: 10_737_418_240,
             allow_local_admin_login: false,
             sso_disable_admin_break_glass: false,
@@ -943,9 +958,17 @@ mod tests {
             password_min_strength: 0,
             presigned_downloads_enabled: false,
             presigned_download_expiry_secs: 300,
+            presigned_canary_interval_secs: 600,
             proxy_singleflight_advisory_locks_enabled: false,
             proxy_singleflight_lock_poll_interval_ms: 200,
             proxy_singleflight_lock_wait_timeout_secs: 65,
+            public_mirror_mode: false,
+            public_mirror_anon_requests_per_window: 1200,
+            public_mirror_window_secs: 60,
+            public_mirror_anon_bytes_per_window: 53_687_091_200,
+            public_mirror_bandwidth_window_secs: 3600,
+            public_mirror_metadata_max_age_secs: 300,
+            public_mirror_artifact_max_age_secs: 31_536_000,
             smtp_host: None,
             smtp_port: 587,
             smtp_username: None,
@@ -959,6 +982,18 @@ mod tests {
             npm_upstream_feed_enabled: false,
             npm_upstream_feed_url: crate::services::upstream_feed::NPM_REPLICATION_FEED_DEFAULT_URL
                 .into(),
+            load_test_mode: false,
+            storage_dedup_enabled: false,
+            storage_capacity_bytes: None,
+            storage_forecast_alert_days: 30,
+            quota_warning_threshold_percent: 80,
+            storage_cold_backend: None,
+            storage_cold_path: None,
+            storage_cold_rehydrated_days: 7,
+            api_token_stale_days: 0,
+            api_token_stale_warning_days: 7,
+            repository_key_alias_ttl_days: 90,
+            access_log_retention_days: 14,
             scan_token_ttl_seconds: 300,
         };
 
@@ -1003,7 +1038,20 @@ mod tests {
             guest_access_enabled: true,
             expose_detailed_health: false,
             setup_password_hint: None,
+            setup_wizard: false,
             grpc_reflection_enabled: false,
+            grpc_keepalive_interval_secs: 30,
+            grpc_keepalive_timeout_secs: 20,
+            grpc_tcp_keepalive_secs: 60,
+            grpc_health_probe_interval_secs: 10,
+            s3_gateway_enabled: false,
+            s3_gateway_region: "us-east-1".to_string(),
+            repository_event_retention_days: 30,
+            pii_retention_days: 0,
+            cache_warm_edge_urls: Vec::new(),
+            admission_registry_hosts: Vec::new(),
+            admission_allowed_repositories: Vec::new(),
+            admission_exempt_namespaces: vec!["kube-system".to_string()],
             plugins_require_signed: true,
             plugins_trusted_pubkey: None,
             peer_instance_name: "test".into(),
@@ -1021,6 +1069,8 @@ mod tests {
             stuck_scan_threshold_secs: 1800,
             stuck_scan_check_interval_secs: 600,
             stuck_scan_reap_limit: 1000,
+            audit_retention_enabled: false,
+            audit_archive_enabled: true,
             max_upload_size_bytes: 10_737_418_240,
             allow_local_admin_login: false,
             sso_disable_admin_break_glass: false,
@@ -1066,9 +1116,17 @@ mod tests {
             password_min_strength: 0,
             presigned_downloads_enabled: false,
             presigned_download_expiry_secs: 300,
+            presigned_canary_interval_secs: 600,
             proxy_singleflight_advisory_locks_enabled: false,
             proxy_singleflight_lock_poll_interval_ms: 200,
             proxy_singleflight_lock_wait_timeout_secs: 65,
+            public_mirror_mode: false,
+            public_mirror_anon_requests_per_window: 1200,
+            public_mirror_window_secs: 60,
+            public_mirror_anon_bytes_per_window: 53_687_091_200,
+            public_mirror_bandwidth_window_secs: 3600,
+            public_mirror_metadata_max_age_secs: 300,
+            public_mirror_artifact_max_age_secs: 31_536_000,
             smtp_host: None,
             smtp_port: 587,
             smtp_username: None,
@@ -1082,6 +1140,18 @@ mod tests {
             npm_upstream_feed_enabled: false,
             npm_upstream_feed_url: crate::services::upstream_feed::NPM_REPLICATION_FEED_DEFAULT_URL
                 .into(),
+            load_test_mode: false,
+            storage_dedup_enabled: false,
+            storage_capacity_bytes: None,
+            storage_forecast_alert_days: 30,
+            quota_warning_threshold_percent: 80,
+            storage_cold_backend: None,
+            storage_cold_path: None,
+            storage_cold_rehydrated_days: 7,
+            api_token_stale_days: 0,
+            api_token_stale_warning_days: 7,
+            repository_key_alias_ttl_days: 90,
+            access_log_retention_days: 14,
             scan_token_ttl_seconds: 300,
         }
     }
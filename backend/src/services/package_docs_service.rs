@@ -0,0 +1,285 @@
+//! README / changelog extraction for package search.
+//!
+//! At publish, the npm, Cargo and PyPI handlers pull the package's README
+//! and changelog out of the uploaded archive and record them in
+//! `artifact_docs`. `SearchService` matches free-text queries against that
+//! text and returns highlighted snippets from it, so a search for what a
+//! package *does* finds it even when the words are not in its name.
+//!
+//! Extraction is best-effort and never fails a publish: archives are read
+//! through the bounded-archive helpers, and anything unreadable is skipped.
+
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::util::bounded_archive::{read_metadata_from_tar_gz, read_metadata_from_zip};
+
+/// Stored text per document is capped at this many bytes. READMEs past it
+/// are almost always generated API dumps; the head is what search needs.
+pub const MAX_PACKAGE_DOC_BYTES: usize = 256 * 1024;
+
+/// README and changelog text extracted from one package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageDocs {
+    pub readme: Option<String>,
+    pub changelog: Option<String>,
+}
+
+impl PackageDocs {
+    pub fn is_empty(&self) -> bool {
+        self.readme.is_none() && self.changelog.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocKind {
+    Readme,
+    Changelog,
+}
+
+/// Classify an archive member as the package README or changelog.
+///
+/// Only files directly under the archive's single top-level directory count
+/// (`package/README.md` in an npm tarball, `foo-1.0.0/CHANGELOG.md` in a
+/// crate), so READMEs of vendored dependencies or examples are ignored.
+fn classify_doc_path(path: &str) -> Option<DocKind> {
+    let mut parts = path.trim_start_matches("./").split('/');
+    let (Some(_root), Some(file), None) = (parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    let file = file.to_ascii_lowercase();
+    let stem = file.split('.').next().unwrap_or_default();
+    match stem {
+        "readme" => Some(DocKind::Readme),
+        "changelog" | "changes" | "history" | "news" => Some(DocKind::Changelog),
+        _ => None,
+    }
+}
+
+/// Cap `text` at [`MAX_PACKAGE_DOC_BYTES`] on a char boundary. Blank text
+/// yields `None`.
+fn cap_doc(text: String) -> Option<String> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    if text.len() <= MAX_PACKAGE_DOC_BYTES {
+        return Some(text);
+    }
+    let mut end = MAX_PACKAGE_DOC_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(text[..end].to_string())
+}
+
+fn read_tar_gz_doc(content: &[u8], kind: DocKind) -> Option<String> {
+    let matches = |path: &Path| classify_doc_path(&path.to_string_lossy()) == Some(kind);
+    let bytes = read_metadata_from_tar_gz(content, matches).ok()??;
+    cap_doc(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Extract the README and changelog from a gzip-compressed tarball (npm
+/// `.tgz`, Cargo `.crate`).
+pub fn extract_from_tar_gz(content: &[u8]) -> PackageDocs {
+    PackageDocs {
+        readme: read_tar_gz_doc(content, DocKind::Readme),
+        changelog: read_tar_gz_doc(content, DocKind::Changelog),
+    }
+}
+
+/// Docs from README text supplied outside the archive (the `description`
+/// field of a PyPI upload).
+pub fn from_readme_text(text: &str) -> PackageDocs {
+    PackageDocs {
+        readme: cap_doc(text.to_string()),
+        changelog: None,
+    }
+}
+
+/// Extract the README from a wheel. A wheel ships no README file; its long
+/// description is the body of `*.dist-info/METADATA` after the header block
+/// (or, for older metadata versions, the `Description:` header).
+pub fn extract_from_wheel<R: Read + Seek>(reader: R) -> PackageDocs {
+    let metadata = read_metadata_from_zip(reader, |name| {
+        name.ends_with(".dist-info/METADATA") && name.matches('/').count() == 1
+    })
+    .ok()
+    .flatten()
+    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+
+    PackageDocs {
+        readme: metadata.as_deref().and_then(wheel_long_description),
+        changelog: None,
+    }
+}
+
+/// Long description from core-metadata text: the message body when present,
+/// otherwise the (continuation-indented) `Description:` header.
+fn wheel_long_description(metadata: &str) -> Option<String> {
+    let metadata = metadata.replace("\r\n", "\n");
+    if let Some((headers, body)) = metadata.split_once("\n\n") {
+        if !body.trim().is_empty() {
+            return cap_doc(body.to_string());
+        }
+        let mut lines = headers.lines();
+        while let Some(line) = lines.next() {
+            if let Some(first) = line.strip_prefix("Description:") {
+                let mut description = first.trim_start().to_string();
+                for cont in lines.by_ref() {
+                    let Some(cont) = cont.strip_prefix("        ").or(cont.strip_prefix('\t'))
+                    else {
+                        break;
+                    };
+                    description.push('\n');
+                    description.push_str(cont.strip_prefix('|').unwrap_or(cont));
+                }
+                return cap_doc(description);
+            }
+        }
+    }
+    None
+}
+
+/// Record extracted docs for an artifact. Best-effort: failures are logged.
+pub async fn record_package_docs(db: &PgPool, artifact_id: Uuid, docs: &PackageDocs) {
+    if docs.is_empty() {
+        return;
+    }
+    let result = sqlx::query(
+        r#"
+        INSERT INTO artifact_docs (artifact_id, readme, changelog)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (artifact_id) DO UPDATE
+        SET readme = EXCLUDED.readme,
+            changelog = EXCLUDED.changelog,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(artifact_id)
+    .bind(docs.readme.as_deref())
+    .bind(docs.changelog.as_deref())
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!(
+            "Failed to record README/changelog for artifact {}: {}",
+            artifact_id,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_gz(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (name, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        use std::io::Write;
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        {
+            let mut w = zip::ZipWriter::new(&mut cursor);
+            let opts: zip::write::FileOptions<'_, ()> = zip::write::FileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            for (name, data) in entries {
+                w.start_file(*name, opts).unwrap();
+                w.write_all(data).unwrap();
+            }
+            w.finish().unwrap();
+        }
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_classify_doc_path() {
+        assert_eq!(
+            classify_doc_path("package/README.md"),
+            Some(DocKind::Readme)
+        );
+        assert_eq!(classify_doc_path("foo-1.0.0/readme"), Some(DocKind::Readme));
+        assert_eq!(
+            classify_doc_path("package/CHANGELOG.md"),
+            Some(DocKind::Changelog)
+        );
+        assert_eq!(
+            classify_doc_path("./package/HISTORY.rst"),
+            Some(DocKind::Changelog)
+        );
+        assert_eq!(classify_doc_path("README.md"), None);
+        assert_eq!(
+            classify_doc_path("package/node_modules/dep/README.md"),
+            None
+        );
+        assert_eq!(classify_doc_path("package/readmeify.js"), None);
+    }
+
+    #[test]
+    fn test_extract_from_tar_gz() {
+        let tgz = tar_gz(&[
+            ("package/package.json", b"{}"),
+            ("package/lib/README.md", b"nested"),
+            ("package/README.md", b"# left-pad\nPads strings."),
+            ("package/CHANGELOG.md", b"## 1.3.0\n- faster"),
+        ]);
+        let docs = extract_from_tar_gz(&tgz);
+        assert_eq!(docs.readme.as_deref(), Some("# left-pad\nPads strings."));
+        assert_eq!(docs.changelog.as_deref(), Some("## 1.3.0\n- faster"));
+    }
+
+    #[test]
+    fn test_extract_from_tar_gz_without_docs_or_invalid() {
+        let tgz = tar_gz(&[("package/index.js", b"")]);
+        assert!(extract_from_tar_gz(&tgz).is_empty());
+        assert!(extract_from_tar_gz(b"not a tarball").is_empty());
+    }
+
+    #[test]
+    fn test_extract_from_wheel_body() {
+        let metadata =
+            b"Metadata-Version: 2.1\nName: demo\nVersion: 1.0\n\n# Demo\n\nDoes things.\n";
+        let whl = zip_bytes(&[
+            ("demo/__init__.py", b""),
+            ("demo-1.0.dist-info/METADATA", metadata),
+        ]);
+        let docs = extract_from_wheel(std::io::Cursor::new(whl));
+        assert_eq!(docs.readme.as_deref(), Some("# Demo\n\nDoes things.\n"));
+        assert_eq!(docs.changelog, None);
+    }
+
+    #[test]
+    fn test_wheel_long_description_header_form() {
+        let metadata = "Metadata-Version: 1.2\nName: old\nDescription: Old style\n        |second line\nKeywords: x\n\n";
+        assert_eq!(
+            wheel_long_description(metadata).as_deref(),
+            Some("Old style\nsecond line")
+        );
+        assert_eq!(wheel_long_description("Name: x\n\n   \n"), None);
+    }
+
+    #[test]
+    fn test_cap_doc_truncates_on_char_boundary() {
+        let text = "é".repeat(MAX_PACKAGE_DOC_BYTES);
+        let capped = cap_doc(text).unwrap();
+        assert!(capped.len() <= MAX_PACKAGE_DOC_BYTES);
+        assert!(capped.chars().all(|c| c == 'é'));
+        assert_eq!(cap_doc("  \n".to_string()), None);
+    }
+}
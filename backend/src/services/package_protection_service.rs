@@ -0,0 +1,883 @@
+//! Dependency-confusion and typosquatting protection for remote repositories.
+//!
+//! A remote repository with a row in `package_protection_policies` has every
+//! proxied package name checked before the proxy fetches it or serves it from
+//! its cache ([`check_upstream_fetch`], called from `ProxyService`):
+//!
+//! * **Dependency confusion** — the name is internal: published in a local or
+//!   staging repository of the same ecosystem, or matching one of the
+//!   policy's `internal_patterns`. Fetching it from a public upstream is how
+//!   an attacker's higher-versioned public package replaces the internal one.
+//! * **Typosquatting** — the name is not internal but lies within
+//!   `typosquat_max_distance` edits of a popular internal package.
+//!
+//! Each check alerts (records a finding and serves) or blocks (records a
+//! finding and answers 403). Virtual repositories are covered through their
+//! remote members, whose fetches go through the same proxy path; a 403 from
+//! a member surfaces from virtual resolution like a quarantine block.
+//!
+//! Names are only extracted for formats whose proxy paths identify a package
+//! (npm, PyPI, NuGet, Cargo, RubyGems, Go and Maven); other formats pass.
+//! Maven names are `groupId:artifactId`, which local uploads do not record,
+//! so Maven relies on `internal_patterns`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::repository::{Repository, RepositoryFormat};
+use crate::util::glob::glob_match;
+
+/// How long policies and the internal-name snapshot are reused.
+const POLICY_CACHE_TTL: Duration = Duration::from_secs(30);
+const SNAPSHOT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Repeat hits of the same finding within this window are not re-recorded.
+const FINDING_DEBOUNCE: Duration = Duration::from_secs(60);
+
+/// Internal packages per ecosystem considered for typosquatting, by downloads
+/// over [`POPULARITY_WINDOW_DAYS`].
+const POPULAR_PER_ECOSYSTEM: usize = 1000;
+const POPULARITY_WINDOW_DAYS: i32 = 30;
+
+/// Internal names shorter than this are too short to tell a typo from an
+/// unrelated package and are not typosquatting targets.
+const MIN_TYPOSQUAT_NAME_LEN: usize = 5;
+
+pub const KIND_DEPENDENCY_CONFUSION: &str = "dependency_confusion";
+pub const KIND_TYPOSQUATTING: &str = "typosquatting";
+
+/// What a check does when it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProtectionAction {
+    Off,
+    Alert,
+    Block,
+}
+
+impl ProtectionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Alert => "alert",
+            Self::Block => "block",
+        }
+    }
+
+    fn from_db(s: &str) -> Self {
+        match s {
+            "alert" => Self::Alert,
+            "block" => Self::Block,
+            _ => Self::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct PolicyRow {
+    repository_id: Uuid,
+    repository_key: String,
+    dependency_confusion_action: String,
+    typosquatting_action: String,
+    internal_patterns: Vec<String>,
+    allowed_patterns: Vec<String>,
+    typosquat_max_distance: i32,
+    updated_at: DateTime<Utc>,
+}
+
+/// Protection policy of one remote repository.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProtectionPolicy {
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub dependency_confusion_action: ProtectionAction,
+    pub typosquatting_action: ProtectionAction,
+    pub internal_patterns: Vec<String>,
+    pub allowed_patterns: Vec<String>,
+    pub typosquat_max_distance: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<PolicyRow> for ProtectionPolicy {
+    fn from(row: PolicyRow) -> Self {
+        Self {
+            repository_id: row.repository_id,
+            repository_key: row.repository_key,
+            dependency_confusion_action: ProtectionAction::from_db(
+                &row.dependency_confusion_action,
+            ),
+            typosquatting_action: ProtectionAction::from_db(&row.typosquatting_action),
+            internal_patterns: row.internal_patterns,
+            allowed_patterns: row.allowed_patterns,
+            typosquat_max_distance: row.typosquat_max_distance,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// Body of a policy update. Omitted fields take their defaults.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateProtectionPolicy {
+    #[serde(default = "default_dependency_confusion_action")]
+    pub dependency_confusion_action: ProtectionAction,
+    #[serde(default = "default_typosquatting_action")]
+    pub typosquatting_action: ProtectionAction,
+    #[serde(default)]
+    pub internal_patterns: Vec<String>,
+    #[serde(default)]
+    pub allowed_patterns: Vec<String>,
+    #[serde(default = "default_max_distance")]
+    pub typosquat_max_distance: i32,
+}
+
+fn default_dependency_confusion_action() -> ProtectionAction {
+    ProtectionAction::Block
+}
+
+fn default_typosquatting_action() -> ProtectionAction {
+    ProtectionAction::Alert
+}
+
+fn default_max_distance() -> i32 {
+    1
+}
+
+impl UpdateProtectionPolicy {
+    /// Trim and lower-case patterns and reject out-of-range settings.
+    pub fn normalized(mut self) -> Result<Self> {
+        if !(1..=3).contains(&self.typosquat_max_distance) {
+            return Err(AppError::Validation(
+                "typosquat_max_distance must be between 1 and 3".to_string(),
+            ));
+        }
+        for patterns in [&mut self.internal_patterns, &mut self.allowed_patterns] {
+            let mut cleaned: Vec<String> = patterns
+                .iter()
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect();
+            cleaned.sort();
+            cleaned.dedup();
+            if cleaned.len() > 500 || cleaned.iter().any(|p| p.len() > 512) {
+                return Err(AppError::Validation(
+                    "At most 500 patterns of up to 512 characters each".to_string(),
+                ));
+            }
+            *patterns = cleaned;
+        }
+        Ok(self)
+    }
+}
+
+/// A recorded detection.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ProtectionFinding {
+    pub id: Uuid,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    /// `dependency_confusion` or `typosquatting`.
+    pub kind: String,
+    /// Name requested from the upstream.
+    pub package_name: String,
+    /// Internal name (or pattern) it collided with.
+    pub internal_name: String,
+    /// Action applied on the most recent hit: `alert` or `block`.
+    pub action: String,
+    pub last_path: Option<String>,
+    pub hit_count: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// Outcome of evaluating one name against a policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detection {
+    pub kind: &'static str,
+    pub internal_name: String,
+    pub action: ProtectionAction,
+}
+
+/// Ecosystem whose local packages a remote repository of `format` shadows.
+/// Aliases share their base ecosystem (Yarn proxies npmjs, Poetry PyPI).
+pub(crate) fn ecosystem(format: &RepositoryFormat) -> Option<&'static str> {
+    match format {
+        RepositoryFormat::Npm
+        | RepositoryFormat::Yarn
+        | RepositoryFormat::Bower
+        | RepositoryFormat::Pnpm => Some("npm"),
+        RepositoryFormat::Pypi | RepositoryFormat::Poetry => Some("pypi"),
+        RepositoryFormat::Nuget | RepositoryFormat::Chocolatey | RepositoryFormat::Powershell => {
+            Some("nuget")
+        }
+        RepositoryFormat::Cargo => Some("cargo"),
+        RepositoryFormat::Rubygems => Some("gem"),
+        RepositoryFormat::Go => Some("golang"),
+        RepositoryFormat::Maven | RepositoryFormat::Gradle | RepositoryFormat::Sbt => Some("maven"),
+        _ => None,
+    }
+}
+
+/// [`ecosystem`] for a `repositories.format::text` value.
+fn ecosystem_of_text(format: &str) -> Option<&'static str> {
+    match format {
+        "npm" | "yarn" | "bower" | "pnpm" => Some("npm"),
+        "pypi" | "poetry" => Some("pypi"),
+        "nuget" | "chocolatey" | "powershell" => Some("nuget"),
+        "cargo" => Some("cargo"),
+        "rubygems" => Some("gem"),
+        "go" => Some("golang"),
+        "maven" | "gradle" | "sbt" => Some("maven"),
+        _ => None,
+    }
+}
+
+/// Name as compared within an ecosystem: lower-case, and PEP 503 for PyPI.
+fn normalize(ecosystem: &str, name: &str) -> String {
+    if ecosystem == "pypi" {
+        crate::formats::pypi::PypiHandler::normalize_name(name)
+    } else {
+        name.to_lowercase()
+    }
+}
+
+/// Package name addressed by a proxy path, or `None` for index pages and
+/// formats without a parser here.
+pub(crate) fn package_name_from_path(format: &RepositoryFormat, path: &str) -> Option<String> {
+    let path = path.trim_start_matches('/');
+    let name = match ecosystem(format)? {
+        "npm" => {
+            // Packuments are `name` or `@scope/name` (often `%2f`-encoded);
+            // tarballs are `name/-/file.tgz`.
+            let decoded = urlencoding::decode(path).ok()?;
+            let mut segments = decoded.split('/').filter(|s| !s.is_empty());
+            let first = segments.next()?;
+            if first.starts_with('@') {
+                Some(format!("{}/{}", first, segments.next()?))
+            } else {
+                Some(first.to_string())
+            }
+        }
+        "pypi" => {
+            crate::formats::pypi::PypiHandler::parse_path(path)
+                .ok()?
+                .name
+        }
+        "nuget" => {
+            crate::formats::nuget::NugetHandler::parse_path(path)
+                .ok()?
+                .id
+        }
+        "cargo" => {
+            crate::formats::cargo::CargoHandler::parse_path(path)
+                .ok()?
+                .name
+        }
+        "gem" => {
+            crate::formats::rubygems::RubygemsHandler::parse_path(path)
+                .ok()?
+                .name
+        }
+        "golang" => Some(crate::formats::go::GoHandler::parse_path(path).ok()?.module),
+        "maven" => crate::services::proxy_service::maven_proxy_package_name(path),
+        _ => None,
+    };
+    name.filter(|name| !name.is_empty())
+}
+
+/// Optimal-string-alignment distance (Levenshtein plus adjacent
+/// transposition), or `None` once it is certain to exceed `max`.
+pub(crate) fn edit_distance_within(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let width = b.len() + 1;
+    let mut prev2 = vec![0usize; width];
+    let mut prev: Vec<usize> = (0..width).collect();
+    let mut cur = vec![0usize; width];
+    for i in 1..=a.len() {
+        cur[0] = i;
+        let mut row_min = cur[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(prev2[j - 2] + 1);
+            }
+            cur[j] = d;
+            row_min = row_min.min(d);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    Some(prev[b.len()]).filter(|d| *d <= max)
+}
+
+/// Internal package names of one ecosystem.
+#[derive(Debug, Default)]
+pub struct InternalNames {
+    names: HashSet<String>,
+    /// Most-downloaded internal names, most popular first.
+    popular: Vec<String>,
+}
+
+impl InternalNames {
+    pub fn new(names: HashSet<String>, popular: Vec<String>) -> Self {
+        Self { names, popular }
+    }
+}
+
+/// Evaluate a (normalized) package name. Dependency confusion takes
+/// precedence: an internal name is never also a typosquat.
+pub fn evaluate(
+    policy: &ProtectionPolicy,
+    internal: &InternalNames,
+    name: &str,
+) -> Option<Detection> {
+    if policy.allowed_patterns.iter().any(|p| glob_match(p, name)) {
+        return None;
+    }
+
+    let internal_match = if internal.names.contains(name) {
+        Some(name.to_string())
+    } else {
+        policy
+            .internal_patterns
+            .iter()
+            .find(|p| glob_match(p, name))
+            .cloned()
+    };
+    if let Some(internal_name) = internal_match {
+        return (policy.dependency_confusion_action != ProtectionAction::Off).then(|| Detection {
+            kind: KIND_DEPENDENCY_CONFUSION,
+            internal_name,
+            action: policy.dependency_confusion_action,
+        });
+    }
+
+    if policy.typosquatting_action == ProtectionAction::Off {
+        return None;
+    }
+    let max = policy.typosquat_max_distance.clamp(1, 3) as usize;
+    internal
+        .popular
+        .iter()
+        .filter(|candidate| candidate.chars().count() >= MIN_TYPOSQUAT_NAME_LEN)
+        .find(|candidate| edit_distance_within(name, candidate, max).is_some_and(|d| d > 0))
+        .map(|candidate| Detection {
+            kind: KIND_TYPOSQUATTING,
+            internal_name: candidate.clone(),
+            action: policy.typosquatting_action,
+        })
+}
+
+type PolicyMap = Arc<HashMap<Uuid, ProtectionPolicy>>;
+type Snapshot = Arc<HashMap<&'static str, InternalNames>>;
+
+static POLICY_CACHE: Lazy<Cache<(), PolicyMap>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(1)
+        .time_to_live(POLICY_CACHE_TTL)
+        .build()
+});
+
+static SNAPSHOT_CACHE: Lazy<Cache<(), Snapshot>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(1)
+        .time_to_live(SNAPSHOT_CACHE_TTL)
+        .build()
+});
+
+static RECENT_FINDINGS: Lazy<Cache<(Uuid, &'static str, String), ()>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(10_000)
+        .time_to_live(FINDING_DEBOUNCE)
+        .build()
+});
+
+const POLICY_SELECT: &str = r#"
+    SELECT p.repository_id, r.key AS repository_key, p.dependency_confusion_action,
+           p.typosquatting_action, p.internal_patterns, p.allowed_patterns,
+           p.typosquat_max_distance, p.updated_at
+    FROM package_protection_policies p
+    JOIN repositories r ON r.id = p.repository_id
+"#;
+
+async fn cached_policies(db: &PgPool) -> Result<PolicyMap> {
+    if let Some(policies) = POLICY_CACHE.get(&()).await {
+        return Ok(policies);
+    }
+    let rows: Vec<PolicyRow> = sqlx::query_as(POLICY_SELECT)
+        .fetch_all(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let policies: PolicyMap = Arc::new(
+        rows.into_iter()
+            .map(|row| (row.repository_id, ProtectionPolicy::from(row)))
+            .collect(),
+    );
+    POLICY_CACHE.insert((), policies.clone()).await;
+    Ok(policies)
+}
+
+async fn load_snapshot(db: &PgPool) -> Result<Snapshot> {
+    let names: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT r.format::text, a.name
+        FROM artifacts a
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE r.repo_type IN ('local', 'staging') AND a.is_deleted = false
+        "#,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let downloads: Vec<(String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT r.format::text, a.name, COUNT(*) AS downloads
+        FROM download_statistics ds
+        JOIN artifacts a ON a.id = ds.artifact_id
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE r.repo_type IN ('local', 'staging') AND a.is_deleted = false
+          AND ds.downloaded_at > NOW() - make_interval(days => $1)
+        GROUP BY r.format, a.name
+        "#,
+    )
+    .bind(POPULARITY_WINDOW_DAYS)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let mut sets: HashMap<&'static str, HashSet<String>> = HashMap::new();
+    for (format, name) in names {
+        if let Some(eco) = ecosystem_of_text(&format) {
+            sets.entry(eco).or_default().insert(normalize(eco, &name));
+        }
+    }
+    let mut counts: HashMap<&'static str, HashMap<String, i64>> = HashMap::new();
+    for (format, name, n) in downloads {
+        if let Some(eco) = ecosystem_of_text(&format) {
+            *counts
+                .entry(eco)
+                .or_default()
+                .entry(normalize(eco, &name))
+                .or_default() += n;
+        }
+    }
+
+    let snapshot = sets
+        .into_iter()
+        .map(|(eco, names)| {
+            let mut ranked: Vec<(String, i64)> =
+                counts.remove(eco).unwrap_or_default().into_iter().collect();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let popular = ranked
+                .into_iter()
+                .map(|(name, _)| name)
+                .take(POPULAR_PER_ECOSYSTEM)
+                .collect();
+            (eco, InternalNames::new(names, popular))
+        })
+        .collect();
+    Ok(Arc::new(snapshot))
+}
+
+async fn cached_snapshot(db: &PgPool) -> Result<Snapshot> {
+    if let Some(snapshot) = SNAPSHOT_CACHE.get(&()).await {
+        return Ok(snapshot);
+    }
+    let snapshot = load_snapshot(db).await?;
+    SNAPSHOT_CACHE.insert((), snapshot.clone()).await;
+    Ok(snapshot)
+}
+
+/// Drop cached policies after they change.
+pub async fn invalidate_policies() {
+    POLICY_CACHE.invalidate(&()).await;
+}
+
+/// Check a proxy fetch of `path` through the remote repository `repo`.
+///
+/// Returns `Err(Authorization)` when a check in `block` mode fires; alerts
+/// are recorded and the fetch proceeds. A repository without a policy, a
+/// path without a package name, or a failure loading the policy data lets
+/// the fetch through: protection is opt-in and must not take the proxy down.
+pub async fn check_upstream_fetch(db: &PgPool, repo: &Repository, path: &str) -> Result<()> {
+    let policies = match cached_policies(db).await {
+        Ok(policies) => policies,
+        Err(e) => {
+            tracing::warn!("Package protection policies unavailable: {}", e);
+            return Ok(());
+        }
+    };
+    let Some(policy) = policies.get(&repo.id) else {
+        return Ok(());
+    };
+    let Some(eco) = ecosystem(&repo.format) else {
+        return Ok(());
+    };
+    let Some(name) = package_name_from_path(&repo.format, path) else {
+        return Ok(());
+    };
+    let name = normalize(eco, &name);
+
+    let snapshot = match cached_snapshot(db).await {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            tracing::warn!("Internal package names unavailable: {}", e);
+            return Ok(());
+        }
+    };
+    let empty = InternalNames::default();
+    let internal = snapshot.get(eco).unwrap_or(&empty);
+    let Some(detection) = evaluate(policy, internal, &name) else {
+        return Ok(());
+    };
+
+    record_finding(db, repo, &name, path, &detection).await;
+
+    if detection.action == ProtectionAction::Block {
+        return Err(AppError::Authorization(match detection.kind {
+            KIND_DEPENDENCY_CONFUSION => format!(
+                "Upstream fetch of '{}' blocked: the name belongs to an internal package",
+                name
+            ),
+            _ => format!(
+                "Upstream fetch of '{}' blocked: the name is a near miss of internal package '{}'",
+                name, detection.internal_name
+            ),
+        }));
+    }
+    Ok(())
+}
+
+/// Upsert the finding, at most once per [`FINDING_DEBOUNCE`] per key on this
+/// replica. Best-effort: a failure is logged, never surfaced.
+async fn record_finding(
+    db: &PgPool,
+    repo: &Repository,
+    name: &str,
+    path: &str,
+    detection: &Detection,
+) {
+    let key = (repo.id, detection.kind, name.to_string());
+    if RECENT_FINDINGS.contains_key(&key) {
+        return;
+    }
+    RECENT_FINDINGS.insert(key, ()).await;
+
+    tracing::warn!(
+        repository = %repo.key,
+        kind = detection.kind,
+        package = name,
+        internal_name = %detection.internal_name,
+        action = detection.action.as_str(),
+        "Package protection check fired"
+    );
+    let result = sqlx::query(
+        r#"
+        INSERT INTO package_protection_findings
+            (repository_id, kind, package_name, internal_name, action, last_path)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (repository_id, kind, package_name) DO UPDATE
+        SET internal_name = EXCLUDED.internal_name, action = EXCLUDED.action,
+            last_path = EXCLUDED.last_path,
+            hit_count = package_protection_findings.hit_count + 1,
+            last_seen_at = NOW()
+        "#,
+    )
+    .bind(repo.id)
+    .bind(detection.kind)
+    .bind(name)
+    .bind(&detection.internal_name)
+    .bind(detection.action.as_str())
+    .bind(path)
+    .execute(db)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to record package protection finding: {}", e);
+    }
+}
+
+pub struct PackageProtectionService {
+    db: PgPool,
+}
+
+impl PackageProtectionService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list_policies(&self) -> Result<Vec<ProtectionPolicy>> {
+        let rows: Vec<PolicyRow> = sqlx::query_as(&format!("{} ORDER BY r.key", POLICY_SELECT))
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(rows.into_iter().map(ProtectionPolicy::from).collect())
+    }
+
+    pub async fn get_policy(&self, repository_id: Uuid) -> Result<Option<ProtectionPolicy>> {
+        let row: Option<PolicyRow> =
+            sqlx::query_as(&format!("{} WHERE p.repository_id = $1", POLICY_SELECT))
+                .bind(repository_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(row.map(ProtectionPolicy::from))
+    }
+
+    pub async fn upsert_policy(
+        &self,
+        repository_id: Uuid,
+        update: &UpdateProtectionPolicy,
+        updated_by: Uuid,
+    ) -> Result<ProtectionPolicy> {
+        sqlx::query(
+            r#"
+            INSERT INTO package_protection_policies
+                (repository_id, dependency_confusion_action, typosquatting_action,
+                 internal_patterns, allowed_patterns, typosquat_max_distance, updated_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (repository_id) DO UPDATE
+            SET dependency_confusion_action = EXCLUDED.dependency_confusion_action,
+                typosquatting_action = EXCLUDED.typosquatting_action,
+                internal_patterns = EXCLUDED.internal_patterns,
+                allowed_patterns = EXCLUDED.allowed_patterns,
+                typosquat_max_distance = EXCLUDED.typosquat_max_distance,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(repository_id)
+        .bind(update.dependency_confusion_action.as_str())
+        .bind(update.typosquatting_action.as_str())
+        .bind(&update.internal_patterns)
+        .bind(&update.allowed_patterns)
+        .bind(update.typosquat_max_distance)
+        .bind(updated_by)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        invalidate_policies().await;
+
+        self.get_policy(repository_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Repository not found".to_string()))
+    }
+
+    pub async fn delete_policy(&self, repository_id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM package_protection_policies WHERE repository_id = $1")
+                .bind(repository_id)
+                .execute(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        invalidate_policies().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_findings(
+        &self,
+        repository_id: Option<Uuid>,
+        kind: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<ProtectionFinding>, i64)> {
+        let items: Vec<ProtectionFinding> = sqlx::query_as(
+            r#"
+            SELECT f.id, f.repository_id, r.key AS repository_key, f.kind, f.package_name,
+                   f.internal_name, f.action, f.last_path, f.hit_count,
+                   f.first_seen_at, f.last_seen_at
+            FROM package_protection_findings f
+            JOIN repositories r ON r.id = f.repository_id
+            WHERE ($1::UUID IS NULL OR f.repository_id = $1)
+              AND ($2::TEXT IS NULL OR f.kind = $2)
+            ORDER BY f.last_seen_at DESC, f.id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(repository_id)
+        .bind(kind)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM package_protection_findings
+            WHERE ($1::UUID IS NULL OR repository_id = $1)
+              AND ($2::TEXT IS NULL OR kind = $2)
+            "#,
+        )
+        .bind(repository_id)
+        .bind(kind)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((items, total))
+    }
+
+    pub async fn delete_finding(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM package_protection_findings WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Finding not found".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(dc: ProtectionAction, ts: ProtectionAction) -> ProtectionPolicy {
+        ProtectionPolicy {
+            repository_id: Uuid::new_v4(),
+            repository_key: "npmjs".to_string(),
+            dependency_confusion_action: dc,
+            typosquatting_action: ts,
+            internal_patterns: vec!["@acme/*".to_string()],
+            allowed_patterns: vec![],
+            typosquat_max_distance: 1,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn internal() -> InternalNames {
+        InternalNames::new(
+            ["acme-utils", "billing-client", "db"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            vec![
+                "billing-client".to_string(),
+                "acme-utils".to_string(),
+                "db".to_string(),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_edit_distance_within() {
+        assert_eq!(edit_distance_within("lodash", "lodash", 1), Some(0));
+        assert_eq!(edit_distance_within("lodash", "lodahs", 1), Some(1));
+        assert_eq!(edit_distance_within("lodash", "1odash", 1), Some(1));
+        assert_eq!(edit_distance_within("lodash", "lodas", 1), Some(1));
+        assert_eq!(edit_distance_within("lodash", "lod", 1), None);
+        assert_eq!(edit_distance_within("kitten", "sitting", 2), None);
+        assert_eq!(edit_distance_within("kitten", "sitting", 3), Some(3));
+    }
+
+    #[test]
+    fn test_dependency_confusion_by_published_name_and_pattern() {
+        let p = policy(ProtectionAction::Block, ProtectionAction::Alert);
+        let d = evaluate(&p, &internal(), "acme-utils").unwrap();
+        assert_eq!(d.kind, KIND_DEPENDENCY_CONFUSION);
+        assert_eq!(d.action, ProtectionAction::Block);
+
+        let d = evaluate(&p, &internal(), "@acme/secret-sdk").unwrap();
+        assert_eq!(d.kind, KIND_DEPENDENCY_CONFUSION);
+        assert_eq!(d.internal_name, "@acme/*");
+
+        assert!(evaluate(&p, &internal(), "react").is_none());
+    }
+
+    #[test]
+    fn test_typosquatting_near_miss_of_popular_name() {
+        let p = policy(ProtectionAction::Block, ProtectionAction::Alert);
+        let d = evaluate(&p, &internal(), "biling-client").unwrap();
+        assert_eq!(d.kind, KIND_TYPOSQUATTING);
+        assert_eq!(d.internal_name, "billing-client");
+        assert_eq!(d.action, ProtectionAction::Alert);
+
+        // Too short to judge.
+        assert!(evaluate(&p, &internal(), "dv").is_none());
+    }
+
+    #[test]
+    fn test_off_and_allowed_patterns() {
+        let mut p = policy(ProtectionAction::Off, ProtectionAction::Off);
+        assert!(evaluate(&p, &internal(), "acme-utils").is_none());
+        assert!(evaluate(&p, &internal(), "acme-util").is_none());
+
+        p = policy(ProtectionAction::Block, ProtectionAction::Block);
+        p.allowed_patterns = vec!["acme-util*".to_string()];
+        assert!(evaluate(&p, &internal(), "acme-utils").is_none());
+        assert!(evaluate(&p, &internal(), "acme-util").is_none());
+    }
+
+    #[test]
+    fn test_internal_name_is_not_reported_as_typosquat() {
+        // Typosquatting off must not turn an internal hit into a typosquat.
+        let p = policy(ProtectionAction::Off, ProtectionAction::Block);
+        assert!(evaluate(&p, &internal(), "acme-utils").is_none());
+    }
+
+    #[test]
+    fn test_package_name_from_path() {
+        let npm = RepositoryFormat::Npm;
+        assert_eq!(
+            package_name_from_path(&npm, "@acme%2futils").as_deref(),
+            Some("@acme/utils")
+        );
+        assert_eq!(
+            package_name_from_path(&npm, "lodash/-/lodash-4.17.21.tgz").as_deref(),
+            Some("lodash")
+        );
+        assert_eq!(
+            package_name_from_path(&RepositoryFormat::Pypi, "simple/Acme_Utils/").as_deref(),
+            Some("acme-utils")
+        );
+        assert_eq!(
+            package_name_from_path(&RepositoryFormat::Maven, "com/acme/core/1.0/core-1.0.jar")
+                .as_deref(),
+            Some("com.acme:core")
+        );
+        assert_eq!(
+            package_name_from_path(&RepositoryFormat::Docker, "v2/x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_update_policy_normalization() {
+        let update = UpdateProtectionPolicy {
+            dependency_confusion_action: ProtectionAction::Block,
+            typosquatting_action: ProtectionAction::Alert,
+            internal_patterns: vec![" @Acme/* ".into(), "".into(), "@acme/*".into()],
+            allowed_patterns: vec![],
+            typosquat_max_distance: 2,
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(update.internal_patterns, vec!["@acme/*".to_string()]);
+
+        let bad = UpdateProtectionPolicy {
+            typosquat_max_distance: 4,
+            ..update
+        };
+        assert!(bad.normalized().is_err());
+    }
+}
@@ -0,0 +1,592 @@
+//! Edge node auto-registration.
+//!
+//! Admins issue join tokens. A new node presents one to
+//! `POST /api/v1/peers/join` together with its hostname, endpoint and key
+//! fingerprint, and receives a node secret. The request waits in a pending
+//! queue; approving it creates the `peer_instances` row (which is what lets
+//! heartbeats and sync policies apply to the node), rejecting it closes it.
+//!
+//! The node uses its secret to poll `GET /api/v1/peers/join/:id` and, once
+//! approved, to report heartbeats. Only SHA-256 digests of join tokens and
+//! node secrets are stored.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// Prefix that makes join tokens recognizable in logs and secret scans.
+pub const JOIN_TOKEN_PREFIX: &str = "akjt_";
+
+/// Prefix of the per-node secret returned by a successful join.
+pub const NODE_SECRET_PREFIX: &str = "akns_";
+
+/// Default and maximum join token lifetime.
+pub const DEFAULT_TOKEN_TTL_HOURS: i64 = 24;
+pub const MAX_TOKEN_TTL_HOURS: i64 = 24 * 30;
+
+/// Upper bound on requests waiting for review, so a leaked token cannot
+/// flood the queue.
+const MAX_PENDING_REQUESTS: i64 = 100;
+
+const STATUS_PENDING: &str = "pending";
+const STATUS_APPROVED: &str = "approved";
+const STATUS_REJECTED: &str = "rejected";
+
+fn generate_secret(prefix: &str) -> String {
+    let bytes: [u8; 32] = rand::random();
+    format!("{}{}", prefix, hex::encode(bytes))
+}
+
+/// SHA-256 of a token or node secret, as stored.
+pub fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.trim().as_bytes()))
+}
+
+// ---------------------------------------------------------------------------
+// Join tokens
+// ---------------------------------------------------------------------------
+
+/// A join token as listed to admins (never includes the token itself).
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JoinToken {
+    pub id: Uuid,
+    pub description: Option<String>,
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Parameters for a new join token.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateJoinToken {
+    pub description: Option<String>,
+    /// Lifetime in hours (default 24, at most 720).
+    pub expires_in_hours: Option<i64>,
+    /// Number of nodes that may join with this token; unlimited when omitted.
+    pub max_uses: Option<i32>,
+}
+
+impl CreateJoinToken {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(hours) = self.expires_in_hours {
+            if !(1..=MAX_TOKEN_TTL_HOURS).contains(&hours) {
+                return Err(AppError::Validation(format!(
+                    "expires_in_hours must be between 1 and {}",
+                    MAX_TOKEN_TTL_HOURS
+                )));
+            }
+        }
+        if matches!(self.max_uses, Some(n) if n < 1) {
+            return Err(AppError::Validation(
+                "max_uses must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+const TOKEN_COLUMNS: &str =
+    "id, description, max_uses, use_count, expires_at, revoked_at, created_by, created_at";
+
+fn token_from_row(row: &sqlx::postgres::PgRow) -> JoinToken {
+    JoinToken {
+        id: row.get("id"),
+        description: row.get("description"),
+        max_uses: row.get("max_uses"),
+        use_count: row.get("use_count"),
+        expires_at: row.get("expires_at"),
+        revoked_at: row.get("revoked_at"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Issue a join token. Returns the stored record and the plaintext token,
+/// which is shown once.
+pub async fn create_token(
+    db: &PgPool,
+    req: &CreateJoinToken,
+    created_by: Uuid,
+) -> Result<(JoinToken, String)> {
+    req.validate()?;
+    let token = generate_secret(JOIN_TOKEN_PREFIX);
+    let ttl = req.expires_in_hours.unwrap_or(DEFAULT_TOKEN_TTL_HOURS);
+    let row = sqlx::query(&format!(
+        "INSERT INTO peer_join_tokens (description, token_hash, max_uses, expires_at, created_by) \
+         VALUES ($1, $2, $3, $4, $5) \
+         RETURNING {TOKEN_COLUMNS}"
+    ))
+    .bind(&req.description)
+    .bind(hash_secret(&token))
+    .bind(req.max_uses)
+    .bind(Utc::now() + Duration::hours(ttl))
+    .bind(created_by)
+    .fetch_one(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok((token_from_row(&row), token))
+}
+
+pub async fn list_tokens(db: &PgPool) -> Result<Vec<JoinToken>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {TOKEN_COLUMNS} FROM peer_join_tokens ORDER BY created_at DESC"
+    ))
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(rows.iter().map(token_from_row).collect())
+}
+
+/// Revoke a token. Requests already made with it are unaffected.
+pub async fn revoke_token(db: &PgPool, id: Uuid) -> Result<()> {
+    let result = sqlx::query(
+        "UPDATE peer_join_tokens SET revoked_at = COALESCE(revoked_at, NOW()) WHERE id = $1",
+    )
+    .bind(id)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Join token not found".to_string()));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Join requests
+// ---------------------------------------------------------------------------
+
+/// What a node reports when it asks to join.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct JoinRequest {
+    pub join_token: String,
+    /// Peer name to register under; defaults to the hostname.
+    pub name: Option<String>,
+    pub hostname: String,
+    pub endpoint_url: String,
+    pub region: Option<String>,
+    pub cache_size_bytes: Option<i64>,
+    /// Fingerprint of the node's key, shown to the reviewing admin so it can
+    /// be compared with the node out of band.
+    pub fingerprint: String,
+    /// Key this instance should present to the node once approved.
+    #[serde(default)]
+    pub api_key: String,
+}
+
+impl JoinRequest {
+    pub fn peer_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or(self.hostname.trim())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let hostname = self.hostname.trim();
+        if hostname.is_empty() || hostname.len() > 255 {
+            return Err(AppError::Validation(
+                "hostname must be 1-255 characters".to_string(),
+            ));
+        }
+        if self.peer_name().len() > 255 {
+            return Err(AppError::Validation(
+                "name must be at most 255 characters".to_string(),
+            ));
+        }
+        let fingerprint = self.fingerprint.trim();
+        if fingerprint.is_empty() || fingerprint.len() > 255 {
+            return Err(AppError::Validation(
+                "fingerprint must be 1-255 characters".to_string(),
+            ));
+        }
+        if self.api_key.len() > 255 {
+            return Err(AppError::Validation(
+                "api_key must be at most 255 characters".to_string(),
+            ));
+        }
+        if matches!(self.cache_size_bytes, Some(n) if n < 0) {
+            return Err(AppError::Validation(
+                "cache_size_bytes must not be negative".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A join request as shown in the review queue.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JoinRequestRecord {
+    pub id: Uuid,
+    pub join_token_id: Option<Uuid>,
+    pub name: String,
+    pub hostname: String,
+    pub endpoint_url: String,
+    pub region: Option<String>,
+    pub cache_size_bytes: i64,
+    pub fingerprint: String,
+    /// `pending`, `approved` or `rejected`.
+    pub status: String,
+    pub peer_instance_id: Option<Uuid>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub rejection_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+const REQUEST_COLUMNS: &str = "id, join_token_id, name, hostname, endpoint_url, region, \
+     cache_size_bytes, fingerprint, status, peer_instance_id, reviewed_by, reviewed_at, \
+     rejection_reason, created_at";
+
+fn request_from_row(row: &sqlx::postgres::PgRow) -> JoinRequestRecord {
+    JoinRequestRecord {
+        id: row.get("id"),
+        join_token_id: row.get("join_token_id"),
+        name: row.get("name"),
+        hostname: row.get("hostname"),
+        endpoint_url: row.get("endpoint_url"),
+        region: row.get("region"),
+        cache_size_bytes: row.get("cache_size_bytes"),
+        fingerprint: row.get("fingerprint"),
+        status: row.get("status"),
+        peer_instance_id: row.get("peer_instance_id"),
+        reviewed_by: row.get("reviewed_by"),
+        reviewed_at: row.get("reviewed_at"),
+        rejection_reason: row.get("rejection_reason"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Redeem a join token and queue the node for review. Returns the queued
+/// request and the node secret, which is shown once.
+pub async fn submit(db: &PgPool, req: &JoinRequest) -> Result<(JoinRequestRecord, String)> {
+    req.validate()?;
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let token_id: Option<Uuid> = sqlx::query_scalar(
+        "UPDATE peer_join_tokens SET use_count = use_count + 1 \
+         WHERE token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW() \
+           AND (max_uses IS NULL OR use_count < max_uses) \
+         RETURNING id",
+    )
+    .bind(hash_secret(&req.join_token))
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let Some(token_id) = token_id else {
+        return Err(AppError::Authentication(
+            "Invalid, expired or exhausted join token".to_string(),
+        ));
+    };
+
+    let pending: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM peer_join_requests WHERE status = $1")
+            .bind(STATUS_PENDING)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    if pending >= MAX_PENDING_REQUESTS {
+        return Err(AppError::Conflict(
+            "Too many join requests are awaiting review".to_string(),
+        ));
+    }
+
+    let secret = generate_secret(NODE_SECRET_PREFIX);
+    let row = sqlx::query(&format!(
+        "INSERT INTO peer_join_requests ( \
+             join_token_id, name, hostname, endpoint_url, region, cache_size_bytes, \
+             fingerprint, api_key, node_secret_hash \
+         ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+         RETURNING {REQUEST_COLUMNS}"
+    ))
+    .bind(token_id)
+    .bind(req.peer_name())
+    .bind(req.hostname.trim())
+    .bind(&req.endpoint_url)
+    .bind(&req.region)
+    .bind(req.cache_size_bytes.unwrap_or(10 * 1024 * 1024 * 1024))
+    .bind(req.fingerprint.trim())
+    .bind(&req.api_key)
+    .bind(hash_secret(&secret))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        if e.to_string().contains("duplicate key") {
+            AppError::Conflict(format!(
+                "A join request for '{}' is already awaiting review",
+                req.peer_name()
+            ))
+        } else {
+            AppError::Database(e.to_string())
+        }
+    })?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok((request_from_row(&row), secret))
+}
+
+pub async fn list_requests(db: &PgPool, status: Option<&str>) -> Result<Vec<JoinRequestRecord>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {REQUEST_COLUMNS} FROM peer_join_requests \
+         WHERE ($1::text IS NULL OR status = $1) \
+         ORDER BY created_at DESC LIMIT 500"
+    ))
+    .bind(status)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(rows.iter().map(request_from_row).collect())
+}
+
+pub async fn get_request(db: &PgPool, id: Uuid) -> Result<JoinRequestRecord> {
+    let row = sqlx::query(&format!(
+        "SELECT {REQUEST_COLUMNS} FROM peer_join_requests WHERE id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Join request not found".to_string()))?;
+    Ok(request_from_row(&row))
+}
+
+/// Resolve a node's own request from its secret. Any mismatch is reported
+/// as not found so request ids cannot be probed.
+pub async fn authenticate_node(db: &PgPool, id: Uuid, secret: &str) -> Result<JoinRequestRecord> {
+    let row = sqlx::query(&format!(
+        "SELECT {REQUEST_COLUMNS} FROM peer_join_requests \
+         WHERE id = $1 AND node_secret_hash = $2"
+    ))
+    .bind(id)
+    .bind(hash_secret(secret))
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Join request not found".to_string()))?;
+    Ok(request_from_row(&row))
+}
+
+/// Approve a pending request: register the node as a peer instance and link
+/// it to the request, in one transaction. Returns the updated request.
+pub async fn approve(db: &PgPool, id: Uuid, reviewer: Uuid) -> Result<JoinRequestRecord> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let row = sqlx::query(
+        "SELECT name, endpoint_url, region, cache_size_bytes, api_key, status \
+         FROM peer_join_requests WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Join request not found".to_string()))?;
+    let status: String = row.get("status");
+    if status != STATUS_PENDING {
+        return Err(AppError::Conflict(format!(
+            "Join request is already {}",
+            status
+        )));
+    }
+    let name: String = row.get("name");
+    let api_key: String = row.get("api_key");
+
+    let peer_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO peer_instances (name, endpoint_url, region, cache_size_bytes, api_key) \
+         VALUES ($1, $2, $3, $4, $5) \
+         RETURNING id",
+    )
+    .bind(&name)
+    .bind(row.get::<String, _>("endpoint_url"))
+    .bind(row.get::<Option<String>, _>("region"))
+    .bind(row.get::<i64, _>("cache_size_bytes"))
+    .bind(&api_key)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        if e.to_string().contains("duplicate key") {
+            AppError::Conflict(format!("Peer instance '{}' already exists", name))
+        } else {
+            AppError::Database(e.to_string())
+        }
+    })?;
+
+    sqlx::query(
+        "UPDATE peer_join_requests \
+         SET status = $2, peer_instance_id = $3, reviewed_by = $4, reviewed_at = NOW() \
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(STATUS_APPROVED)
+    .bind(peer_id)
+    .bind(reviewer)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    get_request(db, id).await
+}
+
+/// Reject a pending request.
+pub async fn reject(
+    db: &PgPool,
+    id: Uuid,
+    reviewer: Uuid,
+    reason: Option<&str>,
+) -> Result<JoinRequestRecord> {
+    let result = sqlx::query(
+        "UPDATE peer_join_requests \
+         SET status = $2, reviewed_by = $3, reviewed_at = NOW(), rejection_reason = $4 \
+         WHERE id = $1 AND status = $5",
+    )
+    .bind(id)
+    .bind(STATUS_REJECTED)
+    .bind(reviewer)
+    .bind(reason)
+    .bind(STATUS_PENDING)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    let request = get_request(db, id).await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::Conflict(format!(
+            "Join request is already {}",
+            request.status
+        )));
+    }
+    Ok(request)
+}
+
+/// The peer instance an approved node may report heartbeats for.
+pub fn approved_peer(request: &JoinRequestRecord) -> Result<Uuid> {
+    match (request.status.as_str(), request.peer_instance_id) {
+        (STATUS_APPROVED, Some(peer_id)) => Ok(peer_id),
+        (STATUS_APPROVED, None) => Err(AppError::NotFound(
+            "The peer instance for this node has been removed".to_string(),
+        )),
+        (STATUS_REJECTED, _) => Err(AppError::Authorization(
+            "Join request was rejected".to_string(),
+        )),
+        _ => Err(AppError::Conflict(
+            "Join request is awaiting approval".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn join_request() -> JoinRequest {
+        serde_json::from_value(serde_json::json!({
+            "join_token": "akjt_abc",
+            "hostname": "edge-fra-1.internal",
+            "endpoint_url": "https://edge-fra-1.example.com",
+            "fingerprint": "SHA256:0f1e"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_generated_secrets_are_prefixed_and_distinct() {
+        let a = generate_secret(JOIN_TOKEN_PREFIX);
+        let b = generate_secret(JOIN_TOKEN_PREFIX);
+        assert!(a.starts_with(JOIN_TOKEN_PREFIX));
+        assert_eq!(a.len(), JOIN_TOKEN_PREFIX.len() + 64);
+        assert_ne!(a, b);
+        assert_eq!(hash_secret(&a), hash_secret(&format!(" {a}\n")));
+    }
+
+    #[test]
+    fn test_peer_name_defaults_to_hostname() {
+        let mut req = join_request();
+        assert_eq!(req.peer_name(), "edge-fra-1.internal");
+        req.name = Some("  ".to_string());
+        assert_eq!(req.peer_name(), "edge-fra-1.internal");
+        req.name = Some("edge-fra".to_string());
+        assert_eq!(req.peer_name(), "edge-fra");
+        req.validate().unwrap();
+    }
+
+    #[test]
+    fn test_join_request_validation() {
+        let mut req = join_request();
+        req.fingerprint = String::new();
+        assert!(req.validate().is_err());
+
+        let mut req = join_request();
+        req.cache_size_bytes = Some(-1);
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_create_token_validation() {
+        let ok = CreateJoinToken {
+            description: None,
+            expires_in_hours: Some(48),
+            max_uses: Some(3),
+        };
+        ok.validate().unwrap();
+        for (hours, uses) in [
+            (Some(0), None),
+            (Some(MAX_TOKEN_TTL_HOURS + 1), None),
+            (None, Some(0)),
+        ] {
+            let bad = CreateJoinToken {
+                description: None,
+                expires_in_hours: hours,
+                max_uses: uses,
+            };
+            assert!(bad.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_approved_peer_by_status() {
+        let mut record = JoinRequestRecord {
+            id: Uuid::new_v4(),
+            join_token_id: None,
+            name: "edge".to_string(),
+            hostname: "edge".to_string(),
+            endpoint_url: "https://edge.example.com".to_string(),
+            region: None,
+            cache_size_bytes: 0,
+            fingerprint: "SHA256:00".to_string(),
+            status: STATUS_PENDING.to_string(),
+            peer_instance_id: None,
+            reviewed_by: None,
+            reviewed_at: None,
+            rejection_reason: None,
+            created_at: Utc::now(),
+        };
+        assert!(matches!(approved_peer(&record), Err(AppError::Conflict(_))));
+        record.status = STATUS_REJECTED.to_string();
+        assert!(matches!(
+            approved_peer(&record),
+            Err(AppError::Authorization(_))
+        ));
+        let peer_id = Uuid::new_v4();
+        record.status = STATUS_APPROVED.to_string();
+        record.peer_instance_id = Some(peer_id);
+        assert_eq!(approved_peer(&record).unwrap(), peer_id);
+    }
+}
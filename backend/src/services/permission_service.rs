@@ -5,15 +5,35 @@
 //! transitive group memberships in a single query. Results are cached
 //! in-process with a 30-second TTL to avoid repeated database round-trips
 //! on hot paths such as artifact downloads.
+//!
+//! Repository checks also honour grants whose target is a label selector
+//! (`target_type = 'label_selector'`). The selector is matched against the
+//! repository's current labels inside the same query, so repositories that
+//! acquire a matching label inherit the grant without rewriting it. Label
+//! mutations fan out `permissions_changed` so cached results do not outlive
+//! the labels they were computed from.
+//!
+//! An optional external authorization hook (see `external_authz_service`)
+//! gets the last word on repository accesses the grants above allow. The
+//! service carries it for the enforcement points that consult it: the native
+//! format middleware, the OCI registry (`/v2`), the S3 gateway and the REST
+//! artifact write/delete gate. A new entry point that authorizes repository
+//! access must call [`PermissionService::authorize_external`] itself.
 
 use sqlx::PgPool;
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
+use crate::services::external_authz_service::{AuthzUser, ExternalAuthzService};
+use crate::services::repository_label_service::LabelEntry;
+
+/// Target type for grants that apply to every repository matching a label
+/// selector rather than a single fixed target.
+pub const LABEL_SELECTOR_TARGET_TYPE: &str = "label_selector";
 
 /// Target type for system-wide permission checks (e.g. creating repositories or groups).
 pub const SYSTEM_TARGET_TYPE: &str = "system";
@@ -106,6 +126,73 @@ fn principal_existence_query(principal_type: &str) -> Option<&'static str> {
     }
 }
 
+/// Validate the target of a grant against its `target_type`.
+///
+/// Label-selector grants need at least one selector entry and every entry
+/// needs a non-empty key; an empty selector would otherwise match nothing and
+/// sit in the table as a dead grant. Their `target_id` is optional because
+/// there is no natural target. Every other target type must name a
+/// `target_id` and leave the selector empty, so a fixed-target grant can never
+/// be widened by accident.
+pub fn validate_grant_target(
+    target_type: &str,
+    target_id: Option<Uuid>,
+    label_selector: &[LabelEntry],
+) -> Result<()> {
+    if target_type != LABEL_SELECTOR_TARGET_TYPE {
+        if target_id.is_none() {
+            return Err(AppError::Validation(format!(
+                "target_id is required for target_type '{target_type}'"
+            )));
+        }
+        if !label_selector.is_empty() {
+            return Err(AppError::Validation(format!(
+                "label_selector is only valid with target_type '{LABEL_SELECTOR_TARGET_TYPE}'"
+            )));
+        }
+        return Ok(());
+    }
+    if label_selector.is_empty() {
+        return Err(AppError::Validation(
+            "label_selector grants require at least one label".to_string(),
+        ));
+    }
+    if label_selector
+        .iter()
+        .any(|entry| entry.key.trim().is_empty())
+    {
+        return Err(AppError::Validation(
+            "label_selector entries require a non-empty 'key'".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// SQL predicate that is true when a `permissions` row is a label-selector
+/// grant whose selector matches the labels of the repository named by
+/// `repo_id_expr`. Every selector entry must be satisfied by some label on the
+/// repository; an empty (or missing) value matches any value for that key.
+///
+/// `col` qualifies the permissions columns (`""` for an unaliased table, `"p."`
+/// when the caller aliases it). Shared with the read-plane visibility
+/// predicate in `repository_service` so both planes agree on what a selector
+/// grant covers.
+pub(crate) fn label_selector_grant_matches(col: &str, repo_id_expr: &str) -> String {
+    format!(
+        r#"({col}target_type = 'label_selector'
+                  AND jsonb_array_length({col}label_selector) > 0
+                  AND NOT EXISTS (
+                      SELECT 1 FROM jsonb_to_recordset({col}label_selector) AS sel(key TEXT, value TEXT)
+                      WHERE NOT EXISTS (
+                          SELECT 1 FROM repository_labels rl
+                          WHERE rl.repository_id = {repo_id_expr}
+                            AND rl.label_key = sel.key
+                            AND (COALESCE(sel.value, '') = '' OR rl.label_value = sel.value)
+                      )
+                  ))"#
+    )
+}
+
 /// Service that evaluates permission rules stored in the `permissions` table.
 ///
 /// The service resolves both direct user grants and group-based grants in a
@@ -115,6 +202,7 @@ pub struct PermissionService {
     db: PgPool,
     cache: RwLock<HashMap<CacheKey, CacheEntry>>,
     rules_cache: RwLock<HashMap<RulesCacheKey, RulesCacheEntry>>,
+    external_authz: Option<Arc<ExternalAuthzService>>,
 }
 
 impl PermissionService {
@@ -123,9 +211,50 @@ impl PermissionService {
             db,
             cache: RwLock::new(HashMap::new()),
             rules_cache: RwLock::new(HashMap::new()),
+            external_authz: None,
         }
     }
 
+    /// Attach the external authorization hook consulted by
+    /// [`authorize_external`](Self::authorize_external).
+    pub fn with_external_authz(mut self, hook: Option<Arc<ExternalAuthzService>>) -> Self {
+        self.external_authz = hook;
+        self
+    }
+
+    /// The configured external authorization hook, if any.
+    pub fn external_authz(&self) -> Option<&Arc<ExternalAuthzService>> {
+        self.external_authz.as_ref()
+    }
+
+    /// Ask the external authorization hook about a repository access the
+    /// built-in checks already allowed. `true` when no hook is configured or
+    /// the caller is an admin; `ServiceUnavailable` when the hook cannot be
+    /// reached and is not configured to fail open.
+    pub async fn authorize_external(
+        &self,
+        user: Option<AuthzUser>,
+        action: &str,
+        repository_id: Uuid,
+        path: Option<&str>,
+    ) -> Result<bool> {
+        let Some(hook) = &self.external_authz else {
+            return Ok(true);
+        };
+        let decision = hook
+            .authorize(&self.db, user, action, repository_id, path)
+            .await?;
+        if !decision.allow {
+            debug!(
+                %repository_id,
+                action,
+                reason = decision.reason.as_deref().unwrap_or(""),
+                "external authorization hook denied access"
+            );
+        }
+        Ok(decision.allow)
+    }
+
     /// Check whether `user_id` holds `action` on the given target.
     ///
     /// Admin users bypass all checks and always receive `true`. For
@@ -195,20 +324,26 @@ impl PermissionService {
         // access model. The `$1 = 'repository'` guard keeps every other target
         // type (group/artifact/system) unaffected, and a NULL `project_id`
         // subquery result never matches (`target_id = NULL` is not true).
-        let exists: bool = sqlx::query_scalar(
+        //
+        // Label-selector grants likewise count as rules for every repository
+        // whose labels currently match the selector.
+        let sql = format!(
             r#"SELECT EXISTS(
                  SELECT 1 FROM permissions
                  WHERE (target_type = $1 AND target_id = $2)
                     OR ($1 = 'repository' AND target_type = 'project' AND target_id = (
                         SELECT project_id FROM repositories WHERE id = $2
                     ))
+                    OR ($1 = 'repository' AND {labels})
                )"#,
-        )
-        .bind(target_type)
-        .bind(target_id)
-        .fetch_one(&self.db)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+            labels = label_selector_grant_matches("", "$2"),
+        );
+        let exists: bool = sqlx::query_scalar(&sql)
+            .bind(target_type)
+            .bind(target_id)
+            .fetch_one(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         // Populate cache.
         match self.rules_cache.write() {
@@ -388,8 +523,10 @@ impl PermissionService {
         // grant on the repository's owning project is inherited. The
         // `$2 = 'repository'` guard confines inheritance to repository
         // targets; for a project-less repository the subquery yields NULL and
-        // the project arm never matches, so behavior is unchanged.
-        let rows: Vec<(String,)> = sqlx::query_as(
+        // the project arm never matches, so behavior is unchanged. Label-selector
+        // grants are matched against the repository's labels at this point, so
+        // a newly-labelled repository picks them up on the next cache miss.
+        let sql = format!(
             r#"
             SELECT DISTINCT unnest(actions) as action
             FROM permissions
@@ -405,15 +542,18 @@ impl PermissionService {
                 OR ($2 = 'repository' AND target_type = 'project' AND target_id = (
                     SELECT project_id FROM repositories WHERE id = $3
                 ))
+                OR ($2 = 'repository' AND {labels})
             )
             "#,
-        )
-        .bind(user_id)
-        .bind(target_type)
-        .bind(target_id)
-        .fetch_all(&self.db)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+            labels = label_selector_grant_matches("", "$3"),
+        );
+        let rows: Vec<(String,)> = sqlx::query_as(&sql)
+            .bind(user_id)
+            .bind(target_type)
+            .bind(target_id)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         Ok(rows.into_iter().map(|(action,)| action).collect())
     }
@@ -1562,4 +1702,48 @@ mod tests {
             .unwrap();
         assert!(!art_read);
     }
+
+    // -----------------------------------------------------------------------
+    // Label-selector grants
+    // -----------------------------------------------------------------------
+
+    fn label(key: &str, value: &str) -> LabelEntry {
+        LabelEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_grant_target_requires_selector_for_label_target() {
+        let target = LABEL_SELECTOR_TARGET_TYPE;
+        assert!(validate_grant_target(target, None, &[]).is_err());
+        assert!(validate_grant_target(target, None, &[label("", "x")]).is_err());
+        assert!(validate_grant_target(target, None, &[label("team", "payments")]).is_ok());
+        assert!(validate_grant_target(target, None, &[label("team", "")]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_grant_target_fixed_target_needs_id_and_no_selector() {
+        let repo_id = Some(Uuid::new_v4());
+        assert!(validate_grant_target("repository", repo_id, &[]).is_ok());
+        assert!(validate_grant_target("repository", None, &[]).is_err());
+        assert!(
+            validate_grant_target("repository", repo_id, &[label("team", "payments")]).is_err()
+        );
+        // The system sentinel is the nil UUID and must stay expressible.
+        assert!(validate_grant_target(SYSTEM_TARGET_TYPE, Some(SYSTEM_SENTINEL_ID), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_label_selector_predicate_uses_caller_repo_expr() {
+        let sql = label_selector_grant_matches("p.", "r.id");
+        assert!(sql.contains("rl.repository_id = r.id"));
+        assert!(sql.contains("p.target_type = 'label_selector'"));
+        assert!(sql.contains("jsonb_to_recordset(p.label_selector)"));
+        assert!(
+            !sql.contains("$"),
+            "predicate must not introduce binds: {sql}"
+        );
+    }
 }
@@ -8,6 +8,9 @@ use std::sync::Arc;
 
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
+use utoipa::openapi::path::{ParameterBuilder, ParameterIn};
+use utoipa::openapi::schema::{Object, Schema, Type};
+use utoipa::openapi::{OpenApi, RefOr, Required};
 use uuid::Uuid;
 
 use crate::models::plugin::{PluginCapabilities, PluginResourceLimits};
@@ -44,6 +47,9 @@ pub struct ActivePlugin {
     pub capabilities: PluginCapabilities,
     /// Resource limits for execution
     pub limits: PluginResourceLimits,
+    /// OpenAPI fragment of the endpoints served under `/ext/<format_key>`,
+    /// with paths already rebased onto the mount
+    pub openapi: Option<Arc<OpenApi>>,
 }
 
 impl std::fmt::Debug for ActivePlugin {
@@ -153,6 +159,24 @@ impl PluginRegistry {
             None
         };
 
+        // Read the OpenAPI fragment of the served endpoints. A broken
+        // fragment only keeps the plugin out of the spec, not off the mount.
+        let openapi = match (&compiled_v2, capabilities.openapi_fragment) {
+            (Some(compiled_v2), true) => {
+                match self
+                    .fetch_openapi_fragment(compiled_v2, id, &format_key, &limits)
+                    .await
+                {
+                    Ok(doc) => Some(Arc::new(doc)),
+                    Err(e) => {
+                        warn!("Plugin {} OpenAPI fragment ignored: {}", name, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
         // Get next internal version
         let internal_version = self.next_version().await;
 
@@ -166,6 +190,7 @@ impl PluginRegistry {
             compiled_v2,
             capabilities,
             limits,
+            openapi,
         });
 
         // Atomically update both indexes
@@ -282,6 +307,47 @@ impl PluginRegistry {
         info!("Plugin registry cleared");
     }
 
+    /// OpenAPI fragments of all active plugins, ordered by format key.
+    pub async fn openapi_fragments(&self) -> Vec<Arc<OpenApi>> {
+        let by_format = self.plugins_by_format.read().await;
+        let mut plugins: Vec<_> = by_format
+            .values()
+            .filter_map(|p| p.openapi.clone().map(|doc| (p.format_key.clone(), doc)))
+            .collect();
+        plugins.sort_by(|a, b| a.0.cmp(&b.0));
+        plugins.into_iter().map(|(_, doc)| doc).collect()
+    }
+
+    /// Instantiate the api-description world and rebase its fragment.
+    async fn fetch_openapi_fragment(
+        &self,
+        compiled: &CompiledPlugin,
+        id: Uuid,
+        format_key: &str,
+        limits: &PluginResourceLimits,
+    ) -> WasmResult<OpenApi> {
+        let mut store = self
+            .runtime
+            .create_store(compiled, &id.to_string(), format_key, limits)?;
+
+        let instance = super::wasm_bindings::v2_openapi::FormatPluginV2Openapi::instantiate_async(
+            &mut store,
+            compiled.component(),
+            compiled.linker(),
+        )
+        .await
+        .map_err(|e| WasmError::InstantiationFailed(e.to_string()))?;
+
+        let fragment = instance
+            .artifact_keeper_format_api_description()
+            .call_openapi_fragment(&mut store)
+            .await
+            .map_err(|e: wasmtime::Error| WasmError::CallFailed(e.to_string()))?
+            .map_err(WasmError::PluginError)?;
+
+        rebase_openapi_fragment(format_key, &fragment).map_err(WasmError::ValidationFailed)
+    }
+
     /// Resolve a plugin by format key, returning an error if not registered.
     async fn resolve_plugin(&self, format_key: &str) -> WasmResult<Arc<ActivePlugin>> {
         self.get_by_format(format_key).await.ok_or_else(|| {
@@ -491,6 +557,35 @@ impl PluginRegistry {
     }
 }
 
+/// Parse a plugin's OpenAPI fragment and move its paths, which are relative
+/// to the plugin mount, under `/ext/{format_key}/{repo_key}`.
+pub fn rebase_openapi_fragment(format_key: &str, json: &str) -> Result<OpenApi, String> {
+    let mut doc: OpenApi =
+        serde_json::from_str(json).map_err(|e| format!("invalid OpenAPI fragment: {}", e))?;
+
+    let repo_key = ParameterBuilder::new()
+        .name("repo_key")
+        .parameter_in(ParameterIn::Path)
+        .required(Required::True)
+        .description(Some("Repository key"))
+        .schema(Some(RefOr::T(Schema::Object(Object::with_type(
+            Type::String,
+        )))))
+        .build();
+
+    for (relative, mut item) in std::mem::take(&mut doc.paths.paths) {
+        let relative = relative.trim_start_matches('/');
+        let path = format!("/ext/{}/{{repo_key}}/{}", format_key, relative);
+        let parameters = item.parameters.get_or_insert_with(Vec::new);
+        if !parameters.iter().any(|p| p.name == "repo_key") {
+            parameters.insert(0, repo_key.clone());
+        }
+        doc.paths.paths.insert(path, item);
+    }
+
+    Ok(doc)
+}
+
 impl Default for PluginRegistry {
     fn default() -> Self {
         Self::new().expect("Failed to create default PluginRegistry")
@@ -511,6 +606,37 @@ pub struct PluginInfo {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rebase_openapi_fragment_moves_paths_under_mount() {
+        let fragment = r#"{
+            "openapi": "3.1.0",
+            "info": {"title": "rpm", "version": "1.0.0"},
+            "paths": {
+                "/repodata/repomd.xml": {"get": {"responses": {"200": {"description": "ok"}}}},
+                "packages/{name}": {"get": {"responses": {"200": {"description": "ok"}}}}
+            }
+        }"#;
+        let doc = rebase_openapi_fragment("rpm", fragment).unwrap();
+        let mut paths: Vec<&str> = doc.paths.paths.keys().map(|k| k.as_str()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                "/ext/rpm/{repo_key}/packages/{name}",
+                "/ext/rpm/{repo_key}/repodata/repomd.xml",
+            ]
+        );
+        for item in doc.paths.paths.values() {
+            let params = item.parameters.as_ref().unwrap();
+            assert_eq!(params.iter().filter(|p| p.name == "repo_key").count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_rebase_openapi_fragment_rejects_invalid_json() {
+        assert!(rebase_openapi_fragment("rpm", "not json").is_err());
+    }
+
     #[tokio::test]
     async fn test_registry_creation() {
         let registry = PluginRegistry::new();
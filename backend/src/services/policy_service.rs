@@ -1,6 +1,9 @@
 //! Service for evaluating and managing security policies.
 
+use serde::Serialize;
 use sqlx::PgPool;
+use std::cmp::Reverse;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
@@ -37,7 +40,7 @@ pub const ALLOWED_MAX_SEVERITIES: [&str; 4] = ["critical", "high", "medium", "lo
 /// message. Before this existed the raw string went straight into the
 /// INSERT/UPDATE and a mis-cased or unknown value surfaced as a
 /// CHECK-constraint violation, i.e. an opaque 500 `DATABASE_ERROR`.
-fn normalize_max_severity(raw: &str) -> Result<String> {
+pub(crate) fn normalize_max_severity(raw: &str) -> Result<String> {
     let normalized = raw.trim().to_ascii_lowercase();
     if ALLOWED_MAX_SEVERITIES.contains(&normalized.as_str()) {
         Ok(normalized)
@@ -63,6 +66,187 @@ fn repository_exists_or_not_found(exists: bool, repository_id: Uuid) -> Result<(
     }
 }
 
+/// Where a scan policy sits in the instance → project → repository
+/// hierarchy. Ordered from least to most specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyLayer {
+    Instance,
+    Project,
+    Repository,
+}
+
+impl PolicyLayer {
+    pub fn of(policy: &ScanPolicy) -> Self {
+        if policy.repository_id.is_some() {
+            PolicyLayer::Repository
+        } else if policy.project_id.is_some() {
+            PolicyLayer::Project
+        } else {
+            PolicyLayer::Instance
+        }
+    }
+}
+
+/// The most specific layer holding an `inherit = false` policy. Every layer
+/// less specific than it is ignored.
+fn override_layer(policies: &[ScanPolicy]) -> Option<PolicyLayer> {
+    policies
+        .iter()
+        .filter(|p| !p.inherit)
+        .map(PolicyLayer::of)
+        .max()
+}
+
+/// Reduce the enabled policies in scope for one repository to the ones that
+/// actually apply, most specific layer first.
+///
+/// Layers merge by default, so every policy applies and each rule takes its
+/// strictest value. An `inherit = false` policy drops the less specific
+/// layers; policies on its own layer still apply.
+pub fn applicable_policies(mut policies: Vec<ScanPolicy>) -> Vec<ScanPolicy> {
+    if let Some(floor) = override_layer(&policies) {
+        policies.retain(|p| PolicyLayer::of(p) >= floor);
+    }
+    policies.sort_by(|a, b| {
+        PolicyLayer::of(b)
+            .cmp(&PolicyLayer::of(a))
+            .then(a.created_at.cmp(&b.created_at))
+    });
+    policies
+}
+
+/// The policy contributing the strictest (greatest) value of `key`. Ties go
+/// to the earliest policy, i.e. the most specific layer after
+/// [`applicable_policies`].
+fn strictest<K: Ord>(
+    policies: &[ScanPolicy],
+    key: impl Fn(&ScanPolicy) -> Option<K>,
+) -> Option<&ScanPolicy> {
+    let mut best: Option<(&ScanPolicy, K)> = None;
+    for policy in policies {
+        if let Some(k) = key(policy) {
+            if best.as_ref().is_none_or(|(_, b)| k > *b) {
+                best = Some((policy, k));
+            }
+        }
+    }
+    best.map(|(policy, _)| policy)
+}
+
+/// Lower is stricter: a `low` threshold blocks more than a `critical` one.
+fn severity_rank(max_severity: &str) -> u8 {
+    match max_severity {
+        "low" => 0,
+        "medium" => 1,
+        "high" => 2,
+        _ => 3,
+    }
+}
+
+/// One resolved rule and the policy it was taken from.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectiveRule {
+    pub rule: String,
+    pub value: serde_json::Value,
+    pub layer: PolicyLayer,
+    pub policy_id: Uuid,
+    pub policy_name: String,
+}
+
+/// The rules the applicable policies add up to.
+#[derive(Debug, Clone)]
+pub struct MergedScanPolicy {
+    pub max_severity: String,
+    pub block_unscanned: bool,
+    pub block_on_fail: bool,
+    pub min_staging_hours: Option<i32>,
+    pub max_artifact_age_days: Option<i32>,
+    pub require_signature: bool,
+    pub rules: Vec<EffectiveRule>,
+}
+
+/// Merge applicable policies rule by rule, strictest value winning. Optional
+/// rules no policy sets are left out. `None` when no policy applies.
+pub fn merge_policies(policies: &[ScanPolicy]) -> Option<MergedScanPolicy> {
+    let severity = strictest(policies, |p| Some(Reverse(severity_rank(&p.max_severity))))?;
+    let unscanned = strictest(policies, |p| Some(p.block_unscanned))?;
+    let on_fail = strictest(policies, |p| Some(p.block_on_fail))?;
+    let signature = strictest(policies, |p| Some(p.require_signature))?;
+    let staging = strictest(policies, |p| p.min_staging_hours);
+    let age = strictest(policies, |p| p.max_artifact_age_days.map(Reverse));
+
+    let rule = |rule: &str, value: serde_json::Value, source: &ScanPolicy| EffectiveRule {
+        rule: rule.to_string(),
+        value,
+        layer: PolicyLayer::of(source),
+        policy_id: source.id,
+        policy_name: source.name.clone(),
+    };
+    let mut rules = vec![
+        rule(
+            "max_severity",
+            severity.max_severity.clone().into(),
+            severity,
+        ),
+        rule(
+            "block_unscanned",
+            unscanned.block_unscanned.into(),
+            unscanned,
+        ),
+        rule("block_on_fail", on_fail.block_on_fail.into(), on_fail),
+        rule(
+            "require_signature",
+            signature.require_signature.into(),
+            signature,
+        ),
+    ];
+    if let Some(p) = staging {
+        rules.push(rule("min_staging_hours", p.min_staging_hours.into(), p));
+    }
+    if let Some(p) = age {
+        rules.push(rule(
+            "max_artifact_age_days",
+            p.max_artifact_age_days.into(),
+            p,
+        ));
+    }
+
+    Some(MergedScanPolicy {
+        max_severity: severity.max_severity.clone(),
+        block_unscanned: unscanned.block_unscanned,
+        block_on_fail: on_fail.block_on_fail,
+        min_staging_hours: staging.and_then(|p| p.min_staging_hours),
+        max_artifact_age_days: age.and_then(|p| p.max_artifact_age_days),
+        require_signature: signature.require_signature,
+        rules,
+    })
+}
+
+/// A policy in scope for a repository and whether an override masks it.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LayeredPolicy {
+    pub id: Uuid,
+    pub name: String,
+    pub layer: PolicyLayer,
+    pub inherit: bool,
+    /// False when a more specific `inherit = false` policy overrides it.
+    pub applied: bool,
+}
+
+/// The effective scan policy of one repository.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectivePolicy {
+    pub repository_id: Uuid,
+    pub project_id: Option<Uuid>,
+    /// Layer whose `inherit = false` policy cuts off the layers above it.
+    pub overridden_at: Option<PolicyLayer>,
+    /// Enabled policies in scope, most specific first.
+    pub policies: Vec<LayeredPolicy>,
+    /// Resolved rules; empty when no policy applies.
+    pub rules: Vec<EffectiveRule>,
+}
+
 pub struct PolicyService {
     db: PgPool,
 }
@@ -79,22 +263,9 @@ impl PolicyService {
         artifact_id: Uuid,
         repository_id: Uuid,
     ) -> Result<PolicyResult> {
-        // Find applicable policies: repo-specific + global (repository_id IS NULL)
-        let policies: Vec<ScanPolicy> = sqlx::query_as(
-            r#"
-            SELECT id, name, repository_id, max_severity, block_unscanned,
-                   block_on_fail, is_enabled, min_staging_hours, max_artifact_age_days,
-                   require_signature, created_at, updated_at
-            FROM scan_policies
-            WHERE is_enabled = true
-              AND (repository_id = $1 OR repository_id IS NULL)
-            ORDER BY repository_id NULLS LAST
-            "#,
-        )
-        .bind(repository_id)
-        .fetch_all(&self.db)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        // Repository, project and instance layers, minus any masked by an
+        // `inherit = false` override.
+        let policies = applicable_policies(self.policies_in_scope(repository_id).await?);
 
         if policies.is_empty() {
             return Ok(PolicyResult {
@@ -213,6 +384,81 @@ impl PolicyService {
         })
     }
 
+    /// Enabled policies on the repository itself, on its project and at the
+    /// instance level.
+    async fn policies_in_scope(&self, repository_id: Uuid) -> Result<Vec<ScanPolicy>> {
+        sqlx::query_as(
+            r#"
+            SELECT id, name, repository_id, project_id, inherit, max_severity, block_unscanned,
+                   block_on_fail, is_enabled, min_staging_hours, max_artifact_age_days,
+                   require_signature, created_at, updated_at
+            FROM scan_policies
+            WHERE is_enabled = true
+              AND (repository_id = $1
+                   OR project_id = (SELECT project_id FROM repositories WHERE id = $1)
+                   OR (repository_id IS NULL AND project_id IS NULL))
+            ORDER BY repository_id NULLS LAST, project_id NULLS LAST, created_at
+            "#,
+        )
+        .bind(repository_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// The enabled policies that gate `repository_id`, most specific first.
+    pub async fn applicable_policies_for(&self, repository_id: Uuid) -> Result<Vec<ScanPolicy>> {
+        Ok(applicable_policies(
+            self.policies_in_scope(repository_id).await?,
+        ))
+    }
+
+    /// The merged rules that gate `repository_id`, or `None` when no policy
+    /// applies.
+    pub async fn merged_policy(&self, repository_id: Uuid) -> Result<Option<MergedScanPolicy>> {
+        let policies = applicable_policies(self.policies_in_scope(repository_id).await?);
+        Ok(merge_policies(&policies))
+    }
+
+    /// Every policy in scope for a repository, which of them apply, and the
+    /// layer each resolved rule comes from.
+    pub async fn effective_policy(&self, repository_id: Uuid) -> Result<EffectivePolicy> {
+        let project_id: Option<Uuid> =
+            sqlx::query_scalar("SELECT project_id FROM repositories WHERE id = $1")
+                .bind(repository_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!("Repository {repository_id} not found"))
+                })?;
+
+        let in_scope = self.policies_in_scope(repository_id).await?;
+        let overridden_at = override_layer(&in_scope);
+        let applied = applicable_policies(in_scope.clone());
+        let mut policies: Vec<LayeredPolicy> = in_scope
+            .iter()
+            .map(|p| LayeredPolicy {
+                id: p.id,
+                name: p.name.clone(),
+                layer: PolicyLayer::of(p),
+                inherit: p.inherit,
+                applied: applied.iter().any(|a| a.id == p.id),
+            })
+            .collect();
+        policies.sort_by(|a, b| b.layer.cmp(&a.layer));
+
+        Ok(EffectivePolicy {
+            repository_id,
+            project_id,
+            overridden_at,
+            policies,
+            rules: merge_policies(&applied)
+                .map(|merged| merged.rules)
+                .unwrap_or_default(),
+        })
+    }
+
     // -----------------------------------------------------------------------
     // CRUD
     // -----------------------------------------------------------------------
@@ -234,32 +480,62 @@ impl PolicyService {
         repository_exists_or_not_found(exists, repository_id)
     }
 
+    /// Same pre-check as [`Self::ensure_repository_exists`] for project-scoped
+    /// policies.
+    async fn ensure_project_exists(&self, project_id: Uuid) -> Result<()> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM projects WHERE id = $1)")
+                .bind(project_id)
+                .fetch_one(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+
+        if exists {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!(
+                "Project {project_id} not found"
+            )))
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn create_policy(
         &self,
         name: &str,
         repository_id: Option<Uuid>,
+        project_id: Option<Uuid>,
         max_severity: &str,
         block_unscanned: bool,
         block_on_fail: bool,
         min_staging_hours: Option<i32>,
         max_artifact_age_days: Option<i32>,
         require_signature: bool,
+        inherit: bool,
     ) -> Result<ScanPolicy> {
         // #2320: validate inputs up front so a bad request comes back as a
         // 4xx instead of tripping the DB CHECK / FK constraint and surfacing
         // as an opaque 500 DATABASE_ERROR.
         let max_severity = normalize_max_severity(max_severity)?;
+        if repository_id.is_some() && project_id.is_some() {
+            return Err(AppError::Validation(
+                "a policy is scoped to a repository or a project, not both".to_string(),
+            ));
+        }
         if let Some(repo_id) = repository_id {
             self.ensure_repository_exists(repo_id).await?;
         }
+        if let Some(project_id) = project_id {
+            self.ensure_project_exists(project_id).await?;
+        }
 
         let policy: ScanPolicy = sqlx::query_as(
             r#"
             INSERT INTO scan_policies (name, repository_id, max_severity, block_unscanned, block_on_fail,
-                                       min_staging_hours, max_artifact_age_days, require_signature)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, name, repository_id, max_severity, block_unscanned,
+                                       min_staging_hours, max_artifact_age_days, require_signature,
+                                       project_id, inherit)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, name, repository_id, project_id, inherit, max_severity, block_unscanned,
                       block_on_fail, is_enabled, min_staging_hours, max_artifact_age_days,
                       require_signature, created_at, updated_at
             "#,
@@ -272,6 +548,8 @@ impl PolicyService {
         .bind(min_staging_hours)
         .bind(max_artifact_age_days)
         .bind(require_signature)
+        .bind(project_id)
+        .bind(inherit)
         .fetch_one(&self.db)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -282,7 +560,7 @@ impl PolicyService {
     pub async fn list_policies(&self) -> Result<Vec<ScanPolicy>> {
         let policies: Vec<ScanPolicy> = sqlx::query_as(
             r#"
-            SELECT id, name, repository_id, max_severity, block_unscanned,
+            SELECT id, name, repository_id, project_id, inherit, max_severity, block_unscanned,
                    block_on_fail, is_enabled, min_staging_hours, max_artifact_age_days,
                    require_signature, created_at, updated_at
             FROM scan_policies
@@ -299,7 +577,7 @@ impl PolicyService {
     pub async fn get_policy(&self, id: Uuid) -> Result<ScanPolicy> {
         sqlx::query_as::<_, ScanPolicy>(
             r#"
-            SELECT id, name, repository_id, max_severity, block_unscanned,
+            SELECT id, name, repository_id, project_id, inherit, max_severity, block_unscanned,
                    block_on_fail, is_enabled, min_staging_hours, max_artifact_age_days,
                    require_signature, created_at, updated_at
             FROM scan_policies
@@ -334,6 +612,7 @@ impl PolicyService {
         min_staging_hours: Option<i32>,
         max_artifact_age_days: Option<i32>,
         require_signature: Option<bool>,
+        inherit: Option<bool>,
     ) -> Result<ScanPolicy> {
         // #2320: same normalization as create_policy — a mis-cased or unknown
         // max_severity on update used to trip the DB CHECK constraint (500).
@@ -350,9 +629,10 @@ impl PolicyService {
                 min_staging_hours = COALESCE($7, min_staging_hours),
                 max_artifact_age_days = COALESCE($8, max_artifact_age_days),
                 require_signature = COALESCE($9, require_signature),
+                inherit = COALESCE($10, inherit),
                 updated_at = NOW()
             WHERE id = $1
-            RETURNING id, name, repository_id, max_severity, block_unscanned,
+            RETURNING id, name, repository_id, project_id, inherit, max_severity, block_unscanned,
                       block_on_fail, is_enabled, min_staging_hours, max_artifact_age_days,
                       require_signature, created_at, updated_at
             "#,
@@ -366,6 +646,7 @@ impl PolicyService {
         .bind(min_staging_hours)
         .bind(max_artifact_age_days)
         .bind(require_signature)
+        .bind(inherit)
         .fetch_optional(&self.db)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?
@@ -552,7 +833,9 @@ mod tests {
     async fn test_create_policy_rejects_invalid_max_severity_before_touching_db() {
         let svc = disconnected_service();
         let err = svc
-            .create_policy("p", None, "bogus", false, false, None, None, false)
+            .create_policy(
+                "p", None, None, "bogus", false, false, None, None, false, true,
+            )
             .await
             .unwrap_err();
         // Validation (not Database/PoolTimedOut) proves the reject happened
@@ -567,12 +850,14 @@ mod tests {
             .create_policy(
                 "p",
                 Some(Uuid::new_v4()),
+                None,
                 "Critical",
                 false,
                 false,
                 None,
                 None,
                 false,
+                true,
             )
             .await
             .unwrap_err();
@@ -586,7 +871,18 @@ mod tests {
     async fn test_create_policy_unscoped_valid_input_reaches_insert() {
         let svc = disconnected_service();
         let err = svc
-            .create_policy("p", None, "high", true, true, Some(1), Some(30), true)
+            .create_policy(
+                "p",
+                None,
+                None,
+                "high",
+                true,
+                true,
+                Some(1),
+                Some(30),
+                true,
+                true,
+            )
             .await
             .unwrap_err();
         // No repository scope: nothing to pre-check, so the INSERT itself is
@@ -608,6 +904,7 @@ mod tests {
                 None,
                 None,
                 None,
+                None,
             )
             .await
             .unwrap_err();
@@ -662,6 +959,8 @@ mod tests {
             id: Uuid::new_v4(),
             name: "no-critical-vulns".to_string(),
             repository_id: None,
+            project_id: None,
+            inherit: true,
             max_severity: "critical".to_string(),
             block_unscanned: true,
             block_on_fail: true,
@@ -687,6 +986,8 @@ mod tests {
             id: Uuid::new_v4(),
             name: "repo-policy".to_string(),
             repository_id: Some(repo_id),
+            project_id: None,
+            inherit: true,
             max_severity: "high".to_string(),
             block_unscanned: false,
             block_on_fail: false,
@@ -707,6 +1008,8 @@ mod tests {
             id: Uuid::nil(),
             name: "test-policy".to_string(),
             repository_id: None,
+            project_id: None,
+            inherit: true,
             max_severity: "medium".to_string(),
             block_unscanned: true,
             block_on_fail: false,
@@ -797,6 +1100,162 @@ mod tests {
         assert!(!result.allowed);
     }
 
+    #[tokio::test]
+    async fn test_create_policy_rejects_repository_and_project_scope() {
+        let svc = disconnected_service();
+        let err = svc
+            .create_policy(
+                "p",
+                Some(Uuid::new_v4()),
+                Some(Uuid::new_v4()),
+                "high",
+                false,
+                false,
+                None,
+                None,
+                false,
+                true,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)), "got: {err:?}");
+    }
+
+    // -----------------------------------------------------------------------
+    // Layering: instance -> project -> repository
+    // -----------------------------------------------------------------------
+
+    fn layered(name: &str, layer: PolicyLayer, inherit: bool) -> ScanPolicy {
+        ScanPolicy {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            repository_id: (layer == PolicyLayer::Repository).then(Uuid::new_v4),
+            project_id: (layer == PolicyLayer::Project).then(Uuid::new_v4),
+            inherit,
+            max_severity: "critical".to_string(),
+            block_unscanned: false,
+            block_on_fail: false,
+            is_enabled: true,
+            min_staging_hours: None,
+            max_artifact_age_days: None,
+            require_signature: false,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn names(policies: &[ScanPolicy]) -> Vec<&str> {
+        policies.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_applicable_policies_merge_all_layers_most_specific_first() {
+        let applied = applicable_policies(vec![
+            layered("instance", PolicyLayer::Instance, true),
+            layered("repo", PolicyLayer::Repository, true),
+            layered("project", PolicyLayer::Project, true),
+        ]);
+        assert_eq!(names(&applied), ["repo", "project", "instance"]);
+    }
+
+    #[test]
+    fn test_applicable_policies_override_drops_less_specific_layers() {
+        let applied = applicable_policies(vec![
+            layered("instance", PolicyLayer::Instance, true),
+            layered("project", PolicyLayer::Project, false),
+            layered("repo", PolicyLayer::Repository, true),
+        ]);
+        assert_eq!(names(&applied), ["repo", "project"]);
+
+        let applied = applicable_policies(vec![
+            layered("instance", PolicyLayer::Instance, true),
+            layered("project", PolicyLayer::Project, false),
+            layered("repo", PolicyLayer::Repository, false),
+        ]);
+        assert_eq!(names(&applied), ["repo"]);
+    }
+
+    #[test]
+    fn test_instance_override_masks_nothing() {
+        let applied = applicable_policies(vec![
+            layered("instance", PolicyLayer::Instance, false),
+            layered("repo", PolicyLayer::Repository, true),
+        ]);
+        assert_eq!(names(&applied), ["repo", "instance"]);
+    }
+
+    #[test]
+    fn test_merge_policies_takes_strictest_value_per_rule() {
+        let mut instance = layered("instance", PolicyLayer::Instance, true);
+        instance.max_severity = "high".to_string();
+        instance.block_unscanned = true;
+        instance.max_artifact_age_days = Some(365);
+        let mut repo = layered("repo", PolicyLayer::Repository, true);
+        repo.max_severity = "critical".to_string();
+        repo.min_staging_hours = Some(24);
+        repo.max_artifact_age_days = Some(90);
+
+        let merged = merge_policies(&applicable_policies(vec![instance, repo])).unwrap();
+        assert_eq!(merged.max_severity, "high");
+        assert!(merged.block_unscanned);
+        assert!(!merged.block_on_fail);
+        assert_eq!(merged.min_staging_hours, Some(24));
+        assert_eq!(merged.max_artifact_age_days, Some(90));
+
+        let source = |rule: &str| {
+            merged
+                .rules
+                .iter()
+                .find(|r| r.rule == rule)
+                .map(|r| (r.layer, r.policy_name.as_str()))
+        };
+        assert_eq!(
+            source("max_severity"),
+            Some((PolicyLayer::Instance, "instance"))
+        );
+        assert_eq!(
+            source("block_unscanned"),
+            Some((PolicyLayer::Instance, "instance"))
+        );
+        // Nobody blocks on failure: the most specific policy is the source.
+        assert_eq!(
+            source("block_on_fail"),
+            Some((PolicyLayer::Repository, "repo"))
+        );
+        assert_eq!(
+            source("max_artifact_age_days"),
+            Some((PolicyLayer::Repository, "repo"))
+        );
+    }
+
+    #[test]
+    fn test_merge_policies_ties_go_to_most_specific_layer() {
+        let mut instance = layered("instance", PolicyLayer::Instance, true);
+        instance.max_severity = "medium".to_string();
+        let mut project = layered("project", PolicyLayer::Project, true);
+        project.max_severity = "medium".to_string();
+
+        let merged = merge_policies(&applicable_policies(vec![instance, project])).unwrap();
+        let rule = merged
+            .rules
+            .iter()
+            .find(|r| r.rule == "max_severity")
+            .unwrap();
+        assert_eq!(rule.layer, PolicyLayer::Project);
+        assert_eq!(rule.value, "medium");
+    }
+
+    #[test]
+    fn test_merge_policies_omits_unset_optional_rules() {
+        let merged = merge_policies(&[layered("only", PolicyLayer::Instance, true)]).unwrap();
+        assert!(merged.rules.iter().all(|r| r.rule != "min_staging_hours"));
+        assert!(merged
+            .rules
+            .iter()
+            .all(|r| r.rule != "max_artifact_age_days"));
+        assert!(merge_policies(&[]).is_none());
+    }
+
     // -----------------------------------------------------------------------
     // #1374 regression: PUT /security/policies/{id} must atomically persist
     // every field the client provided in the same request. Previously the
@@ -834,12 +1293,14 @@ mod tests {
             .create_policy(
                 &format!("1374-fixture-{}", &Uuid::new_v4().to_string()[..8]),
                 None,
+                None,
                 "low", // will become "critical"
                 true,  // block_unscanned: untouched, must stay true
                 false,
                 None,
                 None,
                 false,
+                true,
             )
             .await
             .expect("seed policy");
@@ -861,6 +1322,7 @@ mod tests {
                 None,
                 None,
                 None,
+                None,
             )
             .await
             .expect("partial update must succeed");
@@ -902,12 +1364,14 @@ mod tests {
             .create_policy(
                 &format!("1374-noop-{}", &Uuid::new_v4().to_string()[..8]),
                 None,
+                None,
                 "medium",
                 true,
                 true,
                 Some(24),
                 Some(30),
                 true,
+                true,
             )
             .await
             .expect("seed policy");
@@ -916,7 +1380,18 @@ mod tests {
         // `col = COALESCE(NULL, col)` which is a no-op for every column
         // except `updated_at = NOW()`.
         let after = svc
-            .update_policy(original.id, None, None, None, None, None, None, None, None)
+            .update_policy(
+                original.id,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .expect("empty patch must succeed, not 422");
 
@@ -0,0 +1,203 @@
+//! Canary for presigned-redirect downloads.
+//!
+//! With `PRESIGNED_DOWNLOADS_ENABLED` the backend answers downloads with a
+//! redirect to a presigned URL (S3/CloudFront signature, Azure SAS, GCS
+//! signed URL) that the client fetches straight from the provider. Clock
+//! skew, rotated signing keys or a changed bucket policy break those URLs
+//! without touching anything the backend itself reads, so nothing else
+//! notices until clients fail.
+//!
+//! [`probe`] writes a fresh canary object through the storage backend, mints
+//! a presigned URL for it, fetches the URL like a client would, compares the
+//! bytes with a direct read and deletes the object again. Every replica signs
+//! with its own clock and credentials, so each one probes under its own key.
+//! The health monitor records the outcome as the `presigned-redirect`
+//! service, so a broken redirect raises the usual alerts.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+
+use crate::storage::StorageBackend;
+
+/// Health-monitor service name the canary reports under.
+pub const SERVICE_NAME: &str = "presigned-redirect";
+
+/// Key prefix of the short-lived canary objects.
+pub const CANARY_PREFIX: &str = "canary/presigned-redirect/";
+
+/// Lifetime of the canary's presigned URL.
+const CANARY_URL_EXPIRY: Duration = Duration::from_secs(60);
+
+/// Provider clock skew beyond which a working probe is still reported
+/// degraded: presigned URLs are then close to failing as not-yet-valid or
+/// already expired.
+const MAX_CLOCK_SKEW_SECS: i64 = 120;
+
+/// Outcome of one canary probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanaryOutcome {
+    /// The presigned URL served the canary bytes.
+    Healthy,
+    /// The presigned URL works, but something is close to breaking it.
+    Degraded(String),
+    /// Redirect downloads are broken for clients.
+    Failed(String),
+}
+
+/// Run one probe against `storage`. Returns `None` when the backend does not
+/// serve redirect downloads (filesystem, encrypted storage, redirects off).
+pub async fn probe(storage: &dyn StorageBackend, client: &Client) -> Option<CanaryOutcome> {
+    if !storage.supports_redirect() {
+        return None;
+    }
+    let key = format!("{}{}", CANARY_PREFIX, uuid::Uuid::new_v4());
+    let outcome = run_probe(storage, client, &key)
+        .await
+        .unwrap_or_else(CanaryOutcome::Failed);
+    if let Err(e) = storage.delete(&key).await {
+        tracing::debug!(key = %key, "Failed to delete presigned canary object: {}", e);
+    }
+    Some(outcome)
+}
+
+async fn run_probe(
+    storage: &dyn StorageBackend,
+    client: &Client,
+    key: &str,
+) -> std::result::Result<CanaryOutcome, String> {
+    let payload = canary_payload(Utc::now());
+    storage
+        .put(key, payload.clone())
+        .await
+        .map_err(|e| format!("failed to write canary object: {}", e))?;
+    let direct = storage
+        .get(key)
+        .await
+        .map_err(|e| format!("failed to read canary object: {}", e))?;
+    if direct != payload {
+        return Err("direct read returned different bytes than were written".to_string());
+    }
+
+    let presigned = storage
+        .get_presigned_url(key, CANARY_URL_EXPIRY)
+        .await
+        .map_err(|e| format!("failed to mint presigned URL: {}", e))?
+        .ok_or_else(|| "backend supports redirects but minted no presigned URL".to_string())?;
+    let source = format!("{:?}", presigned.source);
+
+    let response = client
+        .get(&presigned.url)
+        .send()
+        .await
+        .map_err(|e| format!("{} presigned URL fetch failed: {}", source, e))?;
+    let status = response.status();
+    let skew = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| clock_skew_secs(v, Utc::now()));
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("{} presigned URL body read failed: {}", source, e))?;
+
+    if !status.is_success() {
+        let mut message = format!("{} presigned URL returned HTTP {}", source, status.as_u16());
+        if let Some(code) = provider_error_code(&body) {
+            message.push_str(&format!(" ({})", code));
+        }
+        if let Some(skew) = skew.filter(|s| s.abs() > MAX_CLOCK_SKEW_SECS) {
+            message.push_str(&format!("; provider clock is {}s off", skew));
+        }
+        return Err(message);
+    }
+    if body != direct {
+        return Err(format!(
+            "{} presigned URL served {} bytes that differ from the direct read ({} bytes)",
+            source,
+            body.len(),
+            direct.len()
+        ));
+    }
+    Ok(match skew.filter(|s| s.abs() > MAX_CLOCK_SKEW_SECS) {
+        Some(skew) => CanaryOutcome::Degraded(format!(
+            "provider clock is {}s off; presigned URLs may start failing",
+            skew
+        )),
+        None => CanaryOutcome::Healthy,
+    })
+}
+
+/// A unique canary body, so a stale cached copy can never pass the probe.
+fn canary_payload(now: DateTime<Utc>) -> Bytes {
+    Bytes::from(format!(
+        "artifact-keeper presigned-redirect canary {} {}\n",
+        now.to_rfc3339(),
+        uuid::Uuid::new_v4()
+    ))
+}
+
+/// Seconds the provider's `Date` header is ahead of (positive) or behind
+/// (negative) the local clock.
+fn clock_skew_secs(date_header: &str, now: DateTime<Utc>) -> Option<i64> {
+    let provider = DateTime::parse_from_rfc2822(date_header).ok()?;
+    Some((provider.with_timezone(&Utc) - now).num_seconds())
+}
+
+/// The error code of an S3/GCS/Azure XML error body, e.g.
+/// `RequestTimeTooSkewed`, `SignatureDoesNotMatch` or
+/// `AuthenticationFailed`.
+fn provider_error_code(body: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(body).ok()?;
+    let start = text.find("<Code>")? + "<Code>".len();
+    let end = start + text[start..].find("</Code>")?;
+    let code = text[start..end].trim();
+    (!code.is_empty()).then(|| code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_provider_error_code() {
+        let s3 = br#"<?xml version="1.0" encoding="UTF-8"?>
+<Error><Code>RequestTimeTooSkewed</Code><Message>The difference between the request time and the current time is too large.</Message></Error>"#;
+        assert_eq!(
+            provider_error_code(s3).as_deref(),
+            Some("RequestTimeTooSkewed")
+        );
+        let azure = b"<Error><Code>AuthenticationFailed</Code></Error>";
+        assert_eq!(
+            provider_error_code(azure).as_deref(),
+            Some("AuthenticationFailed")
+        );
+        assert_eq!(provider_error_code(b"<Error><Code></Code></Error>"), None);
+        assert_eq!(provider_error_code(b"Forbidden"), None);
+        assert_eq!(provider_error_code(&[0xff, 0xfe]), None);
+    }
+
+    #[test]
+    fn test_clock_skew_secs() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(
+            clock_skew_secs("Sun, 01 Mar 2026 12:05:00 GMT", now),
+            Some(300)
+        );
+        assert_eq!(
+            clock_skew_secs("Sun, 01 Mar 2026 11:59:30 GMT", now),
+            Some(-30)
+        );
+        assert_eq!(clock_skew_secs("not a date", now), None);
+    }
+
+    #[test]
+    fn test_canary_payload_is_unique() {
+        let now = Utc::now();
+        assert_ne!(canary_payload(now), canary_payload(now));
+    }
+}
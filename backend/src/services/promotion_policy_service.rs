@@ -9,6 +9,7 @@ use uuid::Uuid;
 
 use crate::error::Result;
 use crate::models::sbom::PolicyAction;
+use crate::services::policy_service::PolicyService;
 use crate::services::scan_state::{classify_scan_state, ScanState, ScanStateRow, SCAN_STATE_SQL};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -634,22 +635,22 @@ impl PromotionPolicyService {
         Ok(signed)
     }
 
+    /// The repository's effective scan policy: instance, project and
+    /// repository layers merged by [`PolicyService::merged_policy`], so the
+    /// promotion gate enforces the same rules as the download gate.
     async fn get_scan_policy(&self, repository_id: Uuid) -> Result<Option<ScanPolicyConfig>> {
-        let policy: Option<ScanPolicyConfig> = sqlx::query_as(
-            r#"
-            SELECT max_severity, block_unscanned, block_on_fail,
-                   min_staging_hours, max_artifact_age_days, require_signature
-            FROM scan_policies
-            WHERE (repository_id = $1 OR repository_id IS NULL) AND is_enabled = true
-            ORDER BY repository_id DESC NULLS LAST
-            LIMIT 1
-            "#,
-        )
-        .bind(repository_id)
-        .fetch_optional(&self.db)
-        .await?;
+        let merged = PolicyService::new(self.db.clone())
+            .merged_policy(repository_id)
+            .await?;
 
-        Ok(policy)
+        Ok(merged.map(|m| ScanPolicyConfig {
+            max_severity: m.max_severity,
+            block_unscanned: m.block_unscanned,
+            block_on_fail: m.block_on_fail,
+            min_staging_hours: m.min_staging_hours,
+            max_artifact_age_days: m.max_artifact_age_days,
+            require_signature: m.require_signature,
+        }))
     }
 
     async fn get_license_policy(&self, repository_id: Uuid) -> Result<Option<LicensePolicyConfig>> {
@@ -685,7 +686,7 @@ impl PromotionPolicyService {
     }
 }
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone)]
 struct ScanPolicyConfig {
     max_severity: String,
     block_unscanned: bool,
@@ -2113,10 +2114,8 @@ mod tests {
 
     #[test]
     fn test_get_scan_policy_sql_selects_block_unscanned() {
-        // The resolved-policy read must include block_unscanned; without it the
-        // gate would deserialize a default and the toggle would be inert again.
-        // (We assert on the SQL string used by get_scan_policy via the column
-        // list embedded in ScanPolicyConfig's FromRow usage below.)
+        // The resolved policy must carry block_unscanned; without it the gate
+        // would fall back to a default and the toggle would be inert again.
         let cfg = ScanPolicyConfig {
             max_severity: "critical".to_string(),
             block_unscanned: true,
@@ -0,0 +1,645 @@
+//! Sign-on-promotion.
+//!
+//! A release repository can be configured to sign every artifact promoted
+//! into it with an instance signing key (or one of its own keys). Container
+//! image manifests get a cosign signature: a simple-signing payload signed
+//! with the key's RSA material and pushed back into the repository under the
+//! `sha256-<digest>.sig` tag, where `cosign verify --key` finds it. Every
+//! other artifact gets a detached signature over its bytes — ASCII-armored
+//! OpenPGP (`.asc`) for `gpg` keys, raw RSA PKCS#1 v1.5 SHA-256 (`.sig`)
+//! otherwise.
+//!
+//! Signatures are stored in `artifact_signatures` and served from the signing
+//! API. Signing also writes the `used_for_signing` marker, so a promoted
+//! artifact satisfies `require_signature` gates further down the pipeline.
+//! The promotion handlers call [`PromotionSigningService::sign_promoted`]
+//! after the copy commits; a signing failure is logged and audited but does
+//! not undo the promotion.
+
+use std::sync::Arc;
+
+use base64::Engine as _;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::handlers::oci_v2::{
+    blob_storage_key, manifest_storage_key, persist_tag_and_refs_in_tx, upsert_manifest_artifact,
+    ManifestClass,
+};
+use crate::error::{AppError, Result};
+use crate::formats::oci::media_types;
+use crate::models::signing_key::SigningKey;
+use crate::services::signing_service::SigningService;
+use crate::storage::StorageBackend;
+
+/// Media type of the cosign simple-signing payload layer.
+pub const COSIGN_PAYLOAD_MEDIA_TYPE: &str = "application/vnd.dev.cosign.simplesigning.v1+json";
+
+/// Layer annotation carrying the base64 signature over the payload.
+pub const COSIGN_SIGNATURE_ANNOTATION: &str = "dev.cosignproject.cosign/signature";
+
+/// Sign-on-promotion settings for one release repository.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct PromotionSigningConfig {
+    pub repository_id: Uuid,
+    pub signing_key_id: Uuid,
+    pub enabled: bool,
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureFormat {
+    /// cosign signature over a container image manifest digest.
+    Cosign,
+    /// ASCII-armored detached OpenPGP signature.
+    Openpgp,
+    /// Raw RSA PKCS#1 v1.5 SHA-256 signature.
+    Rsa,
+}
+
+impl SignatureFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cosign => "cosign",
+            Self::Openpgp => "openpgp",
+            Self::Rsa => "rsa",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cosign" => Some(Self::Cosign),
+            "openpgp" | "asc" => Some(Self::Openpgp),
+            "rsa" | "sig" => Some(Self::Rsa),
+            _ => None,
+        }
+    }
+
+    /// File extension conventionally appended to the artifact name.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Openpgp => "asc",
+            Self::Cosign | Self::Rsa => "sig",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Openpgp => "application/pgp-signature",
+            Self::Cosign | Self::Rsa => "application/octet-stream",
+        }
+    }
+}
+
+/// A stored signature, without its bytes.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StoredSignature {
+    pub id: Uuid,
+    pub artifact_id: Uuid,
+    pub signing_key_id: Option<Uuid>,
+    pub format: SignatureFormat,
+    pub algorithm: String,
+    pub signature_sha256: String,
+    pub signed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// Registry tag holding the cosign signature (cosign only).
+    pub cosign_tag: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SignatureRow {
+    id: Uuid,
+    artifact_id: Uuid,
+    signing_key_id: Option<Uuid>,
+    format: String,
+    algorithm: String,
+    signature_sha256: String,
+    signed_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    checksum_sha256: String,
+}
+
+impl SignatureRow {
+    fn into_stored(self) -> StoredSignature {
+        let format = SignatureFormat::parse(&self.format).unwrap_or(SignatureFormat::Rsa);
+        StoredSignature {
+            id: self.id,
+            artifact_id: self.artifact_id,
+            signing_key_id: self.signing_key_id,
+            format,
+            algorithm: self.algorithm,
+            signature_sha256: self.signature_sha256,
+            signed_by: self.signed_by,
+            created_at: self.created_at,
+            cosign_tag: (format == SignatureFormat::Cosign)
+                .then(|| cosign_signature_tag(&self.checksum_sha256)),
+        }
+    }
+}
+
+const SELECT_SIGNATURE: &str = r#"
+    SELECT s.id, s.artifact_id, s.signing_key_id, s.format, s.algorithm,
+           s.signature_sha256, s.signed_by, s.created_at, a.checksum_sha256
+    FROM artifact_signatures s
+    JOIN artifacts a ON a.id = s.artifact_id
+"#;
+
+/// The image name and reference of an OCI manifest artifact path
+/// (`v2/<image>/manifests/<reference>`), or `None` for any other artifact.
+pub fn oci_manifest_reference(path: &str) -> Option<(&str, &str)> {
+    let rest = path.strip_prefix("v2/")?;
+    let (image, reference) = rest.rsplit_once("/manifests/")?;
+    (!image.is_empty() && !reference.is_empty()).then_some((image, reference))
+}
+
+/// Tag under which cosign looks up the signature for a manifest digest.
+pub fn cosign_signature_tag(digest_hex: &str) -> String {
+    format!("sha256-{}.sig", digest_hex)
+}
+
+/// The cosign simple-signing payload for `docker_reference` at `digest`
+/// (`sha256:<hex>`).
+pub fn cosign_payload(docker_reference: &str, digest: &str) -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({
+        "critical": {
+            "identity": { "docker-reference": docker_reference },
+            "image": { "docker-manifest-digest": digest },
+            "type": "cosign container image signature",
+        },
+        "optional": null,
+    }))
+    .expect("static JSON serializes")
+}
+
+fn sha256_digest(data: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(data)))
+}
+
+/// Config blob for a cosign signature image: an empty image config whose
+/// single layer is the payload, as cosign itself writes it.
+fn cosign_config(payload_digest: &str) -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({
+        "architecture": "",
+        "created": "0001-01-01T00:00:00Z",
+        "history": [{ "created": "0001-01-01T00:00:00Z" }],
+        "os": "",
+        "rootfs": { "type": "layers", "diff_ids": [payload_digest] },
+        "config": {},
+    }))
+    .expect("static JSON serializes")
+}
+
+/// Image manifest wrapping one cosign signature.
+pub fn cosign_manifest(config: &[u8], payload: &[u8], signature: &[u8]) -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({
+        "schemaVersion": 2,
+        "mediaType": media_types::OCI_MANIFEST,
+        "config": {
+            "mediaType": media_types::OCI_CONFIG,
+            "size": config.len(),
+            "digest": sha256_digest(config),
+        },
+        "layers": [{
+            "mediaType": COSIGN_PAYLOAD_MEDIA_TYPE,
+            "size": payload.len(),
+            "digest": sha256_digest(payload),
+            "annotations": {
+                COSIGN_SIGNATURE_ANNOTATION:
+                    base64::engine::general_purpose::STANDARD.encode(signature),
+            },
+        }],
+    }))
+    .expect("static JSON serializes")
+}
+
+/// Promoted artifact being signed.
+#[derive(sqlx::FromRow)]
+struct ArtifactToSign {
+    path: String,
+    storage_key: String,
+    checksum_sha256: String,
+}
+
+pub struct PromotionSigningService {
+    db: PgPool,
+    signing: SigningService,
+}
+
+impl PromotionSigningService {
+    pub fn new(db: PgPool, signing: SigningService) -> Self {
+        Self { db, signing }
+    }
+
+    pub async fn get_config(&self, repo_id: Uuid) -> Result<Option<PromotionSigningConfig>> {
+        Ok(sqlx::query_as::<_, PromotionSigningConfig>(
+            "SELECT repository_id, signing_key_id, enabled, updated_by, created_at, updated_at
+             FROM promotion_signing_config WHERE repository_id = $1",
+        )
+        .bind(repo_id)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Create or replace the sign-on-promotion settings for a repository.
+    /// The key must be active and either an instance key or owned by the
+    /// repository.
+    pub async fn set_config(
+        &self,
+        repo_id: Uuid,
+        signing_key_id: Uuid,
+        enabled: bool,
+        updated_by: Option<Uuid>,
+    ) -> Result<PromotionSigningConfig> {
+        let key: Option<(Option<Uuid>, bool)> =
+            sqlx::query_as("SELECT repository_id, is_active FROM signing_keys WHERE id = $1")
+                .bind(signing_key_id)
+                .fetch_optional(&self.db)
+                .await?;
+        match key {
+            None => return Err(AppError::NotFound("Signing key not found".to_string())),
+            Some((_, false)) => {
+                return Err(AppError::Validation(
+                    "Signing key is not active".to_string(),
+                ))
+            }
+            Some((Some(owner), _)) if owner != repo_id => {
+                return Err(AppError::Validation(
+                    "Signing key belongs to another repository; use an instance key or one of this repository's keys"
+                        .to_string(),
+                ))
+            }
+            Some(_) => {}
+        }
+
+        Ok(sqlx::query_as::<_, PromotionSigningConfig>(
+            r#"
+            INSERT INTO promotion_signing_config (repository_id, signing_key_id, enabled, updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (repository_id) DO UPDATE SET
+                signing_key_id = EXCLUDED.signing_key_id,
+                enabled = EXCLUDED.enabled,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING repository_id, signing_key_id, enabled, updated_by, created_at, updated_at
+            "#,
+        )
+        .bind(repo_id)
+        .bind(signing_key_id)
+        .bind(enabled)
+        .bind(updated_by)
+        .fetch_one(&self.db)
+        .await?)
+    }
+
+    pub async fn delete_config(&self, repo_id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM promotion_signing_config WHERE repository_id = $1")
+            .bind(repo_id)
+            .execute(&self.db)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Sign-on-promotion is not configured for this repository".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sign an artifact that was just promoted into `repo_id`, if the
+    /// repository signs on promotion. Returns `None` when it does not.
+    ///
+    /// `storage` is the repository's storage backend: detached signatures
+    /// read the artifact bytes from it, and cosign signatures are written
+    /// into it as registry content.
+    pub async fn sign_promoted(
+        &self,
+        storage: &Arc<dyn StorageBackend>,
+        repo_id: Uuid,
+        repo_key: &str,
+        artifact_id: Uuid,
+        signed_by: Option<Uuid>,
+    ) -> Result<Option<StoredSignature>> {
+        let config = match self.get_config(repo_id).await? {
+            Some(c) if c.enabled => c,
+            _ => return Ok(None),
+        };
+        let key = sqlx::query_as::<_, SigningKey>(
+            "SELECT * FROM signing_keys WHERE id = $1 AND is_active = true",
+        )
+        .bind(config.signing_key_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| {
+            AppError::Conflict(
+                "The sign-on-promotion key is no longer active; configure a new key".to_string(),
+            )
+        })?;
+        let artifact = sqlx::query_as::<_, ArtifactToSign>(
+            "SELECT path, storage_key, checksum_sha256 FROM artifacts
+             WHERE id = $1 AND is_deleted = false",
+        )
+        .bind(artifact_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Artifact not found".to_string()))?;
+
+        let (format, algorithm, signature, payload) = if let Some((image, _)) =
+            oci_manifest_reference(&artifact.path)
+        {
+            if key.supports_openpgp() {
+                return Err(AppError::Validation(
+                        "cosign signatures need an RSA key; the sign-on-promotion key is an OpenPGP key"
+                            .to_string(),
+                    ));
+            }
+            let digest = format!("sha256:{}", artifact.checksum_sha256);
+            let payload = cosign_payload(&format!("{}/{}", repo_key, image), &digest);
+            let signature = self.signing.sign_with_key(&key, &payload)?;
+            self.push_cosign_signature(
+                storage,
+                repo_id,
+                image,
+                &artifact.checksum_sha256,
+                &payload,
+                &signature,
+                signed_by,
+            )
+            .await?;
+            (
+                SignatureFormat::Cosign,
+                format!("cosign:rsa-pkcs1v15-sha256:{}", key.algorithm),
+                signature,
+                Some(payload),
+            )
+        } else {
+            let content = storage.get(&artifact.storage_key).await?;
+            let (signature, algorithm) = self.signing.sign_content_with_key(&key, &content).await?;
+            let format = if key.supports_openpgp() {
+                SignatureFormat::Openpgp
+            } else {
+                SignatureFormat::Rsa
+            };
+            (format, algorithm, signature, None)
+        };
+
+        let signature_sha256 = hex::encode(Sha256::digest(&signature));
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO artifact_signatures
+                (artifact_id, signing_key_id, format, algorithm, signature, payload,
+                 signature_sha256, signed_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (artifact_id, format) DO UPDATE SET
+                signing_key_id = EXCLUDED.signing_key_id,
+                algorithm = EXCLUDED.algorithm,
+                signature = EXCLUDED.signature,
+                payload = EXCLUDED.payload,
+                signature_sha256 = EXCLUDED.signature_sha256,
+                signed_by = EXCLUDED.signed_by,
+                created_at = NOW()
+            RETURNING id
+            "#,
+        )
+        .bind(artifact_id)
+        .bind(key.id)
+        .bind(format.as_str())
+        .bind(&algorithm)
+        .bind(&signature)
+        .bind(&payload)
+        .bind(&signature_sha256)
+        .bind(signed_by)
+        .fetch_one(&self.db)
+        .await?;
+
+        self.signing
+            .record_artifact_signature(
+                key.id,
+                artifact_id,
+                signed_by,
+                &algorithm,
+                &signature_sha256,
+            )
+            .await?;
+        self.signing.mark_key_used(key.id).await?;
+
+        Ok(Some(StoredSignature {
+            id,
+            artifact_id,
+            signing_key_id: Some(key.id),
+            format,
+            algorithm,
+            signature_sha256,
+            signed_by,
+            created_at: Utc::now(),
+            cosign_tag: (format == SignatureFormat::Cosign)
+                .then(|| cosign_signature_tag(&artifact.checksum_sha256)),
+        }))
+    }
+
+    /// Write a cosign signature image for `image@sha256:<digest_hex>` into
+    /// the repository and tag it `sha256-<digest_hex>.sig`, registering the
+    /// blobs, manifest and tag exactly as a `cosign sign` push would.
+    #[allow(clippy::too_many_arguments)]
+    async fn push_cosign_signature(
+        &self,
+        storage: &Arc<dyn StorageBackend>,
+        repo_id: Uuid,
+        image: &str,
+        digest_hex: &str,
+        payload: &[u8],
+        signature: &[u8],
+        signed_by: Option<Uuid>,
+    ) -> Result<()> {
+        let payload_digest = sha256_digest(payload);
+        let config = cosign_config(&payload_digest);
+        let config_digest = sha256_digest(&config);
+        let manifest = cosign_manifest(&config, payload, signature);
+        let manifest_digest = sha256_digest(&manifest);
+        let tag = cosign_signature_tag(digest_hex);
+
+        for (digest, body) in [
+            (&config_digest, config.as_slice()),
+            (&payload_digest, payload),
+        ] {
+            storage
+                .put(&blob_storage_key(digest), Bytes::copy_from_slice(body))
+                .await?;
+        }
+        storage
+            .put(
+                &manifest_storage_key(&manifest_digest),
+                Bytes::from(manifest.clone()),
+            )
+            .await?;
+
+        let mut tx = self.db.begin().await?;
+        for (digest, size) in [
+            (&config_digest, config.len()),
+            (&payload_digest, payload.len()),
+        ] {
+            sqlx::query(
+                "INSERT INTO oci_blobs (repository_id, digest, size_bytes, storage_key) \
+                 VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (repository_id, digest) DO UPDATE SET pending_delete_at = NULL",
+            )
+            .bind(repo_id)
+            .bind(digest)
+            .bind(size as i64)
+            .bind(blob_storage_key(digest))
+            .execute(&mut *tx)
+            .await?;
+        }
+        upsert_manifest_artifact(
+            &mut *tx,
+            repo_id,
+            image,
+            &tag,
+            &manifest_digest,
+            media_types::OCI_MANIFEST,
+            &manifest_storage_key(&manifest_digest),
+            (config.len() + payload.len()) as i64,
+            signed_by,
+        )
+        .await?;
+        persist_tag_and_refs_in_tx(
+            &mut tx,
+            repo_id,
+            image,
+            &tag,
+            &manifest_digest,
+            media_types::OCI_MANIFEST,
+            &ManifestClass::Image,
+            &manifest,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn list_signatures(&self, artifact_id: Uuid) -> Result<Vec<StoredSignature>> {
+        let rows = sqlx::query_as::<_, SignatureRow>(&format!(
+            "{} WHERE s.artifact_id = $1 ORDER BY s.format",
+            SELECT_SIGNATURE
+        ))
+        .bind(artifact_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows.into_iter().map(SignatureRow::into_stored).collect())
+    }
+
+    /// A stored signature and its bytes. For cosign the bytes are the
+    /// simple-signing payload's raw signature; the payload itself is returned
+    /// as the third element.
+    pub async fn get_signature(
+        &self,
+        artifact_id: Uuid,
+        format: SignatureFormat,
+    ) -> Result<(StoredSignature, Vec<u8>, Option<Vec<u8>>)> {
+        let row = sqlx::query_as::<_, SignatureRow>(&format!(
+            "{} WHERE s.artifact_id = $1 AND s.format = $2",
+            SELECT_SIGNATURE
+        ))
+        .bind(artifact_id)
+        .bind(format.as_str())
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Signature not found".to_string()))?;
+        let (signature, payload): (Vec<u8>, Option<Vec<u8>>) =
+            sqlx::query_as("SELECT signature, payload FROM artifact_signatures WHERE id = $1")
+                .bind(row.id)
+                .fetch_one(&self.db)
+                .await?;
+        Ok((row.into_stored(), signature, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oci_manifest_reference() {
+        assert_eq!(
+            oci_manifest_reference("v2/team/app/manifests/1.2.0"),
+            Some(("team/app", "1.2.0"))
+        );
+        assert_eq!(
+            oci_manifest_reference("v2/app/manifests/sha256:abc"),
+            Some(("app", "sha256:abc"))
+        );
+        assert_eq!(oci_manifest_reference("v2/app/blobs/sha256:abc"), None);
+        assert_eq!(oci_manifest_reference("com/acme/lib/1.0/lib-1.0.jar"), None);
+        assert_eq!(oci_manifest_reference("v2//manifests/latest"), None);
+    }
+
+    #[test]
+    fn test_signature_format_round_trip() {
+        for format in [
+            SignatureFormat::Cosign,
+            SignatureFormat::Openpgp,
+            SignatureFormat::Rsa,
+        ] {
+            assert_eq!(SignatureFormat::parse(format.as_str()), Some(format));
+        }
+        assert_eq!(
+            SignatureFormat::parse("asc"),
+            Some(SignatureFormat::Openpgp)
+        );
+        assert_eq!(SignatureFormat::parse("sig"), Some(SignatureFormat::Rsa));
+        assert_eq!(SignatureFormat::parse("x509"), None);
+        assert_eq!(SignatureFormat::Openpgp.extension(), "asc");
+    }
+
+    #[test]
+    fn test_cosign_payload_shape() {
+        let payload = cosign_payload("release/app", "sha256:abcd");
+        let value: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(
+            value["critical"]["identity"]["docker-reference"],
+            "release/app"
+        );
+        assert_eq!(
+            value["critical"]["image"]["docker-manifest-digest"],
+            "sha256:abcd"
+        );
+        assert_eq!(
+            value["critical"]["type"],
+            "cosign container image signature"
+        );
+        assert!(value["optional"].is_null());
+    }
+
+    #[test]
+    fn test_cosign_manifest_references_payload_and_signature() {
+        let payload = cosign_payload("release/app", "sha256:abcd");
+        let config = cosign_config(&sha256_digest(&payload));
+        let manifest = cosign_manifest(&config, &payload, b"sig-bytes");
+        let value: serde_json::Value = serde_json::from_slice(&manifest).unwrap();
+
+        assert_eq!(value["config"]["digest"], sha256_digest(&config));
+        let layer = &value["layers"][0];
+        assert_eq!(layer["mediaType"], COSIGN_PAYLOAD_MEDIA_TYPE);
+        assert_eq!(layer["digest"], sha256_digest(&payload));
+        assert_eq!(layer["size"], payload.len());
+        assert_eq!(
+            layer["annotations"][COSIGN_SIGNATURE_ANNOTATION],
+            base64::engine::general_purpose::STANDARD.encode(b"sig-bytes")
+        );
+        assert!(matches!(
+            crate::api::handlers::oci_v2::classify_manifest(&manifest),
+            ManifestClass::Image
+        ));
+    }
+
+    #[test]
+    fn test_cosign_signature_tag() {
+        assert_eq!(cosign_signature_tag("abc123"), "sha256-abc123.sig");
+    }
+}
@@ -32,6 +32,7 @@ use crate::services::proxy_hydration::{
     Coordinator, HydrationCoordinator, StreamHandle, StreamHeaders,
 };
 use crate::services::quarantine_service;
+use crate::services::remote_repo_service::RemoteRepoService;
 use crate::services::storage_service::StorageService;
 
 /// Default cache TTL in seconds (24 hours)
@@ -2571,11 +2572,23 @@ impl ProxyService {
         max: usize,
     ) -> Result<(Bytes, Option<String>)> {
         let upstream_url = Self::remote_target(repo)?;
+        crate::services::package_protection_service::check_upstream_fetch(
+            &self.db, repo, cache_path,
+        )
+        .await?;
 
         // Cache keys use the caller-supplied cache_path
         let cache_key = Self::cache_storage_key(&repo.key, cache_path)?;
         let metadata_key = Self::cache_metadata_key(&repo.key, cache_path)?;
 
+        // Offline mode: serve whatever is cached, however stale, and never
+        // contact upstream.
+        if RemoteRepoService::is_offline(&self.db, repo.id).await {
+            return self
+                .read_cached_offline(cache_path, &cache_key, &metadata_key)
+                .await;
+        }
+
         // #1611: classify the path and evaluate cache freshness up front.
         //   * Immutable Fresh hit  -> serve directly, NEVER contact upstream.
         //   * Mutable   Fresh hit  -> serve directly (within TTL).
@@ -2910,6 +2923,13 @@ impl ProxyService {
         // usually done and the cache is warm. We loop a bounded number of times
         // to avoid an unbounded re-enter storm; in practice one re-enter hits the
         // warm cache or wins the election outright.
+        crate::services::package_protection_service::check_upstream_fetch(
+            &self.db, repo, cache_path,
+        )
+        .await?;
+        if RemoteRepoService::is_offline(&self.db, repo.id).await {
+            return self.open_cached_stream_offline(repo, cache_path).await;
+        }
         const STREAM_REENTER_BUDGET: usize = 8;
         for _ in 0..STREAM_REENTER_BUDGET {
             if let Some(result) = self
@@ -2949,6 +2969,10 @@ impl ProxyService {
         repo: &Repository,
         cache_path: &str,
     ) -> Result<Option<StreamingFetchResult>> {
+        crate::services::package_protection_service::check_upstream_fetch(
+            &self.db, repo, cache_path,
+        )
+        .await?;
         let cache_key = Self::cache_storage_key(&repo.key, cache_path)?;
         let metadata_key = Self::cache_metadata_key(&repo.key, cache_path)?;
         self.try_streaming_cache_hit(repo, cache_path, cache_path, &cache_key, &metadata_key)
@@ -3461,6 +3485,15 @@ impl ProxyService {
         self.invalidate_cache_keys(repo_key, path).await
     }
 
+    /// Stream a proxy-cached object's content by its physical storage key
+    /// (the catalog's `storage_key`), for integrity verification.
+    pub async fn cached_content_stream(
+        &self,
+        storage_key: &str,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        self.storage.get_stream(storage_key).await
+    }
+
     /// Shared invalidation core for [`Self::invalidate_cache`] (keyed off
     /// `repo.key`) and [`Self::invalidate_cache_by_key`] (keyed off a bare
     /// `repo_key`). The two public methods differ only in how they obtain the
@@ -4501,6 +4534,65 @@ impl ProxyService {
         }
     }
 
+    /// Offline-mode buffered read: any cached body is served regardless of
+    /// its TTL, and upstream is never contacted. A negative-cache entry still
+    /// answers 404, as does a path that was never cached.
+    async fn read_cached_offline(
+        &self,
+        cache_path: &str,
+        cache_key: &str,
+        metadata_key: &str,
+    ) -> Result<(Bytes, Option<String>)> {
+        let metadata = self.load_cache_metadata(metadata_key).await.unwrap_or(None);
+        let metadata = Self::offline_servable(metadata, cache_path)?;
+        check_quarantine_until(metadata.quarantine_until)?;
+        self.get_cached(cache_key, metadata_key, true)
+            .await?
+            .ok_or_else(|| Self::offline_miss(cache_path))
+    }
+
+    /// Streaming sibling of [`Self::read_cached_offline`].
+    async fn open_cached_stream_offline(
+        &self,
+        repo: &Repository,
+        cache_path: &str,
+    ) -> Result<StreamingFetchResult> {
+        let cache_key = Self::cache_storage_key(&repo.key, cache_path)?;
+        let metadata_key = Self::cache_metadata_key(&repo.key, cache_path)?;
+        let metadata = self
+            .load_cache_metadata(&metadata_key)
+            .await
+            .unwrap_or(None);
+        let metadata = Self::offline_servable(metadata, cache_path)?;
+        check_quarantine_until(metadata.quarantine_until)?;
+        self.open_cached_stream(&cache_key, &metadata)
+            .await?
+            .ok_or_else(|| Self::offline_miss(cache_path))
+    }
+
+    /// The sidecar an offline read may serve from: present and not a cached
+    /// upstream 404. Expiry is deliberately ignored.
+    fn offline_servable(
+        metadata: Option<CacheMetadata>,
+        cache_path: &str,
+    ) -> Result<CacheMetadata> {
+        match metadata {
+            Some(m) if m.negative_cached_until.is_some() => Err(AppError::NotFound(format!(
+                "Upstream returned 404 (negative-cached) for {}",
+                cache_path
+            ))),
+            Some(m) => Ok(m),
+            None => Err(Self::offline_miss(cache_path)),
+        }
+    }
+
+    fn offline_miss(cache_path: &str) -> AppError {
+        AppError::NotFound(format!(
+            "Repository is offline and {} is not cached",
+            cache_path
+        ))
+    }
+
     /// Cache-only, classifier- and quarantine-aware buffered read for the
     /// virtual metadata first-match resolver (#2069).
     ///
@@ -4522,6 +4614,10 @@ impl ProxyService {
         repo: &Repository,
         cache_path: &str,
     ) -> Result<Option<(Bytes, Option<String>)>> {
+        crate::services::package_protection_service::check_upstream_fetch(
+            &self.db, repo, cache_path,
+        )
+        .await?;
         let cache_key = Self::cache_storage_key(&repo.key, cache_path)?;
         let metadata_key = Self::cache_metadata_key(&repo.key, cache_path)?;
         let mutability = cache_classifier::classify(&repo.format, cache_path);
@@ -0,0 +1,161 @@
+//! Public mirror bandwidth accounting.
+//!
+//! The public mirror middleware counts traffic in memory per replica; this
+//! service adds those counts to `public_mirror_usage` (one row per day and
+//! repository) and reports them.
+
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+use crate::api::middleware::public_mirror::RepoUsage;
+use crate::error::{AppError, Result};
+
+/// Longest reporting period accepted by [`PublicMirrorService::usage`].
+pub const MAX_USAGE_DAYS: u32 = 366;
+
+/// One day of traffic for one repository.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct MirrorUsageRow {
+    pub day: NaiveDate,
+    pub repository_key: String,
+    pub requests: i64,
+    pub bytes_served: i64,
+    pub anonymous_requests: i64,
+    pub anonymous_bytes_served: i64,
+    pub throttled_requests: i64,
+}
+
+/// Sums over a usage report.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct MirrorUsageTotals {
+    pub requests: i64,
+    pub bytes_served: i64,
+    pub anonymous_requests: i64,
+    pub anonymous_bytes_served: i64,
+    pub throttled_requests: i64,
+}
+
+pub fn usage_totals(rows: &[MirrorUsageRow]) -> MirrorUsageTotals {
+    rows.iter().fold(MirrorUsageTotals::default(), |mut t, r| {
+        t.requests += r.requests;
+        t.bytes_served += r.bytes_served;
+        t.anonymous_requests += r.anonymous_requests;
+        t.anonymous_bytes_served += r.anonymous_bytes_served;
+        t.throttled_requests += r.throttled_requests;
+        t
+    })
+}
+
+fn to_i64(v: u64) -> i64 {
+    i64::try_from(v).unwrap_or(i64::MAX)
+}
+
+pub struct PublicMirrorService {
+    db: PgPool,
+}
+
+impl PublicMirrorService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Add drained counters to today's rows.
+    pub async fn add_usage(&self, usage: &[(String, RepoUsage)]) -> Result<()> {
+        let day = Utc::now().date_naive();
+        for (repository_key, u) in usage {
+            // Requests that matched no repository (e.g. `/npm/`) are
+            // accounted under an empty key.
+            sqlx::query(
+                r#"
+                INSERT INTO public_mirror_usage
+                    (day, repository_key, requests, bytes_served, anonymous_requests,
+                     anonymous_bytes_served, throttled_requests)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (day, repository_key) DO UPDATE
+                SET requests = public_mirror_usage.requests + EXCLUDED.requests,
+                    bytes_served = public_mirror_usage.bytes_served + EXCLUDED.bytes_served,
+                    anonymous_requests =
+                        public_mirror_usage.anonymous_requests + EXCLUDED.anonymous_requests,
+                    anonymous_bytes_served =
+                        public_mirror_usage.anonymous_bytes_served + EXCLUDED.anonymous_bytes_served,
+                    throttled_requests =
+                        public_mirror_usage.throttled_requests + EXCLUDED.throttled_requests,
+                    updated_at = NOW()
+                "#,
+            )
+            .bind(day)
+            .bind(repository_key)
+            .bind(to_i64(u.requests))
+            .bind(to_i64(u.bytes_served))
+            .bind(to_i64(u.anonymous_requests))
+            .bind(to_i64(u.anonymous_bytes_served))
+            .bind(to_i64(u.throttled_requests))
+            .execute(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Usage over the last `days` days (today included), optionally for one
+    /// repository, newest first.
+    pub async fn usage(
+        &self,
+        days: u32,
+        repository_key: Option<&str>,
+    ) -> Result<Vec<MirrorUsageRow>> {
+        let days = days.clamp(1, MAX_USAGE_DAYS) as i32;
+        let rows: Vec<MirrorUsageRow> = sqlx::query_as(
+            r#"
+            SELECT day, repository_key, requests, bytes_served, anonymous_requests,
+                   anonymous_bytes_served, throttled_requests
+            FROM public_mirror_usage
+            WHERE day > CURRENT_DATE - $1::INT
+              AND ($2::TEXT IS NULL OR repository_key = $2)
+            ORDER BY day DESC, bytes_served DESC
+            "#,
+        )
+        .bind(days)
+        .bind(repository_key)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(requests: i64, bytes: i64, throttled: i64) -> MirrorUsageRow {
+        MirrorUsageRow {
+            day: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            repository_key: "npm-public".to_string(),
+            requests,
+            bytes_served: bytes,
+            anonymous_requests: requests,
+            anonymous_bytes_served: bytes,
+            throttled_requests: throttled,
+        }
+    }
+
+    #[test]
+    fn test_usage_totals_sums_rows() {
+        let totals = usage_totals(&[row(10, 1000, 1), row(5, 500, 0)]);
+        assert_eq!(totals.requests, 15);
+        assert_eq!(totals.bytes_served, 1500);
+        assert_eq!(totals.anonymous_bytes_served, 1500);
+        assert_eq!(totals.throttled_requests, 1);
+        assert_eq!(usage_totals(&[]), MirrorUsageTotals::default());
+    }
+
+    #[test]
+    fn test_to_i64_saturates() {
+        assert_eq!(to_i64(42), 42);
+        assert_eq!(to_i64(u64::MAX), i64::MAX);
+    }
+}
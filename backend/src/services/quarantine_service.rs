@@ -461,10 +461,13 @@ pub async fn get_status_with_repo(
 ///
 /// This is the common quarantine gate for all download paths. It queries the
 /// artifact's quarantine fields and returns an error if the artifact is
-/// quarantined (409 Conflict) or rejected (403 Forbidden).
+/// quarantined (409 Conflict) or rejected (403 Forbidden). A download that
+/// passes has its policy decision recorded in the background (see
+/// `policy_decision_service`).
 pub async fn check_artifact_download(db: &PgPool, artifact_id: Uuid) -> Result<()> {
     if let Some((status, until)) = fetch_quarantine_fields(db, artifact_id).await? {
         check_download_allowed(status.as_deref(), until, Utc::now())?;
+        crate::services::policy_decision_service::record_download(db, artifact_id);
     }
 
     Ok(())
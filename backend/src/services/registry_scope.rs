@@ -0,0 +1,151 @@
+//! Docker registry token scopes.
+//!
+//! A registry client asks the token endpoint for the access it needs, e.g.
+//! `scope=repository:library/app:pull,push` (repeatable in the query string,
+//! space-separated in the OAuth2 form body). The OCI handler maps each
+//! requested repository action onto the caller's RBAC grants and mints a
+//! token narrowed to what was both requested and allowed: its repository
+//! allow-list is the granted repositories and its action scopes the granted
+//! actions (`pull` → `read`, `push` → `write`, `delete` → `delete`).
+
+use std::collections::BTreeSet;
+
+/// One requested scope: `type:name:action[,action...]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryScope {
+    pub resource_type: String,
+    pub name: String,
+    pub actions: Vec<String>,
+}
+
+impl RegistryScope {
+    pub fn is_repository(&self) -> bool {
+        self.resource_type == "repository"
+    }
+
+    /// The token action scopes this scope's registry actions ask for.
+    pub fn token_scopes(&self) -> BTreeSet<&'static str> {
+        self.actions
+            .iter()
+            .flat_map(|action| token_scopes_for_action(action).iter().copied())
+            .collect()
+    }
+}
+
+/// Parse one scope. The resource name may itself contain `:` (a registry
+/// host with a port), so the type is split off the front and the actions off
+/// the back. A type qualifier such as `repository(plugin)` is dropped.
+pub fn parse_scope(value: &str) -> Option<RegistryScope> {
+    let (resource_type, rest) = value.split_once(':')?;
+    let (name, actions) = rest.rsplit_once(':')?;
+    let resource_type = resource_type
+        .split_once('(')
+        .map_or(resource_type, |(t, _)| t);
+    if resource_type.is_empty() || name.is_empty() {
+        return None;
+    }
+    let actions: Vec<String> = actions
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .collect();
+    if actions.is_empty() {
+        return None;
+    }
+    Some(RegistryScope {
+        resource_type: resource_type.to_string(),
+        name: name.to_string(),
+        actions,
+    })
+}
+
+/// Every scope a token request carries, from the `scope` parameters of the
+/// query string and of an OAuth2 form body. A parameter may repeat and may
+/// hold several space-separated scopes; malformed entries are ignored.
+pub fn requested_scopes(raw_query: Option<&str>, form_body: Option<&[u8]>) -> Vec<RegistryScope> {
+    raw_query
+        .map(str::as_bytes)
+        .into_iter()
+        .chain(form_body)
+        .flat_map(url::form_urlencoded::parse)
+        .filter(|(key, _)| key == "scope")
+        .flat_map(|(_, value)| {
+            value
+                .split_whitespace()
+                .filter_map(parse_scope)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Token action scopes a registry action maps to; unknown actions map to
+/// none.
+pub fn token_scopes_for_action(action: &str) -> &'static [&'static str] {
+    match action {
+        "pull" => &["read"],
+        "push" => &["write"],
+        "delete" => &["delete"],
+        "*" => &["read", "write", "delete"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scope() {
+        let scope = parse_scope("repository:docker-local/app:pull,push").unwrap();
+        assert!(scope.is_repository());
+        assert_eq!(scope.name, "docker-local/app");
+        assert_eq!(scope.actions, vec!["pull", "push"]);
+        assert_eq!(
+            scope.token_scopes().into_iter().collect::<Vec<_>>(),
+            vec!["read", "write"]
+        );
+
+        let scope = parse_scope("repository:localhost:5000/app:pull").unwrap();
+        assert_eq!(scope.name, "localhost:5000/app");
+        let scope = parse_scope("repository(plugin):docker-local/p:pull").unwrap();
+        assert_eq!(scope.resource_type, "repository");
+        let scope = parse_scope("registry:catalog:*").unwrap();
+        assert!(!scope.is_repository());
+        assert_eq!(scope.token_scopes().len(), 3);
+
+        assert_eq!(parse_scope("repository:app"), None);
+        assert_eq!(parse_scope("repository:app:"), None);
+        assert_eq!(parse_scope(":app:pull"), None);
+    }
+
+    #[test]
+    fn test_requested_scopes_from_query_and_form() {
+        let query = "service=artifact-keeper\
+                     &scope=repository%3Adocker-local%2Fapp%3Apull%2Cpush\
+                     &scope=repository:docker-local/base:pull";
+        let scopes = requested_scopes(
+            Some(query),
+            Some(b"grant_type=password&scope=repository%3Adocker-local%2Fextra%3Apull+garbage"),
+        );
+        let names: Vec<&str> = scopes.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "docker-local/app",
+                "docker-local/base",
+                "docker-local/extra"
+            ]
+        );
+        assert!(requested_scopes(Some("service=artifact-keeper"), None).is_empty());
+        assert!(requested_scopes(None, None).is_empty());
+    }
+
+    #[test]
+    fn test_token_scopes_for_action() {
+        assert_eq!(token_scopes_for_action("pull"), &["read"]);
+        assert_eq!(token_scopes_for_action("push"), &["write"]);
+        assert_eq!(token_scopes_for_action("delete"), &["delete"]);
+        assert!(token_scopes_for_action("admin").is_empty());
+    }
+}
@@ -0,0 +1,892 @@
+//! Promotion into a repository on a trusted federated instance.
+//!
+//! The sending instance runs its promotion gates locally, then announces a
+//! [`PromotionManifest`] to the peer (`POST /api/v1/federation/promotions`)
+//! and streams the artifact body
+//! (`PUT /api/v1/federation/promotions/:id/content`). Both requests carry a
+//! federation token (see [`crate::services::federation_service`]).
+//!
+//! The receiving instance registers the artifact, its format metadata and the
+//! transferred scan results in one transaction, and only after the bytes have
+//! arrived and match the announced SHA-256 and size. Signatures cannot be
+//! re-attested without the sender's private key, so they travel as provenance:
+//! they are kept on the inbound record and copied into the artifact's
+//! metadata properties under `federation`.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::artifact::Artifact;
+use crate::services::artifact_service::ContentDigests;
+use crate::services::federation_service::{FederationScope, LocalIdentity, TrustedPeer};
+use crate::services::vulnerability_service;
+
+/// How long an announced promotion waits for its content.
+const INBOUND_TTL_MINUTES: i64 = 60;
+
+/// Upper bound on findings carried per scan.
+pub const MAX_FINDINGS_PER_SCAN: usize = 10_000;
+
+/// Upper bound on scans and signatures carried per manifest.
+const MAX_SCANS: usize = 32;
+const MAX_SIGNATURES: usize = 32;
+
+const SEVERITIES: [&str; 5] = ["critical", "high", "medium", "low", "info"];
+
+// ---------------------------------------------------------------------------
+// Manifest
+// ---------------------------------------------------------------------------
+
+/// Everything the receiving instance needs to register a promoted artifact.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PromotionManifest {
+    /// Sender-side promotion id; retries reuse it.
+    pub promotion_id: Uuid,
+    pub source_instance_id: Uuid,
+    pub source_instance_name: String,
+    pub source_repository: String,
+    pub source_artifact_id: Uuid,
+    pub target_repository: String,
+    pub format: String,
+    pub path: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub size_bytes: i64,
+    pub checksum_sha256: String,
+    pub checksum_sha1: Option<String>,
+    pub checksum_md5: Option<String>,
+    pub content_type: String,
+    pub metadata: Option<ManifestMetadata>,
+    #[serde(default)]
+    pub scans: Vec<ManifestScan>,
+    #[serde(default)]
+    pub signatures: Vec<ManifestSignature>,
+    /// Username of the promoter on the sending instance.
+    pub promoted_by: String,
+    pub notes: Option<String>,
+}
+
+/// The `artifact_metadata` row of the source artifact.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ManifestMetadata {
+    pub format: String,
+    pub metadata: serde_json::Value,
+    #[serde(default)]
+    pub properties: serde_json::Value,
+}
+
+/// The latest completed scan of one type, with its findings.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ManifestScan {
+    pub scan_type: String,
+    pub scanner_version: Option<String>,
+    pub source_tool: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub findings: Vec<ManifestFinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ManifestFinding {
+    pub severity: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub cve_id: Option<String>,
+    pub affected_component: Option<String>,
+    pub affected_version: Option<String>,
+    pub fixed_version: Option<String>,
+    pub source: Option<String>,
+    pub source_url: Option<String>,
+}
+
+/// A signature the sending instance recorded for the artifact.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ManifestSignature {
+    pub key_type: String,
+    pub key_id: Option<String>,
+    pub fingerprint: Option<String>,
+    pub public_key_pem: String,
+    pub algorithm: Option<String>,
+    pub signature_sha256: Option<String>,
+    pub signed_at: DateTime<Utc>,
+}
+
+impl PromotionManifest {
+    /// Structural checks run by the receiver before anything is stored.
+    pub fn validate(&self) -> Result<()> {
+        let path = self.path.trim_start_matches('/');
+        if path.is_empty() || path.split('/').any(|s| s == ".." || s == ".") {
+            return Err(AppError::Validation(format!(
+                "Invalid artifact path '{}'",
+                self.path
+            )));
+        }
+        if self.checksum_sha256.len() != 64
+            || !self.checksum_sha256.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            return Err(AppError::Validation(
+                "checksum_sha256 must be 64 hex characters".to_string(),
+            ));
+        }
+        if self.size_bytes < 0 {
+            return Err(AppError::Validation(
+                "size_bytes must not be negative".to_string(),
+            ));
+        }
+        if self.scans.len() > MAX_SCANS || self.signatures.len() > MAX_SIGNATURES {
+            return Err(AppError::Validation(format!(
+                "A promotion may carry at most {} scans and {} signatures",
+                MAX_SCANS, MAX_SIGNATURES
+            )));
+        }
+        for scan in &self.scans {
+            if scan.findings.len() > MAX_FINDINGS_PER_SCAN {
+                return Err(AppError::Validation(format!(
+                    "Scan '{}' carries {} findings; at most {} are accepted",
+                    scan.scan_type,
+                    scan.findings.len(),
+                    MAX_FINDINGS_PER_SCAN
+                )));
+            }
+            if let Some(f) = scan
+                .findings
+                .iter()
+                .find(|f| !SEVERITIES.contains(&f.severity.as_str()))
+            {
+                return Err(AppError::Validation(format!(
+                    "Unknown finding severity '{}'",
+                    f.severity
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Provenance recorded on the receiving side.
+    pub fn provenance(&self) -> serde_json::Value {
+        serde_json::json!({
+            "source_instance_id": self.source_instance_id,
+            "source_instance_name": self.source_instance_name,
+            "source_repository": self.source_repository,
+            "source_artifact_id": self.source_artifact_id,
+            "promotion_id": self.promotion_id,
+            "promoted_by": self.promoted_by,
+            "signatures": self.signatures,
+        })
+    }
+}
+
+/// Severity counts in `(critical, high, medium, low, info)` order.
+fn severity_counts(findings: &[ManifestFinding]) -> (i32, i32, i32, i32, i32) {
+    let mut counts = (0, 0, 0, 0, 0);
+    for f in findings {
+        match f.severity.as_str() {
+            "critical" => counts.0 += 1,
+            "high" => counts.1 += 1,
+            "medium" => counts.2 += 1,
+            "low" => counts.3 += 1,
+            _ => counts.4 += 1,
+        }
+    }
+    counts
+}
+
+// ---------------------------------------------------------------------------
+// Sending side
+// ---------------------------------------------------------------------------
+
+/// Where a manifest is headed and who asked for it.
+pub struct ManifestTarget<'a> {
+    pub promotion_id: Uuid,
+    pub source_repository: &'a str,
+    pub format: &'a str,
+    pub target_repository: &'a str,
+    pub promoted_by: &'a str,
+    pub notes: Option<&'a str>,
+}
+
+/// Collect the artifact's metadata, latest completed scans and recorded
+/// signatures into a manifest.
+pub async fn build_manifest(
+    db: &PgPool,
+    identity: &LocalIdentity,
+    artifact: &Artifact,
+    target: ManifestTarget<'_>,
+) -> Result<PromotionManifest> {
+    let metadata = sqlx::query(
+        "SELECT format, metadata, properties FROM artifact_metadata WHERE artifact_id = $1",
+    )
+    .bind(artifact.id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .map(|row| ManifestMetadata {
+        format: row.get("format"),
+        metadata: row.get("metadata"),
+        properties: row.get("properties"),
+    });
+
+    let scan_rows = sqlx::query(
+        "SELECT DISTINCT ON (scan_type) id, scan_type, scanner_version, source_tool, completed_at \
+         FROM scan_results \
+         WHERE artifact_id = $1 AND status = 'completed' \
+         ORDER BY scan_type, completed_at DESC NULLS LAST \
+         LIMIT $2",
+    )
+    .bind(artifact.id)
+    .bind(MAX_SCANS as i64)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let mut scans = Vec::with_capacity(scan_rows.len());
+    for row in scan_rows {
+        let scan_id: Uuid = row.get("id");
+        let findings = sqlx::query(
+            "SELECT sf.severity, sf.title, COALESCE(sf.description, v.description) AS description, \
+                    sf.cve_id, sf.affected_component, sf.affected_version, sf.fixed_version, \
+                    sf.source, sf.source_url \
+             FROM scan_findings sf \
+             LEFT JOIN vulnerabilities v ON v.id = sf.vulnerability_id \
+             WHERE sf.scan_result_id = $1 \
+             ORDER BY sf.created_at LIMIT $2",
+        )
+        .bind(scan_id)
+        .bind(MAX_FINDINGS_PER_SCAN as i64)
+        .fetch_all(db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .into_iter()
+        .map(|f| ManifestFinding {
+            severity: f.get("severity"),
+            title: f.get("title"),
+            description: f.get("description"),
+            cve_id: f.get("cve_id"),
+            affected_component: f.get("affected_component"),
+            affected_version: f.get("affected_version"),
+            fixed_version: f.get("fixed_version"),
+            source: f.get("source"),
+            source_url: f.get("source_url"),
+        })
+        .collect();
+        scans.push(ManifestScan {
+            scan_type: row.get("scan_type"),
+            scanner_version: row.get("scanner_version"),
+            source_tool: row.get("source_tool"),
+            completed_at: row.get("completed_at"),
+            findings,
+        });
+    }
+
+    let signatures = sqlx::query(
+        "SELECT k.key_type, k.key_id, k.fingerprint, k.public_key_pem, \
+                a.details->>'algorithm' AS algorithm, \
+                a.details->>'signature_sha256' AS signature_sha256, \
+                a.created_at \
+         FROM signing_key_audit a \
+         JOIN signing_keys k ON k.id = a.signing_key_id \
+         WHERE a.action = 'used_for_signing' AND a.details->>'artifact_id' = $1::text \
+         ORDER BY a.created_at \
+         LIMIT $2",
+    )
+    .bind(artifact.id)
+    .bind(MAX_SIGNATURES as i64)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .into_iter()
+    .map(|row| ManifestSignature {
+        key_type: row.get("key_type"),
+        key_id: row.get("key_id"),
+        fingerprint: row.get("fingerprint"),
+        public_key_pem: row.get("public_key_pem"),
+        algorithm: row.get("algorithm"),
+        signature_sha256: row.get("signature_sha256"),
+        signed_at: row.get("created_at"),
+    })
+    .collect();
+
+    Ok(PromotionManifest {
+        promotion_id: target.promotion_id,
+        source_instance_id: identity.instance_id,
+        source_instance_name: identity.name.clone(),
+        source_repository: target.source_repository.to_string(),
+        source_artifact_id: artifact.id,
+        target_repository: target.target_repository.to_string(),
+        format: target.format.to_string(),
+        path: artifact.path.clone(),
+        name: artifact.name.clone(),
+        version: artifact.version.clone(),
+        size_bytes: artifact.size_bytes,
+        checksum_sha256: artifact.checksum_sha256.clone(),
+        checksum_sha1: artifact.checksum_sha1.clone(),
+        checksum_md5: artifact.checksum_md5.clone(),
+        content_type: artifact.content_type.clone(),
+        metadata,
+        scans,
+        signatures,
+        promoted_by: target.promoted_by.to_string(),
+        notes: target.notes.map(str::to_string),
+    })
+}
+
+/// The receiver's view of an inbound promotion.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InboundPromotionResponse {
+    pub id: Uuid,
+    /// `pending` until the content arrives, then `completed`.
+    pub status: String,
+    pub artifact_id: Option<Uuid>,
+}
+
+/// Announce `manifest` to `peer` and stream the artifact body to it.
+/// Returns the id of the artifact registered on the peer.
+pub async fn push(
+    identity: &LocalIdentity,
+    peer: &TrustedPeer,
+    manifest: &PromotionManifest,
+    content: BoxStream<'static, Result<Bytes>>,
+) -> Result<Uuid> {
+    if peer.status != "trusted" {
+        return Err(AppError::Validation(format!(
+            "Federated peer '{}' is not trusted",
+            peer.name
+        )));
+    }
+    let base = format!(
+        "{}/api/v1/federation/promotions",
+        peer.endpoint_url.trim_end_matches('/')
+    );
+    let client = crate::services::http_client::default_client();
+    let token = |identity: &LocalIdentity| {
+        identity.mint_token(
+            peer.instance_id,
+            FederationScope::Replication,
+            Utc::now().timestamp(),
+        )
+    };
+
+    let response = client
+        .post(&base)
+        .bearer_auth(token(identity))
+        .json(manifest)
+        .send()
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Remote promotion failed: {}", e)))?;
+    let inbound: InboundPromotionResponse = read_peer_response(&peer.name, response).await?;
+    if let (Some(artifact_id), "completed") = (inbound.artifact_id, inbound.status.as_str()) {
+        return Ok(artifact_id);
+    }
+
+    let body =
+        reqwest::Body::wrap_stream(content.map_err(|e| std::io::Error::other(e.to_string())));
+    let response = client
+        .put(format!("{}/{}/content", base, inbound.id))
+        .bearer_auth(token(identity))
+        .header(reqwest::header::CONTENT_LENGTH, manifest.size_bytes)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Remote promotion upload failed: {}", e)))?;
+    let completed: InboundPromotionResponse = read_peer_response(&peer.name, response).await?;
+    completed.artifact_id.ok_or_else(|| {
+        AppError::BadGateway(format!(
+            "Peer '{}' did not register the promoted artifact",
+            peer.name
+        ))
+    })
+}
+
+async fn read_peer_response(
+    peer_name: &str,
+    response: reqwest::Response,
+) -> Result<InboundPromotionResponse> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let detail: String = body.chars().take(500).collect();
+        return Err(AppError::BadGateway(format!(
+            "Peer '{}' rejected the promotion (HTTP {}): {}",
+            peer_name, status, detail
+        )));
+    }
+    response.json().await.map_err(|e| {
+        AppError::BadGateway(format!(
+            "Invalid promotion response from peer '{}': {}",
+            peer_name, e
+        ))
+    })
+}
+
+/// A row of the sender-side remote promotion log.
+pub struct RemotePromotionRecord<'a> {
+    pub id: Uuid,
+    pub artifact_id: Uuid,
+    pub source_repo_id: Uuid,
+    pub peer: &'a TrustedPeer,
+    pub target_repository: &'a str,
+    pub remote_artifact_id: Option<Uuid>,
+    pub error: Option<&'a str>,
+    pub policy_result: &'a serde_json::Value,
+    pub notes: Option<&'a str>,
+    pub promoted_by: Uuid,
+}
+
+pub async fn record_remote_promotion(db: &PgPool, record: RemotePromotionRecord<'_>) -> Result<()> {
+    let status = if record.error.is_none() {
+        "completed"
+    } else {
+        "failed"
+    };
+    sqlx::query(
+        "INSERT INTO remote_promotions ( \
+             id, artifact_id, source_repo_id, federated_peer_id, target_instance_name, \
+             target_repository_key, status, remote_artifact_id, error_message, \
+             policy_result, notes, promoted_by \
+         ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+    )
+    .bind(record.id)
+    .bind(record.artifact_id)
+    .bind(record.source_repo_id)
+    .bind(record.peer.id)
+    .bind(&record.peer.name)
+    .bind(record.target_repository)
+    .bind(status)
+    .bind(record.remote_artifact_id)
+    .bind(record.error)
+    .bind(record.policy_result)
+    .bind(record.notes)
+    .bind(record.promoted_by)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Receiving side
+// ---------------------------------------------------------------------------
+
+/// An announced promotion on the receiving instance.
+#[derive(Debug, Clone)]
+pub struct InboundPromotion {
+    pub id: Uuid,
+    pub repository_id: Uuid,
+    pub federated_peer_id: Option<Uuid>,
+    pub manifest: PromotionManifest,
+    pub status: String,
+    pub artifact_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl InboundPromotion {
+    pub fn response(&self) -> InboundPromotionResponse {
+        InboundPromotionResponse {
+            id: self.id,
+            status: self.status.clone(),
+            artifact_id: self.artifact_id,
+        }
+    }
+}
+
+const INBOUND_COLUMNS: &str =
+    "id, repository_id, federated_peer_id, manifest, status, artifact_id, expires_at";
+
+fn inbound_from_row(row: &sqlx::postgres::PgRow) -> Result<InboundPromotion> {
+    let manifest: serde_json::Value = row.get("manifest");
+    Ok(InboundPromotion {
+        id: row.get("id"),
+        repository_id: row.get("repository_id"),
+        federated_peer_id: row.get("federated_peer_id"),
+        manifest: serde_json::from_value(manifest)
+            .map_err(|e| AppError::Internal(format!("Corrupt promotion manifest: {}", e)))?,
+        status: row.get("status"),
+        artifact_id: row.get("artifact_id"),
+        expires_at: row.get("expires_at"),
+    })
+}
+
+/// Record an announced promotion. Re-announcing a pending promotion (a
+/// sender retry) refreshes it; re-announcing a completed one returns it
+/// unchanged so the sender can pick up the registered artifact id.
+pub async fn announce(
+    db: &PgPool,
+    peer: &TrustedPeer,
+    repository_id: Uuid,
+    manifest: &PromotionManifest,
+) -> Result<InboundPromotion> {
+    let manifest_json =
+        serde_json::to_value(manifest).map_err(|e| AppError::Internal(e.to_string()))?;
+    let row = sqlx::query(&format!(
+        "INSERT INTO inbound_promotions ( \
+             source_instance_id, source_promotion_id, federated_peer_id, repository_id, \
+             manifest, expires_at \
+         ) VALUES ($1, $2, $3, $4, $5, NOW() + make_interval(mins => $6::int)) \
+         ON CONFLICT (source_instance_id, source_promotion_id) DO UPDATE \
+             SET repository_id = EXCLUDED.repository_id, manifest = EXCLUDED.manifest, \
+                 expires_at = EXCLUDED.expires_at, status = 'pending', error_message = NULL \
+             WHERE inbound_promotions.status <> 'completed' \
+         RETURNING {INBOUND_COLUMNS}"
+    ))
+    .bind(manifest.source_instance_id)
+    .bind(manifest.promotion_id)
+    .bind(peer.id)
+    .bind(repository_id)
+    .bind(&manifest_json)
+    .bind(INBOUND_TTL_MINUTES as i32)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    match row {
+        Some(row) => inbound_from_row(&row),
+        None => {
+            let row = sqlx::query(&format!(
+                "SELECT {INBOUND_COLUMNS} FROM inbound_promotions \
+                 WHERE source_instance_id = $1 AND source_promotion_id = $2"
+            ))
+            .bind(manifest.source_instance_id)
+            .bind(manifest.promotion_id)
+            .fetch_one(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            inbound_from_row(&row)
+        }
+    }
+}
+
+/// Load an inbound promotion announced by `peer_id`.
+pub async fn get_inbound(db: &PgPool, id: Uuid, peer_id: Uuid) -> Result<InboundPromotion> {
+    let row = sqlx::query(&format!(
+        "SELECT {INBOUND_COLUMNS} FROM inbound_promotions \
+         WHERE id = $1 AND federated_peer_id = $2"
+    ))
+    .bind(id)
+    .bind(peer_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Inbound promotion not found".to_string()))?;
+    inbound_from_row(&row)
+}
+
+/// Check received content against what the manifest announced.
+pub fn verify_content(
+    manifest: &PromotionManifest,
+    digests: &ContentDigests,
+    size_bytes: i64,
+) -> Result<()> {
+    if !digests
+        .sha256
+        .eq_ignore_ascii_case(&manifest.checksum_sha256)
+    {
+        return Err(AppError::Validation(format!(
+            "Content checksum {} does not match the announced {}",
+            digests.sha256, manifest.checksum_sha256
+        )));
+    }
+    if size_bytes != manifest.size_bytes {
+        return Err(AppError::Validation(format!(
+            "Content size {} does not match the announced {}",
+            size_bytes, manifest.size_bytes
+        )));
+    }
+    Ok(())
+}
+
+pub async fn mark_failed(db: &PgPool, id: Uuid, error: &str) {
+    let _ = sqlx::query(
+        "UPDATE inbound_promotions SET status = 'failed', error_message = $2 \
+         WHERE id = $1 AND status = 'pending'",
+    )
+    .bind(id)
+    .bind(error)
+    .execute(db)
+    .await;
+}
+
+/// Register the promoted artifact with its metadata and scan results, and
+/// complete the inbound record, in one transaction. The content must already
+/// be stored under `storage_key`.
+pub async fn register(
+    db: &PgPool,
+    inbound: &InboundPromotion,
+    storage_key: &str,
+    digests: &ContentDigests,
+    uploaded_by: Uuid,
+) -> Result<Uuid> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let artifact_id = insert_artifact(
+        &mut tx,
+        inbound.repository_id,
+        &inbound.manifest,
+        storage_key,
+        digests,
+        uploaded_by,
+    )
+    .await?;
+
+    sqlx::query(
+        "UPDATE inbound_promotions \
+         SET status = 'completed', artifact_id = $2, completed_at = NOW() \
+         WHERE id = $1",
+    )
+    .bind(inbound.id)
+    .bind(artifact_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(artifact_id)
+}
+
+/// Insert the artifact row described by `manifest`, its metadata (with the
+/// provenance properties) and the carried scan results into `tx`.
+pub(crate) async fn insert_artifact(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    repository_id: Uuid,
+    manifest: &PromotionManifest,
+    storage_key: &str,
+    digests: &ContentDigests,
+    uploaded_by: Uuid,
+) -> Result<Uuid> {
+    let artifact_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO artifacts ( \
+             repository_id, path, name, version, size_bytes, \
+             checksum_sha256, checksum_md5, checksum_sha1, \
+             content_type, storage_key, uploaded_by \
+         ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) \
+         RETURNING id",
+    )
+    .bind(repository_id)
+    .bind(&manifest.path)
+    .bind(&manifest.name)
+    .bind(&manifest.version)
+    .bind(manifest.size_bytes)
+    .bind(&digests.sha256)
+    .bind(&digests.md5)
+    .bind(&digests.sha1)
+    .bind(&manifest.content_type)
+    .bind(storage_key)
+    .bind(uploaded_by)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| {
+        if e.to_string().contains("duplicate key") {
+            AppError::Conflict(format!(
+                "Artifact already exists in target repository: {}",
+                manifest.path
+            ))
+        } else {
+            AppError::Database(e.to_string())
+        }
+    })?;
+
+    if let Some(meta) = &manifest.metadata {
+        let mut properties = match &meta.properties {
+            serde_json::Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        properties.insert("federation".to_string(), manifest.provenance());
+        // The target repository's default properties may already have created
+        // the row (and are merged into this one by the same trigger).
+        sqlx::query(
+            "INSERT INTO artifact_metadata (artifact_id, format, metadata, properties) \
+             VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (artifact_id) DO UPDATE \
+             SET format = EXCLUDED.format, metadata = EXCLUDED.metadata, \
+                 properties = EXCLUDED.properties",
+        )
+        .bind(artifact_id)
+        .bind(&meta.format)
+        .bind(&meta.metadata)
+        .bind(serde_json::Value::Object(properties))
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    }
+
+    // Scans keep their results but are attributed to the sending instance.
+    let source_tool: String = format!("federation:{}", manifest.source_instance_name)
+        .chars()
+        .take(100)
+        .collect();
+    for scan in &manifest.scans {
+        let (critical, high, medium, low, info) = severity_counts(&scan.findings);
+        let scan_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO scan_results ( \
+                 artifact_id, repository_id, scan_type, status, \
+                 findings_count, critical_count, high_count, medium_count, low_count, info_count, \
+                 scanner_version, started_at, completed_at, checksum_sha256, source_tool \
+             ) VALUES ($1, $2, $3, 'completed', $4, $5, $6, $7, $8, $9, $10, \
+                       COALESCE($11, NOW()), COALESCE($11, NOW()), $12, $13) \
+             RETURNING id",
+        )
+        .bind(artifact_id)
+        .bind(repository_id)
+        .bind(&scan.scan_type)
+        .bind(scan.findings.len() as i32)
+        .bind(critical)
+        .bind(high)
+        .bind(medium)
+        .bind(low)
+        .bind(info)
+        .bind(&scan.scanner_version)
+        .bind(scan.completed_at)
+        .bind(&digests.sha256)
+        .bind(&source_tool)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        if scan.findings.is_empty() {
+            continue;
+        }
+        let column = |f: fn(&ManifestFinding) -> Option<&str>| -> Vec<Option<String>> {
+            scan.findings
+                .iter()
+                .map(|finding| f(finding).map(str::to_string))
+                .collect()
+        };
+        sqlx::query(
+            "INSERT INTO scan_findings ( \
+                 scan_result_id, artifact_id, severity, title, description, cve_id, \
+                 affected_component, affected_version, fixed_version, source, source_url \
+             ) \
+             SELECT $1, $2, * FROM UNNEST( \
+                 $3::text[], $4::text[], $5::text[], $6::text[], $7::text[], \
+                 $8::text[], $9::text[], $10::text[], $11::text[] \
+             )",
+        )
+        .bind(scan_id)
+        .bind(artifact_id)
+        .bind(column(|f| Some(f.severity.as_str())))
+        .bind(column(|f| Some(f.title.as_str())))
+        .bind(column(|f| f.description.as_deref()))
+        .bind(column(|f| f.cve_id.as_deref()))
+        .bind(column(|f| f.affected_component.as_deref()))
+        .bind(column(|f| f.affected_version.as_deref()))
+        .bind(column(|f| f.fixed_version.as_deref()))
+        .bind(column(|f| f.source.as_deref()))
+        .bind(column(|f| f.source_url.as_deref()))
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        vulnerability_service::link_scan_findings(&mut **tx, scan_id).await?;
+    }
+
+    Ok(artifact_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> PromotionManifest {
+        serde_json::from_value(serde_json::json!({
+            "promotion_id": Uuid::new_v4(),
+            "source_instance_id": Uuid::new_v4(),
+            "source_instance_name": "dev-site",
+            "source_repository": "libs-staging",
+            "source_artifact_id": Uuid::new_v4(),
+            "target_repository": "libs-release",
+            "format": "generic",
+            "path": "acme/1.0/acme-1.0.tar.gz",
+            "name": "acme-1.0.tar.gz",
+            "version": "1.0",
+            "size_bytes": 42,
+            "checksum_sha256": "a".repeat(64),
+            "checksum_sha1": null,
+            "checksum_md5": null,
+            "content_type": "application/gzip",
+            "metadata": null,
+            "promoted_by": "release-bot",
+            "notes": null
+        }))
+        .unwrap()
+    }
+
+    fn finding(severity: &str) -> ManifestFinding {
+        ManifestFinding {
+            severity: severity.to_string(),
+            title: "CVE-2024-0001".to_string(),
+            description: None,
+            cve_id: Some("CVE-2024-0001".to_string()),
+            affected_component: None,
+            affected_version: None,
+            fixed_version: None,
+            source: None,
+            source_url: None,
+        }
+    }
+
+    #[test]
+    fn test_manifest_defaults_and_validation() {
+        let m = manifest();
+        assert!(m.scans.is_empty());
+        assert!(m.signatures.is_empty());
+        m.validate().unwrap();
+
+        let mut bad = manifest();
+        bad.path = "acme/../../etc/passwd".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = manifest();
+        bad.checksum_sha256 = "not-a-digest".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = manifest();
+        bad.scans.push(ManifestScan {
+            scan_type: "dependency".to_string(),
+            scanner_version: None,
+            source_tool: None,
+            completed_at: None,
+            findings: vec![finding("catastrophic")],
+        });
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_verify_content() {
+        let m = manifest();
+        let digests = ContentDigests {
+            sha256: "A".repeat(64),
+            sha1: String::new(),
+            md5: String::new(),
+            sha512: None,
+            blake3: None,
+        };
+        verify_content(&m, &digests, 42).unwrap();
+        assert!(verify_content(&m, &digests, 41).is_err());
+        let other = ContentDigests {
+            sha256: "b".repeat(64),
+            ..digests
+        };
+        assert!(verify_content(&m, &other, 42).is_err());
+    }
+
+    #[test]
+    fn test_severity_counts() {
+        let findings = vec![
+            finding("critical"),
+            finding("high"),
+            finding("high"),
+            finding("info"),
+        ];
+        assert_eq!(severity_counts(&findings), (1, 2, 0, 0, 1));
+    }
+}
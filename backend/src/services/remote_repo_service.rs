@@ -0,0 +1,157 @@
+//! Operating state of remote (pull-through proxy) repositories.
+//!
+//! A remote repository proxies its `upstream_url`, caching each fetched
+//! artifact (with its upstream ETag / `Last-Modified`) and negative-caching
+//! upstream 404s; see `ProxyService`. This service holds the per-repository
+//! switches layered on top of that, stored in `repository_config`:
+//!
+//! * `offline_mode` — while `true` the proxy never contacts upstream. Cached
+//!   entries are served even past their TTL, and anything not already cached
+//!   is a 404. Useful during upstream outages or in air-gapped deployments
+//!   seeded from a connected instance.
+//!
+//! The flag is read on every proxied fetch, so it is cached in-process for
+//! [`OFFLINE_CACHE_TTL`]; other replicas pick up a change within that window.
+
+use std::time::Duration;
+
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::repository::{Repository, RepositoryType};
+
+/// `repository_config` key holding the offline switch.
+pub const OFFLINE_MODE_KEY: &str = "offline_mode";
+
+/// How long a replica may act on a stale offline flag.
+const OFFLINE_CACHE_TTL: Duration = Duration::from_secs(15);
+
+static OFFLINE_CACHE: Lazy<Cache<Uuid, bool>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(10_000)
+        .time_to_live(OFFLINE_CACHE_TTL)
+        .build()
+});
+
+/// Parse a stored `offline_mode` value. Anything but a recognised true value
+/// reads as online, so a malformed row never takes a proxy offline.
+pub fn parse_offline_flag(value: Option<&str>) -> bool {
+    matches!(
+        value.map(|v| v.trim().to_ascii_lowercase()).as_deref(),
+        Some("true" | "1" | "yes" | "on")
+    )
+}
+
+/// The offline state of a remote repository.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RemoteOfflineState {
+    pub repository_key: String,
+    pub upstream_url: Option<String>,
+    pub offline: bool,
+}
+
+pub struct RemoteRepoService {
+    db: PgPool,
+}
+
+impl RemoteRepoService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Reject operations that only make sense on a remote repository.
+    pub fn require_remote(repo: &Repository) -> Result<()> {
+        if repo.repo_type != RepositoryType::Remote {
+            return Err(AppError::Validation(
+                "offline mode is only available on remote (proxy) repositories".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether repository `repo_id` is offline. A lookup failure reads as
+    /// online: the proxy then behaves exactly as it did before the switch
+    /// existed.
+    pub async fn is_offline(db: &PgPool, repo_id: Uuid) -> bool {
+        if let Some(offline) = OFFLINE_CACHE.get(&repo_id).await {
+            return offline;
+        }
+        let value: Option<String> = match sqlx::query_scalar(
+            "SELECT value FROM repository_config WHERE repository_id = $1 AND key = $2",
+        )
+        .bind(repo_id)
+        .bind(OFFLINE_MODE_KEY)
+        .fetch_optional(db)
+        .await
+        {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!(repository_id = %repo_id, "Failed to read offline_mode: {}", e);
+                return false;
+            }
+        };
+        let offline = parse_offline_flag(value.as_deref());
+        OFFLINE_CACHE.insert(repo_id, offline).await;
+        offline
+    }
+
+    pub async fn offline_state(&self, repo: &Repository) -> Result<RemoteOfflineState> {
+        Self::require_remote(repo)?;
+        OFFLINE_CACHE.invalidate(&repo.id).await;
+        Ok(RemoteOfflineState {
+            repository_key: repo.key.clone(),
+            upstream_url: repo.upstream_url.clone(),
+            offline: Self::is_offline(&self.db, repo.id).await,
+        })
+    }
+
+    /// Take a remote repository offline or bring it back online.
+    pub async fn set_offline(
+        &self,
+        repo: &Repository,
+        offline: bool,
+    ) -> Result<RemoteOfflineState> {
+        Self::require_remote(repo)?;
+        sqlx::query(
+            r#"
+            INSERT INTO repository_config (repository_id, key, value)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (repository_id, key)
+            DO UPDATE SET value = $3, updated_at = NOW()
+            "#,
+        )
+        .bind(repo.id)
+        .bind(OFFLINE_MODE_KEY)
+        .bind(offline.to_string())
+        .execute(&self.db)
+        .await?;
+        OFFLINE_CACHE.insert(repo.id, offline).await;
+        Ok(RemoteOfflineState {
+            repository_key: repo.key.clone(),
+            upstream_url: repo.upstream_url.clone(),
+            offline,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offline_flag() {
+        assert!(parse_offline_flag(Some("true")));
+        assert!(parse_offline_flag(Some(" TRUE ")));
+        assert!(parse_offline_flag(Some("1")));
+        assert!(parse_offline_flag(Some("on")));
+        assert!(!parse_offline_flag(Some("false")));
+        assert!(!parse_offline_flag(Some("garbage")));
+        assert!(!parse_offline_flag(Some("")));
+        assert!(!parse_offline_flag(None));
+    }
+}
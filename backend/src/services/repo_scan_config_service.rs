@@ -0,0 +1,529 @@
+//! Per-repository scan engine configuration.
+//!
+//! By default every registered scanner runs on every artifact of a
+//! scan-enabled repository. A row in `repo_scan_config` narrows that down:
+//!
+//! * `engines` — the engines the dispatcher runs for the repository, e.g.
+//!   Trivy only, Grype + OSV, or none at all for raw binary repositories.
+//!   Scanners of other engines are skipped without leaving a `scan_results`
+//!   row behind.
+//! * `ignore_file` — a `.trivyignore`-style list of vulnerability ids whose
+//!   findings are dropped before they are persisted, whichever engine found
+//!   them. An entry may carry an `exp:YYYY-MM-DD` expiry.
+//! * `scanner_args` — extra arguments per engine, restricted to an allowlist
+//!   of flags that narrow what is scanned. Only CLI-backed scanners honor
+//!   them.
+//!
+//! Results shaped by an ignore file or scanner arguments only describe this
+//! repository, so the dispatcher neither reuses such results elsewhere nor
+//! feeds the repository results computed without them.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::security::RawFinding;
+
+/// Largest ignore file accepted, in bytes.
+pub const MAX_IGNORE_FILE_BYTES: usize = 64 * 1024;
+
+/// Most extra arguments accepted per engine.
+const MAX_ARGS_PER_ENGINE: usize = 32;
+
+/// Longest single extra argument accepted.
+const MAX_ARG_LEN: usize = 256;
+
+/// A vulnerability scan engine that can be switched per repository.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanEngine {
+    /// Trivy: filesystem, container image and Incus image scans.
+    Trivy,
+    /// Grype.
+    Grype,
+    /// Dependency manifests checked against OSV / GitHub advisories.
+    Osv,
+    /// OpenSCAP compliance checks.
+    Openscap,
+}
+
+impl ScanEngine {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScanEngine::Trivy => "trivy",
+            ScanEngine::Grype => "grype",
+            ScanEngine::Osv => "osv",
+            ScanEngine::Openscap => "openscap",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "trivy" => Some(ScanEngine::Trivy),
+            "grype" => Some(ScanEngine::Grype),
+            "osv" => Some(ScanEngine::Osv),
+            "openscap" => Some(ScanEngine::Openscap),
+            _ => None,
+        }
+    }
+
+    /// The engine behind a scanner's `scan_type`. `None` for scanners that
+    /// are not engine-selectable; those always run.
+    pub fn for_scan_type(scan_type: &str) -> Option<Self> {
+        match scan_type {
+            "filesystem" | "image" | "incus" => Some(ScanEngine::Trivy),
+            "grype" => Some(ScanEngine::Grype),
+            "dependency" => Some(ScanEngine::Osv),
+            "openscap" => Some(ScanEngine::Openscap),
+            _ => None,
+        }
+    }
+
+    /// Flags this engine accepts as extra arguments, and whether each takes
+    /// a value (passed as `--flag=value`).
+    fn allowed_flags(self) -> &'static [(&'static str, bool)] {
+        match self {
+            ScanEngine::Trivy => &[
+                ("--skip-dirs", true),
+                ("--skip-files", true),
+                ("--pkg-types", true),
+                ("--ignore-unfixed", false),
+                ("--skip-db-update", false),
+                ("--skip-java-db-update", false),
+                ("--offline-scan", false),
+            ],
+            ScanEngine::Grype => &[
+                ("--exclude", true),
+                ("--platform", true),
+                ("--only-fixed", false),
+                ("--only-notfixed", false),
+            ],
+            ScanEngine::Osv | ScanEngine::Openscap => &[],
+        }
+    }
+}
+
+/// Check the extra arguments for one engine against its allowlist.
+///
+/// Flags that redirect output, load other configuration or change the
+/// report format are never accepted: the dispatcher parses the engine's
+/// JSON report from stdout and the arguments come from a repository
+/// administrator, not an instance administrator.
+pub fn validate_scanner_args(engine: ScanEngine, args: &[String]) -> Result<()> {
+    if args.len() > MAX_ARGS_PER_ENGINE {
+        return Err(AppError::Validation(format!(
+            "at most {} scanner arguments are allowed for {}",
+            MAX_ARGS_PER_ENGINE,
+            engine.as_str()
+        )));
+    }
+    for arg in args {
+        if arg.len() > MAX_ARG_LEN || arg.chars().any(char::is_control) {
+            return Err(AppError::Validation(format!(
+                "invalid {} scanner argument '{}'",
+                engine.as_str(),
+                arg
+            )));
+        }
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value)),
+            None => (arg.as_str(), None),
+        };
+        let accepted = engine.allowed_flags().iter().any(|(allowed, takes_value)| {
+            *allowed == flag && *takes_value == value.is_some_and(|v| !v.is_empty())
+        });
+        if !accepted {
+            let allowed: Vec<String> = engine
+                .allowed_flags()
+                .iter()
+                .map(|(f, takes_value)| {
+                    if *takes_value {
+                        format!("{}=<value>", f)
+                    } else {
+                        f.to_string()
+                    }
+                })
+                .collect();
+            return Err(AppError::Validation(format!(
+                "scanner argument '{}' is not allowed for {} (allowed: {})",
+                arg,
+                engine.as_str(),
+                if allowed.is_empty() {
+                    "none".to_string()
+                } else {
+                    allowed.join(", ")
+                }
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Vulnerability ids suppressed by a repository's ignore file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoreRules {
+    ids: HashSet<String>,
+}
+
+impl IgnoreRules {
+    /// Parse a `.trivyignore`-style file: one vulnerability id per line,
+    /// `#` starts a comment, and an `exp:YYYY-MM-DD` token ends the entry's
+    /// effect after that day. Ids are matched case-insensitively.
+    pub fn parse(text: &str, today: NaiveDate) -> Self {
+        let mut ids = HashSet::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut tokens = line.split_whitespace();
+            let Some(id) = tokens.next() else {
+                continue;
+            };
+            let expired = tokens
+                .filter_map(|t| t.strip_prefix("exp:"))
+                .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .any(|exp| exp < today);
+            if !expired {
+                ids.insert(id.to_ascii_uppercase());
+            }
+        }
+        Self { ids }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Drop the findings whose vulnerability id is ignored; returns how many
+    /// were dropped.
+    pub fn apply(&self, findings: &mut Vec<RawFinding>) -> usize {
+        if self.ids.is_empty() {
+            return 0;
+        }
+        let before = findings.len();
+        findings.retain(|f| {
+            !f.cve_id
+                .as_deref()
+                .is_some_and(|id| self.ids.contains(&id.to_ascii_uppercase()))
+        });
+        before - findings.len()
+    }
+}
+
+/// The scan engine configuration of a repository.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RepoScanConfig {
+    pub repository_id: Uuid,
+    /// Engines run for the repository; empty disables scanning.
+    pub engines: Vec<ScanEngine>,
+    /// `.trivyignore`-style list of ignored vulnerability ids.
+    pub ignore_file: Option<String>,
+    /// Extra arguments per engine.
+    pub scanner_args: BTreeMap<ScanEngine, Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct RepoScanConfigRow {
+    repository_id: Uuid,
+    engines: Vec<String>,
+    ignore_file: Option<String>,
+    scanner_args: serde_json::Value,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<RepoScanConfigRow> for RepoScanConfig {
+    fn from(row: RepoScanConfigRow) -> Self {
+        Self {
+            repository_id: row.repository_id,
+            engines: row
+                .engines
+                .iter()
+                .filter_map(|e| ScanEngine::parse(e))
+                .collect(),
+            ignore_file: row.ignore_file,
+            scanner_args: serde_json::from_value(row.scanner_args).unwrap_or_default(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl RepoScanConfig {
+    /// Whether the dispatcher runs the scanner with `scan_type`.
+    pub fn selects(&self, scan_type: &str) -> bool {
+        ScanEngine::for_scan_type(scan_type).is_none_or(|engine| self.engines.contains(&engine))
+    }
+
+    /// Extra arguments for the scanner with `scan_type`.
+    pub fn args_for(&self, scan_type: &str) -> &[String] {
+        ScanEngine::for_scan_type(scan_type)
+            .and_then(|engine| self.scanner_args.get(&engine))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    pub fn ignore_rules(&self, today: NaiveDate) -> IgnoreRules {
+        self.ignore_file
+            .as_deref()
+            .map(|text| IgnoreRules::parse(text, today))
+            .unwrap_or_default()
+    }
+
+    /// Whether scan results of this repository differ from what a default
+    /// scan of the same bytes would produce.
+    pub fn customizes_results(&self) -> bool {
+        self.ignore_file
+            .as_deref()
+            .is_some_and(|f| !f.trim().is_empty())
+            || !self.scanner_args.is_empty()
+    }
+}
+
+/// Request to set the scan engine configuration of a repository.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SetRepoScanConfigRequest {
+    /// Engines to run; an empty list disables scanning of the repository.
+    pub engines: Vec<ScanEngine>,
+    /// `.trivyignore`-style list of ignored vulnerability ids.
+    #[serde(default)]
+    pub ignore_file: Option<String>,
+    /// Extra arguments per engine, e.g. `{"trivy": ["--skip-dirs=vendor"]}`.
+    #[serde(default)]
+    pub scanner_args: BTreeMap<ScanEngine, Vec<String>>,
+}
+
+impl SetRepoScanConfigRequest {
+    /// Validate the request and normalize it for storage: duplicate engines
+    /// and empty argument lists are dropped, and a blank ignore file is
+    /// stored as none.
+    pub fn normalized(mut self) -> Result<Self> {
+        self.engines.sort();
+        self.engines.dedup();
+        if let Some(text) = &self.ignore_file {
+            if text.len() > MAX_IGNORE_FILE_BYTES {
+                return Err(AppError::Validation(format!(
+                    "ignore_file must not exceed {} bytes",
+                    MAX_IGNORE_FILE_BYTES
+                )));
+            }
+            if text.trim().is_empty() {
+                self.ignore_file = None;
+            }
+        }
+        self.scanner_args.retain(|_, args| !args.is_empty());
+        for (engine, args) in &self.scanner_args {
+            if !self.engines.contains(engine) {
+                return Err(AppError::Validation(format!(
+                    "scanner_args given for {}, which is not among the selected engines",
+                    engine.as_str()
+                )));
+            }
+            validate_scanner_args(*engine, args)?;
+        }
+        Ok(self)
+    }
+}
+
+pub struct RepoScanConfigService {
+    db: PgPool,
+}
+
+impl RepoScanConfigService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// The configuration of a repository; `None` runs every engine.
+    pub async fn get(&self, repository_id: Uuid) -> Result<Option<RepoScanConfig>> {
+        let row = sqlx::query_as::<_, RepoScanConfigRow>(
+            "SELECT * FROM repo_scan_config WHERE repository_id = $1",
+        )
+        .bind(repository_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row.map(RepoScanConfig::from))
+    }
+
+    pub async fn set(
+        &self,
+        repository_id: Uuid,
+        req: SetRepoScanConfigRequest,
+    ) -> Result<RepoScanConfig> {
+        let req = req.normalized()?;
+        let engines: Vec<&str> = req.engines.iter().map(|e| e.as_str()).collect();
+        let scanner_args = serde_json::to_value(&req.scanner_args)
+            .map_err(|e| AppError::Internal(format!("Failed to encode scanner_args: {}", e)))?;
+        let row = sqlx::query_as::<_, RepoScanConfigRow>(
+            r#"
+            INSERT INTO repo_scan_config (repository_id, engines, ignore_file, scanner_args)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (repository_id) DO UPDATE SET
+                engines = EXCLUDED.engines,
+                ignore_file = EXCLUDED.ignore_file,
+                scanner_args = EXCLUDED.scanner_args,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(repository_id)
+        .bind(&engines)
+        .bind(&req.ignore_file)
+        .bind(&scanner_args)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(row.into())
+    }
+
+    /// Remove the configuration, returning the repository to every engine.
+    pub async fn delete(&self, repository_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM repo_scan_config WHERE repository_id = $1")
+            .bind(repository_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::security::Severity;
+
+    fn config(engines: &[ScanEngine]) -> RepoScanConfig {
+        RepoScanConfig {
+            repository_id: Uuid::new_v4(),
+            engines: engines.to_vec(),
+            ignore_file: None,
+            scanner_args: BTreeMap::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn finding(cve: Option<&str>) -> RawFinding {
+        RawFinding {
+            severity: Severity::High,
+            title: "vuln".to_string(),
+            description: None,
+            cve_id: cve.map(str::to_string),
+            affected_component: None,
+            affected_version: None,
+            fixed_version: None,
+            source: None,
+            source_url: None,
+        }
+    }
+
+    #[test]
+    fn test_engine_selection() {
+        let trivy_only = config(&[ScanEngine::Trivy]);
+        assert!(trivy_only.selects("filesystem"));
+        assert!(trivy_only.selects("image"));
+        assert!(!trivy_only.selects("grype"));
+        assert!(!trivy_only.selects("dependency"));
+        // Scanners outside the engine model always run.
+        assert!(trivy_only.selects("custom"));
+
+        let none = config(&[]);
+        assert!(!none.selects("filesystem"));
+        assert!(!none.selects("grype"));
+    }
+
+    #[test]
+    fn test_ignore_rules_parse_and_apply() {
+        let today = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let rules = IgnoreRules::parse(
+            "# accepted risk\n\
+             CVE-2024-0001\n\
+             ghsa-abcd-efgh-ijkl   # vendored, unreachable\n\
+             CVE-2024-0002 exp:2026-12-31\n\
+             CVE-2024-0003 exp:2026-01-01\n\
+             \n",
+            today,
+        );
+        let mut findings = vec![
+            finding(Some("cve-2024-0001")),
+            finding(Some("GHSA-ABCD-EFGH-IJKL")),
+            finding(Some("CVE-2024-0002")),
+            finding(Some("CVE-2024-0003")),
+            finding(None),
+        ];
+        assert_eq!(rules.apply(&mut findings), 3);
+        let left: Vec<Option<&str>> = findings.iter().map(|f| f.cve_id.as_deref()).collect();
+        assert_eq!(left, vec![Some("CVE-2024-0003"), None]);
+
+        assert!(IgnoreRules::parse("# nothing\n\n", today).is_empty());
+    }
+
+    #[test]
+    fn test_validate_scanner_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(validate_scanner_args(
+            ScanEngine::Trivy,
+            &args(&["--skip-dirs=vendor", "--ignore-unfixed"])
+        )
+        .is_ok());
+        assert!(validate_scanner_args(ScanEngine::Grype, &args(&["--only-fixed"])).is_ok());
+
+        // Output redirection, config loading and format changes are refused.
+        for bad in [
+            "--output=/etc/passwd",
+            "--config=/tmp/x.yaml",
+            "--format=table",
+            "-o",
+            "--skip-dirs",
+            "--skip-dirs=",
+            "--ignore-unfixed=true",
+        ] {
+            assert!(
+                validate_scanner_args(ScanEngine::Trivy, &args(&[bad])).is_err(),
+                "{bad} must be rejected"
+            );
+        }
+        assert!(validate_scanner_args(ScanEngine::Osv, &args(&["--only-fixed"])).is_err());
+    }
+
+    #[test]
+    fn test_request_normalization() {
+        let req: SetRepoScanConfigRequest = serde_json::from_str(
+            r#"{
+                "engines": ["grype", "osv", "grype"],
+                "ignore_file": "  ",
+                "scanner_args": {"grype": ["--only-fixed"], "osv": []}
+            }"#,
+        )
+        .unwrap();
+        let req = req.normalized().unwrap();
+        assert_eq!(req.engines, vec![ScanEngine::Grype, ScanEngine::Osv]);
+        assert_eq!(req.ignore_file, None);
+        assert_eq!(req.scanner_args.len(), 1);
+
+        let req: SetRepoScanConfigRequest = serde_json::from_str(
+            r#"{"engines": ["osv"], "scanner_args": {"trivy": ["--ignore-unfixed"]}}"#,
+        )
+        .unwrap();
+        assert!(req.normalized().is_err());
+    }
+
+    #[test]
+    fn test_customizes_results() {
+        let mut c = config(&[ScanEngine::Trivy]);
+        assert!(!c.customizes_results());
+        c.ignore_file = Some("CVE-2024-0001".to_string());
+        assert!(c.customizes_results());
+        c.ignore_file = None;
+        c.scanner_args
+            .insert(ScanEngine::Trivy, vec!["--ignore-unfixed".to_string()]);
+        assert!(c.customizes_results());
+        assert_eq!(c.args_for("filesystem"), ["--ignore-unfixed".to_string()]);
+        assert!(c.args_for("grype").is_empty());
+    }
+}
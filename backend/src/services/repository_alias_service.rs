@@ -0,0 +1,349 @@
+//! Repository key renames and the redirect aliases they leave behind.
+//!
+//! A rename records the old key in `repository_key_aliases`. Until the alias
+//! expires, format-endpoint requests for the old key are redirected to the new
+//! one (see `repo_visibility_middleware`), and OCI names under the old key
+//! resolve to the renamed repository, so client configurations keep working
+//! while they are migrated. An active alias reserves its key: no other
+//! repository can be created or renamed onto it.
+//!
+//! Records that reference the repository by id (permissions, webhooks, peer
+//! subscriptions) follow the rename as they are. The rename rewrites the
+//! key-bearing rows (resumable upload sessions) in the same transaction and
+//! then re-evaluates sync policies, whose name patterns may no longer match.
+//!
+//! Remote repositories cannot be renamed: their proxy cache is stored under
+//! `proxy-cache/<key>/`, so a new key would start cold and strand every
+//! cached object under the old one.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::repository_service::is_duplicate_key_error;
+use crate::services::sync_policy_service::SyncPolicyService;
+
+/// An old repository key redirecting to its renamed repository.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct RepositoryKeyAlias {
+    pub alias: String,
+    pub repository_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Outcome of a repository rename.
+#[derive(Debug, Clone)]
+pub struct RepositoryRename {
+    pub old_key: String,
+    pub new_key: String,
+    /// The alias recorded for the old key, `None` when the rename asked for no
+    /// redirect.
+    pub alias: Option<RepositoryKeyAlias>,
+    /// In-flight upload sessions moved to the new key.
+    pub upload_sessions_updated: u64,
+}
+
+/// The target of an active alias.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AliasTarget {
+    pub repository_id: Uuid,
+    pub key: String,
+    pub is_public: bool,
+}
+
+/// Rewrite a format-endpoint path for an aliased repository key.
+///
+/// `key_index` is the position of the key among the path's `/`-separated
+/// segments, counting the empty segment before the leading slash (2 for
+/// `/{format}/{key}/...`, 4 for conda token channels). Returns `None` when
+/// that segment is not `old_key`. The query string, if any, is carried over.
+pub fn aliased_location(
+    path: &str,
+    query: Option<&str>,
+    key_index: usize,
+    old_key: &str,
+    new_key: &str,
+) -> Option<String> {
+    let mut segments: Vec<&str> = path.split('/').collect();
+    let slot = segments.get_mut(key_index)?;
+    if *slot != old_key {
+        return None;
+    }
+    *slot = new_key;
+    let mut location = segments.join("/");
+    if let Some(query) = query.filter(|q| !q.is_empty()) {
+        location.push('?');
+        location.push_str(query);
+    }
+    Some(location)
+}
+
+pub struct RepositoryAliasService {
+    db: PgPool,
+}
+
+impl RepositoryAliasService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Rename repository `id` to `new_key`. With `alias_ttl_days > 0` the old
+    /// key keeps redirecting for that many days.
+    ///
+    /// Concurrent renames of the same repository are serialized on its row,
+    /// and claims of the same new key on an advisory lock, so two renames can
+    /// neither interleave nor both take one key. Remote repositories are
+    /// refused, since their proxy cache is keyed by the repository key.
+    pub async fn rename(
+        &self,
+        id: Uuid,
+        new_key: &str,
+        alias_ttl_days: u32,
+        actor: Option<Uuid>,
+    ) -> Result<RepositoryRename> {
+        let mut tx = self.db.begin().await?;
+
+        let (old_key, repo_type): (String, String) = sqlx::query_as(
+            "SELECT key, repo_type::text FROM repositories WHERE id = $1 FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Repository not found".to_string()))?;
+        if repo_type == "remote" {
+            return Err(AppError::Validation(format!(
+                "Remote repository '{}' cannot be renamed: its proxy cache is stored under \
+                 the repository key and would be left behind. Create a new remote \
+                 repository with the new key instead",
+                old_key
+            )));
+        }
+        if old_key == new_key {
+            return Err(AppError::Validation(format!(
+                "Repository is already named '{}'",
+                new_key
+            )));
+        }
+
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('repository_key:' || $1))")
+            .bind(new_key)
+            .execute(&mut *tx)
+            .await?;
+
+        // Renaming back onto one of the repository's own aliases retires
+        // that alias; an expired alias of any repository no longer reserves
+        // the key.
+        sqlx::query(
+            r#"
+            DELETE FROM repository_key_aliases
+            WHERE alias = $1 AND (repository_id = $2 OR expires_at <= NOW())
+            "#,
+        )
+        .bind(new_key)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE repositories SET key = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(new_key)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                if is_duplicate_key_error(&e.to_string()) {
+                    AppError::Conflict(format!(
+                        "Repository key '{}' is already in use or reserved by a rename alias",
+                        new_key
+                    ))
+                } else {
+                    AppError::Database(e.to_string())
+                }
+            })?;
+
+        let alias = if alias_ttl_days > 0 {
+            let expires_at = Utc::now() + Duration::days(alias_ttl_days as i64);
+            let alias = sqlx::query_as::<_, RepositoryKeyAlias>(
+                r#"
+                INSERT INTO repository_key_aliases (alias, repository_id, created_by, expires_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (alias) DO UPDATE
+                SET repository_id = EXCLUDED.repository_id,
+                    created_by = EXCLUDED.created_by,
+                    created_at = NOW(),
+                    expires_at = EXCLUDED.expires_at
+                RETURNING *
+                "#,
+            )
+            .bind(&old_key)
+            .bind(id)
+            .bind(actor)
+            .bind(expires_at)
+            .fetch_one(&mut *tx)
+            .await?;
+            Some(alias)
+        } else {
+            None
+        };
+
+        let upload_sessions_updated = sqlx::query(
+            r#"
+            UPDATE upload_sessions SET repository_key = $2, updated_at = NOW()
+            WHERE repository_id = $1 AND status IN ('pending', 'in_progress')
+            "#,
+        )
+        .bind(id)
+        .bind(new_key)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        if let Err(e) = SyncPolicyService::new(self.db.clone())
+            .evaluate_for_repository(id)
+            .await
+        {
+            tracing::warn!(
+                repository_id = %id,
+                "Failed to re-evaluate sync policies after rename: {}",
+                e
+            );
+        }
+
+        Ok(RepositoryRename {
+            old_key,
+            new_key: new_key.to_string(),
+            alias,
+            upload_sessions_updated,
+        })
+    }
+
+    /// Active and expired aliases of repository `id`, newest first.
+    pub async fn list_aliases(&self, id: Uuid) -> Result<Vec<RepositoryKeyAlias>> {
+        let aliases = sqlx::query_as::<_, RepositoryKeyAlias>(
+            "SELECT * FROM repository_key_aliases WHERE repository_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(aliases)
+    }
+
+    /// Drop an alias before it expires, releasing its key.
+    pub async fn delete_alias(&self, id: Uuid, alias: &str) -> Result<()> {
+        let result = sqlx::query(
+            "DELETE FROM repository_key_aliases WHERE repository_id = $1 AND alias = $2",
+        )
+        .bind(id)
+        .bind(alias)
+        .execute(&self.db)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Alias '{}' not found", alias)));
+        }
+        Ok(())
+    }
+
+    /// The repository an active alias points at, if `key` is one.
+    pub async fn resolve(&self, key: &str) -> Result<Option<AliasTarget>> {
+        let target = sqlx::query_as::<_, AliasTarget>(
+            r#"
+            SELECT r.id AS repository_id, r.key, r.is_public
+            FROM repository_key_aliases a
+            JOIN repositories r ON r.id = a.repository_id
+            WHERE a.alias = $1 AND a.expires_at > NOW()
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliased_location_replaces_key_segment() {
+        assert_eq!(
+            aliased_location("/pypi/old/simple/requests/", None, 2, "old", "new").as_deref(),
+            Some("/pypi/new/simple/requests/")
+        );
+        assert_eq!(
+            aliased_location("/npm/old/@scope%2fpkg", Some("write=true"), 2, "old", "new")
+                .as_deref(),
+            Some("/npm/new/@scope%2fpkg?write=true")
+        );
+    }
+
+    #[test]
+    fn test_aliased_location_conda_token_channel() {
+        assert_eq!(
+            aliased_location(
+                "/conda/t/tok/old/noarch/repodata.json",
+                None,
+                4,
+                "old",
+                "new"
+            )
+            .as_deref(),
+            Some("/conda/t/tok/new/noarch/repodata.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_refuses_remote_repository() {
+        use crate::api::handlers::test_db_helpers as tdh;
+
+        let Some(pool) = tdh::try_pool().await else {
+            return;
+        };
+        let (repo_id, key, storage_dir) = tdh::create_repo(&pool, "remote", "npm").await;
+        let new_key = format!("{}-renamed", key);
+
+        let result = RepositoryAliasService::new(pool.clone())
+            .rename(repo_id, &new_key, 30, None)
+            .await;
+        let current: String = sqlx::query_scalar("SELECT key FROM repositories WHERE id = $1")
+            .bind(repo_id)
+            .fetch_one(&pool)
+            .await
+            .expect("load key");
+        let aliases: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM repository_key_aliases WHERE repository_id = $1",
+        )
+        .bind(repo_id)
+        .fetch_one(&pool)
+        .await
+        .expect("count aliases");
+
+        let _ = sqlx::query("DELETE FROM repositories WHERE id = $1")
+            .bind(repo_id)
+            .execute(&pool)
+            .await;
+        let _ = std::fs::remove_dir_all(storage_dir);
+
+        match result {
+            Err(AppError::Validation(msg)) => assert!(msg.contains("proxy cache"), "{msg}"),
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+        assert_eq!(current, key, "the key must be unchanged");
+        assert_eq!(aliases, 0, "no alias may be recorded");
+    }
+
+    #[test]
+    fn test_aliased_location_requires_old_key_at_index() {
+        assert_eq!(aliased_location("/pypi", None, 2, "old", "new"), None);
+        assert_eq!(
+            aliased_location("/pypi/other/simple", None, 2, "old", "new"),
+            None
+        );
+    }
+}
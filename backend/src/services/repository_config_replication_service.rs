@@ -0,0 +1,966 @@
+//! Replication of repository configuration to peers.
+//!
+//! Artifact replication copies bytes; without this the peer enforces its own,
+//! independently edited rules on them. A (peer, repository) subscription can
+//! be enrolled in config replication, after which the repository's
+//!
+//! - labels,
+//! - repository-scoped lifecycle policies, and
+//! - repository-scoped scan policies
+//!
+//! are pushed to the peer as one [`RepositoryConfigBundle`] whenever any of
+//! them changes. Database triggers stamp the enrolment row on every write
+//! (see migration 216) and the sync worker pushes stamped rows on its next
+//! tick via [`RepositoryConfigReplicationService::push_pending`]. Global and
+//! label-selected lifecycle policies are instance-wide and not replicated.
+//!
+//! Policies are matched by name on the receiving side: a policy of the same
+//! name is updated in place, missing ones are created, and policies absent
+//! from the bundle are removed. Applying a bundle that matches the local
+//! configuration writes nothing, so two peers replicating to each other
+//! settle instead of ping-ponging.
+//!
+//! The peer endpoint (`/api/v1/repositories/:key/replicated-config`) is
+//! admin-only, so config replication needs a peer API key of an admin
+//! account; federation tokens are scoped to artifact replication.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::federation_service::{peer_credential, FederationScope};
+use crate::services::lifecycle_service::{LifecycleService, PolicyType};
+use crate::services::peer_instance_service::PeerInstanceService;
+use crate::services::policy_service::normalize_max_severity;
+use crate::services::scheduler_service::normalize_cron_expression;
+
+/// Marks pushes so the receiver can tell them apart from admin edits.
+const REPLICATION_REQUEST_HEADER: &str = "X-Artifact-Keeper-Replication";
+
+/// How long a push the peer rejected waits before it is retried, unless the
+/// configuration changes again in the meantime.
+const FAILED_PUSH_RETRY_SECS: i64 = 300;
+
+const MAX_LABEL_KEY_LEN: usize = 128;
+const MAX_LABEL_VALUE_LEN: usize = 256;
+
+/// A repository's replicated configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RepositoryConfigBundle {
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub lifecycle_policies: Vec<ReplicatedLifecyclePolicy>,
+    #[serde(default)]
+    pub scan_policies: Vec<ReplicatedScanPolicy>,
+}
+
+/// The portable part of a repository-scoped lifecycle policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct ReplicatedLifecyclePolicy {
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub policy_type: String,
+    #[schema(value_type = Object)]
+    pub config: serde_json::Value,
+    pub priority: i32,
+    pub cron_schedule: Option<String>,
+}
+
+/// The portable part of a repository-scoped scan policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct ReplicatedScanPolicy {
+    pub name: String,
+    pub max_severity: String,
+    pub block_unscanned: bool,
+    pub block_on_fail: bool,
+    pub is_enabled: bool,
+    pub min_staging_hours: Option<i32>,
+    pub max_artifact_age_days: Option<i32>,
+    pub require_signature: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSection {
+    Labels,
+    LifecyclePolicies,
+    ScanPolicies,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    /// Present on the source, absent on the target.
+    Missing,
+    /// Present only on the target.
+    Extra,
+    /// Present on both with different settings.
+    Changed,
+}
+
+/// One difference between two bundles, keyed by label key or policy name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ConfigDrift {
+    pub section: ConfigSection,
+    pub name: String,
+    pub kind: DriftKind,
+    #[schema(value_type = Option<Object>)]
+    pub source: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
+    pub target: Option<serde_json::Value>,
+}
+
+/// Config replication state of one (peer, repository) subscription.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ConfigReplicationStatus {
+    pub peer_instance_id: Uuid,
+    pub repository_id: Uuid,
+    pub changed_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_pushed_at: Option<DateTime<Utc>>,
+    pub last_pushed_digest: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Local configuration compared with what a peer currently enforces.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfigDriftReport {
+    pub peer_instance_id: Uuid,
+    pub peer_name: String,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub in_sync: bool,
+    pub local_digest: String,
+    pub peer_digest: String,
+    /// Differences with this instance as source and the peer as target.
+    pub drift: Vec<ConfigDrift>,
+    /// Whether the subscription is enrolled in config replication.
+    pub replicated: bool,
+    pub last_pushed_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl RepositoryConfigBundle {
+    /// Canonical ordering, so equal configurations serialize identically.
+    pub fn normalize(&mut self) {
+        self.lifecycle_policies.sort_by(|a, b| a.name.cmp(&b.name));
+        self.scan_policies.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// sha256 over the canonical JSON form.
+    pub fn digest(&self) -> String {
+        let mut canonical = self.clone();
+        canonical.normalize();
+        let bytes = serde_json::to_vec(&canonical).unwrap_or_default();
+        hex::encode(Sha256::digest(&bytes))
+    }
+}
+
+/// Check a received bundle and canonicalize values the local services would
+/// canonicalize on create. Lifecycle policy configs are checked separately
+/// by [`LifecycleService::validate_policy_config`].
+pub fn validate_bundle(bundle: &mut RepositoryConfigBundle) -> Result<()> {
+    for (key, value) in &bundle.labels {
+        if key.trim().is_empty() || key.chars().count() > MAX_LABEL_KEY_LEN {
+            return Err(AppError::Validation(format!(
+                "label key '{key}' must be 1-{MAX_LABEL_KEY_LEN} characters"
+            )));
+        }
+        if value.chars().count() > MAX_LABEL_VALUE_LEN {
+            return Err(AppError::Validation(format!(
+                "value of label '{key}' exceeds {MAX_LABEL_VALUE_LEN} characters"
+            )));
+        }
+    }
+
+    check_unique_names(
+        "lifecycle policy",
+        bundle.lifecycle_policies.iter().map(|p| p.name.as_str()),
+    )?;
+    for policy in &bundle.lifecycle_policies {
+        PolicyType::parse(&policy.policy_type).map_err(|_| {
+            AppError::Validation(format!(
+                "lifecycle policy '{}': unsupported policy_type '{}'",
+                policy.name, policy.policy_type
+            ))
+        })?;
+        if let Some(ref expr) = policy.cron_schedule {
+            if cron::Schedule::from_str(&normalize_cron_expression(expr)).is_err() {
+                return Err(AppError::Validation(format!(
+                    "lifecycle policy '{}': invalid cron expression '{expr}'",
+                    policy.name
+                )));
+            }
+        }
+    }
+
+    check_unique_names(
+        "scan policy",
+        bundle.scan_policies.iter().map(|p| p.name.as_str()),
+    )?;
+    for policy in &mut bundle.scan_policies {
+        policy.max_severity = normalize_max_severity(&policy.max_severity)?;
+    }
+
+    bundle.normalize();
+    Ok(())
+}
+
+fn check_unique_names<'a>(what: &str, names: impl Iterator<Item = &'a str>) -> Result<()> {
+    let mut seen = BTreeSet::new();
+    for name in names {
+        if name.trim().is_empty() {
+            return Err(AppError::Validation(format!("{what} name is required")));
+        }
+        if !seen.insert(name) {
+            return Err(AppError::Validation(format!(
+                "duplicate {what} name '{name}'"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Differences between `source` and `target`, ordered by section then name.
+pub fn diff_bundles(
+    source: &RepositoryConfigBundle,
+    target: &RepositoryConfigBundle,
+) -> Vec<ConfigDrift> {
+    let mut drift = Vec::new();
+    diff_named(
+        ConfigSection::Labels,
+        source.labels.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+        target.labels.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+        &mut drift,
+    );
+    diff_named(
+        ConfigSection::LifecyclePolicies,
+        source
+            .lifecycle_policies
+            .iter()
+            .map(|p| (p.name.as_str(), p))
+            .collect(),
+        target
+            .lifecycle_policies
+            .iter()
+            .map(|p| (p.name.as_str(), p))
+            .collect(),
+        &mut drift,
+    );
+    diff_named(
+        ConfigSection::ScanPolicies,
+        source
+            .scan_policies
+            .iter()
+            .map(|p| (p.name.as_str(), p))
+            .collect(),
+        target
+            .scan_policies
+            .iter()
+            .map(|p| (p.name.as_str(), p))
+            .collect(),
+        &mut drift,
+    );
+    drift
+}
+
+fn diff_named<T: Serialize + PartialEq>(
+    section: ConfigSection,
+    source: BTreeMap<&str, &T>,
+    target: BTreeMap<&str, &T>,
+    out: &mut Vec<ConfigDrift>,
+) {
+    let to_json = |v: &T| serde_json::to_value(v).ok();
+    let names: BTreeSet<&str> = source.keys().chain(target.keys()).copied().collect();
+    for name in names {
+        let kind = match (source.get(name), target.get(name)) {
+            (Some(_), None) => DriftKind::Missing,
+            (None, Some(_)) => DriftKind::Extra,
+            (Some(s), Some(t)) if s != t => DriftKind::Changed,
+            _ => continue,
+        };
+        out.push(ConfigDrift {
+            section,
+            name: name.to_string(),
+            kind,
+            source: source.get(name).and_then(|v| to_json(*v)),
+            target: target.get(name).and_then(|v| to_json(*v)),
+        });
+    }
+}
+
+/// URL of a peer's replicated-config endpoint for `repository_key`.
+pub(crate) fn peer_config_url(endpoint_url: &str, repository_key: &str) -> String {
+    format!(
+        "{}/api/v1/repositories/{}/replicated-config",
+        endpoint_url.trim_end_matches('/'),
+        repository_key
+    )
+}
+
+async fn peer_error(action: &str, response: reqwest::Response) -> String {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "<unreadable>".to_string());
+    format!("{action} {status}: {body}")
+}
+
+/// Fetch the configuration a peer enforces for `repository_key`.
+pub async fn fetch_peer_bundle(
+    client: &reqwest::Client,
+    endpoint_url: &str,
+    api_key: &str,
+    repository_key: &str,
+) -> std::result::Result<RepositoryConfigBundle, String> {
+    let url = peer_config_url(endpoint_url, repository_key);
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {api_key}"))
+        .send()
+        .await
+        .map_err(|e| format!("request to {url} failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(peer_error("Peer returned", response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("invalid config from {url}: {e}"))
+}
+
+async fn push_peer_bundle(
+    client: &reqwest::Client,
+    endpoint_url: &str,
+    api_key: &str,
+    repository_key: &str,
+    bundle: &RepositoryConfigBundle,
+) -> std::result::Result<(), String> {
+    let url = peer_config_url(endpoint_url, repository_key);
+    let response = client
+        .put(&url)
+        .header("Authorization", format!("Bearer {api_key}"))
+        .header(REPLICATION_REQUEST_HEADER, "true")
+        .json(bundle)
+        .send()
+        .await
+        .map_err(|e| format!("request to {url} failed: {e}"))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(peer_error("Peer rejected config:", response).await)
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PendingPush {
+    peer_instance_id: Uuid,
+    repository_id: Uuid,
+    repository_key: String,
+    peer_name: String,
+    endpoint_url: String,
+    api_key: String,
+    last_pushed_digest: Option<String>,
+    checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PeerEndpoint {
+    name: String,
+    endpoint_url: String,
+    api_key: String,
+}
+
+fn db_err(e: sqlx::Error) -> AppError {
+    AppError::Database(e.to_string())
+}
+
+pub struct RepositoryConfigReplicationService {
+    db: PgPool,
+}
+
+impl RepositoryConfigReplicationService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// The repository's current replicated configuration.
+    pub async fn load_bundle(&self, repository_id: Uuid) -> Result<RepositoryConfigBundle> {
+        let labels: Vec<(String, String)> = sqlx::query_as(
+            "SELECT label_key, label_value FROM repository_labels WHERE repository_id = $1",
+        )
+        .bind(repository_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(db_err)?;
+
+        let lifecycle_policies: Vec<ReplicatedLifecyclePolicy> = sqlx::query_as(
+            r#"
+            SELECT name, description, enabled, policy_type, config, priority, cron_schedule
+            FROM lifecycle_policies
+            WHERE repository_id = $1
+            ORDER BY name, created_at
+            "#,
+        )
+        .bind(repository_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(db_err)?;
+
+        let scan_policies: Vec<ReplicatedScanPolicy> = sqlx::query_as(
+            r#"
+            SELECT name, max_severity, block_unscanned, block_on_fail, is_enabled,
+                   min_staging_hours, max_artifact_age_days, require_signature
+            FROM scan_policies
+            WHERE repository_id = $1
+            ORDER BY name, created_at
+            "#,
+        )
+        .bind(repository_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(db_err)?;
+
+        let mut bundle = RepositoryConfigBundle {
+            labels: labels.into_iter().collect(),
+            lifecycle_policies,
+            scan_policies,
+        };
+        bundle.normalize();
+        Ok(bundle)
+    }
+
+    /// Make the repository's configuration match `bundle`. Returns what
+    /// changed, with the bundle as source; nothing is written when the
+    /// configuration already matches.
+    pub async fn apply_bundle(
+        &self,
+        repository_id: Uuid,
+        mut bundle: RepositoryConfigBundle,
+    ) -> Result<Vec<ConfigDrift>> {
+        validate_bundle(&mut bundle)?;
+        let lifecycle = LifecycleService::new(self.db.clone());
+        for policy in &bundle.lifecycle_policies {
+            lifecycle
+                .validate_policy_config(&policy.policy_type, &policy.config)
+                .map_err(|e| {
+                    AppError::Validation(format!("lifecycle policy '{}': {e}", policy.name))
+                })?;
+        }
+
+        let current = self.load_bundle(repository_id).await?;
+        let drift = diff_bundles(&bundle, &current);
+        if drift.is_empty() {
+            return Ok(drift);
+        }
+        let touches = |section| drift.iter().any(|d| d.section == section);
+
+        let mut tx = self.db.begin().await?;
+
+        if touches(ConfigSection::Labels) {
+            sqlx::query("DELETE FROM repository_labels WHERE repository_id = $1")
+                .bind(repository_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_err)?;
+            for (key, value) in &bundle.labels {
+                sqlx::query(
+                    "INSERT INTO repository_labels (repository_id, label_key, label_value) \
+                     VALUES ($1, $2, $3)",
+                )
+                .bind(repository_id)
+                .bind(key)
+                .bind(value)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_err)?;
+            }
+        }
+
+        if touches(ConfigSection::LifecyclePolicies) {
+            let names: Vec<&str> = bundle
+                .lifecycle_policies
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect();
+            sqlx::query(
+                "DELETE FROM lifecycle_policies WHERE repository_id = $1 AND name <> ALL($2)",
+            )
+            .bind(repository_id)
+            .bind(&names)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_err)?;
+            for policy in &bundle.lifecycle_policies {
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE lifecycle_policies
+                    SET description = $3, enabled = $4, policy_type = $5, config = $6,
+                        priority = $7, cron_schedule = $8, updated_at = NOW()
+                    WHERE repository_id = $1 AND name = $2
+                    "#,
+                )
+                .bind(repository_id)
+                .bind(&policy.name)
+                .bind(&policy.description)
+                .bind(policy.enabled)
+                .bind(&policy.policy_type)
+                .bind(&policy.config)
+                .bind(policy.priority)
+                .bind(&policy.cron_schedule)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_err)?;
+                if updated.rows_affected() == 0 {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO lifecycle_policies
+                            (repository_id, name, description, enabled, policy_type, config,
+                             priority, cron_schedule)
+                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                        "#,
+                    )
+                    .bind(repository_id)
+                    .bind(&policy.name)
+                    .bind(&policy.description)
+                    .bind(policy.enabled)
+                    .bind(&policy.policy_type)
+                    .bind(&policy.config)
+                    .bind(policy.priority)
+                    .bind(&policy.cron_schedule)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(db_err)?;
+                }
+            }
+        }
+
+        if touches(ConfigSection::ScanPolicies) {
+            let names: Vec<&str> = bundle
+                .scan_policies
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect();
+            sqlx::query("DELETE FROM scan_policies WHERE repository_id = $1 AND name <> ALL($2)")
+                .bind(repository_id)
+                .bind(&names)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_err)?;
+            for policy in &bundle.scan_policies {
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE scan_policies
+                    SET max_severity = $3, block_unscanned = $4, block_on_fail = $5,
+                        is_enabled = $6, min_staging_hours = $7, max_artifact_age_days = $8,
+                        require_signature = $9, updated_at = NOW()
+                    WHERE repository_id = $1 AND name = $2
+                    "#,
+                )
+                .bind(repository_id)
+                .bind(&policy.name)
+                .bind(&policy.max_severity)
+                .bind(policy.block_unscanned)
+                .bind(policy.block_on_fail)
+                .bind(policy.is_enabled)
+                .bind(policy.min_staging_hours)
+                .bind(policy.max_artifact_age_days)
+                .bind(policy.require_signature)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_err)?;
+                if updated.rows_affected() == 0 {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO scan_policies
+                            (repository_id, name, max_severity, block_unscanned, block_on_fail,
+                             is_enabled, min_staging_hours, max_artifact_age_days,
+                             require_signature)
+                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                        "#,
+                    )
+                    .bind(repository_id)
+                    .bind(&policy.name)
+                    .bind(&policy.max_severity)
+                    .bind(policy.block_unscanned)
+                    .bind(policy.block_on_fail)
+                    .bind(policy.is_enabled)
+                    .bind(policy.min_staging_hours)
+                    .bind(policy.max_artifact_age_days)
+                    .bind(policy.require_signature)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(db_err)?;
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(drift)
+    }
+
+    /// Config replication state of a subscription.
+    pub async fn status(
+        &self,
+        peer_instance_id: Uuid,
+        repository_id: Uuid,
+    ) -> Result<ConfigReplicationStatus> {
+        self.find_status(peer_instance_id, repository_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound("Config replication is not enabled for this subscription".into())
+            })
+    }
+
+    async fn find_status(
+        &self,
+        peer_instance_id: Uuid,
+        repository_id: Uuid,
+    ) -> Result<Option<ConfigReplicationStatus>> {
+        sqlx::query_as(
+            r#"
+            SELECT peer_instance_id, repository_id, changed_at, last_attempt_at,
+                   last_pushed_at, last_pushed_digest, last_error, created_at
+            FROM repository_config_replication
+            WHERE peer_instance_id = $1 AND repository_id = $2
+            "#,
+        )
+        .bind(peer_instance_id)
+        .bind(repository_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(db_err)
+    }
+
+    /// Enrol an existing subscription in config replication. Enabling an
+    /// enrolled subscription again forces a fresh push.
+    pub async fn enable(
+        &self,
+        peer_instance_id: Uuid,
+        repository_id: Uuid,
+    ) -> Result<ConfigReplicationStatus> {
+        PeerInstanceService::new(self.db.clone())
+            .get_subscription(peer_instance_id, repository_id)
+            .await?;
+        sqlx::query_as(
+            r#"
+            INSERT INTO repository_config_replication (peer_instance_id, repository_id)
+            VALUES ($1, $2)
+            ON CONFLICT (peer_instance_id, repository_id) DO UPDATE
+                SET changed_at = NOW(), last_pushed_digest = NULL, last_error = NULL
+            RETURNING peer_instance_id, repository_id, changed_at, last_attempt_at,
+                      last_pushed_at, last_pushed_digest, last_error, created_at
+            "#,
+        )
+        .bind(peer_instance_id)
+        .bind(repository_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(db_err)
+    }
+
+    /// Stop replicating a subscription's configuration. The peer keeps what
+    /// it last received.
+    pub async fn disable(&self, peer_instance_id: Uuid, repository_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            "DELETE FROM repository_config_replication \
+             WHERE peer_instance_id = $1 AND repository_id = $2",
+        )
+        .bind(peer_instance_id)
+        .bind(repository_id)
+        .execute(&self.db)
+        .await
+        .map_err(db_err)?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Config replication is not enabled for this subscription".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Compare the local configuration with what the peer enforces.
+    pub async fn drift_report(
+        &self,
+        client: &reqwest::Client,
+        peer_instance_id: Uuid,
+        repository_id: Uuid,
+    ) -> Result<ConfigDriftReport> {
+        let peer: PeerEndpoint =
+            sqlx::query_as("SELECT name, endpoint_url, api_key FROM peer_instances WHERE id = $1")
+                .bind(peer_instance_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(db_err)?
+                .ok_or_else(|| AppError::NotFound("Peer instance not found".into()))?;
+        let repository_key: String =
+            sqlx::query_scalar("SELECT key FROM repositories WHERE id = $1")
+                .bind(repository_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(db_err)?
+                .ok_or_else(|| AppError::NotFound("Repository not found".into()))?;
+
+        let local = self.load_bundle(repository_id).await?;
+        let api_key = peer_credential(
+            &self.db,
+            &peer.endpoint_url,
+            &peer.api_key,
+            FederationScope::Replication,
+        )
+        .await;
+        let remote = fetch_peer_bundle(client, &peer.endpoint_url, &api_key, &repository_key)
+            .await
+            .map_err(|e| AppError::BadGateway(format!("Peer '{}': {e}", peer.name)))?;
+        let status = self.find_status(peer_instance_id, repository_id).await?;
+
+        let drift = diff_bundles(&local, &remote);
+        Ok(ConfigDriftReport {
+            peer_instance_id,
+            peer_name: peer.name,
+            repository_id,
+            repository_key,
+            in_sync: drift.is_empty(),
+            local_digest: local.digest(),
+            peer_digest: remote.digest(),
+            drift,
+            replicated: status.is_some(),
+            last_pushed_at: status.as_ref().and_then(|s| s.last_pushed_at),
+            last_error: status.and_then(|s| s.last_error),
+            checked_at: Utc::now(),
+        })
+    }
+
+    /// Push every enrolled configuration that changed since its last push to
+    /// reachable peers. Returns the number of bundles sent.
+    pub async fn push_pending(&self, client: &reqwest::Client) -> Result<usize> {
+        let pending: Vec<PendingPush> = sqlx::query_as(
+            r#"
+            SELECT r.peer_instance_id, r.repository_id, repo.key AS repository_key,
+                   p.name AS peer_name, p.endpoint_url, p.api_key,
+                   r.last_pushed_digest, NOW() AS checked_at
+            FROM repository_config_replication r
+            JOIN peer_instances p ON p.id = r.peer_instance_id
+            JOIN repositories repo ON repo.id = r.repository_id
+            WHERE (r.last_pushed_at IS NULL OR r.changed_at > r.last_pushed_at)
+              AND (r.last_error IS NULL
+                   OR r.changed_at > r.last_attempt_at
+                   OR r.last_attempt_at < NOW() - make_interval(secs => $1))
+              AND p.is_local = false
+              AND p.status IN ('online', 'syncing')
+              AND (p.backoff_until IS NULL OR p.backoff_until <= NOW())
+            "#,
+        )
+        .bind(FAILED_PUSH_RETRY_SECS as f64)
+        .fetch_all(&self.db)
+        .await
+        .map_err(db_err)?;
+
+        let mut pushed = 0;
+        for row in pending {
+            let bundle = self.load_bundle(row.repository_id).await?;
+            let digest = bundle.digest();
+            let outcome = if row.last_pushed_digest.as_deref() == Some(digest.as_str()) {
+                Ok(())
+            } else {
+                let api_key = peer_credential(
+                    &self.db,
+                    &row.endpoint_url,
+                    &row.api_key,
+                    FederationScope::Replication,
+                )
+                .await;
+                let result = push_peer_bundle(
+                    client,
+                    &row.endpoint_url,
+                    &api_key,
+                    &row.repository_key,
+                    &bundle,
+                )
+                .await;
+                if result.is_ok() {
+                    pushed += 1;
+                    tracing::info!(
+                        "Replicated configuration of '{}' to peer '{}'",
+                        row.repository_key,
+                        row.peer_name
+                    );
+                }
+                result
+            };
+
+            // `checked_at` predates the bundle read, so a change that lands
+            // mid-push keeps the row pending.
+            let update = match outcome {
+                Ok(()) => sqlx::query(
+                    r#"
+                    UPDATE repository_config_replication
+                    SET last_attempt_at = NOW(), last_pushed_at = $3,
+                        last_pushed_digest = $4, last_error = NULL
+                    WHERE peer_instance_id = $1 AND repository_id = $2
+                    "#,
+                )
+                .bind(row.peer_instance_id)
+                .bind(row.repository_id)
+                .bind(row.checked_at)
+                .bind(&digest),
+                Err(e) => {
+                    tracing::warn!(
+                        "Config replication of '{}' to peer '{}' failed: {e}",
+                        row.repository_key,
+                        row.peer_name
+                    );
+                    sqlx::query(
+                        r#"
+                        UPDATE repository_config_replication
+                        SET last_attempt_at = NOW(), last_error = $3
+                        WHERE peer_instance_id = $1 AND repository_id = $2
+                        "#,
+                    )
+                    .bind(row.peer_instance_id)
+                    .bind(row.repository_id)
+                    .bind(e)
+                }
+            };
+            update.execute(&self.db).await.map_err(db_err)?;
+        }
+        Ok(pushed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lifecycle(name: &str, days: i64) -> ReplicatedLifecyclePolicy {
+        ReplicatedLifecyclePolicy {
+            name: name.to_string(),
+            description: None,
+            enabled: true,
+            policy_type: "max_age_days".to_string(),
+            config: json!({ "days": days }),
+            priority: 0,
+            cron_schedule: None,
+        }
+    }
+
+    fn scan(name: &str, max_severity: &str) -> ReplicatedScanPolicy {
+        ReplicatedScanPolicy {
+            name: name.to_string(),
+            max_severity: max_severity.to_string(),
+            block_unscanned: true,
+            block_on_fail: false,
+            is_enabled: true,
+            min_staging_hours: None,
+            max_artifact_age_days: None,
+            require_signature: false,
+        }
+    }
+
+    fn bundle() -> RepositoryConfigBundle {
+        RepositoryConfigBundle {
+            labels: [("env".to_string(), "prod".to_string())].into(),
+            lifecycle_policies: vec![lifecycle("expire", 90)],
+            scan_policies: vec![scan("gate", "high")],
+        }
+    }
+
+    #[test]
+    fn test_diff_identical_bundles_is_empty() {
+        assert!(diff_bundles(&bundle(), &bundle()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_missing_extra_and_changed() {
+        let source = bundle();
+        let mut target = bundle();
+        target.labels.clear();
+        target
+            .labels
+            .insert("team".to_string(), "payments".to_string());
+        target.lifecycle_policies[0].config = json!({ "days": 30 });
+        target.scan_policies.push(scan("strict", "low"));
+
+        let drift = diff_bundles(&source, &target);
+        let summary: Vec<(ConfigSection, &str, DriftKind)> = drift
+            .iter()
+            .map(|d| (d.section, d.name.as_str(), d.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ConfigSection::Labels, "env", DriftKind::Missing),
+                (ConfigSection::Labels, "team", DriftKind::Extra),
+                (
+                    ConfigSection::LifecyclePolicies,
+                    "expire",
+                    DriftKind::Changed
+                ),
+                (ConfigSection::ScanPolicies, "strict", DriftKind::Extra),
+            ]
+        );
+        assert_eq!(drift[0].source, Some(json!("prod")));
+        assert_eq!(drift[0].target, None);
+        assert_eq!(drift[2].target.as_ref().unwrap()["config"]["days"], 30);
+    }
+
+    #[test]
+    fn test_digest_ignores_policy_order() {
+        let mut a = bundle();
+        a.scan_policies.push(scan("a-first", "critical"));
+        let mut b = a.clone();
+        b.scan_policies.reverse();
+        assert_eq!(a.digest(), b.digest());
+        assert_eq!(a.digest().len(), 64);
+
+        b.labels.insert("env".to_string(), "staging".to_string());
+        assert_ne!(a.digest(), b.digest());
+    }
+
+    #[test]
+    fn test_validate_bundle_normalizes_and_rejects() {
+        let mut ok = bundle();
+        ok.scan_policies[0].max_severity = "HIGH".to_string();
+        ok.lifecycle_policies.insert(0, lifecycle("zz-last", 7));
+        validate_bundle(&mut ok).unwrap();
+        assert_eq!(ok.scan_policies[0].max_severity, "high");
+        assert_eq!(ok.lifecycle_policies[0].name, "expire");
+
+        let mut dup = bundle();
+        dup.scan_policies.push(scan("gate", "low"));
+        assert!(validate_bundle(&mut dup).is_err());
+
+        let mut bad_type = bundle();
+        bad_type.lifecycle_policies[0].policy_type = "forever".to_string();
+        assert!(validate_bundle(&mut bad_type).is_err());
+
+        let mut bad_cron = bundle();
+        bad_cron.lifecycle_policies[0].cron_schedule = Some("not cron".to_string());
+        assert!(validate_bundle(&mut bad_cron).is_err());
+
+        let mut bad_label = bundle();
+        bad_label.labels.insert(" ".to_string(), String::new());
+        assert!(validate_bundle(&mut bad_label).is_err());
+    }
+
+    #[test]
+    fn test_peer_config_url() {
+        assert_eq!(
+            peer_config_url("https://peer.example.com/", "libs"),
+            "https://peer.example.com/api/v1/repositories/libs/replicated-config"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Per-repository change feed.
+//!
+//! Events are recorded by database triggers on `artifacts`,
+//! `artifact_metadata` and `artifact_labels` (migration 191), so every write
+//! path is covered. This service only reads the log and prunes it.
+//!
+//! The cursor is the id of the last event a consumer has seen. Event ids
+//! come from a sequence and can become visible out of order when
+//! transactions commit late, so a page stops before the first event written
+//! by a transaction that may still have concurrent, uncommitted siblings.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// Page size when the request gives none.
+pub const DEFAULT_EVENT_PAGE_SIZE: i64 = 100;
+/// Largest accepted page size.
+pub const MAX_EVENT_PAGE_SIZE: i64 = 1000;
+
+/// Rows removed per statement by the retention sweep.
+const PRUNE_BATCH: i64 = 10_000;
+
+/// One change to a repository.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct RepositoryEvent {
+    /// Event id; pass the last one seen as `since` to resume.
+    pub id: i64,
+    /// `artifact.created`, `artifact.updated`, `artifact.deleted` or
+    /// `artifact.properties_changed`.
+    pub event_type: String,
+    pub artifact_id: Option<Uuid>,
+    pub path: Option<String>,
+    /// Artifact name, version, size, checksum and content type, or which
+    /// properties changed.
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Where to start reading the feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventCursor {
+    /// After the event with this id; `0` is the oldest retained event.
+    After(i64),
+    /// The current end of the feed.
+    Latest,
+}
+
+impl EventCursor {
+    /// Parse a `since` parameter: an event id, or `latest`.
+    pub fn parse(raw: Option<&str>) -> Result<Self> {
+        match raw.map(str::trim).filter(|s| !s.is_empty()) {
+            None => Ok(Self::After(0)),
+            Some("latest") => Ok(Self::Latest),
+            Some(s) => s
+                .parse::<i64>()
+                .ok()
+                .filter(|id| *id >= 0)
+                .map(Self::After)
+                .ok_or_else(|| {
+                    AppError::Validation(
+                        "since must be an event id returned as next_cursor, or 'latest'"
+                            .to_string(),
+                    )
+                }),
+        }
+    }
+}
+
+/// Clamp a requested page size to `1..=MAX_EVENT_PAGE_SIZE`.
+pub fn clamp_page_size(limit: Option<i64>) -> i64 {
+    limit
+        .unwrap_or(DEFAULT_EVENT_PAGE_SIZE)
+        .clamp(1, MAX_EVENT_PAGE_SIZE)
+}
+
+/// One page of the feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventPage {
+    pub events: Vec<RepositoryEvent>,
+    /// Cursor to pass as `since` for the next page.
+    pub next_cursor: i64,
+    /// More events are readable right away.
+    pub has_more: bool,
+}
+
+/// Trim a fetched `limit + 1` rows to the page and compute the next cursor.
+fn into_page(mut events: Vec<RepositoryEvent>, since: i64, limit: i64) -> EventPage {
+    let has_more = events.len() as i64 > limit;
+    events.truncate(limit as usize);
+    let next_cursor = events.last().map(|e| e.id).unwrap_or(since);
+    EventPage {
+        events,
+        next_cursor,
+        has_more,
+    }
+}
+
+/// Ids at or above this bound may still be followed by lower ids from
+/// in-flight transactions. Shared by the page and `latest` queries.
+const SAFE_BOUND_SQL: &str = r#"
+    COALESCE(
+        (SELECT MIN(id) FROM repository_events
+         WHERE repository_id = $1 AND id > $2
+           AND xid >= pg_snapshot_xmin(pg_current_snapshot())),
+        9223372036854775807
+    )
+"#;
+
+pub struct RepositoryEventService {
+    db: PgPool,
+}
+
+impl RepositoryEventService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Highest event id removed by retention. A non-zero cursor below it
+    /// may have missed events.
+    pub async fn pruned_through(&self) -> Result<i64> {
+        let value: Option<i64> =
+            sqlx::query_scalar("SELECT pruned_through FROM repository_event_log_state")
+                .fetch_optional(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(value.unwrap_or(0))
+    }
+
+    /// Events of `repository_id` after `since`, oldest first.
+    pub async fn list(&self, repository_id: Uuid, since: i64, limit: i64) -> Result<EventPage> {
+        let sql = format!(
+            r#"
+            SELECT id, event_type, artifact_id, path, details, created_at
+            FROM repository_events
+            WHERE repository_id = $1 AND id > $2 AND id < {}
+            ORDER BY id
+            LIMIT $3
+            "#,
+            SAFE_BOUND_SQL
+        );
+        let events: Vec<RepositoryEvent> = sqlx::query_as(&sql)
+            .bind(repository_id)
+            .bind(since)
+            .bind(limit + 1)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(into_page(events, since, limit))
+    }
+
+    /// Cursor at the current end of the feed, for consumers that only want
+    /// changes from now on.
+    pub async fn latest_cursor(&self, repository_id: Uuid) -> Result<i64> {
+        let sql = format!(
+            r#"
+            SELECT COALESCE(MAX(id), 0)
+            FROM repository_events
+            WHERE repository_id = $1 AND id > $2 AND id < {}
+            "#,
+            SAFE_BOUND_SQL
+        );
+        sqlx::query_scalar(&sql)
+            .bind(repository_id)
+            .bind(0_i64)
+            .fetch_one(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Delete events older than `retention_days` in batches and advance the
+    /// pruned-through mark. Returns the number of events removed.
+    pub async fn prune(&self, retention_days: u32) -> Result<u64> {
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(retention_days));
+        let mut removed = 0u64;
+        loop {
+            let (count, max_id): (i64, Option<i64>) = sqlx::query_as(
+                r#"
+                WITH doomed AS (
+                    DELETE FROM repository_events
+                    WHERE id IN (
+                        SELECT id FROM repository_events
+                        WHERE created_at < $1
+                        ORDER BY id
+                        LIMIT $2
+                    )
+                    RETURNING id
+                )
+                SELECT COUNT(*), MAX(id) FROM doomed
+                "#,
+            )
+            .bind(cutoff)
+            .bind(PRUNE_BATCH)
+            .fetch_one(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+            if let Some(max_id) = max_id {
+                sqlx::query(
+                    "UPDATE repository_event_log_state \
+                     SET pruned_through = GREATEST(pruned_through, $1)",
+                )
+                .bind(max_id)
+                .execute(&self.db)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+            removed += count as u64;
+            if count < PRUNE_BATCH {
+                return Ok(removed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: i64) -> RepositoryEvent {
+        RepositoryEvent {
+            id,
+            event_type: "artifact.created".to_string(),
+            artifact_id: None,
+            path: Some(format!("a/{}.bin", id)),
+            details: serde_json::json!({}),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_parse() {
+        assert_eq!(EventCursor::parse(None).unwrap(), EventCursor::After(0));
+        assert_eq!(
+            EventCursor::parse(Some(" ")).unwrap(),
+            EventCursor::After(0)
+        );
+        assert_eq!(
+            EventCursor::parse(Some("42")).unwrap(),
+            EventCursor::After(42)
+        );
+        assert_eq!(
+            EventCursor::parse(Some("latest")).unwrap(),
+            EventCursor::Latest
+        );
+        assert!(EventCursor::parse(Some("-1")).is_err());
+        assert!(EventCursor::parse(Some("abc")).is_err());
+    }
+
+    #[test]
+    fn test_clamp_page_size() {
+        assert_eq!(clamp_page_size(None), DEFAULT_EVENT_PAGE_SIZE);
+        assert_eq!(clamp_page_size(Some(0)), 1);
+        assert_eq!(clamp_page_size(Some(5000)), MAX_EVENT_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_into_page_has_more() {
+        let page = into_page(vec![event(3), event(7), event(9)], 1, 2);
+        assert!(page.has_more);
+        assert_eq!(page.events.len(), 2);
+        assert_eq!(page.next_cursor, 7);
+    }
+
+    #[test]
+    fn test_into_page_empty_keeps_cursor() {
+        let page = into_page(Vec::new(), 12, 100);
+        assert!(!page.has_more);
+        assert!(page.events.is_empty());
+        assert_eq!(page.next_cursor, 12);
+    }
+}
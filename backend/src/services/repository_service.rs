@@ -14,6 +14,7 @@ use crate::models::repository::{
     ReplicationPriority, Repository, RepositoryFormat, RepositoryType,
 };
 use crate::services::opensearch_service::{OpenSearchService, RepositoryDocument};
+use crate::services::permission_service::label_selector_grant_matches;
 
 /// Outcome of an atomic, in-transaction quota admission check
 /// ([`RepositoryService::check_quota_locked`]).
@@ -329,6 +330,9 @@ pub(crate) fn build_search_pattern(query: Option<&str>) -> Option<String> {
 /// `p.target_id = NULL` is never true, so unassigned repositories behave
 /// exactly as before. The subquery aliases `repositories` as `rp` to avoid
 /// colliding with any `r`/`repositories` reference in the caller's query.
+///
+/// Label-selector grants are honoured through the same
+/// [`label_selector_grant_matches`] fragment the data plane uses.
 fn permissions_grant_exists(repo_id_expr: &str, user_param: usize) -> String {
     // The positional-bind instantiation used by the listing/visibility callers:
     // the user principal is a single bound value `$user_param`. Delegates to the
@@ -356,6 +360,7 @@ pub(crate) fn permissions_grant_exists_for(repo_id_expr: &str, user_ref: &str) -
                   OR (p.target_type = 'project' AND p.target_id = (
                       SELECT rp.project_id FROM repositories rp WHERE rp.id = {repo_id_expr}
                   ))
+                  OR {labels}
               )
               AND p.actions <> '{{}}'
               AND (
@@ -364,7 +369,8 @@ pub(crate) fn permissions_grant_exists_for(repo_id_expr: &str, user_ref: &str) -
                       SELECT group_id FROM user_group_members WHERE user_id = {user_ref}
                   ))
               )
-        )"#
+        )"#,
+        labels = label_selector_grant_matches("p.", repo_id_expr),
     )
 }
 
@@ -516,9 +522,11 @@ pub(crate) fn quota_usage_percentage(used_bytes: i64, quota_bytes: i64) -> f64 {
     used_bytes as f64 / quota_bytes as f64
 }
 
-/// Check whether quota usage exceeds the warning threshold (80%).
+/// Check whether quota usage exceeds the warning threshold
+/// (`QUOTA_WARNING_THRESHOLD_PERCENT`, 80% by default).
 pub(crate) fn exceeds_quota_warning_threshold(used_bytes: i64, quota_bytes: i64) -> bool {
-    quota_usage_percentage(used_bytes, quota_bytes) > 0.8
+    quota_usage_percentage(used_bytes, quota_bytes)
+        > crate::config_reload::quota_warning_threshold()
 }
 
 /// Check whether a database error message indicates a duplicate key violation.
@@ -1178,10 +1186,16 @@ impl RepositoryService {
             .map_err(|e| AppError::Database(e.to_string()))?;
         }
 
-        // Index updated repository in search engine (non-blocking)
+        self.reindex_in_background(&repo);
+
+        Ok(repo)
+    }
+
+    /// Re-index `repo` in the search engine after a change, without waiting.
+    pub fn reindex_in_background(&self, repo: &Repository) {
         if let Some(ref search) = self.search_service {
             let search = search.clone();
-            let doc = Self::repo_to_search_doc(&repo);
+            let doc = Self::repo_to_search_doc(repo);
             tokio::spawn(async move {
                 if let Err(e) = search.index_repository(&doc).await {
                     tracing::warn!(
@@ -1192,8 +1206,6 @@ impl RepositoryService {
                 }
             });
         }
-
-        Ok(repo)
     }
 
     /// Delete a repository
@@ -2903,6 +2915,17 @@ mod tests {
         assert!(sql.contains("p.principal_id = $1"));
     }
 
+    #[test]
+    fn test_permissions_grant_exists_has_label_selector_arm() {
+        // Label-selector grants must be honoured by the read plane exactly as
+        // the data plane evaluates them, against the caller's repo expression.
+        let sql = permissions_grant_exists("r.id", 3);
+        assert!(sql.contains("p.target_type = 'label_selector'"));
+        assert!(sql.contains("rl.repository_id = r.id"));
+        // The fail-closed empty-actions rule still applies to selector grants.
+        assert!(sql.contains("p.actions <> '{}'"));
+    }
+
     #[test]
     fn test_visibility_ids_public_all_do_not_consult_permissions() {
         // The repo-scoped token (`Ids`, #1783) and the `PublicOnly`/`All` arms
@@ -0,0 +1,398 @@
+//! Per-repository statistics rollup for dashboards.
+//!
+//! One call gathers what a repository card shows: totals, the most downloaded
+//! packages, daily upload/download activity, the security score and a storage
+//! sparkline. The independent queries run concurrently on the pool.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// Activity window used when the caller does not pick one.
+pub const DEFAULT_WINDOW_DAYS: i64 = 30;
+/// Longest activity window a caller may ask for.
+pub const MAX_WINDOW_DAYS: i64 = 90;
+/// Top packages returned when the caller does not pick a count.
+pub const DEFAULT_TOP_PACKAGES: i64 = 5;
+/// Most top packages a caller may ask for.
+pub const MAX_TOP_PACKAGES: i64 = 25;
+
+/// A package (artifacts sharing a name) ranked by downloads in the window.
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct TopPackage {
+    pub name: String,
+    /// Version of the most recently uploaded artifact.
+    pub latest_version: Option<String>,
+    pub artifact_count: i64,
+    pub size_bytes: i64,
+    /// Downloads within the window.
+    pub download_count: i64,
+    pub last_uploaded_at: DateTime<Utc>,
+}
+
+/// Uploads and downloads on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow, ToSchema)]
+pub struct DailyActivity {
+    pub date: NaiveDate,
+    pub uploads: i64,
+    pub downloads: i64,
+}
+
+/// Upload/download activity over the window.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepositoryActivity {
+    pub uploads: i64,
+    pub downloads: i64,
+    pub last_upload_at: Option<DateTime<Utc>>,
+    pub last_download_at: Option<DateTime<Utc>>,
+    /// One entry per day of the window, oldest first.
+    pub daily: Vec<DailyActivity>,
+}
+
+/// The materialized security score of the repository.
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct RepositoryScanSummary {
+    pub score: i32,
+    pub grade: String,
+    pub total_findings: i32,
+    pub critical_count: i32,
+    pub high_count: i32,
+    pub medium_count: i32,
+    pub low_count: i32,
+    pub acknowledged_count: i32,
+    pub has_failed_scan: bool,
+    pub last_scan_at: Option<DateTime<Utc>>,
+}
+
+/// One point of the storage sparkline.
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow, ToSchema)]
+pub struct StorageTrendPoint {
+    pub date: NaiveDate,
+    pub artifact_count: i64,
+    pub storage_bytes: i64,
+}
+
+/// Everything a repository dashboard card needs.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepositoryStats {
+    pub window_days: i64,
+    pub artifact_count: i64,
+    pub total_size_bytes: i64,
+    pub top_packages: Vec<TopPackage>,
+    pub activity: RepositoryActivity,
+    /// Absent until the repository has been scored.
+    pub scan: Option<RepositoryScanSummary>,
+    /// Daily storage over the window, oldest first; today reflects live totals.
+    pub storage_trend: Vec<StorageTrendPoint>,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TotalsRow {
+    artifact_count: i64,
+    total_size_bytes: i64,
+    last_upload_at: Option<DateTime<Utc>>,
+    last_download_at: Option<DateTime<Utc>>,
+}
+
+/// Daily storage points from `since` through `today`.
+///
+/// Days without a snapshot repeat the previous point; days before the first
+/// snapshot are left out. Today's point always carries the live totals.
+fn fill_storage_trend(
+    snapshots: &[StorageTrendPoint],
+    since: NaiveDate,
+    today: NaiveDate,
+    current: (i64, i64),
+) -> Vec<StorageTrendPoint> {
+    let mut points = Vec::new();
+    let mut snapshots = snapshots.iter().peekable();
+    let mut last: Option<(i64, i64)> = None;
+    let mut day = since;
+    while day < today {
+        while let Some(snapshot) = snapshots.next_if(|s| s.date <= day) {
+            last = Some((snapshot.artifact_count, snapshot.storage_bytes));
+        }
+        if let Some((artifact_count, storage_bytes)) = last {
+            points.push(StorageTrendPoint {
+                date: day,
+                artifact_count,
+                storage_bytes,
+            });
+        }
+        day += Duration::days(1);
+    }
+    points.push(StorageTrendPoint {
+        date: today,
+        artifact_count: current.0,
+        storage_bytes: current.1,
+    });
+    points
+}
+
+/// Builds the dashboard statistics of a repository.
+pub struct RepositoryStatsService {
+    db: PgPool,
+}
+
+impl RepositoryStatsService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Statistics over the last `window_days` days with `top` packages.
+    pub async fn get(
+        &self,
+        repository_id: Uuid,
+        window_days: i64,
+        top: i64,
+    ) -> Result<RepositoryStats> {
+        let window_days = window_days.clamp(1, MAX_WINDOW_DAYS);
+        let top = top.clamp(1, MAX_TOP_PACKAGES);
+        let now = Utc::now();
+        let today = now.date_naive();
+        let since = today - Duration::days(window_days - 1);
+
+        let (totals, top_packages, daily, scan, snapshots) = tokio::try_join!(
+            self.totals(repository_id),
+            self.top_packages(repository_id, since, top),
+            self.daily_activity(repository_id, since),
+            self.scan_summary(repository_id),
+            self.storage_snapshots(repository_id, since),
+        )?;
+
+        let storage_trend = fill_storage_trend(
+            &snapshots,
+            since,
+            today,
+            (totals.artifact_count, totals.total_size_bytes),
+        );
+        Ok(RepositoryStats {
+            window_days,
+            artifact_count: totals.artifact_count,
+            total_size_bytes: totals.total_size_bytes,
+            top_packages,
+            activity: RepositoryActivity {
+                uploads: daily.iter().map(|d| d.uploads).sum(),
+                downloads: daily.iter().map(|d| d.downloads).sum(),
+                last_upload_at: totals.last_upload_at,
+                last_download_at: totals.last_download_at,
+                daily,
+            },
+            scan,
+            storage_trend,
+            generated_at: now,
+        })
+    }
+
+    async fn totals(&self, repository_id: Uuid) -> Result<TotalsRow> {
+        sqlx::query_as::<_, TotalsRow>(
+            r#"
+            SELECT
+                COUNT(*) AS artifact_count,
+                COALESCE(SUM(a.size_bytes), 0)::BIGINT AS total_size_bytes,
+                MAX(a.created_at) AS last_upload_at,
+                (
+                    SELECT MAX(ds.downloaded_at)
+                    FROM download_statistics ds
+                    JOIN artifacts da ON da.id = ds.artifact_id
+                    WHERE da.repository_id = $1
+                ) AS last_download_at
+            FROM artifacts a
+            WHERE a.repository_id = $1 AND a.is_deleted = false
+            "#,
+        )
+        .bind(repository_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    async fn top_packages(
+        &self,
+        repository_id: Uuid,
+        since: NaiveDate,
+        top: i64,
+    ) -> Result<Vec<TopPackage>> {
+        sqlx::query_as::<_, TopPackage>(
+            r#"
+            WITH downloads AS (
+                SELECT ds.artifact_id, COUNT(*) AS n
+                FROM download_statistics ds
+                JOIN artifacts da ON da.id = ds.artifact_id
+                WHERE da.repository_id = $1 AND ds.downloaded_at >= $2::date
+                GROUP BY ds.artifact_id
+            )
+            SELECT
+                a.name,
+                (ARRAY_AGG(a.version ORDER BY a.created_at DESC))[1] AS latest_version,
+                COUNT(*) AS artifact_count,
+                COALESCE(SUM(a.size_bytes), 0)::BIGINT AS size_bytes,
+                COALESCE(SUM(d.n), 0)::BIGINT AS download_count,
+                MAX(a.created_at) AS last_uploaded_at
+            FROM artifacts a
+            LEFT JOIN downloads d ON d.artifact_id = a.id
+            WHERE a.repository_id = $1 AND a.is_deleted = false
+            GROUP BY a.name
+            ORDER BY download_count DESC, last_uploaded_at DESC, a.name
+            LIMIT $3
+            "#,
+        )
+        .bind(repository_id)
+        .bind(since)
+        .bind(top)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    async fn daily_activity(
+        &self,
+        repository_id: Uuid,
+        since: NaiveDate,
+    ) -> Result<Vec<DailyActivity>> {
+        sqlx::query_as::<_, DailyActivity>(
+            r#"
+            WITH days AS (
+                SELECT generate_series($2::date, CURRENT_DATE, INTERVAL '1 day')::date AS day
+            ),
+            uploads AS (
+                SELECT created_at::date AS day, COUNT(*) AS n
+                FROM artifacts
+                WHERE repository_id = $1 AND created_at >= $2::date
+                GROUP BY 1
+            ),
+            downloads AS (
+                SELECT ds.downloaded_at::date AS day, COUNT(*) AS n
+                FROM download_statistics ds
+                JOIN artifacts a ON a.id = ds.artifact_id
+                WHERE a.repository_id = $1 AND ds.downloaded_at >= $2::date
+                GROUP BY 1
+            )
+            SELECT
+                days.day AS date,
+                COALESCE(u.n, 0) AS uploads,
+                COALESCE(d.n, 0) AS downloads
+            FROM days
+            LEFT JOIN uploads u ON u.day = days.day
+            LEFT JOIN downloads d ON d.day = days.day
+            ORDER BY days.day
+            "#,
+        )
+        .bind(repository_id)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    async fn scan_summary(&self, repository_id: Uuid) -> Result<Option<RepositoryScanSummary>> {
+        sqlx::query_as::<_, RepositoryScanSummary>(
+            r#"
+            SELECT score, grade, total_findings, critical_count, high_count,
+                   medium_count, low_count, acknowledged_count, has_failed_scan,
+                   last_scan_at
+            FROM repo_security_scores
+            WHERE repository_id = $1
+            "#,
+        )
+        .bind(repository_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Snapshots in the window plus the last one before it, so the sparkline
+    /// can start on the window's first day.
+    async fn storage_snapshots(
+        &self,
+        repository_id: Uuid,
+        since: NaiveDate,
+    ) -> Result<Vec<StorageTrendPoint>> {
+        sqlx::query_as::<_, StorageTrendPoint>(
+            r#"
+            SELECT snapshot_date AS date, artifact_count, storage_bytes
+            FROM repository_metrics
+            WHERE repository_id = $1
+              AND snapshot_date >= COALESCE(
+                  (SELECT MAX(snapshot_date) FROM repository_metrics
+                   WHERE repository_id = $1 AND snapshot_date <= $2),
+                  $2
+              )
+            ORDER BY snapshot_date ASC
+            "#,
+        )
+        .bind(repository_id)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    fn point(day: u32, artifact_count: i64, storage_bytes: i64) -> StorageTrendPoint {
+        StorageTrendPoint {
+            date: date(day),
+            artifact_count,
+            storage_bytes,
+        }
+    }
+
+    #[test]
+    fn test_trend_carries_snapshots_forward_over_gaps() {
+        let snapshots = [point(1, 2, 200), point(3, 5, 500)];
+        let trend = fill_storage_trend(&snapshots, date(1), date(5), (6, 650));
+        assert_eq!(
+            trend,
+            vec![
+                point(1, 2, 200),
+                point(2, 2, 200),
+                point(3, 5, 500),
+                point(4, 5, 500),
+                point(5, 6, 650),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trend_starts_from_snapshot_before_window() {
+        let snapshots = [point(1, 3, 300), point(4, 4, 400)];
+        let trend = fill_storage_trend(&snapshots, date(3), date(5), (4, 410));
+        assert_eq!(
+            trend,
+            vec![point(3, 3, 300), point(4, 4, 400), point(5, 4, 410)]
+        );
+    }
+
+    #[test]
+    fn test_trend_skips_days_before_first_snapshot() {
+        let snapshots = [point(4, 1, 100)];
+        let trend = fill_storage_trend(&snapshots, date(1), date(5), (1, 100));
+        assert_eq!(trend, vec![point(4, 1, 100), point(5, 1, 100)]);
+    }
+
+    #[test]
+    fn test_trend_without_snapshots_is_live_totals_only() {
+        let trend = fill_storage_trend(&[], date(1), date(5), (7, 700));
+        assert_eq!(trend, vec![point(5, 7, 700)]);
+    }
+
+    #[test]
+    fn test_trend_live_totals_replace_todays_snapshot() {
+        let snapshots = [point(5, 1, 100)];
+        let trend = fill_storage_trend(&snapshots, date(5), date(5), (2, 250));
+        assert_eq!(trend, vec![point(5, 2, 250)]);
+    }
+}
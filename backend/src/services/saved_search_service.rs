@@ -0,0 +1,853 @@
+//! Saved searches and match alerts.
+//!
+//! Users save search criteria ("critical findings in repositories labeled
+//! team=payments") and opt into alerts when new results appear. Instead of
+//! re-running every saved search on a timer, [`start_evaluator`] subscribes
+//! to the EventBus and checks only the artifact each event is about:
+//!
+//! - `artifact.created` is evaluated against `artifact` searches;
+//! - `scan.completed` is evaluated against `finding` searches, using the
+//!   artifact's unacknowledged findings.
+//!
+//! A match is recorded in `saved_search_alerts` (at most once per search,
+//! artifact and event type) and published on the EventBus as
+//! `saved_search.matched`. Matches are only recorded for artifacts the
+//! search owner can see.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::security::Severity;
+use crate::services::event_bus::{DomainEvent, EventBus};
+use crate::services::repository_label_service::LabelEntry;
+
+/// Upper bound on saved searches per user.
+pub const MAX_SAVED_SEARCHES_PER_USER: i64 = 100;
+
+/// What a saved search looks for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchTarget {
+    /// Newly uploaded artifacts.
+    #[default]
+    Artifact,
+    /// Newly scanned artifacts with security findings.
+    Finding,
+}
+
+impl SearchTarget {
+    /// The domain event that can produce new results for this target.
+    pub fn trigger_event(self) -> &'static str {
+        match self {
+            Self::Artifact => "artifact.created",
+            Self::Finding => "scan.completed",
+        }
+    }
+
+    fn from_event(event_type: &str) -> Option<Self> {
+        match event_type {
+            "artifact.created" => Some(Self::Artifact),
+            "scan.completed" => Some(Self::Finding),
+            _ => None,
+        }
+    }
+}
+
+/// Stored search criteria. Every set field must match; unset fields match
+/// anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SavedSearchCriteria {
+    #[serde(default)]
+    pub target: SearchTarget,
+    /// Case-insensitive substring of the artifact name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Repository format, e.g. `npm` or `docker`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository_key: Option<String>,
+    /// Repository labels that must all be present. An empty value matches
+    /// any value for the key.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repository_labels: Vec<LabelEntry>,
+    /// Lowest finding severity that counts (`finding` searches only).
+    /// Defaults to any severity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<String>,
+}
+
+impl SavedSearchCriteria {
+    /// Reject criteria that could never be evaluated.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(sev) = &self.min_severity {
+            if self.target != SearchTarget::Finding {
+                return Err(AppError::Validation(
+                    "min_severity only applies to finding searches".to_string(),
+                ));
+            }
+            if Severity::from_str_loose(sev).is_none() {
+                return Err(AppError::Validation(format!("Unknown severity '{}'", sev)));
+            }
+        }
+        if self
+            .repository_labels
+            .iter()
+            .any(|l| l.key.trim().is_empty())
+        {
+            return Err(AppError::Validation(
+                "Label keys must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The facts about one artifact that saved searches are evaluated against.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactFacts {
+    pub artifact_id: Uuid,
+    pub repository_id: Uuid,
+    pub name: String,
+    pub version: Option<String>,
+    pub repository_key: String,
+    pub format: String,
+    pub labels: Vec<(String, String)>,
+    /// Unacknowledged finding counts by severity. Empty for artifact events.
+    pub findings: HashMap<Severity, i64>,
+}
+
+impl ArtifactFacts {
+    /// Findings at or above `threshold`.
+    fn findings_at_least(&self, threshold: Severity) -> i64 {
+        self.findings
+            .iter()
+            .filter(|(sev, _)| sev.meets_threshold(threshold))
+            .map(|(_, n)| n)
+            .sum()
+    }
+}
+
+/// Whether `facts` satisfies `criteria` for an event of `target`.
+pub fn matches(
+    criteria: &SavedSearchCriteria,
+    target: SearchTarget,
+    facts: &ArtifactFacts,
+) -> bool {
+    if criteria.target != target {
+        return false;
+    }
+    if let Some(q) = criteria.query.as_deref().filter(|q| !q.is_empty()) {
+        if !facts.name.to_lowercase().contains(&q.to_lowercase()) {
+            return false;
+        }
+    }
+    if let Some(format) = &criteria.format {
+        if !facts.format.eq_ignore_ascii_case(format) {
+            return false;
+        }
+    }
+    if let Some(key) = &criteria.repository_key {
+        if facts.repository_key != *key {
+            return false;
+        }
+    }
+    let labels_match = criteria.repository_labels.iter().all(|wanted| {
+        facts
+            .labels
+            .iter()
+            .any(|(k, v)| *k == wanted.key && (wanted.value.is_empty() || *v == wanted.value))
+    });
+    if !labels_match {
+        return false;
+    }
+    if target == SearchTarget::Finding {
+        let threshold = criteria
+            .min_severity
+            .as_deref()
+            .and_then(Severity::from_str_loose)
+            .unwrap_or(Severity::Info);
+        return facts.findings_at_least(threshold) > 0;
+    }
+    true
+}
+
+/// A user's saved search.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    pub name: String,
+    pub criteria: SavedSearchCriteria,
+    pub notify: bool,
+    pub unread_alerts: i64,
+    pub last_matched_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct SavedSearchRow {
+    id: Uuid,
+    name: String,
+    criteria: serde_json::Value,
+    notify: bool,
+    unread_alerts: i64,
+    last_matched_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<SavedSearchRow> for SavedSearch {
+    fn from(row: SavedSearchRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            criteria: serde_json::from_value(row.criteria).unwrap_or_default(),
+            notify: row.notify,
+            unread_alerts: row.unread_alerts,
+            last_matched_at: row.last_matched_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// One new result for a saved search.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct SavedSearchAlert {
+    pub id: Uuid,
+    pub saved_search_id: Uuid,
+    pub saved_search_name: String,
+    pub artifact_id: Uuid,
+    pub artifact_name: String,
+    pub artifact_version: Option<String>,
+    pub repository_key: String,
+    pub event_type: String,
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+const SAVED_SEARCH_COLUMNS: &str = r#"
+    s.id, s.name, s.criteria, s.notify, s.last_matched_at, s.created_at, s.updated_at,
+    (SELECT COUNT(*) FROM saved_search_alerts a
+     WHERE a.saved_search_id = s.id AND a.read_at IS NULL) AS unread_alerts
+"#;
+
+fn db_err(e: sqlx::Error) -> AppError {
+    AppError::Database(e.to_string())
+}
+
+fn name_conflict(e: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db) = &e {
+        if db.is_unique_violation() {
+            return AppError::Conflict("A saved search with this name already exists".to_string());
+        }
+    }
+    db_err(e)
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.len() > 255 {
+        return Err(AppError::Validation(
+            "Name must be between 1 and 255 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The user's saved searches, newest first.
+pub async fn list_saved_searches(db: &PgPool, user_id: Uuid) -> Result<Vec<SavedSearch>> {
+    let sql = format!(
+        "SELECT {} FROM saved_searches s WHERE s.user_id = $1 ORDER BY s.created_at DESC",
+        SAVED_SEARCH_COLUMNS
+    );
+    let rows: Vec<SavedSearchRow> = sqlx::query_as(&sql)
+        .bind(user_id)
+        .fetch_all(db)
+        .await
+        .map_err(db_err)?;
+    Ok(rows.into_iter().map(SavedSearch::from).collect())
+}
+
+pub async fn get_saved_search(db: &PgPool, user_id: Uuid, id: Uuid) -> Result<SavedSearch> {
+    let sql = format!(
+        "SELECT {} FROM saved_searches s WHERE s.id = $1 AND s.user_id = $2",
+        SAVED_SEARCH_COLUMNS
+    );
+    let row: Option<SavedSearchRow> = sqlx::query_as(&sql)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await
+        .map_err(db_err)?;
+    row.map(SavedSearch::from)
+        .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))
+}
+
+/// Save a new search for `user_id`.
+pub async fn create_saved_search(
+    db: &PgPool,
+    user_id: Uuid,
+    name: &str,
+    criteria: &SavedSearchCriteria,
+    notify: bool,
+) -> Result<SavedSearch> {
+    validate_name(name)?;
+    criteria.validate()?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM saved_searches WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(db)
+        .await
+        .map_err(db_err)?;
+    if count >= MAX_SAVED_SEARCHES_PER_USER {
+        return Err(AppError::Validation(format!(
+            "At most {} saved searches are allowed per user",
+            MAX_SAVED_SEARCHES_PER_USER
+        )));
+    }
+
+    let id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO saved_searches (user_id, name, criteria, notify)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(name.trim())
+    .bind(serde_json::to_value(criteria)?)
+    .bind(notify)
+    .fetch_one(db)
+    .await
+    .map_err(name_conflict)?;
+
+    get_saved_search(db, user_id, id).await
+}
+
+/// Update a saved search. `None` fields keep their current value.
+pub async fn update_saved_search(
+    db: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+    name: Option<&str>,
+    criteria: Option<&SavedSearchCriteria>,
+    notify: Option<bool>,
+) -> Result<SavedSearch> {
+    if let Some(name) = name {
+        validate_name(name)?;
+    }
+    if let Some(criteria) = criteria {
+        criteria.validate()?;
+    }
+    let criteria = criteria.map(serde_json::to_value).transpose()?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE saved_searches
+        SET name = COALESCE($3, name),
+            criteria = COALESCE($4, criteria),
+            notify = COALESCE($5, notify),
+            updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(name.map(str::trim))
+    .bind(criteria)
+    .bind(notify)
+    .execute(db)
+    .await
+    .map_err(name_conflict)?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Saved search not found".to_string()));
+    }
+
+    get_saved_search(db, user_id, id).await
+}
+
+/// Delete a saved search and its alerts.
+pub async fn delete_saved_search(db: &PgPool, user_id: Uuid, id: Uuid) -> Result<()> {
+    let result = sqlx::query("DELETE FROM saved_searches WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(db)
+        .await
+        .map_err(db_err)?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Saved search not found".to_string()));
+    }
+    Ok(())
+}
+
+/// Alerts across the user's saved searches, newest first. Alerts for
+/// artifacts deleted since are left out.
+pub async fn list_alerts(
+    db: &PgPool,
+    user_id: Uuid,
+    saved_search_id: Option<Uuid>,
+    unread_only: bool,
+    limit: i64,
+) -> Result<Vec<SavedSearchAlert>> {
+    sqlx::query_as(
+        r#"
+        SELECT al.id, al.saved_search_id, s.name AS saved_search_name,
+               al.artifact_id, a.name AS artifact_name, a.version AS artifact_version,
+               r.key AS repository_key, al.event_type, al.details,
+               al.read_at, al.created_at
+        FROM saved_search_alerts al
+        JOIN saved_searches s ON s.id = al.saved_search_id
+        JOIN artifacts a ON a.id = al.artifact_id AND a.is_deleted = false
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE s.user_id = $1
+          AND ($2::uuid IS NULL OR al.saved_search_id = $2)
+          AND ($3 = false OR al.read_at IS NULL)
+        ORDER BY al.created_at DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(user_id)
+    .bind(saved_search_id)
+    .bind(unread_only)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)
+}
+
+/// Mark the given alerts (or, with `None`, all of the user's alerts) read.
+/// Returns the number of alerts updated.
+pub async fn mark_alerts_read(db: &PgPool, user_id: Uuid, ids: Option<&[Uuid]>) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE saved_search_alerts al
+        SET read_at = NOW()
+        FROM saved_searches s
+        WHERE s.id = al.saved_search_id
+          AND s.user_id = $1
+          AND al.read_at IS NULL
+          AND ($2::uuid[] IS NULL OR al.id = ANY($2))
+        "#,
+    )
+    .bind(user_id)
+    .bind(ids)
+    .execute(db)
+    .await
+    .map_err(db_err)?;
+    Ok(result.rows_affected())
+}
+
+// ---------------------------------------------------------------------------
+// Incremental evaluation
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, sqlx::FromRow)]
+struct ArtifactFactsRow {
+    repository_id: Uuid,
+    name: String,
+    version: Option<String>,
+    repository_key: String,
+    format: String,
+}
+
+async fn load_facts(
+    db: &PgPool,
+    artifact_id: Uuid,
+    target: SearchTarget,
+) -> Result<Option<ArtifactFacts>> {
+    let row: Option<ArtifactFactsRow> = sqlx::query_as(
+        r#"
+        SELECT a.repository_id, a.name, a.version, r.key AS repository_key,
+               r.format::text AS format
+        FROM artifacts a
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE a.id = $1 AND a.is_deleted = false
+        "#,
+    )
+    .bind(artifact_id)
+    .fetch_optional(db)
+    .await
+    .map_err(db_err)?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let labels: Vec<(String, String)> = sqlx::query_as(
+        "SELECT label_key, label_value FROM repository_labels WHERE repository_id = $1",
+    )
+    .bind(row.repository_id)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)?;
+
+    let mut findings = HashMap::new();
+    if target == SearchTarget::Finding {
+        let counts: Vec<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT severity, COUNT(*)
+            FROM scan_findings
+            WHERE artifact_id = $1 AND is_acknowledged = false
+            GROUP BY severity
+            "#,
+        )
+        .bind(artifact_id)
+        .fetch_all(db)
+        .await
+        .map_err(db_err)?;
+        for (sev, n) in counts {
+            if let Some(sev) = Severity::from_str_loose(&sev) {
+                *findings.entry(sev).or_insert(0) += n;
+            }
+        }
+    }
+
+    Ok(Some(ArtifactFacts {
+        artifact_id,
+        repository_id: row.repository_id,
+        name: row.name,
+        version: row.version,
+        repository_key: row.repository_key,
+        format: row.format,
+        labels,
+        findings,
+    }))
+}
+
+/// Alert payload stored with each match.
+fn alert_details(facts: &ArtifactFacts, target: SearchTarget) -> serde_json::Value {
+    let mut details = serde_json::json!({
+        "name": facts.name,
+        "version": facts.version,
+        "repository_key": facts.repository_key,
+        "format": facts.format,
+    });
+    if target == SearchTarget::Finding {
+        let counts: serde_json::Map<String, serde_json::Value> = [
+            Severity::Critical,
+            Severity::High,
+            Severity::Medium,
+            Severity::Low,
+            Severity::Info,
+        ]
+        .into_iter()
+        .map(|sev| {
+            let key = serde_json::to_value(sev)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            (
+                key,
+                serde_json::json!(facts.findings.get(&sev).copied().unwrap_or(0)),
+            )
+        })
+        .collect();
+        details["findings"] = serde_json::Value::Object(counts);
+    }
+    details
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CandidateSearch {
+    id: Uuid,
+    user_id: Uuid,
+    criteria: serde_json::Value,
+}
+
+/// Evaluate notifying saved searches against the artifact `event` is about.
+/// Returns the number of new alerts recorded.
+pub async fn evaluate_event(db: &PgPool, bus: &EventBus, event: &DomainEvent) -> Result<u32> {
+    let Some(target) = SearchTarget::from_event(&event.event_type) else {
+        return Ok(0);
+    };
+    let Ok(artifact_id) = event.entity_id.parse::<Uuid>() else {
+        return Ok(0);
+    };
+
+    // Only searches the owner can still see results for are candidates: the
+    // visibility rule mirrors the search API (admins, public repositories,
+    // or a global or repository-scoped role assignment).
+    let candidates: Vec<CandidateSearch> = sqlx::query_as(
+        r#"
+        SELECT s.id, s.user_id, s.criteria
+        FROM saved_searches s
+        JOIN users u ON u.id = s.user_id AND u.is_active = true
+        JOIN artifacts a ON a.id = $1
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE s.notify = true
+          AND COALESCE(s.criteria->>'target', 'artifact') = $2
+          AND (
+              u.is_admin = true
+              OR r.is_public = true
+              OR EXISTS (
+                  SELECT 1 FROM role_assignments ra
+                  WHERE ra.user_id = u.id
+                    AND (ra.repository_id IS NULL OR ra.repository_id = r.id)
+              )
+          )
+        "#,
+    )
+    .bind(artifact_id)
+    .bind(match target {
+        SearchTarget::Artifact => "artifact",
+        SearchTarget::Finding => "finding",
+    })
+    .fetch_all(db)
+    .await
+    .map_err(db_err)?;
+    if candidates.is_empty() {
+        return Ok(0);
+    }
+
+    let Some(facts) = load_facts(db, artifact_id, target).await? else {
+        return Ok(0);
+    };
+    let details = alert_details(&facts, target);
+
+    let mut recorded = 0;
+    for candidate in candidates {
+        let Ok(criteria) = serde_json::from_value::<SavedSearchCriteria>(candidate.criteria) else {
+            continue;
+        };
+        if !matches(&criteria, target, &facts) {
+            continue;
+        }
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO saved_search_alerts (saved_search_id, artifact_id, event_type, details)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (saved_search_id, artifact_id, event_type) DO NOTHING
+            "#,
+        )
+        .bind(candidate.id)
+        .bind(artifact_id)
+        .bind(&event.event_type)
+        .bind(&details)
+        .execute(db)
+        .await
+        .map_err(db_err)?;
+        if inserted.rows_affected() == 0 {
+            continue;
+        }
+
+        sqlx::query("UPDATE saved_searches SET last_matched_at = NOW() WHERE id = $1")
+            .bind(candidate.id)
+            .execute(db)
+            .await
+            .map_err(db_err)?;
+        tracing::debug!(
+            saved_search_id = %candidate.id,
+            user_id = %candidate.user_id,
+            artifact_id = %artifact_id,
+            "Saved search matched"
+        );
+        bus.emit_for_repo(
+            "saved_search.matched",
+            candidate.id,
+            facts.repository_id,
+            None,
+        );
+        recorded += 1;
+    }
+    Ok(recorded)
+}
+
+/// Start the saved-search evaluator background task.
+///
+/// Listens on the EventBus and evaluates saved searches against each
+/// uploaded or scanned artifact. The task exits when the EventBus closes.
+pub fn start_evaluator(event_bus: Arc<EventBus>, db: PgPool) {
+    let mut rx = event_bus.subscribe();
+
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if SearchTarget::from_event(&event.event_type).is_none() {
+                        continue;
+                    }
+                    if let Err(e) = evaluate_event(&db, &event_bus, &event).await {
+                        tracing::warn!(
+                            event_type = %event.event_type,
+                            entity_id = %event.entity_id,
+                            error = %e,
+                            "Failed to evaluate saved searches"
+                        );
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!(
+                        skipped = n,
+                        "Saved search evaluator lagged, some events were dropped"
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    tracing::info!("EventBus closed, saved search evaluator shutting down");
+                    break;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facts() -> ArtifactFacts {
+        ArtifactFacts {
+            name: "payments-api".to_string(),
+            repository_key: "npm-internal".to_string(),
+            format: "npm".to_string(),
+            labels: vec![
+                ("team".to_string(), "payments".to_string()),
+                ("tier".to_string(), "1".to_string()),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn label(key: &str, value: &str) -> LabelEntry {
+        LabelEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_empty_criteria_match_any_artifact() {
+        let criteria = SavedSearchCriteria::default();
+        assert!(matches(&criteria, SearchTarget::Artifact, &facts()));
+        assert!(!matches(&criteria, SearchTarget::Finding, &facts()));
+    }
+
+    #[test]
+    fn test_query_format_and_repository_filters() {
+        let mut criteria = SavedSearchCriteria {
+            query: Some("PAYMENTS".to_string()),
+            format: Some("NPM".to_string()),
+            repository_key: Some("npm-internal".to_string()),
+            ..Default::default()
+        };
+        assert!(matches(&criteria, SearchTarget::Artifact, &facts()));
+
+        criteria.query = Some("billing".to_string());
+        assert!(!matches(&criteria, SearchTarget::Artifact, &facts()));
+
+        criteria.query = None;
+        criteria.format = Some("pypi".to_string());
+        assert!(!matches(&criteria, SearchTarget::Artifact, &facts()));
+    }
+
+    #[test]
+    fn test_label_filters() {
+        let mut criteria = SavedSearchCriteria {
+            repository_labels: vec![label("team", "payments"), label("tier", "")],
+            ..Default::default()
+        };
+        assert!(matches(&criteria, SearchTarget::Artifact, &facts()));
+
+        criteria.repository_labels = vec![label("team", "search")];
+        assert!(!matches(&criteria, SearchTarget::Artifact, &facts()));
+
+        criteria.repository_labels = vec![label("owner", "")];
+        assert!(!matches(&criteria, SearchTarget::Artifact, &facts()));
+    }
+
+    #[test]
+    fn test_finding_severity_threshold() {
+        let criteria = SavedSearchCriteria {
+            target: SearchTarget::Finding,
+            repository_labels: vec![label("team", "payments")],
+            min_severity: Some("critical".to_string()),
+            ..Default::default()
+        };
+        let mut f = facts();
+        assert!(!matches(&criteria, SearchTarget::Finding, &f));
+
+        f.findings.insert(Severity::High, 3);
+        assert!(!matches(&criteria, SearchTarget::Finding, &f));
+
+        f.findings.insert(Severity::Critical, 1);
+        assert!(matches(&criteria, SearchTarget::Finding, &f));
+        assert!(!matches(&criteria, SearchTarget::Artifact, &f));
+
+        let any = SavedSearchCriteria {
+            target: SearchTarget::Finding,
+            ..Default::default()
+        };
+        let mut low_only = facts();
+        low_only.findings.insert(Severity::Low, 1);
+        assert!(matches(&any, SearchTarget::Finding, &low_only));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(SavedSearchCriteria::default().validate().is_ok());
+
+        let severity_on_artifact = SavedSearchCriteria {
+            min_severity: Some("high".to_string()),
+            ..Default::default()
+        };
+        assert!(severity_on_artifact.validate().is_err());
+
+        let bad_severity = SavedSearchCriteria {
+            target: SearchTarget::Finding,
+            min_severity: Some("severe".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_severity.validate().is_err());
+
+        let empty_label = SavedSearchCriteria {
+            repository_labels: vec![label(" ", "x")],
+            ..Default::default()
+        };
+        assert!(empty_label.validate().is_err());
+    }
+
+    #[test]
+    fn test_criteria_deserialize_defaults() {
+        let criteria: SavedSearchCriteria = serde_json::from_value(serde_json::json!({
+            "query": "api",
+            "repository_labels": [{"key": "team", "value": "payments"}]
+        }))
+        .unwrap();
+        assert_eq!(criteria.target, SearchTarget::Artifact);
+        assert_eq!(criteria.query.as_deref(), Some("api"));
+        assert_eq!(criteria.repository_labels, vec![label("team", "payments")]);
+    }
+
+    #[test]
+    fn test_trigger_events_round_trip() {
+        for target in [SearchTarget::Artifact, SearchTarget::Finding] {
+            assert_eq!(
+                SearchTarget::from_event(target.trigger_event()),
+                Some(target)
+            );
+        }
+        assert_eq!(SearchTarget::from_event("artifact.deleted"), None);
+    }
+
+    #[test]
+    fn test_alert_details_include_finding_counts() {
+        let mut f = facts();
+        f.findings.insert(Severity::Critical, 2);
+        let details = alert_details(&f, SearchTarget::Finding);
+        assert_eq!(details["findings"]["critical"], 2);
+        assert_eq!(details["findings"]["low"], 0);
+        assert!(alert_details(&f, SearchTarget::Artifact)
+            .get("findings")
+            .is_none());
+    }
+}
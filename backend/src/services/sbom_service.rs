@@ -133,7 +133,6 @@ pub(crate) fn scan_row_passes_known_filter(
 /// If you edit the ranks here, also update the four `CASE severity ...`
 /// expressions in `get_cve_trends`. The unit tests in this module assert
 /// the two stay in sync. (#1375 round-2)
-#[allow(dead_code)] // Mirror of an SQL CASE; only used from tests today.
 pub(crate) fn severity_rank(severity: &str) -> i32 {
     match severity.to_ascii_lowercase().as_str() {
         "critical" => 4,
@@ -0,0 +1,855 @@
+//! Prioritized scan job queue and worker pool.
+//!
+//! Every scan trigger — scan-on-upload, a manual artifact scan, a repository
+//! rescan, the inventory backfill — enqueues a `scan_jobs` row instead of
+//! spawning the scan directly. A fixed pool of workers per replica drains the
+//! queue highest-priority first, so a burst of uploads cannot starve a scan a
+//! user is waiting on, and the number of concurrent scanner invocations is
+//! bounded by the pool rather than by the trigger rate.
+//!
+//! Coordination pattern: `RowClaimedQueue` (see [`crate::services::cluster_work`]).
+//! Workers claim one row at a time with `FOR UPDATE SKIP LOCKED`; finalizers
+//! match on the claim token. Each job runs under a timeout; a job that times
+//! out, errors transiently, or whose scan task panics is requeued with
+//! exponential backoff until `max_attempts`. A job whose claim expires (the
+//! owning process died mid-scan) is requeued by the maintenance loop.
+//!
+//! Effective priority is the trigger's base priority ([`ScanTrigger`]) plus an
+//! optional per-repository adjustment from `scan_queue_priorities`.
+//!
+//! Settings (environment, positive integers):
+//!
+//! * `SCAN_QUEUE_WORKERS` — workers per replica (default 4).
+//! * `SCAN_JOB_TIMEOUT_SECS` — per-job wall-clock limit (default 1800).
+//! * `SCAN_JOB_MAX_ATTEMPTS` — attempts before a job is marked failed (default 3).
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Row};
+use tokio::sync::Notify;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::cluster_work::{Claimed, WorkerIdentity};
+use crate::services::metrics_service;
+use crate::services::scanner_service::{positive_env_or, ScannerService};
+
+const SCAN_QUEUE_WORKERS_ENV: &str = "SCAN_QUEUE_WORKERS";
+const SCAN_JOB_TIMEOUT_SECS_ENV: &str = "SCAN_JOB_TIMEOUT_SECS";
+const SCAN_JOB_MAX_ATTEMPTS_ENV: &str = "SCAN_JOB_MAX_ATTEMPTS";
+
+const DEFAULT_SCAN_QUEUE_WORKERS: u64 = 4;
+const DEFAULT_SCAN_JOB_TIMEOUT_SECS: u64 = 1800;
+const DEFAULT_SCAN_JOB_MAX_ATTEMPTS: u64 = 3;
+
+/// Slack added to the job timeout for the claim lease. A live worker always
+/// finalizes (or aborts) the job before its claim lapses, so an expired claim
+/// reliably means the owning process is gone.
+const CLAIM_GRACE_SECS: u64 = 60;
+
+/// Idle workers re-poll at this interval even without a wake-up, so jobs
+/// enqueued by another replica or becoming available after backoff are
+/// picked up.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Interval of the expired-claim sweep, pruning and gauge refresh.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Finished jobs are kept this long for the job list, then pruned.
+const FINISHED_JOB_RETENTION_DAYS: i32 = 7;
+
+const RETRY_BACKOFF_BASE_SECS: u64 = 30;
+const RETRY_BACKOFF_MAX_SECS: u64 = 600;
+
+/// Bound on per-repository priority adjustments, so one repository cannot be
+/// lifted above every manual scan by an arbitrarily large value.
+pub const MAX_REPOSITORY_PRIORITY: i32 = 1000;
+
+/// What caused a scan to be enqueued. Each trigger has a base priority;
+/// interactive triggers outrank background ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanTrigger {
+    /// A user asked for this artifact to be scanned and may be waiting on it.
+    Manual,
+    /// Repository-wide rescans and backfills.
+    Rescan,
+    /// Scan-on-upload.
+    Upload,
+}
+
+impl ScanTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Rescan => "rescan",
+            Self::Upload => "upload",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "manual" => Some(Self::Manual),
+            "rescan" => Some(Self::Rescan),
+            "upload" => Some(Self::Upload),
+            _ => None,
+        }
+    }
+
+    /// Base priority. The gaps leave room for per-repository adjustments
+    /// without one trigger class overtaking another by default.
+    pub fn base_priority(&self) -> i32 {
+        match self {
+            Self::Manual => 2000,
+            Self::Rescan => 1000,
+            Self::Upload => 0,
+        }
+    }
+}
+
+/// Options carried by a job into the scanner call.
+#[derive(Debug, Clone, Default)]
+pub struct ScanJobOptions {
+    /// Scan even if the repository's scan config is disabled.
+    pub force: bool,
+    /// Skip the completed-scan dedup short-circuit.
+    pub bypass_dedup: bool,
+    /// Pre-allocated `scan_results` ids keyed by scan type, for triggers that
+    /// returned them to the caller.
+    pub prepared: Option<HashMap<String, Uuid>>,
+}
+
+/// Queue settings, read once from the environment at startup.
+#[derive(Debug, Clone, Copy)]
+pub struct QueueSettings {
+    pub workers: u64,
+    pub job_timeout: Duration,
+    pub max_attempts: i32,
+}
+
+impl QueueSettings {
+    pub fn from_env() -> Self {
+        Self {
+            workers: positive_env_or(SCAN_QUEUE_WORKERS_ENV, DEFAULT_SCAN_QUEUE_WORKERS),
+            job_timeout: Duration::from_secs(positive_env_or(
+                SCAN_JOB_TIMEOUT_SECS_ENV,
+                DEFAULT_SCAN_JOB_TIMEOUT_SECS,
+            )),
+            max_attempts: positive_env_or(SCAN_JOB_MAX_ATTEMPTS_ENV, DEFAULT_SCAN_JOB_MAX_ATTEMPTS)
+                .min(i32::MAX as u64) as i32,
+        }
+    }
+
+    fn claim_ttl_secs(&self) -> f64 {
+        (self.job_timeout.as_secs() + CLAIM_GRACE_SECS) as f64
+    }
+}
+
+/// A queued, running or finished scan job.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ScanJob {
+    pub id: Uuid,
+    pub artifact_id: Uuid,
+    pub repository_id: Uuid,
+    pub trigger_type: String,
+    pub priority: i32,
+    pub status: String,
+    pub force: bool,
+    pub bypass_dedup: bool,
+    #[serde(skip)]
+    pub prepared: Option<serde_json::Value>,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub claimed_by: Option<String>,
+    pub enqueued_at: DateTime<Utc>,
+    pub available_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ScanJob {
+    fn prepared_map(&self) -> Option<HashMap<String, Uuid>> {
+        self.prepared
+            .clone()
+            .and_then(|v| serde_json::from_value(v).ok())
+    }
+}
+
+const SCAN_JOB_COLUMNS: &str = "id, artifact_id, repository_id, trigger_type, priority, status, \
+     force, bypass_dedup, prepared, attempts, max_attempts, last_error, claimed_by, \
+     enqueued_at, available_at, started_at, finished_at";
+
+/// Queued job counts for one trigger.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TriggerDepth {
+    pub trigger_type: String,
+    pub queued: i64,
+    /// Age of the oldest job of this trigger that is ready to run.
+    pub oldest_age_seconds: Option<f64>,
+}
+
+/// Point-in-time queue statistics.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QueueStats {
+    pub queued: i64,
+    pub running: i64,
+    pub failed_last_24h: i64,
+    pub completed_last_24h: i64,
+    /// Age of the oldest job that is ready to run (excludes jobs waiting out
+    /// a retry backoff).
+    pub oldest_queued_age_seconds: Option<f64>,
+    pub by_trigger: Vec<TriggerDepth>,
+    /// Workers configured on the replica that served this request.
+    pub workers_per_replica: u64,
+    pub job_timeout_secs: u64,
+    pub max_attempts: i32,
+}
+
+/// A per-repository priority adjustment.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct RepositoryPriority {
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    pub priority: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ---------------------------------------------------------------------------
+// Enqueue
+// ---------------------------------------------------------------------------
+
+fn queue_notify() -> &'static Notify {
+    static NOTIFY: OnceLock<Notify> = OnceLock::new();
+    NOTIFY.get_or_init(Notify::new)
+}
+
+/// Enqueue a scan of one artifact. See [`enqueue_many`].
+pub async fn enqueue(
+    db: &PgPool,
+    artifact_id: Uuid,
+    trigger: ScanTrigger,
+    options: ScanJobOptions,
+) -> Result<u64> {
+    enqueue_many(db, &[artifact_id], trigger, options).await
+}
+
+/// Enqueue scans of the given artifacts and wake idle workers. Returns the
+/// number of jobs created or merged; deleted or unknown artifacts are skipped.
+///
+/// An artifact has at most one queued job. Re-triggering merges into it: the
+/// job keeps the higher priority (and that trigger), the `force` and
+/// `bypass_dedup` flags are OR-ed, and newly pre-allocated scan result ids
+/// replace older ones.
+pub async fn enqueue_many(
+    db: &PgPool,
+    artifact_ids: &[Uuid],
+    trigger: ScanTrigger,
+    options: ScanJobOptions,
+) -> Result<u64> {
+    if artifact_ids.is_empty() {
+        return Ok(0);
+    }
+    let prepared = options
+        .prepared
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| AppError::Internal(format!("Failed to encode prepared scans: {e}")))?;
+    let max_attempts = QueueSettings::from_env().max_attempts;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO scan_jobs
+            (artifact_id, repository_id, trigger_type, priority, force, bypass_dedup,
+             prepared, max_attempts)
+        SELECT a.id, a.repository_id, $2, $3 + COALESCE(p.priority, 0), $4, $5, $6, $7
+        FROM artifacts a
+        LEFT JOIN scan_queue_priorities p ON p.repository_id = a.repository_id
+        WHERE a.id = ANY($1) AND a.is_deleted = false
+        ON CONFLICT (artifact_id) WHERE status = 'queued' DO UPDATE SET
+            trigger_type = CASE WHEN EXCLUDED.priority > scan_jobs.priority
+                                THEN EXCLUDED.trigger_type ELSE scan_jobs.trigger_type END,
+            priority = GREATEST(scan_jobs.priority, EXCLUDED.priority),
+            force = scan_jobs.force OR EXCLUDED.force,
+            bypass_dedup = scan_jobs.bypass_dedup OR EXCLUDED.bypass_dedup,
+            prepared = COALESCE(EXCLUDED.prepared, scan_jobs.prepared)
+        "#,
+    )
+    .bind(artifact_ids)
+    .bind(trigger.as_str())
+    .bind(trigger.base_priority())
+    .bind(options.force)
+    .bind(options.bypass_dedup)
+    .bind(prepared)
+    .bind(max_attempts)
+    .execute(db)
+    .await?;
+
+    let enqueued = result.rows_affected();
+    if enqueued > 0 {
+        queue_notify().notify_waiters();
+    }
+    Ok(enqueued)
+}
+
+// ---------------------------------------------------------------------------
+// Claim / finalize
+// ---------------------------------------------------------------------------
+
+/// Claim the highest-priority ready job. Jobs for an artifact that already
+/// has a running scan are skipped so one artifact is never scanned twice
+/// concurrently.
+async fn claim_next(
+    db: &PgPool,
+    claimed_by: &str,
+    claim_ttl_secs: f64,
+) -> Result<Option<Claimed<ScanJob>>> {
+    let row = sqlx::query(
+        r#"
+        WITH candidate AS (
+            SELECT q.id
+            FROM scan_jobs q
+            WHERE q.status = 'queued'
+              AND q.available_at <= NOW()
+              AND NOT EXISTS (
+                  SELECT 1 FROM scan_jobs r
+                  WHERE r.artifact_id = q.artifact_id AND r.status = 'running'
+              )
+            ORDER BY q.priority DESC, q.enqueued_at ASC
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE scan_jobs j
+        SET status = 'running',
+            attempts = j.attempts + 1,
+            started_at = NOW(),
+            claimed_by = $1,
+            claim_token = gen_random_uuid(),
+            claim_expires_at = NOW() + make_interval(secs => $2)
+        FROM candidate
+        WHERE j.id = candidate.id
+        RETURNING j.*
+        "#,
+    )
+    .bind(claimed_by)
+    .bind(claim_ttl_secs)
+    .fetch_optional(db)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let job = ScanJob::from_row(&row)?;
+    let claim_token: Uuid = row.try_get("claim_token")?;
+    let claim_expires_at: DateTime<Utc> = row.try_get("claim_expires_at")?;
+    Ok(Some(Claimed::from_claim_row(
+        job,
+        claim_token,
+        claimed_by.to_string(),
+        claim_expires_at,
+    )))
+}
+
+async fn complete_job(db: &PgPool, job: &Claimed<ScanJob>) -> Result<()> {
+    sqlx::query(
+        "UPDATE scan_jobs \
+         SET status = 'completed', finished_at = NOW(), last_error = NULL, \
+             claim_token = NULL, claim_expires_at = NULL \
+         WHERE id = $1 AND status = 'running' AND claim_token = $2",
+    )
+    .bind(job.id)
+    .bind(job.claim_token())
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed attempt. Retryable failures go back to `queued` after a
+/// backoff while attempts remain, unless a newer queued job for the same
+/// artifact already exists (it supersedes this one).
+async fn fail_job(
+    db: &PgPool,
+    job: &Claimed<ScanJob>,
+    retryable: bool,
+    error: &str,
+) -> Result<bool> {
+    let requeued: Option<bool> = sqlx::query_scalar(
+        r#"
+        WITH decision AS (
+            SELECT j.id,
+                   ($3 AND j.attempts < j.max_attempts AND NOT EXISTS (
+                       SELECT 1 FROM scan_jobs q
+                       WHERE q.artifact_id = j.artifact_id AND q.status = 'queued'
+                   )) AS retry
+            FROM scan_jobs j
+            WHERE j.id = $1 AND j.status = 'running' AND j.claim_token = $2
+        )
+        UPDATE scan_jobs j
+        SET status = CASE WHEN d.retry THEN 'queued' ELSE 'failed' END,
+            available_at = NOW() + make_interval(secs => $4),
+            finished_at = CASE WHEN d.retry THEN NULL ELSE NOW() END,
+            last_error = $5,
+            claim_token = NULL,
+            claimed_by = NULL,
+            claim_expires_at = NULL
+        FROM decision d
+        WHERE j.id = d.id AND j.claim_token = $2
+        RETURNING d.retry
+        "#,
+    )
+    .bind(job.id)
+    .bind(job.claim_token())
+    .bind(retryable)
+    .bind(retry_backoff_secs(job.attempts) as f64)
+    .bind(error)
+    .fetch_optional(db)
+    .await?;
+    Ok(requeued.unwrap_or(false))
+}
+
+/// Requeue (or fail, once attempts are exhausted) running jobs whose claim
+/// lapsed because the owning process died.
+async fn requeue_expired_claims(db: &PgPool) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        WITH decision AS (
+            SELECT j.id,
+                   (j.attempts < j.max_attempts AND NOT EXISTS (
+                       SELECT 1 FROM scan_jobs q
+                       WHERE q.artifact_id = j.artifact_id AND q.status = 'queued'
+                   )) AS retry
+            FROM scan_jobs j
+            WHERE j.status = 'running' AND j.claim_expires_at < NOW()
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE scan_jobs j
+        SET status = CASE WHEN d.retry THEN 'queued' ELSE 'failed' END,
+            available_at = NOW(),
+            finished_at = CASE WHEN d.retry THEN NULL ELSE NOW() END,
+            last_error = 'scan worker lost its claim (process exited mid-scan)',
+            claim_token = NULL,
+            claimed_by = NULL,
+            claim_expires_at = NULL
+        FROM decision d
+        WHERE j.id = d.id
+        "#,
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+async fn prune_finished(db: &PgPool) -> Result<u64> {
+    let result = sqlx::query(
+        "DELETE FROM scan_jobs \
+         WHERE status IN ('completed', 'failed') \
+           AND finished_at < NOW() - make_interval(days => $1)",
+    )
+    .bind(FINISHED_JOB_RETENTION_DAYS)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Delay before retry `attempts + 1`: exponential from
+/// [`RETRY_BACKOFF_BASE_SECS`], capped at [`RETRY_BACKOFF_MAX_SECS`].
+fn retry_backoff_secs(attempts: i32) -> u64 {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    RETRY_BACKOFF_BASE_SECS
+        .saturating_mul(1u64 << exponent)
+        .min(RETRY_BACKOFF_MAX_SECS)
+}
+
+// ---------------------------------------------------------------------------
+// Workers
+// ---------------------------------------------------------------------------
+
+/// How one attempt at a job ended.
+#[derive(Debug)]
+enum JobOutcome {
+    Completed,
+    Failed(AppError),
+    TimedOut,
+    /// The scan task panicked.
+    Crashed(String),
+}
+
+impl JobOutcome {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Failed(_) => "failed",
+            Self::TimedOut => "timed_out",
+            Self::Crashed(_) => "crashed",
+        }
+    }
+
+    /// Whether another attempt could succeed. Timeouts, crashes and scanner or
+    /// storage errors are worth retrying; a missing artifact or invalid
+    /// request is not.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Completed => false,
+            Self::TimedOut | Self::Crashed(_) => true,
+            Self::Failed(e) => !matches!(
+                e,
+                AppError::NotFound(_) | AppError::Validation(_) | AppError::Authorization(_)
+            ),
+        }
+    }
+
+    fn error_message(&self, timeout: Duration) -> String {
+        match self {
+            Self::Completed => String::new(),
+            Self::Failed(e) => e.to_string(),
+            Self::TimedOut => format!("scan timed out after {}s", timeout.as_secs()),
+            Self::Crashed(reason) => format!("scanner crashed: {reason}"),
+        }
+    }
+}
+
+/// Run the scan on its own task so a panic is contained and a timeout can
+/// abort it. An aborted scan leaves its `scan_results` rows `running`; the
+/// stuck-scan janitor reaps them.
+async fn execute_job(scanner: Arc<ScannerService>, job: &ScanJob, timeout: Duration) -> JobOutcome {
+    let artifact_id = job.artifact_id;
+    let (force, bypass_dedup) = (job.force, job.bypass_dedup);
+    let prepared = job.prepared_map();
+    let mut handle = tokio::spawn(async move {
+        match prepared {
+            Some(prepared) => {
+                scanner
+                    .scan_artifact_with_prepared(artifact_id, prepared, force, bypass_dedup)
+                    .await
+            }
+            None => {
+                scanner
+                    .scan_artifact_with_options(artifact_id, force, bypass_dedup)
+                    .await
+            }
+        }
+    });
+    match tokio::time::timeout(timeout, &mut handle).await {
+        Ok(Ok(Ok(()))) => JobOutcome::Completed,
+        Ok(Ok(Err(e))) => JobOutcome::Failed(e),
+        Ok(Err(e)) => JobOutcome::Crashed(e.to_string()),
+        Err(_) => {
+            handle.abort();
+            JobOutcome::TimedOut
+        }
+    }
+}
+
+async fn run_job(
+    db: &PgPool,
+    scanner: &Arc<ScannerService>,
+    settings: &QueueSettings,
+    job: Claimed<ScanJob>,
+) {
+    let wait_secs = (Utc::now() - job.enqueued_at).num_milliseconds().max(0) as f64 / 1000.0;
+    let started = Instant::now();
+    let outcome = execute_job(scanner.clone(), &job, settings.job_timeout).await;
+    metrics_service::record_scan_job(
+        &job.trigger_type,
+        outcome.label(),
+        wait_secs,
+        started.elapsed().as_secs_f64(),
+    );
+
+    let finalized = match &outcome {
+        JobOutcome::Completed => complete_job(db, &job).await.map(|_| false),
+        _ => {
+            let error = outcome.error_message(settings.job_timeout);
+            let result = fail_job(db, &job, outcome.is_retryable(), &error).await;
+            if let Ok(requeued) = result {
+                tracing::warn!(
+                    job_id = %job.id,
+                    artifact_id = %job.artifact_id,
+                    attempt = job.attempts,
+                    max_attempts = job.max_attempts,
+                    requeued,
+                    error = %error,
+                    "Scan job {}",
+                    outcome.label()
+                );
+            }
+            result
+        }
+    };
+    if let Err(e) = finalized {
+        tracing::error!(job_id = %job.id, error = %e, "Failed to finalize scan job");
+    }
+}
+
+async fn worker_loop(db: PgPool, scanner: Arc<ScannerService>, settings: QueueSettings) {
+    let claimed_by = WorkerIdentity::for_process().as_str().to_string();
+    loop {
+        // Register for wake-ups before polling so an enqueue between an empty
+        // poll and the wait is not missed.
+        let notified = queue_notify().notified();
+        match claim_next(&db, &claimed_by, settings.claim_ttl_secs()).await {
+            Ok(Some(job)) => {
+                run_job(&db, &scanner, &settings, job).await;
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to claim scan job"),
+        }
+        tokio::select! {
+            _ = notified => {}
+            _ = tokio::time::sleep(IDLE_POLL_INTERVAL) => {}
+        }
+    }
+}
+
+async fn maintenance_loop(db: PgPool, settings: QueueSettings) {
+    let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+    loop {
+        interval.tick().await;
+        match requeue_expired_claims(&db).await {
+            Ok(0) => {}
+            Ok(n) => {
+                tracing::warn!(jobs = n, "Recovered scan jobs with expired claims");
+                queue_notify().notify_waiters();
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to recover expired scan job claims"),
+        }
+        if let Err(e) = prune_finished(&db).await {
+            tracing::warn!(error = %e, "Failed to prune finished scan jobs");
+        }
+        match queue_stats(&db, &settings).await {
+            Ok(stats) => metrics_service::set_scan_queue_gauges(&stats),
+            Err(e) => tracing::warn!(error = %e, "Failed to read scan queue stats"),
+        }
+    }
+}
+
+/// Start the scan worker pool and its maintenance loop. Called once at
+/// startup after the scanner service is configured.
+pub fn start_workers(db: PgPool, scanner: Arc<ScannerService>) {
+    let settings = QueueSettings::from_env();
+    tracing::info!(
+        workers = settings.workers,
+        job_timeout_secs = settings.job_timeout.as_secs(),
+        max_attempts = settings.max_attempts,
+        "Starting scan queue workers"
+    );
+    for _ in 0..settings.workers {
+        tokio::spawn(worker_loop(db.clone(), scanner.clone(), settings));
+    }
+    tokio::spawn(maintenance_loop(db, settings));
+}
+
+// ---------------------------------------------------------------------------
+// Inspection and priorities
+// ---------------------------------------------------------------------------
+
+pub async fn queue_stats(db: &PgPool, settings: &QueueSettings) -> Result<QueueStats> {
+    let totals = sqlx::query(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE status = 'queued') AS queued,
+            COUNT(*) FILTER (WHERE status = 'running') AS running,
+            COUNT(*) FILTER (WHERE status = 'failed'
+                             AND finished_at > NOW() - INTERVAL '24 hours') AS failed,
+            COUNT(*) FILTER (WHERE status = 'completed'
+                             AND finished_at > NOW() - INTERVAL '24 hours') AS completed,
+            EXTRACT(EPOCH FROM NOW() - MIN(enqueued_at)
+                    FILTER (WHERE status = 'queued' AND available_at <= NOW()))::FLOAT8
+                AS oldest_age
+        FROM scan_jobs
+        "#,
+    )
+    .fetch_one(db)
+    .await?;
+
+    let by_trigger = sqlx::query(
+        r#"
+        SELECT trigger_type,
+               COUNT(*) AS queued,
+               EXTRACT(EPOCH FROM NOW() - MIN(enqueued_at)
+                       FILTER (WHERE available_at <= NOW()))::FLOAT8 AS oldest_age
+        FROM scan_jobs
+        WHERE status = 'queued'
+        GROUP BY trigger_type
+        ORDER BY trigger_type
+        "#,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(TriggerDepth {
+            trigger_type: row.try_get("trigger_type")?,
+            queued: row.try_get("queued")?,
+            oldest_age_seconds: row.try_get("oldest_age")?,
+        })
+    })
+    .collect::<std::result::Result<Vec<_>, sqlx::Error>>()?;
+
+    Ok(QueueStats {
+        queued: totals.try_get("queued")?,
+        running: totals.try_get("running")?,
+        failed_last_24h: totals.try_get("failed")?,
+        completed_last_24h: totals.try_get("completed")?,
+        oldest_queued_age_seconds: totals.try_get("oldest_age")?,
+        by_trigger,
+        workers_per_replica: settings.workers,
+        job_timeout_secs: settings.job_timeout.as_secs(),
+        max_attempts: settings.max_attempts,
+    })
+}
+
+/// List jobs, newest first, optionally filtered by status.
+pub async fn list_jobs(db: &PgPool, status: Option<&str>, limit: i64) -> Result<Vec<ScanJob>> {
+    if let Some(status) = status {
+        if !matches!(status, "queued" | "running" | "completed" | "failed") {
+            return Err(AppError::Validation(format!(
+                "Unknown scan job status '{status}'"
+            )));
+        }
+    }
+    let jobs = sqlx::query_as::<_, ScanJob>(&format!(
+        "SELECT {SCAN_JOB_COLUMNS} FROM scan_jobs \
+         WHERE ($1::TEXT IS NULL OR status = $1) \
+         ORDER BY CASE status WHEN 'running' THEN 0 WHEN 'queued' THEN 1 ELSE 2 END, \
+                  priority DESC, enqueued_at DESC \
+         LIMIT $2"
+    ))
+    .bind(status)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+    Ok(jobs)
+}
+
+pub async fn list_priorities(db: &PgPool) -> Result<Vec<RepositoryPriority>> {
+    let priorities = sqlx::query_as::<_, RepositoryPriority>(
+        "SELECT p.repository_id, r.key AS repository_key, p.priority, p.updated_at \
+         FROM scan_queue_priorities p \
+         JOIN repositories r ON r.id = p.repository_id \
+         ORDER BY p.priority DESC, r.key",
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(priorities)
+}
+
+/// Set a repository's priority adjustment. Already-queued jobs for the
+/// repository are re-prioritized so the change takes effect immediately.
+pub async fn set_priority(db: &PgPool, repository_id: Uuid, priority: i32) -> Result<()> {
+    if !(-MAX_REPOSITORY_PRIORITY..=MAX_REPOSITORY_PRIORITY).contains(&priority) {
+        return Err(AppError::Validation(format!(
+            "priority must be between -{MAX_REPOSITORY_PRIORITY} and {MAX_REPOSITORY_PRIORITY}"
+        )));
+    }
+    let mut tx = db.begin().await?;
+    let previous: Option<i32> = sqlx::query_scalar(
+        "SELECT priority FROM scan_queue_priorities WHERE repository_id = $1 FOR UPDATE",
+    )
+    .bind(repository_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO scan_queue_priorities (repository_id, priority) VALUES ($1, $2) \
+         ON CONFLICT (repository_id) DO UPDATE SET priority = $2, updated_at = NOW()",
+    )
+    .bind(repository_id)
+    .bind(priority)
+    .execute(&mut *tx)
+    .await?;
+    reprioritize_queued(&mut tx, repository_id, priority - previous.unwrap_or(0)).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn delete_priority(db: &PgPool, repository_id: Uuid) -> Result<()> {
+    let mut tx = db.begin().await?;
+    let previous: Option<i32> = sqlx::query_scalar(
+        "DELETE FROM scan_queue_priorities WHERE repository_id = $1 RETURNING priority",
+    )
+    .bind(repository_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(previous) = previous else {
+        return Err(AppError::NotFound(
+            "No scan priority set for this repository".to_string(),
+        ));
+    };
+    reprioritize_queued(&mut tx, repository_id, -previous).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn reprioritize_queued(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    repository_id: Uuid,
+    delta: i32,
+) -> Result<()> {
+    if delta != 0 {
+        sqlx::query(
+            "UPDATE scan_jobs SET priority = priority + $2 \
+             WHERE repository_id = $1 AND status = 'queued'",
+        )
+        .bind(repository_id)
+        .bind(delta)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_priorities_order_interactive_first() {
+        assert!(ScanTrigger::Manual.base_priority() > ScanTrigger::Rescan.base_priority());
+        assert!(ScanTrigger::Rescan.base_priority() > ScanTrigger::Upload.base_priority());
+        // A maximal repository boost cannot lift a background trigger past
+        // the next class up.
+        assert!(
+            ScanTrigger::Upload.base_priority() + MAX_REPOSITORY_PRIORITY
+                <= ScanTrigger::Rescan.base_priority()
+        );
+    }
+
+    #[test]
+    fn trigger_round_trips() {
+        for trigger in [
+            ScanTrigger::Manual,
+            ScanTrigger::Rescan,
+            ScanTrigger::Upload,
+        ] {
+            assert_eq!(ScanTrigger::parse(trigger.as_str()), Some(trigger));
+        }
+        assert_eq!(ScanTrigger::parse("nightly"), None);
+    }
+
+    #[test]
+    fn retry_backoff_grows_and_caps() {
+        assert_eq!(retry_backoff_secs(1), 30);
+        assert_eq!(retry_backoff_secs(2), 60);
+        assert_eq!(retry_backoff_secs(3), 120);
+        assert_eq!(retry_backoff_secs(10), RETRY_BACKOFF_MAX_SECS);
+        assert_eq!(retry_backoff_secs(i32::MAX), RETRY_BACKOFF_MAX_SECS);
+        assert_eq!(retry_backoff_secs(0), 30);
+    }
+
+    #[test]
+    fn retryable_outcomes() {
+        assert!(JobOutcome::TimedOut.is_retryable());
+        assert!(JobOutcome::Crashed("panic".into()).is_retryable());
+        assert!(JobOutcome::Failed(AppError::Storage("io".into())).is_retryable());
+        assert!(JobOutcome::Failed(AppError::Internal("trivy".into())).is_retryable());
+        assert!(!JobOutcome::Failed(AppError::NotFound("gone".into())).is_retryable());
+        assert!(!JobOutcome::Failed(AppError::Validation("bad".into())).is_retryable());
+        assert!(!JobOutcome::Completed.is_retryable());
+    }
+}
@@ -11,6 +11,7 @@ use crate::models::security::{
     Severity,
 };
 use crate::services::audit_service::{AuditAction, AuditEntry, ResourceType};
+use crate::services::vulnerability_service;
 
 /// Default cap on rows the stuck-scan janitor reaps in a single tick.
 /// Bounds memory for the `UPDATE ... RETURNING` payload and the audit-
@@ -1033,6 +1034,7 @@ impl ScanResultService {
         .execute(&mut *tx)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
+        vulnerability_service::link_scan_findings(&mut tx, new_scan.id).await?;
 
         tx.commit()
             .await
@@ -1172,6 +1174,7 @@ impl ScanResultService {
         .execute(&mut *tx)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
+        vulnerability_service::link_scan_findings(&mut tx, target_scan_id).await?;
 
         tx.commit()
             .await
@@ -1575,6 +1578,7 @@ impl ScanResultService {
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        vulnerability_service::link_scan_findings_logged(&self.db, scan_result_id).await;
         Ok(())
     }
 
@@ -1677,7 +1681,7 @@ impl ScanResultService {
         offset: i64,
         limit: i64,
     ) -> Result<(Vec<ScanFinding>, i64)> {
-        let findings = sqlx::query_as!(
+        let mut findings = sqlx::query_as!(
             ScanFinding,
             r#"
             SELECT id, scan_result_id, artifact_id, severity, title, description,
@@ -1713,6 +1717,7 @@ impl ScanResultService {
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        vulnerability_service::hydrate_finding_descriptions(&self.db, &mut findings).await?;
         Ok((findings, total))
     }
 
@@ -1723,7 +1728,7 @@ impl ScanResultService {
         user_id: Uuid,
         reason: &str,
     ) -> Result<ScanFinding> {
-        let finding = sqlx::query_as!(
+        let mut finding = sqlx::query_as!(
             ScanFinding,
             r#"
             UPDATE scan_findings
@@ -1744,12 +1749,18 @@ impl ScanResultService {
         .map_err(|e| AppError::Database(e.to_string()))?
         .ok_or_else(|| AppError::NotFound("Finding not found".to_string()))?;
 
+        vulnerability_service::sync_acknowledgment(&self.db, &finding).await?;
+        vulnerability_service::hydrate_finding_descriptions(
+            &self.db,
+            std::slice::from_mut(&mut finding),
+        )
+        .await?;
         Ok(finding)
     }
 
     /// Revoke acknowledgment of a finding.
     pub async fn revoke_acknowledgment(&self, finding_id: Uuid) -> Result<ScanFinding> {
-        let finding = sqlx::query_as!(
+        let mut finding = sqlx::query_as!(
             ScanFinding,
             r#"
             UPDATE scan_findings
@@ -1768,6 +1779,12 @@ impl ScanResultService {
         .map_err(|e| AppError::Database(e.to_string()))?
         .ok_or_else(|| AppError::NotFound("Finding not found".to_string()))?;
 
+        vulnerability_service::sync_acknowledgment(&self.db, &finding).await?;
+        vulnerability_service::hydrate_finding_descriptions(
+            &self.db,
+            std::slice::from_mut(&mut finding),
+        )
+        .await?;
         Ok(finding)
     }
 
@@ -33,6 +33,8 @@ use crate::models::user::User;
 use crate::services::auth_service::AuthService;
 use crate::services::grype_scanner::GrypeScanner;
 use crate::services::image_scanner::ImageScanner;
+use crate::services::layer_scan_cache;
+use crate::services::repo_scan_config_service::{RepoScanConfig, RepoScanConfigService};
 use crate::services::scan_config_service::ScanConfigService;
 use crate::services::scan_result_service::ScanResultService;
 use crate::services::trivy_fs_scanner::TrivyFsScanner;
@@ -133,7 +135,7 @@ pub(crate) fn sanitize_artifact_filename(name: &str) -> String {
 ///
 /// Common formats (pypi, npm, maven, etc.) get their standard purl type.
 /// Unknown formats fall back to `"generic"`.
-fn format_to_purl_type(format: &str) -> &'static str {
+pub(crate) fn format_to_purl_type(format: &str) -> &'static str {
     match format.to_lowercase().as_str() {
         "pypi" => "pypi",
         "npm" => "npm",
@@ -1016,7 +1018,7 @@ const DEFAULT_MAX_SCAN_EXTRACTED_BYTES: u64 = 2 * 1024 * 1024 * 1024;
 
 /// Maximum number of archive entries extracted into a scan workspace. Bounds
 /// inode-exhaustion bombs (millions of tiny files).
-const MAX_SCAN_EXTRACTED_ENTRIES: u64 = 200_000;
+pub(crate) const MAX_SCAN_EXTRACTED_ENTRIES: u64 = 200_000;
 
 /// Env var overriding the extracted-tree byte ceiling. The value is a plain
 /// decimal byte count (e.g. `4294967296` for 4 GiB).
@@ -1027,7 +1029,7 @@ const MAX_SCAN_EXTRACTED_BYTES_ENV: &str = "MAX_SCAN_EXTRACTED_BYTES";
 /// scan-workspace byte cap and the concurrent-extraction cap so the identical
 /// parse/filter logic is written once (a zero cap in either dimension would
 /// wedge scanning, so it is treated as "unset").
-fn positive_env_or(name: &str, default: u64) -> u64 {
+pub(crate) fn positive_env_or(name: &str, default: u64) -> u64 {
     std::env::var(name)
         .ok()
         .and_then(|s| s.trim().parse::<u64>().ok())
@@ -1039,7 +1041,7 @@ fn positive_env_or(name: &str, default: u64) -> u64 {
 /// [`MAX_SCAN_EXTRACTED_BYTES_ENV`] over [`DEFAULT_MAX_SCAN_EXTRACTED_BYTES`].
 /// A blank, non-numeric, or zero override is treated as "unset" (a zero cap
 /// would reject every archive).
-fn max_scan_extracted_bytes() -> u64 {
+pub(crate) fn max_scan_extracted_bytes() -> u64 {
     positive_env_or(
         MAX_SCAN_EXTRACTED_BYTES_ENV,
         DEFAULT_MAX_SCAN_EXTRACTED_BYTES,
@@ -1262,7 +1264,7 @@ async fn acquire_scan_extraction_permit(repository_id: Uuid) -> ScanExtractionPe
 /// decompression bomb. On success the written byte count is subtracted from
 /// `*remaining`. Shared by the tar and zip extractors so the counting copy is
 /// written once.
-fn copy_entry_bounded<R: std::io::Read, W: std::io::Write>(
+pub(crate) fn copy_entry_bounded<R: std::io::Read, W: std::io::Write>(
     reader: R,
     mut writer: W,
     remaining: &mut u64,
@@ -1957,6 +1959,10 @@ pub struct ScanTarget<'a> {
     /// that case the gate falls back to the path/content-type predicate and
     /// must never flip an artifact applicable→not-applicable (#1971).
     pub manifest_body: Option<&'a [u8]>,
+    /// Extra engine arguments from the repository's `repo_scan_config`,
+    /// already validated against the engine's allowlist. CLI-backed scanners
+    /// append them to their invocation; the others ignore them.
+    pub scanner_args: &'a [String],
 }
 
 /// A pluggable vulnerability scanner.
@@ -3193,9 +3199,158 @@ impl Scanner for DependencyScanner {
 // Scanner orchestrator
 // ---------------------------------------------------------------------------
 
+/// Sidecar endpoints of the scanners that run against a service rather than
+/// a bundled binary. Reloadable at runtime, see
+/// [`ScannerService::reload_endpoints`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScannerEndpoints {
+    pub trivy_url: Option<String>,
+    pub trivy_adapter_url: Option<String>,
+    pub openscap_url: Option<String>,
+    pub openscap_profile: String,
+}
+
+impl ScannerEndpoints {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            trivy_url: config.trivy_url.clone(),
+            trivy_adapter_url: config.trivy_adapter_url.clone(),
+            openscap_url: config.openscap_url.clone(),
+            openscap_profile: config.openscap_profile.clone(),
+        }
+    }
+}
+
+/// Per-repo token minter of the scanners that pull images (#2093), kept so
+/// a reload can wire the rebuilt image scanner the same way.
+#[derive(Clone)]
+struct ScanTokenMinter {
+    auth: Arc<AuthService>,
+    identity: User,
+    ttl_seconds: i64,
+}
+
+/// Scan types of the scanners built from [`ScannerEndpoints`].
+const ENDPOINT_SCAN_TYPES: [&str; 4] = ["image", "filesystem", "incus", "openscap"];
+
+/// Build the scanners that talk to a sidecar: the trivy image, filesystem
+/// and incus scanners, which run before grype, and the OpenSCAP compliance
+/// scanner, which runs last.
+fn build_endpoint_scanners(
+    endpoints: &ScannerEndpoints,
+    token_minter: Option<&ScanTokenMinter>,
+    scan_workspace_path: &str,
+) -> (Vec<Arc<dyn Scanner>>, Option<Arc<dyn Scanner>>) {
+    let mut trivy: Vec<Arc<dyn Scanner>> = Vec::new();
+
+    // Container *image* scanner: Harbor scanner-adapter (#2088). Registered
+    // ONLY when an adapter URL is configured. When unset, no trivy/image
+    // scan row is produced at all (grype still runs) — we do not claim to
+    // have run trivy on images.
+    if let Some(adapter_url) = endpoints.trivy_adapter_url.clone() {
+        info!(
+            "Container image scanner (Harbor adapter) enabled at {}",
+            adapter_url
+        );
+        let mut image_scanner = ImageScanner::new(adapter_url);
+        // Wire the per-repo token minter so the adapter can pull private
+        // images (#2093). Only when the scanner identity is loaded;
+        // otherwise pulls stay anonymous (public repos only).
+        if let Some(minter) = token_minter {
+            image_scanner = image_scanner.with_token_minter(
+                minter.auth.clone(),
+                minter.identity.clone(),
+                minter.ttl_seconds,
+            );
+        }
+        trivy.push(Arc::new(image_scanner));
+    }
+
+    // Trivy filesystem + incus (rootfs) scanners. Preferred wiring (#2363):
+    // when TRIVY_ADAPTER_URL is set, they route through the scanner-adapter's
+    // filesystem endpoint over HTTP — the backend still prepares/hardens the
+    // workspace locally, tars it, and uploads it, so the hardened image stays
+    // CLI-free (#2059). Legacy TRIVY_URL deployments that bundle the trivy
+    // binary keep the local-CLI path (`--server` then standalone) unchanged.
+    match (
+        endpoints.trivy_adapter_url.clone(),
+        endpoints.trivy_url.clone(),
+    ) {
+        (Some(adapter_url), _) => {
+            info!(
+                "Trivy filesystem scanner enabled (scanner-adapter at {})",
+                adapter_url
+            );
+            trivy.push(Arc::new(TrivyFsScanner::new_with_adapter(
+                adapter_url.clone(),
+                scan_workspace_path.to_string(),
+            )));
+            info!("Incus container image scanner enabled (scanner-adapter)");
+            trivy.push(Arc::new(
+                crate::services::incus_scanner::IncusScanner::new_with_adapter(
+                    adapter_url,
+                    scan_workspace_path.to_string(),
+                ),
+            ));
+        }
+        (None, Some(url)) => {
+            info!("Trivy filesystem scanner enabled (local CLI)");
+            trivy.push(Arc::new(TrivyFsScanner::new(
+                url.clone(),
+                scan_workspace_path.to_string(),
+            )));
+            info!("Incus container image scanner enabled (local CLI)");
+            trivy.push(Arc::new(crate::services::incus_scanner::IncusScanner::new(
+                url,
+                scan_workspace_path.to_string(),
+            )));
+        }
+        (None, None) => {}
+    }
+
+    // OpenSCAP compliance scanner (optional sidecar)
+    let openscap = endpoints.openscap_url.clone().map(|url| {
+        info!("OpenSCAP compliance scanner enabled at {}", url);
+        Arc::new(crate::services::openscap_scanner::OpenScapScanner::new(
+            url,
+            endpoints.openscap_profile.clone(),
+            scan_workspace_path.to_string(),
+        )) as Arc<dyn Scanner>
+    });
+
+    (trivy, openscap)
+}
+
+/// Replace the endpoint scanners in `current` with freshly built ones,
+/// keeping the registration order of [`ScannerService::new`]: trivy
+/// scanners before grype (or at the end without grype), OpenSCAP last.
+fn splice_endpoint_scanners(
+    current: &[Arc<dyn Scanner>],
+    trivy: Vec<Arc<dyn Scanner>>,
+    openscap: Option<Arc<dyn Scanner>>,
+) -> Vec<Arc<dyn Scanner>> {
+    let mut trivy = Some(trivy);
+    let mut scanners = Vec::with_capacity(current.len() + 4);
+    for scanner in current {
+        if ENDPOINT_SCAN_TYPES.contains(&scanner.scan_type()) {
+            continue;
+        }
+        if scanner.scan_type() == "grype" {
+            scanners.extend(trivy.take().unwrap_or_default());
+        }
+        scanners.push(scanner.clone());
+    }
+    scanners.extend(trivy.take().unwrap_or_default());
+    scanners.extend(openscap);
+    scanners
+}
+
 pub struct ScannerService {
     db: PgPool,
-    scanners: Vec<Arc<dyn Scanner>>,
+    /// Swapped as a whole by [`Self::reload_endpoints`]; scans work on a
+    /// snapshot taken when they start.
+    scanners: std::sync::RwLock<Vec<Arc<dyn Scanner>>>,
+    scan_token_minter: Option<ScanTokenMinter>,
     scan_result_service: Arc<ScanResultService>,
     scan_config_service: Arc<ScanConfigService>,
     #[allow(dead_code)]
@@ -3206,6 +3361,9 @@ pub struct ScannerService {
     scan_workspace_path: String,
     dependency_track:
         Option<Arc<crate::services::dependency_track_service::DependencyTrackService>>,
+    /// Publishes `scan.started` / `scan.completed` / `scan.failed` so chat
+    /// integrations can thread a scan's messages together.
+    event_bus: Option<Arc<crate::services::event_bus::EventBus>>,
 }
 
 impl ScannerService {
@@ -3232,68 +3390,23 @@ impl ScannerService {
         scan_identity: Option<User>,
         scan_token_ttl_seconds: u64,
     ) -> Self {
-        let scan_token_ttl_seconds = scan_token_ttl_seconds as i64;
+        let scan_token_minter = scan_identity.map(|identity| ScanTokenMinter {
+            auth,
+            identity,
+            ttl_seconds: scan_token_ttl_seconds as i64,
+        });
+        let endpoints = ScannerEndpoints {
+            trivy_url,
+            trivy_adapter_url,
+            openscap_url,
+            openscap_profile,
+        };
+        let (trivy_scanners, openscap_scanner) =
+            build_endpoint_scanners(&endpoints, scan_token_minter.as_ref(), &scan_workspace_path);
+
         let dep_scanner: Arc<dyn Scanner> = Arc::new(DependencyScanner::new(advisory_client));
         let mut scanners: Vec<Arc<dyn Scanner>> = vec![dep_scanner];
-
-        // Container *image* scanner: Harbor scanner-adapter (#2088). Registered
-        // ONLY when an adapter URL is configured. When unset, no trivy/image
-        // scan row is produced at all (grype still runs) — we do not claim to
-        // have run trivy on images.
-        if let Some(adapter_url) = trivy_adapter_url.clone() {
-            info!(
-                "Container image scanner (Harbor adapter) enabled at {}",
-                adapter_url
-            );
-            let mut image_scanner = ImageScanner::new(adapter_url);
-            // Wire the per-repo token minter so the adapter can pull private
-            // images (#2093). Only when the scanner identity is loaded;
-            // otherwise pulls stay anonymous (public repos only).
-            if let Some(identity) = scan_identity.clone() {
-                image_scanner =
-                    image_scanner.with_token_minter(auth.clone(), identity, scan_token_ttl_seconds);
-            }
-            scanners.push(Arc::new(image_scanner));
-        }
-
-        // Trivy filesystem + incus (rootfs) scanners. Preferred wiring (#2363):
-        // when TRIVY_ADAPTER_URL is set, they route through the scanner-adapter's
-        // filesystem endpoint over HTTP — the backend still prepares/hardens the
-        // workspace locally, tars it, and uploads it, so the hardened image stays
-        // CLI-free (#2059). Legacy TRIVY_URL deployments that bundle the trivy
-        // binary keep the local-CLI path (`--server` then standalone) unchanged.
-        match (trivy_adapter_url, trivy_url) {
-            (Some(adapter_url), _) => {
-                info!(
-                    "Trivy filesystem scanner enabled (scanner-adapter at {})",
-                    adapter_url
-                );
-                scanners.push(Arc::new(TrivyFsScanner::new_with_adapter(
-                    adapter_url.clone(),
-                    scan_workspace_path.clone(),
-                )));
-                info!("Incus container image scanner enabled (scanner-adapter)");
-                scanners.push(Arc::new(
-                    crate::services::incus_scanner::IncusScanner::new_with_adapter(
-                        adapter_url,
-                        scan_workspace_path.clone(),
-                    ),
-                ));
-            }
-            (None, Some(url)) => {
-                info!("Trivy filesystem scanner enabled (local CLI)");
-                scanners.push(Arc::new(TrivyFsScanner::new(
-                    url.clone(),
-                    scan_workspace_path.clone(),
-                )));
-                info!("Incus container image scanner enabled (local CLI)");
-                scanners.push(Arc::new(crate::services::incus_scanner::IncusScanner::new(
-                    url,
-                    scan_workspace_path.clone(),
-                )));
-            }
-            (None, None) => {}
-        }
+        scanners.extend(trivy_scanners);
 
         // Grype scanner (CLI-based). Unlike the trivy filesystem/incus scanners
         // — whose engine is optional and, when absent, yields `not_applicable`
@@ -3305,27 +3418,20 @@ impl ScannerService {
         let mut grype_scanner = GrypeScanner::new(scan_workspace_path.clone());
         // Wire the per-repo token minter so grype's registry pull is
         // authenticated for private images (#2093).
-        if let Some(identity) = scan_identity.clone() {
-            grype_scanner =
-                grype_scanner.with_token_minter(auth.clone(), identity, scan_token_ttl_seconds);
+        if let Some(minter) = &scan_token_minter {
+            grype_scanner = grype_scanner.with_token_minter(
+                minter.auth.clone(),
+                minter.identity.clone(),
+                minter.ttl_seconds,
+            );
         }
         scanners.push(Arc::new(grype_scanner));
-
-        // OpenSCAP compliance scanner (optional sidecar)
-        if let Some(url) = openscap_url {
-            info!("OpenSCAP compliance scanner enabled at {}", url);
-            scanners.push(Arc::new(
-                crate::services::openscap_scanner::OpenScapScanner::new(
-                    url,
-                    openscap_profile,
-                    scan_workspace_path.clone(),
-                ),
-            ));
-        }
+        scanners.extend(openscap_scanner);
 
         Self {
             db,
-            scanners,
+            scanners: std::sync::RwLock::new(scanners),
+            scan_token_minter,
             scan_result_service,
             scan_config_service,
             storage,
@@ -3333,9 +3439,30 @@ impl ScannerService {
             storage_base_path,
             scan_workspace_path,
             dependency_track: None,
+            event_bus: None,
         }
     }
 
+    /// Rebuild the sidecar-backed scanners against new endpoints. Scans
+    /// already running keep the scanners they started with.
+    pub fn reload_endpoints(&self, endpoints: &ScannerEndpoints) {
+        let (trivy, openscap) = build_endpoint_scanners(
+            endpoints,
+            self.scan_token_minter.as_ref(),
+            &self.scan_workspace_path,
+        );
+        let mut scanners = self.scanners.write().unwrap_or_else(|e| e.into_inner());
+        *scanners = splice_endpoint_scanners(&scanners, trivy, openscap);
+    }
+
+    /// Snapshot of the registered scanners.
+    fn scanners(&self) -> Vec<Arc<dyn Scanner>> {
+        self.scanners
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
     /// Set the Dependency-Track service for SBOM submission after scans.
     pub fn set_dependency_track(
         &mut self,
@@ -3344,6 +3471,37 @@ impl ScannerService {
         self.dependency_track = Some(dt);
     }
 
+    /// Set the EventBus used to publish scan lifecycle events.
+    pub fn set_event_bus(&mut self, event_bus: Arc<crate::services::event_bus::EventBus>) {
+        self.event_bus = Some(event_bus);
+    }
+
+    /// Run [`Self::scan_artifact_inner`] and, when it got far enough to
+    /// publish `scan.started`, follow up with `scan.completed` or
+    /// `scan.failed`. Scans skipped before starting (repo scanning disabled,
+    /// missing artifact) publish nothing.
+    async fn scan_artifact_observed(
+        &self,
+        artifact_id: Uuid,
+        force: bool,
+        bypass_dedup: bool,
+        prepared: Option<HashMap<String, Uuid>>,
+    ) -> Result<()> {
+        let started = OnceLock::new();
+        let result = self
+            .scan_artifact_inner(artifact_id, force, bypass_dedup, prepared, &started)
+            .await;
+        if let (Some(bus), Some(repository_id)) = (&self.event_bus, started.get()) {
+            let event_type = if result.is_ok() {
+                "scan.completed"
+            } else {
+                "scan.failed"
+            };
+            bus.emit_for_repo(event_type, artifact_id, *repository_id, None);
+        }
+        result
+    }
+
     /// Synchronously create one placeholder `running` scan_result row per
     /// configured scanner for the given artifact, returning the row IDs.
     ///
@@ -3395,6 +3553,10 @@ impl ScannerService {
             return Ok(vec![]);
         }
 
+        // Engine selection (`repo_scan_config`): placeholders are only created
+        // for the scanners the worker will actually run.
+        let engine_config = self.repo_scan_config(artifact.repository_id).await?;
+
         // #1373: short-circuit when this artifact already has a completed
         // scan for the same bytes + scan_type. Without this check, every
         // trigger_scan call on an already-scanned artifact inserts a new
@@ -3407,8 +3569,9 @@ impl ScannerService {
         // The check is per-scanner so a partially-completed scan set (e.g.
         // trivy completed, grype still running from a prior trigger) still
         // gets the missing scanner queued normally.
-        let mut prepared = Vec::with_capacity(self.scanners.len());
-        for scanner in &self.scanners {
+        let selected = self.selected_scanners(engine_config.as_ref());
+        let mut prepared = Vec::with_capacity(selected.len());
+        for scanner in selected {
             if bypass_dedup {
                 // When the caller asked to bypass dedup, skip the dedup
                 // lookup entirely and always insert a fresh placeholder. We
@@ -3475,7 +3638,7 @@ impl ScannerService {
         force: bool,
         bypass_dedup: bool,
     ) -> Result<()> {
-        self.scan_artifact_inner(artifact_id, force, bypass_dedup, Some(prepared))
+        self.scan_artifact_observed(artifact_id, force, bypass_dedup, Some(prepared))
             .await
     }
 
@@ -3491,7 +3654,7 @@ impl ScannerService {
         force: bool,
         bypass_dedup: bool,
     ) -> Result<()> {
-        self.scan_artifact_inner(artifact_id, force, bypass_dedup, None)
+        self.scan_artifact_observed(artifact_id, force, bypass_dedup, None)
             .await
     }
 
@@ -3501,6 +3664,7 @@ impl ScannerService {
         force: bool,
         bypass_dedup: bool,
         prepared: Option<HashMap<String, Uuid>>,
+        started: &OnceLock<Uuid>,
     ) -> Result<()> {
         // Fetch artifact and content
         let artifact = sqlx::query_as!(
@@ -3555,6 +3719,34 @@ impl ScannerService {
             return Ok(());
         }
 
+        // Per-repository engine selection, ignore file and scanner arguments.
+        // A configuration with no engines (raw binary repositories) turns the
+        // scan off even when forced: there is nothing the operator wants run.
+        let engine_config = self.repo_scan_config(artifact.repository_id).await?;
+        if engine_config.as_ref().is_some_and(|c| c.engines.is_empty()) {
+            info!(
+                "No scan engines configured for repository {}, skipping artifact {}",
+                artifact.repository_id, artifact_id
+            );
+            // Placeholders prepared before the engines were switched off.
+            for target_id in prepared.unwrap_or_default().into_values() {
+                self.mark_engine_disabled(target_id).await;
+            }
+            return Ok(());
+        }
+        let ignore_rules = engine_config
+            .as_ref()
+            .map(|c| c.ignore_rules(chrono::Utc::now().date_naive()))
+            .unwrap_or_default();
+        let customized = engine_config
+            .as_ref()
+            .is_some_and(RepoScanConfig::customizes_results);
+
+        if let Some(bus) = &self.event_bus {
+            bus.emit_for_repo("scan.started", artifact_id, artifact.repository_id, None);
+            let _ = started.set(artifact.repository_id);
+        }
+
         // #2540: bound total in-flight scan-workspace extractions across ALL
         // scan-trigger paths (security.rs trigger_scan, artifact_service
         // auto-scan-on-upload, incus scan, admin rescan). Each in-flight
@@ -3612,14 +3804,44 @@ impl ScannerService {
             // share the image manifest mediaType. Only meaningful for OCI
             // manifest artifacts; the gate ignores it for everything else.
             manifest_body: is_oci_image_artifact(&artifact).then(|| content.as_ref()),
+            scanner_args: &[],
+        };
+
+        // Ordered layer digests of image artifacts, for the layer-digest
+        // scan cache (see `layer_scan_cache`).
+        let image_layers = match target.manifest_body {
+            Some(body) => layer_scan_cache::image_layers(storage.as_ref(), &artifact, body).await,
+            None => None,
         };
+        let layer_stack_key = image_layers.as_deref().map(layer_scan_cache::stack_key);
 
-        for scanner in &self.scanners {
+        for scanner in &self.scanners() {
             // Take any pre-allocated row id committed by the trigger handler.
             // The id was already returned to the client in TriggerScanResponse,
             // so we must keep the same row alive (UPDATE rather than INSERT).
             let prepared_action = resolve_prepared_action(prepared.remove(scanner.scan_type()));
 
+            // Engines deselected in `repo_scan_config` are skipped outright:
+            // unlike a non-applicable scanner they leave no row behind, as if
+            // the scanner were not registered. Only a placeholder prepared
+            // before the configuration changed needs closing.
+            if !engine_config
+                .as_ref()
+                .is_none_or(|c| c.selects(scanner.scan_type()))
+            {
+                if let PreparedScanAction::Reuse(target_id) = prepared_action {
+                    self.mark_engine_disabled(target_id).await;
+                }
+                continue;
+            }
+            let target = ScanTarget {
+                scanner_args: engine_config
+                    .as_ref()
+                    .map(|c| c.args_for(scanner.scan_type()))
+                    .unwrap_or(&[]),
+                ..target
+            };
+
             // Gate on applicability BEFORE creating a scan_results row or
             // copying a reusable result. A non-applicable scanner must leave
             // no `completed, findings_count=0` row behind — that row is
@@ -3692,10 +3914,15 @@ impl ScannerService {
             // The bypass_dedup flag (#1469) short-circuits this so the explicit
             // "rescan now" path cannot be silently fed a cached result that was
             // exactly what the caller was trying to escape from.
-            let reusable = if bypass_dedup {
+            //
+            // A repository whose ignore file or scanner arguments shape its
+            // results neither takes another repository's result nor hands its
+            // own to one.
+            let reusable = if bypass_dedup || customized {
                 None
             } else {
-                self.scan_result_service
+                match self
+                    .scan_result_service
                     .find_reusable_scan(
                         checksum,
                         scanner.scan_type(),
@@ -3705,6 +3932,20 @@ impl ScannerService {
                     .await
                     .ok()
                     .flatten()
+                {
+                    Some(source)
+                        if source.repository_id != artifact.repository_id
+                            && self
+                                .repo_scan_config(source.repository_id)
+                                .await
+                                .ok()
+                                .flatten()
+                                .is_some_and(|c| c.customizes_results()) =>
+                    {
+                        None
+                    }
+                    other => other,
+                }
             };
             if let Some(source_scan) = reusable {
                 // #1373: when the matched source scan is for THIS artifact,
@@ -3812,6 +4053,85 @@ impl ScannerService {
                 }
             }
 
+            // Same layer stack under a different manifest checksum (re-tag,
+            // copy to another repository, rebuilt config or annotations):
+            // the image scanners would produce the same result, so reuse it.
+            let layer_cached = layer_scan_cache::is_layer_cached(scanner.scan_type());
+            let layer_source = match &layer_stack_key {
+                Some(key) if layer_cached && !bypass_dedup => layer_scan_cache::find_reusable_scan(
+                    &self.db,
+                    key,
+                    scanner.scan_type(),
+                    artifact_id,
+                    DEDUP_TTL_DAYS,
+                    ZERO_FINDINGS_DEDUP_TTL_DAYS,
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    warn!(
+                        "Layer scan cache lookup failed for artifact {}: {}",
+                        artifact_id, e
+                    );
+                    None
+                }),
+                _ => None,
+            };
+            if let Some(source_id) = layer_source {
+                let copied = match prepared_action {
+                    PreparedScanAction::Reuse(target_id) => {
+                        self.scan_result_service
+                            .convert_to_reused(target_id, source_id, artifact_id)
+                            .await
+                    }
+                    PreparedScanAction::InsertFresh => {
+                        self.scan_result_service
+                            .copy_scan_results(
+                                source_id,
+                                artifact_id,
+                                artifact.repository_id,
+                                scanner.scan_type(),
+                                checksum,
+                            )
+                            .await
+                    }
+                };
+                match copied {
+                    Ok(reused) => {
+                        info!(
+                            "Reusing scan results from {} for artifact {}: identical layer stack (scanner={}, layers={})",
+                            source_id,
+                            artifact_id,
+                            scanner.name(),
+                            image_layers.as_ref().map_or(0, Vec::len),
+                        );
+                        self.update_quarantine_status(artifact_id, reused.findings_count)
+                            .await?;
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to copy layer-cached scan results from {}: {}. Running fresh scan.",
+                            source_id, e
+                        );
+                    }
+                }
+            }
+            if let (Some(layers), true) = (&image_layers, layer_cached) {
+                // The scanner keeps its own per-layer analysis cache keyed by
+                // the same digests; report how much of this image it has seen.
+                if let Ok(known) =
+                    layer_scan_cache::known_layer_count(&self.db, scanner.scan_type(), layers).await
+                {
+                    info!(
+                        "Scanning artifact {} with {}: {}/{} layers previously analyzed",
+                        artifact_id,
+                        scanner.name(),
+                        known,
+                        layers.len(),
+                    );
+                }
+            }
+
             // Either reuse path failed or no reusable scan: run a fresh scan.
             // If we still have a prepared id, reuse it; otherwise create a row.
             let scan_result = match prepared_action {
@@ -3841,10 +4161,21 @@ impl ScannerService {
                 .await
             {
                 Ok(ScanOutput {
-                    findings,
+                    mut findings,
                     packages,
                     scan_completeness,
                 }) => {
+                    let ignored = ignore_rules.apply(&mut findings);
+                    if ignored > 0 {
+                        info!(
+                            "Dropped {} finding(s) listed in the ignore file of repository {} \
+                             (scanner {}, artifact {})",
+                            ignored,
+                            artifact.repository_id,
+                            scanner.name(),
+                            artifact_id
+                        );
+                    }
                     let total = findings.len() as i32;
                     let count = |sev: Severity| -> i32 {
                         findings.iter().filter(|f| f.severity == sev).count() as i32
@@ -3957,6 +4288,22 @@ impl ScannerService {
                         )
                         .await?;
 
+                    if let (Some(layers), true) = (&image_layers, layer_cached) {
+                        if let Err(e) = layer_scan_cache::record_scan(
+                            &self.db,
+                            scan_result.id,
+                            scanner.scan_type(),
+                            layers,
+                        )
+                        .await
+                        {
+                            warn!(
+                                "Failed to record layer stack for scan {}: {}",
+                                scan_result.id, e
+                            );
+                        }
+                    }
+
                     info!(
                         "Scan {} completed for artifact {}: {} findings ({} critical, {} high), scanner_version={:?}, completeness={}",
                         scanner.name(),
@@ -4614,6 +4961,40 @@ impl ScannerService {
         Ok(Bytes::from_owner(mmap))
     }
 
+    /// The scan engine configuration of a repository (`None`: every engine).
+    async fn repo_scan_config(&self, repository_id: Uuid) -> Result<Option<RepoScanConfig>> {
+        RepoScanConfigService::new(self.db.clone())
+            .get(repository_id)
+            .await
+    }
+
+    /// The registered scanners the repository's engine selection runs.
+    fn selected_scanners(&self, config: Option<&RepoScanConfig>) -> Vec<Arc<dyn Scanner>> {
+        self.scanners()
+            .into_iter()
+            .filter(|s| config.is_none_or(|c| c.selects(s.scan_type())))
+            .collect()
+    }
+
+    /// Close a placeholder row whose engine was switched off after the scan
+    /// was prepared.
+    async fn mark_engine_disabled(&self, scan_result_id: Uuid) {
+        if let Err(e) = self
+            .scan_result_service
+            .mark_not_applicable(
+                scan_result_id,
+                "Scan engine is disabled for this repository",
+                chrono::Utc::now(),
+            )
+            .await
+        {
+            warn!(
+                "Failed to close scan {} of a disabled engine: {}",
+                scan_result_id, e
+            );
+        }
+    }
+
     /// Resolve the storage backend for a given repository by looking up
     /// its storage_backend and storage_path, then delegating to the registry.
     async fn resolve_repo_storage(&self, repository_id: Uuid) -> Result<Arc<dyn StorageBackend>> {
@@ -4840,8 +5221,9 @@ pub(crate) mod test_helpers {
 /// .unwrap_or(false);
 /// ```
 ///
-/// — and passes a closure that calls `ScannerService::scan_artifact` (or no-ops
-/// when `state.scanner_service` is `None`).
+/// — and passes a closure that enqueues an upload-triggered job with
+/// `scan_queue_service::enqueue` (or no-ops when `state.scanner_service` is
+/// `None`).
 ///
 /// When `should_scan` is true, the closure is spawned on a background task so
 /// the upload response isn't blocked by the scanner pipeline; the closure
@@ -5586,6 +5968,7 @@ mod tests {
             db: None,
             storage: None,
             manifest_body: None,
+            scanner_args: &[],
         };
         assert!(oci_target_is_scannable_image(&target));
     }
@@ -5604,6 +5987,7 @@ mod tests {
             db: None,
             storage: None,
             manifest_body: Some(HELM_OCI_MANIFEST_BODY),
+            scanner_args: &[],
         };
         assert!(!oci_target_is_scannable_image(&target));
     }
@@ -12164,6 +12548,76 @@ mod tests {
     // Scanner trait applicability gate (issues #961, #994)
     // -----------------------------------------------------------------------
 
+    /// A scanner identified only by its scan type, for registration-order
+    /// tests.
+    struct TypedScanner(&'static str);
+
+    #[async_trait]
+    impl Scanner for TypedScanner {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn scan_type(&self) -> &str {
+            self.0
+        }
+
+        async fn scan(
+            &self,
+            _artifact: &Artifact,
+            _metadata: Option<&ArtifactMetadata>,
+            _content: &Bytes,
+        ) -> Result<ScanOutput> {
+            Ok(ScanOutput::default())
+        }
+    }
+
+    fn scan_types(scanners: &[Arc<dyn Scanner>]) -> Vec<&str> {
+        scanners.iter().map(|s| s.scan_type()).collect()
+    }
+
+    #[test]
+    fn test_splice_endpoint_scanners_keeps_registration_order() {
+        let current: Vec<Arc<dyn Scanner>> = vec![
+            Arc::new(TypedScanner("dependency")),
+            Arc::new(TypedScanner("filesystem")),
+            Arc::new(TypedScanner("incus")),
+            Arc::new(TypedScanner("grype")),
+        ];
+        let spliced = splice_endpoint_scanners(
+            &current,
+            vec![
+                Arc::new(TypedScanner("image")),
+                Arc::new(TypedScanner("filesystem")),
+                Arc::new(TypedScanner("incus")),
+            ],
+            Some(Arc::new(TypedScanner("openscap"))),
+        );
+        assert_eq!(
+            scan_types(&spliced),
+            [
+                "dependency",
+                "image",
+                "filesystem",
+                "incus",
+                "grype",
+                "openscap"
+            ]
+        );
+
+        // Removing every endpoint leaves only the bundled scanners.
+        let removed = splice_endpoint_scanners(&spliced, Vec::new(), None);
+        assert_eq!(scan_types(&removed), ["dependency", "grype"]);
+    }
+
+    #[test]
+    fn test_splice_endpoint_scanners_appends_without_grype() {
+        let current: Vec<Arc<dyn Scanner>> = vec![Arc::new(TypedScanner("dependency"))];
+        let spliced =
+            splice_endpoint_scanners(&current, vec![Arc::new(TypedScanner("filesystem"))], None);
+        assert_eq!(scan_types(&spliced), ["dependency", "filesystem"]);
+    }
+
     /// Shared test fixtures for the applicability-gate tests below.
     mod applicability_fixtures {
         use super::*;
@@ -12738,6 +13192,7 @@ mod tests {
             db: None,
             storage: None,
             manifest_body: None,
+            scanner_args: &[],
         };
 
         assert!(scanner.is_applicable_for_target(&target));
@@ -12762,6 +13217,7 @@ mod tests {
             db: None,
             storage: None,
             manifest_body: None,
+            scanner_args: &[],
         };
 
         assert!(scanner.is_applicable_for_target(&target));
@@ -12900,7 +13356,8 @@ mod tests {
 
         let scanner = ScannerService {
             db: fx.pool.clone(),
-            scanners: vec![Arc::new(NeverApplicableScanner)],
+            scanners: std::sync::RwLock::new(vec![Arc::new(NeverApplicableScanner)]),
+            scan_token_minter: None,
             scan_result_service: Arc::new(ScanResultService::new(fx.pool.clone())),
             scan_config_service: Arc::new(ScanConfigService::new(fx.pool.clone())),
             storage: fx.state.storage.clone(),
@@ -13031,7 +13488,8 @@ mod tests {
         // ScannerEngineUnavailable, and the orchestrator marks it not_applicable.
         let scanner = ScannerService {
             db: fx.pool.clone(),
-            scanners: vec![Arc::new(EngineUnavailableScanner)],
+            scanners: std::sync::RwLock::new(vec![Arc::new(EngineUnavailableScanner)]),
+            scan_token_minter: None,
             scan_result_service: Arc::new(ScanResultService::new(fx.pool.clone())),
             scan_config_service: Arc::new(ScanConfigService::new(fx.pool.clone())),
             storage: fx.state.storage.clone(),
@@ -13122,7 +13580,8 @@ mod tests {
 
         let scanner = ScannerService {
             db: fx.pool.clone(),
-            scanners: vec![Arc::new(NeverApplicableScanner)],
+            scanners: std::sync::RwLock::new(vec![Arc::new(NeverApplicableScanner)]),
+            scan_token_minter: None,
             scan_result_service: scan_result_service.clone(),
             scan_config_service: Arc::new(ScanConfigService::new(fx.pool.clone())),
             storage: fx.state.storage.clone(),
@@ -13212,7 +13671,10 @@ mod tests {
         let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
         let scanner = ScannerService {
             db: fx.pool.clone(),
-            scanners: vec![Arc::new(RecordingContextScanner { seen: seen.clone() })],
+            scanners: std::sync::RwLock::new(vec![Arc::new(RecordingContextScanner {
+                seen: seen.clone(),
+            })]),
+            scan_token_minter: None,
             scan_result_service: Arc::new(ScanResultService::new(fx.pool.clone())),
             scan_config_service: Arc::new(ScanConfigService::new(fx.pool.clone())),
             storage: fx.state.storage.clone(),
@@ -13991,7 +14453,8 @@ mod tests {
         ) -> ScannerService {
             ScannerService {
                 db: fx.pool.clone(),
-                scanners,
+                scanners: std::sync::RwLock::new(scanners),
+                scan_token_minter: None,
                 scan_result_service: Arc::new(ScanResultService::new(fx.pool.clone())),
                 scan_config_service: Arc::new(ScanConfigService::new(fx.pool.clone())),
                 storage: fx.state.storage.clone(),
@@ -16,8 +16,11 @@ use crate::services::analytics_service::AnalyticsService;
 use crate::services::backup_service::{BackupService, BackupType, CreateBackupRequest};
 use crate::services::event_bus::EventBus;
 use crate::services::health_monitor_service::{HealthMonitorService, MonitorConfig};
+use crate::services::incident_escalation_service::IncidentEscalationService;
+use crate::services::issue_tracker_service::IssueTrackerService;
 use crate::services::lifecycle_service::LifecycleService;
 use crate::services::metrics_service;
+use crate::services::notification_email_service::NotificationEmailService;
 use crate::services::scan_result_service::ScanResultService;
 use crate::services::smtp_service::SmtpService;
 use crate::services::storage_service::StorageService;
@@ -50,7 +53,7 @@ fn jittered_startup_delay(base_secs: u64) -> Duration {
 pub fn spawn_all(
     db: PgPool,
     config: Config,
-    _primary_storage: Arc<dyn crate::storage::StorageBackend>,
+    primary_storage: Arc<dyn crate::storage::StorageBackend>,
     storage_registry: Arc<crate::storage::StorageRegistry>,
     smtp_service: Option<Arc<SmtpService>>,
     event_bus: Arc<EventBus>,
@@ -166,9 +169,14 @@ pub fn spawn_all(
     {
         let db = db.clone();
         let config_clone = config.clone();
+        let smtp = smtp_service.clone();
         tokio::spawn(async move {
             tokio::time::sleep(Duration::from_secs(15)).await;
-            let monitor = HealthMonitorService::new(db, MonitorConfig::default());
+            let mut monitor = HealthMonitorService::new(db.clone(), MonitorConfig::default())
+                .with_incident_escalation(IncidentEscalationService::new(db.clone()));
+            if smtp.is_some() {
+                monitor = monitor.with_email_notifier(NotificationEmailService::new(db, smtp));
+            }
             let mut ticker = interval(Duration::from_secs(60));
 
             loop {
@@ -194,6 +202,40 @@ pub fn spawn_all(
         });
     }
 
+    // Presigned-redirect canary: every replica signs URLs with its own clock
+    // and credentials, so each one probes (not leased).
+    if config.presigned_downloads_enabled && config.presigned_canary_interval_secs > 0 {
+        let db = db.clone();
+        let storage = primary_storage.clone();
+        let smtp = smtp_service.clone();
+        let interval_secs = config.presigned_canary_interval_secs;
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(90)).await;
+            let mut monitor = HealthMonitorService::new(db.clone(), MonitorConfig::default())
+                .with_incident_escalation(IncidentEscalationService::new(db.clone()));
+            if smtp.is_some() {
+                monitor = monitor.with_email_notifier(NotificationEmailService::new(db, smtp));
+            }
+            let mut ticker = interval(Duration::from_secs(interval_secs));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                match monitor.check_presigned_redirect(storage.as_ref()).await {
+                    Ok(Some(entry)) if entry.status != "healthy" => {
+                        tracing::warn!(
+                            "Presigned-redirect canary is {}: {:?}",
+                            entry.status,
+                            entry.message
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Presigned-redirect canary failed: {}", e),
+                }
+            }
+        });
+    }
+
     // Lifecycle policy execution (configurable check interval)
     {
         let db = db.clone();
@@ -229,6 +271,48 @@ pub fn spawn_all(
         });
     }
 
+    // Storage tiering worker (every minute, only with STORAGE_COLD_BACKEND).
+    // Moves artifacts queued by `cold_tier_after_days` policies to the cold
+    // backend and rehydrates the ones a download asked for. The short cadence
+    // bounds how long a client polls on 202 + Retry-After.
+    match crate::services::storage_tiering_service::StorageTieringService::from_config(
+        db.clone(),
+        storage_registry.clone(),
+        &config,
+    ) {
+        Ok(Some(service)) => {
+            tokio::spawn(async move {
+                tokio::time::sleep(jittered_startup_delay(45)).await;
+                let mut ticker = interval(Duration::from_secs(60));
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+                loop {
+                    ticker.tick().await;
+                    match service.run_once().await {
+                        Ok(stats) if !stats.is_empty() => {
+                            tracing::info!(
+                                transitioned = stats.transitioned,
+                                rehydrated = stats.rehydrated,
+                                expired = stats.expired,
+                                purged = stats.purged,
+                                failed = stats.failed,
+                                "Storage tiering pass complete"
+                            );
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            tracing::warn!("Storage tiering pass failed: {}", e);
+                        }
+                    }
+                }
+            });
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("Storage tiering disabled: {}", e);
+        }
+    }
+
     // Stuck-scan janitor (every `stuck_scan_check_interval_secs`, default 10 min).
     //
     // Pre-allocated `scan_results` rows can be left wedged in `status='running'`
@@ -693,6 +777,255 @@ pub fn spawn_all(
         });
     }
 
+    // Audit-log retention sweep (every hour, opt-in via AUDIT_RETENTION_ENABLED).
+    //
+    // Removes audit rows older than the `audit_retention_days` system setting,
+    // exporting them to primary storage first unless AUDIT_ARCHIVE_ENABLED is
+    // turned off. Cluster-leased so only one replica sweeps per tick; the
+    // per-batch row locks keep a concurrent manual /admin/cleanup safe too.
+    if config.audit_retention_enabled {
+        let db = db.clone();
+        let archive = config.audit_archive_enabled;
+        let storage = primary_storage.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(150)).await;
+            let service = crate::services::audit_archive_service::AuditArchiveService::new(
+                db.clone(),
+                storage,
+            );
+            let mut ticker = interval(Duration::from_secs(3600)); // 1 hour
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "audit_retention",
+                    3900.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    tracing::debug!(
+                        "Audit retention: another replica holds the lease; skipping tick"
+                    );
+                    continue;
+                };
+
+                let result = match service.configured_retention_days().await {
+                    Ok(days) => service.apply_retention(days, archive).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(outcome) if outcome.records_deleted > 0 => {
+                        tracing::info!(
+                            "Audit retention: removed {} rows ({} archives written)",
+                            outcome.records_deleted,
+                            outcome.archives_written
+                        );
+                        metrics_service::record_cleanup("audit_log", outcome.records_deleted);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!("Audit retention sweep failed: {}", e);
+                    }
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // Abandoned peer transfer sessions (every hour).
+    //
+    // Fails pending / in-progress chunked transfers idle for a day and deletes
+    // the chunks they staged in primary storage. Cluster-leased so replicas do
+    // not sweep the same sessions twice.
+    {
+        let db = db.clone();
+        let storage = primary_storage.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(165)).await;
+            let service = crate::services::transfer_service::TransferService::new(db.clone());
+            let mut ticker = interval(Duration::from_secs(3600)); // 1 hour
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "transfer_session_expiry",
+                    3900.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    tracing::debug!(
+                        "Transfer session expiry: another replica holds the lease; skipping tick"
+                    );
+                    continue;
+                };
+
+                match service.expire_abandoned_sessions(storage.as_ref()).await {
+                    Ok(count) if count > 0 => {
+                        tracing::info!("Expired {} abandoned transfer sessions", count);
+                        metrics_service::record_cleanup("transfer_sessions", count);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!("Transfer session expiry failed: {}", e);
+                    }
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // Quota warning and weekly digest emails (every hour, SMTP only).
+    //
+    // Both sweeps dedupe against `email_deliveries`, so the hourly cadence
+    // sends at most one quota warning per repository per day and one digest
+    // per week. Cluster-leased so replicas do not race each other into
+    // duplicate sends.
+    if let Some(smtp) = smtp_service.clone() {
+        let db = db.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(180)).await;
+            let service = NotificationEmailService::new(db.clone(), Some(smtp));
+            let mut ticker = interval(Duration::from_secs(3600)); // 1 hour
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "notification_emails",
+                    3900.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    tracing::debug!(
+                        "Notification emails: another replica holds the lease; skipping tick"
+                    );
+                    continue;
+                };
+
+                match service.run_quota_warning_sweep().await {
+                    Ok(count) if count > 0 => {
+                        tracing::info!("Sent quota warnings for {} repository(ies)", count);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Quota warning sweep failed: {}", e),
+                }
+                match service.run_weekly_digest().await {
+                    Ok(true) => tracing::info!("Sent weekly digest email"),
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("Weekly digest failed: {}", e),
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // Jira issue sync for policy-violating findings (every 15 minutes).
+    // Leased so replicas never file the same issue twice.
+    {
+        let db = db.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(240)).await;
+            let service = IssueTrackerService::new(db.clone());
+            let mut ticker = interval(Duration::from_secs(900)); // 15 minutes
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "issue_tracker_sync",
+                    1200.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    tracing::debug!(
+                        "Issue tracker sync: another replica holds the lease; skipping tick"
+                    );
+                    continue;
+                };
+
+                match service.sync_all().await {
+                    Ok(summary)
+                        if summary.issues_created
+                            + summary.issues_closed
+                            + summary.issues_acknowledged
+                            + summary.findings_acknowledged
+                            > 0 =>
+                    {
+                        tracing::info!(
+                            "Issue tracker sync: {} created, {} closed, {} acknowledged, {} finding(s) acknowledged from Jira",
+                            summary.issues_created,
+                            summary.issues_closed,
+                            summary.issues_acknowledged,
+                            summary.findings_acknowledged
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Issue tracker sync failed: {}", e),
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // Dependency graph indexing (every 10 minutes). Picks up new artifacts
+    // and rewritten metadata; the first run on an existing instance backfills
+    // in batches across ticks.
+    {
+        let db = db.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(180)).await;
+            let service =
+                crate::services::dependency_graph_service::DependencyGraphService::new(db.clone());
+            let mut ticker = interval(Duration::from_secs(600)); // 10 minutes
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "dependency_graph_index",
+                    900.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    tracing::debug!(
+                        "Dependency graph index: another replica holds the lease; skipping tick"
+                    );
+                    continue;
+                };
+
+                match service.index_pending(20).await {
+                    Ok(summary) if summary.artifacts_indexed > 0 => {
+                        tracing::info!(
+                            "Dependency graph index: {} artifact(s), {} edge(s)",
+                            summary.artifacts_indexed,
+                            summary.edges_written
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Dependency graph indexing failed: {}", e),
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
     // Chunked upload session cleanup + orphaned incus staging sweep (every hour)
     {
         let db = db.clone();
@@ -704,8 +1037,11 @@ pub fn spawn_all(
         // to the manual admin /cleanup endpoint (gap in merged #1622). Run it on
         // the same hourly cadence and 24h threshold as the session reaper.
         let storage_path = config.storage_path.clone();
+        let direct_registry = storage_registry.clone();
         tokio::spawn(async move {
             tokio::time::sleep(Duration::from_secs(120)).await;
+            let direct_uploads =
+                crate::services::direct_upload_service::DirectUploadService::new(db.clone());
             let mut ticker = interval(Duration::from_secs(3600)); // 1 hour
 
             loop {
@@ -728,6 +1064,366 @@ pub fn spawn_all(
                 if swept > 0 {
                     tracing::info!("Swept {} orphaned incus staging file(s)", swept);
                 }
+
+                // Direct-to-storage uploads leave parts (or an assembled
+                // staging object) in the bucket until aborted.
+                match direct_uploads.cleanup_expired(&direct_registry).await {
+                    Ok(count) if count > 0 => {
+                        tracing::info!("Cleaned up {} expired direct uploads", count);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Direct upload cleanup failed: {}", e);
+                    }
+                    _ => {}
+                }
+            }
+        });
+    }
+
+    // WORM retention policies: every replica writes through its own storage
+    // registry, so each one reloads the policy set (not leased).
+    {
+        let db = db.clone();
+        let worm_registry = storage_registry.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(60));
+            loop {
+                ticker.tick().await;
+                if let Err(e) =
+                    crate::services::worm_service::refresh_registry(&db, &worm_registry).await
+                {
+                    tracing::warn!("WORM policy refresh failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Repository change-feed retention (every hour). Leased so replicas do
+    // not delete the same batches concurrently.
+    if config.repository_event_retention_days > 0 {
+        let db = db.clone();
+        let retention_days = config.repository_event_retention_days;
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(150)).await;
+            let service =
+                crate::services::repository_event_service::RepositoryEventService::new(db.clone());
+            let mut ticker = interval(Duration::from_secs(3600)); // 1 hour
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "repository_event_retention",
+                    3900.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    continue;
+                };
+
+                match service.prune(retention_days).await {
+                    Ok(count) if count > 0 => {
+                        tracing::info!("Pruned {} repository change-feed event(s)", count);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Repository change-feed pruning failed: {}", e),
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // SHA-512 / BLAKE3 backfill (every 10 minutes): re-hashes artifacts
+    // stored before those digests were recorded at ingest. Leased so only
+    // one replica reads the blobs.
+    {
+        let db = db.clone();
+        let backfill_registry = storage_registry.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(240)).await;
+            let mut skip = std::collections::HashSet::new();
+            let mut ticker = interval(Duration::from_secs(600)); // 10 minutes
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "checksum_backfill",
+                    900.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    continue;
+                };
+
+                match crate::services::checksum_backfill::run_batch(
+                    &db,
+                    &backfill_registry,
+                    &mut skip,
+                )
+                .await
+                {
+                    Ok(stats) if stats.updated > 0 || stats.failed > 0 => {
+                        tracing::info!(
+                            "Checksum backfill: {} artifact(s) updated, {} skipped",
+                            stats.updated,
+                            stats.failed
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Checksum backfill failed: {}", e),
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // PII retention (every hour): clears client IPs and user agents older
+    // than PII_RETENTION_DAYS from download statistics and the audit log.
+    if config.pii_retention_days > 0 {
+        let db = db.clone();
+        let retention_days = config.pii_retention_days;
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(165)).await;
+            let service =
+                crate::services::data_subject_service::DataSubjectService::new(db.clone());
+            let mut ticker = interval(Duration::from_secs(3600)); // 1 hour
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "pii_retention",
+                    3900.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    continue;
+                };
+
+                match service
+                    .clear_expired_network_identifiers(retention_days)
+                    .await
+                {
+                    Ok(count) if count > 0 => {
+                        tracing::info!(
+                            "PII retention: cleared network identifiers on {} row(s)",
+                            count
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("PII retention sweep failed: {}", e),
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // Block-list sweep (every 15 minutes): rejects stored artifacts that match
+    // an entry but slipped past the upload check, e.g. written by a format
+    // handler that inserts rows directly or uploaded on a replica whose
+    // cached list was stale.
+    {
+        let db = db.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(180)).await;
+            let service = crate::services::blocklist_service::BlocklistService::new(db.clone());
+            let mut ticker = interval(Duration::from_secs(900)); // 15 minutes
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "blocklist_sweep",
+                    1200.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    continue;
+                };
+
+                if let Err(e) = service.sweep().await {
+                    tracing::warn!("Block-list sweep failed: {}", e);
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // Scheduled compliance scans (checked every 15 minutes): runs the
+    // OpenSCAP profiles whose schedule interval elapsed against the newest
+    // image of each repository in scope, and fails scans orphaned by a
+    // restart. Only active when the OpenSCAP sidecar is configured.
+    if let Some(scanner) = crate::services::compliance_scan_service::ComplianceScanner::new(
+        db.clone(),
+        storage_registry.clone(),
+        &config,
+    ) {
+        let db = db.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(210)).await;
+            let mut ticker = interval(Duration::from_secs(900)); // 15 minutes
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                // Scans run sequentially and each may take many minutes.
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "compliance_scans",
+                    14400.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    continue;
+                };
+
+                match crate::services::compliance_scan_service::fail_stale_scans(&db).await {
+                    Ok(n) if n > 0 => {
+                        tracing::warn!("Marked {} stale compliance scan(s) as failed", n);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Stale compliance scan cleanup failed: {}", e),
+                }
+                match scanner.run_due_schedules(20).await {
+                    Ok(n) if n > 0 => {
+                        tracing::info!("Ran {} scheduled compliance scan(s)", n);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Scheduled compliance scans failed: {}", e),
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // API token usage counters (every minute). Every replica flushes its own
+    // in-memory counts, so this is not leased.
+    {
+        let service = crate::services::token_usage_service::TokenUsageService::new(db.clone());
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(60));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                if let Err(e) = service.flush_pending().await {
+                    tracing::warn!("Failed to flush API token usage: {}", e);
+                }
+            }
+        });
+    }
+
+    // Protocol access log (`ACCESS_LOG_RETENTION_DAYS`). Every replica writes
+    // its own buffered entries every 10 seconds; the hourly prune is leased.
+    if config.access_log_retention_days > 0 {
+        let service = crate::services::access_log_service::AccessLogService::new(db.clone());
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(10));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                if let Err(e) = service.flush_pending().await {
+                    tracing::warn!("Failed to flush protocol access log: {}", e);
+                }
+            }
+        });
+
+        let db = db.clone();
+        let retention_days = config.access_log_retention_days;
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(270)).await;
+            let mut ticker = interval(Duration::from_secs(3600));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let service = crate::services::access_log_service::AccessLogService::new(db.clone());
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "access_log_prune",
+                    1800.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    continue;
+                };
+
+                match service.prune(retention_days).await {
+                    Ok(n) if n > 0 => {
+                        tracing::info!(deleted = n, "Pruned protocol access log");
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Protocol access log prune failed: {}", e),
+                }
+
+                lease.release(&db).await;
+            }
+        });
+    }
+
+    // Stale API token warnings and revocation (hourly, `API_TOKEN_STALE_DAYS`)
+    if config.api_token_stale_days > 0 {
+        let db = db.clone();
+        let smtp = smtp_service.clone();
+        let stale_days = config.api_token_stale_days;
+        let warning_days = config.api_token_stale_warning_days;
+        tokio::spawn(async move {
+            tokio::time::sleep(jittered_startup_delay(240)).await;
+            let mut ticker = interval(Duration::from_secs(3600));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let service = crate::services::token_usage_service::TokenUsageService::new(db.clone());
+
+            loop {
+                ticker.tick().await;
+
+                let lease = crate::services::cluster_work::try_acquire_scheduler_lease_quiet(
+                    &db,
+                    "api_token_stale_sweep",
+                    1800.0,
+                )
+                .await;
+                let Some(lease) = lease else {
+                    continue;
+                };
+
+                match service
+                    .run_stale_sweep(smtp.as_deref(), stale_days, warning_days)
+                    .await
+                {
+                    Ok(summary)
+                        if summary.warned > 0
+                            || summary.revoked > 0
+                            || summary.unnotifiable > 0 =>
+                    {
+                        tracing::info!(
+                            warned = summary.warned,
+                            revoked = summary.revoked,
+                            unnotifiable = summary.unnotifiable,
+                            "Stale API token sweep completed"
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Stale API token sweep failed: {}", e),
+                }
+
+                lease.release(&db).await;
             }
         });
     }
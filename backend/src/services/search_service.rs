@@ -1,6 +1,9 @@
 //! Search service for artifact discovery.
 //!
 //! Provides full-text search across artifacts with faceted filtering.
+//! Free-text queries also match the README / changelog text extracted at
+//! publish (`artifact_docs`, see `package_docs_service`), and results that
+//! matched there carry highlighted snippets from it.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -24,6 +27,9 @@ pub struct SearchResult {
     pub created_at: DateTime<Utc>,
     pub download_count: i64,
     pub score: f32,
+    /// Highlighted README / changelog snippets for the query (HTML-escaped,
+    /// matches wrapped in `<mark>`). Empty when the docs did not match.
+    pub highlights: Vec<String>,
 }
 
 /// Search query
@@ -256,9 +262,27 @@ fn row_to_search_result(r: SearchResultRow) -> SearchResult {
         created_at: r.9,
         download_count: r.10,
         score: r.11,
+        highlights: Vec::new(),
     }
 }
 
+/// Markers `ts_headline` wraps matches in. Plain text, so the snippet can be
+/// HTML-escaped as a whole before they are swapped for `<mark>` tags.
+const HEADLINE_START: &str = "[[hl]]";
+const HEADLINE_STOP: &str = "[[/hl]]";
+
+/// Render a raw `ts_headline` snippet for the API: escape the package text
+/// (READMEs are untrusted and routinely contain HTML), then turn the match
+/// markers into `<mark>` tags.
+pub(crate) fn render_highlight(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace(HEADLINE_START, "<mark>")
+        .replace(HEADLINE_STOP, "</mark>")
+}
+
 /// Search service
 pub struct SearchService {
     db: PgPool,
@@ -326,7 +350,8 @@ impl SearchService {
                 FROM artifacts a
                 JOIN repositories r ON r.id = a.repository_id
                 WHERE a.is_deleted = false
-                  AND ($1::text IS NULL OR to_tsvector('english', a.name || ' ' || a.path || ' ' || COALESCE(a.version, '')) @@ to_tsquery('english', $1))
+                  AND ($1::text IS NULL OR to_tsvector('english', a.name || ' ' || a.path || ' ' || COALESCE(a.version, '')) @@ to_tsquery('english', $1)
+                       OR EXISTS (SELECT 1 FROM artifact_docs d WHERE d.artifact_id = a.id AND d.search_vector @@ to_tsquery('english', $1)))
                   AND ($2::text IS NULL OR r.format::text = $2)
                   AND ($3::text IS NULL OR a.name ILIKE $3)
                   AND ($7::uuid[] IS NULL OR r.id = ANY($7))
@@ -350,7 +375,61 @@ impl SearchService {
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(rows.into_iter().map(row_to_search_result).collect())
+        let mut results: Vec<SearchResult> = rows.into_iter().map(row_to_search_result).collect();
+        if let Some(q) = q_filter.as_deref().filter(|q| !q.is_empty()) {
+            self.attach_highlights(&mut results, q).await?;
+        }
+        Ok(results)
+    }
+
+    /// Fill [`SearchResult::highlights`] from the README / changelog of every
+    /// result whose docs match `tsquery`. One query for the whole page;
+    /// `ts_headline` only runs on the rows shown.
+    async fn attach_highlights(&self, results: &mut [SearchResult], tsquery: &str) -> Result<()> {
+        if results.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<Uuid> = results.iter().map(|r| r.id).collect();
+        let options = format!(
+            "MaxFragments=2, MaxWords=30, MinWords=10, StartSel={}, StopSel={}, FragmentDelimiter=\" ... \"",
+            HEADLINE_START, HEADLINE_STOP
+        );
+        let rows: Vec<(Uuid, Option<String>, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT d.artifact_id,
+                   CASE WHEN to_tsvector('english', COALESCE(d.readme, '')) @@ q.q
+                        THEN ts_headline('english', d.readme, q.q, $3) END,
+                   CASE WHEN to_tsvector('english', COALESCE(d.changelog, '')) @@ q.q
+                        THEN ts_headline('english', d.changelog, q.q, $3) END
+            FROM artifact_docs d, to_tsquery('english', $2) AS q(q)
+            WHERE d.artifact_id = ANY($1)
+              AND d.search_vector @@ q.q
+            "#,
+        )
+        .bind(&ids)
+        .bind(tsquery)
+        .bind(&options)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut by_id: std::collections::HashMap<Uuid, Vec<String>> = rows
+            .into_iter()
+            .map(|(id, readme, changelog)| {
+                let snippets = readme
+                    .into_iter()
+                    .chain(changelog)
+                    .map(|raw| render_highlight(&raw))
+                    .collect();
+                (id, snippets)
+            })
+            .collect();
+        for result in results.iter_mut() {
+            if let Some(snippets) = by_id.remove(&result.id) {
+                result.highlights = snippets;
+            }
+        }
+        Ok(())
     }
 
     async fn count_results(&self, query: &SearchQuery) -> Result<i64> {
@@ -363,7 +442,8 @@ impl SearchService {
             FROM artifacts a
             JOIN repositories r ON r.id = a.repository_id
             WHERE a.is_deleted = false
-              AND ($1::text IS NULL OR to_tsvector('english', a.name || ' ' || a.path || ' ' || COALESCE(a.version, '')) @@ to_tsquery('english', $1))
+              AND ($1::text IS NULL OR to_tsvector('english', a.name || ' ' || a.path || ' ' || COALESCE(a.version, '')) @@ to_tsquery('english', $1)
+                       OR EXISTS (SELECT 1 FROM artifact_docs d WHERE d.artifact_id = a.id AND d.search_vector @@ to_tsquery('english', $1)))
               AND ($2::text IS NULL OR r.format::text = $2)
               AND ($3::text IS NULL OR a.name ILIKE $3)
               AND ($5::uuid[] IS NULL OR r.id = ANY($5))
@@ -858,6 +938,7 @@ mod tests {
                 .with_timezone(&Utc),
             download_count: 42,
             score: 1.0,
+            highlights: Vec::new(),
         };
         let json = serde_json::to_value(&result).unwrap();
         assert_eq!(json["name"], "lib");
@@ -883,11 +964,32 @@ mod tests {
             created_at: Utc::now(),
             download_count: 0,
             score: 0.5,
+            highlights: Vec::new(),
         };
         let json = serde_json::to_value(&result).unwrap();
         assert!(json["version"].is_null());
     }
 
+    // -----------------------------------------------------------------------
+    // render_highlight
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_render_highlight_marks_matches() {
+        assert_eq!(
+            render_highlight("a [[hl]]fast[[/hl]] parser"),
+            "a <mark>fast</mark> parser"
+        );
+    }
+
+    #[test]
+    fn test_render_highlight_escapes_package_html() {
+        assert_eq!(
+            render_highlight("<script>x</script> & [[hl]]\"q\"[[/hl]]"),
+            "&lt;script&gt;x&lt;/script&gt; &amp; <mark>&quot;q&quot;</mark>"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // SearchFacets
     // -----------------------------------------------------------------------
@@ -13,6 +13,7 @@ use pgp::composed::{Deserializable, SignedPublicKey, StandaloneSignature};
 use pgp::crypto::hash::HashAlgorithm;
 use pgp::crypto::public_key::PublicKeyAlgorithm;
 use pgp::packet::{SignatureConfig, SignatureType, Subpacket, SubpacketData};
+use pgp::ser::Serialize;
 use pgp::types::{KeyVersion, PublicKeyTrait};
 use pgp::ArmorOptions;
 use rsa::pkcs1v15::SigningKey as RsaSigningKey;
@@ -249,13 +250,13 @@ pub fn verify_detached(trusted_armored_key: &str, data: &[u8], armored_sig: &str
     })
 }
 
-/// Create an ASCII-armored detached OpenPGP signature.
+/// Create a detached OpenPGP signature.
 ///
 /// CPU-bound. Call from within `spawn_blocking`.
-fn sign_openpgp_detached_blocking(
+fn openpgp_detached_signature_blocking(
     secret_key: pgp::SignedSecretKey,
     data: Vec<u8>,
-) -> Result<String> {
+) -> Result<StandaloneSignature> {
     let mut config = SignatureConfig::v4(
         SignatureType::Binary,
         PublicKeyAlgorithm::RSA,
@@ -274,11 +275,33 @@ fn sign_openpgp_detached_blocking(
     let signature = config
         .sign(&secret_key, String::new, &data[..])
         .map_err(|e| AppError::Internal(format!("Failed to sign OpenPGP data: {}", e)))?;
-    StandaloneSignature::new(signature)
+    Ok(StandaloneSignature::new(signature))
+}
+
+/// Create an ASCII-armored detached OpenPGP signature.
+///
+/// CPU-bound. Call from within `spawn_blocking`.
+fn sign_openpgp_detached_blocking(
+    secret_key: pgp::SignedSecretKey,
+    data: Vec<u8>,
+) -> Result<String> {
+    openpgp_detached_signature_blocking(secret_key, data)?
         .to_armored_string(ArmorOptions::default())
         .map_err(|e| AppError::Internal(format!("Failed to armor OpenPGP signature: {}", e)))
 }
 
+/// Create a binary (unarmored) detached OpenPGP signature.
+///
+/// CPU-bound. Call from within `spawn_blocking`.
+fn sign_openpgp_detached_binary_blocking(
+    secret_key: pgp::SignedSecretKey,
+    data: Vec<u8>,
+) -> Result<Vec<u8>> {
+    openpgp_detached_signature_blocking(secret_key, data)?
+        .to_bytes()
+        .map_err(|e| AppError::Internal(format!("Failed to encode OpenPGP signature: {}", e)))
+}
+
 /// Create an OpenPGP cleartext signed message.
 ///
 /// CPU-bound. Call from within `spawn_blocking`.
@@ -626,11 +649,13 @@ impl SigningService {
     /// `used_for_signing` marker the promotion `require_signature` gate reads
     /// (#2535).
     ///
-    /// This is the **sole** writer of the per-artifact marker: it is only
-    /// reachable from the admin-gated `POST /signing/artifacts/:id/sign`
-    /// endpoint, so an artifact can satisfy `require_signature` only through an
-    /// explicit, authenticated signing action over its bytes — never as a side
-    /// effect of an anonymous metadata read.
+    /// Together with sign-on-promotion
+    /// ([`crate::services::promotion_signing_service`]) this is the only
+    /// writer of the per-artifact marker: it is reachable from the admin-gated
+    /// `POST /signing/artifacts/:id/sign` endpoint, so an artifact can satisfy
+    /// `require_signature` only through an explicit, authenticated signing
+    /// action over its bytes — never as a side effect of an anonymous metadata
+    /// read.
     ///
     /// Returns `Ok(None)` when the repository has no active signing key /
     /// signing config (the caller maps this to a 409), so a repo that cannot
@@ -648,16 +673,7 @@ impl SigningService {
             None => return Ok(None),
         };
 
-        // Produce a real content signature with whichever key material the
-        // repo's active key holds.
-        let (signature, algorithm) = if key.key_type == "gpg" {
-            let armored = self.sign_openpgp_detached_with_key(&key, content).await?;
-            (armored.into_bytes(), format!("openpgp:{}", key.algorithm))
-        } else {
-            let sig = self.sign_with_key(&key, content)?;
-            (sig, format!("rsa-pkcs1v15-sha256:{}", key.algorithm))
-        };
-
+        let (signature, algorithm) = self.sign_content_with_key(&key, content).await?;
         let signature_sha256 = hex::encode(Sha256::digest(&signature));
 
         self.record_artifact_signature(
@@ -677,11 +693,29 @@ impl SigningService {
         }))
     }
 
+    /// Produce a detached content signature with whichever key material `key`
+    /// holds: an ASCII-armored OpenPGP signature for `gpg` keys, a raw RSA
+    /// PKCS#1 v1.5 SHA-256 signature otherwise. Returns the signature bytes
+    /// and the algorithm label recorded alongside them.
+    pub async fn sign_content_with_key(
+        &self,
+        key: &SigningKey,
+        content: &[u8],
+    ) -> Result<(Vec<u8>, String)> {
+        if key.supports_openpgp() {
+            let armored = self.sign_openpgp_detached_with_key(key, content).await?;
+            Ok((armored.into_bytes(), format!("openpgp:{}", key.algorithm)))
+        } else {
+            let sig = self.sign_with_key(key, content)?;
+            Ok((sig, format!("rsa-pkcs1v15-sha256:{}", key.algorithm)))
+        }
+    }
+
     /// Insert the single `used_for_signing` audit row that attests `artifact_id`
     /// under `key_id` (#2535). Idempotent on `(key_id, artifact_id)`: re-signing
     /// the same artifact with the same active key does not accumulate duplicate
     /// markers.
-    async fn record_artifact_signature(
+    pub(crate) async fn record_artifact_signature(
         &self,
         key_id: Uuid,
         artifact_id: Uuid,
@@ -999,6 +1033,22 @@ impl SigningService {
         .await
     }
 
+    /// Sign `data` with `key` and return a binary detached OpenPGP
+    /// signature, for clients that do not accept armor (Terraform's
+    /// `SHA256SUMS.sig`).
+    pub async fn sign_openpgp_detached_binary_with_key(
+        &self,
+        key: &SigningKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let secret_key = self.load_openpgp_secret_key(key)?;
+        let data_owned = data.to_vec();
+        run_blocking("openpgp_sign_detached_binary", move || {
+            sign_openpgp_detached_binary_blocking(secret_key, data_owned)
+        })
+        .await
+    }
+
     /// Sign `text` with `key` and return an ASCII-armored cleartext
     /// signed message. See [`Self::sign_openpgp_detached_with_key`] for
     /// the rationale on the public surface.
@@ -1391,6 +1441,18 @@ mod tests {
         let (signature, _) = StandaloneSignature::from_string(&detached).unwrap();
         signature.verify(&public_key, data).unwrap();
 
+        let binary = service
+            .sign_openpgp_detached_binary_with_key(&key, data)
+            .await
+            .unwrap();
+        assert_ne!(
+            binary.first(),
+            Some(&b'-'),
+            "binary signature must not be armored"
+        );
+        let signature = StandaloneSignature::from_bytes(&binary[..]).unwrap();
+        signature.verify(&public_key, data).unwrap();
+
         let cleartext = service
             .sign_openpgp_cleartext_with_key(&key, std::str::from_utf8(data).unwrap())
             .await
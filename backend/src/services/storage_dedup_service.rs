@@ -0,0 +1,94 @@
+//! Content-addressable storage dedup (`STORAGE_DEDUP_ENABLED`).
+//!
+//! Generic uploads store their blob once per storage backend under the shared
+//! [`cas_storage_key`] namespace. `storage_cas_objects` has one row per
+//! physical blob and `storage_refs` one row per artifact pointing at it, so
+//! identical content pushed to several repositories occupies storage once.
+//! Storage GC deletes a blob only when its reference count reaches zero (see
+//! `storage_gc_service::cleanup_unreferenced_cas_objects`).
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::storage::keys::cas_storage_key;
+
+/// Claim the shared CAS object for `checksum_sha256` on `repository_id`'s
+/// backend before its blob is written or reused.
+///
+/// Inserts the object row, or bumps `last_claimed_at` on an existing one. The
+/// upsert waits on a GC sweep that holds the row lock, so after it returns
+/// the row exists and GC leaves the blob alone for the claim window; the
+/// caller then checks for the blob and writes it if it is missing.
+pub async fn claim_cas_object(
+    db: &PgPool,
+    repository_id: Uuid,
+    checksum_sha256: &str,
+    size_bytes: i64,
+) -> Result<()> {
+    let claimed = sqlx::query(
+        r#"
+        INSERT INTO storage_cas_objects
+            (storage_backend, checksum_sha256, storage_key, size_bytes)
+        SELECT r.storage_backend, $2, $3, $4
+        FROM repositories r
+        WHERE r.id = $1
+        ON CONFLICT (storage_backend, checksum_sha256)
+        DO UPDATE SET last_claimed_at = NOW()
+        "#,
+    )
+    .bind(repository_id)
+    .bind(checksum_sha256)
+    .bind(cas_storage_key(checksum_sha256))
+    .bind(size_bytes)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    if claimed.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Repository {repository_id} not found"
+        )));
+    }
+    Ok(())
+}
+
+/// Point `artifact_id` at its shared CAS object, replacing any previous ref
+/// the artifact held (an overwrite with new content).
+///
+/// Best-effort: the artifact row is already committed, and GC will not
+/// reclaim a blob an artifact row still names even without a ref, so a
+/// failure is logged rather than failing the upload.
+pub async fn record_storage_ref(
+    db: &PgPool,
+    artifact_id: Uuid,
+    repository_id: Uuid,
+    checksum_sha256: &str,
+) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO storage_refs
+            (artifact_id, repository_id, storage_backend, checksum_sha256)
+        SELECT $1, r.id, r.storage_backend, $3
+        FROM repositories r
+        WHERE r.id = $2
+        ON CONFLICT (artifact_id) DO UPDATE
+        SET repository_id = EXCLUDED.repository_id,
+            storage_backend = EXCLUDED.storage_backend,
+            checksum_sha256 = EXCLUDED.checksum_sha256,
+            created_at = NOW()
+        "#,
+    )
+    .bind(artifact_id)
+    .bind(repository_id)
+    .bind(checksum_sha256)
+    .execute(db)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(
+            artifact_id = %artifact_id,
+            repository_id = %repository_id,
+            "Failed to record storage dedup reference: {}",
+            e
+        );
+    }
+}
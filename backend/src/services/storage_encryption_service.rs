@@ -0,0 +1,311 @@
+//! Storage encryption migration (`STORAGE_ENCRYPTION`).
+//!
+//! Walks every live artifact blob through the raw (unencrypted) storage
+//! handles: objects stored before encryption was enabled are encrypted in
+//! place, and objects whose data key is wrapped by a retired key-encryption
+//! key are rewrapped with the active one — a header rewrite, not a
+//! re-encryption of the body. Objects already under the active key are left
+//! alone, so a run can be repeated safely after an interruption or a key
+//! rotation.
+//!
+//! Only artifact blobs are migrated. Proxy-cache entries are encrypted as
+//! they are refreshed from upstream.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::storage::encryption::{EnvelopeEncryption, ObjectEncryptionState};
+use crate::storage::{StorageLocation, StorageRegistry};
+
+/// Distinct blobs fetched per page.
+const BATCH_SIZE: i64 = 500;
+
+/// A run whose progress has not been flushed for this long was abandoned by
+/// a restart and no longer blocks a new one.
+const STALE_RUN_SECS: i64 = 600;
+
+/// A recorded migration run.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct StorageEncryptionRun {
+    pub id: Uuid,
+    /// `running`, `completed`, `failed` or `interrupted`.
+    pub status: String,
+    pub requested_by: Option<Uuid>,
+    /// Key-encryption key objects were brought under.
+    pub active_key_id: String,
+    pub objects_scanned: i64,
+    pub objects_encrypted: i64,
+    pub objects_rewrapped: i64,
+    pub objects_failed: i64,
+    pub last_error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Current encryption configuration and the latest run.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StorageEncryptionStatus {
+    pub enabled: bool,
+    /// `local`, `aws-kms` or `azure-keyvault`.
+    pub provider: Option<String>,
+    pub active_key_id: Option<String>,
+    /// Whether unencrypted objects are still readable.
+    pub allow_plaintext: bool,
+    pub latest_run: Option<StorageEncryptionRun>,
+}
+
+#[derive(Default)]
+struct Progress {
+    scanned: i64,
+    encrypted: i64,
+    rewrapped: i64,
+    failed: i64,
+    last_error: Option<String>,
+}
+
+pub struct StorageEncryptionService {
+    db: PgPool,
+    registry: Arc<StorageRegistry>,
+}
+
+impl StorageEncryptionService {
+    pub fn new(db: PgPool, registry: Arc<StorageRegistry>) -> Self {
+        Self { db, registry }
+    }
+
+    fn encryption(&self) -> Result<Arc<EnvelopeEncryption>> {
+        self.registry.encryption().cloned().ok_or_else(|| {
+            AppError::Validation(
+                "storage encryption is not enabled; set STORAGE_ENCRYPTION first".to_string(),
+            )
+        })
+    }
+
+    pub async fn status(&self) -> Result<StorageEncryptionStatus> {
+        let latest_run = self.list_runs(1).await?.into_iter().next();
+        let Some(encryption) = self.registry.encryption() else {
+            return Ok(StorageEncryptionStatus {
+                enabled: false,
+                provider: None,
+                active_key_id: None,
+                allow_plaintext: true,
+                latest_run,
+            });
+        };
+        Ok(StorageEncryptionStatus {
+            enabled: true,
+            provider: Some(encryption.provider().to_string()),
+            active_key_id: Some(encryption.active_key_id().await?),
+            allow_plaintext: encryption.allow_plaintext(),
+            latest_run,
+        })
+    }
+
+    pub async fn list_runs(&self, limit: i64) -> Result<Vec<StorageEncryptionRun>> {
+        let runs = sqlx::query_as::<_, StorageEncryptionRun>(
+            "SELECT * FROM storage_encryption_runs ORDER BY started_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(runs)
+    }
+
+    /// Record a new run and start it in the background. Fails with
+    /// `Conflict` while another run is active.
+    pub async fn start_run(
+        self: Arc<Self>,
+        requested_by: Option<Uuid>,
+    ) -> Result<StorageEncryptionRun> {
+        let encryption = self.encryption()?;
+        let active_key_id = encryption.active_key_id().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE storage_encryption_runs
+            SET status = 'interrupted', finished_at = NOW(),
+                last_error = COALESCE(last_error, 'abandoned before completion')
+            WHERE status = 'running'
+              AND updated_at < NOW() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(STALE_RUN_SECS as f64)
+        .execute(&self.db)
+        .await?;
+
+        let run = sqlx::query_as::<_, StorageEncryptionRun>(
+            r#"
+            INSERT INTO storage_encryption_runs (requested_by, active_key_id)
+            VALUES ($1, $2)
+            RETURNING *
+            "#,
+        )
+        .bind(requested_by)
+        .bind(&active_key_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                AppError::Conflict("a storage encryption migration is already running".to_string())
+            }
+            other => AppError::Database(other.to_string()),
+        })?;
+
+        let run_id = run.id;
+        tokio::spawn(async move {
+            let (status, progress) = match self.execute(run_id, &encryption).await {
+                Ok(progress) => ("completed", progress),
+                Err((e, mut progress)) => {
+                    tracing::error!(run_id = %run_id, "Storage encryption migration failed: {}", e);
+                    progress.last_error = Some(e.to_string());
+                    ("failed", progress)
+                }
+            };
+            if let Err(e) = self.flush(run_id, &progress, Some(status)).await {
+                tracing::warn!(run_id = %run_id, "Failed to record migration result: {}", e);
+            }
+            tracing::info!(
+                run_id = %run_id,
+                status,
+                scanned = progress.scanned,
+                encrypted = progress.encrypted,
+                rewrapped = progress.rewrapped,
+                failed = progress.failed,
+                "Storage encryption migration finished"
+            );
+        });
+
+        Ok(run)
+    }
+
+    async fn execute(
+        &self,
+        run_id: Uuid,
+        encryption: &Arc<EnvelopeEncryption>,
+    ) -> std::result::Result<Progress, (AppError, Progress)> {
+        let mut progress = Progress::default();
+        let mut cursor: Option<(String, String, String)> = None;
+        loop {
+            let rows = match sqlx::query(
+                r#"
+                SELECT DISTINCT r.storage_backend, r.storage_path, a.storage_key
+                FROM artifacts a
+                JOIN repositories r ON r.id = a.repository_id
+                WHERE a.is_deleted = false
+                  AND ($1::text IS NULL
+                       OR (r.storage_backend, r.storage_path, a.storage_key) > ($1, $2, $3))
+                ORDER BY r.storage_backend, r.storage_path, a.storage_key
+                LIMIT $4
+                "#,
+            )
+            .bind(cursor.as_ref().map(|c| c.0.as_str()))
+            .bind(cursor.as_ref().map(|c| c.1.as_str()))
+            .bind(cursor.as_ref().map(|c| c.2.as_str()))
+            .bind(BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await
+            {
+                Ok(rows) => rows,
+                Err(e) => return Err((AppError::Database(e.to_string()), progress)),
+            };
+            if rows.is_empty() {
+                return Ok(progress);
+            }
+
+            for row in &rows {
+                let backend: String = row.try_get("storage_backend").unwrap_or_default();
+                let path: String = row.try_get("storage_path").unwrap_or_default();
+                let key: String = row.try_get("storage_key").unwrap_or_default();
+                progress.scanned += 1;
+                let location = StorageLocation {
+                    backend: backend.clone(),
+                    path: path.clone(),
+                };
+                match self.migrate_object(encryption, &location, &key).await {
+                    Ok(MigrateOutcome::Encrypted) => progress.encrypted += 1,
+                    Ok(MigrateOutcome::Rewrapped) => progress.rewrapped += 1,
+                    Ok(MigrateOutcome::Unchanged) => {}
+                    Err(e) => {
+                        tracing::warn!(
+                            run_id = %run_id,
+                            backend = %backend,
+                            storage_key = %key,
+                            "Failed to migrate object to storage encryption: {}",
+                            e
+                        );
+                        progress.failed += 1;
+                        progress.last_error = Some(format!("{key}: {e}"));
+                    }
+                }
+                cursor = Some((backend, path, key));
+            }
+
+            if let Err(e) = self.flush(run_id, &progress, None).await {
+                return Err((e, progress));
+            }
+        }
+    }
+
+    async fn migrate_object(
+        &self,
+        encryption: &Arc<EnvelopeEncryption>,
+        location: &StorageLocation,
+        key: &str,
+    ) -> Result<MigrateOutcome> {
+        let raw = self.registry.raw_backend_for(location)?;
+        if !raw.exists(key).await? {
+            return Ok(MigrateOutcome::Unchanged);
+        }
+        match encryption.object_state(raw.as_ref(), key).await? {
+            ObjectEncryptionState::Current => Ok(MigrateOutcome::Unchanged),
+            ObjectEncryptionState::Plaintext => {
+                encryption.encrypt_object(raw, key).await?;
+                Ok(MigrateOutcome::Encrypted)
+            }
+            ObjectEncryptionState::Stale { .. } => {
+                encryption.rewrap_object(raw.as_ref(), key).await?;
+                Ok(MigrateOutcome::Rewrapped)
+            }
+        }
+    }
+
+    async fn flush(&self, run_id: Uuid, progress: &Progress, status: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE storage_encryption_runs
+            SET objects_scanned = $2,
+                objects_encrypted = $3,
+                objects_rewrapped = $4,
+                objects_failed = $5,
+                last_error = $6,
+                status = COALESCE($7, status),
+                finished_at = CASE WHEN $7 IS NULL THEN NULL ELSE NOW() END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(run_id)
+        .bind(progress.scanned)
+        .bind(progress.encrypted)
+        .bind(progress.rewrapped)
+        .bind(progress.failed)
+        .bind(&progress.last_error)
+        .bind(status)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
+
+enum MigrateOutcome {
+    Encrypted,
+    Rewrapped,
+    Unchanged,
+}
@@ -11,7 +11,7 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::storage::keys::prefix_matches;
+use crate::storage::keys::{is_cas_storage_key, prefix_matches};
 use crate::storage::{StorageBackend, StorageLocation, StorageRegistry};
 
 const ABANDONED_OCI_UPLOAD_TTL_SQL: &str = "INTERVAL '24 hours'";
@@ -25,8 +25,9 @@ const OCI_UPLOAD_CLEANUP_KEY_SCAN_LIMIT: i64 = 1000;
 ///    the key);
 /// 2. It is not protected by an `oci_tags` row (manifests still tagged);
 /// 3. It is not protected by an `oci_blobs` row (named blobs);
-/// 4. It is not the per-architecture child of a still-tagged OCI image index
-///    (`oci_manifest_refs` joined against `oci_tags`; see migration 092).
+/// 4. It is not a child manifest still referenced by a tagged OCI image
+///    index, directly or through nested indexes
+///    (`ak_oci_manifest_index_referenced`; see migrations 092 and 194).
 ///
 /// The fragment expects two bindings: the outer `artifacts` row aliased
 /// `a` and the outer `repositories` row aliased `r`. Callers either inline
@@ -84,8 +85,6 @@ AND NOT EXISTS (
 AND NOT EXISTS (
     SELECT 1
     FROM oci_manifest_refs omr
-    JOIN oci_tags ot2 ON ot2.repository_id = omr.repository_id
-                     AND ot2.manifest_digest = omr.parent_digest
     JOIN repositories omrr ON omrr.id = omr.repository_id
     WHERE a.storage_key LIKE 'oci-manifests/%'
       AND omr.child_digest = SUBSTRING(
@@ -96,6 +95,7 @@ AND NOT EXISTS (
         r.storage_backend <> 'filesystem'
         OR omrr.storage_path = r.storage_path
       )
+      AND ak_oci_manifest_index_referenced(omr.repository_id, omr.child_digest)
 )
 "#;
 
@@ -259,6 +259,38 @@ AND NOT EXISTS (
 )
 "#;
 
+/// Upper bound on shared CAS objects examined per GC pass.
+const UNREFERENCED_CAS_OBJECT_SCAN_LIMIT: i64 = 1000;
+
+/// SQL fragment: the shared CAS object row aliased `o` (storage dedup,
+/// `storage_cas_objects`) has a reference count of zero and may be reclaimed.
+///
+/// 1. No `storage_refs` row points at it. Refs disappear with their artifact
+///    row (main-sweep hard-delete, repository delete), so a soft-deleted
+///    artifact keeps its blob until the main sweep reclaims the row.
+/// 2. No `artifacts` row on the same backend uses its key, live or
+///    soft-deleted — a backstop for rows that reached a CAS key without a
+///    ref (promotion copies the row's key into the target repository).
+/// 3. No upload claimed it within the last hour: an upload claims the object
+///    before writing the blob and inserts its ref after the artifact row, so
+///    the age floor covers that window.
+///
+/// Scan and per-object locked re-check share this constant (#1180).
+const UNREFERENCED_CAS_OBJECT_PREDICATE_SQL: &str = r#"
+o.last_claimed_at < NOW() - INTERVAL '1 hour'
+AND NOT EXISTS (
+    SELECT 1 FROM storage_refs sr
+    WHERE sr.storage_backend = o.storage_backend
+      AND sr.checksum_sha256 = o.checksum_sha256
+)
+AND NOT EXISTS (
+    SELECT 1 FROM artifacts a
+    JOIN repositories ar ON ar.id = a.repository_id
+    WHERE a.storage_key = o.storage_key
+      AND ar.storage_backend = o.storage_backend
+)
+"#;
+
 /// Result of a storage GC run.
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct StorageGcResult {
@@ -411,9 +443,14 @@ impl StorageGcService {
 
         if dry_run {
             for row in &orphans {
+                let storage_key: String = row.try_get("storage_key").unwrap_or_default();
                 let bytes: i64 = row.try_get("total_bytes").unwrap_or(0);
                 let count: i64 = row.try_get("artifact_count").unwrap_or(0);
-                accumulate_dry_run(&mut result, bytes, count);
+                if is_cas_storage_key(&storage_key) {
+                    result.artifacts_removed += count;
+                } else {
+                    accumulate_dry_run(&mut result, bytes, count);
+                }
             }
         } else {
             for row in &orphans {
@@ -479,6 +516,13 @@ impl StorageGcService {
                     }
                 }
 
+                // Shared CAS objects (storage dedup) are ref-counted: the
+                // hard-delete below drops these rows' `storage_refs`, and the
+                // blob itself is reclaimed by
+                // `cleanup_unreferenced_cas_objects` once no reference is left
+                // on any repository.
+                let shared_cas = is_cas_storage_key(&storage_key);
+
                 // Storage delete is not transactional, but it happens while
                 // the row lock is still held by `tx`. A racing pusher cannot
                 // begin re-using this storage key until we commit/rollback.
@@ -486,16 +530,18 @@ impl StorageGcService {
                 // retry after a crash mid-delete still reclaims the soft-deleted
                 // row instead of erroring every pass — matching the cloud
                 // backends' NotFound→Ok mapping.
-                match storage.delete(&storage_key).await {
-                    Ok(()) | Err(AppError::NotFound(_)) => {}
-                    Err(e) => {
-                        let _ = tx.rollback().await;
-                        let msg =
-                            format_gc_error("delete storage key", &storage_key, &e.to_string());
-                        tracing::warn!("{}", msg);
-                        result.errors.push(msg);
-                        // Skip DB cleanup if storage delete fails
-                        continue;
+                if !shared_cas {
+                    match storage.delete(&storage_key).await {
+                        Ok(()) | Err(AppError::NotFound(_)) => {}
+                        Err(e) => {
+                            let _ = tx.rollback().await;
+                            let msg =
+                                format_gc_error("delete storage key", &storage_key, &e.to_string());
+                            tracing::warn!("{}", msg);
+                            result.errors.push(msg);
+                            // Skip DB cleanup if storage delete fails
+                            continue;
+                        }
                     }
                 }
 
@@ -568,7 +614,11 @@ impl StorageGcService {
                     continue;
                 }
 
-                record_gc_success(&mut result, bytes, count);
+                if shared_cas {
+                    result.artifacts_removed += count;
+                } else {
+                    record_gc_success(&mut result, bytes, count);
+                }
             }
         }
 
@@ -625,6 +675,18 @@ impl StorageGcService {
             tracing::warn!("{}", msg);
             result.errors.push(msg);
         }
+        if let Err(e) = self
+            .cleanup_unreferenced_cas_objects(dry_run, &mut result)
+            .await
+        {
+            let msg = format_gc_error(
+                "run unreferenced CAS object sweep",
+                "<sweep>",
+                &e.to_string(),
+            );
+            tracing::warn!("{}", msg);
+            result.errors.push(msg);
+        }
 
         if result.storage_keys_deleted > 0 {
             tracing::info!(
@@ -1045,8 +1107,8 @@ impl StorageGcService {
     /// the blobs they pinned become reclaimable (#1409 H1).
     ///
     /// A ref is stale when its `manifest_digest` is neither tagged in its repo
-    /// (`oci_tags`) nor a live per-architecture child of a tagged index
-    /// (`oci_manifest_refs` -> tagged `parent_digest`). Tag overwrite, lifecycle
+    /// (`oci_tags`) nor still referenced by a tagged index, directly or through
+    /// nested indexes (`ak_oci_manifest_index_referenced`). Tag overwrite, lifecycle
     /// expiry and manifest/index deletion all leave such orphan refs behind;
     /// without pruning them [`BLOB_PROTECTED_BY_REFS_SQL`] would protect the
     /// digest forever. Conservative: any still-reachable manifest keeps its
@@ -1061,14 +1123,7 @@ impl StorageGcService {
                 WHERE ot.repository_id = mbr.repository_id
                   AND ot.manifest_digest = mbr.manifest_digest
             )
-            AND NOT EXISTS (
-                SELECT 1 FROM oci_manifest_refs omr
-                JOIN oci_tags ot2
-                  ON ot2.repository_id = omr.repository_id
-                 AND ot2.manifest_digest = omr.parent_digest
-                WHERE omr.repository_id = mbr.repository_id
-                  AND omr.child_digest = mbr.manifest_digest
-            )
+            AND NOT ak_oci_manifest_index_referenced(mbr.repository_id, mbr.manifest_digest)
             "#,
         )
         .execute(&self.db)
@@ -1964,6 +2019,199 @@ impl StorageGcService {
             .await
             .map_err(|e| AppError::Database(e.to_string()))
     }
+
+    /// Reclaim shared CAS objects whose reference count has dropped to zero
+    /// (storage dedup).
+    ///
+    /// With dedup enabled one `cas/sha256/` blob backs every artifact row
+    /// with that content on the backend, across repositories. The main sweep
+    /// never deletes such a blob: it hard-deletes the artifact rows, which
+    /// drops their `storage_refs`. This sweep then deletes the blob and its
+    /// `storage_cas_objects` row once [`UNREFERENCED_CAS_OBJECT_PREDICATE_SQL`]
+    /// holds, re-verified under a `FOR UPDATE` lock on the object row. An
+    /// upload claiming the same content blocks on that lock and, once the
+    /// row is gone, inserts a fresh one and rewrites the blob.
+    async fn cleanup_unreferenced_cas_objects(
+        &self,
+        dry_run: bool,
+        result: &mut StorageGcResult,
+    ) -> Result<()> {
+        let candidates = self.select_unreferenced_cas_objects().await?;
+        let mut objects_removed = 0_i64;
+
+        for row in candidates {
+            let storage_backend: String = row
+                .try_get("storage_backend")
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let checksum_sha256: String = row
+                .try_get("checksum_sha256")
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let storage_key: String = row
+                .try_get("storage_key")
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let bytes: i64 = row.try_get("size_bytes").unwrap_or(0);
+
+            if dry_run {
+                accumulate_dry_run(result, bytes, 0);
+                continue;
+            }
+
+            let storage = match self.storage_registry.cas_store(&storage_backend) {
+                Ok(s) => s,
+                Err(e) => {
+                    let msg = format_gc_error("resolve CAS storage", &storage_key, &e.to_string());
+                    tracing::warn!("{}", msg);
+                    result.errors.push(msg);
+                    continue;
+                }
+            };
+
+            let mut tx = match self.db.begin().await {
+                Ok(t) => t,
+                Err(e) => {
+                    let msg = format_gc_error("begin CAS gc tx", &storage_key, &e.to_string());
+                    tracing::warn!("{}", msg);
+                    result.errors.push(msg);
+                    continue;
+                }
+            };
+
+            match is_cas_object_still_unreferenced(&mut tx, &storage_backend, &checksum_sha256)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    let _ = tx.rollback().await;
+                    tracing::debug!(
+                        storage_key = storage_key.as_str(),
+                        "CAS GC skipped object: referenced again after row-lock re-check"
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    let msg = format_gc_error("re-check CAS refs", &storage_key, &e.to_string());
+                    tracing::warn!("{}", msg);
+                    result.errors.push(msg);
+                    continue;
+                }
+            }
+
+            match storage.delete(&storage_key).await {
+                Ok(()) | Err(AppError::NotFound(_)) => {}
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    let msg = format_gc_error("delete CAS object", &storage_key, &e.to_string());
+                    tracing::warn!("{}", msg);
+                    result.errors.push(msg);
+                    continue;
+                }
+            }
+
+            if let Err(e) = sqlx::query(
+                "DELETE FROM storage_cas_objects \
+                 WHERE storage_backend = $1 AND checksum_sha256 = $2",
+            )
+            .bind(&storage_backend)
+            .bind(&checksum_sha256)
+            .execute(&mut *tx)
+            .await
+            {
+                let _ = tx.rollback().await;
+                let msg = format_gc_error("delete CAS object row", &storage_key, &e.to_string());
+                tracing::warn!("{}", msg);
+                result.errors.push(msg);
+                continue;
+            }
+
+            if let Err(e) = tx.commit().await {
+                let msg = format_gc_error("commit CAS gc tx", &storage_key, &e.to_string());
+                tracing::warn!("{}", msg);
+                result.errors.push(msg);
+                continue;
+            }
+
+            objects_removed += 1;
+            record_gc_success(result, bytes, 0);
+        }
+
+        if objects_removed > 0 {
+            tracing::info!(
+                "Storage GC: reclaimed {} unreferenced shared CAS objects",
+                objects_removed
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Candidate scan for [`Self::cleanup_unreferenced_cas_objects`]. A
+    /// snapshot only — every candidate is re-verified under a row lock.
+    pub(crate) async fn select_unreferenced_cas_objects(
+        &self,
+    ) -> Result<Vec<sqlx::postgres::PgRow>> {
+        let sql = format!(
+            r#"
+            SELECT o.storage_backend, o.checksum_sha256, o.storage_key, o.size_bytes
+            FROM storage_cas_objects o
+            WHERE {predicate}
+            ORDER BY o.last_claimed_at
+            LIMIT $1
+            "#,
+            predicate = UNREFERENCED_CAS_OBJECT_PREDICATE_SQL,
+        );
+        sqlx::query(&sql)
+            .bind(UNREFERENCED_CAS_OBJECT_SCAN_LIMIT)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
+
+/// Lock a shared CAS object row and re-check that nothing references it.
+/// Returns `false` when the row is gone or is referenced again.
+///
+/// The lock is taken first and the predicate evaluated in a second statement
+/// so the check sees refs committed by anyone who held the lock before us.
+async fn is_cas_object_still_unreferenced(
+    tx: &mut Transaction<'_, Postgres>,
+    storage_backend: &str,
+    checksum_sha256: &str,
+) -> Result<bool> {
+    let locked = sqlx::query(
+        "SELECT checksum_sha256 FROM storage_cas_objects \
+         WHERE storage_backend = $1 AND checksum_sha256 = $2 \
+         FOR UPDATE",
+    )
+    .bind(storage_backend)
+    .bind(checksum_sha256)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    if locked.is_none() {
+        return Ok(false);
+    }
+
+    let sql = format!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM storage_cas_objects o
+            WHERE o.storage_backend = $1
+              AND o.checksum_sha256 = $2
+              AND {predicate}
+        ) AS still_unreferenced
+        "#,
+        predicate = UNREFERENCED_CAS_OBJECT_PREDICATE_SQL,
+    );
+    let row = sqlx::query(&sql)
+        .bind(storage_backend)
+        .bind(checksum_sha256)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(row
+        .try_get::<bool, _>("still_unreferenced")
+        .unwrap_or(false))
 }
 
 /// Delete the row-less checksum sidecars of a reclaimed Maven storage key and
@@ -3252,6 +3500,34 @@ mod tests {
         );
     }
 
+    // -----------------------------------------------------------------------
+    // Storage dedup: ref-counted shared CAS objects
+    // -----------------------------------------------------------------------
+
+    /// A shared CAS object stays while any ref or artifact row on its
+    /// backend points at it, and always for the claim window.
+    #[test]
+    fn test_cas_object_predicate_is_ref_count_aware() {
+        let sql = UNREFERENCED_CAS_OBJECT_PREDICATE_SQL;
+        assert!(sql.contains("FROM storage_refs sr"));
+        assert!(sql.contains("sr.checksum_sha256 = o.checksum_sha256"));
+        assert!(sql.contains("a.storage_key = o.storage_key"));
+        assert!(sql.contains("ar.storage_backend = o.storage_backend"));
+        assert!(sql.contains("o.last_claimed_at < NOW() - INTERVAL '1 hour'"));
+    }
+
+    #[tokio::test]
+    async fn test_cas_sweep_without_filesystem_root_fails_to_resolve() {
+        // Dedup switched off after objects were written: the sweep must not
+        // fall back to some relative filesystem root and delete from there.
+        let service = make_service("filesystem");
+        let err = match service.storage_registry.cas_store("filesystem") {
+            Ok(_) => panic!("filesystem CAS store resolved without a configured root"),
+            Err(e) => e,
+        };
+        assert!(matches!(err, AppError::Storage(_)));
+    }
+
     /// Insert a Maven `artifacts` row pointing at `storage_key`
     /// (`path` = key without the `maven/` prefix).
     async fn insert_maven_artifact_row(
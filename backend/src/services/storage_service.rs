@@ -423,6 +423,20 @@ macro_rules! impl_storage_wrapper {
             async fn get_stream(&self, key: &str) -> Result<BoxStream<'static, Result<Bytes>>> {
                 crate::storage::StorageBackend::get_stream(self.inner.as_ref(), key).await
             }
+            async fn get_range_stream(
+                &self,
+                key: &str,
+                offset: u64,
+                length: u64,
+            ) -> Result<BoxStream<'static, Result<Bytes>>> {
+                crate::storage::StorageBackend::get_range_stream(
+                    self.inner.as_ref(),
+                    key,
+                    offset,
+                    length,
+                )
+                .await
+            }
             async fn put_stream(
                 &self,
                 key: &str,
@@ -465,6 +479,102 @@ pub struct GcsBackendWrapper {
 
 impl_storage_wrapper!(GcsBackendWrapper, crate::storage::gcs::GcsBackend);
 
+/// Storage-layer view of a facade backend, so it can sit under
+/// [`crate::storage::encryption::EncryptedStorage`].
+struct FacadeAsStorage(Arc<dyn StorageBackend>);
+
+#[async_trait]
+impl crate::storage::StorageBackend for FacadeAsStorage {
+    async fn put(&self, key: &str, content: Bytes) -> Result<()> {
+        self.0.put(key, content).await
+    }
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        self.0.get(key).await
+    }
+    async fn exists(&self, key: &str) -> Result<bool> {
+        self.0.exists(key).await
+    }
+    async fn head_etag(&self, key: &str) -> Result<Option<String>> {
+        self.0.head_etag(key).await
+    }
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.0.delete(key).await
+    }
+    async fn copy(&self, source: &str, dest: &str) -> Result<()> {
+        self.0.copy(source, dest).await
+    }
+    async fn get_stream(&self, key: &str) -> Result<BoxStream<'static, Result<Bytes>>> {
+        self.0.get_stream(key).await
+    }
+    async fn put_stream(
+        &self,
+        key: &str,
+        stream: BoxStream<'static, Result<Bytes>>,
+    ) -> Result<crate::storage::PutStreamResult> {
+        let result = self.0.put_stream(key, stream).await?;
+        Ok(crate::storage::PutStreamResult {
+            checksum_sha256: result.checksum_sha256,
+            bytes_written: result.bytes_written,
+        })
+    }
+}
+
+/// Facade backend that encrypts object bodies (`STORAGE_ENCRYPTION`).
+///
+/// Presigning stays off (the trait defaults): a presigned URL would hand the
+/// client ciphertext.
+struct EncryptedBackend {
+    raw: Arc<dyn StorageBackend>,
+    raw_storage: Arc<dyn crate::storage::StorageBackend>,
+    encryption: Arc<crate::storage::encryption::EnvelopeEncryption>,
+    encrypted: Arc<dyn crate::storage::StorageBackend>,
+}
+
+#[async_trait]
+impl StorageBackend for EncryptedBackend {
+    async fn put(&self, key: &str, content: Bytes) -> Result<()> {
+        self.encrypted.put(key, content).await
+    }
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        self.encrypted.get(key).await
+    }
+    async fn exists(&self, key: &str) -> Result<bool> {
+        self.raw.exists(key).await
+    }
+    async fn head_etag(&self, key: &str) -> Result<Option<String>> {
+        self.raw.head_etag(key).await
+    }
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.raw.delete(key).await
+    }
+    async fn list(&self, prefix: Option<&str>) -> Result<Vec<String>> {
+        self.raw.list(prefix).await
+    }
+    async fn copy(&self, source: &str, dest: &str) -> Result<()> {
+        self.raw.copy(source, dest).await
+    }
+    async fn size(&self, key: &str) -> Result<u64> {
+        let stored = self.raw.size(key).await?;
+        self.encryption
+            .plaintext_len(self.raw_storage.as_ref(), key, stored)
+            .await
+    }
+    async fn get_stream(&self, key: &str) -> Result<BoxStream<'static, Result<Bytes>>> {
+        self.encrypted.get_stream(key).await
+    }
+    async fn put_stream(
+        &self,
+        key: &str,
+        stream: BoxStream<'static, Result<Bytes>>,
+    ) -> Result<PutStreamResult> {
+        let result = self.encrypted.put_stream(key, stream).await?;
+        Ok(PutStreamResult {
+            checksum_sha256: result.checksum_sha256,
+            bytes_written: result.bytes_written,
+        })
+    }
+}
+
 /// Storage service facade
 pub struct StorageService {
     /// Single backend handle. Presign capability is type-enforced on this
@@ -546,6 +656,24 @@ impl StorageService {
         Self { backend }
     }
 
+    /// Encrypt object bodies written through this service, and decrypt them
+    /// on read (`STORAGE_ENCRYPTION`).
+    pub fn with_encryption(
+        self,
+        encryption: Arc<crate::storage::encryption::EnvelopeEncryption>,
+    ) -> Self {
+        let raw_storage: Arc<dyn crate::storage::StorageBackend> =
+            Arc::new(FacadeAsStorage(self.backend.clone()));
+        Self {
+            backend: Arc::new(EncryptedBackend {
+                raw: self.backend,
+                encrypted: encryption.wrap_backend(raw_storage.clone()),
+                raw_storage,
+                encryption,
+            }),
+        }
+    }
+
     /// Calculate SHA-256 hash of content
     pub fn calculate_hash(content: &[u8]) -> String {
         let mut hasher = Sha256::new();
@@ -0,0 +1,272 @@
+//! Storage shard rebalancing (`S3_SHARD_BUCKETS`, `AZURE_SHARD_CONTAINERS`).
+//!
+//! After a shard is added to a sharded backend, objects stored before the
+//! change may sit on a shard that no longer owns them. They stay readable
+//! (reads fall back across shards), but every read of them pays for the
+//! extra lookups. A rebalance run walks every live artifact blob on the
+//! backend and moves each misplaced object to its owner. Objects already in
+//! place are left alone, so a run can be repeated safely after an
+//! interruption.
+//!
+//! Only artifact blobs are moved. Proxy-cache entries stay where they are
+//! until they are refreshed from upstream.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::storage::sharded::{RebalanceOutcome, ShardedStorage};
+use crate::storage::StorageRegistry;
+
+/// Distinct blobs fetched per page.
+const BATCH_SIZE: i64 = 500;
+
+/// A run whose progress has not been flushed for this long was abandoned by
+/// a restart and no longer blocks a new one.
+const STALE_RUN_SECS: i64 = 600;
+
+/// A recorded rebalance run.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct StorageShardRebalanceRun {
+    pub id: Uuid,
+    /// Registered backend name (`s3` or `azure`).
+    pub backend: String,
+    /// `running`, `completed`, `failed` or `interrupted`.
+    pub status: String,
+    pub requested_by: Option<Uuid>,
+    /// Shards the backend had when the run started.
+    pub shards: Vec<String>,
+    pub objects_scanned: i64,
+    pub objects_moved: i64,
+    /// Artifact blobs found on no shard.
+    pub objects_missing: i64,
+    pub objects_failed: i64,
+    pub last_error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// A sharded backend and its shards.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShardedBackendInfo {
+    pub backend: String,
+    pub shards: Vec<String>,
+}
+
+#[derive(Default)]
+struct Progress {
+    scanned: i64,
+    moved: i64,
+    missing: i64,
+    failed: i64,
+    last_error: Option<String>,
+}
+
+pub struct StorageShardService {
+    db: PgPool,
+    registry: Arc<StorageRegistry>,
+}
+
+impl StorageShardService {
+    pub fn new(db: PgPool, registry: Arc<StorageRegistry>) -> Self {
+        Self { db, registry }
+    }
+
+    pub fn backends(&self) -> Vec<ShardedBackendInfo> {
+        self.registry
+            .sharded_backend_names()
+            .into_iter()
+            .filter_map(|name| {
+                let sharded = self.registry.sharded_backend(&name)?;
+                Some(ShardedBackendInfo {
+                    backend: name,
+                    shards: sharded.shard_names(),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn list_runs(&self, limit: i64) -> Result<Vec<StorageShardRebalanceRun>> {
+        let runs = sqlx::query_as::<_, StorageShardRebalanceRun>(
+            "SELECT * FROM storage_shard_rebalance_runs ORDER BY started_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(runs)
+    }
+
+    /// Record a new run for `backend` and start it in the background. Fails
+    /// with `Conflict` while another run on the same backend is active.
+    pub async fn start_run(
+        self: Arc<Self>,
+        backend: &str,
+        requested_by: Option<Uuid>,
+    ) -> Result<StorageShardRebalanceRun> {
+        let sharded = self.registry.sharded_backend(backend).ok_or_else(|| {
+            AppError::Validation(format!(
+                "storage backend '{}' is not sharded; set S3_SHARD_BUCKETS or \
+                 AZURE_SHARD_CONTAINERS first",
+                backend
+            ))
+        })?;
+
+        sqlx::query(
+            r#"
+            UPDATE storage_shard_rebalance_runs
+            SET status = 'interrupted', finished_at = NOW(),
+                last_error = COALESCE(last_error, 'abandoned before completion')
+            WHERE status = 'running' AND backend = $1
+              AND updated_at < NOW() - make_interval(secs => $2)
+            "#,
+        )
+        .bind(backend)
+        .bind(STALE_RUN_SECS as f64)
+        .execute(&self.db)
+        .await?;
+
+        let run = sqlx::query_as::<_, StorageShardRebalanceRun>(
+            r#"
+            INSERT INTO storage_shard_rebalance_runs (backend, requested_by, shards)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(backend)
+        .bind(requested_by)
+        .bind(sharded.shard_names())
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => AppError::Conflict(format!(
+                "a shard rebalance of '{}' is already running",
+                backend
+            )),
+            other => AppError::Database(other.to_string()),
+        })?;
+
+        let run_id = run.id;
+        let backend = backend.to_string();
+        tokio::spawn(async move {
+            let (status, progress) = match self.execute(run_id, &backend, &sharded).await {
+                Ok(progress) => ("completed", progress),
+                Err((e, mut progress)) => {
+                    tracing::error!(run_id = %run_id, "Storage shard rebalance failed: {}", e);
+                    progress.last_error = Some(e.to_string());
+                    ("failed", progress)
+                }
+            };
+            if let Err(e) = self.flush(run_id, &progress, Some(status)).await {
+                tracing::warn!(run_id = %run_id, "Failed to record rebalance result: {}", e);
+            }
+            tracing::info!(
+                run_id = %run_id,
+                backend = %backend,
+                status,
+                scanned = progress.scanned,
+                moved = progress.moved,
+                missing = progress.missing,
+                failed = progress.failed,
+                "Storage shard rebalance finished"
+            );
+        });
+
+        Ok(run)
+    }
+
+    async fn execute(
+        &self,
+        run_id: Uuid,
+        backend: &str,
+        sharded: &ShardedStorage,
+    ) -> std::result::Result<Progress, (AppError, Progress)> {
+        let mut progress = Progress::default();
+        let mut cursor: Option<String> = None;
+        loop {
+            // Cloud backends share one namespace across repositories, so the
+            // storage key alone identifies the object.
+            let keys: Vec<String> = match sqlx::query_scalar(
+                r#"
+                SELECT DISTINCT a.storage_key
+                FROM artifacts a
+                JOIN repositories r ON r.id = a.repository_id
+                WHERE a.is_deleted = false
+                  AND r.storage_backend = $1
+                  AND ($2::text IS NULL OR a.storage_key > $2)
+                ORDER BY a.storage_key
+                LIMIT $3
+                "#,
+            )
+            .bind(backend)
+            .bind(cursor.as_deref())
+            .bind(BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await
+            {
+                Ok(keys) => keys,
+                Err(e) => return Err((AppError::Database(e.to_string()), progress)),
+            };
+            if keys.is_empty() {
+                return Ok(progress);
+            }
+
+            for key in keys {
+                progress.scanned += 1;
+                match sharded.rebalance_key(&key).await {
+                    Ok(RebalanceOutcome::Moved) => progress.moved += 1,
+                    Ok(RebalanceOutcome::Missing) => progress.missing += 1,
+                    Ok(RebalanceOutcome::InPlace) => {}
+                    Err(e) => {
+                        tracing::warn!(
+                            run_id = %run_id,
+                            backend = %backend,
+                            storage_key = %key,
+                            "Failed to move object to its shard: {}",
+                            e
+                        );
+                        progress.failed += 1;
+                        progress.last_error = Some(format!("{key}: {e}"));
+                    }
+                }
+                cursor = Some(key);
+            }
+
+            if let Err(e) = self.flush(run_id, &progress, None).await {
+                return Err((e, progress));
+            }
+        }
+    }
+
+    async fn flush(&self, run_id: Uuid, progress: &Progress, status: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE storage_shard_rebalance_runs
+            SET objects_scanned = $2,
+                objects_moved = $3,
+                objects_missing = $4,
+                objects_failed = $5,
+                last_error = $6,
+                status = COALESCE($7, status),
+                finished_at = CASE WHEN $7 IS NULL THEN NULL ELSE NOW() END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(run_id)
+        .bind(progress.scanned)
+        .bind(progress.moved)
+        .bind(progress.missing)
+        .bind(progress.failed)
+        .bind(&progress.last_error)
+        .bind(status)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
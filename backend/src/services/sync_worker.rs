@@ -10,6 +10,7 @@
 //! when syncing large Docker images, ML models, etc.
 
 use crate::services::cluster_work::{Claimed, WorkerIdentity};
+use crate::services::repository_config_replication_service::RepositoryConfigReplicationService;
 use crate::storage::{StorageLocation, StorageRegistry};
 use chrono::{NaiveTime, Timelike, Utc};
 use sqlx::PgPool;
@@ -199,8 +200,9 @@ pub(crate) fn format_stale_detection_log(
 /// Spawn the background sync worker.
 ///
 /// The worker runs in an infinite loop on a 10-second interval, picking up
-/// pending sync tasks and dispatching transfers to remote peers.  Every 60
-/// seconds it also checks for stale peers and marks them offline.
+/// pending sync tasks and dispatching transfers to remote peers, and pushing
+/// changed repository configuration to peers enrolled in config replication.
+/// Every 60 seconds it also checks for stale peers and marks them offline.
 pub async fn spawn_sync_worker(db: PgPool, storage_registry: Arc<StorageRegistry>) {
     tokio::spawn(async move {
         // Small startup delay so the server can finish initializing.
@@ -285,6 +287,15 @@ pub async fn spawn_sync_worker(db: PgPool, storage_registry: Arc<StorageRegistry
             {
                 tracing::error!("Sync worker error: {e}");
             }
+
+            // Push repository labels and policies that changed since their
+            // last push to peers enrolled in config replication.
+            if let Err(e) = RepositoryConfigReplicationService::new(db.clone())
+                .push_pending(&client)
+                .await
+            {
+                tracing::warn!("Config replication error: {e}");
+            }
         }
     });
 }
@@ -327,7 +338,14 @@ async fn run_peer_heartbeat_probes(
         }
     };
 
-    for peer in peers {
+    for mut peer in peers {
+        peer.api_key = crate::services::federation_service::peer_credential(
+            db,
+            &peer.endpoint_url,
+            &peer.api_key,
+            crate::services::federation_service::FederationScope::Replication,
+        )
+        .await;
         match probe_peer(client, &peer, request_timeout).await {
             Ok(()) => {
                 if let Err(e) = mark_peer_online(db, peer.id).await {
@@ -1032,6 +1050,15 @@ async fn process_pending_tasks(
                 resolve_scored_peer(db, local_peer_id, task.artifact_id, &peer.name)
                     .await
                     .unwrap_or_else(|| (peer.endpoint_url.clone(), peer.api_key.clone()));
+            // Peers registered without an API key authenticate us through
+            // federation trust instead; mint a short-lived token for them.
+            let peer_api_key = crate::services::federation_service::peer_credential(
+                db,
+                &peer_endpoint,
+                &peer_api_key,
+                crate::services::federation_service::FederationScope::Replication,
+            )
+            .await;
 
             let db = db.clone();
             let client = client.clone();
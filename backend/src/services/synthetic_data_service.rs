@@ -0,0 +1,707 @@
+//! Synthetic data for load and performance testing.
+//!
+//! Generates repositories, artifact rows and download history with realistic
+//! shapes: artifact sizes follow a log-normal distribution (most artifacts
+//! are small, a long tail is very large), and downloads follow a Zipf
+//! popularity curve so a handful of artifacts take most of the traffic.
+//!
+//! Only metadata is written. Artifact rows point at storage keys that hold
+//! no content, so the data exercises the database-heavy paths (search, GC
+//! candidate selection, policy evaluation, analytics) without consuming
+//! storage. Every repository is recorded against its run so the whole run
+//! can be removed without touching real data.
+
+use std::time::Instant;
+
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+pub const MAX_REPOSITORIES: u32 = 100;
+pub const MAX_ARTIFACTS_PER_REPOSITORY: u32 = 50_000;
+pub const MAX_DOWNLOAD_EVENTS: u32 = 1_000_000;
+pub const MAX_HISTORY_DAYS: u32 = 365;
+
+/// Rows per UNNEST insert, bounding the size of each statement's arrays.
+const INSERT_BATCH: usize = 5_000;
+
+/// Median artifact size (256 KiB) and spread of the log-normal size model.
+/// With sigma 2.2 roughly 1% of artifacts exceed 40 MiB.
+const SIZE_MEDIAN_BYTES: f64 = 256.0 * 1024.0;
+const SIZE_SIGMA: f64 = 2.2;
+const MIN_SIZE_BYTES: i64 = 512;
+const MAX_SIZE_BYTES: i64 = 4 * 1024 * 1024 * 1024;
+
+/// Zipf exponent for download popularity.
+const ZIPF_EXPONENT: f64 = 1.07;
+
+/// Average number of versions generated per package.
+const VERSIONS_PER_PACKAGE: u32 = 8;
+
+/// Name fragments for generated packages. Public so benchmark queries can
+/// search for terms that are known to match.
+pub const PACKAGE_PREFIXES: &[&str] = &[
+    "core", "data", "net", "auth", "cli", "web", "stream", "cache", "log", "config", "crypto",
+    "queue", "image", "report", "sync", "metrics",
+];
+pub const PACKAGE_SUFFIXES: &[&str] = &[
+    "utils", "client", "server", "sdk", "agent", "engine", "tools", "lib", "api", "worker",
+];
+
+/// File extensions and content types, weighted by position (earlier is more
+/// common).
+const ARTIFACT_KINDS: &[(&str, &str)] = &[
+    ("tar.gz", "application/gzip"),
+    ("zip", "application/zip"),
+    ("jar", "application/java-archive"),
+    ("tgz", "application/gzip"),
+    ("whl", "application/zip"),
+    ("bin", "application/octet-stream"),
+];
+
+const USER_AGENTS: &[&str] = &[
+    "curl/8.5.0",
+    "Wget/1.21.4",
+    "python-requests/2.31.0",
+    "Go-http-client/1.1",
+    "Apache-Maven/3.9.6",
+    "npm/10.2.4",
+];
+
+fn default_repositories() -> u32 {
+    5
+}
+
+fn default_artifacts_per_repository() -> u32 {
+    500
+}
+
+fn default_download_events() -> u32 {
+    10_000
+}
+
+fn default_history_days() -> u32 {
+    30
+}
+
+fn default_deleted_ratio() -> f64 {
+    0.05
+}
+
+/// What to generate.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyntheticDataSpec {
+    /// Repositories to create (default 5, max 100).
+    #[serde(default = "default_repositories")]
+    pub repositories: u32,
+    /// Artifacts per repository (default 500, max 50,000).
+    #[serde(default = "default_artifacts_per_repository")]
+    pub artifacts_per_repository: u32,
+    /// Download events to replay across the generated artifacts (default
+    /// 10,000, max 1,000,000).
+    #[serde(default = "default_download_events")]
+    pub download_events: u32,
+    /// Artifacts and downloads are spread over this many days (default 30).
+    #[serde(default = "default_history_days")]
+    pub history_days: u32,
+    /// Fraction of artifacts created soft-deleted, giving GC work to find
+    /// (default 0.05).
+    #[serde(default = "default_deleted_ratio")]
+    pub deleted_ratio: f64,
+    /// Seed for reproducible data. A random seed is used and recorded when
+    /// omitted.
+    pub seed: Option<u64>,
+}
+
+impl Default for SyntheticDataSpec {
+    fn default() -> Self {
+        Self {
+            repositories: default_repositories(),
+            artifacts_per_repository: default_artifacts_per_repository(),
+            download_events: default_download_events(),
+            history_days: default_history_days(),
+            deleted_ratio: default_deleted_ratio(),
+            seed: None,
+        }
+    }
+}
+
+impl SyntheticDataSpec {
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_REPOSITORIES).contains(&self.repositories) {
+            return Err(AppError::Validation(format!(
+                "repositories must be between 1 and {}",
+                MAX_REPOSITORIES
+            )));
+        }
+        if !(1..=MAX_ARTIFACTS_PER_REPOSITORY).contains(&self.artifacts_per_repository) {
+            return Err(AppError::Validation(format!(
+                "artifacts_per_repository must be between 1 and {}",
+                MAX_ARTIFACTS_PER_REPOSITORY
+            )));
+        }
+        if self.download_events > MAX_DOWNLOAD_EVENTS {
+            return Err(AppError::Validation(format!(
+                "download_events must be at most {}",
+                MAX_DOWNLOAD_EVENTS
+            )));
+        }
+        if !(1..=MAX_HISTORY_DAYS).contains(&self.history_days) {
+            return Err(AppError::Validation(format!(
+                "history_days must be between 1 and {}",
+                MAX_HISTORY_DAYS
+            )));
+        }
+        if !(0.0..1.0).contains(&self.deleted_ratio) {
+            return Err(AppError::Validation(
+                "deleted_ratio must be at least 0 and below 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A recorded generation run.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct SyntheticDataRun {
+    pub id: Uuid,
+    pub created_by: Option<Uuid>,
+    /// Seed the run was generated from; pass it back to reproduce the data.
+    pub seed: i64,
+    #[schema(value_type = Object)]
+    pub spec: serde_json::Value,
+    pub repositories_created: i32,
+    pub artifacts_created: i64,
+    pub downloads_created: i64,
+    /// Sum of the recorded artifact sizes (no content is stored).
+    pub total_bytes: i64,
+    pub duration_ms: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Sample an artifact size from the log-normal model.
+pub fn sample_size(rng: &mut impl Rng) -> i64 {
+    // Box-Muller; 1 - u keeps the logarithm's argument in (0, 1].
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random::<f64>();
+    let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+    let size = (SIZE_MEDIAN_BYTES.ln() + SIZE_SIGMA * z).exp();
+    (size as i64).clamp(MIN_SIZE_BYTES, MAX_SIZE_BYTES)
+}
+
+/// Cumulative Zipf weights for `n` ranks, normalised to end at 1.0.
+pub fn zipf_cumulative(n: usize) -> Vec<f64> {
+    let mut cumulative = Vec::with_capacity(n);
+    let mut total = 0.0;
+    for rank in 1..=n {
+        total += 1.0 / (rank as f64).powf(ZIPF_EXPONENT);
+        cumulative.push(total);
+    }
+    for weight in &mut cumulative {
+        *weight /= total;
+    }
+    cumulative
+}
+
+/// Rank drawn from cumulative weights built by [`zipf_cumulative`].
+pub fn sample_rank(cumulative: &[f64], rng: &mut impl Rng) -> usize {
+    let target: f64 = rng.random();
+    cumulative
+        .partition_point(|&w| w < target)
+        .min(cumulative.len().saturating_sub(1))
+}
+
+/// Package name for the `index`-th package of a repository. Names repeat
+/// across repositories, like real package names do across registries.
+pub fn package_name(index: u32) -> String {
+    let prefixes = PACKAGE_PREFIXES.len() as u32;
+    let suffixes = PACKAGE_SUFFIXES.len() as u32;
+    let combo = index % (prefixes * suffixes);
+    let base = format!(
+        "{}-{}",
+        PACKAGE_PREFIXES[(combo / suffixes) as usize],
+        PACKAGE_SUFFIXES[(combo % suffixes) as usize]
+    );
+    match index / (prefixes * suffixes) {
+        0 => base,
+        n => format!("{}{}", base, n + 1),
+    }
+}
+
+/// Semantic version for the `index`-th release of a package.
+pub fn package_version(index: u32) -> String {
+    format!("{}.{}.{}", 1 + index / 100, (index / 10) % 10, index % 10)
+}
+
+/// Key of the `index`-th repository (zero-based) of a run.
+pub fn repository_key(run_id: Uuid, index: u32) -> String {
+    format!(
+        "synthetic-{}-{:03}",
+        &run_id.simple().to_string()[..8],
+        index + 1
+    )
+}
+
+/// Artifact kind, skewed towards the first entries of [`ARTIFACT_KINDS`].
+fn sample_kind(rng: &mut impl Rng) -> (&'static str, &'static str) {
+    let roll: f64 = rng.random();
+    let index = ((roll * roll) * ARTIFACT_KINDS.len() as f64) as usize;
+    ARTIFACT_KINDS[index.min(ARTIFACT_KINDS.len() - 1)]
+}
+
+/// A generated artifact row.
+struct ArtifactRow {
+    path: String,
+    name: String,
+    version: String,
+    size_bytes: i64,
+    checksum_sha256: String,
+    content_type: String,
+    storage_key: String,
+    is_deleted: bool,
+    created_at: DateTime<Utc>,
+}
+
+fn generate_artifacts(
+    rng: &mut StdRng,
+    repository_id: Uuid,
+    spec: &SyntheticDataSpec,
+    now: DateTime<Utc>,
+) -> Vec<ArtifactRow> {
+    let count = spec.artifacts_per_repository;
+    let packages = count.div_ceil(VERSIONS_PER_PACKAGE).max(1);
+    let window_secs = i64::from(spec.history_days) * 86_400;
+
+    (0..count)
+        .map(|i| {
+            let name = package_name(i % packages);
+            let version = package_version(i / packages);
+            let (extension, content_type) = sample_kind(rng);
+            let path = format!("{name}/{version}/{name}-{version}.{extension}");
+            let checksum_sha256 = hex::encode(Sha256::digest(
+                format!("synthetic:{repository_id}:{path}").as_bytes(),
+            ));
+            let age_secs = rng.random_range(0..window_secs);
+            ArtifactRow {
+                storage_key: format!("synthetic/{checksum_sha256}"),
+                size_bytes: sample_size(rng),
+                is_deleted: rng.random::<f64>() < spec.deleted_ratio,
+                created_at: now - Duration::seconds(age_secs),
+                content_type: content_type.to_string(),
+                checksum_sha256,
+                path,
+                name,
+                version,
+            }
+        })
+        .collect()
+}
+
+pub struct SyntheticDataService {
+    db: PgPool,
+}
+
+impl SyntheticDataService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Generate a run. Repositories use `storage_backend`; filesystem paths
+    /// are placed under `storage_base` like regular repositories. On failure
+    /// everything created so far is removed.
+    pub async fn generate(
+        &self,
+        spec: SyntheticDataSpec,
+        created_by: Option<Uuid>,
+        storage_backend: &str,
+        storage_base: &str,
+    ) -> Result<SyntheticDataRun> {
+        spec.validate()?;
+        let seed = spec.seed.unwrap_or_else(rand::random);
+        let spec = SyntheticDataSpec {
+            seed: Some(seed),
+            ..spec
+        };
+        let spec_json =
+            serde_json::to_value(&spec).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let run_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO synthetic_data_runs (created_by, seed, spec) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(created_by)
+        // Stored bit-for-bit; BIGINT has no unsigned variant.
+        .bind(seed as i64)
+        .bind(&spec_json)
+        .fetch_one(&self.db)
+        .await?;
+
+        let started = Instant::now();
+        match self
+            .populate(run_id, &spec, seed, storage_backend, storage_base)
+            .await
+        {
+            Ok(counts) => {
+                let run = sqlx::query_as::<_, SyntheticDataRun>(
+                    r#"
+                    UPDATE synthetic_data_runs
+                    SET repositories_created = $2, artifacts_created = $3,
+                        downloads_created = $4, total_bytes = $5, duration_ms = $6
+                    WHERE id = $1
+                    RETURNING *
+                    "#,
+                )
+                .bind(run_id)
+                .bind(counts.repositories)
+                .bind(counts.artifacts)
+                .bind(counts.downloads)
+                .bind(counts.total_bytes)
+                .bind(started.elapsed().as_millis() as i64)
+                .fetch_one(&self.db)
+                .await?;
+                Ok(run)
+            }
+            Err(e) => {
+                if let Err(cleanup) = self.delete_run(run_id).await {
+                    tracing::warn!(run_id = %run_id, "Failed to remove partial synthetic data: {}", cleanup);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn populate(
+        &self,
+        run_id: Uuid,
+        spec: &SyntheticDataSpec,
+        seed: u64,
+        storage_backend: &str,
+        storage_base: &str,
+    ) -> Result<RunCounts> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let now = Utc::now();
+        let mut counts = RunCounts::default();
+        // (artifact id, created_at) of every live artifact, for download replay.
+        let mut live: Vec<(Uuid, DateTime<Utc>)> = Vec::new();
+
+        for index in 0..spec.repositories {
+            let key = repository_key(run_id, index);
+            let storage_path = if storage_backend == "filesystem" {
+                format!("{}/{}", storage_base, key)
+            } else {
+                key.clone()
+            };
+            let repository_id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO repositories (key, name, description, format, repo_type,
+                                          storage_backend, storage_path)
+                VALUES ($1, $1, $2, 'generic', 'local', $3, $4)
+                RETURNING id
+                "#,
+            )
+            .bind(&key)
+            .bind(format!("Synthetic load-test data (run {})", run_id))
+            .bind(storage_backend)
+            .bind(&storage_path)
+            .fetch_one(&self.db)
+            .await?;
+            sqlx::query(
+                "INSERT INTO synthetic_data_repositories (run_id, repository_id) VALUES ($1, $2)",
+            )
+            .bind(run_id)
+            .bind(repository_id)
+            .execute(&self.db)
+            .await?;
+            counts.repositories += 1;
+
+            let rows = generate_artifacts(&mut rng, repository_id, spec, now);
+            for batch in rows.chunks(INSERT_BATCH) {
+                let inserted = self.insert_artifacts(repository_id, batch).await?;
+                counts.artifacts += batch.len() as i64;
+                counts.total_bytes += batch.iter().map(|r| r.size_bytes).sum::<i64>();
+                live.extend(inserted);
+            }
+        }
+
+        counts.downloads = self
+            .replay_downloads(&mut rng, &mut live, spec.download_events, now)
+            .await?;
+        Ok(counts)
+    }
+
+    /// Insert a batch, returning the id and creation time of each live row.
+    async fn insert_artifacts(
+        &self,
+        repository_id: Uuid,
+        rows: &[ArtifactRow],
+    ) -> Result<Vec<(Uuid, DateTime<Utc>)>> {
+        let inserted: Vec<(Uuid, DateTime<Utc>, bool)> = sqlx::query_as(
+            r#"
+            INSERT INTO artifacts (
+                repository_id, path, name, version, size_bytes, checksum_sha256,
+                content_type, storage_key, is_deleted, created_at, updated_at
+            )
+            SELECT $1, t.path, t.name, t.version, t.size_bytes, t.checksum_sha256,
+                   t.content_type, t.storage_key, t.is_deleted, t.created_at, t.created_at
+            FROM UNNEST($2::text[], $3::text[], $4::text[], $5::bigint[], $6::text[],
+                        $7::text[], $8::text[], $9::bool[], $10::timestamptz[])
+                AS t(path, name, version, size_bytes, checksum_sha256,
+                     content_type, storage_key, is_deleted, created_at)
+            RETURNING id, created_at, is_deleted
+            "#,
+        )
+        .bind(repository_id)
+        .bind(rows.iter().map(|r| r.path.clone()).collect::<Vec<_>>())
+        .bind(rows.iter().map(|r| r.name.clone()).collect::<Vec<_>>())
+        .bind(rows.iter().map(|r| r.version.clone()).collect::<Vec<_>>())
+        .bind(rows.iter().map(|r| r.size_bytes).collect::<Vec<_>>())
+        .bind(
+            rows.iter()
+                .map(|r| r.checksum_sha256.clone())
+                .collect::<Vec<_>>(),
+        )
+        .bind(
+            rows.iter()
+                .map(|r| r.content_type.clone())
+                .collect::<Vec<_>>(),
+        )
+        .bind(
+            rows.iter()
+                .map(|r| r.storage_key.clone())
+                .collect::<Vec<_>>(),
+        )
+        .bind(rows.iter().map(|r| r.is_deleted).collect::<Vec<_>>())
+        .bind(rows.iter().map(|r| r.created_at).collect::<Vec<_>>())
+        .fetch_all(&self.db)
+        .await?;
+        Ok(inserted
+            .into_iter()
+            .filter(|(_, _, deleted)| !deleted)
+            .map(|(id, created_at, _)| (id, created_at))
+            .collect())
+    }
+
+    /// Record `events` downloads across `artifacts`, shuffled so popularity
+    /// is independent of generation order. Each download happens between the
+    /// artifact's creation and `now`.
+    async fn replay_downloads(
+        &self,
+        rng: &mut StdRng,
+        artifacts: &mut [(Uuid, DateTime<Utc>)],
+        events: u32,
+        now: DateTime<Utc>,
+    ) -> Result<i64> {
+        if artifacts.is_empty() || events == 0 {
+            return Ok(0);
+        }
+        rand::seq::SliceRandom::shuffle(artifacts, rng);
+        let cumulative = zipf_cumulative(artifacts.len());
+
+        let mut recorded = 0i64;
+        let mut remaining = events as usize;
+        while remaining > 0 {
+            let batch = remaining.min(INSERT_BATCH);
+            let mut ids = Vec::with_capacity(batch);
+            let mut ips = Vec::with_capacity(batch);
+            let mut agents = Vec::with_capacity(batch);
+            let mut times = Vec::with_capacity(batch);
+            for _ in 0..batch {
+                let (id, created_at) = artifacts[sample_rank(&cumulative, rng)];
+                let span = (now - created_at).num_seconds().max(1);
+                ids.push(id);
+                // TEST-NET-2 (RFC 5737) so synthetic clients are recognisable.
+                ips.push(format!("198.51.100.{}", rng.random_range(1..=254)));
+                agents.push(USER_AGENTS[rng.random_range(0..USER_AGENTS.len())].to_string());
+                times.push(created_at + Duration::seconds(rng.random_range(0..span)));
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO download_statistics (artifact_id, ip_address, user_agent, downloaded_at)
+                SELECT * FROM UNNEST($1::uuid[], $2::text[], $3::text[], $4::timestamptz[])
+                "#,
+            )
+            .bind(&ids)
+            .bind(&ips)
+            .bind(&agents)
+            .bind(&times)
+            .execute(&self.db)
+            .await?;
+            recorded += batch as i64;
+            remaining -= batch;
+        }
+        Ok(recorded)
+    }
+
+    /// Recorded runs, newest first.
+    pub async fn list_runs(&self, limit: i64) -> Result<Vec<SyntheticDataRun>> {
+        let runs = sqlx::query_as::<_, SyntheticDataRun>(
+            "SELECT * FROM synthetic_data_runs ORDER BY created_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(runs)
+    }
+
+    /// Remove a run and every repository it created (artifacts and download
+    /// history cascade). Returns the number of repositories removed.
+    pub async fn delete_run(&self, id: Uuid) -> Result<u64> {
+        let mut tx = self.db.begin().await?;
+        let removed = sqlx::query(
+            r#"
+            DELETE FROM repositories
+            WHERE id IN (SELECT repository_id FROM synthetic_data_repositories WHERE run_id = $1)
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        let deleted = sqlx::query("DELETE FROM synthetic_data_runs WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!(
+                "Synthetic data run {} not found",
+                id
+            )));
+        }
+        tx.commit().await?;
+        Ok(removed)
+    }
+}
+
+#[derive(Default)]
+struct RunCounts {
+    repositories: i32,
+    artifacts: i64,
+    downloads: i64,
+    total_bytes: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_defaults_are_valid() {
+        let spec: SyntheticDataSpec = serde_json::from_str("{}").unwrap();
+        assert_eq!(spec.repositories, 5);
+        assert_eq!(spec.artifacts_per_repository, 500);
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_spec_rejects_out_of_range_values() {
+        let invalid = [
+            SyntheticDataSpec {
+                repositories: 0,
+                ..Default::default()
+            },
+            SyntheticDataSpec {
+                repositories: MAX_REPOSITORIES + 1,
+                ..Default::default()
+            },
+            SyntheticDataSpec {
+                artifacts_per_repository: MAX_ARTIFACTS_PER_REPOSITORY + 1,
+                ..Default::default()
+            },
+            SyntheticDataSpec {
+                download_events: MAX_DOWNLOAD_EVENTS + 1,
+                ..Default::default()
+            },
+            SyntheticDataSpec {
+                history_days: 0,
+                ..Default::default()
+            },
+            SyntheticDataSpec {
+                deleted_ratio: 1.0,
+                ..Default::default()
+            },
+        ];
+        for spec in invalid {
+            assert!(spec.validate().is_err(), "{:?} should be rejected", spec);
+        }
+    }
+
+    #[test]
+    fn test_sample_size_distribution() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut sizes: Vec<i64> = (0..10_000).map(|_| sample_size(&mut rng)).collect();
+        sizes.sort_unstable();
+        assert!(sizes
+            .iter()
+            .all(|s| (MIN_SIZE_BYTES..=MAX_SIZE_BYTES).contains(s)));
+        // The median lands near the model median and the tail is long.
+        let median = sizes[sizes.len() / 2] as f64;
+        assert!(
+            (median / SIZE_MEDIAN_BYTES - 1.0).abs() < 0.15,
+            "median {median}"
+        );
+        assert!(sizes[sizes.len() * 99 / 100] > 20 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_zipf_is_skewed_towards_low_ranks() {
+        let cumulative = zipf_cumulative(1_000);
+        assert!((cumulative[999] - 1.0).abs() < 1e-9);
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut top_ten = 0;
+        for _ in 0..10_000 {
+            let rank = sample_rank(&cumulative, &mut rng);
+            assert!(rank < 1_000);
+            if rank < 10 {
+                top_ten += 1;
+            }
+        }
+        // 1% of the artifacts take well over a third of the downloads.
+        assert!(top_ten > 3_500, "top ten ranks drew {top_ten}");
+    }
+
+    #[test]
+    fn test_package_names_and_versions() {
+        assert_eq!(package_name(0), "core-utils");
+        assert_eq!(package_name(11), "data-client");
+        let combos = (PACKAGE_PREFIXES.len() * PACKAGE_SUFFIXES.len()) as u32;
+        assert_eq!(package_name(combos), "core-utils2");
+        assert_eq!(package_version(0), "1.0.0");
+        assert_eq!(package_version(123), "2.2.3");
+    }
+
+    #[test]
+    fn test_repository_key() {
+        let run_id = Uuid::parse_str("0123abcd-0000-0000-0000-000000000000").unwrap();
+        assert_eq!(repository_key(run_id, 0), "synthetic-0123abcd-001");
+        assert_eq!(repository_key(run_id, 41), "synthetic-0123abcd-042");
+    }
+
+    #[test]
+    fn test_generated_artifacts_are_unique_and_reproducible() {
+        let spec = SyntheticDataSpec {
+            artifacts_per_repository: 300,
+            ..Default::default()
+        };
+        let repo = Uuid::nil();
+        let now = Utc::now();
+        let first = generate_artifacts(&mut StdRng::seed_from_u64(3), repo, &spec, now);
+        let second = generate_artifacts(&mut StdRng::seed_from_u64(3), repo, &spec, now);
+
+        let paths: std::collections::HashSet<_> = first.iter().map(|r| &r.path).collect();
+        assert_eq!(paths.len(), 300);
+        assert!(first
+            .iter()
+            .zip(&second)
+            .all(|(a, b)| a.path == b.path && a.size_bytes == b.size_bytes));
+        assert!(first
+            .iter()
+            .all(|r| r.created_at <= now && r.storage_key.ends_with(&r.checksum_sha256)));
+    }
+}
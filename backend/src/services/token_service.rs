@@ -1132,6 +1132,7 @@ mod tests {
         // adds the route -- the deliberate review #1315 requires.
         let expected: BTreeMap<&str, &str> = BTreeMap::from([
             ("auth.rs", "enforce_admin_only_scopes"), // POST /api/v1/auth/tokens
+            ("npm.rs", "enforce_admin_only_scopes"),  // PUT /npm/:key/-/user/org.couchdb.user:name
             ("profile.rs", "enforce_admin_only_scopes"), // POST /api/v1/profile/access-tokens
             ("users.rs", "enforce_admin_only_scopes"), // POST /api/v1/users/:id/tokens
             ("repo_tokens.rs", "enforce_admin_only_scopes"), // POST /api/v1/repositories/:key/tokens
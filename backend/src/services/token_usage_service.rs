@@ -0,0 +1,611 @@
+//! API token usage analytics and stale-token expiry.
+//!
+//! Every successful API token validation calls [`record_token_use`], which
+//! counts the use in memory together with the request's client IP. The
+//! scheduler flushes the counters every minute into `api_tokens.use_count`,
+//! `last_used_at` / `last_used_ip` and the per-IP `api_token_usage_ips` table,
+//! so the hot path never waits on the database.
+//!
+//! With `API_TOKEN_STALE_DAYS` set, an hourly sweep warns the owner of a token
+//! that has gone unused for `stale - warning` days and revokes it once it has
+//! been unused for the full `stale` days, provided the warning went out at
+//! least `API_TOKEN_STALE_WARNING_DAYS` earlier. Any use clears the warning.
+//! A token nobody can be warned about (SMTP is off, or neither the owner nor
+//! the issuing admin has an email address) is never revoked; the sweep counts
+//! it as unnotifiable and the stale report flags it.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::middleware::request_client::current_request_client;
+use crate::error::{AppError, Result};
+use crate::services::audit_export;
+use crate::services::audit_service::{AuditAction, AuditEntry, AuditService, ResourceType};
+use crate::services::smtp_service::SmtpService;
+
+/// Distinct source IPs kept per token between flushes. Uses from further IPs
+/// still count toward the token's total.
+const MAX_PENDING_IPS: usize = 64;
+
+/// Tokens warned or revoked per sweep.
+const SWEEP_BATCH: i64 = 500;
+
+// ---------------------------------------------------------------------------
+// In-memory counters
+// ---------------------------------------------------------------------------
+
+/// Uses of one token since the last flush.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PendingUsage {
+    pub calls: i64,
+    pub ips: HashMap<String, i64>,
+    pub last_ip: Option<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+pub struct TokenUsageCounter {
+    tokens: Mutex<HashMap<Uuid, PendingUsage>>,
+}
+
+impl TokenUsageCounter {
+    pub fn record(&self, token_id: Uuid, ip: Option<IpAddr>, at: DateTime<Utc>) {
+        let Ok(mut tokens) = self.tokens.lock() else {
+            return;
+        };
+        let usage = tokens.entry(token_id).or_default();
+        usage.calls += 1;
+        usage.last_used_at = Some(at);
+        if let Some(ip) = ip {
+            let ip = ip.to_string();
+            if usage.ips.len() < MAX_PENDING_IPS || usage.ips.contains_key(&ip) {
+                *usage.ips.entry(ip.clone()).or_default() += 1;
+            }
+            usage.last_ip = Some(ip);
+        }
+    }
+
+    /// Take every pending counter, leaving the counter empty.
+    pub fn drain(&self) -> Vec<(Uuid, PendingUsage)> {
+        match self.tokens.lock() {
+            Ok(mut tokens) => tokens.drain().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+static PENDING_USAGE: Lazy<TokenUsageCounter> = Lazy::new(TokenUsageCounter::default);
+
+/// Count one use of `token_id` by the in-flight request. A token validated
+/// more than once during the same request is counted once.
+pub fn record_token_use(token_id: Uuid) {
+    let ip = match current_request_client() {
+        Some(client) => {
+            if !client.first_use_of(token_id) {
+                return;
+            }
+            client.ip
+        }
+        None => None,
+    };
+    PENDING_USAGE.record(token_id, ip, Utc::now());
+}
+
+// ---------------------------------------------------------------------------
+// Stale-token policy
+// ---------------------------------------------------------------------------
+
+/// Idle time after which a token's owner is warned. The warning window is
+/// capped at the stale period itself.
+pub fn warn_after_days(stale_days: u32, warning_days: u32) -> u32 {
+    stale_days.saturating_sub(warning_days)
+}
+
+/// Build the plain-text body for a stale-token warning email.
+pub fn build_warning_text(
+    username: &str,
+    token_name: &str,
+    idle_days: i64,
+    days_left: i64,
+) -> String {
+    let deadline = match days_left {
+        d if d <= 1 => "within a day".to_string(),
+        d => format!("in {d} days"),
+    };
+    format!(
+        "Hello {username},\n\n\
+         Your API token \"{token_name}\" has not been used for {idle_days} days \
+         and will be revoked {deadline}. Use the token to keep it active, or \
+         revoke it yourself if it is no longer needed.\n\n\
+         Artifact Keeper"
+    )
+}
+
+/// Build the HTML body for a stale-token warning email.
+pub fn build_warning_html(
+    username: &str,
+    token_name: &str,
+    idle_days: i64,
+    days_left: i64,
+) -> String {
+    let deadline = match days_left {
+        d if d <= 1 => "<strong>within a day</strong>".to_string(),
+        d => format!("in <strong>{d} days</strong>"),
+    };
+    format!(
+        "<h2>Unused API Token</h2>\
+         <p>Hello {username},</p>\
+         <p>Your API token <code>{token_name}</code> has not been used for \
+         {idle_days} days and will be revoked {deadline}.</p>\
+         <p>Use the token to keep it active, or revoke it yourself if it is no \
+         longer needed.</p>\
+         <p>Artifact Keeper</p>",
+        token_name = html_escape(token_name),
+    )
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// ---------------------------------------------------------------------------
+// Reports
+// ---------------------------------------------------------------------------
+
+/// Calls from one source IP.
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct TokenIpUsage {
+    pub ip: String,
+    pub call_count: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// Usage of one API token.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenUsage {
+    pub token_id: Uuid,
+    pub name: String,
+    pub use_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    /// Set while the owner has been warned that the token is about to be
+    /// revoked for inactivity.
+    pub stale_warned_at: Option<DateTime<Utc>>,
+    /// Source IPs, most recently seen first.
+    pub ips: Vec<TokenIpUsage>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TokenUsageRow {
+    id: Uuid,
+    name: String,
+    use_count: i64,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    last_used_ip: Option<String>,
+    stale_warned_at: Option<DateTime<Utc>>,
+}
+
+/// An active token that has not been used for a while.
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct StaleToken {
+    pub id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub user_id: Uuid,
+    pub username: String,
+    pub use_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Whole days since the last use, or since creation when never used.
+    pub idle_days: i64,
+    pub stale_warned_at: Option<DateTime<Utc>>,
+    /// Nobody can be emailed about this token, so the stale sweep leaves it
+    /// alone instead of revoking it unannounced.
+    pub unnotifiable: bool,
+}
+
+/// Outcome of one stale-token sweep.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StaleSweepSummary {
+    pub warned: u32,
+    pub revoked: u32,
+    /// Tokens due a warning that nobody could be emailed about; they are
+    /// neither marked nor revoked.
+    pub unnotifiable: u32,
+}
+
+#[derive(sqlx::FromRow)]
+struct WarnCandidate {
+    id: Uuid,
+    name: String,
+    username: String,
+    email: Option<String>,
+    idle_since: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct RevokedToken {
+    id: Uuid,
+    name: String,
+}
+
+pub struct TokenUsageService {
+    db: PgPool,
+}
+
+impl TokenUsageService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Write the pending in-memory counters to the database. Returns the
+    /// number of tokens updated.
+    pub async fn flush_pending(&self) -> Result<usize> {
+        let pending = PENDING_USAGE.drain();
+        let count = pending.len();
+        for (token_id, usage) in pending {
+            self.write_usage(token_id, &usage).await?;
+        }
+        Ok(count)
+    }
+
+    async fn write_usage(&self, token_id: Uuid, usage: &PendingUsage) -> Result<()> {
+        let last_used_at = usage.last_used_at.unwrap_or_else(Utc::now);
+        // A use ends any pending stale warning.
+        sqlx::query(
+            r#"
+            UPDATE api_tokens
+            SET use_count = use_count + $2,
+                last_used_at = GREATEST(COALESCE(last_used_at, $3), $3),
+                last_used_ip = COALESCE($4, last_used_ip),
+                stale_warned_at = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(token_id)
+        .bind(usage.calls)
+        .bind(last_used_at)
+        .bind(&usage.last_ip)
+        .execute(&self.db)
+        .await?;
+
+        if usage.ips.is_empty() {
+            return Ok(());
+        }
+        let (ips, counts): (Vec<String>, Vec<i64>) =
+            usage.ips.iter().map(|(ip, n)| (ip.clone(), *n)).unzip();
+        sqlx::query(
+            r#"
+            INSERT INTO api_token_usage_ips (token_id, ip, call_count, first_seen_at, last_seen_at)
+            SELECT $1, u.ip, u.n, $4, $4
+            FROM UNNEST($2::text[], $3::bigint[]) AS u(ip, n)
+            WHERE EXISTS (SELECT 1 FROM api_tokens WHERE id = $1)
+            ON CONFLICT (token_id, ip) DO UPDATE
+            SET call_count = api_token_usage_ips.call_count + EXCLUDED.call_count,
+                last_seen_at = GREATEST(api_token_usage_ips.last_seen_at, EXCLUDED.last_seen_at)
+            "#,
+        )
+        .bind(token_id)
+        .bind(&ips)
+        .bind(&counts)
+        .bind(last_used_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Usage of `token_id`, which must belong to `user_id`.
+    pub async fn token_usage(&self, user_id: Uuid, token_id: Uuid) -> Result<TokenUsage> {
+        let row = sqlx::query_as::<_, TokenUsageRow>(
+            r#"
+            SELECT id, name, use_count, created_at, last_used_at, last_used_ip, stale_warned_at
+            FROM api_tokens
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(token_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("API token not found".to_string()))?;
+
+        let ips = sqlx::query_as::<_, TokenIpUsage>(
+            r#"
+            SELECT ip, call_count, first_seen_at, last_seen_at
+            FROM api_token_usage_ips
+            WHERE token_id = $1
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(token_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(TokenUsage {
+            token_id: row.id,
+            name: row.name,
+            use_count: row.use_count,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+            last_used_ip: row.last_used_ip,
+            stale_warned_at: row.stale_warned_at,
+            ips,
+        })
+    }
+
+    /// Active tokens unused for at least `idle_days`, longest idle first.
+    /// `smtp_configured` is whether warnings can be emailed at all.
+    pub async fn stale_tokens(
+        &self,
+        idle_days: u32,
+        limit: i64,
+        smtp_configured: bool,
+    ) -> Result<Vec<StaleToken>> {
+        let tokens = sqlx::query_as::<_, StaleToken>(
+            r#"
+            SELECT t.id, t.name, t.token_prefix, t.user_id, u.username, t.use_count,
+                   t.created_at, t.last_used_at,
+                   EXTRACT(DAY FROM NOW() - COALESCE(t.last_used_at, t.created_at))::BIGINT
+                       AS idle_days,
+                   t.stale_warned_at,
+                   (NOT $3::BOOLEAN OR COALESCE(NULLIF(u.email, ''), NULLIF(c.email, '')) IS NULL)
+                       AS unnotifiable
+            FROM api_tokens t
+            JOIN users u ON u.id = t.user_id
+            LEFT JOIN users c ON c.id = t.created_by_user_id
+            WHERE t.revoked_at IS NULL
+              AND (t.expires_at IS NULL OR t.expires_at > NOW())
+              AND COALESCE(t.last_used_at, t.created_at) <= NOW() - make_interval(days => $1)
+            ORDER BY COALESCE(t.last_used_at, t.created_at)
+            LIMIT $2
+            "#,
+        )
+        .bind(idle_days as i32)
+        .bind(limit)
+        .bind(smtp_configured)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(tokens)
+    }
+
+    /// Run one cycle of the stale-token policy: warn owners of tokens nearing
+    /// the limit, then revoke warned tokens past it. A token whose warning
+    /// cannot be emailed stays unwarned, so it is never revoked.
+    pub async fn run_stale_sweep(
+        &self,
+        smtp: Option<&SmtpService>,
+        stale_days: u32,
+        warning_days: u32,
+    ) -> Result<StaleSweepSummary> {
+        let mut summary = StaleSweepSummary::default();
+        if stale_days == 0 {
+            return Ok(summary);
+        }
+        let now = Utc::now();
+
+        // Service account tokens are reported to the admin who issued them.
+        let candidates = sqlx::query_as::<_, WarnCandidate>(
+            r#"
+            SELECT t.id, t.name, u.username,
+                   COALESCE(NULLIF(u.email, ''), NULLIF(c.email, '')) AS email,
+                   COALESCE(t.last_used_at, t.created_at) AS idle_since
+            FROM api_tokens t
+            JOIN users u ON u.id = t.user_id
+            LEFT JOIN users c ON c.id = t.created_by_user_id
+            WHERE t.revoked_at IS NULL
+              AND t.stale_warned_at IS NULL
+              AND (t.expires_at IS NULL OR t.expires_at > NOW())
+              AND COALESCE(t.last_used_at, t.created_at) <= NOW() - make_interval(days => $1)
+            ORDER BY COALESCE(t.last_used_at, t.created_at)
+            LIMIT $2
+            "#,
+        )
+        .bind(warn_after_days(stale_days, warning_days) as i32)
+        .bind(SWEEP_BATCH)
+        .fetch_all(&self.db)
+        .await?;
+
+        let smtp = smtp.filter(|s| s.is_configured());
+        for token in candidates {
+            let (Some(smtp), Some(email)) = (smtp, token.email.as_deref()) else {
+                summary.unnotifiable += 1;
+                continue;
+            };
+            let idle_days = (now - token.idle_since).num_days();
+            // Revocation waits out the full warning period even when the
+            // sweep runs late.
+            let days_left =
+                (stale_days as i64 - idle_days).max(warning_days.min(stale_days) as i64);
+            let subject = format!("API token \"{}\" will be revoked", token.name);
+            let text = build_warning_text(&token.username, &token.name, idle_days, days_left);
+            let html = build_warning_html(&token.username, &token.name, idle_days, days_left);
+            if let Err(e) = smtp.send_email(email, &subject, &html, &text).await {
+                // Not marked as warned, so the next sweep retries.
+                tracing::warn!(token_id = %token.id, "Failed to send stale token warning: {}", e);
+                continue;
+            }
+            sqlx::query(
+                "UPDATE api_tokens SET stale_warned_at = NOW() WHERE id = $1 AND stale_warned_at IS NULL",
+            )
+            .bind(token.id)
+            .execute(&self.db)
+            .await?;
+            summary.warned += 1;
+        }
+
+        let revoked = sqlx::query_as::<_, RevokedToken>(
+            r#"
+            UPDATE api_tokens
+            SET revoked_at = NOW(), revoked_reason = 'stale'
+            WHERE id IN (
+                SELECT id FROM api_tokens
+                WHERE revoked_at IS NULL
+                  AND stale_warned_at IS NOT NULL
+                  AND stale_warned_at <= NOW() - make_interval(days => $2)
+                  AND COALESCE(last_used_at, created_at) <= NOW() - make_interval(days => $1)
+                LIMIT $3
+            )
+            RETURNING id, name
+            "#,
+        )
+        .bind(stale_days as i32)
+        .bind(warning_days.min(stale_days) as i32)
+        .bind(SWEEP_BATCH)
+        .fetch_all(&self.db)
+        .await?;
+
+        let audit = AuditService::new(self.db.clone());
+        for token in revoked {
+            crate::services::auth_service::mark_api_token_revoked(token.id);
+            let entry = AuditEntry::new(AuditAction::ApiTokenRevoked, ResourceType::ApiToken)
+                .resource(token.id)
+                .actor_name("system")
+                .details_typed(audit_export::details::TokenDetails::new(
+                    token.id,
+                    Some(&token.name),
+                    "stale_expiry",
+                ));
+            if let Err(e) = audit.log(entry).await {
+                tracing::warn!(token_id = %token.id, "Failed to audit stale token revocation: {}", e);
+            }
+            summary.revoked += 1;
+        }
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_aggregates_calls_and_ips() {
+        let counter = TokenUsageCounter::default();
+        let token = Uuid::new_v4();
+        let a: IpAddr = "10.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.2".parse().unwrap();
+        let now = Utc::now();
+        counter.record(token, Some(a), now);
+        counter.record(token, Some(a), now);
+        counter.record(token, Some(b), now);
+        counter.record(token, None, now);
+
+        let drained = counter.drain();
+        assert_eq!(drained.len(), 1);
+        let usage = &drained[0].1;
+        assert_eq!(usage.calls, 4);
+        assert_eq!(usage.ips.get("10.0.0.1"), Some(&2));
+        assert_eq!(usage.ips.get("10.0.0.2"), Some(&1));
+        assert_eq!(usage.last_ip.as_deref(), Some("10.0.0.2"));
+        assert!(counter.drain().is_empty());
+    }
+
+    #[test]
+    fn test_counter_caps_distinct_ips() {
+        let counter = TokenUsageCounter::default();
+        let token = Uuid::new_v4();
+        for i in 0..(MAX_PENDING_IPS + 10) {
+            let ip: IpAddr = format!("10.0.{}.{}", i / 256, i % 256).parse().unwrap();
+            counter.record(token, Some(ip), Utc::now());
+        }
+        let usage = &counter.drain()[0].1;
+        assert_eq!(usage.ips.len(), MAX_PENDING_IPS);
+        assert_eq!(usage.calls, (MAX_PENDING_IPS + 10) as i64);
+    }
+
+    #[test]
+    fn test_warn_after_days() {
+        assert_eq!(warn_after_days(90, 7), 83);
+        assert_eq!(warn_after_days(5, 7), 0);
+    }
+
+    #[test]
+    fn test_warning_bodies() {
+        let text = build_warning_text("alice", "ci", 83, 7);
+        assert!(text.contains("Hello alice"));
+        assert!(text.contains("\"ci\""));
+        assert!(text.contains("83 days"));
+        assert!(text.contains("in 7 days"));
+        assert!(build_warning_text("alice", "ci", 89, 1).contains("within a day"));
+
+        let html = build_warning_html("alice", "<ci>", 83, 7);
+        assert!(html.contains("&lt;ci&gt;"));
+        assert!(html.contains("<strong>7 days</strong>"));
+    }
+
+    /// Without SMTP nobody hears about a stale token, so the sweep must not
+    /// start its revocation clock: it is counted as unnotifiable, stays
+    /// unwarned and unrevoked, and the report flags it.
+    #[tokio::test]
+    async fn test_sweep_without_smtp_leaves_tokens_alone() {
+        use crate::api::handlers::test_db_helpers as tdh;
+
+        let Some(pool) = tdh::try_pool().await else {
+            return;
+        };
+        let (user_id, _username) = tdh::create_user(&pool).await;
+        let (token_id,): (Uuid,) = sqlx::query_as(
+            "INSERT INTO api_tokens (user_id, name, token_hash, token_prefix, scopes, created_at) \
+             VALUES ($1, 'idle', 'placeholder-hash', 'idle', '{}', NOW() - INTERVAL '400 days') \
+             RETURNING id",
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .expect("seed idle token");
+
+        let service = TokenUsageService::new(pool.clone());
+        let first = service.run_stale_sweep(None, 90, 7).await;
+        let second = service.run_stale_sweep(None, 90, 7).await;
+        let (warned_at, revoked_at): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) =
+            sqlx::query_as("SELECT stale_warned_at, revoked_at FROM api_tokens WHERE id = $1")
+                .bind(token_id)
+                .fetch_one(&pool)
+                .await
+                .expect("load token");
+        let report = service.stale_tokens(90, 1000, false).await;
+
+        let _ = sqlx::query("DELETE FROM api_tokens WHERE id = $1")
+            .bind(token_id)
+            .execute(&pool)
+            .await;
+        tdh::cleanup_user(&pool, user_id).await;
+
+        let first = first.expect("first sweep");
+        let second = second.expect("second sweep");
+        assert!(first.unnotifiable >= 1, "{first:?}");
+        assert!(
+            second.unnotifiable >= 1,
+            "still unnotifiable on the next run"
+        );
+        assert!(
+            warned_at.is_none(),
+            "an unsent warning must not be recorded"
+        );
+        assert!(
+            revoked_at.is_none(),
+            "an unwarned token must not be revoked"
+        );
+        let report = report.expect("stale report");
+        let entry = report
+            .iter()
+            .find(|t| t.id == token_id)
+            .expect("token in the stale report");
+        assert!(entry.unnotifiable);
+    }
+}
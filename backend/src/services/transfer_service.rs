@@ -2,12 +2,34 @@
 //!
 //! Manages artifact transfers using a piece-based distribution model where
 //! chunks can be sourced from multiple peers simultaneously.
-
+//!
+//! Chunks uploaded through the transfer API are staged in primary storage
+//! under [`chunk_storage_key`], so an interrupted transfer resumes from its
+//! verified chunks ([`TransferService::resume_session`]) even across a
+//! backend restart. Sessions without activity for
+//! [`ABANDONED_SESSION_TTL_HOURS`] are expired by the scheduler, which also
+//! deletes their staged chunks.
+
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
+use crate::storage::StorageBackend;
+
+/// Storage key prefix of staged transfer chunks.
+pub const TRANSFER_CHUNK_PREFIX: &str = "transfer-chunks/";
+
+/// Hours without activity after which an unfinished session is abandoned.
+pub const ABANDONED_SESSION_TTL_HOURS: i64 = 24;
+
+/// Storage key of a staged chunk. Zero-padded so the chunks of a session
+/// list in order.
+pub fn chunk_storage_key(session_id: Uuid, chunk_index: i32) -> String {
+    format!("{}{}/{:08}", TRANSFER_CHUNK_PREFIX, session_id, chunk_index)
+}
 
 /// Sync status reused from peer instance service
 #[derive(Debug, Clone, Copy, PartialEq, sqlx::Type)]
@@ -59,6 +81,55 @@ pub struct PeerChunkInfo {
     pub chunk_bitmap: Vec<u8>,
 }
 
+/// Progress of an unfinished transfer session.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TransferProgress {
+    pub session_id: Uuid,
+    pub artifact_id: Uuid,
+    pub status: String,
+    pub total_size: i64,
+    pub total_chunks: i32,
+    pub completed_chunks: i64,
+    pub pending_chunks: i64,
+    pub failed_chunks: i64,
+    pub bytes_completed: i64,
+    /// Lowest chunk index not yet completed; where a resumed transfer starts.
+    pub resume_from_chunk: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub last_activity_at: DateTime<Utc>,
+}
+
+impl TransferProgress {
+    /// Completed share of the artifact's bytes, 0–100.
+    pub fn percent_complete(&self) -> f64 {
+        percent_of(self.bytes_completed, self.total_size)
+    }
+}
+
+fn percent_of(done: i64, total: i64) -> f64 {
+    if total <= 0 {
+        return 100.0;
+    }
+    ((done as f64 / total as f64) * 1000.0).round() / 10.0
+}
+
+/// Result of resuming a transfer session.
+#[derive(Debug)]
+pub struct ResumedTransfer {
+    pub progress: TransferProgress,
+    /// Chunk indices still to transfer, ascending.
+    pub remaining_chunks: Vec<i32>,
+    /// Chunks that were completed or failed before and were reset to
+    /// pending because their bytes could not be verified.
+    pub reset_chunks: i64,
+}
+
+/// A chunk marked completed still counts after a resume when it carries a
+/// checksum and, if it was staged here, its staged bytes still exist.
+fn chunk_still_verified(checksum: &str, staged: Option<bool>) -> bool {
+    !checksum.is_empty() && staged != Some(false)
+}
+
 /// Request to initialize a transfer
 #[derive(Debug)]
 pub struct InitTransferRequest {
@@ -123,11 +194,14 @@ impl TransferService {
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        // Create chunk records
+        // Create chunk records. A re-init keeps the session's original chunk
+        // layout (the upsert does not change it), so lay chunks out from the
+        // stored session rather than the request.
+        let (chunk_size, total_chunks) = (session.chunk_size, session.total_chunks);
         for i in 0..total_chunks {
             let byte_offset = (i as i64) * (chunk_size as i64);
             let byte_length = if i == total_chunks - 1 {
-                (total_size - byte_offset) as i32
+                (session.total_size - byte_offset) as i32
             } else {
                 chunk_size
             };
@@ -149,7 +223,28 @@ impl TransferService {
             .map_err(|e| AppError::Database(e.to_string()))?;
         }
 
-        Ok(session)
+        // The upsert zeroes the count, but chunks completed before a re-init
+        // are kept; bring the count back in line with them.
+        let completed_chunks: i32 = sqlx::query_scalar(
+            r#"
+            UPDATE transfer_sessions
+            SET completed_chunks = (
+                SELECT COUNT(*) FROM transfer_chunks
+                WHERE session_id = $1 AND status = 'completed'
+            ),
+            updated_at = NOW()
+            WHERE id = $1
+            RETURNING completed_chunks
+            "#,
+        )
+        .bind(session.id)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(TransferSession {
+            completed_chunks,
+            ..session
+        })
     }
 
     /// Get the chunk manifest for a transfer session.
@@ -243,7 +338,7 @@ impl TransferService {
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(())
+        self.touch_session(session_id).await
     }
 
     /// Mark a chunk as failed.
@@ -262,7 +357,7 @@ impl TransferService {
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(())
+        self.touch_session(session_id).await
     }
 
     /// Reset a failed chunk to pending for retry.
@@ -280,7 +375,7 @@ impl TransferService {
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(())
+        self.touch_session(session_id).await
     }
 
     /// Complete a transfer session after all chunks are verified.
@@ -428,6 +523,336 @@ impl TransferService {
 
         Ok(sessions)
     }
+
+    /// Record activity on a session, holding off its expiry.
+    async fn touch_session(&self, session_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE transfer_sessions SET updated_at = NOW() WHERE id = $1")
+            .bind(session_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Progress of the pending and in-progress sessions of a peer, oldest
+    /// first.
+    pub async fn list_active_progress(&self, peer_id: Uuid) -> Result<Vec<TransferProgress>> {
+        self.progress_where(
+            "s.requesting_peer_id = $1 AND s.status IN ('pending', 'in_progress')",
+            peer_id,
+        )
+        .await
+    }
+
+    async fn session_progress(&self, session_id: Uuid) -> Result<TransferProgress> {
+        self.progress_where("s.id = $1", session_id)
+            .await?
+            .pop()
+            .ok_or_else(|| AppError::NotFound("Transfer session not found".to_string()))
+    }
+
+    async fn progress_where(&self, filter: &str, id: Uuid) -> Result<Vec<TransferProgress>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT
+                s.id, s.artifact_id, s.status::text AS status, s.total_size, s.total_chunks,
+                s.created_at, s.updated_at,
+                COUNT(c.id) FILTER (WHERE c.status = 'completed') AS completed_chunks,
+                COUNT(c.id) FILTER (WHERE c.status IN ('pending', 'in_progress')) AS pending_chunks,
+                COUNT(c.id) FILTER (WHERE c.status = 'failed') AS failed_chunks,
+                COALESCE(SUM(c.byte_length) FILTER (WHERE c.status = 'completed'), 0)::BIGINT
+                    AS bytes_completed,
+                MIN(c.chunk_index) FILTER (WHERE c.status != 'completed') AS resume_from_chunk
+            FROM transfer_sessions s
+            LEFT JOIN transfer_chunks c ON c.session_id = s.id
+            WHERE {}
+            GROUP BY s.id
+            ORDER BY s.created_at
+            "#,
+            filter
+        ))
+        .bind(id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TransferProgress {
+                session_id: row.get("id"),
+                artifact_id: row.get("artifact_id"),
+                status: row.get("status"),
+                total_size: row.get("total_size"),
+                total_chunks: row.get("total_chunks"),
+                completed_chunks: row.get("completed_chunks"),
+                pending_chunks: row.get("pending_chunks"),
+                failed_chunks: row.get("failed_chunks"),
+                bytes_completed: row.get("bytes_completed"),
+                resume_from_chunk: row.get("resume_from_chunk"),
+                created_at: row.get("created_at"),
+                last_activity_at: row.get("updated_at"),
+            })
+            .collect())
+    }
+
+    /// Stage the bytes of one chunk in `storage` and mark it completed.
+    ///
+    /// The body must be exactly the chunk's length; its SHA-256 becomes the
+    /// chunk checksum, so a resume can trust the chunk without re-reading it.
+    pub async fn stage_chunk(
+        &self,
+        storage: &dyn StorageBackend,
+        session_id: Uuid,
+        chunk_index: i32,
+        data: Bytes,
+        source_peer_id: Option<Uuid>,
+    ) -> Result<String> {
+        let row = sqlx::query(
+            r#"
+            SELECT c.byte_length, s.status::text AS session_status
+            FROM transfer_chunks c
+            JOIN transfer_sessions s ON s.id = c.session_id
+            WHERE c.session_id = $1 AND c.chunk_index = $2
+            "#,
+        )
+        .bind(session_id)
+        .bind(chunk_index)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Transfer chunk not found".to_string()))?;
+
+        let session_status: String = row.get("session_status");
+        if !matches!(session_status.as_str(), "pending" | "in_progress") {
+            return Err(AppError::Conflict(format!(
+                "Transfer session is {}; resume it before uploading chunks",
+                session_status
+            )));
+        }
+        let byte_length: i32 = row.get("byte_length");
+        if data.len() != byte_length as usize {
+            return Err(AppError::Validation(format!(
+                "Chunk {} is {} bytes, got {}",
+                chunk_index,
+                byte_length,
+                data.len()
+            )));
+        }
+
+        let checksum = format!("{:x}", Sha256::digest(&data));
+        let key = chunk_storage_key(session_id, chunk_index);
+        storage.put(&key, data).await?;
+        sqlx::query(
+            r#"
+            UPDATE transfer_chunks
+            SET storage_key = $3, checksum = $4
+            WHERE session_id = $1 AND chunk_index = $2
+            "#,
+        )
+        .bind(session_id)
+        .bind(chunk_index)
+        .bind(&key)
+        .bind(&checksum)
+        .execute(&self.db)
+        .await?;
+        sqlx::query(
+            "UPDATE transfer_sessions SET status = 'in_progress' WHERE id = $1 AND status = 'pending'",
+        )
+        .bind(session_id)
+        .execute(&self.db)
+        .await?;
+        // complete_chunk is a no-op for a chunk staged again after it
+        // completed, so record the activity here too.
+        self.complete_chunk(session_id, chunk_index, &checksum, source_peer_id)
+            .await?;
+        self.touch_session(session_id).await?;
+        Ok(checksum)
+    }
+
+    /// Resume an interrupted or failed session from its verified chunks.
+    ///
+    /// Completed chunks keep their state when they carry a checksum and, if
+    /// staged here, their staged bytes still exist; every other chunk goes
+    /// back to pending. The session returns to `in_progress`.
+    pub async fn resume_session(
+        &self,
+        storage: &dyn StorageBackend,
+        session_id: Uuid,
+    ) -> Result<ResumedTransfer> {
+        let status: String =
+            sqlx::query_scalar("SELECT status::text FROM transfer_sessions WHERE id = $1")
+                .bind(session_id)
+                .fetch_optional(&self.db)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Transfer session not found".to_string()))?;
+        if matches!(status.as_str(), "completed" | "cancelled") {
+            return Err(AppError::Conflict(format!(
+                "Transfer session is {} and cannot be resumed",
+                status
+            )));
+        }
+
+        let completed = sqlx::query(
+            r#"
+            SELECT chunk_index, checksum, storage_key FROM transfer_chunks
+            WHERE session_id = $1 AND status = 'completed'
+            "#,
+        )
+        .bind(session_id)
+        .fetch_all(&self.db)
+        .await?;
+        let mut unverified: Vec<i32> = Vec::new();
+        for row in &completed {
+            let checksum: String = row.get("checksum");
+            let staged = match row.get::<Option<String>, _>("storage_key") {
+                Some(key) => Some(storage.exists(&key).await?),
+                None => None,
+            };
+            if !chunk_still_verified(&checksum, staged) {
+                unverified.push(row.get("chunk_index"));
+            }
+        }
+
+        let reset = sqlx::query(
+            r#"
+            UPDATE transfer_chunks
+            SET status = 'pending', source_peer_id = NULL, storage_key = NULL,
+                checksum = CASE WHEN status = 'completed' THEN '' ELSE checksum END
+            WHERE session_id = $1
+              AND (status IN ('failed', 'in_progress') OR chunk_index = ANY($2))
+            "#,
+        )
+        .bind(session_id)
+        .bind(&unverified)
+        .execute(&self.db)
+        .await?
+        .rows_affected() as i64;
+
+        sqlx::query(
+            r#"
+            UPDATE transfer_sessions
+            SET status = 'in_progress', error_message = NULL, completed_at = NULL,
+                started_at = COALESCE(started_at, NOW()), updated_at = NOW(),
+                completed_chunks = (
+                    SELECT COUNT(*) FROM transfer_chunks
+                    WHERE session_id = $1 AND status = 'completed'
+                )
+            WHERE id = $1
+            "#,
+        )
+        .bind(session_id)
+        .execute(&self.db)
+        .await?;
+
+        let remaining_chunks: Vec<i32> = sqlx::query_scalar(
+            r#"
+            SELECT chunk_index FROM transfer_chunks
+            WHERE session_id = $1 AND status != 'completed'
+            ORDER BY chunk_index
+            "#,
+        )
+        .bind(session_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(ResumedTransfer {
+            progress: self.session_progress(session_id).await?,
+            remaining_chunks,
+            reset_chunks: reset,
+        })
+    }
+
+    /// Expire sessions without activity for [`ABANDONED_SESSION_TTL_HOURS`]:
+    /// unfinished ones are failed, and the staged chunks of expired and
+    /// long-failed sessions are deleted from `storage`. Returns the number
+    /// of sessions expired or cleaned.
+    pub async fn expire_abandoned_sessions(&self, storage: &dyn StorageBackend) -> Result<u64> {
+        let sessions: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT s.id FROM transfer_sessions s
+            WHERE s.updated_at < NOW() - make_interval(hours => $1::int)
+              AND (
+                  s.status IN ('pending', 'in_progress')
+                  OR (s.status = 'failed' AND EXISTS (
+                      SELECT 1 FROM transfer_chunks c
+                      WHERE c.session_id = s.id AND c.storage_key IS NOT NULL
+                  ))
+              )
+            "#,
+        )
+        .bind(ABANDONED_SESSION_TTL_HOURS as i32)
+        .fetch_all(&self.db)
+        .await?;
+
+        for session_id in &sessions {
+            // Fail the session first: a chunk upload racing the sweep then
+            // sees a failed session instead of staging into a purged one.
+            sqlx::query(
+                r#"
+                UPDATE transfer_sessions
+                SET status = 'failed', completed_at = NOW(),
+                    error_message = COALESCE(error_message, $2)
+                WHERE id = $1 AND status IN ('pending', 'in_progress')
+                "#,
+            )
+            .bind(session_id)
+            .bind(format!(
+                "expired after {} hours without activity",
+                ABANDONED_SESSION_TTL_HOURS
+            ))
+            .execute(&self.db)
+            .await?;
+
+            let keys: Vec<(i32, String)> = sqlx::query_as(
+                r#"
+                SELECT chunk_index, storage_key FROM transfer_chunks
+                WHERE session_id = $1 AND storage_key IS NOT NULL
+                "#,
+            )
+            .bind(session_id)
+            .fetch_all(&self.db)
+            .await?;
+            let mut purged: Vec<i32> = Vec::with_capacity(keys.len());
+            for (chunk_index, key) in keys {
+                match storage.delete(&key).await {
+                    Ok(()) | Err(AppError::NotFound(_)) => purged.push(chunk_index),
+                    Err(e) => {
+                        tracing::warn!(
+                            session_id = %session_id,
+                            key = %key,
+                            "Failed to delete staged transfer chunk: {}",
+                            e
+                        );
+                    }
+                }
+            }
+            sqlx::query(
+                r#"
+                UPDATE transfer_chunks
+                SET storage_key = NULL,
+                    status = CASE WHEN status = 'completed' THEN 'pending' ELSE status END,
+                    checksum = CASE WHEN status = 'completed' THEN '' ELSE checksum END
+                WHERE session_id = $1 AND chunk_index = ANY($2)
+                "#,
+            )
+            .bind(session_id)
+            .bind(&purged)
+            .execute(&self.db)
+            .await?;
+            sqlx::query(
+                r#"
+                UPDATE transfer_sessions
+                SET completed_chunks = (
+                    SELECT COUNT(*) FROM transfer_chunks
+                    WHERE session_id = $1 AND status = 'completed'
+                )
+                WHERE id = $1
+                "#,
+            )
+            .bind(session_id)
+            .execute(&self.db)
+            .await?;
+        }
+
+        Ok(sessions.len() as u64)
+    }
 }
 
 #[cfg(test)]
@@ -465,6 +890,41 @@ mod tests {
         assert_eq!(status, cloned);
     }
 
+    // -----------------------------------------------------------------------
+    // Resume helpers
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_chunk_storage_key_is_ordered_per_session() {
+        let session = Uuid::nil();
+        assert_eq!(
+            chunk_storage_key(session, 7),
+            "transfer-chunks/00000000-0000-0000-0000-000000000000/00000007"
+        );
+        assert!(chunk_storage_key(session, 9) < chunk_storage_key(session, 10));
+        assert!(chunk_storage_key(session, 0).starts_with(TRANSFER_CHUNK_PREFIX));
+    }
+
+    #[test]
+    fn test_chunk_still_verified() {
+        // Completed through the status API (bytes held by the peer).
+        assert!(chunk_still_verified("abc123", None));
+        // Staged here and still present.
+        assert!(chunk_still_verified("abc123", Some(true)));
+        // Staged bytes lost, e.g. scratch storage wiped by a restart.
+        assert!(!chunk_still_verified("abc123", Some(false)));
+        // Marked completed without a checksum: nothing to verify against.
+        assert!(!chunk_still_verified("", None));
+    }
+
+    #[test]
+    fn test_percent_of() {
+        assert_eq!(percent_of(0, 1000), 0.0);
+        assert_eq!(percent_of(1, 3), 33.3);
+        assert_eq!(percent_of(1000, 1000), 100.0);
+        assert_eq!(percent_of(0, 0), 100.0);
+    }
+
     // -----------------------------------------------------------------------
     // InitTransferRequest
     // -----------------------------------------------------------------------
@@ -16,7 +16,7 @@ use crate::models::artifact::{Artifact, ArtifactMetadata};
 use crate::services::image_scanner::TrivyReport;
 use crate::services::scanner_adapter_client::{fs_upload_cap_bytes, TrivyEngine, TrivyFsBackend};
 use crate::services::scanner_service::{
-    fail_scan, ScanOutput, ScanWorkspace, Scanner, VersionCache,
+    fail_scan, ScanOutput, ScanTarget, ScanWorkspace, Scanner, VersionCache,
 };
 // `ScanCompleteness` is used via `output.scan_completeness.as_str()` in the
 // info!() log line below.
@@ -87,10 +87,14 @@ impl TrivyFsScanner {
     /// without failing the process, and the empty Packages block that
     /// results is indistinguishable from "no lockfile present" without
     /// the stderr text.
+    ///
+    /// `extra_args` are the repository's allowlisted scanner arguments
+    /// (`repo_scan_config`), appended before the scan target.
     async fn run_trivy(
         &self,
         workspace: &Path,
         server_url: Option<&str>,
+        extra_args: &[String],
     ) -> Result<(TrivyReport, String)> {
         let ws = workspace.to_string_lossy();
         let mut args = vec!["filesystem"];
@@ -111,8 +115,9 @@ impl TrivyFsScanner {
             "--quiet",
             "--timeout",
             "5m",
-            &ws,
         ]);
+        args.extend(extra_args.iter().map(String::as_str));
+        args.push(&ws);
 
         let mode_label = if server_url.is_some() {
             "server"
@@ -151,15 +156,16 @@ impl TrivyFsScanner {
         workspace: &Path,
         trivy_url: &str,
         artifact_name: &str,
+        extra_args: &[String],
     ) -> Result<(TrivyReport, String)> {
-        match self.run_trivy(workspace, Some(trivy_url)).await {
+        match self.run_trivy(workspace, Some(trivy_url), extra_args).await {
             Ok(out) => Ok(out),
             Err(e) => {
                 warn!(
                     "Trivy server-mode CLI failed for {}: {}. Trying standalone mode.",
                     artifact_name, e
                 );
-                self.run_trivy(workspace, None).await
+                self.run_trivy(workspace, None, extra_args).await
             }
         }
     }
@@ -245,6 +251,27 @@ impl Scanner for TrivyFsScanner {
         artifact: &Artifact,
         _metadata: Option<&ArtifactMetadata>,
         content: &Bytes,
+    ) -> Result<ScanOutput> {
+        self.scan_with_args(artifact, content, &[]).await
+    }
+
+    async fn scan_target(
+        &self,
+        target: &ScanTarget<'_>,
+        _metadata: Option<&ArtifactMetadata>,
+        content: &Bytes,
+    ) -> Result<ScanOutput> {
+        self.scan_with_args(target.artifact, content, target.scanner_args)
+            .await
+    }
+}
+
+impl TrivyFsScanner {
+    async fn scan_with_args(
+        &self,
+        artifact: &Artifact,
+        content: &Bytes,
+        extra_args: &[String],
     ) -> Result<ScanOutput> {
         // The orchestrator gates on `is_applicable` (issues #961, #994), so
         // by the time we get here the artifact should match. Keep a
@@ -270,9 +297,17 @@ impl Scanner for TrivyFsScanner {
         // `not_applicable` (#2324) instead of flooring the grade to F.
         let scan_result = match self.engine.backend() {
             TrivyFsBackend::Cli { trivy_url } => {
-                self.run_cli(&workspace, trivy_url, &artifact.name).await
+                self.run_cli(&workspace, trivy_url, &artifact.name, extra_args)
+                    .await
             }
             TrivyFsBackend::Adapter(client) => {
+                if !extra_args.is_empty() {
+                    warn!(
+                        "Scanner arguments for {} are not applied: the scanner-adapter \
+                         filesystem endpoint takes no Trivy flags",
+                        artifact.name
+                    );
+                }
                 self.engine
                     .scan_dir_via_adapter(client, &workspace, fs_upload_cap_bytes())
                     .await
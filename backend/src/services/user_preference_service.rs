@@ -0,0 +1,263 @@
+//! Per-user preferences.
+//!
+//! Settings the web UI and API clients previously kept in browser storage:
+//! the default landing repository, the preferred page size and timezone,
+//! and a free-form `ui` object the frontend owns. Email notification
+//! opt-ins live in [`crate::services::notification_email_service`] and are
+//! returned alongside, so one endpoint covers everything a user can set.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::notification_email_service::{
+    NotificationEmailService, NotificationPreferences, UpdateNotificationPreferences,
+};
+
+/// Largest page size a user may prefer; matches the list endpoints' cap.
+pub const MAX_PAGE_SIZE: i32 = 100;
+
+/// Upper bound on the serialized `ui` object.
+pub const MAX_UI_SETTINGS_BYTES: usize = 16 * 1024;
+
+/// A user's preferences. `None` means the server default applies.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserPreferences {
+    /// Key of the repository the UI opens by default.
+    pub default_repository: Option<String>,
+    /// Preferred page size for list views and API clients.
+    pub page_size: Option<i32>,
+    /// IANA timezone for displaying timestamps (e.g. `Europe/Berlin`).
+    pub timezone: Option<String>,
+    /// Frontend-owned settings (theme, table layouts, ...).
+    #[schema(value_type = Object)]
+    pub ui: serde_json::Value,
+    pub notifications: NotificationPreferences,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Partial update. Omitted fields keep their value; `null` resets
+/// `default_repository`, `page_size` and `timezone` to the server default.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct UpdateUserPreferences {
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<String>)]
+    pub default_repository: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<i32>)]
+    pub page_size: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<String>)]
+    pub timezone: Option<Option<String>>,
+    /// Replaces the stored `ui` object.
+    #[schema(value_type = Option<Object>)]
+    pub ui: Option<serde_json::Value>,
+    pub notifications: Option<UpdateNotificationPreferences>,
+}
+
+/// Distinguishes an explicit `null` (`Some(None)`) from an absent field
+/// (`None`, via `#[serde(default)]`).
+fn present<'de, D, T>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+pub fn validate_page_size(page_size: i32) -> Result<()> {
+    if (1..=MAX_PAGE_SIZE).contains(&page_size) {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "page_size must be between 1 and {MAX_PAGE_SIZE}"
+        )))
+    }
+}
+
+pub fn validate_ui_settings(ui: &serde_json::Value) -> Result<()> {
+    if !ui.is_object() {
+        return Err(AppError::Validation("ui must be a JSON object".to_string()));
+    }
+    if serde_json::to_vec(ui)
+        .map(|v| v.len())
+        .unwrap_or(usize::MAX)
+        > MAX_UI_SETTINGS_BYTES
+    {
+        return Err(AppError::Validation(format!(
+            "ui settings exceed {MAX_UI_SETTINGS_BYTES} bytes"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PreferenceRow {
+    default_repository: Option<String>,
+    page_size: Option<i32>,
+    timezone: Option<String>,
+    ui_settings: serde_json::Value,
+    updated_at: DateTime<Utc>,
+}
+
+pub struct UserPreferenceService {
+    db: PgPool,
+}
+
+impl UserPreferenceService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    fn notifications(&self) -> NotificationEmailService {
+        // Preference reads and writes never send mail.
+        NotificationEmailService::new(self.db.clone(), None)
+    }
+
+    pub async fn get(&self, user_id: Uuid) -> Result<UserPreferences> {
+        let row: Option<PreferenceRow> = sqlx::query_as(
+            r#"
+            SELECT r.key AS default_repository, p.page_size, p.timezone,
+                   p.ui_settings, p.updated_at
+            FROM user_preferences p
+            LEFT JOIN repositories r ON r.id = p.default_repository_id
+            WHERE p.user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        let notifications = self.notifications().get_preferences(user_id).await?;
+
+        Ok(match row {
+            Some(row) => UserPreferences {
+                default_repository: row.default_repository,
+                page_size: row.page_size,
+                timezone: row.timezone,
+                ui: row.ui_settings,
+                notifications,
+                updated_at: Some(row.updated_at),
+            },
+            None => UserPreferences {
+                default_repository: None,
+                page_size: None,
+                timezone: None,
+                ui: serde_json::json!({}),
+                notifications,
+                updated_at: None,
+            },
+        })
+    }
+
+    /// Apply a partial update. `default_repository_id` is the resolved id of
+    /// `update.default_repository`; the caller checks the user may see it.
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        update: &UpdateUserPreferences,
+        default_repository_id: Option<Option<Uuid>>,
+    ) -> Result<UserPreferences> {
+        if let Some(Some(page_size)) = update.page_size {
+            validate_page_size(page_size)?;
+        }
+        if let Some(ref ui) = update.ui {
+            validate_ui_settings(ui)?;
+        }
+        if let Some(Some(ref tz)) = update.timezone {
+            let known: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1)",
+            )
+            .bind(tz)
+            .fetch_one(&self.db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            if !known {
+                return Err(AppError::Validation(format!("unknown timezone '{tz}'")));
+            }
+        }
+
+        // Absent fields bind NULL with their "present" flag false and keep
+        // the stored value; explicit nulls clear it.
+        sqlx::query(
+            r#"
+            INSERT INTO user_preferences
+                (user_id, default_repository_id, page_size, timezone, ui_settings)
+            VALUES ($1, $3, $5, $7, COALESCE($8, '{}'::jsonb))
+            ON CONFLICT (user_id) DO UPDATE SET
+                default_repository_id = CASE WHEN $2 THEN EXCLUDED.default_repository_id
+                                             ELSE user_preferences.default_repository_id END,
+                page_size = CASE WHEN $4 THEN EXCLUDED.page_size
+                                 ELSE user_preferences.page_size END,
+                timezone = CASE WHEN $6 THEN EXCLUDED.timezone
+                                ELSE user_preferences.timezone END,
+                ui_settings = COALESCE($8, user_preferences.ui_settings),
+                updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(default_repository_id.is_some())
+        .bind(default_repository_id.flatten())
+        .bind(update.page_size.is_some())
+        .bind(update.page_size.flatten())
+        .bind(update.timezone.is_some())
+        .bind(update.timezone.clone().flatten())
+        .bind(update.ui.as_ref())
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        if let Some(ref notifications) = update.notifications {
+            self.notifications()
+                .update_preferences(user_id, notifications)
+                .await?;
+        }
+
+        self.get(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_update_distinguishes_null_from_absent() {
+        let update: UpdateUserPreferences =
+            serde_json::from_value(json!({"page_size": null, "timezone": "UTC"})).unwrap();
+        assert_eq!(update.page_size, Some(None));
+        assert_eq!(update.timezone, Some(Some("UTC".to_string())));
+        assert_eq!(update.default_repository, None);
+        assert!(update.ui.is_none());
+        assert!(update.notifications.is_none());
+    }
+
+    #[test]
+    fn test_update_nested_notifications() {
+        let update: UpdateUserPreferences =
+            serde_json::from_value(json!({"notifications": {"weekly_digest": true}})).unwrap();
+        let notifications = update.notifications.unwrap();
+        assert_eq!(notifications.weekly_digest, Some(true));
+        assert_eq!(notifications.health_alerts, None);
+    }
+
+    #[test]
+    fn test_validate_page_size() {
+        assert!(validate_page_size(1).is_ok());
+        assert!(validate_page_size(MAX_PAGE_SIZE).is_ok());
+        assert!(validate_page_size(0).is_err());
+        assert!(validate_page_size(MAX_PAGE_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_ui_settings() {
+        assert!(validate_ui_settings(&json!({"theme": "dark"})).is_ok());
+        assert!(validate_ui_settings(&json!(["dark"])).is_err());
+        let big = "x".repeat(MAX_UI_SETTINGS_BYTES);
+        assert!(validate_ui_settings(&json!({ "blob": big })).is_err());
+    }
+}
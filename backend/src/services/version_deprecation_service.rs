@@ -0,0 +1,261 @@
+//! Yanked / deprecated package versions.
+//!
+//! Deprecating a version only changes how the format handlers advertise it
+//! (npm `deprecated`, Cargo `yanked: true`, PyPI PEP 592 `yanked`); the
+//! artifacts themselves stay downloadable so pinned lockfiles keep working.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// Longest accepted deprecation message.
+pub const MAX_DEPRECATION_MESSAGE_LEN: usize = 1024;
+
+/// npm requires a non-empty `deprecated` string; used when no message was
+/// given.
+pub const DEFAULT_NPM_DEPRECATION_MESSAGE: &str = "This version has been deprecated";
+
+/// A deprecated (yanked) package version.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct VersionDeprecation {
+    pub id: Uuid,
+    pub repository_id: Uuid,
+    pub package_name: String,
+    pub version: String,
+    pub message: Option<String>,
+    pub deprecated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Deprecation messages keyed by version. A deprecated version without a
+/// message maps to `None`.
+pub type DeprecatedVersions = HashMap<String, Option<String>>;
+
+/// Trim a deprecation message, mapping blank to `None`, and enforce the
+/// length limit.
+pub fn normalize_message(message: Option<&str>) -> Result<Option<String>> {
+    let Some(message) = message.map(str::trim).filter(|m| !m.is_empty()) else {
+        return Ok(None);
+    };
+    if message.chars().count() > MAX_DEPRECATION_MESSAGE_LEN {
+        return Err(AppError::Validation(format!(
+            "Deprecation message must be at most {} characters",
+            MAX_DEPRECATION_MESSAGE_LEN
+        )));
+    }
+    Ok(Some(message.to_string()))
+}
+
+/// The `deprecated` string npm expects for a deprecated version.
+pub fn npm_deprecation_message(message: Option<&str>) -> String {
+    message
+        .filter(|m| !m.is_empty())
+        .unwrap_or(DEFAULT_NPM_DEPRECATION_MESSAGE)
+        .to_string()
+}
+
+pub struct VersionDeprecationService {
+    db: PgPool,
+}
+
+impl VersionDeprecationService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Mark `package_name@version` as deprecated, replacing the message of
+    /// an existing marker. The version must exist in the repository.
+    pub async fn deprecate(
+        &self,
+        repository_id: Uuid,
+        package_name: &str,
+        version: &str,
+        message: Option<&str>,
+        deprecated_by: Option<Uuid>,
+    ) -> Result<VersionDeprecation> {
+        let message = normalize_message(message)?;
+
+        let exists: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM artifacts
+                WHERE repository_id = $1 AND name = $2 AND version = $3
+                  AND is_deleted = false
+            )
+            "#,
+        )
+        .bind(repository_id)
+        .bind(package_name)
+        .bind(version)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        if !exists {
+            return Err(AppError::NotFound(format!(
+                "Version '{}' of package '{}' not found",
+                version, package_name
+            )));
+        }
+
+        let deprecation: VersionDeprecation = sqlx::query_as(
+            r#"
+            INSERT INTO package_version_deprecations
+                (repository_id, package_name, version, message, deprecated_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (repository_id, package_name, version)
+            DO UPDATE SET message = EXCLUDED.message,
+                          deprecated_by = EXCLUDED.deprecated_by,
+                          created_at = NOW()
+            RETURNING id, repository_id, package_name, version, message,
+                      deprecated_by, created_at
+            "#,
+        )
+        .bind(repository_id)
+        .bind(package_name)
+        .bind(version)
+        .bind(&message)
+        .bind(deprecated_by)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(deprecation)
+    }
+
+    /// Remove the deprecation marker. Returns `false` when the version was
+    /// not deprecated.
+    pub async fn undeprecate(
+        &self,
+        repository_id: Uuid,
+        package_name: &str,
+        version: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM package_version_deprecations
+            WHERE repository_id = $1 AND package_name = $2 AND version = $3
+            "#,
+        )
+        .bind(repository_id)
+        .bind(package_name)
+        .bind(version)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List deprecations in a repository, optionally for one package.
+    pub async fn list(
+        &self,
+        repository_id: Uuid,
+        package_name: Option<&str>,
+    ) -> Result<Vec<VersionDeprecation>> {
+        let deprecations: Vec<VersionDeprecation> = sqlx::query_as(
+            r#"
+            SELECT id, repository_id, package_name, version, message,
+                   deprecated_by, created_at
+            FROM package_version_deprecations
+            WHERE repository_id = $1
+              AND ($2::TEXT IS NULL OR package_name = $2)
+            ORDER BY package_name, created_at DESC
+            "#,
+        )
+        .bind(repository_id)
+        .bind(package_name)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(deprecations)
+    }
+
+    /// Deprecated versions of one package, by exact package name.
+    pub async fn deprecated_versions(
+        &self,
+        repository_id: Uuid,
+        package_name: &str,
+    ) -> Result<DeprecatedVersions> {
+        let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT version, message
+            FROM package_version_deprecations
+            WHERE repository_id = $1 AND package_name = $2
+            "#,
+        )
+        .bind(repository_id)
+        .bind(package_name)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Deprecated versions of a PyPI project, matching stored names by their
+    /// PEP 503 normalized form the same way the simple index does.
+    pub async fn deprecated_pypi_versions(
+        &self,
+        repository_id: Uuid,
+        normalized_name: &str,
+    ) -> Result<DeprecatedVersions> {
+        let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT version, message
+            FROM package_version_deprecations
+            WHERE repository_id = $1
+              AND LOWER(REPLACE(REPLACE(REPLACE(package_name, '_', '-'), '.', '-'), '--', '-')) = $2
+            "#,
+        )
+        .bind(repository_id)
+        .bind(normalized_name)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_message_blank_is_none() {
+        assert_eq!(normalize_message(None).unwrap(), None);
+        assert_eq!(normalize_message(Some("   ")).unwrap(), None);
+        assert_eq!(
+            normalize_message(Some("  use 2.0 \n")).unwrap(),
+            Some("use 2.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_message_rejects_overlong() {
+        let long = "x".repeat(MAX_DEPRECATION_MESSAGE_LEN + 1);
+        assert!(normalize_message(Some(&long)).is_err());
+        let max = "x".repeat(MAX_DEPRECATION_MESSAGE_LEN);
+        assert!(normalize_message(Some(&max)).is_ok());
+    }
+
+    #[test]
+    fn test_npm_deprecation_message_falls_back() {
+        assert_eq!(
+            npm_deprecation_message(None),
+            DEFAULT_NPM_DEPRECATION_MESSAGE
+        );
+        assert_eq!(
+            npm_deprecation_message(Some("")),
+            DEFAULT_NPM_DEPRECATION_MESSAGE
+        );
+        assert_eq!(npm_deprecation_message(Some("use 2.x")), "use 2.x");
+    }
+}
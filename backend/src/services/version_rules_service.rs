@@ -0,0 +1,398 @@
+//! Per-repository artifact naming/version parsing rules.
+//!
+//! Generic uploads derive the artifact name and version from the
+//! `{name}/{version}/{file}` path convention, which leaves artifacts stored
+//! under any other layout without a version: `max_versions` retention then
+//! groups them by file name and package listings cannot order them. A
+//! repository can instead declare an ordered list of rules, stored as JSON in
+//! `repository_config` under [`VERSION_RULES_CONFIG_KEY`]. The first rule
+//! that matches an uploaded path supplies the name and version:
+//!
+//! * `regex` — a regular expression over the whole path with a named
+//!   `version` group and an optional `name` group.
+//! * `layout` — a path template such as `firmware/{name}-{version}.bin`.
+//!   `{name}` and `{version}` match within one path segment, `{*}` matches
+//!   any text within a segment and `{**}` anything including `/`. A layout
+//!   version starts with a digit, optionally prefixed by `v`.
+//! * `maven` — the Maven repository layout
+//!   (`group/.../artifact/version/file`), named `group:artifact`.
+//!
+//! A rule without a `name` keeps the name the path convention yields.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// `repository_config` key holding a repository's rules.
+pub const VERSION_RULES_CONFIG_KEY: &str = "version_rules";
+
+/// Most rules a repository may declare.
+const MAX_RULES: usize = 32;
+
+/// Longest accepted regex or layout template.
+const MAX_PATTERN_LEN: usize = 512;
+
+/// Compiled size limit for rule regexes, in bytes.
+const REGEX_SIZE_LIMIT: usize = 256 * 1024;
+
+/// One naming/version parsing rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VersionRule {
+    /// Regular expression over the artifact path. Must contain a named
+    /// `version` group; a `name` group is optional.
+    Regex { pattern: String },
+    /// Path template using `{name}`, `{version}`, `{*}` and `{**}`.
+    Layout { template: String },
+    /// Maven repository layout.
+    Maven,
+}
+
+/// Name and version a rule extracted from a path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ExtractedCoordinates {
+    /// Artifact name, absent when the rule does not capture one.
+    pub name: Option<String>,
+    pub version: String,
+    /// Position of the matching rule in the rule list.
+    pub rule_index: usize,
+}
+
+#[derive(Debug, Clone)]
+enum CompiledRule {
+    Pattern(Regex),
+    Maven,
+}
+
+/// A validated, compiled rule list.
+#[derive(Debug, Clone, Default)]
+pub struct VersionRuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl VersionRuleSet {
+    /// Validate and compile `rules`.
+    pub fn compile(rules: &[VersionRule]) -> Result<Self> {
+        if rules.len() > MAX_RULES {
+            return Err(AppError::Validation(format!(
+                "At most {} version rules may be configured",
+                MAX_RULES
+            )));
+        }
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                compile_rule(rule)
+                    .map_err(|e| AppError::Validation(format!("Version rule {}: {}", i + 1, e)))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Name and version of `path` per the first matching rule.
+    pub fn extract(&self, path: &str) -> Option<ExtractedCoordinates> {
+        let path = path.trim_start_matches('/');
+        self.rules
+            .iter()
+            .enumerate()
+            .find_map(|(rule_index, rule)| {
+                let (name, version) = match rule {
+                    CompiledRule::Pattern(re) => {
+                        let caps = re.captures(path)?;
+                        let version = caps.name("version")?.as_str();
+                        let name = caps
+                            .name("name")
+                            .map(|m| m.as_str().to_string())
+                            .filter(|n| !n.is_empty());
+                        (name, version.to_string())
+                    }
+                    CompiledRule::Maven => {
+                        let coords =
+                            crate::formats::maven::MavenHandler::parse_coordinates(path).ok()?;
+                        (
+                            Some(format!("{}:{}", coords.group_id, coords.artifact_id)),
+                            coords.version,
+                        )
+                    }
+                };
+                if version.is_empty() {
+                    return None;
+                }
+                Some(ExtractedCoordinates {
+                    name,
+                    version,
+                    rule_index,
+                })
+            })
+    }
+}
+
+fn compile_rule(rule: &VersionRule) -> std::result::Result<CompiledRule, String> {
+    let (source, is_layout) = match rule {
+        VersionRule::Maven => return Ok(CompiledRule::Maven),
+        VersionRule::Regex { pattern } => (pattern, false),
+        VersionRule::Layout { template } => (template, true),
+    };
+    if source.is_empty() || source.len() > MAX_PATTERN_LEN {
+        return Err(format!(
+            "pattern must be between 1 and {} characters",
+            MAX_PATTERN_LEN
+        ));
+    }
+    let pattern = if is_layout {
+        layout_to_regex(source)?
+    } else {
+        source.clone()
+    };
+    let re = regex::RegexBuilder::new(&pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("invalid pattern: {}", e))?;
+    if !re.capture_names().any(|n| n == Some("version")) {
+        return Err("pattern must capture a named `version` group".to_string());
+    }
+    Ok(CompiledRule::Pattern(re))
+}
+
+/// Translate a layout template into an anchored regex.
+fn layout_to_regex(template: &str) -> std::result::Result<String, String> {
+    let template = template.trim_start_matches('/');
+    let mut out = String::from("^");
+    let mut seen_name = false;
+    let mut seen_version = false;
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        push_layout_literal(&mut out, &rest[..open])?;
+        let close = rest[open..]
+            .find('}')
+            .map(|i| open + i)
+            .ok_or_else(|| "unterminated `{` in layout".to_string())?;
+        match &rest[open + 1..close] {
+            "name" if !seen_name => {
+                seen_name = true;
+                out.push_str("(?P<name>[^/]+?)");
+            }
+            "version" if !seen_version => {
+                seen_version = true;
+                out.push_str(r"(?P<version>v?[0-9][^/]*?)");
+            }
+            "*" => out.push_str("[^/]*?"),
+            "**" => out.push_str(".*?"),
+            token @ ("name" | "version") => {
+                return Err(format!("`{{{}}}` may appear only once", token));
+            }
+            other => return Err(format!("unknown layout token `{{{}}}`", other)),
+        }
+        rest = &rest[close + 1..];
+    }
+    push_layout_literal(&mut out, rest)?;
+    out.push('$');
+    if !seen_version {
+        return Err("layout must contain `{version}`".to_string());
+    }
+    Ok(out)
+}
+
+fn push_layout_literal(out: &mut String, literal: &str) -> std::result::Result<(), String> {
+    if literal.contains('}') {
+        return Err("unmatched `}` in layout".to_string());
+    }
+    out.push_str(&regex::escape(literal));
+    Ok(())
+}
+
+/// The rules configured for a repository, empty when none are.
+pub async fn load_rules(db: &PgPool, repository_id: Uuid) -> Result<Vec<VersionRule>> {
+    let value: Option<String> = sqlx::query_scalar(
+        "SELECT value FROM repository_config WHERE repository_id = $1 AND key = $2",
+    )
+    .bind(repository_id)
+    .bind(VERSION_RULES_CONFIG_KEY)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    match value {
+        None => Ok(Vec::new()),
+        Some(v) => serde_json::from_str(&v).map_err(|e| {
+            AppError::Internal(format!("Stored version rules are not valid JSON: {}", e))
+        }),
+    }
+}
+
+/// The compiled rules of a repository for an upload. A stored rule list
+/// that no longer loads is logged and ignored so uploads fall back to the
+/// path convention.
+pub async fn load_rule_set(db: &PgPool, repository_id: Uuid) -> Option<VersionRuleSet> {
+    let compiled = load_rules(db, repository_id)
+        .await
+        .and_then(|rules| VersionRuleSet::compile(&rules));
+    match compiled {
+        Ok(set) if !set.is_empty() => Some(set),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!(
+                repository_id = %repository_id,
+                error = %e,
+                "Ignoring unusable version rules"
+            );
+            None
+        }
+    }
+}
+
+/// Replace a repository's rules; an empty list removes them.
+pub async fn save_rules(db: &PgPool, repository_id: Uuid, rules: &[VersionRule]) -> Result<()> {
+    VersionRuleSet::compile(rules)?;
+    if rules.is_empty() {
+        sqlx::query("DELETE FROM repository_config WHERE repository_id = $1 AND key = $2")
+            .bind(repository_id)
+            .bind(VERSION_RULES_CONFIG_KEY)
+            .execute(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        return Ok(());
+    }
+    let value = serde_json::to_string(rules)
+        .map_err(|e| AppError::Internal(format!("Failed to encode version rules: {}", e)))?;
+    sqlx::query(
+        "INSERT INTO repository_config (repository_id, key, value) \
+         VALUES ($1, $2, $3) \
+         ON CONFLICT (repository_id, key) DO UPDATE SET value = $3, updated_at = NOW()",
+    )
+    .bind(repository_id)
+    .bind(VERSION_RULES_CONFIG_KEY)
+    .bind(value)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regex(pattern: &str) -> VersionRule {
+        VersionRule::Regex {
+            pattern: pattern.to_string(),
+        }
+    }
+
+    fn layout(template: &str) -> VersionRule {
+        VersionRule::Layout {
+            template: template.to_string(),
+        }
+    }
+
+    fn extract(rules: &[VersionRule], path: &str) -> Option<(Option<String>, String)> {
+        VersionRuleSet::compile(rules)
+            .expect("valid rules")
+            .extract(path)
+            .map(|c| (c.name, c.version))
+    }
+
+    #[test]
+    fn layout_splits_name_and_version_within_a_segment() {
+        let rules = [layout("firmware/{name}-{version}.bin")];
+        assert_eq!(
+            extract(&rules, "firmware/acme-router-2.4.1.bin"),
+            Some((Some("acme-router".into()), "2.4.1".into()))
+        );
+        assert_eq!(
+            extract(&rules, "/firmware/acme-router-v3.0-rc1.bin"),
+            Some((Some("acme-router".into()), "v3.0-rc1".into()))
+        );
+        assert_eq!(extract(&rules, "firmware/acme-router.bin"), None);
+        assert_eq!(extract(&rules, "other/acme-1.0.bin"), None);
+    }
+
+    #[test]
+    fn layout_wildcards() {
+        let rules = [layout("{**}/{name}/{version}/{*}")];
+        assert_eq!(
+            extract(&rules, "vendor/tools/cli/1.2.0/cli-linux.tar.gz"),
+            Some((Some("cli".into()), "1.2.0".into()))
+        );
+    }
+
+    #[test]
+    fn regex_rule_uses_named_groups() {
+        let rules = [regex(r"^builds/(?P<name>[a-z]+)_b(?P<version>\d+)\.zip$")];
+        assert_eq!(
+            extract(&rules, "builds/app_b1042.zip"),
+            Some((Some("app".into()), "1042".into()))
+        );
+
+        let rules = [regex(r"release-(?P<version>\d+\.\d+)")];
+        assert_eq!(
+            extract(&rules, "bundles/release-4.2/all.tgz"),
+            Some((None, "4.2".into()))
+        );
+    }
+
+    #[test]
+    fn maven_rule_names_group_and_artifact() {
+        assert_eq!(
+            extract(&[VersionRule::Maven], "com/example/lib/1.4.0/lib-1.4.0.jar"),
+            Some((Some("com.example:lib".into()), "1.4.0".into()))
+        );
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = [
+            layout("nightly/{name}/{version}/{*}"),
+            regex(r"(?P<version>\d+\.\d+\.\d+)"),
+        ];
+        let set = VersionRuleSet::compile(&rules).unwrap();
+        let hit = set.extract("nightly/app/20260101/app.tgz").unwrap();
+        assert_eq!(hit.rule_index, 0);
+        let hit = set.extract("misc/app-1.2.3.tgz").unwrap();
+        assert_eq!(hit.rule_index, 1);
+        assert_eq!(hit.version, "1.2.3");
+    }
+
+    #[test]
+    fn compile_rejects_invalid_rules() {
+        for bad in [
+            regex(r"(?P<name>.*)"),
+            regex("("),
+            regex(""),
+            layout("{name}/{file}"),
+            layout("{name}/{version}/{version}"),
+            layout("{name"),
+            layout("{name}/}/{version}"),
+            layout("files/only"),
+        ] {
+            let err = VersionRuleSet::compile(std::slice::from_ref(&bad)).unwrap_err();
+            assert!(matches!(err, AppError::Validation(_)), "{bad:?}");
+        }
+
+        let too_many = vec![VersionRule::Maven; MAX_RULES + 1];
+        assert!(VersionRuleSet::compile(&too_many).is_err());
+    }
+
+    #[test]
+    fn rules_round_trip_as_tagged_json() {
+        let rules = vec![
+            regex(r"(?P<version>\d+)"),
+            layout("{name}-{version}.bin"),
+            VersionRule::Maven,
+        ];
+        let json = serde_json::to_value(&rules).unwrap();
+        assert_eq!(json[0]["type"], "regex");
+        assert_eq!(json[1]["template"], "{name}-{version}.bin");
+        assert_eq!(json[2], serde_json::json!({"type": "maven"}));
+        let back: Vec<VersionRule> = serde_json::from_value(json).unwrap();
+        assert_eq!(back, rules);
+    }
+}
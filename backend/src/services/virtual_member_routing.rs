@@ -0,0 +1,311 @@
+//! Per-member include/exclude path routing for virtual repositories.
+//!
+//! A virtual repository resolves a request by walking its members in priority
+//! order. Without routing, every member is asked for every path, so an
+//! internal coordinate such as `com/mycorp/app/1.0/app-1.0.jar` can be served
+//! by a public proxy member if the internal member happens to miss it. This
+//! matches Artifactory's per-member include/exclude patterns, which migrating
+//! users depend on to pin internal namespaces to internal members.
+//!
+//! Patterns use the shared `*` / `?` glob semantics from
+//! [`crate::util::glob`]. They are matched against the request path with any
+//! leading slash stripped, and also against its dotted form (`/` replaced by
+//! `.`) so Maven group-style patterns like `com.mycorp.*` work without the
+//! operator having to spell out the directory layout.
+//!
+//! A member serves a path when:
+//! * its include list is empty, or at least one include pattern matches; and
+//! * no exclude pattern matches.
+//!
+//! Exclusion always wins over inclusion.
+//!
+//! Requests that are not a single download are routed by the download paths
+//! they describe, so one pattern covers a package everywhere it appears:
+//! * per-package metadata (a packument, a gem's info file, a version list)
+//!   is matched against the directory or filename prefix its downloads share;
+//! * merged indexes (repodata, `PACKAGES`, `index.yaml`, spec lists) keep every
+//!   member but drop each entry whose download path that member may not serve.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::repository::Repository;
+use crate::util::glob::glob_match;
+
+/// Upper bound on patterns per list. Routing runs on every virtual request, so
+/// the pattern set is kept small enough that evaluation stays negligible.
+pub const MAX_PATTERNS_PER_LIST: usize = 64;
+
+/// Upper bound on the length of a single pattern.
+pub const MAX_PATTERN_LEN: usize = 512;
+
+/// Include/exclude patterns attached to one virtual member.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MemberPathPatterns {
+    /// Paths this member may serve. Empty means "everything".
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+    /// Paths this member must never serve. Takes precedence over includes.
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+}
+
+impl MemberPathPatterns {
+    /// True when neither list is set, i.e. the member serves every path.
+    pub fn is_unrestricted(&self) -> bool {
+        self.include_patterns.is_empty() && self.exclude_patterns.is_empty()
+    }
+
+    /// Whether this member is allowed to serve `path`.
+    pub fn allows(&self, path: &str) -> bool {
+        if self.is_unrestricted() {
+            return true;
+        }
+        let slashed = path.trim_start_matches('/');
+        let dotted = slashed.replace('/', ".");
+        let matches = |pattern: &String| {
+            let pattern = pattern.trim_start_matches('/');
+            glob_match(pattern, slashed) || glob_match(pattern, &dotted)
+        };
+
+        if self.exclude_patterns.iter().any(matches) {
+            return false;
+        }
+        self.include_patterns.is_empty() || self.include_patterns.iter().any(matches)
+    }
+}
+
+/// Validate a pattern set before it is persisted.
+pub fn validate_patterns(patterns: &MemberPathPatterns) -> Result<()> {
+    for (label, list) in [
+        ("include_patterns", &patterns.include_patterns),
+        ("exclude_patterns", &patterns.exclude_patterns),
+    ] {
+        if list.len() > MAX_PATTERNS_PER_LIST {
+            return Err(AppError::Validation(format!(
+                "{label} accepts at most {MAX_PATTERNS_PER_LIST} patterns"
+            )));
+        }
+        for pattern in list {
+            if pattern.trim().is_empty() {
+                return Err(AppError::Validation(format!(
+                    "{label} must not contain empty patterns"
+                )));
+            }
+            if pattern.len() > MAX_PATTERN_LEN {
+                return Err(AppError::Validation(format!(
+                    "{label} pattern exceeds {MAX_PATTERN_LEN} characters"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The routing patterns of one virtual repository's members, keyed by member
+/// repository id. Members without an entry are unrestricted.
+#[derive(Debug, Clone, Default)]
+pub struct MemberRoutes {
+    patterns: HashMap<Uuid, MemberPathPatterns>,
+}
+
+impl MemberRoutes {
+    pub fn new(patterns: HashMap<Uuid, MemberPathPatterns>) -> Self {
+        Self { patterns }
+    }
+
+    /// True when no member has patterns configured.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Whether member `member_id` may serve `path`.
+    ///
+    /// Fan-outs that merge many packages into one response (indexes,
+    /// listings) check each entry a member contributes against the path the
+    /// entry is downloaded from, so a member never advertises what it could
+    /// not serve.
+    pub fn allows(&self, member_id: Uuid, path: &str) -> bool {
+        self.patterns
+            .get(&member_id)
+            .map(|p| p.allows(path))
+            .unwrap_or(true)
+    }
+}
+
+/// Keep only the members whose patterns allow `path`, preserving priority
+/// order.
+pub fn filter_members_for_path(
+    members: Vec<Repository>,
+    routes: &MemberRoutes,
+    path: &str,
+) -> Vec<Repository> {
+    members
+        .into_iter()
+        .filter(|m| routes.allows(m.id, path))
+        .collect()
+}
+
+#[derive(sqlx::FromRow)]
+struct MemberPatternRow {
+    member_repo_id: Uuid,
+    include_patterns: Vec<String>,
+    exclude_patterns: Vec<String>,
+}
+
+/// Load the routing patterns for every member of `virtual_repo_id` that has
+/// at least one pattern configured. Unrestricted members are omitted so the
+/// common case (no routing configured) returns empty routes.
+pub async fn load_member_routes(db: &PgPool, virtual_repo_id: Uuid) -> Result<MemberRoutes> {
+    let rows: Vec<MemberPatternRow> = sqlx::query_as(
+        r#"
+        SELECT member_repo_id, include_patterns, exclude_patterns
+        FROM virtual_repo_members
+        WHERE virtual_repo_id = $1
+          AND (cardinality(include_patterns) > 0 OR cardinality(exclude_patterns) > 0)
+        "#,
+    )
+    .bind(virtual_repo_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    Ok(MemberRoutes::new(
+        rows.into_iter()
+            .map(|row| {
+                (
+                    row.member_repo_id,
+                    MemberPathPatterns {
+                        include_patterns: row.include_patterns,
+                        exclude_patterns: row.exclude_patterns,
+                    },
+                )
+            })
+            .collect(),
+    ))
+}
+
+/// Replace the routing patterns of a single virtual member. Returns
+/// `NotFound` when the member is not part of the virtual repository.
+pub async fn set_member_patterns(
+    db: &PgPool,
+    virtual_repo_id: Uuid,
+    member_repo_id: Uuid,
+    patterns: &MemberPathPatterns,
+) -> Result<()> {
+    validate_patterns(patterns)?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE virtual_repo_members
+           SET include_patterns = $3, exclude_patterns = $4
+         WHERE virtual_repo_id = $1 AND member_repo_id = $2
+        "#,
+    )
+    .bind(virtual_repo_id)
+    .bind(member_repo_id)
+    .bind(&patterns.include_patterns)
+    .bind(&patterns.exclude_patterns)
+    .execute(db)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "Member not found in virtual repository".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(include: &[&str], exclude: &[&str]) -> MemberPathPatterns {
+        MemberPathPatterns {
+            include_patterns: include.iter().map(|s| s.to_string()).collect(),
+            exclude_patterns: exclude.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn unrestricted_member_allows_everything() {
+        let p = MemberPathPatterns::default();
+        assert!(p.is_unrestricted());
+        assert!(p.allows("com/mycorp/app/1.0/app-1.0.jar"));
+        assert!(p.allows(""));
+    }
+
+    #[test]
+    fn include_pattern_limits_member_to_namespace() {
+        let internal = patterns(&["com/mycorp/*"], &[]);
+        assert!(internal.allows("com/mycorp/app/1.0/app-1.0.jar"));
+        assert!(internal.allows("/com/mycorp/app/1.0/app-1.0.jar"));
+        assert!(!internal.allows("org/apache/commons/1.0/commons-1.0.jar"));
+    }
+
+    #[test]
+    fn dotted_group_pattern_matches_maven_layout() {
+        let internal = patterns(&["com.mycorp.*"], &[]);
+        assert!(internal.allows("com/mycorp/app/1.0/app-1.0.jar"));
+        assert!(!internal.allows("com/other/app/1.0/app-1.0.jar"));
+    }
+
+    #[test]
+    fn exclude_wins_over_include() {
+        let proxy = patterns(&["*"], &["com/mycorp/*"]);
+        assert!(proxy.allows("org/apache/commons/1.0/commons-1.0.jar"));
+        assert!(!proxy.allows("com/mycorp/app/1.0/app-1.0.jar"));
+    }
+
+    #[test]
+    fn exclude_only_member_serves_everything_else() {
+        let proxy = patterns(&[], &["@mycorp/*"]);
+        assert!(proxy.allows("lodash"));
+        assert!(!proxy.allows("@mycorp/ui"));
+    }
+
+    #[test]
+    fn member_routes_only_restrict_members_with_patterns() {
+        let internal = Uuid::new_v4();
+        let proxy = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let routes = MemberRoutes::new(HashMap::from([
+            (internal, patterns(&["com/mycorp/*"], &[])),
+            (proxy, patterns(&[], &["com/mycorp/*"])),
+        ]));
+        assert!(!routes.is_empty());
+
+        let metadata = "com/mycorp/app/maven-metadata.xml";
+        assert!(routes.allows(internal, metadata));
+        assert!(!routes.allows(proxy, metadata));
+        assert!(routes.allows(other, metadata));
+
+        let public = "org/apache/commons/commons-lang3/maven-metadata.xml";
+        assert!(!routes.allows(internal, public));
+        assert!(routes.allows(proxy, public));
+        assert!(MemberRoutes::default().allows(proxy, metadata));
+    }
+
+    #[test]
+    fn validate_rejects_empty_and_oversized_patterns() {
+        assert!(validate_patterns(&patterns(&["com/*"], &["org/*"])).is_ok());
+        assert!(validate_patterns(&patterns(&[" "], &[])).is_err());
+        let long = "a".repeat(MAX_PATTERN_LEN + 1);
+        assert!(validate_patterns(&patterns(&[], &[&long])).is_err());
+        let many: Vec<String> = (0..=MAX_PATTERNS_PER_LIST)
+            .map(|i| format!("p{i}"))
+            .collect();
+        let too_many = MemberPathPatterns {
+            include_patterns: many,
+            exclude_patterns: vec![],
+        };
+        assert!(validate_patterns(&too_many).is_err());
+    }
+}
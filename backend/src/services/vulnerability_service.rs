@@ -0,0 +1,551 @@
+//! Deduplicated vulnerability records.
+//!
+//! `scan_findings` rows are per scan run; the advisory text they share lives
+//! once in `vulnerabilities`, and `artifact_vulnerabilities` tracks each
+//! artifact's state for a vulnerability across scans (migration 211). Every
+//! path that writes findings calls [`link_scan_findings`] afterwards, which
+//! upserts the reference rows, links the findings, drops a finding's
+//! `description` when it matches the reference text, and refreshes the
+//! artifact's link rows.
+//!
+//! Readers that return a finding's `description` must fall back to the
+//! vulnerability's text (`COALESCE(sf.description, v.description)`, or
+//! [`hydrate_finding_descriptions`] for typed queries).
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::security::ScanFinding;
+
+/// A deduplicated vulnerability (one per advisory id, or per source and
+/// title for findings without one).
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct Vulnerability {
+    pub id: Uuid,
+    pub vuln_key: String,
+    pub cve_id: Option<String>,
+    pub severity: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub source: Option<String>,
+    pub source_url: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// A vulnerability with the number of artifacts currently affected.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct VulnerabilitySummary {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub vulnerability: Vulnerability,
+    pub open_artifacts: i64,
+    pub acknowledged_artifacts: i64,
+}
+
+/// An artifact's state for one vulnerability and affected component.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ArtifactVulnerability {
+    pub vulnerability_id: Uuid,
+    pub cve_id: Option<String>,
+    pub severity: String,
+    pub title: String,
+    pub affected_component: String,
+    pub affected_version: Option<String>,
+    pub fixed_version: Option<String>,
+    pub status: String,
+    pub acknowledged_by: Option<Uuid>,
+    pub acknowledged_reason: Option<String>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub first_detected_at: DateTime<Utc>,
+    pub last_detected_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Whether one version of a package is affected by a vulnerability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionStatus {
+    /// The latest scans report the vulnerability.
+    Affected,
+    /// Reported, and the risk was accepted.
+    Acknowledged,
+    /// Scanned after the vulnerability was first seen and not reported (or
+    /// no longer reported).
+    NotAffected,
+    /// Never scanned, or last scanned before the vulnerability was known.
+    Unknown,
+}
+
+/// One version of a package in the "fixed in which versions" view.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PackageVersionStatus {
+    pub artifact_id: Uuid,
+    pub version: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub status: VersionStatus,
+    /// Fix version the scanner reported for this artifact, if any.
+    pub fixed_version: Option<String>,
+    pub last_scanned_at: Option<DateTime<Utc>>,
+}
+
+/// Per-version status of one vulnerability across a package's versions.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PackageVulnerabilityStatus {
+    pub vulnerability: Vulnerability,
+    pub repository_id: Uuid,
+    pub name: String,
+    /// Versions oldest first.
+    pub versions: Vec<PackageVersionStatus>,
+    /// Versions published after the first affected version that are not
+    /// affected, i.e. the releases that carry the fix.
+    pub fixed_in: Vec<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct VersionRow {
+    artifact_id: Uuid,
+    version: Option<String>,
+    created_at: DateTime<Utc>,
+    link_status: Option<String>,
+    fixed_version: Option<String>,
+    last_scanned_at: Option<DateTime<Utc>>,
+}
+
+/// Classify a version from its link row status (if any) and its last
+/// completed scan.
+pub fn classify_version(
+    link_status: Option<&str>,
+    last_scanned_at: Option<DateTime<Utc>>,
+    vulnerability_first_seen_at: DateTime<Utc>,
+) -> VersionStatus {
+    match link_status {
+        Some("open") => VersionStatus::Affected,
+        Some("acknowledged") => VersionStatus::Acknowledged,
+        Some(_) => VersionStatus::NotAffected,
+        None => match last_scanned_at {
+            Some(scanned) if scanned >= vulnerability_first_seen_at => VersionStatus::NotAffected,
+            _ => VersionStatus::Unknown,
+        },
+    }
+}
+
+/// Versions (oldest first) that follow the first affected version and are
+/// not affected.
+pub fn fixed_in(versions: &[PackageVersionStatus]) -> Vec<String> {
+    let Some(first_affected) = versions.iter().position(|v| {
+        matches!(
+            v.status,
+            VersionStatus::Affected | VersionStatus::Acknowledged
+        )
+    }) else {
+        return Vec::new();
+    };
+    versions[first_affected + 1..]
+        .iter()
+        .filter(|v| v.status == VersionStatus::NotAffected)
+        .filter_map(|v| v.version.clone())
+        .collect()
+}
+
+/// Link a scan's findings to their vulnerability rows and refresh the
+/// artifact's link rows. Idempotent; findings that are already linked are
+/// skipped. Takes a connection so callers can run it inside the transaction
+/// that inserted the findings.
+pub async fn link_scan_findings(conn: &mut PgConnection, scan_result_id: Uuid) -> Result<()> {
+    sqlx::query(
+        r#"
+        WITH findings AS (
+            SELECT sf.id, sf.artifact_id, sf.scan_result_id, sf.severity, sf.title,
+                   sf.description, NULLIF(upper(btrim(sf.cve_id)), '') AS cve_id,
+                   sf.source, sf.source_url,
+                   COALESCE(sf.affected_component, '') AS affected_component,
+                   sf.affected_version, sf.fixed_version,
+                   vulnerability_key(sf.cve_id, sf.source, sf.title) AS vuln_key
+            FROM scan_findings sf
+            WHERE sf.scan_result_id = $1 AND sf.vulnerability_id IS NULL
+        ),
+        upserted AS (
+            INSERT INTO vulnerabilities
+                (vuln_key, cve_id, severity, title, description, source, source_url)
+            SELECT DISTINCT ON (vuln_key)
+                   vuln_key, cve_id, severity, title, description, source, source_url
+            FROM findings
+            ORDER BY vuln_key, length(description) DESC NULLS LAST
+            ON CONFLICT (vuln_key) DO UPDATE SET
+                severity = EXCLUDED.severity,
+                title = EXCLUDED.title,
+                description = COALESCE(vulnerabilities.description, EXCLUDED.description),
+                source_url = COALESCE(EXCLUDED.source_url, vulnerabilities.source_url),
+                last_seen_at = NOW()
+            RETURNING id, vuln_key, description
+        ),
+        linked AS (
+            UPDATE scan_findings sf
+            SET vulnerability_id = u.id,
+                description = CASE WHEN sf.description = u.description THEN NULL
+                                   ELSE sf.description END
+            FROM findings f
+            JOIN upserted u ON u.vuln_key = f.vuln_key
+            WHERE sf.id = f.id
+        )
+        INSERT INTO artifact_vulnerabilities
+            (artifact_id, vulnerability_id, affected_component, affected_version,
+             fixed_version, last_scan_result_id)
+        SELECT DISTINCT ON (u.id, f.affected_component)
+               f.artifact_id, u.id, f.affected_component, f.affected_version,
+               f.fixed_version, f.scan_result_id
+        FROM findings f
+        JOIN upserted u ON u.vuln_key = f.vuln_key
+        ORDER BY u.id, f.affected_component, f.fixed_version NULLS LAST
+        ON CONFLICT (artifact_id, vulnerability_id, affected_component) DO UPDATE SET
+            affected_version = COALESCE(EXCLUDED.affected_version,
+                                        artifact_vulnerabilities.affected_version),
+            fixed_version = COALESCE(EXCLUDED.fixed_version,
+                                     artifact_vulnerabilities.fixed_version),
+            status = CASE WHEN artifact_vulnerabilities.status = 'resolved' THEN 'open'
+                          ELSE artifact_vulnerabilities.status END,
+            resolved_at = NULL,
+            last_scan_result_id = EXCLUDED.last_scan_result_id,
+            last_detected_at = NOW()
+        "#,
+    )
+    .bind(scan_result_id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Open links that none of the artifact's latest scans (one per scan
+    // type, counting this one even before it is marked completed) still
+    // report are resolved.
+    sqlx::query(
+        r#"
+        WITH latest AS (
+            SELECT DISTINCT ON (sr.scan_type) sr.id
+            FROM scan_results sr
+            WHERE sr.artifact_id = (SELECT artifact_id FROM scan_results WHERE id = $1)
+              AND (sr.status = 'completed' OR sr.id = $1)
+            ORDER BY sr.scan_type, (sr.id = $1) DESC, sr.completed_at DESC NULLS LAST
+        )
+        UPDATE artifact_vulnerabilities av
+        SET status = 'resolved', resolved_at = NOW()
+        WHERE av.artifact_id = (SELECT artifact_id FROM scan_results WHERE id = $1)
+          AND av.status = 'open'
+          AND NOT EXISTS (
+              SELECT 1
+              FROM scan_findings sf
+              JOIN latest l ON l.id = sf.scan_result_id
+              WHERE sf.vulnerability_id = av.vulnerability_id
+                AND COALESCE(sf.affected_component, '') = av.affected_component
+          )
+        "#,
+    )
+    .bind(scan_result_id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// [`link_scan_findings`] on a pooled connection. Linking is best-effort
+/// for callers whose findings are already committed: a failure leaves the
+/// findings unlinked (readers still see their own text) and is logged.
+pub async fn link_scan_findings_logged(db: &PgPool, scan_result_id: Uuid) {
+    let result: Result<()> = async {
+        let mut conn = db.acquire().await?;
+        link_scan_findings(&mut conn, scan_result_id).await
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(
+            scan_result_id = %scan_result_id,
+            error = %e,
+            "Failed to link scan findings to vulnerabilities"
+        );
+    }
+}
+
+/// Mirror a finding's acknowledgment onto its artifact link row.
+pub async fn sync_acknowledgment(db: &PgPool, finding: &ScanFinding) -> Result<()> {
+    let status = if finding.is_acknowledged {
+        "acknowledged"
+    } else {
+        "open"
+    };
+    sqlx::query(
+        r#"
+        UPDATE artifact_vulnerabilities av
+        SET status = $2,
+            acknowledged_by = $3,
+            acknowledged_reason = $4,
+            acknowledged_at = $5
+        FROM scan_findings sf
+        WHERE sf.id = $1
+          AND av.artifact_id = sf.artifact_id
+          AND av.vulnerability_id = sf.vulnerability_id
+          AND av.affected_component = COALESCE(sf.affected_component, '')
+          AND av.status <> 'resolved'
+        "#,
+    )
+    .bind(finding.id)
+    .bind(status)
+    .bind(finding.acknowledged_by)
+    .bind(&finding.acknowledged_reason)
+    .bind(finding.acknowledged_at)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Fill in `description` for findings whose text was deduplicated into
+/// their vulnerability row.
+pub async fn hydrate_finding_descriptions(db: &PgPool, findings: &mut [ScanFinding]) -> Result<()> {
+    let ids: Vec<Uuid> = findings
+        .iter()
+        .filter(|f| f.description.is_none())
+        .map(|f| f.id)
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let rows: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT sf.id, v.description \
+         FROM scan_findings sf \
+         JOIN vulnerabilities v ON v.id = sf.vulnerability_id \
+         WHERE sf.id = ANY($1) AND v.description IS NOT NULL",
+    )
+    .bind(&ids)
+    .fetch_all(db)
+    .await?;
+    let descriptions: std::collections::HashMap<Uuid, String> = rows.into_iter().collect();
+    for finding in findings.iter_mut() {
+        if finding.description.is_none() {
+            finding.description = descriptions.get(&finding.id).cloned();
+        }
+    }
+    Ok(())
+}
+
+pub struct VulnerabilityService {
+    db: PgPool,
+}
+
+impl VulnerabilityService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Look up a vulnerability by id or advisory id (case-insensitive).
+    pub async fn get(&self, id_or_cve: &str) -> Result<Vulnerability> {
+        let by_id = Uuid::parse_str(id_or_cve).ok();
+        sqlx::query_as::<_, Vulnerability>(
+            "SELECT id, vuln_key, cve_id, severity, title, description, source, source_url, \
+                    first_seen_at, last_seen_at \
+             FROM vulnerabilities \
+             WHERE id = $1 OR vuln_key = upper(btrim($2))",
+        )
+        .bind(by_id)
+        .bind(id_or_cve)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Vulnerability not found".to_string()))
+    }
+
+    /// List vulnerabilities with affected-artifact counts, most widespread
+    /// first.
+    pub async fn list(
+        &self,
+        cve_id: Option<&str>,
+        severity: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<VulnerabilitySummary>> {
+        let rows = sqlx::query_as::<_, VulnerabilitySummary>(
+            r#"
+            SELECT v.id, v.vuln_key, v.cve_id, v.severity, v.title, v.description,
+                   v.source, v.source_url, v.first_seen_at, v.last_seen_at,
+                   COUNT(DISTINCT av.artifact_id) FILTER (WHERE av.status = 'open')
+                       AS open_artifacts,
+                   COUNT(DISTINCT av.artifact_id) FILTER (WHERE av.status = 'acknowledged')
+                       AS acknowledged_artifacts
+            FROM vulnerabilities v
+            LEFT JOIN artifact_vulnerabilities av ON av.vulnerability_id = v.id
+            WHERE ($1::TEXT IS NULL OR v.cve_id ILIKE '%' || $1 || '%')
+              AND ($2::TEXT IS NULL OR v.severity = lower($2))
+            GROUP BY v.id
+            ORDER BY open_artifacts DESC, v.last_seen_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(cve_id)
+        .bind(severity)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows)
+    }
+
+    /// An artifact's vulnerabilities. Resolved links are included only when
+    /// `include_resolved` is set.
+    pub async fn list_for_artifact(
+        &self,
+        artifact_id: Uuid,
+        include_resolved: bool,
+    ) -> Result<Vec<ArtifactVulnerability>> {
+        let rows = sqlx::query_as::<_, ArtifactVulnerability>(
+            r#"
+            SELECT av.vulnerability_id, v.cve_id, v.severity, v.title,
+                   av.affected_component, av.affected_version, av.fixed_version, av.status,
+                   av.acknowledged_by, av.acknowledged_reason, av.acknowledged_at,
+                   av.first_detected_at, av.last_detected_at, av.resolved_at
+            FROM artifact_vulnerabilities av
+            JOIN vulnerabilities v ON v.id = av.vulnerability_id
+            WHERE av.artifact_id = $1
+              AND ($2 OR av.status <> 'resolved')
+            ORDER BY
+                CASE v.severity
+                    WHEN 'critical' THEN 0
+                    WHEN 'high' THEN 1
+                    WHEN 'medium' THEN 2
+                    WHEN 'low' THEN 3
+                    ELSE 4
+                END,
+                v.cve_id NULLS LAST,
+                av.affected_component
+            "#,
+        )
+        .bind(artifact_id)
+        .bind(include_resolved)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Status of one vulnerability across every version of a package
+    /// (artifacts named `name` in the repository).
+    pub async fn package_status(
+        &self,
+        vulnerability: Vulnerability,
+        repository_id: Uuid,
+        name: &str,
+    ) -> Result<PackageVulnerabilityStatus> {
+        let rows = sqlx::query_as::<_, VersionRow>(
+            r#"
+            SELECT a.id AS artifact_id, a.version, a.created_at,
+                   av.status AS link_status, av.fixed_version,
+                   (SELECT MAX(sr.completed_at) FROM scan_results sr
+                    WHERE sr.artifact_id = a.id AND sr.status = 'completed')
+                       AS last_scanned_at
+            FROM artifacts a
+            LEFT JOIN LATERAL (
+                SELECT status, fixed_version
+                FROM artifact_vulnerabilities
+                WHERE artifact_id = a.id AND vulnerability_id = $3
+                ORDER BY CASE status WHEN 'open' THEN 0 WHEN 'acknowledged' THEN 1 ELSE 2 END
+                LIMIT 1
+            ) av ON true
+            WHERE a.repository_id = $1 AND a.name = $2 AND a.is_deleted = false
+            ORDER BY a.created_at, a.id
+            "#,
+        )
+        .bind(repository_id)
+        .bind(name)
+        .bind(vulnerability.id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let versions: Vec<PackageVersionStatus> = rows
+            .into_iter()
+            .map(|row| PackageVersionStatus {
+                artifact_id: row.artifact_id,
+                status: classify_version(
+                    row.link_status.as_deref(),
+                    row.last_scanned_at,
+                    vulnerability.first_seen_at,
+                ),
+                version: row.version,
+                created_at: row.created_at,
+                fixed_version: row.fixed_version,
+                last_scanned_at: row.last_scanned_at,
+            })
+            .collect();
+        Ok(PackageVulnerabilityStatus {
+            fixed_in: fixed_in(&versions),
+            vulnerability,
+            repository_id,
+            name: name.to_string(),
+            versions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn version(v: &str, status: VersionStatus) -> PackageVersionStatus {
+        PackageVersionStatus {
+            artifact_id: Uuid::new_v4(),
+            version: Some(v.to_string()),
+            created_at: Utc::now(),
+            status,
+            fixed_version: None,
+            last_scanned_at: None,
+        }
+    }
+
+    #[test]
+    fn classify_uses_link_status_first() {
+        let seen = Utc::now();
+        assert_eq!(
+            classify_version(Some("open"), None, seen),
+            VersionStatus::Affected
+        );
+        assert_eq!(
+            classify_version(Some("acknowledged"), None, seen),
+            VersionStatus::Acknowledged
+        );
+        assert_eq!(
+            classify_version(Some("resolved"), None, seen),
+            VersionStatus::NotAffected
+        );
+    }
+
+    #[test]
+    fn classify_unlinked_depends_on_scan_recency() {
+        let seen = Utc::now();
+        assert_eq!(
+            classify_version(None, Some(seen + Duration::hours(1)), seen),
+            VersionStatus::NotAffected
+        );
+        // Last scan predates the advisory: the version may well be affected.
+        assert_eq!(
+            classify_version(None, Some(seen - Duration::hours(1)), seen),
+            VersionStatus::Unknown
+        );
+        assert_eq!(classify_version(None, None, seen), VersionStatus::Unknown);
+    }
+
+    #[test]
+    fn fixed_in_lists_unaffected_versions_after_first_affected() {
+        let versions = vec![
+            version("1.0.0", VersionStatus::NotAffected),
+            version("1.1.0", VersionStatus::Affected),
+            version("1.1.1", VersionStatus::Unknown),
+            version("1.2.0", VersionStatus::NotAffected),
+            version("1.3.0", VersionStatus::NotAffected),
+        ];
+        assert_eq!(fixed_in(&versions), vec!["1.2.0", "1.3.0"]);
+    }
+
+    #[test]
+    fn fixed_in_empty_when_never_affected() {
+        let versions = vec![
+            version("1.0.0", VersionStatus::NotAffected),
+            version("1.1.0", VersionStatus::Unknown),
+        ];
+        assert!(fixed_in(&versions).is_empty());
+    }
+}
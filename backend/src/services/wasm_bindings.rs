@@ -6,6 +6,8 @@
 //! Two worlds are supported:
 //! - `format-plugin` (v1): parse_metadata, validate, generate_index
 //! - `format-plugin-v2`: adds handle_request for native protocol serving
+//! - `format-plugin-v2-openapi`: v2 plus an OpenAPI fragment of the served
+//!   endpoints
 
 use bytes::Bytes;
 
@@ -31,6 +33,16 @@ pub mod v2 {
     });
 }
 
+/// V2 bindings plus the OpenAPI fragment export.
+pub mod v2_openapi {
+    wasmtime::component::bindgen!({
+        world: "format-plugin-v2-openapi",
+        path: "src/wit/format-plugin.wit",
+        imports: { default: async | trappable },
+        exports: { default: async },
+    });
+}
+
 // Re-export the main types for convenience
 pub use v1::FormatPlugin;
 
@@ -574,6 +574,9 @@ impl WasmPluginService {
             ManifestValidationError::InvalidTimeout(secs) => {
                 AppError::Validation(format!("Invalid timeout {}: must be between 1 and 300 seconds", secs))
             }
+            ManifestValidationError::OpenApiWithoutHandleRequest => {
+                AppError::Validation("openapi_fragment requires the handle_request capability".to_string())
+            }
         })
     }
 
@@ -1982,6 +1985,9 @@ mod tests {
                     secs
                 )
             }
+            ManifestValidationError::OpenApiWithoutHandleRequest => {
+                "openapi_fragment requires the handle_request capability".to_string()
+            }
         }
     }
 
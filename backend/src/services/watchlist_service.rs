@@ -0,0 +1,954 @@
+//! Vulnerability watchlist and match alerts.
+//!
+//! Security teams register advisories ("CVE-2021-44228") or packages
+//! ("log4j-core", "org.apache.logging.*") they want to hear about as soon as
+//! they show up in the registry. [`start_evaluator`] subscribes to the
+//! EventBus and matches the artifact each event is about against the
+//! enabled entries:
+//!
+//! - `artifact.created` checks the uploaded package itself (and any findings
+//!   already copied onto it);
+//! - `scan.completed` additionally checks the artifact's current
+//!   vulnerabilities and scanned component inventory.
+//!
+//! Matching is independent of security policies: a watched advisory raises
+//! an alert whether or not any policy blocks it, and acknowledged findings
+//! still match. Each match is recorded once per entry, artifact and matched
+//! item in `vulnerability_watchlist_alerts` and published on the EventBus as
+//! `watchlist.matched` (delivered to webhooks as `watchlist_matched`).
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::event_bus::{DomainEvent, EventBus};
+
+/// Upper bound on watchlist entries.
+pub const MAX_WATCHLIST_ENTRIES: i64 = 1000;
+
+const MAX_PATTERN_LEN: usize = 512;
+const MAX_VERSION_LEN: usize = 100;
+
+/// What a watchlist entry watches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchKind {
+    /// An advisory id (CVE, GHSA, ...) reported by a scanner.
+    Advisory,
+    /// A package name, matched against uploaded packages and scanned
+    /// components.
+    Package,
+}
+
+impl WatchKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Advisory => "advisory",
+            Self::Package => "package",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "advisory" => Some(Self::Advisory),
+            "package" => Some(Self::Package),
+            _ => None,
+        }
+    }
+}
+
+/// Create or replace a watchlist entry.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct WatchlistEntryRequest {
+    pub kind: WatchKind,
+    /// Advisory id, or package name. A package name ending in `*` watches
+    /// every package with that prefix.
+    pub pattern: String,
+    /// Package entries only: exact version to watch. Omit to watch every
+    /// version.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Restrict the entry to one repository. Omit to watch all repositories.
+    #[serde(default)]
+    pub repository_id: Option<Uuid>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A watched advisory or package.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WatchlistEntry {
+    pub id: Uuid,
+    pub kind: WatchKind,
+    pub pattern: String,
+    pub version: Option<String>,
+    pub repository_id: Option<Uuid>,
+    pub repository_key: Option<String>,
+    pub note: Option<String>,
+    pub enabled: bool,
+    pub created_by: Option<Uuid>,
+    /// Alerts not yet acknowledged.
+    pub open_alerts: i64,
+    pub last_matched_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct WatchlistEntryRow {
+    id: Uuid,
+    kind: String,
+    pattern: String,
+    version: Option<String>,
+    repository_id: Option<Uuid>,
+    repository_key: Option<String>,
+    note: Option<String>,
+    enabled: bool,
+    created_by: Option<Uuid>,
+    open_alerts: i64,
+    last_matched_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<WatchlistEntryRow> for WatchlistEntry {
+    fn from(row: WatchlistEntryRow) -> Self {
+        Self {
+            id: row.id,
+            kind: WatchKind::parse(&row.kind).unwrap_or(WatchKind::Package),
+            pattern: row.pattern,
+            version: row.version,
+            repository_id: row.repository_id,
+            repository_key: row.repository_key,
+            note: row.note,
+            enabled: row.enabled,
+            created_by: row.created_by,
+            open_alerts: row.open_alerts,
+            last_matched_at: row.last_matched_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// One artifact that matched a watchlist entry.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct WatchlistAlert {
+    pub id: Uuid,
+    pub watch_id: Uuid,
+    pub watch_kind: String,
+    pub watch_pattern: String,
+    pub artifact_id: Uuid,
+    pub artifact_name: String,
+    pub artifact_version: Option<String>,
+    pub repository_id: Uuid,
+    pub repository_key: String,
+    /// The advisory id, or `name@version` of the matching component.
+    pub matched: String,
+    pub event_type: String,
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+    pub acknowledged_by: Option<Uuid>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+const ENTRY_COLUMNS: &str = r#"
+    w.id, w.kind, w.pattern, w.version, w.repository_id, r.key AS repository_key,
+    w.note, w.enabled, w.created_by, w.last_matched_at, w.created_at, w.updated_at,
+    (SELECT COUNT(*) FROM vulnerability_watchlist_alerts al
+     WHERE al.watch_id = w.id AND al.acknowledged_at IS NULL) AS open_alerts
+"#;
+
+const ALERT_COLUMNS: &str = r#"
+    al.id, al.watch_id, w.kind AS watch_kind, w.pattern AS watch_pattern,
+    al.artifact_id, a.name AS artifact_name, a.version AS artifact_version,
+    a.repository_id, r.key AS repository_key, al.matched, al.event_type,
+    al.details, al.acknowledged_by, al.acknowledged_at, al.created_at
+"#;
+
+fn db_err(e: sqlx::Error) -> AppError {
+    AppError::Database(e.to_string())
+}
+
+fn duplicate_conflict(e: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db) = &e {
+        if db.is_unique_violation() {
+            return AppError::Conflict("This item is already on the watchlist".to_string());
+        }
+    }
+    db_err(e)
+}
+
+/// Normalize and validate an entry's pattern: advisory ids are upper-cased,
+/// package names lower-cased. Only package patterns may use a trailing `*`.
+pub fn normalize_pattern(kind: WatchKind, pattern: &str) -> Result<String> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() || pattern.len() > MAX_PATTERN_LEN {
+        return Err(AppError::Validation(format!(
+            "Pattern must be between 1 and {} characters",
+            MAX_PATTERN_LEN
+        )));
+    }
+    match kind {
+        WatchKind::Advisory => {
+            let id = pattern.to_ascii_uppercase();
+            if !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+            {
+                return Err(AppError::Validation(
+                    "Advisory ids may only contain letters, digits, '-', '_', '.' and ':'"
+                        .to_string(),
+                ));
+            }
+            Ok(id)
+        }
+        WatchKind::Package => {
+            let name = pattern.to_lowercase();
+            let prefix = name.strip_suffix('*');
+            if prefix.unwrap_or(&name).contains('*') {
+                return Err(AppError::Validation(
+                    "A package pattern may only contain '*' as its last character".to_string(),
+                ));
+            }
+            if prefix.is_some_and(|p| p.chars().count() < 2) {
+                return Err(AppError::Validation(
+                    "A package prefix pattern needs at least 2 characters before '*'".to_string(),
+                ));
+            }
+            if name.chars().any(char::is_whitespace) {
+                return Err(AppError::Validation(
+                    "Package names may not contain whitespace".to_string(),
+                ));
+            }
+            Ok(name)
+        }
+    }
+}
+
+fn validate_request(request: &WatchlistEntryRequest) -> Result<(String, Option<String>)> {
+    let pattern = normalize_pattern(request.kind, &request.pattern)?;
+    let version = request
+        .version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string);
+    if let Some(version) = &version {
+        if request.kind == WatchKind::Advisory {
+            return Err(AppError::Validation(
+                "version only applies to package entries".to_string(),
+            ));
+        }
+        if version.len() > MAX_VERSION_LEN {
+            return Err(AppError::Validation(format!(
+                "version must be at most {} characters",
+                MAX_VERSION_LEN
+            )));
+        }
+    }
+    Ok((pattern, version))
+}
+
+async fn ensure_repository(db: &PgPool, repository_id: Option<Uuid>) -> Result<()> {
+    let Some(repository_id) = repository_id else {
+        return Ok(());
+    };
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM repositories WHERE id = $1)")
+            .bind(repository_id)
+            .fetch_one(db)
+            .await
+            .map_err(db_err)?;
+    if !exists {
+        return Err(AppError::NotFound("Repository not found".to_string()));
+    }
+    Ok(())
+}
+
+/// All watchlist entries, newest first.
+pub async fn list(db: &PgPool) -> Result<Vec<WatchlistEntry>> {
+    let sql = format!(
+        "SELECT {} FROM vulnerability_watchlist w \
+         LEFT JOIN repositories r ON r.id = w.repository_id \
+         ORDER BY w.created_at DESC",
+        ENTRY_COLUMNS
+    );
+    let rows: Vec<WatchlistEntryRow> = sqlx::query_as(&sql).fetch_all(db).await.map_err(db_err)?;
+    Ok(rows.into_iter().map(WatchlistEntry::from).collect())
+}
+
+pub async fn get(db: &PgPool, id: Uuid) -> Result<WatchlistEntry> {
+    let sql = format!(
+        "SELECT {} FROM vulnerability_watchlist w \
+         LEFT JOIN repositories r ON r.id = w.repository_id \
+         WHERE w.id = $1",
+        ENTRY_COLUMNS
+    );
+    let row: Option<WatchlistEntryRow> = sqlx::query_as(&sql)
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(db_err)?;
+    row.map(WatchlistEntry::from)
+        .ok_or_else(|| AppError::NotFound("Watchlist entry not found".to_string()))
+}
+
+/// Add an entry to the watchlist.
+pub async fn create(
+    db: &PgPool,
+    request: &WatchlistEntryRequest,
+    created_by: Uuid,
+) -> Result<WatchlistEntry> {
+    let (pattern, version) = validate_request(request)?;
+    ensure_repository(db, request.repository_id).await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vulnerability_watchlist")
+        .fetch_one(db)
+        .await
+        .map_err(db_err)?;
+    if count >= MAX_WATCHLIST_ENTRIES {
+        return Err(AppError::Validation(format!(
+            "At most {} watchlist entries are allowed",
+            MAX_WATCHLIST_ENTRIES
+        )));
+    }
+
+    let id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO vulnerability_watchlist
+            (kind, pattern, version, repository_id, note, enabled, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#,
+    )
+    .bind(request.kind.as_str())
+    .bind(&pattern)
+    .bind(&version)
+    .bind(request.repository_id)
+    .bind(request.note.as_deref().map(str::trim))
+    .bind(request.enabled)
+    .bind(created_by)
+    .fetch_one(db)
+    .await
+    .map_err(duplicate_conflict)?;
+
+    get(db, id).await
+}
+
+/// Replace an entry. Existing alerts are kept.
+pub async fn update(
+    db: &PgPool,
+    id: Uuid,
+    request: &WatchlistEntryRequest,
+) -> Result<WatchlistEntry> {
+    let (pattern, version) = validate_request(request)?;
+    ensure_repository(db, request.repository_id).await?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE vulnerability_watchlist
+        SET kind = $2, pattern = $3, version = $4, repository_id = $5,
+            note = $6, enabled = $7, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(request.kind.as_str())
+    .bind(&pattern)
+    .bind(&version)
+    .bind(request.repository_id)
+    .bind(request.note.as_deref().map(str::trim))
+    .bind(request.enabled)
+    .execute(db)
+    .await
+    .map_err(duplicate_conflict)?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Watchlist entry not found".to_string()));
+    }
+
+    get(db, id).await
+}
+
+/// Remove an entry and its alerts.
+pub async fn delete(db: &PgPool, id: Uuid) -> Result<()> {
+    let result = sqlx::query("DELETE FROM vulnerability_watchlist WHERE id = $1")
+        .bind(id)
+        .execute(db)
+        .await
+        .map_err(db_err)?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Watchlist entry not found".to_string()));
+    }
+    Ok(())
+}
+
+/// Watchlist alerts, newest first. Alerts for artifacts deleted since are
+/// left out.
+pub async fn list_alerts(
+    db: &PgPool,
+    watch_id: Option<Uuid>,
+    open_only: bool,
+    limit: i64,
+) -> Result<Vec<WatchlistAlert>> {
+    let sql = format!(
+        r#"
+        SELECT {}
+        FROM vulnerability_watchlist_alerts al
+        JOIN vulnerability_watchlist w ON w.id = al.watch_id
+        JOIN artifacts a ON a.id = al.artifact_id AND a.is_deleted = false
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE ($1::uuid IS NULL OR al.watch_id = $1)
+          AND ($2 = false OR al.acknowledged_at IS NULL)
+        ORDER BY al.created_at DESC
+        LIMIT $3
+        "#,
+        ALERT_COLUMNS
+    );
+    sqlx::query_as(&sql)
+        .bind(watch_id)
+        .bind(open_only)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+        .map_err(db_err)
+}
+
+pub async fn get_alert(db: &PgPool, id: Uuid) -> Result<WatchlistAlert> {
+    let sql = format!(
+        r#"
+        SELECT {}
+        FROM vulnerability_watchlist_alerts al
+        JOIN vulnerability_watchlist w ON w.id = al.watch_id
+        JOIN artifacts a ON a.id = al.artifact_id
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE al.id = $1
+        "#,
+        ALERT_COLUMNS
+    );
+    let alert: Option<WatchlistAlert> = sqlx::query_as(&sql)
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(db_err)?;
+    alert.ok_or_else(|| AppError::NotFound("Watchlist alert not found".to_string()))
+}
+
+/// Acknowledge the given alerts (or, with `None`, every open alert).
+/// Returns the number of alerts updated.
+pub async fn acknowledge_alerts(db: &PgPool, user_id: Uuid, ids: Option<&[Uuid]>) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE vulnerability_watchlist_alerts
+        SET acknowledged_at = NOW(), acknowledged_by = $1
+        WHERE acknowledged_at IS NULL
+          AND ($2::uuid[] IS NULL OR id = ANY($2))
+        "#,
+    )
+    .bind(user_id)
+    .bind(ids)
+    .execute(db)
+    .await
+    .map_err(db_err)?;
+    Ok(result.rows_affected())
+}
+
+// ---------------------------------------------------------------------------
+// Matching
+// ---------------------------------------------------------------------------
+
+/// Whether a package `pattern` (normalized, optionally ending in `*`) and
+/// optional exact `version` match a component.
+pub fn package_matches(
+    pattern: &str,
+    version: Option<&str>,
+    name: &str,
+    component_version: Option<&str>,
+) -> bool {
+    let name = name.trim().to_lowercase();
+    let name_matches = match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    };
+    if !name_matches {
+        return false;
+    }
+    match version {
+        Some(wanted) => component_version.is_some_and(|v| v.trim() == wanted),
+        None => true,
+    }
+}
+
+/// An advisory currently reported against an artifact.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AdvisoryFact {
+    pub advisory_id: String,
+    pub severity: String,
+    pub component: Option<String>,
+    pub component_version: Option<String>,
+}
+
+/// A package known to be in (or to be) an artifact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentFact {
+    pub name: String,
+    pub version: Option<String>,
+    /// `artifact` for the uploaded package itself, `scan` for a component
+    /// found by a scanner.
+    pub source: &'static str,
+}
+
+/// Everything the watchlist is matched against for one artifact.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactInventory {
+    pub advisories: Vec<AdvisoryFact>,
+    pub components: Vec<ComponentFact>,
+}
+
+/// An enabled watchlist entry being evaluated.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WatchCandidate {
+    pub id: Uuid,
+    pub kind: String,
+    pub pattern: String,
+    pub version: Option<String>,
+}
+
+/// One match of an entry against an artifact's inventory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchMatch {
+    pub watch_id: Uuid,
+    pub matched: String,
+    pub details: serde_json::Value,
+}
+
+/// Match `candidates` against an artifact's inventory. Each entry yields at
+/// most one match per advisory id or component `name@version`.
+pub fn match_inventory(
+    candidates: &[WatchCandidate],
+    inventory: &ArtifactInventory,
+) -> Vec<WatchMatch> {
+    let mut matches = Vec::new();
+    let mut seen: HashSet<(Uuid, String)> = HashSet::new();
+
+    for candidate in candidates {
+        match WatchKind::parse(&candidate.kind) {
+            Some(WatchKind::Advisory) => {
+                for advisory in &inventory.advisories {
+                    if !advisory
+                        .advisory_id
+                        .trim()
+                        .eq_ignore_ascii_case(&candidate.pattern)
+                    {
+                        continue;
+                    }
+                    let matched = candidate.pattern.clone();
+                    if !seen.insert((candidate.id, matched.clone())) {
+                        continue;
+                    }
+                    matches.push(WatchMatch {
+                        watch_id: candidate.id,
+                        matched,
+                        details: serde_json::json!({
+                            "advisory": candidate.pattern,
+                            "severity": advisory.severity,
+                            "component": advisory.component,
+                            "component_version": advisory.component_version,
+                        }),
+                    });
+                }
+            }
+            Some(WatchKind::Package) => {
+                for component in &inventory.components {
+                    if !package_matches(
+                        &candidate.pattern,
+                        candidate.version.as_deref(),
+                        &component.name,
+                        component.version.as_deref(),
+                    ) {
+                        continue;
+                    }
+                    let name = component.name.trim().to_lowercase();
+                    let matched = match &component.version {
+                        Some(v) => format!("{}@{}", name, v.trim()),
+                        None => name,
+                    };
+                    if !seen.insert((candidate.id, matched.clone())) {
+                        continue;
+                    }
+                    matches.push(WatchMatch {
+                        watch_id: candidate.id,
+                        matched,
+                        details: serde_json::json!({
+                            "component": component.name,
+                            "component_version": component.version,
+                            "source": component.source,
+                        }),
+                    });
+                }
+            }
+            None => {}
+        }
+    }
+    matches
+}
+
+fn is_trigger(event_type: &str) -> bool {
+    matches!(event_type, "artifact.created" | "scan.completed")
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ArtifactRow {
+    repository_id: Uuid,
+    name: String,
+    version: Option<String>,
+    repository_key: String,
+}
+
+async fn load_inventory(
+    db: &PgPool,
+    artifact_id: Uuid,
+    artifact: &ArtifactRow,
+) -> Result<ArtifactInventory> {
+    // Current (not resolved) vulnerabilities, acknowledged ones included:
+    // the watchlist reports presence, not policy outcome.
+    let advisories: Vec<AdvisoryFact> = sqlx::query_as(
+        r#"
+        SELECT v.cve_id AS advisory_id, v.severity,
+               NULLIF(av.affected_component, '') AS component,
+               av.affected_version AS component_version
+        FROM artifact_vulnerabilities av
+        JOIN vulnerabilities v ON v.id = av.vulnerability_id
+        WHERE av.artifact_id = $1
+          AND av.status <> 'resolved'
+          AND v.cve_id IS NOT NULL
+        "#,
+    )
+    .bind(artifact_id)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)?;
+
+    let scanned: Vec<(String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT name, version FROM scan_packages WHERE artifact_id = $1
+        UNION
+        SELECT DISTINCT affected_component, affected_version
+        FROM artifact_vulnerabilities
+        WHERE artifact_id = $1 AND status <> 'resolved' AND affected_component <> ''
+        "#,
+    )
+    .bind(artifact_id)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)?;
+
+    let mut components = vec![ComponentFact {
+        name: artifact.name.clone(),
+        version: artifact.version.clone(),
+        source: "artifact",
+    }];
+    components.extend(scanned.into_iter().map(|(name, version)| ComponentFact {
+        name,
+        version,
+        source: "scan",
+    }));
+
+    Ok(ArtifactInventory {
+        advisories,
+        components,
+    })
+}
+
+/// Evaluate enabled watchlist entries against the artifact `event` is
+/// about. Returns the number of new alerts recorded.
+pub async fn evaluate_event(db: &PgPool, bus: &EventBus, event: &DomainEvent) -> Result<u32> {
+    if !is_trigger(&event.event_type) {
+        return Ok(0);
+    }
+    let Ok(artifact_id) = event.entity_id.parse::<Uuid>() else {
+        return Ok(0);
+    };
+
+    let artifact: Option<ArtifactRow> = sqlx::query_as(
+        r#"
+        SELECT a.repository_id, a.name, a.version, r.key AS repository_key
+        FROM artifacts a
+        JOIN repositories r ON r.id = a.repository_id
+        WHERE a.id = $1 AND a.is_deleted = false
+        "#,
+    )
+    .bind(artifact_id)
+    .fetch_optional(db)
+    .await
+    .map_err(db_err)?;
+    let Some(artifact) = artifact else {
+        return Ok(0);
+    };
+
+    let candidates: Vec<WatchCandidate> = sqlx::query_as(
+        r#"
+        SELECT id, kind, pattern, version
+        FROM vulnerability_watchlist
+        WHERE enabled = true
+          AND (repository_id IS NULL OR repository_id = $1)
+        "#,
+    )
+    .bind(artifact.repository_id)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)?;
+    if candidates.is_empty() {
+        return Ok(0);
+    }
+
+    let inventory = load_inventory(db, artifact_id, &artifact).await?;
+    let mut recorded = 0;
+    for found in match_inventory(&candidates, &inventory) {
+        let mut details = found.details;
+        details["name"] = serde_json::json!(artifact.name);
+        details["version"] = serde_json::json!(artifact.version);
+        details["repository"] = serde_json::json!(artifact.repository_key);
+
+        let alert_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO vulnerability_watchlist_alerts
+                (watch_id, artifact_id, matched, event_type, details)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (watch_id, artifact_id, matched) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(found.watch_id)
+        .bind(artifact_id)
+        .bind(&found.matched)
+        .bind(&event.event_type)
+        .bind(&details)
+        .fetch_optional(db)
+        .await
+        .map_err(db_err)?;
+        let Some(alert_id) = alert_id else {
+            continue;
+        };
+
+        sqlx::query("UPDATE vulnerability_watchlist SET last_matched_at = NOW() WHERE id = $1")
+            .bind(found.watch_id)
+            .execute(db)
+            .await
+            .map_err(db_err)?;
+        tracing::info!(
+            watch_id = %found.watch_id,
+            artifact_id = %artifact_id,
+            matched = %found.matched,
+            "Watchlist entry matched"
+        );
+        bus.emit_for_repo("watchlist.matched", alert_id, artifact.repository_id, None);
+        recorded += 1;
+    }
+    Ok(recorded)
+}
+
+/// Start the watchlist evaluator background task.
+///
+/// Listens on the EventBus and matches the watchlist against each uploaded
+/// or scanned artifact. The task exits when the EventBus closes.
+pub fn start_evaluator(event_bus: Arc<EventBus>, db: PgPool) {
+    let mut rx = event_bus.subscribe();
+
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if !is_trigger(&event.event_type) {
+                        continue;
+                    }
+                    if let Err(e) = evaluate_event(&db, &event_bus, &event).await {
+                        tracing::warn!(
+                            event_type = %event.event_type,
+                            entity_id = %event.entity_id,
+                            error = %e,
+                            "Failed to evaluate vulnerability watchlist"
+                        );
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!(
+                        skipped = n,
+                        "Watchlist evaluator lagged, some events were dropped"
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    tracing::info!("EventBus closed, watchlist evaluator shutting down");
+                    break;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(kind: WatchKind, pattern: &str, version: Option<&str>) -> WatchCandidate {
+        WatchCandidate {
+            id: Uuid::new_v4(),
+            kind: kind.as_str().to_string(),
+            pattern: pattern.to_string(),
+            version: version.map(str::to_string),
+        }
+    }
+
+    fn component(name: &str, version: Option<&str>, source: &'static str) -> ComponentFact {
+        ComponentFact {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            source,
+        }
+    }
+
+    fn advisory(id: &str, component: &str) -> AdvisoryFact {
+        AdvisoryFact {
+            advisory_id: id.to_string(),
+            severity: "critical".to_string(),
+            component: Some(component.to_string()),
+            component_version: Some("2.14.1".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_normalize_advisory_pattern() {
+        assert_eq!(
+            normalize_pattern(WatchKind::Advisory, " cve-2021-44228 ").unwrap(),
+            "CVE-2021-44228"
+        );
+        assert_eq!(
+            normalize_pattern(WatchKind::Advisory, "GHSA-jfh8-c2jp-5v3q").unwrap(),
+            "GHSA-JFH8-C2JP-5V3Q"
+        );
+        assert!(normalize_pattern(WatchKind::Advisory, "CVE-2021-*").is_err());
+        assert!(normalize_pattern(WatchKind::Advisory, "CVE 2021").is_err());
+        assert!(normalize_pattern(WatchKind::Advisory, "  ").is_err());
+    }
+
+    #[test]
+    fn test_normalize_package_pattern() {
+        assert_eq!(
+            normalize_pattern(WatchKind::Package, "Log4j-Core").unwrap(),
+            "log4j-core"
+        );
+        assert_eq!(
+            normalize_pattern(WatchKind::Package, "org.apache.logging.*").unwrap(),
+            "org.apache.logging.*"
+        );
+        assert!(normalize_pattern(WatchKind::Package, "*").is_err());
+        assert!(normalize_pattern(WatchKind::Package, "a*").is_err());
+        assert!(normalize_pattern(WatchKind::Package, "lo*dash").is_err());
+        assert!(normalize_pattern(WatchKind::Package, "left pad").is_err());
+        assert!(normalize_pattern(WatchKind::Package, &"x".repeat(513)).is_err());
+    }
+
+    #[test]
+    fn test_package_matches() {
+        assert!(package_matches("lodash", None, "Lodash", Some("4.17.20")));
+        assert!(!package_matches("lodash", None, "lodash-es", None));
+        assert!(package_matches("@babel/*", None, "@babel/core", None));
+        assert!(package_matches(
+            "lodash",
+            Some("4.17.20"),
+            "lodash",
+            Some("4.17.20")
+        ));
+        assert!(!package_matches(
+            "lodash",
+            Some("4.17.20"),
+            "lodash",
+            Some("4.17.21")
+        ));
+        assert!(!package_matches("lodash", Some("4.17.20"), "lodash", None));
+    }
+
+    #[test]
+    fn test_match_inventory_advisory_and_package() {
+        let log4shell = candidate(WatchKind::Advisory, "CVE-2021-44228", None);
+        let log4j = candidate(WatchKind::Package, "org.apache.logging.log4j:*", None);
+        let unrelated = candidate(WatchKind::Advisory, "CVE-2014-0160", None);
+        let inventory = ArtifactInventory {
+            advisories: vec![advisory("cve-2021-44228", "log4j-core")],
+            components: vec![
+                component("my-service", Some("1.0.0"), "artifact"),
+                component(
+                    "org.apache.logging.log4j:log4j-core",
+                    Some("2.14.1"),
+                    "scan",
+                ),
+                component("org.apache.logging.log4j:log4j-api", Some("2.14.1"), "scan"),
+            ],
+        };
+
+        let matches = match_inventory(&[log4shell.clone(), log4j.clone(), unrelated], &inventory);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[0].watch_id, log4shell.id);
+        assert_eq!(matches[0].matched, "CVE-2021-44228");
+        assert_eq!(matches[0].details["severity"], "critical");
+        assert!(matches[1..].iter().all(|m| m.watch_id == log4j.id));
+        assert_eq!(
+            matches[1].matched,
+            "org.apache.logging.log4j:log4j-core@2.14.1"
+        );
+        assert_eq!(matches[1].details["source"], "scan");
+    }
+
+    #[test]
+    fn test_match_inventory_dedupes_per_entry() {
+        let watch = candidate(WatchKind::Advisory, "CVE-2021-44228", None);
+        let inventory = ArtifactInventory {
+            advisories: vec![
+                advisory("CVE-2021-44228", "log4j-core"),
+                advisory("CVE-2021-44228", "log4j-api"),
+            ],
+            components: vec![
+                component("lodash", Some("4.17.20"), "artifact"),
+                component("lodash", Some("4.17.20"), "scan"),
+            ],
+        };
+        assert_eq!(match_inventory(&[watch], &inventory).len(), 1);
+
+        let package = candidate(WatchKind::Package, "lodash", None);
+        let matches = match_inventory(&[package], &inventory);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].details["source"], "artifact");
+    }
+
+    #[test]
+    fn test_match_inventory_version_pinned_package() {
+        let watch = candidate(WatchKind::Package, "lodash", Some("4.17.20"));
+        let inventory = ArtifactInventory {
+            advisories: vec![],
+            components: vec![component("lodash", Some("4.17.21"), "artifact")],
+        };
+        assert!(match_inventory(&[watch], &inventory).is_empty());
+    }
+
+    #[test]
+    fn test_is_trigger() {
+        assert!(is_trigger("artifact.created"));
+        assert!(is_trigger("scan.completed"));
+        assert!(!is_trigger("scan.failed"));
+        assert!(!is_trigger("watchlist.matched"));
+    }
+}
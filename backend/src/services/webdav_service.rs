@@ -0,0 +1,300 @@
+//! WebDAV resource model over generic repositories.
+//!
+//! Files are the repository's artifacts, addressed by their path. Folders
+//! are implicit (some artifact lies under the path) or explicit, created by
+//! MKCOL and recorded in `webdav_collections` so they survive while empty.
+//! All paths here are relative to the repository root, without leading or
+//! trailing slashes.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::handlers::escape_like_literal;
+use crate::error::{AppError, Result};
+
+/// A file or folder as reported by PROPFIND.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct DavEntry {
+    pub path: String,
+    pub is_collection: bool,
+    pub size_bytes: Option<i64>,
+    pub content_type: Option<String>,
+    pub etag: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl DavEntry {
+    pub fn collection(path: impl Into<String>, created_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            path: path.into(),
+            is_collection: true,
+            size_bytes: None,
+            content_type: None,
+            etag: None,
+            created_at,
+            last_modified: created_at,
+        }
+    }
+
+    /// Last path segment; empty for the repository root.
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or_default()
+    }
+}
+
+/// Strip leading and trailing slashes from a request path.
+pub fn normalize_path(raw: &str) -> &str {
+    raw.trim_matches('/')
+}
+
+/// Parent folder of `path`, `""` being the repository root. `None` for the
+/// root itself.
+pub fn parent_path(path: &str) -> Option<&str> {
+    if path.is_empty() {
+        return None;
+    }
+    Some(
+        path.rsplit_once('/')
+            .map(|(parent, _)| parent)
+            .unwrap_or(""),
+    )
+}
+
+/// LIKE pattern matching every path strictly below `path`.
+fn descendants_pattern(path: &str) -> String {
+    if path.is_empty() {
+        "%".to_string()
+    } else {
+        format!("{}/%", escape_like_literal(path))
+    }
+}
+
+/// Prefix that children of `path` start with.
+fn child_prefix(path: &str) -> String {
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", path)
+    }
+}
+
+pub struct WebDavService {
+    db: PgPool,
+}
+
+impl WebDavService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Resolve `path` to a file or folder, or `None` when nothing exists
+    /// there. The empty path is the repository root.
+    pub async fn lookup(&self, repository_id: Uuid, path: &str) -> Result<Option<DavEntry>> {
+        if path.is_empty() {
+            return Ok(Some(DavEntry::collection("", None)));
+        }
+
+        let file: Option<DavEntry> = sqlx::query_as(
+            r#"
+            SELECT path, false AS is_collection, size_bytes, content_type,
+                   checksum_sha256::TEXT AS etag, created_at, updated_at AS last_modified
+            FROM artifacts
+            WHERE repository_id = $1 AND path = $2 AND is_deleted = false
+            "#,
+        )
+        .bind(repository_id)
+        .bind(path)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        if file.is_some() {
+            return Ok(file);
+        }
+
+        let (explicit, implicit): (Option<DateTime<Utc>>, bool) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT MIN(created_at) FROM webdav_collections
+                 WHERE repository_id = $1
+                   AND (path = $2 OR path LIKE $3 ESCAPE '\')),
+                EXISTS (SELECT 1 FROM artifacts
+                        WHERE repository_id = $1 AND is_deleted = false
+                          AND path LIKE $3 ESCAPE '\')
+            "#,
+        )
+        .bind(repository_id)
+        .bind(path)
+        .bind(descendants_pattern(path))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok((explicit.is_some() || implicit).then(|| DavEntry::collection(path, explicit)))
+    }
+
+    /// Direct children of the folder at `path`, folders first, each group
+    /// ordered by name.
+    pub async fn list_children(&self, repository_id: Uuid, path: &str) -> Result<Vec<DavEntry>> {
+        let prefix = child_prefix(path);
+        let pattern = descendants_pattern(path);
+        // Postgres substr() is 1-based and counts characters.
+        let offset = prefix.chars().count() as i32 + 1;
+
+        let folders: Vec<(String, Option<DateTime<Utc>>)> = sqlx::query_as(
+            r#"
+            SELECT name, MIN(created_at) FROM (
+                SELECT split_part(substr(path, $3), '/', 1) AS name,
+                       NULL::TIMESTAMPTZ AS created_at
+                FROM artifacts
+                WHERE repository_id = $1 AND is_deleted = false
+                  AND path LIKE $2 ESCAPE '\'
+                  AND strpos(substr(path, $3), '/') > 0
+                UNION ALL
+                SELECT split_part(substr(path, $3), '/', 1),
+                       CASE WHEN strpos(substr(path, $3), '/') = 0 THEN created_at END
+                FROM webdav_collections
+                WHERE repository_id = $1 AND path LIKE $2 ESCAPE '\'
+            ) children
+            GROUP BY name
+            ORDER BY name COLLATE "C"
+            "#,
+        )
+        .bind(repository_id)
+        .bind(&pattern)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let files: Vec<DavEntry> = sqlx::query_as(
+            r#"
+            SELECT path, false AS is_collection, size_bytes, content_type,
+                   checksum_sha256::TEXT AS etag, created_at, updated_at AS last_modified
+            FROM artifacts
+            WHERE repository_id = $1 AND is_deleted = false
+              AND path LIKE $2 ESCAPE '\'
+              AND strpos(substr(path, $3), '/') = 0
+            ORDER BY path COLLATE "C"
+            "#,
+        )
+        .bind(repository_id)
+        .bind(&pattern)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut entries: Vec<DavEntry> = folders
+            .into_iter()
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, created_at)| {
+                DavEntry::collection(format!("{}{}", prefix, name), created_at)
+            })
+            .collect();
+        entries.extend(files);
+        Ok(entries)
+    }
+
+    /// Record an explicit folder. Returns false if it was already recorded.
+    pub async fn create_collection(
+        &self,
+        repository_id: Uuid,
+        path: &str,
+        created_by: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webdav_collections (repository_id, path, created_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (repository_id, path) DO NOTHING
+            "#,
+        )
+        .bind(repository_id)
+        .bind(path)
+        .bind(created_by)
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Paths of every live artifact below the folder at `path`.
+    pub async fn artifact_paths_under(
+        &self,
+        repository_id: Uuid,
+        path: &str,
+    ) -> Result<Vec<String>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT path FROM artifacts
+            WHERE repository_id = $1 AND is_deleted = false
+              AND path LIKE $2 ESCAPE '\'
+            ORDER BY path COLLATE "C"
+            "#,
+        )
+        .bind(repository_id)
+        .bind(descendants_pattern(path))
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Forget the explicit folder at `path` and every explicit folder below it.
+    pub async fn delete_collections(&self, repository_id: Uuid, path: &str) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM webdav_collections
+            WHERE repository_id = $1
+              AND (path = $2 OR path LIKE $3 ESCAPE '\')
+            "#,
+        )
+        .bind(repository_id)
+        .bind(path)
+        .bind(descendants_pattern(path))
+        .execute(&self.db)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path(""), "");
+        assert_eq!(normalize_path("/"), "");
+        assert_eq!(normalize_path("a/b/"), "a/b");
+        assert_eq!(normalize_path("/a/b.txt"), "a/b.txt");
+    }
+
+    #[test]
+    fn test_parent_path() {
+        assert_eq!(parent_path(""), None);
+        assert_eq!(parent_path("a"), Some(""));
+        assert_eq!(parent_path("a/b/c.txt"), Some("a/b"));
+    }
+
+    #[test]
+    fn test_descendants_pattern_escapes() {
+        assert_eq!(descendants_pattern(""), "%");
+        assert_eq!(descendants_pattern("a_b"), "a\\_b/%");
+        assert_eq!(descendants_pattern("100%"), "100\\%/%");
+    }
+
+    #[test]
+    fn test_child_prefix() {
+        assert_eq!(child_prefix(""), "");
+        assert_eq!(child_prefix("a/b"), "a/b/");
+    }
+
+    #[test]
+    fn test_entry_name() {
+        assert_eq!(DavEntry::collection("", None).name(), "");
+        assert_eq!(DavEntry::collection("a/b", None).name(), "b");
+    }
+}
@@ -78,6 +78,7 @@ fn event_title(event: &str, details: &serde_json::Value) -> String {
         "build_started" => format!("Build started: {}", suffix),
         "build_completed" => format!("Build completed: {}", suffix),
         "build_failed" => format!("Build failed: {}", suffix),
+        "watchlist_matched" => format!("Watchlist match: {}", suffix),
         "test" => "Test webhook delivery".to_string(),
         _ => format!("Event: {}", event),
     }
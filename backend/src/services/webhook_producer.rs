@@ -51,6 +51,7 @@ pub fn map_event_type(event_type: &str) -> Option<&'static str> {
         "age_gate.queued" => Some("age_gate_queued"),
         "age_gate.approved" => Some("age_gate_approved"),
         "age_gate.rejected" => Some("age_gate_rejected"),
+        "watchlist.matched" => Some("watchlist_matched"),
         _ => None,
     }
 }
@@ -417,6 +418,7 @@ mod tests {
                 WebhookEvent::AgeGateQueued => ("age_gate.queued", "age_gate_queued"),
                 WebhookEvent::AgeGateApproved => ("age_gate.approved", "age_gate_approved"),
                 WebhookEvent::AgeGateRejected => ("age_gate.rejected", "age_gate_rejected"),
+                WebhookEvent::WatchlistMatched => ("watchlist.matched", "watchlist_matched"),
             }
         }
 
@@ -437,6 +439,7 @@ mod tests {
             WebhookEvent::AgeGateQueued,
             WebhookEvent::AgeGateApproved,
             WebhookEvent::AgeGateRejected,
+            WebhookEvent::WatchlistMatched,
         ];
 
         for variant in &all_variants {
@@ -0,0 +1,183 @@
+//! WORM retention policies for compliance repositories.
+//!
+//! A repository with a row in `repository_worm_policies` has every object
+//! written to it locked at the storage provider for `retention_days` (see
+//! `storage::retention`). The lock is enforced by the provider, so neither a
+//! compromised instance nor an administrator can delete a release artifact
+//! before it expires. Policies can be created and extended, never shortened
+//! or removed.
+//!
+//! The storage registry holds the active policies in memory; [`refresh_registry`]
+//! reloads them at startup, after every change and periodically from the
+//! scheduler so other replicas pick up changes.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::repository::{Repository, RepositoryType};
+use crate::storage::{StorageLocation, StorageRegistry};
+
+/// Longest retention a policy may ask for (100 years).
+pub const MAX_RETENTION_DAYS: u32 = 36_500;
+
+/// The WORM policy of a repository.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct WormPolicy {
+    pub repository_id: Uuid,
+    pub retention_days: i32,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Check a requested retention against the current one, if any.
+pub fn validate_retention(requested: u32, current: Option<i32>) -> Result<()> {
+    if requested == 0 || requested > MAX_RETENTION_DAYS {
+        return Err(AppError::Validation(format!(
+            "retention_days must be between 1 and {}",
+            MAX_RETENTION_DAYS
+        )));
+    }
+    if let Some(current) = current {
+        if (requested as i64) < current as i64 {
+            return Err(AppError::Validation(format!(
+                "WORM retention can only be extended (currently {} days)",
+                current
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct PolicyLocation {
+    storage_backend: String,
+    storage_path: String,
+    retention_days: i32,
+}
+
+/// Reload the registry's retention policies from the database.
+pub async fn refresh_registry(db: &PgPool, registry: &StorageRegistry) -> Result<usize> {
+    let rows = sqlx::query_as::<_, PolicyLocation>(
+        r#"
+        SELECT r.storage_backend, r.storage_path, w.retention_days
+        FROM repository_worm_policies w
+        JOIN repositories r ON r.id = w.repository_id
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+    let count = rows.len();
+    registry.set_retention_policies(
+        rows.into_iter()
+            .map(|row| {
+                (
+                    StorageLocation {
+                        backend: row.storage_backend,
+                        path: row.storage_path,
+                    },
+                    row.retention_days as u32,
+                )
+            })
+            .collect(),
+    );
+    Ok(count)
+}
+
+pub struct WormService {
+    db: PgPool,
+}
+
+impl WormService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn get(&self, repository_id: Uuid) -> Result<Option<WormPolicy>> {
+        let policy = sqlx::query_as::<_, WormPolicy>(
+            "SELECT * FROM repository_worm_policies WHERE repository_id = $1",
+        )
+        .bind(repository_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(policy)
+    }
+
+    /// Create or extend the WORM policy of `repo`.
+    ///
+    /// Only hosted (local and staging) repositories hold release artifacts,
+    /// and the repository's storage backend must be able to lock objects.
+    /// Objects written before the policy existed are not locked
+    /// retroactively.
+    pub async fn set(
+        &self,
+        repo: &Repository,
+        registry: &StorageRegistry,
+        retention_days: u32,
+        actor: Option<Uuid>,
+    ) -> Result<WormPolicy> {
+        if !matches!(
+            repo.repo_type,
+            RepositoryType::Local | RepositoryType::Staging
+        ) {
+            return Err(AppError::Validation(
+                "WORM retention is only available on local and staging repositories".to_string(),
+            ));
+        }
+        let current = self.get(repo.id).await?;
+        validate_retention(retention_days, current.as_ref().map(|p| p.retention_days))?;
+        if !registry
+            .raw_backend_for(&repo.storage_location())?
+            .supports_retention()
+        {
+            return Err(AppError::Validation(format!(
+                "Storage backend '{}' does not support object retention; enable \
+                 S3_OBJECT_LOCK or AZURE_IMMUTABLE_STORAGE on a bucket or container \
+                 configured for it",
+                repo.storage_backend
+            )));
+        }
+
+        let policy = sqlx::query_as::<_, WormPolicy>(
+            r#"
+            INSERT INTO repository_worm_policies (repository_id, retention_days, created_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (repository_id) DO UPDATE
+            SET retention_days = EXCLUDED.retention_days, updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(repo.id)
+        .bind(retention_days as i32)
+        .bind(actor)
+        .fetch_one(&self.db)
+        .await?;
+
+        refresh_registry(&self.db, registry).await?;
+        Ok(policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_retention_bounds() {
+        assert!(validate_retention(1, None).is_ok());
+        assert!(validate_retention(MAX_RETENTION_DAYS, None).is_ok());
+        assert!(validate_retention(0, None).is_err());
+        assert!(validate_retention(MAX_RETENTION_DAYS + 1, None).is_err());
+    }
+
+    #[test]
+    fn test_validate_retention_extend_only() {
+        assert!(validate_retention(365, Some(365)).is_ok());
+        assert!(validate_retention(730, Some(365)).is_ok());
+        assert!(validate_retention(30, Some(365)).is_err());
+    }
+}
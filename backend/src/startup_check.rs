@@ -0,0 +1,791 @@
+//! Startup configuration validation.
+//!
+//! Verifies that the things the server depends on actually work before it
+//! starts taking traffic: database connectivity, a storage test write,
+//! scanner availability, SSO metadata URLs and webhook reachability. Each
+//! check yields a [`CheckResult`]; together they form a [`ConfigReport`].
+//!
+//! The same checks back two entry points:
+//! * `artifact-keeper --check-config [--json]` runs them, prints the report
+//!   and exits non-zero when any check failed, without starting the server.
+//! * Every server start runs them once the database and storage are up. With
+//!   `STARTUP_VALIDATION=enforce` (the default) a failed check aborts the
+//!   start; `warn` only logs the report and `off` skips it.
+//!
+//! Webhook endpoints belong to third parties and go down independently of
+//! this server, so an unreachable one is only a warning.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::storage::StorageBackend;
+
+/// Upper bound on any single network probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Most webhooks probed in one run; the rest are reported as skipped.
+const MAX_WEBHOOK_PROBES: i64 = 50;
+/// Webhooks probed concurrently.
+const WEBHOOK_PROBE_CONCURRENCY: usize = 8;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+    Skipped,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Ok => " OK ",
+            Self::Warning => "WARN",
+            Self::Error => "FAIL",
+            Self::Skipped => "SKIP",
+        }
+    }
+}
+
+/// One line of the report.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    /// What was checked, e.g. `storage` or `webhook:build-notify`.
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to change when the check did not pass.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn ok(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Ok, detail, None)
+    }
+
+    fn skipped(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Skipped, detail, None)
+    }
+
+    fn error(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Error, detail, Some(hint.into()))
+    }
+
+    fn warning(
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self::new(name, CheckStatus::Warning, detail, Some(hint.into()))
+    }
+
+    fn new(
+        name: impl Into<String>,
+        status: CheckStatus,
+        detail: impl Into<String>,
+        hint: Option<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint,
+        }
+    }
+}
+
+/// Results of a validation run, in check order.
+#[derive(Debug, Default, Serialize)]
+pub struct ConfigReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl ConfigReport {
+    fn count(&self, status: CheckStatus) -> usize {
+        self.results.iter().filter(|r| r.status == status).count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(CheckStatus::Error) > 0
+    }
+
+    /// Human-readable report, one check per line with hints underneath.
+    pub fn render_text(&self) -> String {
+        let width = self.results.iter().map(|r| r.name.len()).max().unwrap_or(0);
+        let mut out = String::from("Artifact Keeper configuration check\n\n");
+        for result in &self.results {
+            out.push_str(&format!(
+                "  [{}] {:width$}  {}\n",
+                result.status.label(),
+                result.name,
+                result.detail,
+                width = width
+            ));
+            if let Some(hint) = &result.hint {
+                out.push_str(&format!(
+                    "         {:width$}  hint: {}\n",
+                    "",
+                    hint,
+                    width = width
+                ));
+            }
+        }
+        out.push_str(&format!(
+            "\n{} error(s), {} warning(s), {} passed\n",
+            self.count(CheckStatus::Error),
+            self.count(CheckStatus::Warning),
+            self.count(CheckStatus::Ok),
+        ));
+        out
+    }
+
+    /// Log every result at a level matching its status.
+    pub fn log(&self) {
+        for r in &self.results {
+            let hint = r.hint.as_deref().unwrap_or("");
+            match r.status {
+                CheckStatus::Ok => tracing::info!(check = %r.name, "{}", r.detail),
+                CheckStatus::Skipped => tracing::debug!(check = %r.name, "{}", r.detail),
+                CheckStatus::Warning => {
+                    tracing::warn!(check = %r.name, hint, "{}", r.detail)
+                }
+                CheckStatus::Error => {
+                    tracing::error!(check = %r.name, hint, "{}", r.detail)
+                }
+            }
+        }
+    }
+
+    /// One-line summary of the failed checks, for the startup error.
+    pub fn failure_summary(&self) -> String {
+        let failed: Vec<&str> = self
+            .results
+            .iter()
+            .filter(|r| r.status == CheckStatus::Error)
+            .map(|r| r.name.as_str())
+            .collect();
+        format!(
+            "startup validation failed: {} (run with --check-config for the full report, \
+             or set STARTUP_VALIDATION=warn to start anyway)",
+            failed.join(", ")
+        )
+    }
+}
+
+/// What a server start does with the report (`STARTUP_VALIDATION`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupValidation {
+    /// Abort the start when a check failed.
+    Enforce,
+    /// Log the report and start regardless.
+    Warn,
+    /// Do not run the checks.
+    Off,
+}
+
+impl StartupValidation {
+    pub fn from_env() -> Result<Self> {
+        Self::parse(std::env::var("STARTUP_VALIDATION").ok().as_deref())
+    }
+
+    fn parse(value: Option<&str>) -> Result<Self> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("enforce") => Ok(Self::Enforce),
+            Some("warn") => Ok(Self::Warn),
+            Some("off") => Ok(Self::Off),
+            Some(other) => Err(AppError::Config(format!(
+                "STARTUP_VALIDATION must be one of enforce, warn, off (got '{}')",
+                other
+            ))),
+        }
+    }
+}
+
+/// Full `--check-config` run: loads the configuration, connects to the
+/// database and opens the primary storage backend itself, then runs every
+/// check.
+pub async fn check_config() -> ConfigReport {
+    let mut report = ConfigReport::default();
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            report.results.push(CheckResult::error(
+                "configuration",
+                e.to_string(),
+                "Fix the environment variable named above; nothing else can be checked \
+                 until the configuration loads",
+            ));
+            return report;
+        }
+    };
+    report
+        .results
+        .push(CheckResult::ok("configuration", "environment parsed"));
+    let config = &config;
+
+    let db = match tokio::time::timeout(PROBE_TIMEOUT, crate::db::create_pool(config)).await {
+        Ok(Ok(pool)) => Some(pool),
+        Ok(Err(e)) => {
+            report.results.push(database_error(e.to_string()));
+            None
+        }
+        Err(_) => {
+            report.results.push(database_error(format!(
+                "connection timed out after {}s",
+                PROBE_TIMEOUT.as_secs()
+            )));
+            None
+        }
+    };
+
+    let storage = match open_primary_storage(config).await {
+        Ok(storage) => Some(storage),
+        Err(e) => {
+            report.results.push(storage_error(
+                config,
+                format!(
+                    "could not initialize {} storage: {}",
+                    config.storage_backend, e
+                ),
+            ));
+            None
+        }
+    };
+
+    run_checks(&mut report, config, db.as_ref(), storage.as_deref()).await;
+    report
+}
+
+/// Startup pass against the already-initialized database and storage.
+pub async fn validate_startup(
+    config: &Config,
+    db: &PgPool,
+    storage: &dyn StorageBackend,
+) -> ConfigReport {
+    let mut report = ConfigReport::default();
+    run_checks(&mut report, config, Some(db), Some(storage)).await;
+    report
+}
+
+async fn run_checks(
+    report: &mut ConfigReport,
+    config: &Config,
+    db: Option<&PgPool>,
+    storage: Option<&dyn StorageBackend>,
+) {
+    if let Some(db) = db {
+        report.results.push(check_database(db).await);
+    }
+    if let Some(storage) = storage {
+        report.results.push(check_storage(config, storage).await);
+    }
+    report.results.extend(check_scanners(config).await);
+    report.results.extend(check_sso(config, db).await);
+    report.results.extend(check_webhooks(db).await);
+}
+
+fn database_error(detail: String) -> CheckResult {
+    CheckResult::error(
+        "database",
+        detail,
+        "Check DATABASE_URL (host, port, credentials, database name) and that \
+         PostgreSQL accepts connections from this host",
+    )
+}
+
+fn storage_error(config: &Config, detail: String) -> CheckResult {
+    let hint = match config.storage_backend.as_str() {
+        "s3" => {
+            "Check S3_BUCKET, S3_REGION, S3_ENDPOINT and the AWS credentials; the \
+                 principal needs PutObject, GetObject and DeleteObject on the bucket"
+        }
+        "azure" => {
+            "Check the AZURE_* container and credential settings; the identity \
+                    needs blob read, write and delete on the container"
+        }
+        "gcs" => {
+            "Check GCS_BUCKET and the service account credentials; the account \
+                  needs object create, get and delete on the bucket"
+        }
+        _ => "Check that STORAGE_PATH exists, is mounted and is writable by this process",
+    };
+    CheckResult::error("storage", detail, hint)
+}
+
+async fn check_database(db: &PgPool) -> CheckResult {
+    let version = sqlx::query_scalar::<_, String>("SHOW server_version").fetch_one(db);
+    match tokio::time::timeout(PROBE_TIMEOUT, version).await {
+        Ok(Ok(version)) => {
+            CheckResult::ok("database", format!("connected (PostgreSQL {})", version))
+        }
+        Ok(Err(e)) => database_error(format!("query failed: {}", e)),
+        Err(_) => database_error(format!(
+            "query timed out after {}s",
+            PROBE_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+/// Open the configured primary backend the way the server does, including
+/// sharding and client-side encryption.
+async fn open_primary_storage(config: &Config) -> Result<Arc<dyn StorageBackend>> {
+    use crate::storage::{azure, encryption, filesystem, gcs, s3, sharded};
+
+    let backend: Arc<dyn StorageBackend> = match config.storage_backend.as_str() {
+        "s3" => match sharded::s3_from_env().await? {
+            Some(shards) => Arc::new(shards),
+            None => Arc::new(s3::S3Backend::from_env().await?),
+        },
+        "azure" => match sharded::azure_from_env().await? {
+            Some(shards) => Arc::new(shards),
+            None => Arc::new(azure::AzureBackend::new(azure::AzureConfig::from_env()?).await?),
+        },
+        "gcs" => Arc::new(gcs::GcsBackend::new(gcs::GcsConfig::from_env()?).await?),
+        _ => Arc::new(filesystem::FilesystemStorage::new(&config.storage_path)),
+    };
+    Ok(match encryption::EnvelopeEncryption::from_env()? {
+        Some(enc) => Arc::new(encryption::EncryptedStorage::new(backend, enc)),
+        None => backend,
+    })
+}
+
+/// Write, read back and delete a probe object.
+async fn check_storage(config: &Config, storage: &dyn StorageBackend) -> CheckResult {
+    let key = format!(".config-check/{}", uuid::Uuid::new_v4());
+    let payload = Bytes::from_static(b"artifact-keeper config check");
+    let probe = async {
+        storage.put(&key, payload.clone()).await?;
+        let read = storage.get(&key).await;
+        let deleted = storage.delete(&key).await;
+        if read? != payload {
+            return Err(AppError::Storage(
+                "probe object read back with different content".to_string(),
+            ));
+        }
+        deleted
+    };
+    match tokio::time::timeout(PROBE_TIMEOUT, probe).await {
+        Ok(Ok(())) => CheckResult::ok(
+            "storage",
+            format!(
+                "{} test write, read and delete succeeded",
+                config.storage_backend
+            ),
+        ),
+        Ok(Err(e)) => storage_error(
+            config,
+            format!("{} test write failed: {}", config.storage_backend, e),
+        ),
+        Err(_) => storage_error(
+            config,
+            format!(
+                "{} test write timed out after {}s",
+                config.storage_backend,
+                PROBE_TIMEOUT.as_secs()
+            ),
+        ),
+    }
+}
+
+/// Outcome of a GET against a configured endpoint.
+enum Probe {
+    Response(reqwest::StatusCode, String),
+    Unreachable(String),
+}
+
+async fn probe_get(client: &reqwest::Client, url: &str, read_body: bool) -> Probe {
+    match client.get(url).timeout(PROBE_TIMEOUT).send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            let body = if read_body {
+                resp.text().await.unwrap_or_default()
+            } else {
+                String::new()
+            };
+            Probe::Response(status, body)
+        }
+        Err(e) => Probe::Unreachable(e.to_string()),
+    }
+}
+
+/// A configured scanner service must answer its health endpoint with 2xx.
+async fn check_scanner_endpoint(
+    client: &reqwest::Client,
+    name: &str,
+    env_var: &str,
+    base_url: &str,
+    health_path: &str,
+) -> CheckResult {
+    let url = format!("{}{}", base_url.trim_end_matches('/'), health_path);
+    let hint = format!(
+        "Check {} and that the service is running and reachable from this host",
+        env_var
+    );
+    match probe_get(client, &url, false).await {
+        Probe::Response(status, _) if status.is_success() => {
+            CheckResult::ok(name, format!("{} answered {}", url, status))
+        }
+        Probe::Response(status, _) => {
+            CheckResult::error(name, format!("{} answered {}", url, status), hint)
+        }
+        Probe::Unreachable(e) => {
+            CheckResult::error(name, format!("{} unreachable: {}", url, e), hint)
+        }
+    }
+}
+
+async fn check_scanners(config: &Config) -> Vec<CheckResult> {
+    let client = crate::services::http_client::internal_service_client_builder()
+        .build()
+        .unwrap_or_default();
+    let mut results = Vec::new();
+
+    // The adapter takes precedence over the legacy trivy server.
+    match (&config.trivy_adapter_url, &config.trivy_url) {
+        (Some(url), _) => results.push(
+            check_scanner_endpoint(
+                &client,
+                "scanner:trivy-adapter",
+                "TRIVY_ADAPTER_URL",
+                url,
+                "/probe/ready",
+            )
+            .await,
+        ),
+        (None, Some(url)) => results.push(
+            check_scanner_endpoint(&client, "scanner:trivy", "TRIVY_URL", url, "/healthz").await,
+        ),
+        (None, None) => results.push(CheckResult::skipped(
+            "scanner:trivy",
+            "neither TRIVY_ADAPTER_URL nor TRIVY_URL is set",
+        )),
+    }
+    if let Some(url) = &config.openscap_url {
+        results.push(
+            check_scanner_endpoint(&client, "scanner:openscap", "OPENSCAP_URL", url, "/health")
+                .await,
+        );
+    }
+    if let Some(url) = crate::services::health_monitor_service::dependency_track_probe_url(config) {
+        results.push(
+            check_scanner_endpoint(
+                &client,
+                "scanner:dependency-track",
+                "DEPENDENCY_TRACK_URL",
+                url,
+                "/api/version",
+            )
+            .await,
+        );
+    }
+
+    // Grype runs in-process for every scan; a missing binary only degrades
+    // coverage, so it is a warning.
+    let grype = tokio::process::Command::new("grype")
+        .arg("version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+    results.push(match tokio::time::timeout(PROBE_TIMEOUT, grype).await {
+        Ok(Ok(status)) if status.success() => CheckResult::ok("scanner:grype", "grype available"),
+        Ok(Ok(status)) => CheckResult::warning(
+            "scanner:grype",
+            format!("`grype version` exited with {}", status),
+            "Reinstall grype or use the prebuilt backend image, which bundles it",
+        ),
+        Ok(Err(e)) => CheckResult::warning(
+            "scanner:grype",
+            format!("grype could not be run: {}", e),
+            "Install grype on PATH or use the prebuilt backend image, which bundles it",
+        ),
+        Err(_) => CheckResult::warning(
+            "scanner:grype",
+            "`grype version` timed out",
+            "Check that grype runs on this host",
+        ),
+    });
+    results
+}
+
+/// Whether `body` is an OIDC discovery document.
+fn is_discovery_document(body: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(body)
+        .map(|doc| {
+            ["issuer", "authorization_endpoint", "jwks_uri"]
+                .iter()
+                .all(|field| doc.get(field).and_then(|v| v.as_str()).is_some())
+        })
+        .unwrap_or(false)
+}
+
+/// Whether `body` looks like SAML IdP metadata.
+fn is_saml_metadata(body: &str) -> bool {
+    body.contains("EntityDescriptor") && body.contains("IDPSSODescriptor")
+}
+
+async fn check_metadata_url(
+    client: &reqwest::Client,
+    name: String,
+    url: &str,
+    valid: fn(&str) -> bool,
+    kind: &str,
+    hint: &str,
+) -> CheckResult {
+    match probe_get(client, url, true).await {
+        Probe::Response(status, body) if status.is_success() && valid(&body) => {
+            CheckResult::ok(name, format!("{} serves valid {}", url, kind))
+        }
+        Probe::Response(status, _) if status.is_success() => CheckResult::error(
+            name,
+            format!("{} answered {} but is not {}", url, status, kind),
+            hint,
+        ),
+        Probe::Response(status, _) => {
+            CheckResult::error(name, format!("{} answered {}", url, status), hint)
+        }
+        Probe::Unreachable(e) => {
+            CheckResult::error(name, format!("{} unreachable: {}", url, e), hint)
+        }
+    }
+}
+
+fn oidc_discovery_url(issuer: &str) -> String {
+    format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    )
+}
+
+async fn check_sso(config: &Config, db: Option<&PgPool>) -> Vec<CheckResult> {
+    let client = crate::services::http_client::sso_client();
+    let mut results = Vec::new();
+
+    if let Some(issuer) = &config.oidc_issuer {
+        results.push(
+            check_metadata_url(
+                &client,
+                "sso:oidc(env)".to_string(),
+                &oidc_discovery_url(issuer),
+                is_discovery_document,
+                "an OIDC discovery document",
+                "Check OIDC_ISSUER; it must be the issuer URL without the \
+                 /.well-known suffix",
+            )
+            .await,
+        );
+    }
+    if let Ok(url) = std::env::var("SAML_IDP_METADATA_URL") {
+        results.push(
+            check_metadata_url(
+                &client,
+                "sso:saml(env)".to_string(),
+                &url,
+                is_saml_metadata,
+                "SAML IdP metadata",
+                "Check SAML_IDP_METADATA_URL; it must point at the IdP's metadata XML",
+            )
+            .await,
+        );
+    }
+
+    let Some(db) = db else {
+        return results;
+    };
+    let providers = sqlx::query_as::<_, (String, String)>(
+        "SELECT name, issuer_url FROM oidc_configs WHERE is_enabled = true ORDER BY name",
+    )
+    .fetch_all(db)
+    .await;
+    match providers {
+        Ok(providers) => {
+            for (name, issuer) in providers {
+                results.push(
+                    check_metadata_url(
+                        &client,
+                        format!("sso:oidc:{}", name),
+                        &oidc_discovery_url(&issuer),
+                        is_discovery_document,
+                        "an OIDC discovery document",
+                        "Fix the issuer URL of this provider under SSO settings, or disable it",
+                    )
+                    .await,
+                );
+            }
+        }
+        Err(e) => results.push(CheckResult::warning(
+            "sso:oidc",
+            format!("could not list OIDC providers: {}", e),
+            "Run the database migrations",
+        )),
+    }
+    results
+}
+
+async fn check_webhooks(db: Option<&PgPool>) -> Vec<CheckResult> {
+    let Some(db) = db else {
+        return vec![CheckResult::skipped("webhooks", "database unavailable")];
+    };
+    let webhooks = sqlx::query_as::<_, (String, String)>(
+        "SELECT name, url FROM webhooks WHERE is_enabled = true ORDER BY name LIMIT $1",
+    )
+    .bind(MAX_WEBHOOK_PROBES + 1)
+    .fetch_all(db)
+    .await;
+    let mut webhooks = match webhooks {
+        Ok(webhooks) => webhooks,
+        Err(e) => {
+            return vec![CheckResult::warning(
+                "webhooks",
+                format!("could not list webhooks: {}", e),
+                "Run the database migrations",
+            )]
+        }
+    };
+    if webhooks.is_empty() {
+        return vec![CheckResult::skipped("webhooks", "no enabled webhooks")];
+    }
+    let truncated = webhooks.len() as i64 > MAX_WEBHOOK_PROBES;
+    webhooks.truncate(MAX_WEBHOOK_PROBES as usize);
+
+    let client = crate::services::http_client::webhook_client();
+    // Any HTTP answer proves reachability; receivers commonly reject GET.
+    let mut results: Vec<CheckResult> = futures::stream::iter(webhooks)
+        .map(|(name, url)| {
+            let client = client.clone();
+            async move {
+                let name = format!("webhook:{}", name);
+                match probe_get(&client, &url, false).await {
+                    Probe::Response(status, _) => {
+                        CheckResult::ok(name, format!("{} reachable ({})", url, status))
+                    }
+                    Probe::Unreachable(e) => CheckResult::warning(
+                        name,
+                        format!("{} unreachable: {}", url, e),
+                        "Deliveries will be retried; fix or disable the webhook if the \
+                         endpoint has moved",
+                    ),
+                }
+            }
+        })
+        .buffered(WEBHOOK_PROBE_CONCURRENCY)
+        .collect()
+        .await;
+    if truncated {
+        results.push(CheckResult::skipped(
+            "webhooks",
+            format!(
+                "only the first {} enabled webhooks were probed",
+                MAX_WEBHOOK_PROBES
+            ),
+        ));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_validation_parse() {
+        assert_eq!(
+            StartupValidation::parse(None).unwrap(),
+            StartupValidation::Enforce
+        );
+        assert_eq!(
+            StartupValidation::parse(Some(" WARN ")).unwrap(),
+            StartupValidation::Warn
+        );
+        assert_eq!(
+            StartupValidation::parse(Some("off")).unwrap(),
+            StartupValidation::Off
+        );
+        assert!(StartupValidation::parse(Some("strict")).is_err());
+    }
+
+    #[test]
+    fn test_report_errors_and_summary() {
+        let mut report = ConfigReport::default();
+        report
+            .results
+            .push(CheckResult::ok("database", "connected"));
+        report
+            .results
+            .push(CheckResult::warning("webhook:ci", "down", "fix it"));
+        assert!(!report.has_errors());
+
+        report
+            .results
+            .push(CheckResult::error("storage", "write failed", "check it"));
+        report.results.push(CheckResult::error(
+            "scanner:trivy",
+            "unreachable",
+            "check it",
+        ));
+        assert!(report.has_errors());
+        let summary = report.failure_summary();
+        assert!(summary.contains("storage, scanner:trivy"));
+        assert!(!summary.contains("webhook:ci"));
+    }
+
+    #[test]
+    fn test_render_text_aligns_and_includes_hints() {
+        let report = ConfigReport {
+            results: vec![
+                CheckResult::ok("database", "connected"),
+                CheckResult::error("storage", "write failed", "check STORAGE_PATH"),
+                CheckResult::skipped("webhooks", "no enabled webhooks"),
+            ],
+        };
+        let text = report.render_text();
+        assert!(text.contains("  [ OK ] database  connected\n"));
+        assert!(text.contains("  [FAIL] storage   write failed\n"));
+        assert!(text.contains("hint: check STORAGE_PATH\n"));
+        assert!(text.contains("  [SKIP] webhooks  no enabled webhooks\n"));
+        assert!(text.ends_with("1 error(s), 0 warning(s), 1 passed\n"));
+    }
+
+    #[test]
+    fn test_report_json_shape() {
+        let report = ConfigReport {
+            results: vec![CheckResult::ok("database", "connected")],
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["results"][0]["status"], "ok");
+        assert!(json["results"][0].get("hint").is_none());
+    }
+
+    #[test]
+    fn test_is_discovery_document() {
+        assert!(is_discovery_document(
+            r#"{"issuer":"https://idp","authorization_endpoint":"https://idp/auth","jwks_uri":"https://idp/jwks"}"#
+        ));
+        assert!(!is_discovery_document(r#"{"issuer":"https://idp"}"#));
+        assert!(!is_discovery_document("<html>login</html>"));
+    }
+
+    #[test]
+    fn test_is_saml_metadata() {
+        assert!(is_saml_metadata(
+            r#"<md:EntityDescriptor entityID="x"><md:IDPSSODescriptor/></md:EntityDescriptor>"#
+        ));
+        assert!(!is_saml_metadata("<html>login</html>"));
+    }
+
+    #[test]
+    fn test_oidc_discovery_url_trims_trailing_slash() {
+        assert_eq!(
+            oidc_discovery_url("https://idp.example.com/realms/main/"),
+            "https://idp.example.com/realms/main/.well-known/openid-configuration"
+        );
+    }
+}
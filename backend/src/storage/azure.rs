@@ -2,12 +2,14 @@
 //!
 //! Supports two authentication modes:
 //!
-//! **Shared Key** (access key): Signs requests with HMAC-SHA256. Supports SAS
-//! URL redirect downloads.
+//! **Shared Key** (access key): Signs requests with HMAC-SHA256. Redirect
+//! downloads use service SAS URLs signed with the account key.
 //!
 //! **Azure RBAC** (OAuth2 bearer token): Uses service principal credentials or
 //! managed identity to acquire tokens from Azure AD. Requires the identity to
 //! have the `Storage Blob Data Contributor` role on the storage account.
+//! Redirect downloads use user delegation SAS URLs, signed with a key from
+//! the Get User Delegation Key API.
 //!
 //! ## Configuration
 //!
@@ -27,19 +29,41 @@
 //! # Option 3: Managed Identity (RBAC, no env vars needed on Azure)
 //! # Optionally set AZURE_CLIENT_ID for user-assigned managed identity
 //!
-//! # SAS redirect downloads (Shared Key only)
+//! # SAS redirect downloads
 //! AZURE_REDIRECT_DOWNLOADS=true
 //! AZURE_SAS_EXPIRY=3600  # seconds, default 1 hour
 //!
+//! # Block uploads (Put Block / Put Block List)
+//! AZURE_UPLOAD_SINGLE_PUT_MAX_MB=64  # larger bodies are uploaded in blocks
+//! AZURE_UPLOAD_BLOCK_SIZE_MB=8
+//! AZURE_UPLOAD_CONCURRENCY=4         # Put Block requests in flight per upload
+//!
+//! # Compliance (WORM) repositories: the container has version-level
+//! # immutability support, so blobs can carry a locked immutability policy
+//! AZURE_IMMUTABLE_STORAGE=true
+//!
+//! # Blob snapshots: delete a blob's snapshots together with it. Without
+//! # this, deleting a blob that has snapshots fails with 409 SnapshotsPresent
+//! AZURE_DELETE_SNAPSHOTS=true
+//!
 //! # For Artifactory migration:
 //! STORAGE_PATH_FORMAT=migration  # native, artifactory, or migration
 //! ```
+//!
+//! ## Soft delete
+//!
+//! With blob soft delete enabled on the account, `delete()` (and therefore
+//! storage GC) leaves the blob recoverable for the account's retention
+//! period, and `exists()` reports it absent. The backend implements
+//! [`SoftDeleteRecovery`] so admins can list such blobs and undelete them
+//! through `/api/v1/admin/storage-recovery`.
 
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use bytes::{Bytes, BytesMut};
 use chrono::{Duration as ChronoDuration, Utc};
-use futures::stream::BoxStream;
+use futures::future::BoxFuture;
+use futures::stream::{BoxStream, FuturesUnordered};
 use futures::StreamExt;
 use hmac::{Hmac, Mac};
 use sha2::{Digest, Sha256};
@@ -50,7 +74,8 @@ use uuid::Uuid;
 
 use crate::error::{AppError, Result};
 use crate::storage::{
-    PresignedUrl, PresignedUrlSource, PutStreamResult, StorageBackend, StoragePathFormat,
+    PresignedUrl, PresignedUrlSource, PutStreamResult, SoftDeleteRecovery, SoftDeletedObject,
+    SoftDeletedPage, StorageBackend, StoragePathFormat,
 };
 
 type HmacSha256 = Hmac<Sha256>;
@@ -86,6 +111,67 @@ pub struct AzureConfig {
     pub sas_expiry: Duration,
     /// Storage path format (native, artifactory, or migration)
     pub path_format: StoragePathFormat,
+    /// Block upload tuning for large blobs
+    pub upload: AzureUploadConfig,
+    /// The container supports version-level immutability, so blobs can be
+    /// given a locked immutability policy.
+    pub immutable_storage: bool,
+    /// Delete a blob's snapshots together with the blob
+    /// (`x-ms-delete-snapshots: include`).
+    pub delete_snapshots: bool,
+}
+
+/// How large blobs are split into Put Block requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AzureUploadConfig {
+    /// Bodies up to this size are written with a single Put Blob; larger
+    /// ones are staged as blocks and committed with Put Block List.
+    pub single_put_max: usize,
+    /// Size of each staged block (the last block may be shorter).
+    pub block_size: usize,
+    /// Put Block requests in flight per upload.
+    pub concurrency: usize,
+}
+
+impl Default for AzureUploadConfig {
+    fn default() -> Self {
+        Self {
+            single_put_max: AZURE_DEFAULT_SINGLE_PUT_MAX,
+            block_size: AZURE_DEFAULT_BLOCK_SIZE,
+            concurrency: AZURE_DEFAULT_UPLOAD_CONCURRENCY,
+        }
+    }
+}
+
+impl AzureUploadConfig {
+    /// Read `AZURE_UPLOAD_*` overrides, falling back to the defaults.
+    pub fn from_env() -> Self {
+        let mb = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .map(|v| v.saturating_mul(1024 * 1024))
+        };
+        let defaults = Self::default();
+        Self {
+            single_put_max: mb("AZURE_UPLOAD_SINGLE_PUT_MAX_MB").unwrap_or(defaults.single_put_max),
+            block_size: mb("AZURE_UPLOAD_BLOCK_SIZE_MB").unwrap_or(defaults.block_size),
+            concurrency: std::env::var("AZURE_UPLOAD_CONCURRENCY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.concurrency),
+        }
+        .normalized()
+    }
+
+    /// Clamp values into the ranges Azure accepts.
+    pub fn normalized(self) -> Self {
+        Self {
+            single_put_max: self.single_put_max.min(AZURE_MAX_SINGLE_PUT_SIZE),
+            block_size: self.block_size.clamp(1, AZURE_MAX_BLOCK_SIZE),
+            concurrency: self.concurrency.clamp(1, AZURE_MAX_UPLOAD_CONCURRENCY),
+        }
+    }
 }
 
 impl AzureConfig {
@@ -116,6 +202,14 @@ impl AzureConfig {
 
         let path_format = StoragePathFormat::from_env();
 
+        let immutable_storage = std::env::var("AZURE_IMMUTABLE_STORAGE")
+            .map(|v| v.to_lowercase() == "true" || v == "1")
+            .unwrap_or(false);
+
+        let delete_snapshots = std::env::var("AZURE_DELETE_SNAPSHOTS")
+            .map(|v| v.to_lowercase() == "true" || v == "1")
+            .unwrap_or(false);
+
         Ok(Self {
             account_name,
             container_name,
@@ -124,6 +218,9 @@ impl AzureConfig {
             redirect_downloads,
             sas_expiry,
             path_format,
+            upload: AzureUploadConfig::from_env(),
+            immutable_storage,
+            delete_snapshots,
         })
     }
 
@@ -138,6 +235,18 @@ impl AzureConfig {
         self.sas_expiry = expiry;
         self
     }
+
+    /// Builder: set block upload tuning
+    pub fn with_upload(mut self, upload: AzureUploadConfig) -> Self {
+        self.upload = upload.normalized();
+        self
+    }
+
+    /// Builder: delete snapshots together with their blob
+    pub fn with_delete_snapshots(mut self, enabled: bool) -> Self {
+        self.delete_snapshots = enabled;
+        self
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -163,6 +272,31 @@ pub(crate) struct TokenCredentialProvider {
     client: reqwest::Client,
     credential: TokenCredentialSource,
     cache: RwLock<Option<CachedToken>>,
+    /// User delegation key for signing SAS URLs in RBAC mode.
+    delegation_key: RwLock<Option<CachedDelegationKey>>,
+}
+
+/// A key returned by the Get User Delegation Key API. The signed fields are
+/// echoed verbatim into every user delegation SAS signed with it.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct UserDelegationKey {
+    signed_oid: String,
+    signed_tid: String,
+    signed_start: String,
+    signed_expiry: String,
+    signed_service: String,
+    signed_version: String,
+    value: String,
+}
+
+#[derive(Debug, Clone)]
+struct CachedDelegationKey {
+    key: UserDelegationKey,
+    /// Base64-decoded `value`, the HMAC key for SAS signatures.
+    decoded_value: Vec<u8>,
+    /// The key's expiry; no SAS signed with it may outlive this.
+    expires_at: chrono::DateTime<Utc>,
 }
 
 #[derive(Debug, Clone)]
@@ -183,7 +317,12 @@ const STORAGE_SCOPE: &str = "https://storage.azure.com/.default";
 /// Refresh tokens 5 minutes before expiry.
 const TOKEN_REFRESH_MARGIN_SECS: i64 = 300;
 
-const AZURE_BLOCK_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+const AZURE_DEFAULT_SINGLE_PUT_MAX: usize = 64 * 1024 * 1024;
+const AZURE_DEFAULT_BLOCK_SIZE: usize = 8 * 1024 * 1024;
+const AZURE_DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+const AZURE_MAX_UPLOAD_CONCURRENCY: usize = 64;
+const AZURE_MAX_SINGLE_PUT_SIZE: usize = 5_000 * 1024 * 1024;
+const AZURE_MAX_BLOCK_SIZE: usize = 4_000 * 1024 * 1024;
 const AZURE_MAX_BLOCKS: usize = 50_000;
 const AZURE_BLOCK_WARNING_THRESHOLD: usize = 40_000;
 const AZURE_PUT_BLOB_FROM_URL_MAX_SIZE: u64 = 5_000 * 1024 * 1024;
@@ -193,6 +332,13 @@ const AZURE_COPY_SOURCE_URL_MAX_LEN: usize = 2 * 1024;
 /// skew between this host and Azure storage (Azure's documented allowance).
 const SAS_CLOCK_SKEW_ALLOWANCE_MINUTES: i64 = 15;
 
+/// Lifetime requested for a user delegation key. A key is reused for every
+/// SAS that expires before it does, so one request covers a day of redirects.
+const USER_DELEGATION_KEY_LIFETIME_HOURS: i64 = 24;
+
+/// Azure rejects user delegation keys valid for longer than seven days.
+const USER_DELEGATION_KEY_MAX_LIFETIME_DAYS: i64 = 7;
+
 impl TokenCredentialProvider {
     /// Build a provider from environment variables.
     fn from_env(client: &reqwest::Client) -> Result<Self> {
@@ -225,6 +371,7 @@ impl TokenCredentialProvider {
             client: client.clone(),
             credential,
             cache: RwLock::new(None),
+            delegation_key: RwLock::new(None),
         })
     }
 
@@ -255,6 +402,118 @@ impl TokenCredentialProvider {
         Ok(access_token)
     }
 
+    /// Get a user delegation key that stays valid until at least
+    /// `valid_until`, requesting a new one from the account when needed.
+    async fn get_user_delegation_key(
+        &self,
+        base_url: &str,
+        valid_until: chrono::DateTime<Utc>,
+    ) -> Result<CachedDelegationKey> {
+        {
+            let cache = self.delegation_key.read().await;
+            if let Some(ref cached) = *cache {
+                if valid_until < cached.expires_at {
+                    return Ok(cached.clone());
+                }
+            }
+        }
+
+        let mut cache = self.delegation_key.write().await;
+        if let Some(ref cached) = *cache {
+            if valid_until < cached.expires_at {
+                return Ok(cached.clone());
+            }
+        }
+
+        let key = self
+            .acquire_user_delegation_key(base_url, valid_until)
+            .await?;
+        *cache = Some(key.clone());
+        Ok(key)
+    }
+
+    /// Call Get User Delegation Key. Requires the
+    /// `generateUserDelegationKey` action, which `Storage Blob Data
+    /// Contributor` includes.
+    async fn acquire_user_delegation_key(
+        &self,
+        base_url: &str,
+        valid_until: chrono::DateTime<Utc>,
+    ) -> Result<CachedDelegationKey> {
+        let now = Utc::now();
+        let max_expiry = now + ChronoDuration::days(USER_DELEGATION_KEY_MAX_LIFETIME_DAYS);
+        if valid_until > max_expiry {
+            return Err(AppError::Storage(format!(
+                "User delegation SAS cannot be valid for more than {} days",
+                USER_DELEGATION_KEY_MAX_LIFETIME_DAYS
+            )));
+        }
+        let start = now - ChronoDuration::minutes(SAS_CLOCK_SKEW_ALLOWANCE_MINUTES);
+        let expiry = valid_until
+            .max(now + ChronoDuration::hours(USER_DELEGATION_KEY_LIFETIME_HOURS))
+            .min(max_expiry);
+
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><KeyInfo><Start>{}</Start><Expiry>{}</Expiry></KeyInfo>"#,
+            start.format("%Y-%m-%dT%H:%M:%SZ"),
+            expiry.format("%Y-%m-%dT%H:%M:%SZ"),
+        );
+        let token = self.get_token().await?;
+        let url = format!(
+            "{}/?restype=service&comp=userdelegationkey",
+            base_url.trim_end_matches('/')
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .header(
+                "x-ms-date",
+                now.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+            )
+            .header("x-ms-version", "2021-06-08")
+            .header("Content-Type", "application/xml")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::Storage(format!("Azure Get User Delegation Key failed: {}", e))
+            })?;
+
+        let status = response.status();
+        let text = response.text().await.map_err(|e| {
+            AppError::Storage(format!(
+                "Failed to read Azure user delegation key response: {}",
+                e
+            ))
+        })?;
+        if !status.is_success() {
+            return Err(AppError::Storage(format!(
+                "Azure Get User Delegation Key failed with status {}: {}",
+                status, text
+            )));
+        }
+
+        let key: UserDelegationKey = quick_xml::de::from_str(&text).map_err(|e| {
+            AppError::Storage(format!("Invalid Azure user delegation key response: {}", e))
+        })?;
+        let decoded_value = BASE64.decode(&key.value).map_err(|e| {
+            AppError::Storage(format!("Invalid Azure user delegation key value: {}", e))
+        })?;
+
+        tracing::debug!(
+            signed_expiry = %key.signed_expiry,
+            "Acquired Azure user delegation key"
+        );
+
+        Ok(CachedDelegationKey {
+            key,
+            decoded_value,
+            expires_at: expiry,
+        })
+    }
+
     /// Acquire a fresh token from Azure AD or IMDS.
     async fn acquire_token(&self) -> Result<CachedToken> {
         match &self.credential {
@@ -393,16 +652,15 @@ pub(crate) fn resolve_auth_mode(access_key: &Option<String>) -> &'static str {
     }
 }
 
-/// Check whether SAS-based redirect downloads are compatible with the auth mode.
-/// SAS tokens require Shared Key; RBAC mode cannot generate them.
-pub(crate) fn is_redirect_compatible(
-    access_key: &Option<String>,
-    redirect_downloads: bool,
-) -> bool {
-    if redirect_downloads && access_key.is_none() {
-        return false;
+/// Which kind of SAS signs redirect download URLs for the auth mode. Shared
+/// Key signs a service SAS with the account key; RBAC signs a user
+/// delegation SAS with a key from the Get User Delegation Key API.
+pub(crate) fn redirect_sas_kind(access_key: &Option<String>) -> &'static str {
+    if access_key.is_some() {
+        "service"
+    } else {
+        "user_delegation"
     }
-    true
 }
 
 // ---------------------------------------------------------------------------
@@ -465,11 +723,10 @@ impl AzureBackend {
             }
         };
 
-        // Warn if redirect downloads requested but RBAC mode cannot generate SAS
-        if !is_redirect_compatible(&config.access_key, config.redirect_downloads) {
-            tracing::warn!(
-                "AZURE_REDIRECT_DOWNLOADS is enabled but no AZURE_STORAGE_ACCESS_KEY is set. \
-                 SAS URL generation requires an access key. Redirect downloads will be disabled."
+        if config.redirect_downloads {
+            tracing::info!(
+                sas_kind = redirect_sas_kind(&config.access_key),
+                "Azure redirect downloads enabled"
             );
         }
 
@@ -600,6 +857,18 @@ impl AzureBackend {
         Ok(Self::append_query(url, "comp=blocklist"))
     }
 
+    /// Set Blob Immutability Policy URL. Shared Key signs a SAS with the
+    /// `i` (set immutability policy) permission.
+    fn immutability_policy_url(&self, key: &str) -> Result<String> {
+        let url = match &self.auth {
+            AzureAuthMode::SharedKey { .. } => {
+                self.generate_sas_url_with_permissions(key, Duration::from_secs(300), "i")?
+            }
+            AzureAuthMode::TokenCredential { .. } => self.blob_url(key),
+        };
+        Ok(Self::append_query(url, "comp=immutabilityPolicies"))
+    }
+
     /// Content-Length field for a Shared Key string-to-sign.
     ///
     /// For service version 2015-02-21 and later, Azure requires this field
@@ -672,10 +941,47 @@ impl AzureBackend {
 
     /// String-to-sign for a Delete Blob request in Shared Key mode.
     fn delete_blob_string_to_sign(&self, date_str: &str, key: &str) -> String {
+        let delete_snapshots = if self.config.delete_snapshots {
+            "x-ms-delete-snapshots:include\n"
+        } else {
+            ""
+        };
         format!(
-            "DELETE\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:{}\nx-ms-version:2021-06-08\n{}",
+            "DELETE\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:{}\n{}x-ms-version:2021-06-08\n{}",
             date_str,
+            delete_snapshots,
+            self.shared_key_canonicalized_resource(key)
+        )
+    }
+
+    /// String-to-sign for a bodiless request in Shared Key mode. `key` is
+    /// the blob, or empty for a container-level request; `query` holds the
+    /// request's parameters, which Shared Key signs sorted by name.
+    fn bodiless_string_to_sign(
+        &self,
+        verb: &str,
+        date_str: &str,
+        key: &str,
+        query: &[(&str, String)],
+    ) -> String {
+        let mut resource = if key.is_empty() {
+            format!(
+                "/{}{}/{}",
+                self.config.account_name,
+                self.endpoint_uri_path(),
+                self.config.container_name
+            )
+        } else {
             self.shared_key_canonicalized_resource(key)
+        };
+        let mut query: Vec<&(&str, String)> = query.iter().collect();
+        query.sort_by_key(|(name, _)| *name);
+        for (name, value) in query {
+            resource.push_str(&format!("\n{}:{}", name, value));
+        }
+        format!(
+            "{}\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:{}\nx-ms-version:2021-06-08\n{}",
+            verb, date_str, resource
         )
     }
 
@@ -914,11 +1220,8 @@ impl AzureBackend {
                 let auth_header =
                     Self::shared_key_auth(decoded_key, &self.config.account_name, &string_to_sign)?;
 
-                self.client
-                    .delete(url)
+                self.delete_request(url, &date_str)
                     .header("Authorization", auth_header)
-                    .header("x-ms-date", &date_str)
-                    .header("x-ms-version", "2021-06-08")
                     .send()
                     .await
                     .map_err(|e| AppError::Storage(format!("Azure delete failed: {}", e)))
@@ -926,11 +1229,8 @@ impl AzureBackend {
             AzureAuthMode::TokenCredential { provider } => {
                 let token = provider.get_token().await?;
 
-                self.client
-                    .delete(url)
+                self.delete_request(url, &date_str)
                     .header("Authorization", format!("Bearer {}", token))
-                    .header("x-ms-date", &date_str)
-                    .header("x-ms-version", "2021-06-08")
                     .send()
                     .await
                     .map_err(|e| AppError::Storage(format!("Azure delete failed: {}", e)))
@@ -938,6 +1238,65 @@ impl AzureBackend {
         }
     }
 
+    fn delete_request(&self, url: &str, date_str: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .delete(url)
+            .header("x-ms-date", date_str)
+            .header("x-ms-version", "2021-06-08");
+        if self.config.delete_snapshots {
+            request.header("x-ms-delete-snapshots", "include")
+        } else {
+            request
+        }
+    }
+
+    /// Send a bodiless request for `key` (empty for the container) with the
+    /// query parameters `query`, authorized for the current auth mode.
+    async fn authorized_bodiless(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        query: &[(&str, String)],
+    ) -> Result<reqwest::Response> {
+        let date_str = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let base = if key.is_empty() {
+            format!("{}/{}", self.base_url(), self.config.container_name)
+        } else {
+            self.blob_url(key)
+        };
+        let query_string = query
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, urlencoding::encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let url = Self::append_query(base, &query_string);
+
+        let mut request = self
+            .client
+            .request(method.clone(), &url)
+            .header("x-ms-date", &date_str)
+            .header("x-ms-version", "2021-06-08");
+        if method == reqwest::Method::PUT {
+            request = request.header("Content-Length", "0");
+        }
+        let authorization = match &self.auth {
+            AzureAuthMode::SharedKey { decoded_key } => {
+                let string_to_sign =
+                    self.bodiless_string_to_sign(method.as_str(), &date_str, key, query);
+                Self::shared_key_auth(decoded_key, &self.config.account_name, &string_to_sign)?
+            }
+            AzureAuthMode::TokenCredential { provider } => {
+                format!("Bearer {}", provider.get_token().await?)
+            }
+        };
+        request
+            .header("Authorization", authorization)
+            .send()
+            .await
+            .map_err(|e| AppError::Storage(format!("Azure {} request failed: {}", method, e)))
+    }
+
     /// Get the URL to use for a read operation.
     /// SharedKey mode appends a SAS token; RBAC mode uses the bare blob URL
     /// (authorization comes from the bearer token header).
@@ -1058,13 +1417,106 @@ impl AzureBackend {
         Ok(format!("{}?{}", self.blob_url(key), sas_token))
     }
 
-    async fn put_stream_block(
+    /// Generate a read-only user delegation SAS URL for a blob (RBAC mode).
+    ///
+    /// Fetches (or reuses) a user delegation key valid past the SAS expiry.
+    async fn generate_user_delegation_sas_url(
         &self,
         key: &str,
-        upload_nonce: &str,
-        block_ids: &mut Vec<String>,
-        content: Bytes,
-    ) -> Result<()> {
+        expires_in: Duration,
+    ) -> Result<String> {
+        let provider = match &self.auth {
+            AzureAuthMode::TokenCredential { provider } => provider,
+            AzureAuthMode::SharedKey { .. } => {
+                return Err(AppError::Storage(
+                    "User delegation SAS requires RBAC auth".to_string(),
+                ));
+            }
+        };
+
+        let now = Utc::now();
+        let expiry = now + ChronoDuration::seconds(expires_in.as_secs() as i64);
+        let delegation_key = provider
+            .get_user_delegation_key(&self.base_url(), expiry)
+            .await?;
+        let sas_token = self.user_delegation_sas_token(key, now, expiry, "r", &delegation_key)?;
+        Ok(format!("{}?{}", self.blob_url(key), sas_token))
+    }
+
+    fn user_delegation_sas_token(
+        &self,
+        key: &str,
+        now: chrono::DateTime<Utc>,
+        expiry: chrono::DateTime<Utc>,
+        signed_permissions: &str,
+        delegation_key: &CachedDelegationKey,
+    ) -> Result<String> {
+        // Same clock-skew backdating as the service SAS.
+        let start = now - ChronoDuration::minutes(SAS_CLOCK_SKEW_ALLOWANCE_MINUTES);
+
+        let signed_version = "2021-06-08";
+        let signed_resource = "b";
+        let signed_start = start.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let signed_expiry = expiry.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let signed_protocol = "https";
+        let udk = &delegation_key.key;
+
+        let canonicalized_resource = format!(
+            "/blob/{}/{}/{}",
+            self.config.account_name, self.config.container_name, key
+        );
+
+        // User delegation SAS string-to-sign for API version 2020-12-06 and
+        // later (24 fields, 23 newlines):
+        // sp, st, se, canonicalizedResource, skoid, sktid, skt, ske, sks, skv,
+        // saoid, suoid, scid, sip, spr, sv, sr, snapshotTime, encryptionScope,
+        // rscc, rscd, rsce, rscl, rsct
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n\n\n\n\n{}\n{}\n{}\n\n\n\n\n\n\n",
+            signed_permissions,
+            signed_start,
+            signed_expiry,
+            canonicalized_resource,
+            udk.signed_oid,
+            udk.signed_tid,
+            udk.signed_start,
+            udk.signed_expiry,
+            udk.signed_service,
+            udk.signed_version,
+            // saoid, suoid, scid, sip - empty
+            signed_protocol,
+            signed_version,
+            signed_resource,
+            // snapshotTime, encryptionScope - empty
+            // rscc, rscd, rsce, rscl, rsct - empty
+        );
+
+        let mut mac = HmacSha256::new_from_slice(&delegation_key.decoded_value)
+            .map_err(|e| AppError::Storage(format!("Failed to create HMAC: {}", e)))?;
+        mac.update(string_to_sign.as_bytes());
+        let signature = BASE64.encode(mac.finalize().into_bytes());
+
+        Ok(format!(
+            "sv={}&st={}&se={}&sr={}&sp={}&spr={}&skoid={}&sktid={}&skt={}&ske={}&sks={}&skv={}&sig={}",
+            urlencoding::encode(signed_version),
+            urlencoding::encode(&signed_start),
+            urlencoding::encode(&signed_expiry),
+            signed_resource,
+            signed_permissions,
+            signed_protocol,
+            urlencoding::encode(&udk.signed_oid),
+            urlencoding::encode(&udk.signed_tid),
+            urlencoding::encode(&udk.signed_start),
+            urlencoding::encode(&udk.signed_expiry),
+            urlencoding::encode(&udk.signed_service),
+            urlencoding::encode(&udk.signed_version),
+            urlencoding::encode(&signature),
+        ))
+    }
+
+    /// Reserve the next block ID of an upload, enforcing Azure's limit on
+    /// blocks per blob before anything is sent.
+    fn next_block_id(key: &str, upload_nonce: &str, block_ids: &mut Vec<String>) -> Result<String> {
         if block_ids.len() >= AZURE_MAX_BLOCKS {
             return Err(AppError::Storage(format!(
                 "Azure block blob limit exceeded for '{}': {} blocks staged; maximum is {}",
@@ -1078,19 +1530,24 @@ impl AzureBackend {
                 key = %key,
                 staged_blocks = block_ids.len(),
                 max_blocks = AZURE_MAX_BLOCKS,
-                "Azure streaming upload is approaching the block blob limit"
+                "Azure block upload is approaching the block blob limit"
             );
         }
-        // The block ID embeds a per-upload nonce so two concurrent streaming
-        // writes to the same key stage into disjoint uncommitted block lists.
-        // Without it, both would use block IDs derived from the index alone
-        // (0, 1, …) and overwrite each other's uncommitted blocks, letting one
-        // Put Block List assemble the other request's bytes under this key.
+        // The block ID embeds a per-upload nonce so two concurrent writes to
+        // the same key stage into disjoint uncommitted block lists. Without
+        // it, both would use block IDs derived from the index alone (0, 1, …)
+        // and overwrite each other's uncommitted blocks, letting one Put
+        // Block List assemble the other request's bytes under this key.
         // The pre-base64 string (32-hex nonce + 16-digit index = 48 bytes) is
         // a fixed length for every block, satisfying Azure's same-size-per-blob
         // and <=64-byte block-ID rules.
         let block_id = BASE64.encode(format!("{upload_nonce}{:016}", block_ids.len()));
-        let url = self.block_url(key, &block_id)?;
+        block_ids.push(block_id.clone());
+        Ok(block_id)
+    }
+
+    async fn stage_block(&self, key: &str, block_id: &str, content: Bytes) -> Result<()> {
+        let url = self.block_url(key, block_id)?;
         let response = self.authorized_put_block(&url, content).await?;
 
         if !response.status().is_success() {
@@ -1102,7 +1559,38 @@ impl AzureBackend {
             )));
         }
 
-        block_ids.push(block_id);
+        Ok(())
+    }
+
+    /// Upload an in-memory body as blocks (see [`AzureUploadConfig`]).
+    async fn put_blocks(&self, key: &str, content: Bytes) -> Result<()> {
+        let block_size = self.config.upload.block_size;
+        let blocks = content.len().div_ceil(block_size);
+        if blocks > AZURE_MAX_BLOCKS {
+            return Err(AppError::Storage(format!(
+                "Azure block blob limit exceeded for '{}': {} bytes needs {} blocks of {} bytes; maximum is {}",
+                key,
+                content.len(),
+                blocks,
+                block_size,
+                AZURE_MAX_BLOCKS
+            )));
+        }
+
+        let mut upload = BlockUpload::new(self, key);
+        let mut offset = 0;
+        while offset < content.len() {
+            let end = (offset + block_size).min(content.len());
+            if let Err(e) = upload.stage(content.slice(offset..end)).await {
+                upload.abort().await;
+                return Err(e);
+            }
+            offset = end;
+        }
+        if let Err(e) = upload.commit().await {
+            upload.abort().await;
+            return Err(e);
+        }
         Ok(())
     }
 
@@ -1207,10 +1695,80 @@ impl AzureBackend {
     }
 }
 
+/// One block upload: stages blocks with up to `upload.concurrency` Put
+/// Block requests in flight and commits them in order.
+struct BlockUpload<'a> {
+    backend: &'a AzureBackend,
+    key: &'a str,
+    // Per-upload nonce woven into every block ID (see next_block_id) so
+    // concurrent writes to the same key cannot collide on block IDs. With
+    // that guarantee a failed upload's staged blocks are private and Azure
+    // auto-GCs them, so we neither probe existence up front nor mutate the
+    // destination blob on failure.
+    upload_nonce: String,
+    block_ids: Vec<String>,
+    in_flight: FuturesUnordered<BoxFuture<'a, Result<()>>>,
+}
+
+impl<'a> BlockUpload<'a> {
+    fn new(backend: &'a AzureBackend, key: &'a str) -> Self {
+        Self {
+            backend,
+            key,
+            upload_nonce: Uuid::new_v4().simple().to_string(),
+            block_ids: Vec::new(),
+            in_flight: FuturesUnordered::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.block_ids.is_empty()
+    }
+
+    /// Queue the next block, waiting for a slot when the upload is at its
+    /// concurrency limit.
+    async fn stage(&mut self, content: Bytes) -> Result<()> {
+        while self.in_flight.len() >= self.backend.config.upload.concurrency {
+            if let Some(result) = self.in_flight.next().await {
+                result?;
+            }
+        }
+        let block_id =
+            AzureBackend::next_block_id(self.key, &self.upload_nonce, &mut self.block_ids)?;
+        let (backend, key) = (self.backend, self.key);
+        self.in_flight.push(Box::pin(async move {
+            backend.stage_block(key, &block_id, content).await
+        }));
+        Ok(())
+    }
+
+    /// Wait for the remaining blocks, then commit the block list.
+    async fn commit(&mut self) -> Result<()> {
+        while let Some(result) = self.in_flight.next().await {
+            result?;
+        }
+        self.backend
+            .commit_stream_blocks(self.key, &self.block_ids)
+            .await
+    }
+
+    /// Cancel in-flight blocks and report the staged ones.
+    async fn abort(&mut self) {
+        self.in_flight.clear();
+        self.backend
+            .report_uncommitted_stream_blocks(self.key, &self.block_ids)
+            .await;
+    }
+}
+
 #[async_trait]
 impl StorageBackend for AzureBackend {
     #[tracing::instrument(skip(self, content), fields(otel.kind = "client", storage.system = "azure", storage.operation = "put"))]
     async fn put(&self, key: &str, content: Bytes) -> Result<()> {
+        if content.len() > self.config.upload.single_put_max {
+            return self.put_blocks(key, content).await;
+        }
+
         let url = self.blob_url(key);
         let response = self.authorized_put(&url, key, &content).await?;
 
@@ -1366,23 +1924,18 @@ impl StorageBackend for AzureBackend {
         key: &str,
         stream: BoxStream<'static, Result<Bytes>>,
     ) -> Result<PutStreamResult> {
+        let block_size = self.config.upload.block_size;
         let mut hasher = Sha256::new();
         let mut total: u64 = 0;
-        let mut buffer = BytesMut::with_capacity(AZURE_BLOCK_CHUNK_SIZE);
-        let mut block_ids = Vec::new();
-        // Per-upload nonce woven into every block ID (see put_stream_block) so
-        // concurrent streaming writes to the same key cannot collide on block
-        // IDs. With that guarantee a failed upload's staged blocks are private
-        // and Azure auto-GCs them, so we neither probe existence up front nor
-        // mutate the destination blob on failure.
-        let upload_nonce = Uuid::new_v4().simple().to_string();
+        let mut buffer = BytesMut::with_capacity(block_size);
+        let mut upload = BlockUpload::new(self, key);
 
         tokio::pin!(stream);
         while let Some(chunk) = stream.next().await {
             let mut chunk = match chunk {
                 Ok(chunk) => chunk,
                 Err(e) => {
-                    self.report_uncommitted_stream_blocks(key, &block_ids).await;
+                    upload.abort().await;
                     return Err(e);
                 }
             };
@@ -1393,18 +1946,15 @@ impl StorageBackend for AzureBackend {
             total += chunk.len() as u64;
 
             while !chunk.is_empty() {
-                let remaining = AZURE_BLOCK_CHUNK_SIZE - buffer.len();
+                let remaining = block_size - buffer.len();
                 let take = remaining.min(chunk.len());
                 let piece = chunk.split_to(take);
                 buffer.extend_from_slice(&piece);
 
-                if buffer.len() == AZURE_BLOCK_CHUNK_SIZE {
+                if buffer.len() == block_size {
                     let block = buffer.split().freeze();
-                    if let Err(e) = self
-                        .put_stream_block(key, &upload_nonce, &mut block_ids, block)
-                        .await
-                    {
-                        self.report_uncommitted_stream_blocks(key, &block_ids).await;
+                    if let Err(e) = upload.stage(block).await {
+                        upload.abort().await;
                         return Err(e);
                     }
                 }
@@ -1413,19 +1963,16 @@ impl StorageBackend for AzureBackend {
 
         if !buffer.is_empty() {
             let block = buffer.split().freeze();
-            if let Err(e) = self
-                .put_stream_block(key, &upload_nonce, &mut block_ids, block)
-                .await
-            {
-                self.report_uncommitted_stream_blocks(key, &block_ids).await;
+            if let Err(e) = upload.stage(block).await {
+                upload.abort().await;
                 return Err(e);
             }
         }
 
-        if block_ids.is_empty() {
+        if upload.is_empty() {
             self.put(key, Bytes::new()).await?;
-        } else if let Err(e) = self.commit_stream_blocks(key, &block_ids).await {
-            self.report_uncommitted_stream_blocks(key, &block_ids).await;
+        } else if let Err(e) = upload.commit().await {
+            upload.abort().await;
             return Err(e);
         }
 
@@ -1481,6 +2028,19 @@ impl StorageBackend for AzureBackend {
         Ok(())
     }
 
+    async fn copy_from(
+        &self,
+        source: &dyn StorageBackend,
+        source_key: &str,
+        dest_key: &str,
+    ) -> Result<bool> {
+        super::copy_within_instance(self, source, source_key, dest_key).await
+    }
+
+    /// A soft-deleted blob answers HEAD with 404 and so reports absent until
+    /// it is undeleted. Any other failure is an error rather than `false`:
+    /// GC and dedup must not take throttling or an auth failure for a
+    /// missing blob.
     #[tracing::instrument(skip(self), fields(otel.kind = "client", storage.system = "azure", storage.operation = "exists"))]
     async fn exists(&self, key: &str) -> Result<bool> {
         let url = self.read_url(key, Duration::from_secs(60))?;
@@ -1489,6 +2049,13 @@ impl StorageBackend for AzureBackend {
         if response.status().is_success() {
             return Ok(true);
         }
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            return Err(AppError::Storage(format!(
+                "Azure exists check for '{}' failed with status {}",
+                key,
+                response.status()
+            )));
+        }
 
         // In migration mode, also check the Artifactory fallback path
         if self.path_format.has_fallback() {
@@ -1519,9 +2086,16 @@ impl StorageBackend for AzureBackend {
         if !response.status().is_success() && response.status() != reqwest::StatusCode::NOT_FOUND {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            return Err(AppError::Storage(format!(
-                "Azure delete failed with status {}: {}",
-                status, body
+            if status == reqwest::StatusCode::CONFLICT && body.contains("SnapshotsPresent") {
+                return Err(AppError::Storage(format!(
+                    "Azure delete of '{}' failed: the blob has snapshots. Set \
+                     AZURE_DELETE_SNAPSHOTS=true to delete them together with the blob",
+                    key
+                )));
+            }
+            return Err(AppError::Storage(format!(
+                "Azure delete failed with status {}: {}",
+                status, body
             )));
         }
 
@@ -1556,9 +2130,63 @@ impl StorageBackend for AzureBackend {
         Ok(etag)
     }
 
+    fn supports_retention(&self) -> bool {
+        self.config.immutable_storage
+    }
+
+    fn soft_delete_recovery(&self) -> Option<&dyn SoftDeleteRecovery> {
+        Some(self)
+    }
+
+    /// Set Blob Immutability Policy in `Locked` mode: the blob cannot be
+    /// deleted or overwritten, nor the policy shortened, until `until`.
+    async fn set_retention(&self, key: &str, until: chrono::DateTime<Utc>) -> Result<()> {
+        if !self.config.immutable_storage {
+            return Err(AppError::Storage(
+                "Azure blob retention requires a container with version-level immutability \
+                 (AZURE_IMMUTABLE_STORAGE)"
+                    .to_string(),
+            ));
+        }
+        let url = self.immutability_policy_url(key)?;
+        let date_str = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let until_str = until.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let mut request = self
+            .client
+            .put(&url)
+            .header("x-ms-date", &date_str)
+            .header("x-ms-version", "2021-06-08")
+            .header("x-ms-immutability-policy-until-date", &until_str)
+            .header("x-ms-immutability-policy-mode", "Locked")
+            .header("Content-Length", "0");
+        if let AzureAuthMode::TokenCredential { provider } = &self.auth {
+            let token = provider.get_token().await?;
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        let response = request.send().await.map_err(|e| {
+            AppError::Storage(format!("Azure Set Blob Immutability Policy failed: {}", e))
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(AppError::NotFound(format!(
+                "Storage key not found: {}",
+                key
+            )));
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(AppError::Storage(format!(
+            "Azure Set Blob Immutability Policy for '{}' failed with status {}: {}",
+            key, status, body
+        )))
+    }
+
     fn supports_redirect(&self) -> bool {
-        // SAS redirect downloads require Shared Key auth
-        self.config.redirect_downloads && !self.is_rbac()
+        // Shared Key signs a service SAS; RBAC signs a user delegation SAS.
+        self.config.redirect_downloads
     }
 
     #[tracing::instrument(skip(self), fields(otel.kind = "client", storage.system = "azure", storage.operation = "get_presigned_url"))]
@@ -1571,11 +2199,18 @@ impl StorageBackend for AzureBackend {
             return Ok(None);
         }
 
-        let url = self.generate_sas_url(key, expires_in)?;
+        let url = match &self.auth {
+            AzureAuthMode::SharedKey { .. } => self.generate_sas_url(key, expires_in)?,
+            AzureAuthMode::TokenCredential { .. } => {
+                self.generate_user_delegation_sas_url(key, expires_in)
+                    .await?
+            }
+        };
 
         tracing::debug!(
             key = %key,
             expires_in = ?expires_in,
+            rbac = self.is_rbac(),
             "Generated Azure SAS URL"
         );
 
@@ -1697,9 +2332,140 @@ impl StorageBackend for AzureBackend {
     }
 }
 
+/// The List Blobs response, as far as soft-delete recovery reads it.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BlobEnumeration {
+    #[serde(default)]
+    blobs: BlobList,
+    #[serde(default)]
+    next_marker: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct BlobList {
+    #[serde(rename = "Blob", default)]
+    blobs: Vec<ListedBlob>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListedBlob {
+    name: String,
+    #[serde(default)]
+    deleted: bool,
+    #[serde(default)]
+    snapshot: Option<String>,
+    #[serde(default)]
+    properties: ListedBlobProperties,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct ListedBlobProperties {
+    #[serde(rename = "Content-Length", default)]
+    content_length: u64,
+    #[serde(rename = "DeletedTime", default)]
+    deleted_time: Option<String>,
+    #[serde(rename = "RemainingRetentionDays", default)]
+    remaining_retention_days: Option<u32>,
+}
+
+/// Soft-deleted base blobs of a List Blobs (`include=deleted`) page.
+/// Deleted snapshots are left out: undeleting their blob restores them.
+fn parse_soft_deleted_page(xml: &str) -> Result<SoftDeletedPage> {
+    let enumeration: BlobEnumeration = quick_xml::de::from_str(xml)
+        .map_err(|e| AppError::Storage(format!("Invalid Azure List Blobs response: {}", e)))?;
+    let objects = enumeration
+        .blobs
+        .blobs
+        .into_iter()
+        .filter(|blob| blob.deleted && blob.snapshot.is_none())
+        .map(|blob| SoftDeletedObject {
+            key: blob.name,
+            size_bytes: blob.properties.content_length,
+            deleted_at: blob
+                .properties
+                .deleted_time
+                .as_deref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc2822(t).ok())
+                .map(|t| t.with_timezone(&Utc)),
+            remaining_retention_days: blob.properties.remaining_retention_days,
+        })
+        .collect();
+    Ok(SoftDeletedPage {
+        objects,
+        next_marker: enumeration.next_marker.filter(|m| !m.is_empty()),
+    })
+}
+
+#[async_trait]
+impl SoftDeleteRecovery for AzureBackend {
+    async fn list_soft_deleted(
+        &self,
+        prefix: Option<&str>,
+        marker: Option<&str>,
+        limit: usize,
+    ) -> Result<SoftDeletedPage> {
+        let mut query = vec![
+            ("restype", "container".to_string()),
+            ("comp", "list".to_string()),
+            ("include", "deleted".to_string()),
+            ("maxresults", limit.clamp(1, 5000).to_string()),
+        ];
+        if let Some(prefix) = prefix.filter(|p| !p.is_empty()) {
+            query.push(("prefix", prefix.to_string()));
+        }
+        if let Some(marker) = marker.filter(|m| !m.is_empty()) {
+            query.push(("marker", marker.to_string()));
+        }
+        let response = self
+            .authorized_bodiless(reqwest::Method::GET, "", &query)
+            .await?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to read response: {}", e)))?;
+        if !status.is_success() {
+            return Err(AppError::Storage(format!(
+                "Azure List Blobs failed with status {}: {}",
+                status, body
+            )));
+        }
+        parse_soft_deleted_page(&body)
+    }
+
+    async fn undelete(&self, key: &str) -> Result<()> {
+        let response = self
+            .authorized_bodiless(
+                reqwest::Method::PUT,
+                key,
+                &[("comp", "undelete".to_string())],
+            )
+            .await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(AppError::NotFound(format!(
+                "No soft-deleted blob '{}' to restore",
+                key
+            )));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!(
+                "Azure Undelete Blob failed with status {}: {}",
+                status, body
+            )));
+        }
+        tracing::info!(key = %key, "Restored soft-deleted Azure blob");
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     fn create_test_config() -> AzureConfig {
         AzureConfig {
@@ -1714,6 +2480,9 @@ mod tests {
             redirect_downloads: true,
             sas_expiry: Duration::from_secs(3600),
             path_format: StoragePathFormat::Native,
+            upload: AzureUploadConfig::default(),
+            immutable_storage: false,
+            delete_snapshots: false,
         }
     }
 
@@ -1726,6 +2495,9 @@ mod tests {
             redirect_downloads: false,
             sas_expiry: Duration::from_secs(3600),
             path_format: StoragePathFormat::Native,
+            upload: AzureUploadConfig::default(),
+            immutable_storage: false,
+            delete_snapshots: false,
         }
     }
 
@@ -1759,6 +2531,7 @@ mod tests {
             client: client.clone(),
             credential,
             cache: RwLock::new(None),
+            delegation_key: RwLock::new(None),
         };
         AzureBackend {
             config: create_rbac_config(),
@@ -1782,6 +2555,7 @@ mod tests {
                 access_token: "cached-test-token".to_string(),
                 expires_at: Utc::now() + ChronoDuration::hours(1),
             })),
+            delegation_key: RwLock::new(None),
         };
         let mut config = create_rbac_config();
         config.endpoint = Some(endpoint);
@@ -1832,27 +2606,15 @@ mod tests {
     }
 
     #[test]
-    fn test_redirect_compatible_shared_key_enabled() {
+    fn test_redirect_sas_kind_shared_key() {
         let key = Some("key".to_string());
-        assert!(is_redirect_compatible(&key, true));
+        assert_eq!(redirect_sas_kind(&key), "service");
     }
 
     #[test]
-    fn test_redirect_compatible_shared_key_disabled() {
-        let key = Some("key".to_string());
-        assert!(is_redirect_compatible(&key, false));
-    }
-
-    #[test]
-    fn test_redirect_incompatible_rbac_with_redirect() {
-        let key: Option<String> = None;
-        assert!(!is_redirect_compatible(&key, true));
-    }
-
-    #[test]
-    fn test_redirect_compatible_rbac_without_redirect() {
+    fn test_redirect_sas_kind_rbac() {
         let key: Option<String> = None;
-        assert!(is_redirect_compatible(&key, false));
+        assert_eq!(redirect_sas_kind(&key), "user_delegation");
     }
 
     #[test]
@@ -2150,6 +2912,15 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_immutability_policy_url_signs_set_policy_permission() {
+        let backend = create_test_backend().await;
+        let url = backend.immutability_policy_url("a/b.jar").unwrap();
+        assert!(url.contains("sp=i&"));
+        assert!(url.ends_with("&comp=immutabilityPolicies"));
+        assert!(!StorageBackend::supports_retention(&backend));
+    }
+
     // ── Health probe URL ─────────────────────────────────────────────────
 
     #[tokio::test]
@@ -2284,6 +3055,141 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_delete_string_to_sign_with_snapshots() {
+        let mut config = create_test_config();
+        config.delete_snapshots = true;
+        let backend = AzureBackend::new(config).await.unwrap();
+        let string_to_sign =
+            backend.delete_blob_string_to_sign("Mon, 06 Jul 2026 22:37:12 GMT", "test/blob");
+        assert!(string_to_sign.contains(
+            "\nx-ms-date:Mon, 06 Jul 2026 22:37:12 GMT\nx-ms-delete-snapshots:include\nx-ms-version:2021-06-08\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bodiless_string_to_sign_sorts_query() {
+        let backend = create_test_backend().await;
+        let list = backend.bodiless_string_to_sign(
+            "GET",
+            "Mon, 06 Jul 2026 22:37:12 GMT",
+            "",
+            &[
+                ("restype", "container".to_string()),
+                ("comp", "list".to_string()),
+                ("include", "deleted".to_string()),
+            ],
+        );
+        assert!(list.starts_with("GET\n"));
+        assert!(list.ends_with(
+            "\n/testaccount/testcontainer\ncomp:list\ninclude:deleted\nrestype:container"
+        ));
+
+        let undelete = backend.bodiless_string_to_sign(
+            "PUT",
+            "Mon, 06 Jul 2026 22:37:12 GMT",
+            "ab/cd/blob",
+            &[("comp", "undelete".to_string())],
+        );
+        assert!(undelete.ends_with("\n/testaccount/testcontainer/ab/cd/blob\ncomp:undelete"));
+    }
+
+    #[test]
+    fn test_parse_soft_deleted_page() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ServiceEndpoint="https://testaccount.blob.core.windows.net/" ContainerName="testcontainer">
+  <MaxResults>2</MaxResults>
+  <Blobs>
+    <Blob>
+      <Name>ab/cd/deleted</Name>
+      <Deleted>true</Deleted>
+      <Properties>
+        <Content-Length>1024</Content-Length>
+        <DeletedTime>Mon, 06 Jul 2026 22:37:12 GMT</DeletedTime>
+        <RemainingRetentionDays>6</RemainingRetentionDays>
+      </Properties>
+    </Blob>
+    <Blob>
+      <Name>ab/cd/deleted</Name>
+      <Snapshot>2026-07-01T10:00:00.0000000Z</Snapshot>
+      <Deleted>true</Deleted>
+      <Properties><Content-Length>1024</Content-Length></Properties>
+    </Blob>
+    <Blob>
+      <Name>ab/cd/live</Name>
+      <Properties><Content-Length>10</Content-Length></Properties>
+    </Blob>
+  </Blobs>
+  <NextMarker>2!marker</NextMarker>
+</EnumerationResults>"#;
+        let page = parse_soft_deleted_page(xml).unwrap();
+        assert_eq!(page.next_marker.as_deref(), Some("2!marker"));
+        assert_eq!(
+            page.objects,
+            vec![SoftDeletedObject {
+                key: "ab/cd/deleted".to_string(),
+                size_bytes: 1024,
+                deleted_at: chrono::DateTime::parse_from_rfc3339("2026-07-06T22:37:12Z")
+                    .ok()
+                    .map(|t| t.with_timezone(&Utc)),
+                remaining_retention_days: Some(6),
+            }]
+        );
+
+        let empty = parse_soft_deleted_page(
+            "<EnumerationResults><Blobs /><NextMarker /></EnumerationResults>",
+        )
+        .unwrap();
+        assert!(empty.objects.is_empty());
+        assert!(empty.next_marker.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_undelete_and_exists_status_handling() {
+        use crate::storage::StorageBackend as StorageBackendTrait;
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/testcontainer/ab/cd/restore-me"))
+            .and(query_param("comp", "undelete"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/testcontainer/ab/cd/gone"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/testcontainer/ab/cd/throttled"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/testcontainer/ab/cd/gone"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let backend = create_cached_rbac_backend_with_endpoint(server.uri());
+        let recovery = StorageBackendTrait::soft_delete_recovery(&backend).unwrap();
+        recovery.undelete("ab/cd/restore-me").await.unwrap();
+        assert!(matches!(
+            recovery.undelete("ab/cd/gone").await,
+            Err(AppError::NotFound(_))
+        ));
+
+        assert!(!StorageBackendTrait::exists(&backend, "ab/cd/gone")
+            .await
+            .unwrap());
+        assert!(StorageBackendTrait::exists(&backend, "ab/cd/throttled")
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_get_range_sends_azure_range_headers() {
         use crate::storage::StorageBackend as StorageBackendTrait;
@@ -2607,18 +3513,10 @@ mod tests {
             .mount_as_scoped(&server)
             .await;
         let backend = create_cached_rbac_backend_with_endpoint(server.uri());
-        let mut block_ids = (0..50_000)
-            .map(|i| format!("block-{i}"))
-            .collect::<Vec<_>>();
+        let mut upload = BlockUpload::new(&backend, "streamed/blob.txt");
+        upload.block_ids = (0..50_000).map(|i| format!("block-{i}")).collect();
 
-        let result = backend
-            .put_stream_block(
-                "streamed/blob.txt",
-                "0123456789abcdef0123456789abcdef",
-                &mut block_ids,
-                Bytes::from_static(b"x"),
-            )
-            .await;
+        let result = upload.stage(Bytes::from_static(b"x")).await;
 
         let error = result.expect_err("50,000 staged blocks must fail locally");
         assert!(
@@ -2632,6 +3530,216 @@ mod tests {
         );
     }
 
+    /// Block IDs named in a Put Block List body, in order.
+    fn committed_block_ids(body: &[u8]) -> Vec<String> {
+        let body = std::str::from_utf8(body).unwrap();
+        body.split("<Latest>")
+            .skip(1)
+            .map(|part| part.split("</Latest>").next().unwrap().to_string())
+            .collect()
+    }
+
+    fn staged_block_ids(requests: &[wiremock::Request]) -> Vec<String> {
+        requests
+            .iter()
+            .filter_map(|request| {
+                let pairs: HashMap<_, _> = request.url.query_pairs().into_owned().collect();
+                (pairs.get("comp").map(String::as_str) == Some("block"))
+                    .then(|| pairs.get("blockid").cloned())
+                    .flatten()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_put_large_body_uses_parallel_block_upload() {
+        use crate::storage::StorageBackend as StorageBackendTrait;
+        use wiremock::matchers::{method, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(query_param("comp", "block"))
+            .respond_with(ResponseTemplate::new(201).set_delay(Duration::from_millis(20)))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(query_param("comp", "blocklist"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&server)
+            .await;
+
+        let mut backend = create_cached_rbac_backend_with_endpoint(server.uri());
+        backend.config.upload = AzureUploadConfig {
+            single_put_max: 16,
+            block_size: 10,
+            concurrency: 3,
+        };
+        let content = Bytes::from(vec![7u8; 95]);
+        StorageBackendTrait::put(&backend, "large/blob.bin", content)
+            .await
+            .expect("block upload should succeed");
+
+        let requests = server.received_requests().await.unwrap_or_default();
+        let staged = staged_block_ids(&requests);
+        assert_eq!(staged.len(), 10, "95 bytes in 10-byte blocks");
+        let block_sizes: Vec<usize> = requests
+            .iter()
+            .filter(|r| r.url.query().is_some_and(|q| q.contains("comp=block&")))
+            .map(|r| r.body.len())
+            .collect();
+        assert_eq!(block_sizes.iter().sum::<usize>(), 95);
+        assert!(block_sizes.iter().all(|&len| len == 10 || len == 5));
+
+        let commit = requests
+            .iter()
+            .find(|r| r.url.query().is_some_and(|q| q.contains("comp=blocklist")))
+            .expect("blocks must be committed");
+        let committed = committed_block_ids(&commit.body);
+        let indexes: Vec<u64> = committed
+            .iter()
+            .map(|id| {
+                let raw = String::from_utf8(BASE64.decode(id).unwrap()).unwrap();
+                raw[32..].parse().unwrap()
+            })
+            .collect();
+        assert_eq!(
+            indexes,
+            (0..10).collect::<Vec<u64>>(),
+            "block list must follow block order"
+        );
+        let mut committed_sorted = committed.clone();
+        committed_sorted.sort();
+        let mut staged_sorted = staged;
+        staged_sorted.sort();
+        assert_eq!(committed_sorted, staged_sorted);
+        assert!(
+            !requests
+                .iter()
+                .any(|request| request.headers.contains_key("x-ms-blob-type")),
+            "large put must not send a single Put Blob"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_small_body_uses_single_put_blob() {
+        use crate::storage::StorageBackend as StorageBackendTrait;
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(header("x-ms-blob-type", "BlockBlob"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut backend = create_cached_rbac_backend_with_endpoint(server.uri());
+        backend.config.upload.single_put_max = 16;
+        StorageBackendTrait::put(
+            &backend,
+            "small/blob.bin",
+            Bytes::from_static(b"sixteen bytes!!!"),
+        )
+        .await
+        .expect("single put should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_put_large_body_shared_key_stages_blocks_with_sas() {
+        use crate::storage::StorageBackend as StorageBackendTrait;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&server)
+            .await;
+
+        let mut config = create_test_config();
+        config.endpoint = Some(server.uri());
+        config.upload = AzureUploadConfig {
+            single_put_max: 4,
+            block_size: 4,
+            concurrency: 2,
+        };
+        let decoded_key = BASE64.decode(config.access_key.as_ref().unwrap()).unwrap();
+        let backend = AzureBackend {
+            config,
+            client: reqwest::Client::new(),
+            auth: AzureAuthMode::SharedKey { decoded_key },
+            path_format: StoragePathFormat::Native,
+        };
+        StorageBackendTrait::put(
+            &backend,
+            "large/blob.bin",
+            Bytes::from_static(b"0123456789"),
+        )
+        .await
+        .expect("shared key block upload should succeed");
+
+        let requests = server.received_requests().await.unwrap_or_default();
+        assert_eq!(requests.len(), 4, "3 blocks + 1 block list");
+        for request in &requests {
+            assert!(
+                request.url.query_pairs().any(|(k, _)| k == "sig"),
+                "shared key block requests must be SAS-authorized"
+            );
+            assert!(!request.headers.contains_key("authorization"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_put_stream_uses_configured_block_size() {
+        use crate::storage::StorageBackend as StorageBackendTrait;
+        use futures::stream;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&server)
+            .await;
+
+        let mut backend = create_cached_rbac_backend_with_endpoint(server.uri());
+        backend.config.upload.block_size = 4;
+        let stream = stream::iter([
+            Ok(Bytes::from_static(b"hello ")),
+            Ok(Bytes::from_static(b"stream")),
+        ]);
+        StorageBackendTrait::put_stream(&backend, "streamed/blob.txt", Box::pin(stream))
+            .await
+            .expect("stream upload should succeed");
+
+        let requests = server.received_requests().await.unwrap_or_default();
+        assert_eq!(staged_block_ids(&requests).len(), 3);
+    }
+
+    #[test]
+    fn test_upload_config_normalized_clamps_to_azure_limits() {
+        let config = AzureUploadConfig {
+            single_put_max: usize::MAX,
+            block_size: 0,
+            concurrency: 0,
+        }
+        .normalized();
+        assert_eq!(config.single_put_max, AZURE_MAX_SINGLE_PUT_SIZE);
+        assert_eq!(config.block_size, 1);
+        assert_eq!(config.concurrency, 1);
+
+        let config = AzureUploadConfig {
+            single_put_max: 1,
+            block_size: usize::MAX,
+            concurrency: 1_000,
+        }
+        .normalized();
+        assert_eq!(config.block_size, AZURE_MAX_BLOCK_SIZE);
+        assert_eq!(config.concurrency, AZURE_MAX_UPLOAD_CONCURRENCY);
+    }
+
     // ── URL construction ─────────────────────────────────────────────────
 
     #[tokio::test]
@@ -2959,7 +4067,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_rbac_get_presigned_url_returns_none() {
+    async fn test_rbac_get_presigned_url_returns_none_when_disabled() {
         let backend = create_rbac_backend(service_principal_cred());
 
         let result = backend
@@ -2968,18 +4076,18 @@ mod tests {
             .unwrap();
         assert!(
             result.is_none(),
-            "RBAC mode should not generate presigned URLs"
+            "RBAC mode should not generate presigned URLs when redirects are disabled"
         );
     }
 
     #[test]
-    fn test_rbac_supports_redirect_false() {
-        // Even if redirect_downloads is true in config, RBAC mode disables it
+    fn test_rbac_supports_redirect_when_enabled() {
         let client = reqwest::Client::new();
         let provider = TokenCredentialProvider {
             client: client.clone(),
             credential: service_principal_cred(),
             cache: RwLock::new(None),
+            delegation_key: RwLock::new(None),
         };
         let mut config = create_rbac_config();
         config.redirect_downloads = true;
@@ -2992,11 +4100,189 @@ mod tests {
             path_format: StoragePathFormat::Native,
         };
         assert!(
-            !backend.supports_redirect(),
-            "RBAC should not support redirect even if config says true"
+            backend.supports_redirect(),
+            "RBAC redirects are signed with a user delegation SAS"
         );
     }
 
+    // ── User delegation SAS ─────────────────────────────────────────────
+
+    fn user_delegation_key_xml() -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><UserDelegationKey><SignedOid>oid-1</SignedOid><SignedTid>tid-1</SignedTid><SignedStart>2026-10-16T00:00:00Z</SignedStart><SignedExpiry>2026-10-17T00:00:00Z</SignedExpiry><SignedService>b</SignedService><SignedVersion>2021-06-08</SignedVersion><Value>{}</Value></UserDelegationKey>"#,
+            BASE64.encode(b"user-delegation-key")
+        )
+    }
+
+    fn test_delegation_key(expires_at: chrono::DateTime<Utc>) -> CachedDelegationKey {
+        let key: UserDelegationKey = quick_xml::de::from_str(&user_delegation_key_xml()).unwrap();
+        CachedDelegationKey {
+            decoded_value: BASE64.decode(&key.value).unwrap(),
+            key,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_user_delegation_key_parses_response() {
+        let key = test_delegation_key(Utc::now()).key;
+        assert_eq!(key.signed_oid, "oid-1");
+        assert_eq!(key.signed_tid, "tid-1");
+        assert_eq!(key.signed_start, "2026-10-16T00:00:00Z");
+        assert_eq!(key.signed_expiry, "2026-10-17T00:00:00Z");
+        assert_eq!(key.signed_service, "b");
+        assert_eq!(key.signed_version, "2021-06-08");
+    }
+
+    #[test]
+    fn test_user_delegation_sas_token_fields() {
+        let backend = create_rbac_backend(service_principal_cred());
+        let now = chrono::DateTime::parse_from_rfc3339("2026-10-16T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let expiry = now + ChronoDuration::hours(1);
+        let delegation_key = test_delegation_key(now + ChronoDuration::hours(12));
+
+        let token = backend
+            .user_delegation_sas_token("a/b.txt", now, expiry, "r", &delegation_key)
+            .unwrap();
+        let params: HashMap<String, String> = token
+            .split('&')
+            .map(|kv| {
+                let (k, v) = kv.split_once('=').unwrap();
+                (k.to_string(), urlencoding::decode(v).unwrap().into_owned())
+            })
+            .collect();
+
+        assert_eq!(params["sv"], "2021-06-08");
+        assert_eq!(params["st"], "2026-10-16T11:45:00Z");
+        assert_eq!(params["se"], "2026-10-16T13:00:00Z");
+        assert_eq!(params["sr"], "b");
+        assert_eq!(params["sp"], "r");
+        assert_eq!(params["spr"], "https");
+        assert_eq!(params["skoid"], "oid-1");
+        assert_eq!(params["sktid"], "tid-1");
+        assert_eq!(params["skt"], "2026-10-16T00:00:00Z");
+        assert_eq!(params["ske"], "2026-10-17T00:00:00Z");
+        assert_eq!(params["sks"], "b");
+        assert_eq!(params["skv"], "2021-06-08");
+        assert!(!params["sig"].is_empty());
+
+        // The signature covers the delegation key, not just the blob path.
+        let other_key = CachedDelegationKey {
+            decoded_value: b"another-key".to_vec(),
+            ..delegation_key.clone()
+        };
+        let other = backend
+            .user_delegation_sas_token("a/b.txt", now, expiry, "r", &other_key)
+            .unwrap();
+        assert_ne!(token, other);
+    }
+
+    #[tokio::test]
+    async fn test_rbac_presigned_url_uses_cached_user_delegation_key() {
+        use wiremock::matchers::{body_string_contains, header, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .and(query_param("restype", "service"))
+            .and(query_param("comp", "userdelegationkey"))
+            .and(header("Authorization", "Bearer cached-test-token"))
+            .and(body_string_contains("<KeyInfo><Start>"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(user_delegation_key_xml()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut backend = create_cached_rbac_backend_with_endpoint(server.uri());
+        backend.config.redirect_downloads = true;
+
+        let first = backend
+            .get_presigned_url("a/b.txt", Duration::from_secs(3600))
+            .await
+            .unwrap()
+            .expect("RBAC redirect URL");
+        assert!(first
+            .url
+            .starts_with(&format!("{}/testcontainer/a/b.txt?", server.uri())));
+        assert!(first.url.contains("skoid=oid-1"));
+        assert!(first.url.contains("sktid=tid-1"));
+        assert_eq!(first.source, PresignedUrlSource::Azure);
+
+        // The second URL reuses the key fetched for the first.
+        backend
+            .get_presigned_url("a/c.txt", Duration::from_secs(3600))
+            .await
+            .unwrap()
+            .expect("RBAC redirect URL");
+    }
+
+    #[tokio::test]
+    async fn test_rbac_user_delegation_key_refreshed_when_sas_outlives_it() {
+        use wiremock::matchers::{method, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(query_param("comp", "userdelegationkey"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(user_delegation_key_xml()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut backend = create_cached_rbac_backend_with_endpoint(server.uri());
+        backend.config.redirect_downloads = true;
+        if let AzureAuthMode::TokenCredential { provider } = &backend.auth {
+            *provider.delegation_key.write().await = Some(test_delegation_key(
+                Utc::now() + ChronoDuration::minutes(10),
+            ));
+        }
+
+        backend
+            .get_presigned_url("a/b.txt", Duration::from_secs(3600))
+            .await
+            .unwrap()
+            .expect("RBAC redirect URL");
+    }
+
+    #[tokio::test]
+    async fn test_rbac_presigned_url_fails_when_key_request_denied() {
+        use wiremock::matchers::{method, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(query_param("comp", "userdelegationkey"))
+            .respond_with(
+                ResponseTemplate::new(403).set_body_string("AuthorizationPermissionMismatch"),
+            )
+            .mount(&server)
+            .await;
+
+        let mut backend = create_cached_rbac_backend_with_endpoint(server.uri());
+        backend.config.redirect_downloads = true;
+
+        let err = backend
+            .get_presigned_url("a/b.txt", Duration::from_secs(3600))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("403"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_rbac_presigned_url_rejects_expiry_beyond_seven_days() {
+        let mut backend =
+            create_cached_rbac_backend_with_endpoint("http://127.0.0.1:1".to_string());
+        backend.config.redirect_downloads = true;
+
+        let result = backend
+            .get_presigned_url("a/b.txt", Duration::from_secs(8 * 24 * 3600))
+            .await;
+        assert!(result.is_err());
+    }
+
     // ── Auth mode enum ──────────────────────────────────────────────────
 
     #[test]
@@ -3019,6 +4305,7 @@ mod tests {
                 access_token: "cached-token-value".to_string(),
                 expires_at: Utc::now() + ChronoDuration::hours(1),
             })),
+            delegation_key: RwLock::new(None),
         };
 
         let token = provider.get_token().await.unwrap();
@@ -3034,6 +4321,7 @@ mod tests {
                 access_token: "expired-token".to_string(),
                 expires_at: Utc::now() - ChronoDuration::hours(1),
             })),
+            delegation_key: RwLock::new(None),
         };
 
         // This will try to reach IMDS and fail since we're not on Azure,
@@ -3051,6 +4339,7 @@ mod tests {
             client: reqwest::Client::new(),
             credential: managed_identity_cred(None),
             cache: RwLock::new(None),
+            delegation_key: RwLock::new(None),
         };
 
         let result = provider.get_token().await;
@@ -3069,6 +4358,7 @@ mod tests {
                 access_token: "old-sp-token".to_string(),
                 expires_at: Utc::now() - ChronoDuration::hours(1),
             })),
+            delegation_key: RwLock::new(None),
         };
 
         // SP token refresh will fail because the credentials are fake,
@@ -3083,6 +4373,7 @@ mod tests {
             client: reqwest::Client::new(),
             credential: service_principal_cred(),
             cache: RwLock::new(None),
+            delegation_key: RwLock::new(None),
         };
 
         let result = provider.get_token().await;
@@ -3100,6 +4391,7 @@ mod tests {
                 access_token: "still-valid".to_string(),
                 expires_at: Utc::now() + ChronoDuration::hours(2),
             })),
+            delegation_key: RwLock::new(None),
         };
 
         // Two concurrent calls should both succeed with the cached value.
@@ -3117,6 +4409,7 @@ mod tests {
             client: client.clone(),
             credential: service_principal_cred(),
             cache: RwLock::new(None),
+            delegation_key: RwLock::new(None),
         };
         let mut config = create_rbac_config();
         config.endpoint = Some("https://gov.blob.core.usgovcloudapi.net".to_string());
@@ -3142,6 +4435,7 @@ mod tests {
             client: client.clone(),
             credential: service_principal_cred(),
             cache: RwLock::new(None),
+            delegation_key: RwLock::new(None),
         };
         let backend = AzureBackend {
             config: create_rbac_config(),
@@ -0,0 +1,218 @@
+//! Shared content-addressed namespace for filesystem repositories.
+//!
+//! Cloud backends (S3/GCS/Azure) already share one object namespace across
+//! every repository on the bucket, so a [`cas_storage_key`] written through
+//! any repository's handle is the same physical object. Filesystem
+//! repositories are each rooted at their own `storage_path`, so the dedup
+//! layer needs one shared root for the `cas/sha256/` keys: [`CasRoutedStorage`]
+//! wraps a repository's handle and sends CAS keys to that shared root while
+//! every other key stays in the repository's own tree.
+//!
+//! [`cas_storage_key`]: super::keys::cas_storage_key
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+
+use super::keys::is_cas_storage_key;
+use super::{DirectUploadStore, PresignedUrl, PutStreamResult, StorageBackend};
+use crate::error::Result;
+
+/// A repository storage handle whose `cas/sha256/` keys live in a shared
+/// content-addressed store.
+pub struct CasRoutedStorage {
+    repo: Arc<dyn StorageBackend>,
+    cas: Arc<dyn StorageBackend>,
+}
+
+impl CasRoutedStorage {
+    pub fn new(repo: Arc<dyn StorageBackend>, cas: Arc<dyn StorageBackend>) -> Self {
+        Self { repo, cas }
+    }
+
+    fn route(&self, key: &str) -> &dyn StorageBackend {
+        if is_cas_storage_key(key) {
+            self.cas.as_ref()
+        } else {
+            self.repo.as_ref()
+        }
+    }
+}
+
+#[async_trait]
+impl StorageBackend for CasRoutedStorage {
+    async fn put(&self, key: &str, content: Bytes) -> Result<()> {
+        self.route(key).put(key, content).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        self.route(key).get(key).await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        self.route(key).exists(key).await
+    }
+
+    async fn head_etag(&self, key: &str) -> Result<Option<String>> {
+        self.route(key).head_etag(key).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.route(key).delete(key).await
+    }
+
+    async fn copy(&self, source: &str, dest: &str) -> Result<()> {
+        let target = self.route(dest);
+        if target.copy_from(self.route(source), source, dest).await? {
+            return Ok(());
+        }
+        let stream = self.get_stream(source).await?;
+        target.put_stream(dest, stream).await.map(|_| ())
+    }
+
+    async fn copy_from(
+        &self,
+        source: &dyn StorageBackend,
+        source_key: &str,
+        dest_key: &str,
+    ) -> Result<bool> {
+        self.route(dest_key)
+            .copy_from(source, source_key, dest_key)
+            .await
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        self.route(key).local_path(key)
+    }
+
+    fn supports_redirect(&self) -> bool {
+        self.repo.supports_redirect()
+    }
+
+    async fn get_presigned_url(
+        &self,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<PresignedUrl>> {
+        self.route(key).get_presigned_url(key, expires_in).await
+    }
+
+    fn direct_uploads(&self) -> Option<&dyn DirectUploadStore> {
+        self.repo.direct_uploads()
+    }
+
+    async fn put_file(&self, key: &str, path: &std::path::Path) -> Result<()> {
+        self.route(key).put_file(key, path).await
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<BoxStream<'static, Result<Bytes>>> {
+        self.route(key).get_stream(key).await
+    }
+
+    async fn get_range(&self, key: &str, offset: u64, length: usize) -> Result<Bytes> {
+        self.route(key).get_range(key, offset, length).await
+    }
+
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        self.route(key).get_range_stream(key, offset, length).await
+    }
+
+    async fn put_stream(
+        &self,
+        key: &str,
+        stream: BoxStream<'static, Result<Bytes>>,
+    ) -> Result<PutStreamResult> {
+        self.route(key).put_stream(key, stream).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.repo.health_check().await?;
+        self.cas.health_check().await
+    }
+
+    fn supports_retention(&self) -> bool {
+        self.repo.supports_retention() && self.cas.supports_retention()
+    }
+
+    async fn set_retention(&self, key: &str, until: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        self.route(key).set_retention(key, until).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::filesystem::FilesystemStorage;
+    use crate::storage::keys::cas_storage_key;
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn routed(repo_root: &std::path::Path, cas_root: &std::path::Path) -> CasRoutedStorage {
+        CasRoutedStorage::new(
+            Arc::new(FilesystemStorage::new(repo_root.to_str().unwrap())),
+            Arc::new(FilesystemStorage::new(cas_root.to_str().unwrap())),
+        )
+    }
+
+    #[tokio::test]
+    async fn cas_keys_land_in_the_shared_root() {
+        let repo_a = tempfile::TempDir::new().unwrap();
+        let repo_b = tempfile::TempDir::new().unwrap();
+        let cas = tempfile::TempDir::new().unwrap();
+        let a = routed(repo_a.path(), cas.path());
+        let b = routed(repo_b.path(), cas.path());
+        let key = cas_storage_key(SHA);
+
+        a.put(&key, Bytes::from_static(b"shared")).await.unwrap();
+
+        // Written once, visible through every repository's handle.
+        assert_eq!(b.get(&key).await.unwrap(), Bytes::from_static(b"shared"));
+        assert!(cas.path().join(&key).is_file());
+        assert!(!repo_a.path().join(&key).exists());
+    }
+
+    #[tokio::test]
+    async fn other_keys_stay_in_the_repository_tree() {
+        let repo_a = tempfile::TempDir::new().unwrap();
+        let repo_b = tempfile::TempDir::new().unwrap();
+        let cas = tempfile::TempDir::new().unwrap();
+        let a = routed(repo_a.path(), cas.path());
+        let b = routed(repo_b.path(), cas.path());
+
+        a.put("maven/com/example/lib.jar", Bytes::from_static(b"jar"))
+            .await
+            .unwrap();
+
+        assert!(repo_a.path().join("maven/com/example/lib.jar").is_file());
+        assert!(!b.exists("maven/com/example/lib.jar").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn copy_moves_legacy_content_into_the_shared_root() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let cas = tempfile::TempDir::new().unwrap();
+        let storage = routed(repo.path(), cas.path());
+        let legacy = format!("{}/{}/{}", &SHA[..2], &SHA[2..4], SHA);
+        let key = cas_storage_key(SHA);
+
+        storage
+            .put(&legacy, Bytes::from_static(b"payload"))
+            .await
+            .unwrap();
+        storage.copy(&legacy, &key).await.unwrap();
+
+        assert_eq!(
+            tokio::fs::read(cas.path().join(&key)).await.unwrap(),
+            b"payload"
+        );
+    }
+}
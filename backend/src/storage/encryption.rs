@@ -0,0 +1,1026 @@
+//! Client-side envelope encryption for storage backends
+//! (`STORAGE_ENCRYPTION`).
+//!
+//! [`EncryptedStorage`] wraps any [`StorageBackend`] so object bodies are
+//! encrypted before they leave the process. Every object gets its own random
+//! 256-bit data key (DEK); the DEK is wrapped by a key-encryption key (KEK)
+//! held by a [`KeyWrapper`] — a local master key or a cloud KMS (see
+//! [`super::kms`]) — and the wrapped DEK travels in the object header, so an
+//! object can always be decrypted on its own.
+//!
+//! Object layout:
+//!
+//! ```text
+//! magic (8) | version (1) | segment size (u32) | nonce prefix (7)
+//! | key id length (u16) | key id | wrapped DEK length (u16) | wrapped DEK
+//! | segment 0 | segment 1 | ... | final segment
+//! ```
+//!
+//! The body is split into fixed-size segments, each sealed with AES-256-GCM
+//! under the nonce `prefix || segment index || final flag` and the fixed
+//! header fields as associated data (the STREAM construction). Streams and
+//! ranged reads therefore decrypt segment by segment without buffering the
+//! object, and truncation or reordering fails authentication. The key id and
+//! wrapped DEK are outside the associated data so a key rotation can rewrap
+//! an object's DEK without re-encrypting its body.
+//!
+//! Objects written before encryption was enabled have no header and are read
+//! through unchanged while `STORAGE_ENCRYPTION_ALLOW_PLAINTEXT` is on (the
+//! default); the migration job (`storage_encryption_service`) encrypts them
+//! and rewraps objects still under a retired key.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use super::{PutStreamResult, StorageBackend};
+use crate::error::{AppError, Result};
+
+/// First bytes of every encrypted object.
+pub const ENCRYPTED_OBJECT_MAGIC: [u8; 8] = *b"\x89AKENC\r\n";
+
+const FORMAT_VERSION: u8 = 1;
+
+/// Plaintext bytes per sealed segment.
+const SEGMENT_SIZE: usize = 64 * 1024;
+
+const TAG_LEN: usize = 16;
+const NONCE_PREFIX_LEN: usize = 7;
+
+/// Magic, version, segment size and nonce prefix: the authenticated part of
+/// the header.
+const FIXED_HEADER_LEN: usize = 8 + 1 + 4 + NONCE_PREFIX_LEN;
+
+/// Bytes read to find an object's header on a ranged read. Key ids and
+/// wrapped DEKs from every supported provider fit well within this.
+const HEADER_PROBE_LEN: usize = 8 * 1024;
+
+/// Unwrapped data keys are cached so reads do not call the KMS per request.
+const DEK_CACHE_CAPACITY: u64 = 10_000;
+const DEK_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// A DEK wrapped by a key-encryption key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    /// Identifies the KEK that wrapped the DEK, prefixed with the provider
+    /// (`local:`, `aws-kms:`, `azure-keyvault:`).
+    pub key_id: String,
+    pub ciphertext: Vec<u8>,
+}
+
+/// Wraps and unwraps data keys with a key-encryption key.
+#[async_trait]
+pub trait KeyWrapper: Send + Sync {
+    /// Provider name for status output (`local`, `aws-kms`, `azure-keyvault`).
+    fn provider(&self) -> &'static str;
+
+    /// Id of the KEK new objects are wrapped with.
+    async fn active_key_id(&self) -> Result<String>;
+
+    /// Wrap `dek` with the active KEK.
+    async fn wrap(&self, dek: &[u8]) -> Result<WrappedKey>;
+
+    /// Unwrap a DEK wrapped by the KEK `key_id`, which may be a retired one.
+    async fn unwrap(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// The header at the start of an encrypted object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    pub segment_size: u32,
+    pub nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    pub key_id: String,
+    pub wrapped_key: Vec<u8>,
+}
+
+/// Outcome of parsing the start of an object.
+#[derive(Debug, PartialEq, Eq)]
+pub enum HeaderParse {
+    /// The object does not start with the magic: it was stored unencrypted.
+    Plaintext,
+    /// More bytes are needed to decide.
+    Incomplete,
+    /// A header of the given encoded length.
+    Complete(EnvelopeHeader, usize),
+}
+
+impl EnvelopeHeader {
+    fn fixed_part(&self) -> [u8; FIXED_HEADER_LEN] {
+        let mut out = [0u8; FIXED_HEADER_LEN];
+        out[..8].copy_from_slice(&ENCRYPTED_OBJECT_MAGIC);
+        out[8] = FORMAT_VERSION;
+        out[9..13].copy_from_slice(&self.segment_size.to_be_bytes());
+        out[13..].copy_from_slice(&self.nonce_prefix);
+        out
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let key_id_len = u16::try_from(self.key_id.len())
+            .map_err(|_| AppError::Storage("encryption key id is too long".to_string()))?;
+        let wrapped_len = u16::try_from(self.wrapped_key.len())
+            .map_err(|_| AppError::Storage("wrapped data key is too long".to_string()))?;
+        let mut out = Vec::with_capacity(self.encoded_len());
+        out.extend_from_slice(&self.fixed_part());
+        out.extend_from_slice(&key_id_len.to_be_bytes());
+        out.extend_from_slice(self.key_id.as_bytes());
+        out.extend_from_slice(&wrapped_len.to_be_bytes());
+        out.extend_from_slice(&self.wrapped_key);
+        Ok(out)
+    }
+
+    pub fn encoded_len(&self) -> usize {
+        FIXED_HEADER_LEN + 2 + self.key_id.len() + 2 + self.wrapped_key.len()
+    }
+
+    /// Parse the header from the first bytes of an object.
+    pub fn parse(bytes: &[u8]) -> Result<HeaderParse> {
+        let magic_len = bytes.len().min(ENCRYPTED_OBJECT_MAGIC.len());
+        if bytes[..magic_len] != ENCRYPTED_OBJECT_MAGIC[..magic_len] {
+            return Ok(HeaderParse::Plaintext);
+        }
+        if bytes.len() < FIXED_HEADER_LEN + 2 {
+            return Ok(HeaderParse::Incomplete);
+        }
+        if bytes[8] != FORMAT_VERSION {
+            return Err(AppError::Storage(format!(
+                "unsupported encrypted object version {}",
+                bytes[8]
+            )));
+        }
+        let segment_size = u32::from_be_bytes(bytes[9..13].try_into().unwrap());
+        if segment_size == 0 {
+            return Err(AppError::Storage(
+                "encrypted object has a zero segment size".to_string(),
+            ));
+        }
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+        nonce_prefix.copy_from_slice(&bytes[13..FIXED_HEADER_LEN]);
+
+        let mut pos = FIXED_HEADER_LEN;
+        let key_id_len = u16::from_be_bytes([bytes[pos], bytes[pos + 1]]) as usize;
+        pos += 2;
+        if bytes.len() < pos + key_id_len + 2 {
+            return Ok(HeaderParse::Incomplete);
+        }
+        let key_id = std::str::from_utf8(&bytes[pos..pos + key_id_len])
+            .map_err(|_| AppError::Storage("encryption key id is not UTF-8".to_string()))?
+            .to_string();
+        pos += key_id_len;
+        let wrapped_len = u16::from_be_bytes([bytes[pos], bytes[pos + 1]]) as usize;
+        pos += 2;
+        if bytes.len() < pos + wrapped_len {
+            return Ok(HeaderParse::Incomplete);
+        }
+        let wrapped_key = bytes[pos..pos + wrapped_len].to_vec();
+        pos += wrapped_len;
+
+        Ok(HeaderParse::Complete(
+            Self {
+                segment_size,
+                nonce_prefix,
+                key_id,
+                wrapped_key,
+            },
+            pos,
+        ))
+    }
+}
+
+/// Seals and opens the segments of one object.
+struct SegmentCipher {
+    cipher: Arc<Aes256Gcm>,
+    nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    aad: [u8; FIXED_HEADER_LEN],
+}
+
+impl SegmentCipher {
+    fn nonce(&self, index: u32, last: bool) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..NONCE_PREFIX_LEN].copy_from_slice(&self.nonce_prefix);
+        nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&index.to_be_bytes());
+        nonce[11] = u8::from(last);
+        nonce
+    }
+
+    fn seal(&self, index: u32, last: bool, plaintext: &[u8]) -> Result<Bytes> {
+        let nonce = self.nonce(index, last);
+        self.cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &self.aad,
+                },
+            )
+            .map(Bytes::from)
+            .map_err(|_| AppError::Storage("failed to encrypt object segment".to_string()))
+    }
+
+    fn open(&self, index: u32, last: bool, ciphertext: &[u8]) -> Result<Bytes> {
+        let nonce = self.nonce(index, last);
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &self.aad,
+                },
+            )
+            .map(Bytes::from)
+            .map_err(|_| {
+                AppError::Storage(
+                    "encrypted object failed authentication (corrupted, truncated or wrong key)"
+                        .to_string(),
+                )
+            })
+    }
+}
+
+fn segment_index(index: usize) -> Result<u32> {
+    u32::try_from(index)
+        .map_err(|_| AppError::Storage("object is too large to encrypt".to_string()))
+}
+
+/// Whether an object is encrypted, and under which key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectEncryptionState {
+    Plaintext,
+    /// Encrypted under the active KEK.
+    Current,
+    /// Encrypted under a retired KEK.
+    Stale {
+        key_id: String,
+    },
+}
+
+/// The data-key lifecycle shared by every encrypted storage handle.
+pub struct EnvelopeEncryption {
+    wrapper: Arc<dyn KeyWrapper>,
+    allow_plaintext: bool,
+    dek_cache: moka::future::Cache<Vec<u8>, Arc<Aes256Gcm>>,
+}
+
+impl EnvelopeEncryption {
+    pub fn new(wrapper: Arc<dyn KeyWrapper>, allow_plaintext: bool) -> Self {
+        Self {
+            wrapper,
+            allow_plaintext,
+            dek_cache: moka::future::Cache::builder()
+                .max_capacity(DEK_CACHE_CAPACITY)
+                .time_to_live(DEK_CACHE_TTL)
+                .build(),
+        }
+    }
+
+    /// Build from `STORAGE_ENCRYPTION` and the provider's variables, or
+    /// `None` when encryption is off.
+    pub fn from_env() -> Result<Option<Arc<Self>>> {
+        let Some(wrapper) = super::kms::key_wrapper_from_env()? else {
+            return Ok(None);
+        };
+        let allow_plaintext = !matches!(
+            std::env::var("STORAGE_ENCRYPTION_ALLOW_PLAINTEXT")
+                .map(|v| v.trim().to_lowercase())
+                .as_deref(),
+            Ok("false" | "0")
+        );
+        Ok(Some(Arc::new(Self::new(wrapper, allow_plaintext))))
+    }
+
+    pub fn provider(&self) -> &'static str {
+        self.wrapper.provider()
+    }
+
+    /// Whether objects without an encryption header may still be read.
+    pub fn allow_plaintext(&self) -> bool {
+        self.allow_plaintext
+    }
+
+    pub async fn active_key_id(&self) -> Result<String> {
+        self.wrapper.active_key_id().await
+    }
+
+    /// Wrap `inner` so everything written through it is encrypted.
+    pub fn wrap_backend(
+        self: &Arc<Self>,
+        inner: Arc<dyn StorageBackend>,
+    ) -> Arc<dyn StorageBackend> {
+        Arc::new(EncryptedStorage::new(inner, self.clone()))
+    }
+
+    /// A fresh DEK, wrapped, for a new object.
+    async fn new_object(&self) -> Result<(EnvelopeHeader, SegmentCipher)> {
+        let mut dek = Zeroizing::new([0u8; 32]);
+        rand::rng().fill_bytes(dek.as_mut());
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+        rand::rng().fill_bytes(&mut nonce_prefix);
+        let wrapped = self.wrapper.wrap(dek.as_ref()).await?;
+        let header = EnvelopeHeader {
+            segment_size: SEGMENT_SIZE as u32,
+            nonce_prefix,
+            key_id: wrapped.key_id,
+            wrapped_key: wrapped.ciphertext,
+        };
+        let cipher = Arc::new(
+            Aes256Gcm::new_from_slice(dek.as_ref())
+                .map_err(|_| AppError::Storage("invalid data key".to_string()))?,
+        );
+        let segments = SegmentCipher {
+            cipher,
+            nonce_prefix,
+            aad: header.fixed_part(),
+        };
+        Ok((header, segments))
+    }
+
+    /// The segment cipher for an existing object, unwrapping its DEK through
+    /// the cache.
+    async fn open_object(&self, header: &EnvelopeHeader) -> Result<SegmentCipher> {
+        let mut cache_key = header.key_id.as_bytes().to_vec();
+        cache_key.push(0);
+        cache_key.extend_from_slice(&header.wrapped_key);
+        let cipher = match self.dek_cache.get(&cache_key).await {
+            Some(cipher) => cipher,
+            None => {
+                let dek = Zeroizing::new(
+                    self.wrapper
+                        .unwrap(&header.key_id, &header.wrapped_key)
+                        .await?,
+                );
+                let cipher = Arc::new(Aes256Gcm::new_from_slice(&dek).map_err(|_| {
+                    AppError::Storage("unwrapped data key has the wrong length".to_string())
+                })?);
+                self.dek_cache.insert(cache_key, cipher.clone()).await;
+                cipher
+            }
+        };
+        Ok(SegmentCipher {
+            cipher,
+            nonce_prefix: header.nonce_prefix,
+            aad: header.fixed_part(),
+        })
+    }
+
+    fn check_plaintext_allowed(&self) -> Result<()> {
+        if self.allow_plaintext {
+            Ok(())
+        } else {
+            Err(AppError::Storage(
+                "object is not encrypted and STORAGE_ENCRYPTION_ALLOW_PLAINTEXT is off".to_string(),
+            ))
+        }
+    }
+
+    /// Read the header of `key` straight from an unwrapped backend.
+    pub async fn read_header(
+        &self,
+        raw: &dyn StorageBackend,
+        key: &str,
+    ) -> Result<Option<(EnvelopeHeader, usize)>> {
+        let probe = raw.get_range(key, 0, HEADER_PROBE_LEN).await?;
+        match EnvelopeHeader::parse(&probe)? {
+            HeaderParse::Complete(header, len) => Ok(Some((header, len))),
+            HeaderParse::Plaintext => Ok(None),
+            // Shorter than the magic: a tiny plaintext object.
+            HeaderParse::Incomplete if probe.len() < ENCRYPTED_OBJECT_MAGIC.len() => Ok(None),
+            HeaderParse::Incomplete => Err(AppError::Storage(format!(
+                "encrypted object {key} has a truncated header"
+            ))),
+        }
+    }
+
+    /// Classify `key` on an unwrapped backend.
+    pub async fn object_state(
+        &self,
+        raw: &dyn StorageBackend,
+        key: &str,
+    ) -> Result<ObjectEncryptionState> {
+        let Some((header, _)) = self.read_header(raw, key).await? else {
+            return Ok(ObjectEncryptionState::Plaintext);
+        };
+        if header.key_id == self.active_key_id().await? {
+            Ok(ObjectEncryptionState::Current)
+        } else {
+            Ok(ObjectEncryptionState::Stale {
+                key_id: header.key_id,
+            })
+        }
+    }
+
+    /// Plaintext length of `key`, given the length stored on the backend.
+    pub async fn plaintext_len(
+        &self,
+        raw: &dyn StorageBackend,
+        key: &str,
+        stored_len: u64,
+    ) -> Result<u64> {
+        let Some((header, header_len)) = self.read_header(raw, key).await? else {
+            return Ok(stored_len);
+        };
+        let body = stored_len.saturating_sub(header_len as u64);
+        let full = header.segment_size as u64 + TAG_LEN as u64;
+        let segments = body.div_ceil(full).max(1);
+        Ok(body.saturating_sub(segments * TAG_LEN as u64))
+    }
+
+    /// Encrypt a plaintext object in place. The backend replaces the object
+    /// atomically, so readers see either the old or the new body.
+    pub async fn encrypt_object(
+        self: &Arc<Self>,
+        raw: Arc<dyn StorageBackend>,
+        key: &str,
+    ) -> Result<()> {
+        let plaintext = raw.get_stream(key).await?;
+        EncryptedStorage::new(raw, self.clone())
+            .put_stream(key, plaintext)
+            .await
+            .map(|_| ())
+    }
+
+    /// Rewrap an object's DEK with the active KEK. The body is copied
+    /// unchanged behind the new header; nothing is re-encrypted.
+    pub async fn rewrap_object(&self, raw: &dyn StorageBackend, key: &str) -> Result<()> {
+        let Some((header, header_len)) = self.read_header(raw, key).await? else {
+            return Err(AppError::Storage(format!(
+                "object {key} is not encrypted; encrypt it instead of rewrapping"
+            )));
+        };
+        let dek = Zeroizing::new(
+            self.wrapper
+                .unwrap(&header.key_id, &header.wrapped_key)
+                .await?,
+        );
+        let wrapped = self.wrapper.wrap(&dek).await?;
+        let new_header = EnvelopeHeader {
+            key_id: wrapped.key_id,
+            wrapped_key: wrapped.ciphertext,
+            ..header
+        }
+        .encode()?;
+
+        let mut body = raw.get_stream(key).await?;
+        let stream: BoxStream<'static, Result<Bytes>> = Box::pin(async_stream::try_stream! {
+            yield Bytes::from(new_header);
+            let mut skip = header_len;
+            while let Some(chunk) = body.next().await {
+                let chunk = chunk?;
+                if skip >= chunk.len() {
+                    skip -= chunk.len();
+                    continue;
+                }
+                yield chunk.slice(skip..);
+                skip = 0;
+            }
+        });
+        raw.put_stream(key, stream).await.map(|_| ())
+    }
+
+    async fn encrypt_all(&self, content: &[u8]) -> Result<Bytes> {
+        let (header, cipher) = self.new_object().await?;
+        let segments = content.len().div_ceil(SEGMENT_SIZE).max(1);
+        let mut out =
+            BytesMut::with_capacity(header.encoded_len() + content.len() + segments * TAG_LEN);
+        out.extend_from_slice(&header.encode()?);
+        for index in 0..segments {
+            let start = index * SEGMENT_SIZE;
+            let end = (start + SEGMENT_SIZE).min(content.len());
+            let last = index + 1 == segments;
+            out.extend_from_slice(&cipher.seal(
+                segment_index(index)?,
+                last,
+                &content[start..end],
+            )?);
+        }
+        Ok(out.freeze())
+    }
+
+    async fn decrypt_all(&self, raw: Bytes) -> Result<Bytes> {
+        let (header, header_len) = match EnvelopeHeader::parse(&raw)? {
+            HeaderParse::Complete(header, len) => (header, len),
+            HeaderParse::Incomplete if raw.len() >= ENCRYPTED_OBJECT_MAGIC.len() => {
+                return Err(AppError::Storage(
+                    "encrypted object has a truncated header".to_string(),
+                ))
+            }
+            HeaderParse::Plaintext | HeaderParse::Incomplete => {
+                self.check_plaintext_allowed()?;
+                return Ok(raw);
+            }
+        };
+        let cipher = self.open_object(&header).await?;
+        let full = header.segment_size as usize + TAG_LEN;
+        let body = &raw[header_len..];
+        let segments = body.len().div_ceil(full).max(1);
+        let mut out = BytesMut::with_capacity(body.len());
+        for index in 0..segments {
+            let start = index * full;
+            let end = (start + full).min(body.len());
+            let last = index + 1 == segments;
+            out.extend_from_slice(&cipher.open(segment_index(index)?, last, &body[start..end])?);
+        }
+        Ok(out.freeze())
+    }
+}
+
+/// A storage handle that encrypts on write and decrypts on read.
+///
+/// Presigned downloads and direct uploads are disabled: both would move
+/// bytes between the client and the object store without passing through
+/// the cipher.
+pub struct EncryptedStorage {
+    inner: Arc<dyn StorageBackend>,
+    encryption: Arc<EnvelopeEncryption>,
+}
+
+impl EncryptedStorage {
+    pub fn new(inner: Arc<dyn StorageBackend>, encryption: Arc<EnvelopeEncryption>) -> Self {
+        Self { inner, encryption }
+    }
+}
+
+#[async_trait]
+impl StorageBackend for EncryptedStorage {
+    async fn put(&self, key: &str, content: Bytes) -> Result<()> {
+        let sealed = self.encryption.encrypt_all(&content).await?;
+        self.inner.put(key, sealed).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        let raw = self.inner.get(key).await?;
+        self.encryption.decrypt_all(raw).await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        self.inner.exists(key).await
+    }
+
+    async fn head_etag(&self, key: &str) -> Result<Option<String>> {
+        self.inner.head_etag(key).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.inner.delete(key).await
+    }
+
+    /// Encrypted objects are self-contained, so a same-backend copy moves
+    /// the ciphertext as is.
+    async fn copy(&self, source: &str, dest: &str) -> Result<()> {
+        self.inner.copy(source, dest).await
+    }
+
+    async fn copy_from(
+        &self,
+        source: &dyn StorageBackend,
+        source_key: &str,
+        dest_key: &str,
+    ) -> Result<bool> {
+        self.inner.copy_from(source, source_key, dest_key).await
+    }
+
+    fn local_path(&self, key: &str) -> Option<std::path::PathBuf> {
+        self.inner.local_path(key)
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<BoxStream<'static, Result<Bytes>>> {
+        let mut raw = self.inner.get_stream(key).await?;
+        let encryption = self.encryption.clone();
+        Ok(Box::pin(async_stream::try_stream! {
+            let mut buf = BytesMut::new();
+            let mut opened = None;
+            loop {
+                match EnvelopeHeader::parse(&buf)? {
+                    HeaderParse::Complete(header, len) => {
+                        let _ = buf.split_to(len);
+                        let cipher = encryption.open_object(&header).await?;
+                        opened = Some((cipher, header.segment_size as usize + TAG_LEN));
+                        break;
+                    }
+                    HeaderParse::Plaintext => break,
+                    HeaderParse::Incomplete => match raw.next().await {
+                        Some(chunk) => buf.extend_from_slice(&chunk?),
+                        None if buf.len() < ENCRYPTED_OBJECT_MAGIC.len() => break,
+                        None => Err::<(), _>(AppError::Storage(
+                            "encrypted object has a truncated header".to_string(),
+                        ))?,
+                    },
+                }
+            }
+
+            match opened {
+                None => {
+                    encryption.check_plaintext_allowed()?;
+                    if !buf.is_empty() {
+                        yield buf.split().freeze();
+                    }
+                    while let Some(chunk) = raw.next().await {
+                        yield chunk?;
+                    }
+                }
+                Some((cipher, full)) => {
+                    let mut index = 0usize;
+                    loop {
+                        // A full segment is only known not to be the final
+                        // one once at least one more byte follows it.
+                        while buf.len() > full {
+                            let sealed = buf.split_to(full);
+                            yield cipher.open(segment_index(index)?, false, &sealed)?;
+                            index += 1;
+                        }
+                        match raw.next().await {
+                            Some(chunk) => buf.extend_from_slice(&chunk?),
+                            None => break,
+                        }
+                    }
+                    yield cipher.open(segment_index(index)?, true, &buf)?;
+                }
+            }
+        }))
+    }
+
+    async fn get_range(&self, key: &str, offset: u64, length: usize) -> Result<Bytes> {
+        if length == 0 {
+            return Ok(Bytes::new());
+        }
+        let Some((header, header_len)) = self
+            .encryption
+            .read_header(self.inner.as_ref(), key)
+            .await?
+        else {
+            self.encryption.check_plaintext_allowed()?;
+            return self.inner.get_range(key, offset, length).await;
+        };
+        let cipher = self.encryption.open_object(&header).await?;
+        let segment = header.segment_size as u64;
+        let full = segment + TAG_LEN as u64;
+        let first = offset / segment;
+        let last = (offset + length as u64 - 1) / segment;
+        let sealed = self
+            .inner
+            .get_range(
+                key,
+                header_len as u64 + first * full,
+                usize::try_from((last - first + 1) * full)
+                    .map_err(|_| AppError::Storage("requested range is too large".to_string()))?,
+            )
+            .await?;
+
+        let mut plaintext = BytesMut::new();
+        for (i, chunk) in sealed.chunks(full as usize).enumerate() {
+            let index = segment_index(first as usize + i)?;
+            // A short chunk is the final segment; a full one at the end of
+            // the window may be final too when the object ends on a segment
+            // boundary.
+            let opened = if (chunk.len() as u64) < full {
+                cipher.open(index, true, chunk)?
+            } else {
+                match cipher.open(index, false, chunk) {
+                    Ok(opened) => opened,
+                    Err(_) => cipher.open(index, true, chunk)?,
+                }
+            };
+            plaintext.extend_from_slice(&opened);
+        }
+        let skip = (offset - first * segment) as usize;
+        if skip >= plaintext.len() {
+            return Ok(Bytes::new());
+        }
+        let end = (skip + length).min(plaintext.len());
+        Ok(plaintext.freeze().slice(skip..end))
+    }
+
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        if length == 0 {
+            return Ok(Box::pin(futures::stream::empty()));
+        }
+        let Some((header, header_len)) = self
+            .encryption
+            .read_header(self.inner.as_ref(), key)
+            .await?
+        else {
+            self.encryption.check_plaintext_allowed()?;
+            return self.inner.get_range_stream(key, offset, length).await;
+        };
+        // Read only the sealed segments covering the window, then trim the
+        // plaintext to it.
+        let cipher = self.encryption.open_object(&header).await?;
+        let segment = header.segment_size as u64;
+        let full = segment + TAG_LEN as u64;
+        let first = offset / segment;
+        let last = (offset + length - 1) / segment;
+        let mut raw = self
+            .inner
+            .get_range_stream(
+                key,
+                header_len as u64 + first * full,
+                (last - first + 1) * full,
+            )
+            .await?;
+        let full = full as usize;
+        let opened: BoxStream<'static, Result<Bytes>> = Box::pin(async_stream::try_stream! {
+            let mut buf = BytesMut::new();
+            let mut index = first as usize;
+            loop {
+                while buf.len() > full {
+                    let sealed = buf.split_to(full);
+                    yield cipher.open(segment_index(index)?, false, &sealed)?;
+                    index += 1;
+                }
+                match raw.next().await {
+                    Some(chunk) => buf.extend_from_slice(&chunk?),
+                    None => break,
+                }
+            }
+            if !buf.is_empty() {
+                // As in `get_range`: a full segment at the end of the window
+                // may also be the object's final one.
+                let index = segment_index(index)?;
+                let opened = if buf.len() < full {
+                    cipher.open(index, true, &buf)?
+                } else {
+                    match cipher.open(index, false, &buf) {
+                        Ok(opened) => opened,
+                        Err(_) => cipher.open(index, true, &buf)?,
+                    }
+                };
+                yield opened;
+            }
+        });
+        Ok(super::slice_stream(
+            opened,
+            offset - first * segment,
+            length,
+        ))
+    }
+
+    async fn put_stream(
+        &self,
+        key: &str,
+        stream: BoxStream<'static, Result<Bytes>>,
+    ) -> Result<PutStreamResult> {
+        let (header, cipher) = self.encryption.new_object().await?;
+        let header = header.encode()?;
+        // The checksum callers record is of the plaintext, so hash it on the
+        // way into the cipher rather than trusting the inner backend's.
+        let digest = Arc::new(Mutex::new((Sha256::new(), 0u64)));
+        let tally = digest.clone();
+        let mut plaintext = stream;
+        let sealed: BoxStream<'static, Result<Bytes>> = Box::pin(async_stream::try_stream! {
+            yield Bytes::from(header);
+            let mut buf = BytesMut::new();
+            let mut index = 0usize;
+            while let Some(chunk) = plaintext.next().await {
+                let chunk = chunk?;
+                {
+                    let mut tally = tally.lock().unwrap();
+                    tally.0.update(&chunk);
+                    tally.1 += chunk.len() as u64;
+                }
+                buf.extend_from_slice(&chunk);
+                while buf.len() > SEGMENT_SIZE {
+                    let segment = buf.split_to(SEGMENT_SIZE);
+                    yield cipher.seal(segment_index(index)?, false, &segment)?;
+                    index += 1;
+                }
+            }
+            yield cipher.seal(segment_index(index)?, true, &buf)?;
+        });
+        self.inner.put_stream(key, sealed).await?;
+
+        let (hasher, bytes_written) = std::mem::take(&mut *digest.lock().unwrap());
+        Ok(PutStreamResult {
+            checksum_sha256: format!("{:x}", hasher.finalize()),
+            bytes_written,
+        })
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+
+    fn supports_retention(&self) -> bool {
+        self.inner.supports_retention()
+    }
+
+    async fn set_retention(&self, key: &str, until: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        self.inner.set_retention(key, until).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::filesystem::FilesystemStorage;
+    use crate::storage::kms::LocalKeyWrapper;
+
+    fn encryption(keys: &[(&str, u8)], active: &str) -> Arc<EnvelopeEncryption> {
+        let keys = keys
+            .iter()
+            .map(|(id, byte)| (id.to_string(), [*byte; 32]))
+            .collect();
+        Arc::new(EnvelopeEncryption::new(
+            Arc::new(LocalKeyWrapper::new(keys, active.to_string()).unwrap()),
+            true,
+        ))
+    }
+
+    fn storage(
+        dir: &tempfile::TempDir,
+        encryption: &Arc<EnvelopeEncryption>,
+    ) -> (Arc<dyn StorageBackend>, Arc<dyn StorageBackend>) {
+        let raw: Arc<dyn StorageBackend> =
+            Arc::new(FilesystemStorage::new(dir.path().to_str().unwrap()));
+        (raw.clone(), encryption.wrap_backend(raw))
+    }
+
+    fn body(len: usize) -> Bytes {
+        Bytes::from((0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>())
+    }
+
+    fn chunked(content: &Bytes, size: usize) -> BoxStream<'static, Result<Bytes>> {
+        let chunks: Vec<Result<Bytes>> = content
+            .chunks(size)
+            .map(|c| Ok(Bytes::copy_from_slice(c)))
+            .collect();
+        Box::pin(futures::stream::iter(chunks))
+    }
+
+    async fn collect(stream: BoxStream<'static, Result<Bytes>>) -> Result<Bytes> {
+        let chunks: Vec<Result<Bytes>> = stream.collect().await;
+        let mut out = BytesMut::new();
+        for chunk in chunks {
+            out.extend_from_slice(&chunk?);
+        }
+        Ok(out.freeze())
+    }
+
+    #[tokio::test]
+    async fn round_trips_across_segment_boundaries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let enc = encryption(&[("k1", 1)], "k1");
+        let (raw, storage) = storage(&dir, &enc);
+
+        for len in [0, 1, SEGMENT_SIZE - 1, SEGMENT_SIZE, 2 * SEGMENT_SIZE + 17] {
+            let content = body(len);
+            let key = format!("obj-{len}");
+            storage.put(&key, content.clone()).await.unwrap();
+            assert_eq!(storage.get(&key).await.unwrap(), content, "get {len}");
+            assert_eq!(
+                collect(storage.get_stream(&key).await.unwrap())
+                    .await
+                    .unwrap(),
+                content,
+                "stream {len}"
+            );
+
+            let streamed = format!("streamed-{len}");
+            let result = storage
+                .put_stream(&streamed, chunked(&content, 10_000))
+                .await
+                .unwrap();
+            assert_eq!(result.bytes_written, len as u64);
+            assert_eq!(
+                result.checksum_sha256,
+                format!("{:x}", Sha256::digest(&content))
+            );
+            assert_eq!(storage.get(&streamed).await.unwrap(), content);
+
+            // Nothing readable lands on disk.
+            let stored = raw.get(&key).await.unwrap();
+            assert!(stored.starts_with(&ENCRYPTED_OBJECT_MAGIC));
+            if len > 64 {
+                assert!(!stored.windows(64).any(|w| w == &content[..64]));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn ranged_reads_decrypt_only_the_window() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let enc = encryption(&[("k1", 1)], "k1");
+        let (_, storage) = storage(&dir, &enc);
+        let content = body(3 * SEGMENT_SIZE);
+        storage.put("obj", content.clone()).await.unwrap();
+
+        for (offset, length) in [
+            (0, 10),
+            (SEGMENT_SIZE - 5, 10),
+            (2 * SEGMENT_SIZE, SEGMENT_SIZE),
+            (3 * SEGMENT_SIZE - 1, 100),
+        ] {
+            let got = storage
+                .get_range("obj", offset as u64, length)
+                .await
+                .unwrap();
+            let end = (offset + length).min(content.len());
+            assert_eq!(got, content.slice(offset..end), "range {offset}+{length}");
+        }
+    }
+
+    #[tokio::test]
+    async fn tampering_and_truncation_fail_authentication() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let enc = encryption(&[("k1", 1)], "k1");
+        let (raw, storage) = storage(&dir, &enc);
+        storage
+            .put("obj", body(2 * SEGMENT_SIZE + 3))
+            .await
+            .unwrap();
+        let sealed = raw.get("obj").await.unwrap();
+
+        let mut flipped = sealed.to_vec();
+        let last = flipped.len() - 1;
+        flipped[last] ^= 1;
+        raw.put("flipped", Bytes::from(flipped)).await.unwrap();
+        assert!(storage.get("flipped").await.is_err());
+
+        // Dropping the final segment leaves a full segment that was not
+        // sealed as final.
+        let header_len = match EnvelopeHeader::parse(&sealed).unwrap() {
+            HeaderParse::Complete(_, len) => len,
+            other => panic!("unexpected {other:?}"),
+        };
+        let truncated = sealed.slice(..header_len + 2 * (SEGMENT_SIZE + TAG_LEN));
+        raw.put("truncated", truncated).await.unwrap();
+        assert!(storage.get("truncated").await.is_err());
+        assert!(collect(storage.get_stream("truncated").await.unwrap())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn plaintext_objects_pass_through_until_disallowed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let enc = encryption(&[("k1", 1)], "k1");
+        let (raw, storage) = storage(&dir, &enc);
+        raw.put("legacy", Bytes::from_static(b"legacy bytes"))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get("legacy").await.unwrap(),
+            Bytes::from_static(b"legacy bytes")
+        );
+        assert_eq!(
+            enc.object_state(raw.as_ref(), "legacy").await.unwrap(),
+            ObjectEncryptionState::Plaintext
+        );
+
+        let strict = Arc::new(EnvelopeEncryption::new(enc.wrapper.clone(), false));
+        let strict_storage = strict.wrap_backend(raw.clone());
+        assert!(strict_storage.get("legacy").await.is_err());
+
+        enc.encrypt_object(raw.clone(), "legacy").await.unwrap();
+        assert_eq!(
+            strict_storage.get("legacy").await.unwrap(),
+            Bytes::from_static(b"legacy bytes")
+        );
+    }
+
+    #[tokio::test]
+    async fn rotation_rewraps_without_reencrypting() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let old = encryption(&[("k1", 1)], "k1");
+        let (raw, storage) = storage(&dir, &old);
+        let content = body(SEGMENT_SIZE + 10);
+        storage.put("obj", content.clone()).await.unwrap();
+
+        let rotated = encryption(&[("k1", 1), ("k2", 2)], "k2");
+        assert_eq!(
+            rotated.object_state(raw.as_ref(), "obj").await.unwrap(),
+            ObjectEncryptionState::Stale {
+                key_id: "local:k1".to_string()
+            }
+        );
+        let before = raw.get("obj").await.unwrap();
+        rotated.rewrap_object(raw.as_ref(), "obj").await.unwrap();
+        let after = raw.get("obj").await.unwrap();
+        assert_eq!(
+            rotated.object_state(raw.as_ref(), "obj").await.unwrap(),
+            ObjectEncryptionState::Current
+        );
+        // Same sealed body behind a new header.
+        assert!(after.ends_with(&before[before.len() - SEGMENT_SIZE..]));
+
+        // Only the new key is needed from here on.
+        let new_only = encryption(&[("k2", 2)], "k2");
+        assert_eq!(
+            new_only.wrap_backend(raw).get("obj").await.unwrap(),
+            content
+        );
+    }
+}
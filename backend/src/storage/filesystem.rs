@@ -116,6 +116,87 @@ impl FilesystemStorage {
         let prefix = &sanitized_str[..2.min(sanitized_str.len())];
         self.base_path.join(prefix).join(&sanitized)
     }
+
+    /// Place the file at `source_path` at key `dest` through a temp file and
+    /// rename. A hard link is tried first: every write in this backend goes
+    /// through a temp file and rename, so stored files are never modified in
+    /// place and sharing an inode is safe. Across volumes, or where links are
+    /// unsupported, this falls back to `fs::copy`, which uses
+    /// `copy_file_range` on Linux and so clones extents on filesystems with
+    /// reflink support.
+    async fn copy_file_into(&self, source_path: &Path, source: &str, dest: &str) -> Result<()> {
+        let dest_path = self.key_to_path(dest);
+        if source_path == dest_path {
+            // Renaming a link onto the file it points at is a no-op that
+            // would leave the temp link behind.
+            return if fs::try_exists(source_path).await? {
+                Ok(())
+            } else {
+                Err(AppError::NotFound(format!(
+                    "Storage key not found: {}",
+                    source
+                )))
+            };
+        }
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let temp_path = temp_path_for_dest(&dest_path, Uuid::new_v4())?;
+
+        match fs::hard_link(source_path, &temp_path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(AppError::NotFound(format!(
+                    "Storage key not found: {}",
+                    source
+                )));
+            }
+            Err(link_err) => {
+                tracing::debug!(
+                    source = %source_path.display(),
+                    error = %link_err,
+                    "Hard link unavailable; copying file content"
+                );
+                if let Err(e) = fs::copy(source_path, &temp_path).await {
+                    remove_temp_file_best_effort(&temp_path, "filesystem copy failed").await;
+                    return Err(if e.kind() == std::io::ErrorKind::NotFound {
+                        AppError::NotFound(format!("Storage key not found: {}", source))
+                    } else {
+                        AppError::Storage(format!("Failed to copy {} to {}: {}", source, dest, e))
+                    });
+                }
+            }
+        }
+
+        let file = match fs::OpenOptions::new().read(true).open(&temp_path).await {
+            Ok(file) => file,
+            Err(e) => {
+                remove_temp_file_best_effort(&temp_path, "filesystem copy temp open failed").await;
+                return Err(AppError::Storage(format!(
+                    "Failed to open copied temp file for {}: {}",
+                    dest, e
+                )));
+            }
+        };
+        if let Err(e) = file.sync_all().await {
+            remove_temp_file_best_effort(&temp_path, "filesystem copy temp sync failed").await;
+            return Err(AppError::Storage(format!(
+                "Failed to sync copied temp file for {}: {}",
+                dest, e
+            )));
+        }
+        drop(file);
+
+        if let Err(e) = fs::rename(&temp_path, &dest_path).await {
+            remove_temp_file_best_effort(&temp_path, "filesystem copy temp promote failed").await;
+            return Err(AppError::Storage(format!(
+                "Failed to promote copied temp file to {}: {}",
+                dest, e
+            )));
+        }
+        sync_parent_directory(&dest_path).await?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -210,50 +291,27 @@ impl StorageBackend for FilesystemStorage {
 
     #[tracing::instrument(skip(self), fields(otel.kind = "internal", storage.system = "filesystem", storage.operation = "copy"))]
     async fn copy(&self, source: &str, dest: &str) -> Result<()> {
-        let source_path = self.key_to_path(source);
-        let dest_path = self.key_to_path(dest);
-        if let Some(parent) = dest_path.parent() {
-            fs::create_dir_all(parent).await?;
-        }
-        let temp_path = temp_path_for_dest(&dest_path, Uuid::new_v4())?;
-
-        if let Err(e) = fs::copy(&source_path, &temp_path).await {
-            remove_temp_file_best_effort(&temp_path, "filesystem copy failed").await;
-            return Err(if e.kind() == std::io::ErrorKind::NotFound {
-                AppError::NotFound(format!("Storage key not found: {}", source))
-            } else {
-                AppError::Storage(format!("Failed to copy {} to {}: {}", source, dest, e))
-            });
-        }
+        self.copy_file_into(&self.key_to_path(source), source, dest)
+            .await
+    }
 
-        let file = match fs::OpenOptions::new().read(true).open(&temp_path).await {
-            Ok(file) => file,
-            Err(e) => {
-                remove_temp_file_best_effort(&temp_path, "filesystem copy temp open failed").await;
-                return Err(AppError::Storage(format!(
-                    "Failed to open copied temp file for {}: {}",
-                    dest, e
-                )));
-            }
+    #[tracing::instrument(skip(self, source), fields(otel.kind = "internal", storage.system = "filesystem", storage.operation = "copy_from"))]
+    async fn copy_from(
+        &self,
+        source: &dyn StorageBackend,
+        source_key: &str,
+        dest_key: &str,
+    ) -> Result<bool> {
+        let Some(source_path) = source.local_path(source_key) else {
+            return Ok(false);
         };
-        if let Err(e) = file.sync_all().await {
-            remove_temp_file_best_effort(&temp_path, "filesystem copy temp sync failed").await;
-            return Err(AppError::Storage(format!(
-                "Failed to sync copied temp file for {}: {}",
-                dest, e
-            )));
-        }
-        drop(file);
+        self.copy_file_into(&source_path, source_key, dest_key)
+            .await?;
+        Ok(true)
+    }
 
-        if let Err(e) = fs::rename(&temp_path, &dest_path).await {
-            remove_temp_file_best_effort(&temp_path, "filesystem copy temp promote failed").await;
-            return Err(AppError::Storage(format!(
-                "Failed to promote copied temp file to {}: {}",
-                dest, e
-            )));
-        }
-        sync_parent_directory(&dest_path).await?;
-        Ok(())
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.key_to_path(key))
     }
 
     #[tracing::instrument(skip(self), fields(otel.kind = "internal", storage.system = "filesystem", storage.operation = "put_file"))]
@@ -334,6 +392,34 @@ impl StorageBackend for FilesystemStorage {
         Ok(Bytes::from(out))
     }
 
+    #[tracing::instrument(skip(self), fields(otel.kind = "internal", storage.system = "filesystem", storage.operation = "get_range_stream"))]
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        let path = self.key_to_path(key);
+        let mut file = fs::File::open(&path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                AppError::NotFound(format!("Storage key not found: {}", key))
+            } else {
+                AppError::Storage(format!("Failed to open {}: {}", key, e))
+            }
+        })?;
+
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to seek {}: {}", key, e)))?;
+
+        let reader = BufReader::new(file.take(length));
+        let stream = ReaderStream::with_capacity(reader, STREAM_CHUNK_SIZE);
+        let mapped = stream
+            .map(|result| result.map_err(|e| AppError::Storage(format!("Read error: {}", e))));
+
+        Ok(Box::pin(mapped))
+    }
+
     #[tracing::instrument(skip(self, stream), fields(otel.kind = "internal", storage.system = "filesystem", storage.operation = "put_stream"))]
     async fn put_stream(
         &self,
@@ -594,6 +680,23 @@ mod tests {
         assert_eq!(range, Bytes::from_static(b"fghijklm"));
     }
 
+    #[tokio::test]
+    async fn test_get_range_stream_reads_window() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FilesystemStorage::new(temp_dir.path());
+
+        let key = "range-stream-key";
+        storage
+            .put(key, Bytes::from_static(b"abcdefghijklmnop"))
+            .await
+            .unwrap();
+
+        let stream = storage.get_range_stream(key, 5, 8).await.unwrap();
+        let chunks: Vec<Bytes> = stream.map(|c| c.unwrap()).collect().await;
+
+        assert_eq!(chunks.concat(), b"fghijklm");
+    }
+
     #[tokio::test]
     async fn test_get_range_past_eof_returns_empty() {
         let temp_dir = tempfile::TempDir::new().unwrap();
@@ -815,6 +918,77 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_copy_from_other_root_links_the_file() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let staging = FilesystemStorage::new(temp_dir.path().join("staging"));
+        let release = FilesystemStorage::new(temp_dir.path().join("release"));
+        staging
+            .put("generic/tool.tar.gz", Bytes::from_static(b"tool"))
+            .await
+            .unwrap();
+
+        assert!(release
+            .copy_from(&staging, "generic/tool.tar.gz", "generic/tool.tar.gz")
+            .await
+            .unwrap());
+
+        assert_eq!(
+            release.get("generic/tool.tar.gz").await.unwrap(),
+            Bytes::from_static(b"tool")
+        );
+        let source_ino = tokio::fs::metadata(staging.key_to_path("generic/tool.tar.gz"))
+            .await
+            .unwrap()
+            .ino();
+        let dest_ino = tokio::fs::metadata(release.key_to_path("generic/tool.tar.gz"))
+            .await
+            .unwrap()
+            .ino();
+        assert_eq!(source_ino, dest_ino, "same-volume copy should hard link");
+
+        // Deleting the source leaves the promoted copy intact.
+        staging.delete("generic/tool.tar.gz").await.unwrap();
+        assert_eq!(
+            release.get("generic/tool.tar.gz").await.unwrap(),
+            Bytes::from_static(b"tool")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_from_missing_source_is_not_found() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let staging = FilesystemStorage::new(temp_dir.path().join("staging"));
+        let release = FilesystemStorage::new(temp_dir.path().join("release"));
+
+        let result = release.copy_from(&staging, "a/missing", "a/missing").await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_copy_onto_itself_leaves_no_temp_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FilesystemStorage::new(temp_dir.path());
+        storage
+            .put("dir/object", Bytes::from_static(b"same"))
+            .await
+            .unwrap();
+
+        storage.copy("dir/object", "dir/object").await.unwrap();
+
+        let mut entries = tokio::fs::read_dir(temp_dir.path().join("dir"))
+            .await
+            .unwrap();
+        let mut names = Vec::new();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        assert_eq!(names, vec!["object".to_string()]);
+    }
+
     #[tokio::test]
     async fn test_get_nonexistent_key() {
         let temp_dir = tempfile::TempDir::new().unwrap();
@@ -908,6 +908,33 @@ impl GcsBackend {
         Ok(None)
     }
 
+    /// Streaming counterpart of [`Self::try_fallback_get`]: returns the
+    /// successful fallback response with its body unread, opened on the
+    /// long-timeout streaming client.
+    async fn try_fallback_get_stream(&self, key: &str) -> Result<Option<reqwest::Response>> {
+        if !self.path_format.has_fallback() {
+            return Ok(None);
+        }
+        if let Some(fallback_key) = self.try_artifactory_fallback(key) {
+            tracing::debug!(
+                original = %key,
+                fallback = %fallback_key,
+                "Trying Artifactory fallback path for stream"
+            );
+            let url = self.object_download_url(&fallback_key);
+            let response = self.authorized_get_stream(&url).await?;
+            if response.status().is_success() {
+                tracing::info!(
+                    key = %key,
+                    fallback = %fallback_key,
+                    "Found artifact at Artifactory fallback path"
+                );
+                return Ok(Some(response));
+            }
+        }
+        Ok(None)
+    }
+
     /// Try a fallback ranged GET in migration mode. Returns `Ok(Some(bytes))`
     /// if found at the fallback path, `Ok(None)` otherwise.
     async fn try_fallback_get_range(&self, key: &str, range_header: &str) -> Result<Option<Bytes>> {
@@ -1764,7 +1791,17 @@ impl StorageBackend for GcsBackend {
     #[tracing::instrument(skip(self), fields(otel.kind = "client", storage.system = "gcs", storage.operation = "get_stream"))]
     async fn get_stream(&self, key: &str) -> Result<BoxStream<'static, Result<Bytes>>> {
         let url = self.object_download_url(key);
-        let response = self.authorized_get_stream(&url).await?;
+        let mut response = self.authorized_get_stream(&url).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            // Objects found at the migration-mode Artifactory fallback path
+            // stream the same way, so a multi-GiB migrated layer is not
+            // buffered just because of where it lives.
+            response = self
+                .try_fallback_get_stream(key)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Object not found: {}", key)))?;
+        }
 
         if response.status().is_success() {
             let stream = response.bytes_stream().map(|r| {
@@ -1773,16 +1810,6 @@ impl StorageBackend for GcsBackend {
             return Ok(Box::pin(stream));
         }
 
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
-            // The migration-mode Artifactory fallback still buffers
-            // (try_fallback_get returns Bytes); wrap it in a single-item stream
-            // so the caller's interface stays uniform.
-            if let Some(bytes) = self.try_fallback_get(key).await? {
-                return Ok(Box::pin(futures::stream::once(async move { Ok(bytes) })));
-            }
-            return Err(AppError::NotFound(format!("Object not found: {}", key)));
-        }
-
         require_success(response, "GCS download failed").await?;
         unreachable!()
     }
@@ -1821,6 +1848,15 @@ impl StorageBackend for GcsBackend {
         GcsBackend::copy(self, source, dest).await
     }
 
+    async fn copy_from(
+        &self,
+        source: &dyn StorageBackend,
+        source_key: &str,
+        dest_key: &str,
+    ) -> Result<bool> {
+        super::copy_within_instance(self, source, source_key, dest_key).await
+    }
+
     /// Fetch the GCS object's `etag` field via the JSON metadata endpoint
     /// (no body transfer). GCS ETags change on every object replacement,
     /// which makes them suitable for the #1051 fast-path tamper check.
@@ -2135,6 +2171,40 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_get_stream_fallback_streams_from_fallback_path() {
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let checksum = "abcdefabcdefabcdefabcdefabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+        let body = vec![7u8; 256 * 1024];
+        Mock::given(method("GET"))
+            .and(path_regex(
+                "/storage/v1/b/.*/o/repos%2Fgeneric%2Fabcdefabcdef",
+            ))
+            .respond_with(ResponseTemplate::new(404).set_body_string("not found"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex("/storage/v1/b/.*/o/ab%2Fabcdefabcdef"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(body.clone()))
+            .mount(&server)
+            .await;
+
+        let backend =
+            mock_backend_with_path_format(&server.uri(), StoragePathFormat::Migration).await;
+        let mut stream = backend
+            .get_stream(&format!("repos/generic/{checksum}"))
+            .await
+            .unwrap();
+        let mut collected = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            collected.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(collected, body);
+    }
+
     #[tokio::test]
     async fn test_get_range_sends_http_range_header() {
         use wiremock::matchers::{header, method, path_regex};
@@ -12,6 +12,9 @@
 //! storage key (`{format}/{repository_id}/{path}`, the same shape the
 //! rpm/alpine/incus handlers already use) or keep the legacy flat
 //! `{format}/{path}` namespace.
+//!
+//! [`CAS_STORAGE_PREFIX`] is the shared content-addressed namespace used by
+//! the optional storage dedup layer.
 
 use uuid::Uuid;
 
@@ -133,6 +136,33 @@ pub const fn prefix_matches(literal: &str) -> bool {
     true
 }
 
+/// Storage-key prefix of the shared content-addressed dedup namespace:
+/// `cas/sha256/`.
+///
+/// With `STORAGE_DEDUP_ENABLED` the generic upload path writes each blob once
+/// per backend under [`cas_storage_key`] and records one `storage_refs` row
+/// per artifact pointing at it. Objects under this prefix are never owned by
+/// a single repository: repository deletion leaves them in place and only the
+/// storage GC ref-count sweep reclaims them. The literal is embedded in SQL
+/// (`'cas/sha256/%'`) by the repository-delete purge query.
+pub const CAS_STORAGE_PREFIX: &str = "cas/sha256/";
+
+/// The shared CAS key for content with the given lowercase-hex SHA-256:
+/// `cas/sha256/{ab}/{cd}/{sha256}`.
+pub fn cas_storage_key(sha256: &str) -> String {
+    format!(
+        "{CAS_STORAGE_PREFIX}{}/{}/{}",
+        &sha256[..2],
+        &sha256[2..4],
+        sha256
+    )
+}
+
+/// Whether `key` lives in the shared CAS namespace.
+pub fn is_cas_storage_key(key: &str) -> bool {
+    key.starts_with(CAS_STORAGE_PREFIX)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,4 +266,23 @@ mod tests {
     fn default_scheme_is_repo_scoped() {
         assert_eq!(StorageKeyScheme::default(), StorageKeyScheme::RepoScoped);
     }
+
+    // -- Shared CAS namespace -----------------------------------------------
+
+    #[test]
+    fn cas_storage_key_shards_by_digest_prefix() {
+        let sha = "abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789";
+        let key = cas_storage_key(sha);
+        assert_eq!(key, format!("cas/sha256/ab/cd/{sha}"));
+        assert!(is_cas_storage_key(&key));
+    }
+
+    #[test]
+    fn legacy_content_keys_are_not_cas_keys() {
+        // The pre-dedup per-repository content key and the storage-service
+        // `cas/` layout sit outside the shared namespace.
+        assert!(!is_cas_storage_key("ab/cd/abcdef"));
+        assert!(!is_cas_storage_key("cas/ab/cd/abcdef"));
+        assert!(!is_cas_storage_key("oci-blobs/sha256:abcdef"));
+    }
 }
@@ -0,0 +1,739 @@
+//! Key-encryption-key providers for storage encryption.
+//!
+//! `STORAGE_ENCRYPTION` picks the provider that wraps per-object data keys:
+//!
+//! ```bash
+//! # Local master keys (base64, 32 bytes each). Old keys stay listed after a
+//! # rotation so existing objects remain readable until they are rewrapped.
+//! STORAGE_ENCRYPTION=local
+//! STORAGE_ENCRYPTION_LOCAL_KEYS=2026-10:BASE64...,2026-01:BASE64...
+//! STORAGE_ENCRYPTION_LOCAL_ACTIVE_KEY=2026-10    # default: first listed
+//!
+//! # AWS KMS (credentials from AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY /
+//! # AWS_SESSION_TOKEN)
+//! STORAGE_ENCRYPTION=aws-kms
+//! STORAGE_ENCRYPTION_KMS_KEY_ID=arn:aws:kms:us-east-1:123456789012:key/...
+//! STORAGE_ENCRYPTION_KMS_REGION=us-east-1        # default: AWS_REGION
+//! STORAGE_ENCRYPTION_KMS_ENDPOINT=https://...    # optional, e.g. a VPC endpoint
+//!
+//! # Azure Key Vault (service principal or managed identity, same variables
+//! # as the Azure storage backend)
+//! STORAGE_ENCRYPTION=azure-keyvault
+//! STORAGE_ENCRYPTION_KEYVAULT_KEY_ID=https://myvault.vault.azure.net/keys/artifacts
+//! ```
+//!
+//! Rotation: in AWS KMS, rotate the key in place (KMS decrypts old data keys
+//! with the key's retired material) or point the key id at a new key; in Key
+//! Vault, create a new key version — an unversioned key id always wraps with
+//! the current version. The storage encryption migration then rewraps every
+//! object still under an older key id.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use async_trait::async_trait;
+use axum::http::{HeaderMap, HeaderValue};
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+use super::encryption::{KeyWrapper, WrappedKey};
+use crate::error::{AppError, Result};
+use crate::services::s3_gateway_service::{
+    canonical_request, sign, string_to_sign, SigV4Authorization,
+};
+
+/// Associated data binding locally wrapped keys to their purpose.
+const LOCAL_WRAP_AAD: &[u8] = b"artifact-keeper/storage-dek/v1";
+
+/// KMS encryption context; Decrypt fails unless the same context is given.
+const KMS_ENCRYPTION_CONTEXT: &str = "artifact-keeper-storage";
+
+const KMS_TIMEOUT: Duration = Duration::from_secs(10);
+
+const KEYVAULT_API_VERSION: &str = "7.4";
+const KEYVAULT_RESOURCE: &str = "https://vault.azure.net";
+const KEYVAULT_WRAP_ALGORITHM: &str = "RSA-OAEP-256";
+
+/// How long an unversioned Key Vault key id stays resolved to one version.
+const KEYVAULT_VERSION_TTL_SECS: i64 = 300;
+
+/// Refresh Azure tokens this long before they expire.
+const TOKEN_REFRESH_MARGIN_SECS: i64 = 300;
+
+/// Build the key wrapper selected by `STORAGE_ENCRYPTION`, or `None` when it
+/// is unset or `off`.
+pub fn key_wrapper_from_env() -> Result<Option<Arc<dyn KeyWrapper>>> {
+    let mode = std::env::var("STORAGE_ENCRYPTION")
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    let wrapper: Arc<dyn KeyWrapper> = match mode.as_str() {
+        "" | "off" | "none" | "false" => return Ok(None),
+        "local" => Arc::new(LocalKeyWrapper::from_env()?),
+        "aws-kms" => Arc::new(AwsKmsKeyWrapper::from_env()?),
+        "azure-keyvault" => Arc::new(AzureKeyVaultWrapper::from_env()?),
+        other => {
+            return Err(AppError::Config(format!(
+                "STORAGE_ENCRYPTION must be off, local, aws-kms or azure-keyvault, got {other:?}"
+            )))
+        }
+    };
+    Ok(Some(wrapper))
+}
+
+fn required_env(name: &str) -> Result<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| AppError::Config(format!("{name} is required for storage encryption")))
+}
+
+/// Master keys held in the process environment.
+pub struct LocalKeyWrapper {
+    keys: HashMap<String, Aes256Gcm>,
+    active: String,
+}
+
+impl LocalKeyWrapper {
+    pub fn new(keys: Vec<(String, [u8; 32])>, active: String) -> Result<Self> {
+        if !keys.iter().any(|(id, _)| *id == active) {
+            return Err(AppError::Config(format!(
+                "active storage encryption key {active:?} is not among the configured keys"
+            )));
+        }
+        let keys = keys
+            .into_iter()
+            .map(|(id, key)| (id, Aes256Gcm::new(&key.into())))
+            .collect();
+        Ok(Self { keys, active })
+    }
+
+    /// Parse `STORAGE_ENCRYPTION_LOCAL_KEYS` (`id:base64,...`).
+    pub fn from_env() -> Result<Self> {
+        let raw = required_env("STORAGE_ENCRYPTION_LOCAL_KEYS")?;
+        let mut keys = Vec::new();
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, encoded) = entry.split_once(':').ok_or_else(|| {
+                AppError::Config(
+                    "STORAGE_ENCRYPTION_LOCAL_KEYS entries must look like id:base64key".to_string(),
+                )
+            })?;
+            let id = id.trim();
+            if id.is_empty() || keys.iter().any(|(existing, _)| existing == id) {
+                return Err(AppError::Config(format!(
+                    "STORAGE_ENCRYPTION_LOCAL_KEYS has an empty or duplicate key id {id:?}"
+                )));
+            }
+            let bytes = B64.decode(encoded.trim()).map_err(|e| {
+                AppError::Config(format!("storage encryption key {id:?} is not base64: {e}"))
+            })?;
+            let key: [u8; 32] = bytes.as_slice().try_into().map_err(|_| {
+                AppError::Config(format!(
+                    "storage encryption key {id:?} must decode to 32 bytes, got {}",
+                    bytes.len()
+                ))
+            })?;
+            keys.push((id.to_string(), key));
+        }
+        let active = match std::env::var("STORAGE_ENCRYPTION_LOCAL_ACTIVE_KEY") {
+            Ok(id) if !id.trim().is_empty() => id.trim().to_string(),
+            _ => keys.first().map(|(id, _)| id.clone()).ok_or_else(|| {
+                AppError::Config("STORAGE_ENCRYPTION_LOCAL_KEYS is empty".to_string())
+            })?,
+        };
+        Self::new(keys, active)
+    }
+}
+
+#[async_trait]
+impl KeyWrapper for LocalKeyWrapper {
+    fn provider(&self) -> &'static str {
+        "local"
+    }
+
+    async fn active_key_id(&self) -> Result<String> {
+        Ok(format!("local:{}", self.active))
+    }
+
+    async fn wrap(&self, dek: &[u8]) -> Result<WrappedKey> {
+        let mut nonce = [0u8; 12];
+        rand::rng().fill_bytes(&mut nonce);
+        let sealed = self.keys[&self.active]
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: dek,
+                    aad: LOCAL_WRAP_AAD,
+                },
+            )
+            .map_err(|_| AppError::Storage("failed to wrap data key".to_string()))?;
+        let mut ciphertext = nonce.to_vec();
+        ciphertext.extend_from_slice(&sealed);
+        Ok(WrappedKey {
+            key_id: self.active_key_id().await?,
+            ciphertext,
+        })
+    }
+
+    async fn unwrap(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        let id = key_id.strip_prefix("local:").ok_or_else(|| {
+            AppError::Storage(format!(
+                "object was encrypted with {key_id:?}, not a local master key"
+            ))
+        })?;
+        let key = self.keys.get(id).ok_or_else(|| {
+            AppError::Storage(format!(
+                "local master key {id:?} is not configured in STORAGE_ENCRYPTION_LOCAL_KEYS"
+            ))
+        })?;
+        if wrapped.len() < 12 {
+            return Err(AppError::Storage(
+                "wrapped data key is truncated".to_string(),
+            ));
+        }
+        let (nonce, sealed) = wrapped.split_at(12);
+        key.decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: sealed,
+                aad: LOCAL_WRAP_AAD,
+            },
+        )
+        .map_err(|_| AppError::Storage(format!("failed to unwrap data key with {key_id:?}")))
+    }
+}
+
+/// AWS KMS `Encrypt`/`Decrypt` over the JSON API, signed with SigV4.
+pub struct AwsKmsKeyWrapper {
+    client: reqwest::Client,
+    key_id: String,
+    region: String,
+    endpoint: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl AwsKmsKeyWrapper {
+    pub fn from_env() -> Result<Self> {
+        let key_id = required_env("STORAGE_ENCRYPTION_KMS_KEY_ID")?;
+        let region = std::env::var("STORAGE_ENCRYPTION_KMS_REGION")
+            .or_else(|_| std::env::var("AWS_REGION"))
+            .map_err(|_| {
+                AppError::Config(
+                    "STORAGE_ENCRYPTION_KMS_REGION or AWS_REGION is required for aws-kms"
+                        .to_string(),
+                )
+            })?;
+        let endpoint = std::env::var("STORAGE_ENCRYPTION_KMS_ENDPOINT")
+            .ok()
+            .filter(|e| !e.trim().is_empty())
+            .unwrap_or_else(|| format!("https://kms.{region}.amazonaws.com"));
+        let client = crate::services::http_client::base_client_builder()
+            .timeout(KMS_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Config(format!("failed to build KMS client: {e}")))?;
+        Ok(Self {
+            client,
+            key_id,
+            region,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            access_key_id: required_env("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: required_env("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
+        })
+    }
+
+    async fn call(&self, action: &str, body: serde_json::Value) -> Result<serde_json::Value> {
+        let url = url::Url::parse(&format!("{}/", self.endpoint))
+            .map_err(|e| AppError::Config(format!("invalid KMS endpoint: {e}")))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(AppError::Config("KMS endpoint has no host".to_string())),
+        };
+        let payload = serde_json::to_vec(&body).map_err(|e| AppError::Internal(e.to_string()))?;
+        let payload_hash = hex::encode(Sha256::digest(&payload));
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+
+        let mut headers = HeaderMap::new();
+        let header = |v: &str| {
+            HeaderValue::from_str(v)
+                .map_err(|_| AppError::Config(format!("invalid KMS request header value {v:?}")))
+        };
+        headers.insert("content-type", header("application/x-amz-json-1.1")?);
+        headers.insert("host", header(&host)?);
+        headers.insert("x-amz-date", header(&amz_date)?);
+        headers.insert("x-amz-target", header(&format!("TrentService.{action}"))?);
+        if let Some(token) = &self.session_token {
+            headers.insert("x-amz-security-token", header(token)?);
+        }
+        let mut signed_headers: Vec<String> = headers.keys().map(|k| k.to_string()).collect();
+        signed_headers.sort();
+
+        let mut auth = SigV4Authorization {
+            access_key_id: self.access_key_id.clone(),
+            date: now.format("%Y%m%d").to_string(),
+            region: self.region.clone(),
+            service: "kms".to_string(),
+            signed_headers,
+            signature: String::new(),
+        };
+        let canonical = canonical_request(
+            "POST",
+            url.path(),
+            None,
+            &headers,
+            &auth.signed_headers,
+            &payload_hash,
+        )
+        .map_err(|e| AppError::Internal(format!("failed to sign KMS request: {e}")))?;
+        auth.signature = sign(
+            &self.secret_access_key,
+            &auth,
+            &string_to_sign(&amz_date, &auth.scope(), &canonical),
+        );
+        headers.insert(
+            "authorization",
+            header(&format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                auth.access_key_id,
+                auth.scope(),
+                auth.signed_headers.join(";"),
+                auth.signature
+            ))?,
+        );
+        headers.remove("host");
+
+        let response = self
+            .client
+            .post(url)
+            .headers(headers)
+            .body(payload)
+            .send()
+            .await
+            .map_err(|e| AppError::Storage(format!("AWS KMS {action} request failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!(
+                "AWS KMS {action} failed ({status}): {body}"
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| AppError::Storage(format!("invalid AWS KMS {action} response: {e}")))
+    }
+
+    fn blob(response: &serde_json::Value, field: &str) -> Result<Vec<u8>> {
+        response[field]
+            .as_str()
+            .and_then(|b| B64.decode(b).ok())
+            .ok_or_else(|| AppError::Storage(format!("AWS KMS response is missing {field}")))
+    }
+}
+
+#[async_trait]
+impl KeyWrapper for AwsKmsKeyWrapper {
+    fn provider(&self) -> &'static str {
+        "aws-kms"
+    }
+
+    async fn active_key_id(&self) -> Result<String> {
+        Ok(format!("aws-kms:{}", self.key_id))
+    }
+
+    async fn wrap(&self, dek: &[u8]) -> Result<WrappedKey> {
+        let response = self
+            .call(
+                "Encrypt",
+                serde_json::json!({
+                    "KeyId": self.key_id,
+                    "Plaintext": B64.encode(dek),
+                    "EncryptionContext": { "purpose": KMS_ENCRYPTION_CONTEXT },
+                }),
+            )
+            .await?;
+        Ok(WrappedKey {
+            key_id: self.active_key_id().await?,
+            ciphertext: Self::blob(&response, "CiphertextBlob")?,
+        })
+    }
+
+    async fn unwrap(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        if !key_id.starts_with("aws-kms:") {
+            return Err(AppError::Storage(format!(
+                "object was encrypted with {key_id:?}, not an AWS KMS key"
+            )));
+        }
+        // The ciphertext blob names the KMS key, so retired key ids decrypt
+        // as long as the key itself is still enabled.
+        let response = self
+            .call(
+                "Decrypt",
+                serde_json::json!({
+                    "CiphertextBlob": B64.encode(wrapped),
+                    "EncryptionContext": { "purpose": KMS_ENCRYPTION_CONTEXT },
+                }),
+            )
+            .await?;
+        Self::blob(&response, "Plaintext")
+    }
+}
+
+enum AzureCredential {
+    ServicePrincipal {
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+    },
+    ManagedIdentity {
+        client_id: Option<String>,
+    },
+}
+
+struct CachedValue {
+    value: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Azure Key Vault `wrapkey`/`unwrapkey` with an RSA key.
+pub struct AzureKeyVaultWrapper {
+    client: reqwest::Client,
+    /// `https://<vault>.vault.azure.net/keys/<name>[/<version>]`
+    key_id: String,
+    credential: AzureCredential,
+    token: RwLock<Option<CachedValue>>,
+    /// Resolved versioned key id, when `key_id` is unversioned.
+    current_version: RwLock<Option<CachedValue>>,
+}
+
+impl AzureKeyVaultWrapper {
+    pub fn from_env() -> Result<Self> {
+        let key_id = required_env("STORAGE_ENCRYPTION_KEYVAULT_KEY_ID")?
+            .trim_end_matches('/')
+            .to_string();
+        let parsed = url::Url::parse(&key_id).map_err(|e| {
+            AppError::Config(format!(
+                "STORAGE_ENCRYPTION_KEYVAULT_KEY_ID is not a URL: {e}"
+            ))
+        })?;
+        let segments: Vec<&str> = parsed
+            .path_segments()
+            .map(|s| s.collect())
+            .unwrap_or_default();
+        if parsed.scheme() != "https"
+            || !matches!(segments.as_slice(), ["keys", _] | ["keys", _, _])
+        {
+            return Err(AppError::Config(
+                "STORAGE_ENCRYPTION_KEYVAULT_KEY_ID must look like https://<vault>.vault.azure.net/keys/<name>[/<version>]"
+                    .to_string(),
+            ));
+        }
+        let credential = match (
+            std::env::var("AZURE_TENANT_ID").ok(),
+            std::env::var("AZURE_CLIENT_ID").ok(),
+            std::env::var("AZURE_CLIENT_SECRET").ok(),
+        ) {
+            (Some(tenant_id), Some(client_id), Some(client_secret)) => {
+                AzureCredential::ServicePrincipal {
+                    tenant_id,
+                    client_id,
+                    client_secret,
+                }
+            }
+            (_, client_id, _) => AzureCredential::ManagedIdentity { client_id },
+        };
+        let client = crate::services::http_client::base_client_builder()
+            .timeout(KMS_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Config(format!("failed to build Key Vault client: {e}")))?;
+        Ok(Self {
+            client,
+            key_id,
+            credential,
+            token: RwLock::new(None),
+            current_version: RwLock::new(None),
+        })
+    }
+
+    fn is_versioned(key_id: &str) -> bool {
+        key_id
+            .split("/keys/")
+            .nth(1)
+            .is_some_and(|rest| rest.contains('/'))
+    }
+
+    async fn get_token(&self) -> Result<String> {
+        if let Some(cached) = self.token.read().await.as_ref() {
+            if Utc::now() < cached.expires_at {
+                return Ok(cached.value.clone());
+            }
+        }
+        let mut cache = self.token.write().await;
+        if let Some(cached) = cache.as_ref() {
+            if Utc::now() < cached.expires_at {
+                return Ok(cached.value.clone());
+            }
+        }
+
+        let response = match &self.credential {
+            AzureCredential::ServicePrincipal {
+                tenant_id,
+                client_id,
+                client_secret,
+            } => self
+                .client
+                .post(format!(
+                    "https://login.microsoftonline.com/{tenant_id}/oauth2/v2.0/token"
+                ))
+                .form(&[
+                    ("grant_type", "client_credentials"),
+                    ("client_id", client_id.as_str()),
+                    ("client_secret", client_secret.as_str()),
+                    ("scope", &format!("{KEYVAULT_RESOURCE}/.default")),
+                ])
+                .send()
+                .await,
+            AzureCredential::ManagedIdentity { client_id } => {
+                let mut url = format!(
+                    "http://169.254.169.254/metadata/identity/oauth2/token?api-version=2019-08-01&resource={}",
+                    urlencoding::encode(KEYVAULT_RESOURCE)
+                );
+                if let Some(cid) = client_id {
+                    url.push_str(&format!("&client_id={}", urlencoding::encode(cid)));
+                }
+                self.client
+                    .get(url)
+                    .header("Metadata", "true")
+                    .timeout(Duration::from_secs(5))
+                    .send()
+                    .await
+            }
+        }
+        .map_err(|e| AppError::Storage(format!("failed to request Key Vault token: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!(
+                "Key Vault token request failed ({status}): {body}"
+            )));
+        }
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::Storage(format!("invalid Azure token response: {e}")))?;
+        let token = body["access_token"]
+            .as_str()
+            .ok_or_else(|| AppError::Storage("Azure token response missing access_token".into()))?
+            .to_string();
+        // IMDS returns expires_in as a string, Azure AD as a number.
+        let expires_in: i64 = body["expires_in"]
+            .as_i64()
+            .or_else(|| body["expires_in"].as_str().and_then(|s| s.parse().ok()))
+            .unwrap_or(3600);
+        *cache = Some(CachedValue {
+            value: token.clone(),
+            expires_at: Utc::now()
+                + ChronoDuration::seconds(expires_in - TOKEN_REFRESH_MARGIN_SECS),
+        });
+        Ok(token)
+    }
+
+    async fn post(&self, url: &str, body: serde_json::Value) -> Result<serde_json::Value> {
+        let token = self.get_token().await?;
+        let response = self
+            .client
+            .post(format!("{url}?api-version={KEYVAULT_API_VERSION}"))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AppError::Storage(format!("Key Vault request failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!(
+                "Key Vault request failed ({status}): {body}"
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| AppError::Storage(format!("invalid Key Vault response: {e}")))
+    }
+
+    /// The versioned id new data keys are wrapped with. Recording the version
+    /// in each object is what lets a new key version be detected as a
+    /// rotation.
+    async fn current_key(&self) -> Result<String> {
+        if Self::is_versioned(&self.key_id) {
+            return Ok(self.key_id.clone());
+        }
+        if let Some(cached) = self.current_version.read().await.as_ref() {
+            if Utc::now() < cached.expires_at {
+                return Ok(cached.value.clone());
+            }
+        }
+        let token = self.get_token().await?;
+        let response = self
+            .client
+            .get(format!(
+                "{}?api-version={KEYVAULT_API_VERSION}",
+                self.key_id
+            ))
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| AppError::Storage(format!("Key Vault key lookup failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!(
+                "Key Vault key lookup failed ({status}): {body}"
+            )));
+        }
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::Storage(format!("invalid Key Vault key response: {e}")))?;
+        let kid = body["key"]["kid"]
+            .as_str()
+            .ok_or_else(|| AppError::Storage("Key Vault key response missing kid".into()))?
+            .to_string();
+        *self.current_version.write().await = Some(CachedValue {
+            value: kid.clone(),
+            expires_at: Utc::now() + ChronoDuration::seconds(KEYVAULT_VERSION_TTL_SECS),
+        });
+        Ok(kid)
+    }
+}
+
+#[async_trait]
+impl KeyWrapper for AzureKeyVaultWrapper {
+    fn provider(&self) -> &'static str {
+        "azure-keyvault"
+    }
+
+    async fn active_key_id(&self) -> Result<String> {
+        Ok(format!("azure-keyvault:{}", self.current_key().await?))
+    }
+
+    async fn wrap(&self, dek: &[u8]) -> Result<WrappedKey> {
+        let kid = self.current_key().await?;
+        let response = self
+            .post(
+                &format!("{kid}/wrapkey"),
+                serde_json::json!({
+                    "alg": KEYVAULT_WRAP_ALGORITHM,
+                    "value": base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(dek),
+                }),
+            )
+            .await?;
+        Ok(WrappedKey {
+            key_id: format!("azure-keyvault:{kid}"),
+            ciphertext: keyvault_value(&response)?,
+        })
+    }
+
+    async fn unwrap(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        let kid = key_id.strip_prefix("azure-keyvault:").ok_or_else(|| {
+            AppError::Storage(format!(
+                "object was encrypted with {key_id:?}, not an Azure Key Vault key"
+            ))
+        })?;
+        let response = self
+            .post(
+                &format!("{kid}/unwrapkey"),
+                serde_json::json!({
+                    "alg": KEYVAULT_WRAP_ALGORITHM,
+                    "value": base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(wrapped),
+                }),
+            )
+            .await?;
+        keyvault_value(&response)
+    }
+}
+
+/// Key Vault returns base64url without padding.
+fn keyvault_value(response: &serde_json::Value) -> Result<Vec<u8>> {
+    response["value"]
+        .as_str()
+        .and_then(|v| {
+            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .decode(v.trim_end_matches('='))
+                .ok()
+        })
+        .ok_or_else(|| AppError::Storage("Key Vault response is missing value".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrapper(keys: &[(&str, u8)], active: &str) -> LocalKeyWrapper {
+        LocalKeyWrapper::new(
+            keys.iter()
+                .map(|(id, byte)| (id.to_string(), [*byte; 32]))
+                .collect(),
+            active.to_string(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn local_wrap_round_trips_and_survives_rotation() {
+        let old = wrapper(&[("a", 1)], "a");
+        let dek = [7u8; 32];
+        let wrapped = old.wrap(&dek).await.unwrap();
+        assert_eq!(wrapped.key_id, "local:a");
+
+        let rotated = wrapper(&[("b", 2), ("a", 1)], "b");
+        assert_eq!(rotated.active_key_id().await.unwrap(), "local:b");
+        assert_eq!(
+            rotated
+                .unwrap(&wrapped.key_id, &wrapped.ciphertext)
+                .await
+                .unwrap(),
+            dek
+        );
+
+        let dropped = wrapper(&[("b", 2)], "b");
+        assert!(dropped
+            .unwrap(&wrapped.key_id, &wrapped.ciphertext)
+            .await
+            .is_err());
+        assert!(rotated
+            .unwrap("aws-kms:alias/x", &wrapped.ciphertext)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn local_rejects_unknown_active_key() {
+        assert!(LocalKeyWrapper::new(vec![("a".into(), [0; 32])], "b".into()).is_err());
+    }
+
+    #[test]
+    fn keyvault_versioned_ids() {
+        assert!(AzureKeyVaultWrapper::is_versioned(
+            "https://v.vault.azure.net/keys/k/0123abcd"
+        ));
+        assert!(!AzureKeyVaultWrapper::is_versioned(
+            "https://v.vault.azure.net/keys/k"
+        ));
+    }
+}
@@ -1,12 +1,17 @@
 //! Storage backends.
 
 pub mod azure;
+pub mod cas;
+pub mod encryption;
 pub mod filesystem;
 pub mod gcs;
 pub mod keys;
+pub mod kms;
 pub mod path_format;
 pub mod registry;
+pub mod retention;
 pub mod s3;
+pub mod sharded;
 
 pub use keys::StorageKeyScheme;
 pub use path_format::StoragePathFormat;
@@ -84,6 +89,119 @@ pub enum PresignedUrlSource {
     Gcs,
 }
 
+/// A part a client uploaded straight to object storage through a presigned
+/// URL, identified by the ETag the storage service returned for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadedPart {
+    /// 1-based part number.
+    pub part_number: u32,
+    pub etag: String,
+}
+
+/// Multipart uploads that clients send directly to the object store.
+///
+/// The backend only starts, signs and finishes the upload; part bodies never
+/// pass through the server.
+#[async_trait]
+pub trait DirectUploadStore: Send + Sync {
+    /// Start a multipart upload for `key` and return its upload ID.
+    async fn create_multipart_upload(&self, key: &str) -> Result<String>;
+
+    /// Presigned `PUT` URL for one part of an upload started with
+    /// [`create_multipart_upload`](Self::create_multipart_upload).
+    async fn presign_upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: u32,
+        expires_in: Duration,
+    ) -> Result<String>;
+
+    /// Assemble the object from its parts. `parts` must be numbered
+    /// contiguously from 1.
+    async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &[UploadedPart],
+    ) -> Result<()>;
+
+    /// Discard an upload and any parts already stored for it.
+    async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()>;
+}
+
+/// An object the store soft-deleted and can still restore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoftDeletedObject {
+    pub key: String,
+    pub size_bytes: u64,
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Days until the store purges the object for good.
+    pub remaining_retention_days: Option<u32>,
+}
+
+/// One page of [`SoftDeleteRecovery::list_soft_deleted`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoftDeletedPage {
+    pub objects: Vec<SoftDeletedObject>,
+    /// Continuation marker for the next page, if there is one.
+    pub next_marker: Option<String>,
+}
+
+/// Recovery of objects kept by the store's own soft delete (Azure blob soft
+/// delete), independent of artifact soft delete in the database.
+#[async_trait]
+pub trait SoftDeleteRecovery: Send + Sync {
+    /// Soft-deleted objects whose key starts with `prefix`, at most `limit`
+    /// per page.
+    async fn list_soft_deleted(
+        &self,
+        prefix: Option<&str>,
+        marker: Option<&str>,
+        limit: usize,
+    ) -> Result<SoftDeletedPage>;
+
+    /// Restore a soft-deleted object, together with its soft-deleted
+    /// snapshots.
+    async fn undelete(&self, key: &str) -> Result<()>;
+}
+
+/// Restrict a byte stream to `length` bytes starting at `offset`.
+///
+/// Leading chunks are discarded as they arrive and the stream ends once the
+/// window is complete, so the tail of the source is never polled.
+pub fn slice_stream(
+    stream: BoxStream<'static, Result<Bytes>>,
+    offset: u64,
+    length: u64,
+) -> BoxStream<'static, Result<Bytes>> {
+    use futures::StreamExt;
+
+    // `scan` ends the stream (returns `None`) once the window is complete;
+    // `filter_map` then drops the chunks that fell wholly before it.
+    Box::pin(
+        stream
+            .scan((offset, length), |(to_skip, remaining), chunk| {
+                if *remaining == 0 {
+                    return futures::future::ready(None);
+                }
+                let out = match chunk {
+                    Ok(mut bytes) => {
+                        let skip = (*to_skip).min(bytes.len() as u64) as usize;
+                        let _ = bytes.split_to(skip);
+                        *to_skip -= skip as u64;
+                        let take = (*remaining).min(bytes.len() as u64) as usize;
+                        *remaining -= take as u64;
+                        (take > 0).then(|| Ok(bytes.split_to(take)))
+                    }
+                    Err(e) => Some(Err(e)),
+                };
+                futures::future::ready(Some(out))
+            })
+            .filter_map(futures::future::ready),
+    )
+}
+
 /// Storage backend trait
 #[async_trait]
 pub trait StorageBackend: Send + Sync {
@@ -131,6 +249,32 @@ pub trait StorageBackend: Send + Sync {
         self.put_stream(dest, stream).await.map(|_| ())
     }
 
+    /// Copy `source_key` from another backend handle to `dest_key` on this
+    /// one without the bytes passing through this process.
+    ///
+    /// Returns `Ok(false)` when there is no native route from `source` (a
+    /// different provider, bucket or volume); the caller then streams with
+    /// `get_stream()` + `put_stream()`. Promotion needs this rather than
+    /// [`copy`](Self::copy) because the source and target repositories
+    /// resolve to separate handles.
+    async fn copy_from(
+        &self,
+        source: &dyn StorageBackend,
+        source_key: &str,
+        dest_key: &str,
+    ) -> Result<bool> {
+        let _ = (source, source_key, dest_key); // Suppress unused warnings
+        Ok(false)
+    }
+
+    /// Local file holding `key`, for backends that keep objects as plain
+    /// files. Lets a filesystem handle rooted elsewhere link or clone the file
+    /// in [`copy_from`](Self::copy_from).
+    fn local_path(&self, key: &str) -> Option<std::path::PathBuf> {
+        let _ = key; // Suppress unused warning for default impl
+        None
+    }
+
     /// Check if this backend supports redirect downloads via presigned URLs
     fn supports_redirect(&self) -> bool {
         false
@@ -149,6 +293,18 @@ pub trait StorageBackend: Send + Sync {
         Ok(None)
     }
 
+    /// Direct client-to-storage multipart uploads, when the backend supports
+    /// and enables them.
+    fn direct_uploads(&self) -> Option<&dyn DirectUploadStore> {
+        None
+    }
+
+    /// Listing and restoring objects the store itself soft-deleted, when the
+    /// backend supports it.
+    fn soft_delete_recovery(&self) -> Option<&dyn SoftDeleteRecovery> {
+        None
+    }
+
     /// Store content from a file.
     ///
     /// Default implementation opens the file and delegates to `put_stream`,
@@ -227,6 +383,24 @@ pub trait StorageBackend: Send + Sync {
         Ok(Bytes::from(out))
     }
 
+    /// Stream a byte range of an object without buffering it.
+    ///
+    /// This is what ranged HTTP downloads (`206 Partial Content`) read from.
+    /// Backends with native ranged reads (filesystem seek, S3 ranged GET)
+    /// override it so a resumed download does not re-read the skipped
+    /// prefix. The default slices [`get_stream`](Self::get_stream), which is
+    /// correct for every backend. A range running past the end of the object
+    /// yields the bytes that exist.
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        let stream = self.get_stream(key).await?;
+        Ok(slice_stream(stream, offset, length))
+    }
+
     /// Store content from a byte stream, computing a SHA-256 checksum
     /// incrementally as data arrives.
     ///
@@ -251,6 +425,49 @@ pub trait StorageBackend: Send + Sync {
     async fn health_check(&self) -> Result<()> {
         Ok(())
     }
+
+    /// Whether [`set_retention`](Self::set_retention) can lock objects on
+    /// this backend (S3 Object Lock, Azure immutability policies).
+    fn supports_retention(&self) -> bool {
+        false
+    }
+
+    /// Place a provider-side compliance retention on `key` until `until`:
+    /// nobody, including this process and the account that owns it, can
+    /// delete or overwrite the object before then. Retention can only be
+    /// extended. The default reports "unsupported" with an error.
+    async fn set_retention(&self, key: &str, until: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        let _ = until;
+        Err(crate::error::AppError::Storage(format!(
+            "storage backend does not support object retention (key '{}')",
+            key
+        )))
+    }
+}
+
+/// [`StorageBackend::copy_from`] for backends the registry shares as one
+/// instance across repositories (S3, GCS, Azure). When `source` is that same
+/// instance the copy runs server-side through [`StorageBackend::copy`].
+/// Promotion keeps the source's storage key, so on a shared namespace the
+/// object is usually already in place and only its presence is checked.
+pub(crate) async fn copy_within_instance<B: StorageBackend>(
+    backend: &B,
+    source: &dyn StorageBackend,
+    source_key: &str,
+    dest_key: &str,
+) -> Result<bool> {
+    if !std::ptr::addr_eq(backend as *const B, source as *const dyn StorageBackend) {
+        return Ok(false);
+    }
+    if source_key != dest_key {
+        backend.copy(source_key, dest_key).await?;
+    } else if !backend.exists(source_key).await? {
+        return Err(crate::error::AppError::NotFound(format!(
+            "Storage key not found: {}",
+            source_key
+        )));
+    }
+    Ok(true)
 }
 
 /// Buffered `put_stream` implementation of last resort.
@@ -410,6 +627,12 @@ mod tests {
         assert!(!backend.supports_redirect());
     }
 
+    #[test]
+    fn test_default_direct_uploads_unsupported() {
+        let backend = TestBackend;
+        assert!(backend.direct_uploads().is_none());
+    }
+
     #[tokio::test]
     async fn test_default_get_presigned_url() {
         let backend = TestBackend;
@@ -530,6 +753,97 @@ mod tests {
         assert_eq!(writes[0].1, Bytes::from_static(b"copied bytes"));
     }
 
+    #[tokio::test]
+    async fn test_default_copy_from_has_no_native_route() {
+        let source = TestBackend;
+        let target = TestBackend;
+        assert!(!target.copy_from(&source, "a", "a").await.unwrap());
+        assert!(target.local_path("a").is_none());
+    }
+
+    /// One shared bucket: records server-side copies and answers `exists`
+    /// from a fixed key set.
+    struct SharedBucket {
+        keys: Vec<&'static str>,
+        copies: std::sync::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl StorageBackend for SharedBucket {
+        async fn put(&self, _key: &str, _content: Bytes) -> Result<()> {
+            unreachable!("native copy must not write through put")
+        }
+
+        async fn get(&self, _key: &str) -> Result<Bytes> {
+            unreachable!("native copy must not read content")
+        }
+
+        async fn exists(&self, key: &str) -> Result<bool> {
+            Ok(self.keys.contains(&key))
+        }
+
+        async fn delete(&self, _key: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn copy(&self, source: &str, dest: &str) -> Result<()> {
+            self.copies
+                .lock()
+                .unwrap()
+                .push((source.to_string(), dest.to_string()));
+            Ok(())
+        }
+
+        async fn put_stream(
+            &self,
+            _key: &str,
+            _stream: BoxStream<'static, Result<Bytes>>,
+        ) -> Result<PutStreamResult> {
+            unreachable!("native copy must not stream")
+        }
+    }
+
+    fn shared_bucket() -> SharedBucket {
+        SharedBucket {
+            keys: vec!["present"],
+            copies: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_copy_within_instance_copies_server_side() {
+        let bucket = shared_bucket();
+        assert!(copy_within_instance(&bucket, &bucket, "present", "other")
+            .await
+            .unwrap());
+        assert_eq!(
+            *bucket.copies.lock().unwrap(),
+            vec![("present".to_string(), "other".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_within_instance_same_key_only_checks_presence() {
+        let bucket = shared_bucket();
+        assert!(copy_within_instance(&bucket, &bucket, "present", "present")
+            .await
+            .unwrap());
+        assert!(bucket.copies.lock().unwrap().is_empty());
+
+        let missing = copy_within_instance(&bucket, &bucket, "missing", "missing").await;
+        assert!(matches!(missing, Err(crate::error::AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_copy_within_instance_declines_other_instances() {
+        let bucket = shared_bucket();
+        let other = shared_bucket();
+        assert!(!copy_within_instance(&bucket, &other, "present", "other")
+            .await
+            .unwrap());
+        assert!(bucket.copies.lock().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_default_get_range_slices_streamed_bytes() {
         let backend = TestBackend;
@@ -548,6 +862,34 @@ mod tests {
         assert!(range.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_default_get_range_stream_slices_streamed_bytes() {
+        use futures::StreamExt;
+
+        let backend = TestBackend;
+
+        let stream = backend.get_range_stream("any-key", 1, 2).await.unwrap();
+        let chunks: Vec<Bytes> = stream.map(|c| c.unwrap()).collect().await;
+
+        assert_eq!(chunks.concat(), b"es");
+    }
+
+    #[tokio::test]
+    async fn test_slice_stream_spans_chunks_and_stops_early() {
+        use futures::StreamExt;
+
+        let chunks: Vec<Result<Bytes>> = vec![
+            Ok(Bytes::from_static(b"ab")),
+            Ok(Bytes::from_static(b"cdef")),
+            Ok(Bytes::from_static(b"ghij")),
+            Err(crate::error::AppError::Storage("never polled".to_string())),
+        ];
+        let sliced = slice_stream(futures::stream::iter(chunks).boxed(), 3, 4);
+        let out: Vec<Bytes> = sliced.map(|c| c.unwrap()).collect().await;
+
+        assert_eq!(out.concat(), b"defg");
+    }
+
     #[test]
     fn test_download_range_header_is_inclusive() {
         // offset 1024, length 4096 -> bytes=1024-5119 (inclusive end).
@@ -4,10 +4,16 @@
 //! lookup by `StorageLocation`. The `"filesystem"` backend is handled specially:
 //! each call to `backend_for` creates a new `FilesystemStorage` rooted at the
 //! location's path, so every repository gets its own directory tree.
+//! Locations with a WORM retention policy get handles that lock what they
+//! write (see [`RetentionStorage`]).
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
+use super::cas::CasRoutedStorage;
+use super::encryption::EnvelopeEncryption;
+use super::retention::RetentionStorage;
+use super::sharded::ShardedStorage;
 use super::StorageBackend;
 use crate::error::{AppError, Result};
 use crate::storage::filesystem::FilesystemStorage;
@@ -29,6 +35,11 @@ pub struct StorageLocation {
 pub struct StorageRegistry {
     backends: HashMap<String, Arc<dyn StorageBackend>>,
     default_backend: String,
+    filesystem_cas_root: Option<String>,
+    encryption: Option<Arc<EnvelopeEncryption>>,
+    sharded: HashMap<String, Arc<ShardedStorage>>,
+    /// Retention days of WORM repositories, keyed by (backend, path).
+    retention: RwLock<HashMap<(String, String), u32>>,
 }
 
 impl StorageRegistry {
@@ -40,17 +51,121 @@ impl StorageRegistry {
         Self {
             backends,
             default_backend,
+            filesystem_cas_root: None,
+            encryption: None,
+            sharded: HashMap::new(),
+            retention: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Register `backend` under `name` as a sharded backend, so the shard
+    /// rebalance job can reach its shards.
+    pub fn with_sharded_backend(
+        mut self,
+        name: impl Into<String>,
+        backend: Arc<ShardedStorage>,
+    ) -> Self {
+        let name = name.into();
+        self.backends.insert(name.clone(), backend.clone());
+        self.sharded.insert(name, backend);
+        self
+    }
+
+    /// The raw (unencrypted) sharded backend registered under `name`.
+    pub fn sharded_backend(&self, name: &str) -> Option<Arc<ShardedStorage>> {
+        self.sharded.get(name).cloned()
+    }
+
+    /// Names of the sharded backends, sorted.
+    pub fn sharded_backend_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.sharded.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Route the shared `cas/sha256/` keys of every filesystem repository to
+    /// one store rooted at `root` (storage dedup). Cloud backends need no
+    /// routing: their namespace is already shared across repositories.
+    pub fn with_filesystem_cas_root(mut self, root: impl Into<String>) -> Self {
+        self.filesystem_cas_root = Some(root.into());
+        self
+    }
+
+    /// Encrypt everything written through handles this registry returns
+    /// (`STORAGE_ENCRYPTION`).
+    pub fn with_encryption(mut self, encryption: Arc<EnvelopeEncryption>) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// The envelope encryption applied to every handle, if enabled.
+    pub fn encryption(&self) -> Option<&Arc<EnvelopeEncryption>> {
+        self.encryption.as_ref()
+    }
+
+    /// Wrap `backend` in this registry's encryption, if enabled.
+    pub fn encrypt(&self, backend: Arc<dyn StorageBackend>) -> Arc<dyn StorageBackend> {
+        match &self.encryption {
+            Some(encryption) => encryption.wrap_backend(backend),
+            None => backend,
         }
     }
 
     /// Resolve a `StorageLocation` to a concrete backend instance.
     ///
     /// For `"filesystem"` locations a fresh `FilesystemStorage` is created using
-    /// the location's path. All other backend names are looked up in the
-    /// registry's map of shared instances.
+    /// the location's path, wrapped in a [`CasRoutedStorage`] when a shared
+    /// CAS root is configured. All other backend names are looked up in the
+    /// registry's map of shared instances. The handle encrypts and decrypts
+    /// object bodies when storage encryption is enabled.
     pub fn backend_for(&self, location: &StorageLocation) -> Result<Arc<dyn StorageBackend>> {
+        self.raw_backend_for(location)
+            .map(|b| self.encrypt(self.with_retention(location, b)))
+    }
+
+    /// Replace the WORM retention policies: `(location, retention_days)`
+    /// for every repository whose writes must be locked.
+    pub fn set_retention_policies(&self, policies: Vec<(StorageLocation, u32)>) {
+        let policies = policies
+            .into_iter()
+            .map(|(location, days)| ((location.backend, location.path), days))
+            .collect();
+        *self.retention.write().unwrap() = policies;
+    }
+
+    /// Retention days applied to writes at `location`, if it is WORM.
+    pub fn retention_days(&self, location: &StorageLocation) -> Option<u32> {
+        self.retention
+            .read()
+            .unwrap()
+            .get(&(location.backend.clone(), location.path.clone()))
+            .copied()
+    }
+
+    fn with_retention(
+        &self,
+        location: &StorageLocation,
+        backend: Arc<dyn StorageBackend>,
+    ) -> Arc<dyn StorageBackend> {
+        match self.retention_days(location) {
+            Some(days) => Arc::new(RetentionStorage::new(backend, days)),
+            None => backend,
+        }
+    }
+
+    /// Like [`Self::backend_for`], but reads and writes stored bytes as they
+    /// are, without encryption. Only the storage encryption migration needs
+    /// this.
+    pub fn raw_backend_for(&self, location: &StorageLocation) -> Result<Arc<dyn StorageBackend>> {
         if location.backend == "filesystem" {
-            return Ok(Arc::new(FilesystemStorage::new(&location.path)));
+            let repo: Arc<dyn StorageBackend> = Arc::new(FilesystemStorage::new(&location.path));
+            return Ok(match &self.filesystem_cas_root {
+                Some(root) => Arc::new(CasRoutedStorage::new(
+                    repo,
+                    Arc::new(FilesystemStorage::new(root)),
+                )),
+                None => repo,
+            });
         }
 
         self.backends
@@ -64,6 +179,26 @@ impl StorageRegistry {
             })
     }
 
+    /// The handle holding `backend`'s shared `cas/sha256/` objects.
+    ///
+    /// Cloud backends hold them in their one shared namespace. Filesystem
+    /// needs the root set by [`Self::with_filesystem_cas_root`]; without it
+    /// there is no shared store to resolve and this returns an error.
+    pub fn cas_store(&self, backend: &str) -> Result<Arc<dyn StorageBackend>> {
+        if backend == "filesystem" {
+            return match &self.filesystem_cas_root {
+                Some(root) => Ok(self.encrypt(Arc::new(FilesystemStorage::new(root)))),
+                None => Err(AppError::Storage(
+                    "no shared CAS root is configured for filesystem storage".to_string(),
+                )),
+            };
+        }
+        self.backend_for(&StorageLocation {
+            backend: backend.to_string(),
+            path: String::new(),
+        })
+    }
+
     /// Check whether a backend name is available.
     ///
     /// `"filesystem"` is always considered available because it does not require
@@ -192,6 +327,29 @@ mod tests {
         assert_eq!(registry.default_backend(), "filesystem");
     }
 
+    // -- Retention policies ---------------------------------------------------
+
+    #[test]
+    fn test_retention_policies_keyed_by_location() {
+        let registry = make_registry();
+        let worm = StorageLocation {
+            backend: "s3-primary".to_string(),
+            path: "repos/releases".to_string(),
+        };
+        let other = StorageLocation {
+            backend: "s3-primary".to_string(),
+            path: "repos/snapshots".to_string(),
+        };
+        assert_eq!(registry.retention_days(&worm), None);
+
+        registry.set_retention_policies(vec![(worm.clone(), 365)]);
+        assert_eq!(registry.retention_days(&worm), Some(365));
+        assert_eq!(registry.retention_days(&other), None);
+
+        registry.set_retention_policies(Vec::new());
+        assert_eq!(registry.retention_days(&worm), None);
+    }
+
     // -- StorageRegistry::backend_for -----------------------------------------
 
     #[tokio::test]
@@ -379,4 +537,29 @@ mod tests {
         let bytes = resolved.get("coincide-key").await.unwrap();
         assert_eq!(bytes, Bytes::from_static(b"hello"));
     }
+
+    #[tokio::test]
+    async fn test_backend_for_filesystem_shares_cas_root_across_repositories() {
+        use crate::storage::keys::cas_storage_key;
+        use tempfile::TempDir;
+
+        let cas = TempDir::new().unwrap();
+        let repo_a = TempDir::new().unwrap();
+        let repo_b = TempDir::new().unwrap();
+        let registry = StorageRegistry::new(HashMap::new(), "filesystem".to_string())
+            .with_filesystem_cas_root(cas.path().to_str().unwrap());
+        let location = |dir: &TempDir| StorageLocation {
+            backend: "filesystem".to_string(),
+            path: dir.path().to_str().unwrap().to_string(),
+        };
+        let a = registry.backend_for(&location(&repo_a)).unwrap();
+        let b = registry.backend_for(&location(&repo_b)).unwrap();
+        let key = cas_storage_key(&"ab".repeat(32));
+
+        a.put(&key, Bytes::from_static(b"once")).await.unwrap();
+        a.put("own-key", Bytes::from_static(b"a")).await.unwrap();
+
+        assert_eq!(b.get(&key).await.unwrap(), Bytes::from_static(b"once"));
+        assert!(!b.exists("own-key").await.unwrap());
+    }
 }
@@ -0,0 +1,261 @@
+//! Provider-side retention (WORM) for compliance repositories.
+//!
+//! [`RetentionStorage`] wraps the storage handle of a repository with a WORM
+//! policy (see `services::worm_service`). Every object written through it is
+//! locked at the provider — S3 Object Lock in COMPLIANCE mode, or a locked
+//! Azure immutability policy — for the policy's retention period, so a
+//! compromised instance or administrator cannot delete or overwrite a
+//! released artifact before it ends.
+//!
+//! A write only succeeds once its object is locked. Scratch objects that the
+//! upload paths delete again (OCI chunked-upload parts, direct uploads) are
+//! left unlocked. Deletes pass through: the provider refuses them while the
+//! retention holds.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::Utc;
+use futures::stream::BoxStream;
+
+use super::{DirectUploadStore, PresignedUrl, PutStreamResult, StorageBackend};
+use crate::error::Result;
+
+/// Key prefixes holding temporary upload state, never locked.
+const SCRATCH_PREFIXES: &[&str] = &["oci-uploads/", "direct-uploads/"];
+
+/// Whether `key` holds temporary upload state rather than an artifact.
+pub fn is_scratch_key(key: &str) -> bool {
+    SCRATCH_PREFIXES.iter().any(|p| key.starts_with(p))
+}
+
+/// Locks every object written through it for `retention_days`.
+pub struct RetentionStorage {
+    inner: Arc<dyn StorageBackend>,
+    retention_days: u32,
+}
+
+impl RetentionStorage {
+    pub fn new(inner: Arc<dyn StorageBackend>, retention_days: u32) -> Self {
+        Self {
+            inner,
+            retention_days,
+        }
+    }
+
+    async fn lock(&self, key: &str) -> Result<()> {
+        if is_scratch_key(key) {
+            return Ok(());
+        }
+        let until = Utc::now() + chrono::Duration::days(self.retention_days as i64);
+        self.inner.set_retention(key, until).await
+    }
+}
+
+#[async_trait]
+impl StorageBackend for RetentionStorage {
+    async fn put(&self, key: &str, content: Bytes) -> Result<()> {
+        self.inner.put(key, content).await?;
+        self.lock(key).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        self.inner.get(key).await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        self.inner.exists(key).await
+    }
+
+    async fn head_etag(&self, key: &str) -> Result<Option<String>> {
+        self.inner.head_etag(key).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.inner.delete(key).await
+    }
+
+    async fn copy(&self, source: &str, dest: &str) -> Result<()> {
+        self.inner.copy(source, dest).await?;
+        self.lock(dest).await
+    }
+
+    async fn copy_from(
+        &self,
+        source: &dyn StorageBackend,
+        source_key: &str,
+        dest_key: &str,
+    ) -> Result<bool> {
+        let copied = self.inner.copy_from(source, source_key, dest_key).await?;
+        if copied {
+            self.lock(dest_key).await?;
+        }
+        Ok(copied)
+    }
+
+    fn local_path(&self, key: &str) -> Option<std::path::PathBuf> {
+        self.inner.local_path(key)
+    }
+
+    fn supports_redirect(&self) -> bool {
+        self.inner.supports_redirect()
+    }
+
+    async fn get_presigned_url(
+        &self,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<PresignedUrl>> {
+        self.inner.get_presigned_url(key, expires_in).await
+    }
+
+    /// Direct uploads are completed by the client against the provider, out
+    /// of reach of the lock, so they are not offered on WORM repositories.
+    fn direct_uploads(&self) -> Option<&dyn DirectUploadStore> {
+        None
+    }
+
+    async fn put_file(&self, key: &str, path: &std::path::Path) -> Result<()> {
+        self.inner.put_file(key, path).await?;
+        self.lock(key).await
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<BoxStream<'static, Result<Bytes>>> {
+        self.inner.get_stream(key).await
+    }
+
+    async fn get_range(&self, key: &str, offset: u64, length: usize) -> Result<Bytes> {
+        self.inner.get_range(key, offset, length).await
+    }
+
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        self.inner.get_range_stream(key, offset, length).await
+    }
+
+    async fn put_stream(
+        &self,
+        key: &str,
+        stream: BoxStream<'static, Result<Bytes>>,
+    ) -> Result<PutStreamResult> {
+        let result = self.inner.put_stream(key, stream).await?;
+        self.lock(key).await?;
+        Ok(result)
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+
+    fn supports_retention(&self) -> bool {
+        self.inner.supports_retention()
+    }
+
+    async fn set_retention(&self, key: &str, until: chrono::DateTime<Utc>) -> Result<()> {
+        self.inner.set_retention(key, until).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::error::AppError;
+    use crate::storage::buffered_put_stream_fallback;
+
+    #[derive(Default)]
+    struct LockRecorder {
+        objects: Mutex<std::collections::HashMap<String, Bytes>>,
+        locked: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl StorageBackend for LockRecorder {
+        async fn put(&self, key: &str, content: Bytes) -> Result<()> {
+            self.objects
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), content);
+            Ok(())
+        }
+        async fn get(&self, key: &str) -> Result<Bytes> {
+            self.objects
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .ok_or_else(|| AppError::NotFound(key.to_string()))
+        }
+        async fn exists(&self, key: &str) -> Result<bool> {
+            Ok(self.objects.lock().unwrap().contains_key(key))
+        }
+        async fn delete(&self, key: &str) -> Result<()> {
+            self.objects.lock().unwrap().remove(key);
+            Ok(())
+        }
+        async fn put_stream(
+            &self,
+            key: &str,
+            stream: BoxStream<'static, Result<Bytes>>,
+        ) -> Result<PutStreamResult> {
+            buffered_put_stream_fallback(self, key, stream).await
+        }
+        fn supports_retention(&self) -> bool {
+            true
+        }
+        async fn set_retention(&self, key: &str, until: chrono::DateTime<Utc>) -> Result<()> {
+            assert!(until > Utc::now() + chrono::Duration::days(29));
+            self.locked.lock().unwrap().push(key.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_is_scratch_key() {
+        assert!(is_scratch_key("oci-uploads/1234"));
+        assert!(is_scratch_key("direct-uploads/abcd"));
+        assert!(!is_scratch_key("oci-blobs/sha256:abc"));
+        assert!(!is_scratch_key("com/acme/app/1.0/app-1.0.jar"));
+    }
+
+    #[tokio::test]
+    async fn test_writes_are_locked_except_scratch_keys() {
+        let inner = Arc::new(LockRecorder::default());
+        let storage = RetentionStorage::new(inner.clone(), 30);
+
+        storage
+            .put("releases/app-1.0.jar", Bytes::from_static(b"jar"))
+            .await
+            .unwrap();
+        storage
+            .put("oci-uploads/part", Bytes::from_static(b"part"))
+            .await
+            .unwrap();
+        storage
+            .copy("releases/app-1.0.jar", "releases/app-1.0-copy.jar")
+            .await
+            .unwrap();
+        let stream = futures::stream::once(async { Ok(Bytes::from_static(b"streamed")) });
+        storage
+            .put_stream("releases/app-1.1.jar", Box::pin(stream))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *inner.locked.lock().unwrap(),
+            vec![
+                "releases/app-1.0.jar".to_string(),
+                "releases/app-1.0-copy.jar".to_string(),
+                "releases/app-1.1.jar".to_string(),
+            ]
+        );
+        assert!(storage.direct_uploads().is_none());
+    }
+}
@@ -0,0 +1,775 @@
+//! Storage sharded across several buckets or containers.
+//!
+//! Large installations hit per-bucket (S3) or per-container (Azure)
+//! throughput limits. [`ShardedStorage`] spreads keys over several backends
+//! of the same kind, picking each key's shard by rendezvous hashing: every
+//! shard scores the key and the highest score wins. Adding a shard moves
+//! only the keys the new shard now wins, roughly `1/n` of them, and removing
+//! one moves only the keys it held.
+//!
+//! Writes always go to a key's owning shard. Reads try the owner first and
+//! then the remaining shards in score order, so objects written before a
+//! shard was added stay readable until a rebalance
+//! ([`ShardedStorage::rebalance_key`]) moves them to their new owner.
+//!
+//! Direct uploads and store-level soft-delete recovery are offered only when
+//! every shard offers them. A direct upload runs entirely on its key's
+//! owning shard, so the shard list must not change while uploads are in
+//! flight. Recovery lists each shard in turn behind a combined
+//! `<shard>:<marker>` continuation marker, and restoring a key restores
+//! every shard's copy, mirroring delete.
+//!
+//! Configured with `S3_SHARD_BUCKETS` or `AZURE_SHARD_CONTAINERS`, each a
+//! comma-separated list that should include the existing `S3_BUCKET` /
+//! `AZURE_STORAGE_CONTAINER` so objects already stored there are found.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use sha2::{Digest, Sha256};
+
+use super::{
+    DirectUploadStore, PresignedUrl, PutStreamResult, SoftDeleteRecovery, SoftDeletedPage,
+    StorageBackend, UploadedPart,
+};
+use crate::error::{AppError, Result};
+
+/// One bucket or container of a sharded backend.
+pub struct Shard {
+    /// Bucket or container name; also the rendezvous hashing seed, so it
+    /// must not change while the shard holds data.
+    pub name: String,
+    pub backend: Arc<dyn StorageBackend>,
+}
+
+/// What [`ShardedStorage::rebalance_key`] did with a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebalanceOutcome {
+    /// Already on its owning shard.
+    InPlace,
+    /// Copied to its owning shard and removed from the old one.
+    Moved,
+    /// Not found on any shard.
+    Missing,
+}
+
+/// A backend that spreads keys over several shards.
+pub struct ShardedStorage {
+    shards: Vec<Shard>,
+}
+
+impl ShardedStorage {
+    pub fn new(shards: Vec<Shard>) -> Result<Self> {
+        if shards.is_empty() {
+            return Err(AppError::Config(
+                "sharded storage needs at least one shard".to_string(),
+            ));
+        }
+        let mut names: Vec<&str> = shards.iter().map(|s| s.name.as_str()).collect();
+        names.sort_unstable();
+        if names.windows(2).any(|w| w[0] == w[1]) {
+            return Err(AppError::Config(
+                "sharded storage shard names must be unique".to_string(),
+            ));
+        }
+        Ok(Self { shards })
+    }
+
+    pub fn shard_names(&self) -> Vec<String> {
+        self.shards.iter().map(|s| s.name.clone()).collect()
+    }
+
+    /// The shard that owns `key`.
+    pub fn owner(&self, key: &str) -> &Shard {
+        let names: Vec<&str> = self.shards.iter().map(|s| s.name.as_str()).collect();
+        &self.shards[owner_index(&names, key)]
+    }
+
+    /// Shards in the order reads try them for `key`: owner first.
+    fn ranked(&self, key: &str) -> Vec<&Shard> {
+        let mut ranked: Vec<(u64, &Shard)> = self
+            .shards
+            .iter()
+            .map(|s| (shard_score(&s.name, key), s))
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        ranked.into_iter().map(|(_, s)| s).collect()
+    }
+
+    /// The shard currently holding `key`, searching in read order.
+    async fn locate(&self, key: &str) -> Result<Option<&Shard>> {
+        for shard in self.ranked(key) {
+            if shard.backend.exists(key).await? {
+                return Ok(Some(shard));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Move `key` to its owning shard if it is stored elsewhere.
+    ///
+    /// Uploads always land on the owner, which is checked first, so only an
+    /// upload of the same key between that check and the copy can be
+    /// overwritten by the older bytes; for content-addressed keys both are
+    /// the same. The old copy is deleted only after the owner holds the
+    /// object.
+    pub async fn rebalance_key(&self, key: &str) -> Result<RebalanceOutcome> {
+        let owner = self.owner(key);
+        if owner.backend.exists(key).await? {
+            return Ok(RebalanceOutcome::InPlace);
+        }
+        let Some(source) = self.locate(key).await? else {
+            return Ok(RebalanceOutcome::Missing);
+        };
+        let stream = source.backend.get_stream(key).await?;
+        owner.backend.put_stream(key, stream).await?;
+        if !owner.backend.exists(key).await? {
+            return Err(AppError::Storage(format!(
+                "copy of '{}' to shard '{}' is not visible",
+                key, owner.name
+            )));
+        }
+        source.backend.delete(key).await?;
+        Ok(RebalanceOutcome::Moved)
+    }
+
+    /// The direct upload store of the shard that owns `key`.
+    fn direct_store(&self, key: &str) -> Result<&dyn DirectUploadStore> {
+        let shard = self.owner(key);
+        shard.backend.direct_uploads().ok_or_else(|| {
+            AppError::Storage(format!(
+                "shard '{}' does not support direct uploads",
+                shard.name
+            ))
+        })
+    }
+}
+
+/// The soft-delete recovery of `shard`.
+fn recovery(shard: &Shard) -> Result<&dyn SoftDeleteRecovery> {
+    shard.backend.soft_delete_recovery().ok_or_else(|| {
+        AppError::Storage(format!(
+            "shard '{}' does not support soft-delete recovery",
+            shard.name
+        ))
+    })
+}
+
+/// Rendezvous score of `key` on the shard named `shard`.
+fn shard_score(shard: &str, key: &str) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(shard.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(key.as_bytes());
+    let digest = hasher.finalize();
+    u64::from_be_bytes(digest[..8].try_into().expect("8-byte prefix"))
+}
+
+/// Index of the shard in `names` that owns `key`. Ties, which need a hash
+/// collision, go to the lexically smaller name.
+pub fn owner_index(names: &[&str], key: &str) -> usize {
+    names
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| {
+            shard_score(a, key)
+                .cmp(&shard_score(b, key))
+                .then_with(|| b.cmp(a))
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Try `$op` on each shard in read order, moving on only when a shard does
+/// not have the key.
+macro_rules! read_through {
+    ($self:ident, $key:expr, |$backend:ident| $op:expr) => {{
+        let mut missing = None;
+        for shard in $self.ranked($key) {
+            let $backend = &shard.backend;
+            match $op.await {
+                Err(AppError::NotFound(msg)) => missing = Some(msg),
+                other => return other,
+            }
+        }
+        Err(AppError::NotFound(missing.unwrap_or_else(|| {
+            format!("Storage key not found: {}", $key)
+        })))
+    }};
+}
+
+#[async_trait]
+impl StorageBackend for ShardedStorage {
+    async fn put(&self, key: &str, content: Bytes) -> Result<()> {
+        self.owner(key).backend.put(key, content).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        read_through!(self, key, |backend| backend.get(key))
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.locate(key).await?.is_some())
+    }
+
+    async fn head_etag(&self, key: &str) -> Result<Option<String>> {
+        match self.locate(key).await? {
+            Some(shard) => shard.backend.head_etag(key).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Deletes from every shard, so a copy left behind by an interrupted
+    /// rebalance does not resurface.
+    async fn delete(&self, key: &str) -> Result<()> {
+        for shard in &self.shards {
+            match shard.backend.delete(key).await {
+                Ok(()) | Err(AppError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn supports_redirect(&self) -> bool {
+        self.shards.iter().all(|s| s.backend.supports_redirect())
+    }
+
+    async fn get_presigned_url(
+        &self,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<PresignedUrl>> {
+        match self.locate(key).await? {
+            Some(shard) => shard.backend.get_presigned_url(key, expires_in).await,
+            None => Ok(None),
+        }
+    }
+
+    fn direct_uploads(&self) -> Option<&dyn DirectUploadStore> {
+        self.shards
+            .iter()
+            .all(|s| s.backend.direct_uploads().is_some())
+            .then_some(self as &dyn DirectUploadStore)
+    }
+
+    fn soft_delete_recovery(&self) -> Option<&dyn SoftDeleteRecovery> {
+        self.shards
+            .iter()
+            .all(|s| s.backend.soft_delete_recovery().is_some())
+            .then_some(self as &dyn SoftDeleteRecovery)
+    }
+
+    async fn put_file(&self, key: &str, path: &std::path::Path) -> Result<()> {
+        self.owner(key).backend.put_file(key, path).await
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<BoxStream<'static, Result<Bytes>>> {
+        read_through!(self, key, |backend| backend.get_stream(key))
+    }
+
+    async fn get_range(&self, key: &str, offset: u64, length: usize) -> Result<Bytes> {
+        read_through!(self, key, |backend| backend.get_range(key, offset, length))
+    }
+
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        read_through!(self, key, |backend| backend
+            .get_range_stream(key, offset, length))
+    }
+
+    async fn put_stream(
+        &self,
+        key: &str,
+        stream: BoxStream<'static, Result<Bytes>>,
+    ) -> Result<PutStreamResult> {
+        self.owner(key).backend.put_stream(key, stream).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        for shard in &self.shards {
+            shard.backend.health_check().await.map_err(|e| {
+                AppError::Storage(format!("shard '{}' is unhealthy: {}", shard.name, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn supports_retention(&self) -> bool {
+        self.shards.iter().all(|s| s.backend.supports_retention())
+    }
+
+    /// Locks the copy readers find first: the owner's, or a not yet
+    /// rebalanced one.
+    async fn set_retention(&self, key: &str, until: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        match self.locate(key).await? {
+            Some(shard) => shard.backend.set_retention(key, until).await,
+            None => Err(AppError::NotFound(format!(
+                "Storage key not found: {}",
+                key
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl DirectUploadStore for ShardedStorage {
+    async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        self.direct_store(key)?.create_multipart_upload(key).await
+    }
+
+    async fn presign_upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: u32,
+        expires_in: Duration,
+    ) -> Result<String> {
+        self.direct_store(key)?
+            .presign_upload_part(key, upload_id, part_number, expires_in)
+            .await
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &[UploadedPart],
+    ) -> Result<()> {
+        self.direct_store(key)?
+            .complete_multipart_upload(key, upload_id, parts)
+            .await
+    }
+
+    async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.direct_store(key)?
+            .abort_multipart_upload(key, upload_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl SoftDeleteRecovery for ShardedStorage {
+    /// Lists the shards in configuration order. The marker names the shard
+    /// to resume on and carries that shard's own marker, so a key
+    /// soft-deleted on several shards is listed once per shard.
+    async fn list_soft_deleted(
+        &self,
+        prefix: Option<&str>,
+        marker: Option<&str>,
+        limit: usize,
+    ) -> Result<SoftDeletedPage> {
+        let (mut index, mut inner) = match marker {
+            None => (0, None),
+            Some(marker) => {
+                let resumed = marker.split_once(':').and_then(|(name, inner)| {
+                    let index = self.shards.iter().position(|s| s.name == name)?;
+                    Some((index, (!inner.is_empty()).then(|| inner.to_string())))
+                });
+                resumed.ok_or_else(|| {
+                    AppError::Validation(format!("Invalid continuation marker '{}'", marker))
+                })?
+            }
+        };
+
+        let mut objects = Vec::new();
+        while let Some(shard) = self.shards.get(index) {
+            if objects.len() >= limit {
+                return Ok(SoftDeletedPage {
+                    objects,
+                    next_marker: Some(format!("{}:{}", shard.name, inner.unwrap_or_default())),
+                });
+            }
+            let page = recovery(shard)?
+                .list_soft_deleted(prefix, inner.as_deref(), limit - objects.len())
+                .await?;
+            objects.extend(page.objects);
+            inner = page.next_marker;
+            if inner.is_none() {
+                index += 1;
+            }
+        }
+        Ok(SoftDeletedPage {
+            objects,
+            next_marker: None,
+        })
+    }
+
+    /// Restores the copy on every shard that holds one, as delete removes
+    /// them all.
+    async fn undelete(&self, key: &str) -> Result<()> {
+        let mut restored = false;
+        for shard in self.ranked(key) {
+            match recovery(shard)?.undelete(key).await {
+                Ok(()) => restored = true,
+                Err(AppError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if restored {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!(
+                "No soft-deleted object '{}' to restore",
+                key
+            )))
+        }
+    }
+}
+
+/// Shard names from a comma-separated environment variable. `None` when the
+/// variable is unset or lists fewer than two names, i.e. sharding is off.
+fn shard_names_from_env(var: &str) -> Option<Vec<String>> {
+    let names = parse_shard_names(&std::env::var(var).ok()?);
+    (names.len() >= 2).then_some(names)
+}
+
+/// Split a comma-separated shard list, dropping blanks and repeats.
+pub fn parse_shard_names(value: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// S3 sharded over `S3_SHARD_BUCKETS`, each bucket otherwise configured like
+/// `S3_BUCKET`.
+pub async fn s3_from_env() -> Result<Option<ShardedStorage>> {
+    let Some(buckets) = shard_names_from_env("S3_SHARD_BUCKETS") else {
+        return Ok(None);
+    };
+    let base = super::s3::S3Config::from_env()?;
+    let mut shards = Vec::with_capacity(buckets.len());
+    for bucket in buckets {
+        let mut config = base.clone();
+        config.bucket = bucket.clone();
+        let backend = super::s3::S3Backend::new(config).await?;
+        backend.verify_encryption().await?;
+        shards.push(Shard {
+            name: bucket,
+            backend: Arc::new(backend),
+        });
+    }
+    ShardedStorage::new(shards).map(Some)
+}
+
+/// Azure Blob sharded over `AZURE_SHARD_CONTAINERS`, each container
+/// otherwise configured like `AZURE_STORAGE_CONTAINER`.
+pub async fn azure_from_env() -> Result<Option<ShardedStorage>> {
+    let Some(containers) = shard_names_from_env("AZURE_SHARD_CONTAINERS") else {
+        return Ok(None);
+    };
+    let base = super::azure::AzureConfig::from_env()?;
+    let mut shards = Vec::with_capacity(containers.len());
+    for container in containers {
+        let mut config = base.clone();
+        config.container_name = container.clone();
+        let backend = super::azure::AzureBackend::new(config).await?;
+        shards.push(Shard {
+            name: container,
+            backend: Arc::new(backend),
+        });
+    }
+    ShardedStorage::new(shards).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::filesystem::FilesystemStorage;
+
+    fn sharded(dir: &tempfile::TempDir, names: &[&str]) -> ShardedStorage {
+        ShardedStorage::new(
+            names
+                .iter()
+                .map(|name| Shard {
+                    name: name.to_string(),
+                    backend: Arc::new(FilesystemStorage::new(dir.path().join(name))),
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_adding_a_shard_moves_only_keys_it_wins() {
+        let before = ["a", "b", "c"];
+        let after = ["a", "b", "c", "d"];
+        let mut moved = 0;
+        for i in 0..2000 {
+            let key = format!("sha256/{i:064}");
+            let old = before[owner_index(&before, &key)];
+            let new = after[owner_index(&after, &key)];
+            if old != new {
+                assert_eq!(new, "d", "key {key} moved between existing shards");
+                moved += 1;
+            }
+        }
+        // Expect about a quarter of the keys to move.
+        assert!((300..700).contains(&moved), "moved {moved} of 2000");
+    }
+
+    #[test]
+    fn test_owner_is_independent_of_shard_order() {
+        let key = "maven/com/example/app/1.0/app-1.0.jar";
+        let forward = ["x", "y", "z"];
+        let reverse = ["z", "y", "x"];
+        assert_eq!(
+            forward[owner_index(&forward, key)],
+            reverse[owner_index(&reverse, key)]
+        );
+    }
+
+    #[test]
+    fn test_parse_shard_names() {
+        assert_eq!(
+            parse_shard_names(" b1, b2,,b1 ,b3"),
+            vec!["b1".to_string(), "b2".to_string(), "b3".to_string()]
+        );
+        assert!(parse_shard_names("").is_empty());
+    }
+
+    #[test]
+    fn test_new_rejects_duplicate_names() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let shard = |name: &str| Shard {
+            name: name.to_string(),
+            backend: Arc::new(FilesystemStorage::new(dir.path())),
+        };
+        assert!(ShardedStorage::new(vec![shard("a"), shard("a")]).is_err());
+        assert!(ShardedStorage::new(Vec::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reads_fall_back_until_rebalanced() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let old = sharded(&dir, &["a"]);
+        let keys: Vec<String> = (0..20).map(|i| format!("blob-{i}")).collect();
+        for key in &keys {
+            old.put(key, Bytes::from(key.clone())).await.unwrap();
+        }
+
+        let grown = sharded(&dir, &["a", "b"]);
+        let mut moved = 0;
+        for key in &keys {
+            assert_eq!(grown.get(key).await.unwrap(), Bytes::from(key.clone()));
+            match grown.rebalance_key(key).await.unwrap() {
+                RebalanceOutcome::Moved => moved += 1,
+                RebalanceOutcome::InPlace => {}
+                RebalanceOutcome::Missing => panic!("{key} went missing"),
+            }
+            assert!(grown.owner(key).backend.exists(key).await.unwrap());
+            assert_eq!(grown.get(key).await.unwrap(), Bytes::from(key.clone()));
+        }
+        assert!(moved > 0, "no key moved to the new shard");
+        assert_eq!(
+            grown.rebalance_key("never-stored").await.unwrap(),
+            RebalanceOutcome::Missing
+        );
+
+        grown.delete(&keys[0]).await.unwrap();
+        assert!(!grown.exists(&keys[0]).await.unwrap());
+    }
+
+    /// Shard that records direct uploads and serves a fixed list of
+    /// soft-deleted keys.
+    #[derive(Default)]
+    struct RecordingShard {
+        soft_deleted: Vec<String>,
+        restored: std::sync::Mutex<Vec<String>>,
+        uploads: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl StorageBackend for RecordingShard {
+        async fn put(&self, _key: &str, _content: Bytes) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get(&self, key: &str) -> Result<Bytes> {
+            Err(AppError::NotFound(key.to_string()))
+        }
+
+        async fn exists(&self, _key: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn delete(&self, _key: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn direct_uploads(&self) -> Option<&dyn DirectUploadStore> {
+            Some(self)
+        }
+
+        fn soft_delete_recovery(&self) -> Option<&dyn SoftDeleteRecovery> {
+            Some(self)
+        }
+    }
+
+    #[async_trait]
+    impl DirectUploadStore for RecordingShard {
+        async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+            self.uploads.lock().unwrap().push(key.to_string());
+            Ok(format!("upload-{key}"))
+        }
+
+        async fn presign_upload_part(
+            &self,
+            key: &str,
+            _upload_id: &str,
+            part_number: u32,
+            _expires_in: Duration,
+        ) -> Result<String> {
+            Ok(format!("https://store.test/{key}?part={part_number}"))
+        }
+
+        async fn complete_multipart_upload(
+            &self,
+            _key: &str,
+            _upload_id: &str,
+            _parts: &[UploadedPart],
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn abort_multipart_upload(&self, _key: &str, _upload_id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl SoftDeleteRecovery for RecordingShard {
+        async fn list_soft_deleted(
+            &self,
+            _prefix: Option<&str>,
+            marker: Option<&str>,
+            limit: usize,
+        ) -> Result<SoftDeletedPage> {
+            let start = marker.map_or(0, |m| m.parse::<usize>().unwrap());
+            let end = (start + limit).min(self.soft_deleted.len());
+            Ok(SoftDeletedPage {
+                objects: self.soft_deleted[start..end]
+                    .iter()
+                    .map(|key| crate::storage::SoftDeletedObject {
+                        key: key.clone(),
+                        size_bytes: 0,
+                        deleted_at: None,
+                        remaining_retention_days: None,
+                    })
+                    .collect(),
+                next_marker: (end < self.soft_deleted.len()).then(|| end.to_string()),
+            })
+        }
+
+        async fn undelete(&self, key: &str) -> Result<()> {
+            if !self.soft_deleted.iter().any(|k| k == key) {
+                return Err(AppError::NotFound(key.to_string()));
+            }
+            self.restored.lock().unwrap().push(key.to_string());
+            Ok(())
+        }
+    }
+
+    fn recording(shards: &[(&str, Arc<RecordingShard>)]) -> ShardedStorage {
+        ShardedStorage::new(
+            shards
+                .iter()
+                .map(|(name, shard)| Shard {
+                    name: name.to_string(),
+                    backend: shard.clone(),
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_direct_uploads_go_to_the_owning_shard() {
+        let a = Arc::new(RecordingShard::default());
+        let b = Arc::new(RecordingShard::default());
+        let storage = recording(&[("a", a.clone()), ("b", b.clone())]);
+        let direct = storage.direct_uploads().expect("all shards support it");
+
+        let keys: Vec<String> = (0..20).map(|i| format!("upload-{i}")).collect();
+        for key in &keys {
+            assert_eq!(
+                direct.create_multipart_upload(key).await.unwrap(),
+                format!("upload-{key}")
+            );
+        }
+        for key in &keys {
+            let (owner, other) = if storage.owner(key).name == "a" {
+                (&a, &b)
+            } else {
+                (&b, &a)
+            };
+            assert!(owner.uploads.lock().unwrap().contains(key));
+            assert!(!other.uploads.lock().unwrap().contains(key));
+        }
+
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(sharded(&dir, &["a", "b"]).direct_uploads().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_recovery_pages_across_shards() {
+        let a = Arc::new(RecordingShard {
+            soft_deleted: vec!["a1".into(), "a2".into(), "a3".into(), "both".into()],
+            ..Default::default()
+        });
+        let b = Arc::new(RecordingShard {
+            soft_deleted: vec!["b1".into(), "both".into()],
+            ..Default::default()
+        });
+        let storage = recording(&[("a", a.clone()), ("b", b.clone())]);
+        let recovery = storage
+            .soft_delete_recovery()
+            .expect("all shards support it");
+
+        let mut listed = Vec::new();
+        let mut marker: Option<String> = None;
+        let mut pages = 0;
+        loop {
+            let page = recovery
+                .list_soft_deleted(None, marker.as_deref(), 4)
+                .await
+                .unwrap();
+            assert!(page.objects.len() <= 4);
+            listed.extend(page.objects.into_iter().map(|o| o.key));
+            pages += 1;
+            match page.next_marker {
+                Some(next) => marker = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(listed, ["a1", "a2", "a3", "both", "b1", "both"]);
+        assert_eq!(pages, 2);
+        assert!(recovery
+            .list_soft_deleted(None, Some("unknown:0"), 4)
+            .await
+            .is_err());
+
+        recovery.undelete("both").await.unwrap();
+        recovery.undelete("b1").await.unwrap();
+        assert_eq!(*a.restored.lock().unwrap(), ["both"]);
+        assert_eq!(*b.restored.lock().unwrap(), ["both", "b1"]);
+        assert!(matches!(
+            recovery.undelete("missing").await,
+            Err(AppError::NotFound(_))
+        ));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(sharded(&dir, &["a"]).soft_delete_recovery().is_none());
+    }
+}
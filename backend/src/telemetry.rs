@@ -12,11 +12,20 @@
 //! The diagnostics stdout format is selected via `LOG_FORMAT`:
 //!   - `pretty` (default) -- the human-readable multi-line `fmt` output
 //!   - `json` -- one JSON object per line, for structured stdout collection by a SIEM / log shipper (#2413 item 1)
+//!
+//! The `RUST_LOG` filter sits behind a reload layer so a configuration
+//! reload can change it without restarting (see [`reload_log_filter`]).
 
 use opentelemetry::KeyValue;
 use opentelemetry_otlp::{SpanExporter, WithExportConfig};
 use opentelemetry_sdk::Resource;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+use std::sync::OnceLock;
+
+use tracing_subscriber::{
+    layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
+
+use crate::services::edge_diagnostics_service::LogCaptureLayer;
 
 /// Diagnostics stdout log format, selected via `LOG_FORMAT` (#2413 item 1).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -52,6 +61,34 @@ impl LogFormat {
     }
 }
 
+/// Filter used when `RUST_LOG` is unset or does not parse.
+const DEFAULT_LOG_FILTER: &str = "artifact_keeper_backend=debug,tower_http=debug,sqlx::query=info";
+
+/// Handle to the live filter, set once by [`init_tracing`].
+static LOG_FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// The filter directives `RUST_LOG` currently selects, falling back to the
+/// built-in default the same way [`init_tracing`] does.
+pub fn log_filter_from_env() -> String {
+    std::env::var("RUST_LOG")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_LOG_FILTER.to_string())
+}
+
+/// Replace the active log filter. Fails, leaving the current filter in
+/// place, when the directives do not parse or tracing was not set up through
+/// [`init_tracing`].
+pub fn reload_log_filter(directives: &str) -> Result<(), String> {
+    let filter = EnvFilter::try_new(directives)
+        .map_err(|e| format!("invalid log filter {directives:?}: {e}"))?;
+    LOG_FILTER_HANDLE
+        .get()
+        .ok_or_else(|| "log filter is not reloadable in this process".to_string())?
+        .reload(filter)
+        .map_err(|e| e.to_string())
+}
+
 /// Build the diagnostics `fmt` layer in the configured format.
 ///
 /// Boxed so both arms have the same type regardless of the concrete
@@ -133,9 +170,10 @@ fn build_span_exporter(protocol: OtlpProtocol, endpoint: &str) -> SpanExporter {
 /// Returns an optional guard that must be held for the lifetime of the
 /// application to ensure spans are flushed on shutdown.
 pub fn init_tracing(otel_endpoint: Option<&str>, service_name: &str) -> Option<OtelGuard> {
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-        "artifact_keeper_backend=debug,tower_http=debug,sqlx::query=info".into()
-    });
+    let env_filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| DEFAULT_LOG_FILTER.into());
+    let (env_filter, filter_handle) = reload::Layer::new(env_filter);
+    let _ = LOG_FILTER_HANDLE.set(filter_handle);
     let log_format = LogFormat::from_env();
 
     match otel_endpoint {
@@ -155,6 +193,7 @@ pub fn init_tracing(otel_endpoint: Option<&str>, service_name: &str) -> Option<O
             tracing_subscriber::registry()
                 .with(env_filter)
                 .with(build_fmt_layer(log_format))
+                .with(LogCaptureLayer)
                 .init();
             None
         }
@@ -178,7 +217,7 @@ impl Drop for OtelGuard {
 fn init_with_otel(
     endpoint: &str,
     service_name: &str,
-    env_filter: EnvFilter,
+    env_filter: reload::Layer<EnvFilter, Registry>,
     protocol: OtlpProtocol,
     log_format: LogFormat,
 ) -> OtelGuard {
@@ -199,6 +238,7 @@ fn init_with_otel(
     tracing_subscriber::registry()
         .with(env_filter)
         .with(build_fmt_layer(log_format))
+        .with(LogCaptureLayer)
         .with(otel_layer)
         .init();
 
@@ -0,0 +1,642 @@
+//! Upload-time archive content policy.
+//!
+//! [`bounded_archive`](super::bounded_archive) bounds the metadata extractors,
+//! which read one small file and stop. Generic uploads are different: the
+//! whole body is handed to WASM format plugins, and clients that download the
+//! artifact unpack all of it. Before either happens, an upload that looks like
+//! an archive gets a full walk here that rejects:
+//!
+//! 1. a **decompression ratio** above [`DEFAULT_MAX_INGEST_COMPRESSION_RATIO`]
+//!    (env [`MAX_INGEST_COMPRESSION_RATIO_ENV`]): every decoded byte at every
+//!    level counts against `ratio × upload size`, never less than the ingest
+//!    decompression budget so small, highly compressible files still pass,
+//! 2. more than [`DEFAULT_MAX_INGEST_UPLOAD_ARCHIVE_ENTRIES`] members in total
+//!    (env [`MAX_INGEST_UPLOAD_ARCHIVE_ENTRIES_ENV`]),
+//! 3. archives nested deeper than [`DEFAULT_MAX_INGEST_ARCHIVE_DEPTH`] levels
+//!    (env [`MAX_INGEST_ARCHIVE_DEPTH_ENV`]); a compressed tar is one level,
+//!    a jar inside a zip is two,
+//! 4. member paths that leave the extraction root (zip-slip): absolute paths,
+//!    drive letters and `..` components that climb above the root, including
+//!    tar symlink and hardlink targets.
+//!
+//! Archives are recognised from their first bytes, not the file name; other
+//! uploads pass without being read past the first 512 bytes. Breaches surface
+//! as [`AppError::Validation`] (HTTP 400).
+
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use super::bounded_archive::{
+    acquire_ingest_extraction, max_ingest_decompressed_bytes, positive_env_or, read_capped,
+    MAX_INGEST_METADATA_ENTRY_BYTES,
+};
+use crate::error::{AppError, Result};
+
+/// Largest accepted ratio of decoded bytes to upload bytes. Source tarballs
+/// and jars sit well below 20x; only degenerate content goes past 200x.
+pub const DEFAULT_MAX_INGEST_COMPRESSION_RATIO: u64 = 200;
+
+/// Env var overriding [`DEFAULT_MAX_INGEST_COMPRESSION_RATIO`].
+pub const MAX_INGEST_COMPRESSION_RATIO_ENV: &str = "MAX_INGEST_COMPRESSION_RATIO";
+
+/// Maximum members across all nesting levels of one upload. Higher than
+/// [`MAX_INGEST_ARCHIVE_ENTRIES`](super::bounded_archive::MAX_INGEST_ARCHIVE_ENTRIES)
+/// because the whole archive is walked, not just the part before a metadata
+/// file.
+pub const DEFAULT_MAX_INGEST_UPLOAD_ARCHIVE_ENTRIES: u64 = 100_000;
+
+/// Env var overriding [`DEFAULT_MAX_INGEST_UPLOAD_ARCHIVE_ENTRIES`].
+pub const MAX_INGEST_UPLOAD_ARCHIVE_ENTRIES_ENV: &str = "MAX_INGEST_UPLOAD_ARCHIVE_ENTRIES";
+
+/// Maximum archive nesting depth. Three levels admit a fat jar inside a
+/// distribution zip; deeper nesting is only seen in recursive bombs.
+pub const DEFAULT_MAX_INGEST_ARCHIVE_DEPTH: u64 = 3;
+
+/// Env var overriding [`DEFAULT_MAX_INGEST_ARCHIVE_DEPTH`].
+pub const MAX_INGEST_ARCHIVE_DEPTH_ENV: &str = "MAX_INGEST_ARCHIVE_DEPTH";
+
+/// A zip nested inside another archive has no seekable reader, so it is
+/// buffered to be walked. Larger nested zips are rejected rather than passed
+/// through uninspected.
+pub const MAX_NESTED_ZIP_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Bytes sniffed to recognise an archive; covers the tar `ustar` magic.
+const SNIFF_LEN: usize = 512;
+
+/// Marker embedded in the ratio-breach `io::Error`, like `BOMB_SENTINEL` in
+/// `bounded_archive`.
+const RATIO_SENTINEL: &str = "archive decompression ratio exceeded";
+
+/// Limits applied by [`inspect_archive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveLimits {
+    pub max_ratio: u64,
+    pub max_entries: u64,
+    pub max_depth: u64,
+    /// Decoded bytes always allowed, whatever the ratio.
+    pub min_budget: u64,
+}
+
+impl ArchiveLimits {
+    /// Limits from the environment, falling back to the defaults.
+    pub fn from_env() -> Self {
+        Self {
+            max_ratio: positive_env_or(
+                MAX_INGEST_COMPRESSION_RATIO_ENV,
+                DEFAULT_MAX_INGEST_COMPRESSION_RATIO,
+            ),
+            max_entries: positive_env_or(
+                MAX_INGEST_UPLOAD_ARCHIVE_ENTRIES_ENV,
+                DEFAULT_MAX_INGEST_UPLOAD_ARCHIVE_ENTRIES,
+            ),
+            max_depth: positive_env_or(
+                MAX_INGEST_ARCHIVE_DEPTH_ENV,
+                DEFAULT_MAX_INGEST_ARCHIVE_DEPTH,
+            ),
+            min_budget: max_ingest_decompressed_bytes(),
+        }
+    }
+
+    /// Total decoded bytes allowed for an upload of `upload_len` bytes.
+    fn decoded_budget(&self, upload_len: u64) -> u64 {
+        self.max_ratio
+            .saturating_mul(upload_len)
+            .max(self.min_budget)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Tar,
+    Zip,
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+/// Recognise an archive or compressed stream from its first bytes.
+fn sniff(prefix: &[u8]) -> Option<ArchiveKind> {
+    if prefix.starts_with(&[0x1f, 0x8b]) {
+        Some(ArchiveKind::Gzip)
+    } else if prefix.len() >= 4 && prefix.starts_with(b"BZh") && prefix[3].is_ascii_digit() {
+        Some(ArchiveKind::Bzip2)
+    } else if prefix.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+        Some(ArchiveKind::Xz)
+    } else if prefix.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Some(ArchiveKind::Zstd)
+    } else if prefix.starts_with(b"PK\x03\x04") || prefix.starts_with(b"PK\x05\x06") {
+        Some(ArchiveKind::Zip)
+    } else if prefix.len() >= 262 && &prefix[257..262] == b"ustar" {
+        Some(ArchiveKind::Tar)
+    } else {
+        None
+    }
+}
+
+/// Fill `buf` from `reader` until it is full or the stream ends.
+fn read_prefix(reader: &mut dyn Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// True when `path` resolves outside the directory it is extracted into.
+/// Backslashes count as separators so Windows consumers are covered too.
+fn escapes_root(path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    let bytes = path.as_bytes();
+    if path.starts_with('/')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+    {
+        return true;
+    }
+    let mut depth: i64 = 0;
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                depth -= 1;
+                if depth < 0 {
+                    return true;
+                }
+            }
+            _ => depth += 1,
+        }
+    }
+    false
+}
+
+/// Reject an archive member whose path would be written outside the
+/// extraction root (zip-slip).
+pub fn check_member_path(name: &str) -> Result<()> {
+    if escapes_root(name) {
+        return Err(AppError::Validation(format!(
+            "Archive member path escapes the archive root: {}",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Reject a symlink whose target, resolved from the link's own directory,
+/// points outside the extraction root.
+fn check_symlink_target(link: &str, target: &str) -> Result<()> {
+    let dir = link
+        .trim_end_matches('/')
+        .rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or("");
+    let resolved = if dir.is_empty() || target.starts_with('/') {
+        target.to_string()
+    } else {
+        format!("{}/{}", dir, target)
+    };
+    if escapes_root(&resolved) {
+        return Err(AppError::Validation(format!(
+            "Archive link {} points outside the archive root: {}",
+            link, target
+        )));
+    }
+    Ok(())
+}
+
+/// Translate an `io::Error` from a walk into a validation error, naming a
+/// ratio breach explicitly.
+fn map_inspect_err(context: &str, err: &dyn std::fmt::Display) -> AppError {
+    let message = err.to_string();
+    if message.contains(RATIO_SENTINEL) {
+        AppError::Validation(
+            "Archive expands beyond the allowed decompression ratio; refusing suspected decompression bomb"
+                .to_string(),
+        )
+    } else {
+        AppError::Validation(format!("{}: {}", context, message))
+    }
+}
+
+/// Counts decoded bytes against the budget shared by every level of one
+/// inspection.
+struct Metered<R> {
+    inner: R,
+    remaining: Rc<Cell<u64>>,
+    read: u64,
+}
+
+impl<R: Read> Metered<R> {
+    fn new(inner: R, remaining: Rc<Cell<u64>>) -> Self {
+        Self {
+            inner,
+            remaining,
+            read: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for Metered<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        let left = self.remaining.get();
+        if n as u64 > left {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, RATIO_SENTINEL));
+        }
+        self.remaining.set(left - n as u64);
+        self.read += n as u64;
+        Ok(n)
+    }
+}
+
+struct Inspector {
+    limits: ArchiveLimits,
+    remaining: Rc<Cell<u64>>,
+    entries: u64,
+}
+
+impl Inspector {
+    fn new(limits: ArchiveLimits, upload_len: u64) -> Self {
+        Self {
+            limits,
+            remaining: Rc::new(Cell::new(limits.decoded_budget(upload_len))),
+            entries: 0,
+        }
+    }
+
+    /// Enter one more archive level below `depth`.
+    fn enter(&self, depth: u64) -> Result<u64> {
+        let depth = depth + 1;
+        if depth > self.limits.max_depth {
+            return Err(AppError::Validation(format!(
+                "Archive nesting exceeds {} levels; refusing nested archive",
+                self.limits.max_depth
+            )));
+        }
+        Ok(depth)
+    }
+
+    fn count_entry(&mut self) -> Result<()> {
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(AppError::Validation(format!(
+                "Archive contains too many entries (> {}); refusing suspected decompression bomb",
+                self.limits.max_entries
+            )));
+        }
+        Ok(())
+    }
+
+    /// Charge bytes that are accounted for without being decoded.
+    fn charge(&self, bytes: u64) -> Result<()> {
+        let left = self.remaining.get();
+        if bytes > left {
+            return Err(map_inspect_err("", &RATIO_SENTINEL));
+        }
+        self.remaining.set(left - bytes);
+        Ok(())
+    }
+
+    /// Inspect a stream found at `depth`: a member of an archive at that
+    /// level, or the upload itself at depth 0. Non-archives are left unread
+    /// past the sniffed prefix.
+    fn inspect_stream(&mut self, reader: &mut dyn Read, depth: u64) -> Result<()> {
+        let mut prefix = vec![0u8; SNIFF_LEN];
+        let n =
+            read_prefix(reader, &mut prefix).map_err(|e| map_inspect_err("Invalid archive", &e))?;
+        prefix.truncate(n);
+        let Some(kind) = sniff(&prefix) else {
+            return Ok(());
+        };
+        let mut stream = io::Cursor::new(prefix).chain(reader);
+
+        match kind {
+            ArchiveKind::Tar => {
+                let depth = self.enter(depth)?;
+                self.walk_tar(&mut stream, depth)
+            }
+            ArchiveKind::Zip => {
+                let depth = self.enter(depth)?;
+                let bytes = read_capped(&mut stream, MAX_NESTED_ZIP_BYTES, "nested ZIP archive")?;
+                self.walk_zip(io::Cursor::new(bytes), depth)
+            }
+            ArchiveKind::Gzip | ArchiveKind::Bzip2 | ArchiveKind::Xz | ArchiveKind::Zstd => {
+                let decoder: Box<dyn Read + '_> = match kind {
+                    ArchiveKind::Gzip => Box::new(flate2::read::MultiGzDecoder::new(stream)),
+                    ArchiveKind::Bzip2 => Box::new(bzip2::read::BzDecoder::new(stream)),
+                    ArchiveKind::Xz => Box::new(xz2::read::XzDecoder::new(stream)),
+                    _ => Box::new(zstd::Decoder::new(stream).map_err(|e| {
+                        AppError::Validation(format!("Invalid zstd stream: {}", e))
+                    })?),
+                };
+                let mut decoded = Metered::new(decoder, self.remaining.clone());
+                self.inspect_decoded(&mut decoded, depth)
+            }
+        }
+    }
+
+    /// Inspect the output of a decompressor. A compressed tar is one level;
+    /// any other compressed payload is a level of its own and is decoded to
+    /// the end so its size counts against the budget.
+    fn inspect_decoded(&mut self, decoded: &mut dyn Read, depth: u64) -> Result<()> {
+        let mut prefix = vec![0u8; SNIFF_LEN];
+        let n = read_prefix(decoded, &mut prefix)
+            .map_err(|e| map_inspect_err("Invalid compressed stream", &e))?;
+        prefix.truncate(n);
+        let depth = self.enter(depth)?;
+        let kind = sniff(&prefix);
+        let mut stream = io::Cursor::new(prefix).chain(decoded);
+
+        match kind {
+            Some(ArchiveKind::Tar) => self.walk_tar(&mut stream, depth),
+            Some(_) => self.inspect_stream(&mut stream, depth),
+            None => io::copy(&mut stream, &mut io::sink())
+                .map(|_| ())
+                .map_err(|e| map_inspect_err("Invalid compressed stream", &e)),
+        }
+    }
+
+    fn walk_tar(&mut self, reader: &mut dyn Read, depth: u64) -> Result<()> {
+        let mut archive = tar::Archive::new(reader);
+        let entries = archive
+            .entries()
+            .map_err(|e| map_inspect_err("Invalid archive", &e))?;
+
+        for entry in entries {
+            let mut entry = entry.map_err(|e| map_inspect_err("Invalid archive entry", &e))?;
+            self.count_entry()?;
+
+            let path = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+            check_member_path(&path)?;
+
+            let entry_type = entry.header().entry_type();
+            if let Some(target) = entry.link_name_bytes() {
+                let target = String::from_utf8_lossy(&target).into_owned();
+                if entry_type.is_symlink() {
+                    check_symlink_target(&path, &target)?;
+                } else if entry_type.is_hard_link() {
+                    check_member_path(&target)?;
+                }
+            }
+
+            if entry_type.is_file() {
+                self.inspect_stream(&mut entry, depth)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn walk_zip<R: Read + Seek>(&mut self, reader: R, depth: u64) -> Result<()> {
+        let mut archive =
+            zip::ZipArchive::new(reader).map_err(|e| map_inspect_err("Invalid ZIP archive", &e))?;
+
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|e| map_inspect_err("Cannot read ZIP entry", &e))?;
+            self.count_entry()?;
+            check_member_path(file.name())?;
+            if !file.is_file() {
+                continue;
+            }
+
+            // Reject an honest bomb from its header before inflating it.
+            let size = file.size();
+            if size > MAX_INGEST_METADATA_ENTRY_BYTES
+                && size / file.compressed_size().max(1) > self.limits.max_ratio
+            {
+                return Err(map_inspect_err("", &RATIO_SENTINEL));
+            }
+
+            let mut metered = Metered::new(&mut file, self.remaining.clone());
+            self.inspect_stream(&mut metered, depth)?;
+            // The rest of a non-archive member is never inflated here, but
+            // whoever unpacks the upload will; charge its declared size.
+            let read = metered.read;
+            self.charge(size.saturating_sub(read))?;
+        }
+        Ok(())
+    }
+}
+
+/// Apply the content policy to an upload of `upload_len` bytes read from
+/// `reader`. Non-archives pass after a 512-byte sniff.
+pub fn inspect_archive<R: Read + Seek>(
+    mut reader: R,
+    upload_len: u64,
+    limits: &ArchiveLimits,
+) -> Result<()> {
+    let mut prefix = [0u8; SNIFF_LEN];
+    let n = read_prefix(&mut reader, &mut prefix)
+        .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))?;
+    let Some(kind) = sniff(&prefix[..n]) else {
+        return Ok(());
+    };
+    reader
+        .seek(SeekFrom::Start(0))
+        .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))?;
+
+    let mut inspector = Inspector::new(*limits, upload_len);
+    if kind == ArchiveKind::Zip {
+        let depth = inspector.enter(0)?;
+        inspector.walk_zip(reader, depth)
+    } else {
+        inspector.inspect_stream(&mut reader, 0)
+    }
+}
+
+/// Apply the content policy to a staged upload on disk. Runs on a blocking
+/// thread and holds an ingest extraction slot while it decodes, shedding with
+/// 503 like the metadata extractors when the server is saturated.
+pub async fn inspect_staged_upload(path: &Path) -> Result<()> {
+    let path: PathBuf = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let file = File::open(&path)
+            .map_err(|e| AppError::Internal(format!("Failed to open staged upload: {}", e)))?;
+        let upload_len = file
+            .metadata()
+            .map_err(|e| AppError::Internal(format!("Failed to stat staged upload: {}", e)))?
+            .len();
+        let mut reader = BufReader::new(file);
+
+        let mut prefix = [0u8; SNIFF_LEN];
+        let n = read_prefix(&mut reader, &mut prefix)
+            .map_err(|e| AppError::Internal(format!("Failed to read staged upload: {}", e)))?;
+        if sniff(&prefix[..n]).is_none() {
+            return Ok(());
+        }
+        let _permit = acquire_ingest_extraction()?;
+        inspect_archive(reader, upload_len, &ArchiveLimits::from_env())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Archive inspection task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn limits() -> ArchiveLimits {
+        ArchiveLimits {
+            max_ratio: 20,
+            max_entries: 100,
+            max_depth: 2,
+            min_budget: 64 * 1024,
+        }
+    }
+
+    fn tar_header(name: &str, size: u64) -> tar::Header {
+        let mut header = tar::Header::new_gnu();
+        // Written raw: `set_path` refuses the `..` paths these tests need.
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_size(size);
+        header.set_mode(0o644);
+        header.set_cksum();
+        header
+    }
+
+    fn plain_tar(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, data) in entries {
+            builder
+                .append(&tar_header(name, data.len() as u64), *data)
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut cursor = Cursor::new(Vec::new());
+        {
+            let mut w = zip::ZipWriter::new(&mut cursor);
+            let opts: zip::write::FileOptions<'_, ()> = zip::write::FileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated);
+            for (name, data) in entries {
+                w.start_file(*name, opts).unwrap();
+                w.write_all(data).unwrap();
+            }
+            w.finish().unwrap();
+        }
+        cursor.into_inner()
+    }
+
+    fn inspect(bytes: &[u8]) -> Result<()> {
+        inspect_archive(Cursor::new(bytes), bytes.len() as u64, &limits())
+    }
+
+    #[test]
+    fn test_escapes_root() {
+        assert!(!escapes_root("a/b.txt"));
+        assert!(!escapes_root("./a/../b.txt"));
+        assert!(escapes_root("../evil"));
+        assert!(escapes_root("a/../../evil"));
+        assert!(escapes_root("/etc/passwd"));
+        assert!(escapes_root("C:/Windows/evil.dll"));
+        assert!(escapes_root("a\\..\\..\\evil"));
+    }
+
+    #[test]
+    fn test_symlink_target_resolves_from_link_dir() {
+        assert!(check_symlink_target("a/b/link", "../c").is_ok());
+        assert!(check_symlink_target("a/link", "../../etc/passwd").is_err());
+        assert!(check_symlink_target("link", "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_non_archive_passes() {
+        assert!(inspect(b"just some text").is_ok());
+        assert!(inspect(&[0u8; 4096]).is_ok());
+    }
+
+    #[test]
+    fn test_normal_archives_pass() {
+        let tar = plain_tar(&[("pkg/README", b"hello"), ("pkg/lib.so", b"\x7fELF")]);
+        assert!(inspect(&tar).is_ok());
+        assert!(inspect(&gzip(&tar)).is_ok());
+        assert!(inspect(&zip_bytes(&[("a/b.txt", b"hello")])).is_ok());
+    }
+
+    #[test]
+    fn test_traversal_rejected() {
+        let tar = gzip(&plain_tar(&[("../../evil.sh", b"x")]));
+        let err = inspect(&tar).unwrap_err();
+        assert!(err.to_string().contains("escapes the archive root"));
+
+        let zip = zip_bytes(&[("ok.txt", b"x"), ("../evil.sh", b"x")]);
+        assert!(inspect(&zip).is_err());
+    }
+
+    #[test]
+    fn test_escaping_symlink_rejected() {
+        let mut header = tar_header("a/link", 0);
+        let target = b"../../etc/passwd";
+        header.as_old_mut().linkname[..target.len()].copy_from_slice(target);
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_cksum();
+        let mut builder = tar::Builder::new(Vec::new());
+        builder.append(&header, &[][..]).unwrap();
+        let tar = builder.into_inner().unwrap();
+        assert!(inspect(&tar).is_err());
+    }
+
+    #[test]
+    fn test_ratio_breach_rejected() {
+        // 1 MiB of zeros compresses to about 1 KiB.
+        let bomb = gzip(&vec![0u8; 1024 * 1024]);
+        let err = inspect(&bomb).unwrap_err();
+        assert!(err.to_string().contains("decompression ratio"));
+
+        let zip = zip_bytes(&[("zeros.bin", &vec![0u8; 1024 * 1024])]);
+        assert!(inspect(&zip).is_err());
+    }
+
+    #[test]
+    fn test_small_compressible_file_within_floor() {
+        // Far above the ratio, but below the minimum budget.
+        assert!(inspect(&gzip(&vec![0u8; 32 * 1024])).is_ok());
+    }
+
+    #[test]
+    fn test_entry_count_rejected() {
+        let entries: Vec<(String, Vec<u8>)> =
+            (0..150).map(|i| (format!("f{}", i), vec![b'a'])).collect();
+        let refs: Vec<(&str, &[u8])> = entries
+            .iter()
+            .map(|(n, d)| (n.as_str(), d.as_slice()))
+            .collect();
+        let err = inspect(&plain_tar(&refs)).unwrap_err();
+        assert!(err.to_string().contains("too many entries"));
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let level1 = zip_bytes(&[("inner.txt", b"x")]);
+        let level2 = zip_bytes(&[("lib/inner.jar", &level1)]);
+        assert!(inspect(&level2).is_ok());
+
+        let level3 = gzip(&plain_tar(&[("dist.zip", &level2)]));
+        let err = inspect(&level3).unwrap_err();
+        assert!(err.to_string().contains("nesting exceeds 2 levels"));
+    }
+
+    #[test]
+    fn test_nested_gzip_counts_as_a_level() {
+        assert!(inspect(&gzip(b"payload")).is_ok());
+        assert!(inspect(&gzip(&gzip(b"payload"))).is_ok());
+        assert!(inspect(&gzip(&gzip(&gzip(b"payload")))).is_err());
+    }
+}
@@ -46,6 +46,7 @@ use std::sync::{Arc, OnceLock};
 
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
+use super::archive_policy::check_member_path;
 use crate::error::{AppError, Result};
 
 /// Total decompressed bytes any single ingestion metadata-extraction may
@@ -415,7 +416,8 @@ pub fn read_capped<R: Read>(reader: R, cap: u64, what: &str) -> Result<Vec<u8>>
 /// entry through the per-entry cap. The total-byte budget is enforced by the
 /// [`BudgetReader`] the caller wrapped `archive_reader` in, so *skipped* entries
 /// (which tar must still inflate to reach the next header) also count against
-/// the budget — defeating the pre-target-inflation bomb.
+/// the budget — defeating the pre-target-inflation bomb. A member path that
+/// escapes the archive root rejects the whole archive.
 fn read_tar_entries<R: Read>(
     archive_reader: R,
     matches: impl Fn(&Path) -> bool,
@@ -444,6 +446,7 @@ fn read_tar_entries<R: Read>(
             .path()
             .map_err(|e| map_archive_err("Invalid entry path", &e))?
             .to_path_buf();
+        check_member_path(&path.to_string_lossy())?;
 
         if matches(&path) {
             let bytes = read_capped(&mut entry, max_entry, "archive metadata entry")?;
@@ -697,6 +700,7 @@ pub fn read_metadata_from_zip_limited<R: Read + Seek>(
         let mut file = archive
             .by_index(i)
             .map_err(|e| AppError::Validation(format!("Cannot read ZIP entry: {}", e)))?;
+        check_member_path(file.name())?;
         if !file.is_file() {
             continue;
         }
@@ -918,6 +922,15 @@ mod tests {
         assert!(err.is_err(), "oversized zip entry must reject");
     }
 
+    #[test]
+    fn zip_traversal_member_is_rejected() {
+        let archive = zip_bytes(&[("../../evil.nuspec", b"<id>Evil</id>")]);
+        let cursor = std::io::Cursor::new(&archive);
+        let err =
+            read_metadata_from_zip_limited(cursor, |n| n.ends_with(".nuspec"), 1000, 1024 * 1024);
+        assert!(err.is_err(), "zip-slip member must reject");
+    }
+
     #[test]
     fn zip_entry_count_breach_is_rejected() {
         let entries: Vec<(String, Vec<u8>)> = (0..20)
@@ -1,4 +1,5 @@
 //! Small, dependency-free utilities shared across the backend.
 
+pub mod archive_policy;
 pub mod bounded_archive;
 pub mod glob;
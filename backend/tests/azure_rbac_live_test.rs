@@ -31,6 +31,7 @@ async fn test_azure_rbac_put_get_exists_delete() {
         redirect_downloads: false,
         sas_expiry: std::time::Duration::from_secs(3600),
         path_format: artifact_keeper_backend::storage::StoragePathFormat::Native,
+        upload: artifact_keeper_backend::storage::azure::AzureUploadConfig::default(),
     };
 
     use artifact_keeper_backend::storage::StorageBackend;
@@ -42,6 +42,7 @@ fn test_config(storage_path: &str) -> Config {
         s3_endpoint: None,
         jwt_secret: "test-secret-at-least-32-bytes-long-for-testing".into(),
         setup_password_hint: None,
+        setup_wizard: false,
         jwt_expiration_secs: 86400,
         jwt_access_token_expiry_minutes: 30,
         jwt_refresh_token_expiry_days: 7,
@@ -64,6 +65,18 @@ fn test_config(storage_path: &str) -> Config {
         guest_access_enabled: true,
         expose_detailed_health: false,
         grpc_reflection_enabled: false,
+        grpc_keepalive_interval_secs: 30,
+        grpc_keepalive_timeout_secs: 20,
+        grpc_tcp_keepalive_secs: 60,
+        grpc_health_probe_interval_secs: 10,
+        s3_gateway_enabled: false,
+        s3_gateway_region: "us-east-1".to_string(),
+        repository_event_retention_days: 30,
+        pii_retention_days: 0,
+        cache_warm_edge_urls: Vec::new(),
+        admission_registry_hosts: Vec::new(),
+        admission_allowed_repositories: Vec::new(),
+        admission_exempt_namespaces: vec!["kube-system".to_string()],
         plugins_require_signed: true,
         plugins_trusted_pubkey: None,
         peer_instance_name: "test".into(),
@@ -83,6 +96,8 @@ fn test_config(storage_path: &str) -> Config {
         stuck_scan_reap_limit: 1000,
         allow_local_admin_login: false,
         sso_disable_admin_break_glass: false,
+        audit_retention_enabled: false,
+        audit_archive_enabled: true,
         max_upload_size_bytes: 10_737_418_240,
         metrics_port: None,
         database_max_connections: 20,
@@ -125,9 +140,17 @@ fn test_config(storage_path: &str) -> Config {
         password_min_strength: 0,
         presigned_downloads_enabled: false,
         presigned_download_expiry_secs: 300,
+        presigned_canary_interval_secs: 600,
         proxy_singleflight_advisory_locks_enabled: false,
         proxy_singleflight_lock_poll_interval_ms: 200,
         proxy_singleflight_lock_wait_timeout_secs: 65,
+        public_mirror_mode: false,
+        public_mirror_anon_requests_per_window: 1200,
+        public_mirror_window_secs: 60,
+        public_mirror_anon_bytes_per_window: 53_687_091_200,
+        public_mirror_bandwidth_window_secs: 3600,
+        public_mirror_metadata_max_age_secs: 300,
+        public_mirror_artifact_max_age_secs: 31_536_000,
         smtp_host: None,
         smtp_port: 587,
         smtp_username: None,
@@ -142,6 +165,18 @@ fn test_config(storage_path: &str) -> Config {
         npm_upstream_feed_url:
             artifact_keeper_backend::services::upstream_feed::NPM_REPLICATION_FEED_DEFAULT_URL
                 .to_string(),
+        load_test_mode: false,
+        storage_dedup_enabled: false,
+        storage_capacity_bytes: None,
+        storage_forecast_alert_days: 30,
+        quota_warning_threshold_percent: 80,
+        storage_cold_backend: None,
+        storage_cold_path: None,
+        storage_cold_rehydrated_days: 7,
+        api_token_stale_days: 0,
+        api_token_stale_warning_days: 7,
+        repository_key_alias_ttl_days: 90,
+        access_log_retention_days: 14,
     }
 }
 
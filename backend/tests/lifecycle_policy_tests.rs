@@ -116,6 +116,7 @@ async fn test_tag_pattern_keep_deletes_non_matching_artifacts() {
             config: serde_json::json!({"pattern": "^(release-|v)"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .expect("failed to create policy");
@@ -197,6 +198,7 @@ async fn test_tag_pattern_keep_all_match_deletes_nothing() {
             config: serde_json::json!({"pattern": "^release-"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -236,6 +238,7 @@ async fn test_tag_pattern_keep_none_match_deletes_all() {
             config: serde_json::json!({"pattern": "^release-"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -276,6 +279,7 @@ async fn test_tag_pattern_delete_still_works() {
             config: serde_json::json!({"pattern": "^snapshot-"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -378,6 +382,7 @@ async fn test_size_quota_lru_evicts_never_downloaded_first() {
             config: serde_json::json!({"quota_bytes": 200}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -446,6 +451,7 @@ async fn test_size_quota_lru_frequently_downloaded_survives() {
             config: serde_json::json!({"quota_bytes": 100}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -659,6 +665,7 @@ async fn test_tag_pattern_delete_cascades_oci_tags_for_soft_deleted_manifest() {
             config: serde_json::json!({"pattern": "-snapshot-images$"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .expect("failed to create policy");
@@ -752,6 +759,7 @@ async fn test_max_age_days_cascades_oci_tags() {
             config: serde_json::json!({"days": 7}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .expect("failed to create policy");
@@ -817,6 +825,7 @@ async fn test_cascade_respects_repo_scope() {
             config: serde_json::json!({"pattern": "-images$"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -878,6 +887,7 @@ async fn test_cascade_handles_port_in_image_name() {
             config: serde_json::json!({"pattern": "snapshot-keep-me$"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -953,6 +963,7 @@ async fn test_cascade_handles_digest_reference() {
             config: serde_json::json!({"days": 7}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1044,6 +1055,7 @@ async fn test_execute_policy_reclaims_orphan_oci_tags() {
             config: serde_json::json!({"pattern": "^never-matches$"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1163,6 +1175,7 @@ async fn test_cascade_picks_up_orphans_from_prior_run() {
             config: serde_json::json!({"pattern": "^never-matches-anything$"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1230,6 +1243,7 @@ async fn test_size_quota_under_limit_evicts_nothing() {
             config: serde_json::json!({"quota_bytes": 500}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1337,6 +1351,7 @@ async fn test_max_versions_cascades_oci_tags() {
             config: serde_json::json!({"keep": 1}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1433,6 +1448,7 @@ async fn test_no_downloads_days_cascades_oci_tags() {
             config: serde_json::json!({"days": 7}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1502,6 +1518,7 @@ async fn test_tag_pattern_keep_cascades_oci_tags() {
             config: serde_json::json!({"pattern": ":v"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1574,6 +1591,7 @@ async fn test_size_quota_bytes_cascades_oci_tags() {
             config: serde_json::json!({"quota_bytes": 200}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1667,6 +1685,7 @@ async fn test_lifecycle_cascade_unblocks_storage_gc_orphan_detection() {
             config: serde_json::json!({"days": 7}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1732,6 +1751,7 @@ async fn cascade_retains_sole_protecting_oci_tag() {
             config: serde_json::json!({"pattern": ":prod$"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1790,6 +1810,7 @@ async fn cascade_prunes_redundant_tag_when_sibling_survives() {
             config: serde_json::json!({"pattern": ":stale$"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -1853,6 +1874,7 @@ async fn cascade_retains_all_when_every_protecting_tag_pruned() {
             config: serde_json::json!({"pattern": ":prod"}),
             priority: None,
             cron_schedule: None,
+            label_selector: vec![],
         })
         .await
         .unwrap();
@@ -0,0 +1,159 @@
+//! The client tools exercised by the harness, one per format handler.
+//!
+//! Each case runs one of the existing `scripts/native-tests/test-*.sh`
+//! scripts inside the same image the docker-compose E2E suite uses, so a
+//! case here and its compose profile always test the same thing.
+
+/// A client tool run against one format handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientCase {
+    /// Repository format under test (matches the compose profile name).
+    pub format: &'static str,
+    /// The client tool(s) the script drives.
+    pub client: &'static str,
+    /// Container image, `name:tag`.
+    pub image: &'static str,
+    /// Shell command run with `sh -c`; `/scripts` holds the native tests.
+    pub command: &'static str,
+    /// The client talks to the host Docker daemon, so it runs with the
+    /// daemon socket mounted and on the host network.
+    pub needs_docker: bool,
+}
+
+pub const CLIENT_CASES: &[ClientCase] = &[
+    ClientCase {
+        format: "pypi",
+        client: "pip + twine",
+        image: "ghcr.io/artifact-keeper/ci-mirror/python:3.12-slim",
+        command: "bash /scripts/test-pypi.sh",
+        needs_docker: false,
+    },
+    ClientCase {
+        format: "npm",
+        client: "npm",
+        image: "ghcr.io/artifact-keeper/ci-mirror/node:20-slim",
+        command: "bash /scripts/test-npm.sh",
+        needs_docker: false,
+    },
+    ClientCase {
+        format: "cargo",
+        client: "cargo",
+        image: "ghcr.io/artifact-keeper/ci-mirror/rust:1.75-slim",
+        command: "bash /scripts/test-cargo.sh",
+        needs_docker: false,
+    },
+    ClientCase {
+        format: "helm",
+        client: "helm",
+        image: "alpine/helm:3.16",
+        command: "apk add --no-cache bash curl >/dev/null 2>&1 && bash /scripts/test-helm.sh",
+        needs_docker: false,
+    },
+    ClientCase {
+        format: "conda",
+        client: "conda",
+        image: "continuumio/miniconda3:latest",
+        command: "bash /scripts/test-conda.sh",
+        needs_docker: false,
+    },
+    ClientCase {
+        format: "docker",
+        client: "docker",
+        image: "docker:24-cli",
+        command: "apk add --no-cache bash curl jq >/dev/null 2>&1 && bash /scripts/test-docker.sh",
+        needs_docker: true,
+    },
+];
+
+impl ClientCase {
+    /// Split `image` into the name and tag testcontainers expects.
+    pub fn image_name_and_tag(&self) -> (&'static str, &'static str) {
+        match self.image.rsplit_once(':') {
+            Some((name, tag)) if !tag.contains('/') => (name, tag),
+            _ => (self.image, "latest"),
+        }
+    }
+}
+
+/// Cases selected by a comma-separated format list (`CONFORMANCE_FORMATS`).
+/// `None` or an empty list selects every case; unknown formats are an error
+/// so a typo cannot silently shrink the matrix.
+pub fn select_cases(filter: Option<&str>) -> anyhow::Result<Vec<ClientCase>> {
+    let Some(filter) = filter.map(str::trim).filter(|f| !f.is_empty()) else {
+        return Ok(CLIENT_CASES.to_vec());
+    };
+
+    let mut selected = Vec::new();
+    for format in filter.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        let case = CLIENT_CASES
+            .iter()
+            .find(|c| c.format == format)
+            .ok_or_else(|| {
+                let known: Vec<_> = CLIENT_CASES.iter().map(|c| c.format).collect();
+                anyhow::anyhow!(
+                    "unknown conformance format '{}' (known: {})",
+                    format,
+                    known.join(", ")
+                )
+            })?;
+        if !selected.contains(case) {
+            selected.push(*case);
+        }
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_formats_are_unique() {
+        for (i, case) in CLIENT_CASES.iter().enumerate() {
+            assert!(
+                CLIENT_CASES[i + 1..]
+                    .iter()
+                    .all(|c| c.format != case.format),
+                "duplicate format {}",
+                case.format
+            );
+        }
+    }
+
+    #[test]
+    fn test_case_scripts_exist() {
+        for case in CLIENT_CASES {
+            let script = format!("scripts/native-tests/test-{}.sh", case.format);
+            assert!(crate::repo_path(&script).is_file(), "{} is missing", script);
+        }
+    }
+
+    #[test]
+    fn test_image_name_and_tag() {
+        let case = CLIENT_CASES[0];
+        assert_eq!(
+            case.image_name_and_tag(),
+            ("ghcr.io/artifact-keeper/ci-mirror/python", "3.12-slim")
+        );
+        let untagged = ClientCase {
+            image: "localhost:5000/tools",
+            ..case
+        };
+        assert_eq!(
+            untagged.image_name_and_tag(),
+            ("localhost:5000/tools", "latest")
+        );
+    }
+
+    #[test]
+    fn test_select_cases() {
+        assert_eq!(select_cases(None).unwrap().len(), CLIENT_CASES.len());
+        assert_eq!(select_cases(Some(" ")).unwrap().len(), CLIENT_CASES.len());
+
+        let selected = select_cases(Some("npm, pypi,npm")).unwrap();
+        let formats: Vec<_> = selected.iter().map(|c| c.format).collect();
+        assert_eq!(formats, vec!["npm", "pypi"]);
+
+        assert!(select_cases(Some("pypi,nuget-typo")).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! The containers a conformance run needs: Postgres, the backend, the
+//! repository bootstrap and one container per client case.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+use testcontainers::core::{IntoContainerPort, Mount};
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, GenericImage, ImageExt};
+use testcontainers_modules::postgres::Postgres;
+
+use crate::clients::ClientCase;
+use crate::report::{CaseOutcome, CaseStatus};
+use crate::{repo_path, ADMIN_PASS, ADMIN_USER};
+
+const BACKEND_PORT: u16 = 8080;
+const DEFAULT_BACKEND_IMAGE: &str = "artifact-keeper-backend:test";
+const DEFAULT_CLIENT_TIMEOUT_SECS: u64 = 900;
+const BACKEND_READY_TIMEOUT: Duration = Duration::from_secs(180);
+const SETUP_TIMEOUT: Duration = Duration::from_secs(120);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A running backend with its database, reachable from client containers
+/// at `http://<backend_host>:8080` on the run's private network.
+pub struct ConformanceEnv {
+    pub backend_image: String,
+    network: String,
+    backend_host: String,
+    backend_port: u16,
+    client_timeout: Duration,
+    // Held so the containers live as long as the environment.
+    _postgres: ContainerAsync<Postgres>,
+    _backend: ContainerAsync<GenericImage>,
+}
+
+impl ConformanceEnv {
+    /// Start Postgres and the backend, wait for `/health`, then create the
+    /// test repositories the native scripts expect.
+    pub async fn start() -> anyhow::Result<Self> {
+        let backend_image = std::env::var("CONFORMANCE_BACKEND_IMAGE")
+            .unwrap_or_else(|_| DEFAULT_BACKEND_IMAGE.to_string());
+        let client_timeout = Duration::from_secs(
+            std::env::var("CONFORMANCE_CLIENT_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_CLIENT_TIMEOUT_SECS),
+        );
+
+        let run_id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let network = format!("ak-conformance-{}", run_id);
+        let postgres_host = format!("ak-conformance-db-{}", run_id);
+        let backend_host = format!("ak-conformance-backend-{}", run_id);
+
+        let postgres = Postgres::default()
+            .with_user("registry")
+            .with_password("registry")
+            .with_db_name("artifact_registry")
+            .with_name("ghcr.io/artifact-keeper/ci-mirror/postgres")
+            .with_tag("16-alpine")
+            .with_network(&network)
+            .with_container_name(&postgres_host)
+            .start()
+            .await
+            .context("failed to start Postgres")?;
+
+        let (name, tag) = backend_image
+            .rsplit_once(':')
+            .unwrap_or((backend_image.as_str(), "latest"));
+        let backend = GenericImage::new(name, tag)
+            .with_exposed_port(BACKEND_PORT.tcp())
+            .with_network(&network)
+            .with_container_name(&backend_host)
+            .with_env_var(
+                "DATABASE_URL",
+                format!(
+                    "postgresql://registry:registry@{}:5432/artifact_registry",
+                    postgres_host
+                ),
+            )
+            .with_env_var("STORAGE_PATH", "/data/storage")
+            .with_env_var("BACKUP_PATH", "/data/backups")
+            .with_env_var("PLUGINS_DIR", "/data/plugins")
+            .with_env_var(
+                "JWT_SECRET",
+                "conformance-nonprod-amber-harbor-lantern-5127",
+            )
+            .with_env_var("ADMIN_PASSWORD", ADMIN_PASS)
+            .with_env_var("HOST", "0.0.0.0")
+            .with_env_var("PORT", BACKEND_PORT.to_string())
+            .with_env_var("RUST_LOG", "info")
+            .start()
+            .await
+            .with_context(|| format!("failed to start backend image {}", backend_image))?;
+        let backend_port = backend
+            .get_host_port_ipv4(BACKEND_PORT.tcp())
+            .await
+            .context("backend port is not mapped")?;
+
+        let env = Self {
+            backend_image,
+            network,
+            backend_host,
+            backend_port,
+            client_timeout,
+            _postgres: postgres,
+            _backend: backend,
+        };
+        env.wait_for_health().await?;
+        env.bootstrap_repositories().await?;
+        Ok(env)
+    }
+
+    /// Backend URL as seen from containers on the run's network.
+    pub fn network_url(&self) -> String {
+        format!("http://{}:{}", self.backend_host, BACKEND_PORT)
+    }
+
+    async fn wait_for_health(&self) -> anyhow::Result<()> {
+        let url = format!("http://127.0.0.1:{}/health", self.backend_port);
+        let client = reqwest::Client::new();
+        let deadline = Instant::now() + BACKEND_READY_TIMEOUT;
+        loop {
+            if let Ok(resp) = client.get(&url).send().await {
+                if resp.status().is_success() {
+                    return Ok(());
+                }
+            }
+            if Instant::now() > deadline {
+                bail!(
+                    "backend did not become healthy within {}s",
+                    BACKEND_READY_TIMEOUT.as_secs()
+                );
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Create the test repositories with the same script the compose E2E
+    /// suite uses.
+    async fn bootstrap_repositories(&self) -> anyhow::Result<()> {
+        let setup = GenericImage::new("ghcr.io/artifact-keeper/ci-mirror/alpine", "3.19")
+            .with_entrypoint("sh")
+            .with_cmd(["/setup.sh"])
+            .with_mount(
+                Mount::bind_mount(
+                    repo_path("scripts/e2e-setup.sh").to_string_lossy(),
+                    "/setup.sh",
+                )
+                .with_access_mode(testcontainers::core::AccessMode::ReadOnly),
+            )
+            .with_network(&self.network)
+            .with_env_var("REGISTRY_URL", self.network_url())
+            .with_env_var("ADMIN_USER", ADMIN_USER)
+            .with_env_var("ADMIN_PASS", ADMIN_PASS)
+            .start()
+            .await
+            .context("failed to start repository bootstrap")?;
+
+        match wait_for_exit(&setup, SETUP_TIMEOUT).await? {
+            Some(0) => Ok(()),
+            code => {
+                let log = container_log(&setup).await;
+                bail!("repository bootstrap exited with {:?}:\n{}", code, log)
+            }
+        }
+    }
+
+    /// Run one client case to completion and classify the result.
+    pub async fn run_case(&self, case: &ClientCase) -> CaseOutcome {
+        let started = Instant::now();
+        let (name, tag) = case.image_name_and_tag();
+        let scripts = Mount::bind_mount(
+            repo_path("scripts/native-tests").to_string_lossy(),
+            "/scripts",
+        )
+        .with_access_mode(testcontainers::core::AccessMode::ReadOnly);
+
+        let request = GenericImage::new(name, tag)
+            .with_entrypoint("sh")
+            .with_cmd(["-c", case.command])
+            .with_mount(scripts)
+            .with_env_var("ADMIN_USER", ADMIN_USER)
+            .with_env_var("ADMIN_PASS", ADMIN_PASS);
+
+        // The Docker client pushes through the host daemon, which cannot
+        // resolve names on the run's network; point it at the mapped port.
+        let request = if case.needs_docker {
+            request
+                .with_network("host")
+                .with_mount(Mount::bind_mount(
+                    "/var/run/docker.sock",
+                    "/var/run/docker.sock",
+                ))
+                .with_env_var("REGISTRY_URL", format!("localhost:{}", self.backend_port))
+        } else {
+            request
+                .with_network(&self.network)
+                .with_env_var("REGISTRY_URL", self.network_url())
+        };
+
+        let container = match request.start().await {
+            Ok(container) => container,
+            Err(e) => {
+                return CaseOutcome::new(
+                    case,
+                    CaseStatus::Error,
+                    None,
+                    started.elapsed(),
+                    &format!("ERROR: failed to start {}: {}", case.image, e),
+                );
+            }
+        };
+
+        let exit = wait_for_exit(&container, self.client_timeout).await;
+        let log = container_log(&container).await;
+        let (status, exit_code) = match exit {
+            Ok(Some(code)) => (CaseStatus::from_exit(Some(code), &log), Some(code)),
+            Ok(None) => (CaseStatus::TimedOut, None),
+            Err(e) => {
+                let log = format!("{}\nERROR: {}", log, e);
+                return CaseOutcome::new(case, CaseStatus::Error, None, started.elapsed(), &log);
+            }
+        };
+        CaseOutcome::new(case, status, exit_code, started.elapsed(), &log)
+    }
+}
+
+/// Wait for a container to exit. `Ok(None)` means it was still running
+/// at the timeout.
+async fn wait_for_exit<I: testcontainers::Image>(
+    container: &ContainerAsync<I>,
+    timeout: Duration,
+) -> anyhow::Result<Option<i64>> {
+    let deadline = Instant::now() + timeout;
+    while container.is_running().await? {
+        if Instant::now() > deadline {
+            return Ok(None);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    Ok(container.exit_code().await?)
+}
+
+/// Combined stdout and stderr of a container.
+async fn container_log<I: testcontainers::Image>(container: &ContainerAsync<I>) -> String {
+    let stdout = container.stdout_to_vec().await.unwrap_or_default();
+    let stderr = container.stderr_to_vec().await.unwrap_or_default();
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&stdout),
+        String::from_utf8_lossy(&stderr)
+    )
+}
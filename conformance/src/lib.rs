@@ -0,0 +1,42 @@
+//! Native protocol conformance harness.
+//!
+//! Starts Postgres (testcontainers) and the backend image on a private
+//! Docker network, bootstraps the test repositories with
+//! `scripts/e2e-setup.sh`, then runs each real client tool (pip, npm,
+//! cargo, helm, conda, docker) in its own container against the matching
+//! format handler using the scripts in `scripts/native-tests/`. The outcome
+//! of every client is collected into a compatibility matrix written as
+//! Markdown and JSON.
+//!
+//! ## Running
+//!
+//! ```bash
+//! docker build -t artifact-keeper-backend:test -f docker/Dockerfile.backend .
+//! cargo test -p artifact-keeper-conformance -- --ignored --nocapture
+//!
+//! # Optional
+//! CONFORMANCE_BACKEND_IMAGE=artifact-keeper-backend:test
+//! CONFORMANCE_FORMATS=pypi,npm          # default: every client
+//! CONFORMANCE_REPORT_DIR=target/conformance
+//! CONFORMANCE_CLIENT_TIMEOUT_SECS=900
+//! ```
+
+pub mod clients;
+pub mod environment;
+pub mod report;
+
+pub use clients::{ClientCase, CLIENT_CASES};
+pub use environment::ConformanceEnv;
+pub use report::{CaseOutcome, CaseStatus, CompatibilityReport};
+
+/// Admin credentials the backend is bootstrapped with; the native test
+/// scripts default to the same values.
+pub const ADMIN_USER: &str = "admin";
+pub const ADMIN_PASS: &str = "TestRunner!2026secure";
+
+/// Path of a file or directory relative to the repository root.
+pub fn repo_path(relative: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(relative)
+}
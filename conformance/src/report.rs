@@ -0,0 +1,286 @@
+//! Compatibility matrix produced by a conformance run.
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::clients::ClientCase;
+
+/// How many trailing log lines are kept for a failed or skipped case.
+const LOG_TAIL_LINES: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStatus {
+    Passed,
+    Failed,
+    /// The script exited 0 after printing `SKIP:` (tool missing in image).
+    Skipped,
+    /// The client did not finish within the timeout.
+    TimedOut,
+    /// The client container could not be started.
+    Error,
+}
+
+impl CaseStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CaseStatus::Passed => "pass",
+            CaseStatus::Failed => "FAIL",
+            CaseStatus::Skipped => "skip",
+            CaseStatus::TimedOut => "TIMEOUT",
+            CaseStatus::Error => "ERROR",
+        }
+    }
+
+    pub fn is_failure(self) -> bool {
+        matches!(
+            self,
+            CaseStatus::Failed | CaseStatus::TimedOut | CaseStatus::Error
+        )
+    }
+
+    /// Classify a finished client run from its exit code and combined log.
+    pub fn from_exit(exit_code: Option<i64>, log: &str) -> Self {
+        match exit_code {
+            Some(0) if log.lines().any(|l| l.starts_with("SKIP:")) => CaseStatus::Skipped,
+            Some(0) => CaseStatus::Passed,
+            _ => CaseStatus::Failed,
+        }
+    }
+}
+
+/// Result of one client case.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseOutcome {
+    pub format: String,
+    pub client: String,
+    pub image: String,
+    pub status: CaseStatus,
+    pub exit_code: Option<i64>,
+    pub duration_secs: f64,
+    /// First line explaining a non-passing status (failure or skip reason).
+    pub summary: Option<String>,
+    /// Last lines of the client log for non-passing cases.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub log_tail: Vec<String>,
+}
+
+impl CaseOutcome {
+    pub fn new(
+        case: &ClientCase,
+        status: CaseStatus,
+        exit_code: Option<i64>,
+        duration: Duration,
+        log: &str,
+    ) -> Self {
+        let (summary, log_tail) = if status == CaseStatus::Passed {
+            (None, Vec::new())
+        } else {
+            (summarize(status, log), tail(log, LOG_TAIL_LINES))
+        };
+        Self {
+            format: case.format.to_string(),
+            client: case.client.to_string(),
+            image: case.image.to_string(),
+            status,
+            exit_code,
+            duration_secs: duration.as_secs_f64(),
+            summary,
+            log_tail,
+        }
+    }
+}
+
+/// Pick the line that best explains a non-passing case: the skip reason,
+/// else the last `FAIL`/`ERROR` line, else the last non-empty line.
+fn summarize(status: CaseStatus, log: &str) -> Option<String> {
+    let lines: Vec<&str> = log
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let line = if status == CaseStatus::Skipped {
+        lines.iter().find(|l| l.starts_with("SKIP:"))
+    } else {
+        lines
+            .iter()
+            .rev()
+            .find(|l| l.contains("FAIL") || l.contains("ERROR"))
+            .or(lines.last())
+    };
+    line.map(|l| l.to_string())
+}
+
+fn tail(log: &str, n: usize) -> Vec<String> {
+    let lines: Vec<&str> = log.lines().collect();
+    lines[lines.len().saturating_sub(n)..]
+        .iter()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+/// The compatibility matrix for one run.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatibilityReport {
+    pub backend_image: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub outcomes: Vec<CaseOutcome>,
+}
+
+impl CompatibilityReport {
+    pub fn failures(&self) -> Vec<&CaseOutcome> {
+        self.outcomes
+            .iter()
+            .filter(|o| o.status.is_failure())
+            .collect()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let passed = self
+            .outcomes
+            .iter()
+            .filter(|o| o.status == CaseStatus::Passed)
+            .count();
+
+        let mut out = String::new();
+        out.push_str("# Native client compatibility matrix\n\n");
+        out.push_str(&format!(
+            "Backend image `{}`, run {} ({} of {} passed).\n\n",
+            self.backend_image,
+            self.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            passed,
+            self.outcomes.len()
+        ));
+        out.push_str("| Format | Client | Image | Result | Duration | Notes |\n");
+        out.push_str("|---|---|---|---|---|---|\n");
+        for o in &self.outcomes {
+            out.push_str(&format!(
+                "| {} | {} | `{}` | {} | {:.0}s | {} |\n",
+                o.format,
+                o.client,
+                o.image,
+                o.status.label(),
+                o.duration_secs,
+                o.summary.as_deref().map(escape_cell).unwrap_or_default()
+            ));
+        }
+
+        for o in self.outcomes.iter().filter(|o| o.status.is_failure()) {
+            out.push_str(&format!("\n## {} ({})\n\n```\n", o.format, o.client));
+            for line in &o.log_tail {
+                out.push_str(line);
+                out.push('\n');
+            }
+            out.push_str("```\n");
+        }
+        out
+    }
+
+    /// Write `report.md` and `report.json` into `dir`.
+    pub fn write_to(&self, dir: &Path) -> anyhow::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("report.md"), self.to_markdown())?;
+        std::fs::write(dir.join("report.json"), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::CLIENT_CASES;
+
+    fn report(outcomes: Vec<CaseOutcome>) -> CompatibilityReport {
+        CompatibilityReport {
+            backend_image: "artifact-keeper-backend:test".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            outcomes,
+        }
+    }
+
+    #[test]
+    fn test_status_from_exit() {
+        assert_eq!(
+            CaseStatus::from_exit(Some(0), "==> ok\nPASS"),
+            CaseStatus::Passed
+        );
+        assert_eq!(
+            CaseStatus::from_exit(Some(0), "SKIP: helm not found"),
+            CaseStatus::Skipped
+        );
+        assert_eq!(CaseStatus::from_exit(Some(1), "boom"), CaseStatus::Failed);
+        assert_eq!(CaseStatus::from_exit(None, ""), CaseStatus::Failed);
+    }
+
+    #[test]
+    fn test_outcome_summary() {
+        let case = &CLIENT_CASES[0];
+        let log = "==> Upload\nERROR: upload returned 500\n==> cleanup\n";
+        let failed = CaseOutcome::new(
+            case,
+            CaseStatus::Failed,
+            Some(1),
+            Duration::from_secs(3),
+            log,
+        );
+        assert_eq!(
+            failed.summary.as_deref(),
+            Some("ERROR: upload returned 500")
+        );
+        assert_eq!(failed.log_tail.len(), 3);
+
+        let passed = CaseOutcome::new(case, CaseStatus::Passed, Some(0), Duration::ZERO, log);
+        assert!(passed.summary.is_none());
+        assert!(passed.log_tail.is_empty());
+    }
+
+    #[test]
+    fn test_markdown_matrix() {
+        let pypi = CaseOutcome::new(
+            &CLIENT_CASES[0],
+            CaseStatus::Passed,
+            Some(0),
+            Duration::from_secs(12),
+            "",
+        );
+        let npm = CaseOutcome::new(
+            &CLIENT_CASES[1],
+            CaseStatus::Failed,
+            Some(1),
+            Duration::from_secs(5),
+            "FAIL: npm install | exit 1",
+        );
+        let report = report(vec![pypi, npm]);
+        let md = report.to_markdown();
+
+        assert!(md.contains("(1 of 2 passed)"));
+        assert!(md.contains("| pypi | pip + twine |"));
+        assert!(md.contains("| pass | 12s |"));
+        assert!(md.contains("| FAIL | 5s | FAIL: npm install \\| exit 1 |"));
+        assert!(md.contains("## npm (npm)"));
+        assert_eq!(report.failures().len(), 1);
+    }
+
+    #[test]
+    fn test_json_status_names() {
+        let outcome = CaseOutcome::new(
+            &CLIENT_CASES[0],
+            CaseStatus::TimedOut,
+            None,
+            Duration::from_secs(900),
+            "",
+        );
+        let json = serde_json::to_value(report(vec![outcome])).unwrap();
+        assert_eq!(json["outcomes"][0]["status"], "timed_out");
+        assert_eq!(json["outcomes"][0]["format"], "pypi");
+    }
+}
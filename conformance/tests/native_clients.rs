@@ -0,0 +1,56 @@
+//! Runs every selected native client against a freshly started backend and
+//! writes the compatibility matrix. Needs Docker and the backend image, so
+//! it is `#[ignore]`d; run with `cargo test -p artifact-keeper-conformance
+//! -- --ignored --nocapture`.
+
+use artifact_keeper_conformance::clients::select_cases;
+use artifact_keeper_conformance::{repo_path, CompatibilityReport, ConformanceEnv};
+
+#[tokio::test]
+#[ignore = "requires Docker and the backend image"]
+async fn native_client_compatibility_matrix() {
+    let cases = select_cases(std::env::var("CONFORMANCE_FORMATS").ok().as_deref())
+        .expect("invalid CONFORMANCE_FORMATS");
+    let report_dir = std::env::var("CONFORMANCE_REPORT_DIR")
+        .map(Into::into)
+        .unwrap_or_else(|_| repo_path("target/conformance"));
+
+    let started_at = chrono::Utc::now();
+    let env = ConformanceEnv::start()
+        .await
+        .expect("failed to start conformance environment");
+
+    let mut outcomes = Vec::with_capacity(cases.len());
+    for case in &cases {
+        println!(">>> {} ({})", case.format, case.client);
+        let outcome = env.run_case(case).await;
+        println!(
+            ">>> {}: {:?} in {:.0}s",
+            case.format, outcome.status, outcome.duration_secs
+        );
+        outcomes.push(outcome);
+    }
+
+    let report = CompatibilityReport {
+        backend_image: env.backend_image.clone(),
+        started_at,
+        finished_at: chrono::Utc::now(),
+        outcomes,
+    };
+    report
+        .write_to(&report_dir)
+        .expect("failed to write conformance report");
+    println!("{}", report.to_markdown());
+    println!("Report written to {}", report_dir.display());
+
+    let failures: Vec<_> = report
+        .failures()
+        .iter()
+        .map(|o| format!("{} ({:?})", o.format, o.status))
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "native client conformance failures: {}",
+        failures.join(", ")
+    );
+}
@@ -17,6 +17,10 @@ type Config struct {
 	TrivyPath string
 	// CacheDir is trivy's --cache-dir (vuln DB + fanal cache).
 	CacheDir string
+	// CacheBackend is trivy's --cache-backend for the per-layer analysis cache
+	// ("fs", or "redis://host:6379" to share analyzed layers across adapter
+	// replicas). Empty keeps trivy's default (fs under CacheDir).
+	CacheBackend string
 	// Insecure passes --insecure to trivy so it pulls manifests/blobs from the
 	// AK registry over plain HTTP on the rig/cluster network.
 	Insecure bool
@@ -67,6 +71,7 @@ func LoadConfig() *Config {
 		Addr:         getenv("SCANNER_ADAPTER_ADDR", ":8080"),
 		TrivyPath:    getenv("SCANNER_TRIVY_PATH", "trivy"),
 		CacheDir:     getenv("SCANNER_TRIVY_CACHE_DIR", "/home/scanner/.cache/trivy"),
+		CacheBackend: getenv("SCANNER_TRIVY_CACHE_BACKEND", ""),
 		Insecure:     getenvBool("SCANNER_TRIVY_INSECURE", true),
 		SkipDBUpdate: getenvBool("SCANNER_TRIVY_SKIP_DB_UPDATE", false),
 		DBRepository: getenv("SCANNER_TRIVY_DB_REPOSITORY", ""),
@@ -67,6 +67,9 @@ func (s *Scanner) buildArgs(imageRef string) []string {
 		"--cache-dir", s.cfg.CacheDir,
 		"--quiet",
 	}
+	if s.cfg.CacheBackend != "" {
+		args = append(args, "--cache-backend", s.cfg.CacheBackend)
+	}
 	if s.cfg.Insecure {
 		args = append(args, "--insecure")
 	}
@@ -105,6 +105,18 @@ func TestBuildArgsOmitsOptionalFlags(t *testing.T) {
 	if strings.Contains(args, "--db-repository") {
 		t.Error("--db-repository should be absent when DBRepository is empty")
 	}
+	if strings.Contains(args, "--cache-backend") {
+		t.Error("--cache-backend should be absent when CacheBackend is empty")
+	}
+}
+
+func TestBuildArgsIncludesCacheBackend(t *testing.T) {
+	cfg := &Config{Severity: "LOW", ScanTimeout: time.Minute, CacheDir: "/c",
+		CacheBackend: "redis://redis:6379"}
+	args := strings.Join(NewScanner(cfg).buildArgs("ref"), " ")
+	if !strings.Contains(args, "--cache-backend redis://redis:6379") {
+		t.Errorf("expected --cache-backend in args; got: %s", args)
+	}
 }
 
 func TestBuildArgsIncludesDBRepository(t *testing.T) {
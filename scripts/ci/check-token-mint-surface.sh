@@ -57,6 +57,7 @@ handlers_dir = sys.argv[1]
 # ---------------------------------------------------------------------------
 EXPECTED = {
     "auth.rs": "scopes",              # POST /api/v1/auth/tokens
+    "npm.rs": "scopes",               # PUT /npm/:key/-/user/org.couchdb.user:name (npm login)
     "profile.rs": "scopes",           # POST /api/v1/profile/access-tokens
     "users.rs": "scopes",             # POST /api/v1/users/:id/tokens (self-or-admin)
     "repo_tokens.rs": "scopes",       # POST /api/v1/repositories/:key/tokens
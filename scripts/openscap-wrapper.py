@@ -2,8 +2,19 @@
 """Thin HTTP wrapper around the oscap CLI for use as a sidecar scanner.
 
 Endpoints:
-    GET  /health  - Health check with oscap version
-    POST /scan    - Run XCCDF compliance evaluation and return JSON findings
+    GET  /health   - Health check with oscap version
+    GET  /profiles - XCCDF profiles available in each SCAP content file
+    POST /scan     - Run XCCDF compliance evaluation and return JSON findings
+
+POST /scan body:
+    path            Directory to evaluate (an extracted filesystem)
+    profile         XCCDF profile id
+    content         Optional SCAP content label (e.g. "rhel9"); auto-detected
+                    from the scanned filesystem when omitted
+    all_results     Also return every evaluated rule (pass, notapplicable, ...)
+                    plus the benchmark score, for compliance reports
+    strict_profile  Fail instead of falling back to the first profile when
+                    the requested profile is not in the selected content
 """
 
 import json
@@ -118,8 +129,8 @@ def select_content_file(scan_path, available_content):
     return None
 
 
-def list_profiles(content_file):
-    """List available profile IDs in the given SCAP content file."""
+def list_profiles_with_titles(content_file):
+    """List (profile id, title) pairs in the given SCAP content file."""
     try:
         result = subprocess.run(
             ["oscap", "info", "--profiles", content_file],
@@ -128,15 +139,21 @@ def list_profiles(content_file):
         profiles = []
         for line in result.stdout.strip().splitlines():
             if ":" in line:
-                profiles.append(line.split(":")[0].strip())
+                profile_id, title = line.split(":", 1)
+                profiles.append((profile_id.strip(), title.strip()))
             elif line.strip():
-                profiles.append(line.strip())
+                profiles.append((line.strip(), line.strip()))
         return profiles
     except Exception:
         return []
 
 
-def run_oscap_scan(scan_path, profile, content_file):
+def list_profiles(content_file):
+    """List available profile IDs in the given SCAP content file."""
+    return [profile_id for profile_id, _ in list_profiles_with_titles(content_file)]
+
+
+def run_oscap_scan(scan_path, profile, content_file, all_results=False):
     """Run oscap xccdf eval and return parsed findings."""
     # Re-validate inputs at point of use (defense in depth, satisfies static analysis)
     if not re.fullmatch(r"[a-zA-Z0-9._\-]+", profile):
@@ -178,6 +195,12 @@ def run_oscap_scan(scan_path, profile, content_file):
 
     # Parse XCCDF results XML
     findings = parse_xccdf_results(results_file, content_file)
+    response = {"findings": findings, "profile": profile}
+    if all_results:
+        response["results"] = parse_xccdf_results(
+            results_file, content_file, include_all=True
+        )
+        response["score"] = parse_xccdf_score(results_file)
 
     # Cleanup
     try:
@@ -185,11 +208,15 @@ def run_oscap_scan(scan_path, profile, content_file):
     except OSError:
         pass
 
-    return {"findings": findings, "profile": profile}
+    return response
+
 
+def parse_xccdf_results(results_file, content_file, include_all=False):
+    """Parse XCCDF results XML into a list of finding dicts.
 
-def parse_xccdf_results(results_file, content_file):
-    """Parse XCCDF results XML into a list of finding dicts."""
+    Only failures are returned unless include_all is set, in which case every
+    evaluated rule is returned with its result (pass, fail, notapplicable, ...).
+    """
     if not os.path.exists(results_file):
         return []
 
@@ -224,7 +251,7 @@ def parse_xccdf_results(results_file, content_file):
         result_val = result_el.text if result_el is not None else "unknown"
 
         # Only report failures
-        if result_val not in ("fail", "error", "unknown"):
+        if not include_all and result_val not in ("fail", "error", "unknown"):
             continue
 
         meta = rule_meta.get(idref, {})
@@ -248,6 +275,22 @@ def parse_xccdf_results(results_file, content_file):
     return findings
 
 
+def parse_xccdf_score(results_file):
+    """Return the default-model benchmark score from XCCDF results, if any."""
+    if not os.path.exists(results_file):
+        return None
+    try:
+        root = ET.parse(results_file).getroot()
+    except ET.ParseError:
+        return None
+    for score in root.iter(f"{{{XCCDF_NS}}}score"):
+        try:
+            return float(score.text)
+        except (TypeError, ValueError):
+            return None
+    return None
+
+
 class OpenSCAPHandler(BaseHTTPRequestHandler):
     available_content = find_scap_content()
 
@@ -264,6 +307,16 @@ class OpenSCAPHandler(BaseHTTPRequestHandler):
                 "version": version,
                 "content_files": list(self.available_content.keys()),
             })
+        elif self.path == "/profiles":
+            self._json_response(200, {
+                "content": {
+                    label: [
+                        {"id": profile_id, "title": title}
+                        for profile_id, title in list_profiles_with_titles(path)
+                    ]
+                    for label, path in self.available_content.items()
+                },
+            })
         else:
             self._json_response(404, {"error": "not found"})
 
@@ -295,7 +348,17 @@ class OpenSCAPHandler(BaseHTTPRequestHandler):
             self._json_response(400, {"error": "scan path not found or not allowed"})
             return
 
-        content_file = select_content_file(scan_path, self.available_content)
+        content_label = req.get("content")
+        if content_label:
+            content_file = self.available_content.get(content_label)
+            if not content_file:
+                self._json_response(400, {
+                    "error": f"unknown SCAP content: {content_label}",
+                    "findings": [],
+                })
+                return
+        else:
+            content_file = select_content_file(scan_path, self.available_content)
         if not content_file:
             self._json_response(500, {
                 "error": "no SCAP content available",
@@ -306,10 +369,22 @@ class OpenSCAPHandler(BaseHTTPRequestHandler):
         # Verify the requested profile exists in the SCAP content
         profiles = list_profiles(content_file)
         if profile not in profiles and profiles:
+            if req.get("strict_profile"):
+                self._json_response(400, {
+                    "error": f"profile {profile} not found in {os.path.basename(content_file)}",
+                    "findings": [],
+                })
+                return
             # Fall back to first available profile
             profile = profiles[0]
 
-        result = run_oscap_scan(scan_path, profile, content_file)
+        result = run_oscap_scan(
+            scan_path, profile, content_file, all_results=bool(req.get("all_results")),
+        )
+        result["content"] = next(
+            (label for label, path in self.available_content.items() if path == content_file),
+            os.path.basename(content_file),
+        )
         self._json_response(200, result)
 
     def _json_response(self, status, data):